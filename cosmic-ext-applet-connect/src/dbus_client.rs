@@ -269,15 +269,20 @@ impl DeviceConfig {
 /// Events emitted by the daemon
 #[derive(Debug, Clone)]
 pub enum DaemonEvent {
-    /// Device was added (discovered)
-    DeviceAdded {
-        device_id: String,
-        device_info: DeviceInfo,
-    },
     /// Device was removed (disappeared)
     DeviceRemoved { device_id: String },
-    /// Device state changed
-    DeviceStateChanged { device_id: String, state: String },
+    /// Batch of discovered and changed devices, merged by the daemon
+    DevicesUpdated {
+        devices: HashMap<String, DeviceInfo>,
+    },
+    /// Device reported its battery state
+    BatteryStatusChanged {
+        device_id: String,
+        level: i32,
+        is_charging: bool,
+    },
+    /// A device started or stopped making this desktop ring
+    FindMyPhoneRinging { device_id: String, ringing: bool },
     /// Pairing request received
     PairingRequest { device_id: String },
     /// Pairing status changed
@@ -436,6 +441,9 @@ trait CConnect {
     /// Trigger find phone on a device
     async fn find_phone(&self, device_id: &str) -> zbus::fdo::Result<()>;
 
+    /// Stop ringing between this desktop and a device
+    async fn stop_ring(&self, device_id: &str) -> zbus::fdo::Result<()>;
+
     /// Lock a device remotely
     async fn lock_device(&self, device_id: &str) -> zbus::fdo::Result<()>;
 
@@ -577,17 +585,25 @@ trait CConnect {
     /// Get list of synced folders for a device
     async fn get_sync_folders(&self, device_id: String) -> zbus::fdo::Result<Vec<SyncFolderInfo>>;
 
-    /// Signal: Device was added
-    #[zbus(signal)]
-    fn device_added(device_id: &str, device_info: DeviceInfo) -> zbus::fdo::Result<()>;
-
     /// Signal: Device was removed
     #[zbus(signal)]
     fn device_removed(device_id: &str) -> zbus::fdo::Result<()>;
 
-    /// Signal: Device state changed
+    /// Signal: Devices updated (debounced batch)
     #[zbus(signal)]
-    fn device_state_changed(device_id: &str, state: &str) -> zbus::fdo::Result<()>;
+    fn devices_updated(devices: HashMap<String, DeviceInfo>) -> zbus::fdo::Result<()>;
+
+    /// Signal: Battery status changed
+    #[zbus(signal)]
+    fn battery_status_changed(
+        device_id: &str,
+        level: i32,
+        is_charging: bool,
+    ) -> zbus::fdo::Result<()>;
+
+    /// Signal: Find My Phone ringing state changed
+    #[zbus(signal)]
+    fn find_my_phone_ringing(device_id: &str, ringing: bool) -> zbus::fdo::Result<()>;
 
     /// Signal: Pairing request received
     #[zbus(signal)]
@@ -885,17 +901,13 @@ impl DbusClient {
         debug!("Starting signal listener");
 
         let event_tx = self.event_tx.clone();
-        let mut device_added_stream = self.proxy.receive_device_added().await?;
+        let mut device_removed_stream = self.proxy.receive_device_removed().await?;
         tokio::spawn(async move {
-            while let Some(signal) = device_added_stream.next().await {
+            while let Some(signal) = device_removed_stream.next().await {
                 if let Ok(args) = signal.args() {
                     let device_id = args.device_id().to_string();
-                    let device_info = args.device_info().clone();
-                    if event_tx.send(DaemonEvent::DeviceAdded {
-                        device_id,
-                        device_info,
-                    }).is_err() {
-                        tracing::warn!("Event channel closed, stopping DeviceAdded signal listener");
+                    if event_tx.send(DaemonEvent::DeviceRemoved { device_id }).is_err() {
+                        tracing::warn!("Event channel closed, stopping DeviceRemoved signal listener");
                         break;
                     }
                 }
@@ -903,13 +915,13 @@ impl DbusClient {
         });
 
         let event_tx = self.event_tx.clone();
-        let mut device_removed_stream = self.proxy.receive_device_removed().await?;
+        let mut devices_updated_stream = self.proxy.receive_devices_updated().await?;
         tokio::spawn(async move {
-            while let Some(signal) = device_removed_stream.next().await {
+            while let Some(signal) = devices_updated_stream.next().await {
                 if let Ok(args) = signal.args() {
-                    let device_id = args.device_id().to_string();
-                    if event_tx.send(DaemonEvent::DeviceRemoved { device_id }).is_err() {
-                        tracing::warn!("Event channel closed, stopping DeviceRemoved signal listener");
+                    let devices = args.devices().clone();
+                    if event_tx.send(DaemonEvent::DevicesUpdated { devices }).is_err() {
+                        tracing::warn!("Event channel closed, stopping DevicesUpdated signal listener");
                         break;
                     }
                 }
@@ -917,14 +929,34 @@ impl DbusClient {
         });
 
         let event_tx = self.event_tx.clone();
-        let mut device_state_changed_stream = self.proxy.receive_device_state_changed().await?;
+        let mut battery_status_changed_stream = self.proxy.receive_battery_status_changed().await?;
         tokio::spawn(async move {
-            while let Some(signal) = device_state_changed_stream.next().await {
+            while let Some(signal) = battery_status_changed_stream.next().await {
                 if let Ok(args) = signal.args() {
-                    let device_id = args.device_id().to_string();
-                    let state = args.state().to_string();
-                    if event_tx.send(DaemonEvent::DeviceStateChanged { device_id, state }).is_err() {
-                        tracing::warn!("Event channel closed, stopping DeviceStateChanged signal listener");
+                    let event = DaemonEvent::BatteryStatusChanged {
+                        device_id: args.device_id().to_string(),
+                        level: *args.level(),
+                        is_charging: *args.is_charging(),
+                    };
+                    if event_tx.send(event).is_err() {
+                        tracing::warn!("Event channel closed, stopping BatteryStatusChanged signal listener");
+                        break;
+                    }
+                }
+            }
+        });
+
+        let event_tx = self.event_tx.clone();
+        let mut find_my_phone_ringing_stream = self.proxy.receive_find_my_phone_ringing().await?;
+        tokio::spawn(async move {
+            while let Some(signal) = find_my_phone_ringing_stream.next().await {
+                if let Ok(args) = signal.args() {
+                    let event = DaemonEvent::FindMyPhoneRinging {
+                        device_id: args.device_id().to_string(),
+                        ringing: *args.ringing(),
+                    };
+                    if event_tx.send(event).is_err() {
+                        tracing::warn!("Event channel closed, stopping FindMyPhoneRinging signal listener");
                         break;
                     }
                 }
@@ -1366,6 +1398,15 @@ impl DbusClient {
             .context("Failed to trigger find phone")
     }
 
+    /// Stop ringing between this desktop and a device
+    pub async fn stop_ring(&self, device_id: &str) -> Result<()> {
+        info!("Stopping ring for device {}", device_id);
+        self.proxy
+            .stop_ring(device_id)
+            .await
+            .context("Failed to stop ring")
+    }
+
     /// Lock a device remotely
     pub async fn lock_device(&self, device_id: &str) -> Result<()> {
         info!("Locking device {}", device_id);
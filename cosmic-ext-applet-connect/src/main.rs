@@ -32,6 +32,7 @@ use cosmic_ext_connect_protocol::{
 };
 
 use dbus_client::DbusClient;
+use pinned_devices_config::DefaultTarget;
 
 // COSMIC Design System: Use theme::active().cosmic().space_*() for spacing
 // Available: space_none(), space_xxxs(), space_xxs(), space_xs(), space_s(), space_m(), space_l(), space_xl()
@@ -448,6 +449,8 @@ struct CConnectApplet {
     last_screen_share_stats_poll: Option<std::time::Instant>, // Last time we polled screen share stats
     // Settings window state
     settings_window: Option<(window::Id, String)>, // (window_id, device_id)
+    // Full-screen "found" dialog shown while a device makes us ring
+    find_my_phone_window: Option<(window::Id, String)>, // (window_id, device_id)
     // App Continuity (Open plugin) state
     open_url_dialog_device: Option<String>, // device_id showing open URL dialog
     open_url_input: String,                 // URL input field
@@ -716,6 +719,7 @@ fn convert_device_info(info: &dbus_client::DeviceInfo) -> DeviceState {
         port: None,
         certificate_fingerprint: None,
         certificate_data: None,
+        negotiated_capabilities: None,
     };
 
     DeviceState {
@@ -809,6 +813,7 @@ impl cosmic::Application for CConnectApplet {
             onboarding_step: 0,
             last_screen_share_stats_poll: None,
             settings_window: None,
+            find_my_phone_window: None,
             open_url_dialog_device: None,
             open_url_input: String::new(),
             sms_dialog_device: None,
@@ -902,6 +907,17 @@ impl cosmic::Application for CConnectApplet {
 
                 Task::none()
             }
+            Message::BatteryStatusChanged(device_id, status) => {
+                if let Some(device_state) = self
+                    .devices
+                    .iter_mut()
+                    .find(|d| d.device.info.device_id == device_id)
+                {
+                    device_state.battery_level = Some(status.level.clamp(0, 100) as u8);
+                    device_state.is_charging = status.is_charging;
+                }
+                Task::none()
+            }
             Message::PairDevice(device_id) => {
                 let id = device_id.clone();
                 Task::batch(vec![
@@ -1877,6 +1893,42 @@ impl cosmic::Application for CConnectApplet {
                     Task::none()
                 }
             }
+            Message::FindMyPhoneRinging(device_id, true) => {
+                if self.find_my_phone_window.is_some() {
+                    return Task::none();
+                }
+
+                let (id, open) = window::open(window::Settings {
+                    level: window::Level::AlwaysOnTop,
+                    ..Default::default()
+                });
+                self.find_my_phone_window = Some((id, device_id));
+                open.discard()
+                    .chain(window::change_mode(id, window::Mode::Fullscreen))
+                    .chain(window::gain_focus(id))
+            }
+            Message::FindMyPhoneRinging(device_id, false) => {
+                // Dismissed remotely (or by another client)
+                match &self.find_my_phone_window {
+                    Some((id, ringing_id)) if *ringing_id == device_id => {
+                        let id = *id;
+                        self.find_my_phone_window = None;
+                        window::close(id)
+                    }
+                    _ => Task::none(),
+                }
+            }
+            Message::DismissFindMyPhone => {
+                let Some((id, device_id)) = self.find_my_phone_window.take() else {
+                    return Task::none();
+                };
+                Task::batch(vec![
+                    window::close(id),
+                    device_operation_task(device_id, "stop ring", |client, id| async move {
+                        client.stop_ring(&id).await
+                    }),
+                ])
+            }
             Message::RemoteDesktopSettingsLoaded(device_id, settings) => {
                 tracing::debug!("RemoteDesktop settings loaded for {}", device_id);
 
@@ -2099,6 +2151,17 @@ impl cosmic::Application for CConnectApplet {
 
                 Task::none()
             }
+            Message::TogglePrimaryDevice(device_id) => {
+                self.pinned_devices_config.toggle_primary(device_id);
+
+                if let Err(e) = self.pinned_devices_config.save() {
+                    tracing::error!("Failed to save pinned devices config: {}", e);
+                }
+
+                Task::none()
+            }
+            Message::QuickPing => self.with_default_target(Message::SendPing),
+            Message::QuickShareText => self.with_default_target(Message::ShareText),
             Message::DaemonConnected => {
                 self.daemon_connected = true;
                 cosmic::task::message(cosmic::Action::App(Message::RefreshDevices))
@@ -2921,11 +2984,21 @@ impl cosmic::Application for CConnectApplet {
                                 success,
                                 error,
                             )),
-                            e @ dbus_client::DaemonEvent::DeviceAdded { .. }
-                            | e @ dbus_client::DaemonEvent::DeviceRemoved { .. }
+                            dbus_client::DaemonEvent::BatteryStatusChanged {
+                                device_id,
+                                level,
+                                is_charging,
+                            } => Some(Message::BatteryStatusChanged(
+                                device_id,
+                                dbus_client::BatteryStatus { level, is_charging },
+                            )),
+                            dbus_client::DaemonEvent::FindMyPhoneRinging { device_id, ringing } => {
+                                Some(Message::FindMyPhoneRinging(device_id, ringing))
+                            }
+                            e @ dbus_client::DaemonEvent::DeviceRemoved { .. }
                             | e @ dbus_client::DaemonEvent::PairingRequest { .. }
                             | e @ dbus_client::DaemonEvent::PairingStatusChanged { .. }
-                            | e @ dbus_client::DaemonEvent::DeviceStateChanged { .. }
+                            | e @ dbus_client::DaemonEvent::DevicesUpdated { .. }
                             | e @ dbus_client::DaemonEvent::IncomingCall { .. }
                             | e @ dbus_client::DaemonEvent::MissedCall { .. }
                             | e @ dbus_client::DaemonEvent::CallStateChanged { .. }
@@ -2961,52 +3034,72 @@ impl cosmic::Application for CConnectApplet {
     fn view(&self) -> Element<'_, Self::Message> {
         let have_popup = self.popup;
 
-        let btn = self
-            .core
-            .applet
-            .icon_button("cosmic-ext-connect-symbolic")
-            .on_press_with_rectangle(move |offset, bounds| {
-                if let Some(id) = have_popup {
-                    Message::Surface(destroy_popup(id))
-                } else {
-                    Message::Surface(app_popup::<CConnectApplet>(
-                        move |state: &mut CConnectApplet| {
-                            let new_id = window::Id::unique();
-                            state.popup = Some(new_id);
-
-                            let mut popup_settings = state.core.applet.get_popup_settings(
-                                state.core.main_window_id()
-                                    .expect("applet must have a main window"),
-                                new_id,
-                                None,
-                                None,
-                                None,
-                            );
+        // Overlay the primary (or lowest) device battery next to the panel icon
+        let btn = match self.panel_battery() {
+            Some((level, is_charging)) => {
+                let (size, _) = self.core.applet.suggested_size(true);
+                button::custom(
+                    row![
+                        icon::from_name("cosmic-ext-connect-symbolic")
+                            .size(size)
+                            .symbolic(true),
+                        icon::from_name(views::device::battery_icon_name(level, is_charging))
+                            .size(size)
+                            .symbolic(true),
+                    ]
+                    .spacing(space_xxxs())
+                    .align_y(cosmic::iced::Alignment::Center),
+                )
+                .padding(self.core.applet.suggested_padding(true))
+                .class(cosmic::theme::Button::AppletIcon)
+            }
+            None => self.core.applet.icon_button("cosmic-ext-connect-symbolic"),
+        };
 
-                            // Popup size limits - use reasonable constraints that adapt
-                            // to content while preventing excessive sizes
-                            popup_settings.positioner.size_limits = Limits::NONE
-                                .min_width(350.0)
-                                .max_width(500.0)
-                                .max_height(650.0);
-
-                            popup_settings.positioner.anchor_rect = Rectangle {
-                                x: (bounds.x - offset.x) as i32,
-                                y: (bounds.y - offset.y) as i32,
-                                width: bounds.width as i32,
-                                height: bounds.height as i32,
-                            };
+        let btn = btn.on_press_with_rectangle(move |offset, bounds| {
+            if let Some(id) = have_popup {
+                Message::Surface(destroy_popup(id))
+            } else {
+                Message::Surface(app_popup::<CConnectApplet>(
+                    move |state: &mut CConnectApplet| {
+                        let new_id = window::Id::unique();
+                        state.popup = Some(new_id);
+
+                        let mut popup_settings = state.core.applet.get_popup_settings(
+                            state
+                                .core
+                                .main_window_id()
+                                .expect("applet must have a main window"),
+                            new_id,
+                            None,
+                            None,
+                            None,
+                        );
 
-                            popup_settings
-                        },
-                        Some(Box::new(|state: &CConnectApplet| {
-                            let content = state.popup_view();
-                            Element::from(state.core.applet.popup_container(content))
-                                .map(cosmic::Action::App)
-                        })),
-                    ))
-                }
-            });
+                        // Popup size limits - use reasonable constraints that adapt
+                        // to content while preventing excessive sizes
+                        popup_settings.positioner.size_limits = Limits::NONE
+                            .min_width(350.0)
+                            .max_width(500.0)
+                            .max_height(650.0);
+
+                        popup_settings.positioner.anchor_rect = Rectangle {
+                            x: (bounds.x - offset.x) as i32,
+                            y: (bounds.y - offset.y) as i32,
+                            width: bounds.width as i32,
+                            height: bounds.height as i32,
+                        };
+
+                        popup_settings
+                    },
+                    Some(Box::new(|state: &CConnectApplet| {
+                        let content = state.popup_view();
+                        Element::from(state.core.applet.popup_container(content))
+                            .map(cosmic::Action::App)
+                    })),
+                ))
+            }
+        });
 
         Element::from(self.core.applet.applet_tooltip::<Message>(
             btn,
@@ -3025,10 +3118,24 @@ impl cosmic::Application for CConnectApplet {
             }
         }
 
+        if let Some((found_id, ref device_id)) = self.find_my_phone_window {
+            if id == found_id {
+                return self.view_find_my_phone_window(device_id);
+            }
+        }
+
         text("CConnect").into()
     }
 
     fn on_close_requested(&self, id: window::Id) -> Option<Message> {
+        // Closing the "found" dialog any other way still counts as a dismissal
+        if self
+            .find_my_phone_window
+            .as_ref()
+            .is_some_and(|(found_id, _)| *found_id == id)
+        {
+            return Some(Message::DismissFindMyPhone);
+        }
         Some(Message::PopupClosed(id))
     }
 
@@ -3121,6 +3228,29 @@ impl CConnectApplet {
             .into()
     }
 
+    /// Renders the full-screen dialog shown while a device makes this desktop ring
+    fn view_find_my_phone_window(&self, device_id: &str) -> Element<'_, Message> {
+        let device_name = self
+            .devices
+            .iter()
+            .find(|d| d.device.id() == device_id)
+            .map(|d| d.device.name())
+            .unwrap_or("A paired device");
+
+        let content = column![
+            icon::from_name("find-location-symbolic").size(ICON_XL * 2),
+            text("This device is being located").size(32.0),
+            text(format!("{} asked this computer to ring", device_name)).size(16.0),
+            button::suggested("I found it")
+                .on_press(Message::DismissFindMyPhone)
+                .padding(space_s()),
+        ]
+        .spacing(space_l())
+        .align_x(cosmic::iced::Alignment::Center);
+
+        container(content).center(Length::Fill).into()
+    }
+
     /// Records a received file in the history for display in the transfer queue view.
     fn record_received_file(&mut self, device_id: String, filename: String, success: bool) {
         let device_name = self
@@ -3156,17 +3286,6 @@ impl CConnectApplet {
     fn handle_device_event(&mut self, event: dbus_client::DaemonEvent) -> Task<Message> {
         let timestamp = std::time::SystemTime::now();
         match &event {
-            dbus_client::DaemonEvent::DeviceAdded {
-                device_id,
-                device_info,
-            } => {
-                self.history.push(HistoryEvent {
-                    timestamp,
-                    event_type: "Device Found".to_string(),
-                    device_name: device_info.name.clone(),
-                    details: format!("ID: {}", device_id),
-                });
-            }
             dbus_client::DaemonEvent::DeviceRemoved { device_id } => {
                 let name = self
                     .devices
@@ -3186,20 +3305,32 @@ impl CConnectApplet {
                     details: format!("ID: {}", device_id),
                 });
             }
-            dbus_client::DaemonEvent::DeviceStateChanged { device_id, state } => {
-                let name = self
-                    .devices
-                    .iter()
-                    .find(|d| d.device.info.device_id == *device_id)
-                    .map(|d| d.device.info.device_name.clone())
-                    .unwrap_or_else(|| "Unknown".to_string());
-
-                self.history.push(HistoryEvent {
-                    timestamp,
-                    event_type: "State Changed".to_string(),
-                    device_name: name,
-                    details: state.clone(),
-                });
+            dbus_client::DaemonEvent::DevicesUpdated { devices } => {
+                // The refetch below shows the new state, record what changed
+                for (device_id, device_info) in devices {
+                    let known = self
+                        .devices
+                        .iter()
+                        .find(|d| d.device.info.device_id == *device_id);
+                    let (event_type, details) = match known {
+                        None => ("Device Found", format!("ID: {}", device_id)),
+                        Some(d) if d.device.is_connected() != device_info.is_connected => {
+                            let state = if device_info.is_connected {
+                                "connected"
+                            } else {
+                                "disconnected"
+                            };
+                            ("State Changed", state.to_string())
+                        }
+                        Some(_) => continue,
+                    };
+                    self.history.push(HistoryEvent {
+                        timestamp,
+                        event_type: event_type.to_string(),
+                        device_name: device_info.name.clone(),
+                        details,
+                    });
+                }
             }
             dbus_client::DaemonEvent::PairingRequest { device_id } => {
                 self.history.push(HistoryEvent {
@@ -3219,6 +3350,19 @@ impl CConnectApplet {
                     device_name: "Unknown".to_string(),
                     details: status.clone(),
                 });
+
+                let failure = match status.as_str() {
+                    "rejected" => Some("Pairing was declined"),
+                    "timed_out" => Some("Pairing request timed out"),
+                    _ => None,
+                };
+                if let Some(failure) = failure {
+                    return cosmic::task::message(cosmic::Action::App(Message::ShowNotification(
+                        failure.into(),
+                        NotificationType::Error,
+                        None,
+                    )));
+                }
             }
             dbus_client::DaemonEvent::ScreenShareRequested { device_id } => {
                 self.history.push(HistoryEvent {
@@ -3606,6 +3750,8 @@ impl CConnectApplet {
                         }
                     },
                     "m" => cosmic::task::message(cosmic::Action::App(Message::OpenManager)),
+                    "p" => cosmic::task::message(cosmic::Action::App(Message::QuickPing)),
+                    "t" => cosmic::task::message(cosmic::Action::App(Message::QuickShareText)),
                     _ => Task::none(),
                 };
             }
@@ -3630,6 +3776,47 @@ impl CConnectApplet {
             .unwrap_or_else(Task::none)
     }
 
+    /// Dispatch a quick action to the default target device
+    ///
+    /// Uses the primary device or the sole connected device; otherwise asks the
+    /// user to pick a device.
+    fn with_default_target(&self, action: impl FnOnce(String) -> Message) -> Task<Message> {
+        let connected: Vec<&str> = self
+            .devices
+            .iter()
+            .filter(|d| d.device.is_connected() && d.device.is_paired())
+            .map(|d| d.device.id())
+            .collect();
+
+        let message = match self.pinned_devices_config.resolve_default_target(&connected) {
+            DefaultTarget::Device(device_id) => action(device_id),
+            DefaultTarget::Prompt => Message::ShowNotification(
+                "Select a device or set a primary device".into(),
+                NotificationType::Info,
+                None,
+            ),
+        };
+
+        cosmic::task::message(cosmic::Action::App(message))
+    }
+
+    /// Battery to show on the panel icon
+    ///
+    /// Prefers the connected primary device; otherwise shows the connected
+    /// device with the lowest battery level.
+    fn panel_battery(&self) -> Option<(u8, bool)> {
+        let connected = self.devices.iter().filter(|d| {
+            d.device.is_connected() && d.device.is_paired() && d.battery_level.is_some()
+        });
+
+        let state = connected
+            .clone()
+            .find(|d| self.pinned_devices_config.is_primary(d.device.id()))
+            .or_else(|| connected.min_by_key(|d| d.battery_level))?;
+
+        state.battery_level.map(|level| (level, state.is_charging))
+    }
+
     /// Get list of focusable elements in current view
     fn get_focusable_elements(&self) -> Vec<FocusTarget> {
         let device_count = if self.view_mode == ViewMode::Devices {
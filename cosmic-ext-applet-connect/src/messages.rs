@@ -60,6 +60,7 @@ pub enum Message {
     // Daemon responses
     DeviceListUpdated(HashMap<String, dbus_client::DeviceInfo>),
     BatteryStatusesUpdated(HashMap<String, dbus_client::BatteryStatus>),
+    BatteryStatusChanged(String, dbus_client::BatteryStatus), // device_id, status
     // MPRIS control
     MprisPlayersUpdated(Vec<String>),
     MprisPlayerSelected(String),
@@ -140,6 +141,10 @@ pub enum Message {
     LaunchManager(String), // Launch manager with device_id pre-selected
     // Pinned devices
     ToggleDevicePin(String), // device_id
+    // Primary device (default target for quick actions)
+    TogglePrimaryDevice(String), // device_id
+    QuickPing,                   // ping the default target device
+    QuickShareText,              // share clipboard text with the default target device
     // Daemon status
     DaemonConnected,
     DaemonDisconnected,
@@ -255,4 +260,7 @@ pub enum Message {
     Loop(Box<Message>),
     // Settings window
     CloseSettingsWindow,
+    // Find My Phone
+    FindMyPhoneRinging(String, bool), // device_id, ringing
+    DismissFindMyPhone,
 }
@@ -11,6 +11,19 @@ pub struct PinnedDevicesConfig {
     /// Set of device IDs that are pinned/favorited
     #[serde(default)]
     pub pinned_devices: HashSet<String>,
+
+    /// Device ID used as the default target for quick actions
+    #[serde(default)]
+    pub primary_device: Option<String>,
+}
+
+/// Resolved target for a quick action that was triggered without a device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefaultTarget {
+    /// Send the action to this device
+    Device(String),
+    /// No unambiguous target; the user must pick a device
+    Prompt,
 }
 
 
@@ -84,6 +97,43 @@ impl PinnedDevicesConfig {
     pub fn unpin_device(&mut self, device_id: &str) {
         self.pinned_devices.remove(device_id);
     }
+
+    /// Check if a device is the primary device
+    pub fn is_primary(&self, device_id: &str) -> bool {
+        self.primary_device.as_deref() == Some(device_id)
+    }
+
+    /// Toggle primary state for a device
+    ///
+    /// Returns true if the device is now the primary device.
+    pub fn toggle_primary(&mut self, device_id: String) -> bool {
+        if self.is_primary(&device_id) {
+            self.primary_device = None;
+            false
+        } else {
+            self.primary_device = Some(device_id);
+            true
+        }
+    }
+
+    /// Resolve the target for a quick action triggered without a device
+    ///
+    /// Priority:
+    /// 1. The primary device, if it is currently connected
+    /// 2. The only connected device, if exactly one is connected
+    /// 3. Prompt the user
+    pub fn resolve_default_target(&self, connected_device_ids: &[&str]) -> DefaultTarget {
+        if let Some(primary) = &self.primary_device {
+            if connected_device_ids.contains(&primary.as_str()) {
+                return DefaultTarget::Device(primary.clone());
+            }
+        }
+
+        match connected_device_ids {
+            [only] => DefaultTarget::Device((*only).to_string()),
+            _ => DefaultTarget::Prompt,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -119,6 +169,88 @@ mod tests {
         assert!(!config.is_pinned("device1"));
     }
 
+    #[test]
+    fn test_toggle_primary() {
+        let mut config = PinnedDevicesConfig::default();
+
+        assert!(config.toggle_primary("device1".to_string()));
+        assert!(config.is_primary("device1"));
+
+        // Selecting another device replaces the primary
+        assert!(config.toggle_primary("device2".to_string()));
+        assert!(!config.is_primary("device1"));
+        assert!(config.is_primary("device2"));
+
+        assert!(!config.toggle_primary("device2".to_string()));
+        assert_eq!(config.primary_device, None);
+    }
+
+    #[test]
+    fn test_resolve_default_target_prefers_primary() {
+        let config = PinnedDevicesConfig {
+            primary_device: Some("device2".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.resolve_default_target(&["device1", "device2"]),
+            DefaultTarget::Device("device2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_default_target_sole_connected_device() {
+        let config = PinnedDevicesConfig::default();
+        assert_eq!(
+            config.resolve_default_target(&["device1"]),
+            DefaultTarget::Device("device1".to_string())
+        );
+
+        // Disconnected primary falls back to the sole connected device
+        let config = PinnedDevicesConfig {
+            primary_device: Some("offline".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.resolve_default_target(&["device1"]),
+            DefaultTarget::Device("device1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_resolve_default_target_prompts() {
+        let config = PinnedDevicesConfig::default();
+        assert_eq!(config.resolve_default_target(&[]), DefaultTarget::Prompt);
+        assert_eq!(
+            config.resolve_default_target(&["device1", "device2"]),
+            DefaultTarget::Prompt
+        );
+
+        let config = PinnedDevicesConfig {
+            primary_device: Some("offline".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.resolve_default_target(&["device1", "device2"]),
+            DefaultTarget::Prompt
+        );
+    }
+
+    #[test]
+    fn test_primary_device_persistence() {
+        let mut config = PinnedDevicesConfig::default();
+        config.toggle_primary("device1".to_string());
+
+        let toml_str = toml::to_string(&config).unwrap();
+        let parsed: PinnedDevicesConfig = toml::from_str(&toml_str).unwrap();
+        assert!(parsed.is_primary("device1"));
+
+        // Configs written before the primary device existed still load
+        let parsed: PinnedDevicesConfig = toml::from_str("pinned_devices = [\"device1\"]").unwrap();
+        assert_eq!(parsed.primary_device, None);
+        assert!(parsed.is_pinned("device1"));
+    }
+
     #[test]
     fn test_config_serialization() {
         let mut config = PinnedDevicesConfig::default();
@@ -865,6 +865,18 @@ impl CConnectApplet {
 
         // Settings section
         if device.is_paired() {
+            let is_primary = self.pinned_devices_config.is_primary(device_id);
+            menu_items.push(menu_item(
+                "emblem-default-symbolic",
+                if is_primary {
+                    "Unset as primary device"
+                } else {
+                    "Set as primary device"
+                },
+                Message::TogglePrimaryDevice(device_id.to_string()),
+                cosmic::theme::Button::MenuItem,
+            ));
+
             menu_items.push(menu_item(
                 "document-properties-symbolic",
                 "Device details",
@@ -242,6 +242,18 @@ impl CConnectApplet {
                         cosmic::widget::text::body("Open Manager").width(Length::FillPortion(3)),
                     ]
                     .spacing(space_xxs()),
+                    row![
+                        cosmic::widget::text::body("Ctrl+P").width(Length::FillPortion(2)),
+                        cosmic::widget::text::body("Ping primary device")
+                            .width(Length::FillPortion(3)),
+                    ]
+                    .spacing(space_xxs()),
+                    row![
+                        cosmic::widget::text::body("Ctrl+T").width(Length::FillPortion(2)),
+                        cosmic::widget::text::body("Share clipboard with primary device")
+                            .width(Length::FillPortion(3)),
+                    ]
+                    .spacing(space_xxs()),
                     row![
                         cosmic::widget::text::body("F1 or ?").width(Length::FillPortion(2)),
                         cosmic::widget::text::body("Show this help dialog")
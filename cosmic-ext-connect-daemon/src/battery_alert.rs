@@ -0,0 +1,129 @@
+//! Low Battery Alerts
+//!
+//! Decides when a paired device's battery report should raise a desktop
+//! notification. An alert fires once when a discharging device drops to the
+//! configured threshold (`plugins.low_battery_threshold`). It is re-armed only
+//! after the level climbs [`LOW_BATTERY_HYSTERESIS`] points above the
+//! threshold, so a level hovering around the threshold or a charger being
+//! plugged in and out does not notify again.
+
+use std::collections::HashSet;
+
+/// Default battery percentage at or below which a device is considered low
+pub const DEFAULT_LOW_BATTERY_THRESHOLD: u8 = 15;
+
+/// Percentage points above the threshold needed before a device can alert again
+pub const LOW_BATTERY_HYSTERESIS: u8 = 5;
+
+/// Per-device low battery alert state
+#[derive(Debug, Default)]
+pub struct LowBatteryAlerts {
+    /// Devices that have alerted and not yet recovered past the hysteresis band
+    alerted: HashSet<String>,
+}
+
+impl LowBatteryAlerts {
+    /// Create an empty alert tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a battery report from a device
+    ///
+    /// Returns `true` if a low battery notification should be shown. A
+    /// threshold of 0 disables alerts.
+    pub fn update(&mut self, device_id: &str, level: u8, is_charging: bool, threshold: u8) -> bool {
+        if level >= threshold.saturating_add(LOW_BATTERY_HYSTERESIS) {
+            self.alerted.remove(device_id);
+            return false;
+        }
+
+        if threshold == 0 || is_charging || level > threshold {
+            return false;
+        }
+
+        self.alerted.insert(device_id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed a sequence of (level, is_charging) reports and collect alerts
+    fn run(alerts: &mut LowBatteryAlerts, reports: &[(u8, bool)]) -> Vec<bool> {
+        reports
+            .iter()
+            .map(|&(level, charging)| {
+                alerts.update("phone", level, charging, DEFAULT_LOW_BATTERY_THRESHOLD)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_alerts_once_when_crossing_threshold() {
+        let mut alerts = LowBatteryAlerts::new();
+        let fired = run(
+            &mut alerts,
+            &[(30, false), (16, false), (15, false), (12, false)],
+        );
+        assert_eq!(fired, vec![false, false, true, false]);
+    }
+
+    #[test]
+    fn test_no_refire_while_hovering_around_threshold() {
+        let mut alerts = LowBatteryAlerts::new();
+        let fired = run(
+            &mut alerts,
+            &[
+                (15, false),
+                (16, false),
+                (19, false),
+                (15, false),
+                (14, false),
+            ],
+        );
+        assert_eq!(fired, vec![true, false, false, false, false]);
+    }
+
+    #[test]
+    fn test_rearms_after_recovering_past_hysteresis() {
+        let mut alerts = LowBatteryAlerts::new();
+        let fired = run(
+            &mut alerts,
+            &[(14, false), (20, true), (40, false), (15, false)],
+        );
+        assert_eq!(fired, vec![true, false, false, true]);
+    }
+
+    #[test]
+    fn test_charging_suppresses_and_does_not_rearm() {
+        let mut alerts = LowBatteryAlerts::new();
+        // Plugged in while low: no alert
+        assert_eq!(run(&mut alerts, &[(10, true)]), vec![false]);
+        // Unplugged while still low: alerts once
+        assert_eq!(run(&mut alerts, &[(10, false)]), vec![true]);
+        // Flapping charger below the re-arm level stays quiet
+        assert_eq!(
+            run(
+                &mut alerts,
+                &[(12, true), (12, false), (13, true), (11, false)]
+            ),
+            vec![false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn test_devices_tracked_independently() {
+        let mut alerts = LowBatteryAlerts::new();
+        assert!(alerts.update("phone", 10, false, 15));
+        assert!(alerts.update("tablet", 10, false, 15));
+        assert!(!alerts.update("phone", 9, false, 15));
+    }
+
+    #[test]
+    fn test_zero_threshold_disables_alerts() {
+        let mut alerts = LowBatteryAlerts::new();
+        assert!(!alerts.update("phone", 0, false, 0));
+    }
+}
@@ -2,16 +2,34 @@
 //!
 //! Configuration management for the CConnect daemon.
 
+use crate::disconnect_action::DisconnectAction;
+use crate::metered_policy::{MeteredAction, MeteredFeature};
+use crate::notification_listener::AppCaptureRule;
+use crate::ring_action::RingAction;
+use crate::schema::{self, Migration};
 use anyhow::{Context, Result};
-use cosmic_ext_connect_protocol::TransportPreference;
+use cosmic_ext_connect_protocol::plugins::presenter::SlideKey;
+use cosmic_ext_connect_protocol::{
+    CompressionConfig, TcpKeepaliveConfig, TcpSocketOptions, TransportPreference,
+};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use std::time::Duration;
 
+/// Current schema version of `daemon.toml`
+pub const CONFIG_SCHEMA_VERSION: u32 = 1;
+
+/// Migrations for `daemon.toml`, see [`schema::migrate`]
+const CONFIG_MIGRATIONS: &[Migration] = &[];
+
 /// Daemon configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Schema version of the config file
+    #[serde(default = "default_config_schema_version")]
+    pub schema_version: u32,
+
     /// Device configuration
     pub device: DeviceConfig,
 
@@ -29,6 +47,18 @@ pub struct Config {
     #[serde(default)]
     pub notification_listener: NotificationListenerConfig,
 
+    /// Opt-in local usage report
+    #[serde(default)]
+    pub usage_report: UsageReportConfig,
+
+    /// Per-device disconnect action permissions
+    #[serde(default)]
+    pub disconnect_actions: DisconnectActionConfig,
+
+    /// Handling of heavy operations while a peer is on mobile data
+    #[serde(default)]
+    pub metered: MeteredConfig,
+
     /// Storage paths
     pub paths: PathConfig,
 }
@@ -69,6 +99,10 @@ pub struct NetworkConfig {
     /// Device timeout in seconds (how long before a device is considered offline)
     #[serde(default = "default_device_timeout")]
     pub device_timeout: u64,
+
+    /// Pairing timeout in seconds (how long a pairing request waits for an answer)
+    #[serde(default = "default_pairing_timeout")]
+    pub pairing_timeout: u64,
 }
 
 /// Transport configuration
@@ -84,6 +118,14 @@ pub struct TransportConfig {
     #[serde(default = "default_false")]
     pub enable_bluetooth: bool,
 
+    /// Enable QUIC transport
+    #[serde(default = "default_false")]
+    pub enable_quic: bool,
+
+    /// UDP port for incoming QUIC connections
+    #[serde(default = "default_quic_port")]
+    pub quic_port: u16,
+
     /// Transport preference for new connections
     #[serde(default)]
     pub preference: TransportPreferenceConfig,
@@ -103,6 +145,45 @@ pub struct TransportConfig {
     /// Bluetooth device filtering (empty = no filter, accepts all)
     #[serde(default)]
     pub bluetooth_device_filter: Vec<String>,
+
+    /// Compress large packets for devices that support it
+    #[serde(default = "default_true")]
+    pub enable_compression: bool,
+
+    /// Packet body size in bytes from which packets are compressed
+    #[serde(default = "default_compression_threshold")]
+    pub compression_threshold: usize,
+
+    /// Largest packet in bytes accepted from a device; a device sending a
+    /// larger one is disconnected
+    #[serde(default = "default_max_packet_size")]
+    pub max_packet_size: usize,
+
+    /// Close a connection after this many seconds without traffic from the
+    /// device (0 = keep idle connections open). Never shorter than three
+    /// keepalive intervals of the device.
+    #[serde(default)]
+    pub heartbeat_timeout_secs: u64,
+
+    /// Send small packets immediately (`TCP_NODELAY`)
+    #[serde(default = "default_true")]
+    pub tcp_nodelay: bool,
+
+    /// Let the OS probe idle TCP connections to detect dead peers
+    #[serde(default = "default_true")]
+    pub tcp_keepalive: bool,
+
+    /// Idle time in seconds before the first keepalive probe
+    #[serde(default = "default_tcp_keepalive_idle")]
+    pub tcp_keepalive_idle_secs: u64,
+
+    /// Time in seconds between keepalive probes
+    #[serde(default = "default_tcp_keepalive_interval")]
+    pub tcp_keepalive_interval_secs: u64,
+
+    /// Unanswered keepalive probes before the connection is dropped
+    #[serde(default = "default_tcp_keepalive_probes")]
+    pub tcp_keepalive_probes: u32,
 }
 
 /// Transport preference configuration (serialization wrapper)
@@ -166,6 +247,25 @@ pub struct NotificationListenerConfig {
     #[serde(default)]
     pub included_apps: Vec<String>,
 
+    /// Per-app capture rules, evaluated top to bottom after the app lists
+    ///
+    /// Patterns may use `*` and `?` wildcards and also match the app's desktop
+    /// entry. A matching rule can include or exclude the app and override
+    /// `include_transient`/`include_low_urgency`; later rules win. For example:
+    ///
+    /// ```toml
+    /// [[notification_listener.app_rules]]
+    /// app = "Firefox*"
+    /// action = "exclude"
+    ///
+    /// [[notification_listener.app_rules]]
+    /// app = "Firefox Developer Edition"
+    /// action = "include"
+    /// include_low_urgency = false
+    /// ```
+    #[serde(default)]
+    pub app_rules: Vec<AppCaptureRule>,
+
     /// Include transient notifications (e.g., temporary notifications that auto-dismiss)
     #[serde(default = "default_false")]
     pub include_transient: bool,
@@ -183,6 +283,148 @@ pub struct NotificationListenerConfig {
     /// traffic and improve performance on mobile devices.
     #[serde(default = "default_max_body_length")]
     pub max_body_length: usize,
+
+    /// Length of the notification rate limit window in seconds
+    #[serde(default = "default_notification_rate_window_secs")]
+    pub rate_limit_window_secs: u64,
+
+    /// Maximum notifications forwarded to all devices per window (0 = unlimited)
+    ///
+    /// Notifications over the limit are dropped and a single summary with the
+    /// number of suppressed notifications is sent once the window has passed.
+    #[serde(default = "default_max_forwarded_per_window")]
+    pub max_forwarded_per_window: u32,
+
+    /// Maximum notifications forwarded to each device per window (0 = unlimited)
+    ///
+    /// Can be overridden per device.
+    #[serde(default = "default_max_forwarded_per_device")]
+    pub max_forwarded_per_device: u32,
+}
+
+impl NotificationListenerConfig {
+    /// Notification rate limit window as a Duration
+    pub fn rate_limit_window(&self) -> Duration {
+        Duration::from_secs(self.rate_limit_window_secs)
+    }
+}
+
+/// Usage report configuration
+///
+/// Controls the local, anonymized aggregate of peer protocol versions, plugins
+/// and transports. Off by default; the report is never sent anywhere.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageReportConfig {
+    /// Record the usage report (requires explicit user consent)
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+}
+
+/// Disconnect action configuration
+///
+/// Per-device disconnect actions only run if their type is allowed here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisconnectActionConfig {
+    /// Seconds a device must stay offline before its action runs
+    #[serde(default = "default_disconnect_grace_period_secs")]
+    pub grace_period_secs: u64,
+
+    /// Allow locking the desktop
+    #[serde(default = "default_true")]
+    pub allow_lock: bool,
+
+    /// Allow suspending the system
+    #[serde(default = "default_false")]
+    pub allow_suspend: bool,
+
+    /// Allow running shell commands
+    #[serde(default = "default_false")]
+    pub allow_run_command: bool,
+}
+
+impl DisconnectActionConfig {
+    /// Grace period as a Duration
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period_secs)
+    }
+
+    /// Whether `action` is allowed to run
+    pub fn permits(&self, action: &DisconnectAction) -> bool {
+        match action {
+            DisconnectAction::None => true,
+            DisconnectAction::Lock => self.allow_lock,
+            DisconnectAction::Suspend => self.allow_suspend,
+            DisconnectAction::RunCommand { .. } => self.allow_run_command,
+        }
+    }
+}
+
+impl Default for DisconnectActionConfig {
+    fn default() -> Self {
+        Self {
+            grace_period_secs: default_disconnect_grace_period_secs(),
+            allow_lock: true,
+            allow_suspend: false,
+            allow_run_command: false,
+        }
+    }
+}
+
+/// Metered connection configuration
+///
+/// While a device reports mobile data through the connectivity report plugin,
+/// or NetworkManager reports this desktop's link as metered, each heavy
+/// feature with that device runs, warns or is deferred.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeteredConfig {
+    /// Apply the per-feature actions while a connection is metered
+    #[serde(default = "default_true")]
+    pub avoid_metered: bool,
+
+    /// Payload size from which file transfers count as heavy
+    #[serde(default = "default_metered_large_payload_bytes")]
+    pub large_payload_bytes: u64,
+
+    /// Action for sending files
+    #[serde(default = "default_metered_warn")]
+    pub file_share: MeteredAction,
+
+    /// Action for file sync transfers
+    #[serde(default = "default_metered_defer")]
+    pub file_sync: MeteredAction,
+
+    /// Action for starting remote desktop sessions
+    #[serde(default = "default_metered_defer")]
+    pub remote_desktop: MeteredAction,
+
+    /// Action for starting screen share sessions
+    #[serde(default = "default_metered_warn")]
+    pub screen_share: MeteredAction,
+}
+
+impl MeteredConfig {
+    /// Configured action for a feature
+    pub fn action_for(&self, feature: MeteredFeature) -> MeteredAction {
+        match feature {
+            MeteredFeature::FileShare => self.file_share,
+            MeteredFeature::FileSync => self.file_sync,
+            MeteredFeature::RemoteDesktop => self.remote_desktop,
+            MeteredFeature::ScreenShare => self.screen_share,
+        }
+    }
+}
+
+impl Default for MeteredConfig {
+    fn default() -> Self {
+        Self {
+            avoid_metered: true,
+            large_payload_bytes: default_metered_large_payload_bytes(),
+            file_share: MeteredAction::Warn,
+            file_sync: MeteredAction::Defer,
+            remote_desktop: MeteredAction::Defer,
+            screen_share: MeteredAction::Warn,
+        }
+    }
 }
 
 /// Plugin configuration
@@ -196,6 +438,11 @@ pub struct PluginConfig {
     #[serde(default = "default_true")]
     pub enable_battery: bool,
 
+    /// Battery percentage at or below which a discharging device raises a
+    /// low battery notification (0 disables)
+    #[serde(default = "default_low_battery_threshold")]
+    pub low_battery_threshold: u8,
+
     /// Enable notification plugin
     #[serde(default = "default_true")]
     pub enable_notification: bool,
@@ -204,10 +451,23 @@ pub struct PluginConfig {
     #[serde(default = "default_true")]
     pub enable_share: bool,
 
+    /// Save received files into a subfolder named after the sending device
+    ///
+    /// Files go to `Downloads/<device name>/` instead of `Downloads/`.
+    /// Can be overridden per device.
+    #[serde(default = "default_false")]
+    pub share_device_subfolders: bool,
+
     /// Enable clipboard plugin
     #[serde(default = "default_true")]
     pub enable_clipboard: bool,
 
+    /// Also sync the PRIMARY selection (selected text, middle-click paste)
+    ///
+    /// Off by default: the PRIMARY selection changes on every text selection.
+    #[serde(default = "default_false")]
+    pub clipboard_sync_primary: bool,
+
     /// Enable MPRIS plugin
     #[serde(default = "default_true")]
     pub enable_mpris: bool,
@@ -220,6 +480,10 @@ pub struct PluginConfig {
     #[serde(default = "default_true")]
     pub enable_remoteinput: bool,
 
+    /// Speed up fast pointer movements from the phone's touchpad
+    #[serde(default = "default_false")]
+    pub remoteinput_pointer_acceleration: bool,
+
     /// Enable Find My Phone plugin
     #[serde(default = "default_true")]
     pub enable_findmyphone: bool,
@@ -232,10 +496,30 @@ pub struct PluginConfig {
     #[serde(default = "default_true")]
     pub enable_telephony: bool,
 
+    /// Action to run while a phone rings, undone when the call ends
+    ///
+    /// One of `none`, `pause_media` or `mute`.
+    #[serde(default)]
+    pub telephony_ring_action: RingAction,
+
     /// Enable Presenter plugin
     #[serde(default = "default_true")]
     pub enable_presenter: bool,
 
+    /// Key pressed for the next slide in presenter mode
+    #[serde(default = "default_presenter_next_key")]
+    pub presenter_next_key: SlideKey,
+
+    /// Key pressed for the previous slide in presenter mode
+    #[serde(default = "default_presenter_previous_key")]
+    pub presenter_previous_key: SlideKey,
+
+    /// Output the presenter laser pointer is shown on (e.g. "HDMI-A-1")
+    ///
+    /// Unset spans the pointer across all outputs.
+    #[serde(default)]
+    pub presenter_output: Option<String>,
+
     /// Enable Contacts plugin
     #[serde(default = "default_true")]
     pub enable_contacts: bool,
@@ -260,6 +544,11 @@ pub struct PluginConfig {
     #[serde(default = "default_true")]
     pub enable_power: bool,
 
+    /// Also require PolicyKit authorization for power, run-command and
+    /// remote input actions from allowlisted devices
+    #[serde(default = "default_false")]
+    pub permission_polkit: bool,
+
     /// Enable ClipboardHistory plugin (persistent clipboard history)
     #[serde(default = "default_true")]
     pub enable_clipboardhistory: bool,
@@ -280,6 +569,12 @@ pub struct PluginConfig {
     #[serde(default = "default_true")]
     pub enable_filesync: bool,
 
+    /// Maximum number of file sync conflicts kept for manual resolution
+    ///
+    /// The oldest conflicts are dropped beyond this limit.
+    #[serde(default = "default_filesync_max_pending_conflicts")]
+    pub filesync_max_pending_conflicts: usize,
+
     /// Enable ScreenShare plugin (one-way screen sharing for presentations)
     #[serde(default = "default_true")]
     pub enable_screenshare: bool,
@@ -299,6 +594,13 @@ pub struct PluginConfig {
     #[serde(default = "default_true")]
     pub enable_networkshare: bool,
 
+    /// How long received SFTP credentials are considered fresh, in seconds
+    ///
+    /// New mounts require fresh credentials. Already mounted shares are kept
+    /// while the mount is healthy. Can be overridden per device.
+    #[serde(default = "default_networkshare_freshness_secs")]
+    pub networkshare_freshness_secs: u64,
+
     /// Enable Camera plugin (remote camera/webcam access)
     #[serde(default = "default_true")]
     pub enable_camera: bool,
@@ -314,6 +616,12 @@ pub struct PluginConfig {
     /// Enable ExtendedDisplay plugin (wireless extended display to Android tablet)
     #[serde(default = "default_true")]
     pub enable_extendeddisplay: bool,
+
+    /// Maximum number of CPU-heavy plugin jobs running at once
+    ///
+    /// Covers file sync hashing and process scanning. Applied at startup.
+    #[serde(default = "default_cpu_pool_max_concurrent")]
+    pub cpu_pool_max_concurrent: usize,
 }
 
 /// Storage paths configuration
@@ -349,6 +657,10 @@ fn default_device_timeout() -> u64 {
     30
 }
 
+fn default_pairing_timeout() -> u64 {
+    30
+}
+
 fn default_tcp_timeout() -> u64 {
     10
 }
@@ -357,6 +669,34 @@ fn default_bluetooth_timeout() -> u64 {
     15
 }
 
+fn default_quic_port() -> u16 {
+    1717
+}
+
+fn default_compression_threshold() -> usize {
+    CompressionConfig::default().threshold
+}
+
+fn default_tcp_keepalive_idle() -> u64 {
+    TcpKeepaliveConfig::default().idle.as_secs()
+}
+
+fn default_tcp_keepalive_interval() -> u64 {
+    TcpKeepaliveConfig::default().interval.as_secs()
+}
+
+fn default_tcp_keepalive_probes() -> u32 {
+    TcpKeepaliveConfig::default().probes
+}
+
+fn default_max_packet_size() -> usize {
+    cosmic_ext_connect_protocol::transport::DEFAULT_MAX_PACKET_SIZE
+}
+
+fn default_config_schema_version() -> u32 {
+    CONFIG_SCHEMA_VERSION
+}
+
 fn default_true() -> bool {
     true
 }
@@ -365,10 +705,62 @@ fn default_false() -> bool {
     false
 }
 
+fn default_disconnect_grace_period_secs() -> u64 {
+    30
+}
+
+fn default_metered_large_payload_bytes() -> u64 {
+    10 * 1024 * 1024 // 10 MiB
+}
+
+fn default_presenter_next_key() -> SlideKey {
+    SlideKey::PageDown
+}
+
+fn default_presenter_previous_key() -> SlideKey {
+    SlideKey::PageUp
+}
+
+fn default_metered_warn() -> MeteredAction {
+    MeteredAction::Warn
+}
+
+fn default_metered_defer() -> MeteredAction {
+    MeteredAction::Defer
+}
+
 fn default_max_body_length() -> usize {
     2000
 }
 
+fn default_notification_rate_window_secs() -> u64 {
+    60
+}
+
+fn default_max_forwarded_per_window() -> u32 {
+    60
+}
+
+fn default_max_forwarded_per_device() -> u32 {
+    30
+}
+
+fn default_low_battery_threshold() -> u8 {
+    crate::battery_alert::DEFAULT_LOW_BATTERY_THRESHOLD
+}
+
+fn default_networkshare_freshness_secs() -> u64 {
+    cosmic_ext_connect_protocol::plugins::networkshare::DEFAULT_FRESHNESS_WINDOW_SECS
+}
+
+fn default_filesync_max_pending_conflicts() -> usize {
+    cosmic_ext_connect_protocol::plugins::filesync::DEFAULT_MAX_PENDING_CONFLICTS
+}
+
+fn default_cpu_pool_max_concurrent() -> usize {
+    cosmic_ext_connect_protocol::cpu_pool::DEFAULT_MAX_CONCURRENT
+}
+
 impl Default for NetworkConfig {
     fn default() -> Self {
         Self {
@@ -377,6 +769,7 @@ impl Default for NetworkConfig {
             transfer_port_end: default_transfer_port_end(),
             discovery_interval: default_discovery_interval(),
             device_timeout: default_device_timeout(),
+            pairing_timeout: default_pairing_timeout(),
         }
     }
 }
@@ -387,9 +780,13 @@ impl Default for NotificationListenerConfig {
             enabled: false,
             excluded_apps: Vec::new(),
             included_apps: Vec::new(),
+            app_rules: Vec::new(),
             include_transient: false,
             include_low_urgency: true,
             max_body_length: default_max_body_length(),
+            rate_limit_window_secs: default_notification_rate_window_secs(),
+            max_forwarded_per_window: default_max_forwarded_per_window(),
+            max_forwarded_per_device: default_max_forwarded_per_device(),
         }
     }
 }
@@ -401,6 +798,9 @@ impl Default for TransportConfig {
             enable_tcp: true,
             // Bluetooth disabled by default (opt-in)
             enable_bluetooth: false,
+            // QUIC disabled by default (opt-in)
+            enable_quic: false,
+            quic_port: default_quic_port(),
             // Prefer TCP by default (faster, more reliable on local network)
             preference: TransportPreferenceConfig::PreferTcp,
             // TCP timeout: 10 seconds
@@ -411,6 +811,20 @@ impl Default for TransportConfig {
             auto_fallback: true,
             // No device filter by default (accept all)
             bluetooth_device_filter: Vec::new(),
+            // Compress bodies of 16 KiB and more
+            enable_compression: true,
+            compression_threshold: default_compression_threshold(),
+            // 1 MiB
+            max_packet_size: default_max_packet_size(),
+            // Idle connections stay open
+            heartbeat_timeout_secs: 0,
+            tcp_nodelay: true,
+            // First probe after 60 seconds idle, then every 10 seconds,
+            // dropped after 3 unanswered probes
+            tcp_keepalive: true,
+            tcp_keepalive_idle_secs: default_tcp_keepalive_idle(),
+            tcp_keepalive_interval_secs: default_tcp_keepalive_interval(),
+            tcp_keepalive_probes: default_tcp_keepalive_probes(),
         }
     }
 }
@@ -426,6 +840,31 @@ impl TransportConfig {
         Duration::from_secs(self.bluetooth_timeout_secs)
     }
 
+    /// Get the heartbeat timeout, `None` if disabled
+    pub fn heartbeat_timeout(&self) -> Option<Duration> {
+        (self.heartbeat_timeout_secs > 0).then(|| Duration::from_secs(self.heartbeat_timeout_secs))
+    }
+
+    /// Get the options applied to TCP sockets
+    pub fn socket_options(&self) -> TcpSocketOptions {
+        TcpSocketOptions {
+            nodelay: self.tcp_nodelay,
+            keepalive: self.tcp_keepalive.then(|| TcpKeepaliveConfig {
+                idle: Duration::from_secs(self.tcp_keepalive_idle_secs),
+                interval: Duration::from_secs(self.tcp_keepalive_interval_secs),
+                probes: self.tcp_keepalive_probes,
+            }),
+        }
+    }
+
+    /// Get packet compression settings
+    pub fn compression(&self) -> CompressionConfig {
+        CompressionConfig {
+            enabled: self.enable_compression,
+            threshold: self.compression_threshold,
+        }
+    }
+
     /// Check if a Bluetooth device address should be accepted
     #[allow(dead_code)]
     pub fn should_accept_bluetooth_device(&self, address: &str) -> bool {
@@ -446,35 +885,47 @@ impl Default for PluginConfig {
         Self {
             enable_ping: true,
             enable_battery: true,
+            low_battery_threshold: default_low_battery_threshold(),
             enable_notification: true,
             enable_share: true,
+            share_device_subfolders: false,
             enable_clipboard: true,
+            clipboard_sync_primary: false,
             enable_mpris: true,
             enable_runcommand: true,
             enable_remoteinput: true,
+            remoteinput_pointer_acceleration: false,
             enable_findmyphone: true,
             enable_lock: true,
             enable_telephony: true,
+            telephony_ring_action: RingAction::None,
             enable_presenter: true,
+            presenter_next_key: SlideKey::PageDown,
+            presenter_previous_key: SlideKey::PageUp,
+            presenter_output: None,
             enable_contacts: true,
             enable_systemmonitor: true,
             enable_wol: true,
             enable_screenshot: true,
             enable_remotedesktop: true,
             enable_power: true,
+            permission_polkit: false,
             enable_clipboardhistory: true,
             enable_macro: true,
             enable_chat: true,
             enable_audiostream: true,
             enable_filesync: true,
+            filesync_max_pending_conflicts: default_filesync_max_pending_conflicts(),
             enable_screenshare: true,
             screenshare_restore_session: true,
             enable_mousekeyboardshare: true,
             enable_networkshare: true,
+            networkshare_freshness_secs: default_networkshare_freshness_secs(),
             enable_camera: true,
             enable_systemvolume: true,
             enable_connectivityreport: true,
             enable_extendeddisplay: true,
+            cpu_pool_max_concurrent: default_cpu_pool_max_concurrent(),
         }
     }
 }
@@ -494,6 +945,7 @@ impl Default for Config {
         let cert_dir = config_dir.join("certs");
 
         Self {
+            schema_version: CONFIG_SCHEMA_VERSION,
             device: DeviceConfig {
                 name: format!(
                     "CD-{}",
@@ -509,6 +961,9 @@ impl Default for Config {
             transport: TransportConfig::default(),
             plugins: PluginConfig::default(),
             notification_listener: NotificationListenerConfig::default(),
+            usage_report: UsageReportConfig::default(),
+            disconnect_actions: DisconnectActionConfig::default(),
+            metered: MeteredConfig::default(),
             paths: PathConfig {
                 config_dir,
                 data_dir,
@@ -531,9 +986,7 @@ impl Config {
         if config_path.exists() {
             let contents =
                 fs::read_to_string(&config_path).context("Failed to read config file")?;
-            let config: Config =
-                toml::from_str(&contents).context("Failed to parse config file")?;
-            Ok(config)
+            Self::from_toml(&contents)
         } else {
             // Create default config
             let config = Config::default();
@@ -542,6 +995,15 @@ impl Config {
         }
     }
 
+    /// Parse a config file, migrating older schema versions
+    fn from_toml(contents: &str) -> Result<Self> {
+        let doc: serde_json::Value =
+            toml::from_str(contents).context("Failed to parse config file")?;
+        let doc = schema::upgrade(doc, CONFIG_SCHEMA_VERSION, CONFIG_MIGRATIONS)
+            .context("Unsupported config file")?;
+        serde_json::from_value(doc).context("Failed to parse config file")
+    }
+
     /// Save configuration to file
     pub fn save(&self) -> Result<()> {
         // Ensure config directory exists
@@ -637,6 +1099,29 @@ mod tests {
         assert_eq!(config.network.transfer_port_start, 1739);
         assert!(config.plugins.enable_ping);
         assert!(config.plugins.enable_battery);
+        assert_eq!(config.plugins.low_battery_threshold, 15);
+        assert_eq!(config.plugins.networkshare_freshness_secs, 300);
+        assert_eq!(config.plugins.cpu_pool_max_concurrent, 2);
+        assert_eq!(config.plugins.filesync_max_pending_conflicts, 100);
+        assert!(!config.plugins.share_device_subfolders);
+        assert!(!config.plugins.clipboard_sync_primary);
+        assert_eq!(config.plugins.telephony_ring_action, RingAction::None);
+        assert!(!config.plugins.remoteinput_pointer_acceleration);
+        assert!(!config.plugins.permission_polkit);
+        assert_eq!(config.plugins.presenter_next_key, SlideKey::PageDown);
+        assert_eq!(config.plugins.presenter_previous_key, SlideKey::PageUp);
+        assert!(!config.usage_report.enabled);
+        assert_eq!(config.disconnect_actions.grace_period_secs, 30);
+        assert!(config.disconnect_actions.permits(&DisconnectAction::Lock));
+        assert!(!config
+            .disconnect_actions
+            .permits(&DisconnectAction::Suspend));
+        assert!(config.metered.avoid_metered);
+        assert_eq!(
+            config.metered.action_for(MeteredFeature::FileSync),
+            MeteredAction::Defer
+        );
+        assert_eq!(config.metered.file_share, MeteredAction::Warn);
     }
 
     #[test]
@@ -647,14 +1132,44 @@ mod tests {
         assert_eq!(parsed.network.discovery_port, config.network.discovery_port);
     }
 
+    #[test]
+    fn test_load_unversioned_config() {
+        // Files written before versioning have no schema_version
+        let toml_str = toml::to_string(&Config::default()).unwrap();
+        let unversioned: String = toml_str
+            .lines()
+            .filter(|line| !line.starts_with("schema_version"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        assert!(!unversioned.contains("schema_version"));
+
+        let parsed = Config::from_toml(&unversioned).unwrap();
+        assert_eq!(parsed.schema_version, CONFIG_SCHEMA_VERSION);
+        assert_eq!(parsed.network.discovery_port, 1814);
+    }
+
+    #[test]
+    fn test_reject_newer_config() {
+        let toml_str = toml::to_string(&Config::default()).unwrap().replace(
+            &format!("schema_version = {}", CONFIG_SCHEMA_VERSION),
+            &format!("schema_version = {}", CONFIG_SCHEMA_VERSION + 1),
+        );
+
+        let err = Config::from_toml(&toml_str).unwrap_err();
+        assert!(format!("{:#}", err).contains("newer than the supported version"));
+    }
+
     #[test]
     fn test_transport_config_defaults() {
         let transport = TransportConfig::default();
         assert!(transport.enable_tcp);
         assert!(!transport.enable_bluetooth);
+        assert!(!transport.enable_quic);
+        assert_eq!(transport.quic_port, 1717);
         assert!(transport.auto_fallback);
         assert_eq!(transport.tcp_timeout_secs, 10);
         assert_eq!(transport.bluetooth_timeout_secs, 15);
+        assert_eq!(transport.compression(), CompressionConfig::default());
     }
 
     #[test]
@@ -662,6 +1177,38 @@ mod tests {
         let transport = TransportConfig::default();
         assert_eq!(transport.tcp_timeout(), Duration::from_secs(10));
         assert_eq!(transport.bluetooth_timeout(), Duration::from_secs(15));
+        assert_eq!(transport.heartbeat_timeout(), None);
+    }
+
+    #[test]
+    fn test_heartbeat_timeout_opt_in() {
+        let transport: TransportConfig = toml::from_str("heartbeat_timeout_secs = 300").unwrap();
+        assert_eq!(
+            transport.heartbeat_timeout(),
+            Some(Duration::from_secs(300))
+        );
+    }
+
+    #[test]
+    fn test_socket_options() {
+        assert_eq!(
+            TransportConfig::default().socket_options(),
+            TcpSocketOptions::default()
+        );
+
+        let transport: TransportConfig = toml::from_str(
+            "tcp_nodelay = false\ntcp_keepalive_idle_secs = 30\ntcp_keepalive_probes = 5",
+        )
+        .unwrap();
+        let options = transport.socket_options();
+        assert!(!options.nodelay);
+        let keepalive = options.keepalive.unwrap();
+        assert_eq!(keepalive.idle, Duration::from_secs(30));
+        assert_eq!(keepalive.interval, Duration::from_secs(10));
+        assert_eq!(keepalive.probes, 5);
+
+        let transport: TransportConfig = toml::from_str("tcp_keepalive = false").unwrap();
+        assert!(transport.socket_options().keepalive.is_none());
     }
 
     #[test]
@@ -693,9 +1240,13 @@ mod tests {
         assert!(!config.enabled);
         assert!(config.excluded_apps.is_empty());
         assert!(config.included_apps.is_empty());
+        assert!(config.app_rules.is_empty());
         assert!(!config.include_transient);
         assert!(config.include_low_urgency);
         assert_eq!(config.max_body_length, 2000);
+        assert_eq!(config.rate_limit_window(), Duration::from_secs(60));
+        assert_eq!(config.max_forwarded_per_window, 60);
+        assert_eq!(config.max_forwarded_per_device, 30);
     }
 
     #[test]
@@ -719,6 +1270,33 @@ mod tests {
         assert_eq!(parsed.max_body_length, 1500);
     }
 
+    #[test]
+    fn test_notification_app_rules_parse() {
+        use crate::notification_listener::CaptureAction;
+
+        let config: NotificationListenerConfig = toml::from_str(
+            r#"
+            [[app_rules]]
+            app = "Firefox*"
+            action = "exclude"
+
+            [[app_rules]]
+            app = "Calendar"
+            include_low_urgency = false
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.app_rules.len(), 2);
+        assert_eq!(config.app_rules[0].action, Some(CaptureAction::Exclude));
+        assert_eq!(config.app_rules[1].action, None);
+        assert_eq!(config.app_rules[1].include_low_urgency, Some(false));
+
+        let toml_str = toml::to_string(&config).unwrap();
+        let parsed: NotificationListenerConfig = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.app_rules, config.app_rules);
+    }
+
     #[test]
     fn test_config_with_notification_listener() {
         let config = Config::default();
@@ -43,6 +43,15 @@ pub enum Urgency {
     Critical = 2,
 }
 
+/// Signal from the notification server about one of our notifications
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotificationSignal {
+    /// The user clicked an action (notification ID, action key)
+    ActionInvoked(u32, String),
+    /// The notification was closed (expired, dismissed or closed by us)
+    Closed(u32),
+}
+
 /// Notification builder for COSMIC Desktop
 #[derive(Debug, Clone)]
 pub struct NotificationBuilder {
@@ -480,6 +489,25 @@ impl CosmicNotifier {
         .await
     }
 
+    /// Ask the user to confirm a command requested by a device
+    pub async fn notify_run_command_confirmation(
+        &self,
+        device_name: &str,
+        name: &str,
+        command: &str,
+    ) -> Result<u32> {
+        self.send(
+            NotificationBuilder::new(format!("{} wants to run \"{}\"", device_name, name))
+                .body(command)
+                .icon("utilities-terminal-symbolic")
+                .urgency(Urgency::Normal)
+                .timeout(0) // Wait for the user to decide
+                .action("run", "Run")
+                .action("cancel", "Cancel"),
+        )
+        .await
+    }
+
     /// Send a file received notification
     pub async fn notify_file_received(
         &self,
@@ -510,6 +538,30 @@ impl CosmicNotifier {
         .await
     }
 
+    /// Send an incoming call notification
+    pub async fn notify_incoming_call(&self, device_name: &str, caller: &str) -> Result<u32> {
+        self.send(
+            NotificationBuilder::new(format!("Incoming Call on {}", device_name))
+                .body(caller)
+                .icon("call-start-symbolic")
+                .urgency(Urgency::Critical)
+                .timeout(30000),
+        )
+        .await
+    }
+
+    /// Send a missed call notification
+    pub async fn notify_missed_call(&self, device_name: &str, caller: &str) -> Result<u32> {
+        self.send(
+            NotificationBuilder::new(format!("Missed Call on {}", device_name))
+                .body(caller)
+                .icon("call-missed-symbolic")
+                .urgency(Urgency::Normal)
+                .timeout(0),
+        )
+        .await
+    }
+
     /// Send a device connected notification
     #[allow(dead_code)]
     pub async fn notify_device_connected(&self, device_name: &str) -> Result<u32> {
@@ -638,7 +690,6 @@ impl CosmicNotifier {
     }
 
     /// Send a disk space error notification
-    #[allow(dead_code)]
     pub async fn notify_disk_full_error(&self, path: &str) -> Result<u32> {
         self.send(
             NotificationBuilder::new("Disk Full")
@@ -719,6 +770,36 @@ impl CosmicNotifier {
         .await
     }
 
+    /// Send a notification about a heavy operation on a metered connection
+    pub async fn notify_metered_operation(
+        &self,
+        device_name: &str,
+        feature: &str,
+        deferred: bool,
+    ) -> Result<u32> {
+        let body = if deferred {
+            format!(
+                "The connection to {} is metered. {} was skipped; \
+                 try again on an unmetered network.",
+                device_name, feature
+            )
+        } else {
+            format!(
+                "The connection to {} is metered. {} may use a lot of data.",
+                device_name, feature
+            )
+        };
+
+        self.send(
+            NotificationBuilder::new("Metered Connection")
+                .body(body)
+                .icon("network-cellular-symbolic")
+                .urgency(Urgency::Low)
+                .timeout(7000),
+        )
+        .await
+    }
+
     /// Send a generic error notification with recovery action
     #[allow(dead_code)]
     pub async fn notify_error_with_recovery(
@@ -741,7 +822,6 @@ impl CosmicNotifier {
     }
 
     /// Close a notification by ID
-    #[allow(dead_code)]
     pub async fn close(&self, notification_id: u32) -> Result<()> {
         let proxy = zbus::Proxy::new(
             &self.connection,
@@ -795,12 +875,13 @@ impl CosmicNotifier {
             .and_then(|m| m.get(&notification_id).cloned())
     }
 
-    /// Subscribe to notification action signals
+    /// Subscribe to notification action and close signals
     ///
-    /// Returns a stream of (notification_id, action_key) tuples when users click notification actions.
+    /// Returns a stream of [`NotificationSignal`]s, in the order the server
+    /// sent them: an action is followed by the close of its notification.
     pub async fn subscribe_actions(
         &self,
-    ) -> Result<impl futures::Stream<Item = (u32, String)> + Unpin> {
+    ) -> Result<impl futures::Stream<Item = NotificationSignal> + Unpin> {
         use futures::stream::StreamExt;
 
         // Create a proxy for the notifications service
@@ -819,7 +900,6 @@ impl CosmicNotifier {
                 .msg_type(zbus::message::Type::Signal)
                 .sender("org.freedesktop.Notifications")?
                 .interface("org.freedesktop.Notifications")?
-                .member("ActionInvoked")?
                 .build(),
             &self.connection,
             Some(64),
@@ -831,7 +911,7 @@ impl CosmicNotifier {
             while let Some(msg_result) = stream.next().await {
                 // Handle the Result from the stream
                 if let Ok(msg) = msg_result {
-                    // Check if this is an ActionInvoked signal
+                    // Check if this is an ActionInvoked or NotificationClosed signal
                     if let Some(member) = msg.header().member() {
                         if member.as_str() == "ActionInvoked" {
                             // Deserialize the message body
@@ -840,7 +920,11 @@ impl CosmicNotifier {
                                     "Notification action invoked: id={}, action={}",
                                     notification_id, action_key
                                 );
-                                yield (notification_id, action_key);
+                                yield NotificationSignal::ActionInvoked(notification_id, action_key);
+                            }
+                        } else if member.as_str() == "NotificationClosed" {
+                            if let Ok((notification_id, _reason)) = msg.body().deserialize::<(u32, u32)>() {
+                                yield NotificationSignal::Closed(notification_id);
                             }
                         }
                     }
@@ -6,61 +6,260 @@
 //! Provides IPC between the background daemon and COSMIC panel applet.
 //! Exposes device management, pairing, and plugin actions via DBus.
 
+use crate::history::{self, History, HistoryFilter};
+use crate::metered_policy::{self, MeteredDecision, MeteredFeature, MeteredLink};
+use crate::signal_batch::{UpdateBatcher, DEFAULT_BATCH_WINDOW};
 use anyhow::{Context, Result};
+use cosmic_ext_connect_protocol::plugins::audit::{AuditEntry, AuditLog, AuditOutcome};
 use cosmic_ext_connect_protocol::plugins::filesync::{
     ConflictStrategy as FilesyncConflictStrategy, FileSyncPlugin, SyncFolder as FilesyncFolder,
 };
-use cosmic_ext_connect_protocol::{ConnectionManager, Device, DeviceManager, PluginManager};
+use cosmic_ext_connect_protocol::plugins::findmyphone::FindMyPhonePlugin;
+use cosmic_ext_connect_protocol::plugins::mpris::{MprisPlugin, PlaybackAction, PlayerState};
+#[cfg(feature = "native-sftp")]
+use cosmic_ext_connect_protocol::plugins::networkshare::NetworkSharePlugin;
+use cosmic_ext_connect_protocol::plugins::permission::{self, AllowlistPolicy, PolkitSubject};
+use cosmic_ext_connect_protocol::plugins::ping::PING_TIMEOUT;
+#[cfg(feature = "native-sftp")]
+use cosmic_ext_connect_protocol::plugins::sftp_client::SftpEntry;
+use cosmic_ext_connect_protocol::plugins::share::SharePlugin;
+use cosmic_ext_connect_protocol::{
+    CapabilityDiff, ConnectionManager, Device, DeviceManager, LatencyCategory, PluginManager,
+    ProtocolError,
+};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::runtime::Handle;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
+use zbus::message::Header;
 use zbus::object_server::{InterfaceRef, SignalEmitter};
 use zbus::{connection, interface, Connection};
 
-/// Tracks active file transfers with cancellation support
+/// Transfer state: bytes are still moving
+pub const TRANSFER_ACTIVE: &str = "active";
+/// Transfer state: finished successfully
+pub const TRANSFER_COMPLETED: &str = "completed";
+/// Transfer state: aborted by an error
+pub const TRANSFER_FAILED: &str = "failed";
+/// Transfer state: aborted by the user
+pub const TRANSFER_CANCELLED: &str = "cancelled";
+
+/// Number of finished transfers kept for `GetTransfers`
+const MAX_FINISHED_TRANSFERS: usize = 50;
+
+/// File transfer state for DBus serialization
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct Transfer {
+    /// Unique transfer ID
+    pub id: String,
+    /// Device ID on the other end
+    pub device_id: String,
+    /// Name of the file being transferred
+    pub filename: String,
+    /// "sending" or "receiving"
+    pub direction: String,
+    /// Total file size in bytes
+    pub total_bytes: u64,
+    /// Bytes transferred so far
+    pub transferred_bytes: u64,
+    /// Average speed since the transfer started (bytes per second)
+    pub speed_bps: u64,
+    /// One of "active", "completed", "failed" or "cancelled"
+    pub state: String,
+    /// Last update (UNIX timestamp in milliseconds)
+    pub updated_at: i64,
+}
+
+impl Transfer {
+    /// Create an active transfer with nothing transferred yet
+    pub fn new(
+        id: impl Into<String>,
+        device_id: impl Into<String>,
+        filename: impl Into<String>,
+        direction: impl Into<String>,
+        total_bytes: u64,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            device_id: device_id.into(),
+            filename: filename.into(),
+            direction: direction.into(),
+            total_bytes,
+            transferred_bytes: 0,
+            speed_bps: 0,
+            state: TRANSFER_ACTIVE.to_string(),
+            updated_at: now_millis(),
+        }
+    }
+
+    /// Whether the transfer is still running
+    pub fn is_active(&self) -> bool {
+        self.state == TRANSFER_ACTIVE
+    }
+
+    /// One-line description of a finished transfer for the history log
+    fn history_summary(&self) -> String {
+        let verb = if self.direction == "sending" {
+            "Sent"
+        } else {
+            "Received"
+        };
+        match self.state.as_str() {
+            TRANSFER_COMPLETED => format!("{} {}", verb, self.filename),
+            TRANSFER_CANCELLED => format!("Cancelled transfer of {}", self.filename),
+            _ => format!("Failed to transfer {}", self.filename),
+        }
+    }
+}
+
+/// Current UNIX time in milliseconds
+fn now_millis() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// A transfer known to the [`TransferManager`]
+struct TrackedTransfer {
+    transfer: Transfer,
+    cancel_flag: Arc<AtomicBool>,
+    started: std::time::Instant,
+}
+
+/// Tracks file transfers with progress and cancellation support
+///
+/// Finished transfers are kept (up to [`MAX_FINISHED_TRANSFERS`]) so clients
+/// that start later can still show them.
 pub struct TransferManager {
-    /// Map of transfer_id -> cancellation flag
-    active_transfers: Arc<RwLock<HashMap<String, Arc<AtomicBool>>>>,
+    /// Map of transfer_id -> tracked transfer
+    transfers: Arc<RwLock<HashMap<String, TrackedTransfer>>>,
+    /// Event log that finished transfers are recorded in
+    history: Option<History>,
 }
 
 impl TransferManager {
     /// Create a new transfer manager
     pub fn new() -> Self {
         Self {
-            active_transfers: Arc::new(RwLock::new(HashMap::new())),
+            transfers: Arc::new(RwLock::new(HashMap::new())),
+            history: None,
+        }
+    }
+
+    /// Create a transfer manager that records finished transfers in `history`
+    pub fn with_history(history: History) -> Self {
+        Self {
+            history: Some(history),
+            ..Self::new()
         }
     }
 
     /// Register a new transfer and get its cancellation flag
-    pub async fn register_transfer(&self, transfer_id: String) -> Arc<AtomicBool> {
+    pub async fn register_transfer(&self, transfer: Transfer) -> Arc<AtomicBool> {
         let cancel_flag = Arc::new(AtomicBool::new(false));
-        self.active_transfers
+        self.track_transfer(transfer, cancel_flag.clone()).await;
+        cancel_flag
+    }
+
+    /// Track a transfer whose cancellation flag is owned elsewhere
+    pub async fn track_transfer(&self, transfer: Transfer, cancel_flag: Arc<AtomicBool>) {
+        let tracked = TrackedTransfer {
+            transfer,
+            cancel_flag,
+            started: std::time::Instant::now(),
+        };
+        self.transfers
             .write()
             .await
-            .insert(transfer_id, cancel_flag.clone());
-        cancel_flag
+            .insert(tracked.transfer.id.clone(), tracked);
     }
 
-    /// Cancel a transfer by ID
+    /// Cancel an active transfer by ID
     pub async fn cancel_transfer(&self, transfer_id: &str) -> bool {
-        if let Some(cancel_flag) = self.active_transfers.read().await.get(transfer_id) {
-            cancel_flag.store(true, Ordering::SeqCst);
-            info!("Transfer {} marked for cancellation", transfer_id);
-            true
-        } else {
-            warn!("Transfer {} not found", transfer_id);
-            false
+        match self.transfers.read().await.get(transfer_id) {
+            Some(tracked) if tracked.transfer.is_active() => {
+                tracked.cancel_flag.store(true, Ordering::SeqCst);
+                info!("Transfer {} marked for cancellation", transfer_id);
+                true
+            }
+            _ => {
+                warn!("Transfer {} not found", transfer_id);
+                false
+            }
+        }
+    }
+
+    /// Record progress of an active transfer, returning its updated state
+    pub async fn update_progress(
+        &self,
+        transfer_id: &str,
+        transferred_bytes: u64,
+        total_bytes: u64,
+    ) -> Option<Transfer> {
+        let mut transfers = self.transfers.write().await;
+        let tracked = transfers
+            .get_mut(transfer_id)
+            .filter(|tracked| tracked.transfer.is_active())?;
+
+        let elapsed = tracked.started.elapsed().as_secs_f64();
+        let transfer = &mut tracked.transfer;
+        transfer.transferred_bytes = transferred_bytes;
+        transfer.total_bytes = total_bytes;
+        if elapsed > 0.0 {
+            transfer.speed_bps = (transferred_bytes as f64 / elapsed) as u64;
         }
+        transfer.updated_at = now_millis();
+        Some(transfer.clone())
     }
 
-    /// Remove a completed or cancelled transfer
-    pub async fn remove_transfer(&self, transfer_id: &str) {
-        self.active_transfers.write().await.remove(transfer_id);
-        debug!("Transfer {} removed from tracking", transfer_id);
+    /// Mark a transfer as finished with `state`, returning its final state
+    pub async fn finish_transfer(&self, transfer_id: &str, state: &str) -> Option<Transfer> {
+        let mut transfers = self.transfers.write().await;
+        let transfer = &mut transfers.get_mut(transfer_id)?.transfer;
+        transfer.state = state.to_string();
+        if state == TRANSFER_COMPLETED {
+            transfer.transferred_bytes = transfer.total_bytes;
+        }
+        transfer.updated_at = now_millis();
+        let finished = transfer.clone();
+        debug!("Transfer {} finished: {}", transfer_id, state);
+
+        if let Some(history) = &self.history {
+            history.record(
+                &finished.device_id,
+                history::EVENT_TRANSFER,
+                finished.history_summary(),
+            );
+        }
+
+        // Forget the oldest finished transfers
+        let mut done: Vec<(i64, String)> = transfers
+            .values()
+            .filter(|tracked| !tracked.transfer.is_active())
+            .map(|tracked| (tracked.transfer.updated_at, tracked.transfer.id.clone()))
+            .collect();
+        if done.len() > MAX_FINISHED_TRANSFERS {
+            done.sort();
+            for (_, id) in done.drain(..done.len() - MAX_FINISHED_TRANSFERS) {
+                transfers.remove(&id);
+            }
+        }
+
+        Some(finished)
+    }
+
+    /// All tracked transfers, active and recently finished
+    pub async fn list_transfers(&self) -> Vec<Transfer> {
+        self.transfers
+            .read()
+            .await
+            .values()
+            .map(|tracked| tracked.transfer.clone())
+            .collect()
     }
 }
 
@@ -70,6 +269,65 @@ impl Default for TransferManager {
     }
 }
 
+/// Emit a `TransferUpdated` signal
+async fn emit_transfer_updated(connection: &Connection, transfer: Transfer) {
+    let object_server = connection.object_server();
+    let iface_ref = match object_server
+        .interface::<_, CConnectInterface>(OBJECT_PATH)
+        .await
+    {
+        Ok(iface) => iface,
+        Err(e) => {
+            warn!("Failed to get interface for signal emission: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = CConnectInterface::transfer_updated(iface_ref.signal_emitter(), transfer).await
+    {
+        warn!("Failed to emit TransferUpdated signal: {}", e);
+    }
+}
+
+/// Record the outcome of an outgoing file share and announce it
+///
+/// An empty `error_message` means the file was sent successfully.
+async fn finish_shared_file(
+    connection: &Connection,
+    transfer_manager: &TransferManager,
+    transfer: &Transfer,
+    cancelled: bool,
+    error_message: String,
+) {
+    let state = if cancelled {
+        TRANSFER_CANCELLED
+    } else if error_message.is_empty() {
+        TRANSFER_COMPLETED
+    } else {
+        TRANSFER_FAILED
+    };
+
+    if let Ok(iface_ref) = connection
+        .object_server()
+        .interface::<_, CConnectInterface>(OBJECT_PATH)
+        .await
+    {
+        let _ = CConnectInterface::transfer_complete(
+            iface_ref.signal_emitter(),
+            &transfer.id,
+            &transfer.device_id,
+            &transfer.filename,
+            state == TRANSFER_COMPLETED,
+            &error_message,
+        )
+        .await;
+    }
+
+    if let Some(finished) = transfer_manager.finish_transfer(&transfer.id, state).await {
+        emit_transfer_updated(connection, finished).await;
+    }
+}
+
 /// DBus service name
 pub const SERVICE_NAME: &str = "io.github.olafkfreund.CosmicExtConnect";
 
@@ -131,6 +389,140 @@ pub struct BatteryStatus {
     pub is_charging: bool,
 }
 
+/// Measured connection latency for DBus serialization
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct PingLatency {
+    /// Round-trip time in milliseconds
+    pub rtt_ms: u64,
+    /// Latency category ("low", "medium" or "high")
+    pub category: String,
+}
+
+/// Media player on a remote device for DBus serialization
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct RemotePlayer {
+    /// Device the player runs on
+    pub device_id: String,
+    /// Player name
+    pub name: String,
+    /// Track title (empty if unknown)
+    pub title: String,
+    /// Track artist (empty if unknown)
+    pub artist: String,
+    /// Album name (empty if unknown)
+    pub album: String,
+    /// Local path of the cached album art (empty until downloaded)
+    pub album_art_path: String,
+    /// Is the player playing
+    pub is_playing: bool,
+    /// Playback position in milliseconds at `updated_at`
+    pub position_ms: i64,
+    /// Track length in milliseconds (0 if unknown)
+    pub length_ms: i64,
+    /// Volume (0-100)
+    pub volume: i32,
+    /// Can start playback
+    pub can_play: bool,
+    /// Can pause playback
+    pub can_pause: bool,
+    /// Can skip to the next track
+    pub can_go_next: bool,
+    /// Can skip to the previous track
+    pub can_go_previous: bool,
+    /// Can seek within the track
+    pub can_seek: bool,
+    /// When the state was received (UNIX timestamp in milliseconds)
+    pub updated_at: i64,
+}
+
+impl RemotePlayer {
+    /// Build from the MPRIS plugin's view of a remote player
+    pub fn from_state(device_id: &str, state: &PlayerState, album_art: Option<&Path>) -> Self {
+        let status = &state.status;
+        let metadata = &state.metadata;
+        Self {
+            device_id: device_id.to_string(),
+            name: state.name.clone(),
+            title: metadata.title.clone().unwrap_or_default(),
+            artist: metadata.artist.clone().unwrap_or_default(),
+            album: metadata.album.clone().unwrap_or_default(),
+            album_art_path: album_art
+                .map(|path| path.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            is_playing: status.is_playing,
+            position_ms: status.position,
+            length_ms: status.length,
+            volume: status.volume,
+            can_play: status.capabilities.can_play,
+            can_pause: status.capabilities.can_pause,
+            can_go_next: status.capabilities.can_go_next,
+            can_go_previous: status.capabilities.can_go_previous,
+            can_seek: status.capabilities.can_seek,
+            updated_at: state.updated_at,
+        }
+    }
+}
+
+/// History event for DBus serialization
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct HistoryEntry {
+    /// Event ID, increasing with time
+    pub id: i64,
+    /// When the event happened (UNIX timestamp in milliseconds)
+    pub timestamp: i64,
+    /// Device the event relates to
+    pub device_id: String,
+    /// "ping", "transfer", "sms", "notification" or "pairing"
+    pub event_type: String,
+    /// Human-readable description
+    pub summary: String,
+}
+
+impl From<history::HistoryRecord> for HistoryEntry {
+    fn from(record: history::HistoryRecord) -> Self {
+        Self {
+            id: record.id,
+            timestamp: record.timestamp,
+            device_id: record.device_id,
+            event_type: record.event_type,
+            summary: record.summary,
+        }
+    }
+}
+
+/// Audit log entry for DBus serialization
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct AuditLogEntry {
+    /// When the decision was made (UNIX timestamp in milliseconds)
+    pub timestamp: i64,
+    /// Device that requested the action
+    pub device_id: String,
+    /// "power", "runcommand" or "remoteinput"
+    pub plugin: String,
+    /// Action detail, e.g. the power action or command key
+    pub action: String,
+    /// Redacted request parameters as JSON
+    pub parameters: String,
+    /// Whether the action was allowed
+    pub allowed: bool,
+    /// Why the action was denied (empty if allowed)
+    pub reason: String,
+}
+
+impl From<AuditEntry> for AuditLogEntry {
+    fn from(entry: AuditEntry) -> Self {
+        Self {
+            timestamp: entry.timestamp,
+            device_id: entry.device_id,
+            plugin: entry.plugin,
+            action: entry.action,
+            parameters: entry.parameters.to_string(),
+            allowed: entry.outcome == AuditOutcome::Allowed,
+            reason: entry.reason.unwrap_or_default(),
+        }
+    }
+}
+
 /// Screen share statistics for DBus serialization
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
 pub struct ScreenShareStats {
@@ -204,6 +596,34 @@ impl From<&FilesyncFolder> for SyncFolderInfo {
     }
 }
 
+/// File or directory on a device's SFTP share for DBus serialization
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct SftpEntryInfo {
+    /// File name
+    pub name: String,
+    /// Path relative to the share root
+    pub path: String,
+    /// Whether the entry is a directory
+    pub is_dir: bool,
+    /// Size in bytes (0 if unknown)
+    pub size: u64,
+    /// Modification time (UNIX timestamp in seconds, 0 if unknown)
+    pub modified: u64,
+}
+
+#[cfg(feature = "native-sftp")]
+impl From<SftpEntry> for SftpEntryInfo {
+    fn from(entry: SftpEntry) -> Self {
+        Self {
+            name: entry.name,
+            path: entry.path,
+            is_dir: entry.is_dir,
+            size: entry.size,
+            modified: entry.modified.unwrap_or_default(),
+        }
+    }
+}
+
 /// DBus interface for CConnect daemon
 pub struct CConnectInterface {
     /// Device manager
@@ -214,6 +634,10 @@ pub struct CConnectInterface {
     connection_manager: Arc<RwLock<ConnectionManager>>,
     /// Device configuration registry
     device_config_registry: Arc<RwLock<crate::device_config::DeviceConfigRegistry>>,
+    /// Privileged plugins allowed per device
+    permission_policy: AllowlistPolicy,
+    /// Audit log of privileged plugin actions
+    audit_log: Arc<AuditLog>,
     /// Pairing service (optional - may not be started yet)
     pairing_service: Option<Arc<RwLock<cosmic_ext_connect_protocol::pairing::PairingService>>>,
     /// MPRIS manager for local media player control (optional)
@@ -226,8 +650,18 @@ pub struct CConnectInterface {
     metrics: Option<Arc<RwLock<crate::diagnostics::Metrics>>>,
     /// Daemon configuration (for settings management)
     config: Arc<RwLock<crate::config::Config>>,
+    /// Apps whose forwarded notifications are temporarily snoozed
+    notification_snoozes: Arc<RwLock<crate::notification_snooze::NotificationSnoozes>>,
+    /// Opt-in anonymized usage report
+    usage_reporter: Arc<RwLock<crate::usage_report::UsageReporter>>,
+    /// Whether this desktop's network link is metered
+    metered_link: MeteredLink,
     /// Transfer manager for tracking and cancelling file transfers
     transfer_manager: Arc<TransferManager>,
+    /// Persistent device event log
+    history: History,
+    /// Outgoing packet channel for plugins started at runtime
+    packet_sender: tokio::sync::mpsc::Sender<(String, cosmic_ext_connect_protocol::Packet)>,
     /// Tokio runtime handle for spawning async tasks from zbus executor
     tokio_handle: Handle,
 }
@@ -240,12 +674,19 @@ impl CConnectInterface {
         plugin_manager: Arc<RwLock<PluginManager>>,
         connection_manager: Arc<RwLock<ConnectionManager>>,
         device_config_registry: Arc<RwLock<crate::device_config::DeviceConfigRegistry>>,
+        permission_policy: AllowlistPolicy,
+        audit_log: Arc<AuditLog>,
         pairing_service: Option<Arc<RwLock<cosmic_ext_connect_protocol::pairing::PairingService>>>,
         mpris_manager: Option<Arc<crate::mpris_manager::MprisManager>>,
         pending_pairing_requests: Arc<RwLock<HashMap<String, bool>>>,
         dbus_connection: Connection,
         metrics: Option<Arc<RwLock<crate::diagnostics::Metrics>>>,
         config: Arc<RwLock<crate::config::Config>>,
+        notification_snoozes: Arc<RwLock<crate::notification_snooze::NotificationSnoozes>>,
+        usage_reporter: Arc<RwLock<crate::usage_report::UsageReporter>>,
+        metered_link: MeteredLink,
+        history: History,
+        packet_sender: tokio::sync::mpsc::Sender<(String, cosmic_ext_connect_protocol::Packet)>,
         tokio_handle: Handle,
     ) -> Self {
         Self {
@@ -253,13 +694,20 @@ impl CConnectInterface {
             plugin_manager,
             connection_manager,
             device_config_registry,
+            permission_policy,
+            audit_log,
             pairing_service,
             mpris_manager,
             pending_pairing_requests,
             dbus_connection,
             metrics,
             config,
-            transfer_manager: Arc::new(TransferManager::new()),
+            notification_snoozes,
+            usage_reporter,
+            metered_link,
+            transfer_manager: Arc::new(TransferManager::with_history(history.clone())),
+            history,
+            packet_sender,
             tokio_handle,
         }
     }
@@ -294,6 +742,143 @@ impl CConnectInterface {
             );
         }
     }
+
+    /// Emit a find_my_phone_ringing signal
+    async fn emit_find_my_phone_ringing(&self, device_id: &str, ringing: bool) {
+        let object_server = self.dbus_connection.object_server();
+        let iface_ref = match object_server
+            .interface::<_, CConnectInterface>(OBJECT_PATH)
+            .await
+        {
+            Ok(iface) => iface,
+            Err(e) => {
+                warn!("Failed to get interface for signal emission: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) =
+            Self::find_my_phone_ringing(iface_ref.signal_emitter(), device_id, ringing).await
+        {
+            warn!("Failed to emit find_my_phone_ringing signal: {}", e);
+        }
+    }
+
+    /// NetworkShare plugin of a device, detached from the plugin lock
+    ///
+    /// SFTP calls may wait for the device to send credentials, which the
+    /// plugin can only receive while its lock is free.
+    #[cfg(feature = "native-sftp")]
+    async fn network_share(&self, device_id: &str) -> Result<NetworkSharePlugin, zbus::fdo::Error> {
+        let plugin_manager = self.plugin_manager.read().await;
+        let plugin = plugin_manager
+            .get_device_plugin_as::<NetworkSharePlugin>(device_id, "networkshare")
+            .await
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed("Network share is not enabled for this device".to_string())
+            })?;
+        Ok(plugin.detached())
+    }
+
+    /// Start or stop a plugin on a connected device to match its config
+    ///
+    /// Plugins the device config disables are stopped; any other plugin is
+    /// started if it has a registered factory and is negotiated with the
+    /// device. Disconnected devices only get their disabled set updated.
+    async fn sync_device_plugin(&self, device_id: &str, plugin_name: &str) {
+        let disabled = {
+            let registry = self.device_config_registry.read().await;
+            registry
+                .get(device_id)
+                .map(|config| config.disabled_plugins())
+                .unwrap_or_default()
+        };
+        let enabled = !disabled.contains(plugin_name);
+
+        let device_manager = self.device_manager.read().await;
+        let mut plugin_manager = self.plugin_manager.write().await;
+        plugin_manager.set_disabled_plugins(device_id, disabled);
+
+        if !enabled {
+            if let Err(e) = plugin_manager.disable_plugin(device_id, plugin_name).await {
+                warn!(
+                    "Failed to stop plugin {} for device {}: {}",
+                    plugin_name, device_id, e
+                );
+            }
+            return;
+        }
+
+        let Some(device) = device_manager.get_device(device_id) else {
+            return;
+        };
+        match plugin_manager
+            .enable_plugin(device_id, plugin_name, device, self.packet_sender.clone())
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => {
+                warn!(
+                    "Failed to start plugin {} for device {}: {}",
+                    plugin_name, device_id, e
+                );
+                return;
+            }
+        }
+
+        // Share and MPRIS payloads arrive over TLS, as set up on connect
+        let tls_config = {
+            let conn_mgr = self.connection_manager.read().await;
+            conn_mgr.tls_config()
+        };
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin(device_id, plugin_name)
+            .await
+        {
+            if let Some(share) = plugin.as_any_mut().downcast_mut::<SharePlugin>() {
+                share.set_tls_config(tls_config);
+            } else if let Some(mpris) = plugin.as_any_mut().downcast_mut::<MprisPlugin>() {
+                mpris.set_tls_config(tls_config);
+            }
+        }
+    }
+
+    /// Build an MPRIS request for a player on a remote device and send it
+    ///
+    /// `build` sees the last reported state so it can check capabilities.
+    async fn send_remote_mpris<F>(
+        &self,
+        device_id: &str,
+        player: &str,
+        build: F,
+    ) -> Result<(), zbus::fdo::Error>
+    where
+        F: FnOnce(
+            &MprisPlugin,
+            PlayerState,
+        ) -> Result<cosmic_ext_connect_protocol::Packet, zbus::fdo::Error>,
+    {
+        let packet = {
+            let plugin_manager = self.plugin_manager.read().await;
+            let plugin = plugin_manager
+                .get_device_plugin_as::<MprisPlugin>(device_id, "mpris")
+                .await
+                .ok_or_else(|| {
+                    zbus::fdo::Error::Failed("MPRIS not available for device".to_string())
+                })?;
+            let state = plugin.get_player_state(player).await.ok_or_else(|| {
+                zbus::fdo::Error::Failed(format!("Player not found: {}", player))
+            })?;
+            build(plugin, state)?
+        };
+
+        let conn_manager = self.connection_manager.read().await;
+        conn_manager
+            .send_packet(device_id, &packet)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to send MPRIS command: {}", e)))
+    }
 }
 
 /// Attempt to manually connect to a device at the specified address
@@ -331,33 +916,49 @@ async fn attempt_manual_connection(
     Err(anyhow::anyhow!("Device did not respond with identity"))
 }
 
-/// Parse vCard data to extract contact information
-fn parse_vcard(vcard_data: &str) -> (String, Vec<String>, Vec<String>) {
-    let mut name = String::new();
-    let mut phone_numbers = Vec::new();
-    let mut emails = Vec::new();
-
-    for line in vcard_data.lines() {
-        let line = line.trim();
-        if let Some(fn_name) = line.strip_prefix("FN:") {
-            name = fn_name.to_string();
-        } else if line.starts_with("TEL") {
-            if let Some(number) = line.split(':').nth(1) {
-                phone_numbers.push(number.to_string());
-            }
-        } else if line.starts_with("EMAIL") {
-            if let Some(email) = line.split(':').nth(1) {
-                emails.push(email.to_string());
-            }
-        }
+/// History filter from DBus arguments, where an empty string matches everything
+fn history_filter<'a>(device_id: &'a str, event_type: &'a str) -> HistoryFilter<'a> {
+    HistoryFilter {
+        device_id: Some(device_id).filter(|id| !id.is_empty()),
+        event_type: Some(event_type).filter(|kind| !kind.is_empty()),
     }
+}
 
-    // Use UID as fallback name if FN not found
-    if name.is_empty() {
-        name = "Unknown".to_string();
+/// Map a protocol error onto the closest standard D-Bus error
+///
+/// Keeps the rejection reason visible to clients (applet, manager) so they can
+/// show e.g. "not paired" or "not supported" instead of a generic failure.
+fn protocol_error_to_dbus(error: &ProtocolError) -> zbus::fdo::Error {
+    match error {
+        ProtocolError::PermissionDenied(_) | ProtocolError::NotPaired => {
+            zbus::fdo::Error::AccessDenied(error.user_message())
+        }
+        ProtocolError::Timeout(_) | ProtocolError::HandshakeTimeout(_) => {
+            zbus::fdo::Error::Timeout(error.user_message())
+        }
+        ProtocolError::UnsupportedFeature(_) => {
+            zbus::fdo::Error::NotSupported(error.user_message())
+        }
+        ProtocolError::DeviceBusy(_) => zbus::fdo::Error::LimitsExceeded(error.user_message()),
+        _ => zbus::fdo::Error::Failed(error.user_message()),
     }
+}
+
+/// Error for SFTP calls in a daemon built without native SFTP support
+#[cfg(not(feature = "native-sftp"))]
+fn sftp_not_supported() -> zbus::fdo::Error {
+    zbus::fdo::Error::NotSupported("The daemon was built without native SFTP support".to_string())
+}
 
-    (name, phone_numbers, emails)
+/// Parse vCard data to extract contact information
+fn parse_vcard(vcard_data: &str) -> (String, Vec<String>, Vec<String>) {
+    let card = cosmic_ext_connect_protocol::plugins::contacts::vcard::VCard::parse(vcard_data);
+
+    (
+        card.name.unwrap_or_else(|| "Unknown".to_string()),
+        card.phone_numbers.into_iter().map(|p| p.number).collect(),
+        card.emails.into_iter().map(|e| e.address).collect(),
+    )
 }
 
 #[allow(clippy::too_many_arguments)] // DBus interface methods need many parameters
@@ -415,13 +1016,16 @@ impl CConnectInterface {
 
     /// Request pairing with a device
     ///
+    /// Succeeds without doing anything if the device is already paired or a
+    /// pairing request is already pending.
+    ///
     /// # Arguments
     /// * `device_id` - The device ID to pair with
     ///
     /// # Returns
     /// Success or error message
-    async fn pair_device(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
-        info!("DBus: PairDevice called for {}", device_id);
+    async fn request_pair(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: RequestPair called for {}", device_id);
 
         // Check if pairing service is available
         let pairing_service = self
@@ -436,22 +1040,20 @@ impl CConnectInterface {
             .get_device(&device_id)
             .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {}", device_id)))?;
 
-        // Check if already paired
+        // Nothing to do if already paired
         if device.is_paired() {
-            return Err(zbus::fdo::Error::Failed(format!(
-                "Device {} is already paired",
-                device_id
-            )));
+            debug!("Device {} is already paired", device_id);
+            return Ok(());
         }
 
         let device_info = device.info.clone();
-        let remote_addr: std::net::SocketAddr = format!(
-            "{}:{}",
-            device.host.as_deref().unwrap_or("0.0.0.0"),
-            device.port.unwrap_or(1816)
-        )
-        .parse()
-        .map_err(|e| zbus::fdo::Error::Failed(format!("Invalid remote address: {}", e)))?;
+        let remote_addr = device
+            .connect_addresses()
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed(format!("No known address for device {}", device_id))
+            })?;
 
         drop(device_manager);
 
@@ -474,6 +1076,13 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Request pairing with a device
+    ///
+    /// Older name of `RequestPair`, kept for existing clients.
+    async fn pair_device(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        self.request_pair(device_id).await
+    }
+
     /// Unpair a device
     ///
     /// # Arguments
@@ -495,12 +1104,10 @@ impl CConnectInterface {
             .get_device(&device_id)
             .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {}", device_id)))?;
 
-        // Check if device is paired
+        // Unpairing is idempotent
         if !device.is_paired() {
-            return Err(zbus::fdo::Error::Failed(format!(
-                "Device {} is not paired",
-                device_id
-            )));
+            debug!("Device {} is not paired, nothing to unpair", device_id);
+            return Ok(());
         }
 
         drop(device_manager);
@@ -578,13 +1185,16 @@ impl CConnectInterface {
 
     /// Accept a pairing request from a device
     ///
+    /// Succeeds without doing anything if the device is already paired, so
+    /// answering a request that was accepted elsewhere is harmless.
+    ///
     /// # Arguments
     /// * `device_id` - The device ID to accept pairing from
     ///
     /// # Returns
     /// Success or error message
-    async fn accept_pairing(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
-        info!("DBus: AcceptPairing called for {}", device_id);
+    async fn accept_pair(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: AcceptPair called for {}", device_id);
 
         // Check if pairing service is available
         let pairing_service = self.pairing_service.as_ref().ok_or_else(|| {
@@ -602,15 +1212,25 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Accept a pairing request from a device
+    ///
+    /// Older name of `AcceptPair`, kept for existing clients.
+    async fn accept_pairing(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        self.accept_pair(device_id).await
+    }
+
     /// Reject a pairing request from a device
     ///
+    /// Succeeds without doing anything if no request from the device is
+    /// pending any more (answered elsewhere or timed out).
+    ///
     /// # Arguments
     /// * `device_id` - The device ID to reject pairing from
     ///
     /// # Returns
     /// Success or error message
-    async fn reject_pairing(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
-        info!("DBus: RejectPairing called for {}", device_id);
+    async fn reject_pair(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: RejectPair called for {}", device_id);
 
         // Check if pairing service is available
         let pairing_service = self.pairing_service.as_ref().ok_or_else(|| {
@@ -628,6 +1248,13 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Reject a pairing request from a device
+    ///
+    /// Older name of `RejectPair`, kept for existing clients.
+    async fn reject_pairing(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        self.reject_pair(device_id).await
+    }
+
     /// Trigger device discovery
     ///
     /// Broadcasts UDP discovery packet to find new devices on the network.
@@ -728,6 +1355,28 @@ impl CConnectInterface {
         Ok(state.to_string())
     }
 
+    /// Get the certificate fingerprint a device is pinned to
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to query
+    ///
+    /// # Returns
+    /// SHA-256 fingerprint of the device's certificate, or an empty string if
+    /// the device is not paired
+    async fn get_certificate_fingerprint(
+        &self,
+        device_id: String,
+    ) -> Result<String, zbus::fdo::Error> {
+        debug!("DBus: GetCertificateFingerprint called for {}", device_id);
+
+        let device_manager = self.device_manager.read().await;
+        let device = device_manager
+            .get_device(&device_id)
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {}", device_id)))?;
+
+        Ok(device.pinned_fingerprint().unwrap_or_default().to_string())
+    }
+
     /// Send a ping to a device
     ///
     /// # Arguments
@@ -773,39 +1422,96 @@ impl CConnectInterface {
         Ok(())
     }
 
-    /// Trigger find phone on a device
+    /// Measure the round-trip time to a device
+    ///
+    /// Sends a silent timing ping and waits for the device to echo it.
     ///
     /// # Arguments
-    /// * `device_id` - The device ID to trigger find phone on
-    async fn find_phone(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
-        info!("DBus: FindPhone called for {}", device_id);
+    /// * `device_id` - The device ID to measure
+    ///
+    /// # Returns
+    /// Round-trip time and its latency category
+    async fn measure_latency(&self, device_id: String) -> Result<PingLatency, zbus::fdo::Error> {
+        debug!("DBus: MeasureLatency called for {}", device_id);
 
-        let device_manager = self.device_manager.read().await;
-        let device = device_manager
-            .get_device(&device_id)
-            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {}", device_id)))?;
+        // Take the timer so the plugin manager isn't locked while waiting
+        let timer = self
+            .plugin_manager
+            .read()
+            .await
+            .get_device_ping_timer(&device_id)
+            .await
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed("Ping plugin not active for device".to_string())
+            })?;
 
-        if !device.is_connected() {
+        let rtt = timer
+            .ping_with_timing(PING_TIMEOUT)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to measure latency: {}", e)))?;
+
+        let category = match LatencyCategory::from_rtt(rtt) {
+            LatencyCategory::Low => "low",
+            LatencyCategory::Medium => "medium",
+            LatencyCategory::High => "high",
+        };
+        info!(
+            "DBus: Round trip to {}: {} ms ({})",
+            device_id,
+            rtt.as_millis(),
+            category
+        );
+
+        Ok(PingLatency {
+            rtt_ms: rtt.as_millis() as u64,
+            category: category.to_string(),
+        })
+    }
+
+    /// Trigger find phone on a device
+    ///
+    /// Kept for existing clients; equivalent to `ring`.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to trigger find phone on
+    async fn find_phone(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: FindPhone called for {}", device_id);
+        self.ring(device_id).await
+    }
+
+    /// Make a device ring so it can be located
+    ///
+    /// The ring continues until it is dismissed on the device or `stop_ring`
+    /// is called.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to ring
+    async fn ring(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: Ring called for {}", device_id);
+
+        let device_manager = self.device_manager.read().await;
+        let device = device_manager
+            .get_device(&device_id)
+            .ok_or_else(|| zbus::fdo::Error::Failed(format!("Device not found: {}", device_id)))?;
+
+        if !device.is_connected() {
             return Err(zbus::fdo::Error::Failed("Device not connected".to_string()));
         }
 
         drop(device_manager);
 
-        // Create findmyphone packet
-        use cosmic_ext_connect_protocol::Packet;
-        use serde_json::json;
-
-        let packet = Packet::new("cconnect.findmyphone.request", json!({}));
-
-        // Send packet via ConnectionManager
-        let conn_manager = self.connection_manager.read().await;
-        conn_manager
-            .send_packet(&device_id, &packet)
+        let plugin_manager = self.plugin_manager.read().await;
+        let mut plugin = plugin_manager
+            .get_device_plugin_as::<FindMyPhonePlugin>(&device_id, "findmyphone")
             .await
-            .map_err(|e| {
-                zbus::fdo::Error::Failed(format!("Failed to send find phone request: {}", e))
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed("Find My Phone is not enabled for this device".to_string())
             })?;
 
+        plugin.ring().await.map_err(|e| {
+            zbus::fdo::Error::Failed(format!("Failed to send find phone request: {}", e))
+        })?;
+
         info!(
             "DBus: Find phone request sent successfully to {}",
             device_id
@@ -813,6 +1519,81 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Stop ringing between this desktop and a device
+    ///
+    /// Dismisses a local ring requested by the device (acknowledging it) and
+    /// cancels a ring this desktop requested on the device.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    async fn stop_ring(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: StopRing called for {}", device_id);
+
+        let plugin_manager = self.plugin_manager.read().await;
+        let mut plugin = plugin_manager
+            .get_device_plugin_as::<FindMyPhonePlugin>(&device_id, "findmyphone")
+            .await
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed("Find My Phone is not enabled for this device".to_string())
+            })?;
+
+        let was_ringing = plugin.is_ringing();
+        plugin
+            .stop_ring()
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to send stop request: {}", e)))?;
+        drop(plugin_manager);
+
+        if was_ringing {
+            self.emit_find_my_phone_ringing(&device_id, false).await;
+        }
+
+        Ok(())
+    }
+
+    /// Mute or unmute Find My Phone ring requests from a device
+    ///
+    /// Muting silences a ring that is already playing.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `muted` - Whether ring requests should be ignored
+    async fn set_find_my_phone_muted(
+        &self,
+        device_id: String,
+        muted: bool,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: SetFindMyPhoneMuted called for {}: {}",
+            device_id, muted
+        );
+
+        {
+            let mut registry = self.device_config_registry.write().await;
+            registry.get_or_create(&device_id).mute_find_my_phone = muted;
+            registry.save().map_err(|e| {
+                zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
+            })?;
+        }
+
+        let plugin_manager = self.plugin_manager.read().await;
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin_as::<FindMyPhonePlugin>(&device_id, "findmyphone")
+            .await
+        {
+            let was_ringing = plugin.is_ringing();
+            plugin.set_muted(muted).await;
+            let silenced = was_ringing && !plugin.is_ringing();
+            drop(plugin_manager);
+
+            if silenced {
+                self.emit_find_my_phone_ringing(&device_id, false).await;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Mute incoming call ringer on a device
     ///
     /// # Arguments
@@ -1075,7 +1856,7 @@ impl CConnectInterface {
         conn_manager
             .send_packet(&device_id, &packet)
             .await
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to send power action: {}", e)))?;
+            .map_err(|e| protocol_error_to_dbus(&e))?;
 
         info!(
             "DBus: Power action '{}' sent successfully to {}",
@@ -1274,6 +2055,8 @@ impl CConnectInterface {
             return Err(zbus::fdo::Error::Failed("Device not connected".to_string()));
         }
 
+        SharePlugin::validate_share_target(device).map_err(|e| protocol_error_to_dbus(&e))?;
+
         drop(device_manager);
 
         // Validate file exists (using std::fs which doesn't require tokio runtime)
@@ -1284,6 +2067,34 @@ impl CConnectInterface {
             )));
         }
 
+        // Hold back large files while the connection to the device is metered
+        let metered_config = self.config.read().await.metered.clone();
+        let file_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if file_size >= metered_config.large_payload_bytes {
+            let metered = metered_policy::is_connection_metered(
+                &*self.plugin_manager.read().await,
+                &self.metered_link,
+                &device_id,
+            )
+            .await;
+            match metered_policy::evaluate(&metered_config, MeteredFeature::FileShare, metered) {
+                MeteredDecision::Defer(feature) => {
+                    return Err(zbus::fdo::Error::LimitsExceeded(format!(
+                        "{} skipped: the network connection is metered. \
+                         Disable metered connection avoidance to send anyway.",
+                        feature
+                    )));
+                }
+                MeteredDecision::Warn(_) => {
+                    warn!(
+                        "DBus: Sharing {} bytes to {} over a metered connection",
+                        file_size, device_id
+                    );
+                }
+                MeteredDecision::Proceed => {}
+            }
+        }
+
         // Generate unique transfer ID
         let timestamp_millis = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -1292,10 +2103,16 @@ impl CConnectInterface {
         let transfer_id = format!("{}_{}", device_id, timestamp_millis);
 
         // Register transfer and get cancellation flag
+        let filename = std::path::Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.clone());
+        let transfer = Transfer::new(&transfer_id, &device_id, filename, "sending", file_size);
         let cancel_flag = self
             .transfer_manager
-            .register_transfer(transfer_id.clone())
+            .register_transfer(transfer.clone())
             .await;
+        emit_transfer_updated(&self.dbus_connection, transfer.clone()).await;
 
         // Clone all needed values for the spawned task
         let file_path = path.clone();
@@ -1304,6 +2121,7 @@ impl CConnectInterface {
         let dbus_conn = self.dbus_connection.clone();
         let transfer_manager = self.transfer_manager.clone();
         let conn_manager = self.connection_manager.clone();
+        let plugin_manager = self.plugin_manager.clone();
         let tokio_handle = self.tokio_handle.clone();
 
         // Spawn the entire file transfer operation on tokio runtime
@@ -1313,11 +2131,20 @@ impl CConnectInterface {
             use cosmic_ext_connect_protocol::plugins::share::{FileShareInfo, SharePlugin};
             use cosmic_ext_connect_protocol::{FileTransferInfo, TlsPayloadServer};
 
+            let fail = |error: String| {
+                finish_shared_file(&dbus_conn, &transfer_manager, &transfer, false, error)
+            };
+
             // Extract file metadata (inside tokio runtime)
             let file_info = match FileTransferInfo::from_path(&file_path).await {
+                Ok(info) => info.with_hash().await,
+                Err(e) => Err(e),
+            };
+            let file_info = match file_info {
                 Ok(info) => info,
                 Err(e) => {
                     warn!("Failed to read file metadata: {}", e);
+                    fail(e.to_string()).await;
                     return;
                 }
             };
@@ -1338,6 +2165,7 @@ impl CConnectInterface {
                 Ok(s) => s,
                 Err(e) => {
                     warn!("Failed to create TLS payload server: {}", e);
+                    fail(e.to_string()).await;
                     return;
                 }
             };
@@ -1348,12 +2176,14 @@ impl CConnectInterface {
             // Create share packet with file info and payload transfer port
             let share_info: FileShareInfo = file_info.clone().into();
             let plugin = SharePlugin::new();
-            let packet = plugin.create_file_packet(share_info, port);
+            let packet = plugin.create_file_packet(share_info.clone(), port);
 
             // Send packet via ConnectionManager
             let conn_mgr = conn_manager.read().await;
             if let Err(e) = conn_mgr.send_packet(&device_id_clone, &packet).await {
                 warn!("Failed to send share packet: {}", e);
+                drop(conn_mgr);
+                fail(e.to_string()).await;
                 return;
             }
             drop(conn_mgr);
@@ -1363,6 +2193,18 @@ impl CConnectInterface {
                 device_id_clone
             );
 
+            // Let the device resume the transfer if the connection drops
+            if let Some(share) = plugin_manager
+                .read()
+                .await
+                .get_device_plugin_as::<SharePlugin>(&device_id_clone, "share")
+                .await
+            {
+                share
+                    .register_outgoing_file(packet.id.to_string(), &file_path, share_info)
+                    .await;
+            }
+
             let filename = file_info.filename.clone();
 
             // Create progress callback that emits DBus signals
@@ -1372,6 +2214,9 @@ impl CConnectInterface {
             let fname = filename.clone();
             let cancel_flag_inner = cancel_flag.clone();
             let handle_inner = tokio_handle.clone();
+            let progress_manager = transfer_manager.clone();
+            let transfer_start = std::time::Instant::now();
+            let last_update = AtomicU64::new(0);
 
             let progress_callback =
                 Box::new(move |bytes_transferred: u64, total_bytes: u64| -> bool {
@@ -1381,14 +2226,29 @@ impl CConnectInterface {
                         return false; // Stop transfer
                     }
 
+                    // Signal at most every 500ms, but always the final update
+                    let now = transfer_start.elapsed().as_millis() as u64;
+                    let last = last_update.load(Ordering::Relaxed);
+                    if now - last < 500 && bytes_transferred < total_bytes {
+                        return true;
+                    }
+                    last_update.store(now, Ordering::Relaxed);
+
                     let conn_clone = conn.clone();
                     let tid_clone = tid.clone();
                     let did_clone = did.clone();
                     let fname_clone = fname.clone();
+                    let manager = progress_manager.clone();
 
                     // Emit progress signal (non-blocking)
                     // Use the handle to spawn since we may be called from a non-tokio context
                     handle_inner.spawn(async move {
+                        if let Some(transfer) = manager
+                            .update_progress(&tid_clone, bytes_transferred, total_bytes)
+                            .await
+                        {
+                            emit_transfer_updated(&conn_clone, transfer).await;
+                        }
                         if let Ok(object_server) = conn_clone
                             .object_server()
                             .interface::<_, CConnectInterface>(OBJECT_PATH)
@@ -1415,7 +2275,8 @@ impl CConnectInterface {
             let result = server_with_progress.send_file(&file_path).await;
 
             // Determine completion status
-            let (success, error_msg) = if cancel_flag.load(Ordering::SeqCst) {
+            let cancelled = cancel_flag.load(Ordering::SeqCst);
+            let (success, error_msg) = if cancelled {
                 (false, "Transfer cancelled by user".to_string())
             } else {
                 (
@@ -1428,26 +2289,31 @@ impl CConnectInterface {
                 )
             };
 
-            // Emit completion signal
-            if let Ok(object_server) = dbus_conn
-                .object_server()
-                .interface::<_, CConnectInterface>(OBJECT_PATH)
+            // Emit completion signals and record the final state
+            finish_shared_file(
+                &dbus_conn,
+                &transfer_manager,
+                &transfer,
+                cancelled,
+                error_msg.clone(),
+            )
+            .await;
+
+            // Only a failed transfer is worth keeping for a resume request
+            if let Some(share) = plugin_manager
+                .read()
+                .await
+                .get_device_plugin_as::<SharePlugin>(&device_id_clone, "share")
                 .await
             {
-                let _ = CConnectInterface::transfer_complete(
-                    object_server.signal_emitter(),
-                    &transfer_id_clone,
-                    &device_id_clone,
-                    &filename,
-                    success,
-                    &error_msg,
-                )
-                .await;
+                let transfer_id = packet.id.to_string();
+                if success || cancelled {
+                    share.finish_outgoing_file(&transfer_id).await;
+                } else {
+                    share.fail_outgoing_file(&transfer_id).await;
+                }
             }
 
-            // Remove transfer from manager
-            transfer_manager.remove_transfer(&transfer_id_clone).await;
-
             if success {
                 info!(
                     "File transfer completed successfully for device {}",
@@ -1488,6 +2354,8 @@ impl CConnectInterface {
             return Err(zbus::fdo::Error::Failed("Device not connected".to_string()));
         }
 
+        SharePlugin::validate_share_target(device).map_err(|e| protocol_error_to_dbus(&e))?;
+
         drop(device_manager);
 
         // Create share text packet
@@ -1501,7 +2369,7 @@ impl CConnectInterface {
         conn_manager
             .send_packet(&device_id, &packet)
             .await
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to share text: {}", e)))?;
+            .map_err(|e| protocol_error_to_dbus(&e))?;
 
         info!("DBus: Text shared successfully to {}", device_id);
         Ok(())
@@ -1524,6 +2392,8 @@ impl CConnectInterface {
             return Err(zbus::fdo::Error::Failed("Device not connected".to_string()));
         }
 
+        SharePlugin::validate_share_target(device).map_err(|e| protocol_error_to_dbus(&e))?;
+
         drop(device_manager);
 
         // Create share URL packet
@@ -1537,13 +2407,20 @@ impl CConnectInterface {
         conn_manager
             .send_packet(&device_id, &packet)
             .await
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to share URL: {}", e)))?;
+            .map_err(|e| protocol_error_to_dbus(&e))?;
 
         info!("DBus: URL shared successfully to {}", device_id);
         Ok(())
     }
 
-    /// Cancel an active file transfer
+    /// Get all tracked file transfers, active and recently finished
+    async fn get_transfers(&self) -> Vec<Transfer> {
+        self.transfer_manager.list_transfers().await
+    }
+
+    /// Cancel an active file transfer, sending or receiving
+    ///
+    /// The transfer ends with a `TransferUpdated` signal in the "cancelled" state.
     ///
     /// # Arguments
     /// * `transfer_id` - The transfer ID to cancel
@@ -1654,10 +2531,13 @@ impl CConnectInterface {
             device_id, path
         );
 
-        let mut plugin_manager = self.plugin_manager.write().await;
+        let plugin_manager = self.plugin_manager.read().await;
 
         // Get the filesync plugin for this device
-        if let Some(plugin) = plugin_manager.get_device_plugin_mut(&device_id, "filesync") {
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin(&device_id, "filesync")
+            .await
+        {
             // Downcast to concrete FileSyncPlugin
             if let Some(filesync) = plugin.as_any_mut().downcast_mut::<FileSyncPlugin>() {
                 // Parse strategy
@@ -1704,9 +2584,12 @@ impl CConnectInterface {
             device_id, folder_id
         );
 
-        let mut plugin_manager = self.plugin_manager.write().await;
+        let plugin_manager = self.plugin_manager.read().await;
 
-        if let Some(plugin) = plugin_manager.get_device_plugin_mut(&device_id, "filesync") {
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin(&device_id, "filesync")
+            .await
+        {
             if let Some(filesync) = plugin.as_any_mut().downcast_mut::<FileSyncPlugin>() {
                 filesync.remove_folder(&folder_id).await.map_err(|e| {
                     zbus::fdo::Error::Failed(format!("Failed to remove folder: {}", e))
@@ -1733,15 +2616,14 @@ impl CConnectInterface {
     ) -> Result<Vec<SyncFolderInfo>, zbus::fdo::Error> {
         info!("DBus: GetSyncFolders called for {}", device_id);
 
-        // We only need read access here, but get_device_plugin requires iterating
-        // However, PluginManager only has get_device_plugin (which returns &dyn Plugin)
-        // and get_device_plugin_mut. We need to downcast.
-        // Downcasting for &dyn Plugin to &Concrete requires the trait to implement as_any() which returns &dyn Any.
-        // Let's assume Plugin trait has as_any(). checking... Yes it does. (lines 196 in mod.rs)
+        // get_device_plugin returns a guard over the boxed plugin, so downcast it
 
         let plugin_manager = self.plugin_manager.read().await;
 
-        if let Some(plugin) = plugin_manager.get_device_plugin(&device_id, "filesync") {
+        if let Some(plugin) = plugin_manager
+            .get_device_plugin(&device_id, "filesync")
+            .await
+        {
             if let Some(filesync) = plugin.as_any().downcast_ref::<FileSyncPlugin>() {
                 let folders: Vec<FilesyncFolder> = filesync.get_folders().await;
                 let result: Vec<SyncFolderInfo> =
@@ -1758,6 +2640,133 @@ impl CConnectInterface {
         }
     }
 
+    /// Get the number of file sync conflicts awaiting manual resolution
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to query
+    ///
+    /// # Returns
+    /// Number of pending conflicts, 0 if the plugin is not active
+    async fn get_pending_sync_conflict_count(
+        &self,
+        device_id: String,
+    ) -> Result<u32, zbus::fdo::Error> {
+        debug!("DBus: GetPendingSyncConflictCount called for {}", device_id);
+
+        let plugin_manager = self.plugin_manager.read().await;
+
+        if let Some(plugin) = plugin_manager
+            .get_device_plugin(&device_id, "filesync")
+            .await
+        {
+            if let Some(filesync) = plugin.as_any().downcast_ref::<FileSyncPlugin>() {
+                Ok(filesync.pending_conflict_count() as u32)
+            } else {
+                Err(zbus::fdo::Error::Failed(
+                    "Plugin is not FileSyncPlugin".to_string(),
+                ))
+            }
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// List a directory on a device's SFTP share
+    ///
+    /// Connects natively (without sshfs) on first use, asking the device for
+    /// credentials if needed. Requires the `native-sftp` feature.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `path` - Directory relative to the share root (empty for the root)
+    async fn sftp_list_dir(
+        &self,
+        device_id: String,
+        path: String,
+    ) -> Result<Vec<SftpEntryInfo>, zbus::fdo::Error> {
+        debug!("DBus: SftpListDir called for {}: {:?}", device_id, path);
+
+        #[cfg(feature = "native-sftp")]
+        {
+            let plugin = self.network_share(&device_id).await?;
+            let entries = plugin
+                .list_dir(&device_id, &path)
+                .await
+                .map_err(|e| protocol_error_to_dbus(&e))?;
+            Ok(entries.into_iter().map(SftpEntryInfo::from).collect())
+        }
+
+        #[cfg(not(feature = "native-sftp"))]
+        {
+            Err(sftp_not_supported())
+        }
+    }
+
+    /// Read a file from a device's SFTP share
+    ///
+    /// Requires the `native-sftp` feature.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `path` - File path relative to the share root
+    async fn sftp_read_file(
+        &self,
+        device_id: String,
+        path: String,
+    ) -> Result<Vec<u8>, zbus::fdo::Error> {
+        debug!("DBus: SftpReadFile called for {}: {:?}", device_id, path);
+
+        #[cfg(feature = "native-sftp")]
+        {
+            let plugin = self.network_share(&device_id).await?;
+            plugin
+                .read_file(&device_id, &path)
+                .await
+                .map_err(|e| protocol_error_to_dbus(&e))
+        }
+
+        #[cfg(not(feature = "native-sftp"))]
+        {
+            Err(sftp_not_supported())
+        }
+    }
+
+    /// Write a file to a device's SFTP share, replacing it if it exists
+    ///
+    /// Requires the `native-sftp` feature.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `path` - File path relative to the share root
+    /// * `data` - New file contents
+    async fn sftp_write_file(
+        &self,
+        device_id: String,
+        path: String,
+        data: Vec<u8>,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: SftpWriteFile called for {}: {:?} ({} bytes)",
+            device_id,
+            path,
+            data.len()
+        );
+
+        #[cfg(feature = "native-sftp")]
+        {
+            let plugin = self.network_share(&device_id).await?;
+            plugin
+                .write_file(&device_id, &path, &data)
+                .await
+                .map_err(|e| protocol_error_to_dbus(&e))
+        }
+
+        #[cfg(not(feature = "native-sftp"))]
+        {
+            Err(sftp_not_supported())
+        }
+    }
+
     /// Get battery status from a device
     ///
     /// # Arguments
@@ -1786,6 +2795,7 @@ impl CConnectInterface {
         let plugin_manager = self.plugin_manager.read().await;
         let status = plugin_manager
             .get_device_battery_status(&device_id)
+            .await
             .ok_or_else(|| {
                 zbus::fdo::Error::Failed("No battery status available for device".to_string())
             })?;
@@ -1829,6 +2839,7 @@ impl CConnectInterface {
         let plugin_manager = self.plugin_manager.read().await;
         let stats = plugin_manager
             .get_device_screen_share_stats(&device_id)
+            .await
             .ok_or_else(|| {
                 zbus::fdo::Error::Failed("No screen share session active for device".to_string())
             })?;
@@ -1951,6 +2962,7 @@ impl CConnectInterface {
         let plugin_manager = self.plugin_manager.read().await;
         let plugin = plugin_manager
             .get_device_plugin(&device_id, "contacts")
+            .await
             .ok_or_else(|| {
                 zbus::fdo::Error::Failed("Contacts plugin not available for device".to_string())
             })?;
@@ -2016,6 +3028,7 @@ impl CConnectInterface {
         let plugin_manager = self.plugin_manager.read().await;
         let plugin = plugin_manager
             .get_device_plugin(&device_id, "runcommand")
+            .await
             .ok_or_else(|| {
                 zbus::fdo::Error::Failed("RunCommand plugin not found for device".to_string())
             })?;
@@ -2121,6 +3134,316 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Snooze forwarding of notifications from an application
+    ///
+    /// # Arguments
+    /// * `app_name` - The application name as reported by the notification
+    /// * `duration_secs` - How long to snooze for, in seconds
+    ///
+    /// # Returns
+    /// The UNIX timestamp (seconds) at which the snooze expires
+    async fn snooze_app(
+        &self,
+        app_name: String,
+        duration_secs: u64,
+    ) -> Result<u64, zbus::fdo::Error> {
+        info!(
+            "DBus: SnoozeApp called for {} ({}s)",
+            app_name, duration_secs
+        );
+
+        if app_name.is_empty() || duration_secs == 0 {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "App name and a non-zero duration are required".to_string(),
+            ));
+        }
+
+        self.notification_snoozes
+            .write()
+            .await
+            .snooze_app(&app_name, std::time::Duration::from_secs(duration_secs))
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to snooze app: {}", e)))
+    }
+
+    /// Clear the notification snooze for an application
+    ///
+    /// # Arguments
+    /// * `app_name` - The application name to resume forwarding for
+    async fn unsnooze_app(&self, app_name: String) -> Result<bool, zbus::fdo::Error> {
+        info!("DBus: UnsnoozeApp called for {}", app_name);
+
+        self.notification_snoozes
+            .write()
+            .await
+            .unsnooze_app(&app_name)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to unsnooze app: {}", e)))
+    }
+
+    /// Get currently snoozed applications
+    ///
+    /// # Returns
+    /// Map of app name to snooze expiry (UNIX timestamp in seconds)
+    async fn get_snoozed_apps(&self) -> HashMap<String, u64> {
+        let mut snoozes = self.notification_snoozes.write().await;
+        if let Err(e) = snoozes.clear_expired() {
+            warn!("Failed to persist expired notification snoozes: {}", e);
+        }
+        snoozes.active_snoozes()
+    }
+
+    /// Get the local anonymized usage report
+    ///
+    /// The report only contains aggregate counts of peer protocol versions,
+    /// plugins and transports. It is never sent anywhere by the daemon.
+    ///
+    /// # Returns
+    /// JSON-serialized usage report
+    async fn get_usage_report(&self) -> Result<String, zbus::fdo::Error> {
+        debug!("DBus: GetUsageReport called");
+
+        self.usage_reporter
+            .read()
+            .await
+            .export()
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to export usage report: {}", e)))
+    }
+
+    /// Enable or disable recording of the usage report
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether the user consents to recording
+    async fn set_usage_report_enabled(&self, enabled: bool) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: SetUsageReportEnabled called: {}", enabled);
+
+        let mut config = self.config.write().await;
+        config.usage_report.enabled = enabled;
+        config
+            .save()
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to save config: {}", e)))?;
+
+        self.usage_reporter.write().await.set_enabled(enabled);
+        Ok(())
+    }
+
+    /// Enable or disable metered connection avoidance
+    ///
+    /// When enabled, heavy operations are warned about or deferred while a
+    /// device reports being on mobile data.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether to apply the metered connection policy
+    async fn set_avoid_metered(&self, enabled: bool) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: SetAvoidMetered called: {}", enabled);
+
+        let mut config = self.config.write().await;
+        config.metered.avoid_metered = enabled;
+        config
+            .save()
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to save config: {}", e)))?;
+        Ok(())
+    }
+
+    /// Check whether a device is on a metered connection
+    ///
+    /// True if the device reports mobile data or this desktop's own network
+    /// connection is metered.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID to check
+    async fn is_device_metered(&self, device_id: String) -> bool {
+        let plugin_manager = self.plugin_manager.read().await;
+        metered_policy::is_connection_metered(&plugin_manager, &self.metered_link, &device_id).await
+    }
+
+    /// Delete all data recorded in the usage report
+    async fn clear_usage_report(&self) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: ClearUsageReport called");
+
+        self.usage_reporter
+            .write()
+            .await
+            .clear()
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to clear usage report: {}", e)))
+    }
+
+    /// Get one page of the device event history, newest first
+    ///
+    /// # Arguments
+    /// * `device_id` - Only events of this device (empty for all devices)
+    /// * `event_type` - Only events of this type (empty for all types)
+    /// * `limit` - Maximum number of events (capped at 500)
+    /// * `offset` - Number of newer events to skip
+    async fn get_history(
+        &self,
+        device_id: String,
+        event_type: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<HistoryEntry>, zbus::fdo::Error> {
+        debug!(
+            "DBus: GetHistory called (device: {:?}, type: {:?}, limit: {}, offset: {})",
+            device_id, event_type, limit, offset
+        );
+
+        let records = self
+            .history
+            .run_blocking(move |store| {
+                store.query(history_filter(&device_id, &event_type), limit, offset)
+            })
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read history: {}", e)))?;
+        Ok(records.into_iter().map(HistoryEntry::from).collect())
+    }
+
+    /// Get the number of history events matching a filter
+    ///
+    /// # Arguments
+    /// * `device_id` - Only events of this device (empty for all devices)
+    /// * `event_type` - Only events of this type (empty for all types)
+    async fn get_history_count(
+        &self,
+        device_id: String,
+        event_type: String,
+    ) -> Result<u64, zbus::fdo::Error> {
+        self.history
+            .run_blocking(move |store| store.count(history_filter(&device_id, &event_type)))
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to count history: {}", e)))
+    }
+
+    /// Get one page of the privileged action audit log, newest first
+    ///
+    /// # Arguments
+    /// * `device_id` - Only entries of this device (empty for all devices)
+    /// * `limit` - Maximum number of entries (capped at 500)
+    /// * `offset` - Number of newer entries to skip
+    async fn get_audit_log(
+        &self,
+        device_id: String,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<AuditLogEntry>, zbus::fdo::Error> {
+        debug!(
+            "DBus: GetAuditLog called (device: {:?}, limit: {}, offset: {})",
+            device_id, limit, offset
+        );
+
+        let audit_log = self.audit_log.clone();
+        let entries = self
+            .tokio_handle
+            .spawn_blocking(move || {
+                let device_id = Some(device_id.as_str()).filter(|id| !id.is_empty());
+                audit_log.query(device_id, offset, limit)
+            })
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Audit log task failed: {}", e)))?
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to read audit log: {}", e)))?;
+        Ok(entries.into_iter().map(AuditLogEntry::from).collect())
+    }
+
+    /// Get the number of audit log entries
+    ///
+    /// # Arguments
+    /// * `device_id` - Only entries of this device (empty for all devices)
+    async fn get_audit_log_count(&self, device_id: String) -> Result<u64, zbus::fdo::Error> {
+        let audit_log = self.audit_log.clone();
+        self.tokio_handle
+            .spawn_blocking(move || {
+                let device_id = Some(device_id.as_str()).filter(|id| !id.is_empty());
+                audit_log.count(device_id)
+            })
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Audit log task failed: {}", e)))?
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to count audit log: {}", e)))
+    }
+
+    /// Delete device event history
+    ///
+    /// # Arguments
+    /// * `device_id` - Only delete events of this device (empty for all devices)
+    async fn clear_history(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: ClearHistory called (device: {:?})", device_id);
+
+        let deleted = self
+            .history
+            .run_blocking(move |store| {
+                store.clear(Some(device_id.as_str()).filter(|id| !id.is_empty()))
+            })
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to clear history: {}", e)))?;
+        info!("Deleted {} history events", deleted);
+        Ok(())
+    }
+
+    /// Set a device's nickname and plugin states in one call
+    ///
+    /// Plugins whose state changes are started or stopped on the device right
+    /// away and announced with `device_plugin_state_changed`. Nothing is
+    /// changed if validation fails.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `nickname` - The new nickname; must not be blank
+    /// * `plugins` - Plugin name to enabled state; unlisted plugins keep theirs
+    async fn set_device_config(
+        &self,
+        device_id: String,
+        nickname: String,
+        plugins: HashMap<String, bool>,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: SetDeviceConfig called for {}: nickname {:?}, plugins {:?}",
+            device_id, nickname, plugins
+        );
+
+        let nickname = nickname.trim();
+        if nickname.is_empty() {
+            return Err(zbus::fdo::Error::InvalidArgs(
+                "Nickname must not be empty".to_string(),
+            ));
+        }
+        if let Some(unknown) = plugins
+            .keys()
+            .find(|name| !crate::device_config::DEVICE_PLUGIN_NAMES.contains(&name.as_str()))
+        {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Unknown plugin: {}",
+                unknown
+            )));
+        }
+
+        let changed: Vec<(String, bool)> = {
+            let global_config = self.config.read().await;
+            let mut registry = self.device_config_registry.write().await;
+            let config = registry.get_or_create(&device_id);
+
+            let changed = plugins
+                .into_iter()
+                .filter(|(name, enabled)| {
+                    config.is_plugin_enabled(name, &global_config.plugins) != *enabled
+                })
+                .collect::<Vec<_>>();
+
+            config.nickname = Some(nickname.to_string());
+            for (name, enabled) in &changed {
+                config.set_plugin_enabled(name, *enabled);
+            }
+
+            registry.save().map_err(|e| {
+                zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
+            })?;
+            changed
+        };
+
+        for (plugin_name, enabled) in changed {
+            self.sync_device_plugin(&device_id, &plugin_name).await;
+            self.emit_plugin_state_changed(&device_id, &plugin_name, enabled)
+                .await;
+        }
+
+        Ok(())
+    }
+
     /// Set plugin enabled state for a device
     ///
     /// # Arguments
@@ -2138,14 +3461,18 @@ impl CConnectInterface {
             device_id, plugin_name, enabled
         );
 
-        let mut registry = self.device_config_registry.write().await;
-        let config = registry.get_or_create(&device_id);
+        {
+            let mut registry = self.device_config_registry.write().await;
+            let config = registry.get_or_create(&device_id);
 
-        config.set_plugin_enabled(&plugin_name, enabled);
+            config.set_plugin_enabled(&plugin_name, enabled);
 
-        registry.save().map_err(|e| {
-            zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
-        })?;
+            registry.save().map_err(|e| {
+                zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
+            })?;
+        }
+
+        self.sync_device_plugin(&device_id, &plugin_name).await;
 
         info!(
             "DBus: Plugin {} {} for device {}",
@@ -2193,6 +3520,8 @@ impl CConnectInterface {
             config.is_plugin_enabled(&plugin_name, &global_config.plugins)
         };
 
+        self.sync_device_plugin(&device_id, &plugin_name).await;
+
         info!(
             "DBus: Plugin override cleared for {} on device {}, now using global config ({})",
             plugin_name,
@@ -2206,89 +3535,124 @@ impl CConnectInterface {
         Ok(())
     }
 
-    /// Reset all plugin overrides for a device (revert to global config)
+    /// Allow or revoke a privileged plugin (power, runcommand, remoteinput)
+    ///
+    /// Takes effect immediately and is saved to the device config. With
+    /// PolicyKit enabled, allowing a plugin requires the caller to be
+    /// authorized for the plugin's PolicyKit action.
     ///
     /// # Arguments
     /// * `device_id` - The device ID
-    async fn reset_all_plugin_overrides(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
-        info!("DBus: ResetAllPluginOverrides called for {}", device_id);
+    /// * `plugin_name` - The privileged plugin name
+    /// * `allowed` - Whether the device may use the plugin
+    async fn set_device_privileged_plugin(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        device_id: String,
+        plugin_name: String,
+        allowed: bool,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: SetDevicePrivilegedPlugin called for {}, plugin {}: {}",
+            device_id, plugin_name, allowed
+        );
 
-        // Get list of all plugins that have overrides
-        let plugin_names: Vec<String> = {
-            let registry = self.device_config_registry.read().await;
-            if let Some(config) = registry.get(&device_id) {
-                // Collect all plugin names that have overrides
-                let mut names = Vec::new();
-                if config.plugins.enable_ping.is_some() {
-                    names.push("ping".to_string());
-                }
-                if config.plugins.enable_battery.is_some() {
-                    names.push("battery".to_string());
-                }
-                if config.plugins.enable_notification.is_some() {
-                    names.push("notification".to_string());
-                }
-                if config.plugins.enable_share.is_some() {
-                    names.push("share".to_string());
-                }
-                if config.plugins.enable_clipboard.is_some() {
-                    names.push("clipboard".to_string());
-                }
-                if config.plugins.enable_mpris.is_some() {
-                    names.push("mpris".to_string());
-                }
-                if config.plugins.enable_remotedesktop.is_some() {
-                    names.push("remotedesktop".to_string());
-                }
-                if config.plugins.enable_findmyphone.is_some() {
-                    names.push("findmyphone".to_string());
-                }
-                names
-            } else {
-                Vec::new()
-            }
+        let Some(action_id) = permission::plugin_polkit_action_id(&plugin_name) else {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "Not a privileged plugin: {}",
+                plugin_name
+            )));
         };
 
-        // Clear all overrides
+        if allowed && self.permission_policy.uses_polkit() {
+            let sender = header.sender().ok_or_else(|| {
+                zbus::fdo::Error::AccessDenied("Caller has no bus name".to_string())
+            })?;
+            let caller = PolkitSubject::dbus_caller(&self.dbus_connection, sender)
+                .await
+                .map_err(zbus::fdo::Error::AccessDenied)?;
+            let authorized = permission::polkit_check(&caller, action_id)
+                .await
+                .map_err(zbus::fdo::Error::AccessDenied)?;
+            if !authorized {
+                warn!(
+                    "DBus: {} not authorized to allow {} for device {}",
+                    sender, plugin_name, device_id
+                );
+                return Err(zbus::fdo::Error::AccessDenied(format!(
+                    "Not authorized by PolicyKit ({})",
+                    action_id
+                )));
+            }
+        }
+
         {
+            let mut registry = self.device_config_registry.write().await;
+            registry
+                .get_or_create(&device_id)
+                .set_privileged_plugin_allowed(&plugin_name, allowed);
+
+            registry.save().map_err(|e| {
+                zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
+            })?;
+        }
+
+        if allowed {
+            self.permission_policy.allow(&device_id, &plugin_name);
+        } else {
+            self.permission_policy.revoke(&device_id, &plugin_name);
+        }
+
+        info!(
+            "DBus: Privileged plugin {} {} for device {}",
+            plugin_name,
+            if allowed { "allowed" } else { "revoked" },
+            device_id
+        );
+
+        Ok(())
+    }
+
+    /// Reset all plugin overrides for a device (revert to global config)
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    async fn reset_all_plugin_overrides(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
+        info!("DBus: ResetAllPluginOverrides called for {}", device_id);
+
+        // Clear all overrides, remembering which plugins had one
+        let plugin_names: Vec<&'static str> = {
             let mut registry = self.device_config_registry.write().await;
             let config = registry.get_or_create(&device_id);
 
-            // Reset all plugin overrides to None
-            config.plugins.enable_ping = None;
-            config.plugins.enable_battery = None;
-            config.plugins.enable_notification = None;
-            config.plugins.enable_share = None;
-            config.plugins.enable_clipboard = None;
-            config.plugins.enable_mpris = None;
-            config.plugins.enable_remotedesktop = None;
-            config.plugins.enable_findmyphone = None;
+            let names: Vec<&'static str> = config
+                .plugins
+                .overrides()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect();
+            for name in &names {
+                config.plugins.set(name, None);
+            }
 
             registry.save().map_err(|e| {
                 zbus::fdo::Error::Failed(format!("Failed to save device config: {}", e))
             })?;
-        }
+            names
+        };
 
         // Emit signals for all plugins that had overrides
         let num_affected = plugin_names.len();
         for plugin_name in plugin_names {
-            // Get the global config to know what the default state is
+            // Without an override the global config decides
             let enabled = {
                 let config = self.config.read().await;
-                match plugin_name.as_str() {
-                    "ping" => config.plugins.enable_ping,
-                    "battery" => config.plugins.enable_battery,
-                    "notification" => config.plugins.enable_notification,
-                    "share" => config.plugins.enable_share,
-                    "clipboard" => config.plugins.enable_clipboard,
-                    "mpris" => config.plugins.enable_mpris,
-                    "remotedesktop" => config.plugins.enable_remotedesktop,
-                    "findmyphone" => config.plugins.enable_findmyphone,
-                    _ => false,
-                }
+                crate::device_config::DeviceConfig::new(device_id.clone())
+                    .is_plugin_enabled(plugin_name, &config.plugins)
             };
 
-            self.emit_plugin_state_changed(&device_id, &plugin_name, enabled)
+            self.sync_device_plugin(&device_id, plugin_name).await;
+            self.emit_plugin_state_changed(&device_id, plugin_name, enabled)
                 .await;
         }
 
@@ -2320,6 +3684,37 @@ impl CConnectInterface {
         Ok(json)
     }
 
+    /// Get the capability diff with a device as JSON
+    ///
+    /// Lists which packet types each side sends that the other can consume,
+    /// and the mismatches in both directions, for the diagnostics view.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    ///
+    /// # Returns
+    /// JSON string with `peer_consumes`, `we_consume`, `peer_missing` and
+    /// `we_missing` lists
+    async fn get_capability_diff(&self, device_id: String) -> Result<String, zbus::fdo::Error> {
+        debug!("DBus: GetCapabilityDiff called for {}", device_id);
+
+        let device_manager = self.device_manager.read().await;
+        let device = device_manager.get_device(&device_id).ok_or_else(|| {
+            zbus::fdo::Error::Failed(format!("Device not found: {}", device_id))
+        })?;
+
+        let plugin_manager = self.plugin_manager.read().await;
+        let diff = CapabilityDiff::compute(
+            &plugin_manager.get_all_incoming_capabilities(),
+            &plugin_manager.get_all_outgoing_capabilities(),
+            &device.info.incoming_capabilities,
+            &device.info.outgoing_capabilities,
+        );
+
+        serde_json::to_string_pretty(&diff)
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to serialize diff: {}", e)))
+    }
+
     /// Get RemoteDesktop settings for a device as JSON
     ///
     /// Returns the RemoteDesktop-specific settings (quality, fps, resolution)
@@ -2378,28 +3773,79 @@ impl CConnectInterface {
         Ok(())
     }
 
-    /// Add a run command for a device
+    /// Add a run command for a device
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID
+    /// * `command_id` - Unique identifier for the command
+    /// * `name` - User-friendly command name
+    /// * `command` - Shell command to execute
+    async fn add_run_command(
+        &self,
+        device_id: String,
+        command_id: String,
+        name: String,
+        command: String,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: AddRunCommand called for {} - ID: {}, Name: {}",
+            device_id, command_id, name
+        );
+
+        let plugin_manager = self.plugin_manager.read().await;
+        let plugin = plugin_manager
+            .get_device_plugin(&device_id, "runcommand")
+            .await
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed(format!(
+                    "RunCommand plugin not found for device: {}",
+                    device_id
+                ))
+            })?;
+
+        // Downcast to RunCommandPlugin
+        use cosmic_ext_connect_protocol::plugins::runcommand::RunCommandPlugin;
+        let runcommand_plugin = plugin
+            .as_any()
+            .downcast_ref::<RunCommandPlugin>()
+            .ok_or_else(|| {
+                zbus::fdo::Error::Failed("Failed to downcast to RunCommandPlugin".to_string())
+            })?;
+
+        // Add the command
+        runcommand_plugin
+            .add_command(&command_id, &name, &command)
+            .await
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to add command: {}", e)))?;
+
+        info!(
+            "DBus: Command '{}' added successfully for device {}",
+            command_id, device_id
+        );
+        Ok(())
+    }
+
+    /// Set whether a run command needs confirmation on the desktop
     ///
     /// # Arguments
     /// * `device_id` - The device ID
-    /// * `command_id` - Unique identifier for the command
-    /// * `name` - User-friendly command name
-    /// * `command` - Shell command to execute
-    async fn add_run_command(
+    /// * `command_id` - Command identifier
+    /// * `required` - Ask before running the command when the device requests it
+    async fn set_run_command_confirmation(
         &self,
         device_id: String,
         command_id: String,
-        name: String,
-        command: String,
+        required: bool,
     ) -> Result<(), zbus::fdo::Error> {
         info!(
-            "DBus: AddRunCommand called for {} - ID: {}, Name: {}",
-            device_id, command_id, name
+            "DBus: SetRunCommandConfirmation called for {} - ID: {}, Required: {}",
+            device_id, command_id, required
         );
 
         let plugin_manager = self.plugin_manager.read().await;
         let plugin = plugin_manager
             .get_device_plugin(&device_id, "runcommand")
+            .await
             .ok_or_else(|| {
                 zbus::fdo::Error::Failed(format!(
                     "RunCommand plugin not found for device: {}",
@@ -2416,16 +3862,11 @@ impl CConnectInterface {
                 zbus::fdo::Error::Failed("Failed to downcast to RunCommandPlugin".to_string())
             })?;
 
-        // Add the command
         runcommand_plugin
-            .add_command(&command_id, &name, &command)
+            .set_require_confirmation(&command_id, required)
             .await
-            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to add command: {}", e)))?;
+            .map_err(|e| zbus::fdo::Error::Failed(format!("Failed to update command: {}", e)))?;
 
-        info!(
-            "DBus: Command '{}' added successfully for device {}",
-            command_id, device_id
-        );
         Ok(())
     }
 
@@ -2447,6 +3888,7 @@ impl CConnectInterface {
         let plugin_manager = self.plugin_manager.read().await;
         let plugin = plugin_manager
             .get_device_plugin(&device_id, "runcommand")
+            .await
             .ok_or_else(|| {
                 zbus::fdo::Error::Failed(format!(
                     "RunCommand plugin not found for device: {}",
@@ -2482,13 +3924,15 @@ impl CConnectInterface {
     /// * `device_id` - The device ID
     ///
     /// # Returns
-    /// JSON string with command map {id: {name: string, command: string}}
+    /// JSON string with command map
+    /// {id: {name: string, command: string, requireConfirmation?: bool}}
     async fn get_run_commands(&self, device_id: String) -> Result<String, zbus::fdo::Error> {
         debug!("DBus: GetRunCommands called for {}", device_id);
 
         let plugin_manager = self.plugin_manager.read().await;
         let plugin = plugin_manager
             .get_device_plugin(&device_id, "runcommand")
+            .await
             .ok_or_else(|| {
                 zbus::fdo::Error::Failed(format!(
                     "RunCommand plugin not found for device: {}",
@@ -2531,6 +3975,7 @@ impl CConnectInterface {
         let plugin_manager = self.plugin_manager.read().await;
         let plugin = plugin_manager
             .get_device_plugin(&device_id, "runcommand")
+            .await
             .ok_or_else(|| {
                 zbus::fdo::Error::Failed(format!(
                     "RunCommand plugin not found for device: {}",
@@ -2617,9 +4062,12 @@ impl CConnectInterface {
             device_id, port
         );
 
-        let mut plugin_manager = self.plugin_manager.write().await;
+        let plugin_manager = self.plugin_manager.read().await;
 
-        if let Some(plugin) = plugin_manager.get_device_plugin_mut(&device_id, "screenshare") {
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin(&device_id, "screenshare")
+            .await
+        {
             use cosmic_ext_connect_protocol::plugins::screenshare::ScreenSharePlugin;
 
             if let Some(screenshare) = plugin.as_any_mut().downcast_mut::<ScreenSharePlugin>() {
@@ -2673,9 +4121,12 @@ impl CConnectInterface {
         }
         drop(device_manager);
 
-        let mut plugin_manager = self.plugin_manager.write().await;
+        let plugin_manager = self.plugin_manager.read().await;
 
-        if let Some(plugin) = plugin_manager.get_device_plugin_mut(&device_id, "screenshare") {
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin(&device_id, "screenshare")
+            .await
+        {
             use cosmic_ext_connect_protocol::plugins::screenshare::{ScreenSharePlugin, ShareConfig};
 
             if let Some(screenshare) = plugin.as_any_mut().downcast_mut::<ScreenSharePlugin>() {
@@ -2709,9 +4160,12 @@ impl CConnectInterface {
     async fn stop_screen_share(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
         info!("DBus: StopScreenShare called for {}", device_id);
 
-        let mut plugin_manager = self.plugin_manager.write().await;
+        let plugin_manager = self.plugin_manager.read().await;
 
-        if let Some(plugin) = plugin_manager.get_device_plugin_mut(&device_id, "screenshare") {
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin(&device_id, "screenshare")
+            .await
+        {
             use cosmic_ext_connect_protocol::plugins::screenshare::ScreenSharePlugin;
 
             if let Some(screenshare) = plugin.as_any_mut().downcast_mut::<ScreenSharePlugin>() {
@@ -2756,10 +4210,11 @@ impl CConnectInterface {
     ) -> Result<(), zbus::fdo::Error> {
         info!("DBus: StartExtendedDisplay called for {}", device_id);
 
-        let mut plugin_manager = self.plugin_manager.write().await;
+        let plugin_manager = self.plugin_manager.read().await;
 
-        if let Some(plugin) =
-            plugin_manager.get_device_plugin_mut(&device_id, "extendeddisplay")
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin(&device_id, "extendeddisplay")
+            .await
         {
             use cosmic_ext_connect_protocol::plugins::extendeddisplay::ExtendedDisplayPlugin;
 
@@ -2796,10 +4251,11 @@ impl CConnectInterface {
     ) -> Result<(), zbus::fdo::Error> {
         info!("DBus: StopExtendedDisplay called for {}", device_id);
 
-        let mut plugin_manager = self.plugin_manager.write().await;
+        let plugin_manager = self.plugin_manager.read().await;
 
-        if let Some(plugin) =
-            plugin_manager.get_device_plugin_mut(&device_id, "extendeddisplay")
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin(&device_id, "extendeddisplay")
+            .await
         {
             use cosmic_ext_connect_protocol::plugins::extendeddisplay::ExtendedDisplayPlugin;
 
@@ -2850,7 +4306,10 @@ impl CConnectInterface {
 
         let plugin_manager = self.plugin_manager.read().await;
 
-        if let Some(plugin) = plugin_manager.get_device_plugin(&device_id, "screenshare") {
+        if let Some(plugin) = plugin_manager
+            .get_device_plugin(&device_id, "screenshare")
+            .await
+        {
             use cosmic_ext_connect_protocol::plugins::screenshare::ScreenSharePlugin;
 
             if let Some(screenshare) = plugin.as_any().downcast_ref::<ScreenSharePlugin>() {
@@ -2882,9 +4341,12 @@ impl CConnectInterface {
     async fn pause_screen_share(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
         info!("DBus: PauseScreenShare called for {}", device_id);
 
-        let mut plugin_manager = self.plugin_manager.write().await;
+        let plugin_manager = self.plugin_manager.read().await;
 
-        if let Some(plugin) = plugin_manager.get_device_plugin_mut(&device_id, "screenshare") {
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin(&device_id, "screenshare")
+            .await
+        {
             use cosmic_ext_connect_protocol::plugins::screenshare::ScreenSharePlugin;
 
             if let Some(screenshare) = plugin.as_any_mut().downcast_mut::<ScreenSharePlugin>() {
@@ -2915,9 +4377,12 @@ impl CConnectInterface {
     async fn resume_screen_share(&self, device_id: String) -> Result<(), zbus::fdo::Error> {
         info!("DBus: ResumeScreenShare called for {}", device_id);
 
-        let mut plugin_manager = self.plugin_manager.write().await;
+        let plugin_manager = self.plugin_manager.read().await;
 
-        if let Some(plugin) = plugin_manager.get_device_plugin_mut(&device_id, "screenshare") {
+        if let Some(mut plugin) = plugin_manager
+            .get_device_plugin(&device_id, "screenshare")
+            .await
+        {
             use cosmic_ext_connect_protocol::plugins::screenshare::ScreenSharePlugin;
 
             if let Some(screenshare) = plugin.as_any_mut().downcast_mut::<ScreenSharePlugin>() {
@@ -3151,6 +4616,128 @@ impl CConnectInterface {
         Ok(())
     }
 
+    /// Get media players on all connected devices
+    async fn get_remote_players(&self) -> Vec<RemotePlayer> {
+        let device_ids: Vec<String> = self
+            .device_manager
+            .read()
+            .await
+            .connected_devices()
+            .map(|device| device.id().to_string())
+            .collect();
+
+        let plugin_manager = self.plugin_manager.read().await;
+        let mut players = Vec::new();
+        for device_id in device_ids {
+            let Some(plugin) = plugin_manager
+                .get_device_plugin_as::<MprisPlugin>(&device_id, "mpris")
+                .await
+            else {
+                continue;
+            };
+            for name in plugin.get_player_list().await {
+                let Some(state) = plugin.get_player_state(&name).await else {
+                    continue;
+                };
+                let album_art = match &state.metadata.album_art_url {
+                    Some(url) => plugin.album_art_path(url).await,
+                    None => None,
+                };
+                players.push(RemotePlayer::from_state(
+                    &device_id,
+                    &state,
+                    album_art.as_deref(),
+                ));
+            }
+        }
+        players
+    }
+
+    /// Control a media player on a remote device
+    ///
+    /// # Arguments
+    /// * `device_id` - Device the player runs on
+    /// * `player` - Player name
+    /// * `action` - "Play", "Pause", "PlayPause", "Stop", "Next" or "Previous"
+    async fn remote_mpris_control(
+        &self,
+        device_id: String,
+        player: String,
+        action: String,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: RemoteMprisControl called: {} on {} ({})",
+            action, player, device_id
+        );
+
+        let action = PlaybackAction::parse_str(&action).ok_or_else(|| {
+            zbus::fdo::Error::InvalidArgs(format!("Unknown MPRIS action: {}", action))
+        })?;
+
+        self.send_remote_mpris(&device_id, &player, |plugin, _| {
+            Ok(plugin.create_control_packet(player.clone(), action))
+        })
+        .await
+    }
+
+    /// Set the volume of a media player on a remote device
+    ///
+    /// # Arguments
+    /// * `device_id` - Device the player runs on
+    /// * `player` - Player name
+    /// * `volume` - Volume level (0.0 to 1.0, clamped)
+    async fn remote_mpris_set_volume(
+        &self,
+        device_id: String,
+        player: String,
+        volume: f64,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: RemoteMprisSetVolume called: {} on {} - {}",
+            player, device_id, volume
+        );
+
+        // The remote side expects a percentage
+        let percent = (volume.clamp(0.0, 1.0) * 100.0).round() as i32;
+        self.send_remote_mpris(&device_id, &player, |plugin, _| {
+            Ok(plugin.create_set_volume_packet(player.clone(), percent))
+        })
+        .await
+    }
+
+    /// Jump to an absolute position in the current track of a remote player
+    ///
+    /// # Arguments
+    /// * `device_id` - Device the player runs on
+    /// * `player` - Player name
+    /// * `position_microseconds` - Target position, clamped to the track length
+    async fn remote_mpris_set_position(
+        &self,
+        device_id: String,
+        player: String,
+        position_microseconds: i64,
+    ) -> Result<(), zbus::fdo::Error> {
+        info!(
+            "DBus: RemoteMprisSetPosition called: {} on {} - {}μs",
+            player, device_id, position_microseconds
+        );
+
+        self.send_remote_mpris(&device_id, &player, |plugin, state| {
+            if !state.status.capabilities.can_seek {
+                return Err(zbus::fdo::Error::NotSupported(format!(
+                    "Player cannot seek: {}",
+                    player
+                )));
+            }
+            let mut position_ms = (position_microseconds / 1000).max(0);
+            if state.status.length > 0 {
+                position_ms = position_ms.min(state.status.length);
+            }
+            Ok(plugin.create_set_position_packet(player.clone(), position_ms))
+        })
+        .await
+    }
+
     // ===== Settings Management Methods =====
 
     /// Get daemon configuration as JSON
@@ -3769,6 +5356,7 @@ impl CConnectInterface {
         let plugin_manager = self.plugin_manager.read().await;
         let camera_plugin = plugin_manager
             .get_device_plugin(&device_id, "camera")
+            .await
             .ok_or_else(|| {
                 zbus::fdo::Error::Failed(format!(
                     "Camera plugin not found for device {}",
@@ -3803,44 +5391,61 @@ impl CConnectInterface {
         }
     }
 
-    /// Signal: Device was added (discovered)
+    /// Signal: Device was removed (disappeared)
     ///
-    /// Emitted when a new device is discovered on the network.
+    /// Emitted when a device is no longer reachable on the network.
     ///
     /// # Arguments
     /// * `device_id` - The device ID
-    /// * `device_info` - Device information
     #[zbus(signal)]
-    async fn device_added(
+    async fn device_removed(
         signal_emitter: &SignalEmitter<'_>,
         device_id: &str,
-        device_info: DeviceInfo,
     ) -> zbus::Result<()>;
 
-    /// Signal: Device was removed (disappeared)
+    /// Signal: Battery status changed
     ///
-    /// Emitted when a device is no longer reachable on the network.
+    /// Emitted whenever a device reports its battery state.
     ///
     /// # Arguments
     /// * `device_id` - The device ID
+    /// * `level` - Battery level percentage (0-100)
+    /// * `is_charging` - Whether the device is charging
     #[zbus(signal)]
-    async fn device_removed(
+    async fn battery_status_changed(
         signal_emitter: &SignalEmitter<'_>,
         device_id: &str,
+        level: i32,
+        is_charging: bool,
     ) -> zbus::Result<()>;
 
-    /// Signal: Device state changed
+    /// Signal: Find My Phone ringing state changed
     ///
-    /// Emitted when a device's connection state changes (connected, paired, etc).
+    /// Emitted when a device starts or stops making this desktop ring.
     ///
     /// # Arguments
-    /// * `device_id` - The device ID
-    /// * `state` - New state: "connected", "paired", "reachable", or "unknown"
+    /// * `device_id` - The device that requested the ring
+    /// * `ringing` - Whether this desktop is ringing
     #[zbus(signal)]
-    async fn device_state_changed(
+    async fn find_my_phone_ringing(
         signal_emitter: &SignalEmitter<'_>,
         device_id: &str,
-        state: &str,
+        ringing: bool,
+    ) -> zbus::Result<()>;
+
+    /// Signal: Devices updated
+    ///
+    /// Emitted after a short debounce with the latest state of every device
+    /// that was discovered, connected, disconnected or otherwise changed since
+    /// the previous batch. This is the only signal for device changes; only
+    /// removals have their own `DeviceRemoved` signal.
+    ///
+    /// # Arguments
+    /// * `devices` - Updated devices, keyed by device ID
+    #[zbus(signal)]
+    async fn devices_updated(
+        signal_emitter: &SignalEmitter<'_>,
+        devices: HashMap<String, DeviceInfo>,
     ) -> zbus::Result<()>;
 
     /// Signal: Pairing request received
@@ -3855,13 +5460,29 @@ impl CConnectInterface {
         device_id: &str,
     ) -> zbus::Result<()>;
 
+    /// Signal: Pairing requested
+    ///
+    /// Emitted together with `PairingRequest`, with the fingerprint the user
+    /// should compare before answering with `AcceptPair` or `RejectPair`.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device ID requesting pairing
+    /// * `fingerprint` - SHA-256 fingerprint of the certificate the device
+    ///   presented on its TLS connection
+    #[zbus(signal)]
+    async fn pairing_requested(
+        signal_emitter: &SignalEmitter<'_>,
+        device_id: &str,
+        fingerprint: &str,
+    ) -> zbus::Result<()>;
+
     /// Signal: Pairing status changed
     ///
     /// Emitted when pairing completes or fails.
     ///
     /// # Arguments
     /// * `device_id` - The device ID
-    /// * `status` - Status: "paired", "rejected", or "failed"
+    /// * `status` - Status: "paired", "rejected", "timed_out", or "failed"
     /// Signal: Messaging notification received
     ///
     /// Emitted when a messaging app notification arrives.
@@ -3936,6 +5557,40 @@ impl CConnectInterface {
         direction: &str,
     ) -> zbus::Result<()>;
 
+    /// Signal: A media player on a remote device changed
+    ///
+    /// # Arguments
+    /// * `player` - Current state of the player
+    #[zbus(signal)]
+    async fn remote_player_updated(
+        signal_emitter: &SignalEmitter<'_>,
+        player: RemotePlayer,
+    ) -> zbus::Result<()>;
+
+    /// Signal: A media player on a remote device went away
+    ///
+    /// # Arguments
+    /// * `device_id` - Device the player ran on
+    /// * `player` - Player name
+    #[zbus(signal)]
+    async fn remote_player_removed(
+        signal_emitter: &SignalEmitter<'_>,
+        device_id: &str,
+        player: &str,
+    ) -> zbus::Result<()>;
+
+    /// Signal: Transfer state changed
+    ///
+    /// Emitted when a transfer starts, makes progress or finishes.
+    ///
+    /// # Arguments
+    /// * `transfer` - Current state of the transfer
+    #[zbus(signal)]
+    async fn transfer_updated(
+        signal_emitter: &SignalEmitter<'_>,
+        transfer: Transfer,
+    ) -> zbus::Result<()>;
+
     /// Signal: Transfer complete or cancelled
     ///
     /// Emitted when a file transfer finishes (successfully or not).
@@ -4050,7 +5705,8 @@ impl CConnectInterface {
 
     /// Signal: Call state changed
     ///
-    /// Emitted when an active call changes state (e.g. ringing -> talking).
+    /// Emitted when an active call changes state (e.g. ringing -> talking, or "ended"
+    /// when the call is over).
     #[zbus(signal)]
     async fn call_state_changed(
         signal_emitter: &SignalEmitter<'_>,
@@ -4110,6 +5766,12 @@ impl CConnectInterface {
 pub struct DbusServer {
     /// DBus connection
     connection: Connection,
+
+    /// Device manager, used to look up devices for batched updates
+    device_manager: Arc<RwLock<DeviceManager>>,
+
+    /// Coalesces device updates into `DevicesUpdated` signals
+    device_updates: UpdateBatcher<DeviceInfo>,
 }
 
 impl DbusServer {
@@ -4120,6 +5782,8 @@ impl DbusServer {
     /// * `plugin_manager` - Plugin manager reference
     /// * `connection_manager` - Connection manager reference
     /// * `device_config_registry` - Device configuration registry
+    /// * `permission_policy` - Privileged plugins allowed per device
+    /// * `audit_log` - Audit log of privileged plugin actions
     /// * `pairing_service` - Optional pairing service reference
     /// * `mpris_manager` - Optional MPRIS manager for local media player control
     /// * `config` - Daemon configuration (for settings management)
@@ -4132,11 +5796,18 @@ impl DbusServer {
         plugin_manager: Arc<RwLock<PluginManager>>,
         connection_manager: Arc<RwLock<ConnectionManager>>,
         device_config_registry: Arc<RwLock<crate::device_config::DeviceConfigRegistry>>,
+        permission_policy: AllowlistPolicy,
+        audit_log: Arc<AuditLog>,
         pairing_service: Option<Arc<RwLock<cosmic_ext_connect_protocol::pairing::PairingService>>>,
         mpris_manager: Option<Arc<crate::mpris_manager::MprisManager>>,
         pending_pairing_requests: Arc<RwLock<std::collections::HashMap<String, bool>>>,
         metrics: Option<Arc<RwLock<crate::diagnostics::Metrics>>>,
         config: Arc<RwLock<crate::config::Config>>,
+        notification_snoozes: Arc<RwLock<crate::notification_snooze::NotificationSnoozes>>,
+        usage_reporter: Arc<RwLock<crate::usage_report::UsageReporter>>,
+        metered_link: MeteredLink,
+        history: History,
+        packet_sender: tokio::sync::mpsc::Sender<(String, cosmic_ext_connect_protocol::Packet)>,
     ) -> Result<Self> {
         info!("Starting DBus server on {}", SERVICE_NAME);
 
@@ -4148,6 +5819,7 @@ impl DbusServer {
 
         // Clone device_manager and connection_manager for the Open interface before moving to CConnectInterface
         let device_manager_for_open = device_manager.clone();
+        let device_manager_for_updates = device_manager.clone();
         let connection_manager_for_open = connection_manager.clone();
 
         // Create interface with connection reference
@@ -4157,12 +5829,19 @@ impl DbusServer {
             plugin_manager,
             connection_manager,
             device_config_registry,
+            permission_policy,
+            audit_log,
             pairing_service,
             mpris_manager,
             pending_pairing_requests,
             connection.clone(),
             metrics,
             config,
+            notification_snoozes,
+            usage_reporter,
+            metered_link,
+            history,
+            packet_sender,
             Handle::current(),
         );
 
@@ -4192,7 +5871,21 @@ impl DbusServer {
 
         info!("DBus server started successfully");
 
-        Ok(Self { connection })
+        let signal_connection = connection.clone();
+        let device_updates = UpdateBatcher::spawn(DEFAULT_BATCH_WINDOW, move |devices| {
+            let connection = signal_connection.clone();
+            async move {
+                if let Err(e) = Self::emit_devices_updated(&connection, devices).await {
+                    warn!("Failed to emit DevicesUpdated signal: {}", e);
+                }
+            }
+        });
+
+        Ok(Self {
+            connection,
+            device_manager: device_manager_for_updates,
+            device_updates,
+        })
     }
 
     /// Get the DBus connection
@@ -4210,60 +5903,107 @@ impl DbusServer {
             .context("Failed to get interface reference")
     }
 
-    /// Emit a device_added signal
-    pub async fn emit_device_added(&self, device: &Device) -> Result<()> {
-        let device_info = DeviceInfo::from(device);
+    /// Emit a device_removed signal
+    #[allow(dead_code)]
+    pub async fn emit_device_removed(&self, device_id: &str) -> Result<()> {
         let object_server = self.connection.object_server();
         let iface_ref = object_server
             .interface::<_, CConnectInterface>(OBJECT_PATH)
             .await?;
 
-        CConnectInterface::device_added(iface_ref.signal_emitter(), device.id(), device_info)
-            .await?;
+        CConnectInterface::device_removed(iface_ref.signal_emitter(), device_id).await?;
 
-        debug!("Emitted DeviceAdded signal for {}", device.id());
+        debug!("Emitted DeviceRemoved signal for {}", device_id);
         Ok(())
     }
 
-    /// Emit a device_removed signal
-    #[allow(dead_code)]
-    pub async fn emit_device_removed(&self, device_id: &str) -> Result<()> {
+    /// Emit a battery_status_changed signal
+    pub async fn emit_battery_status_changed(
+        &self,
+        device_id: &str,
+        level: i32,
+        is_charging: bool,
+    ) -> Result<()> {
         let object_server = self.connection.object_server();
         let iface_ref = object_server
             .interface::<_, CConnectInterface>(OBJECT_PATH)
             .await?;
 
-        CConnectInterface::device_removed(iface_ref.signal_emitter(), device_id).await?;
+        CConnectInterface::battery_status_changed(
+            iface_ref.signal_emitter(),
+            device_id,
+            level,
+            is_charging,
+        )
+        .await?;
 
-        debug!("Emitted DeviceRemoved signal for {}", device_id);
+        debug!(
+            "Emitted BatteryStatusChanged signal for {} ({}%, charging: {})",
+            device_id, level, is_charging
+        );
         Ok(())
     }
 
-    /// Emit a device_state_changed signal
-    pub async fn emit_device_state_changed(&self, device_id: &str, state: &str) -> Result<()> {
+    /// Emit a find_my_phone_ringing signal
+    pub async fn emit_find_my_phone_ringing(&self, device_id: &str, ringing: bool) -> Result<()> {
         let object_server = self.connection.object_server();
         let iface_ref = object_server
             .interface::<_, CConnectInterface>(OBJECT_PATH)
             .await?;
 
-        CConnectInterface::device_state_changed(iface_ref.signal_emitter(), device_id, state)
+        CConnectInterface::find_my_phone_ringing(iface_ref.signal_emitter(), device_id, ringing)
             .await?;
 
         debug!(
-            "Emitted DeviceStateChanged signal for {} ({})",
-            device_id, state
+            "Emitted FindMyPhoneRinging signal for {} (ringing: {})",
+            device_id, ringing
         );
         Ok(())
     }
 
-    /// Emit a pairing_request signal
-    pub async fn emit_pairing_request(&self, device_id: &str) -> Result<()> {
+    /// Queue the current state of a device for the next `DevicesUpdated` batch
+    ///
+    /// Takes a read lock on the device manager, so it must not be called while
+    /// holding a write lock on it.
+    pub async fn queue_device_update(&self, device_id: &str) {
+        let manager = self.device_manager.read().await;
+        if let Some(device) = manager.get_device(device_id) {
+            self.queue_device(device);
+        }
+    }
+
+    /// Queue a device for the next `DevicesUpdated` batch
+    fn queue_device(&self, device: &Device) {
+        self.device_updates.update(device.id(), DeviceInfo::from(device));
+    }
+
+    /// Emit a devices_updated signal with a batch of device updates
+    async fn emit_devices_updated(
+        connection: &Connection,
+        devices: HashMap<String, DeviceInfo>,
+    ) -> Result<()> {
+        let object_server = connection.object_server();
+        let iface_ref = object_server
+            .interface::<_, CConnectInterface>(OBJECT_PATH)
+            .await?;
+
+        let count = devices.len();
+        CConnectInterface::devices_updated(iface_ref.signal_emitter(), devices).await?;
+
+        debug!("Emitted DevicesUpdated signal for {} devices", count);
+        Ok(())
+    }
+
+    /// Emit the pairing_request and pairing_requested signals
+    pub async fn emit_pairing_request(&self, device_id: &str, fingerprint: &str) -> Result<()> {
         let object_server = self.connection.object_server();
         let iface_ref = object_server
             .interface::<_, CConnectInterface>(OBJECT_PATH)
             .await?;
 
         CConnectInterface::pairing_request(iface_ref.signal_emitter(), device_id).await?;
+        CConnectInterface::pairing_requested(iface_ref.signal_emitter(), device_id, fingerprint)
+            .await?;
 
         debug!("Emitted PairingRequest signal for {}", device_id);
         Ok(())
@@ -4328,7 +6068,6 @@ impl DbusServer {
     }
 
     /// Emit a transfer_progress signal
-    #[allow(dead_code)]
     pub async fn emit_transfer_progress(
         &self,
         transfer_id: &str,
@@ -4362,7 +6101,6 @@ impl DbusServer {
     }
 
     /// Emit a transfer_complete signal
-    #[allow(dead_code)]
     pub async fn emit_transfer_complete(
         &self,
         transfer_id: &str,
@@ -4389,6 +6127,71 @@ impl DbusServer {
         Ok(())
     }
 
+    /// Start tracking a transfer driven outside the DBus interface
+    ///
+    /// `cancel_flag` is set when a client calls `CancelTransfer`.
+    pub async fn track_transfer(
+        &self,
+        transfer: Transfer,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Result<()> {
+        let iface_ref = self.interface_ref().await?;
+        let transfer_manager = iface_ref.get().await.transfer_manager.clone();
+        transfer_manager
+            .track_transfer(transfer.clone(), cancel_flag)
+            .await;
+        CConnectInterface::transfer_updated(iface_ref.signal_emitter(), transfer).await?;
+        Ok(())
+    }
+
+    /// Record progress of a tracked transfer and emit `TransferUpdated`
+    pub async fn update_transfer(
+        &self,
+        transfer_id: &str,
+        transferred_bytes: u64,
+        total_bytes: u64,
+    ) -> Result<()> {
+        let iface_ref = self.interface_ref().await?;
+        let transfer_manager = iface_ref.get().await.transfer_manager.clone();
+        if let Some(transfer) = transfer_manager
+            .update_progress(transfer_id, transferred_bytes, total_bytes)
+            .await
+        {
+            CConnectInterface::transfer_updated(iface_ref.signal_emitter(), transfer).await?;
+        }
+        Ok(())
+    }
+
+    /// Mark a tracked transfer as finished and emit `TransferUpdated`
+    pub async fn finish_transfer(&self, transfer_id: &str, state: &str) -> Result<()> {
+        let iface_ref = self.interface_ref().await?;
+        let transfer_manager = iface_ref.get().await.transfer_manager.clone();
+        if let Some(transfer) = transfer_manager.finish_transfer(transfer_id, state).await {
+            CConnectInterface::transfer_updated(iface_ref.signal_emitter(), transfer).await?;
+        }
+        Ok(())
+    }
+
+    /// Emit a remote_player_updated signal
+    pub async fn emit_remote_player_updated(&self, player: RemotePlayer) -> Result<()> {
+        let iface_ref = self.interface_ref().await?;
+        debug!(
+            "Emitting RemotePlayerUpdated signal for {} on {}",
+            player.name, player.device_id
+        );
+        CConnectInterface::remote_player_updated(iface_ref.signal_emitter(), player).await?;
+        Ok(())
+    }
+
+    /// Emit a remote_player_removed signal
+    pub async fn emit_remote_player_removed(&self, device_id: &str, player: &str) -> Result<()> {
+        let iface_ref = self.interface_ref().await?;
+        CConnectInterface::remote_player_removed(iface_ref.signal_emitter(), device_id, player)
+            .await?;
+        debug!("Emitted RemotePlayerRemoved signal for {} on {}", player, device_id);
+        Ok(())
+    }
+
     /// Emit a screen_share_requested signal (remote wants to share their screen with us)
     pub async fn emit_screen_share_requested(&self, device_id: &str) -> Result<()> {
         let iface_ref = self.interface_ref().await?;
@@ -4830,6 +6633,10 @@ impl OpenInterface {
 
             // Extract file metadata
             let file_info = match FileTransferInfo::from_path(&file_path_clone).await {
+                Ok(info) => info.with_hash().await,
+                Err(e) => Err(e),
+            };
+            let file_info = match file_info {
                 Ok(info) => info,
                 Err(e) => {
                     error!("Failed to read file metadata for open: {}", e);
@@ -4868,6 +6675,7 @@ impl OpenInterface {
                 creation_time: file_info.creation_time,
                 last_modified: file_info.last_modified,
                 open: true, // Auto-open after transfer
+                hash: file_info.hash.clone(),
             };
 
             let packet = share_plugin.create_file_packet(share_info, port);
@@ -4929,6 +6737,43 @@ impl OpenInterface {
     }
 }
 
+#[cfg(test)]
+mod protocol_error_mapping_tests {
+    use super::*;
+
+    #[test]
+    fn test_rejection_reasons_map_to_dbus_errors() {
+        assert!(matches!(
+            protocol_error_to_dbus(&ProtocolError::NotPaired),
+            zbus::fdo::Error::AccessDenied(_)
+        ));
+        assert!(matches!(
+            protocol_error_to_dbus(&ProtocolError::PermissionDenied("suspend".into())),
+            zbus::fdo::Error::AccessDenied(_)
+        ));
+        assert!(matches!(
+            protocol_error_to_dbus(&ProtocolError::Timeout("handshake".into())),
+            zbus::fdo::Error::Timeout(_)
+        ));
+        assert!(matches!(
+            protocol_error_to_dbus(&ProtocolError::HandshakeTimeout("phone".into())),
+            zbus::fdo::Error::Timeout(_)
+        ));
+        assert!(matches!(
+            protocol_error_to_dbus(&ProtocolError::unsupported_feature("share")),
+            zbus::fdo::Error::NotSupported(_)
+        ));
+        assert!(matches!(
+            protocol_error_to_dbus(&ProtocolError::device_busy("shutdown")),
+            zbus::fdo::Error::LimitsExceeded(_)
+        ));
+        assert!(matches!(
+            protocol_error_to_dbus(&ProtocolError::NetworkError("reset".into())),
+            zbus::fdo::Error::Failed(_)
+        ));
+    }
+}
+
 #[cfg(test)]
 mod open_interface_tests {
     use super::*;
@@ -3,9 +3,12 @@
 //! Manages configuration settings specific to individual devices,
 //! including per-device plugin enable/disable settings.
 
+use crate::disconnect_action::DisconnectAction;
+use crate::schema::{self, Migration};
 use anyhow::{Context, Result};
+use cosmic_ext_connect_protocol::{CapabilityOverrides, DeviceType};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
@@ -67,10 +70,66 @@ pub struct DeviceConfig {
     /// RemoteDesktop plugin-specific settings
     #[serde(default)]
     pub remotedesktop_settings: Option<RemoteDesktopSettings>,
+
+    /// SFTP share freshness window in seconds (None = use global config)
+    #[serde(default)]
+    pub networkshare_freshness_secs: Option<u64>,
+
+    /// Keepalive heartbeat interval in seconds (None = derive from transport latency)
+    #[serde(default)]
+    pub heartbeat_interval_secs: Option<u64>,
+
+    /// Save received files into a per-device subfolder (None = use global config)
+    #[serde(default)]
+    pub share_device_subfolders: Option<bool>,
+
+    /// Maximum notifications forwarded per rate limit window (None = use global config)
+    #[serde(default)]
+    pub max_forwarded_notifications: Option<u32>,
+
+    /// Action to run when the device goes offline past the grace period
+    #[serde(default)]
+    pub on_disconnect: DisconnectAction,
+
+    /// Capabilities to force on or off regardless of what the device advertises
+    #[serde(default)]
+    pub capabilities: CapabilityOverrides,
+
+    /// Ignore Find My Phone ring requests from this device
+    #[serde(default)]
+    pub mute_find_my_phone: bool,
+
+    /// Privileged plugins this device may use (see [`PRIVILEGED_PLUGIN_NAMES`])
+    ///
+    /// Requests to plugins not listed here are denied. Applied when the
+    /// daemon starts and changed at runtime with `SetDevicePrivilegedPlugin`.
+    #[serde(default)]
+    pub allowed_privileged_plugins: Vec<String>,
 }
 
+/// Plugins that can be enabled or disabled per device
+pub const DEVICE_PLUGIN_NAMES: &[&str] = &[
+    "ping",
+    "battery",
+    "notification",
+    "share",
+    "clipboard",
+    "mpris",
+    "remotedesktop",
+    "findmyphone",
+    "lock",
+];
+
+/// Plugins whose actions need the device to be allowlisted
+pub const PRIVILEGED_PLUGIN_NAMES: &[&str] = &["power", "runcommand", "remoteinput"];
+
 /// Per-device plugin configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+///
+/// Each plugin in [`DEVICE_PLUGIN_NAMES`] has an `enable_<plugin>` override;
+/// `None` falls back to the global config. Keys that name no plugin (e.g. a
+/// typo in a hand-edited file) are kept in [`Self::unknown`] so they are
+/// reported by [`Self::validate`] and survive a save instead of vanishing.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct DevicePluginConfig {
     /// Enable ping plugin for this device (None = use global config)
     #[serde(default)]
@@ -107,6 +166,91 @@ pub struct DevicePluginConfig {
     /// Enable Lock plugin for this device
     #[serde(default)]
     pub enable_lock: Option<bool>,
+
+    /// Keys that do not name a plugin
+    #[serde(flatten)]
+    pub unknown: BTreeMap<String, serde_json::Value>,
+}
+
+impl DevicePluginConfig {
+    /// Defaults for a device of the given type
+    ///
+    /// Only plugins that make no sense for the type are turned off. Nothing
+    /// is turned on, so a plugin disabled in the global config stays
+    /// disabled for new devices.
+    pub fn default_for_device_type(device_type: DeviceType) -> Self {
+        let mut config = Self::default();
+        match device_type {
+            DeviceType::Phone | DeviceType::Tablet | DeviceType::Laptop => {}
+            DeviceType::Desktop => {
+                config.enable_battery = Some(false);
+            }
+            DeviceType::Tv => {
+                config.enable_battery = Some(false);
+                config.enable_clipboard = Some(false);
+            }
+        }
+        config
+    }
+
+    /// Override for a plugin, `None` if unset or the plugin is unknown
+    pub fn get(&self, plugin_name: &str) -> Option<bool> {
+        match plugin_name {
+            "ping" => self.enable_ping,
+            "battery" => self.enable_battery,
+            "notification" => self.enable_notification,
+            "share" => self.enable_share,
+            "clipboard" => self.enable_clipboard,
+            "mpris" => self.enable_mpris,
+            "remotedesktop" => self.enable_remotedesktop,
+            "findmyphone" => self.enable_findmyphone,
+            "lock" => self.enable_lock,
+            _ => None,
+        }
+    }
+
+    /// Set or clear (`None`) the override for a plugin
+    ///
+    /// Returns `false` if the plugin is unknown.
+    pub fn set(&mut self, plugin_name: &str, enabled: Option<bool>) -> bool {
+        let slot = match plugin_name {
+            "ping" => &mut self.enable_ping,
+            "battery" => &mut self.enable_battery,
+            "notification" => &mut self.enable_notification,
+            "share" => &mut self.enable_share,
+            "clipboard" => &mut self.enable_clipboard,
+            "mpris" => &mut self.enable_mpris,
+            "remotedesktop" => &mut self.enable_remotedesktop,
+            "findmyphone" => &mut self.enable_findmyphone,
+            "lock" => &mut self.enable_lock,
+            _ => return false,
+        };
+        *slot = enabled;
+        true
+    }
+
+    /// Plugins with an override, in [`DEVICE_PLUGIN_NAMES`] order
+    pub fn overrides(&self) -> Vec<(&'static str, bool)> {
+        DEVICE_PLUGIN_NAMES
+            .iter()
+            .filter_map(|name| self.get(name).map(|enabled| (*name, enabled)))
+            .collect()
+    }
+
+    /// Report keys that do not name a plugin
+    ///
+    /// Logs a warning for each and returns them. Unknown keys have no effect.
+    pub fn validate(&self) -> Vec<String> {
+        for key in self.unknown.keys() {
+            warn!(
+                "Unknown plugin setting '{}' in device config (expected enable_<plugin> with \
+                 plugin one of {})",
+                key,
+                DEVICE_PLUGIN_NAMES.join(", ")
+            );
+        }
+        self.unknown.keys().cloned().collect()
+    }
 }
 
 /// RemoteDesktop plugin-specific settings
@@ -175,6 +319,22 @@ impl DeviceConfig {
             notification_preference: NotificationPreference::default(),
             mac_address: None,
             remotedesktop_settings: None,
+            networkshare_freshness_secs: None,
+            heartbeat_interval_secs: None,
+            share_device_subfolders: None,
+            max_forwarded_notifications: None,
+            on_disconnect: DisconnectAction::None,
+            capabilities: CapabilityOverrides::default(),
+            mute_find_my_phone: false,
+            allowed_privileged_plugins: Vec::new(),
+        }
+    }
+
+    /// Create a device configuration with the plugin defaults for its type
+    pub fn for_device_type(device_id: String, device_type: DeviceType) -> Self {
+        Self {
+            plugins: DevicePluginConfig::default_for_device_type(device_type),
+            ..Self::new(device_id)
         }
     }
 
@@ -229,33 +389,63 @@ impl DeviceConfig {
 
     /// Set plugin enabled state for this device
     pub fn set_plugin_enabled(&mut self, plugin_name: &str, enabled: bool) {
-        match plugin_name {
-            "ping" => self.plugins.enable_ping = Some(enabled),
-            "battery" => self.plugins.enable_battery = Some(enabled),
-            "notification" => self.plugins.enable_notification = Some(enabled),
-            "share" => self.plugins.enable_share = Some(enabled),
-            "clipboard" => self.plugins.enable_clipboard = Some(enabled),
-            "mpris" => self.plugins.enable_mpris = Some(enabled),
-            "remotedesktop" => self.plugins.enable_remotedesktop = Some(enabled),
-            "findmyphone" => self.plugins.enable_findmyphone = Some(enabled),
-            "lock" => self.plugins.enable_lock = Some(enabled),
-            _ => warn!("Unknown plugin name: {}", plugin_name),
+        if !self.plugins.set(plugin_name, Some(enabled)) {
+            warn!("Unknown plugin name: {}", plugin_name);
+        }
+    }
+
+    /// Plugins explicitly disabled for this device
+    ///
+    /// Only device overrides count; plugins left to the global config are
+    /// governed by which factories the daemon registers.
+    pub fn disabled_plugins(&self) -> HashSet<String> {
+        self.plugins
+            .overrides()
+            .into_iter()
+            .filter(|(_, enabled)| !enabled)
+            .map(|(name, _)| name.to_string())
+            .collect()
+    }
+
+    /// Privileged plugins this device is allowed to use
+    ///
+    /// Unknown plugin names are ignored.
+    pub fn privileged_plugins(&self) -> HashSet<String> {
+        self.allowed_privileged_plugins
+            .iter()
+            .filter(|name| {
+                let known = PRIVILEGED_PLUGIN_NAMES.contains(&name.as_str());
+                if !known {
+                    warn!("Unknown privileged plugin name: {}", name);
+                }
+                known
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Allow or revoke a privileged plugin for this device
+    ///
+    /// Returns `false` for names outside [`PRIVILEGED_PLUGIN_NAMES`].
+    pub fn set_privileged_plugin_allowed(&mut self, plugin_name: &str, allowed: bool) -> bool {
+        if !PRIVILEGED_PLUGIN_NAMES.contains(&plugin_name) {
+            warn!("Unknown privileged plugin name: {}", plugin_name);
+            return false;
+        }
+
+        self.allowed_privileged_plugins
+            .retain(|name| name != plugin_name);
+        if allowed {
+            self.allowed_privileged_plugins
+                .push(plugin_name.to_string());
         }
+        true
     }
 
     /// Clear device-specific plugin override (use global config)
     pub fn clear_plugin_override(&mut self, plugin_name: &str) {
-        match plugin_name {
-            "ping" => self.plugins.enable_ping = None,
-            "battery" => self.plugins.enable_battery = None,
-            "notification" => self.plugins.enable_notification = None,
-            "share" => self.plugins.enable_share = None,
-            "clipboard" => self.plugins.enable_clipboard = None,
-            "mpris" => self.plugins.enable_mpris = None,
-            "remotedesktop" => self.plugins.enable_remotedesktop = None,
-            "findmyphone" => self.plugins.enable_findmyphone = None,
-            "lock" => self.plugins.enable_lock = None,
-            _ => warn!("Unknown plugin name: {}", plugin_name),
+        if !self.plugins.set(plugin_name, None) {
+            warn!("Unknown plugin name: {}", plugin_name);
         }
     }
 
@@ -275,6 +465,28 @@ impl DeviceConfig {
         self.remotedesktop_settings = None;
     }
 
+    /// Get the SFTP share freshness window for this device in seconds
+    ///
+    /// Returns the device-specific setting if set, otherwise falls back to global config.
+    pub fn get_networkshare_freshness_secs(
+        &self,
+        global_config: &crate::config::PluginConfig,
+    ) -> u64 {
+        self.networkshare_freshness_secs
+            .unwrap_or(global_config.networkshare_freshness_secs)
+    }
+
+    /// Get the forwarded notification limit per window for this device
+    ///
+    /// Returns the device-specific setting if set, otherwise falls back to global config.
+    pub fn get_max_forwarded_notifications(
+        &self,
+        global_config: &crate::config::NotificationListenerConfig,
+    ) -> u32 {
+        self.max_forwarded_notifications
+            .unwrap_or(global_config.max_forwarded_per_device)
+    }
+
     /// Get MAC address for Wake-on-LAN
     pub fn get_mac_address(&self) -> Option<String> {
         self.mac_address.clone()
@@ -330,6 +542,27 @@ impl DeviceConfig {
     }
 }
 
+/// Current schema version of the device configs file
+///
+/// - v1: bare map of device ID to config
+/// - v2: `{ "schema_version": 2, "devices": { ... } }`
+pub const DEVICE_CONFIGS_SCHEMA_VERSION: u32 = 2;
+
+/// Migrations for the device configs file, see [`schema::migrate`]
+const DEVICE_CONFIGS_MIGRATIONS: &[Migration] = &[wrap_device_configs];
+
+/// v1 -> v2: move the bare device map under `devices`
+fn wrap_device_configs(doc: serde_json::Value) -> Result<serde_json::Value> {
+    Ok(serde_json::json!({ "devices": doc }))
+}
+
+/// On-disk layout of the device configs file
+#[derive(Debug, Serialize, Deserialize)]
+struct DeviceConfigsFile {
+    schema_version: u32,
+    devices: HashMap<String, DeviceConfig>,
+}
+
 /// Device configuration registry
 ///
 /// Manages per-device configurations with persistence.
@@ -361,10 +594,24 @@ impl DeviceConfigRegistry {
         let contents =
             fs::read_to_string(&self.config_path).context("Failed to read device configs file")?;
 
-        let configs: HashMap<String, DeviceConfig> =
+        let doc: serde_json::Value =
             serde_json::from_str(&contents).context("Failed to parse device configs")?;
+        let doc = schema::upgrade(
+            doc,
+            DEVICE_CONFIGS_SCHEMA_VERSION,
+            DEVICE_CONFIGS_MIGRATIONS,
+        )
+        .context("Unsupported device configs file")?;
+        let file: DeviceConfigsFile =
+            serde_json::from_value(doc).context("Failed to parse device configs")?;
+
+        for (device_id, config) in &file.devices {
+            if !config.plugins.validate().is_empty() {
+                warn!("Device config of {} has unknown plugin settings", device_id);
+            }
+        }
 
-        self.configs = configs;
+        self.configs = file.devices;
         info!("Loaded {} device configurations", self.configs.len());
 
         Ok(())
@@ -372,8 +619,12 @@ impl DeviceConfigRegistry {
 
     /// Save device configurations to disk
     pub fn save(&self) -> Result<()> {
-        let contents = serde_json::to_string_pretty(&self.configs)
-            .context("Failed to serialize device configs")?;
+        let file = DeviceConfigsFile {
+            schema_version: DEVICE_CONFIGS_SCHEMA_VERSION,
+            devices: self.configs.clone(),
+        };
+        let contents =
+            serde_json::to_string_pretty(&file).context("Failed to serialize device configs")?;
 
         fs::write(&self.config_path, contents).context("Failed to write device configs file")?;
 
@@ -389,6 +640,23 @@ impl DeviceConfigRegistry {
             .or_insert_with(|| DeviceConfig::new(device_id.to_string()))
     }
 
+    /// Get device configuration, creating one with the defaults for the device
+    /// type if not found
+    ///
+    /// Returns whether the configuration was created.
+    pub fn get_or_create_for_device_type(
+        &mut self,
+        device_id: &str,
+        device_type: DeviceType,
+    ) -> (&mut DeviceConfig, bool) {
+        let created = !self.configs.contains_key(device_id);
+        let config = self
+            .configs
+            .entry(device_id.to_string())
+            .or_insert_with(|| DeviceConfig::for_device_type(device_id.to_string(), device_type));
+        (config, created)
+    }
+
     /// Get device configuration (read-only)
     pub fn get(&self, device_id: &str) -> Option<&DeviceConfig> {
         self.configs.get(device_id)
@@ -418,6 +686,69 @@ impl DeviceConfigRegistry {
         self.configs.contains_key(device_id)
     }
 
+    /// Get per-device SFTP share freshness overrides, keyed by device ID
+    pub fn networkshare_freshness_overrides(&self) -> HashMap<String, u64> {
+        self.configs
+            .iter()
+            .filter_map(|(id, config)| {
+                config
+                    .networkshare_freshness_secs
+                    .map(|secs| (id.clone(), secs))
+            })
+            .collect()
+    }
+
+    /// Get per-device receive subfolder overrides, keyed by device ID
+    pub fn share_subfolder_overrides(&self) -> HashMap<String, bool> {
+        self.configs
+            .iter()
+            .filter_map(|(id, config)| {
+                config
+                    .share_device_subfolders
+                    .map(|enabled| (id.clone(), enabled))
+            })
+            .collect()
+    }
+
+    /// Get per-device heartbeat interval overrides, keyed by device ID
+    pub fn heartbeat_interval_overrides(&self) -> HashMap<String, u64> {
+        self.configs
+            .iter()
+            .filter_map(|(id, config)| {
+                config
+                    .heartbeat_interval_secs
+                    .map(|secs| (id.clone(), secs))
+            })
+            .collect()
+    }
+
+    /// Get per-device capability overrides, keyed by device ID
+    pub fn capability_overrides(&self) -> HashMap<String, CapabilityOverrides> {
+        self.configs
+            .iter()
+            .filter(|(_, config)| !config.capabilities.is_empty())
+            .map(|(id, config)| (id.clone(), config.capabilities.clone()))
+            .collect()
+    }
+
+    /// Get per-device privileged plugin allowlists, keyed by device ID
+    pub fn privileged_allowlist(&self) -> HashMap<String, HashSet<String>> {
+        self.configs
+            .iter()
+            .map(|(id, config)| (id.clone(), config.privileged_plugins()))
+            .filter(|(_, allowed)| !allowed.is_empty())
+            .collect()
+    }
+
+    /// Get per-device disabled plugins, keyed by device ID
+    pub fn disabled_plugins(&self) -> HashMap<String, HashSet<String>> {
+        self.configs
+            .iter()
+            .map(|(id, config)| (id.clone(), config.disabled_plugins()))
+            .filter(|(_, disabled)| !disabled.is_empty())
+            .collect()
+    }
+
     /// Get number of configured devices
     #[allow(dead_code)]
     pub fn len(&self) -> usize {
@@ -461,6 +792,48 @@ mod tests {
         assert!(config.is_plugin_enabled("ping", &global_config));
     }
 
+    #[test]
+    fn test_disabled_plugins_only_explicit_overrides() {
+        let mut config = DeviceConfig::new("test-device".to_string());
+        assert!(config.disabled_plugins().is_empty());
+
+        config.set_plugin_enabled("mpris", false);
+        config.set_plugin_enabled("share", true);
+        assert_eq!(
+            config.disabled_plugins(),
+            HashSet::from(["mpris".to_string()])
+        );
+
+        config.clear_plugin_override("mpris");
+        assert!(config.disabled_plugins().is_empty());
+    }
+
+    #[test]
+    fn test_privileged_plugins_allowlist() {
+        let mut config = DeviceConfig::new("test-device".to_string());
+        assert!(config.privileged_plugins().is_empty());
+
+        config.allowed_privileged_plugins = vec!["remoteinput".to_string(), "bogus".to_string()];
+        assert_eq!(
+            config.privileged_plugins(),
+            HashSet::from(["remoteinput".to_string()])
+        );
+
+        // Configs written before the allowlist existed allow nothing
+        let mut json = serde_json::to_value(&config).unwrap();
+        json.as_object_mut()
+            .unwrap()
+            .remove("allowed_privileged_plugins");
+        let parsed: DeviceConfig = serde_json::from_value(json).unwrap();
+        assert!(parsed.privileged_plugins().is_empty());
+
+        assert!(config.set_privileged_plugin_allowed("power", true));
+        assert!(config.set_privileged_plugin_allowed("power", true));
+        assert!(config.set_privileged_plugin_allowed("remoteinput", false));
+        assert!(!config.set_privileged_plugin_allowed("ping", true));
+        assert_eq!(config.allowed_privileged_plugins, vec!["bogus", "power"]);
+    }
+
     #[test]
     fn test_device_config_serialization() {
         let mut config = DeviceConfig::new("test-device".to_string());
@@ -475,6 +848,126 @@ mod tests {
         assert_eq!(parsed.plugins.enable_battery, Some(false));
     }
 
+    #[test]
+    fn test_plugin_config_round_trip() {
+        let mut plugins = DevicePluginConfig::default();
+        for (i, name) in DEVICE_PLUGIN_NAMES.iter().enumerate() {
+            assert!(plugins.set(name, Some(i % 2 == 0)));
+        }
+        assert!(!plugins.set("bogus", Some(true)));
+
+        let json = serde_json::to_value(&plugins).unwrap();
+        assert_eq!(json["enable_ping"], serde_json::json!(true));
+        assert_eq!(json["enable_battery"], serde_json::json!(false));
+
+        let parsed: DevicePluginConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, plugins);
+        assert_eq!(parsed.overrides().len(), DEVICE_PLUGIN_NAMES.len());
+        assert!(parsed.validate().is_empty());
+    }
+
+    #[test]
+    fn test_unknown_plugin_key_is_reported() {
+        let parsed: DevicePluginConfig =
+            serde_json::from_str(r#"{"enable_battery": false, "enable_batery": true}"#).unwrap();
+
+        assert_eq!(parsed.enable_battery, Some(false));
+        assert_eq!(parsed.validate(), vec!["enable_batery".to_string()]);
+
+        // Kept on save so the user can still fix it
+        let json = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(json["enable_batery"], serde_json::json!(true));
+    }
+
+    #[test]
+    fn test_default_for_device_type() {
+        // Phones keep the global config for everything
+        let phone = DeviceConfig::for_device_type("phone".to_string(), DeviceType::Phone);
+        assert!(phone.plugins.overrides().is_empty());
+        let global = crate::config::PluginConfig {
+            enable_battery: false,
+            ..Default::default()
+        };
+        assert!(!phone.is_plugin_enabled("battery", &global));
+
+        let desktop = DevicePluginConfig::default_for_device_type(DeviceType::Desktop);
+        assert_eq!(desktop.overrides(), vec![("battery", false)]);
+
+        let mut registry = DeviceConfigRegistry::new(&std::env::temp_dir());
+        let (config, created) = registry.get_or_create_for_device_type("tv", DeviceType::Tv);
+        assert!(created);
+        config.set_plugin_enabled("battery", true);
+        let (config, created) = registry.get_or_create_for_device_type("tv", DeviceType::Tv);
+        assert!(!created);
+        assert_eq!(config.plugins.get("battery"), Some(true));
+        assert_eq!(config.plugins.get("clipboard"), Some(false));
+    }
+
+    #[test]
+    fn test_capability_overrides_config() {
+        let mut json = serde_json::to_value(DeviceConfig::new("test-device".to_string())).unwrap();
+        assert_eq!(json["capabilities"]["force_enable"], serde_json::json!([]));
+
+        json["capabilities"] = serde_json::json!({
+            "force_enable": ["mpris"],
+            "force_disable": ["kdeconnect.clipboard"],
+        });
+        let parsed: DeviceConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.capabilities.force_enable, vec!["mpris".to_string()]);
+        assert_eq!(
+            parsed.capabilities.force_disable,
+            vec!["kdeconnect.clipboard".to_string()]
+        );
+
+        // Configs written before overrides existed have none
+        let mut json = serde_json::to_value(&parsed).unwrap();
+        json.as_object_mut().unwrap().remove("capabilities");
+        let parsed: DeviceConfig = serde_json::from_value(json).unwrap();
+        assert!(parsed.capabilities.is_empty());
+    }
+
+    #[test]
+    fn test_networkshare_freshness_override() {
+        let mut config = DeviceConfig::new("test-device".to_string());
+        let global_config = crate::config::PluginConfig::default();
+
+        assert_eq!(config.get_networkshare_freshness_secs(&global_config), 300);
+
+        config.networkshare_freshness_secs = Some(60);
+        assert_eq!(config.get_networkshare_freshness_secs(&global_config), 60);
+
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: DeviceConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.networkshare_freshness_secs, Some(60));
+    }
+
+    #[test]
+    fn test_max_forwarded_notifications_override() {
+        let mut config = DeviceConfig::new("test-device".to_string());
+        let global_config = crate::config::NotificationListenerConfig::default();
+
+        assert_eq!(config.get_max_forwarded_notifications(&global_config), 30);
+
+        config.max_forwarded_notifications = Some(5);
+        assert_eq!(config.get_max_forwarded_notifications(&global_config), 5);
+    }
+
+    #[test]
+    fn test_heartbeat_interval_overrides() {
+        let temp_dir = std::env::temp_dir().join("cconnect-test-heartbeat");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let mut registry = DeviceConfigRegistry::new(&temp_dir);
+
+        registry.get_or_create("phone").heartbeat_interval_secs = Some(45);
+        registry.get_or_create("laptop");
+
+        let overrides = registry.heartbeat_interval_overrides();
+        assert_eq!(overrides.len(), 1);
+        assert_eq!(overrides.get("phone"), Some(&45));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
     #[test]
     fn test_device_registry() {
         let temp_dir = std::env::temp_dir().join("cconnect-test");
@@ -502,4 +995,59 @@ mod tests {
         // Cleanup
         fs::remove_dir_all(&temp_dir).ok();
     }
+    #[test]
+    fn test_load_v1_device_configs() {
+        let temp_dir = std::env::temp_dir().join("cconnect-test-schema-v1");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        // v1 files are a bare map without a schema version
+        fs::write(
+            temp_dir.join("device_configs.json"),
+            r#"{"device-1": {"device_id": "device-1", "nickname": "Old Phone", "plugins": {}}}"#,
+        )
+        .unwrap();
+
+        let mut registry = DeviceConfigRegistry::new(&temp_dir);
+        registry.load().unwrap();
+        assert_eq!(
+            registry.get("device-1").unwrap().nickname,
+            Some("Old Phone".to_string())
+        );
+
+        // Saving writes the current schema
+        registry.save().unwrap();
+        let saved: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(temp_dir.join("device_configs.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            saved["schema_version"],
+            serde_json::json!(DEVICE_CONFIGS_SCHEMA_VERSION)
+        );
+        assert!(saved["devices"]["device-1"].is_object());
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_reject_newer_device_configs() {
+        let temp_dir = std::env::temp_dir().join("cconnect-test-schema-newer");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        fs::write(
+            temp_dir.join("device_configs.json"),
+            format!(
+                r#"{{"schema_version": {}, "devices": {{}}}}"#,
+                DEVICE_CONFIGS_SCHEMA_VERSION + 1
+            ),
+        )
+        .unwrap();
+
+        let mut registry = DeviceConfigRegistry::new(&temp_dir);
+        let err = registry.load().unwrap_err();
+        assert!(format!("{:#}", err).contains("newer than the supported version"));
+        assert!(registry.is_empty());
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
 }
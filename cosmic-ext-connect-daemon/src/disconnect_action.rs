@@ -0,0 +1,203 @@
+//! Device Disconnect Actions
+//!
+//! Runs a per-device action, such as locking the desktop, when a device goes
+//! offline and stays offline for the grace period. Reconnecting within the
+//! grace period cancels the pending action, so a flapping Wi-Fi link does not
+//! lock the screen.
+//!
+//! Each action type must also be allowed globally via the `allow_*` switches
+//! in [`DisconnectActionConfig`](crate::config::DisconnectActionConfig).
+
+use anyhow::{anyhow, Context, Result};
+use cosmic_ext_connect_protocol::plugins::logind_backend::LogindBackend;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+/// Action to run when a device goes offline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DisconnectAction {
+    /// Do nothing
+    #[default]
+    None,
+    /// Lock the desktop session
+    Lock,
+    /// Suspend the system
+    Suspend,
+    /// Run a shell command
+    RunCommand {
+        /// Command line passed to `sh -c`
+        command: String,
+    },
+}
+
+impl DisconnectAction {
+    /// Whether this is [`DisconnectAction::None`]
+    pub fn is_none(&self) -> bool {
+        *self == DisconnectAction::None
+    }
+}
+
+/// Schedules disconnect actions after a grace period
+///
+/// When the grace period expires the device ID and action are sent on the
+/// channel given to [`new`](Self::new); the receiver runs the action with
+/// [`execute`].
+pub struct DisconnectActions {
+    /// How long a device must stay offline before its action runs
+    grace_period: Duration,
+
+    /// Pending actions per device
+    pending: HashMap<String, JoinHandle<()>>,
+
+    /// Channel for actions whose grace period expired
+    action_tx: UnboundedSender<(String, DisconnectAction)>,
+}
+
+impl DisconnectActions {
+    /// Create a scheduler with the given grace period
+    pub fn new(
+        grace_period: Duration,
+        action_tx: UnboundedSender<(String, DisconnectAction)>,
+    ) -> Self {
+        Self {
+            grace_period,
+            pending: HashMap::new(),
+            action_tx,
+        }
+    }
+
+    /// Schedule `action` for a device that just went offline
+    ///
+    /// Replaces any action already pending for the device.
+    pub fn device_disconnected(&mut self, device_id: &str, action: DisconnectAction) {
+        self.device_connected(device_id);
+        if action.is_none() {
+            return;
+        }
+
+        debug!(
+            "Scheduling disconnect action {:?} for device {} in {:?}",
+            action, device_id, self.grace_period
+        );
+
+        let grace_period = self.grace_period;
+        let action_tx = self.action_tx.clone();
+        let id = device_id.to_string();
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(grace_period).await;
+            let _ = action_tx.send((id, action));
+        });
+        self.pending.insert(device_id.to_string(), handle);
+    }
+
+    /// Cancel the pending action of a device that came back online
+    ///
+    /// Returns `true` if an action was cancelled.
+    pub fn device_connected(&mut self, device_id: &str) -> bool {
+        match self.pending.remove(device_id) {
+            Some(handle) if !handle.is_finished() => {
+                handle.abort();
+                debug!(
+                    "Device {} reconnected within grace period, disconnect action cancelled",
+                    device_id
+                );
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Run a disconnect action
+pub async fn execute(device_id: &str, action: &DisconnectAction) -> Result<()> {
+    info!(
+        "Device {} stayed offline, running disconnect action {:?}",
+        device_id, action
+    );
+
+    match action {
+        DisconnectAction::None => Ok(()),
+        DisconnectAction::Lock => LogindBackend::new()
+            .lock()
+            .await
+            .map_err(|e| anyhow!("Failed to lock desktop: {}", e)),
+        DisconnectAction::Suspend => LogindBackend::new()
+            .suspend(false)
+            .await
+            .map_err(|e| anyhow!("Failed to suspend: {}", e)),
+        DisconnectAction::RunCommand { command } => {
+            let status = tokio::process::Command::new("/bin/sh")
+                .arg("-c")
+                .arg(command)
+                .status()
+                .await
+                .context("Failed to run disconnect command")?;
+            if !status.success() {
+                return Err(anyhow!("Disconnect command exited with {}", status));
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    const GRACE: Duration = Duration::from_millis(50);
+
+    #[tokio::test]
+    async fn test_action_runs_after_grace_period() {
+        let (tx, mut rx) = unbounded_channel();
+        let mut actions = DisconnectActions::new(GRACE, tx);
+
+        actions.device_disconnected("phone", DisconnectAction::Lock);
+        assert!(rx.try_recv().is_err());
+
+        let fired = tokio::time::timeout(GRACE * 10, rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fired, ("phone".to_string(), DisconnectAction::Lock));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_within_grace_cancels_action() {
+        let (tx, mut rx) = unbounded_channel();
+        let mut actions = DisconnectActions::new(GRACE, tx);
+
+        // Link flaps: offline, back online before the grace period ends
+        actions.device_disconnected("phone", DisconnectAction::Suspend);
+        tokio::time::sleep(GRACE / 5).await;
+        assert!(actions.device_connected("phone"));
+
+        tokio::time::sleep(GRACE * 3).await;
+        assert!(rx.try_recv().is_err());
+
+        // No action configured means nothing is scheduled
+        actions.device_disconnected("tablet", DisconnectAction::None);
+        assert!(!actions.device_connected("tablet"));
+    }
+
+    #[test]
+    fn test_action_serialization() {
+        let action = DisconnectAction::RunCommand {
+            command: "notify-send bye".to_string(),
+        };
+        let json = serde_json::to_string(&action).unwrap();
+        assert_eq!(
+            json,
+            r#"{"action":"run_command","command":"notify-send bye"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<DisconnectAction>(r#"{"action":"lock"}"#).unwrap(),
+            DisconnectAction::Lock
+        );
+    }
+}
@@ -115,7 +115,7 @@ impl ErrorHandler {
                     .await?;
             }
 
-            ProtocolError::Timeout(_msg) => {
+            ProtocolError::Timeout(_msg) | ProtocolError::HandshakeTimeout(_msg) => {
                 notifier.notify_connection_timeout(device_name).await?;
             }
 
@@ -163,6 +163,12 @@ impl ErrorHandler {
                 notifier.notify_certificate_error(device_name, msg).await?;
             }
 
+            ProtocolError::CertUntrusted(_) | ProtocolError::CertMismatch(_) => {
+                notifier
+                    .notify_certificate_error(device_name, &error.user_message())
+                    .await?;
+            }
+
             ProtocolError::Certificate(err) => {
                 let msg = format!("{}", err);
                 notifier.notify_certificate_error(device_name, &msg).await?;
@@ -0,0 +1,605 @@
+//! Event History
+//!
+//! Persistent log of device activity shown on the manager's History page:
+//! pings, file transfers, SMS messages and notifications, and pairing changes.
+//!
+//! Recording never touches the database on the caller's task. Events go
+//! through an unbounded channel to a dedicated writer thread, which stores
+//! them in batches and trims the log to [`MAX_HISTORY_ENTRIES`]. Queries and
+//! clearing from async code go through [`History::run_blocking`], which moves
+//! them onto the blocking thread pool.
+//!
+//! ## Database Schema
+//!
+//! ```sql
+//! CREATE TABLE history (
+//!     id INTEGER PRIMARY KEY AUTOINCREMENT,
+//!     timestamp INTEGER NOT NULL,
+//!     device_id TEXT NOT NULL,
+//!     event_type TEXT NOT NULL,
+//!     summary TEXT NOT NULL
+//! );
+//! ```
+//!
+//! ## Storage Location
+//!
+//! `<data_dir>/history.db`
+
+use anyhow::{anyhow, Context, Result};
+use cosmic_ext_connect_protocol::pairing::PairingEvent;
+use cosmic_ext_connect_protocol::Packet;
+use rusqlite::{params, params_from_iter, Connection};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// Event type: ping received from a device
+pub const EVENT_PING: &str = "ping";
+
+/// Event type: file transfer finished, failed or was cancelled
+pub const EVENT_TRANSFER: &str = "transfer";
+
+/// Event type: SMS messages received
+pub const EVENT_SMS: &str = "sms";
+
+/// Event type: notification received from a device
+pub const EVENT_NOTIFICATION: &str = "notification";
+
+/// Event type: pairing request, pairing or unpairing
+pub const EVENT_PAIRING: &str = "pairing";
+
+/// Oldest entries beyond this count are dropped
+pub const MAX_HISTORY_ENTRIES: i64 = 10_000;
+
+/// Largest page a single query returns
+pub const MAX_PAGE_SIZE: u32 = 500;
+
+/// Most events the writer stores in one transaction
+const WRITE_BATCH_SIZE: usize = 100;
+
+/// Current UNIX time in milliseconds
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// A stored history event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryRecord {
+    /// Row ID, increasing with insertion order
+    pub id: i64,
+    /// When the event happened (UNIX timestamp in milliseconds)
+    pub timestamp: i64,
+    /// Device the event relates to
+    pub device_id: String,
+    /// One of the `EVENT_*` constants
+    pub event_type: String,
+    /// Human-readable description
+    pub summary: String,
+}
+
+/// Query filter; `None` matches everything
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HistoryFilter<'a> {
+    /// Only events of this device
+    pub device_id: Option<&'a str>,
+    /// Only events of this type
+    pub event_type: Option<&'a str>,
+}
+
+impl HistoryFilter<'_> {
+    /// SQL `WHERE` clause and its parameters
+    fn where_clause(&self) -> (String, Vec<&str>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(device_id) = self.device_id {
+            values.push(device_id);
+            conditions.push(format!("device_id = ?{}", values.len()));
+        }
+        if let Some(event_type) = self.event_type {
+            values.push(event_type);
+            conditions.push(format!("event_type = ?{}", values.len()));
+        }
+
+        if conditions.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!("WHERE {}", conditions.join(" AND ")), values)
+        }
+    }
+}
+
+/// SQLite-backed history log
+#[derive(Clone)]
+pub struct HistoryStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl HistoryStore {
+    /// Open or create the database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).context("Failed to create history directory")?;
+        }
+        let conn = Connection::open(path).context("Failed to open history database")?;
+        debug!("Opened history database at {}", path.display());
+        Self::init(conn)
+    }
+
+    /// Database that lives only as long as the daemon
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory().context("Failed to open history database")?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                device_id TEXT NOT NULL,
+                event_type TEXT NOT NULL,
+                summary TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS history_device ON history (device_id, id);",
+        )
+        .context("Failed to create history table")?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| anyhow!("History database lock poisoned"))
+    }
+
+    /// Store events in one transaction, then trim the log to `max_entries`
+    pub fn insert_batch(&self, records: &[HistoryRecord], max_entries: i64) -> Result<()> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction().context("Failed to start transaction")?;
+        for record in records {
+            tx.execute(
+                "INSERT INTO history (timestamp, device_id, event_type, summary)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    record.timestamp,
+                    record.device_id,
+                    record.event_type,
+                    record.summary
+                ],
+            )
+            .context("Failed to insert history event")?;
+        }
+        tx.execute(
+            "DELETE FROM history WHERE id <= (SELECT MAX(id) FROM history) - ?1",
+            params![max_entries],
+        )
+        .context("Failed to trim history")?;
+        tx.commit().context("Failed to commit history events")
+    }
+
+    /// One page of events matching `filter`, newest first
+    pub fn query(
+        &self,
+        filter: HistoryFilter<'_>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<HistoryRecord>> {
+        let (where_clause, values) = filter.where_clause();
+        let sql = format!(
+            "SELECT id, timestamp, device_id, event_type, summary FROM history {}
+             ORDER BY id DESC LIMIT {} OFFSET {}",
+            where_clause,
+            limit.min(MAX_PAGE_SIZE),
+            offset
+        );
+
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(&sql).context("Failed to query history")?;
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok(HistoryRecord {
+                    id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    device_id: row.get(2)?,
+                    event_type: row.get(3)?,
+                    summary: row.get(4)?,
+                })
+            })
+            .context("Failed to query history")?;
+
+        rows.collect::<rusqlite::Result<_>>()
+            .context("Failed to read history")
+    }
+
+    /// Number of events matching `filter`
+    pub fn count(&self, filter: HistoryFilter<'_>) -> Result<u64> {
+        let (where_clause, values) = filter.where_clause();
+        let sql = format!("SELECT COUNT(*) FROM history {}", where_clause);
+
+        let conn = self.lock()?;
+        let count: i64 = conn
+            .query_row(&sql, params_from_iter(values), |row| row.get(0))
+            .context("Failed to count history")?;
+        Ok(count as u64)
+    }
+
+    /// Delete events, optionally only those of one device
+    ///
+    /// Returns the number of deleted events.
+    pub fn clear(&self, device_id: Option<&str>) -> Result<usize> {
+        let conn = self.lock()?;
+        let deleted = match device_id {
+            Some(device_id) => conn.execute(
+                "DELETE FROM history WHERE device_id = ?1",
+                params![device_id],
+            ),
+            None => conn.execute("DELETE FROM history", []),
+        }
+        .context("Failed to clear history")?;
+        Ok(deleted)
+    }
+}
+
+/// History log with a background writer
+///
+/// Cheap to clone; all clones feed the same writer thread.
+#[derive(Clone)]
+pub struct History {
+    store: HistoryStore,
+    sender: mpsc::UnboundedSender<HistoryRecord>,
+}
+
+impl History {
+    /// Open the history database in `data_dir`
+    ///
+    /// Falls back to an in-memory log if the database cannot be opened, so
+    /// history keeps working for the current session.
+    pub fn open(data_dir: &Path) -> Self {
+        let store = HistoryStore::open(&data_dir.join("history.db"))
+            .or_else(|e| {
+                warn!(
+                    "Failed to open history database, keeping history in memory: {}",
+                    e
+                );
+                HistoryStore::in_memory()
+            })
+            .expect("in-memory SQLite database");
+        Self::with_store(store)
+    }
+
+    /// Start the writer thread for `store`
+    pub fn with_store(store: HistoryStore) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let writer_store = store.clone();
+        std::thread::Builder::new()
+            .name("history-writer".to_string())
+            .spawn(move || run_writer(writer_store, receiver))
+            .expect("failed to spawn history writer thread");
+        Self { store, sender }
+    }
+
+    /// Queue an event for storage without waiting for the database
+    pub fn record(&self, device_id: &str, event_type: &str, summary: impl Into<String>) {
+        let record = HistoryRecord {
+            id: 0,
+            timestamp: now_millis(),
+            device_id: device_id.to_string(),
+            event_type: event_type.to_string(),
+            summary: summary.into(),
+        };
+        if self.sender.send(record).is_err() {
+            warn!("History writer stopped, dropping event");
+        }
+    }
+
+    /// Stored events (see [`HistoryStore::query`])
+    pub fn store(&self) -> &HistoryStore {
+        &self.store
+    }
+
+    /// Run a database operation on the blocking thread pool
+    pub async fn run_blocking<T, F>(&self, operation: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&HistoryStore) -> Result<T> + Send + 'static,
+    {
+        let store = self.store.clone();
+        tokio::task::spawn_blocking(move || operation(&store))
+            .await
+            .context("History database task failed")?
+    }
+}
+
+/// Drain the event channel into the database until all senders are gone
+fn run_writer(store: HistoryStore, mut receiver: mpsc::UnboundedReceiver<HistoryRecord>) {
+    while let Some(first) = receiver.blocking_recv() {
+        let mut batch = vec![first];
+        while batch.len() < WRITE_BATCH_SIZE {
+            match receiver.try_recv() {
+                Ok(record) => batch.push(record),
+                Err(_) => break,
+            }
+        }
+        if let Err(e) = store.insert_batch(&batch, MAX_HISTORY_ENTRIES) {
+            warn!("Failed to store {} history events: {}", batch.len(), e);
+        }
+    }
+    info!("History writer stopped");
+}
+
+/// History entry for an incoming packet, if it is worth recording
+///
+/// Returns the event type and summary. Keepalive pings, cancelled or silent
+/// notifications and other packet types are skipped.
+pub fn packet_event(packet: &Packet, device_name: &str) -> Option<(&'static str, String)> {
+    let body = &packet.body;
+    match packet.packet_type.as_str() {
+        "cconnect.ping" => {
+            if body.get("keepalive").and_then(|v| v.as_bool()) == Some(true) {
+                return None;
+            }
+            let summary = match body.get("message").and_then(|v| v.as_str()) {
+                Some(message) => format!("Ping from {}: {}", device_name, message),
+                None => format!("Ping from {}", device_name),
+            };
+            Some((EVENT_PING, summary))
+        }
+        "cconnect.notification" => {
+            if body.get("isCancel").and_then(|v| v.as_bool()) == Some(true)
+                || body.get("silent").and_then(|v| v.as_str()) == Some("true")
+            {
+                return None;
+            }
+            let app_name = body.get("appName").and_then(|v| v.as_str()).unwrap_or("");
+            let title = body
+                .get("title")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Notification");
+            let summary = if app_name.is_empty() {
+                title.to_string()
+            } else {
+                format!("{}: {}", app_name, title)
+            };
+            Some((EVENT_NOTIFICATION, summary))
+        }
+        "cconnect.sms.messages" => {
+            let messages: Vec<&serde_json::Value> = body
+                .get("conversations")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|conversation| conversation.get("messages")?.as_array())
+                .flatten()
+                .collect();
+            match messages.as_slice() {
+                [] => None,
+                [message] => {
+                    let address = message
+                        .get("address")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("unknown sender");
+                    Some((EVENT_SMS, format!("SMS from {}", address)))
+                }
+                _ => Some((
+                    EVENT_SMS,
+                    format!("{} SMS messages received", messages.len()),
+                )),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// History entry for a pairing event, if it is worth recording
+///
+/// Returns the device ID and summary.
+pub fn pairing_event(event: &PairingEvent) -> Option<(&str, String)> {
+    match event {
+        PairingEvent::RequestReceived {
+            device_id,
+            device_name,
+            ..
+        } => Some((device_id, format!("Pairing requested by {}", device_name))),
+        PairingEvent::PairingAccepted {
+            device_id,
+            device_name,
+            ..
+        } => Some((device_id, format!("Paired with {}", device_name))),
+        PairingEvent::PairingRejected { device_id, reason } => {
+            let summary = match reason {
+                Some(reason) => format!("Pairing rejected: {}", reason),
+                None => "Pairing rejected".to_string(),
+            };
+            Some((device_id, summary))
+        }
+        PairingEvent::DeviceUnpaired { device_id } => Some((device_id, "Unpaired".to_string())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(device_id: &str, event_type: &str, summary: &str) -> HistoryRecord {
+        HistoryRecord {
+            id: 0,
+            timestamp: now_millis(),
+            device_id: device_id.to_string(),
+            event_type: event_type.to_string(),
+            summary: summary.to_string(),
+        }
+    }
+
+    fn store_with(records: &[HistoryRecord]) -> HistoryStore {
+        let store = HistoryStore::in_memory().unwrap();
+        store.insert_batch(records, MAX_HISTORY_ENTRIES).unwrap();
+        store
+    }
+
+    #[test]
+    fn test_query_pages_newest_first() {
+        let records: Vec<_> = (0..5)
+            .map(|i| record("phone", EVENT_PING, &format!("ping {}", i)))
+            .collect();
+        let store = store_with(&records);
+
+        let page = store.query(HistoryFilter::default(), 2, 0).unwrap();
+        let summaries: Vec<_> = page.iter().map(|r| r.summary.as_str()).collect();
+        assert_eq!(summaries, ["ping 4", "ping 3"]);
+
+        let page = store.query(HistoryFilter::default(), 2, 4).unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].summary, "ping 0");
+
+        assert!(store
+            .query(HistoryFilter::default(), 2, 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let store = store_with(&[
+            record("phone", EVENT_PING, "a"),
+            record("tablet", EVENT_PING, "b"),
+            record("phone", EVENT_TRANSFER, "c"),
+        ]);
+
+        let phone = HistoryFilter {
+            device_id: Some("phone"),
+            event_type: None,
+        };
+        assert_eq!(store.count(phone).unwrap(), 2);
+
+        let phone_pings = HistoryFilter {
+            device_id: Some("phone"),
+            event_type: Some(EVENT_PING),
+        };
+        let page = store.query(phone_pings, 10, 0).unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].summary, "a");
+
+        let transfers = HistoryFilter {
+            device_id: None,
+            event_type: Some(EVENT_TRANSFER),
+        };
+        assert_eq!(store.count(transfers).unwrap(), 1);
+        assert_eq!(store.count(HistoryFilter::default()).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_insert_trims_oldest() {
+        let store = HistoryStore::in_memory().unwrap();
+        for i in 0..5 {
+            store
+                .insert_batch(&[record("phone", EVENT_PING, &i.to_string())], 3)
+                .unwrap();
+        }
+
+        let page = store.query(HistoryFilter::default(), 10, 0).unwrap();
+        let summaries: Vec<_> = page.iter().map(|r| r.summary.as_str()).collect();
+        assert_eq!(summaries, ["4", "3", "2"]);
+    }
+
+    #[test]
+    fn test_clear() {
+        let store = store_with(&[
+            record("phone", EVENT_PING, "a"),
+            record("tablet", EVENT_PING, "b"),
+            record("phone", EVENT_SMS, "c"),
+        ]);
+
+        assert_eq!(store.clear(Some("phone")).unwrap(), 2);
+        assert_eq!(store.count(HistoryFilter::default()).unwrap(), 1);
+        assert_eq!(store.clear(None).unwrap(), 1);
+        assert_eq!(store.count(HistoryFilter::default()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_history_records_in_background() {
+        let history = History::with_store(HistoryStore::in_memory().unwrap());
+        history.record("phone", EVENT_PAIRING, "Paired with Phone");
+
+        let mut count = 0;
+        for _ in 0..100 {
+            count = history.store().count(HistoryFilter::default()).unwrap();
+            if count == 1 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_blocking_queries_store() {
+        let history = History::with_store(store_with(&[record("phone", EVENT_PING, "a")]));
+
+        let count = history
+            .run_blocking(|store| store.count(HistoryFilter::default()))
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_packet_event() {
+        let ping = Packet::new("cconnect.ping", json!({ "message": "hi" }));
+        assert_eq!(
+            packet_event(&ping, "Phone"),
+            Some((EVENT_PING, "Ping from Phone: hi".to_string()))
+        );
+
+        let keepalive = Packet::new("cconnect.ping", json!({ "keepalive": true }));
+        assert_eq!(packet_event(&keepalive, "Phone"), None);
+
+        let notification = Packet::new(
+            "cconnect.notification",
+            json!({ "appName": "Signal", "title": "New message" }),
+        );
+        assert_eq!(
+            packet_event(&notification, "Phone"),
+            Some((EVENT_NOTIFICATION, "Signal: New message".to_string()))
+        );
+
+        let cancel = Packet::new("cconnect.notification", json!({ "isCancel": true }));
+        assert_eq!(packet_event(&cancel, "Phone"), None);
+
+        let sms = Packet::new(
+            "cconnect.sms.messages",
+            json!({ "conversations": [
+                { "messages": [{ "address": "+123", "body": "Hello" }] }
+            ] }),
+        );
+        assert_eq!(
+            packet_event(&sms, "Phone"),
+            Some((EVENT_SMS, "SMS from +123".to_string()))
+        );
+
+        let battery = Packet::new("cconnect.battery", json!({}));
+        assert_eq!(packet_event(&battery, "Phone"), None);
+    }
+
+    #[test]
+    fn test_pairing_event() {
+        let accepted = PairingEvent::PairingAccepted {
+            device_id: "phone".to_string(),
+            device_name: "Phone".to_string(),
+            certificate_fingerprint: String::new(),
+        };
+        assert_eq!(
+            pairing_event(&accepted),
+            Some(("phone", "Paired with Phone".to_string()))
+        );
+
+        let timeout = PairingEvent::PairingTimeout {
+            device_id: "phone".to_string(),
+        };
+        assert_eq!(pairing_event(&timeout), None);
+    }
+}
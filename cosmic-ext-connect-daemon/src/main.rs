@@ -1,55 +1,71 @@
+mod battery_alert;
 mod config;
 mod cosmic_notifications;
 mod dbus;
 mod desktop_icons;
 mod device_config;
 mod diagnostics;
+mod disconnect_action;
 mod error_handler;
+mod history;
+mod metered_policy;
 mod mpris_manager;
 mod notification_image;
 mod notification_listener;
+mod notification_rate_limit;
+mod notification_reply;
+mod notification_snooze;
+mod notification_tracker;
+mod ring_action;
+mod schema;
+mod signal_batch;
+mod usage_report;
 
 use anyhow::{Context, Result};
 use clap::Parser;
 use cosmic_ext_connect_protocol::{
     connection::{ConnectionConfig, ConnectionEvent, ConnectionManager},
     discovery::{
-        default_additional_broadcast_addrs, DiscoveryConfig, DiscoveryEvent, DiscoveryService,
+        default_additional_broadcast_addrs, DiscoveryConfig, DiscoveryEvent, IdentityPacket,
+        UnifiedDiscoveryConfig, UnifiedDiscoveryService,
     },
-    pairing::{PairingConfig, PairingEvent, PairingService, PairingStatus},
+    pairing::{PairingConfig, PairingEvent, PairingService},
+    payload::TRANSFER_FAILED_CANCELLED,
     plugins::{
         audiostream::AudioStreamPluginFactory,
+        audit::AuditLog,
         battery::BatteryPluginFactory,
         camera::CameraPluginFactory,
         chat::ChatPluginFactory,
-        clipboard::ClipboardPluginFactory,
+        clipboard::{ClipboardPlugin, ClipboardPluginFactory},
         clipboardhistory::ClipboardHistoryPluginFactory,
         connectivity_report::ConnectivityReportPluginFactory,
         contacts::{ContactsPlugin, ContactsPluginFactory},
         filesync::FileSyncPluginFactory,
-        findmyphone::FindMyPhonePluginFactory,
+        findmyphone::{FindMyPhonePlugin, FindMyPhonePluginFactory},
         lock::LockPluginFactory,
         mousekeyboardshare::MouseKeyboardSharePluginFactory,
-        mpris::MprisPluginFactory,
-        networkshare::NetworkSharePluginFactory,
+        mpris::{MprisEvent, MprisPluginFactory},
+        networkshare::{NetworkShareConfig, NetworkSharePluginFactory},
         notification::NotificationPluginFactory,
+        permission::AllowlistPolicy,
         ping::PingPluginFactory,
         power::PowerPluginFactory,
-        presenter::PresenterPluginFactory,
+        presenter::{LaserPointerConfig, PresenterConfig, PresenterPluginFactory},
         r#macro::MacroPluginFactory,
-        remoteinput::RemoteInputPluginFactory,
-        runcommand::RunCommandPluginFactory,
+        remoteinput::{RemoteInputConfig, RemoteInputPluginFactory},
+        runcommand::{RunCommandPlugin, RunCommandPluginFactory, PACKET_TYPE_RUNCOMMAND_CONFIRM},
         screenshare::ScreenSharePluginFactory,
         screenshot::ScreenshotPluginFactory,
-        share::SharePluginFactory,
+        share::{ShareConfig, ShareEvent, SharePluginFactory},
         systemmonitor::SystemMonitorPluginFactory,
         systemvolume::SystemVolumePluginFactory,
         telephony::TelephonyPluginFactory,
         wol::WolPluginFactory,
         PluginManager,
     },
-    CertificateInfo, DeviceInfo, DeviceManager, DeviceType, Packet, TransportManager,
-    TransportManagerConfig, TransportManagerEvent,
+    CapabilityDiff, CertificateInfo, DeviceInfo, DeviceManager, DeviceType, Packet,
+    RecoveryManager, TransportManager, TransportManagerConfig, TransportManagerEvent,
 };
 use dbus::DbusServer;
 use diagnostics::{BuildInfo, Cli, DiagnosticCommand, Metrics};
@@ -63,16 +79,30 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 /// Placeholder address for Bluetooth connections that lack a real SocketAddr
 const BT_PLACEHOLDER_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+
+/// Delay before reconnecting a paired device over another transport after
+/// its link dropped, so a replacement connection can arrive first
+const TRANSPORT_FAILOVER_DELAY: Duration = Duration::from_secs(2);
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, trace, warn};
 
+use battery_alert::LowBatteryAlerts;
 use config::Config;
 
+use disconnect_action::DisconnectActions;
 use error_handler::ErrorHandler;
+use metered_policy::{MeteredDecision, MeteredLink, MeteredNotices};
 
-use notification_listener::{CapturedNotification, NotificationListener};
+use notification_listener::{CapturedNotification, NotificationEvent, NotificationListener};
+use notification_rate_limit::NotificationRateLimiter;
+use notification_reply::{NotificationReplies, ReplyTarget};
+use history::History;
+use notification_snooze::NotificationSnoozes;
+use notification_tracker::ActiveNotifications;
+use ring_action::RingActions;
+use usage_report::UsageReporter;
 
 /// Main daemon state
 #[allow(clippy::type_complexity)] // Complex types needed for async shared state
@@ -102,8 +132,14 @@ struct Daemon {
     /// Per-device configuration registry
     device_config_registry: Arc<RwLock<device_config::DeviceConfigRegistry>>,
 
-    /// Discovery service
-    discovery_service: Option<DiscoveryService>,
+    /// Privileged plugins allowed per device, shared with plugins and DBus
+    permission_policy: AllowlistPolicy,
+
+    /// Audit log of privileged plugin actions
+    audit_log: Arc<AuditLog>,
+
+    /// Discovery service, merging UDP and mDNS discovery
+    discovery_service: Option<UnifiedDiscoveryService>,
 
     /// Pairing service (wrapped for shared access with DBus)
     pairing_service: Option<Arc<RwLock<PairingService>>>,
@@ -126,6 +162,9 @@ struct Daemon {
     /// Map of notification IDs to device IDs for pairing notifications
     pairing_notifications: Arc<RwLock<std::collections::HashMap<u32, String>>>,
 
+    /// Map of notification IDs to (device ID, command key) for run command confirmations
+    runcommand_confirmations: Arc<RwLock<std::collections::HashMap<u32, (String, String)>>>,
+
     /// Map of device IDs to pending pairing request status
     pending_pairing_requests: Arc<RwLock<std::collections::HashMap<String, bool>>>,
 
@@ -146,7 +185,36 @@ struct Daemon {
 
     /// Receiver for captured notifications from the notification listener
     notification_receiver:
-        Arc<tokio::sync::Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<CapturedNotification>>>>,
+        Arc<tokio::sync::Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<NotificationEvent>>>>,
+
+    /// Apps whose forwarded notifications are temporarily snoozed
+    notification_snoozes: Arc<RwLock<NotificationSnoozes>>,
+
+    /// Opt-in anonymized usage report
+    usage_reporter: Arc<RwLock<UsageReporter>>,
+
+    /// Whether this desktop's network link is metered
+    metered_link: MeteredLink,
+
+    /// Pending per-device disconnect actions
+    disconnect_actions: Arc<RwLock<DisconnectActions>>,
+
+    /// Persistent device event log for the History page
+    history: History,
+
+    /// Low battery notification state per device
+    battery_alerts: Arc<RwLock<LowBatteryAlerts>>,
+
+    /// Where replies to forwarded notifications are delivered
+    notification_replies: Arc<RwLock<NotificationReplies>>,
+
+    /// Receiver for share plugin events (wrapped in Mutex to allow extraction)
+    share_event_receiver:
+        Arc<tokio::sync::Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<ShareEvent>>>>,
+
+    /// Receiver for remote media player events (wrapped in Mutex to allow extraction)
+    mpris_event_receiver:
+        Arc<tokio::sync::Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<MprisEvent>>>>,
 }
 
 impl Daemon {
@@ -220,8 +288,60 @@ impl Daemon {
         device_config_registry
             .load()
             .context("Failed to load device configurations")?;
+
+        // Append-only log of privileged plugin actions, read by GetAuditLog
+        let audit_log = Arc::new(AuditLog::open(config.paths.data_dir.join("audit.log")));
+
+        // Power, run-command and remote input actions are denied unless the
+        // device config allowlists the plugin
+        let permission_policy = AllowlistPolicy::new()
+            .with_polkit(config.plugins.permission_polkit)
+            .with_audit_log(audit_log.clone());
+        for (device_id, plugins) in device_config_registry.privileged_allowlist() {
+            info!(
+                "Privileged plugins allowed for device {}: {:?}",
+                device_id, plugins
+            );
+            permission_policy.set_allowed(&device_id, plugins);
+        }
         let device_config_registry = Arc::new(RwLock::new(device_config_registry));
 
+        // Load notification snoozes (expired entries are dropped on load)
+        let mut notification_snoozes = NotificationSnoozes::new(&config.paths.data_dir);
+        if let Err(e) = notification_snoozes.load() {
+            warn!("Failed to load notification snoozes: {}", e);
+        }
+        let notification_snoozes = Arc::new(RwLock::new(notification_snoozes));
+
+        // Load the usage report (only recorded if the user opted in)
+        let mut usage_reporter =
+            UsageReporter::new(&config.paths.data_dir, config.usage_report.enabled);
+        if let Err(e) = usage_reporter.load() {
+            warn!("Failed to load usage report: {}", e);
+        }
+        let usage_reporter = Arc::new(RwLock::new(usage_reporter));
+
+        // NetworkManager tells us whether our own link is metered
+        let metered_link = MeteredLink::connect().await;
+
+        // Device event log, written from a background thread
+        let history = History::open(&config.paths.data_dir);
+
+        // Run disconnect actions once their grace period expires
+        let (disconnect_action_tx, mut disconnect_action_rx) =
+            tokio::sync::mpsc::unbounded_channel();
+        let disconnect_actions = Arc::new(RwLock::new(DisconnectActions::new(
+            config.disconnect_actions.grace_period(),
+            disconnect_action_tx,
+        )));
+        tokio::spawn(async move {
+            while let Some((device_id, action)) = disconnect_action_rx.recv().await {
+                if let Err(e) = disconnect_action::execute(&device_id, &action).await {
+                    warn!("Disconnect action for device {} failed: {}", device_id, e);
+                }
+            }
+        });
+
         // Create TLS configuration for payload transfers
         let tls_config = Arc::new(
             cosmic_ext_connect_protocol::TlsConfig::new(&certificate)
@@ -235,6 +355,10 @@ impl Daemon {
                 .context("Invalid listen address")?,
             keep_alive_interval: Duration::from_secs(30),
             connection_timeout: Duration::from_secs(60),
+            heartbeat_timeout: config.transport.heartbeat_timeout(),
+            compression: config.transport.compression(),
+            max_packet_size: config.transport.max_packet_size,
+            socket_options: config.transport.socket_options(),
         };
 
         // Create connection manager (not started yet)
@@ -245,14 +369,19 @@ impl Daemon {
             connection_config,
         )?));
 
-        // Create transport manager if Bluetooth is enabled
-        let transport_manager = if config.transport.enable_bluetooth {
-            info!("Bluetooth transport enabled - creating TransportManager");
+        // Create transport manager if Bluetooth or QUIC is enabled
+        let transport_manager = if config.transport.enable_bluetooth || config.transport.enable_quic
+        {
+            info!("Bluetooth or QUIC transport enabled - creating TransportManager");
 
             // Convert daemon TransportConfig to TransportManagerConfig
             let transport_config = TransportManagerConfig {
                 enable_tcp: config.transport.enable_tcp,
                 enable_bluetooth: config.transport.enable_bluetooth,
+                enable_quic: config.transport.enable_quic,
+                quic_listen_addr: format!("[::]:{}", config.transport.quic_port)
+                    .parse()
+                    .context("Invalid QUIC listen address")?,
                 preference: config.transport.preference.clone().into(),
                 tcp_timeout: config.transport.tcp_timeout(),
                 bluetooth_timeout: config.transport.bluetooth_timeout(),
@@ -260,7 +389,12 @@ impl Daemon {
                 bluetooth_device_filter: config.transport.bluetooth_device_filter.clone(),
             };
 
-            match TransportManager::new(connection_manager.clone(), transport_config) {
+            match TransportManager::new(
+                connection_manager.clone(),
+                &certificate,
+                device_manager.clone(),
+                transport_config,
+            ) {
                 Ok(tm) => {
                     info!("TransportManager created successfully");
                     Some(Arc::new(tm))
@@ -272,7 +406,7 @@ impl Daemon {
                 }
             }
         } else {
-            debug!("Bluetooth transport disabled - using ConnectionManager directly");
+            debug!("Bluetooth and QUIC transports disabled - using ConnectionManager directly");
             None
         };
 
@@ -318,6 +452,8 @@ impl Daemon {
             plugin_manager,
             device_manager,
             device_config_registry,
+            permission_policy,
+            audit_log,
             discovery_service: None,
             pairing_service: None,
             connection_manager,
@@ -326,6 +462,7 @@ impl Daemon {
             dbus_server: None,
             mpris_manager,
             pairing_notifications: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            runcommand_confirmations: Arc::new(RwLock::new(std::collections::HashMap::new())),
             pending_pairing_requests: Arc::new(RwLock::new(std::collections::HashMap::new())),
             metrics: None,
             dump_packets: false,
@@ -333,6 +470,15 @@ impl Daemon {
             packet_receiver,
             connection_attempts: Arc::new(RwLock::new(std::collections::HashMap::new())),
             notification_receiver: Arc::new(tokio::sync::Mutex::new(None)),
+            notification_snoozes,
+            usage_reporter,
+            metered_link,
+            disconnect_actions,
+            history,
+            battery_alerts: Arc::new(RwLock::new(LowBatteryAlerts::new())),
+            notification_replies: Arc::new(RwLock::new(NotificationReplies::new())),
+            share_event_receiver: Arc::new(tokio::sync::Mutex::new(None)),
+            mpris_event_receiver: Arc::new(tokio::sync::Mutex::new(None)),
         })
     }
 
@@ -370,8 +516,16 @@ impl Daemon {
         let mut manager = self.plugin_manager.write().await;
         let config = self.config.read().await;
 
+        if !cosmic_ext_connect_protocol::cpu_pool::configure_global(
+            config.plugins.cpu_pool_max_concurrent,
+        ) {
+            warn!("CPU pool already in use, concurrency limit not applied");
+        }
+
         info!("Registering plugin factories...");
 
+        let permission_policy = Arc::new(self.permission_policy.clone());
+
         // Register enabled plugin factories
         if config.plugins.enable_ping {
             info!("Registering ping plugin factory");
@@ -396,8 +550,30 @@ impl Daemon {
 
         if config.plugins.enable_share {
             info!("Registering share plugin factory");
+            let (share_tx, share_rx) = tokio::sync::mpsc::unbounded_channel();
+            *self.share_event_receiver.lock().await = Some(share_rx);
+
+            // Interrupted downloads are kept here so they resume after a restart
+            let transfer_recovery = Arc::new(RecoveryManager::new(&config.paths.data_dir));
+            transfer_recovery.init().await?;
+            if let Err(e) = transfer_recovery.cleanup_old_transfers().await {
+                warn!("Failed to clean up old transfers: {}", e);
+            }
+
             manager
-                .register_factory(Arc::new(SharePluginFactory))
+                .register_factory(Arc::new(
+                    SharePluginFactory::with_config(ShareConfig {
+                        device_subfolders: config.plugins.share_device_subfolders,
+                        device_subfolder_overrides: self
+                            .device_config_registry
+                            .read()
+                            .await
+                            .share_subfolder_overrides(),
+                        ..Default::default()
+                    })
+                    .with_events(share_tx)
+                    .with_recovery(transfer_recovery),
+                ))
                 .context("Failed to register share plugin factory")?;
         }
 
@@ -410,22 +586,31 @@ impl Daemon {
 
         if config.plugins.enable_mpris {
             info!("Registering MPRIS plugin factory");
+            let (mpris_tx, mpris_rx) = tokio::sync::mpsc::unbounded_channel();
+            *self.mpris_event_receiver.lock().await = Some(mpris_rx);
             manager
-                .register_factory(Arc::new(MprisPluginFactory))
+                .register_factory(Arc::new(MprisPluginFactory::new().with_events(mpris_tx)))
                 .context("Failed to register MPRIS plugin factory")?;
         }
 
         if config.plugins.enable_runcommand {
             info!("Registering RunCommand plugin factory");
             manager
-                .register_factory(Arc::new(RunCommandPluginFactory))
+                .register_factory(Arc::new(
+                    RunCommandPluginFactory::new().with_policy(permission_policy.clone()),
+                ))
                 .context("Failed to register RunCommand plugin factory")?;
         }
 
         if config.plugins.enable_remoteinput {
             info!("Registering Remote Input plugin factory");
             manager
-                .register_factory(Arc::new(RemoteInputPluginFactory))
+                .register_factory(Arc::new(
+                    RemoteInputPluginFactory::with_config(RemoteInputConfig {
+                        pointer_acceleration: config.plugins.remoteinput_pointer_acceleration,
+                    })
+                    .with_policy(permission_policy.clone()),
+                ))
                 .context("Failed to register Remote Input plugin factory")?;
         }
 
@@ -453,7 +638,16 @@ impl Daemon {
         if config.plugins.enable_presenter {
             info!("Registering Presenter plugin factory");
             manager
-                .register_factory(Arc::new(PresenterPluginFactory))
+                .register_factory(Arc::new(PresenterPluginFactory::with_config(
+                    PresenterConfig {
+                        next_key: config.plugins.presenter_next_key,
+                        previous_key: config.plugins.presenter_previous_key,
+                        pointer: LaserPointerConfig {
+                            output: config.plugins.presenter_output.clone(),
+                            ..Default::default()
+                        },
+                    },
+                )))
                 .context("Failed to register Presenter plugin factory")?;
         }
 
@@ -495,7 +689,9 @@ impl Daemon {
         if config.plugins.enable_power {
             info!("Registering Power plugin factory");
             manager
-                .register_factory(Arc::new(PowerPluginFactory))
+                .register_factory(Arc::new(
+                    PowerPluginFactory::new().with_policy(permission_policy.clone()),
+                ))
                 .context("Failed to register Power plugin factory")?;
         }
 
@@ -530,7 +726,10 @@ impl Daemon {
         if config.plugins.enable_filesync {
             info!("Registering FileSync plugin factory");
             manager
-                .register_factory(Arc::new(FileSyncPluginFactory))
+                .register_factory(Arc::new(
+                    FileSyncPluginFactory::new()
+                        .with_max_pending_conflicts(config.plugins.filesync_max_pending_conflicts),
+                ))
                 .context("Failed to register FileSync plugin factory")?;
         }
 
@@ -553,7 +752,16 @@ impl Daemon {
         if config.plugins.enable_networkshare {
             info!("Registering NetworkShare plugin factory");
             manager
-                .register_factory(Arc::new(NetworkSharePluginFactory))
+                .register_factory(Arc::new(NetworkSharePluginFactory::with_config(
+                    NetworkShareConfig {
+                        freshness_window_secs: config.plugins.networkshare_freshness_secs,
+                        device_freshness_overrides: self
+                            .device_config_registry
+                            .read()
+                            .await
+                            .networkshare_freshness_overrides(),
+                    },
+                )))
                 .context("Failed to register NetworkShare plugin factory")?;
         }
 
@@ -591,6 +799,22 @@ impl Daemon {
             manager.factory_count()
         );
 
+        let (capability_overrides, disabled_plugins) = {
+            let registry = self.device_config_registry.read().await;
+            (registry.capability_overrides(), registry.disabled_plugins())
+        };
+        for (device_id, overrides) in capability_overrides {
+            info!(
+                "Capability overrides for device {}: force_enable {:?}, force_disable {:?}",
+                device_id, overrides.force_enable, overrides.force_disable
+            );
+            manager.set_capability_overrides(&device_id, overrides);
+        }
+        for (device_id, plugins) in disabled_plugins {
+            info!("Plugins disabled for device {}: {:?}", device_id, plugins);
+            manager.set_disabled_plugins(&device_id, plugins);
+        }
+
         Ok(())
     }
 
@@ -600,37 +824,42 @@ impl Daemon {
 
         let config = self.config.read().await;
 
-        // Get capabilities from plugin manager
+        // Advertise the capabilities of all registered plugins
         let manager = self.plugin_manager.read().await;
-        let incoming = manager.get_all_incoming_capabilities();
-        let outgoing = manager.get_all_outgoing_capabilities();
+        self.device_info = IdentityPacket::local_info(&self.device_info, &manager);
         drop(manager);
 
-        // Update self.device_info with capabilities
-        self.device_info.incoming_capabilities = incoming;
-        self.device_info.outgoing_capabilities = outgoing;
-
         // Update connection manager with new capabilities
         self.connection_manager
             .write()
             .await
             .update_device_info(self.device_info.clone());
 
-        let device_info = self.device_info.clone();
-        let discovery_config = DiscoveryConfig {
-            broadcast_interval: Duration::from_secs(config.network.discovery_interval),
-            device_timeout: Duration::from_secs(config.network.device_timeout),
-            enable_timeout_check: true,
-            additional_broadcast_addrs: default_additional_broadcast_addrs(),
+        // UDP broadcasts, plus mDNS for networks that filter broadcasts. A
+        // device seen by both is discovered and timed out only once.
+        let discovery_config = UnifiedDiscoveryConfig {
+            enable_tcp: true,
+            enable_bluetooth: false,
+            #[cfg(feature = "mdns")]
+            enable_mdns: true,
+            tcp_config: DiscoveryConfig {
+                broadcast_interval: Duration::from_secs(config.network.discovery_interval),
+                device_timeout: Duration::from_secs(config.network.device_timeout),
+                enable_timeout_check: true,
+                additional_broadcast_addrs: default_additional_broadcast_addrs(),
+            },
+            bluetooth_config: Default::default(),
         };
         drop(config);
 
         // Create discovery service
-        let mut discovery_service = DiscoveryService::new(device_info, discovery_config)
-            .context("Failed to create discovery service")?;
+        let mut discovery_service =
+            UnifiedDiscoveryService::new(self.device_info.clone(), discovery_config)
+                .await
+                .context("Failed to create discovery service")?;
 
         // Subscribe to discovery events
-        let mut event_rx = discovery_service.subscribe().await;
+        let event_rx = discovery_service.subscribe().await;
 
         // Start discovery service
         discovery_service
@@ -640,17 +869,26 @@ impl Daemon {
 
         info!(
             "Discovery service started on port {}",
-            discovery_service.local_port()?
+            discovery_service.tcp_port().await?
         );
 
         // Store discovery service
         self.discovery_service = Some(discovery_service);
+        self.spawn_discovery_event_handler(event_rx);
+
+        Ok(())
+    }
 
-        // Spawn task to handle discovery events
+    /// Spawn a task handling the merged discovery events
+    fn spawn_discovery_event_handler(
+        &self,
+        mut event_rx: tokio::sync::mpsc::UnboundedReceiver<DiscoveryEvent>,
+    ) {
         let device_manager = self.device_manager.clone();
         let dbus_server = self.dbus_server.clone();
         let error_handler = self.error_handler.clone();
         let connection_manager = self.connection_manager.clone();
+        let transport_manager = self.transport_manager.clone();
         let connection_attempts = self.connection_attempts.clone();
         tokio::spawn(async move {
             while let Some(event) = event_rx.recv().await {
@@ -660,6 +898,7 @@ impl Daemon {
                     &dbus_server,
                     &error_handler,
                     &connection_manager,
+                    &transport_manager,
                     &connection_attempts,
                 )
                 .await
@@ -669,8 +908,6 @@ impl Daemon {
             }
             info!("Discovery event handler stopped");
         });
-
-        Ok(())
     }
 
     /// Start pairing service
@@ -682,7 +919,7 @@ impl Daemon {
         // Create pairing service with certificate directory from config
         let pairing_config = PairingConfig {
             cert_dir: config.paths.cert_dir.clone(),
-            timeout: Duration::from_secs(30),
+            timeout: Duration::from_secs(config.network.pairing_timeout),
         };
 
         let pairing_service =
@@ -722,8 +959,13 @@ impl Daemon {
         let plugin_manager = self.plugin_manager.clone();
         let packet_sender = self.packet_sender.clone();
         let tls_config = self.tls_config.clone();
+        let history = self.history.clone();
+        let device_config_registry = self.device_config_registry.clone();
         tokio::spawn(async move {
             while let Some(event) = event_rx.recv().await {
+                if let Some((device_id, summary)) = history::pairing_event(&event) {
+                    history.record(device_id, history::EVENT_PAIRING, summary);
+                }
                 if let Err(e) = Self::handle_pairing_event(
                     event,
                     &device_manager,
@@ -735,6 +977,7 @@ impl Daemon {
                     &plugin_manager,
                     &packet_sender,
                     &tls_config,
+                    &device_config_registry,
                 )
                 .await
                 {
@@ -762,6 +1005,7 @@ impl Daemon {
         plugin_manager: &Arc<RwLock<PluginManager>>,
         packet_sender: &Sender<(String, Packet)>,
         tls_config: &Arc<cosmic_ext_connect_protocol::TlsConfig>,
+        device_config_registry: &Arc<RwLock<device_config::DeviceConfigRegistry>>,
     ) -> Result<()> {
         match event {
             PairingEvent::RequestSent {
@@ -793,7 +1037,10 @@ impl Daemon {
 
                 // Emit DBus signal for pairing request
                 if let Some(dbus) = dbus_server {
-                    if let Err(e) = dbus.emit_pairing_request(&device_id).await {
+                    if let Err(e) = dbus
+                        .emit_pairing_request(&device_id, &their_fingerprint)
+                        .await
+                    {
                         warn!("Failed to emit PairingRequest signal: {}", e);
                     }
                 }
@@ -846,11 +1093,27 @@ impl Daemon {
                 // Initialize plugins for newly paired device
                 // This handles the case where device connected first, then paired later
                 {
-                    let dev_manager = device_manager.read().await;
-                    if let Some(device) = dev_manager.get_device(&device_id) {
+                    // Not held while taking the config registry lock below
+                    let device = device_manager.read().await.get_device(&device_id).cloned();
+                    if let Some(device) = device {
+                        // First pairing gets the plugin defaults for the device type
+                        let disabled_plugins = {
+                            let mut registry = device_config_registry.write().await;
+                            let (config, created) = registry
+                                .get_or_create_for_device_type(&device_id, device.info.device_type);
+                            let disabled_plugins = config.disabled_plugins();
+                            if created {
+                                if let Err(e) = registry.save() {
+                                    error!("Failed to save device config: {}", e);
+                                }
+                            }
+                            disabled_plugins
+                        };
+
                         let mut plug_manager = plugin_manager.write().await;
+                        plug_manager.set_disabled_plugins(&device_id, disabled_plugins);
                         if let Err(e) = plug_manager
-                            .init_device_plugins(&device_id, device, packet_sender.clone())
+                            .init_device_plugins(&device_id, &device, packet_sender.clone())
                             .await
                         {
                             error!(
@@ -861,8 +1124,8 @@ impl Daemon {
                             info!("Initialized plugins for device {} after pairing", device_id);
 
                             // Set TLS config on SharePlugin for secure file transfers
-                            if let Some(plugin) =
-                                plug_manager.get_device_plugin_mut(&device_id, "share")
+                            if let Some(mut plugin) =
+                                plug_manager.get_device_plugin(&device_id, "share").await
                             {
                                 use cosmic_ext_connect_protocol::plugins::share::SharePlugin;
                                 if let Some(share_plugin) =
@@ -875,6 +1138,18 @@ impl Daemon {
                                     );
                                 }
                             }
+
+                            // Album art for remote players also arrives over TLS
+                            if let Some(mut plugin) =
+                                plug_manager.get_device_plugin(&device_id, "mpris").await
+                            {
+                                use cosmic_ext_connect_protocol::plugins::mpris::MprisPlugin;
+                                if let Some(mpris_plugin) =
+                                    plugin.as_any_mut().downcast_mut::<MprisPlugin>()
+                                {
+                                    mpris_plugin.set_tls_config(tls_config.clone());
+                                }
+                            }
                         }
                     } else {
                         warn!("Device {} not found in manager after pairing", device_id);
@@ -923,14 +1198,27 @@ impl Daemon {
             }
             PairingEvent::DeviceUnpaired { device_id } => {
                 info!("Device unpaired: {}", device_id);
-                let mut manager = device_manager.write().await;
-                if let Err(e) = manager.update_pairing_status(&device_id, PairingStatus::Unpaired) {
+                {
+                    let mut manager = device_manager.write().await;
+                    if let Err(e) = manager.unpair(&device_id) {
+                        warn!("Failed to revoke trust in device {}: {}", device_id, e);
+                    } else if let Err(e) = manager.save_registry() {
+                        warn!("Failed to save device registry: {}", e);
+                    }
+                }
+
+                // The connection is closed by the pairing service; stop the
+                // plugins right away in case the device was not connected
+                if let Err(e) = plugin_manager
+                    .write()
+                    .await
+                    .cleanup_device_plugins(&device_id)
+                    .await
+                {
                     warn!(
-                        "Failed to update device {} pairing status: {}",
+                        "Failed to clean up plugins for unpaired device {}: {}",
                         device_id, e
                     );
-                } else if let Err(e) = manager.save_registry() {
-                    warn!("Failed to save device registry: {}", e);
                 }
 
                 // Remove desktop icon for unpaired device
@@ -945,6 +1233,17 @@ impl Daemon {
             }
             PairingEvent::PairingTimeout { device_id } => {
                 warn!("Pairing request timed out for device {}", device_id);
+                Self::clear_pending_pairing_request(pending_pairing_requests, &device_id).await;
+
+                if let Some(dbus) = dbus_server {
+                    if let Err(e) = dbus
+                        .emit_pairing_status_changed(&device_id, "timed_out")
+                        .await
+                    {
+                        warn!("Failed to emit PairingStatusChanged signal: {}", e);
+                    }
+                }
+
                 let error = cosmic_ext_connect_protocol::ProtocolError::Timeout(
                     "Pairing request timed out".to_string(),
                 );
@@ -982,6 +1281,21 @@ impl Daemon {
     async fn start_connections(&mut self) -> Result<()> {
         info!("Starting connection manager...");
 
+        // Apply per-device heartbeat overrides before any device connects
+        let heartbeat_overrides = self
+            .device_config_registry
+            .read()
+            .await
+            .heartbeat_interval_overrides();
+        {
+            let conn_mgr = self.connection_manager.read().await;
+            for (device_id, secs) in heartbeat_overrides {
+                conn_mgr
+                    .set_heartbeat_override(&device_id, Some(Duration::from_secs(secs)))
+                    .await;
+            }
+        }
+
         // If TransportManager is available, use it; otherwise use ConnectionManager directly
         if let Some(transport_mgr) = &self.transport_manager {
             info!("Using TransportManager (Bluetooth enabled)");
@@ -1011,6 +1325,13 @@ impl Daemon {
             let config = self.config.clone();
             let error_handler = Some(self.error_handler.clone());
             let tls_config = self.tls_config.clone();
+            let notification_snoozes = self.notification_snoozes.clone();
+            let usage_reporter = self.usage_reporter.clone();
+            let disconnect_actions = self.disconnect_actions.clone();
+            let history = self.history.clone();
+            let battery_alerts = self.battery_alerts.clone();
+            let notification_replies = self.notification_replies.clone();
+            let failover_manager = self.transport_manager.clone();
             tokio::spawn(async move {
                 while let Some(event) = event_rx.recv().await {
                     // Convert TransportManagerEvent to ConnectionEvent
@@ -1035,6 +1356,38 @@ impl Daemon {
                                 "Device {} disconnected from {:?} (reason: {:?})",
                                 device_id, transport_type, reason
                             );
+
+                            // Bring a paired device back over whichever transport
+                            // still works, e.g. Bluetooth when WiFi dropped
+                            let addresses = device_manager
+                                .read()
+                                .await
+                                .get_device(&device_id)
+                                .filter(|device| device.is_paired())
+                                .map(|device| device.transport_addresses())
+                                .unwrap_or_default();
+                            if let (Some(transport_mgr), false) =
+                                (&failover_manager, addresses.is_empty())
+                            {
+                                let device_id = device_id.clone();
+                                let connection_mgr = connection_mgr.clone();
+                                let transport_mgr = transport_mgr.clone();
+                                tokio::spawn(async move {
+                                    tokio::time::sleep(TRANSPORT_FAILOVER_DELAY).await;
+                                    if transport_mgr.has_connection(&device_id).await {
+                                        return;
+                                    }
+                                    info!("Reconnecting {} after its transport dropped", device_id);
+                                    Self::connect_device(
+                                        &device_id,
+                                        &addresses,
+                                        &connection_mgr,
+                                        &Some(transport_mgr),
+                                    )
+                                    .await;
+                                });
+                            }
+
                             ConnectionEvent::Disconnected {
                                 device_id,
                                 reason,
@@ -1085,6 +1438,12 @@ impl Daemon {
                         &config,
                         &error_handler,
                         &tls_config,
+                        &notification_snoozes,
+                        &usage_reporter,
+                        &disconnect_actions,
+                        &history,
+                        &battery_alerts,
+                        &notification_replies,
                     )
                     .await
                     {
@@ -1127,6 +1486,12 @@ impl Daemon {
             let config = self.config.clone();
             let error_handler = Some(self.error_handler.clone());
             let tls_config = self.tls_config.clone();
+            let notification_snoozes = self.notification_snoozes.clone();
+            let usage_reporter = self.usage_reporter.clone();
+            let disconnect_actions = self.disconnect_actions.clone();
+            let history = self.history.clone();
+            let battery_alerts = self.battery_alerts.clone();
+            let notification_replies = self.notification_replies.clone();
             tokio::spawn(async move {
                 while let Some(event) = event_rx.recv().await {
                     if let Err(e) = Self::handle_connection_event(
@@ -1144,6 +1509,12 @@ impl Daemon {
                         &config,
                         &error_handler,
                         &tls_config,
+                        &notification_snoozes,
+                        &usage_reporter,
+                        &disconnect_actions,
+                        &history,
+                        &battery_alerts,
+                        &notification_replies,
                     )
                     .await
                     {
@@ -1168,11 +1539,18 @@ impl Daemon {
             self.plugin_manager.clone(),
             self.connection_manager.clone(),
             self.device_config_registry.clone(),
+            self.permission_policy.clone(),
+            self.audit_log.clone(),
             self.pairing_service.clone(),
             self.mpris_manager.clone(),
             self.pending_pairing_requests.clone(),
             self.metrics.clone(),
             self.config.clone(),
+            self.notification_snoozes.clone(),
+            self.usage_reporter.clone(),
+            self.metered_link.clone(),
+            self.history.clone(),
+            self.packet_sender.clone(),
         )
         .await
         .context("Failed to start DBus server")?;
@@ -1184,6 +1562,161 @@ impl Daemon {
         Ok(())
     }
 
+    /// Forward incoming file progress from the share plugin as DBus transfer signals
+    async fn start_share_event_forwarder(&self) -> Result<()> {
+        let Some(mut share_rx) = self.share_event_receiver.lock().await.take() else {
+            return Ok(());
+        };
+        let Some(dbus_server) = self.dbus_server.clone() else {
+            return Ok(());
+        };
+        let cosmic_notifier = self.cosmic_notifier.clone();
+        let device_manager = self.device_manager.clone();
+
+        tokio::spawn(async move {
+            while let Some(event) = share_rx.recv().await {
+                let result = match &event {
+                    ShareEvent::Started {
+                        transfer_id,
+                        device_id,
+                        filename,
+                        total_bytes,
+                        cancel,
+                    } => {
+                        let transfer = dbus::Transfer::new(
+                            transfer_id,
+                            device_id,
+                            filename,
+                            "receiving",
+                            *total_bytes,
+                        );
+                        dbus_server.track_transfer(transfer, cancel.flag()).await
+                    }
+                    ShareEvent::Progress {
+                        device_id,
+                        progress,
+                    } => {
+                        let legacy = dbus_server
+                            .emit_transfer_progress(
+                                &progress.transfer_id,
+                                device_id,
+                                &progress.filename,
+                                progress.bytes_transferred,
+                                progress.total_bytes,
+                                "receiving",
+                            )
+                            .await;
+                        let updated = dbus_server
+                            .update_transfer(
+                                &progress.transfer_id,
+                                progress.bytes_transferred,
+                                progress.total_bytes,
+                            )
+                            .await;
+                        legacy.and(updated)
+                    }
+                    ShareEvent::Completed {
+                        transfer_id,
+                        device_id,
+                        filename,
+                        path,
+                    } => {
+                        // Announce the file where the share plugin actually saved it
+                        if let Some(notifier) = &cosmic_notifier {
+                            let device_name = device_manager
+                                .read()
+                                .await
+                                .get_device(device_id)
+                                .map(|d| d.name().to_string())
+                                .unwrap_or_else(|| device_id.clone());
+                            if let Err(e) = notifier
+                                .notify_file_received(
+                                    &device_name,
+                                    filename,
+                                    &path.to_string_lossy(),
+                                )
+                                .await
+                            {
+                                warn!("Failed to send file received notification: {}", e);
+                            }
+                        }
+
+                        let legacy = dbus_server
+                            .emit_transfer_complete(transfer_id, device_id, filename, true, "")
+                            .await;
+                        let updated = dbus_server
+                            .finish_transfer(transfer_id, dbus::TRANSFER_COMPLETED)
+                            .await;
+                        legacy.and(updated)
+                    }
+                    ShareEvent::Failed {
+                        transfer_id,
+                        device_id,
+                        filename,
+                        reason,
+                        error,
+                    } => {
+                        let state = if *reason == TRANSFER_FAILED_CANCELLED {
+                            dbus::TRANSFER_CANCELLED
+                        } else {
+                            dbus::TRANSFER_FAILED
+                        };
+                        let legacy = dbus_server
+                            .emit_transfer_complete(transfer_id, device_id, filename, false, error)
+                            .await;
+                        let updated = dbus_server.finish_transfer(transfer_id, state).await;
+                        legacy.and(updated)
+                    }
+                    // Opened or copied when the share packet is handled
+                    ShareEvent::TextReceived { .. } | ShareEvent::UrlReceived { .. } => Ok(()),
+                };
+                if let Err(e) = result {
+                    warn!("Failed to emit transfer signal: {}", e);
+                }
+            }
+            debug!("Share event forwarder stopped");
+        });
+
+        Ok(())
+    }
+
+    /// Forward remote media player changes from the MPRIS plugin as DBus signals
+    async fn start_mpris_event_forwarder(&self) -> Result<()> {
+        let Some(mut mpris_rx) = self.mpris_event_receiver.lock().await.take() else {
+            return Ok(());
+        };
+        let Some(dbus_server) = self.dbus_server.clone() else {
+            return Ok(());
+        };
+
+        tokio::spawn(async move {
+            while let Some(event) = mpris_rx.recv().await {
+                let result = match event {
+                    MprisEvent::PlayerUpdated {
+                        device_id,
+                        state,
+                        album_art,
+                    } => {
+                        let player =
+                            dbus::RemotePlayer::from_state(&device_id, &state, album_art.as_deref());
+                        dbus_server.emit_remote_player_updated(player).await
+                    }
+                    MprisEvent::PlayerRemoved { device_id, player } => {
+                        dbus_server
+                            .emit_remote_player_removed(&device_id, &player)
+                            .await
+                    }
+                };
+                if let Err(e) = result {
+                    warn!("Failed to emit remote player signal: {}", e);
+                }
+            }
+            debug!("MPRIS event forwarder stopped");
+        });
+
+        Ok(())
+    }
+
     /// Start MPRIS player monitoring
     async fn start_mpris_monitoring(&self) -> Result<()> {
         let Some(mpris_manager) = &self.mpris_manager else {
@@ -1225,10 +1758,14 @@ impl Daemon {
         let device_manager = self.device_manager.clone();
         let plugin_manager = self.plugin_manager.clone();
         let connection_manager = self.connection_manager.clone();
+        let sync_primary = config.plugins.clipboard_sync_primary;
 
         // Spawn background task to monitor clipboard
         tokio::spawn(async move {
-            use arboard::Clipboard;
+            use arboard::{Clipboard, GetExtLinux, LinuxClipboardKind};
+            use cosmic_ext_connect_protocol::plugins::clipboard::{
+                ClipboardSelection, LocalClipboardTracker,
+            };
             use std::time::Duration;
 
             // Initialize clipboard
@@ -1240,7 +1777,7 @@ impl Daemon {
                 }
             };
 
-            let mut last_content = String::new();
+            let mut tracker = LocalClipboardTracker::new(sync_primary);
             let poll_interval = Duration::from_millis(500);
 
             info!(
@@ -1251,17 +1788,25 @@ impl Daemon {
             loop {
                 tokio::time::sleep(poll_interval).await;
 
-                // Read current clipboard content
-                let current_content = match clipboard.get_text() {
-                    Ok(text) => text,
-                    Err(_) => continue, // Clipboard might be empty or contain non-text
-                };
+                for selection in tracker.selections() {
+                    // Read current selection content
+                    let text = match selection {
+                        ClipboardSelection::Clipboard => clipboard.get_text(),
+                        ClipboardSelection::Primary => clipboard
+                            .get()
+                            .clipboard(LinuxClipboardKind::Primary)
+                            .text(),
+                    };
+                    let Ok(text) = text else {
+                        continue; // Clipboard might be empty or contain non-text
+                    };
 
-                // Check if clipboard changed
-                if current_content != last_content && !current_content.is_empty() {
-                    debug!("Clipboard changed: {} chars", current_content.len());
+                    // Check if the selection changed
+                    let Some(current_content) = tracker.observe(selection, &text) else {
+                        continue;
+                    };
+                    debug!("{:?} changed: {} chars", selection, current_content.len());
 
-                    // Update local clipboard plugin state for all connected devices
                     let dev_manager = device_manager.read().await;
                     let connected_devices: Vec<String> = dev_manager
                         .devices()
@@ -1270,46 +1815,42 @@ impl Daemon {
                         .collect();
                     drop(dev_manager);
 
-                    if !connected_devices.is_empty() {
-                        let plug_manager = plugin_manager.read().await;
+                    let plug_manager = plugin_manager.read().await;
+                    let mut clipboard_plugins = Vec::new();
+                    for device_id in &connected_devices {
+                        if let Some(plugin) = plug_manager
+                            .get_device_plugin_as::<ClipboardPlugin>(device_id, "clipboard")
+                            .await
+                        {
+                            clipboard_plugins.push((device_id, plugin));
+                        }
+                    }
 
-                        for device_id in &connected_devices {
-                            if let Some(plugin) =
-                                plug_manager.get_device_plugin(device_id, "clipboard")
-                            {
-                                // Downcast to ClipboardPlugin
-                                use cosmic_ext_connect_protocol::plugins::clipboard::ClipboardPlugin;
-                                if let Some(clipboard_plugin) =
-                                    plugin.as_any().downcast_ref::<ClipboardPlugin>()
-                                {
-                                    // Create clipboard packet
-                                    let packet = clipboard_plugin
-                                        .create_clipboard_packet(current_content.clone())
-                                        .await;
+                    for (device_id, clipboard_plugin) in clipboard_plugins {
+                        // Content just applied from this device (or already sent
+                        // to it) isn't sent again, so it can't bounce back
+                        if clipboard_plugin.holds(&current_content).await {
+                            debug!("Clipboard content already synced with {}", device_id);
+                            continue;
+                        }
 
-                                    // Send packet via connection manager
-                                    let conn_manager = connection_manager.read().await;
-                                    if let Err(e) =
-                                        conn_manager.send_packet(device_id, &packet).await
-                                    {
-                                        warn!(
-                                            "Failed to send clipboard update to {}: {}",
-                                            device_id, e
-                                        );
-                                    } else {
-                                        debug!(
-                                            "Sent clipboard update to {} ({} chars)",
-                                            device_id,
-                                            current_content.len()
-                                        );
-                                    }
-                                }
-                            }
+                        // Create clipboard packet
+                        let packet = clipboard_plugin
+                            .create_clipboard_packet(current_content.clone())
+                            .await;
+
+                        // Send packet via connection manager
+                        let conn_manager = connection_manager.read().await;
+                        if let Err(e) = conn_manager.send_packet(device_id, &packet).await {
+                            warn!("Failed to send clipboard update to {}: {}", device_id, e);
+                        } else {
+                            debug!(
+                                "Sent clipboard update to {} ({} chars)",
+                                device_id,
+                                current_content.len()
+                            );
                         }
-                        drop(plug_manager);
                     }
-
-                    last_content = current_content;
                 }
             }
         });
@@ -1323,21 +1864,60 @@ impl Daemon {
             let notifier_clone = notifier.clone();
             let pairing_service = self.pairing_service.clone();
             let pairing_notifications = self.pairing_notifications.clone();
+            let runcommand_confirmations = self.runcommand_confirmations.clone();
+            let plugin_manager = self.plugin_manager.clone();
             let _device_manager = self.device_manager.clone();
 
             tokio::spawn(async move {
+                use cosmic_notifications::NotificationSignal;
                 use futures::StreamExt;
 
                 match notifier_clone.subscribe_actions().await {
                     Ok(mut action_stream) => {
                         info!("Notification action listener started");
 
-                        while let Some((notification_id, action_key)) = action_stream.next().await {
+                        while let Some(signal) = action_stream.next().await {
+                            let (notification_id, action_key) = match signal {
+                                NotificationSignal::ActionInvoked(id, action_key) => {
+                                    (id, action_key)
+                                }
+                                NotificationSignal::Closed(id) => {
+                                    // A confirmation closed without an answer is declined
+                                    let confirmation =
+                                        runcommand_confirmations.write().await.remove(&id);
+                                    if let Some((device_id, key)) = confirmation {
+                                        Self::handle_runcommand_confirmation(
+                                            &plugin_manager,
+                                            &device_id,
+                                            &key,
+                                            "dismissed",
+                                        )
+                                        .await;
+                                    }
+                                    continue;
+                                }
+                            };
                             debug!(
                                 "Received notification action: id={}, action={}",
                                 notification_id, action_key
                             );
 
+                            // Check if this is a run command confirmation
+                            let confirmation = runcommand_confirmations
+                                .write()
+                                .await
+                                .remove(&notification_id);
+                            if let Some((device_id, key)) = confirmation {
+                                Self::handle_runcommand_confirmation(
+                                    &plugin_manager,
+                                    &device_id,
+                                    &key,
+                                    &action_key,
+                                )
+                                .await;
+                                continue;
+                            }
+
                             // Check if this is a pairing notification
                             let device_id = {
                                 let notifications = pairing_notifications.read().await;
@@ -1418,6 +1998,129 @@ impl Daemon {
         Ok(())
     }
 
+    /// Run or drop a command after the user answered its confirmation notification
+    async fn handle_runcommand_confirmation(
+        plugin_manager: &Arc<RwLock<PluginManager>>,
+        device_id: &str,
+        key: &str,
+        action_key: &str,
+    ) {
+        let manager = plugin_manager.read().await;
+        let Some(plugin) = manager
+            .get_device_plugin_as::<RunCommandPlugin>(device_id, "runcommand")
+            .await
+        else {
+            warn!("RunCommand plugin not found for device {}", device_id);
+            return;
+        };
+
+        if action_key == "run" {
+            info!("User confirmed command '{}' from {}", key, device_id);
+            if let Err(e) = plugin.confirm_command(key).await {
+                error!("Failed to run confirmed command '{}': {}", key, e);
+            }
+        } else {
+            info!("User declined command '{}' from {}", key, device_id);
+            plugin.reject_command(key).await;
+        }
+    }
+
+    /// Fill in a call's contact name from the contacts synced from the device
+    async fn resolve_caller_name(
+        plugin_manager: &Arc<RwLock<PluginManager>>,
+        device_id: &str,
+        packet: &mut Packet,
+    ) {
+        if packet
+            .body
+            .get("contactName")
+            .is_some_and(|v| v.is_string())
+        {
+            return;
+        }
+        let Some(number) = packet
+            .body
+            .get("phoneNumber")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+        else {
+            return;
+        };
+        let store = match plugin_manager
+            .read()
+            .await
+            .get_device_plugin_as::<ContactsPlugin>(device_id, "contacts")
+            .await
+        {
+            Some(contacts) => contacts.store(),
+            None => return,
+        };
+
+        if let Some(name) = store.name_for_number(&number).await {
+            debug!("Resolved caller {} to contact {}", number, name);
+            packet.body["contactName"] = serde_json::Value::String(name);
+        }
+    }
+
+    /// Handle a remote trigger of the "Snooze app" action on a forwarded notification
+    async fn handle_snooze_action(
+        packet: &Packet,
+        notification_snoozes: &Arc<RwLock<NotificationSnoozes>>,
+    ) {
+        let action = packet.body.get("action").and_then(|v| v.as_str());
+        if !matches!(
+            action,
+            Some(notification_snooze::SNOOZE_ACTION_ID | notification_snooze::SNOOZE_ACTION_LABEL)
+        ) {
+            return;
+        }
+
+        let Some(app_name) = packet
+            .body
+            .get("key")
+            .and_then(|v| v.as_str())
+            .and_then(notification_snooze::app_name_from_notification_key)
+        else {
+            debug!("Snooze action without a desktop notification key, ignoring");
+            return;
+        };
+
+        if let Err(e) = notification_snoozes
+            .write()
+            .await
+            .snooze_app(app_name, notification_snooze::DEFAULT_SNOOZE_DURATION)
+        {
+            warn!("Failed to snooze notifications from {}: {}", app_name, e);
+        }
+    }
+
+    /// Deliver a reply from the remote device to the notification it answers
+    async fn handle_notification_reply(
+        packet: &Packet,
+        notification_replies: &Arc<RwLock<NotificationReplies>>,
+        dbus_server: &Option<Arc<DbusServer>>,
+    ) {
+        let Some((target, message)) = notification_replies.read().await.resolve(packet) else {
+            debug!("Reply for an unknown or expired notification, ignoring");
+            return;
+        };
+
+        let Some(dbus) = dbus_server else {
+            warn!("DBus server not running, cannot deliver notification reply");
+            return;
+        };
+
+        if let Err(e) = notification_reply::deliver_reply(dbus.connection(), &target, message).await
+        {
+            warn!(
+                "Failed to deliver reply to notification {}: {}",
+                target.notification_id, e
+            );
+        } else {
+            info!("Delivered reply to notification {}", target.notification_id);
+        }
+    }
+
     /// Start notification listener
     async fn start_notification_listener(&mut self) -> Result<()> {
         let config = self.config.read().await;
@@ -1440,7 +2143,12 @@ impl Daemon {
             include_transient: config.notification_listener.include_transient,
             include_low_urgency: config.notification_listener.include_low_urgency,
             max_body_length: config.notification_listener.max_body_length,
+            app_rules: config.notification_listener.app_rules.clone(),
         };
+        let rate_limiter = Arc::new(tokio::sync::Mutex::new(NotificationRateLimiter::new(
+            config.notification_listener.rate_limit_window(),
+            config.notification_listener.max_forwarded_per_window,
+        )));
 
         match NotificationListener::new(listener_config, tx).await {
             Ok(listener) => {
@@ -1465,6 +2173,48 @@ impl Daemon {
                 let plugin_manager = self.plugin_manager.clone();
                 let connection_manager = self.connection_manager.clone();
                 let notification_receiver_mutex = self.notification_receiver.clone();
+                let notification_snoozes = self.notification_snoozes.clone();
+                let notification_replies = self.notification_replies.clone();
+                let device_config_registry = self.device_config_registry.clone();
+                let config = self.config.clone();
+
+                // Send one summary per device once its suppression window has passed
+                let summary_limiter = rate_limiter.clone();
+                let summary_connection_manager = connection_manager.clone();
+                tokio::spawn(async move {
+                    use cosmic_ext_connect_protocol::plugins::notification::NotificationPlugin;
+
+                    let mut interval = tokio::time::interval(Duration::from_secs(1));
+                    loop {
+                        interval.tick().await;
+                        let summaries = summary_limiter.lock().await.take_due_summaries();
+                        for (device_id, count) in summaries {
+                            let timestamp = std::time::SystemTime::now()
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .unwrap_or_default()
+                                .as_millis() as i64;
+                            let packet = NotificationPlugin::create_desktop_notification_packet(
+                                notification_rate_limit::SUMMARY_APP_NAME,
+                                "Notifications suppressed",
+                                &notification_rate_limit::summary_text(count),
+                                timestamp,
+                                None,
+                                &[],
+                                None,
+                                None,
+                                None,
+                            );
+
+                            let conn_manager = summary_connection_manager.read().await;
+                            if let Err(e) = conn_manager.send_packet(&device_id, &packet).await {
+                                warn!(
+                                    "Failed to send suppression summary to device {}: {}",
+                                    device_id, e
+                                );
+                            }
+                        }
+                    }
+                });
 
                 tokio::spawn(async move {
                     let mut receiver_guard = notification_receiver_mutex.lock().await;
@@ -1475,7 +2225,25 @@ impl Daemon {
 
                     info!("Notification forwarding task started");
 
-                    while let Some(notification) = receiver.recv().await {
+                    let mut active_notifications = ActiveNotifications::new();
+                    while let Some(event) = receiver.recv().await {
+                        let notification = match event {
+                            NotificationEvent::Posted(notification) => notification,
+                            NotificationEvent::Closed(notification_id) => {
+                                if let Some(remote_id) = active_notifications.close(notification_id)
+                                {
+                                    Self::dismiss_forwarded_notification(
+                                        &remote_id,
+                                        &device_manager,
+                                        &plugin_manager,
+                                        &connection_manager,
+                                    )
+                                    .await;
+                                }
+                                continue;
+                            }
+                        };
+
                         debug!(
                             "Captured notification: app={}, summary={}, body={}",
                             notification.app_name,
@@ -1483,6 +2251,18 @@ impl Daemon {
                             notification.body.chars().take(50).collect::<String>()
                         );
 
+                        if notification_snoozes
+                            .read()
+                            .await
+                            .is_snoozed(&notification.app_name)
+                        {
+                            trace!(
+                                "Skipping notification from snoozed app: {}",
+                                notification.app_name
+                            );
+                            continue;
+                        }
+
                         // Get list of paired and connected devices
                         let devices = {
                             let dev_manager = device_manager.read().await;
@@ -1498,31 +2278,75 @@ impl Daemon {
                             continue;
                         }
 
-                        // Actions are already in the correct format
-                        let actions = &notification.actions;
-
-                        // Process notification image if present
-                        let image_bytes = Self::process_notification_image(&notification).await;
+                        // Actions are already in the correct format; append our snooze action.
+                        // Inline reply is offered as a reply field rather than a button.
+                        let mut actions: Vec<_> = notification
+                            .actions
+                            .iter()
+                            .filter(|(id, _)| id != notification_listener::REPLY_ACTION_ID)
+                            .cloned()
+                            .collect();
+                        actions.push((
+                            notification_snooze::SNOOZE_ACTION_ID.to_string(),
+                            notification_snooze::SNOOZE_ACTION_LABEL.to_string(),
+                        ));
 
                         // Create notification packet using NotificationPlugin
                         use cosmic_ext_connect_protocol::plugins::notification::{
                             NotificationPlugin, NotificationUrgency,
                         };
 
+                        // The icon travels as a PNG payload, identified by its hash
+                        let icon = Self::process_notification_image(&notification).await;
+                        let icon_hash = icon.as_deref().map(NotificationPlugin::icon_hash);
+
                         // Map urgency from notification hints
                         let urgency = Some(NotificationUrgency::from_byte(notification.urgency()));
 
-                        let packet = NotificationPlugin::create_desktop_notification_packet(
+                        let mut packet = NotificationPlugin::create_desktop_notification_packet(
                             &notification.app_name,
                             &notification.summary,
                             &notification.body,
                             notification.timestamp as i64,
-                            image_bytes.as_deref(),
-                            actions,
+                            None, // image is sent as the payload
+                            &actions,
                             urgency,
                             notification.category(),
-                            None, // app_icon - could be enhanced later
+                            None, // app_icon is the payload's fallback
                         );
+                        if let Some(hash) = &icon_hash {
+                            NotificationPlugin::set_icon_hash(&mut packet, hash);
+                        }
+
+                        // Replacements update the notification already on the device
+                        let remote = active_notifications.assign(
+                            &notification,
+                            packet.body["id"].as_str().unwrap_or_default(),
+                        );
+                        if remote.is_update {
+                            packet.body["id"] = serde_json::json!(remote.id);
+                            packet.body["silent"] = serde_json::json!("true");
+                        }
+
+                        // Replies are routed by the server-assigned ID, keyed by the packet ID
+                        if notification.is_repliable() && notification.notification_id != 0 {
+                            if let Some(key) = packet.body["id"].as_str().map(String::from) {
+                                notification_replies.write().await.register(
+                                    &key,
+                                    ReplyTarget {
+                                        notification_id: notification.notification_id,
+                                        sender: notification.sender.clone(),
+                                        action_id: notification_listener::REPLY_ACTION_ID
+                                            .to_string(),
+                                    },
+                                );
+                                NotificationPlugin::set_request_reply_id(
+                                    &mut packet,
+                                    &key,
+                                    notification.reply_placeholder(),
+                                );
+                            }
+                        }
 
                         // Forward to each device that supports notifications
                         for device_id in &devices {
@@ -1530,7 +2354,8 @@ impl Daemon {
                             let supports_notifications = {
                                 let plug_manager = plugin_manager.read().await;
                                 plug_manager
-                                    .get_device_plugin(device_id, "cconnect.notification")
+                                    .get_device_plugin(device_id, "notification")
+                                    .await
                                     .is_some()
                             };
 
@@ -1542,9 +2367,72 @@ impl Daemon {
                                 continue;
                             }
 
+                            let device_limit = {
+                                let config = config.read().await;
+                                let registry = device_config_registry.read().await;
+                                match registry.get(device_id) {
+                                    Some(device_config) => device_config
+                                        .get_max_forwarded_notifications(
+                                            &config.notification_listener,
+                                        ),
+                                    None => config.notification_listener.max_forwarded_per_device,
+                                }
+                            };
+                            if !rate_limiter.lock().await.allow(device_id, device_limit) {
+                                trace!(
+                                    "Notification rate limit exceeded for device {}, dropping",
+                                    device_id
+                                );
+                                continue;
+                            }
+
+                            // Attach the icon unless the device already got it this connection
+                            let send_icon = match &icon_hash {
+                                Some(hash) => plugin_manager
+                                    .read()
+                                    .await
+                                    .get_device_plugin_as::<NotificationPlugin>(
+                                        device_id,
+                                        "notification",
+                                    )
+                                    .await
+                                    .is_some_and(|mut plugin| plugin.needs_icon(hash)),
+                                None => false,
+                            };
+                            let device_packet = match (&icon, &icon_hash) {
+                                (Some(icon), Some(hash)) if send_icon => {
+                                    match Self::serve_notification_icon(
+                                        &connection_manager,
+                                        &plugin_manager,
+                                        device_id,
+                                        icon,
+                                        hash,
+                                    )
+                                    .await
+                                    {
+                                        Ok(port) => packet
+                                            .clone()
+                                            .with_payload_size(icon.len() as i64)
+                                            .with_payload_transfer_info(
+                                                std::collections::HashMap::from([(
+                                                    "port".to_string(),
+                                                    serde_json::json!(port),
+                                                )]),
+                                            ),
+                                        Err(e) => {
+                                            warn!("Failed to serve notification icon: {}", e);
+                                            packet.clone()
+                                        }
+                                    }
+                                }
+                                _ => packet.clone(),
+                            };
+
                             // Send packet
                             let conn_manager = connection_manager.read().await;
-                            if let Err(e) = conn_manager.send_packet(device_id, &packet).await {
+                            if let Err(e) =
+                                conn_manager.send_packet(device_id, &device_packet).await
+                            {
                                 error!(
                                     "Failed to forward notification to device {}: {}",
                                     device_id, e
@@ -1574,6 +2462,7 @@ impl Daemon {
     /// Attempts to extract and process an image from the notification, trying:
     /// 1. Raw image data from `image-data` hint
     /// 2. Image file path from `image-path` hint
+    /// 3. The `app_icon`, as a path or a name in the icon theme
     ///
     /// Returns PNG-encoded image bytes if successful, None otherwise.
     async fn process_notification_image(notification: &CapturedNotification) -> Option<Vec<u8>> {
@@ -1622,9 +2511,168 @@ impl Daemon {
             }
         }
 
+        // Fall back to the app icon
+        match NotificationImage::from_app_icon(&notification.app_icon) {
+            Ok(img) => match img.to_png() {
+                Ok(png_bytes) => {
+                    debug!(
+                        "Processed notification app icon {}: {} bytes",
+                        notification.app_icon,
+                        png_bytes.len()
+                    );
+                    return Some(png_bytes);
+                }
+                Err(e) => {
+                    warn!("Failed to convert app icon to PNG: {}", e);
+                }
+            },
+            Err(e) => {
+                trace!("Failed to load app icon {:?}: {}", notification.app_icon, e);
+            }
+        }
+
         None
     }
 
+    /// Dismiss a forwarded notification on every device it may have reached
+    async fn dismiss_forwarded_notification(
+        remote_id: &str,
+        device_manager: &Arc<RwLock<DeviceManager>>,
+        plugin_manager: &Arc<RwLock<PluginManager>>,
+        connection_manager: &Arc<RwLock<ConnectionManager>>,
+    ) {
+        use cosmic_ext_connect_protocol::plugins::notification::NotificationPlugin;
+
+        let devices = {
+            let dev_manager = device_manager.read().await;
+            dev_manager
+                .devices()
+                .filter(|d| d.is_paired() && d.is_connected())
+                .map(|d| d.id().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let packet = NotificationPlugin::new().create_desktop_dismissed_packet(remote_id);
+        for device_id in &devices {
+            let supports_notifications = plugin_manager
+                .read()
+                .await
+                .get_device_plugin(device_id, "notification")
+                .await
+                .is_some();
+            if !supports_notifications {
+                continue;
+            }
+
+            let conn_manager = connection_manager.read().await;
+            if let Err(e) = conn_manager.send_packet(device_id, &packet).await {
+                warn!(
+                    "Failed to dismiss notification {} on device {}: {}",
+                    remote_id, device_id, e
+                );
+            } else {
+                debug!(
+                    "Dismissed notification {} on device {}",
+                    remote_id, device_id
+                );
+            }
+        }
+    }
+
+    /// Serve a notification icon as a payload, returning the port it is served on
+    ///
+    /// Payloads are streamed from files, so icons are cached on disk under their hash.
+    ///
+    /// The icon is recorded as sent to the device only once the transfer
+    /// succeeded, so a failed transfer is retried with the next notification.
+    async fn serve_notification_icon(
+        connection_manager: &Arc<RwLock<ConnectionManager>>,
+        plugin_manager: &Arc<RwLock<PluginManager>>,
+        device_id: &str,
+        icon: &[u8],
+        hash: &str,
+    ) -> Result<u16> {
+        use cosmic_ext_connect_protocol::TlsPayloadServer;
+
+        let icon_dir = dirs::cache_dir()
+            .unwrap_or_else(|| std::path::PathBuf::from(".cache"))
+            .join("cosmic")
+            .join("cosmic-ext-connect")
+            .join("notification-icons");
+        let icon_path = icon_dir.join(format!("{}.png", hash));
+
+        if !icon_path.exists() {
+            tokio::fs::create_dir_all(&icon_dir)
+                .await
+                .context("Failed to create notification icon cache")?;
+            tokio::fs::write(&icon_path, icon)
+                .await
+                .context("Failed to cache notification icon")?;
+        }
+
+        let tls_config = connection_manager.read().await.tls_config();
+        let server = TlsPayloadServer::new(tls_config)
+            .await
+            .context("Failed to start icon payload server")?;
+        let port = server.port();
+
+        let plugin_manager = plugin_manager.clone();
+        let device_id = device_id.to_string();
+        let hash = hash.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = server.send_file(&icon_path).await {
+                debug!("Notification icon was not transferred: {}", e);
+                return;
+            }
+
+            if let Some(mut plugin) = plugin_manager
+                .read()
+                .await
+                .get_device_plugin_as::<NotificationPlugin>(&device_id, "notification")
+                .await
+            {
+                plugin.mark_icon_sent(&hash);
+            }
+        });
+
+        Ok(port)
+    }
+
+    /// Serve a plugin packet's attached payload and point the packet at it
+    ///
+    /// Plugins attach payloads with `Packet::with_payload` (e.g. the rest of
+    /// a resumed file share). The stream is sent by a background task once
+    /// the device connects to the announced port.
+    async fn serve_packet_payload(
+        connection_manager: &Arc<RwLock<ConnectionManager>>,
+        packet: &mut Packet,
+    ) -> Result<()> {
+        use cosmic_ext_connect_protocol::TlsPayloadServer;
+
+        let Some(payload) = packet.take_payload() else {
+            return Ok(());
+        };
+
+        let tls_config = connection_manager.read().await.tls_config();
+        let server = TlsPayloadServer::new(tls_config)
+            .await
+            .context("Failed to start payload server")?;
+        packet.payload_transfer_info = Some(std::collections::HashMap::from([(
+            "port".to_string(),
+            serde_json::json!(server.port()),
+        )]));
+
+        let packet_type = packet.packet_type.clone();
+        tokio::spawn(async move {
+            let size = payload.size();
+            if let Err(e) = server.send_stream(payload, size).await {
+                warn!("Payload of {} was not transferred: {}", packet_type, e);
+            }
+        });
+
+        Ok(())
+    }
+
     /// Handle a connection event
     #[allow(clippy::too_many_arguments)]
     async fn handle_connection_event(
@@ -1642,6 +2690,12 @@ impl Daemon {
         config: &Arc<RwLock<Config>>,
         error_handler: &Option<Arc<ErrorHandler>>,
         tls_config: &Arc<cosmic_ext_connect_protocol::TlsConfig>,
+        notification_snoozes: &Arc<RwLock<NotificationSnoozes>>,
+        usage_reporter: &Arc<RwLock<UsageReporter>>,
+        disconnect_actions: &Arc<RwLock<DisconnectActions>>,
+        history: &History,
+        battery_alerts: &Arc<RwLock<LowBatteryAlerts>>,
+        notification_replies: &Arc<RwLock<NotificationReplies>>,
     ) -> Result<()> {
         match event {
             ConnectionEvent::Connected {
@@ -1650,6 +2704,55 @@ impl Daemon {
             } => {
                 info!("Device {} connected from {}", device_id, remote_addr);
 
+                // Back online within the grace period: skip the disconnect action
+                disconnect_actions
+                    .write()
+                    .await
+                    .device_connected(&device_id);
+
+                // Record anonymized usage aggregate (no-op unless the user opted in)
+                if usage_reporter.read().await.is_enabled() {
+                    let dev_manager = device_manager.read().await;
+                    if let Some(device) = dev_manager.get_device(&device_id) {
+                        let plug_manager = plugin_manager.read().await;
+                        let plugins: std::collections::BTreeSet<&str> = device
+                            .info
+                            .incoming_capabilities
+                            .iter()
+                            .chain(&device.info.outgoing_capabilities)
+                            .filter_map(|cap| plug_manager.get_plugin_for_packet(cap))
+                            .collect();
+                        let transport = if remote_addr == BT_PLACEHOLDER_ADDR {
+                            usage_report::TRANSPORT_BLUETOOTH
+                        } else {
+                            usage_report::TRANSPORT_TCP
+                        };
+                        if let Err(e) = usage_reporter.write().await.record_connection(
+                            device.info.protocol_version,
+                            plugins,
+                            transport,
+                        ) {
+                            warn!("Failed to record usage report: {}", e);
+                        }
+                    }
+                }
+
+                // Log which features work in each direction with this peer
+                {
+                    let dev_manager = device_manager.read().await;
+                    if let Some(device) = dev_manager.get_device(&device_id) {
+                        let plug_manager = plugin_manager.read().await;
+                        let diff = CapabilityDiff::compute(
+                            &plug_manager.get_all_incoming_capabilities(),
+                            &plug_manager.get_all_outgoing_capabilities(),
+                            &device.info.incoming_capabilities,
+                            &device.info.outgoing_capabilities,
+                        );
+                        info!("Capabilities for device {}: {}", device_id, diff);
+                        debug!("Capability diff for device {}: {:?}", device_id, diff);
+                    }
+                }
+
                 // Get device name for notifications
                 let _device_name = {
                     let dev_manager = device_manager.read().await;
@@ -1677,8 +2780,8 @@ impl Daemon {
                                 info!("Initialized plugins for device {}", device_id);
 
                                 // Set TLS config on SharePlugin for secure file transfers
-                                if let Some(plugin) =
-                                    plug_manager.get_device_plugin_mut(&device_id, "share")
+                                if let Some(mut plugin) =
+                                    plug_manager.get_device_plugin(&device_id, "share").await
                                 {
                                     use cosmic_ext_connect_protocol::plugins::share::SharePlugin;
                                     if let Some(share_plugin) =
@@ -1692,13 +2795,25 @@ impl Daemon {
                                     }
                                 }
 
+                                // Album art for remote players also arrives over TLS
+                                if let Some(mut plugin) =
+                                    plug_manager.get_device_plugin(&device_id, "mpris").await
+                                {
+                                    use cosmic_ext_connect_protocol::plugins::mpris::MprisPlugin;
+                                    if let Some(mpris_plugin) =
+                                        plugin.as_any_mut().downcast_mut::<MprisPlugin>()
+                                    {
+                                        mpris_plugin.set_tls_config(tls_config.clone());
+                                    }
+                                }
+
                                 // Load MAC address from config and set it on WOL plugin
                                 let config_registry = device_config_registry.read().await;
                                 if let Some(device_config) = config_registry.get(&device_id) {
                                     if let Some(mac_address) = device_config.get_mac_address() {
                                         use cosmic_ext_connect_protocol::plugins::wol::WolPlugin;
-                                        if let Some(wol_plugin) =
-                                            plug_manager.get_device_plugin_mut(&device_id, "wol")
+                                        if let Some(mut wol_plugin) =
+                                            plug_manager.get_device_plugin(&device_id, "wol").await
                                         {
                                             if let Some(wol) =
                                                 wol_plugin.as_any_mut().downcast_mut::<WolPlugin>()
@@ -1713,9 +2828,28 @@ impl Daemon {
                                     }
                                 }
 
+                                // Send our clipboard so the device starts out in sync
+                                if let Some(clipboard) = plug_manager
+                                    .get_device_plugin_as::<ClipboardPlugin>(
+                                        &device_id,
+                                        "clipboard",
+                                    )
+                                    .await
+                                {
+                                    let packet = clipboard.create_connect_packet().await;
+                                    if let Err(e) =
+                                        packet_sender.send((device_id.clone(), packet)).await
+                                    {
+                                        warn!(
+                                            "Failed to send clipboard sync to {}: {}",
+                                            device_id, e
+                                        );
+                                    }
+                                }
+
                                 // Initialize Contacts plugin database and signals
-                                if let Some(contacts_plugin) =
-                                    plug_manager.get_device_plugin_mut(&device_id, "contacts")
+                                if let Some(mut contacts_plugin) =
+                                    plug_manager.get_device_plugin(&device_id, "contacts").await
                                 {
                                     if let Some(contacts) = contacts_plugin
                                         .as_any_mut()
@@ -1773,14 +2907,9 @@ impl Daemon {
                     }
                 }
 
-                // Emit DBus signal for device state changed
+                // Report the connected device in the next DevicesUpdated batch
                 if let Some(dbus) = dbus_server {
-                    if let Err(e) = dbus
-                        .emit_device_state_changed(&device_id, "connected")
-                        .await
-                    {
-                        warn!("Failed to emit DeviceStateChanged signal: {}", e);
-                    }
+                    dbus.queue_device_update(&device_id).await;
                 }
 
                 // Show COSMIC notification for device connection
@@ -1800,48 +2929,40 @@ impl Daemon {
             } => {
                 info!(
                     "Device {} disconnected (reason: {:?}, reconnect: {})",
-                    device_id, reason, reconnect
-                );
-
-                // Get device name for notifications
-                let _device_name = {
-                    let dev_manager = device_manager.read().await;
-                    dev_manager
-                        .get_device(&device_id)
-                        .map(|d| d.name().to_string())
-                };
-
-                // Cleanup per-device plugins ONLY if not a socket replacement
-                if !reconnect {
-                    let mut plug_manager = plugin_manager.write().await;
-                    if let Err(e) = plug_manager.cleanup_device_plugins(&device_id).await {
-                        error!("Failed to cleanup plugins for device {}: {}", device_id, e);
-                    } else {
-                        info!("Cleaned up plugins for device {}", device_id);
-                    }
-                } else {
-                    info!(
-                        "Socket replacement for {} - preserving plugin state",
-                        device_id
-                    );
-                }
+                    device_id, reason, reconnect
+                );
 
-                // Emit DBus signal for device state changed
-                if let Some(dbus) = dbus_server {
-                    if let Err(e) = dbus.emit_device_state_changed(&device_id, "disconnected").await {
-                        warn!("Failed to emit DeviceStateChanged signal: {}", e);
-                    }
-                }
+                Self::handle_device_disconnected(
+                    &device_id,
+                    reconnect,
+                    device_manager,
+                    plugin_manager,
+                    device_config_registry,
+                    dbus_server,
+                    config,
+                    disconnect_actions,
+                )
+                .await;
+            }
+            ConnectionEvent::Timeout { device_id, idle } => {
+                warn!(
+                    "Device {} timed out after {}s without traffic",
+                    device_id,
+                    idle.as_secs()
+                );
 
-                // Show COSMIC notification for device disconnection
-                // TEMPORARILY DISABLED TO REDUCE SPAM
-                // if let Some(notifier) = cosmic_notifier {
-                //     if let Some(name) = device_name {
-                //         if let Err(e) = notifier.notify_device_disconnected(&name).await {
-                //             warn!("Failed to send device disconnected notification: {}", e);
-                //         }
-                //     }
-                // }
+                // Torn down like any other lost connection
+                Self::handle_device_disconnected(
+                    &device_id,
+                    false,
+                    device_manager,
+                    plugin_manager,
+                    device_config_registry,
+                    dbus_server,
+                    config,
+                    disconnect_actions,
+                )
+                .await;
             }
             ConnectionEvent::PacketReceived {
                 device_id,
@@ -1952,17 +3073,79 @@ impl Daemon {
                     let device_name = device.name().to_string();
 
                     // Route packet to plugin manager
-                    let mut plug_manager = plugin_manager.write().await;
-                    if let Err(e) = plug_manager
-                        .handle_packet(&device_id, &packet, device)
-                        .await
-                    {
-                        error!("Error handling packet from device {}: {}", device_id, e);
-                        if let Some(handler) = error_handler {
-                            handler
-                                .handle_error(&e, "plugin_packet", Some(&device_id))
-                                .await;
+                    let plug_manager = plugin_manager.read().await;
+
+                    // Apply the mute policy before a ring request reaches the plugin
+                    let ringing_before = if FindMyPhonePlugin::is_ring_request(&packet) {
+                        let muted = device_config_registry
+                            .read()
+                            .await
+                            .get(&device_id)
+                            .is_some_and(|c| c.mute_find_my_phone);
+                        match plug_manager
+                            .get_device_plugin_as::<FindMyPhonePlugin>(&device_id, "findmyphone")
+                            .await
+                        {
+                            Some(mut findmyphone) => {
+                                let ringing = findmyphone.is_ringing();
+                                findmyphone.set_mute_policy(muted);
+                                Some(ringing)
+                            }
+                            None => None,
+                        }
+                    } else {
+                        None
+                    };
+
+                    // Plugins handle packets on their own queues. Only wait for the
+                    // result when the state checked below depends on it.
+                    let handled = match plug_manager.dispatch_packet(&device_id, &packet, device) {
+                        Ok(receipt) => {
+                            if ringing_before.is_some()
+                                || packet.packet_type == "cconnect.wol.config"
+                            {
+                                receipt.wait().await
+                            } else {
+                                let device_id = device_id.clone();
+                                let error_handler = error_handler.clone();
+                                tokio::spawn(async move {
+                                    if let Err(e) = receipt.wait().await {
+                                        error!(
+                                            "Error handling packet from device {}: {}",
+                                            device_id, e
+                                        );
+                                        if let Some(handler) = error_handler {
+                                            handler
+                                                .handle_error(&e, "plugin_packet", Some(&device_id))
+                                                .await;
+                                        }
+                                    }
+                                });
+                                Ok(())
+                            }
+                        }
+                        Err(e) => Err(e),
+                    };
+                    match handled {
+                        // Peers on a newer protocol version may send types we don't know
+                        Err(_) if !plug_manager.handles_packet_type(&packet.packet_type) => {
+                            debug!(
+                                "Ignoring packet {} from device {}: no plugin handles it",
+                                packet.packet_type, device_id
+                            );
+                        }
+                        // A full plugin queue is logged by the plugin manager, once
+                        // per overflow; reporting every dropped packet would flood
+                        Err(cosmic_ext_connect_protocol::ProtocolError::ResourceExhausted(_)) => {}
+                        Err(e) => {
+                            error!("Error handling packet from device {}: {}", device_id, e);
+                            if let Some(handler) = error_handler {
+                                handler
+                                    .handle_error(&e, "plugin_packet", Some(&device_id))
+                                    .await;
+                            }
                         }
+                        Ok(()) => {}
                     }
 
                     // Handle camera frame payload reception (Issue #139)
@@ -2034,11 +3217,12 @@ impl Daemon {
                                                             // Pass payload to camera plugin
                                                             let plug_manager =
                                                                 plugin_manager_clone.write().await;
-                                                            if let Some(camera_plugin) =
-                                                                plug_manager.get_device_plugin(
+                                                            if let Some(camera_plugin) = plug_manager
+                                                                .get_device_plugin(
                                                                     &device_id_clone,
                                                                     "camera",
                                                                 )
+                                                                .await
                                                             {
                                                                 if let Some(camera) = camera_plugin
                                                                     .as_any()
@@ -2087,37 +3271,72 @@ impl Daemon {
                     if packet.packet_type == "cconnect.wol.config" {
                         use cosmic_ext_connect_protocol::plugins::wol::WolPlugin;
 
-                        if let Some(wol_plugin) =
-                            plug_manager.get_device_plugin_mut(&device_id, "wol")
+                        if let Some(wol) = plug_manager
+                            .get_device_plugin_as::<WolPlugin>(&device_id, "wol")
+                            .await
                         {
-                            if let Some(wol) = wol_plugin.as_any_mut().downcast_mut::<WolPlugin>() {
-                                if let Some(mac_address) = wol.get_mac_address() {
-                                    info!(
-                                        "Persisting MAC address {} for device {}",
-                                        mac_address, device_id
-                                    );
+                            if let Some(mac_address) = wol.get_mac_address() {
+                                info!(
+                                    "Persisting MAC address {} for device {}",
+                                    mac_address, device_id
+                                );
 
-                                    let mut config_registry = device_config_registry.write().await;
-                                    let device_config = config_registry.get_or_create(&device_id);
+                                let mut config_registry = device_config_registry.write().await;
+                                let device_config = config_registry.get_or_create(&device_id);
 
-                                    if let Err(e) = device_config.set_mac_address(mac_address) {
-                                        error!("Failed to set MAC address: {}", e);
+                                if let Err(e) = device_config.set_mac_address(mac_address) {
+                                    error!("Failed to set MAC address: {}", e);
+                                } else {
+                                    // Save config to disk
+                                    if let Err(e) = config_registry.save() {
+                                        error!("Failed to save device config: {}", e);
                                     } else {
-                                        // Save config to disk
-                                        if let Err(e) = config_registry.save() {
-                                            error!("Failed to save device config: {}", e);
-                                        } else {
-                                            info!("MAC address saved to device config");
-                                        }
+                                        info!("MAC address saved to device config");
                                     }
                                 }
                             }
                         }
                     }
 
+                    let ringing_after = plug_manager
+                        .get_device_plugin_as::<FindMyPhonePlugin>(&device_id, "findmyphone")
+                        .await
+                        .map(|p| p.is_ringing());
+
                     drop(plug_manager);
                     drop(dev_manager);
 
+                    // Tell the applet to show or dismiss the "found" dialog
+                    if let (Some(before), Some(ringing)) = (ringing_before, ringing_after) {
+                        if before != ringing {
+                            if let Some(dbus) = dbus_server {
+                                if let Err(e) =
+                                    dbus.emit_find_my_phone_ringing(&device_id, ringing).await
+                                {
+                                    warn!("Failed to emit find my phone signal: {}", e);
+                                }
+                            }
+                        }
+                    }
+
+                    // Log user-visible activity for the History page
+                    if let Some((event_type, summary)) =
+                        history::packet_event(&packet, &device_name)
+                    {
+                        history.record(&device_id, event_type, summary);
+                    }
+
+                    // Snooze the originating app when the remote triggers our snooze action
+                    if packet.is_type("cconnect.notification.action") {
+                        Self::handle_snooze_action(&packet, notification_snoozes).await;
+                    }
+
+                    // Deliver replies typed on the device to the desktop notification
+                    if packet.is_type("cconnect.notification.reply") {
+                        Self::handle_notification_reply(&packet, notification_replies, dbus_server)
+                            .await;
+                    }
+
                     // Check device notification preference
                     let notification_pref = {
                         let config_registry = device_config_registry.read().await;
@@ -2372,40 +3591,9 @@ impl Daemon {
                             }
                             "cconnect.share.request" => {
                                 // Handle different share types: file, URL, or text
-                                if let Some(filename) =
-                                    packet.body.get("filename").and_then(|v| v.as_str())
-                                {
-                                    if packet.payload_size.is_some() {
-                                        // This is a file transfer, show notification
-                                        let file_size = packet.payload_size.unwrap_or(0);
-
-                                        // Construct download path
-                                        let downloads_dir = std::path::PathBuf::from(
-                                            std::env::var("HOME")
-                                                .unwrap_or_else(|_| "/tmp".to_string()),
-                                        )
-                                        .join("Downloads");
-                                        let file_path = downloads_dir.join(filename);
-
-                                        if let Err(e) = notifier
-                                            .notify_file_received(
-                                                &device_name,
-                                                filename,
-                                                &file_path.to_string_lossy(),
-                                            )
-                                            .await
-                                        {
-                                            warn!(
-                                                "Failed to send file received notification: {}",
-                                                e
-                                            );
-                                        } else {
-                                            info!(
-                                                "Sent file received notification for '{}' ({} bytes) from {}",
-                                                filename, file_size, device_name
-                                            );
-                                        }
-                                    }
+                                if packet.body.get("filename").is_some() {
+                                    // Files are announced by the share event
+                                    // forwarder once they are saved
                                 } else if let Some(url) =
                                     packet.body.get("url").and_then(|v| v.as_str())
                                 {
@@ -2466,73 +3654,59 @@ impl Daemon {
                                     }
                                 }
                             }
-                            "cconnect.clipboard" | "kdeconnect.clipboard.connect" => {
-                                // Update system clipboard with received content
-                                if let Some(content) =
-                                    packet.body.get("content").and_then(|v| v.as_str())
-                                {
-                                    if !content.is_empty() {
-                                        use arboard::Clipboard;
-                                        match Clipboard::new() {
-                                            Ok(mut clipboard) => {
-                                                if let Err(e) = clipboard.set_text(content) {
-                                                    warn!(
-                                                        "Failed to update system clipboard: {}",
-                                                        e
-                                                    );
-                                                } else {
-                                                    info!("Updated system clipboard from {} ({} chars)",
-                                                        device_name, content.len());
-                                                }
-                                            }
-                                            Err(e) => {
-                                                warn!("Failed to initialize clipboard: {}", e);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
                             "cconnect.battery" => {
                                 // Handle battery status updates - show notification for low battery
                                 if let Some(charge) =
                                     packet.body.get("currentCharge").and_then(|v| v.as_i64())
                                 {
+                                    let level = charge.clamp(0, 100) as u8;
                                     let is_charging = packet
                                         .body
                                         .get("isCharging")
                                         .and_then(|v| v.as_bool())
                                         .unwrap_or(false);
-                                    let threshold_event = packet
-                                        .body
-                                        .get("thresholdEvent")
-                                        .and_then(|v| v.as_i64())
-                                        .unwrap_or(0);
 
-                                    // threshold_event == 1 means low battery
-                                    if threshold_event == 1 && !is_charging {
+                                    if let Some(dbus) = dbus_server {
+                                        if let Err(e) = dbus
+                                            .emit_battery_status_changed(
+                                                &device_id,
+                                                level.into(),
+                                                is_charging,
+                                            )
+                                            .await
+                                        {
+                                            warn!("Failed to emit battery status signal: {}", e);
+                                        }
+                                    }
+
+                                    let threshold =
+                                        config.read().await.plugins.low_battery_threshold;
+                                    let alert = battery_alerts.write().await.update(
+                                        &device_id,
+                                        level,
+                                        is_charging,
+                                        threshold,
+                                    );
+                                    if alert {
                                         info!(
                                             "Low battery detected on {} ({}%)",
-                                            device_name, charge
+                                            device_name, level
                                         );
 
-                                        if let Err(e) = notifier
-                                            .notify_battery_low(
-                                                &device_name,
-                                                charge.clamp(0, 100) as u8,
-                                            )
-                                            .await
+                                        if let Err(e) =
+                                            notifier.notify_battery_low(&device_name, level).await
                                         {
                                             warn!("Failed to send low battery notification: {}", e);
                                         } else {
                                             info!(
                                                 "Sent low battery notification for {} ({}%)",
-                                                device_name, charge
+                                                device_name, level
                                             );
                                         }
                                     } else {
                                         debug!(
                                             "Battery status from {}: {}% (charging: {})",
-                                            device_name, charge, is_charging
+                                            device_name, level, is_charging
                                         );
                                     }
                                 }
@@ -2578,6 +3752,81 @@ impl Daemon {
         Ok(())
     }
 
+    /// Clean up after a device's connection was lost
+    ///
+    /// `reconnect` is set when the socket was replaced by a new connection, in
+    /// which case plugin state is preserved.
+    #[allow(clippy::too_many_arguments)]
+    async fn handle_device_disconnected(
+        device_id: &str,
+        reconnect: bool,
+        device_manager: &Arc<RwLock<DeviceManager>>,
+        plugin_manager: &Arc<RwLock<PluginManager>>,
+        device_config_registry: &Arc<RwLock<device_config::DeviceConfigRegistry>>,
+        dbus_server: &Option<Arc<DbusServer>>,
+        config: &Arc<RwLock<Config>>,
+        disconnect_actions: &Arc<RwLock<DisconnectActions>>,
+    ) {
+        // Get device name for notifications
+        let _device_name = {
+            let dev_manager = device_manager.read().await;
+            dev_manager
+                .get_device(device_id)
+                .map(|d| d.name().to_string())
+        };
+
+        // Cleanup per-device plugins ONLY if not a socket replacement
+        if !reconnect {
+            let mut plug_manager = plugin_manager.write().await;
+            if let Err(e) = plug_manager.cleanup_device_plugins(device_id).await {
+                error!("Failed to cleanup plugins for device {}: {}", device_id, e);
+            } else {
+                info!("Cleaned up plugins for device {}", device_id);
+            }
+        } else {
+            info!(
+                "Socket replacement for {} - preserving plugin state",
+                device_id
+            );
+        }
+
+        // Schedule the device's disconnect action, if any and allowed
+        if !reconnect {
+            let action = device_config_registry
+                .read()
+                .await
+                .get(device_id)
+                .map(|c| c.on_disconnect.clone())
+                .unwrap_or_default();
+            if config.read().await.disconnect_actions.permits(&action) {
+                disconnect_actions
+                    .write()
+                    .await
+                    .device_disconnected(device_id, action);
+            } else {
+                warn!(
+                    "Disconnect action {:?} for device {} is not allowed by config",
+                    action, device_id
+                );
+            }
+        }
+
+        // Report the disconnected device in the next DevicesUpdated batch
+        if let Some(dbus) = dbus_server {
+            dbus.queue_device_update(device_id).await;
+        }
+
+        // Show COSMIC notification for device disconnection
+        // TEMPORARILY DISABLED TO REDUCE SPAM
+        // if let Some(notifier) = cosmic_notifier {
+        //     if let Some(name) = device_name {
+        //         if let Err(e) = notifier.notify_device_disconnected(&name).await {
+        //             warn!("Failed to send device disconnected notification: {}", e);
+        //         }
+        //     }
+        // }
+    }
+
     /// Convert MprisManager PlayerState to protocol types for CConnect
     fn convert_player_state(
         state: &mpris_manager::PlayerState,
@@ -2664,8 +3913,8 @@ impl Daemon {
 
             let plug_manager = plugin_manager.read().await;
             if let Some(mpris_plugin) = plug_manager
-                .get_device_plugin(device_id, "mpris")
-                .and_then(|p| p.as_any().downcast_ref::<MprisPlugin>())
+                .get_device_plugin_as::<MprisPlugin>(device_id, "mpris")
+                .await
             {
                 let packet = mpris_plugin.create_player_list_packet(players);
                 drop(plug_manager);
@@ -2784,8 +4033,8 @@ impl Daemon {
 
             let plug_manager = plugin_manager.read().await;
             if let Some(mpris_plugin) = plug_manager
-                .get_device_plugin(device_id, "mpris")
-                .and_then(|p| p.as_any().downcast_ref::<MprisPlugin>())
+                .get_device_plugin_as::<MprisPlugin>(device_id, "mpris")
+                .await
             {
                 let packet =
                     mpris_plugin.create_status_packet(player.to_string(), status, metadata);
@@ -2803,6 +4052,7 @@ impl Daemon {
         dbus_server: &Option<Arc<DbusServer>>,
         _error_handler: &ErrorHandler,
         connection_manager: &Arc<RwLock<ConnectionManager>>,
+        transport_manager: &Option<Arc<TransportManager>>,
         connection_attempts: &Arc<
             RwLock<std::collections::HashMap<String, (std::time::Instant, u32)>>,
         >,
@@ -2830,12 +4080,15 @@ impl Daemon {
                 }
 
                 // Auto-connect if paired
-                let should_connect = {
+                let (should_connect, addresses) = {
                     let manager = device_manager.read().await;
                     if let Some(device) = manager.get_device(&device_id) {
-                        device.is_paired() && !device.is_connected()
+                        (
+                            device.is_paired() && !device.is_connected(),
+                            device.transport_addresses(),
+                        )
                     } else {
-                        false
+                        (false, Vec::new())
                     }
                 };
 
@@ -2858,24 +4111,20 @@ impl Daemon {
                         *last_attempt = now;
                         *count += 1;
 
-                        // Try to connect
-                        let _mgr = connection_manager.read().await;
-                        // Need to extract SocketAddr from TransportAddress if it's TCP
-                        // DiscoveryService usually returns TransportAddress::Tcp for UDP discovery results
-                        if let cosmic_ext_connect_protocol::transport::TransportAddress::Tcp(addr) =
-                            transport_address
-                        {
-                            let device_id_clone = device_id.clone();
-                            let socket_addr = *addr;
-
-                            let mgr_arc = connection_manager.clone();
-                            tokio::spawn(async move {
-                                let mgr = mgr_arc.read().await;
-                                if let Err(e) = mgr.connect(&device_id_clone, socket_addr).await {
-                                    warn!("Failed to auto-connect to {}: {}", device_id_clone, e);
-                                }
-                            });
-                        }
+                        // Dial the addresses from this announcement (the port from
+                        // the identity packet, or the known defaults)
+                        let device_id_clone = device_id.clone();
+                        let connection_manager = connection_manager.clone();
+                        let transport_manager = transport_manager.clone();
+                        tokio::spawn(async move {
+                            Self::connect_device(
+                                &device_id_clone,
+                                &addresses,
+                                &connection_manager,
+                                &transport_manager,
+                            )
+                            .await;
+                        });
                     }
                 } else if !should_connect {
                     // Reset attempts if connected or not paired
@@ -2888,30 +4137,17 @@ impl Daemon {
                     }
                 }
 
-                // Emit DBus signal for device added (only on discovery, logic handles updated)
-                if matches!(event, DiscoveryEvent::DeviceDiscovered { .. }) {
-                    if let Some(dbus) = dbus_server {
-                        let manager = device_manager.read().await;
-                        if let Some(device) = manager.get_device(&device_id) {
-                            if let Err(e) = dbus.emit_device_added(device).await {
-                                warn!("Failed to emit DeviceAdded signal: {}", e);
-                            }
-                        }
-                    }
+                // New devices and repeated broadcasts are batched into DevicesUpdated
+                if let Some(dbus) = dbus_server {
+                    dbus.queue_device_update(&device_id).await;
                 }
             }
             DiscoveryEvent::DeviceTimeout { device_id } => {
                 info!("Device timed out: {}", device_id);
                 // We don't mark as disconnected here, ConnectionManager handles TCP timeout.
-                // But we can emit a signal if needed.
+                // Clients see the new reachability in the next DevicesUpdated batch.
                 if let Some(dbus) = dbus_server {
-                    if let Err(e) = dbus
-                        .emit_device_state_changed(&device_id, "reachable")
-                        .await
-                    {
-                        // Just updating reachability state logic handled in list_devices mostly
-                        warn!("Failed to emit state change: {}", e);
-                    }
+                    dbus.queue_device_update(&device_id).await;
                 }
             }
             _ => {}
@@ -2919,6 +4155,42 @@ impl Daemon {
         Ok(())
     }
 
+    /// Connect to a device at its known addresses
+    ///
+    /// With a TransportManager the addresses are ranked by the configured
+    /// transport preference and every enabled transport is tried in turn.
+    /// Without one, the TCP addresses are dialled in order.
+    async fn connect_device(
+        device_id: &str,
+        addresses: &[cosmic_ext_connect_protocol::transport::TransportAddress],
+        connection_manager: &Arc<RwLock<ConnectionManager>>,
+        transport_manager: &Option<Arc<TransportManager>>,
+    ) {
+        if let Some(transport_mgr) = transport_manager {
+            let candidates = transport_mgr.candidate_addresses(addresses);
+            if let Err(e) = transport_mgr.connect(device_id, &candidates).await {
+                warn!("Failed to auto-connect to {}: {}", device_id, e);
+            }
+            return;
+        }
+
+        let mgr = connection_manager.read().await;
+        for address in addresses {
+            let cosmic_ext_connect_protocol::transport::TransportAddress::Tcp(socket_addr) =
+                address
+            else {
+                continue;
+            };
+            match mgr.connect(device_id, *socket_addr).await {
+                Ok(_) => return,
+                Err(e) => warn!(
+                    "Failed to auto-connect to {} at {}: {}",
+                    device_id, socket_addr, e
+                ),
+            }
+        }
+    }
+
     /// Run the daemon
     async fn run(&self) -> Result<()> {
         info!("CConnect daemon running");
@@ -2932,7 +4204,15 @@ impl Daemon {
         // Spawn proactive packet handler
         let packet_receiver_mutex = self.packet_receiver.clone();
         let connection_manager = self.connection_manager.clone();
+        let transport_manager = self.transport_manager.clone();
         let dbus_server = self.dbus_server.clone();
+        let cosmic_notifier = self.cosmic_notifier.clone();
+        let config = self.config.clone();
+        let plugin_manager = self.plugin_manager.clone();
+        let device_manager = self.device_manager.clone();
+        let mpris_manager = self.mpris_manager.clone();
+        let runcommand_confirmations = self.runcommand_confirmations.clone();
+        let metered_link = self.metered_link.clone();
 
         tokio::spawn(async move {
             let mut receiver_guard = packet_receiver_mutex.lock().await;
@@ -2941,8 +4221,149 @@ impl Daemon {
             };
             drop(receiver_guard); // Release mutex
 
+            let mut metered_notices = MeteredNotices::default();
+            let mut ring_actions = RingActions::default();
+            let mut call_notifications = std::collections::HashMap::new();
+
             info!("Started proactive packet handler");
-            while let Some((device_id, packet)) = receiver.recv().await {
+            while let Some((device_id, mut packet)) = receiver.recv().await {
+                // Suggest freeing space when a download ran out of disk
+                if packet.is_type("cconnect.internal.share.transfer_failed")
+                    && packet.body.get("reason").and_then(|v| v.as_str()) == Some("disk_full")
+                {
+                    if let Some(notifier) = &cosmic_notifier {
+                        let path = packet
+                            .body
+                            .get("path")
+                            .and_then(|v| v.as_str())
+                            .and_then(|p| std::path::Path::new(p).parent())
+                            .map(|dir| dir.display().to_string())
+                            .unwrap_or_else(|| "the download directory".to_string());
+                        if let Err(e) = notifier.notify_disk_full_error(&path).await {
+                            warn!("Failed to send disk full notification: {}", e);
+                        }
+                    }
+                }
+
+                // Name callers the phone didn't know from the synced contacts
+                if packet
+                    .packet_type
+                    .starts_with("cconnect.internal.telephony.")
+                {
+                    Self::resolve_caller_name(&plugin_manager, &device_id, &mut packet).await;
+                }
+
+                // Run the ring action once per call and undo it when the call ends
+                if packet.is_type("cconnect.internal.telephony.ringing")
+                    || packet.is_type("cconnect.internal.telephony.missed_call")
+                {
+                    let ringing = packet.is_type("cconnect.internal.telephony.ringing");
+                    if ringing && ring_actions.call_started(&device_id) {
+                        let action = config.read().await.plugins.telephony_ring_action;
+                        if let Some(undo) =
+                            ring_action::apply(action, mpris_manager.as_deref()).await
+                        {
+                            ring_actions.record(&device_id, undo);
+                        }
+                    }
+
+                    if let Some(notifier) = &cosmic_notifier {
+                        let device_name = device_manager
+                            .read()
+                            .await
+                            .get_device(&device_id)
+                            .map(|d| d.name().to_string())
+                            .unwrap_or_else(|| device_id.clone());
+                        let number = packet.body.get("phoneNumber").and_then(|v| v.as_str());
+                        let name = packet.body.get("contactName").and_then(|v| v.as_str());
+                        let caller = match name {
+                            Some(name) => format!("{} ({})", name, number.unwrap_or("Unknown")),
+                            None => number.unwrap_or("Unknown caller").to_string(),
+                        };
+                        let result = if ringing {
+                            notifier.notify_incoming_call(&device_name, &caller).await
+                        } else {
+                            notifier.notify_missed_call(&device_name, &caller).await
+                        };
+                        match result {
+                            Ok(id) if ringing => {
+                                call_notifications.insert(device_id.clone(), id);
+                            }
+                            Ok(_) => {}
+                            Err(e) => warn!("Failed to send call notification: {}", e),
+                        }
+                    }
+                } else if packet.is_type("cconnect.internal.telephony.ended") {
+                    if let Some(undo) = ring_actions.call_ended(&device_id) {
+                        ring_action::undo(undo, mpris_manager.as_deref()).await;
+                    }
+                }
+
+                // The incoming call notification is stale once the call is answered or over
+                if packet.is_type("cconnect.internal.telephony.talking")
+                    || packet.is_type("cconnect.internal.telephony.ended")
+                    || packet.is_type("cconnect.internal.telephony.missed_call")
+                {
+                    if let (Some(id), Some(notifier)) =
+                        (call_notifications.remove(&device_id), &cosmic_notifier)
+                    {
+                        if let Err(e) = notifier.close(id).await {
+                            debug!("Failed to close incoming call notification: {}", e);
+                        }
+                    }
+                }
+
+                // Ask the user before running a command that needs confirmation
+                if packet.is_type(PACKET_TYPE_RUNCOMMAND_CONFIRM) {
+                    let key = packet
+                        .body
+                        .get("key")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("");
+                    let name = packet
+                        .body
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(key);
+                    let command = packet.body.get("command").and_then(|v| v.as_str());
+                    let device_name = device_manager
+                        .read()
+                        .await
+                        .get_device(&device_id)
+                        .map(|d| d.name().to_string())
+                        .unwrap_or_else(|| device_id.clone());
+                    let notification = match &cosmic_notifier {
+                        Some(notifier) => notifier
+                            .notify_run_command_confirmation(
+                                &device_name,
+                                name,
+                                command.unwrap_or(""),
+                            )
+                            .await
+                            .map_err(|e| e.to_string()),
+                        None => Err("notifications unavailable".to_string()),
+                    };
+                    match notification {
+                        Ok(id) => {
+                            runcommand_confirmations
+                                .write()
+                                .await
+                                .insert(id, (device_id.clone(), key.to_string()));
+                        }
+                        Err(e) => {
+                            warn!("Cannot confirm command '{}', not running it: {}", key, e);
+                            Self::handle_runcommand_confirmation(
+                                &plugin_manager,
+                                &device_id,
+                                key,
+                                "cancel",
+                            )
+                            .await;
+                        }
+                    }
+                    continue;
+                }
+
                 // Handle internal signaling packets for DBus emission
                 let handled = if let Some(dbus) = &dbus_server {
                     handle_internal_packet(dbus, &device_id, &packet).await
@@ -2950,10 +4371,70 @@ impl Daemon {
                     false
                 };
 
+                // Hold back heavy operations while the peer is on mobile data
+                // or our own link is metered
+                let decision = if handled {
+                    MeteredDecision::Proceed
+                } else {
+                    let metered_config = config.read().await.metered.clone();
+                    let plugin_manager = plugin_manager.read().await;
+                    metered_policy::check_packet(
+                        &metered_config,
+                        &plugin_manager,
+                        &metered_link,
+                        &device_id,
+                        &packet,
+                    )
+                    .await
+                };
+                if let MeteredDecision::Warn(feature) | MeteredDecision::Defer(feature) = decision
+                {
+                    let deferred = !decision.proceeds();
+                    info!(
+                        "Connection to {} is metered, {} {}",
+                        device_id,
+                        if deferred { "skipping" } else { "warning about" },
+                        feature
+                    );
+                    if let Some(notifier) = &cosmic_notifier {
+                        if metered_notices.should_notify(&device_id, feature) {
+                            let device_name = device_manager
+                                .read()
+                                .await
+                                .get_device(&device_id)
+                                .map(|d| d.name().to_string())
+                                .unwrap_or_else(|| device_id.clone());
+                            if let Err(e) = notifier
+                                .notify_metered_operation(
+                                    &device_name,
+                                    &feature.to_string(),
+                                    deferred,
+                                )
+                                .await
+                            {
+                                warn!("Failed to send metered connection notification: {}", e);
+                            }
+                        }
+                    }
+                }
+
                 // Forward non-internal packets to the connection manager
-                if !handled {
-                    let manager = connection_manager.read().await;
-                    if let Err(e) = manager.send_packet(&device_id, &packet).await {
+                if !handled && decision.proceeds() {
+                    if let Err(e) =
+                        Self::serve_packet_payload(&connection_manager, &mut packet).await
+                    {
+                        error!("Failed to serve payload for {}: {}", device_id, e);
+                        continue;
+                    }
+                    // The TransportManager routes to whichever transport the device is on
+                    let sent = match &transport_manager {
+                        Some(transport_mgr) => transport_mgr.send_packet(&device_id, &packet).await,
+                        None => {
+                            let manager = connection_manager.read().await;
+                            manager.send_packet(&device_id, &packet).await
+                        }
+                    };
+                    if let Err(e) = sent {
                         error!("Failed to send proactive packet to {}: {}", device_id, e);
                     }
                 }
@@ -3053,7 +4534,7 @@ impl Daemon {
             async {
                 // Stop discovery service
                 if let Some(mut discovery) = self.discovery_service.take() {
-                    let _ = discovery.stop().await;
+                    discovery.stop().await;
                 }
 
                 // Stop transport manager or connection manager
@@ -3195,6 +4676,7 @@ async fn handle_internal_packet(dbus: &dbus::DbusServer, device_id: &str, packet
         }
         "cconnect.internal.telephony.ringing"
         | "cconnect.internal.telephony.talking"
+        | "cconnect.internal.telephony.ended"
         | "cconnect.internal.telephony.missed_call" => {
             let phone_number = packet
                 .body
@@ -3216,6 +4698,10 @@ async fn handle_internal_packet(dbus: &dbus::DbusServer, device_id: &str, packet
                     dbus.emit_call_state_changed(device_id, "talking", phone_number, contact_name)
                         .await
                 }
+                "cconnect.internal.telephony.ended" => {
+                    dbus.emit_call_state_changed(device_id, "ended", phone_number, contact_name)
+                        .await
+                }
                 _ => {
                     // missed_call
                     dbus.emit_missed_call(device_id, phone_number, contact_name)
@@ -3299,6 +4785,30 @@ async fn handle_internal_packet(dbus: &dbus::DbusServer, device_id: &str, packet
             }
             true
         }
+        "cconnect.internal.share.transfer_failed" => {
+            let filename = packet
+                .body
+                .get("filename")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let transfer_id = packet
+                .body
+                .get("path")
+                .and_then(|v| v.as_str())
+                .unwrap_or(filename);
+            let error_msg = packet
+                .body
+                .get("error")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unknown error");
+            if let Err(e) = dbus
+                .emit_transfer_complete(transfer_id, device_id, filename, false, error_msg)
+                .await
+            {
+                error!("Failed to emit transfer_complete signal: {}", e);
+            }
+            true
+        }
         _ => false, // Not an internal packet
     }
 }
@@ -3558,6 +5068,18 @@ async fn main() -> Result<()> {
         .await
         .context("Failed to start DBus server")?;
 
+    // Forward share plugin transfer progress to DBus
+    daemon
+        .start_share_event_forwarder()
+        .await
+        .context("Failed to start share event forwarder")?;
+
+    // Forward remote media player state to DBus
+    daemon
+        .start_mpris_event_forwarder()
+        .await
+        .context("Failed to start MPRIS event forwarder")?;
+
     // Start discovery
     daemon
         .start_discovery()
@@ -0,0 +1,451 @@
+//! Metered Connection Policy
+//!
+//! Phones report their network type through the connectivity report plugin.
+//! While the peer is on mobile data its connection is treated as metered.
+//! NetworkManager additionally reports whether this desktop's own link is
+//! metered (a phone hotspot, a mobile broadband modem, or a connection the
+//! user marked as metered). While either is, bandwidth-heavy operations with
+//! the device are warned about or deferred according to the per-feature
+//! actions in [`MeteredConfig`](crate::config::MeteredConfig).
+//!
+//! Only packets that start a heavy operation are gated: file shares and file
+//! sync transfers with a payload at or above the size threshold, and requests
+//! to start a remote desktop or screen share session. Control packets always
+//! proceed. Deferred packets are not queued; the user is told the operation
+//! was skipped and can retry once the link is unmetered.
+
+use crate::config::MeteredConfig;
+use cosmic_ext_connect_protocol::capabilities::normalize_capability;
+use cosmic_ext_connect_protocol::plugins::connectivity_report::{
+    ConnectivityReportPlugin, SignalInfo,
+};
+use cosmic_ext_connect_protocol::plugins::PluginManager;
+use cosmic_ext_connect_protocol::Packet;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+const NM_SERVICE: &str = "org.freedesktop.NetworkManager";
+const NM_PATH: &str = "/org/freedesktop/NetworkManager";
+const NM_INTERFACE: &str = "org.freedesktop.NetworkManager";
+
+/// `NMMetered` value for a link known to be metered
+const NM_METERED_YES: u32 = 1;
+/// `NMMetered` value for a link NetworkManager guesses is metered
+const NM_METERED_GUESS_YES: u32 = 3;
+
+/// Minimum time between notices for the same device and feature
+pub const NOTICE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Bandwidth-heavy feature subject to the metered policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeteredFeature {
+    /// Sending files with the share plugin
+    FileShare,
+    /// File sync transfers
+    FileSync,
+    /// Remote desktop sessions
+    RemoteDesktop,
+    /// Screen share sessions
+    ScreenShare,
+}
+
+impl MeteredFeature {
+    /// Feature started by `packet`, if it is a heavy operation
+    ///
+    /// Transfers count as heavy when their payload is at least
+    /// `large_payload_bytes`.
+    pub fn for_packet(packet: &Packet, large_payload_bytes: u64) -> Option<Self> {
+        let large = packet
+            .payload_size
+            .is_some_and(|size| size >= 0 && size as u64 >= large_payload_bytes);
+
+        match normalize_capability(&packet.packet_type) {
+            "share.request" if large => Some(Self::FileShare),
+            t if t.starts_with("filesync") && large => Some(Self::FileSync),
+            "remotedesktop.request" | "remotedesktop.response" => Some(Self::RemoteDesktop),
+            "screenshare.request" | "screenshare.start" => Some(Self::ScreenShare),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MeteredFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::FileShare => "File sharing",
+            Self::FileSync => "File sync",
+            Self::RemoteDesktop => "Remote desktop",
+            Self::ScreenShare => "Screen sharing",
+        })
+    }
+}
+
+/// What to do with a heavy operation while the connection is metered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeteredAction {
+    /// Proceed as usual
+    Allow,
+    /// Proceed and warn the user
+    Warn,
+    /// Skip the operation while the connection is metered
+    Defer,
+}
+
+/// Outcome of checking an operation against the metered policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeteredDecision {
+    /// Send without further action
+    Proceed,
+    /// Send, but warn the user about data usage
+    Warn(MeteredFeature),
+    /// Do not send; the operation is skipped
+    Defer(MeteredFeature),
+}
+
+impl MeteredDecision {
+    /// Whether the operation may go ahead
+    pub fn proceeds(&self) -> bool {
+        !matches!(self, Self::Defer(_))
+    }
+}
+
+/// Whether a peer reporting `signal` is on a metered connection
+pub fn is_metered(signal: Option<&SignalInfo>) -> bool {
+    signal.is_some_and(SignalInfo::is_mobile)
+}
+
+/// Whether a NetworkManager `Metered` property value marks the link as metered
+pub fn is_link_metered(nm_metered: u32) -> bool {
+    matches!(nm_metered, NM_METERED_YES | NM_METERED_GUESS_YES)
+}
+
+/// Whether a device's latest connectivity report puts it on mobile data
+pub async fn is_device_metered(plugin_manager: &PluginManager, device_id: &str) -> bool {
+    let Some(plugin) = plugin_manager
+        .get_device_plugin_as::<ConnectivityReportPlugin>(device_id, "connectivity_report")
+        .await
+    else {
+        return false;
+    };
+    is_metered(plugin.get_primary_signal().await.as_ref())
+}
+
+/// Whether the connection to a device is metered
+///
+/// True if the device reports mobile data, or this desktop's own link is
+/// metered.
+pub async fn is_connection_metered(
+    plugin_manager: &PluginManager,
+    link: &MeteredLink,
+    device_id: &str,
+) -> bool {
+    is_device_metered(plugin_manager, device_id).await || link.is_metered().await
+}
+
+/// This desktop's network link as reported by NetworkManager
+///
+/// Without NetworkManager on the system bus the link is assumed unmetered.
+#[derive(Clone, Default)]
+pub struct MeteredLink {
+    proxy: Option<zbus::Proxy<'static>>,
+}
+
+impl MeteredLink {
+    /// Connect to NetworkManager on the system bus
+    pub async fn connect() -> Self {
+        let proxy = match zbus::Connection::system().await {
+            Ok(connection) => {
+                zbus::Proxy::new_owned(connection, NM_SERVICE, NM_PATH, NM_INTERFACE).await
+            }
+            Err(e) => Err(e),
+        };
+
+        match proxy {
+            Ok(proxy) => Self { proxy: Some(proxy) },
+            Err(e) => {
+                warn!(
+                    "NetworkManager unavailable, treating the network as unmetered: {}",
+                    e
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// Whether the desktop's primary connection is metered
+    pub async fn is_metered(&self) -> bool {
+        let Some(proxy) = &self.proxy else {
+            return false;
+        };
+
+        match proxy.get_property::<u32>("Metered").await {
+            Ok(nm_metered) => is_link_metered(nm_metered),
+            Err(e) => {
+                debug!("Failed to read NetworkManager Metered property: {}", e);
+                false
+            }
+        }
+    }
+}
+
+/// Check a feature against the policy
+pub fn evaluate(config: &MeteredConfig, feature: MeteredFeature, metered: bool) -> MeteredDecision {
+    if !metered || !config.avoid_metered {
+        return MeteredDecision::Proceed;
+    }
+
+    match config.action_for(feature) {
+        MeteredAction::Allow => MeteredDecision::Proceed,
+        MeteredAction::Warn => MeteredDecision::Warn(feature),
+        MeteredAction::Defer => MeteredDecision::Defer(feature),
+    }
+}
+
+/// Check an outgoing packet against the policy
+pub fn evaluate_packet(config: &MeteredConfig, packet: &Packet, metered: bool) -> MeteredDecision {
+    match MeteredFeature::for_packet(packet, config.large_payload_bytes) {
+        Some(feature) => evaluate(config, feature, metered),
+        None => MeteredDecision::Proceed,
+    }
+}
+
+/// Check an outgoing packet against the policy for a device
+///
+/// Only looks up the device's connectivity and this desktop's link for
+/// packets that start a heavy operation.
+pub async fn check_packet(
+    config: &MeteredConfig,
+    plugin_manager: &PluginManager,
+    link: &MeteredLink,
+    device_id: &str,
+    packet: &Packet,
+) -> MeteredDecision {
+    if MeteredFeature::for_packet(packet, config.large_payload_bytes).is_none() {
+        return MeteredDecision::Proceed;
+    }
+
+    let metered = is_connection_metered(plugin_manager, link, device_id).await;
+    evaluate_packet(config, packet, metered)
+}
+
+/// Limits how often the user is told about metered operations
+#[derive(Debug, Default)]
+pub struct MeteredNotices {
+    last_notice: HashMap<(String, MeteredFeature), Instant>,
+}
+
+impl MeteredNotices {
+    /// Whether to notify about `feature` on `device_id` now
+    ///
+    /// Returns `true` at most once per [`NOTICE_INTERVAL`] for each device and
+    /// feature.
+    pub fn should_notify(&mut self, device_id: &str, feature: MeteredFeature) -> bool {
+        self.should_notify_at(device_id, feature, Instant::now())
+    }
+
+    fn should_notify_at(&mut self, device_id: &str, feature: MeteredFeature, now: Instant) -> bool {
+        let key = (device_id.to_string(), feature);
+        match self.last_notice.get(&key) {
+            Some(last) if now.duration_since(*last) < NOTICE_INTERVAL => false,
+            _ => {
+                self.last_notice.insert(key, now);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const LARGE: i64 = 50 * 1024 * 1024;
+
+    fn mobile() -> SignalInfo {
+        SignalInfo::new("LTE", 3)
+    }
+
+    #[test]
+    fn test_is_metered() {
+        assert!(is_metered(Some(&mobile())));
+        assert!(!is_metered(Some(&SignalInfo::new("WiFi", 4))));
+        assert!(!is_metered(Some(&SignalInfo::default())));
+        assert!(!is_metered(None));
+    }
+
+    #[test]
+    fn test_is_link_metered() {
+        // NM_METERED_UNKNOWN, YES, NO, GUESS_YES, GUESS_NO
+        assert!(!is_link_metered(0));
+        assert!(is_link_metered(1));
+        assert!(!is_link_metered(2));
+        assert!(is_link_metered(3));
+        assert!(!is_link_metered(4));
+    }
+
+    #[tokio::test]
+    async fn test_unreported_device_without_network_manager_unmetered() {
+        let plugin_manager = PluginManager::new();
+        let link = MeteredLink::default();
+        let transfer =
+            Packet::new("cconnect.filesync.transfer", json!({})).with_payload_size(LARGE);
+
+        assert!(!link.is_metered().await);
+        assert!(!is_device_metered(&plugin_manager, "phone").await);
+        assert_eq!(
+            check_packet(
+                &MeteredConfig::default(),
+                &plugin_manager,
+                &link,
+                "phone",
+                &transfer
+            )
+            .await,
+            MeteredDecision::Proceed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_device_on_mobile_data_metered() {
+        use cosmic_ext_connect_protocol::plugins::connectivity_report::{
+            ConnectivityReportPluginFactory, PACKET_TYPE_CONNECTIVITY_REPORT,
+        };
+        use cosmic_ext_connect_protocol::{Device, DeviceInfo, DeviceType};
+        use std::sync::Arc;
+
+        let mut info = DeviceInfo::new("Phone", DeviceType::Phone, 1716);
+        info.outgoing_capabilities = vec![PACKET_TYPE_CONNECTIVITY_REPORT.to_string()];
+        let device = Device::from_discovery(info);
+        let device_id = device.id().to_string();
+
+        let mut plugin_manager = PluginManager::new();
+        plugin_manager
+            .register_factory(Arc::new(ConnectivityReportPluginFactory))
+            .unwrap();
+        let (tx, _rx) = tokio::sync::mpsc::channel(8);
+        plugin_manager
+            .init_device_plugins(&device_id, &device, tx)
+            .await
+            .unwrap();
+
+        let report = Packet::new(
+            PACKET_TYPE_CONNECTIVITY_REPORT,
+            json!({ "signalStrengths": { "0": { "networkType": "LTE", "signalStrength": 3 } } }),
+        );
+        plugin_manager
+            .handle_packet(&device_id, &report, &device)
+            .await
+            .unwrap();
+
+        // Metered through the device's report even though the link is not
+        let link = MeteredLink::default();
+        assert!(is_device_metered(&plugin_manager, &device_id).await);
+        assert!(is_connection_metered(&plugin_manager, &link, &device_id).await);
+        let transfer =
+            Packet::new("cconnect.filesync.transfer", json!({})).with_payload_size(LARGE);
+        assert_eq!(
+            check_packet(
+                &MeteredConfig::default(),
+                &plugin_manager,
+                &link,
+                &device_id,
+                &transfer
+            )
+            .await,
+            MeteredDecision::Defer(MeteredFeature::FileSync)
+        );
+    }
+
+    #[test]
+    fn test_large_sync_deferred_small_control_proceeds() {
+        let config = MeteredConfig::default();
+        let metered = true;
+
+        let transfer = Packet::new("cconnect.filesync.transfer", json!({"path": "video.mkv"}))
+            .with_payload_size(LARGE);
+        assert_eq!(
+            evaluate_packet(&config, &transfer, metered),
+            MeteredDecision::Defer(MeteredFeature::FileSync)
+        );
+
+        let index = Packet::new("cconnect.filesync.index", json!({"files": []}));
+        assert_eq!(
+            evaluate_packet(&config, &index, metered),
+            MeteredDecision::Proceed
+        );
+
+        let small = Packet::new("cconnect.filesync.transfer", json!({"path": "notes.txt"}))
+            .with_payload_size(4096);
+        assert!(evaluate_packet(&config, &small, metered).proceeds());
+
+        let ping = Packet::new("cconnect.ping", json!({}));
+        assert_eq!(
+            evaluate_packet(&config, &ping, metered),
+            MeteredDecision::Proceed
+        );
+    }
+
+    #[test]
+    fn test_large_share_warned() {
+        let config = MeteredConfig::default();
+        let share = Packet::new("kdeconnect.share.request", json!({"filename": "a.iso"}))
+            .with_payload_size(LARGE);
+
+        assert_eq!(
+            evaluate_packet(&config, &share, true),
+            MeteredDecision::Warn(MeteredFeature::FileShare)
+        );
+        assert!(evaluate_packet(&config, &share, true).proceeds());
+        assert_eq!(
+            evaluate_packet(&config, &share, false),
+            MeteredDecision::Proceed
+        );
+    }
+
+    #[test]
+    fn test_per_feature_actions_and_toggle() {
+        let mut config = MeteredConfig::default();
+        let request = Packet::new("cconnect.remotedesktop.request", json!({}));
+
+        assert_eq!(
+            evaluate_packet(&config, &request, true),
+            MeteredDecision::Defer(MeteredFeature::RemoteDesktop)
+        );
+
+        config.remote_desktop = MeteredAction::Allow;
+        assert_eq!(
+            evaluate_packet(&config, &request, true),
+            MeteredDecision::Proceed
+        );
+
+        // Turning off metered avoidance lets everything through
+        config.avoid_metered = false;
+        let transfer =
+            Packet::new("cconnect.filesync.transfer", json!({})).with_payload_size(LARGE);
+        assert_eq!(
+            evaluate_packet(&config, &transfer, true),
+            MeteredDecision::Proceed
+        );
+    }
+
+    #[test]
+    fn test_notices_throttled() {
+        let mut notices = MeteredNotices::default();
+        let start = Instant::now();
+
+        assert!(notices.should_notify_at("phone", MeteredFeature::FileSync, start));
+        assert!(!notices.should_notify_at("phone", MeteredFeature::FileSync, start));
+        assert!(notices.should_notify_at("phone", MeteredFeature::FileShare, start));
+        assert!(notices.should_notify_at("tablet", MeteredFeature::FileSync, start));
+        assert!(notices.should_notify_at(
+            "phone",
+            MeteredFeature::FileSync,
+            start + NOTICE_INTERVAL
+        ));
+    }
+}
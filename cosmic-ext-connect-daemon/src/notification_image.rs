@@ -6,9 +6,10 @@
 //!
 //! ## Purpose
 //!
-//! Desktop notifications can include images from two sources:
+//! Desktop notifications can include images from three sources:
 //! - Raw image data in the `image-data` hint (RGBA/RGB pixel data)
 //! - File paths in the `image-path` hint
+//! - The `app_icon` argument, an icon name resolved in the icon theme or a path
 //!
 //! For efficient transmission to Android devices over the network, images need to be:
 //! - Resized to a reasonable dimension (max 256x256) to reduce bandwidth
@@ -37,13 +38,17 @@
 //! // Or load from a file path
 //! let processed = NotificationImage::from_path("/path/to/icon.png")?;
 //! let png_bytes = processed.to_png()?;
+//!
+//! // Or resolve a named app icon
+//! let processed = NotificationImage::from_app_icon("firefox")?;
+//! let png_bytes = processed.to_png()?;
 //! ```
 
 use anyhow::{Context, Result};
 use image::{DynamicImage, ImageBuffer, ImageFormat, Rgba};
 use std::io::Cursor;
-use std::path::Path;
-use tracing::{debug, trace, warn};
+use std::path::{Path, PathBuf};
+use tracing::{debug, trace};
 
 use crate::notification_listener::ImageData;
 
@@ -54,6 +59,14 @@ use crate::notification_listener::ImageData;
 /// quality with network transmission efficiency.
 pub const MAX_IMAGE_DIMENSION: u32 = 256;
 
+/// Icon themes searched for named app icons, in order
+///
+/// Only PNG icons are used, so themes shipping just SVGs are skipped over.
+const ICON_THEMES: &[&str] = &["Cosmic", "hicolor"];
+
+/// Icon theme sizes searched for named app icons, largest first
+const ICON_SIZES: &[&str] = &["256x256", "128x128", "96x96", "64x64", "48x48", "32x32"];
+
 /// Processed notification image ready for transmission
 ///
 /// This struct holds a processed image that has been resized and is ready
@@ -102,18 +115,23 @@ impl NotificationImage {
         let width = image_data.width as u32;
         let height = image_data.height as u32;
 
-        // Convert raw bytes to image buffer
-        let image = if image_data.has_alpha && image_data.channels == 4 {
-            Self::from_rgba_data(image_data, width, height)?
-        } else if !image_data.has_alpha && image_data.channels == 3 {
-            Self::from_rgb_data(image_data, width, height)?
-        } else {
+        if image_data.bits_per_sample != 8 {
+            return Err(anyhow::anyhow!(
+                "Unsupported bits per sample: {}",
+                image_data.bits_per_sample
+            ));
+        }
+
+        // Without alpha the fourth channel, if any, is padding
+        if !(image_data.channels == 4 || (image_data.channels == 3 && !image_data.has_alpha)) {
             return Err(anyhow::anyhow!(
                 "Unsupported image format: channels={}, has_alpha={}",
                 image_data.channels,
                 image_data.has_alpha
             ));
-        };
+        }
+
+        let image = Self::from_pixel_data(image_data, width, height)?;
 
         // Resize if necessary
         let resized = Self::resize_if_needed(image, MAX_IMAGE_DIMENSION);
@@ -155,6 +173,23 @@ impl NotificationImage {
         Ok(Self { image: resized })
     }
 
+    /// Create a NotificationImage from a notification's `app_icon`
+    ///
+    /// The icon may be a `file://` URI, an absolute path, or an icon name
+    /// looked up in the icon theme (see [`find_app_icon`]).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let notification_image = NotificationImage::from_app_icon("thunderbird")?;
+    /// ```
+    pub fn from_app_icon(app_icon: &str) -> Result<Self> {
+        let path = find_app_icon(app_icon, &icon_data_dirs())
+            .ok_or_else(|| anyhow::anyhow!("No PNG icon found for app icon {:?}", app_icon))?;
+
+        Self::from_path(path)
+    }
+
     /// Convert the image to PNG format
     ///
     /// Encodes the processed image as PNG bytes suitable for transmission
@@ -191,58 +226,27 @@ impl NotificationImage {
         (self.image.width(), self.image.height())
     }
 
-    /// Convert RGBA image data to DynamicImage
-    fn from_rgba_data(image_data: &ImageData, width: u32, height: u32) -> Result<DynamicImage> {
-        trace!("Converting RGBA image data");
-
-        // Calculate expected data size
-        let expected_size = (width * height * 4) as usize;
-
-        if image_data.data.len() < expected_size {
-            warn!(
-                "Image data size mismatch: expected at least {}, got {}",
-                expected_size,
-                image_data.data.len()
-            );
-        }
-
-        // Handle rowstride (padding at end of each row)
-        let rowstride = image_data.rowstride as usize;
-        let row_bytes = (width * 4) as usize;
-
-        let mut pixels = Vec::with_capacity(expected_size);
-
-        for y in 0..height as usize {
-            let row_start = y * rowstride;
-            let row_end = row_start + row_bytes;
+    /// Convert RGB/RGBA image data to an RGBA DynamicImage
+    ///
+    /// Skips the padding at the end of each row and makes pixels opaque when
+    /// the data has no alpha channel.
+    fn from_pixel_data(image_data: &ImageData, width: u32, height: u32) -> Result<DynamicImage> {
+        trace!("Converting {}-channel image data", image_data.channels);
 
-            if row_end > image_data.data.len() {
-                return Err(anyhow::anyhow!(
-                    "Image data truncated at row {}: need {} bytes, have {}",
-                    y,
-                    row_end,
-                    image_data.data.len()
-                ));
-            }
+        let channels = image_data.channels as usize;
+        let rowstride = usize::try_from(image_data.rowstride)
+            .map_err(|_| anyhow::anyhow!("Invalid rowstride: {}", image_data.rowstride))?;
+        let row_bytes = width as usize * channels;
 
-            pixels.extend_from_slice(&image_data.data[row_start..row_end]);
+        if rowstride < row_bytes {
+            return Err(anyhow::anyhow!(
+                "Rowstride {} is shorter than a row of {} bytes",
+                rowstride,
+                row_bytes
+            ));
         }
 
-        let image_buffer = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(width, height, pixels)
-            .ok_or_else(|| anyhow::anyhow!("Failed to create RGBA image buffer"))?;
-
-        Ok(DynamicImage::ImageRgba8(image_buffer))
-    }
-
-    /// Convert RGB image data to DynamicImage
-    fn from_rgb_data(image_data: &ImageData, width: u32, height: u32) -> Result<DynamicImage> {
-        trace!("Converting RGB image data to RGBA");
-
-        // RGB data needs to be converted to RGBA
-        let rowstride = image_data.rowstride as usize;
-        let row_bytes = (width * 3) as usize;
-
-        let mut rgba_pixels = Vec::with_capacity((width * height * 4) as usize);
+        let mut rgba_pixels = Vec::with_capacity(width as usize * height as usize * 4);
 
         for y in 0..height as usize {
             let row_start = y * rowstride;
@@ -257,20 +261,14 @@ impl NotificationImage {
                 ));
             }
 
-            // Convert RGB to RGBA by adding alpha channel
-            for x in 0..width as usize {
-                let pixel_start = row_start + (x * 3);
-                rgba_pixels.push(image_data.data[pixel_start]); // R
-                rgba_pixels.push(image_data.data[pixel_start + 1]); // G
-                rgba_pixels.push(image_data.data[pixel_start + 2]); // B
-                rgba_pixels.push(255); // A (fully opaque)
+            for pixel in image_data.data[row_start..row_end].chunks_exact(channels) {
+                let alpha = if image_data.has_alpha { pixel[3] } else { 255 };
+                rgba_pixels.extend_from_slice(&[pixel[0], pixel[1], pixel[2], alpha]);
             }
         }
 
         let image_buffer = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(width, height, rgba_pixels)
-            .ok_or_else(|| {
-            anyhow::anyhow!("Failed to create RGBA image buffer from RGB data")
-        })?;
+            .ok_or_else(|| anyhow::anyhow!("Failed to create RGBA image buffer"))?;
 
         Ok(DynamicImage::ImageRgba8(image_buffer))
     }
@@ -306,6 +304,59 @@ impl NotificationImage {
     }
 }
 
+/// Resolve a notification `app_icon` to an image file
+///
+/// Paths and `file://` URIs are returned as-is. Names are looked up as PNGs in
+/// the `icons/<theme>/<size>/apps` and `pixmaps` directories under each of
+/// `data_dirs`.
+pub fn find_app_icon(app_icon: &str, data_dirs: &[PathBuf]) -> Option<PathBuf> {
+    if app_icon.is_empty() {
+        return None;
+    }
+
+    if let Some(path) = app_icon.strip_prefix("file://") {
+        return Some(PathBuf::from(path));
+    }
+
+    if app_icon.starts_with('/') {
+        return Some(PathBuf::from(app_icon));
+    }
+
+    let file_name = format!("{}.png", app_icon);
+
+    let themed = ICON_THEMES.iter().flat_map(|theme| {
+        ICON_SIZES.iter().flat_map(move |size| {
+            data_dirs
+                .iter()
+                .map(move |dir| dir.join("icons").join(theme).join(size).join("apps"))
+        })
+    });
+    let pixmaps = data_dirs.iter().map(|dir| dir.join("pixmaps"));
+
+    themed
+        .chain(pixmaps)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.is_file())
+}
+
+/// XDG data directories icons are installed under, user directory first
+fn icon_data_dirs() -> Vec<PathBuf> {
+    let system_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    dirs::data_dir()
+        .into_iter()
+        .chain(
+            system_dirs
+                .split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        )
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -478,4 +529,126 @@ mod tests {
         let result = NotificationImage::from_path("/nonexistent/path/to/image.png");
         assert!(result.is_err());
     }
+
+    fn pixel(image: &NotificationImage, x: u32, y: u32) -> [u8; 4] {
+        image.image.to_rgba8().get_pixel(x, y).0
+    }
+
+    #[test]
+    fn test_rgb_data_with_rowstride_padding() {
+        // 2x2 RGB image, rows padded from 6 to 8 bytes
+        let image_data = ImageData {
+            width: 2,
+            height: 2,
+            rowstride: 8,
+            has_alpha: false,
+            bits_per_sample: 8,
+            channels: 3,
+            data: vec![
+                10, 20, 30, 40, 50, 60, 0xAA, 0xAA, // Row 1 + padding
+                70, 80, 90, 100, 110, 120, 0xAA, 0xAA, // Row 2 + padding
+            ],
+        };
+
+        let img = NotificationImage::from_image_data(&image_data).unwrap();
+        assert_eq!(pixel(&img, 0, 0), [10, 20, 30, 255]);
+        assert_eq!(pixel(&img, 1, 0), [40, 50, 60, 255]);
+        assert_eq!(pixel(&img, 0, 1), [70, 80, 90, 255]);
+        assert_eq!(pixel(&img, 1, 1), [100, 110, 120, 255]);
+    }
+
+    #[test]
+    fn test_rgba_data_keeps_alpha_and_skips_padding() {
+        let image_data = ImageData {
+            width: 1,
+            height: 2,
+            rowstride: 6,
+            has_alpha: true,
+            bits_per_sample: 8,
+            channels: 4,
+            data: vec![1, 2, 3, 128, 0xAA, 0xAA, 4, 5, 6, 0, 0xAA, 0xAA],
+        };
+
+        let img = NotificationImage::from_image_data(&image_data).unwrap();
+        assert_eq!(pixel(&img, 0, 0), [1, 2, 3, 128]);
+        assert_eq!(pixel(&img, 0, 1), [4, 5, 6, 0]);
+    }
+
+    #[test]
+    fn test_four_channels_without_alpha_are_opaque() {
+        // RGBx: the fourth byte is padding, not alpha
+        let image_data = ImageData {
+            width: 2,
+            height: 1,
+            rowstride: 8,
+            has_alpha: false,
+            bits_per_sample: 8,
+            channels: 4,
+            data: vec![1, 2, 3, 0, 4, 5, 6, 0],
+        };
+
+        let img = NotificationImage::from_image_data(&image_data).unwrap();
+        assert_eq!(pixel(&img, 0, 0), [1, 2, 3, 255]);
+        assert_eq!(pixel(&img, 1, 0), [4, 5, 6, 255]);
+    }
+
+    #[test]
+    fn test_invalid_rowstride_and_sample_depth_rejected() {
+        let short_rowstride = ImageData {
+            width: 2,
+            height: 2,
+            rowstride: 4,
+            has_alpha: true,
+            bits_per_sample: 8,
+            channels: 4,
+            data: vec![0; 16],
+        };
+        let sixteen_bit = ImageData {
+            bits_per_sample: 16,
+            rowstride: 8,
+            ..short_rowstride.clone()
+        };
+        let alpha_without_channel = ImageData {
+            channels: 3,
+            rowstride: 6,
+            ..short_rowstride.clone()
+        };
+
+        assert!(NotificationImage::from_image_data(&short_rowstride).is_err());
+        assert!(NotificationImage::from_image_data(&sixteen_bit).is_err());
+        assert!(NotificationImage::from_image_data(&alpha_without_channel).is_err());
+    }
+
+    #[test]
+    fn test_find_app_icon() {
+        let data_dir =
+            std::env::temp_dir().join(format!("cosmic-connect-icon-test-{}", std::process::id()));
+        let small = data_dir.join("icons/hicolor/48x48/apps");
+        let large = data_dir.join("icons/hicolor/128x128/apps");
+        let pixmaps = data_dir.join("pixmaps");
+        for dir in [&small, &large, &pixmaps] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::write(small.join("chat.png"), b"").unwrap();
+        std::fs::write(large.join("chat.png"), b"").unwrap();
+        std::fs::write(pixmaps.join("legacy.png"), b"").unwrap();
+
+        let data_dirs = vec![data_dir.clone()];
+        assert_eq!(
+            find_app_icon("chat", &data_dirs),
+            Some(large.join("chat.png"))
+        );
+        assert_eq!(
+            find_app_icon("legacy", &data_dirs),
+            Some(pixmaps.join("legacy.png"))
+        );
+        assert_eq!(find_app_icon("missing", &data_dirs), None);
+        assert_eq!(find_app_icon("", &data_dirs), None);
+        assert_eq!(
+            find_app_icon("file:///opt/app/icon.png", &data_dirs),
+            Some(PathBuf::from("/opt/app/icon.png"))
+        );
+
+        std::fs::remove_dir_all(&data_dir).unwrap();
+    }
 }
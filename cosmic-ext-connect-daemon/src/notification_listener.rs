@@ -8,6 +8,8 @@
 //! This module monitors the session DBus for notification events using `MatchRule`
 //! to intercept `org.freedesktop.Notifications.Notify` method calls. All captured
 //! notifications are filtered according to configuration and sent via an mpsc channel.
+//! `CloseNotification` calls and `NotificationClosed` signals are sent along as
+//! [`NotificationEvent::Closed`], so dismissals can follow the notification.
 //!
 //! ## DBus Notification Specification
 //!
@@ -35,12 +37,31 @@
 //! - `action-icons`: Boolean indicating if actions have icons
 //! - `transient`: Boolean, should not persist
 //! - `resident`: Boolean, stays after dismissal
+//! - `x-kde-reply-placeholder-text`: Placeholder for the inline reply field
+//!
+//! ## Notification IDs
+//!
+//! Replacements, closes and inline replies all refer to the ID the
+//! notification server returns from `Notify`. For new notifications the
+//! listener also watches `Notify` method returns and holds the notification
+//! until its ID arrives (or [`NOTIFY_ID_TIMEOUT`] passes, in which case it is
+//! forwarded without an ID, so it can't be updated, dismissed or replied to).
+//!
+//! New notifications are forwarded as soon as their ID arrives. Replacements
+//! are debounced per notification: the first one is forwarded right away, and
+//! replacements following within [`UPDATE_DEBOUNCE`] are collapsed into the
+//! latest one (e.g. progress updates from a download).
+//!
+//! ## Inline Replies
+//!
+//! Apps advertise inline reply support with an `inline-reply` action (or the
+//! `x-kde-reply-placeholder-text` hint).
 //!
 //! ## Example
 //!
 //! ```rust,ignore
 //! use cosmic_connect_daemon::notification_listener::{
-//!     NotificationListener, NotificationListenerConfig
+//!     NotificationEvent, NotificationListener, NotificationListenerConfig
 //! };
 //!
 //! let config = NotificationListenerConfig {
@@ -53,19 +74,33 @@
 //! let listener = NotificationListener::new(config, tx).await?;
 //!
 //! // Listen for notifications
-//! while let Some(notification) = rx.recv().await {
-//!     println!("Got notification: {}", notification.summary);
+//! while let Some(event) = rx.recv().await {
+//!     if let NotificationEvent::Posted(notification) = event {
+//!         println!("Got notification: {}", notification.summary);
+//!     }
 //! }
 //! ```
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use tracing::{debug, info, trace, warn};
 use zbus::{Connection, MatchRule};
 
+/// Action ID apps use to advertise inline reply support
+pub const REPLY_ACTION_ID: &str = "inline-reply";
+
+/// Hint carrying the placeholder text for the inline reply field
+pub const REPLY_PLACEHOLDER_HINT: &str = "x-kde-reply-placeholder-text";
+
+/// How long a new notification waits for its server-assigned ID
+pub const NOTIFY_ID_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Minimum time between forwarded replacements of the same notification
+pub const UPDATE_DEBOUNCE: Duration = Duration::from_millis(500);
+
 /// Notification hint value types
 ///
 /// DBus hints can contain various types of data. This enum represents
@@ -122,6 +157,10 @@ pub struct CapturedNotification {
     /// Notification ID (assigned by notification daemon)
     pub notification_id: u32,
 
+    /// ID of the notification this one replaces (0 for new)
+    #[serde(default)]
+    pub replaces_id: u32,
+
     /// Application icon name or path
     pub app_icon: String,
 
@@ -142,6 +181,20 @@ pub struct CapturedNotification {
 
     /// Timestamp when notification was captured
     pub timestamp: u64,
+
+    /// Unique bus name of the application that sent the notification
+    #[serde(default)]
+    pub sender: String,
+}
+
+/// Event captured from the notification service
+#[derive(Debug, Clone)]
+pub enum NotificationEvent {
+    /// A notification was posted or replaced
+    Posted(CapturedNotification),
+
+    /// The notification with this ID was closed
+    Closed(u32),
 }
 
 /// Rich notification content extracted from hints
@@ -263,6 +316,22 @@ impl CapturedNotification {
             .unwrap_or(false)
     }
 
+    /// Check if the app accepts inline replies to this notification
+    pub fn is_repliable(&self) -> bool {
+        self.actions.iter().any(|(id, _)| id == REPLY_ACTION_ID)
+            || self.hints.contains_key(REPLY_PLACEHOLDER_HINT)
+    }
+
+    /// Get the placeholder text for the inline reply field
+    pub fn reply_placeholder(&self) -> Option<&str> {
+        self.hints
+            .get(REPLY_PLACEHOLDER_HINT)
+            .and_then(|v| match v {
+                HintValue::String(s) => Some(s.as_str()),
+                _ => None,
+            })
+    }
+
     /// Extract all rich notification data from hints
     ///
     /// Provides a convenient way to access all rich content fields
@@ -309,6 +378,83 @@ pub struct NotificationListenerConfig {
     /// Maximum body length (truncate if longer, 0 = no limit)
     #[serde(default)]
     pub max_body_length: usize,
+
+    /// Per-app capture rules, evaluated top to bottom after the app lists
+    #[serde(default)]
+    pub app_rules: Vec<AppCaptureRule>,
+}
+
+/// Whether a capture rule includes or excludes the apps it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureAction {
+    /// Capture notifications from matching apps
+    Include,
+    /// Skip notifications from matching apps
+    Exclude,
+}
+
+/// Per-app capture rule
+///
+/// `app` is a wildcard pattern (`*` matches any run of characters, `?` a
+/// single one), compared case-insensitively with both the app name and the
+/// `desktop-entry` hint. Every matching rule applies in order, so later rules
+/// override earlier ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppCaptureRule {
+    /// Wildcard pattern matched against the app name and desktop entry
+    pub app: String,
+
+    /// Include or exclude matching apps (unset leaves the decision unchanged)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<CaptureAction>,
+
+    /// Override `include_transient` for matching apps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_transient: Option<bool>,
+
+    /// Override `include_low_urgency` for matching apps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_low_urgency: Option<bool>,
+}
+
+impl AppCaptureRule {
+    /// Check if the rule applies to a notification
+    pub fn matches(&self, notification: &CapturedNotification) -> bool {
+        wildcard_match(&self.app, &notification.app_name)
+            || notification
+                .desktop_entry()
+                .is_some_and(|entry| wildcard_match(&self.app, entry))
+    }
+}
+
+/// Case-insensitive wildcard match supporting `*` and `?`
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it currently matches up to
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` swallow one more character
+            backtrack = Some((star, matched + 1));
+            p = star + 1;
+            t = matched + 1;
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
 }
 
 fn default_true() -> bool {
@@ -330,6 +476,7 @@ impl Default for NotificationListenerConfig {
             include_transient: true,
             include_low_urgency: true,
             max_body_length: 0, // No limit
+            app_rules: Vec::new(),
         }
     }
 }
@@ -357,21 +504,49 @@ impl NotificationListenerConfig {
             .any(|included| included == app_name)
     }
 
+    /// Check if a notification's app should be captured, applying app rules
+    fn captures_app(&self, notification: &CapturedNotification) -> bool {
+        self.matching_rules(notification)
+            .filter_map(|rule| rule.action)
+            .last()
+            .map_or_else(
+                || self.should_capture_app(&notification.app_name),
+                |action| action == CaptureAction::Include,
+            )
+    }
+
     /// Check if a notification should be captured based on hints
     fn should_capture_notification(&self, notification: &CapturedNotification) -> bool {
+        let mut include_transient = self.include_transient;
+        let mut include_low_urgency = self.include_low_urgency;
+        for rule in self.matching_rules(notification) {
+            include_transient = rule.include_transient.unwrap_or(include_transient);
+            include_low_urgency = rule.include_low_urgency.unwrap_or(include_low_urgency);
+        }
+
         // Filter transient notifications
-        if !self.include_transient && notification.is_transient() {
+        if !include_transient && notification.is_transient() {
             return false;
         }
 
         // Filter low-urgency notifications
-        if !self.include_low_urgency && notification.urgency() == 0 {
+        if !include_low_urgency && notification.urgency() == 0 {
             return false;
         }
 
         true
     }
 
+    /// App rules that apply to a notification, in order
+    fn matching_rules<'a>(
+        &'a self,
+        notification: &'a CapturedNotification,
+    ) -> impl Iterator<Item = &'a AppCaptureRule> + 'a {
+        self.app_rules
+            .iter()
+            .filter(move |rule| rule.matches(notification))
+    }
+
     /// Truncate body if needed
     fn truncate_body(&self, body: String) -> String {
         if self.max_body_length > 0 && body.len() > self.max_body_length {
@@ -383,13 +558,81 @@ impl NotificationListenerConfig {
     }
 }
 
+/// Debounces repeated replacements of the same notification
+#[derive(Debug, Default)]
+pub struct UpdateDebouncer {
+    /// Last forwarded time and held back replacement, keyed by notification ID
+    updates: HashMap<u32, (Instant, Option<CapturedNotification>)>,
+}
+
+impl UpdateDebouncer {
+    /// Create an empty debouncer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Offer a replacement, returning it if it should be forwarded now
+    ///
+    /// Replacements arriving within [`UPDATE_DEBOUNCE`] of the last forwarded
+    /// one are held back, newer ones replacing older ones.
+    pub fn offer(
+        &mut self,
+        notification: CapturedNotification,
+        now: Instant,
+    ) -> Option<CapturedNotification> {
+        match self.updates.get_mut(&notification.replaces_id) {
+            Some((forwarded_at, held)) if now.duration_since(*forwarded_at) < UPDATE_DEBOUNCE => {
+                *held = Some(notification);
+                None
+            }
+            _ => {
+                self.updates.insert(notification.replaces_id, (now, None));
+                Some(notification)
+            }
+        }
+    }
+
+    /// Take the held back replacements that are due
+    ///
+    /// Notifications that saw no replacement for a debounce period are
+    /// forgotten, so their next replacement is forwarded right away.
+    pub fn take_due(&mut self, now: Instant) -> Vec<CapturedNotification> {
+        let mut due = Vec::new();
+
+        self.updates.retain(|_, (forwarded_at, held)| {
+            if now.duration_since(*forwarded_at) < UPDATE_DEBOUNCE {
+                return true;
+            }
+            match held.take() {
+                Some(notification) => {
+                    *forwarded_at = now;
+                    due.push(notification);
+                    true
+                }
+                None => false,
+            }
+        });
+
+        due
+    }
+
+    /// Drop a held back replacement of a closed notification
+    pub fn close(&mut self, notification_id: u32) {
+        self.updates.remove(&notification_id);
+    }
+}
+
 /// DBus notification listener
 ///
 /// Monitors the session DBus for org.freedesktop.Notifications.Notify calls
-/// and captures notification data.
+/// and captures notification data, along with notifications being closed.
 pub struct NotificationListener {
     config: NotificationListenerConfig,
-    sender: mpsc::UnboundedSender<CapturedNotification>,
+    sender: mpsc::UnboundedSender<NotificationEvent>,
+    /// New notifications waiting for their ID, keyed by (caller, call serial)
+    awaiting_id: HashMap<(String, u32), (Instant, CapturedNotification)>,
+    /// Replacements held back to collapse rapid updates
+    updates: UpdateDebouncer,
 }
 
 impl NotificationListener {
@@ -413,18 +656,28 @@ impl NotificationListener {
     /// ```
     pub async fn new(
         config: NotificationListenerConfig,
-        sender: mpsc::UnboundedSender<CapturedNotification>,
+        sender: mpsc::UnboundedSender<NotificationEvent>,
     ) -> Result<Self> {
         if !config.enabled {
             info!("Notification listener is disabled");
-            return Ok(Self { config, sender });
+            return Ok(Self {
+                config,
+                sender,
+                awaiting_id: HashMap::new(),
+                updates: UpdateDebouncer::new(),
+            });
         }
 
         info!("Starting notification listener");
         debug!("Excluded apps: {:?}", config.excluded_apps);
         debug!("Included apps: {:?}", config.included_apps);
 
-        Ok(Self { config, sender })
+        Ok(Self {
+            config,
+            sender,
+            awaiting_id: HashMap::new(),
+            updates: UpdateDebouncer::new(),
+        })
     }
 
     /// Start listening for notifications
@@ -441,7 +694,7 @@ impl NotificationListener {
     ///     }
     /// });
     /// ```
-    pub async fn listen(self) -> Result<()> {
+    pub async fn listen(mut self) -> Result<()> {
         if !self.config.enabled {
             debug!("Notification listener disabled, not starting");
             return Ok(());
@@ -453,14 +706,13 @@ impl NotificationListener {
 
         info!("Connected to session DBus for notification monitoring");
 
-        // Create match rule for Notify method calls
+        // Create match rule for Notify and CloseNotification method calls
         let match_rule = MatchRule::builder()
             .msg_type(zbus::message::Type::MethodCall)
             .interface("org.freedesktop.Notifications")?
-            .member("Notify")?
             .build();
 
-        let mut stream = zbus::MessageStream::for_match_rule(
+        let calls = zbus::MessageStream::for_match_rule(
             match_rule,
             &connection,
             Some(256), // Buffer size
@@ -468,11 +720,49 @@ impl NotificationListener {
         .await
         .context("Failed to create message stream")?;
 
+        // Notify returns carry the IDs that replaces, closes and replies refer to
+        let return_rule = MatchRule::builder()
+            .msg_type(zbus::message::Type::MethodReturn)
+            .sender("org.freedesktop.Notifications")?
+            .build();
+
+        let returns = zbus::MessageStream::for_match_rule(return_rule, &connection, Some(256))
+            .await
+            .context("Failed to create method return stream")?;
+
+        // Notifications closed by the server (expired, dismissed, or closed by the app)
+        let closed_rule = MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .interface("org.freedesktop.Notifications")?
+            .member("NotificationClosed")?
+            .build();
+
+        let closed = zbus::MessageStream::for_match_rule(closed_rule, &connection, Some(256))
+            .await
+            .context("Failed to create NotificationClosed stream")?;
+
         info!("Notification listener started successfully");
 
         use futures::StreamExt;
-        while let Some(msg_result) = stream.next().await {
+        let mut stream = futures::stream::select(futures::stream::select(calls, returns), closed);
+        loop {
+            let msg_result = match tokio::time::timeout(NOTIFY_ID_TIMEOUT, stream.next()).await {
+                Ok(Some(msg_result)) => msg_result,
+                Ok(None) => break,
+                Err(_) => {
+                    self.flush_awaiting_id();
+                    self.flush_updates();
+                    continue;
+                }
+            };
+
             match msg_result {
+                Ok(msg) if msg.message_type() == zbus::message::Type::MethodReturn => {
+                    self.process_notify_return(&msg);
+                }
+                Ok(msg) if msg.message_type() == zbus::message::Type::Signal => {
+                    self.process_closed_message(&msg);
+                }
                 Ok(msg) => {
                     if let Err(e) = self.process_notification_message(&msg).await {
                         warn!("Failed to process notification: {}", e);
@@ -482,6 +772,9 @@ impl NotificationListener {
                     warn!("Error receiving DBus message: {}", e);
                 }
             }
+
+            self.flush_awaiting_id();
+            self.flush_updates();
         }
 
         warn!("Notification listener stream ended unexpectedly");
@@ -489,9 +782,13 @@ impl NotificationListener {
     }
 
     /// Process a DBus notification message
-    async fn process_notification_message(&self, msg: &zbus::Message) -> Result<()> {
+    async fn process_notification_message(&mut self, msg: &zbus::Message) -> Result<()> {
         // Verify this is a Notify method call
         if let Some(member) = msg.header().member() {
+            if member.as_str() == "CloseNotification" {
+                self.process_closed_message(msg);
+                return Ok(());
+            }
             if member.as_str() != "Notify" {
                 return Ok(());
             }
@@ -505,7 +802,7 @@ impl NotificationListener {
         let notification = self.parse_notification(msg)?;
 
         // Apply filters
-        if !self.config.should_capture_app(&notification.app_name) {
+        if !self.config.captures_app(&notification) {
             trace!(
                 "Skipping notification from excluded app: {}",
                 notification.app_name
@@ -525,14 +822,109 @@ impl NotificationListener {
             notification.urgency()
         );
 
-        // Send to channel
-        if let Err(e) = self.sender.send(notification.clone()) {
-            warn!("Failed to send notification to channel: {}", e);
+        // Replaces, closes and replies need the ID the server is about to assign
+        if notification.notification_id == 0 {
+            let serial = msg.primary_header().serial_num().get();
+            self.awaiting_id.insert(
+                (notification.sender.clone(), serial),
+                (Instant::now(), notification),
+            );
+            return Ok(());
+        }
+
+        if notification.replaces_id != 0 {
+            if let Some(notification) = self.updates.offer(notification, Instant::now()) {
+                self.forward(notification);
+            }
+            return Ok(());
         }
 
+        self.forward(notification);
         Ok(())
     }
 
+    /// Match a Notify method return to a notification awaiting its ID
+    fn process_notify_return(&mut self, msg: &zbus::Message) {
+        let header = msg.header();
+        let (Some(destination), Some(reply_serial)) = (header.destination(), header.reply_serial())
+        else {
+            return;
+        };
+
+        let key = (destination.to_string(), reply_serial.get());
+        let Some((_, mut notification)) = self.awaiting_id.remove(&key) else {
+            return;
+        };
+
+        match msg.body().deserialize::<u32>() {
+            Ok(id) => notification.notification_id = id,
+            Err(e) => debug!("Unexpected Notify return body: {}", e),
+        }
+
+        self.forward(notification);
+    }
+
+    /// Forward notifications whose ID never arrived
+    ///
+    /// They are still forwarded, just without update, dismissal or reply support.
+    fn flush_awaiting_id(&mut self) {
+        let expired: Vec<_> = self
+            .awaiting_id
+            .iter()
+            .filter(|(_, (captured_at, _))| captured_at.elapsed() >= NOTIFY_ID_TIMEOUT)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in expired {
+            if let Some((_, notification)) = self.awaiting_id.remove(&key) {
+                debug!(
+                    "No ID returned for notification from {}, forwarding without an ID",
+                    notification.app_name
+                );
+                self.forward(notification);
+            }
+        }
+    }
+
+    /// Forward held back replacements that are due
+    fn flush_updates(&mut self) {
+        for notification in self.updates.take_due(Instant::now()) {
+            self.forward(notification);
+        }
+    }
+
+    /// Handle a `CloseNotification` call or `NotificationClosed` signal
+    ///
+    /// Both start with the ID of the closed notification.
+    fn process_closed_message(&mut self, msg: &zbus::Message) {
+        let body = msg.body();
+        let id = match msg.message_type() {
+            zbus::message::Type::Signal => body.deserialize::<(u32, u32)>().map(|(id, _)| id),
+            _ => body.deserialize::<u32>(),
+        };
+
+        match id {
+            Ok(id) => {
+                trace!("Notification {} closed", id);
+                self.updates.close(id);
+                self.send(NotificationEvent::Closed(id));
+            }
+            Err(e) => debug!("Unexpected notification close body: {}", e),
+        }
+    }
+
+    /// Send a captured notification to the channel
+    fn forward(&self, notification: CapturedNotification) {
+        self.send(NotificationEvent::Posted(notification));
+    }
+
+    /// Send an event to the channel
+    fn send(&self, event: NotificationEvent) {
+        if let Err(e) = self.sender.send(event) {
+            warn!("Failed to send notification event to channel: {}", e);
+        }
+    }
+
     /// Parse notification parameters from DBus message
     #[allow(clippy::type_complexity)] // DBus tuple type is inherently complex
     fn parse_notification(&self, msg: &zbus::Message) -> Result<CapturedNotification> {
@@ -572,6 +964,12 @@ impl NotificationListener {
         // Truncate body if configured
         let body_text = self.config.truncate_body(body_text);
 
+        let sender = msg
+            .header()
+            .sender()
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+
         // Get current timestamp
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -581,6 +979,7 @@ impl NotificationListener {
         Ok(CapturedNotification {
             app_name,
             notification_id: replaces_id,
+            replaces_id,
             app_icon,
             summary,
             body: body_text,
@@ -588,6 +987,7 @@ impl NotificationListener {
             hints,
             timeout,
             timestamp,
+            sender,
         })
     }
 
@@ -724,6 +1124,123 @@ mod tests {
         assert!(!config.should_capture_app("Chrome"));
     }
 
+    fn rule(app: &str, action: Option<CaptureAction>) -> AppCaptureRule {
+        AppCaptureRule {
+            app: app.to_string(),
+            action,
+            include_transient: None,
+            include_low_urgency: None,
+        }
+    }
+
+    fn notification_from(app_name: &str) -> CapturedNotification {
+        CapturedNotification {
+            app_name: app_name.to_string(),
+            ..create_test_notification()
+        }
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("Firefox*", "Firefox"));
+        assert!(wildcard_match("Firefox*", "Firefox Developer Edition"));
+        assert!(wildcard_match("*firefox*", "Mozilla Firefox"));
+        assert!(wildcard_match("Thunderbir?", "thunderbird"));
+        assert!(wildcard_match("*", ""));
+        assert!(!wildcard_match("Firefox*", "Mozilla Firefox"));
+        assert!(!wildcard_match("Thunderbir?", "Thunderbirds"));
+        assert!(!wildcard_match("", "Slack"));
+    }
+
+    #[test]
+    fn test_app_rules_later_rules_override() {
+        let mut config = NotificationListenerConfig {
+            app_rules: vec![rule("Firefox*", Some(CaptureAction::Exclude))],
+            ..Default::default()
+        };
+
+        assert!(!config.captures_app(&notification_from("Firefox")));
+        assert!(!config.captures_app(&notification_from("Firefox Developer Edition")));
+        assert!(config.captures_app(&notification_from("Thunderbird")));
+
+        config
+            .app_rules
+            .push(rule("firefox developer*", Some(CaptureAction::Include)));
+
+        assert!(!config.captures_app(&notification_from("Firefox")));
+        assert!(config.captures_app(&notification_from("Firefox Developer Edition")));
+    }
+
+    #[test]
+    fn test_app_rules_match_desktop_entry() {
+        let config = NotificationListenerConfig {
+            app_rules: vec![rule("org.mozilla.*", Some(CaptureAction::Exclude))],
+            ..Default::default()
+        };
+
+        let mut notification = notification_from("Mozilla Firefox");
+        assert!(config.captures_app(&notification));
+
+        notification.hints.insert(
+            "desktop-entry".to_string(),
+            HintValue::String("org.mozilla.firefox".to_string()),
+        );
+        assert!(!config.captures_app(&notification));
+    }
+
+    #[test]
+    fn test_app_rules_include_over_app_lists() {
+        let config = NotificationListenerConfig {
+            included_apps: vec!["Firefox".to_string()],
+            app_rules: vec![
+                rule("Slack", None),
+                rule("Thunderbird", Some(CaptureAction::Include)),
+            ],
+            ..Default::default()
+        };
+
+        assert!(config.captures_app(&notification_from("Firefox")));
+        assert!(config.captures_app(&notification_from("Thunderbird")));
+        // A rule without an action leaves the app lists' decision alone
+        assert!(!config.captures_app(&notification_from("Slack")));
+    }
+
+    #[test]
+    fn test_app_rules_override_hint_filters() {
+        let config = NotificationListenerConfig {
+            include_low_urgency: false,
+            app_rules: vec![
+                AppCaptureRule {
+                    include_low_urgency: Some(true),
+                    ..rule("Calendar", None)
+                },
+                AppCaptureRule {
+                    include_transient: Some(false),
+                    ..rule("Spotify", None)
+                },
+            ],
+            ..Default::default()
+        };
+
+        let mut low_urgency = notification_from("Calendar");
+        low_urgency
+            .hints
+            .insert("urgency".to_string(), HintValue::Byte(0));
+        assert!(config.should_capture_notification(&low_urgency));
+
+        low_urgency.app_name = "Chat".to_string();
+        assert!(!config.should_capture_notification(&low_urgency));
+
+        let mut transient = notification_from("Spotify");
+        transient
+            .hints
+            .insert("transient".to_string(), HintValue::Boolean(true));
+        assert!(!config.should_capture_notification(&transient));
+
+        transient.app_name = "Chat".to_string();
+        assert!(config.should_capture_notification(&transient));
+    }
+
     #[test]
     fn test_truncate_body() {
         let config = NotificationListenerConfig {
@@ -963,11 +1480,79 @@ mod tests {
         assert_eq!(rich_data.image_data.unwrap().width, 128);
     }
 
+    #[test]
+    fn test_captured_notification_is_repliable() {
+        let mut notification = create_test_notification();
+        assert!(!notification.is_repliable());
+
+        notification
+            .actions
+            .push((REPLY_ACTION_ID.to_string(), "Reply".to_string()));
+        assert!(notification.is_repliable());
+        assert_eq!(notification.reply_placeholder(), None);
+
+        let mut notification = create_test_notification();
+        notification.hints.insert(
+            REPLY_PLACEHOLDER_HINT.to_string(),
+            HintValue::String("Reply to Alice".to_string()),
+        );
+        assert!(notification.is_repliable());
+        assert_eq!(notification.reply_placeholder(), Some("Reply to Alice"));
+    }
+
+    fn replacement(id: u32, body: &str) -> CapturedNotification {
+        let mut notification = create_test_notification();
+        notification.notification_id = id;
+        notification.replaces_id = id;
+        notification.body = body.to_string();
+        notification
+    }
+
+    #[test]
+    fn test_only_repeated_updates_debounced() {
+        let mut debouncer = UpdateDebouncer::new();
+        let start = Instant::now();
+
+        // The first replacement goes out right away
+        assert!(debouncer.offer(replacement(7, "10%"), start).is_some());
+
+        // Rapid follow-ups collapse into the latest one
+        let soon = start + UPDATE_DEBOUNCE / 2;
+        assert!(debouncer.offer(replacement(7, "20%"), soon).is_none());
+        assert!(debouncer.offer(replacement(7, "30%"), soon).is_none());
+        assert!(debouncer.take_due(soon).is_empty());
+
+        // Another notification is not held back by it
+        assert!(debouncer.offer(replacement(8, "Hi"), soon).is_some());
+
+        let due = debouncer.take_due(start + UPDATE_DEBOUNCE);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].body, "30%");
+
+        // Once quiet, the next replacement goes out right away again
+        let later = start + UPDATE_DEBOUNCE * 3;
+        assert!(debouncer.take_due(later).is_empty());
+        assert!(debouncer.offer(replacement(7, "100%"), later).is_some());
+    }
+
+    #[test]
+    fn test_closed_notification_drops_held_update() {
+        let mut debouncer = UpdateDebouncer::new();
+        let start = Instant::now();
+
+        debouncer.offer(replacement(7, "10%"), start);
+        debouncer.offer(replacement(7, "20%"), start);
+        debouncer.close(7);
+
+        assert!(debouncer.take_due(start + UPDATE_DEBOUNCE).is_empty());
+    }
+
     // Helper function to create test notification
     fn create_test_notification() -> CapturedNotification {
         CapturedNotification {
             app_name: "TestApp".to_string(),
             notification_id: 1,
+            replaces_id: 0,
             app_icon: "test-icon".to_string(),
             summary: "Test Summary".to_string(),
             body: "Test body".to_string(),
@@ -975,6 +1560,7 @@ mod tests {
             hints: HashMap::new(),
             timeout: 5000,
             timestamp: 1234567890,
+            sender: ":1.42".to_string(),
         }
     }
 }
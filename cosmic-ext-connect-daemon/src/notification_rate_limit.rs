@@ -0,0 +1,217 @@
+//! Notification Rate Limiting
+//!
+//! Caps how many desktop notifications are forwarded within a sliding window,
+//! both in total and per device, so a misbehaving app or a forwarding loop
+//! cannot flood the phone.
+//!
+//! Notifications over the limit are dropped and counted. Once the window that
+//! started with the first dropped notification has passed, a single summary
+//! with the number of suppressed notifications is due for that device.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// App name used for suppression summary notifications
+pub const SUMMARY_APP_NAME: &str = "COSMIC Connect";
+
+/// Suppressed notifications for a device awaiting a summary
+#[derive(Debug, Clone, Copy)]
+struct Suppression {
+    /// When the first notification was dropped
+    since: Instant,
+
+    /// Number of dropped notifications
+    count: u32,
+}
+
+/// Sliding window rate limiter for forwarded notifications
+///
+/// A limit of 0 disables that limit.
+#[derive(Debug)]
+pub struct NotificationRateLimiter {
+    /// Length of the sliding window
+    window: Duration,
+
+    /// Maximum notifications forwarded to all devices per window
+    global_limit: u32,
+
+    /// Forward times across all devices
+    global_sent: VecDeque<Instant>,
+
+    /// Forward times per device
+    device_sent: HashMap<String, VecDeque<Instant>>,
+
+    /// Dropped notifications per device
+    suppressed: HashMap<String, Suppression>,
+}
+
+/// Drop timestamps that fell out of the window ending at `now`
+fn prune(sent: &mut VecDeque<Instant>, window: Duration, now: Instant) {
+    while let Some(&oldest) = sent.front() {
+        if now.duration_since(oldest) < window {
+            break;
+        }
+        sent.pop_front();
+    }
+}
+
+impl NotificationRateLimiter {
+    /// Create a rate limiter with the given window and global limit
+    pub fn new(window: Duration, global_limit: u32) -> Self {
+        Self {
+            window,
+            global_limit,
+            global_sent: VecDeque::new(),
+            device_sent: HashMap::new(),
+            suppressed: HashMap::new(),
+        }
+    }
+
+    /// Check whether a notification may be forwarded to a device
+    ///
+    /// `device_limit` is the maximum number of notifications forwarded to
+    /// this device per window. Allowed notifications are recorded; dropped
+    /// ones are counted towards the device's suppression summary.
+    pub fn allow(&mut self, device_id: &str, device_limit: u32) -> bool {
+        self.allow_at(device_id, device_limit, Instant::now())
+    }
+
+    /// Take the suppression summaries whose window has passed
+    ///
+    /// Returns the device ID and number of dropped notifications. Each
+    /// suppression period yields exactly one summary.
+    pub fn take_due_summaries(&mut self) -> Vec<(String, u32)> {
+        self.take_due_summaries_at(Instant::now())
+    }
+
+    fn allow_at(&mut self, device_id: &str, device_limit: u32, now: Instant) -> bool {
+        let window = self.window;
+        prune(&mut self.global_sent, window, now);
+        let device_sent = self.device_sent.entry(device_id.to_string()).or_default();
+        prune(device_sent, window, now);
+
+        let global_full =
+            self.global_limit > 0 && self.global_sent.len() >= self.global_limit as usize;
+        let device_full = device_limit > 0 && device_sent.len() >= device_limit as usize;
+
+        if global_full || device_full {
+            let suppression = self
+                .suppressed
+                .entry(device_id.to_string())
+                .or_insert_with(|| {
+                    warn!(
+                        "Notification rate limit reached for device {}, suppressing",
+                        device_id
+                    );
+                    Suppression {
+                        since: now,
+                        count: 0,
+                    }
+                });
+            suppression.count += 1;
+            return false;
+        }
+
+        device_sent.push_back(now);
+        self.global_sent.push_back(now);
+        true
+    }
+
+    fn take_due_summaries_at(&mut self, now: Instant) -> Vec<(String, u32)> {
+        let window = self.window;
+        let due: Vec<String> = self
+            .suppressed
+            .iter()
+            .filter(|(_, s)| now.duration_since(s.since) >= window)
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        due.into_iter()
+            .filter_map(|id| {
+                let suppression = self.suppressed.remove(&id)?;
+                debug!(
+                    "{} notifications suppressed for device {}",
+                    suppression.count, id
+                );
+                Some((id, suppression.count))
+            })
+            .collect()
+    }
+}
+
+/// Body text of a suppression summary
+pub fn summary_text(count: u32) -> String {
+    if count == 1 {
+        "1 notification suppressed".to_string()
+    } else {
+        format!("{} notifications suppressed", count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_excess_dropped_with_single_summary() {
+        let mut limiter = NotificationRateLimiter::new(WINDOW, 0);
+        let start = Instant::now();
+
+        for i in 0..3 {
+            assert!(limiter.allow_at("phone", 3, start + Duration::from_secs(i)));
+        }
+        for i in 3..10 {
+            assert!(!limiter.allow_at("phone", 3, start + Duration::from_secs(i)));
+        }
+
+        // No summary until the suppression window has passed
+        assert!(limiter
+            .take_due_summaries_at(start + Duration::from_secs(30))
+            .is_empty());
+
+        let summaries = limiter.take_due_summaries_at(start + WINDOW + Duration::from_secs(3));
+        assert_eq!(summaries, vec![("phone".to_string(), 7)]);
+        assert!(limiter.take_due_summaries_at(start + WINDOW * 2).is_empty());
+    }
+
+    #[test]
+    fn test_window_slides() {
+        let mut limiter = NotificationRateLimiter::new(WINDOW, 0);
+        let start = Instant::now();
+
+        assert!(limiter.allow_at("phone", 2, start));
+        assert!(limiter.allow_at("phone", 2, start + Duration::from_secs(10)));
+        assert!(!limiter.allow_at("phone", 2, start + Duration::from_secs(20)));
+
+        // The first notification left the window, making room for one more
+        assert!(limiter.allow_at("phone", 2, start + WINDOW));
+        assert!(!limiter.allow_at("phone", 2, start + WINDOW + Duration::from_secs(1)));
+
+        // Other devices have their own budget
+        assert!(limiter.allow_at("tablet", 2, start + WINDOW));
+    }
+
+    #[test]
+    fn test_global_limit_applies_across_devices() {
+        let mut limiter = NotificationRateLimiter::new(WINDOW, 3);
+        let start = Instant::now();
+
+        assert!(limiter.allow_at("phone", 0, start));
+        assert!(limiter.allow_at("tablet", 0, start));
+        assert!(limiter.allow_at("phone", 0, start));
+        assert!(!limiter.allow_at("tablet", 0, start));
+        assert!(!limiter.allow_at("tablet", 0, start));
+
+        let summaries = limiter.take_due_summaries_at(start + WINDOW);
+        assert_eq!(summaries, vec![("tablet".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_summary_text() {
+        assert_eq!(summary_text(1), "1 notification suppressed");
+        assert_eq!(summary_text(12), "12 notifications suppressed");
+    }
+}
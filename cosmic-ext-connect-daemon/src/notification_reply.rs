@@ -0,0 +1,251 @@
+//! Notification Replies
+//!
+//! Routes replies typed on a remote device back to the desktop notification
+//! they answer. Repliable notifications are forwarded with a `requestReplyId`
+//! (the forwarded notification key); when the device sends a
+//! `cconnect.notification.reply` with that ID, the reply is delivered to the
+//! originating app the way KDE Plasma's notification server does it: a
+//! `NotificationReplied` signal followed by `ActionInvoked` for the
+//! `inline-reply` action, both addressed to the app's bus name.
+//!
+//! Replies are only delivered to the connection that created the
+//! notification: the target must be a unique bus name that is still
+//! connected. Unique names are never reused, so a reply can not reach
+//! another process after the app exits.
+
+use anyhow::{Context, Result};
+use cosmic_ext_connect_protocol::Packet;
+use std::collections::{HashMap, VecDeque};
+use zbus::names::{BusName, UniqueName};
+use zbus::Connection;
+
+/// Maximum number of forwarded notifications kept for reply routing
+pub const MAX_REPLY_TARGETS: usize = 200;
+
+/// Object path of the freedesktop notification service
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+
+/// Interface of the freedesktop notification service
+const NOTIFICATIONS_INTERFACE: &str = "org.freedesktop.Notifications";
+
+/// Desktop notification a forwarded notification's replies are delivered to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplyTarget {
+    /// ID assigned by the notification server
+    pub notification_id: u32,
+
+    /// Unique bus name of the application that sent the notification
+    pub sender: String,
+
+    /// Action invoked once the reply text has been delivered
+    pub action_id: String,
+}
+
+/// Reply targets of recently forwarded notifications
+#[derive(Debug, Default)]
+pub struct NotificationReplies {
+    /// Reply targets keyed by `requestReplyId`
+    targets: HashMap<String, ReplyTarget>,
+
+    /// Registration order, oldest first, for eviction
+    order: VecDeque<String>,
+}
+
+impl NotificationReplies {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember where replies to a forwarded notification should go
+    ///
+    /// The oldest target is dropped once [`MAX_REPLY_TARGETS`] is exceeded.
+    pub fn register(&mut self, request_reply_id: &str, target: ReplyTarget) {
+        if self
+            .targets
+            .insert(request_reply_id.to_string(), target)
+            .is_none()
+        {
+            self.order.push_back(request_reply_id.to_string());
+        }
+
+        while self.order.len() > MAX_REPLY_TARGETS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.targets.remove(&oldest);
+            }
+        }
+    }
+
+    /// Look up the target and reply text of a `cconnect.notification.reply` packet
+    pub fn resolve<'a>(&self, packet: &'a Packet) -> Option<(ReplyTarget, &'a str)> {
+        if !packet.is_type("cconnect.notification.reply") {
+            return None;
+        }
+
+        let request_reply_id = packet.body.get("requestReplyId")?.as_str()?;
+        let message = packet.body.get("message")?.as_str()?;
+        let target = self.targets.get(request_reply_id)?;
+
+        Some((target.clone(), message))
+    }
+}
+
+impl ReplyTarget {
+    /// Unique bus name of the connection that created the notification
+    ///
+    /// `None` if the sender was not captured as a unique name.
+    pub fn unique_sender(&self) -> Option<UniqueName<'_>> {
+        UniqueName::try_from(self.sender.as_str()).ok()
+    }
+}
+
+/// Deliver reply text to the application that sent the notification
+pub async fn deliver_reply(
+    connection: &Connection,
+    target: &ReplyTarget,
+    message: &str,
+) -> Result<()> {
+    let sender = target.unique_sender().with_context(|| {
+        format!(
+            "Notification sender {:?} is not a unique name",
+            target.sender
+        )
+    })?;
+
+    let has_owner = zbus::fdo::DBusProxy::new(connection)
+        .await
+        .context("Failed to create DBus proxy")?
+        .name_has_owner(BusName::from(sender.clone()))
+        .await
+        .context("Failed to look up notification sender")?;
+    if !has_owner {
+        anyhow::bail!("Notification sender {} is no longer connected", sender);
+    }
+
+    connection
+        .emit_signal(
+            Some(sender.as_str()),
+            NOTIFICATIONS_PATH,
+            NOTIFICATIONS_INTERFACE,
+            "NotificationReplied",
+            &(target.notification_id, message),
+        )
+        .await
+        .context("Failed to emit NotificationReplied")?;
+
+    connection
+        .emit_signal(
+            Some(sender.as_str()),
+            NOTIFICATIONS_PATH,
+            NOTIFICATIONS_INTERFACE,
+            "ActionInvoked",
+            &(target.notification_id, target.action_id.as_str()),
+        )
+        .await
+        .context("Failed to emit ActionInvoked")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn target(notification_id: u32) -> ReplyTarget {
+        ReplyTarget {
+            notification_id,
+            sender: ":1.42".to_string(),
+            action_id: "inline-reply".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_reply_packet_maps_to_action() {
+        let mut replies = NotificationReplies::new();
+        replies.register("desktop-Chat-1", target(7));
+        replies.register("desktop-Chat-2", target(8));
+
+        let packet = Packet::new(
+            "cconnect.notification.reply",
+            json!({ "requestReplyId": "desktop-Chat-2", "message": "On my way" }),
+        );
+
+        let (resolved, message) = replies.resolve(&packet).unwrap();
+        assert_eq!(resolved.notification_id, 8);
+        assert_eq!(resolved.action_id, "inline-reply");
+        assert_eq!(message, "On my way");
+    }
+
+    #[test]
+    fn test_kdeconnect_reply_packet_resolves() {
+        let mut replies = NotificationReplies::new();
+        replies.register("desktop-Chat-1", target(7));
+
+        let packet = Packet::new(
+            "kdeconnect.notification.reply",
+            json!({ "requestReplyId": "desktop-Chat-1", "message": "Yes" }),
+        );
+
+        assert!(replies.resolve(&packet).is_some());
+    }
+
+    #[test]
+    fn test_unknown_or_malformed_replies_ignored() {
+        let mut replies = NotificationReplies::new();
+        replies.register("desktop-Chat-1", target(7));
+
+        let unknown = Packet::new(
+            "cconnect.notification.reply",
+            json!({ "requestReplyId": "desktop-Other-1", "message": "Hi" }),
+        );
+        let no_message = Packet::new(
+            "cconnect.notification.reply",
+            json!({ "requestReplyId": "desktop-Chat-1" }),
+        );
+        let wrong_type = Packet::new(
+            "cconnect.notification.action",
+            json!({ "requestReplyId": "desktop-Chat-1", "message": "Hi" }),
+        );
+
+        assert!(replies.resolve(&unknown).is_none());
+        assert!(replies.resolve(&no_message).is_none());
+        assert!(replies.resolve(&wrong_type).is_none());
+    }
+
+    #[test]
+    fn test_only_unique_senders_targeted() {
+        assert_eq!(target(7).unique_sender().unwrap().as_str(), ":1.42");
+
+        let mut well_known = target(7);
+        well_known.sender = "org.example.Chat".to_string();
+        assert!(well_known.unique_sender().is_none());
+
+        let mut missing = target(7);
+        missing.sender = String::new();
+        assert!(missing.unique_sender().is_none());
+    }
+
+    #[test]
+    fn test_oldest_targets_evicted() {
+        let mut replies = NotificationReplies::new();
+        for i in 0..=MAX_REPLY_TARGETS {
+            replies.register(&format!("desktop-Chat-{}", i), target(i as u32));
+        }
+
+        let oldest = Packet::new(
+            "cconnect.notification.reply",
+            json!({ "requestReplyId": "desktop-Chat-0", "message": "Hi" }),
+        );
+        let newest = Packet::new(
+            "cconnect.notification.reply",
+            json!({
+                "requestReplyId": format!("desktop-Chat-{}", MAX_REPLY_TARGETS),
+                "message": "Hi"
+            }),
+        );
+
+        assert!(replies.resolve(&oldest).is_none());
+        assert!(replies.resolve(&newest).is_some());
+    }
+}
@@ -0,0 +1,240 @@
+//! Notification Snoozing
+//!
+//! Lets the user temporarily stop forwarding desktop notifications from a
+//! chatty application without editing `excluded_apps` in the config.
+//!
+//! Snoozes are keyed by `app_name` and store an absolute expiry (UNIX seconds),
+//! so they survive daemon restarts and clear themselves once the expiry passes.
+//! Forwarded notifications carry a "Snooze app" action; when the remote device
+//! triggers it, the originating app is snoozed for [`DEFAULT_SNOOZE_DURATION`].
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info};
+
+/// Action ID attached to forwarded notifications for snoozing the sender app
+pub const SNOOZE_ACTION_ID: &str = "cconnect-snooze-app";
+
+/// Label shown on the remote device for the snooze action
+pub const SNOOZE_ACTION_LABEL: &str = "Snooze app for 1 hour";
+
+/// Snooze duration used when triggered from a forwarded notification
+pub const DEFAULT_SNOOZE_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// Prefix of notification keys generated for forwarded desktop notifications
+const DESKTOP_NOTIFICATION_KEY_PREFIX: &str = "desktop-";
+
+/// Current UNIX time in seconds
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Extract the app name from a forwarded desktop notification key
+///
+/// Keys are generated as `desktop-{app_name}-{timestamp}` by
+/// `NotificationPlugin::create_desktop_notification_packet`.
+pub fn app_name_from_notification_key(key: &str) -> Option<&str> {
+    let rest = key.strip_prefix(DESKTOP_NOTIFICATION_KEY_PREFIX)?;
+    let (app_name, timestamp) = rest.rsplit_once('-')?;
+    if app_name.is_empty() || timestamp.parse::<i64>().is_err() {
+        return None;
+    }
+    Some(app_name)
+}
+
+/// Registry of snoozed applications with persistence
+///
+/// Maps app name to the UNIX time (seconds) at which the snooze expires.
+pub struct NotificationSnoozes {
+    /// Snooze expiry per app name
+    snoozes: HashMap<String, u64>,
+
+    /// Path to the snooze state file
+    snooze_path: PathBuf,
+}
+
+impl NotificationSnoozes {
+    /// Create an empty snooze registry stored in `data_dir`
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            snoozes: HashMap::new(),
+            snooze_path: data_dir.join("notification_snoozes.json"),
+        }
+    }
+
+    /// Load snoozes from disk, dropping any that have already expired
+    pub fn load(&mut self) -> Result<()> {
+        if !self.snooze_path.exists() {
+            debug!("Notification snooze file not found, starting with no snoozes");
+            return Ok(());
+        }
+
+        let contents = fs::read_to_string(&self.snooze_path)
+            .context("Failed to read notification snooze file")?;
+        self.snoozes =
+            serde_json::from_str(&contents).context("Failed to parse notification snoozes")?;
+
+        if self.clear_expired_at(now_secs()) > 0 {
+            self.save()?;
+        }
+        info!("Loaded {} active notification snoozes", self.snoozes.len());
+
+        Ok(())
+    }
+
+    /// Save snoozes to disk
+    pub fn save(&self) -> Result<()> {
+        let contents = serde_json::to_string_pretty(&self.snoozes)
+            .context("Failed to serialize notification snoozes")?;
+
+        fs::write(&self.snooze_path, contents)
+            .context("Failed to write notification snooze file")?;
+
+        debug!("Saved {} notification snoozes", self.snoozes.len());
+
+        Ok(())
+    }
+
+    /// Snooze forwarding for `app_name` for `duration`, returning the expiry
+    ///
+    /// Snoozing an already snoozed app replaces its expiry.
+    pub fn snooze_app(&mut self, app_name: &str, duration: Duration) -> Result<u64> {
+        let until = self.snooze_app_at(app_name, duration, now_secs());
+        self.save()?;
+        info!(
+            "Snoozed notifications from '{}' for {}s",
+            app_name,
+            duration.as_secs()
+        );
+        Ok(until)
+    }
+
+    /// Remove the snooze for `app_name`, returning whether one existed
+    pub fn unsnooze_app(&mut self, app_name: &str) -> Result<bool> {
+        let removed = self.snoozes.remove(app_name).is_some();
+        if removed {
+            self.save()?;
+            info!("Cleared notification snooze for '{}'", app_name);
+        }
+        Ok(removed)
+    }
+
+    /// Check whether notifications from `app_name` are currently snoozed
+    pub fn is_snoozed(&self, app_name: &str) -> bool {
+        self.is_snoozed_at(app_name, now_secs())
+    }
+
+    /// Active snoozes as app name -> expiry (UNIX seconds)
+    pub fn active_snoozes(&self) -> HashMap<String, u64> {
+        let now = now_secs();
+        self.snoozes
+            .iter()
+            .filter(|(_, until)| **until > now)
+            .map(|(app, until)| (app.clone(), *until))
+            .collect()
+    }
+
+    /// Drop expired snoozes and persist if anything changed
+    pub fn clear_expired(&mut self) -> Result<usize> {
+        let cleared = self.clear_expired_at(now_secs());
+        if cleared > 0 {
+            self.save()?;
+        }
+        Ok(cleared)
+    }
+
+    fn snooze_app_at(&mut self, app_name: &str, duration: Duration, now: u64) -> u64 {
+        let until = now.saturating_add(duration.as_secs());
+        self.snoozes.insert(app_name.to_string(), until);
+        until
+    }
+
+    fn is_snoozed_at(&self, app_name: &str, now: u64) -> bool {
+        self.snoozes.get(app_name).is_some_and(|until| *until > now)
+    }
+
+    fn clear_expired_at(&mut self, now: u64) -> usize {
+        let before = self.snoozes.len();
+        self.snoozes.retain(|_, until| *until > now);
+        before - self.snoozes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_registry(name: &str) -> (NotificationSnoozes, PathBuf) {
+        let temp_dir = std::env::temp_dir().join(format!("cconnect-snooze-test-{}", name));
+        fs::create_dir_all(&temp_dir).unwrap();
+        (NotificationSnoozes::new(&temp_dir), temp_dir)
+    }
+
+    #[test]
+    fn test_snoozed_app_suppressed_until_expiry() {
+        let (mut snoozes, temp_dir) = temp_registry("expiry");
+        let now = 1_000_000;
+
+        let until = snoozes.snooze_app_at("Slack", Duration::from_secs(600), now);
+        assert_eq!(until, now + 600);
+
+        assert!(snoozes.is_snoozed_at("Slack", now));
+        assert!(snoozes.is_snoozed_at("Slack", now + 599));
+        assert!(!snoozes.is_snoozed_at("Firefox", now));
+
+        // Resumes once the snooze expires
+        assert!(!snoozes.is_snoozed_at("Slack", now + 600));
+        assert_eq!(snoozes.clear_expired_at(now + 600), 1);
+        assert!(snoozes.snoozes.is_empty());
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_snooze_persists_across_restarts() {
+        let (mut snoozes, temp_dir) = temp_registry("persist");
+
+        snoozes
+            .snooze_app("Slack", Duration::from_secs(3600))
+            .unwrap();
+        // Already-expired entry should be dropped on load
+        snoozes.snooze_app_at("Discord", Duration::from_secs(0), 0);
+        snoozes.save().unwrap();
+
+        let mut reloaded = NotificationSnoozes::new(&temp_dir);
+        reloaded.load().unwrap();
+        assert!(reloaded.is_snoozed("Slack"));
+        assert!(!reloaded.snoozes.contains_key("Discord"));
+        assert_eq!(reloaded.active_snoozes().len(), 1);
+
+        assert!(reloaded.unsnooze_app("Slack").unwrap());
+        assert!(!reloaded.is_snoozed("Slack"));
+        assert!(!reloaded.unsnooze_app("Slack").unwrap());
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_app_name_from_notification_key() {
+        assert_eq!(
+            app_name_from_notification_key("desktop-Slack-1700000000000"),
+            Some("Slack")
+        );
+        assert_eq!(
+            app_name_from_notification_key("desktop-gnome-calendar-1700000000000"),
+            Some("gnome-calendar")
+        );
+        assert_eq!(
+            app_name_from_notification_key("desktop--1700000000000"),
+            None
+        );
+        assert_eq!(app_name_from_notification_key("desktop-Slack"), None);
+        assert_eq!(app_name_from_notification_key("0|com.android.phone"), None);
+    }
+}
@@ -0,0 +1,192 @@
+//! Active Notification Tracking
+//!
+//! Remembers which remote notification each forwarded desktop notification
+//! became, keyed by `(app_name, notification_id)`. A `Notify` call that
+//! replaces an earlier notification is forwarded under the same remote ID, so
+//! the device updates it in place instead of showing a duplicate, and closing
+//! a desktop notification maps back to the remote notification to dismiss.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::notification_listener::CapturedNotification;
+
+/// Maximum number of forwarded notifications tracked
+pub const MAX_ACTIVE_NOTIFICATIONS: usize = 500;
+
+/// Remote ID a notification is forwarded under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteNotification {
+    /// Notification ID on the remote device
+    pub id: String,
+
+    /// Whether this replaces a notification already on the device
+    pub is_update: bool,
+}
+
+/// Forwarded notifications that are still open on the desktop
+#[derive(Debug, Default)]
+pub struct ActiveNotifications {
+    /// Remote IDs keyed by app name and server-assigned notification ID
+    remote_ids: HashMap<(String, u32), String>,
+
+    /// Tracking order, oldest first, for eviction
+    order: VecDeque<(String, u32)>,
+}
+
+impl ActiveNotifications {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pick the remote ID to forward a notification under
+    ///
+    /// Replacements of a tracked notification reuse its remote ID; anything
+    /// else is forwarded as `new_id`. Notifications without a server-assigned
+    /// ID cannot be replaced or closed later, so they are not tracked.
+    pub fn assign(
+        &mut self,
+        notification: &CapturedNotification,
+        new_id: &str,
+    ) -> RemoteNotification {
+        let key = (notification.app_name.clone(), notification.notification_id);
+
+        if notification.replaces_id != 0 {
+            if let Some(id) = self.remote_ids.get(&key) {
+                return RemoteNotification {
+                    id: id.clone(),
+                    is_update: true,
+                };
+            }
+        }
+
+        if notification.notification_id != 0 {
+            if self
+                .remote_ids
+                .insert(key.clone(), new_id.to_string())
+                .is_none()
+            {
+                self.order.push_back(key);
+            }
+
+            while self.order.len() > MAX_ACTIVE_NOTIFICATIONS {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.remote_ids.remove(&oldest);
+                }
+            }
+        }
+
+        RemoteNotification {
+            id: new_id.to_string(),
+            is_update: false,
+        }
+    }
+
+    /// Stop tracking a closed notification
+    ///
+    /// Returns the remote ID to dismiss, or `None` if the notification was
+    /// never forwarded or has already been closed.
+    pub fn close(&mut self, notification_id: u32) -> Option<String> {
+        let key = self
+            .remote_ids
+            .keys()
+            .find(|(_, id)| *id == notification_id)?
+            .clone();
+
+        self.order.retain(|k| *k != key);
+        self.remote_ids.remove(&key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmic_ext_connect_protocol::plugins::notification::NotificationPlugin;
+
+    fn notification(notification_id: u32, replaces_id: u32) -> CapturedNotification {
+        CapturedNotification {
+            app_name: "Chat".to_string(),
+            notification_id,
+            replaces_id,
+            app_icon: String::new(),
+            summary: "Alice".to_string(),
+            body: "Hi".to_string(),
+            actions: vec![],
+            hints: HashMap::new(),
+            timeout: -1,
+            timestamp: 1704067200000,
+            sender: ":1.42".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_replace_maps_to_same_remote_id() {
+        let mut active = ActiveNotifications::new();
+
+        let first = active.assign(&notification(7, 0), "desktop-Chat-1");
+        assert_eq!(first.id, "desktop-Chat-1");
+        assert!(!first.is_update);
+
+        let replaced = active.assign(&notification(7, 7), "desktop-Chat-2");
+        assert_eq!(replaced.id, "desktop-Chat-1");
+        assert!(replaced.is_update);
+
+        // Another app's notification with the same ID is unrelated
+        let mut other = notification(7, 7);
+        other.app_name = "Mail".to_string();
+        assert_eq!(active.assign(&other, "desktop-Mail-3").id, "desktop-Mail-3");
+    }
+
+    #[test]
+    fn test_replace_of_unknown_notification_is_new() {
+        let mut active = ActiveNotifications::new();
+
+        let remote = active.assign(&notification(9, 9), "desktop-Chat-1");
+        assert_eq!(remote.id, "desktop-Chat-1");
+        assert!(!remote.is_update);
+
+        // ...and later replacements update it
+        let replaced = active.assign(&notification(9, 9), "desktop-Chat-2");
+        assert_eq!(replaced.id, "desktop-Chat-1");
+        assert!(replaced.is_update);
+    }
+
+    #[test]
+    fn test_close_emits_dismissal() {
+        let mut active = ActiveNotifications::new();
+        active.assign(&notification(7, 0), "desktop-Chat-1");
+
+        let remote_id = active.close(7).unwrap();
+        let packet = NotificationPlugin::new().create_desktop_dismissed_packet(&remote_id);
+        assert_eq!(packet.packet_type, "cconnect.notification.dismissed");
+        assert_eq!(packet.body["id"], "desktop-Chat-1");
+
+        // Closing twice (CloseNotification, then NotificationClosed) dismisses once
+        assert_eq!(active.close(7), None);
+        assert_eq!(active.close(8), None);
+
+        // A closed notification's ID may be reused for a new one
+        let reused = active.assign(&notification(7, 7), "desktop-Chat-2");
+        assert_eq!(reused.id, "desktop-Chat-2");
+        assert!(!reused.is_update);
+    }
+
+    #[test]
+    fn test_untracked_without_id() {
+        let mut active = ActiveNotifications::new();
+        active.assign(&notification(0, 0), "desktop-Chat-1");
+
+        assert_eq!(active.close(0), None);
+    }
+
+    #[test]
+    fn test_oldest_notifications_evicted() {
+        let mut active = ActiveNotifications::new();
+        for i in 1..=MAX_ACTIVE_NOTIFICATIONS as u32 + 1 {
+            active.assign(&notification(i, 0), &format!("desktop-Chat-{}", i));
+        }
+
+        assert_eq!(active.close(1), None);
+        assert!(active.close(2).is_some());
+    }
+}
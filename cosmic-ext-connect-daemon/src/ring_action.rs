@@ -0,0 +1,215 @@
+//! Telephony Ring Actions
+//!
+//! Pauses media players or mutes system audio while a paired phone rings, and
+//! undoes it when the call ends. The undo is taken out of [`RingActions`] when
+//! it runs, so media is resumed once even if the phone reports the end of the
+//! call several times. Only what the action changed is undone: players that
+//! were already paused stay paused and a sink that was already muted stays
+//! muted.
+
+use crate::mpris_manager::MprisManager;
+use cosmic_ext_connect_protocol::plugins::audio_backend::AudioBackend;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+
+/// Action to run while a phone rings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RingAction {
+    /// Do nothing
+    #[default]
+    None,
+    /// Pause playing media players
+    PauseMedia,
+    /// Mute the default audio output
+    Mute,
+}
+
+/// Changes made by a ring action that are undone when the call ends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RingUndo {
+    /// Resume these media players
+    Resume(Vec<String>),
+    /// Unmute this audio sink
+    Unmute(u32),
+}
+
+/// Tracks the ring action applied for each device's current call
+#[derive(Debug, Default)]
+pub struct RingActions {
+    /// Devices with a call in progress, with what to undo when it ends
+    active: HashMap<String, Option<RingUndo>>,
+}
+
+impl RingActions {
+    /// Record that a device's phone started ringing
+    ///
+    /// Returns `true` if this starts a new call, i.e. the ring action should
+    /// run. Repeated ringing events for the same call return `false`.
+    pub fn call_started(&mut self, device_id: &str) -> bool {
+        if self.active.contains_key(device_id) {
+            return false;
+        }
+        self.active.insert(device_id.to_string(), None);
+        true
+    }
+
+    /// Remember what to undo when the device's current call ends
+    pub fn record(&mut self, device_id: &str, undo: RingUndo) {
+        if let Some(slot) = self.active.get_mut(device_id) {
+            *slot = Some(undo);
+        }
+    }
+
+    /// Record that a device's call ended
+    ///
+    /// Returns what to undo the first time; later calls return `None`.
+    pub fn call_ended(&mut self, device_id: &str) -> Option<RingUndo> {
+        self.active.remove(device_id).flatten()
+    }
+}
+
+/// Run a ring action
+///
+/// Returns what to undo when the call ends, if anything was changed.
+pub async fn apply(action: RingAction, mpris: Option<&MprisManager>) -> Option<RingUndo> {
+    match action {
+        RingAction::None => None,
+        RingAction::PauseMedia => {
+            let Some(mpris) = mpris else {
+                debug!("MPRIS is disabled, not pausing media for incoming call");
+                return None;
+            };
+
+            let mut paused = Vec::new();
+            for player in mpris.get_player_list().await {
+                let playing = mpris
+                    .query_player_state(&player)
+                    .await
+                    .is_ok_and(|state| state.playback_status.is_playing());
+                if !playing {
+                    continue;
+                }
+                match mpris.call_player_method(&player, "Pause").await {
+                    Ok(()) => paused.push(player),
+                    Err(e) => warn!("Failed to pause {} for incoming call: {}", player, e),
+                }
+            }
+
+            if paused.is_empty() {
+                return None;
+            }
+            info!("Paused {} media player(s) for incoming call", paused.len());
+            Some(RingUndo::Resume(paused))
+        }
+        RingAction::Mute => {
+            let sink = tokio::task::spawn_blocking(|| {
+                AudioBackend::list_sinks()
+                    .into_iter()
+                    .find(|s| s.is_default)
+            })
+            .await
+            .ok()
+            .flatten()?;
+            if sink.muted {
+                return None;
+            }
+
+            let id = sink.id;
+            let muted = tokio::task::spawn_blocking(move || AudioBackend::set_mute(id, true))
+                .await
+                .unwrap_or(false);
+            if !muted {
+                warn!("Failed to mute audio sink {} for incoming call", id);
+                return None;
+            }
+            info!("Muted audio sink {} for incoming call", id);
+            Some(RingUndo::Unmute(id))
+        }
+    }
+}
+
+/// Undo a ring action after the call ended
+pub async fn undo(undo: RingUndo, mpris: Option<&MprisManager>) {
+    match undo {
+        RingUndo::Resume(players) => {
+            let Some(mpris) = mpris else {
+                return;
+            };
+            for player in players {
+                if let Err(e) = mpris.call_player_method(&player, "Play").await {
+                    warn!("Failed to resume {} after call: {}", player, e);
+                }
+            }
+        }
+        RingUndo::Unmute(id) => {
+            let unmuted = tokio::task::spawn_blocking(move || AudioBackend::set_mute(id, false))
+                .await
+                .unwrap_or(false);
+            if !unmuted {
+                warn!("Failed to unmute audio sink {} after call", id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_action_default() {
+        assert_eq!(RingAction::default(), RingAction::None);
+    }
+
+    #[test]
+    fn test_ring_action_serde() {
+        let action: RingAction = serde_json::from_str("\"pause_media\"").unwrap();
+        assert_eq!(action, RingAction::PauseMedia);
+        assert_eq!(
+            serde_json::to_string(&RingAction::Mute).unwrap(),
+            "\"mute\""
+        );
+    }
+
+    #[test]
+    fn test_ring_talk_end_undoes_once() {
+        let mut actions = RingActions::default();
+        let undo = RingUndo::Resume(vec!["spotify".to_string()]);
+
+        // Ringing starts the call and runs the action
+        assert!(actions.call_started("phone"));
+        actions.record("phone", undo.clone());
+
+        // Answering the call does not run the action again
+        assert!(!actions.call_started("phone"));
+
+        // The first end undoes it, repeated ends do nothing
+        assert_eq!(actions.call_ended("phone"), Some(undo));
+        assert_eq!(actions.call_ended("phone"), None);
+        assert_eq!(actions.call_ended("phone"), None);
+
+        // A new call runs the action again
+        assert!(actions.call_started("phone"));
+    }
+
+    #[test]
+    fn test_ring_actions_per_device() {
+        let mut actions = RingActions::default();
+
+        assert!(actions.call_started("a"));
+        assert!(actions.call_started("b"));
+        actions.record("a", RingUndo::Unmute(42));
+
+        assert_eq!(actions.call_ended("b"), None);
+        assert_eq!(actions.call_ended("a"), Some(RingUndo::Unmute(42)));
+    }
+
+    #[test]
+    fn test_record_without_call_ignored() {
+        let mut actions = RingActions::default();
+        actions.record("phone", RingUndo::Unmute(1));
+        assert_eq!(actions.call_ended("phone"), None);
+    }
+}
@@ -0,0 +1,121 @@
+//! Schema Versioning for Persisted Files
+//!
+//! Persisted config and state documents carry a `schema_version` field.
+//! On load the raw document is checked and upgraded one version at a time
+//! through a chain of migrations before it is parsed into its typed struct,
+//! so old files keep loading after the schema changes.
+//!
+//! Documents without a version are treated as version 1. Documents newer
+//! than the running daemon supports are rejected instead of being loaded
+//! partially.
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+
+/// Field holding the schema version of a persisted document
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Version assumed for documents written before versioning was added
+pub const INITIAL_SCHEMA_VERSION: u32 = 1;
+
+/// Upgrades a document by exactly one schema version
+pub type Migration = fn(Value) -> Result<Value>;
+
+/// Read the schema version of a document
+///
+/// Returns [`INITIAL_SCHEMA_VERSION`] if the document has no version.
+pub fn schema_version(doc: &Value) -> Result<u32> {
+    match doc.get(SCHEMA_VERSION_KEY) {
+        None => Ok(INITIAL_SCHEMA_VERSION),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= INITIAL_SCHEMA_VERSION)
+            .ok_or_else(|| anyhow!("Invalid {}: {}", SCHEMA_VERSION_KEY, version)),
+    }
+}
+
+/// Upgrade a document from schema version `from` to `to`
+///
+/// `migrations[i]` upgrades version `i + 1` to `i + 2`. The version field is
+/// updated after each step.
+pub fn migrate(mut doc: Value, from: u32, to: u32, migrations: &[Migration]) -> Result<Value> {
+    if from > to {
+        bail!(
+            "Schema version {} is newer than the supported version {}",
+            from,
+            to
+        );
+    }
+
+    for version in from..to {
+        let migration = migrations
+            .get((version - INITIAL_SCHEMA_VERSION) as usize)
+            .ok_or_else(|| anyhow!("No migration from schema version {}", version))?;
+        doc = migration(doc)?;
+
+        match doc.as_object_mut() {
+            Some(object) => {
+                object.insert(SCHEMA_VERSION_KEY.to_string(), Value::from(version + 1));
+            }
+            None => bail!(
+                "Migration to schema version {} did not produce an object",
+                version + 1
+            ),
+        }
+    }
+
+    Ok(doc)
+}
+
+/// Check and upgrade a loaded document to the `current` schema version
+pub fn upgrade(doc: Value, current: u32, migrations: &[Migration]) -> Result<Value> {
+    let version = schema_version(&doc)?;
+    migrate(doc, version, current, migrations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_name_to_title(mut doc: Value) -> Result<Value> {
+        if let Some(name) = doc.as_object_mut().and_then(|o| o.remove("name")) {
+            doc["title"] = name;
+        }
+        Ok(doc)
+    }
+
+    fn add_tags(mut doc: Value) -> Result<Value> {
+        doc["tags"] = json!([]);
+        Ok(doc)
+    }
+
+    const MIGRATIONS: &[Migration] = &[rename_name_to_title, add_tags];
+
+    #[test]
+    fn test_missing_version_is_v1() {
+        assert_eq!(schema_version(&json!({"name": "x"})).unwrap(), 1);
+        assert_eq!(schema_version(&json!({"schema_version": 2})).unwrap(), 2);
+        assert!(schema_version(&json!({"schema_version": "2"})).is_err());
+        assert!(schema_version(&json!({"schema_version": 0})).is_err());
+    }
+
+    #[test]
+    fn test_migration_chain() {
+        let doc = upgrade(json!({"name": "x"}), 3, MIGRATIONS).unwrap();
+        assert_eq!(doc, json!({"schema_version": 3, "title": "x", "tags": []}));
+
+        // Partially migrated documents only run the remaining steps
+        let doc = upgrade(json!({"schema_version": 2, "title": "y"}), 3, MIGRATIONS).unwrap();
+        assert_eq!(doc, json!({"schema_version": 3, "title": "y", "tags": []}));
+    }
+
+    #[test]
+    fn test_newer_version_rejected() {
+        let err = upgrade(json!({"schema_version": 4}), 3, MIGRATIONS).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("newer than the supported version 3"));
+    }
+}
@@ -0,0 +1,152 @@
+//! Batched D-Bus Signal Emission
+//!
+//! Discovery broadcasts and connection churn can update many devices in a
+//! short time. Instead of emitting one D-Bus signal per change, updates are
+//! collected per key for a short debounce window and emitted together, with
+//! only the latest value kept for each key.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::time::{timeout_at, Instant};
+use tracing::debug;
+
+/// Default debounce window for batched device updates
+pub const DEFAULT_BATCH_WINDOW: Duration = Duration::from_millis(200);
+
+/// Coalesces keyed updates into batches
+///
+/// The first update after an idle period opens a window of `window`; all
+/// updates received until it closes are merged and passed to the emit
+/// callback as one map.
+#[derive(Debug, Clone)]
+pub struct UpdateBatcher<T> {
+    update_tx: UnboundedSender<(String, T)>,
+}
+
+impl<T: Send + 'static> UpdateBatcher<T> {
+    /// Spawn the batching task
+    ///
+    /// `emit` is called once per batch. The task stops when all batcher
+    /// handles are dropped, after flushing any pending updates.
+    pub fn spawn<F, Fut>(window: Duration, emit: F) -> Self
+    where
+        F: Fn(HashMap<String, T>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let (update_tx, mut update_rx) = unbounded_channel::<(String, T)>();
+
+        tokio::spawn(async move {
+            while let Some((key, value)) = update_rx.recv().await {
+                let mut batch = HashMap::new();
+                batch.insert(key, value);
+
+                let deadline = Instant::now() + window;
+                let mut closed = false;
+                loop {
+                    match timeout_at(deadline, update_rx.recv()).await {
+                        Ok(Some((key, value))) => {
+                            batch.insert(key, value);
+                        }
+                        Ok(None) => {
+                            closed = true;
+                            break;
+                        }
+                        Err(_) => break,
+                    }
+                }
+
+                debug!("Emitting batch of {} updates", batch.len());
+                emit(batch).await;
+
+                if closed {
+                    break;
+                }
+            }
+        });
+
+        Self { update_tx }
+    }
+
+    /// Queue an update, replacing any pending update for the same key
+    pub fn update(&self, key: impl Into<String>, value: T) {
+        let _ = self.update_tx.send((key.into(), value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::UnboundedReceiver;
+
+    const WINDOW: Duration = Duration::from_millis(50);
+
+    fn batcher() -> (
+        UpdateBatcher<&'static str>,
+        UnboundedReceiver<HashMap<String, &'static str>>,
+    ) {
+        let (batch_tx, batch_rx) = unbounded_channel();
+        let batcher = UpdateBatcher::spawn(WINDOW, move |batch| {
+            let _ = batch_tx.send(batch);
+            async {}
+        });
+        (batcher, batch_rx)
+    }
+
+    #[tokio::test]
+    async fn test_burst_emits_single_merged_batch() {
+        let (batcher, mut batches) = batcher();
+
+        batcher.update("phone", "reachable");
+        batcher.update("tablet", "reachable");
+        batcher.update("phone", "connected");
+        batcher.update("tablet", "disconnected");
+
+        let batch = tokio::time::timeout(WINDOW * 10, batches.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch["phone"], "connected");
+        assert_eq!(batch["tablet"], "disconnected");
+
+        // Nothing else was emitted for the burst
+        tokio::time::sleep(WINDOW * 3).await;
+        assert!(batches.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_updates_after_window_start_new_batch() {
+        let (batcher, mut batches) = batcher();
+
+        batcher.update("phone", "connected");
+        let first = tokio::time::timeout(WINDOW * 10, batches.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first["phone"], "connected");
+
+        batcher.update("phone", "disconnected");
+        let second = tokio::time::timeout(WINDOW * 10, batches.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(second["phone"], "disconnected");
+    }
+
+    #[tokio::test]
+    async fn test_pending_updates_flushed_on_drop() {
+        let (batcher, mut batches) = batcher();
+
+        batcher.update("phone", "connected");
+        drop(batcher);
+
+        let batch = tokio::time::timeout(WINDOW * 10, batches.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch["phone"], "connected");
+        assert!(batches.recv().await.is_none());
+    }
+}
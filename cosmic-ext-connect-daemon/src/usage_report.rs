@@ -0,0 +1,253 @@
+//! Opt-in Usage Report
+//!
+//! Keeps a local, anonymized aggregate of which peer protocol versions,
+//! plugins and transports this desktop connects with, so users can share it
+//! with maintainers to help prioritize compatibility work.
+//!
+//! Recording is disabled unless the user opts in via `usage_report.enabled`.
+//! The report only holds counters keyed by protocol version, plugin name and
+//! transport type; device IDs, names and addresses are never stored. Nothing
+//! is sent anywhere: the report is written to the data directory and exported
+//! on request.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info};
+
+/// Transport label recorded for TCP/TLS connections
+pub const TRANSPORT_TCP: &str = "tcp";
+
+/// Transport label recorded for Bluetooth connections
+pub const TRANSPORT_BLUETOOTH: &str = "bluetooth";
+
+/// Current UNIX time in seconds
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Anonymized aggregate of observed connections
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageReport {
+    /// UNIX time (seconds) of the first recorded connection
+    pub since: Option<u64>,
+
+    /// UNIX time (seconds) of the most recent recorded connection
+    pub updated: Option<u64>,
+
+    /// Number of recorded connections
+    pub connections: u64,
+
+    /// Connections per peer protocol version
+    pub protocol_versions: BTreeMap<u32, u64>,
+
+    /// Connections per negotiated plugin
+    pub plugins: BTreeMap<String, u64>,
+
+    /// Connections per transport type
+    pub transports: BTreeMap<String, u64>,
+}
+
+/// Local usage report recorder with persistence
+pub struct UsageReporter {
+    /// Whether the user has opted in to recording
+    enabled: bool,
+
+    /// Aggregated report
+    report: UsageReport,
+
+    /// Path to the report file
+    report_path: PathBuf,
+}
+
+impl UsageReporter {
+    /// Create a reporter stored in `data_dir`
+    pub fn new(data_dir: &Path, enabled: bool) -> Self {
+        Self {
+            enabled,
+            report: UsageReport::default(),
+            report_path: data_dir.join("usage_report.json"),
+        }
+    }
+
+    /// Load the report from disk
+    pub fn load(&mut self) -> Result<()> {
+        if !self.report_path.exists() {
+            debug!("Usage report file not found, starting empty");
+            return Ok(());
+        }
+
+        let contents =
+            fs::read_to_string(&self.report_path).context("Failed to read usage report file")?;
+        self.report = serde_json::from_str(&contents).context("Failed to parse usage report")?;
+
+        Ok(())
+    }
+
+    /// Save the report to disk
+    pub fn save(&self) -> Result<()> {
+        let contents = serde_json::to_string_pretty(&self.report)
+            .context("Failed to serialize usage report")?;
+
+        fs::write(&self.report_path, contents).context("Failed to write usage report file")?;
+
+        Ok(())
+    }
+
+    /// Whether recording is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enable or disable recording
+    ///
+    /// Disabling keeps the existing report so it can still be exported; use
+    /// [`clear`](Self::clear) to delete it.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if self.enabled != enabled {
+            info!(
+                "Usage report recording {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+        self.enabled = enabled;
+    }
+
+    /// Record a connection to a peer
+    ///
+    /// Does nothing unless the user has opted in. Only the protocol version,
+    /// plugin names and transport type are recorded.
+    pub fn record_connection<'a>(
+        &mut self,
+        protocol_version: u32,
+        plugins: impl IntoIterator<Item = &'a str>,
+        transport: &str,
+    ) -> Result<()> {
+        if !self.record_connection_at(protocol_version, plugins, transport, now_secs()) {
+            return Ok(());
+        }
+        self.save()
+    }
+
+    /// Current report
+    pub fn report(&self) -> &UsageReport {
+        &self.report
+    }
+
+    /// Export the report as pretty-printed JSON
+    pub fn export(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.report).context("Failed to serialize usage report")
+    }
+
+    /// Delete all recorded data
+    pub fn clear(&mut self) -> Result<()> {
+        self.report = UsageReport::default();
+        if self.report_path.exists() {
+            fs::remove_file(&self.report_path).context("Failed to remove usage report file")?;
+        }
+        info!("Usage report cleared");
+        Ok(())
+    }
+
+    fn record_connection_at<'a>(
+        &mut self,
+        protocol_version: u32,
+        plugins: impl IntoIterator<Item = &'a str>,
+        transport: &str,
+        now: u64,
+    ) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let report = &mut self.report;
+        report.since.get_or_insert(now);
+        report.updated = Some(now);
+        report.connections += 1;
+        *report
+            .protocol_versions
+            .entry(protocol_version)
+            .or_default() += 1;
+        for plugin in plugins {
+            *report.plugins.entry(plugin.to_string()).or_default() += 1;
+        }
+        *report.transports.entry(transport.to_string()).or_default() += 1;
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_reporter(name: &str, enabled: bool) -> (UsageReporter, PathBuf) {
+        let temp_dir = std::env::temp_dir().join(format!("cconnect-usage-test-{}", name));
+        fs::create_dir_all(&temp_dir).unwrap();
+        (UsageReporter::new(&temp_dir, enabled), temp_dir)
+    }
+
+    #[test]
+    fn test_nothing_recorded_without_consent() {
+        let (mut reporter, temp_dir) = temp_reporter("disabled", false);
+
+        reporter
+            .record_connection(8, ["battery", "share"], TRANSPORT_TCP)
+            .unwrap();
+
+        assert_eq!(reporter.report(), &UsageReport::default());
+        assert!(!temp_dir.join("usage_report.json").exists());
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_aggregate_excludes_identifiers() {
+        let (mut reporter, temp_dir) = temp_reporter("enabled", true);
+
+        assert!(reporter.record_connection_at(8, ["battery", "share"], TRANSPORT_TCP, 100));
+        assert!(reporter.record_connection_at(7, ["battery"], TRANSPORT_BLUETOOTH, 200));
+        reporter.save().unwrap();
+
+        let report = reporter.report();
+        assert_eq!(report.connections, 2);
+        assert_eq!(report.since, Some(100));
+        assert_eq!(report.updated, Some(200));
+        assert_eq!(report.protocol_versions.get(&8), Some(&1));
+        assert_eq!(report.plugins.get("battery"), Some(&2));
+        assert_eq!(report.transports.get(TRANSPORT_BLUETOOTH), Some(&1));
+
+        // The exported report only contains the aggregate fields
+        let exported: serde_json::Value =
+            serde_json::from_str(&reporter.export().unwrap()).unwrap();
+        let mut keys: Vec<_> = exported.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "connections",
+                "plugins",
+                "protocol_versions",
+                "since",
+                "transports",
+                "updated"
+            ]
+        );
+
+        let mut reloaded = UsageReporter::new(&temp_dir, true);
+        reloaded.load().unwrap();
+        assert_eq!(reloaded.report(), reporter.report());
+
+        reloaded.clear().unwrap();
+        assert_eq!(reloaded.report().connections, 0);
+        assert!(!temp_dir.join("usage_report.json").exists());
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+}
@@ -18,6 +18,7 @@ fn create_mock_device() -> Device {
         port: Some(1716),
         certificate_fingerprint: None,
         certificate_data: None,
+        negotiated_capabilities: None,
     }
 }
 
@@ -24,6 +24,7 @@ fn create_mock_device() -> Device {
         port: Some(1716),
         certificate_fingerprint: None,
         certificate_data: None,
+        negotiated_capabilities: None,
     }
 }
 
@@ -179,7 +180,7 @@ async fn test_plugin_manager_battery_query() {
         .unwrap();
 
     // Initially no battery status
-    let status = manager.get_device_battery_status(&device_id);
+    let status = manager.get_device_battery_status(&device_id).await;
     assert!(status.is_none());
 
     // Simulate receiving a battery packet from the remote device
@@ -188,14 +189,13 @@ async fn test_plugin_manager_battery_query() {
     let battery_packet = battery_plugin.create_battery_packet(&battery_status);
 
     // Handle the battery packet through the plugin manager
-    let mut device_mut = device.clone();
     manager
-        .handle_packet(&device_id, &battery_packet, &mut device_mut)
+        .handle_packet(&device_id, &battery_packet, &device)
         .await
         .unwrap();
 
     // Now we should be able to query the battery status
-    let status = manager.get_device_battery_status(&device_id);
+    let status = manager.get_device_battery_status(&device_id).await;
     assert!(status.is_some(), "Battery status should be available after receiving battery packet");
 
     let status = status.unwrap();
@@ -365,6 +365,7 @@ async fn test_share_plugin_file() -> Result<()> {
         creation_time: None,
         last_modified: None,
         open: false,
+        hash: None,
     };
     let packet = plugin.create_file_packet(file_info, 1739);
     assert_eq!(packet.packet_type, "cconnect.share.request");
@@ -553,7 +554,7 @@ async fn test_plugin_manager_multi_device() -> Result<()> {
     manager.register_factory(Arc::new(ping::PingPluginFactory))?;
     manager.register_factory(Arc::new(notification::NotificationPluginFactory))?;
     manager.register_factory(Arc::new(clipboard::ClipboardPluginFactory))?;
-    manager.register_factory(Arc::new(share::SharePluginFactory))?;
+    manager.register_factory(Arc::new(share::SharePluginFactory::new()))?;
 
     // Create two devices
     let device1 = create_mock_device();
@@ -574,17 +575,35 @@ async fn test_plugin_manager_multi_device() -> Result<()> {
         .await?;
 
     // Verify both devices have plugins initialized
-    assert!(manager.get_device_plugin(&device1_id, "battery").is_some());
-    assert!(manager.get_device_plugin(&device2_id, "battery").is_some());
-    assert!(manager.get_device_plugin(&device1_id, "ping").is_some());
-    assert!(manager.get_device_plugin(&device2_id, "ping").is_some());
+    assert!(manager
+        .get_device_plugin(&device1_id, "battery")
+        .await
+        .is_some());
+    assert!(manager
+        .get_device_plugin(&device2_id, "battery")
+        .await
+        .is_some());
+    assert!(manager
+        .get_device_plugin(&device1_id, "ping")
+        .await
+        .is_some());
+    assert!(manager
+        .get_device_plugin(&device2_id, "ping")
+        .await
+        .is_some());
 
     // Cleanup one device
     manager.cleanup_device_plugins(&device1_id).await?;
 
     // Verify device1 plugins removed but device2 remains
-    assert!(manager.get_device_plugin(&device1_id, "battery").is_none());
-    assert!(manager.get_device_plugin(&device2_id, "battery").is_some());
+    assert!(manager
+        .get_device_plugin(&device1_id, "battery")
+        .await
+        .is_none());
+    assert!(manager
+        .get_device_plugin(&device2_id, "battery")
+        .await
+        .is_some());
 
     Ok(())
 }
@@ -617,13 +636,12 @@ async fn test_packet_routing_to_correct_plugin() -> Result<()> {
     assert_eq!(battery_packet.packet_type, "cconnect.battery.request");
 
     // Route packets through manager
-    let mut device_mut = device.clone();
     manager
-        .handle_packet(&device_id, &ping_packet, &mut device_mut)
+        .handle_packet(&device_id, &ping_packet, &device)
         .await
         .unwrap();
     manager
-        .handle_packet(&device_id, &battery_packet, &mut device_mut)
+        .handle_packet(&device_id, &battery_packet, &device)
         .await
         .unwrap();
 
@@ -53,6 +53,15 @@ pub struct BatteryStatus {
     pub is_charging: bool,
 }
 
+/// Measured connection latency from DBus
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct PingLatency {
+    /// Round-trip time in milliseconds
+    pub rtt_ms: u64,
+    /// Latency category ("low", "medium" or "high")
+    pub category: String,
+}
+
 /// Screen share statistics from DBus
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
 pub struct ScreenShareStats {
@@ -167,6 +176,119 @@ pub struct SyncFolderInfo {
     pub strategy: String,
 }
 
+/// File or directory on a device's SFTP share from DBus
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct SftpEntryInfo {
+    pub name: String,
+    /// Path relative to the share root
+    pub path: String,
+    pub is_dir: bool,
+    /// Size in bytes (0 if unknown)
+    pub size: u64,
+    /// Modification time (UNIX timestamp in seconds, 0 if unknown)
+    pub modified: u64,
+}
+
+/// File transfer state from DBus
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct Transfer {
+    pub id: String,
+    pub device_id: String,
+    pub filename: String,
+    /// "sending" or "receiving"
+    pub direction: String,
+    pub total_bytes: u64,
+    pub transferred_bytes: u64,
+    /// Average speed in bytes per second
+    pub speed_bps: u64,
+    /// "active", "completed", "failed" or "cancelled"
+    pub state: String,
+    /// Last update (UNIX timestamp in milliseconds)
+    pub updated_at: i64,
+}
+
+impl Transfer {
+    /// Whether the transfer is still running
+    pub fn is_active(&self) -> bool {
+        self.state == "active"
+    }
+
+    /// Progress as a fraction between 0 and 1
+    pub fn fraction(&self) -> f32 {
+        if self.total_bytes == 0 {
+            return if self.is_active() { 0.0 } else { 1.0 };
+        }
+        (self.transferred_bytes as f64 / self.total_bytes as f64).min(1.0) as f32
+    }
+}
+
+/// Device history event from DBus
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct HistoryEntry {
+    pub id: i64,
+    /// When the event happened (UNIX timestamp in milliseconds)
+    pub timestamp: i64,
+    pub device_id: String,
+    /// "ping", "transfer", "sms", "notification" or "pairing"
+    pub event_type: String,
+    pub summary: String,
+}
+
+/// Privileged action audit log entry from DBus
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct AuditLogEntry {
+    /// When the decision was made (UNIX timestamp in milliseconds)
+    pub timestamp: i64,
+    pub device_id: String,
+    /// "power", "runcommand" or "remoteinput"
+    pub plugin: String,
+    pub action: String,
+    /// Redacted request parameters as JSON
+    pub parameters: String,
+    pub allowed: bool,
+    /// Why the action was denied (empty if allowed)
+    pub reason: String,
+}
+
+/// Media player on a remote device from DBus
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, zbus::zvariant::Type)]
+pub struct RemotePlayer {
+    pub device_id: String,
+    pub name: String,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    /// Local path of the cached album art (empty until downloaded)
+    pub album_art_path: String,
+    pub is_playing: bool,
+    /// Playback position in milliseconds at `updated_at`
+    pub position_ms: i64,
+    /// Track length in milliseconds (0 if unknown)
+    pub length_ms: i64,
+    pub volume: i32,
+    pub can_play: bool,
+    pub can_pause: bool,
+    pub can_go_next: bool,
+    pub can_go_previous: bool,
+    pub can_seek: bool,
+    /// When the daemon received the state (UNIX timestamp in milliseconds)
+    pub updated_at: i64,
+}
+
+impl RemotePlayer {
+    /// Playback position at `now_ms`, extrapolated while playing
+    pub fn position_at(&self, now_ms: i64) -> i64 {
+        let mut position = self.position_ms;
+        if self.is_playing {
+            position += (now_ms - self.updated_at).max(0);
+        }
+        if self.length_ms > 0 {
+            position = position.min(self.length_ms);
+        }
+        position
+    }
+}
+
 impl Default for RemoteDesktopSettings {
     fn default() -> Self {
         Self {
@@ -295,23 +417,19 @@ impl DeviceConfig {
 /// Events emitted by the daemon
 #[derive(Debug, Clone)]
 pub enum DaemonEvent {
-    /// Device was added (discovered)
-    DeviceAdded {
-        device_id: String,
-        device_info: DeviceInfo,
-    },
     /// Device was removed (disappeared)
     DeviceRemoved { device_id: String },
-    /// Device state changed
-    DeviceStateChanged { device_id: String, state: String },
-    /// Pairing request received
-    PairingRequest { device_id: String },
-    /// Pairing status changed
-    PairingStatusChanged {
-        #[allow(dead_code)]
+    /// Batch of discovered and changed devices, merged by the daemon
+    DevicesUpdated {
+        devices: HashMap<String, DeviceInfo>,
+    },
+    /// Pairing request received, with the fingerprint to show the user
+    PairingRequested {
         device_id: String,
-        status: String,
+        fingerprint: String,
     },
+    /// Pairing status changed
+    PairingStatusChanged { device_id: String, status: String },
     /// Plugin event
     PluginEvent {
         #[allow(dead_code)]
@@ -330,21 +448,21 @@ pub enum DaemonEvent {
         #[allow(dead_code)]
         enabled: bool,
     },
+    /// Battery level or charging state of a device changed
+    BatteryStatusChanged {
+        device_id: String,
+        status: BatteryStatus,
+    },
     /// Daemon disconnected
-    #[allow(dead_code)]
     DaemonDisconnected,
     /// Daemon reconnected
-    #[allow(dead_code)]
     DaemonReconnected,
-    /// File transfer progress
-    TransferProgress {
-        transfer_id: String,
-        device_id: String,
-        filename: String,
-        current: u64,
-        total: u64,
-        direction: String,
-    },
+    /// File transfer state changed
+    TransferUpdated { transfer: Transfer },
+    /// Media player on a remote device changed
+    RemotePlayerUpdated { player: RemotePlayer },
+    /// Media player on a remote device went away
+    RemotePlayerRemoved { device_id: String, player: String },
     /// File transfer complete
     TransferComplete {
         transfer_id: String,
@@ -393,7 +511,13 @@ trait CConnect {
     async fn get_device(&self, device_id: &str) -> zbus::fdo::Result<DeviceInfo>;
 
     /// Request pairing with a device
-    async fn pair_device(&self, device_id: &str) -> zbus::fdo::Result<()>;
+    async fn request_pair(&self, device_id: &str) -> zbus::fdo::Result<()>;
+
+    /// Accept a pairing request from a device
+    async fn accept_pair(&self, device_id: &str) -> zbus::fdo::Result<()>;
+
+    /// Reject a pairing request from a device
+    async fn reject_pair(&self, device_id: &str) -> zbus::fdo::Result<()>;
 
     /// Unpair a device
     async fn unpair_device(&self, device_id: &str) -> zbus::fdo::Result<()>;
@@ -410,9 +534,15 @@ trait CConnect {
     /// Get device connection state
     async fn get_device_state(&self, device_id: &str) -> zbus::fdo::Result<String>;
 
+    /// Get the certificate fingerprint a device is pinned to
+    async fn get_certificate_fingerprint(&self, device_id: &str) -> zbus::fdo::Result<String>;
+
     /// Send a ping to a device
     async fn send_ping(&self, device_id: &str, message: &str) -> zbus::fdo::Result<()>;
 
+    /// Measure the round-trip time to a device
+    async fn measure_latency(&self, device_id: &str) -> zbus::fdo::Result<PingLatency>;
+
     /// Trigger find phone on a device
     async fn find_phone(&self, device_id: &str) -> zbus::fdo::Result<()>;
 
@@ -422,6 +552,35 @@ trait CConnect {
     /// Cancel an active file transfer
     async fn cancel_transfer(&self, transfer_id: &str) -> zbus::fdo::Result<()>;
 
+    /// Get active and recently finished file transfers
+    async fn get_transfers(&self) -> zbus::fdo::Result<Vec<Transfer>>;
+
+    /// Get one page of the device event history, newest first
+    async fn get_history(
+        &self,
+        device_id: &str,
+        event_type: &str,
+        limit: u32,
+        offset: u32,
+    ) -> zbus::fdo::Result<Vec<HistoryEntry>>;
+
+    /// Get the number of history events matching a filter
+    async fn get_history_count(&self, device_id: &str, event_type: &str) -> zbus::fdo::Result<u64>;
+
+    /// Get one page of the privileged action audit log, newest first
+    async fn get_audit_log(
+        &self,
+        device_id: &str,
+        limit: u32,
+        offset: u32,
+    ) -> zbus::fdo::Result<Vec<AuditLogEntry>>;
+
+    /// Get the number of audit log entries
+    async fn get_audit_log_count(&self, device_id: &str) -> zbus::fdo::Result<u64>;
+
+    /// Delete device event history
+    async fn clear_history(&self, device_id: &str) -> zbus::fdo::Result<()>;
+
     /// Share text or URL with a device
     async fn share_text(&self, device_id: &str, text: &str) -> zbus::fdo::Result<()>;
 
@@ -477,6 +636,33 @@ trait CConnect {
     /// Control MPRIS player playback
     async fn mpris_control(&self, player: &str, action: &str) -> zbus::fdo::Result<()>;
 
+    /// Get media players on all connected devices
+    async fn get_remote_players(&self) -> zbus::fdo::Result<Vec<RemotePlayer>>;
+
+    /// Control a media player on a remote device
+    async fn remote_mpris_control(
+        &self,
+        device_id: &str,
+        player: &str,
+        action: &str,
+    ) -> zbus::fdo::Result<()>;
+
+    /// Set the volume of a media player on a remote device
+    async fn remote_mpris_set_volume(
+        &self,
+        device_id: &str,
+        player: &str,
+        volume: f64,
+    ) -> zbus::fdo::Result<()>;
+
+    /// Jump to a position in the current track of a remote player
+    async fn remote_mpris_set_position(
+        &self,
+        device_id: &str,
+        player: &str,
+        position_microseconds: i64,
+    ) -> zbus::fdo::Result<()>;
+
     /// Set MPRIS player volume
     async fn mpris_set_volume(&self, player: &str, volume: f64) -> zbus::fdo::Result<()>;
 
@@ -497,6 +683,22 @@ trait CConnect {
         enabled: bool,
     ) -> zbus::fdo::Result<()>;
 
+    /// Allow or revoke a privileged plugin for a device
+    async fn set_device_privileged_plugin(
+        &self,
+        device_id: &str,
+        plugin: &str,
+        allowed: bool,
+    ) -> zbus::fdo::Result<()>;
+
+    /// Set a device's nickname and plugin states in one call
+    async fn set_device_config(
+        &self,
+        device_id: &str,
+        nickname: &str,
+        plugins: HashMap<String, bool>,
+    ) -> zbus::fdo::Result<()>;
+
     /// Clear device-specific plugin override
     async fn clear_device_plugin_override(
         &self,
@@ -546,21 +748,52 @@ trait CConnect {
     /// Get list of synced folders for a device
     async fn get_sync_folders(&self, device_id: String) -> zbus::fdo::Result<Vec<SyncFolderInfo>>;
 
-    /// Signal: Device was added
-    #[zbus(signal)]
-    fn device_added(device_id: &str, device_info: DeviceInfo) -> zbus::fdo::Result<()>;
+    /// List a directory on a device's SFTP share
+    async fn sftp_list_dir(
+        &self,
+        device_id: &str,
+        path: &str,
+    ) -> zbus::fdo::Result<Vec<SftpEntryInfo>>;
+
+    /// Read a file from a device's SFTP share
+    async fn sftp_read_file(&self, device_id: &str, path: &str) -> zbus::fdo::Result<Vec<u8>>;
+
+    /// Write a file to a device's SFTP share
+    async fn sftp_write_file(
+        &self,
+        device_id: &str,
+        path: &str,
+        data: &[u8],
+    ) -> zbus::fdo::Result<()>;
+
+    /// Snooze forwarded notifications from an app, returning the expiry timestamp
+    async fn snooze_app(&self, app_name: &str, duration_secs: u64) -> zbus::fdo::Result<u64>;
+
+    /// Clear the notification snooze for an app
+    async fn unsnooze_app(&self, app_name: &str) -> zbus::fdo::Result<bool>;
+
+    /// Get snoozed apps (app name -> expiry timestamp)
+    async fn get_snoozed_apps(&self) -> zbus::fdo::Result<HashMap<String, u64>>;
 
     /// Signal: Device was removed
     #[zbus(signal)]
     fn device_removed(device_id: &str) -> zbus::fdo::Result<()>;
 
-    /// Signal: Device state changed
+    /// Signal: Batch of device updates (device ID -> latest state)
     #[zbus(signal)]
-    fn device_state_changed(device_id: &str, state: &str) -> zbus::fdo::Result<()>;
+    fn devices_updated(devices: HashMap<String, DeviceInfo>) -> zbus::fdo::Result<()>;
 
-    /// Signal: Pairing request received
+    /// Signal: Pairing request received, with the peer's certificate fingerprint
     #[zbus(signal)]
-    fn pairing_request(device_id: &str) -> zbus::fdo::Result<()>;
+    fn pairing_requested(device_id: &str, fingerprint: &str) -> zbus::fdo::Result<()>;
+
+    /// Signal: Battery status changed
+    #[zbus(signal)]
+    fn battery_status_changed(
+        device_id: &str,
+        level: i32,
+        is_charging: bool,
+    ) -> zbus::fdo::Result<()>;
 
     /// Signal: Pairing status changed
     #[zbus(signal)]
@@ -686,6 +919,18 @@ trait CConnect {
     /// Take screenshot
     async fn take_screenshot(&self, device_id: &str) -> zbus::fdo::Result<()>;
 
+    /// Signal: File transfer state changed
+    #[zbus(signal)]
+    fn transfer_updated(transfer: Transfer) -> zbus::fdo::Result<()>;
+
+    /// Signal: Media player on a remote device changed
+    #[zbus(signal)]
+    fn remote_player_updated(player: RemotePlayer) -> zbus::fdo::Result<()>;
+
+    /// Signal: Media player on a remote device went away
+    #[zbus(signal)]
+    fn remote_player_removed(device_id: &str, player: &str) -> zbus::fdo::Result<()>;
+
     /// Signal: File transfer complete
     #[zbus(signal)]
     fn transfer_complete(
@@ -802,50 +1047,68 @@ impl DbusClient {
         debug!("Starting signal listener");
 
         let event_tx = self.event_tx.clone();
-        let mut device_added_stream = self.proxy.receive_device_added().await?;
+        let mut device_removed_stream = self.proxy.receive_device_removed().await?;
         tokio::spawn(async move {
-            while let Some(signal) = device_added_stream.next().await {
+            while let Some(signal) = device_removed_stream.next().await {
                 if let Ok(args) = signal.args() {
                     let device_id = args.device_id().to_string();
-                    let device_info = args.device_info().clone();
-                    let _ = event_tx.send(DaemonEvent::DeviceAdded {
-                        device_id,
-                        device_info,
-                    });
+                    let _ = event_tx.send(DaemonEvent::DeviceRemoved { device_id });
                 }
             }
         });
 
         let event_tx = self.event_tx.clone();
-        let mut device_removed_stream = self.proxy.receive_device_removed().await?;
+        let mut devices_updated_stream = self.proxy.receive_devices_updated().await?;
         tokio::spawn(async move {
-            while let Some(signal) = device_removed_stream.next().await {
+            while let Some(signal) = devices_updated_stream.next().await {
                 if let Ok(args) = signal.args() {
-                    let device_id = args.device_id().to_string();
-                    let _ = event_tx.send(DaemonEvent::DeviceRemoved { device_id });
+                    let devices = args.devices().clone();
+                    let _ = event_tx.send(DaemonEvent::DevicesUpdated { devices });
                 }
             }
         });
 
         let event_tx = self.event_tx.clone();
-        let mut device_state_changed_stream = self.proxy.receive_device_state_changed().await?;
+        let mut battery_status_changed_stream = self.proxy.receive_battery_status_changed().await?;
         tokio::spawn(async move {
-            while let Some(signal) = device_state_changed_stream.next().await {
+            while let Some(signal) = battery_status_changed_stream.next().await {
                 if let Ok(args) = signal.args() {
                     let device_id = args.device_id().to_string();
-                    let state = args.state().to_string();
-                    let _ = event_tx.send(DaemonEvent::DeviceStateChanged { device_id, state });
+                    let status = BatteryStatus {
+                        level: *args.level(),
+                        is_charging: *args.is_charging(),
+                    };
+                    let _ = event_tx.send(DaemonEvent::BatteryStatusChanged { device_id, status });
                 }
             }
         });
 
+        // Match rules follow the daemon's bus name, so the signal streams keep
+        // delivering after a daemon restart; the UI reloads its state when the
+        // name gets a new owner
         let event_tx = self.event_tx.clone();
-        let mut pairing_request_stream = self.proxy.receive_pairing_request().await?;
+        let mut owner_changed_stream = self.proxy.inner().receive_owner_changed().await?;
         tokio::spawn(async move {
-            while let Some(signal) = pairing_request_stream.next().await {
+            while let Some(owner) = owner_changed_stream.next().await {
+                let event = match owner {
+                    Some(_) => DaemonEvent::DaemonReconnected,
+                    None => DaemonEvent::DaemonDisconnected,
+                };
+                let _ = event_tx.send(event);
+            }
+        });
+
+        let event_tx = self.event_tx.clone();
+        let mut pairing_requested_stream = self.proxy.receive_pairing_requested().await?;
+        tokio::spawn(async move {
+            while let Some(signal) = pairing_requested_stream.next().await {
                 if let Ok(args) = signal.args() {
                     let device_id = args.device_id().to_string();
-                    let _ = event_tx.send(DaemonEvent::PairingRequest { device_id });
+                    let fingerprint = args.fingerprint().to_string();
+                    let _ = event_tx.send(DaemonEvent::PairingRequested {
+                        device_id,
+                        fingerprint,
+                    });
                 }
             }
         });
@@ -898,17 +1161,37 @@ impl DbusClient {
         });
 
         let event_tx = self.event_tx.clone();
-        let mut progress_stream = self.proxy.receive_transfer_progress().await?;
+        let mut transfer_stream = self.proxy.receive_transfer_updated().await?;
         tokio::spawn(async move {
-            while let Some(signal) = progress_stream.next().await {
+            while let Some(signal) = transfer_stream.next().await {
                 if let Ok(args) = signal.args() {
-                    let _ = event_tx.send(DaemonEvent::TransferProgress {
-                        transfer_id: args.transfer_id().to_string(),
+                    let _ = event_tx.send(DaemonEvent::TransferUpdated {
+                        transfer: args.transfer().clone(),
+                    });
+                }
+            }
+        });
+
+        let event_tx = self.event_tx.clone();
+        let mut remote_player_stream = self.proxy.receive_remote_player_updated().await?;
+        tokio::spawn(async move {
+            while let Some(signal) = remote_player_stream.next().await {
+                if let Ok(args) = signal.args() {
+                    let _ = event_tx.send(DaemonEvent::RemotePlayerUpdated {
+                        player: args.player().clone(),
+                    });
+                }
+            }
+        });
+
+        let event_tx = self.event_tx.clone();
+        let mut remote_player_removed_stream = self.proxy.receive_remote_player_removed().await?;
+        tokio::spawn(async move {
+            while let Some(signal) = remote_player_removed_stream.next().await {
+                if let Ok(args) = signal.args() {
+                    let _ = event_tx.send(DaemonEvent::RemotePlayerRemoved {
                         device_id: args.device_id().to_string(),
-                        filename: args.filename().to_string(),
-                        current: *args.current(),
-                        total: *args.total(),
-                        direction: args.direction().to_string(),
+                        player: args.player().to_string(),
                     });
                 }
             }
@@ -1027,11 +1310,29 @@ impl DbusClient {
     pub async fn pair_device(&self, device_id: &str) -> Result<()> {
         info!("Requesting pairing with device {}", device_id);
         self.proxy
-            .pair_device(device_id)
+            .request_pair(device_id)
             .await
             .context("Failed to pair device")
     }
 
+    /// Accept a pairing request from a device
+    pub async fn accept_pair(&self, device_id: &str) -> Result<()> {
+        info!("Accepting pairing with device {}", device_id);
+        self.proxy
+            .accept_pair(device_id)
+            .await
+            .context("Failed to accept pairing")
+    }
+
+    /// Reject a pairing request from a device
+    pub async fn reject_pair(&self, device_id: &str) -> Result<()> {
+        info!("Rejecting pairing with device {}", device_id);
+        self.proxy
+            .reject_pair(device_id)
+            .await
+            .context("Failed to reject pairing")
+    }
+
     /// Unpair a device
     pub async fn unpair_device(&self, device_id: &str) -> Result<()> {
         info!("Unpairing device {}", device_id);
@@ -1079,6 +1380,15 @@ impl DbusClient {
             .context("Failed to get device state")
     }
 
+    /// Get the certificate fingerprint a device is pinned to (empty if unpaired)
+    pub async fn get_certificate_fingerprint(&self, device_id: &str) -> Result<String> {
+        debug!("Getting certificate fingerprint for {}", device_id);
+        self.proxy
+            .get_certificate_fingerprint(device_id)
+            .await
+            .context("Failed to get certificate fingerprint")
+    }
+
     /// Send a ping to a device
     pub async fn send_ping(&self, device_id: &str, message: &str) -> Result<()> {
         info!("Sending ping to device {}: {}", device_id, message);
@@ -1088,6 +1398,15 @@ impl DbusClient {
             .context("Failed to send ping")
     }
 
+    /// Measure the round-trip time to a device
+    pub async fn measure_latency(&self, device_id: &str) -> Result<PingLatency> {
+        debug!("Measuring latency to device {}", device_id);
+        self.proxy
+            .measure_latency(device_id)
+            .await
+            .context("Failed to measure latency")
+    }
+
     /// Trigger find phone on a device
     pub async fn find_phone(&self, device_id: &str) -> Result<()> {
         info!("Triggering find phone for device {}", device_id);
@@ -1115,6 +1434,71 @@ impl DbusClient {
             .context("Failed to cancel transfer")
     }
 
+    /// Get active and recently finished file transfers
+    pub async fn get_transfers(&self) -> Result<Vec<Transfer>> {
+        self.proxy
+            .get_transfers()
+            .await
+            .context("Failed to get transfers")
+    }
+
+    /// Get one page of the device event history and the total matching count
+    ///
+    /// # Arguments
+    /// * `device_id` - Only events of this device (empty for all devices)
+    /// * `event_type` - Only events of this type (empty for all types)
+    pub async fn get_history(
+        &self,
+        device_id: &str,
+        event_type: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<(Vec<HistoryEntry>, u64)> {
+        let entries = self
+            .proxy
+            .get_history(device_id, event_type, limit, offset)
+            .await
+            .context("Failed to get history")?;
+        let total = self
+            .proxy
+            .get_history_count(device_id, event_type)
+            .await
+            .context("Failed to count history")?;
+        Ok((entries, total))
+    }
+
+    /// Get one page of the privileged action audit log and the total count
+    ///
+    /// # Arguments
+    /// * `device_id` - Only entries of this device (empty for all devices)
+    pub async fn get_audit_log(
+        &self,
+        device_id: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<(Vec<AuditLogEntry>, u64)> {
+        let entries = self
+            .proxy
+            .get_audit_log(device_id, limit, offset)
+            .await
+            .context("Failed to get audit log")?;
+        let total = self
+            .proxy
+            .get_audit_log_count(device_id)
+            .await
+            .context("Failed to count audit log")?;
+        Ok((entries, total))
+    }
+
+    /// Delete device event history (all devices if `device_id` is empty)
+    pub async fn clear_history(&self, device_id: &str) -> Result<()> {
+        info!("Clearing history (device: {:?})", device_id);
+        self.proxy
+            .clear_history(device_id)
+            .await
+            .context("Failed to clear history")
+    }
+
     /// Share text with a device
     pub async fn share_text(&self, device_id: &str, text: &str) -> Result<()> {
         info!("Sharing text with device {}: {}", device_id, text);
@@ -1274,6 +1658,73 @@ impl DbusClient {
             .context("Failed to control MPRIS player")
     }
 
+    /// Get media players on all connected devices
+    pub async fn get_remote_players(&self) -> Result<Vec<RemotePlayer>> {
+        self.proxy
+            .get_remote_players()
+            .await
+            .context("Failed to get remote players")
+    }
+
+    /// Control a media player on a remote device
+    ///
+    /// # Arguments
+    /// * `device_id` - Device the player runs on
+    /// * `player` - Player name
+    /// * `action` - Action: "Play", "Pause", "PlayPause", "Stop", "Next", "Previous"
+    pub async fn remote_mpris_control(
+        &self,
+        device_id: &str,
+        player: &str,
+        action: &str,
+    ) -> Result<()> {
+        info!(
+            "Sending MPRIS control {} to player {} on {}",
+            action, player, device_id
+        );
+        self.proxy
+            .remote_mpris_control(device_id, player, action)
+            .await
+            .context("Failed to control remote player")
+    }
+
+    /// Set the volume of a media player on a remote device
+    ///
+    /// # Arguments
+    /// * `volume` - Volume level (0.0 to 1.0)
+    pub async fn remote_mpris_set_volume(
+        &self,
+        device_id: &str,
+        player: &str,
+        volume: f64,
+    ) -> Result<()> {
+        debug!(
+            "Setting volume of {} on {} to {}",
+            player, device_id, volume
+        );
+        self.proxy
+            .remote_mpris_set_volume(device_id, player, volume)
+            .await
+            .context("Failed to set remote player volume")
+    }
+
+    /// Jump to a position in the current track of a remote player
+    ///
+    /// # Arguments
+    /// * `position_us` - Target position in microseconds
+    pub async fn remote_mpris_set_position(
+        &self,
+        device_id: &str,
+        player: &str,
+        position_us: i64,
+    ) -> Result<()> {
+        debug!("Seeking {} on {} to {}μs", player, device_id, position_us);
+        self.proxy
+            .remote_mpris_set_position(device_id, player, position_us)
+            .await
+            .context("Failed to seek remote player")
+    }
+
     /// Set MPRIS player volume
     ///
     /// # Arguments
@@ -1351,6 +1802,52 @@ impl DbusClient {
             .context("Failed to set device plugin enabled")
     }
 
+    /// Allow or revoke a privileged plugin for a device
+    ///
+    /// # Arguments
+    /// * `device_id` - Device ID
+    /// * `plugin` - Privileged plugin name ("power", "runcommand", "remoteinput")
+    /// * `allowed` - Whether the device may use the plugin
+    pub async fn set_device_privileged_plugin(
+        &self,
+        device_id: &str,
+        plugin: &str,
+        allowed: bool,
+    ) -> Result<()> {
+        info!(
+            "Setting privileged plugin {} to {} for device {}",
+            plugin,
+            if allowed { "allowed" } else { "revoked" },
+            device_id
+        );
+        self.proxy
+            .set_device_privileged_plugin(device_id, plugin, allowed)
+            .await
+            .context("Failed to set device privileged plugin")
+    }
+
+    /// Set a device's nickname and plugin states in one call
+    ///
+    /// The daemon rejects a blank nickname and starts or stops changed
+    /// plugins on the device immediately.
+    ///
+    /// # Arguments
+    /// * `device_id` - Device ID
+    /// * `nickname` - Device nickname
+    /// * `plugins` - Plugin name to enabled state
+    pub async fn set_device_config(
+        &self,
+        device_id: &str,
+        nickname: &str,
+        plugins: HashMap<String, bool>,
+    ) -> Result<()> {
+        info!("Setting config for device {}: '{}'", device_id, nickname);
+        self.proxy
+            .set_device_config(device_id, nickname, plugins)
+            .await
+            .context("Failed to set device config")
+    }
+
     /// Clear device-specific plugin override
     ///
     /// # Arguments
@@ -1484,6 +1981,36 @@ impl DbusClient {
             .context("Failed to call get_sync_folders")
     }
 
+    /// List a directory on a device's SFTP share (empty path for the root)
+    pub async fn sftp_list_dir(&self, device_id: &str, path: &str) -> Result<Vec<SftpEntryInfo>> {
+        self.proxy
+            .sftp_list_dir(device_id, path)
+            .await
+            .context("Failed to call sftp_list_dir")
+    }
+
+    /// Read a file from a device's SFTP share
+    pub async fn sftp_read_file(&self, device_id: &str, path: &str) -> Result<Vec<u8>> {
+        self.proxy
+            .sftp_read_file(device_id, path)
+            .await
+            .context("Failed to call sftp_read_file")
+    }
+
+    /// Write a file to a device's SFTP share
+    pub async fn sftp_write_file(&self, device_id: &str, path: &str, data: &[u8]) -> Result<()> {
+        info!(
+            "Writing {} bytes to {} on device {}",
+            data.len(),
+            path,
+            device_id
+        );
+        self.proxy
+            .sftp_write_file(device_id, path, data)
+            .await
+            .context("Failed to call sftp_write_file")
+    }
+
     /// Add a run command
     pub async fn add_run_command(
         &self,
@@ -1606,6 +2133,35 @@ impl DbusClient {
             .context("Failed to send power action")
     }
 
+    /// Snooze forwarded notifications from an app for `duration_secs`
+    pub async fn snooze_app(&self, app_name: &str, duration_secs: u64) -> Result<u64> {
+        info!(
+            "Snoozing notifications from '{}' for {}s",
+            app_name, duration_secs
+        );
+        self.proxy
+            .snooze_app(app_name, duration_secs)
+            .await
+            .context("Failed to snooze app")
+    }
+
+    /// Resume forwarding notifications from a snoozed app
+    pub async fn unsnooze_app(&self, app_name: &str) -> Result<bool> {
+        info!("Clearing notification snooze for '{}'", app_name);
+        self.proxy
+            .unsnooze_app(app_name)
+            .await
+            .context("Failed to unsnooze app")
+    }
+
+    /// Get currently snoozed apps with their expiry timestamps
+    pub async fn get_snoozed_apps(&self) -> Result<HashMap<String, u64>> {
+        self.proxy
+            .get_snoozed_apps()
+            .await
+            .context("Failed to get snoozed apps")
+    }
+
     /// Wake-on-LAN
     pub async fn wake_device(&self, device_id: &str) -> Result<()> {
         info!("Sending Wake-on-LAN to device {}", device_id);
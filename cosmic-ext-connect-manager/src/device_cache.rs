@@ -0,0 +1,276 @@
+//! Offline Device Cache
+//!
+//! Remembers the paired devices seen in earlier sessions so the device list
+//! has something to show before the daemon answers, or while it is not
+//! running at all. Only paired devices are kept; transient discoveries are
+//! never written to the cache.
+
+use crate::dbus_client::DeviceInfo;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+/// A paired device as last seen by the daemon
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedDevice {
+    /// Device name
+    pub name: String,
+    /// Device type
+    pub device_type: String,
+    /// Last seen timestamp (UNIX timestamp)
+    #[serde(default)]
+    pub last_seen: i64,
+}
+
+impl CachedDevice {
+    /// Whether both entries match apart from the last seen timestamp
+    fn same_device(&self, other: &Self) -> bool {
+        self.name == other.name && self.device_type == other.device_type
+    }
+}
+
+/// How an update changed the cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CacheChange {
+    /// Nothing changed
+    None,
+    /// Only last seen timestamps moved
+    LastSeen,
+    /// Devices were added, removed or renamed
+    Devices,
+}
+
+/// Paired devices known from earlier sessions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceCache {
+    /// Cached devices by device ID
+    #[serde(default)]
+    pub devices: BTreeMap<String, CachedDevice>,
+}
+
+impl DeviceCache {
+    /// Get the cache file path
+    fn cache_path() -> PathBuf {
+        dirs::cache_dir()
+            .unwrap_or_else(|| PathBuf::from(".cache"))
+            .join("io.github.olafkfreund.CosmicExtConnect.Manager")
+            .join("devices.toml")
+    }
+
+    /// Load the cache from file, empty if not found
+    pub fn load() -> Result<Self> {
+        let cache_path = Self::cache_path();
+
+        if cache_path.exists() {
+            let contents =
+                fs::read_to_string(&cache_path).context("Failed to read device cache file")?;
+            toml::from_str(&contents).context("Failed to parse device cache file")
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Save the cache to file
+    pub fn save(&self) -> Result<()> {
+        let cache_path = Self::cache_path();
+
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create cache directory")?;
+        }
+
+        let contents = toml::to_string_pretty(self).context("Failed to serialize device cache")?;
+        fs::write(&cache_path, contents).context("Failed to write device cache file")?;
+
+        tracing::debug!("Saved device cache to {}", cache_path.display());
+        Ok(())
+    }
+
+    /// Record the paired devices among live daemon data
+    ///
+    /// Devices the daemon reports as no longer paired are dropped. Returns
+    /// how the cache changed, so callers can save last seen updates less
+    /// often than other changes.
+    pub fn update(&mut self, devices: &HashMap<String, DeviceInfo>) -> CacheChange {
+        let mut change = CacheChange::None;
+
+        for (device_id, device) in devices {
+            if !device.is_paired {
+                if self.devices.remove(device_id).is_some() {
+                    change = CacheChange::Devices;
+                }
+                continue;
+            }
+
+            let previous = self.devices.get(device_id);
+            let last_seen = previous.map_or(device.last_seen, |cached| {
+                cached.last_seen.max(device.last_seen)
+            });
+            let cached = CachedDevice {
+                name: device.name.clone(),
+                device_type: device.device_type.clone(),
+                last_seen,
+            };
+            let device_change = match previous {
+                Some(previous) if *previous == cached => CacheChange::None,
+                Some(previous) if previous.same_device(&cached) => CacheChange::LastSeen,
+                _ => CacheChange::Devices,
+            };
+            if device_change != CacheChange::None {
+                self.devices.insert(device_id.clone(), cached);
+                change = change.max(device_change);
+            }
+        }
+
+        change
+    }
+
+    /// Drop cached devices missing from the daemon's full device list
+    ///
+    /// The daemon lists every paired device, reachable or not, so one it
+    /// no longer lists was forgotten. Returns whether any were dropped.
+    pub fn retain_listed(&mut self, devices: &HashMap<String, DeviceInfo>) -> bool {
+        let before = self.devices.len();
+        self.devices
+            .retain(|device_id, _| devices.contains_key(device_id));
+        self.devices.len() != before
+    }
+
+    /// Forget a device, returns whether it was cached
+    pub fn remove(&mut self, device_id: &str) -> bool {
+        self.devices.remove(device_id).is_some()
+    }
+
+    /// Cached devices as offline device entries
+    pub fn offline_devices(&self) -> HashMap<String, DeviceInfo> {
+        self.devices
+            .iter()
+            .map(|(device_id, cached)| {
+                let device = DeviceInfo {
+                    id: device_id.clone(),
+                    name: cached.name.clone(),
+                    device_type: cached.device_type.clone(),
+                    is_paired: true,
+                    is_reachable: false,
+                    is_connected: false,
+                    has_pairing_request: false,
+                    last_seen: cached.last_seen,
+                    incoming_capabilities: Vec::new(),
+                    outgoing_capabilities: Vec::new(),
+                };
+                (device_id.clone(), device)
+            })
+            .collect()
+    }
+
+    /// Live devices, plus offline entries for cached devices they lack
+    pub fn merge_live(&self, live: HashMap<String, DeviceInfo>) -> HashMap<String, DeviceInfo> {
+        let mut devices = self.offline_devices();
+        devices.extend(live);
+        devices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(id: &str, is_paired: bool, last_seen: i64) -> DeviceInfo {
+        DeviceInfo {
+            id: id.to_string(),
+            name: format!("{} name", id),
+            device_type: "phone".to_string(),
+            is_paired,
+            is_reachable: true,
+            is_connected: is_paired,
+            has_pairing_request: false,
+            last_seen,
+            incoming_capabilities: Vec::new(),
+            outgoing_capabilities: Vec::new(),
+        }
+    }
+
+    fn live(devices: &[DeviceInfo]) -> HashMap<String, DeviceInfo> {
+        devices
+            .iter()
+            .map(|device| (device.id.clone(), device.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_only_paired_devices_cached() {
+        let mut cache = DeviceCache::default();
+
+        assert_eq!(
+            cache.update(&live(&[
+                device("phone", true, 100),
+                device("tv", false, 100)
+            ])),
+            CacheChange::Devices
+        );
+        assert_eq!(cache.devices.len(), 1);
+        assert_eq!(cache.devices["phone"].last_seen, 100);
+
+        // Same data again is not a change
+        assert_eq!(
+            cache.update(&live(&[device("phone", true, 100)])),
+            CacheChange::None
+        );
+
+        // A newer timestamp only moves last seen
+        assert_eq!(
+            cache.update(&live(&[device("phone", true, 150)])),
+            CacheChange::LastSeen
+        );
+
+        // An unpaired device is dropped
+        assert_eq!(
+            cache.update(&live(&[device("phone", false, 200)])),
+            CacheChange::Devices
+        );
+        assert!(cache.devices.is_empty());
+    }
+
+    #[test]
+    fn test_full_list_drops_forgotten_devices() {
+        let mut cache = DeviceCache::default();
+        cache.update(&live(&[
+            device("phone", true, 100),
+            device("tablet", true, 50),
+        ]));
+
+        assert!(cache.retain_listed(&live(&[device("phone", false, 100)])));
+        assert_eq!(cache.devices.keys().collect::<Vec<_>>(), vec!["phone"]);
+        assert!(!cache.retain_listed(&live(&[device("phone", true, 100)])));
+    }
+
+    #[test]
+    fn test_live_devices_replace_cached_entries() {
+        let mut cache = DeviceCache::default();
+        cache.update(&live(&[
+            device("phone", true, 100),
+            device("tablet", true, 50),
+        ]));
+
+        let offline = cache.offline_devices();
+        assert!(offline.values().all(|d| d.is_paired && !d.is_reachable));
+
+        let merged = cache.merge_live(live(&[device("phone", true, 200)]));
+        assert_eq!(merged.len(), 2);
+        assert!(merged["phone"].is_connected);
+        assert_eq!(merged["phone"].last_seen, 200);
+        assert!(!merged["tablet"].is_reachable);
+        assert_eq!(merged["tablet"].last_seen, 50);
+    }
+
+    #[test]
+    fn test_cache_round_trips_through_toml() {
+        let mut cache = DeviceCache::default();
+        cache.update(&live(&[device("phone", true, 100)]));
+
+        let contents = toml::to_string_pretty(&cache).unwrap();
+        let loaded: DeviceCache = toml::from_str(&contents).unwrap();
+        assert_eq!(loaded.devices, cache.devices);
+    }
+}
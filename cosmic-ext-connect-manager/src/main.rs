@@ -1,11 +1,12 @@
 mod dbus_client;
+mod device_cache;
 
 use clap::Parser;
 use cosmic::{
     app::{Core, Task},
     iced::{Alignment, Length, Size},
     theme,
-    widget::{button, column, container, divider, horizontal_space, icon, row, scrollable, text, toggler, vertical_space},
+    widget::{button, column, container, divider, horizontal_space, icon, progress_bar, row, scrollable, slider, text, toggler, vertical_space},
     Application, Element,
 };
 
@@ -42,11 +43,69 @@ impl Page {
     }
 }
 
-use dbus_client::{DaemonEvent, DbusClient, DeviceConfig, DeviceInfo, RunCommand};
+use dbus_client::{
+    AuditLogEntry, DaemonEvent, DbusClient, DeviceConfig, DeviceInfo, HistoryEntry, RemotePlayer,
+    RunCommand, Transfer,
+};
+use device_cache::{CacheChange, DeviceCache};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 const APP_ID: &str = "io.github.olafkfreund.CosmicExtConnect.Manager";
 
+/// Quiet period after the last slider movement before it is sent to the device
+const MEDIA_SLIDER_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Delay before saving a device cache change that only moved last seen times
+const DEVICE_CACHE_SAVE_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Number of history events shown per page
+const HISTORY_PAGE_SIZE: u32 = 50;
+
+/// History filter showing the privileged action audit log instead of events
+const HISTORY_AUDIT_TYPE: &str = "audit";
+
+/// History event types the History page can filter on: (event_type, label)
+const HISTORY_EVENT_TYPES: [(&str, &str); 6] = [
+    ("ping", "Pings"),
+    ("transfer", "Transfers"),
+    ("sms", "SMS"),
+    ("notification", "Notifications"),
+    ("pairing", "Pairing"),
+    (HISTORY_AUDIT_TYPE, "Privileged actions"),
+];
+
+/// Plugins that can be switched per device: (plugin, label)
+const DEVICE_SETTINGS_PLUGINS: [(&str, &str); 9] = [
+    ("ping", "Ping"),
+    ("battery", "Battery"),
+    ("notification", "Notifications"),
+    ("share", "File Sharing"),
+    ("clipboard", "Clipboard"),
+    ("mpris", "Media Control"),
+    ("remotedesktop", "Remote Desktop"),
+    ("findmyphone", "Find My Phone"),
+    ("lock", "Lock Screen"),
+];
+
+/// Remote media player identity: (device_id, player)
+pub type RemotePlayerKey = (String, String);
+
+/// Slider changes waiting for the debounce period to pass
+#[derive(Debug, Default)]
+struct PendingMediaChange {
+    volume: Option<f64>,
+    position_us: Option<i64>,
+    generation: u64,
+}
+
+/// Device settings from before an optimistic change, restored if saving fails
+#[derive(Debug, Clone, Default)]
+pub struct DeviceSettingsRollback {
+    nickname: Option<String>,
+    plugins: HashMap<String, bool>,
+}
+
 #[derive(Parser, Debug, Clone)]
 #[command(name = "cosmic-ext-connect-manager")]
 #[command(about = "COSMIC Connect Device Manager")]
@@ -376,30 +435,108 @@ fn main() -> cosmic::iced::Result {
     cosmic::app::run::<CosmicConnectManager>(settings, args)
 }
 
-#[derive(Debug, Clone)]
-pub struct TransferInfo {
-    pub transfer_id: String,
-    pub device_id: String,
-    pub filename: String,
-    pub current: u64,
-    pub total: u64,
-    pub direction: String,
+/// Format a byte count for display, e.g. "4.2 MB"
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct CompletedTransfer {
-    pub filename: String,
-    pub size: u64,
-    pub timestamp: chrono::DateTime<chrono::Local>,
-    pub success: bool,
+/// Format a duration in milliseconds as "m:ss"
+fn format_duration(ms: i64) -> String {
+    let secs = ms.max(0) / 1000;
+    format!("{}:{:02}", secs / 60, secs % 60)
 }
 
-#[derive(Debug, Clone)]
-pub struct HistoryEvent {
-    pub icon_name: String,
-    pub event_type: String,
-    pub description: String,
-    pub timestamp: chrono::DateTime<chrono::Local>,
+/// Icon for a transferred file, based on its extension
+fn file_icon_name(filename: &str) -> &'static str {
+    if filename.ends_with(".jpg") || filename.ends_with(".png") {
+        "image-x-generic-symbolic"
+    } else {
+        "text-x-generic-symbolic"
+    }
+}
+
+/// Icon for a history event type
+fn history_icon_name(event_type: &str) -> &'static str {
+    match event_type {
+        "ping" => "emblem-ok-symbolic",
+        "transfer" => "folder-download-symbolic",
+        "sms" => "mail-message-new-symbolic",
+        "notification" => "preferences-system-notifications-symbolic",
+        "pairing" => "network-wireless-symbolic",
+        HISTORY_AUDIT_TYPE => "security-high-symbolic",
+        _ => "document-open-recent-symbolic",
+    }
+}
+
+/// Show an audit log entry as a History page event
+fn audit_history_entry(entry: AuditLogEntry) -> HistoryEntry {
+    let outcome = if entry.allowed {
+        "allowed".to_string()
+    } else if entry.reason.is_empty() {
+        "denied".to_string()
+    } else {
+        format!("denied: {}", entry.reason)
+    };
+    HistoryEntry {
+        id: 0,
+        timestamp: entry.timestamp,
+        device_id: entry.device_id,
+        event_type: HISTORY_AUDIT_TYPE.to_string(),
+        summary: format!("{} {} {}", entry.plugin, entry.action, outcome),
+    }
+}
+
+/// Relative time for recent history events, date and time for older ones
+fn format_history_time(timestamp_ms: i64) -> String {
+    let Some(time) = chrono::DateTime::from_timestamp_millis(timestamp_ms) else {
+        return String::new();
+    };
+    let time = time.with_timezone(&chrono::Local);
+    let diff = chrono::Local::now().signed_duration_since(time);
+
+    if diff.num_minutes() < 1 {
+        "Just now".to_string()
+    } else if diff.num_minutes() < 60 {
+        format!("{} minutes ago", diff.num_minutes())
+    } else if diff.num_hours() < 24 {
+        format!("{} hours ago", diff.num_hours())
+    } else {
+        time.format("%b %d, %H:%M").to_string()
+    }
+}
+
+/// Relative time a device was last seen, from a UNIX timestamp in seconds
+fn format_last_seen(timestamp: i64) -> String {
+    let Some(time) = chrono::DateTime::from_timestamp(timestamp, 0) else {
+        return String::new();
+    };
+    let diff = chrono::Utc::now().signed_duration_since(time);
+
+    if diff.num_minutes() < 1 {
+        "Last seen just now".to_string()
+    } else if diff.num_minutes() < 60 {
+        format!("Last seen {} minutes ago", diff.num_minutes())
+    } else if diff.num_hours() < 24 {
+        format!("Last seen {} hours ago", diff.num_hours())
+    } else if diff.num_days() < 30 {
+        format!("Last seen {} days ago", diff.num_days())
+    } else {
+        format!(
+            "Last seen {}",
+            time.with_timezone(&chrono::Local).format("%b %d, %Y")
+        )
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -407,6 +544,8 @@ pub enum Message {
     NavigateTo(Page),
     SelectDevice(String),
     DevicesUpdated(HashMap<String, DeviceInfo>),
+    DevicesRefreshed(HashMap<String, DeviceInfo>), // full list from the daemon
+    SaveDeviceCache,
     DeviceConfigLoaded(String, DeviceConfig),
     ExecuteAction(String, DeviceAction),
     DbusReady(DbusClient),
@@ -414,23 +553,44 @@ pub enum Message {
     MediaNext(String),
     MediaPrevious(String),
     CancelTransfer(String),
+    RefreshHistory,
+    HistoryLoaded(Vec<HistoryEntry>, u64), // page, total matching events
+    HistoryPage(u32),
+    HistoryDeviceFilter(Option<String>),
+    HistoryTypeFilter(Option<&'static str>),
     ClearHistory,
     ToggleAutoStart(bool),
     ToggleNotifications(bool),
     TogglePlugin(String, bool),
+    // Notification snooze messages
+    RefreshSnoozedApps,
+    SnoozedAppsLoaded(HashMap<String, u64>),
+    SnoozeAppNameChanged(String),
+    SnoozeApp(String, u64), // app_name, duration_secs
+    UnsnoozeApp(String),
     DbusConnected(DbusClient),
     DbusError(String),
-    DeviceAdded(String, DeviceInfo),
     DeviceRemoved(String),
-    DeviceStateChanged(String, String),
     MprisPlayersLoaded(Vec<String>),
     MprisPlayerStateLoaded(String, dbus_client::PlayerState),
-    TransferProgressUpdate(TransferInfo),
+    RefreshTransfers,
+    TransfersLoaded(Vec<Transfer>),
+    TransferUpdated(Transfer),
+    RefreshRemotePlayers,
+    RemotePlayersLoaded(Vec<RemotePlayer>),
+    RemotePlayerUpdated(RemotePlayer),
+    RemotePlayerRemoved(String, String), // device_id, player
+    RemoteMediaControl(String, String, &'static str), // device_id, player, action
+    MediaSetVolume(RemotePlayerKey, f64),
+    MediaSeek(RemotePlayerKey, i64), // position in microseconds
+    MediaCommitChange(RemotePlayerKey, u64), // generation
+    MediaTick,
     TransferCompleted(String, String, String, bool, String),
-    AddHistoryEvent(HistoryEvent),
     RefreshDevices,
     RefreshMprisPlayers,
     BatteryStatusLoaded(String, dbus_client::BatteryStatus),
+    LatencyMeasured(String, dbus_client::PingLatency),
+    CertificateFingerprintLoaded(String, String),
     DaemonEventReceived(DaemonEvent),
     OpenRunCommandDialog(String),
     CloseRunCommandDialog,
@@ -451,8 +611,10 @@ pub enum Message {
     CloseDeviceSettings,
     DeviceSettingsLoaded(DeviceConfig),
     SaveDeviceSettings,
+    DeviceSettingsSaveFailed(String, DeviceSettingsRollback, String),
     DeviceNicknameChanged(String),
     DevicePluginToggled(String, bool),
+    SelectSettingsDevice(String),
     // Unpair device messages
     UnpairDevice(String),
     ConfirmUnpairDevice(String),
@@ -466,6 +628,9 @@ pub enum Message {
     OpenPowerDialog(String),
     ClosePowerDialog,
     ExecutePowerAction(String, String), // device_id, action
+    // Pairing request dialog messages
+    AcceptPairing(String),
+    RejectPairing(String),
     // File picker
     FileSelected(String, String),
     // Extended display state updates
@@ -487,8 +652,12 @@ pub struct CosmicConnectManager {
     active_page: Page,
     dbus_client: Option<DbusClient>,
     devices: HashMap<String, DeviceInfo>,
+    device_cache: DeviceCache,
+    device_cache_save_queued: bool,
     device_configs: HashMap<String, DeviceConfig>,
     battery_status: HashMap<String, dbus_client::BatteryStatus>,
+    ping_latency: HashMap<String, dbus_client::PingLatency>,
+    certificate_fingerprints: HashMap<String, String>,
     selected_device: Option<String>,
     _initial_device: Option<String>,
     _initial_action: Option<DeviceAction>,
@@ -501,11 +670,22 @@ pub struct CosmicConnectManager {
     auto_start_enabled: bool,
     show_notifications: bool,
     plugin_states: HashMap<String, bool>,
+    // Notification snooze state (app name -> expiry UNIX seconds)
+    snoozed_apps: HashMap<String, u64>,
+    snooze_app_name: String,
     mpris_players: Vec<(String, Option<dbus_client::PlayerState>)>,
-    active_transfers: HashMap<String, TransferInfo>,
-    completed_transfers: Vec<CompletedTransfer>,
-    history_events: Vec<HistoryEvent>,
-    _event_rx: Option<tokio::sync::mpsc::UnboundedReceiver<DaemonEvent>>,
+    remote_players: HashMap<RemotePlayerKey, RemotePlayer>,
+    pending_media: HashMap<RemotePlayerKey, PendingMediaChange>,
+    media_generation: u64,
+    transfers: HashMap<String, Transfer>,
+    // History page: current page of events and its filters
+    history_entries: Vec<HistoryEntry>,
+    history_total: u64,
+    history_page: u32,
+    history_device_filter: Option<String>,
+    history_type_filter: Option<&'static str>,
+    // Daemon signal receiver, taken by the event subscription once connected
+    event_rx: Arc<Mutex<Option<tokio::sync::mpsc::UnboundedReceiver<DaemonEvent>>>>,
     show_runcommand_dialog: bool,
     runcommand_device_id: Option<String>,
     available_commands: HashMap<String, RunCommand>,
@@ -532,6 +712,8 @@ pub struct CosmicConnectManager {
     // Power dialog state
     show_power_dialog: bool,
     power_device_id: Option<String>,
+    // Pairing request awaiting an answer (device_id, certificate fingerprint)
+    pairing_request: Option<(String, String)>,
     // Extended display state
     extended_display_devices: std::collections::HashSet<String>,
     // Status message for action feedback
@@ -643,6 +825,32 @@ impl CosmicConnectManager {
         .into()
     }
 
+    /// Save the device cache off the UI thread
+    fn save_device_cache(&self) -> Task<Message> {
+        let cache = self.device_cache.clone();
+        cosmic::task::future(async move {
+            let saved = tokio::task::spawn_blocking(move || cache.save()).await;
+            if let Ok(Err(e)) = saved {
+                tracing::warn!("Failed to save device cache: {}", e);
+            }
+            Message::None
+        })
+    }
+
+    /// Save the device cache after [`DEVICE_CACHE_SAVE_DELAY`]
+    ///
+    /// Changes made while a save is queued go out with it.
+    fn queue_device_cache_save(&mut self) -> Task<Message> {
+        if self.device_cache_save_queued {
+            return Task::none();
+        }
+        self.device_cache_save_queued = true;
+        cosmic::task::future(async {
+            tokio::time::sleep(DEVICE_CACHE_SAVE_DELAY).await;
+            Message::SaveDeviceCache
+        })
+    }
+
     fn device_list_view(&self) -> Element<'_, Message> {
         let mut connected_devices = Vec::new();
         let mut available_devices = Vec::new();
@@ -715,7 +923,22 @@ impl CosmicConnectManager {
 
         sections = sections.push(text("Media Players").size(18));
 
-        if self.mpris_players.is_empty() {
+        if !self.remote_players.is_empty() {
+            let now = chrono::Utc::now().timestamp_millis();
+            let mut players: Vec<&RemotePlayer> = self.remote_players.values().collect();
+            players.sort_by(|a, b| (&a.device_id, &a.name).cmp(&(&b.device_id, &b.name)));
+            for player in players {
+                sections = sections.push(self.remote_player_card(player, now));
+            }
+        }
+
+        if !self.mpris_players.is_empty() {
+            sections = sections.push(text("This Computer").size(16));
+            for (player_id, state) in &self.mpris_players {
+                sections =
+                    sections.push(self.media_player_card_with_state(player_id, state.as_ref()));
+            }
+        } else if self.remote_players.is_empty() {
             sections = sections.push(
                 container(
                     column::with_capacity(3)
@@ -728,11 +951,6 @@ impl CosmicConnectManager {
                 .center_x(Length::Fill)
                 .center_y(Length::Fill),
             );
-        } else {
-            for (player_id, state) in &self.mpris_players {
-                sections =
-                    sections.push(self.media_player_card_with_state(player_id, state.as_ref()));
-            }
         }
 
         container(sections)
@@ -741,6 +959,313 @@ impl CosmicConnectManager {
             .into()
     }
 
+    /// Record a slider change and (re)start its debounce period
+    fn queue_media_change(
+        &mut self,
+        key: RemotePlayerKey,
+        apply: impl FnOnce(&mut PendingMediaChange),
+    ) -> Task<Message> {
+        self.media_generation += 1;
+        let generation = self.media_generation;
+
+        let change = self.pending_media.entry(key.clone()).or_default();
+        apply(change);
+        change.generation = generation;
+
+        cosmic::task::future(async move {
+            tokio::time::sleep(MEDIA_SLIDER_DEBOUNCE).await;
+            Message::MediaCommitChange(key, generation)
+        })
+    }
+
+    /// Load a device's config into the device settings state
+    fn load_device_settings(&mut self, device_id: String) -> Task<Message> {
+        self.settings_device_id = Some(device_id.clone());
+        self.device_settings_config = None;
+        self.device_settings_nickname.clear();
+        self.device_settings_plugins.clear();
+
+        let Some(client) = self.dbus_client.clone() else {
+            return Task::none();
+        };
+        cosmic::task::future(async move {
+            match client.get_device_config(&device_id).await {
+                Ok(config) => Message::DeviceSettingsLoaded(config),
+                Err(e) => {
+                    tracing::error!("Failed to load device config: {}", e);
+                    Message::None
+                }
+            }
+        })
+    }
+
+    /// The nickname last saved for a device, falling back to its name
+    fn saved_device_nickname(&self, device_id: &str) -> String {
+        self.device_configs
+            .get(device_id)
+            .and_then(|config| config.nickname.clone())
+            .or_else(|| {
+                self.device_settings_config
+                    .as_ref()
+                    .filter(|config| config.device_id == device_id)
+                    .and_then(|config| config.nickname.clone())
+            })
+            .or_else(|| {
+                self.devices
+                    .get(device_id)
+                    .map(|device| device.name.clone())
+            })
+            .unwrap_or_else(|| device_id.to_string())
+    }
+
+    /// Send device settings to the daemon, rolling back the UI if it refuses
+    fn save_device_settings(
+        &self,
+        device_id: String,
+        nickname: String,
+        plugins: HashMap<String, bool>,
+        rollback: DeviceSettingsRollback,
+    ) -> Task<Message> {
+        let Some(client) = self.dbus_client.clone() else {
+            return Task::none();
+        };
+        cosmic::task::future(async move {
+            match client
+                .set_device_config(&device_id, &nickname, plugins)
+                .await
+            {
+                Ok(()) => Message::None,
+                Err(e) => {
+                    tracing::error!("Failed to save device settings: {}", e);
+                    Message::DeviceSettingsSaveFailed(device_id, rollback, e.to_string())
+                }
+            }
+        })
+    }
+
+    /// Per-device nickname and plugin toggles for the Settings page
+    fn device_settings_section(&self) -> Element<'_, Message> {
+        use cosmic::widget::text_input;
+
+        let mut section = column::with_capacity(4).spacing(theme::active().cosmic().space_s());
+
+        let mut devices: Vec<(&String, &DeviceInfo)> = self
+            .devices
+            .iter()
+            .filter(|(_, device)| device.is_paired)
+            .collect();
+        devices.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+        if devices.is_empty() {
+            return section
+                .push(text("Pair a device to change its settings.").size(14))
+                .into();
+        }
+
+        let mut device_row =
+            row::with_capacity(devices.len()).spacing(theme::active().cosmic().space_xxs());
+        for (device_id, device) in devices {
+            let selected = self.settings_device_id.as_ref() == Some(device_id);
+            device_row = device_row.push(
+                button::text(device.name.clone())
+                    .on_press(Message::SelectSettingsDevice(device_id.clone()))
+                    .class(if selected {
+                        theme::Button::Suggested
+                    } else {
+                        theme::Button::Standard
+                    })
+                    .padding(theme::active().cosmic().space_xxs()),
+            );
+        }
+        section = section.push(device_row);
+
+        if self.settings_device_id.is_none() {
+            return section
+                .push(text("Select a device to change its settings.").size(14))
+                .into();
+        }
+        if self.device_settings_config.is_none() {
+            return section.push(text("Loading...").size(14)).into();
+        }
+
+        let nickname_valid = !self.device_settings_nickname.trim().is_empty();
+        let mut nickname_section = column::with_capacity(2)
+            .spacing(theme::active().cosmic().space_xxs())
+            .push(
+                row::with_capacity(2)
+                    .spacing(theme::active().cosmic().space_s())
+                    .align_y(Alignment::Center)
+                    .push(
+                        text_input("Device nickname", &self.device_settings_nickname)
+                            .on_input(Message::DeviceNicknameChanged)
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        button::text("Save")
+                            .on_press_maybe(nickname_valid.then_some(Message::SaveDeviceSettings))
+                            .class(theme::Button::Suggested)
+                            .padding(theme::active().cosmic().space_xxs()),
+                    ),
+            );
+        if !nickname_valid {
+            nickname_section = nickname_section.push(text("Nickname can't be empty").size(12));
+        }
+        section = section.push(nickname_section);
+
+        for (plugin_id, plugin_name) in DEVICE_SETTINGS_PLUGINS {
+            let enabled = self
+                .device_settings_plugins
+                .get(plugin_id)
+                .copied()
+                .unwrap_or(false);
+            section = section.push(
+                row::with_capacity(3)
+                    .spacing(theme::active().cosmic().space_s())
+                    .align_y(Alignment::Center)
+                    .push(text(plugin_name).size(14))
+                    .push(horizontal_space())
+                    .push(toggler(enabled).on_toggle(move |enabled| {
+                        Message::DevicePluginToggled(plugin_id.to_string(), enabled)
+                    })),
+            );
+        }
+
+        section.into()
+    }
+
+    fn remote_player_card(&self, player: &RemotePlayer, now: i64) -> Element<'_, Message> {
+        let art: Element<'_, Message> = if player.album_art_path.is_empty() {
+            container(icon::from_name("audio-x-generic-symbolic").size(48))
+                .width(Length::Fixed(64.0))
+                .center_x(Length::Fixed(64.0))
+                .into()
+        } else {
+            cosmic::widget::image(cosmic::iced::widget::image::Handle::from_path(
+                &player.album_art_path,
+            ))
+            .width(Length::Fixed(64.0))
+            .height(Length::Fixed(64.0))
+            .content_fit(cosmic::iced::ContentFit::Cover)
+            .into()
+        };
+
+        let device_name = self
+            .devices
+            .get(&player.device_id)
+            .map(|device| device.name.as_str())
+            .unwrap_or(&player.device_id);
+
+        let title = if player.title.is_empty() {
+            "No track playing".to_string()
+        } else {
+            player.title.clone()
+        };
+        let details = [player.artist.as_str(), player.album.as_str()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" - ");
+
+        let info_column = column::with_capacity(3)
+            .spacing(theme::active().cosmic().space_xxs())
+            .push(text(title).size(16))
+            .push(text(details).size(12))
+            .push(text(format!("{} on {}", player.name, device_name)).size(11));
+
+        let header_row = row::with_capacity(2)
+            .spacing(theme::active().cosmic().space_s())
+            .align_y(Alignment::Center)
+            .push(art)
+            .push(info_column);
+
+        let mut card_content = column::with_capacity(3)
+            .spacing(theme::active().cosmic().space_s())
+            .push(header_row);
+
+        let key = (player.device_id.clone(), player.name.clone());
+        let pending = self.pending_media.get(&key);
+
+        if player.length_ms > 0 {
+            let position = pending
+                .and_then(|change| change.position_us)
+                .map(|position_us| position_us / 1000)
+                .unwrap_or_else(|| player.position_at(now));
+            let position_control: Element<'_, Message> = if player.can_seek {
+                let key = key.clone();
+                slider(0.0..=player.length_ms as f64, position as f64, move |ms| {
+                    Message::MediaSeek(key.clone(), (ms * 1000.0) as i64)
+                })
+                .into()
+            } else {
+                progress_bar(0.0..=player.length_ms as f32, position as f32).into()
+            };
+            card_content = card_content.push(
+                row::with_capacity(2)
+                    .spacing(theme::active().cosmic().space_s())
+                    .align_y(Alignment::Center)
+                    .push(position_control)
+                    .push(
+                        text(format!(
+                            "{} / {}",
+                            format_duration(position),
+                            format_duration(player.length_ms)
+                        ))
+                        .size(12),
+                    ),
+            );
+        }
+
+        let control = |icon_name: &'static str, action: &'static str, enabled: bool| {
+            button::icon(icon::from_name(icon_name).size(16))
+                .on_press_maybe(enabled.then(|| {
+                    Message::RemoteMediaControl(
+                        player.device_id.clone(),
+                        player.name.clone(),
+                        action,
+                    )
+                }))
+                .padding(theme::active().cosmic().space_xxs())
+        };
+
+        let play_pause_button = if player.is_playing {
+            control("media-playback-pause-symbolic", "Pause", player.can_pause)
+        } else {
+            control("media-playback-start-symbolic", "Play", player.can_play)
+        };
+
+        let volume = pending
+            .and_then(|change| change.volume)
+            .unwrap_or(player.volume as f64 / 100.0);
+
+        let controls_row = row::with_capacity(6)
+            .spacing(theme::active().cosmic().space_xs())
+            .align_y(Alignment::Center)
+            .push(control(
+                "media-skip-backward-symbolic",
+                "Previous",
+                player.can_go_previous,
+            ))
+            .push(play_pause_button)
+            .push(control(
+                "media-skip-forward-symbolic",
+                "Next",
+                player.can_go_next,
+            ))
+            .push(horizontal_space())
+            .push(icon::from_name("audio-volume-medium-symbolic").size(16))
+            .push(
+                slider(0.0..=1.0, volume, move |volume| {
+                    Message::MediaSetVolume(key.clone(), volume)
+                })
+                .step(0.01)
+                .width(Length::Fixed(120.0)),
+            );
+
+        container(card_content.push(controls_row))
+            .padding(theme::active().cosmic().space_s())
+            .width(Length::Fill)
+            .into()
+    }
+
     fn media_player_card_with_state(
         &self,
         player_id: &str,
@@ -868,79 +1393,32 @@ impl CosmicConnectManager {
             .spacing(theme::active().cosmic().space_m())
             .padding(theme::active().cosmic().space_m());
 
-        let active_count = self.active_transfers.len();
-        content = content.push(text(format!("Active Transfers ({})", active_count)).size(16));
-
-        if !self.active_transfers.is_empty() {
-            for (transfer_id, info) in &self.active_transfers {
-                let progress = if info.total > 0 {
-                    ((info.current as f64 / info.total as f64) * 100.0) as u8
-                } else {
-                    0
-                };
-
-                let speed = if info.current > 0 {
-                    format!("{:.1} MB/s", info.current as f64 / 1_000_000.0)
-                } else {
-                    "Calculating...".to_string()
-                };
+        let (mut active, mut finished): (Vec<&Transfer>, Vec<&Transfer>) = self
+            .transfers
+            .values()
+            .partition(|transfer| transfer.is_active());
+        active.sort_by(|a, b| a.id.cmp(&b.id));
+        finished.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
 
-                let icon_name =
-                    if info.filename.ends_with(".pdf") || info.filename.ends_with(".txt") {
-                        "text-x-generic-symbolic"
-                    } else if info.filename.ends_with(".jpg") || info.filename.ends_with(".png") {
-                        "image-x-generic-symbolic"
-                    } else {
-                        "text-x-generic-symbolic"
-                    };
+        content = content.push(text(format!("Active Transfers ({})", active.len())).size(16));
 
-                content = content.push(self.transfer_card(
-                    transfer_id,
-                    &info.filename,
-                    icon_name,
-                    progress,
-                    &speed,
-                    true,
-                ));
+        if !active.is_empty() {
+            for transfer in active {
+                content = content.push(self.transfer_card(transfer));
             }
         } else {
             content = content.push(text("No active transfers").size(14));
         }
 
-        if !self.completed_transfers.is_empty() {
+        if !finished.is_empty() {
             content = content.push(vertical_space().height(theme::active().cosmic().space_m()));
-            content = content
-                .push(text(format!("Completed ({})", self.completed_transfers.len())).size(16));
-
-            let mut completed_col = column::with_capacity(self.completed_transfers.len())
-                .spacing(theme::active().cosmic().space_xs());
-
-            for transfer in &self.completed_transfers {
-                let icon_name = if transfer.filename.ends_with(".pdf")
-                    || transfer.filename.ends_with(".txt")
-                {
-                    "text-x-generic-symbolic"
-                } else if transfer.filename.ends_with(".jpg") || transfer.filename.ends_with(".png")
-                {
-                    "image-x-generic-symbolic"
-                } else {
-                    "text-x-generic-symbolic"
-                };
-
-                let size_str = if transfer.size > 0 {
-                    format!("{:.1} MB", transfer.size as f64 / 1_000_000.0)
-                } else {
-                    "Unknown".to_string()
-                };
+            content = content.push(text(format!("Completed ({})", finished.len())).size(16));
 
-                let time_str = transfer.timestamp.format("%H:%M").to_string();
+            let mut completed_col =
+                column::with_capacity(finished.len()).spacing(theme::active().cosmic().space_xs());
 
-                completed_col = completed_col.push(self.completed_transfer_item(
-                    &transfer.filename,
-                    icon_name,
-                    &size_str,
-                    &time_str,
-                ));
+            for transfer in finished {
+                completed_col = completed_col.push(self.completed_transfer_item(transfer));
             }
 
             content = content.push(completed_col);
@@ -952,47 +1430,52 @@ impl CosmicConnectManager {
             .into()
     }
 
-    fn transfer_card(
-        &self,
-        transfer_id: &str,
-        filename: &str,
-        icon_name: &str,
-        progress: u8,
-        speed: &str,
-        is_active: bool,
-    ) -> Element<'_, Message> {
-        let file_icon = icon::from_name(icon_name).size(24);
-        let filename_text = text(filename.to_string()).size(14);
+    fn transfer_card(&self, transfer: &Transfer) -> Element<'_, Message> {
+        let direction_icon = if transfer.direction == "sending" {
+            "go-up-symbolic"
+        } else {
+            "go-down-symbolic"
+        };
 
-        let header_row = row::with_capacity(2)
+        let header_row = row::with_capacity(3)
             .spacing(theme::active().cosmic().space_s())
             .align_y(Alignment::Center)
-            .push(file_icon)
-            .push(filename_text);
+            .push(icon::from_name(file_icon_name(&transfer.filename)).size(24))
+            .push(text(transfer.filename.clone()).size(14))
+            .push(icon::from_name(direction_icon).size(16));
+
+        let progress = transfer.fraction() * 100.0;
+        let speed = if transfer.speed_bps > 0 {
+            format!("{}/s", format_bytes(transfer.speed_bps))
+        } else {
+            "Calculating...".to_string()
+        };
+        let size = format!(
+            "{} of {}",
+            format_bytes(transfer.transferred_bytes),
+            format_bytes(transfer.total_bytes)
+        );
 
-        let progress_text = format!("{}%", progress);
-        let progress_label = text(progress_text).size(12);
-        let speed_label = text(speed.to_string()).size(12);
+        let cancel_button = button::text("Cancel")
+            .on_press(Message::CancelTransfer(transfer.id.clone()))
+            .class(theme::Button::Destructive)
+            .padding(theme::active().cosmic().space_xxs());
 
-        let mut info_row = row::with_capacity(3)
+        let info_row = row::with_capacity(7)
             .spacing(theme::active().cosmic().space_s())
             .align_y(Alignment::Center)
-            .push(progress_label)
+            .push(text(format!("{:.0}%", progress)).size(12))
             .push(text("·").size(12))
-            .push(speed_label);
-
-        if is_active {
-            let cancel_button = button::text("Cancel")
-                .on_press(Message::CancelTransfer(transfer_id.to_string()))
-                .class(theme::Button::Destructive)
-                .padding(theme::active().cosmic().space_xxs());
-
-            info_row = info_row.push(cancel_button);
-        }
+            .push(text(speed).size(12))
+            .push(text("·").size(12))
+            .push(text(size).size(12))
+            .push(horizontal_space())
+            .push(cancel_button);
 
-        let card_content = column::with_capacity(2)
+        let card_content = column::with_capacity(3)
             .spacing(theme::active().cosmic().space_xs())
             .push(header_row)
+            .push(progress_bar(0.0..=100.0, progress).height(Length::Fixed(6.0)))
             .push(info_row);
 
         container(card_content)
@@ -1001,27 +1484,28 @@ impl CosmicConnectManager {
             .into()
     }
 
-    fn completed_transfer_item(
-        &self,
-        filename: &str,
-        icon_name: &str,
-        size: &str,
-        time: &str,
-    ) -> Element<'_, Message> {
-        let file_icon = icon::from_name(icon_name).size(20);
-        let filename_text = text(filename.to_string()).size(14);
-        let size_text = text(size.to_string()).size(12);
-        let time_text = text(time.to_string()).size(12);
+    fn completed_transfer_item(&self, transfer: &Transfer) -> Element<'_, Message> {
+        let (state_icon, state_label) = match transfer.state.as_str() {
+            "completed" => (file_icon_name(&transfer.filename), "Completed"),
+            "cancelled" => ("process-stop-symbolic", "Cancelled"),
+            _ => ("dialog-error-symbolic", "Failed"),
+        };
+        let time = chrono::TimeZone::timestamp_millis_opt(&chrono::Local, transfer.updated_at)
+            .single()
+            .map(|time| time.format("%H:%M").to_string())
+            .unwrap_or_default();
 
-        let item_row = row::with_capacity(5)
+        let item_row = row::with_capacity(8)
             .spacing(theme::active().cosmic().space_s())
             .align_y(Alignment::Center)
-            .push(file_icon)
-            .push(filename_text)
+            .push(icon::from_name(state_icon).size(20))
+            .push(text(transfer.filename.clone()).size(14))
+            .push(text("-").size(12))
+            .push(text(format_bytes(transfer.total_bytes)).size(12))
             .push(text("-").size(12))
-            .push(size_text)
+            .push(text(state_label).size(12))
             .push(text("-").size(12))
-            .push(time_text);
+            .push(text(time).size(12));
 
         container(item_row)
             .padding(theme::active().cosmic().space_xs())
@@ -1030,24 +1514,87 @@ impl CosmicConnectManager {
     }
 
     fn history_view(&self) -> Element<'_, Message> {
-        let mut content = column::with_capacity(2)
+        let mut content = column::with_capacity(5)
             .spacing(theme::active().cosmic().space_m())
             .padding(theme::active().cosmic().space_m());
 
-        let header = row::with_capacity(2)
+        let clear_label = if self.history_device_filter.is_some() {
+            "Clear Device History"
+        } else {
+            "Clear All"
+        };
+        let header = row::with_capacity(3)
             .spacing(theme::active().cosmic().space_s())
             .align_y(Alignment::Center)
             .push(text("Event History").size(18))
+            .push(horizontal_space())
             .push(
-                button::text("Clear")
-                    .on_press(Message::ClearHistory)
+                button::text(clear_label)
+                    // The audit log is append-only
+                    .on_press_maybe(
+                        (self.history_total > 0
+                            && self.history_type_filter != Some(HISTORY_AUDIT_TYPE))
+                        .then_some(Message::ClearHistory),
+                    )
                     .class(theme::Button::Destructive)
                     .padding(theme::active().cosmic().space_xxs()),
             );
 
         content = content.push(header);
 
-        if self.history_events.is_empty() {
+        // Device filter
+        let filter_button = |label: String, selected: bool, message: Message| {
+            button::text(label)
+                .on_press(message)
+                .class(if selected {
+                    theme::Button::Suggested
+                } else {
+                    theme::Button::Standard
+                })
+                .padding(theme::active().cosmic().space_xxs())
+        };
+
+        let mut devices: Vec<(&String, &DeviceInfo)> = self.devices.iter().collect();
+        devices.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+        let mut device_row = row::with_capacity(devices.len() + 1)
+            .spacing(theme::active().cosmic().space_xxs())
+            .push(filter_button(
+                "All devices".to_string(),
+                self.history_device_filter.is_none(),
+                Message::HistoryDeviceFilter(None),
+            ));
+        for (device_id, device) in devices {
+            device_row = device_row.push(filter_button(
+                device.name.clone(),
+                self.history_device_filter.as_ref() == Some(device_id),
+                Message::HistoryDeviceFilter(Some(device_id.clone())),
+            ));
+        }
+
+        // Event type filter
+        let mut type_row = row::with_capacity(HISTORY_EVENT_TYPES.len() + 1)
+            .spacing(theme::active().cosmic().space_xxs())
+            .push(filter_button(
+                "All events".to_string(),
+                self.history_type_filter.is_none(),
+                Message::HistoryTypeFilter(None),
+            ));
+        for (event_type, label) in HISTORY_EVENT_TYPES {
+            type_row = type_row.push(filter_button(
+                label.to_string(),
+                self.history_type_filter == Some(event_type),
+                Message::HistoryTypeFilter(Some(event_type)),
+            ));
+        }
+
+        content = content.push(
+            column::with_capacity(2)
+                .spacing(theme::active().cosmic().space_xs())
+                .push(device_row)
+                .push(type_row),
+        );
+
+        if self.history_entries.is_empty() {
             content = content.push(
                 container(
                     column::with_capacity(3)
@@ -1061,34 +1608,52 @@ impl CosmicConnectManager {
                 .center_y(Length::Fill),
             );
         } else {
-            let mut events_list = column::with_capacity(self.history_events.len())
+            let mut events_list = column::with_capacity(self.history_entries.len())
                 .spacing(theme::active().cosmic().space_xs());
 
-            for event in &self.history_events {
-                let timestamp_str = {
-                    let now = chrono::Local::now();
-                    let diff = now.signed_duration_since(event.timestamp);
-
-                    if diff.num_minutes() < 1 {
-                        "Just now".to_string()
-                    } else if diff.num_minutes() < 60 {
-                        format!("{} minutes ago", diff.num_minutes())
-                    } else if diff.num_hours() < 24 {
-                        format!("{} hours ago", diff.num_hours())
-                    } else {
-                        event.timestamp.format("%b %d, %H:%M").to_string()
-                    }
-                };
+            for entry in &self.history_entries {
+                let device_name = self
+                    .devices
+                    .get(&entry.device_id)
+                    .map(|device| device.name.as_str())
+                    .unwrap_or(&entry.device_id);
+                let label = HISTORY_EVENT_TYPES
+                    .iter()
+                    .find(|(event_type, _)| *event_type == entry.event_type)
+                    .map(|(_, label)| *label)
+                    .unwrap_or(entry.event_type.as_str());
 
                 events_list = events_list.push(self.history_event_item(
-                    &event.icon_name,
-                    &event.event_type,
-                    &event.description,
-                    &timestamp_str,
+                    history_icon_name(&entry.event_type),
+                    &format!("{} · {}", label, device_name),
+                    &entry.summary,
+                    &format_history_time(entry.timestamp),
                 ));
             }
 
             content = content.push(events_list);
+
+            // Pagination
+            let page_count = self.history_total.div_ceil(HISTORY_PAGE_SIZE as u64).max(1);
+            let page = self.history_page;
+            let pager = row::with_capacity(3)
+                .spacing(theme::active().cosmic().space_s())
+                .align_y(Alignment::Center)
+                .push(
+                    button::text("Newer")
+                        .on_press_maybe((page > 0).then(|| Message::HistoryPage(page - 1)))
+                        .padding(theme::active().cosmic().space_xxs()),
+                )
+                .push(text(format!("Page {} of {}", page + 1, page_count)).size(12))
+                .push(
+                    button::text("Older")
+                        .on_press_maybe(
+                            ((page as u64 + 1) < page_count)
+                                .then(|| Message::HistoryPage(page + 1)),
+                        )
+                        .padding(theme::active().cosmic().space_xxs()),
+                );
+            content = content.push(pager);
         }
 
         container(content)
@@ -1162,6 +1727,18 @@ impl CosmicConnectManager {
                 .width(Length::Fill),
         );
 
+        content = content.push(vertical_space().height(theme::active().cosmic().space_m()));
+        content = content.push(text("Device Settings").size(18));
+        content = content.push(
+            container(self.device_settings_section())
+                .padding(theme::active().cosmic().space_s())
+                .width(Length::Fill),
+        );
+
+        content = content.push(vertical_space().height(theme::active().cosmic().space_m()));
+        content = content.push(text("Snoozed Notifications").size(18));
+        content = content.push(self.snoozed_apps_section());
+
         content = content.push(vertical_space().height(theme::active().cosmic().space_m()));
         content = content.push(text("Plugin Settings").size(18));
 
@@ -1217,22 +1794,80 @@ impl CosmicConnectManager {
             .into()
     }
 
-    fn device_card<'a>(
-        &self,
-        device_id: &'a str,
-        device: &'a DeviceInfo,
-        config: Option<&'a DeviceConfig>,
-        is_selected: bool,
-    ) -> Element<'a, Message> {
-        let device_icon = icon::from_name(device_icon_name(&device.device_type)).size(48);
-        let display_name = config
-            .and_then(|c| c.nickname.as_deref())
-            .unwrap_or(&device.name);
-        let name_text = text(display_name).size(16);
-        let status_text = connection_status(device);
-        let status_badge = text(status_text).size(12);
+    fn snoozed_apps_section(&self) -> Element<'_, Message> {
+        use cosmic::widget::text_input;
 
-        let mut info_column = column::with_capacity(2)
+        const SNOOZE_ONE_HOUR_SECS: u64 = 60 * 60;
+
+        let app_name = self.snooze_app_name.clone();
+        let snooze_row = row::with_capacity(2)
+            .spacing(theme::active().cosmic().space_s())
+            .align_y(Alignment::Center)
+            .push(
+                text_input("Application name", &self.snooze_app_name)
+                    .on_input(Message::SnoozeAppNameChanged)
+                    .width(Length::Fill),
+            )
+            .push(
+                button::text("Snooze 1 hour")
+                    .on_press(Message::SnoozeApp(app_name, SNOOZE_ONE_HOUR_SECS)),
+            );
+
+        let mut section = column::with_capacity(self.snoozed_apps.len() + 1)
+            .spacing(theme::active().cosmic().space_xs())
+            .push(snooze_row);
+
+        let now = chrono::Local::now().timestamp().max(0) as u64;
+        let mut snoozed: Vec<_> = self.snoozed_apps.iter().collect();
+        snoozed.sort_by(|a, b| a.0.cmp(b.0));
+
+        if snoozed.is_empty() {
+            section = section.push(text("No apps are snoozed").size(12));
+        }
+
+        for (app_name, until) in snoozed {
+            let remaining_mins = until.saturating_sub(now).div_ceil(60);
+            let app_row = row::with_capacity(3)
+                .spacing(theme::active().cosmic().space_s())
+                .align_y(Alignment::Center)
+                .push(
+                    column::with_capacity(2)
+                        .spacing(theme::active().cosmic().space_xxs())
+                        .push(text(app_name.as_str()).size(14))
+                        .push(text(format!("{} min remaining", remaining_mins)).size(12)),
+                )
+                .push(horizontal_space())
+                .push(button::text("Resume").on_press(Message::UnsnoozeApp(app_name.clone())));
+
+            section = section.push(
+                container(app_row)
+                    .padding(theme::active().cosmic().space_s())
+                    .width(Length::Fill),
+            );
+        }
+
+        container(section)
+            .padding(theme::active().cosmic().space_s())
+            .width(Length::Fill)
+            .into()
+    }
+
+    fn device_card<'a>(
+        &self,
+        device_id: &'a str,
+        device: &'a DeviceInfo,
+        config: Option<&'a DeviceConfig>,
+        is_selected: bool,
+    ) -> Element<'a, Message> {
+        let device_icon = icon::from_name(device_icon_name(&device.device_type)).size(48);
+        let display_name = config
+            .and_then(|c| c.nickname.as_deref())
+            .unwrap_or(&device.name);
+        let name_text = text(display_name).size(16);
+        let status_text = connection_status(device);
+        let status_badge = text(status_text).size(12);
+
+        let mut info_column = column::with_capacity(2)
             .spacing(theme::active().cosmic().space_xxs())
             .push(name_text)
             .push(status_badge);
@@ -1259,6 +1894,28 @@ impl CosmicConnectManager {
             info_column = info_column.push(battery_row);
         }
 
+        if let Some(latency) = self.ping_latency.get(device_id) {
+            let latency_text = format!("{} ms ({} latency)", latency.rtt_ms, latency.category);
+            let latency_row = row::with_capacity(2)
+                .spacing(theme::active().cosmic().space_xxs())
+                .align_y(Alignment::Center)
+                .push(icon::from_name("network-transmit-receive-symbolic").size(16))
+                .push(text(latency_text).size(12));
+
+            info_column = info_column.push(latency_row);
+        }
+
+        if !device.is_connected && device.last_seen > 0 {
+            info_column = info_column.push(text(format_last_seen(device.last_seen)).size(12));
+        }
+
+        if is_selected && device.is_paired {
+            if let Some(fingerprint) = self.certificate_fingerprints.get(device_id) {
+                info_column =
+                    info_column.push(text(format!("Fingerprint: {}", fingerprint)).size(10));
+            }
+        }
+
         let info_row = row::with_capacity(2)
             .spacing(theme::active().cosmic().space_s())
             .align_y(Alignment::Center)
@@ -1535,22 +2192,15 @@ impl CosmicConnectManager {
                         .padding(theme::active().cosmic().space_s()),
                 ),
         );
+        let nickname_valid = !self.device_settings_nickname.trim().is_empty();
+        if !nickname_valid {
+            content = content.push(text("Nickname can't be empty").size(12));
+        }
 
-        // Plugin toggles
+        // Plugin toggles apply immediately
         content = content.push(text("Plugins").size(16));
 
-        let plugins = [
-            ("ping", "Ping"),
-            ("battery", "Battery"),
-            ("notification", "Notifications"),
-            ("share", "File Sharing"),
-            ("clipboard", "Clipboard"),
-            ("mpris", "Media Control"),
-            ("remotedesktop", "Remote Desktop"),
-            ("findmyphone", "Find My Phone"),
-        ];
-
-        for (plugin_id, plugin_name) in plugins {
+        for (plugin_id, plugin_name) in DEVICE_SETTINGS_PLUGINS {
             let enabled = self
                 .device_settings_plugins
                 .get(plugin_id)
@@ -1621,7 +2271,7 @@ impl CosmicConnectManager {
                 )
                 .push(
                     button::text("Save")
-                        .on_press(Message::SaveDeviceSettings)
+                        .on_press_maybe(nickname_valid.then_some(Message::SaveDeviceSettings))
                         .class(theme::Button::Suggested)
                         .padding(theme::active().cosmic().space_s()),
                 ),
@@ -1760,6 +2410,45 @@ impl CosmicConnectManager {
             .into()
     }
 
+    fn pairing_dialog_view(&self, device_id: &str, fingerprint: &str) -> Element<'_, Message> {
+        let device_name = self
+            .devices
+            .get(device_id)
+            .map(|device| device.name.clone())
+            .unwrap_or_else(|| device_id.to_string());
+
+        let buttons = row::with_capacity(2)
+            .spacing(theme::active().cosmic().space_s())
+            .push(
+                button::text("Reject")
+                    .on_press(Message::RejectPairing(device_id.to_string()))
+                    .class(theme::Button::Standard),
+            )
+            .push(
+                button::text("Accept")
+                    .on_press(Message::AcceptPairing(device_id.to_string()))
+                    .class(theme::Button::Suggested),
+            );
+
+        let content = column::with_capacity(5)
+            .spacing(theme::active().cosmic().space_m())
+            .padding(theme::active().cosmic().space_m())
+            .push(text("Pairing Request").size(18))
+            .push(text(format!("{} wants to pair with this computer.", device_name)).size(14))
+            .push(
+                text("Only accept if this fingerprint matches the one shown on the device:")
+                    .size(12),
+            )
+            .push(text(fingerprint.to_string()).size(12))
+            .push(buttons);
+
+        container(content)
+            .padding(theme::active().cosmic().space_m())
+            .width(Length::Fixed(450.0))
+            .class(theme::Container::Dialog)
+            .into()
+    }
+
     fn power_dialog_view(&self) -> Element<'_, Message> {
         let device_id = self.power_device_id.clone().unwrap_or_default();
 
@@ -1865,6 +2554,12 @@ impl Application for CosmicConnectManager {
         let pending_device_action = flags.device_action.clone();
         let pending_files = flags.files.clone();
 
+        // Paired devices from earlier sessions, shown until the daemon answers
+        let device_cache = DeviceCache::load().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load device cache: {}", e);
+            DeviceCache::default()
+        });
+
         // Log CLI args for debugging
         if pending_select_device.is_some()
             || pending_tab.is_some()
@@ -1892,9 +2587,11 @@ impl Application for CosmicConnectManager {
         plugin_states.insert("remotedesktop".to_string(), false);
         plugin_states.insert("camera".to_string(), false);
 
+        let event_rx = Arc::new(Mutex::new(None));
+        let event_rx_slot = event_rx.clone();
         let connect_task = cosmic::task::future(async move {
             match DbusClient::connect().await {
-                Ok((client, _event_rx)) => {
+                Ok((client, rx)) => {
                     if let Err(e) = client.start_signal_listener().await {
                         tracing::warn!("Failed to start signal listener: {}", e);
                         return Message::DbusError(format!(
@@ -1903,6 +2600,9 @@ impl Application for CosmicConnectManager {
                         ));
                     }
 
+                    if let Ok(mut slot) = event_rx_slot.lock() {
+                        *slot = Some(rx);
+                    }
                     Message::DbusConnected(client)
                 }
                 Err(e) => {
@@ -1917,9 +2617,13 @@ impl Application for CosmicConnectManager {
                 core,
                 active_page: Page::Devices,
                 dbus_client: None,
-                devices: HashMap::new(),
+                devices: device_cache.offline_devices(),
+                device_cache,
+                device_cache_save_queued: false,
                 device_configs: HashMap::new(),
                 battery_status: HashMap::new(),
+                ping_latency: HashMap::new(),
+                certificate_fingerprints: HashMap::new(),
                 selected_device: initial_device.clone(),
                 _initial_device: initial_device,
                 _initial_action: initial_action,
@@ -1932,11 +2636,19 @@ impl Application for CosmicConnectManager {
                 auto_start_enabled: true,
                 show_notifications: true,
                 plugin_states,
+                snoozed_apps: HashMap::new(),
+                snooze_app_name: String::new(),
                 mpris_players: Vec::new(),
-                active_transfers: HashMap::new(),
-                completed_transfers: Vec::new(),
-                history_events: Vec::new(),
-                _event_rx: None,
+                remote_players: HashMap::new(),
+                pending_media: HashMap::new(),
+                media_generation: 0,
+                transfers: HashMap::new(),
+                history_entries: Vec::new(),
+                history_total: 0,
+                history_page: 0,
+                history_device_filter: None,
+                history_type_filter: None,
+                event_rx,
                 show_runcommand_dialog: false,
                 runcommand_device_id: None,
                 available_commands: HashMap::new(),
@@ -1963,6 +2675,7 @@ impl Application for CosmicConnectManager {
                 // Power dialog
                 show_power_dialog: false,
                 power_device_id: None,
+                pairing_request: None,
                 extended_display_devices: std::collections::HashSet::new(),
                 status_message: None,
             },
@@ -1971,7 +2684,44 @@ impl Application for CosmicConnectManager {
     }
 
     fn subscription(&self) -> cosmic::iced::Subscription<Self::Message> {
-        cosmic::iced::Subscription::none()
+        struct DaemonEvents;
+        struct MediaTicker;
+
+        let mut subscriptions = Vec::new();
+
+        if self.dbus_ready {
+            let event_rx = self.event_rx.clone();
+            subscriptions.push(cosmic::iced::Subscription::run_with_id(
+                std::any::TypeId::of::<DaemonEvents>(),
+                // Device updates arrive already batched by the daemon
+                futures::stream::unfold(None, move |events| {
+                    let event_rx = event_rx.clone();
+                    async move {
+                        let mut events = match events {
+                            Some(events) => events,
+                            None => event_rx.lock().ok()?.take()?,
+                        };
+                        let event = events.recv().await?;
+                        Some((Message::DaemonEventReceived(event), Some(events)))
+                    }
+                }),
+            ));
+        }
+
+        // Redraw once a second so playing tracks show a moving position
+        if self.active_page == Page::MediaPlayers
+            && self.remote_players.values().any(|player| player.is_playing)
+        {
+            subscriptions.push(cosmic::iced::Subscription::run_with_id(
+                std::any::TypeId::of::<MediaTicker>(),
+                futures::stream::unfold((), |()| async {
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    Some((Message::MediaTick, ()))
+                }),
+            ));
+        }
+
+        cosmic::iced::Subscription::batch(subscriptions)
     }
 
     fn header_start(&self) -> Vec<Element<'_, Self::Message>> {
@@ -1985,7 +2735,14 @@ impl Application for CosmicConnectManager {
             .height(Length::Fill);
 
         // Show dialog instead of main view when a dialog is open
-        if self.show_runcommand_dialog {
+        if let Some((device_id, fingerprint)) = &self.pairing_request {
+            container(self.pairing_dialog_view(device_id, fingerprint))
+                .center_x(Length::Fill)
+                .center_y(Length::Fill)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into()
+        } else if self.show_runcommand_dialog {
             container(self.runcommand_dialog_view())
                 .center_x(Length::Fill)
                 .center_y(Length::Fill)
@@ -2050,13 +2807,44 @@ impl Application for CosmicConnectManager {
         match message {
             Message::NavigateTo(page) => {
                 self.active_page = page;
-                Task::none()
+                match page {
+                    Page::Settings => {
+                        let refresh_snoozes =
+                            cosmic::task::future(async { Message::RefreshSnoozedApps });
+                        match self.selected_device.clone() {
+                            Some(device_id) => Task::batch(vec![
+                                refresh_snoozes,
+                                self.load_device_settings(device_id),
+                            ]),
+                            None => refresh_snoozes,
+                        }
+                    }
+                    Page::History => cosmic::task::future(async { Message::RefreshHistory }),
+                    _ => Task::none(),
+                }
             }
             Message::SelectDevice(device_id) => {
-                self.selected_device = Some(device_id);
-                Task::none()
+                self.selected_device = Some(device_id.clone());
+                let Some(client) = self.dbus_client.clone() else {
+                    return Task::none();
+                };
+                cosmic::task::future(async move {
+                    match client.get_certificate_fingerprint(&device_id).await {
+                        Ok(fingerprint) => {
+                            Message::CertificateFingerprintLoaded(device_id, fingerprint)
+                        }
+                        Err(_) => Message::None,
+                    }
+                })
             }
             Message::DevicesUpdated(devices) => {
+                let save = match self.device_cache.update(&devices) {
+                    CacheChange::None => Task::none(),
+                    CacheChange::LastSeen => self.queue_device_cache_save(),
+                    CacheChange::Devices => self.save_device_cache(),
+                };
+                let devices = self.device_cache.merge_live(devices);
+
                 if let Some(client) = &self.dbus_client {
                     let client_clone = client.clone();
                     let connected_device_ids: Vec<String> = devices
@@ -2080,12 +2868,26 @@ impl Application for CosmicConnectManager {
                         })
                         .collect();
 
-                    Task::batch(battery_tasks)
+                    Task::batch(vec![Task::batch(battery_tasks), save])
                 } else {
                     self.devices = devices;
-                    Task::none()
+                    save
                 }
             }
+            Message::DevicesRefreshed(devices) => {
+                // Paired devices the daemon no longer lists were forgotten
+                let save = if self.device_cache.retain_listed(&devices) {
+                    self.save_device_cache()
+                } else {
+                    Task::none()
+                };
+                let update = self.update(Message::DevicesUpdated(devices));
+                Task::batch(vec![save, update])
+            }
+            Message::SaveDeviceCache => {
+                self.device_cache_save_queued = false;
+                self.save_device_cache()
+            }
             Message::DeviceConfigLoaded(device_id, config) => {
                 self.device_configs.insert(device_id, config);
                 Task::none()
@@ -2097,6 +2899,8 @@ impl Application for CosmicConnectManager {
                 Task::batch(vec![
                     cosmic::task::future(async { Message::RefreshDevices }),
                     cosmic::task::future(async { Message::RefreshMprisPlayers }),
+                    cosmic::task::future(async { Message::RefreshRemotePlayers }),
+                    cosmic::task::future(async { Message::RefreshTransfers }),
                     // Issue #143: Process CLI args after DBus is ready
                     cosmic::task::future(async { Message::ProcessPendingCliArgs }),
                 ])
@@ -2111,7 +2915,7 @@ impl Application for CosmicConnectManager {
                     let client = client.clone();
                     cosmic::task::future(async move {
                         match client.list_devices().await {
-                            Ok(devices) => Message::DevicesUpdated(devices),
+                            Ok(devices) => Message::DevicesRefreshed(devices),
                             Err(e) => {
                                 tracing::warn!("Failed to list devices: {}", e);
                                 Message::None
@@ -2195,8 +2999,15 @@ impl Application for CosmicConnectManager {
                             if let Err(e) = client.send_ping(&device_id, "Ping from manager").await
                             {
                                 tracing::error!("Failed to send ping: {}", e);
+                                return Message::None;
+                            }
+                            match client.measure_latency(&device_id).await {
+                                Ok(latency) => Message::LatencyMeasured(device_id, latency),
+                                Err(e) => {
+                                    tracing::warn!("Failed to measure latency: {}", e);
+                                    Message::None
+                                }
                             }
-                            Message::None
                         }),
                         DeviceAction::SendFile => {
                             // Open file picker using xdg-desktop-portal
@@ -2258,21 +3069,7 @@ impl Application for CosmicConnectManager {
                         DeviceAction::Settings => {
                             // Open device settings dialog
                             self.show_device_settings = true;
-                            self.settings_device_id = Some(device_id.clone());
-                            if let Some(client) = &self.dbus_client {
-                                let client = client.clone();
-                                cosmic::task::future(async move {
-                                    match client.get_device_config(&device_id).await {
-                                        Ok(config) => Message::DeviceSettingsLoaded(config),
-                                        Err(e) => {
-                                            tracing::error!("Failed to load device config: {}", e);
-                                            Message::None
-                                        }
-                                    }
-                                })
-                            } else {
-                                Task::none()
-                            }
+                            self.load_device_settings(device_id)
                         }
 
                         // Mobile-only actions
@@ -2311,22 +3108,17 @@ impl Application for CosmicConnectManager {
                                             "Camera started successfully on device {}",
                                             device_id
                                         );
-                                        Message::AddHistoryEvent(HistoryEvent {
-                                            icon_name: "camera-web-symbolic".to_string(),
-                                            event_type: "Camera started".to_string(),
-                                            description: "Streaming 720p @ 30fps to V4L2 loopback"
+                                        Message::ActionSuccess(
+                                            "Camera streaming 720p @ 30fps to V4L2 loopback"
                                                 .to_string(),
-                                            timestamp: chrono::Local::now(),
-                                        })
+                                        )
                                     }
                                     Err(e) => {
                                         tracing::error!("Failed to start camera: {}", e);
-                                        Message::AddHistoryEvent(HistoryEvent {
-                                            icon_name: "dialog-error-symbolic".to_string(),
-                                            event_type: "Camera failed".to_string(),
-                                            description: format!("Failed to start camera: {}", e),
-                                            timestamp: chrono::Local::now(),
-                                        })
+                                        Message::ActionError(format!(
+                                            "Failed to start camera: {}",
+                                            e
+                                        ))
                                     }
                                 }
                             })
@@ -2516,81 +3308,255 @@ impl Application for CosmicConnectManager {
                     Task::none()
                 }
             }
-            Message::TransferProgressUpdate(info) => {
-                self.active_transfers.insert(info.transfer_id.clone(), info);
+            Message::RefreshTransfers => {
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    cosmic::task::future(async move {
+                        match client.get_transfers().await {
+                            Ok(transfers) => Message::TransfersLoaded(transfers),
+                            Err(e) => {
+                                tracing::warn!("Failed to get transfers: {}", e);
+                                Message::None
+                            }
+                        }
+                    })
+                } else {
+                    Task::none()
+                }
+            }
+            Message::TransfersLoaded(transfers) => {
+                self.transfers = transfers
+                    .into_iter()
+                    .map(|transfer| (transfer.id.clone(), transfer))
+                    .collect();
                 Task::none()
             }
-            Message::TransferCompleted(transfer_id, _device_id, filename, success, _error) => {
-                self.active_transfers.remove(&transfer_id);
-
-                let completed = CompletedTransfer {
-                    filename: filename.clone(),
-                    size: 0,
-                    timestamp: chrono::Local::now(),
-                    success,
+            Message::TransferUpdated(transfer) => {
+                self.transfers.insert(transfer.id.clone(), transfer);
+                Task::none()
+            }
+            Message::RefreshRemotePlayers => {
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    cosmic::task::future(async move {
+                        match client.get_remote_players().await {
+                            Ok(players) => Message::RemotePlayersLoaded(players),
+                            Err(e) => {
+                                tracing::warn!("Failed to get remote players: {}", e);
+                                Message::None
+                            }
+                        }
+                    })
+                } else {
+                    Task::none()
+                }
+            }
+            Message::RemotePlayersLoaded(players) => {
+                self.remote_players = players
+                    .into_iter()
+                    .map(|player| ((player.device_id.clone(), player.name.clone()), player))
+                    .collect();
+                Task::none()
+            }
+            Message::RemotePlayerUpdated(player) => {
+                self.remote_players
+                    .insert((player.device_id.clone(), player.name.clone()), player);
+                Task::none()
+            }
+            Message::RemotePlayerRemoved(device_id, player) => {
+                let key = (device_id, player);
+                self.remote_players.remove(&key);
+                self.pending_media.remove(&key);
+                Task::none()
+            }
+            Message::RemoteMediaControl(device_id, player, action) => {
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    cosmic::task::future(async move {
+                        match client
+                            .remote_mpris_control(&device_id, &player, action)
+                            .await
+                        {
+                            Ok(()) => Message::None,
+                            Err(e) => {
+                                // The player may have gone away, resync the list
+                                tracing::warn!("Failed to control remote player: {}", e);
+                                Message::RefreshRemotePlayers
+                            }
+                        }
+                    })
+                } else {
+                    Task::none()
+                }
+            }
+            Message::MediaSetVolume(key, volume) => {
+                if !self.remote_players.contains_key(&key) {
+                    return Task::none();
+                }
+                let volume = volume.clamp(0.0, 1.0);
+                self.queue_media_change(key, |change| change.volume = Some(volume))
+            }
+            Message::MediaSeek(key, position_us) => {
+                let Some(player) = self.remote_players.get(&key) else {
+                    return Task::none();
                 };
-                self.completed_transfers.push(completed);
-
-                let event = HistoryEvent {
-                    icon_name: if success {
-                        "document-save-symbolic".to_string()
-                    } else {
-                        "dialog-error-symbolic".to_string()
-                    },
-                    event_type: if success {
-                        "File received".to_string()
-                    } else {
-                        "Transfer failed".to_string()
-                    },
-                    description: filename,
-                    timestamp: chrono::Local::now(),
+                if !player.can_seek {
+                    return Task::none();
+                }
+                let mut position_us = position_us.max(0);
+                if player.length_ms > 0 {
+                    position_us = position_us.min(player.length_ms * 1000);
+                }
+                self.queue_media_change(key, |change| change.position_us = Some(position_us))
+            }
+            Message::MediaCommitChange(key, generation) => {
+                // A newer slider movement restarted the debounce period
+                let latest = self.pending_media.get(&key).map(|change| change.generation);
+                if latest != Some(generation) {
+                    return Task::none();
+                }
+                let Some(change) = self.pending_media.remove(&key) else {
+                    return Task::none();
                 };
-                self.history_events.push(event);
 
-                Task::none()
-            }
-            Message::DeviceAdded(device_id, device_info) => {
-                self.devices.insert(device_id.clone(), device_info.clone());
+                // Show the new values until the device reports back
+                if let Some(player) = self.remote_players.get_mut(&key) {
+                    if let Some(volume) = change.volume {
+                        player.volume = (volume * 100.0).round() as i32;
+                    }
+                    if let Some(position_us) = change.position_us {
+                        player.position_ms = position_us / 1000;
+                        player.updated_at = chrono::Utc::now().timestamp_millis();
+                    }
+                }
 
-                let event = HistoryEvent {
-                    icon_name: "network-wireless-signal-excellent-symbolic".to_string(),
-                    event_type: "Device discovered".to_string(),
-                    description: device_info.name,
-                    timestamp: chrono::Local::now(),
+                let Some(client) = self.dbus_client.clone() else {
+                    return Task::none();
                 };
-                self.history_events.push(event);
+                let (device_id, player) = key;
+                cosmic::task::future(async move {
+                    if let Some(volume) = change.volume {
+                        if let Err(e) = client
+                            .remote_mpris_set_volume(&device_id, &player, volume)
+                            .await
+                        {
+                            tracing::warn!("Failed to set remote player volume: {}", e);
+                            return Message::RefreshRemotePlayers;
+                        }
+                    }
+                    if let Some(position_us) = change.position_us {
+                        if let Err(e) = client
+                            .remote_mpris_set_position(&device_id, &player, position_us)
+                            .await
+                        {
+                            tracing::warn!("Failed to seek remote player: {}", e);
+                            return Message::RefreshRemotePlayers;
+                        }
+                    }
+                    Message::None
+                })
+            }
+            Message::MediaTick => Task::none(),
+            Message::TransferCompleted(transfer_id, device_id, filename, success, error) => {
+                tracing::debug!(
+                    "Transfer {} of {} with {} finished (success: {}, error: {:?})",
+                    transfer_id,
+                    filename,
+                    device_id,
+                    success,
+                    error
+                );
 
-                Task::none()
+                // The daemon logs finished transfers, show them if the page is open
+                if self.active_page == Page::History {
+                    cosmic::task::future(async { Message::RefreshHistory })
+                } else {
+                    Task::none()
+                }
             }
             Message::DeviceRemoved(device_id) => {
-                if let Some(device) = self.devices.remove(&device_id) {
-                    let event = HistoryEvent {
-                        icon_name: "network-wireless-offline-symbolic".to_string(),
-                        event_type: "Device disconnected".to_string(),
-                        description: device.name,
-                        timestamp: chrono::Local::now(),
-                    };
-                    self.history_events.push(event);
+                // The daemon forgot the device, so the cache does too
+                self.devices.remove(&device_id);
+                if self.device_cache.remove(&device_id) {
+                    self.save_device_cache()
+                } else {
+                    Task::none()
                 }
-                Task::none()
             }
-            Message::DeviceStateChanged(device_id, state) => {
-                if let Some(device) = self.devices.get_mut(&device_id) {
-                    match state.as_str() {
-                        "connected" => device.is_connected = true,
-                        "disconnected" => device.is_connected = false,
-                        _ => {}
-                    }
+            Message::RefreshHistory => {
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    let device_id = self.history_device_filter.clone().unwrap_or_default();
+                    let event_type = self.history_type_filter.unwrap_or_default();
+                    let offset = self.history_page * HISTORY_PAGE_SIZE;
+                    cosmic::task::future(async move {
+                        let page = if event_type == HISTORY_AUDIT_TYPE {
+                            client
+                                .get_audit_log(&device_id, HISTORY_PAGE_SIZE, offset)
+                                .await
+                                .map(|(entries, total)| {
+                                    let entries = entries.into_iter().map(audit_history_entry);
+                                    (entries.collect(), total)
+                                })
+                        } else {
+                            client
+                                .get_history(&device_id, event_type, HISTORY_PAGE_SIZE, offset)
+                                .await
+                        };
+                        match page {
+                            Ok((entries, total)) => Message::HistoryLoaded(entries, total),
+                            Err(e) => {
+                                tracing::warn!("Failed to get history: {}", e);
+                                Message::None
+                            }
+                        }
+                    })
+                } else {
+                    Task::none()
                 }
-                Task::none()
             }
-            Message::AddHistoryEvent(event) => {
-                self.history_events.push(event);
+            Message::HistoryLoaded(entries, total) => {
+                self.history_entries = entries;
+                self.history_total = total;
+
+                // Clearing or trimming can leave us past the last page
+                let last_page = total.saturating_sub(1) / HISTORY_PAGE_SIZE as u64;
+                if self.history_page as u64 > last_page {
+                    self.history_page = last_page as u32;
+                    return cosmic::task::future(async { Message::RefreshHistory });
+                }
                 Task::none()
             }
+            Message::HistoryPage(page) => {
+                self.history_page = page;
+                cosmic::task::future(async { Message::RefreshHistory })
+            }
+            Message::HistoryDeviceFilter(device_id) => {
+                self.history_device_filter = device_id;
+                self.history_page = 0;
+                cosmic::task::future(async { Message::RefreshHistory })
+            }
+            Message::HistoryTypeFilter(event_type) => {
+                self.history_type_filter = event_type;
+                self.history_page = 0;
+                cosmic::task::future(async { Message::RefreshHistory })
+            }
             Message::ClearHistory => {
-                self.history_events.clear();
-                Task::none()
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    let device_id = self.history_device_filter.clone().unwrap_or_default();
+                    cosmic::task::future(async move {
+                        match client.clear_history(&device_id).await {
+                            Ok(()) => Message::RefreshHistory,
+                            Err(e) => {
+                                tracing::error!("Failed to clear history: {}", e);
+                                Message::ActionError(format!("Failed to clear history: {}", e))
+                            }
+                        }
+                    })
+                } else {
+                    Task::none()
+                }
             }
             Message::ToggleAutoStart(enabled) => {
                 self.auto_start_enabled = enabled;
@@ -2600,6 +3566,67 @@ impl Application for CosmicConnectManager {
                 self.show_notifications = enabled;
                 Task::none()
             }
+            Message::RefreshSnoozedApps => {
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    cosmic::task::future(async move {
+                        match client.get_snoozed_apps().await {
+                            Ok(snoozes) => Message::SnoozedAppsLoaded(snoozes),
+                            Err(e) => {
+                                tracing::warn!("Failed to get snoozed apps: {}", e);
+                                Message::None
+                            }
+                        }
+                    })
+                } else {
+                    Task::none()
+                }
+            }
+            Message::SnoozedAppsLoaded(snoozes) => {
+                self.snoozed_apps = snoozes;
+                Task::none()
+            }
+            Message::SnoozeAppNameChanged(app_name) => {
+                self.snooze_app_name = app_name;
+                Task::none()
+            }
+            Message::SnoozeApp(app_name, duration_secs) => {
+                let app_name = app_name.trim().to_string();
+                if app_name.is_empty() {
+                    return Task::none();
+                }
+                self.snooze_app_name.clear();
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    cosmic::task::future(async move {
+                        match client.snooze_app(&app_name, duration_secs).await {
+                            Ok(_) => Message::RefreshSnoozedApps,
+                            Err(e) => Message::ActionError(format!(
+                                "Failed to snooze {}: {}",
+                                app_name, e
+                            )),
+                        }
+                    })
+                } else {
+                    Task::none()
+                }
+            }
+            Message::UnsnoozeApp(app_name) => {
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    cosmic::task::future(async move {
+                        match client.unsnooze_app(&app_name).await {
+                            Ok(_) => Message::RefreshSnoozedApps,
+                            Err(e) => Message::ActionError(format!(
+                                "Failed to resume {}: {}",
+                                app_name, e
+                            )),
+                        }
+                    })
+                } else {
+                    Task::none()
+                }
+            }
             Message::TogglePlugin(plugin_id, enabled) => {
                 self.plugin_states.insert(plugin_id, enabled);
                 Task::none()
@@ -2608,37 +3635,37 @@ impl Application for CosmicConnectManager {
                 self.battery_status.insert(device_id, status);
                 Task::none()
             }
+            Message::LatencyMeasured(device_id, latency) => {
+                self.ping_latency.insert(device_id, latency);
+                Task::none()
+            }
+            Message::CertificateFingerprintLoaded(device_id, fingerprint) => {
+                if fingerprint.is_empty() {
+                    self.certificate_fingerprints.remove(&device_id);
+                } else {
+                    self.certificate_fingerprints.insert(device_id, fingerprint);
+                }
+                Task::none()
+            }
             Message::DaemonEventReceived(event) => match event {
-                DaemonEvent::DeviceAdded {
-                    device_id,
-                    device_info,
-                } => cosmic::task::future(
-                    async move { Message::DeviceAdded(device_id, device_info) },
-                ),
                 DaemonEvent::DeviceRemoved { device_id } => {
                     cosmic::task::future(async move { Message::DeviceRemoved(device_id) })
                 }
-                DaemonEvent::DeviceStateChanged { device_id, state } => cosmic::task::future(
-                    async move { Message::DeviceStateChanged(device_id, state) },
-                ),
-                DaemonEvent::TransferProgress {
-                    transfer_id,
-                    device_id,
-                    filename,
-                    current,
-                    total,
-                    direction,
-                } => {
-                    let info = TransferInfo {
-                        transfer_id,
-                        device_id,
-                        filename,
-                        current,
-                        total,
-                        direction,
-                    };
-                    cosmic::task::future(async move { Message::TransferProgressUpdate(info) })
+                DaemonEvent::DevicesUpdated { devices } => {
+                    // Batches only carry changed devices, merge into the known set
+                    let mut merged = self.devices.clone();
+                    merged.extend(devices);
+                    cosmic::task::future(async move { Message::DevicesUpdated(merged) })
+                }
+                DaemonEvent::TransferUpdated { transfer } => {
+                    cosmic::task::future(async move { Message::TransferUpdated(transfer) })
+                }
+                DaemonEvent::RemotePlayerUpdated { player } => {
+                    cosmic::task::future(async move { Message::RemotePlayerUpdated(player) })
                 }
+                DaemonEvent::RemotePlayerRemoved { device_id, player } => cosmic::task::future(
+                    async move { Message::RemotePlayerRemoved(device_id, player) },
+                ),
                 DaemonEvent::TransferComplete {
                     transfer_id,
                     device_id,
@@ -2648,6 +3675,46 @@ impl Application for CosmicConnectManager {
                 } => cosmic::task::future(async move {
                     Message::TransferCompleted(transfer_id, device_id, filename, success, error)
                 }),
+                DaemonEvent::BatteryStatusChanged { device_id, status } => {
+                    self.battery_status.insert(device_id, status);
+                    Task::none()
+                }
+                DaemonEvent::DaemonDisconnected => {
+                    tracing::warn!("Daemon left the session bus");
+                    self.status_message = Some(("Connection to the daemon lost".to_string(), true));
+                    Task::none()
+                }
+                DaemonEvent::DaemonReconnected => {
+                    // A restarted daemon has its own state, reload all of it
+                    tracing::info!("Daemon restarted, refreshing");
+                    self.status_message = None;
+                    self.pairing_request = None;
+                    self.battery_status.clear();
+                    Task::batch(vec![
+                        cosmic::task::future(async { Message::RefreshDevices }),
+                        cosmic::task::future(async { Message::RefreshMprisPlayers }),
+                        cosmic::task::future(async { Message::RefreshRemotePlayers }),
+                        cosmic::task::future(async { Message::RefreshTransfers }),
+                    ])
+                }
+                DaemonEvent::PairingRequested {
+                    device_id,
+                    fingerprint,
+                } => {
+                    self.pairing_request = Some((device_id, fingerprint));
+                    Task::none()
+                }
+                DaemonEvent::PairingStatusChanged { device_id, .. } => {
+                    // Answered elsewhere (notification, applet) or timed out
+                    if self
+                        .pairing_request
+                        .as_ref()
+                        .is_some_and(|(id, _)| *id == device_id)
+                    {
+                        self.pairing_request = None;
+                    }
+                    cosmic::task::future(async { Message::RefreshDevices })
+                }
                 _ => Task::none(),
             },
             Message::DbusReady(client) => {
@@ -2792,21 +3859,7 @@ impl Application for CosmicConnectManager {
             // Settings dialog handlers
             Message::OpenDeviceSettings(device_id) => {
                 self.show_device_settings = true;
-                self.settings_device_id = Some(device_id.clone());
-                if let Some(client) = &self.dbus_client {
-                    let client = client.clone();
-                    cosmic::task::future(async move {
-                        match client.get_device_config(&device_id).await {
-                            Ok(config) => Message::DeviceSettingsLoaded(config),
-                            Err(e) => {
-                                tracing::error!("Failed to load device config: {}", e);
-                                Message::None
-                            }
-                        }
-                    })
-                } else {
-                    Task::none()
-                }
+                self.load_device_settings(device_id)
             }
             Message::CloseDeviceSettings => {
                 self.show_device_settings = false;
@@ -2825,21 +3878,33 @@ impl Application for CosmicConnectManager {
                 if let Some(client) = &self.dbus_client {
                     let client = client.clone();
                     self.devices.remove(&device_id);
+                    let save = if self.device_cache.remove(&device_id) {
+                        self.save_device_cache()
+                    } else {
+                        Task::none()
+                    };
                     self.device_configs.remove(&device_id);
                     self.battery_status.remove(&device_id);
+                    self.ping_latency.remove(&device_id);
                     if self.selected_device.as_deref() == Some(device_id.as_str()) {
                         self.selected_device = None;
                     }
                     self.show_device_settings = false;
                     self.settings_device_id = None;
                     self.confirm_unpair_device_id = None;
-                    cosmic::task::future(async move {
-                        if let Err(e) = client.unpair_device(&device_id).await {
-                            tracing::error!("Failed to unpair device: {}", e);
-                            return Message::ActionError(format!("Failed to unpair device: {}", e));
-                        }
-                        Message::RefreshDevices
-                    })
+                    Task::batch(vec![
+                        save,
+                        cosmic::task::future(async move {
+                            if let Err(e) = client.unpair_device(&device_id).await {
+                                tracing::error!("Failed to unpair device: {}", e);
+                                return Message::ActionError(format!(
+                                    "Failed to unpair device: {}",
+                                    e
+                                ));
+                            }
+                            Message::RefreshDevices
+                        }),
+                    ])
                 } else {
                     Task::none()
                 }
@@ -2848,26 +3913,39 @@ impl Application for CosmicConnectManager {
                 if let Some(client) = &self.dbus_client {
                     let client = client.clone();
                     self.devices.remove(&device_id);
+                    let save = if self.device_cache.remove(&device_id) {
+                        self.save_device_cache()
+                    } else {
+                        Task::none()
+                    };
                     self.device_configs.remove(&device_id);
                     self.battery_status.remove(&device_id);
+                    self.ping_latency.remove(&device_id);
                     if self.selected_device.as_deref() == Some(device_id.as_str()) {
                         self.selected_device = None;
                     }
-                    cosmic::task::future(async move {
-                        if let Err(e) = client.forget_device(&device_id).await {
-                            tracing::error!("Failed to dismiss device: {}", e);
-                            return Message::ActionError(format!(
-                                "Failed to dismiss device: {}",
-                                e
-                            ));
-                        }
-                        Message::RefreshDevices
-                    })
+                    Task::batch(vec![
+                        save,
+                        cosmic::task::future(async move {
+                            if let Err(e) = client.forget_device(&device_id).await {
+                                tracing::error!("Failed to dismiss device: {}", e);
+                                return Message::ActionError(format!(
+                                    "Failed to dismiss device: {}",
+                                    e
+                                ));
+                            }
+                            Message::RefreshDevices
+                        }),
+                    ])
                 } else {
                     Task::none()
                 }
             }
             Message::DeviceSettingsLoaded(config) => {
+                // Ignore a load for a device the user has since switched away from
+                if self.settings_device_id.as_deref() != Some(config.device_id.as_str()) {
+                    return Task::none();
+                }
                 self.device_settings_nickname = config.nickname.clone().unwrap_or_default();
                 self.device_settings_plugins.clear();
                 self.device_settings_plugins.insert(
@@ -2906,43 +3984,93 @@ impl Application for CosmicConnectManager {
                 Task::none()
             }
             Message::SaveDeviceSettings => {
-                if let (Some(client), Some(device_id)) =
-                    (&self.dbus_client, &self.settings_device_id)
-                {
-                    let client = client.clone();
-                    let device_id = device_id.clone();
-                    let nickname = self.device_settings_nickname.clone();
-                    let plugins = self.device_settings_plugins.clone();
+                let nickname = self.device_settings_nickname.trim().to_string();
+                let Some(device_id) = self.settings_device_id.clone() else {
                     self.show_device_settings = false;
-                    cosmic::task::future(async move {
-                        if !nickname.is_empty() {
-                            if let Err(e) = client.set_device_nickname(&device_id, &nickname).await
-                            {
-                                tracing::error!("Failed to set nickname: {}", e);
-                            }
-                        }
-                        for (plugin, enabled) in plugins {
-                            if let Err(e) = client
-                                .set_device_plugin_enabled(&device_id, &plugin, enabled)
-                                .await
-                            {
-                                tracing::error!("Failed to set plugin {} state: {}", plugin, e);
-                            }
-                        }
-                        Message::CloseDeviceSettings
-                    })
+                    return Task::none();
+                };
+                if nickname.is_empty() {
+                    return Task::none();
+                }
+
+                // Show the new nickname right away; rolled back if the daemon refuses it
+                let rollback = DeviceSettingsRollback {
+                    nickname: Some(self.saved_device_nickname(&device_id)),
+                    plugins: HashMap::new(),
+                };
+                if let Some(config) = self.device_configs.get_mut(&device_id) {
+                    config.nickname = Some(nickname.clone());
+                }
+                if let Some(config) = &mut self.device_settings_config {
+                    config.nickname = Some(nickname.clone());
+                }
+                self.device_settings_nickname = nickname.clone();
+
+                let save = self.save_device_settings(device_id, nickname, HashMap::new(), rollback);
+                if self.show_device_settings {
+                    Task::batch(vec![
+                        save,
+                        cosmic::task::future(async { Message::CloseDeviceSettings }),
+                    ])
                 } else {
-                    self.show_device_settings = false;
-                    Task::none()
+                    save
                 }
             }
+            Message::DeviceSettingsSaveFailed(device_id, rollback, error) => {
+                let is_settings_device = self.settings_device_id.as_deref() == Some(&device_id);
+                if let Some(nickname) = rollback.nickname {
+                    if let Some(config) = self.device_configs.get_mut(&device_id) {
+                        config.nickname = Some(nickname.clone());
+                    }
+                    if is_settings_device {
+                        if let Some(config) = &mut self.device_settings_config {
+                            config.nickname = Some(nickname.clone());
+                        }
+                        self.device_settings_nickname = nickname;
+                    }
+                }
+                if is_settings_device {
+                    self.device_settings_plugins.extend(rollback.plugins);
+                }
+                cosmic::task::future(async move {
+                    Message::ActionError(format!("Failed to save device settings: {}", error))
+                })
+            }
             Message::DeviceNicknameChanged(nickname) => {
                 self.device_settings_nickname = nickname;
                 Task::none()
             }
             Message::DevicePluginToggled(plugin, enabled) => {
-                self.device_settings_plugins.insert(plugin, enabled);
-                Task::none()
+                let Some(device_id) = self.settings_device_id.clone() else {
+                    return Task::none();
+                };
+                if self.device_settings_config.is_none() {
+                    return Task::none();
+                }
+
+                // Flip the toggle now and start/stop the plugin on the daemon
+                let previous = self
+                    .device_settings_plugins
+                    .insert(plugin.clone(), enabled)
+                    .unwrap_or(!enabled);
+                if previous == enabled {
+                    return Task::none();
+                }
+                let rollback = DeviceSettingsRollback {
+                    nickname: None,
+                    plugins: HashMap::from([(plugin.clone(), previous)]),
+                };
+                let nickname = self.saved_device_nickname(&device_id);
+                self.save_device_settings(
+                    device_id,
+                    nickname,
+                    HashMap::from([(plugin, enabled)]),
+                    rollback,
+                )
+            }
+            Message::SelectSettingsDevice(device_id) => {
+                self.selected_device = Some(device_id.clone());
+                self.load_device_settings(device_id)
             }
             // File picker handler
             Message::FileSelected(device_id, file_path) => {
@@ -3018,6 +4146,42 @@ impl Application for CosmicConnectManager {
                     Task::none()
                 }
             }
+            Message::AcceptPairing(device_id) => {
+                self.pairing_request = None;
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    cosmic::task::future(async move {
+                        if let Err(e) = client.accept_pair(&device_id).await {
+                            tracing::error!("Failed to accept pairing: {}", e);
+                            return Message::ActionError(format!(
+                                "Failed to accept pairing: {}",
+                                e
+                            ));
+                        }
+                        Message::RefreshDevices
+                    })
+                } else {
+                    Task::none()
+                }
+            }
+            Message::RejectPairing(device_id) => {
+                self.pairing_request = None;
+                if let Some(client) = &self.dbus_client {
+                    let client = client.clone();
+                    cosmic::task::future(async move {
+                        if let Err(e) = client.reject_pair(&device_id).await {
+                            tracing::error!("Failed to reject pairing: {}", e);
+                            return Message::ActionError(format!(
+                                "Failed to reject pairing: {}",
+                                e
+                            ));
+                        }
+                        Message::None
+                    })
+                } else {
+                    Task::none()
+                }
+            }
             Message::ExtendedDisplayStarted(device_id) => {
                 self.extended_display_devices.insert(device_id);
                 self.status_message = Some(("Extended display started".to_string(), false));
@@ -53,6 +53,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         quality: preset,
         buffer_size: 3,
         allow_frame_skip: true,
+        ..StreamConfig::default()
     };
 
     let mut session = StreamingSession::new(config);
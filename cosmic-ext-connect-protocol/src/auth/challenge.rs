@@ -1,38 +1,57 @@
 //! Challenge generation and management for phone authentication.
 //!
 //! The `ChallengeManager` generates cryptographically secure challenges
-//! and tracks them to prevent replay attacks.
+//! and tracks them to prevent replay attacks. It also holds the registry of
+//! enrolled phones, and every challenge is issued to one of them: a response
+//! only satisfies a challenge if it comes from the phone it was issued to and
+//! is signed with that phone's key.
+//!
+//! Expired challenges are swept before each new one is issued, and
+//! [`ChallengeManager::spawn_cleanup_task`] sweeps them periodically. A new
+//! challenge is always issued: once a phone has [`MAX_CHALLENGES_PER_PHONE`]
+//! pending challenges (or all phones [`MAX_ACTIVE_CHALLENGES`]), the oldest
+//! pending one is evicted to make room, so a flood of unanswered challenges
+//! for one phone never keeps other phones from authenticating.
 
 use std::collections::HashMap;
-use std::sync::Mutex;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use base64::Engine;
 use ring::rand::{SecureRandom, SystemRandom};
 
 use super::types::{
-    AuthError, Challenge, CHALLENGE_EXPIRY_SECS, CHALLENGE_SIZE, MAX_ACTIVE_CHALLENGES, NONCE_SIZE,
+    AuthError, Challenge, ChallengeResponse, KeyRotation, CHALLENGE_EXPIRY_SECS, CHALLENGE_SIZE,
+    MAX_ACTIVE_CHALLENGES, MAX_CHALLENGES_PER_PHONE, NONCE_SIZE,
 };
+use super::verify::Verifier;
 
 /// Manages challenge generation and tracking for phone authentication.
 ///
 /// The `ChallengeManager` is responsible for:
 /// - Generating cryptographically secure challenges using `ring::rand::SystemRandom`
 /// - Tracking active challenges with their creation timestamps
+/// - Scoping each challenge to the enrolled phone it was issued to
 /// - Preventing nonce reuse (replay attacks)
 /// - Enforcing challenge expiry
-/// - Limiting active challenges to prevent DoS attacks
+/// - Limiting active challenges to prevent DoS attacks (per phone and across
+///   all phones), evicting the oldest pending challenge first
 pub struct ChallengeManager {
     /// Cryptographically secure random number generator.
     rng: SystemRandom,
     /// Desktop identifier included in challenges.
     desktop_id: String,
     /// Active challenges keyed by nonce (base64 encoded).
-    /// Value is (Challenge, creation_timestamp_unix).
-    active_challenges: Mutex<HashMap<String, (Challenge, u64)>>,
+    /// Value is (Challenge, creation_timestamp_unix, phone_id, issue_serial).
+    active_challenges: Mutex<HashMap<String, (Challenge, u64, String, u64)>>,
+    /// Serial of the next issued challenge, orders challenges for eviction.
+    next_serial: AtomicU64,
     /// Previously used nonces to detect replay attacks.
     /// Value is the timestamp when the nonce was used.
     used_nonces: Mutex<HashMap<String, u64>>,
+    /// Enrolled phones keyed by phone ID.
+    enrolled: Mutex<HashMap<String, Verifier>>,
 }
 
 impl ChallengeManager {
@@ -47,10 +66,52 @@ impl ChallengeManager {
             rng: SystemRandom::new(),
             desktop_id,
             active_challenges: Mutex::new(HashMap::new()),
+            next_serial: AtomicU64::new(0),
             used_nonces: Mutex::new(HashMap::new()),
+            enrolled: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Enrolls a phone, replacing its verifier if it was already enrolled.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AuthError::CryptoError` if the registry lock is poisoned.
+    pub fn enroll(&self, phone_id: &str, verifier: Verifier) -> Result<(), AuthError> {
+        self.lock_enrolled()?.insert(phone_id.to_string(), verifier);
+        Ok(())
+    }
+
+    /// Removes an enrolled phone and any challenges issued to it.
+    ///
+    /// Returns `true` if the phone was enrolled.
+    pub fn unenroll(&self, phone_id: &str) -> bool {
+        if let Ok(mut challenges) = self.active_challenges.lock() {
+            challenges.retain(|_, (_, _, issued_to, _)| issued_to != phone_id);
+        }
+
+        self.enrolled
+            .lock()
+            .map(|mut enrolled| enrolled.remove(phone_id).is_some())
+            .unwrap_or(false)
+    }
+
+    /// Checks whether a phone is enrolled.
+    pub fn is_enrolled(&self, phone_id: &str) -> bool {
+        self.enrolled
+            .lock()
+            .map(|enrolled| enrolled.contains_key(phone_id))
+            .unwrap_or(false)
+    }
+
+    /// Returns the IDs of all enrolled phones.
+    pub fn enrolled_phones(&self) -> Vec<String> {
+        self.enrolled
+            .lock()
+            .map(|enrolled| enrolled.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
     /// Generates a new authentication challenge.
     ///
     /// The challenge contains:
@@ -59,25 +120,65 @@ impl ChallengeManager {
     /// - Unix timestamp for expiry checking
     /// - Desktop identifier for binding
     ///
+    /// Only `phone_id` can answer the challenge.
+    ///
+    /// # Arguments
+    ///
+    /// * `phone_id` - The enrolled phone the challenge is issued to.
+    ///
     /// # Errors
     ///
+    /// Returns `AuthError::UnknownPhone` if the phone is not enrolled.
     /// Returns `AuthError::CryptoError` if random generation fails.
-    /// Returns `AuthError::TooManyChallenges` if the maximum number of active
-    /// challenges has been reached (DoS protection).
-    pub fn generate_challenge(&self) -> Result<Challenge, AuthError> {
-        // Clean up expired challenges first
-        self.cleanup_expired_challenges();
+    pub fn generate_challenge(&self, phone_id: &str) -> Result<Challenge, AuthError> {
+        self.issue_challenge(phone_id, None)
+    }
 
-        // Check DoS limit
-        {
-            let challenges = self.active_challenges.lock().map_err(|e| {
-                AuthError::CryptoError(format!("Failed to acquire challenges lock: {e}"))
-            })?;
-            if challenges.len() >= MAX_ACTIVE_CHALLENGES {
-                return Err(AuthError::TooManyChallenges);
-            }
+    /// Generates a challenge bound to the connection it will be sent on.
+    ///
+    /// The response must be verified with [`Self::verify_bound_response`],
+    /// passing the channel binding of the connection it arrived on.
+    ///
+    /// # Arguments
+    ///
+    /// * `phone_id` - The enrolled phone the challenge is issued to.
+    /// * `binding` - Channel binding of the connection (see
+    ///   [`Challenge::with_channel_binding`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Self::generate_challenge`].
+    pub fn generate_bound_challenge(
+        &self,
+        phone_id: &str,
+        binding: &[u8],
+    ) -> Result<Challenge, AuthError> {
+        self.issue_challenge(phone_id, Some(binding))
+    }
+
+    /// Generates a challenge for `phone_id`, optionally bound to a connection.
+    fn issue_challenge(
+        &self,
+        phone_id: &str,
+        binding: Option<&[u8]>,
+    ) -> Result<Challenge, AuthError> {
+        self.issue_challenge_at(phone_id, binding, current_unix_timestamp())
+    }
+
+    /// Generates a challenge as of `now` (a Unix timestamp).
+    fn issue_challenge_at(
+        &self,
+        phone_id: &str,
+        binding: Option<&[u8]>,
+        now: u64,
+    ) -> Result<Challenge, AuthError> {
+        if !self.lock_enrolled()?.contains_key(phone_id) {
+            return Err(AuthError::UnknownPhone(phone_id.to_string()));
         }
 
+        // Clean up expired challenges first
+        self.cleanup_expired_challenges_at(now);
+
         // Generate random challenge bytes
         let mut challenge_bytes = [0u8; CHALLENGE_SIZE];
         self.rng.fill(&mut challenge_bytes).map_err(|e| {
@@ -90,23 +191,36 @@ impl ChallengeManager {
             .fill(&mut nonce_bytes)
             .map_err(|e| AuthError::CryptoError(format!("Failed to generate random nonce: {e}")))?;
 
-        let timestamp = current_unix_timestamp();
+        let timestamp = now;
         let challenge_b64 = base64::engine::general_purpose::STANDARD.encode(challenge_bytes);
         let nonce_b64 = base64::engine::general_purpose::STANDARD.encode(nonce_bytes);
 
-        let challenge = Challenge {
+        let mut challenge = Challenge {
             challenge: challenge_b64,
             nonce: nonce_b64.clone(),
             timestamp,
             desktop_id: self.desktop_id.clone(),
+            channel_binding: None,
         };
+        if let Some(binding) = binding {
+            challenge = challenge.with_channel_binding(binding);
+        }
 
-        // Store the challenge
+        // Store the challenge, evicting the oldest pending ones over the limits
         {
             let mut challenges = self.active_challenges.lock().map_err(|e| {
                 AuthError::CryptoError(format!("Failed to acquire challenges lock: {e}"))
             })?;
-            challenges.insert(nonce_b64, (challenge.clone(), timestamp));
+            evict_oldest(&mut challenges, MAX_CHALLENGES_PER_PHONE, |issued_to| {
+                issued_to == phone_id
+            });
+            evict_oldest(&mut challenges, MAX_ACTIVE_CHALLENGES, |_| true);
+
+            let serial = self.next_serial.fetch_add(1, Ordering::Relaxed);
+            challenges.insert(
+                nonce_b64,
+                (challenge.clone(), timestamp, phone_id.to_string(), serial),
+            );
         }
 
         Ok(challenge)
@@ -115,20 +229,37 @@ impl ChallengeManager {
     /// Retrieves and consumes a challenge by its nonce.
     ///
     /// This is called when verifying a response. The challenge is removed
-    /// from active challenges and the nonce is marked as used.
+    /// from active challenges and the nonce is marked as used. A challenge
+    /// issued to another phone is left untouched.
     ///
     /// # Arguments
     ///
+    /// * `phone_id` - The phone the response claims to come from.
     /// * `nonce` - The base64-encoded nonce from the challenge response.
     ///
     /// # Errors
     ///
     /// Returns `AuthError::ChallengeExpired` if:
     /// - The challenge does not exist
+    /// - The challenge was issued to a different phone
     /// - The challenge has expired (older than 30 seconds)
     ///
     /// Returns `AuthError::NonceReuse` if the nonce was already used.
-    pub fn get_and_consume_challenge(&self, nonce: &str) -> Result<Challenge, AuthError> {
+    pub fn get_and_consume_challenge(
+        &self,
+        phone_id: &str,
+        nonce: &str,
+    ) -> Result<Challenge, AuthError> {
+        self.get_and_consume_challenge_at(phone_id, nonce, current_unix_timestamp())
+    }
+
+    /// Retrieves and consumes a challenge as of `now` (a Unix timestamp).
+    fn get_and_consume_challenge_at(
+        &self,
+        phone_id: &str,
+        nonce: &str,
+        now: u64,
+    ) -> Result<Challenge, AuthError> {
         // Check for nonce reuse
         {
             let used = self.used_nonces.lock().map_err(|e| {
@@ -139,18 +270,22 @@ impl ChallengeManager {
             }
         }
 
-        // Remove and retrieve the challenge
+        // Remove and retrieve the challenge, if it was issued to this phone
         let (challenge, created_at) = {
             let mut challenges = self.active_challenges.lock().map_err(|e| {
                 AuthError::CryptoError(format!("Failed to acquire challenges lock: {e}"))
             })?;
-            challenges
+            match challenges.get(nonce) {
+                Some((_, _, issued_to, _)) if issued_to == phone_id => {}
+                _ => return Err(AuthError::ChallengeExpired),
+            }
+            let (challenge, created_at, _, _) = challenges
                 .remove(nonce)
-                .ok_or(AuthError::ChallengeExpired)?
+                .ok_or(AuthError::ChallengeExpired)?;
+            (challenge, created_at)
         };
 
         // Check expiry
-        let now = current_unix_timestamp();
         if now.saturating_sub(created_at) > CHALLENGE_EXPIRY_SECS {
             return Err(AuthError::ChallengeExpired);
         }
@@ -166,6 +301,78 @@ impl ChallengeManager {
         Ok(challenge)
     }
 
+    /// Consumes the challenge a response answers and verifies its signature.
+    ///
+    /// The response must come from the phone the challenge was issued to and
+    /// be signed with that phone's enrolled key.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`Self::get_and_consume_challenge`], plus
+    /// `AuthError::UnknownPhone` if the phone is no longer enrolled and the
+    /// errors of [`Verifier::verify_response`].
+    /// Returns `AuthError::ChannelBindingMismatch` if the challenge was bound
+    /// to a connection; use [`Self::verify_bound_response`] for those.
+    pub fn verify_response(&self, response: &ChallengeResponse) -> Result<Challenge, AuthError> {
+        self.verify_response_on(response, None)
+    }
+
+    /// Verifies a response to a challenge bound to a connection.
+    ///
+    /// `binding` is the channel binding of the connection the response
+    /// arrived on. It must match the one the challenge was bound to, and the
+    /// phone must have signed over it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AuthError::ChannelBindingMismatch` if the challenge was not
+    /// bound to `binding`, plus the errors of [`Self::verify_response`].
+    pub fn verify_bound_response(
+        &self,
+        response: &ChallengeResponse,
+        binding: &[u8],
+    ) -> Result<Challenge, AuthError> {
+        self.verify_response_on(response, Some(binding))
+    }
+
+    /// Verifies a response that arrived on a connection with `binding`.
+    fn verify_response_on(
+        &self,
+        response: &ChallengeResponse,
+        binding: Option<&[u8]>,
+    ) -> Result<Challenge, AuthError> {
+        let challenge = self.get_and_consume_challenge(&response.phone_id, &response.nonce)?;
+
+        let bound = match binding {
+            Some(binding) => challenge.is_bound_to(binding),
+            None => challenge.channel_binding.is_none(),
+        };
+        if !bound {
+            return Err(AuthError::ChannelBindingMismatch);
+        }
+
+        let enrolled = self.lock_enrolled()?;
+        let verifier = enrolled
+            .get(&response.phone_id)
+            .ok_or_else(|| AuthError::UnknownPhone(response.phone_id.clone()))?;
+        verifier.verify_response(&challenge, response)?;
+
+        Ok(challenge)
+    }
+
+    /// Accepts a key rotation announced by an enrolled phone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AuthError::UnknownPhone` if the phone is not enrolled, and the
+    /// errors of [`Verifier::accept_rotation`].
+    pub fn accept_rotation(&self, rotation: &KeyRotation) -> Result<(), AuthError> {
+        self.lock_enrolled()?
+            .get_mut(&rotation.phone_id)
+            .ok_or_else(|| AuthError::UnknownPhone(rotation.phone_id.clone()))?
+            .accept_rotation(rotation)
+    }
+
     /// Returns the number of currently active challenges.
     ///
     /// This is primarily useful for testing and monitoring.
@@ -178,14 +385,18 @@ impl ChallengeManager {
     /// This is called automatically before generating new challenges,
     /// but can also be called explicitly for maintenance.
     pub fn cleanup_expired_challenges(&self) {
-        let now = current_unix_timestamp();
+        self.cleanup_expired_challenges_at(current_unix_timestamp());
+    }
+
+    /// Cleans up challenges and used nonces expired as of `now` (a Unix timestamp).
+    fn cleanup_expired_challenges_at(&self, now: u64) {
         let expiry_threshold = now.saturating_sub(CHALLENGE_EXPIRY_SECS);
         // Keep used nonces for 2x the expiry time to catch delayed replays
         let nonce_expiry_threshold = now.saturating_sub(CHALLENGE_EXPIRY_SECS * 2);
 
         // Clean up expired active challenges
         if let Ok(mut challenges) = self.active_challenges.lock() {
-            challenges.retain(|_, (_, created_at)| *created_at > expiry_threshold);
+            challenges.retain(|_, (_, created_at, _, _)| *created_at > expiry_threshold);
         }
 
         // Clean up old used nonces
@@ -193,10 +404,62 @@ impl ChallengeManager {
             used.retain(|_, used_at| *used_at > nonce_expiry_threshold);
         }
     }
+
+    /// Spawns a task that cleans up expired challenges every `interval`.
+    ///
+    /// The task only holds a weak reference and stops once the manager is
+    /// dropped.
+    pub fn spawn_cleanup_task(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let manager = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut timer = tokio::time::interval(interval);
+            // The first tick completes immediately
+            timer.tick().await;
+            loop {
+                timer.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                manager.cleanup_expired_challenges();
+            }
+        })
+    }
+
+    /// Locks the registry of enrolled phones.
+    fn lock_enrolled(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, HashMap<String, Verifier>>, AuthError> {
+        self.enrolled
+            .lock()
+            .map_err(|e| AuthError::CryptoError(format!("Failed to acquire enrolled lock: {e}")))
+    }
+}
+
+/// Evicts the oldest challenges matching `issued_to` until fewer than `limit` remain.
+fn evict_oldest(
+    challenges: &mut HashMap<String, (Challenge, u64, String, u64)>,
+    limit: usize,
+    issued_to: impl Fn(&str) -> bool,
+) {
+    loop {
+        let matching = challenges
+            .iter()
+            .filter(|(_, (_, _, phone_id, _))| issued_to(phone_id));
+        if matching.clone().count() < limit {
+            break;
+        }
+        let Some(oldest) = matching
+            .min_by_key(|(_, (_, _, _, serial))| *serial)
+            .map(|(nonce, _)| nonce.clone())
+        else {
+            break;
+        };
+        challenges.remove(&oldest);
+    }
 }
 
 /// Returns the current Unix timestamp in seconds.
-fn current_unix_timestamp() -> u64 {
+pub(super) fn current_unix_timestamp() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|d| d.as_secs())
@@ -208,13 +471,17 @@ mod tests {
     use super::*;
 
     fn create_test_manager() -> ChallengeManager {
-        ChallengeManager::new("test-desktop".to_string())
+        let manager = ChallengeManager::new("test-desktop".to_string());
+        manager
+            .enroll("test-phone", Verifier::new(vec![0u8; 32]).unwrap())
+            .unwrap();
+        manager
     }
 
     #[test]
     fn test_challenge_generation() {
         let manager = create_test_manager();
-        let challenge = manager.generate_challenge().unwrap();
+        let challenge = manager.generate_challenge("test-phone").unwrap();
 
         assert!(!challenge.challenge.is_empty());
         assert!(!challenge.nonce.is_empty());
@@ -234,9 +501,9 @@ mod tests {
     fn test_challenge_uniqueness() {
         let manager = create_test_manager();
 
-        let c1 = manager.generate_challenge().unwrap();
-        let c2 = manager.generate_challenge().unwrap();
-        let c3 = manager.generate_challenge().unwrap();
+        let c1 = manager.generate_challenge("test-phone").unwrap();
+        let c2 = manager.generate_challenge("test-phone").unwrap();
+        let c3 = manager.generate_challenge("test-phone").unwrap();
 
         // All challenges should be unique
         assert_ne!(c1.challenge, c2.challenge);
@@ -253,11 +520,13 @@ mod tests {
     fn test_get_and_consume_challenge() {
         let manager = create_test_manager();
 
-        let challenge = manager.generate_challenge().unwrap();
+        let challenge = manager.generate_challenge("test-phone").unwrap();
         let nonce = challenge.nonce.clone();
 
         // Should successfully retrieve and consume the challenge
-        let retrieved = manager.get_and_consume_challenge(&nonce).unwrap();
+        let retrieved = manager
+            .get_and_consume_challenge("test-phone", &nonce)
+            .unwrap();
         assert_eq!(retrieved.challenge, challenge.challenge);
         assert_eq!(retrieved.nonce, challenge.nonce);
     }
@@ -267,7 +536,7 @@ mod tests {
         let manager = create_test_manager();
 
         // Try to get a challenge that doesn't exist
-        let result = manager.get_and_consume_challenge("nonexistent-nonce");
+        let result = manager.get_and_consume_challenge("test-phone", "nonexistent-nonce");
         assert!(matches!(result, Err(AuthError::ChallengeExpired)));
     }
 
@@ -275,14 +544,16 @@ mod tests {
     fn test_nonce_reuse_detection() {
         let manager = create_test_manager();
 
-        let challenge = manager.generate_challenge().unwrap();
+        let challenge = manager.generate_challenge("test-phone").unwrap();
         let nonce = challenge.nonce.clone();
 
         // First use should succeed
-        let _ = manager.get_and_consume_challenge(&nonce).unwrap();
+        let _ = manager
+            .get_and_consume_challenge("test-phone", &nonce)
+            .unwrap();
 
         // Second use should fail with NonceReuse
-        let result = manager.get_and_consume_challenge(&nonce);
+        let result = manager.get_and_consume_challenge("test-phone", &nonce);
         assert!(matches!(result, Err(AuthError::NonceReuse)));
     }
 
@@ -292,17 +563,21 @@ mod tests {
 
         assert_eq!(manager.active_challenge_count(), 0);
 
-        let c1 = manager.generate_challenge().unwrap();
+        let c1 = manager.generate_challenge("test-phone").unwrap();
         assert_eq!(manager.active_challenge_count(), 1);
 
-        let c2 = manager.generate_challenge().unwrap();
+        let c2 = manager.generate_challenge("test-phone").unwrap();
         assert_eq!(manager.active_challenge_count(), 2);
 
         // Consuming a challenge should reduce the count
-        manager.get_and_consume_challenge(&c1.nonce).unwrap();
+        manager
+            .get_and_consume_challenge("test-phone", &c1.nonce)
+            .unwrap();
         assert_eq!(manager.active_challenge_count(), 1);
 
-        manager.get_and_consume_challenge(&c2.nonce).unwrap();
+        manager
+            .get_and_consume_challenge("test-phone", &c2.nonce)
+            .unwrap();
         assert_eq!(manager.active_challenge_count(), 0);
     }
 
@@ -310,14 +585,20 @@ mod tests {
     fn test_dos_protection() {
         let manager = create_test_manager();
 
-        // Generate maximum allowed challenges
-        for _ in 0..MAX_ACTIVE_CHALLENGES {
-            manager.generate_challenge().unwrap();
+        let oldest = manager.generate_challenge("test-phone").unwrap();
+        for _ in 1..MAX_CHALLENGES_PER_PHONE {
+            manager.generate_challenge("test-phone").unwrap();
         }
 
-        // Next challenge should fail
-        let result = manager.generate_challenge();
-        assert!(matches!(result, Err(AuthError::TooManyChallenges)));
+        // The next challenge evicts the oldest pending one
+        let newest = manager.generate_challenge("test-phone").unwrap();
+        assert_eq!(manager.active_challenge_count(), MAX_CHALLENGES_PER_PHONE);
+
+        let result = manager.get_and_consume_challenge("test-phone", &oldest.nonce);
+        assert!(matches!(result, Err(AuthError::ChallengeExpired)));
+        assert!(manager
+            .get_and_consume_challenge("test-phone", &newest.nonce)
+            .is_ok());
     }
 
     #[test]
@@ -325,7 +606,7 @@ mod tests {
         let manager = create_test_manager();
 
         // Generate a challenge
-        let _ = manager.generate_challenge().unwrap();
+        let _ = manager.generate_challenge("test-phone").unwrap();
         assert_eq!(manager.active_challenge_count(), 1);
 
         // Cleanup should not remove fresh challenges
@@ -337,10 +618,179 @@ mod tests {
     fn test_consumed_challenge_removed_from_active() {
         let manager = create_test_manager();
 
-        let challenge = manager.generate_challenge().unwrap();
+        let challenge = manager.generate_challenge("test-phone").unwrap();
+        assert_eq!(manager.active_challenge_count(), 1);
+
+        manager
+            .get_and_consume_challenge("test-phone", &challenge.nonce)
+            .unwrap();
+        assert_eq!(manager.active_challenge_count(), 0);
+    }
+
+    #[test]
+    fn test_challenge_for_unknown_phone_rejected() {
+        let manager = create_test_manager();
+
+        let result = manager.generate_challenge("other-phone");
+        assert!(matches!(result, Err(AuthError::UnknownPhone(_))));
+        assert_eq!(manager.active_challenge_count(), 0);
+    }
+
+    #[test]
+    fn test_challenge_scoped_to_phone() {
+        let manager = create_test_manager();
+        manager
+            .enroll("other-phone", Verifier::new(vec![1u8; 32]).unwrap())
+            .unwrap();
+
+        let challenge = manager.generate_challenge("test-phone").unwrap();
+
+        // Another phone can't consume it, and doesn't use it up either
+        let result = manager.get_and_consume_challenge("other-phone", &challenge.nonce);
+        assert!(matches!(result, Err(AuthError::ChallengeExpired)));
+        assert!(manager
+            .get_and_consume_challenge("test-phone", &challenge.nonce)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_flood_for_one_phone_spares_others() {
+        let manager = create_test_manager();
+        manager
+            .enroll("other-phone", Verifier::new(vec![1u8; 32]).unwrap())
+            .unwrap();
+
+        let other = manager.generate_challenge("other-phone").unwrap();
+        for _ in 0..MAX_ACTIVE_CHALLENGES {
+            manager.generate_challenge("test-phone").unwrap();
+        }
+
+        assert_eq!(
+            manager.active_challenge_count(),
+            MAX_CHALLENGES_PER_PHONE + 1
+        );
+        assert!(manager
+            .get_and_consume_challenge("other-phone", &other.nonce)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_global_limit_evicts_oldest() {
+        let manager = create_test_manager();
+        let phones = MAX_ACTIVE_CHALLENGES / MAX_CHALLENGES_PER_PHONE + 1;
+        for i in 0..phones {
+            manager
+                .enroll(&format!("phone-{i}"), Verifier::new(vec![1u8; 32]).unwrap())
+                .unwrap();
+        }
+
+        let oldest = manager.generate_challenge("phone-0").unwrap();
+        for i in 0..phones {
+            for _ in 0..MAX_CHALLENGES_PER_PHONE {
+                manager.generate_challenge(&format!("phone-{i}")).unwrap();
+            }
+        }
+
+        assert_eq!(manager.active_challenge_count(), MAX_ACTIVE_CHALLENGES);
+        let result = manager.get_and_consume_challenge("phone-0", &oldest.nonce);
+        assert!(matches!(result, Err(AuthError::ChallengeExpired)));
+    }
+
+    #[test]
+    fn test_unenroll_drops_challenges() {
+        let manager = create_test_manager();
+        let challenge = manager.generate_challenge("test-phone").unwrap();
+
+        assert!(manager.unenroll("test-phone"));
+        assert!(!manager.unenroll("test-phone"));
+        assert!(!manager.is_enrolled("test-phone"));
+        assert!(manager.enrolled_phones().is_empty());
+        assert_eq!(manager.active_challenge_count(), 0);
+
+        let result = manager.get_and_consume_challenge("test-phone", &challenge.nonce);
+        assert!(matches!(result, Err(AuthError::ChallengeExpired)));
+    }
+
+    #[test]
+    fn test_bound_challenge_needs_bound_verification() {
+        let manager = create_test_manager();
+        let challenge = manager
+            .generate_bound_challenge("test-phone", &[1u8; 32])
+            .unwrap();
+        assert!(challenge.is_bound_to(&[1u8; 32]));
+
+        let response = ChallengeResponse {
+            nonce: challenge.nonce.clone(),
+            signature: String::new(),
+            phone_id: "test-phone".to_string(),
+        };
+        let result = manager.verify_response(&response);
+        assert!(matches!(result, Err(AuthError::ChannelBindingMismatch)));
+    }
+
+    #[test]
+    fn test_expired_challenges_swept() {
+        let manager = create_test_manager();
+        let start = current_unix_timestamp();
+
+        let challenge = manager
+            .issue_challenge_at("test-phone", None, start)
+            .unwrap();
+        manager.cleanup_expired_challenges_at(start + CHALLENGE_EXPIRY_SECS);
         assert_eq!(manager.active_challenge_count(), 1);
 
-        manager.get_and_consume_challenge(&challenge.nonce).unwrap();
+        // Past expiry the challenge is gone, not just rejected
+        manager.cleanup_expired_challenges_at(start + CHALLENGE_EXPIRY_SECS + 1);
         assert_eq!(manager.active_challenge_count(), 0);
+
+        let result = manager.get_and_consume_challenge_at(
+            "test-phone",
+            &challenge.nonce,
+            start + CHALLENGE_EXPIRY_SECS + 1,
+        );
+        assert!(matches!(result, Err(AuthError::ChallengeExpired)));
+    }
+
+    #[test]
+    fn test_flood_expires_before_fresh_challenge() {
+        let manager = create_test_manager();
+        let start = current_unix_timestamp();
+
+        // Flood the store with challenges nobody answers
+        for _ in 0..MAX_ACTIVE_CHALLENGES {
+            manager
+                .issue_challenge_at("test-phone", None, start)
+                .unwrap();
+        }
+
+        // A fresh challenge is still issued and can be answered
+        let challenge = manager
+            .issue_challenge_at("test-phone", None, start + 1)
+            .unwrap();
+        assert!(manager
+            .get_and_consume_challenge_at("test-phone", &challenge.nonce, start + 1)
+            .is_ok());
+
+        // Once they expire the flood is gone entirely
+        let later = start + CHALLENGE_EXPIRY_SECS + 1;
+        let challenge = manager
+            .issue_challenge_at("test-phone", None, later)
+            .unwrap();
+        assert_eq!(manager.active_challenge_count(), 1);
+        assert!(manager
+            .get_and_consume_challenge_at("test-phone", &challenge.nonce, later)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_task_stops_with_manager() {
+        let manager = Arc::new(create_test_manager());
+        let task = manager.spawn_cleanup_task(Duration::from_millis(10));
+
+        drop(manager);
+        tokio::time::timeout(Duration::from_secs(5), task)
+            .await
+            .expect("cleanup task should stop")
+            .unwrap();
     }
 }
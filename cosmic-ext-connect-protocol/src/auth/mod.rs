@@ -7,11 +7,13 @@
 //!
 //! The authentication flow works as follows:
 //!
-//! 1. Desktop generates a challenge using [`ChallengeManager::generate_challenge`]
-//! 2. Challenge is sent to the phone (via existing pairing channel)
-//! 3. Phone signs the challenge with its Ed25519 private key
-//! 4. Phone sends back a [`ChallengeResponse`] with the signature
-//! 5. Desktop verifies the signature using [`Verifier::verify_response`]
+//! 1. Desktop enrolls each trusted phone's key using [`ChallengeManager::enroll`]
+//! 2. Desktop generates a challenge for one phone using
+//!    [`ChallengeManager::generate_challenge`]
+//! 3. Challenge is sent to the phone (via existing pairing channel)
+//! 4. Phone signs the challenge with its Ed25519 private key
+//! 5. Phone sends back a [`ChallengeResponse`] with the signature
+//! 6. Desktop verifies the signature using [`ChallengeManager::verify_response`]
 //!
 //! # Security Features
 //!
@@ -19,36 +21,44 @@
 //! - **16-byte nonces**: Unique identifier for replay prevention
 //! - **30-second expiry**: Limits the window for attacks
 //! - **Nonce tracking**: Prevents replay attacks
-//! - **DoS protection**: Bounded active challenge storage
+//! - **Channel binding**: [`ChallengeManager::generate_bound_challenge`] binds a
+//!   challenge to the TLS connection, so it can't be relayed to the real phone
+//!   over another connection (see [`Challenge::with_channel_binding`])
+//! - **Per-phone challenges**: A challenge can only be answered by the phone
+//!   it was issued to
+//! - **DoS protection**: Bounded active challenge storage with per-phone
+//!   quotas; the oldest pending challenge makes room for a new one
 //! - **Constant-time verification**: Prevents timing attacks
+//! - **Key rotation**: A phone can replace its key with a [`KeyRotation`]
+//!   announcement signed by the current key; the old key stays valid for
+//!   [`KEY_ROTATION_OVERLAP_SECS`] (see [`Verifier::accept_rotation`])
 //!
 //! # Example
 //!
 //! ```rust,no_run
 //! use cosmic_ext_connect_protocol::auth::{ChallengeManager, Verifier, ChallengeResponse};
 //!
-//! // Desktop: Generate a challenge
+//! // Desktop: Enroll the phone's public key
+//! # let phone_public_key = vec![0u8; 32];
 //! let manager = ChallengeManager::new("my-desktop-id".to_string());
-//! let challenge = manager.generate_challenge().unwrap();
+//! manager
+//!     .enroll("phone-123", Verifier::new(phone_public_key).unwrap())
+//!     .unwrap();
 //!
-//! // ... send challenge to phone, phone signs it ...
+//! // Desktop: Generate a challenge for that phone
+//! let challenge = manager.generate_challenge("phone-123").unwrap();
 //!
-//! // Desktop: Verify the response
-//! # let phone_public_key = vec![0u8; 32];
+//! // ... send challenge to phone, phone signs it ...
 //! # let signature = "".to_string();
-//! let verifier = Verifier::new(phone_public_key).unwrap();
 //!
-//! // Consume the challenge (prevents replay)
-//! let challenge = manager.get_and_consume_challenge(&challenge.nonce).unwrap();
-//!
-//! // Verify the signature
+//! // Desktop: Consume the challenge (prevents replay) and verify the signature
 //! let response = ChallengeResponse {
 //!     nonce: challenge.nonce.clone(),
 //!     signature,
 //!     phone_id: "phone-123".to_string(),
 //! };
 //!
-//! verifier.verify_response(&challenge, &response).unwrap();
+//! manager.verify_response(&response).unwrap();
 //! ```
 
 mod challenge;
@@ -57,8 +67,10 @@ mod verify;
 
 pub use challenge::ChallengeManager;
 pub use types::{
-    AuthError, Challenge, ChallengeResponse, CHALLENGE_EXPIRY_SECS, CHALLENGE_SIZE,
-    MAX_ACTIVE_CHALLENGES, NONCE_SIZE,
+    AuthError, Challenge, ChallengeResponse, KeyRotation, CHALLENGE_EXPIRY_SECS, CHALLENGE_SIZE,
+    CHANNEL_BINDING_EXPORTER_LABEL, CHANNEL_BINDING_SIZE, KEY_ROTATION_MAX_AGE_SECS,
+    KEY_ROTATION_OVERLAP_SECS, MAX_ACTIVE_CHALLENGES, MAX_CHALLENGES_PER_PHONE, NONCE_SIZE,
+    PACKET_TYPE_KEY_ROTATION,
 };
 pub use verify::{Verifier, ED25519_PUBLIC_KEY_SIZE, ED25519_SIGNATURE_SIZE};
 
@@ -78,12 +90,14 @@ mod integration_tests {
         let phone_keypair = Ed25519KeyPair::from_pkcs8(pkcs8_bytes.as_ref()).unwrap();
         let phone_public_key = phone_keypair.public_key().as_ref().to_vec();
 
-        // Desktop: Create challenge manager and verifier
+        // Desktop: Create challenge manager and enroll the phone
         let challenge_manager = ChallengeManager::new("test-desktop".to_string());
-        let verifier = Verifier::new(phone_public_key).unwrap();
+        challenge_manager
+            .enroll("test-phone", Verifier::new(phone_public_key).unwrap())
+            .unwrap();
 
         // Desktop: Generate challenge
-        let challenge = challenge_manager.generate_challenge().unwrap();
+        let challenge = challenge_manager.generate_challenge("test-phone").unwrap();
         assert_eq!(challenge.desktop_id, "test-desktop");
 
         // Phone: Sign the challenge
@@ -98,11 +112,7 @@ mod integration_tests {
         };
 
         // Desktop: Consume and verify
-        let consumed_challenge = challenge_manager
-            .get_and_consume_challenge(&response.nonce)
-            .unwrap();
-
-        let result = verifier.verify_response(&consumed_challenge, &response);
+        let result = challenge_manager.verify_response(&response);
         assert!(result.is_ok(), "Authentication should succeed");
     }
 
@@ -115,10 +125,12 @@ mod integration_tests {
         let phone_public_key = phone_keypair.public_key().as_ref().to_vec();
 
         let challenge_manager = ChallengeManager::new("test-desktop".to_string());
-        let verifier = Verifier::new(phone_public_key).unwrap();
+        challenge_manager
+            .enroll("test-phone", Verifier::new(phone_public_key).unwrap())
+            .unwrap();
 
         // Generate and sign a challenge
-        let challenge = challenge_manager.generate_challenge().unwrap();
+        let challenge = challenge_manager.generate_challenge("test-phone").unwrap();
         let message = challenge.signing_message();
         let signature = phone_keypair.sign(&message);
         let signature_b64 = base64::engine::general_purpose::STANDARD.encode(signature.as_ref());
@@ -130,13 +142,10 @@ mod integration_tests {
         };
 
         // First verification succeeds
-        let consumed = challenge_manager
-            .get_and_consume_challenge(&response.nonce)
-            .unwrap();
-        assert!(verifier.verify_response(&consumed, &response).is_ok());
+        assert!(challenge_manager.verify_response(&response).is_ok());
 
         // Replay attack: try to use the same response again
-        let replay_result = challenge_manager.get_and_consume_challenge(&response.nonce);
+        let replay_result = challenge_manager.verify_response(&response);
         assert!(
             matches!(replay_result, Err(AuthError::NonceReuse)),
             "Replay attack should be detected"
@@ -159,9 +168,11 @@ mod integration_tests {
 
         let challenge_manager = ChallengeManager::new("test-desktop".to_string());
         // Desktop only trusts the legitimate phone's public key
-        let verifier = Verifier::new(legit_public_key).unwrap();
+        challenge_manager
+            .enroll("test-phone", Verifier::new(legit_public_key).unwrap())
+            .unwrap();
 
-        let challenge = challenge_manager.generate_challenge().unwrap();
+        let challenge = challenge_manager.generate_challenge("test-phone").unwrap();
 
         // Attacker signs with their own key
         let message = challenge.signing_message();
@@ -172,18 +183,165 @@ mod integration_tests {
         let response = ChallengeResponse {
             nonce: challenge.nonce.clone(),
             signature: signature_b64,
-            phone_id: "attacker-phone".to_string(),
+            phone_id: "test-phone".to_string(),
         };
 
-        let consumed = challenge_manager
-            .get_and_consume_challenge(&response.nonce)
-            .unwrap();
-
         // Verification should fail - attacker's signature doesn't match
-        let result = verifier.verify_response(&consumed, &response);
+        let result = challenge_manager.verify_response(&response);
         assert!(
             matches!(result, Err(AuthError::InvalidSignature)),
             "Impersonation attack should be detected"
         );
     }
+
+    fn sign_response(
+        keypair: &Ed25519KeyPair,
+        challenge: &Challenge,
+        phone_id: &str,
+    ) -> ChallengeResponse {
+        let signature = keypair.sign(&challenge.signing_message());
+        ChallengeResponse {
+            nonce: challenge.nonce.clone(),
+            signature: base64::engine::general_purpose::STANDARD.encode(signature.as_ref()),
+            phone_id: phone_id.to_string(),
+        }
+    }
+
+    /// Test that an enrolled phone can't answer a challenge issued to another.
+    #[test]
+    fn test_wrong_enrolled_phone_rejected() {
+        let rng = SystemRandom::new();
+        let pkcs8_a = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let keypair_a = Ed25519KeyPair::from_pkcs8(pkcs8_a.as_ref()).unwrap();
+        let pkcs8_b = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let keypair_b = Ed25519KeyPair::from_pkcs8(pkcs8_b.as_ref()).unwrap();
+
+        let challenge_manager = ChallengeManager::new("test-desktop".to_string());
+        challenge_manager
+            .enroll(
+                "phone-a",
+                Verifier::new(keypair_a.public_key().as_ref().to_vec()).unwrap(),
+            )
+            .unwrap();
+        challenge_manager
+            .enroll(
+                "phone-b",
+                Verifier::new(keypair_b.public_key().as_ref().to_vec()).unwrap(),
+            )
+            .unwrap();
+
+        // Challenge for phone B, answered with a valid signature from phone A
+        let challenge = challenge_manager.generate_challenge("phone-b").unwrap();
+        let response = sign_response(&keypair_a, &challenge, "phone-a");
+        assert!(
+            matches!(
+                challenge_manager.verify_response(&response),
+                Err(AuthError::ChallengeExpired)
+            ),
+            "Response from the wrong phone should be rejected"
+        );
+
+        // Claiming to be phone B doesn't help: the signature is A's
+        let response = sign_response(&keypair_a, &challenge, "phone-b");
+        assert!(matches!(
+            challenge_manager.verify_response(&response),
+            Err(AuthError::InvalidSignature)
+        ));
+    }
+
+    /// Test that each enrolled phone authenticates independently.
+    #[test]
+    fn test_multiple_enrolled_phones() {
+        let rng = SystemRandom::new();
+        let challenge_manager = ChallengeManager::new("test-desktop".to_string());
+
+        let phones: Vec<(String, Ed25519KeyPair)> = (0..3)
+            .map(|i| {
+                let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+                let keypair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+                let phone_id = format!("phone-{i}");
+                let verifier = Verifier::new(keypair.public_key().as_ref().to_vec()).unwrap();
+                challenge_manager.enroll(&phone_id, verifier).unwrap();
+                (phone_id, keypair)
+            })
+            .collect();
+
+        // Outstanding challenges for every phone at once
+        let challenges: Vec<Challenge> = phones
+            .iter()
+            .map(|(phone_id, _)| challenge_manager.generate_challenge(phone_id).unwrap())
+            .collect();
+        assert_eq!(challenge_manager.active_challenge_count(), phones.len());
+
+        // Answered in reverse order, each succeeds on its own
+        for ((phone_id, keypair), challenge) in phones.iter().zip(&challenges).rev() {
+            let response = sign_response(keypair, challenge, phone_id);
+            assert!(challenge_manager.verify_response(&response).is_ok());
+        }
+        assert_eq!(challenge_manager.active_challenge_count(), 0);
+    }
+
+    /// Test that a response bound to one connection fails on another.
+    #[test]
+    fn test_channel_binding_prevents_relay() {
+        let rng = SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+        let keypair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+        let challenge_manager = ChallengeManager::new("test-desktop".to_string());
+        let verifier = Verifier::new(keypair.public_key().as_ref().to_vec()).unwrap();
+        challenge_manager.enroll("test-phone", verifier).unwrap();
+
+        let desktop_channel = [1u8; CHANNEL_BINDING_SIZE];
+        let relay_channel = [2u8; CHANNEL_BINDING_SIZE];
+
+        // The phone binds the challenge to the session it received it on
+        let received = |challenge: &Challenge, channel: &[u8]| {
+            let json = serde_json::to_string(challenge).unwrap();
+            serde_json::from_str::<Challenge>(&json)
+                .unwrap()
+                .with_channel_binding(channel)
+        };
+
+        // Valid for the channel it was signed on...
+        let challenge = challenge_manager
+            .generate_bound_challenge("test-phone", &desktop_channel)
+            .unwrap();
+        let response = sign_response(
+            &keypair,
+            &received(&challenge, &desktop_channel),
+            "test-phone",
+        );
+        assert!(challenge_manager
+            .verify_bound_response(&response, &desktop_channel)
+            .is_ok());
+
+        // ...but not if it arrives on a different one
+        let challenge = challenge_manager
+            .generate_bound_challenge("test-phone", &desktop_channel)
+            .unwrap();
+        let response = sign_response(
+            &keypair,
+            &received(&challenge, &desktop_channel),
+            "test-phone",
+        );
+        assert!(matches!(
+            challenge_manager.verify_bound_response(&response, &relay_channel),
+            Err(AuthError::ChannelBindingMismatch)
+        ));
+
+        // Relayed to the real phone, which signs over the relay's channel
+        let challenge = challenge_manager
+            .generate_bound_challenge("test-phone", &desktop_channel)
+            .unwrap();
+        let response = sign_response(
+            &keypair,
+            &received(&challenge, &relay_channel),
+            "test-phone",
+        );
+        assert!(matches!(
+            challenge_manager.verify_bound_response(&response, &desktop_channel),
+            Err(AuthError::InvalidSignature)
+        ));
+    }
 }
@@ -3,6 +3,7 @@
 //! This module defines the core types used in the phone authentication system:
 //! - `Challenge`: Generated by the desktop to authenticate a phone
 //! - `ChallengeResponse`: Signed response from the phone
+//! - `KeyRotation`: Announcement of a new phone key, signed with the current one
 //! - `AuthError`: Error types for authentication operations
 
 use serde::{Deserialize, Serialize};
@@ -20,11 +21,45 @@ pub const CHALLENGE_EXPIRY_SECS: u64 = 30;
 /// Maximum number of active challenges to prevent DoS attacks.
 pub const MAX_ACTIVE_CHALLENGES: usize = 100;
 
+/// Maximum number of active challenges issued to a single phone.
+pub const MAX_CHALLENGES_PER_PHONE: usize = 10;
+
+/// Packet type of a key rotation announcement.
+pub const PACKET_TYPE_KEY_ROTATION: &str = "cconnect.auth.keyrotation";
+
+/// Maximum age of a key rotation announcement in seconds.
+pub const KEY_ROTATION_MAX_AGE_SECS: u64 = 300;
+
+/// How long the previous key stays valid after a rotation, in seconds (one week).
+pub const KEY_ROTATION_OVERLAP_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// TLS exporter label for the channel binding of a connection (RFC 9266).
+///
+/// Export [`CHANNEL_BINDING_SIZE`] bytes of keying material with this label
+/// and no context to get the value passed to [`Challenge::with_channel_binding`].
+pub const CHANNEL_BINDING_EXPORTER_LABEL: &[u8] = b"EXPORTER-Channel-Binding";
+
+/// Size of an exported TLS channel binding in bytes.
+pub const CHANNEL_BINDING_SIZE: usize = 32;
+
+/// Domain separator for the channel binding in a challenge signature.
+const CHANNEL_BINDING_CONTEXT: &[u8] = b"cconnect-channel-binding";
+
+/// Domain separator for key rotation signatures, so a challenge signature
+/// can never be passed off as a rotation and vice versa.
+const KEY_ROTATION_CONTEXT: &[u8] = b"cconnect-key-rotation";
+
 /// A cryptographic challenge for phone authentication.
 ///
 /// The challenge is generated by the desktop and sent to the phone.
 /// The phone signs the challenge with its Ed25519 private key and
 /// returns a `ChallengeResponse`.
+///
+/// A challenge can be bound to the TLS connection it is sent on (see
+/// [`Challenge::with_channel_binding`]). The binding is never sent: each side
+/// computes it from its own TLS session, and the phone signs over the binding
+/// of the connection it received the challenge on. A challenge relayed to the
+/// real phone over another connection yields a signature the desktop rejects.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Challenge {
     /// Base64-encoded 32-byte random challenge.
@@ -35,6 +70,11 @@ pub struct Challenge {
     pub timestamp: u64,
     /// Identifier of the desktop that generated the challenge.
     pub desktop_id: String,
+    /// Base64-encoded channel binding of the connection, if bound.
+    ///
+    /// Local to each side, never serialized.
+    #[serde(skip)]
+    pub channel_binding: Option<String>,
 }
 
 impl Challenge {
@@ -62,9 +102,34 @@ impl Challenge {
             .map_err(|e| AuthError::CryptoError(format!("Failed to decode nonce: {e}")))
     }
 
+    /// Binds the challenge to a connection's channel binding.
+    ///
+    /// `binding` is the TLS exporter value of the connection (see
+    /// [`CHANNEL_BINDING_EXPORTER_LABEL`]), computed by each side from its own
+    /// TLS session. The transports don't export keying material yet, so
+    /// nothing binds challenges until they do.
+    pub fn with_channel_binding(mut self, binding: &[u8]) -> Self {
+        use base64::Engine;
+        self.channel_binding = Some(base64::engine::general_purpose::STANDARD.encode(binding));
+        self
+    }
+
+    /// Checks whether the challenge is bound to `binding`.
+    ///
+    /// Unbound challenges are not bound to any connection.
+    pub fn is_bound_to(&self, binding: &[u8]) -> bool {
+        use base64::Engine;
+        self.channel_binding.as_deref().is_some_and(|bound| {
+            base64::engine::general_purpose::STANDARD
+                .decode(bound)
+                .is_ok_and(|bound| bound == binding)
+        })
+    }
+
     /// Creates the message that should be signed by the phone.
     ///
-    /// The message format is: `challenge || nonce || timestamp || desktop_id`
+    /// The message format is: `challenge || nonce || timestamp || desktop_id`,
+    /// followed by `context || channel_binding` for bound challenges.
     /// This ensures the signature covers all challenge parameters.
     pub fn signing_message(&self) -> Vec<u8> {
         let mut message = Vec::new();
@@ -72,6 +137,10 @@ impl Challenge {
         message.extend_from_slice(self.nonce.as_bytes());
         message.extend_from_slice(&self.timestamp.to_le_bytes());
         message.extend_from_slice(self.desktop_id.as_bytes());
+        if let Some(binding) = &self.channel_binding {
+            message.extend_from_slice(CHANNEL_BINDING_CONTEXT);
+            message.extend_from_slice(binding.as_bytes());
+        }
         message
     }
 }
@@ -103,6 +172,86 @@ impl ChallengeResponse {
     }
 }
 
+/// Announcement that a phone is switching to a new Ed25519 key.
+///
+/// Signed with the phone's current key, so only the holder of the key the
+/// desktop already trusts can replace it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KeyRotation {
+    /// Identifier of the phone rotating its key.
+    pub phone_id: String,
+    /// Base64-encoded new Ed25519 public key.
+    pub new_public_key: String,
+    /// Unix timestamp when the announcement was created.
+    pub timestamp: u64,
+    /// Base64-encoded Ed25519 signature by the current key over the announcement.
+    pub signature: String,
+}
+
+impl KeyRotation {
+    /// Returns the raw new public key bytes decoded from base64.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AuthError::InvalidPublicKey` if base64 decoding fails.
+    pub fn new_public_key_bytes(&self) -> Result<Vec<u8>, AuthError> {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD
+            .decode(&self.new_public_key)
+            .map_err(|e| AuthError::InvalidPublicKey(format!("Invalid base64: {e}")))
+    }
+
+    /// Returns the raw signature bytes decoded from base64.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AuthError::CryptoError` if base64 decoding fails.
+    pub fn signature_bytes(&self) -> Result<Vec<u8>, AuthError> {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD
+            .decode(&self.signature)
+            .map_err(|e| AuthError::CryptoError(format!("Failed to decode signature: {e}")))
+    }
+
+    /// Creates the message that should be signed with the current key.
+    ///
+    /// The message format is: `context || new_public_key || timestamp || phone_id`
+    pub fn signing_message(&self) -> Vec<u8> {
+        let mut message = Vec::new();
+        message.extend_from_slice(KEY_ROTATION_CONTEXT);
+        message.extend_from_slice(self.new_public_key.as_bytes());
+        message.extend_from_slice(&self.timestamp.to_le_bytes());
+        message.extend_from_slice(self.phone_id.as_bytes());
+        message
+    }
+
+    /// Creates a `cconnect.auth.keyrotation` packet carrying this announcement.
+    pub fn to_packet(&self) -> crate::Packet {
+        crate::Packet::new(
+            PACKET_TYPE_KEY_ROTATION,
+            serde_json::to_value(self).unwrap_or_default(),
+        )
+    }
+
+    /// Parses an announcement from a `cconnect.auth.keyrotation` packet.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AuthError::CryptoError` if the packet is not a well-formed
+    /// key rotation announcement.
+    pub fn from_packet(packet: &crate::Packet) -> Result<Self, AuthError> {
+        if !packet.is_type(PACKET_TYPE_KEY_ROTATION) {
+            return Err(AuthError::CryptoError(format!(
+                "Not a key rotation packet: {}",
+                packet.packet_type
+            )));
+        }
+
+        serde_json::from_value(packet.body.clone())
+            .map_err(|e| AuthError::CryptoError(format!("Invalid key rotation packet: {e}")))
+    }
+}
+
 /// Errors that can occur during authentication operations.
 #[derive(Debug, Error)]
 pub enum AuthError {
@@ -129,6 +278,22 @@ pub enum AuthError {
     /// The public key is invalid.
     #[error("Invalid public key: {0}")]
     InvalidPublicKey(String),
+
+    /// A key rotation announcement is too old (or from the future).
+    #[error("Key rotation announcement expired")]
+    RotationExpired,
+
+    /// The phone is not enrolled with this desktop.
+    #[error("Unknown phone: {0}")]
+    UnknownPhone(String),
+
+    /// The response arrived on a different connection than the challenge was bound to.
+    #[error("Channel binding mismatch")]
+    ChannelBindingMismatch,
+
+    /// A paired device presented a certificate other than the one it paired with.
+    #[error("Certificate does not match the one pinned for {0}")]
+    CertificateMismatch(String),
 }
 
 #[cfg(test)]
@@ -143,6 +308,7 @@ mod tests {
             nonce: base64::engine::general_purpose::STANDARD.encode([1u8; 16]),
             timestamp: 1234567890,
             desktop_id: "test-desktop".to_string(),
+            channel_binding: None,
         };
 
         let message = challenge.signing_message();
@@ -159,6 +325,7 @@ mod tests {
             nonce: base64::engine::general_purpose::STANDARD.encode([0u8; 16]),
             timestamp: 0,
             desktop_id: String::new(),
+            channel_binding: None,
         };
 
         let bytes = challenge.challenge_bytes().unwrap();
@@ -172,6 +339,7 @@ mod tests {
             nonce: String::new(),
             timestamp: 0,
             desktop_id: String::new(),
+            channel_binding: None,
         };
 
         let result = challenge.challenge_bytes();
@@ -198,6 +366,7 @@ mod tests {
             nonce: "bm9uY2U=".to_string(),
             timestamp: 1234567890,
             desktop_id: "desktop-1".to_string(),
+            channel_binding: None,
         };
 
         let json = serde_json::to_string(&challenge).unwrap();
@@ -217,4 +386,77 @@ mod tests {
         let parsed: ChallengeResponse = serde_json::from_str(&json).unwrap();
         assert_eq!(response, parsed);
     }
+
+    #[test]
+    fn test_key_rotation_packet_roundtrip() {
+        let rotation = KeyRotation {
+            phone_id: "phone-1".to_string(),
+            new_public_key: base64::engine::general_purpose::STANDARD.encode([7u8; 32]),
+            timestamp: 1234567890,
+            signature: "c2lnbmF0dXJl".to_string(),
+        };
+
+        let packet = rotation.to_packet();
+        assert_eq!(packet.packet_type, PACKET_TYPE_KEY_ROTATION);
+        assert_eq!(KeyRotation::from_packet(&packet).unwrap(), rotation);
+        assert_eq!(rotation.new_public_key_bytes().unwrap(), [7u8; 32]);
+
+        let other = crate::Packet::new("cconnect.ping", serde_json::json!({}));
+        assert!(matches!(
+            KeyRotation::from_packet(&other),
+            Err(AuthError::CryptoError(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_rotation_signing_message_differs_from_challenge() {
+        let rotation = KeyRotation {
+            phone_id: "desktop-1".to_string(),
+            new_public_key: "dGVzdA==".to_string(),
+            timestamp: 1234567890,
+            signature: String::new(),
+        };
+        let challenge = Challenge {
+            challenge: "dGVzdA==".to_string(),
+            nonce: String::new(),
+            timestamp: 1234567890,
+            desktop_id: "desktop-1".to_string(),
+            channel_binding: None,
+        };
+
+        assert_ne!(rotation.signing_message(), challenge.signing_message());
+    }
+
+    #[test]
+    fn test_channel_binding_in_signing_message() {
+        let challenge = Challenge {
+            challenge: "dGVzdA==".to_string(),
+            nonce: "bm9uY2U=".to_string(),
+            timestamp: 1234567890,
+            desktop_id: "desktop-1".to_string(),
+            channel_binding: None,
+        };
+        let bound = challenge
+            .clone()
+            .with_channel_binding(&[1u8; CHANNEL_BINDING_SIZE]);
+        let other = challenge
+            .clone()
+            .with_channel_binding(&[2u8; CHANNEL_BINDING_SIZE]);
+
+        assert!(bound.is_bound_to(&[1u8; CHANNEL_BINDING_SIZE]));
+        assert!(!bound.is_bound_to(&[2u8; CHANNEL_BINDING_SIZE]));
+        assert!(!challenge.is_bound_to(&[1u8; CHANNEL_BINDING_SIZE]));
+        assert_ne!(bound.signing_message(), challenge.signing_message());
+        assert_ne!(bound.signing_message(), other.signing_message());
+
+        // The binding never goes over the wire...
+        let json = serde_json::to_value(&bound).unwrap();
+        assert!(json.get("channel_binding").is_none());
+        let parsed: Challenge = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, challenge);
+
+        // ...the receiving side binds it to its own session instead
+        let rebound = parsed.with_channel_binding(&[1u8; CHANNEL_BINDING_SIZE]);
+        assert_eq!(rebound.signing_message(), bound.signing_message());
+    }
 }
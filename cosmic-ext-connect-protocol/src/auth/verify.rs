@@ -2,11 +2,23 @@
 //!
 //! This module provides the `Verifier` type for verifying Ed25519 signatures
 //! from phones during the authentication challenge-response protocol.
+//!
+//! # Key Rotation
+//!
+//! A phone that switches keys announces the new key in a [`KeyRotation`]
+//! signed with its current key. Once [`Verifier::accept_rotation`] accepts it,
+//! the new key becomes current and the old one keeps verifying responses for
+//! an overlap window, so devices mid-handshake aren't locked out. After the
+//! deadline only the new key is accepted.
 
 use base64::Engine;
 use ring::signature::{self, UnparsedPublicKey};
 
-use super::types::{AuthError, Challenge, ChallengeResponse};
+use super::challenge::current_unix_timestamp;
+use super::types::{
+    AuthError, Challenge, ChallengeResponse, KeyRotation, KEY_ROTATION_MAX_AGE_SECS,
+    KEY_ROTATION_OVERLAP_SECS,
+};
 
 /// Ed25519 public key size in bytes.
 pub const ED25519_PUBLIC_KEY_SIZE: usize = 32;
@@ -18,6 +30,7 @@ pub const ED25519_SIGNATURE_SIZE: usize = 64;
 ///
 /// The `Verifier` holds a phone's public key and can verify that
 /// challenge responses were signed by the corresponding private key.
+/// During a key rotation it also holds the previous key until its deadline.
 ///
 /// # Security Properties
 ///
@@ -27,6 +40,8 @@ pub const ED25519_SIGNATURE_SIZE: usize = 64;
 pub struct Verifier {
     /// The raw Ed25519 public key bytes.
     public_key_bytes: Vec<u8>,
+    /// The key being rotated out and the Unix timestamp it is accepted until.
+    previous_key: Option<(Vec<u8>, u64)>,
 }
 
 impl Verifier {
@@ -40,19 +55,34 @@ impl Verifier {
     ///
     /// Returns `AuthError::InvalidPublicKey` if the key is not exactly 32 bytes.
     pub fn new(public_key: Vec<u8>) -> Result<Self, AuthError> {
-        if public_key.len() != ED25519_PUBLIC_KEY_SIZE {
-            return Err(AuthError::InvalidPublicKey(format!(
-                "Expected {} bytes, got {}",
-                ED25519_PUBLIC_KEY_SIZE,
-                public_key.len()
-            )));
-        }
+        validate_public_key(&public_key)?;
 
         Ok(Self {
             public_key_bytes: public_key,
+            previous_key: None,
         })
     }
 
+    /// Creates a new `Verifier` in the middle of a key rotation.
+    ///
+    /// Responses signed with either key verify until `rotate_deadline`
+    /// (a Unix timestamp); after that only `current` is accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AuthError::InvalidPublicKey` if either key is not exactly 32 bytes.
+    pub fn with_keys(
+        current: Vec<u8>,
+        previous: Vec<u8>,
+        rotate_deadline: u64,
+    ) -> Result<Self, AuthError> {
+        validate_public_key(&previous)?;
+
+        let mut verifier = Self::new(current)?;
+        verifier.previous_key = Some((previous, rotate_deadline));
+        Ok(verifier)
+    }
+
     /// Creates a new `Verifier` from a base64-encoded public key.
     ///
     /// # Arguments
@@ -97,26 +127,110 @@ impl Verifier {
         &self,
         challenge: &Challenge,
         response: &ChallengeResponse,
+    ) -> Result<(), AuthError> {
+        self.verify_response_at(challenge, response, current_unix_timestamp())
+    }
+
+    /// Verifies a challenge response as of `now` (a Unix timestamp).
+    fn verify_response_at(
+        &self,
+        challenge: &Challenge,
+        response: &ChallengeResponse,
+        now: u64,
     ) -> Result<(), AuthError> {
         // Decode the signature
         let signature_bytes = response.signature_bytes()?;
 
-        // Validate signature length
-        if signature_bytes.len() != ED25519_SIGNATURE_SIZE {
-            return Err(AuthError::InvalidSignature);
-        }
-
         // Reconstruct the message that was signed
         let message = challenge.signing_message();
 
-        // Create the public key wrapper for verification
-        let public_key =
-            UnparsedPublicKey::new(&signature::ED25519, self.public_key_bytes.as_slice());
+        // Try the current key, then the previous one while it is still valid
+        verify_signature(&self.public_key_bytes, &message, &signature_bytes).or_else(|err| {
+            match self.previous_key_at(now) {
+                Some(previous) => verify_signature(previous, &message, &signature_bytes),
+                None => Err(err),
+            }
+        })
+    }
+
+    /// Switches to a new key, accepting the current one until `rotate_deadline`.
+    ///
+    /// This trusts `new_public_key` unconditionally; keys announced by the
+    /// phone should go through [`Self::accept_rotation`] instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AuthError::InvalidPublicKey` if the key is not exactly 32 bytes.
+    pub fn rotate_key(
+        &mut self,
+        new_public_key: Vec<u8>,
+        rotate_deadline: u64,
+    ) -> Result<(), AuthError> {
+        validate_public_key(&new_public_key)?;
+
+        let previous = std::mem::replace(&mut self.public_key_bytes, new_public_key);
+        self.previous_key = Some((previous, rotate_deadline));
+        Ok(())
+    }
+
+    /// Accepts a key rotation announced by the phone.
+    ///
+    /// The announcement must be signed with the current key (not the previous
+    /// one) and be at most [`KEY_ROTATION_MAX_AGE_SECS`] old. The current key
+    /// stays valid for [`KEY_ROTATION_OVERLAP_SECS`] afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AuthError::InvalidSignature` if the announcement was not signed
+    /// by the current key.
+    /// Returns `AuthError::RotationExpired` if the announcement is stale.
+    /// Returns `AuthError::InvalidPublicKey` if the new key is malformed or
+    /// is already the current key.
+    pub fn accept_rotation(&mut self, rotation: &KeyRotation) -> Result<(), AuthError> {
+        self.accept_rotation_at(rotation, current_unix_timestamp())
+    }
+
+    /// Accepts a key rotation as of `now` (a Unix timestamp).
+    fn accept_rotation_at(&mut self, rotation: &KeyRotation, now: u64) -> Result<(), AuthError> {
+        let signature_bytes = rotation.signature_bytes()?;
+        verify_signature(
+            &self.public_key_bytes,
+            &rotation.signing_message(),
+            &signature_bytes,
+        )?;
+
+        if now.abs_diff(rotation.timestamp) > KEY_ROTATION_MAX_AGE_SECS {
+            return Err(AuthError::RotationExpired);
+        }
+
+        let new_public_key = rotation.new_public_key_bytes()?;
+        if new_public_key == self.public_key_bytes {
+            return Err(AuthError::InvalidPublicKey(
+                "New key is already the current key".to_string(),
+            ));
+        }
+
+        self.rotate_key(new_public_key, now + KEY_ROTATION_OVERLAP_SECS)
+    }
+
+    /// Drops the previous key once its deadline has passed.
+    pub fn prune_previous_key(&mut self) {
+        self.prune_previous_key_at(current_unix_timestamp());
+    }
+
+    /// Drops the previous key if its deadline has passed as of `now`.
+    fn prune_previous_key_at(&mut self, now: u64) {
+        if self.previous_key_at(now).is_none() {
+            self.previous_key = None;
+        }
+    }
 
-        // Verify the signature (constant-time)
-        public_key
-            .verify(&message, &signature_bytes)
-            .map_err(|_| AuthError::InvalidSignature)
+    /// Returns the previous key if it is still accepted at `now`.
+    fn previous_key_at(&self, now: u64) -> Option<&[u8]> {
+        self.previous_key
+            .as_ref()
+            .filter(|(_, deadline)| now < *deadline)
+            .map(|(key, _)| key.as_slice())
     }
 
     /// Returns the raw public key bytes.
@@ -130,6 +244,38 @@ impl Verifier {
     pub fn public_key_base64(&self) -> String {
         base64::engine::general_purpose::STANDARD.encode(&self.public_key_bytes)
     }
+
+    /// Returns the previous key and its deadline, if a rotation is in progress.
+    #[must_use]
+    pub fn previous_key(&self) -> Option<(&[u8], u64)> {
+        self.previous_key
+            .as_ref()
+            .map(|(key, deadline)| (key.as_slice(), *deadline))
+    }
+}
+
+/// Checks that a public key has the Ed25519 key size.
+fn validate_public_key(public_key: &[u8]) -> Result<(), AuthError> {
+    if public_key.len() != ED25519_PUBLIC_KEY_SIZE {
+        return Err(AuthError::InvalidPublicKey(format!(
+            "Expected {} bytes, got {}",
+            ED25519_PUBLIC_KEY_SIZE,
+            public_key.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Verifies an Ed25519 signature over `message` (constant-time).
+fn verify_signature(public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<(), AuthError> {
+    // Validate signature length
+    if signature.len() != ED25519_SIGNATURE_SIZE {
+        return Err(AuthError::InvalidSignature);
+    }
+
+    UnparsedPublicKey::new(&signature::ED25519, public_key)
+        .verify(message, signature)
+        .map_err(|_| AuthError::InvalidSignature)
 }
 
 #[cfg(test)]
@@ -147,12 +293,40 @@ mod tests {
         (keypair, public_key)
     }
 
+    /// Helper to sign a challenge and build the response.
+    fn sign_response(keypair: &Ed25519KeyPair, challenge: &Challenge) -> ChallengeResponse {
+        let signature = keypair.sign(&challenge.signing_message());
+        ChallengeResponse {
+            nonce: challenge.nonce.clone(),
+            signature: base64::engine::general_purpose::STANDARD.encode(signature.as_ref()),
+            phone_id: "test-phone".to_string(),
+        }
+    }
+
+    /// Helper to build a rotation announcement signed by `signer`.
+    fn sign_rotation(
+        signer: &Ed25519KeyPair,
+        new_public_key: &[u8],
+        timestamp: u64,
+    ) -> KeyRotation {
+        let mut rotation = KeyRotation {
+            phone_id: "test-phone".to_string(),
+            new_public_key: base64::engine::general_purpose::STANDARD.encode(new_public_key),
+            timestamp,
+            signature: String::new(),
+        };
+        let signature = signer.sign(&rotation.signing_message());
+        rotation.signature = base64::engine::general_purpose::STANDARD.encode(signature.as_ref());
+        rotation
+    }
+
     fn create_test_challenge() -> Challenge {
         Challenge {
             challenge: base64::engine::general_purpose::STANDARD.encode([42u8; 32]),
             nonce: base64::engine::general_purpose::STANDARD.encode([1u8; 16]),
             timestamp: 1234567890,
             desktop_id: "test-desktop".to_string(),
+            channel_binding: None,
         }
     }
 
@@ -280,6 +454,7 @@ mod tests {
             nonce: original_challenge.nonce.clone(),
             timestamp: original_challenge.timestamp,
             desktop_id: original_challenge.desktop_id.clone(),
+            channel_binding: None,
         };
 
         let response = ChallengeResponse {
@@ -324,4 +499,154 @@ mod tests {
             .unwrap();
         assert_eq!(decoded, public_key);
     }
+
+    #[test]
+    fn test_with_keys_accepts_previous_until_deadline() {
+        let (current, current_key) = generate_test_keypair();
+        let (previous, previous_key) = generate_test_keypair();
+        let (stranger, _) = generate_test_keypair();
+        let verifier = Verifier::with_keys(current_key, previous_key, 1000).unwrap();
+
+        let challenge = create_test_challenge();
+        let from_current = sign_response(&current, &challenge);
+        let from_previous = sign_response(&previous, &challenge);
+
+        assert!(verifier
+            .verify_response_at(&challenge, &from_current, 999)
+            .is_ok());
+        assert!(verifier
+            .verify_response_at(&challenge, &from_previous, 999)
+            .is_ok());
+
+        // The deadline is enforced
+        assert!(verifier
+            .verify_response_at(&challenge, &from_current, 1000)
+            .is_ok());
+        assert!(matches!(
+            verifier.verify_response_at(&challenge, &from_previous, 1000),
+            Err(AuthError::InvalidSignature)
+        ));
+
+        assert!(matches!(
+            verifier.verify_response_at(&challenge, &sign_response(&stranger, &challenge), 999),
+            Err(AuthError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_with_keys_invalid_previous_key() {
+        let (_, current_key) = generate_test_keypair();
+        let result = Verifier::with_keys(current_key, vec![0u8; 16], 1000);
+        assert!(matches!(result, Err(AuthError::InvalidPublicKey(_))));
+    }
+
+    #[test]
+    fn test_accept_rotation() {
+        let (old, old_key) = generate_test_keypair();
+        let (new, new_key) = generate_test_keypair();
+        let mut verifier = Verifier::new(old_key.clone()).unwrap();
+
+        let now = 1_700_000_000;
+        let rotation = sign_rotation(&old, &new_key, now);
+        verifier.accept_rotation_at(&rotation, now).unwrap();
+
+        assert_eq!(verifier.public_key_bytes(), new_key.as_slice());
+        let deadline = now + KEY_ROTATION_OVERLAP_SECS;
+        assert_eq!(
+            verifier.previous_key(),
+            Some((old_key.as_slice(), deadline))
+        );
+
+        // Both keys verify during the overlap, only the new one afterwards
+        let challenge = create_test_challenge();
+        let from_old = sign_response(&old, &challenge);
+        let from_new = sign_response(&new, &challenge);
+        assert!(verifier
+            .verify_response_at(&challenge, &from_old, now)
+            .is_ok());
+        assert!(verifier
+            .verify_response_at(&challenge, &from_new, now)
+            .is_ok());
+        assert!(verifier
+            .verify_response_at(&challenge, &from_old, deadline)
+            .is_err());
+        assert!(verifier
+            .verify_response_at(&challenge, &from_new, deadline)
+            .is_ok());
+
+        verifier.prune_previous_key_at(deadline - 1);
+        assert!(verifier.previous_key().is_some());
+        verifier.prune_previous_key_at(deadline);
+        assert!(verifier.previous_key().is_none());
+    }
+
+    #[test]
+    fn test_rotation_signed_by_unknown_key_rejected() {
+        let (_, old_key) = generate_test_keypair();
+        let (attacker, attacker_key) = generate_test_keypair();
+        let mut verifier = Verifier::new(old_key.clone()).unwrap();
+
+        let now = 1_700_000_000;
+        let rotation = sign_rotation(&attacker, &attacker_key, now);
+
+        assert!(matches!(
+            verifier.accept_rotation_at(&rotation, now),
+            Err(AuthError::InvalidSignature)
+        ));
+        assert_eq!(verifier.public_key_bytes(), old_key.as_slice());
+        assert!(verifier.previous_key().is_none());
+    }
+
+    #[test]
+    fn test_rotation_signed_by_previous_key_rejected() {
+        let (first, first_key) = generate_test_keypair();
+        let (_, second_key) = generate_test_keypair();
+        let (_, third_key) = generate_test_keypair();
+        let mut verifier = Verifier::new(first_key).unwrap();
+
+        let now = 1_700_000_000;
+        verifier
+            .accept_rotation_at(&sign_rotation(&first, &second_key, now), now)
+            .unwrap();
+
+        // The rotated-out key is still accepted for responses, but can't rotate again
+        let rotation = sign_rotation(&first, &third_key, now);
+        assert!(matches!(
+            verifier.accept_rotation_at(&rotation, now),
+            Err(AuthError::InvalidSignature)
+        ));
+        assert_eq!(verifier.public_key_bytes(), second_key.as_slice());
+    }
+
+    #[test]
+    fn test_rotation_tampered_or_stale_rejected() {
+        let (old, old_key) = generate_test_keypair();
+        let (_, new_key) = generate_test_keypair();
+        let (_, other_key) = generate_test_keypair();
+        let mut verifier = Verifier::new(old_key.clone()).unwrap();
+
+        let now = 1_700_000_000;
+
+        // Swapping the announced key invalidates the signature
+        let mut tampered = sign_rotation(&old, &new_key, now);
+        tampered.new_public_key = base64::engine::general_purpose::STANDARD.encode(&other_key);
+        assert!(matches!(
+            verifier.accept_rotation_at(&tampered, now),
+            Err(AuthError::InvalidSignature)
+        ));
+
+        let stale = sign_rotation(&old, &new_key, now - KEY_ROTATION_MAX_AGE_SECS - 1);
+        assert!(matches!(
+            verifier.accept_rotation_at(&stale, now),
+            Err(AuthError::RotationExpired)
+        ));
+
+        let same_key = sign_rotation(&old, &old_key, now);
+        assert!(matches!(
+            verifier.accept_rotation_at(&same_key, now),
+            Err(AuthError::InvalidPublicKey(_))
+        ));
+
+        assert_eq!(verifier.public_key_bytes(), old_key.as_slice());
+    }
 }
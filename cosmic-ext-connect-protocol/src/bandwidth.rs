@@ -0,0 +1,177 @@
+//! Bandwidth Limiting for Payload Transfers
+//!
+//! A token bucket refilled at the configured rate. Senders call
+//! [`BandwidthLimiter::consume`] before writing each chunk and are held back
+//! once the bucket runs dry. Clones share one bucket, so all transfers using
+//! clones of a limiter together stay within its rate.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Share of a second's worth of bytes that may be sent in one burst
+const BURST_FRACTION: f64 = 0.1;
+
+#[derive(Debug)]
+struct Bucket {
+    /// Bytes that may be sent right away; negative while senders are waiting
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+#[derive(Debug)]
+struct Shared {
+    bytes_per_sec: f64,
+    capacity: f64,
+    bucket: Mutex<Bucket>,
+}
+
+/// Token-bucket rate limiter shared by concurrent transfers
+///
+/// A limit of 0 KB/s means unlimited: [`consume`](Self::consume) returns
+/// immediately without touching any shared state.
+#[derive(Debug, Clone)]
+pub struct BandwidthLimiter {
+    limit_kbps: u32,
+    shared: Option<Arc<Shared>>,
+}
+
+impl BandwidthLimiter {
+    /// Create a limiter for `limit_kbps` KB/s, or an unlimited one for 0
+    pub fn new(limit_kbps: u32) -> Self {
+        if limit_kbps == 0 {
+            return Self::unlimited();
+        }
+
+        let bytes_per_sec = limit_kbps as f64 * 1024.0;
+        let capacity = bytes_per_sec * BURST_FRACTION;
+        Self {
+            limit_kbps,
+            shared: Some(Arc::new(Shared {
+                bytes_per_sec,
+                capacity,
+                bucket: Mutex::new(Bucket {
+                    tokens: capacity,
+                    refilled_at: Instant::now(),
+                }),
+            })),
+        }
+    }
+
+    /// A limiter that never waits
+    pub fn unlimited() -> Self {
+        Self {
+            limit_kbps: 0,
+            shared: None,
+        }
+    }
+
+    /// Configured limit in KB/s, 0 if unlimited
+    pub fn limit_kbps(&self) -> u32 {
+        self.limit_kbps
+    }
+
+    /// Whether this limiter never waits
+    pub fn is_unlimited(&self) -> bool {
+        self.shared.is_none()
+    }
+
+    /// Take `bytes` from the bucket, waiting until the rate allows them
+    ///
+    /// Chunks larger than the bucket are allowed; the sender then waits for
+    /// the whole chunk to be paid off at the configured rate.
+    pub async fn consume(&self, bytes: usize) {
+        let Some(shared) = &self.shared else {
+            return;
+        };
+
+        let wait = {
+            let mut bucket = shared
+                .bucket
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * shared.bytes_per_sec).min(shared.capacity);
+            bucket.refilled_at = now;
+            bucket.tokens -= bytes as f64;
+
+            if bucket.tokens < 0.0 {
+                Duration::from_secs_f64(-bucket.tokens / shared.bytes_per_sec)
+            } else {
+                Duration::ZERO
+            }
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl Default for BandwidthLimiter {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK: usize = 64 * 1024;
+
+    #[tokio::test]
+    async fn test_rate_enforced() {
+        let limiter = BandwidthLimiter::new(256);
+        let start = std::time::Instant::now();
+
+        for _ in 0..(1024 * 1024 / CHUNK) {
+            limiter.consume(CHUNK).await;
+        }
+
+        let elapsed = start.elapsed().as_secs_f64();
+        assert!(
+            (3.5..4.5).contains(&elapsed),
+            "1MB at 256 KB/s took {:.2}s",
+            elapsed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clones_share_bucket() {
+        let limiter = BandwidthLimiter::new(512);
+        let start = std::time::Instant::now();
+
+        let transfers: Vec<_> = (0..2)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move {
+                    for _ in 0..(512 * 1024 / CHUNK) {
+                        limiter.consume(CHUNK).await;
+                    }
+                })
+            })
+            .collect();
+        for transfer in transfers {
+            transfer.await.unwrap();
+        }
+
+        let elapsed = start.elapsed().as_secs_f64();
+        assert!(
+            (1.7..2.5).contains(&elapsed),
+            "2 x 512KB at 512 KB/s took {:.2}s",
+            elapsed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zero_is_unlimited() {
+        let limiter = BandwidthLimiter::new(0);
+        assert!(limiter.is_unlimited());
+
+        let start = std::time::Instant::now();
+        limiter.consume(100 * 1024 * 1024).await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+}
@@ -261,7 +261,7 @@ impl BluetoothConnectionManager {
         let connection = BluetoothConnection::connect(bt_address.to_string(), channel)
             .await
             .map_err(|e| {
-                ProtocolError::Transport(format!(
+                ProtocolError::transport(format!(
                     "Failed to connect to Bluetooth device {}: {}",
                     bt_address, e
                 ))
@@ -0,0 +1,322 @@
+//! Capability Diff
+//!
+//! Compares the capability lists exchanged in identity packets to show which
+//! features work in each direction between this desktop and a peer.
+//!
+//! A packet type flows from A to B when it is in A's outgoing capabilities and
+//! in B's incoming capabilities. Capabilities are compared without their
+//! `cconnect.` / `kdeconnect.` prefix, since peers may use either namespace for
+//! the same packet type.
+//!
+//! A plugin matches a peer when the peer advertises a capability the plugin
+//! consumes or produces (see [`PluginNegotiation`]). [`CapabilityOverrides`]
+//! let the user force a capability on or off for one device, to work around
+//! peers that advertise a feature that is broken or omit one they actually
+//! support.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Strip the protocol namespace from a capability
+///
+/// `cconnect.battery` and `kdeconnect.battery` both become `battery`.
+pub fn normalize_capability(capability: &str) -> &str {
+    capability
+        .strip_prefix("cconnect.")
+        .or_else(|| capability.strip_prefix("kdeconnect."))
+        .unwrap_or(capability)
+}
+
+fn normalized_set(capabilities: &[String]) -> BTreeSet<&str> {
+    capabilities
+        .iter()
+        .map(|c| normalize_capability(c))
+        .collect()
+}
+
+fn to_strings<'a>(capabilities: impl Iterator<Item = &'a &'a str>) -> Vec<String> {
+    capabilities.map(|c| c.to_string()).collect()
+}
+
+/// Difference between our capabilities and a peer's
+///
+/// All lists hold normalized capability names and are sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityDiff {
+    /// Packet types we send that the peer can consume
+    pub peer_consumes: Vec<String>,
+
+    /// Packet types the peer sends that we can consume
+    pub we_consume: Vec<String>,
+
+    /// Packet types we send that the peer cannot consume
+    pub peer_missing: Vec<String>,
+
+    /// Packet types the peer sends that we cannot consume
+    pub we_missing: Vec<String>,
+}
+
+impl CapabilityDiff {
+    /// Compute the diff from both sides' capability lists
+    pub fn compute(
+        our_incoming: &[String],
+        our_outgoing: &[String],
+        peer_incoming: &[String],
+        peer_outgoing: &[String],
+    ) -> Self {
+        let our_incoming = normalized_set(our_incoming);
+        let our_outgoing = normalized_set(our_outgoing);
+        let peer_incoming = normalized_set(peer_incoming);
+        let peer_outgoing = normalized_set(peer_outgoing);
+
+        Self {
+            peer_consumes: to_strings(our_outgoing.intersection(&peer_incoming)),
+            we_consume: to_strings(peer_outgoing.intersection(&our_incoming)),
+            peer_missing: to_strings(our_outgoing.difference(&peer_incoming)),
+            we_missing: to_strings(peer_outgoing.difference(&our_incoming)),
+        }
+    }
+
+    /// Whether both sides can consume everything the other sends
+    pub fn is_symmetric(&self) -> bool {
+        self.peer_missing.is_empty() && self.we_missing.is_empty()
+    }
+}
+
+impl fmt::Display for CapabilityDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "peer consumes {} of ours, we consume {} of theirs",
+            self.peer_consumes.len(),
+            self.we_consume.len()
+        )?;
+        if !self.peer_missing.is_empty() {
+            write!(
+                f,
+                "; peer cannot consume [{}]",
+                self.peer_missing.join(", ")
+            )?;
+        }
+        if !self.we_missing.is_empty() {
+            write!(f, "; we cannot consume [{}]", self.we_missing.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// User overrides applied on top of a peer's advertised capabilities
+///
+/// Capabilities may be given with or without their namespace prefix. When a
+/// capability is in both lists, disabling wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityOverrides {
+    /// Capabilities treated as advertised even if the peer omits them
+    #[serde(default)]
+    pub force_enable: Vec<String>,
+
+    /// Capabilities treated as absent even if the peer advertises them
+    #[serde(default)]
+    pub force_disable: Vec<String>,
+}
+
+impl CapabilityOverrides {
+    /// Whether no overrides are set
+    pub fn is_empty(&self) -> bool {
+        self.force_enable.is_empty() && self.force_disable.is_empty()
+    }
+
+    fn first_match<'a>(list: &[String], capabilities: &[&'a str]) -> Option<&'a str> {
+        let list = normalized_set(list);
+        capabilities.iter().copied().find(|c| list.contains(c))
+    }
+}
+
+/// Outcome of negotiating a plugin with a peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginNegotiation {
+    /// The peer advertises a capability of the plugin
+    Negotiated,
+    /// The peer advertises none of the plugin's capabilities
+    NotAdvertised,
+    /// Loaded only because the user force-enabled this capability
+    ForceEnabled(String),
+    /// Not loaded because the user force-disabled this capability
+    ForceDisabled(String),
+}
+
+impl PluginNegotiation {
+    /// Negotiate a plugin from both sides' capabilities and the user's overrides
+    ///
+    /// Plugins without capabilities, and peers that advertised no capabilities
+    /// at all, always negotiate. The override variants are only returned when
+    /// the override changes the outcome.
+    pub fn negotiate(
+        plugin_incoming: &[String],
+        plugin_outgoing: &[String],
+        peer_incoming: &[String],
+        peer_outgoing: &[String],
+        overrides: &CapabilityOverrides,
+    ) -> Self {
+        let plugin_incoming = normalized_set(plugin_incoming);
+        let plugin_outgoing = normalized_set(plugin_outgoing);
+        let plugin_capabilities: Vec<&str> =
+            plugin_incoming.union(&plugin_outgoing).copied().collect();
+
+        let advertised = plugin_capabilities.is_empty()
+            || (peer_incoming.is_empty() && peer_outgoing.is_empty())
+            || !plugin_incoming.is_disjoint(&normalized_set(peer_outgoing))
+            || !plugin_outgoing.is_disjoint(&normalized_set(peer_incoming));
+
+        let disabled =
+            CapabilityOverrides::first_match(&overrides.force_disable, &plugin_capabilities);
+        let enabled =
+            CapabilityOverrides::first_match(&overrides.force_enable, &plugin_capabilities);
+
+        match (advertised, disabled, enabled) {
+            (true, Some(capability), _) => Self::ForceDisabled(capability.to_string()),
+            (true, None, _) => Self::Negotiated,
+            (false, None, Some(capability)) => Self::ForceEnabled(capability.to_string()),
+            (false, _, _) => Self::NotAdvertised,
+        }
+    }
+
+    /// Whether the plugin should be loaded
+    pub fn is_active(&self) -> bool {
+        matches!(self, Self::Negotiated | Self::ForceEnabled(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(list: &[&str]) -> Vec<String> {
+        list.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_capability() {
+        assert_eq!(normalize_capability("cconnect.battery"), "battery");
+        assert_eq!(normalize_capability("kdeconnect.battery"), "battery");
+        assert_eq!(normalize_capability("custom.thing"), "custom.thing");
+    }
+
+    #[test]
+    fn test_asymmetric_capabilities() {
+        let diff = CapabilityDiff::compute(
+            &caps(&[
+                "cconnect.ping",
+                "cconnect.battery",
+                "cconnect.share.request",
+            ]),
+            &caps(&["cconnect.ping", "cconnect.clipboard", "cconnect.mpris"]),
+            &caps(&["kdeconnect.ping", "kdeconnect.clipboard"]),
+            &caps(&[
+                "kdeconnect.ping",
+                "kdeconnect.battery",
+                "kdeconnect.telephony",
+            ]),
+        );
+
+        assert_eq!(diff.peer_consumes, caps(&["clipboard", "ping"]));
+        assert_eq!(diff.we_consume, caps(&["battery", "ping"]));
+        assert_eq!(diff.peer_missing, caps(&["mpris"]));
+        assert_eq!(diff.we_missing, caps(&["telephony"]));
+        assert!(!diff.is_symmetric());
+
+        let summary = diff.to_string();
+        assert!(summary.contains("peer cannot consume [mpris]"));
+        assert!(summary.contains("we cannot consume [telephony]"));
+    }
+
+    #[test]
+    fn test_symmetric_capabilities() {
+        let ours = caps(&["cconnect.ping"]);
+        let diff = CapabilityDiff::compute(&ours, &ours, &ours, &ours);
+
+        assert_eq!(diff.peer_consumes, caps(&["ping"]));
+        assert_eq!(diff.we_consume, caps(&["ping"]));
+        assert!(diff.is_symmetric());
+        assert_eq!(
+            diff.to_string(),
+            "peer consumes 1 of ours, we consume 1 of theirs"
+        );
+    }
+
+    #[test]
+    fn test_peer_without_capabilities() {
+        let diff = CapabilityDiff::compute(
+            &caps(&["cconnect.ping"]),
+            &caps(&["cconnect.ping"]),
+            &[],
+            &[],
+        );
+
+        assert!(diff.peer_consumes.is_empty());
+        assert!(diff.we_consume.is_empty());
+        assert_eq!(diff.peer_missing, caps(&["ping"]));
+        assert!(diff.we_missing.is_empty());
+    }
+
+    #[test]
+    fn test_plugin_negotiation() {
+        let none = CapabilityOverrides::default();
+
+        assert_eq!(
+            PluginNegotiation::negotiate(
+                &caps(&["cconnect.battery"]),
+                &caps(&["cconnect.battery.request"]),
+                &caps(&["kdeconnect.battery.request"]),
+                &caps(&["kdeconnect.battery"]),
+                &none,
+            ),
+            PluginNegotiation::Negotiated
+        );
+        assert_eq!(
+            PluginNegotiation::negotiate(
+                &caps(&["cconnect.mpris"]),
+                &caps(&["cconnect.mpris"]),
+                &caps(&["kdeconnect.ping"]),
+                &caps(&["kdeconnect.ping"]),
+                &none,
+            ),
+            PluginNegotiation::NotAdvertised
+        );
+
+        // Peers without a capability list get every plugin
+        assert!(
+            PluginNegotiation::negotiate(&caps(&["cconnect.mpris"]), &[], &[], &[], &none)
+                .is_active()
+        );
+    }
+
+    #[test]
+    fn test_overrides_change_negotiation() {
+        let overrides = CapabilityOverrides {
+            force_enable: caps(&["mpris", "cconnect.ping"]),
+            force_disable: caps(&["kdeconnect.clipboard", "ping"]),
+        };
+        let peer = caps(&["cconnect.clipboard"]);
+
+        let clipboard = PluginNegotiation::negotiate(&peer, &peer, &peer, &peer, &overrides);
+        assert_eq!(
+            clipboard,
+            PluginNegotiation::ForceDisabled("clipboard".to_string())
+        );
+        assert!(!clipboard.is_active());
+
+        let mpris =
+            PluginNegotiation::negotiate(&caps(&["cconnect.mpris"]), &[], &peer, &peer, &overrides);
+        assert_eq!(mpris, PluginNegotiation::ForceEnabled("mpris".to_string()));
+        assert!(mpris.is_active());
+
+        // Disabling wins over enabling
+        let ping = caps(&["cconnect.ping"]);
+        assert_eq!(
+            PluginNegotiation::negotiate(&ping, &ping, &peer, &peer, &overrides),
+            PluginNegotiation::NotAdvertised
+        );
+    }
+}
@@ -0,0 +1,581 @@
+//! Packet Compression
+//!
+//! Large JSON bodies, such as system monitor process lists or file sync
+//! indexes, can be compressed per packet. A device advertises the algorithms
+//! it can decode in its identity packet:
+//!
+//! ```json
+//! { "type": "cconnect.identity", "body": { ..., "supportsCompression": ["zstd", "gzip"] } }
+//! ```
+//!
+//! The sender picks the first of its own algorithms the peer listed and
+//! compresses bodies whose serialized size reaches the configured threshold.
+//! Peers that don't advertise the field (e.g. KDE Connect) always get plain
+//! packets.
+//!
+//! ## Wire Format
+//!
+//! A compressed packet keeps its `id`, `type` and payload fields; only the
+//! body is replaced:
+//!
+//! ```json
+//! { "type": "cconnect.systemmonitor.processes", "body": { "compression": "zstd", "compressedBody": "<base64>" } }
+//! ```
+//!
+//! The receiver restores the original body before the packet reaches any
+//! plugin. Only connections that negotiated compression decompress, and the
+//! restored body may be no larger than the connection's packet size limit.
+
+use crate::transport::DEFAULT_MAX_PACKET_SIZE;
+use crate::{Packet, ProtocolError, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+use std::fmt;
+use std::io::{Read, Write};
+
+/// Identity packet field listing the algorithms a device can decode
+pub const IDENTITY_FIELD: &str = "supportsCompression";
+
+/// Default body size (bytes of JSON) from which packets are compressed
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 16 * 1024;
+
+/// Body field naming the algorithm of a compressed packet
+const ALGORITHM_FIELD: &str = "compression";
+
+/// Body field holding the base64-encoded compressed body
+const DATA_FIELD: &str = "compressedBody";
+
+/// zstd level, favouring speed over ratio
+const ZSTD_LEVEL: i32 = 3;
+
+/// Compression algorithm for packet bodies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompressionAlgorithm {
+    /// Zstandard
+    Zstd,
+    /// gzip (deflate)
+    Gzip,
+}
+
+/// Algorithms we can encode and decode, in order of preference
+pub const SUPPORTED_ALGORITHMS: &[CompressionAlgorithm] =
+    &[CompressionAlgorithm::Zstd, CompressionAlgorithm::Gzip];
+
+impl CompressionAlgorithm {
+    /// Name used on the wire
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CompressionAlgorithm::Zstd => "zstd",
+            CompressionAlgorithm::Gzip => "gzip",
+        }
+    }
+
+    /// Parse a wire name, `None` if unknown
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "zstd" => Some(CompressionAlgorithm::Zstd),
+            "gzip" => Some(CompressionAlgorithm::Gzip),
+            _ => None,
+        }
+    }
+
+    /// Compress `data`
+    pub fn compress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            CompressionAlgorithm::Zstd => Ok(zstd::encode_all(data, ZSTD_LEVEL)?),
+            CompressionAlgorithm::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+        }
+    }
+
+    /// Decompress `data`, refusing output larger than `max_size`
+    pub fn decompress(self, data: &[u8], max_size: usize) -> Result<Vec<u8>> {
+        let reader: Box<dyn Read + '_> = match self {
+            CompressionAlgorithm::Zstd => Box::new(zstd::Decoder::new(data)?),
+            CompressionAlgorithm::Gzip => Box::new(flate2::read::GzDecoder::new(data)),
+        };
+
+        // Read one byte past the limit to tell "exactly max" from "too large"
+        let mut output = Vec::new();
+        reader
+            .take(max_size as u64 + 1)
+            .read_to_end(&mut output)
+            .map_err(|e| {
+                ProtocolError::MalformedFrame(format!("invalid {} data: {}", self.as_str(), e))
+            })?;
+        if output.len() > max_size {
+            return Err(ProtocolError::payload_too_large(output.len(), max_size));
+        }
+        Ok(output)
+    }
+}
+
+impl fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Compression settings for outgoing packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionConfig {
+    /// Advertise support and compress packets for peers that support it
+    pub enabled: bool,
+
+    /// Body size (bytes of JSON) from which packets are compressed
+    pub threshold: usize,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: DEFAULT_COMPRESSION_THRESHOLD,
+        }
+    }
+}
+
+impl CompressionConfig {
+    /// Add the supported algorithms to our identity packet
+    ///
+    /// Does nothing if compression is disabled.
+    pub fn advertise(&self, identity: &mut Packet) {
+        if !self.enabled {
+            return;
+        }
+        if let Value::Object(body) = &mut identity.body {
+            let names: Vec<&str> = SUPPORTED_ALGORITHMS.iter().map(|a| a.as_str()).collect();
+            body.insert(IDENTITY_FIELD.to_string(), json!(names));
+        }
+    }
+}
+
+/// Algorithms a peer advertised in its identity packet
+///
+/// Unknown names are skipped; a missing field means no support.
+pub fn peer_algorithms(identity: &Packet) -> Vec<CompressionAlgorithm> {
+    identity
+        .body
+        .get(IDENTITY_FIELD)
+        .and_then(|v| v.as_array())
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|name| name.as_str())
+                .filter_map(CompressionAlgorithm::from_name)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Per-connection packet compressor
+///
+/// Created once the peer's identity is known. Compresses outgoing packets
+/// with the negotiated algorithm and decompresses incoming ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketCompressor {
+    /// Algorithm used for outgoing packets, `None` to send plain packets
+    algorithm: Option<CompressionAlgorithm>,
+
+    /// Body size from which packets are compressed
+    threshold: usize,
+
+    /// Largest body an incoming packet may expand to
+    max_body_size: usize,
+}
+
+impl PacketCompressor {
+    /// Compressor sending and accepting plain packets only
+    pub fn plain() -> Self {
+        Self {
+            algorithm: None,
+            threshold: DEFAULT_COMPRESSION_THRESHOLD,
+            max_body_size: DEFAULT_MAX_PACKET_SIZE,
+        }
+    }
+
+    /// Negotiate compression with a peer from its identity packet
+    ///
+    /// Picks our most preferred algorithm the peer supports.
+    pub fn negotiate(config: &CompressionConfig, peer_identity: &Packet) -> Self {
+        let algorithm = if config.enabled {
+            let theirs = peer_algorithms(peer_identity);
+            SUPPORTED_ALGORITHMS
+                .iter()
+                .copied()
+                .find(|algorithm| theirs.contains(algorithm))
+        } else {
+            None
+        };
+
+        Self {
+            algorithm,
+            threshold: config.threshold,
+            max_body_size: DEFAULT_MAX_PACKET_SIZE,
+        }
+    }
+
+    /// Limit the size incoming bodies may expand to
+    ///
+    /// Connections pass their packet size limit, so a compressed packet can't
+    /// grow past what the peer could have sent plain.
+    pub fn with_max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Algorithm used for outgoing packets, `None` if packets are sent plain
+    pub fn algorithm(&self) -> Option<CompressionAlgorithm> {
+        self.algorithm
+    }
+
+    /// Compress a packet's body if it reaches the threshold
+    ///
+    /// Packets are returned unchanged if no algorithm was negotiated, the
+    /// body is below the threshold, or compressing would not make it smaller.
+    pub fn compress(&self, mut packet: Packet) -> Result<Packet> {
+        let Some((algorithm, body)) = self.body_to_compress(&packet)? else {
+            return Ok(packet);
+        };
+
+        if let Some(compressed) = compressed_body(algorithm, &body)? {
+            packet.body = compressed;
+        }
+        Ok(packet)
+    }
+
+    /// Like [`compress`](Self::compress), but compresses on a blocking thread
+    ///
+    /// Used by connection tasks so a large body doesn't stall the runtime.
+    pub async fn compress_blocking(&self, mut packet: Packet) -> Result<Packet> {
+        let Some((algorithm, body)) = self.body_to_compress(&packet)? else {
+            return Ok(packet);
+        };
+
+        let compressed = tokio::task::spawn_blocking(move || compressed_body(algorithm, &body))
+            .await
+            .map_err(|e| ProtocolError::Plugin(format!("Compression task panicked: {}", e)))??;
+        if let Some(compressed) = compressed {
+            packet.body = compressed;
+        }
+        Ok(packet)
+    }
+
+    /// Negotiated algorithm and serialized body, if the body should be compressed
+    fn body_to_compress(&self, packet: &Packet) -> Result<Option<(CompressionAlgorithm, Vec<u8>)>> {
+        let Some(algorithm) = self.algorithm else {
+            return Ok(None);
+        };
+
+        let body = serde_json::to_vec(&packet.body)
+            .map_err(|e| ProtocolError::deserialization("packet body", e))?;
+        if body.len() < self.threshold {
+            return Ok(None);
+        }
+        Ok(Some((algorithm, body)))
+    }
+
+    /// Restore the body of a compressed packet
+    ///
+    /// Plain packets, and every packet if no compression was negotiated, are
+    /// returned unchanged. Packets compressed with an unknown algorithm or
+    /// holding corrupt data are rejected with [`ProtocolError::MalformedFrame`],
+    /// bodies expanding past the size limit with
+    /// [`ProtocolError::PayloadTooLarge`].
+    pub fn decompress(&self, mut packet: Packet) -> Result<Packet> {
+        let Some((algorithm, compressed)) = self.body_to_decompress(&packet)? else {
+            return Ok(packet);
+        };
+
+        packet.body = decompressed_body(algorithm, &compressed, self.max_body_size)?;
+        Ok(packet)
+    }
+
+    /// Like [`decompress`](Self::decompress), but decompresses on a blocking thread
+    ///
+    /// Used by connection tasks so a large body doesn't stall the runtime.
+    pub async fn decompress_blocking(&self, mut packet: Packet) -> Result<Packet> {
+        let Some((algorithm, compressed)) = self.body_to_decompress(&packet)? else {
+            return Ok(packet);
+        };
+
+        let max_body_size = self.max_body_size;
+        packet.body = tokio::task::spawn_blocking(move || {
+            decompressed_body(algorithm, &compressed, max_body_size)
+        })
+        .await
+        .map_err(|e| ProtocolError::Plugin(format!("Decompression task panicked: {}", e)))??;
+        Ok(packet)
+    }
+
+    /// Algorithm and compressed data, if the packet should be decompressed
+    fn body_to_decompress(
+        &self,
+        packet: &Packet,
+    ) -> Result<Option<(CompressionAlgorithm, Vec<u8>)>> {
+        if self.algorithm.is_none() {
+            return Ok(None);
+        }
+        let (Some(name), Some(data)) = (
+            packet.body.get(ALGORITHM_FIELD).and_then(|v| v.as_str()),
+            packet.body.get(DATA_FIELD).and_then(|v| v.as_str()),
+        ) else {
+            return Ok(None);
+        };
+
+        let algorithm = CompressionAlgorithm::from_name(name).ok_or_else(|| {
+            ProtocolError::MalformedFrame(format!("unknown compression '{}'", name))
+        })?;
+        let compressed = BASE64.decode(data).map_err(|e| {
+            ProtocolError::MalformedFrame(format!("invalid compressed body: {}", e))
+        })?;
+        Ok(Some((algorithm, compressed)))
+    }
+}
+
+/// Compressed replacement for a serialized body, `None` if it would not be smaller
+fn compressed_body(algorithm: CompressionAlgorithm, body: &[u8]) -> Result<Option<Value>> {
+    let encoded = BASE64.encode(algorithm.compress(body)?);
+    if encoded.len() >= body.len() {
+        return Ok(None);
+    }
+
+    Ok(Some(json!({
+        ALGORITHM_FIELD: algorithm.as_str(),
+        DATA_FIELD: encoded,
+    })))
+}
+
+/// Body restored from compressed data of at most `max_size` bytes
+fn decompressed_body(
+    algorithm: CompressionAlgorithm,
+    compressed: &[u8],
+    max_size: usize,
+) -> Result<Value> {
+    let body = algorithm.decompress(compressed, max_size)?;
+    serde_json::from_slice(&body)
+        .map_err(|e| ProtocolError::deserialization("decompressed packet body", e))
+}
+
+impl Default for PacketCompressor {
+    fn default() -> Self {
+        Self::plain()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeviceInfo, DeviceType};
+
+    fn large_body() -> Value {
+        let processes: Vec<Value> = (0..2000)
+            .map(|pid| {
+                json!({
+                    "pid": pid,
+                    "name": format!("process-{}", pid % 40),
+                    "cpu": pid % 100,
+                    "memory": pid * 4096,
+                })
+            })
+            .collect();
+        json!({ "processes": processes })
+    }
+
+    fn identity_with(algorithms: Option<Vec<&str>>) -> Packet {
+        let mut identity = DeviceInfo::new("Phone", DeviceType::Phone, 1716).to_identity_packet();
+        if let Some(algorithms) = algorithms {
+            identity.body[IDENTITY_FIELD] = json!(algorithms);
+        }
+        identity
+    }
+
+    #[test]
+    fn test_large_body_round_trips() {
+        for algorithm in SUPPORTED_ALGORITHMS {
+            let compressor = PacketCompressor {
+                algorithm: Some(*algorithm),
+                ..PacketCompressor::plain()
+            };
+            let original = Packet::new("cconnect.systemmonitor.processes", large_body());
+
+            let compressed = compressor.compress(original.clone()).unwrap();
+            assert_eq!(compressed.body[ALGORITHM_FIELD], algorithm.as_str());
+            assert_eq!(compressed.id, original.id);
+            assert_eq!(compressed.packet_type, original.packet_type);
+            assert!(
+                serde_json::to_vec(&compressed.body).unwrap().len()
+                    < serde_json::to_vec(&original.body).unwrap().len()
+            );
+
+            // Through the wire encoding and back
+            let received = Packet::from_bytes(&compressed.to_bytes().unwrap()).unwrap();
+            assert_eq!(compressor.decompress(received).unwrap(), original);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compress_blocking_matches_compress() {
+        let compressor = PacketCompressor {
+            algorithm: Some(CompressionAlgorithm::Zstd),
+            ..PacketCompressor::plain()
+        };
+        let large = Packet::new("cconnect.systemmonitor.processes", large_body());
+        let small = Packet::new("cconnect.ping", json!({ "message": "hi" }));
+
+        let compressed = compressor.compress_blocking(large.clone()).await.unwrap();
+        assert_eq!(compressed, compressor.compress(large.clone()).unwrap());
+        assert_eq!(
+            compressor.decompress_blocking(compressed).await.unwrap(),
+            large
+        );
+        assert_eq!(
+            compressor.compress_blocking(small.clone()).await.unwrap(),
+            small
+        );
+    }
+
+    #[test]
+    fn test_small_body_sent_plain() {
+        let config = CompressionConfig::default();
+        let compressor = PacketCompressor::negotiate(&config, &identity_with(Some(vec!["zstd"])));
+        let packet = Packet::new("cconnect.ping", json!({ "message": "hi" }));
+
+        assert_eq!(compressor.compress(packet.clone()).unwrap(), packet);
+    }
+
+    #[test]
+    fn test_threshold_configurable() {
+        let config = CompressionConfig {
+            enabled: true,
+            threshold: 16,
+        };
+        let compressor = PacketCompressor::negotiate(&config, &identity_with(Some(vec!["gzip"])));
+        let packet = Packet::new("cconnect.ping", json!({ "message": "a".repeat(200) }));
+
+        let compressed = compressor.compress(packet.clone()).unwrap();
+        assert_eq!(compressed.body[ALGORITHM_FIELD], "gzip");
+        assert_eq!(compressor.decompress(compressed).unwrap(), packet);
+    }
+
+    #[test]
+    fn test_negotiation() {
+        let config = CompressionConfig::default();
+
+        let both = PacketCompressor::negotiate(&config, &identity_with(Some(vec!["gzip", "zstd"])));
+        assert_eq!(both.algorithm(), Some(CompressionAlgorithm::Zstd));
+
+        let gzip = PacketCompressor::negotiate(&config, &identity_with(Some(vec!["gzip", "lz4"])));
+        assert_eq!(gzip.algorithm(), Some(CompressionAlgorithm::Gzip));
+
+        // Peers that don't advertise support get plain packets
+        let legacy = PacketCompressor::negotiate(&config, &identity_with(None));
+        assert_eq!(legacy.algorithm(), None);
+        let packet = Packet::new("cconnect.systemmonitor.processes", large_body());
+        assert_eq!(legacy.compress(packet.clone()).unwrap(), packet);
+
+        let disabled = CompressionConfig {
+            enabled: false,
+            ..CompressionConfig::default()
+        };
+        let off = PacketCompressor::negotiate(&disabled, &identity_with(Some(vec!["zstd"])));
+        assert_eq!(off.algorithm(), None);
+    }
+
+    #[test]
+    fn test_advertise() {
+        let mut identity = identity_with(None);
+        CompressionConfig::default().advertise(&mut identity);
+        assert_eq!(peer_algorithms(&identity), SUPPORTED_ALGORITHMS.to_vec());
+
+        let mut identity = identity_with(None);
+        CompressionConfig {
+            enabled: false,
+            ..CompressionConfig::default()
+        }
+        .advertise(&mut identity);
+        assert!(identity.body.get(IDENTITY_FIELD).is_none());
+    }
+
+    #[test]
+    fn test_corrupt_body_rejected() {
+        let config = CompressionConfig::default();
+        let compressor = PacketCompressor::negotiate(&config, &identity_with(Some(vec!["zstd"])));
+        let packet = Packet::new(
+            "cconnect.systemmonitor.processes",
+            json!({ ALGORITHM_FIELD: "zstd", DATA_FIELD: BASE64.encode(b"not zstd") }),
+        );
+        assert!(matches!(
+            compressor.decompress(packet),
+            Err(ProtocolError::MalformedFrame(_))
+        ));
+
+        let unknown = Packet::new(
+            "cconnect.ping",
+            json!({ ALGORITHM_FIELD: "brotli", DATA_FIELD: "" }),
+        );
+        assert!(compressor.decompress(unknown).is_err());
+    }
+
+    #[test]
+    fn test_not_negotiated_left_compressed() {
+        let compressor = PacketCompressor::negotiate(
+            &CompressionConfig::default(),
+            &identity_with(Some(vec!["zstd"])),
+        );
+        let compressed = compressor
+            .compress(Packet::new(
+                "cconnect.systemmonitor.processes",
+                large_body(),
+            ))
+            .unwrap();
+
+        let legacy =
+            PacketCompressor::negotiate(&CompressionConfig::default(), &identity_with(None));
+        assert_eq!(legacy.decompress(compressed.clone()).unwrap(), compressed);
+    }
+
+    #[tokio::test]
+    async fn test_decompressed_body_capped() {
+        let compressor = PacketCompressor::negotiate(
+            &CompressionConfig::default(),
+            &identity_with(Some(vec!["zstd"])),
+        );
+        let packet = Packet::new("cconnect.ping", json!({ "message": "a".repeat(64 * 1024) }));
+        let compressed = compressor.compress(packet.clone()).unwrap();
+        assert!(compressed.to_bytes().unwrap().len() < 4096);
+
+        assert!(matches!(
+            compressor
+                .with_max_body_size(4096)
+                .decompress_blocking(compressed.clone())
+                .await,
+            Err(ProtocolError::PayloadTooLarge { .. })
+        ));
+        assert_eq!(
+            compressor.decompress_blocking(compressed).await.unwrap(),
+            packet
+        );
+    }
+
+    #[test]
+    fn test_decompress_size_limit() {
+        let data = CompressionAlgorithm::Gzip.compress(&[0u8; 4096]).unwrap();
+        assert!(matches!(
+            CompressionAlgorithm::Gzip.decompress(&data, 1024),
+            Err(ProtocolError::PayloadTooLarge { .. })
+        ));
+        assert_eq!(
+            CompressionAlgorithm::Gzip
+                .decompress(&data, 4096)
+                .unwrap()
+                .len(),
+            4096
+        );
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::Packet;
 use std::net::SocketAddr;
+use std::time::Duration;
 
 /// Connection event types
 #[derive(Debug, Clone)]
@@ -26,6 +27,18 @@ pub enum ConnectionEvent {
         reconnect: bool,
     },
 
+    /// A connection was closed after receiving no traffic within the heartbeat
+    /// timeout
+    ///
+    /// Emitted instead of [`ConnectionEvent::Disconnected`], so a silently
+    /// dropped link can be told apart from a clean disconnect.
+    Timeout {
+        /// Device ID
+        device_id: String,
+        /// Time since the last packet was received
+        idle: Duration,
+    },
+
     /// A packet has been received from a device
     PacketReceived {
         /// Device ID that sent the packet
@@ -19,14 +19,20 @@
 
 use super::events::ConnectionEvent;
 use crate::{
-    CertificateInfo, Device, DeviceInfo, DeviceManager, Packet, ProtocolError, Result, TlsConfig,
-    TlsConnection, TlsDeviceInfo, TlsServer,
+    compression::{CompressionAlgorithm, CompressionConfig, PacketCompressor},
+    transport::{
+        HeartbeatIntervals, LatencyCategory, TcpSocketOptions, Transport, TransportType,
+        DEFAULT_MAX_PACKET_SIZE,
+    },
+    CertificateInfo, CorePacket, Device, DeviceInfo, DeviceManager, IdentityPacket, Packet,
+    ProtocolError, Result, TlsConfig, TlsConnection, TlsDeviceInfo, TlsServer,
 };
+use async_trait::async_trait;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, oneshot, RwLock};
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
@@ -36,6 +42,21 @@ const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
 /// Connection timeout (consider disconnected after 60 seconds of no activity)
 const CONNECTION_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Keepalive intervals a device may stay silent before the heartbeat
+/// timeout can close its connection
+const MISSED_KEEPALIVES: u32 = 3;
+
+/// First nonce of keepalive pings
+///
+/// Keepalives carry a nonce so peers running the ping plugin echo them, which
+/// keeps an idle link's traffic flowing. Starting at the top of the range keeps
+/// the echoes apart from the ping plugin's own timing pings, counted from 0.
+const KEEPALIVE_NONCE_BASE: u64 = 1 << 63;
+
+/// How long a disconnect waits for the connection task to close gracefully
+/// before aborting it
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Minimum delay between connection attempts from the same device
 /// Issue #52: This is now used for logging warnings, not rejection
 /// Socket replacement prevents connection storms while maintaining stability
@@ -49,6 +70,8 @@ enum ConnectionCommand {
     Close,
     /// Close due to socket replacement (do not trigger plugin cleanup)
     CloseForReconnect,
+    /// Change the keepalive ping interval
+    SetKeepAliveInterval(Duration),
 }
 
 /// Active connection to a device
@@ -62,6 +85,8 @@ struct ActiveConnection {
     device_id: String,
     /// Remote address
     remote_addr: SocketAddr,
+    /// Compression used for packets sent to the device
+    compression: Option<CompressionAlgorithm>,
 }
 
 /// Connection manager configuration
@@ -73,6 +98,20 @@ pub struct ConnectionConfig {
     pub keep_alive_interval: Duration,
     /// Connection timeout
     pub connection_timeout: Duration,
+    /// Close a connection that has received no packets for this long,
+    /// `None` (the default) to keep idle connections open
+    ///
+    /// The timeout is stretched to at least three keepalive intervals of the
+    /// device, as peers that don't echo keepalives (e.g. KDE Connect) only
+    /// send traffic of their own.
+    pub heartbeat_timeout: Option<Duration>,
+    /// Compression of large packets for devices that support it
+    pub compression: CompressionConfig,
+    /// Largest packet accepted from a device, as serialized on the wire;
+    /// the connection is dropped when a device exceeds it
+    pub max_packet_size: usize,
+    /// `TCP_NODELAY` and OS keepalive settings of device connections
+    pub socket_options: TcpSocketOptions,
 }
 
 impl Default for ConnectionConfig {
@@ -81,10 +120,104 @@ impl Default for ConnectionConfig {
             listen_addr: "0.0.0.0:1814".parse().unwrap(),
             keep_alive_interval: KEEP_ALIVE_INTERVAL,
             connection_timeout: CONNECTION_TIMEOUT,
+            heartbeat_timeout: None,
+            compression: CompressionConfig::default(),
+            max_packet_size: DEFAULT_MAX_PACKET_SIZE,
+            socket_options: TcpSocketOptions::default(),
         }
     }
 }
 
+/// Packet connection driven by a connection handler
+///
+/// Implemented by [`TlsConnection`] and by boxed [`Transport`]s, so a
+/// connection manager can also run over other links, see
+/// [`ConnectionManager::attach_connection`].
+#[async_trait]
+pub trait PacketConnection: Send + 'static {
+    /// Send a packet
+    async fn send_packet(&mut self, packet: &CorePacket) -> Result<()>;
+
+    /// Receive the next packet
+    async fn receive_packet(&mut self) -> Result<CorePacket>;
+
+    /// Record the device ID once the peer has identified itself
+    fn set_device_id(&mut self, device_id: String);
+
+    /// DER-encoded leaf certificate presented by the peer
+    fn peer_certificate(&self) -> Option<Vec<u8>>;
+
+    /// Close the connection
+    async fn close(self) -> Result<()>;
+}
+
+#[async_trait]
+impl PacketConnection for TlsConnection {
+    async fn send_packet(&mut self, packet: &CorePacket) -> Result<()> {
+        Ok(TlsConnection::send_packet(self, packet).await?)
+    }
+
+    async fn receive_packet(&mut self) -> Result<CorePacket> {
+        Ok(TlsConnection::receive_packet(self).await?)
+    }
+
+    fn set_device_id(&mut self, device_id: String) {
+        TlsConnection::set_device_id(self, device_id);
+    }
+
+    fn peer_certificate(&self) -> Option<Vec<u8>> {
+        TlsConnection::peer_certificate(self).map(|cert| cert.to_vec())
+    }
+
+    async fn close(self) -> Result<()> {
+        Ok(TlsConnection::close(self).await?)
+    }
+}
+
+/// Transports present no certificate, so they can't carry a paired device
+#[async_trait]
+impl PacketConnection for Box<dyn Transport> {
+    async fn send_packet(&mut self, packet: &CorePacket) -> Result<()> {
+        Transport::send_packet(self.as_mut(), &Packet::from_core_packet(packet.clone())).await
+    }
+
+    async fn receive_packet(&mut self) -> Result<CorePacket> {
+        Ok(Transport::receive_packet(self.as_mut())
+            .await?
+            .to_core_packet())
+    }
+
+    fn set_device_id(&mut self, _device_id: String) {}
+
+    fn peer_certificate(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    async fn close(self) -> Result<()> {
+        <dyn Transport>::close(self).await
+    }
+}
+
+/// Check a packet read from a connection and decompress it
+///
+/// Packets larger than `max_packet_size` once serialized, or expanding past
+/// it, are rejected with [`ProtocolError::PayloadTooLarge`], compressed
+/// bodies that do not decode with [`ProtocolError::MalformedFrame`]. Either
+/// way the caller drops the connection, since nothing that follows on the
+/// stream can be trusted.
+async fn accept_packet(
+    core_packet: CorePacket,
+    compressor: &PacketCompressor,
+    max_packet_size: usize,
+) -> Result<Packet> {
+    let packet = Packet::from_core_packet(core_packet);
+    let size = packet.to_bytes()?.len();
+    if size > max_packet_size {
+        return Err(ProtocolError::payload_too_large(size, max_packet_size));
+    }
+    compressor.decompress_blocking(packet).await
+}
+
 /// Connection manager for handling multiple TLS connections
 pub struct ConnectionManager {
     /// Our device certificate
@@ -116,6 +249,9 @@ pub struct ConnectionManager {
 
     /// Last connection time per device (for rate limiting to prevent connection storms)
     last_connection_time: Arc<RwLock<HashMap<String, Instant>>>,
+
+    /// Keepalive interval per device, derived from transport latency
+    heartbeats: Arc<RwLock<HeartbeatIntervals>>,
 }
 
 /// Helper to convert discovery::DeviceInfo to TlsDeviceInfo
@@ -131,6 +267,68 @@ fn device_info_to_tls(info: &crate::DeviceInfo) -> TlsDeviceInfo {
     }
 }
 
+/// Apply the configured socket options to a TLS connection's TCP socket
+///
+/// The core crate's [`TlsConnection`] doesn't expose its stream, so the
+/// socket is found by its peer address. Failures are logged, not returned.
+fn apply_socket_options(options: &TcpSocketOptions, remote_addr: SocketAddr) {
+    match options.apply_to_peer(remote_addr) {
+        Ok(true) => debug!("Applied socket options to connection with {}", remote_addr),
+        Ok(false) => debug!("No socket connected to {} to apply options to", remote_addr),
+        Err(e) => warn!("Failed to set socket options for {}: {}", remote_addr, e),
+    }
+}
+
+/// Round-trip time of the last keepalive, if `packet` is its echo
+///
+/// Peers answer a keepalive like a timing ping of the ping plugin, echoing
+/// its `nonce` with `reply` set. The keepalive is consumed by its echo.
+fn keepalive_rtt(packet: &Packet, last_keepalive: &mut Option<(u64, Instant)>) -> Option<Duration> {
+    if packet.packet_type != "cconnect.ping"
+        || packet.body.get("reply").and_then(|v| v.as_bool()) != Some(true)
+    {
+        return None;
+    }
+    let nonce = packet.body.get("nonce")?.as_u64()?;
+    let (sent_nonce, sent_at) = (*last_keepalive)?;
+    if nonce != sent_nonce {
+        return None;
+    }
+    *last_keepalive = None;
+    Some(sent_at.elapsed())
+}
+
+/// Negotiate capabilities and protocol version with a newly identified device
+fn negotiate_capabilities(device: &mut Device, our_info: &DeviceInfo) {
+    device.negotiate_capabilities(
+        &our_info.incoming_capabilities,
+        &our_info.outgoing_capabilities,
+    );
+    match &device.negotiated_capabilities {
+        Some(diff) => debug!("Negotiated capabilities with {}: {}", device.id(), diff),
+        None => debug!(
+            "Device {} advertised no capabilities, assuming full support",
+            device.id()
+        ),
+    }
+
+    let compat = device.version_compat();
+    if compat.peer_is_newer() {
+        info!(
+            "Device {} speaks protocol version {}, newer than ours; using {}",
+            device.id(),
+            compat.peer_version(),
+            compat.effective_version()
+        );
+    } else {
+        debug!(
+            "Using protocol version {} with {}",
+            compat.effective_version(),
+            device.id()
+        );
+    }
+}
+
 impl ConnectionManager {
     /// Create a new connection manager
     pub fn new(
@@ -155,6 +353,7 @@ impl ConnectionManager {
             config,
             server_task: Arc::new(RwLock::new(None)),
             last_connection_time: Arc::new(RwLock::new(HashMap::new())),
+            heartbeats: Arc::new(RwLock::new(HeartbeatIntervals::new())),
         })
     }
 
@@ -211,6 +410,11 @@ impl ConnectionManager {
         let device_manager = self.device_manager.clone();
         let device_info = self.device_info.clone();
         let last_connection_time = self.last_connection_time.clone();
+        let heartbeats = self.heartbeats.clone();
+        let heartbeat_timeout = self.config.heartbeat_timeout;
+        let compression = self.config.compression;
+        let max_packet_size = self.config.max_packet_size;
+        let socket_options = self.config.socket_options;
 
         let server_task = tokio::spawn(async move {
             let mut consecutive_errors = 0u32;
@@ -223,6 +427,7 @@ impl ConnectionManager {
                         consecutive_errors = 0;
 
                         let remote_addr = connection.remote_addr();
+                        apply_socket_options(&socket_options, remote_addr);
                         let device_name = core_identity
                             .get_body_field::<String>("deviceName")
                             .unwrap_or_else(|| "Unknown".to_string());
@@ -245,6 +450,10 @@ impl ConnectionManager {
                             device_manager.clone(),
                             Some(remote_identity), // Pass the already-received identity
                             last_connection_time.clone(),
+                            heartbeats.clone(),
+                            heartbeat_timeout,
+                            compression,
+                            max_packet_size,
                         );
                     }
                     Err(e) => {
@@ -279,7 +488,13 @@ impl ConnectionManager {
                             tokio::time::sleep(std::time::Duration::from_secs(backoff_secs)).await;
                         } else {
                             // Brief delay for occasional errors
-                            error!("Error accepting connection: {}", e);
+                            let error = ProtocolError::from(e)
+                                .classify_tls_handshake("Accepting connection");
+                            error!(
+                                "Error accepting connection: {} ({})",
+                                error,
+                                error.user_message()
+                            );
                             tokio::time::sleep(std::time::Duration::from_millis(100)).await;
                         }
                     }
@@ -311,10 +526,7 @@ impl ConnectionManager {
         // Connect with TLS (rustls with TOFU)
         // Note: cosmic-ext-connect-core TLS uses TOFU - no pre-verification needed
         // Create identity packet to send before TLS handshake (KDE Connect protocol v8)
-        let identity_packet = self.device_info.to_identity_packet();
-        let identity_bytes = identity_packet.to_bytes()?;
-        let mut connection =
-            TlsConnection::connect(addr, &self.tls_config, &identity_bytes).await?;
+        let mut connection = self.open_tls_connection(device_id, addr).await?;
 
         connection.set_device_id(device_id.to_string());
 
@@ -329,6 +541,10 @@ impl ConnectionManager {
             self.device_manager.clone(),
             None, // Will perform identity exchange in handler
             self.last_connection_time.clone(),
+            self.heartbeats.clone(),
+            self.config.heartbeat_timeout,
+            self.config.compression,
+            self.config.max_packet_size,
         );
 
         info!("Connected to device {} at {}", device_id, addr);
@@ -358,10 +574,7 @@ impl ConnectionManager {
         // Note: peer_cert is ignored - cosmic-ext-connect-core uses TOFU model
         // Certificate verification happens at application layer via SHA256 fingerprint
         // Create identity packet to send before TLS handshake (KDE Connect protocol v8)
-        let identity_packet = self.device_info.to_identity_packet();
-        let identity_bytes = identity_packet.to_bytes()?;
-        let mut connection =
-            TlsConnection::connect(addr, &self.tls_config, &identity_bytes).await?;
+        let mut connection = self.open_tls_connection(device_id, addr).await?;
 
         connection.set_device_id(device_id.to_string());
 
@@ -376,6 +589,10 @@ impl ConnectionManager {
             self.device_manager.clone(),
             None, // Will perform identity exchange in handler
             self.last_connection_time.clone(),
+            self.heartbeats.clone(),
+            self.config.heartbeat_timeout,
+            self.config.compression,
+            self.config.max_packet_size,
         );
 
         info!(
@@ -386,13 +603,100 @@ impl ConnectionManager {
         Ok(())
     }
 
+    /// Open an outgoing TLS connection, bounded by the configured connection timeout
+    ///
+    /// Sends our identity packet before the TLS handshake (KDE Connect protocol v8).
+    /// Returns [`ProtocolError::HandshakeTimeout`] if the peer does not complete the
+    /// handshake in time, so callers can report an unreachable device rather than
+    /// hanging. Certificate and TLS version failures are mapped to their specific
+    /// variants by [`ProtocolError::classify_tls_handshake`].
+    async fn open_tls_connection(
+        &self,
+        device_id: &str,
+        addr: SocketAddr,
+    ) -> Result<TlsConnection> {
+        let mut identity_packet = self.device_info.to_identity_packet();
+        self.config.compression.advertise(&mut identity_packet);
+        let identity_bytes = identity_packet.to_bytes()?;
+
+        let connection = tokio::time::timeout(
+            self.config.connection_timeout,
+            TlsConnection::connect(addr, &self.tls_config, &identity_bytes),
+        )
+        .await
+        .map_err(|_| {
+            ProtocolError::HandshakeTimeout(format!(
+                "TLS handshake with {} at {} did not complete within {}s",
+                device_id,
+                addr,
+                self.config.connection_timeout.as_secs()
+            ))
+        })?
+        .map_err(|e| {
+            ProtocolError::from(e)
+                .classify_tls_handshake(&format!("TLS handshake with {} at {}", device_id, addr))
+        })?;
+
+        apply_socket_options(&self.config.socket_options, connection.remote_addr());
+        Ok(connection)
+    }
+
+    /// Handle a connection established outside the manager
+    ///
+    /// Identities are exchanged over `connection`, after which it is handled
+    /// like an accepted TLS connection: packets are sent with
+    /// [`Self::send_packet`] and arrive as [`ConnectionEvent`]s. Used to run
+    /// the manager over other transports, such as
+    /// [`MockTransport`](crate::transport::MockTransport) in tests.
+    pub fn attach_connection<C: PacketConnection>(&self, connection: C, remote_addr: SocketAddr) {
+        Self::spawn_connection_handler(
+            connection,
+            remote_addr,
+            self.device_info.clone(),
+            self.event_tx.clone(),
+            self.connections.clone(),
+            self.device_manager.clone(),
+            None, // Will perform identity exchange in handler
+            self.last_connection_time.clone(),
+            self.heartbeats.clone(),
+            self.config.heartbeat_timeout,
+            self.config.compression,
+            self.config.max_packet_size,
+        );
+    }
+
     /// Send a packet to a device
+    ///
+    /// Packets the device ignores according to the capabilities negotiated on
+    /// connect (see [`Device::ignores_packet`]) are dropped without an error.
     pub async fn send_packet(&self, device_id: &str, packet: &Packet) -> Result<()> {
         debug!(
             "Sending packet '{}' to device {}",
             packet.packet_type, device_id
         );
 
+        // Don't send what the device told us it can't handle, and drop fields
+        // its protocol version does not know
+        let mut packet = packet.clone();
+        if let Some(device) = self.device_manager.read().await.get_device(device_id) {
+            if device.ignores_packet(&packet.packet_type) {
+                debug!(
+                    "Not sending '{}' to device {}: not in its incoming capabilities",
+                    packet.packet_type, device_id
+                );
+                return Ok(());
+            }
+            if !device.version_compat().adapt_outgoing(&mut packet) {
+                debug!(
+                    "Not sending '{}' to device {}: unknown to protocol version {}",
+                    packet.packet_type,
+                    device_id,
+                    device.effective_protocol_version()
+                );
+                return Ok(());
+            }
+        }
+
         let connections = self.connections.read().await;
         let connection = connections.get(device_id).ok_or_else(|| {
             ProtocolError::DeviceNotFound(format!("Not connected to device {}", device_id))
@@ -400,36 +704,89 @@ impl ConnectionManager {
 
         connection
             .command_tx
-            .send(ConnectionCommand::SendPacket(packet.clone()))
-            .map_err(|_| {
-                ProtocolError::Io(std::io::Error::new(
-                    std::io::ErrorKind::BrokenPipe,
-                    "Connection closed",
-                ))
-            })?;
+            .send(ConnectionCommand::SendPacket(packet))
+            .map_err(|_| ProtocolError::transport(format!("Connection to {} closed", device_id)))?;
 
         debug!("Packet queued for device {}", device_id);
         Ok(())
     }
 
     /// Disconnect from a device
+    ///
+    /// The connection task writes out packets queued before the disconnect,
+    /// then closes the connection. Returns once it has, or aborts the task if
+    /// that takes longer than [`DISCONNECT_TIMEOUT`].
     pub async fn disconnect(&self, device_id: &str) -> Result<()> {
         info!("Disconnecting from device {}", device_id);
 
-        let mut connections = self.connections.write().await;
-        if let Some(active_conn) = connections.remove(device_id) {
-            // Send close command
-            let _ = active_conn.command_tx.send(ConnectionCommand::Close);
-
-            // Abort task
-            active_conn.task.abort();
+        // Not held while waiting: the task takes the lock to clean up
+        let active_conn = self.connections.write().await.remove(device_id);
+        let Some(active_conn) = active_conn else {
+            return Ok(());
+        };
 
-            info!("Disconnected from device {}", device_id);
+        let _ = active_conn.command_tx.send(ConnectionCommand::Close);
+
+        let mut task = active_conn.task;
+        if tokio::time::timeout(DISCONNECT_TIMEOUT, &mut task)
+            .await
+            .is_err()
+        {
+            warn!(
+                "Connection to {} did not close within {}s, aborting",
+                device_id,
+                DISCONNECT_TIMEOUT.as_secs()
+            );
+            task.abort();
         }
 
+        info!("Disconnected from device {}", device_id);
         Ok(())
     }
 
+    /// Get the shared per-device heartbeat interval registry
+    pub fn heartbeat_intervals(&self) -> Arc<RwLock<HeartbeatIntervals>> {
+        Arc::clone(&self.heartbeats)
+    }
+
+    /// Get the keepalive interval currently used for a device
+    pub async fn heartbeat_interval(&self, device_id: &str) -> Option<Duration> {
+        self.heartbeats.read().await.interval_for(device_id)
+    }
+
+    /// Override the keepalive interval for a device
+    ///
+    /// Passing `None` restores the default derived from the transport latency.
+    /// Takes effect immediately if the device is connected.
+    pub async fn set_heartbeat_override(&self, device_id: &str, interval: Option<Duration>) {
+        let interval = {
+            let mut heartbeats = self.heartbeats.write().await;
+            heartbeats.set_override(device_id, interval);
+            heartbeats.interval_for(device_id)
+        };
+
+        if let Some(interval) = interval {
+            if let Some(conn) = self.connections.read().await.get(device_id) {
+                let _ = conn
+                    .command_tx
+                    .send(ConnectionCommand::SetKeepAliveInterval(interval));
+            }
+        }
+    }
+
+    /// Compression used for packets sent to a connected device
+    ///
+    /// Returns `None` if the device is not connected or did not advertise
+    /// support for any of our algorithms, in which case packets are sent
+    /// plain.
+    pub async fn connection_compression(&self, device_id: &str) -> Option<CompressionAlgorithm> {
+        self.connections
+            .read()
+            .await
+            .get(device_id)
+            .and_then(|conn| conn.compression)
+    }
+
     /// Check if there's an active connection to a device
     pub async fn has_connection(&self, device_id: &str) -> bool {
         let connections = self.connections.read().await;
@@ -468,9 +825,19 @@ impl ConnectionManager {
     /// If `remote_identity` is Some, the identity exchange has already been completed
     /// (e.g., by TLS server's accept() method for protocol v8). Otherwise, perform
     /// the identity exchange here.
+    ///
+    /// If `heartbeat_timeout` is Some, the connection is closed with a
+    /// [`ConnectionEvent::Timeout`] once no packet has been received for that long.
+    ///
+    /// Packets are compressed and decompressed as negotiated from `compression`
+    /// and the peer's identity; a decompressed body may not exceed
+    /// `max_packet_size` either. An incoming packet over the limit, or one that
+    /// does not decode,
+    /// is reported as [`ConnectionEvent::ConnectionError`] and closes the
+    /// connection.
     #[allow(clippy::too_many_arguments)]
-    fn spawn_connection_handler(
-        mut connection: TlsConnection,
+    fn spawn_connection_handler<C: PacketConnection>(
+        mut connection: C,
         remote_addr: SocketAddr,
         device_info: Arc<crate::DeviceInfo>,
         event_tx: mpsc::UnboundedSender<ConnectionEvent>,
@@ -478,10 +845,20 @@ impl ConnectionManager {
         device_manager: Arc<RwLock<DeviceManager>>,
         remote_identity: Option<crate::Packet>,
         last_connection_time: Arc<RwLock<HashMap<String, Instant>>>,
+        heartbeats: Arc<RwLock<HeartbeatIntervals>>,
+        heartbeat_timeout: Option<Duration>,
+        compression: CompressionConfig,
+        max_packet_size: usize,
     ) {
         let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+        let (task_tx, task_rx) = oneshot::channel();
 
-        let _task = tokio::spawn(async move {
+        let task = tokio::spawn(async move {
+            // Our own handle, kept with the active connection so a disconnect
+            // can wait for this task to finish
+            let Ok(task) = task_rx.await else {
+                return;
+            };
             let device_id: Option<String>;
 
             // If remote_identity is already provided, skip the identity exchange
@@ -490,7 +867,8 @@ impl ConnectionManager {
                 identity_packet
             } else {
                 // CConnect protocol v8: Send our identity over encrypted connection first
-                let our_identity = device_info.to_identity_packet();
+                let mut our_identity = device_info.to_identity_packet();
+                compression.advertise(&mut our_identity);
                 let core_identity = our_identity.to_core_packet();
                 if let Err(e) = connection.send_packet(&core_identity).await {
                     error!("Failed to send identity over TLS to {}: {}", remote_addr, e);
@@ -498,9 +876,16 @@ impl ConnectionManager {
                 }
                 debug!("Sent encrypted identity packet to {}", remote_addr);
 
-                // Now receive the client's encrypted identity packet
-                match connection.receive_packet().await {
-                    Ok(core_pkt) => Packet::from_core_packet(core_pkt),
+                // Now receive the client's encrypted identity packet, which is
+                // never compressed
+                let identity = match connection.receive_packet().await {
+                    Ok(core_pkt) => {
+                        accept_packet(core_pkt, &PacketCompressor::plain(), max_packet_size).await
+                    }
+                    Err(e) => Err(e),
+                };
+                match identity {
+                    Ok(packet) => packet,
                     Err(e) => {
                         error!(
                             "Failed to receive identity packet from {}: {}",
@@ -511,6 +896,10 @@ impl ConnectionManager {
                 }
             };
 
+            // Compress packets only if the peer said it can decode them
+            let compressor = PacketCompressor::negotiate(&compression, &packet)
+                .with_max_body_size(max_packet_size);
+
             // Extract device ID from the identity packet
             if let Some(id) = packet.body.get("deviceId").and_then(|v| v.as_str()) {
                 device_id = Some(id.to_string());
@@ -518,15 +907,40 @@ impl ConnectionManager {
 
                 info!("Connection identified as device {}", id);
 
+                // Peers older than we can talk to are refused up front
+                if let Err(e) = IdentityPacket::check_version(&packet) {
+                    error!("Rejecting connection from {}: {}", remote_addr, e);
+                    let _ = event_tx.send(ConnectionEvent::ConnectionError {
+                        device_id: Some(id.to_string()),
+                        message: e.user_message(),
+                    });
+                    let _ = connection.close().await;
+                    return;
+                }
+
                 // Update device manager - register device if not exists before marking connected
                 let mut dm = device_manager.write().await;
 
+                // A paired device must present the certificate it paired with
+                let peer_certificate = connection.peer_certificate();
+                if let Err(e) = dm.verify_certificate(id, peer_certificate.as_deref()) {
+                    drop(dm);
+                    error!("Rejecting connection from {}: {}", remote_addr, e);
+                    let _ = event_tx.send(ConnectionEvent::ConnectionError {
+                        device_id: Some(id.to_string()),
+                        message: e.user_message(),
+                    });
+                    let _ = connection.close().await;
+                    return;
+                }
+
                 // Register new device or update capabilities for existing one
                 if dm.get_device(id).is_none() {
                     // Device doesn't exist — try full parse to create it
                     match DeviceInfo::from_identity_packet(&packet) {
-                        Ok(device_info) => {
-                            let device = Device::from_discovery(device_info);
+                        Ok(peer_info) => {
+                            let mut device = Device::from_discovery(peer_info);
+                            negotiate_capabilities(&mut device, &device_info);
                             dm.add_device(device);
                             info!("Registered new device {} from incoming connection", id);
                         }
@@ -551,6 +965,25 @@ impl ConnectionManager {
                             device.info.outgoing_capabilities.len()
                         );
                     }
+
+                    // The peer may have been updated since it last connected
+                    if let Some(version) = packet.get_body_field::<u32>("protocolVersion") {
+                        device.info.protocol_version = version;
+                    }
+
+                    negotiate_capabilities(device, &device_info);
+
+                    // Always dial the port from the latest identity packet
+                    if let Some(tcp_port) = packet.get_body_field::<u16>("tcpPort") {
+                        device.update_tcp_port(tcp_port);
+                    }
+                }
+
+                // Pairing requests show the fingerprint of the certificate
+                // presented here, so keep it with the device
+                if let (Some(certificate), Some(device)) = (peer_certificate, dm.get_device_mut(id))
+                {
+                    device.certificate_data = Some(certificate);
                 }
 
                 if let Err(e) =
@@ -615,13 +1048,21 @@ impl ConnectionManager {
                     id.to_string(),
                     ActiveConnection {
                         command_tx: command_tx.clone(),
-                        task: tokio::task::spawn(async {}), // Placeholder task
+                        task,
                         device_id: id.to_string(),
                         remote_addr,
+                        compression: compressor.algorithm(),
                     },
                 );
                 drop(conns);
 
+                match compressor.algorithm() {
+                    Some(algorithm) => {
+                        debug!("Compressing large packets to {} with {}", id, algorithm)
+                    }
+                    None => debug!("Sending plain packets to {}", id),
+                }
+
                 // Emit connected event
                 let _ = event_tx.send(ConnectionEvent::Connected {
                     device_id: id.to_string(),
@@ -644,13 +1085,33 @@ impl ConnectionManager {
 
             let device_id = device_id.unwrap();
 
+            // Latency of the link: typical for TLS over TCP until keepalive
+            // echoes give a measured round-trip time
+            let mut latency = TransportType::Tcp.latency();
+
             // Keepalive pings to maintain connection stability
             // Uses "keepalive" flag so Android handles these silently without notifications
-            let mut keepalive_timer = Some(tokio::time::interval(KEEP_ALIVE_INTERVAL));
+            let mut keepalive_interval = heartbeats
+                .write()
+                .await
+                .transport_changed(&device_id, latency);
+            debug!(
+                "Keepalive interval for {}: {}s",
+                device_id,
+                keepalive_interval.as_secs()
+            );
+            let mut keepalive_timer = tokio::time::interval(keepalive_interval);
+            let mut keepalive_nonce = KEEPALIVE_NONCE_BASE;
+            let mut last_keepalive: Option<(u64, Instant)> = None;
 
             // Track if this is a socket replacement (reconnect) to preserve plugins
             let mut is_reconnect = false;
 
+            // Last time a packet arrived, and how long the link had been silent
+            // if the heartbeat timeout closed it
+            let mut last_activity = tokio::time::Instant::now();
+            let mut timed_out: Option<Duration> = None;
+
             // Main connection loop
             loop {
                 tokio::select! {
@@ -660,6 +1121,13 @@ impl ConnectionManager {
                             ConnectionCommand::SendPacket(packet) => {
                                 // Convert applet Packet to core Packet for TLS
                                 debug!("Connection task sending packet '{}' to {}", packet.packet_type, device_id);
+                                let packet = match compressor.compress_blocking(packet).await {
+                                    Ok(packet) => packet,
+                                    Err(e) => {
+                                        error!("Failed to compress packet for {}: {}", device_id, e);
+                                        continue;
+                                    }
+                                };
                                 let core_packet = packet.to_core_packet();
                                 match connection.send_packet(&core_packet).await {
                                     Ok(_) => {
@@ -680,6 +1148,14 @@ impl ConnectionManager {
                                 is_reconnect = true;
                                 break;
                             }
+                            ConnectionCommand::SetKeepAliveInterval(interval) => {
+                                debug!("Keepalive interval for {} changed to {}s", device_id, interval.as_secs());
+                                keepalive_interval = interval;
+                                keepalive_timer = tokio::time::interval_at(
+                                    tokio::time::Instant::now() + interval,
+                                    interval,
+                                );
+                            }
                         }
                     }
 
@@ -687,9 +1163,40 @@ impl ConnectionManager {
                     result = connection.receive_packet() => {
                         match result {
                             Ok(core_packet) => {
+                                last_activity = tokio::time::Instant::now();
+
                                 // Convert core Packet to applet Packet
-                                let packet = crate::Packet::from_core_packet(core_packet);
+                                let packet = match accept_packet(core_packet, &compressor, max_packet_size).await {
+                                    Ok(packet) => packet,
+                                    Err(e) => {
+                                        warn!("Closing connection to {}: {}", device_id, e);
+                                        let _ = event_tx.send(ConnectionEvent::ConnectionError {
+                                            device_id: Some(device_id.clone()),
+                                            message: e.user_message(),
+                                        });
+                                        break;
+                                    }
+                                };
                                 debug!("Received packet '{}' from {}", packet.packet_type, device_id);
+                                if let Some(rtt) = keepalive_rtt(&packet, &mut last_keepalive) {
+                                    let measured = LatencyCategory::from_rtt(rtt);
+                                    if measured != latency {
+                                        debug!("Keepalive round trip to {} took {:?}, latency {:?}", device_id, rtt, measured);
+                                        latency = measured;
+                                        let interval = heartbeats
+                                            .write()
+                                            .await
+                                            .transport_changed(&device_id, latency);
+                                        if interval != keepalive_interval {
+                                            debug!("Keepalive interval for {} changed to {}s", device_id, interval.as_secs());
+                                            keepalive_interval = interval;
+                                            keepalive_timer = tokio::time::interval_at(
+                                                tokio::time::Instant::now() + interval,
+                                                interval,
+                                            );
+                                        }
+                                    }
+                                }
                                 let _ = event_tx.send(ConnectionEvent::PacketReceived {
                                     device_id: device_id.clone(),
                                     packet,
@@ -703,25 +1210,37 @@ impl ConnectionManager {
                         }
                     }
 
-                    // Keepalive timer - only for paired devices
-                    _ = async {
-                        if let Some(ref mut timer) = keepalive_timer {
-                            timer.tick().await;
-                        } else {
-                            // For unpaired devices, never send keepalive
-                            std::future::pending::<()>().await;
-                        }
-                    } => {
+                    // Keepalive timer
+                    _ = keepalive_timer.tick() => {
                         // Send keepalive ping with silent flag to prevent Android notifications
                         debug!("Sending keepalive ping to device {}", device_id);
                         let ping_packet = crate::Packet::new("cconnect.ping", serde_json::json!({
-                            "keepalive": true
+                            "keepalive": true,
+                            "nonce": keepalive_nonce,
                         }));
                         let core_ping = ping_packet.to_core_packet();
                         if let Err(e) = connection.send_packet(&core_ping).await {
                             error!("Failed to send keepalive ping to {}: {}", device_id, e);
                             break;
                         }
+                        last_keepalive = Some((keepalive_nonce, Instant::now()));
+                        keepalive_nonce = keepalive_nonce.wrapping_add(1).max(KEEPALIVE_NONCE_BASE);
+                    }
+
+                    // Heartbeat timeout - no traffic from the device
+                    _ = async {
+                        match heartbeat_timeout {
+                            Some(timeout) => {
+                                let timeout = timeout.max(keepalive_interval * MISSED_KEEPALIVES);
+                                tokio::time::sleep_until(last_activity + timeout).await
+                            }
+                            None => std::future::pending::<()>().await,
+                        }
+                    } => {
+                        let idle = last_activity.elapsed();
+                        warn!("No traffic from {} for {}s, closing connection", device_id, idle.as_secs());
+                        timed_out = Some(idle);
+                        break;
                     }
                 }
             }
@@ -756,16 +1275,28 @@ impl ConnectionManager {
             // Update device manager only if this was the active connection
             // and NOT a socket replacement (reconnect)
             if should_mark_disconnected && !is_reconnect {
+                heartbeats
+                    .write()
+                    .await
+                    .transport_closed(&device_id, latency);
+
                 let mut dm = device_manager.write().await;
                 let _ = dm.mark_disconnected(&device_id);
                 drop(dm);
 
-                // Emit disconnected event
-                let _ = event_tx.send(ConnectionEvent::Disconnected {
-                    device_id: device_id.clone(),
-                    reason: Some("Connection closed".to_string()),
-                    reconnect: false,
-                });
+                // Emit timeout or disconnected event
+                let event = match timed_out {
+                    Some(idle) => ConnectionEvent::Timeout {
+                        device_id: device_id.clone(),
+                        idle,
+                    },
+                    None => ConnectionEvent::Disconnected {
+                        device_id: device_id.clone(),
+                        reason: Some("Connection closed".to_string()),
+                        reconnect: false,
+                    },
+                };
+                let _ = event_tx.send(event);
             } else if is_reconnect {
                 // Socket replacement - emit reconnect event so daemon knows not to cleanup plugins
                 info!("Socket replaced for {} - plugins preserved", device_id);
@@ -781,10 +1312,673 @@ impl ConnectionManager {
 
             info!("Connection handler for {} stopped", device_id);
         });
+        let _ = task_tx.send(task);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pairing::{PairingConfig, PairingEvent, PairingService};
+    use crate::DeviceType;
+    use std::sync::Mutex;
+
+    /// Connection whose peer sends a packet every `period`, or never if `None`
+    struct MockConnection {
+        period: Option<Duration>,
+        sent: Arc<Mutex<Vec<Packet>>>,
+        certificate: Option<Vec<u8>>,
+    }
+
+    #[async_trait]
+    impl PacketConnection for MockConnection {
+        async fn send_packet(&mut self, packet: &CorePacket) -> Result<()> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push(Packet::new(packet.packet_type.clone(), packet.body.clone()));
+            Ok(())
+        }
+
+        async fn receive_packet(&mut self) -> Result<CorePacket> {
+            match self.period {
+                Some(period) => {
+                    tokio::time::sleep(period).await;
+                    let ping =
+                        Packet::new("cconnect.ping", serde_json::json!({ "keepalive": true }));
+                    Ok(ping.to_core_packet())
+                }
+                // Stalled link: nothing ever arrives and nothing fails
+                None => std::future::pending().await,
+            }
+        }
+
+        fn set_device_id(&mut self, _device_id: String) {}
+
+        fn peer_certificate(&self) -> Option<Vec<u8>> {
+            self.certificate.clone()
+        }
+
+        async fn close(self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Connection whose peer echoes each keepalive after `delay`
+    struct EchoConnection {
+        delay: Duration,
+        echoes: std::collections::VecDeque<(u64, tokio::time::Instant)>,
+    }
+
+    #[async_trait]
+    impl PacketConnection for EchoConnection {
+        async fn send_packet(&mut self, packet: &CorePacket) -> Result<()> {
+            if let Some(nonce) = packet.body.get("nonce").and_then(|v| v.as_u64()) {
+                self.echoes
+                    .push_back((nonce, tokio::time::Instant::now() + self.delay));
+            }
+            Ok(())
+        }
+
+        async fn receive_packet(&mut self) -> Result<CorePacket> {
+            let Some(&(nonce, due)) = self.echoes.front() else {
+                return std::future::pending().await;
+            };
+            tokio::time::sleep_until(due).await;
+            self.echoes.pop_front();
+            let echo = Packet::new(
+                "cconnect.ping",
+                serde_json::json!({ "keepalive": true, "nonce": nonce, "reply": true }),
+            );
+            Ok(echo.to_core_packet())
+        }
+
+        fn set_device_id(&mut self, _device_id: String) {}
+
+        fn peer_certificate(&self) -> Option<Vec<u8>> {
+            None
+        }
+
+        async fn close(self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Harness {
+        device_id: String,
+        events: mpsc::UnboundedReceiver<ConnectionEvent>,
+        connections: Arc<RwLock<HashMap<String, ActiveConnection>>>,
+        device_manager: Arc<RwLock<DeviceManager>>,
+        sent: Arc<Mutex<Vec<Packet>>>,
+        _registry_dir: tempfile::TempDir,
+    }
+
+    /// Keepalive interval of the mock device
+    const MOCK_KEEPALIVE_INTERVAL: Duration = Duration::from_millis(50);
+
+    fn spawn_mock(period: Option<Duration>, heartbeat_timeout: Option<Duration>) -> Harness {
+        spawn_mock_with_certificate(period, heartbeat_timeout, None, None)
+    }
+
+    /// Like [`spawn_mock`], with the peer presenting `certificate` and, if
+    /// `pinned` is set, already paired with that certificate's fingerprint
+    fn spawn_mock_with_certificate(
+        period: Option<Duration>,
+        heartbeat_timeout: Option<Duration>,
+        certificate: Option<Vec<u8>>,
+        pinned: Option<&[u8]>,
+    ) -> Harness {
+        spawn_mock_with_limit(
+            period,
+            heartbeat_timeout,
+            certificate,
+            pinned,
+            DEFAULT_MAX_PACKET_SIZE,
+        )
+    }
+
+    /// Like [`spawn_mock_with_certificate`], accepting packets of at most
+    /// `max_packet_size` bytes
+    fn spawn_mock_with_limit(
+        period: Option<Duration>,
+        heartbeat_timeout: Option<Duration>,
+        certificate: Option<Vec<u8>>,
+        pinned: Option<&[u8]>,
+        max_packet_size: usize,
+    ) -> Harness {
+        let registry_dir = tempfile::TempDir::new().unwrap();
+        let mut dm = DeviceManager::new(registry_dir.path().join("registry.json")).unwrap();
+        let connections = Arc::new(RwLock::new(HashMap::new()));
+        let (event_tx, events) = mpsc::unbounded_channel();
+        let sent = Arc::new(Mutex::new(Vec::new()));
+
+        let remote = DeviceInfo::new("Stalled Phone", DeviceType::Phone, 1716);
+        let local = DeviceInfo::new("Desktop", DeviceType::Desktop, 1716);
+
+        if let Some(pinned) = pinned {
+            dm.add_device(Device::from_discovery(remote.clone()));
+            dm.mark_paired(
+                &remote.device_id,
+                CertificateInfo::calculate_fingerprint(pinned),
+            )
+            .unwrap();
+        }
+        let device_manager = Arc::new(RwLock::new(dm));
+        let mut heartbeats = HeartbeatIntervals::new();
+        heartbeats.set_override(&remote.device_id, Some(MOCK_KEEPALIVE_INTERVAL));
+
+        ConnectionManager::spawn_connection_handler(
+            MockConnection {
+                period,
+                sent: sent.clone(),
+                certificate,
+            },
+            "192.168.1.50:1716".parse().unwrap(),
+            Arc::new(local),
+            event_tx,
+            connections.clone(),
+            device_manager.clone(),
+            Some(remote.to_identity_packet()),
+            Arc::new(RwLock::new(HashMap::new())),
+            Arc::new(RwLock::new(heartbeats)),
+            heartbeat_timeout,
+            CompressionConfig::default(),
+            max_packet_size,
+        );
+
+        Harness {
+            device_id: remote.device_id,
+            events,
+            connections,
+            device_manager,
+            sent,
+            _registry_dir: registry_dir,
+        }
+    }
+
+    /// Wait for the first event that isn't a connect or received packet
+    async fn next_lifecycle_event(
+        events: &mut mpsc::UnboundedReceiver<ConnectionEvent>,
+        wait: Duration,
+    ) -> Option<ConnectionEvent> {
+        tokio::time::timeout(wait, async {
+            while let Some(event) = events.recv().await {
+                match event {
+                    ConnectionEvent::Connected { .. } | ConnectionEvent::PacketReceived { .. } => {}
+                    other => return Some(other),
+                }
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    #[tokio::test]
+    async fn test_stalled_connection_times_out() {
+        let timeout = Duration::from_millis(200);
+        let mut harness = spawn_mock(None, Some(timeout));
+
+        let event = next_lifecycle_event(&mut harness.events, Duration::from_secs(5)).await;
+        match event {
+            Some(ConnectionEvent::Timeout { device_id, idle }) => {
+                assert_eq!(device_id, harness.device_id);
+                assert!(idle >= timeout);
+            }
+            other => panic!("expected timeout event, got {:?}", other),
+        }
+
+        // The keepalive ping went out before the link was declared dead
+        assert!(!harness.sent.lock().unwrap().is_empty());
+
+        // Torn down so reconnection can take over
+        assert!(!harness
+            .connections
+            .read()
+            .await
+            .contains_key(&harness.device_id));
+        let dm = harness.device_manager.read().await;
+        assert!(!dm.get_device(&harness.device_id).unwrap().is_connected());
+    }
+
+    #[tokio::test]
+    async fn test_pinned_certificate_mismatch_refused() {
+        let paired = CertificateInfo::generate("phone").unwrap().certificate;
+        let impostor = CertificateInfo::generate("phone").unwrap().certificate;
+        let mut harness = spawn_mock_with_certificate(None, None, Some(impostor), Some(&paired));
+
+        let event = next_lifecycle_event(&mut harness.events, Duration::from_secs(5)).await;
+        match event {
+            Some(ConnectionEvent::ConnectionError { device_id, .. }) => {
+                assert_eq!(device_id.as_deref(), Some(harness.device_id.as_str()));
+            }
+            other => panic!("expected connection error, got {:?}", other),
+        }
+        assert!(!harness
+            .connections
+            .read()
+            .await
+            .contains_key(&harness.device_id));
+        let dm = harness.device_manager.read().await;
+        assert!(!dm.get_device(&harness.device_id).unwrap().is_connected());
+    }
+
+    #[tokio::test]
+    async fn test_pinned_certificate_match_accepted() {
+        let paired = CertificateInfo::generate("phone").unwrap().certificate;
+        let mut harness =
+            spawn_mock_with_certificate(None, None, Some(paired.clone()), Some(&paired));
+
+        match harness.events.recv().await {
+            Some(ConnectionEvent::Connected { device_id, .. }) => {
+                assert_eq!(device_id, harness.device_id);
+            }
+            other => panic!("expected connected event, got {:?}", other),
+        }
+        assert!(harness
+            .connections
+            .read()
+            .await
+            .contains_key(&harness.device_id));
+    }
+
+    #[tokio::test]
+    async fn test_unpaired_peer_certificate_kept() {
+        let certificate = CertificateInfo::generate("phone").unwrap().certificate;
+        let mut harness = spawn_mock_with_certificate(None, None, Some(certificate.clone()), None);
+
+        assert!(matches!(
+            harness.events.recv().await,
+            Some(ConnectionEvent::Connected { .. })
+        ));
+        let dm = harness.device_manager.read().await;
+        let device = dm.get_device(&harness.device_id).unwrap();
+        assert!(!device.is_paired());
+        assert_eq!(device.certificate_data, Some(certificate));
+    }
+
+    #[tokio::test]
+    async fn test_unpair_drops_session() {
+        let registry_dir = tempfile::TempDir::new().unwrap();
+        let cert_dir = tempfile::TempDir::new().unwrap();
+        let device_manager = Arc::new(RwLock::new(
+            DeviceManager::new(registry_dir.path().join("registry.json")).unwrap(),
+        ));
+        let remote = DeviceInfo::new("Phone", DeviceType::Phone, 1716);
+        let local = DeviceInfo::new("Desktop", DeviceType::Desktop, 1716);
+
+        let manager = ConnectionManager::new(
+            CertificateInfo::generate(&local.device_id).unwrap(),
+            local.clone(),
+            device_manager.clone(),
+            ConnectionConfig::default(),
+        )
+        .unwrap();
+        let mut events = manager.subscribe().await;
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        ConnectionManager::spawn_connection_handler(
+            MockConnection {
+                period: None,
+                sent: sent.clone(),
+                certificate: None,
+            },
+            "192.168.1.50:1716".parse().unwrap(),
+            manager.device_info.clone(),
+            manager.event_tx.clone(),
+            manager.connections.clone(),
+            device_manager.clone(),
+            Some(remote.to_identity_packet()),
+            manager.last_connection_time.clone(),
+            manager.heartbeats.clone(),
+            None,
+            CompressionConfig::default(),
+            DEFAULT_MAX_PACKET_SIZE,
+        );
+        assert!(matches!(
+            events.recv().await,
+            Some(ConnectionEvent::Connected { .. })
+        ));
+
+        let connection_manager = Arc::new(RwLock::new(manager));
+        let mut pairing = PairingService::new(
+            local.device_id.clone(),
+            PairingConfig {
+                cert_dir: cert_dir.path().to_path_buf(),
+                timeout: Duration::from_secs(30),
+            },
+        )
+        .unwrap();
+        pairing.set_connection_manager(connection_manager.clone());
+        let mut pairing_events = pairing.subscribe().await;
+
+        pairing.unpair(&remote.device_id).await.unwrap();
+
+        // Unpairing waits for the connection task, so by now the unpair packet
+        // is written and the device is marked disconnected
+        assert!(sent
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|p| p.is_type("cconnect.pair") && p.body["pair"] == false));
+        assert!(
+            !connection_manager
+                .read()
+                .await
+                .has_connection(&remote.device_id)
+                .await
+        );
+        assert!(!device_manager
+            .read()
+            .await
+            .get_device(&remote.device_id)
+            .unwrap()
+            .is_connected());
+
+        // The daemon stops the device's plugins on a non-reconnect disconnect
+        match next_lifecycle_event(&mut events, Duration::from_secs(5)).await {
+            Some(ConnectionEvent::Disconnected {
+                device_id,
+                reconnect,
+                ..
+            }) => {
+                assert_eq!(device_id, remote.device_id);
+                assert!(!reconnect);
+            }
+            other => panic!("expected disconnect, got {:?}", other),
+        }
+        assert!(matches!(
+            pairing_events.recv().await,
+            Some(PairingEvent::DeviceUnpaired { device_id }) if device_id == remote.device_id
+        ));
 
-        // Note: We can't update the task handle in ActiveConnection here
-        // because we just moved it into the spawn. This is a limitation
-        // of the current design. We could use Arc<Mutex<JoinHandle>> but
-        // it's not necessary since we can abort via the command channel.
+        // Unpairing again succeeds without a connection
+        pairing.unpair(&remote.device_id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_traffic_keeps_connection_alive() {
+        let mut harness = spawn_mock(
+            Some(Duration::from_millis(50)),
+            Some(Duration::from_millis(200)),
+        );
+
+        let event = next_lifecycle_event(&mut harness.events, Duration::from_millis(600)).await;
+        assert!(event.is_none(), "unexpected event {:?}", event);
+        assert!(harness
+            .connections
+            .read()
+            .await
+            .contains_key(&harness.device_id));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_packet_closes_connection() {
+        // Even the peer's keepalive pings are over this limit
+        let mut harness =
+            spawn_mock_with_limit(Some(Duration::from_millis(20)), None, None, None, 16);
+
+        match next_lifecycle_event(&mut harness.events, Duration::from_secs(5)).await {
+            Some(ConnectionEvent::ConnectionError { device_id, message }) => {
+                assert_eq!(device_id, Some(harness.device_id.clone()));
+                assert!(message.contains("too large"), "{}", message);
+            }
+            other => panic!("expected connection error, got {:?}", other),
+        }
+        assert!(matches!(
+            next_lifecycle_event(&mut harness.events, Duration::from_secs(5)).await,
+            Some(ConnectionEvent::Disconnected {
+                reconnect: false,
+                ..
+            })
+        ));
+        assert!(!harness
+            .connections
+            .read()
+            .await
+            .contains_key(&harness.device_id));
+    }
+
+    /// Compressor for a peer that advertised compression support
+    fn negotiated_compressor() -> PacketCompressor {
+        let mut identity = DeviceInfo::new("Phone", DeviceType::Phone, 1716).to_identity_packet();
+        CompressionConfig::default().advertise(&mut identity);
+        PacketCompressor::negotiate(&CompressionConfig::default(), &identity)
+    }
+
+    #[tokio::test]
+    async fn test_accept_packet_rejects_undecodable_body() {
+        let compressor = negotiated_compressor();
+        let packet = Packet::new(
+            "cconnect.filesync.index",
+            serde_json::json!({ "compression": "zstd", "compressedBody": "not base64!" }),
+        );
+        assert!(matches!(
+            accept_packet(
+                packet.to_core_packet(),
+                &compressor,
+                DEFAULT_MAX_PACKET_SIZE
+            )
+            .await,
+            Err(ProtocolError::MalformedFrame(_))
+        ));
+
+        let ping = Packet::new("cconnect.ping", serde_json::json!({}));
+        let accepted = accept_packet(ping.to_core_packet(), &compressor, DEFAULT_MAX_PACKET_SIZE)
+            .await
+            .unwrap();
+        assert!(accepted.is_type("cconnect.ping"));
+    }
+
+    #[tokio::test]
+    async fn test_accept_packet_caps_decompressed_size() {
+        let max_packet_size = 4096;
+        let compressor = negotiated_compressor().with_max_body_size(max_packet_size);
+        let packet = Packet::new(
+            "cconnect.filesync.index",
+            serde_json::json!({ "files": "a".repeat(64 * 1024) }),
+        );
+        let compressed = compressor.compress(packet).unwrap().to_core_packet();
+
+        // Small on the wire, too large once expanded
+        assert!(matches!(
+            accept_packet(compressed.clone(), &compressor, max_packet_size).await,
+            Err(ProtocolError::PayloadTooLarge { .. })
+        ));
+
+        // Peers that didn't negotiate compression get no decompression at all
+        let accepted = accept_packet(compressed, &PacketCompressor::plain(), max_packet_size)
+            .await
+            .unwrap();
+        assert!(accepted.body.get("compressedBody").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_timeout_disabled() {
+        assert!(ConnectionConfig::default().heartbeat_timeout.is_none());
+        let mut harness = spawn_mock(None, None);
+
+        let event = next_lifecycle_event(&mut harness.events, Duration::from_millis(300)).await;
+        assert!(event.is_none(), "unexpected event {:?}", event);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_timeout_outlasts_keepalives() {
+        // Shorter than the keepalive interval, so it is stretched
+        let mut harness = spawn_mock(None, Some(Duration::from_millis(10)));
+
+        let event = next_lifecycle_event(&mut harness.events, Duration::from_secs(5)).await;
+        match event {
+            Some(ConnectionEvent::Timeout { idle, .. }) => {
+                assert!(idle >= MOCK_KEEPALIVE_INTERVAL * MISSED_KEEPALIVES);
+            }
+            other => panic!("expected timeout event, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_pings_carry_nonce() {
+        let harness = spawn_mock(None, None);
+        tokio::time::sleep(MOCK_KEEPALIVE_INTERVAL * 3).await;
+
+        let sent = harness.sent.lock().unwrap();
+        let nonces: Vec<u64> = sent
+            .iter()
+            .filter(|p| p.packet_type == "cconnect.ping")
+            .map(|p| {
+                assert_eq!(p.body["keepalive"], true);
+                p.body["nonce"].as_u64().unwrap()
+            })
+            .collect();
+        assert!(nonces.len() >= 2);
+        // Apart from the ping plugin's timing nonces, and distinct per ping
+        assert!(nonces.iter().all(|&nonce| nonce >= KEEPALIVE_NONCE_BASE));
+        assert!(nonces.windows(2).all(|pair| pair[0] != pair[1]));
+    }
+
+    #[tokio::test]
+    async fn test_latency_follows_keepalive_round_trip() {
+        let registry_dir = tempfile::TempDir::new().unwrap();
+        let device_manager = Arc::new(RwLock::new(
+            DeviceManager::new(registry_dir.path().join("registry.json")).unwrap(),
+        ));
+        let remote = DeviceInfo::new("Phone", DeviceType::Phone, 1716);
+        let local = DeviceInfo::new("Desktop", DeviceType::Desktop, 1716);
+        let connections = Arc::new(RwLock::new(HashMap::new()));
+        let heartbeats = Arc::new(RwLock::new(HeartbeatIntervals::new()));
+        let (event_tx, mut events) = mpsc::unbounded_channel();
+
+        // The first keepalive goes out right away and is echoed 100ms later
+        ConnectionManager::spawn_connection_handler(
+            EchoConnection {
+                delay: Duration::from_millis(100),
+                echoes: Default::default(),
+            },
+            "192.168.1.50:1716".parse().unwrap(),
+            Arc::new(local),
+            event_tx,
+            connections.clone(),
+            device_manager,
+            Some(remote.to_identity_packet()),
+            Arc::new(RwLock::new(HashMap::new())),
+            heartbeats.clone(),
+            None,
+            CompressionConfig::default(),
+            DEFAULT_MAX_PACKET_SIZE,
+        );
+        assert!(matches!(
+            events.recv().await,
+            Some(ConnectionEvent::Connected { .. })
+        ));
+        assert_eq!(
+            heartbeats.read().await.active_latency(&remote.device_id),
+            Some(LatencyCategory::Low)
+        );
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while heartbeats.read().await.active_latency(&remote.device_id)
+                != Some(LatencyCategory::High)
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("latency not derived from the keepalive round trip");
+        assert_eq!(
+            heartbeats.read().await.interval_for(&remote.device_id),
+            Some(LatencyCategory::High.heartbeat_interval())
+        );
+
+        // Closing forgets the measured latency, not the typical one
+        let _ = connections.read().await[&remote.device_id]
+            .command_tx
+            .send(ConnectionCommand::Close);
+        assert!(matches!(
+            next_lifecycle_event(&mut events, Duration::from_secs(5)).await,
+            Some(ConnectionEvent::Disconnected { .. })
+        ));
+        assert_eq!(
+            heartbeats.read().await.active_latency(&remote.device_id),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compression_recorded_per_connection() {
+        let registry_dir = tempfile::TempDir::new().unwrap();
+        let device_manager = Arc::new(RwLock::new(
+            DeviceManager::new(registry_dir.path().join("registry.json")).unwrap(),
+        ));
+        let remote = DeviceInfo::new("Phone", DeviceType::Phone, 1716);
+        let local = DeviceInfo::new("Desktop", DeviceType::Desktop, 1716);
+        let manager = ConnectionManager::new(
+            CertificateInfo::generate(&local.device_id).unwrap(),
+            local,
+            device_manager.clone(),
+            ConnectionConfig::default(),
+        )
+        .unwrap();
+        let mut events = manager.subscribe().await;
+
+        let mut identity = remote.to_identity_packet();
+        CompressionConfig::default().advertise(&mut identity);
+        let compressor = PacketCompressor::negotiate(&manager.config.compression, &identity);
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        ConnectionManager::spawn_connection_handler(
+            MockConnection {
+                period: None,
+                sent: sent.clone(),
+                certificate: None,
+            },
+            "192.168.1.50:1716".parse().unwrap(),
+            manager.device_info.clone(),
+            manager.event_tx.clone(),
+            manager.connections.clone(),
+            device_manager,
+            Some(identity),
+            manager.last_connection_time.clone(),
+            manager.heartbeats.clone(),
+            None,
+            manager.config.compression,
+            manager.config.max_packet_size,
+        );
+        assert!(matches!(
+            events.recv().await,
+            Some(ConnectionEvent::Connected { .. })
+        ));
+        assert_eq!(
+            manager.connection_compression(&remote.device_id).await,
+            Some(CompressionAlgorithm::Zstd)
+        );
+
+        let index: Vec<String> = (0..5000)
+            .map(|i| format!("Music/track-{}.flac", i))
+            .collect();
+        let packet = Packet::new(
+            "cconnect.filesync.index",
+            serde_json::json!({ "files": index }),
+        );
+        manager
+            .send_packet(&remote.device_id, &packet)
+            .await
+            .unwrap();
+
+        let on_wire = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(packet) = sent
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .find(|p| p.packet_type == "cconnect.filesync.index")
+                {
+                    return packet.clone();
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_ne!(on_wire.body, packet.body);
+        assert_eq!(compressor.decompress(on_wire).unwrap().body, packet.body);
     }
 }
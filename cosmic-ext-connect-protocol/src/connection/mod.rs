@@ -7,4 +7,4 @@ pub mod events;
 pub mod manager;
 
 pub use events::ConnectionEvent;
-pub use manager::{ConnectionConfig, ConnectionManager};
+pub use manager::{ConnectionConfig, ConnectionManager, PacketConnection};
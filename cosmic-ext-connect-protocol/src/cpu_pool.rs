@@ -0,0 +1,176 @@
+//! Bounded Executor for CPU-Heavy Work
+//!
+//! Hashing large files or scanning `/proc` on a runtime worker thread stalls
+//! every connection scheduled on that thread. Such work runs on tokio's
+//! blocking thread pool instead, with a semaphore capping how many jobs run at
+//! once so a large sync cannot occupy every core.
+//!
+//! Plugins use the process-wide [`global`] pool. Its concurrency cap is set
+//! once at startup with [`configure_global`].
+
+use crate::{ProtocolError, Result};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::Semaphore;
+use tracing::debug;
+
+/// Default maximum number of CPU-heavy jobs running at once
+pub const DEFAULT_MAX_CONCURRENT: usize = 2;
+
+static GLOBAL_POOL: OnceLock<CpuPool> = OnceLock::new();
+
+/// Counters shared by a pool and its running jobs
+#[derive(Debug, Default)]
+struct Counters {
+    dispatched: AtomicU64,
+    active: AtomicUsize,
+    peak_active: AtomicUsize,
+}
+
+/// Snapshot of a pool's counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuPoolStats {
+    /// Concurrency cap
+    pub max_concurrent: usize,
+
+    /// Jobs dispatched to the blocking pool since creation
+    pub dispatched: u64,
+
+    /// Jobs currently running
+    pub active: usize,
+
+    /// Largest number of jobs that ran at once
+    pub peak_active: usize,
+}
+
+/// Runs CPU-heavy closures off the async runtime with a concurrency cap
+#[derive(Debug, Clone)]
+pub struct CpuPool {
+    max_concurrent: usize,
+    permits: Arc<Semaphore>,
+    counters: Arc<Counters>,
+}
+
+impl CpuPool {
+    /// Create a pool running at most `max_concurrent` jobs at once
+    ///
+    /// A cap of 0 is raised to 1.
+    pub fn new(max_concurrent: usize) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            max_concurrent,
+            permits: Arc::new(Semaphore::new(max_concurrent)),
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    /// Run `job` on the blocking thread pool
+    ///
+    /// Waits for a free slot if the cap is reached.
+    pub async fn run<F, T>(&self, job: F) -> Result<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| ProtocolError::invalid_state("CPU pool closed"))?;
+
+        let counters = self.counters.clone();
+        counters.dispatched.fetch_add(1, Ordering::Relaxed);
+
+        tokio::task::spawn_blocking(move || {
+            let active = counters.active.fetch_add(1, Ordering::SeqCst) + 1;
+            counters.peak_active.fetch_max(active, Ordering::SeqCst);
+
+            let output = job();
+
+            counters.active.fetch_sub(1, Ordering::SeqCst);
+            drop(permit);
+            output
+        })
+        .await
+        .map_err(|e| ProtocolError::Plugin(format!("CPU-heavy task failed: {}", e)))
+    }
+
+    /// Current counters
+    pub fn stats(&self) -> CpuPoolStats {
+        CpuPoolStats {
+            max_concurrent: self.max_concurrent,
+            dispatched: self.counters.dispatched.load(Ordering::Relaxed),
+            active: self.counters.active.load(Ordering::SeqCst),
+            peak_active: self.counters.peak_active.load(Ordering::SeqCst),
+        }
+    }
+}
+
+/// Set the concurrency cap of the global pool
+///
+/// Only takes effect before the global pool is first used. Returns `false`
+/// if it was already created.
+pub fn configure_global(max_concurrent: usize) -> bool {
+    let configured = GLOBAL_POOL.set(CpuPool::new(max_concurrent)).is_ok();
+    if configured {
+        debug!("CPU pool limited to {} concurrent jobs", max_concurrent);
+    }
+    configured
+}
+
+/// The process-wide pool for CPU-heavy plugin work
+pub fn global() -> &'static CpuPool {
+    GLOBAL_POOL.get_or_init(|| CpuPool::new(DEFAULT_MAX_CONCURRENT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_job_runs_off_async_worker() {
+        let pool = CpuPool::new(2);
+        let reactor_thread = std::thread::current().id();
+
+        let job_thread = pool.run(|| std::thread::current().id()).await.unwrap();
+        pool.run(|| ()).await.unwrap();
+
+        assert_ne!(job_thread, reactor_thread);
+        assert_eq!(pool.stats().dispatched, 2);
+        assert_eq!(pool.stats().active, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_concurrency_cap_respected() {
+        let pool = CpuPool::new(2);
+
+        let jobs: Vec<_> = (0..6)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move {
+                    pool.run(|| std::thread::sleep(Duration::from_millis(30)))
+                        .await
+                })
+            })
+            .collect();
+        for job in jobs {
+            job.await.unwrap().unwrap();
+        }
+
+        let stats = pool.stats();
+        assert_eq!(stats.dispatched, 6);
+        assert!(
+            stats.peak_active <= 2,
+            "peak {} over cap",
+            stats.peak_active
+        );
+        assert_eq!(stats.active, 0);
+    }
+
+    #[test]
+    fn test_zero_cap_raised_to_one() {
+        assert_eq!(CpuPool::new(0).stats().max_concurrent, 1);
+    }
+}
@@ -15,15 +15,36 @@
 //! The `DeviceManager` maintains a registry of all known devices and their states.
 //! It provides methods for adding, removing, and querying devices.
 //!
+//! ## Capability Negotiation
+//!
+//! When a device connects, [`Device::negotiate_capabilities`] compares our
+//! capabilities with the ones in its identity packet. The result is kept for
+//! the session, so packets the device would ignore are not sent and the UI can
+//! hide actions the device doesn't support. Plugins are negotiated per device
+//! by [`PluginManager`](crate::PluginManager) from the same capability lists.
+//!
+//! ## Certificate Pinning
+//!
+//! Pairing stores the SHA-256 fingerprint of the device's certificate. When a
+//! paired device reconnects, [`DeviceManager::verify_certificate`] checks the
+//! certificate it presents against that fingerprint, so another device can't
+//! take over a paired device's ID.
+//!
 //! ## Persistence
 //!
 //! Device information is persisted to disk to remember paired devices
 //! across application restarts.
 
-use crate::{DeviceInfo, PairingStatus, ProtocolError, Result, TransportAddress};
+use crate::auth::AuthError;
+use crate::capabilities::normalize_capability;
+use crate::{
+    CapabilityDiff, CertificateInfo, DeviceInfo, PairingStatus, ProtocolError, Result,
+    TransportAddress, VersionCompat,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, info, warn};
@@ -91,6 +112,11 @@ pub struct Device {
     /// Certificate data (DER-encoded, for TLS validation)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub certificate_data: Option<Vec<u8>>,
+
+    /// Capabilities negotiated on the current connection, `None` until the
+    /// device identified itself or if it advertised no capabilities
+    #[serde(skip)]
+    pub negotiated_capabilities: Option<CapabilityDiff>,
 }
 
 impl Device {
@@ -107,6 +133,7 @@ impl Device {
             port: None,
             certificate_fingerprint: None,
             certificate_data: None,
+            negotiated_capabilities: None,
         }
     }
 
@@ -127,6 +154,7 @@ impl Device {
             port: None,
             certificate_fingerprint: None,
             certificate_data: None,
+            negotiated_capabilities: None,
         }
     }
 
@@ -227,6 +255,162 @@ impl Device {
         self.update_last_seen();
     }
 
+    /// Negotiate capabilities with the device's advertised ones
+    ///
+    /// `our_incoming` and `our_outgoing` are this desktop's capabilities. A
+    /// device that advertised no capabilities at all is treated as supporting
+    /// everything, like [`PluginNegotiation`](crate::PluginNegotiation) does.
+    pub fn negotiate_capabilities(
+        &mut self,
+        our_incoming: &[String],
+        our_outgoing: &[String],
+    ) -> Option<&CapabilityDiff> {
+        self.negotiated_capabilities = if self.info.incoming_capabilities.is_empty()
+            && self.info.outgoing_capabilities.is_empty()
+        {
+            None
+        } else {
+            Some(CapabilityDiff::compute(
+                our_incoming,
+                our_outgoing,
+                &self.info.incoming_capabilities,
+                &self.info.outgoing_capabilities,
+            ))
+        };
+        self.negotiated_capabilities.as_ref()
+    }
+
+    /// Whether the device ignores a packet type we send
+    ///
+    /// Only packet types we advertise can be ignored; protocol packets such as
+    /// pairing and identity are never filtered.
+    pub fn ignores_packet(&self, packet_type: &str) -> bool {
+        let packet_type = normalize_capability(packet_type);
+        self.negotiated_capabilities
+            .as_ref()
+            .is_some_and(|diff| diff.peer_missing.iter().any(|c| c == packet_type))
+    }
+
+    /// Protocol features usable with the device
+    ///
+    /// Negotiated from the protocol version of its latest identity packet.
+    pub fn version_compat(&self) -> VersionCompat {
+        VersionCompat::negotiate(self.info.protocol_version)
+    }
+
+    /// Protocol version used with the device, the lower of ours and its own
+    pub fn effective_protocol_version(&self) -> u32 {
+        self.version_compat().effective_version()
+    }
+
+    /// Revoke trust in the device
+    ///
+    /// Clears the pinned certificate fingerprint and stored certificate, so the
+    /// device has to go through a fresh pairing request before it is trusted
+    /// again. Returns `false` if the device was not paired (nothing changes).
+    pub fn unpair(&mut self) -> bool {
+        let was_paired = self.is_paired()
+            || self.certificate_fingerprint.is_some()
+            || self.certificate_data.is_some();
+
+        self.pairing_status = PairingStatus::Unpaired;
+        self.is_trusted = false;
+        self.certificate_fingerprint = None;
+        self.certificate_data = None;
+
+        was_paired
+    }
+
+    /// Check a presented certificate against the pinned fingerprint
+    ///
+    /// Devices without a pinned fingerprint (not paired yet) accept any
+    /// certificate. Returns [`ProtocolError::Authentication`] if the
+    /// certificate's SHA-256 fingerprint differs from the pinned one.
+    pub fn verify_certificate(&self, certificate: &[u8]) -> Result<()> {
+        let Some(pinned) = self.pinned_fingerprint() else {
+            return Ok(());
+        };
+
+        let presented = CertificateInfo::calculate_fingerprint(certificate);
+        if normalize_fingerprint(pinned) == normalize_fingerprint(&presented) {
+            Ok(())
+        } else {
+            warn!(
+                "Device {} presented certificate {} but is pinned to {}",
+                self.id(),
+                presented,
+                pinned
+            );
+            Err(AuthError::CertificateMismatch(self.id().to_string()).into())
+        }
+    }
+
+    /// Fingerprint the device's certificate is pinned to, if it is paired
+    pub fn pinned_fingerprint(&self) -> Option<&str> {
+        if self.is_paired() {
+            self.certificate_fingerprint.as_deref()
+        } else {
+            None
+        }
+    }
+
+    /// Update the advertised TCP port from the peer's latest identity packet
+    ///
+    /// Returns `true` if the port changed.
+    pub fn update_tcp_port(&mut self, tcp_port: u16) -> bool {
+        if self.info.tcp_port == tcp_port {
+            return false;
+        }
+        info!(
+            "Device {} ({}) advertised TCP port changed {} -> {}",
+            self.id(),
+            self.name(),
+            self.info.tcp_port,
+            tcp_port
+        );
+        self.info.tcp_port = tcp_port;
+        true
+    }
+
+    /// Socket addresses to try, in order, for the next outbound TCP connection
+    ///
+    /// Uses the port from the peer's latest identity packet, or the known
+    /// defaults if it did not advertise one. Empty if the host is unknown.
+    pub fn connect_addresses(&self) -> Vec<SocketAddr> {
+        match self
+            .host
+            .as_deref()
+            .and_then(|host| host.parse::<IpAddr>().ok())
+        {
+            Some(ip) => self
+                .info
+                .tcp_port_candidates()
+                .into_iter()
+                .map(|port| SocketAddr::new(ip, port))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Transport addresses to try for the next outbound connection
+    ///
+    /// The TCP candidates from [`connect_addresses`](Self::connect_addresses),
+    /// or the Bluetooth address if the device was last discovered over
+    /// Bluetooth. Empty if the device has not been seen.
+    pub fn transport_addresses(&self) -> Vec<TransportAddress> {
+        let tcp = self.connect_addresses();
+        if !tcp.is_empty() {
+            return tcp.into_iter().map(TransportAddress::Tcp).collect();
+        }
+        match (&self.host, self.port) {
+            (Some(address), None) => vec![TransportAddress::Bluetooth {
+                address: address.clone(),
+                service_uuid: Some(crate::transport::CCONNECT_SERVICE_UUID),
+            }],
+            _ => Vec::new(),
+        }
+    }
+
     /// Check if device has a specific incoming capability
     pub fn has_incoming_capability(&self, capability: &str) -> bool {
         self.info
@@ -252,6 +436,15 @@ impl Device {
     }
 }
 
+/// Hex digits of a fingerprint, upper-cased and without separators
+fn normalize_fingerprint(fingerprint: &str) -> String {
+    fingerprint
+        .chars()
+        .filter(char::is_ascii_hexdigit)
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
 /// Device manager for tracking multiple devices
 pub struct DeviceManager {
     /// Map of device ID to device
@@ -365,12 +558,20 @@ impl DeviceManager {
 
         // Extract connection info based on transport
         let (host, port) = match &address {
-            TransportAddress::Tcp(addr) => (Some(addr.ip().to_string()), Some(info.tcp_port)),
+            TransportAddress::Tcp(addr) => (
+                Some(addr.ip().to_string()),
+                info.tcp_port_candidates().first().copied(),
+            ),
             TransportAddress::Bluetooth { address, .. } => (Some(address.clone()), None),
+            TransportAddress::Quic(addr) => (
+                Some(addr.ip().to_string()),
+                info.tcp_port_candidates().first().copied(),
+            ),
         };
 
         if let Some(device) = self.devices.get_mut(&device_id) {
             // Update existing device
+            device.update_tcp_port(info.tcp_port);
             device.info = info;
             device.host = host;
             device.port = port;
@@ -442,6 +643,18 @@ impl DeviceManager {
         Ok(())
     }
 
+    /// Update a device's advertised TCP port
+    ///
+    /// Returns `true` if the port changed.
+    pub fn update_tcp_port(&mut self, device_id: &str, tcp_port: u16) -> Result<bool> {
+        let device = self
+            .devices
+            .get_mut(device_id)
+            .ok_or_else(|| ProtocolError::DeviceNotFound(device_id.to_string()))?;
+
+        Ok(device.update_tcp_port(tcp_port))
+    }
+
     /// Mark device as connected
     pub fn mark_connected(&mut self, device_id: &str, host: String, port: u16) -> Result<()> {
         let device = self
@@ -486,6 +699,48 @@ impl DeviceManager {
         Ok(())
     }
 
+    /// Revoke trust in a device, see [`Device::unpair`]
+    ///
+    /// Returns `false` if the device was not paired.
+    pub fn unpair(&mut self, device_id: &str) -> Result<bool> {
+        let device = self
+            .devices
+            .get_mut(device_id)
+            .ok_or_else(|| ProtocolError::DeviceNotFound(device_id.to_string()))?;
+
+        Ok(device.unpair())
+    }
+
+    /// Check the certificate presented by a connecting device
+    ///
+    /// Unknown and unpaired devices are accepted. A paired device must present
+    /// the certificate it paired with; a missing certificate is rejected as
+    /// well. See [`Device::verify_certificate`].
+    pub fn verify_certificate(&self, device_id: &str, certificate: Option<&[u8]>) -> Result<()> {
+        let Some(device) = self.devices.get(device_id) else {
+            return Ok(());
+        };
+        match certificate {
+            Some(certificate) => device.verify_certificate(certificate),
+            None if device.pinned_fingerprint().is_some() => {
+                Err(AuthError::CertificateMismatch(device_id.to_string()).into())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Paired device whose pinned certificate is `certificate`
+    ///
+    /// Identifies peers on links that carry no identity packet.
+    pub fn paired_device_for_certificate(&self, certificate: &[u8]) -> Option<&Device> {
+        let presented = normalize_fingerprint(&CertificateInfo::calculate_fingerprint(certificate));
+        self.paired_devices().find(|device| {
+            device
+                .pinned_fingerprint()
+                .is_some_and(|pinned| normalize_fingerprint(pinned) == presented)
+        })
+    }
+
     /// Save device registry to disk
     pub fn save_registry(&self) -> Result<()> {
         let json = serde_json::to_string_pretty(&self.devices)?;
@@ -813,4 +1068,239 @@ mod tests {
         assert!(manager.has_device("new_uuid"));
         assert!(!manager.has_device("old_uuid"));
     }
+
+    #[test]
+    fn test_changed_advertised_port_used_for_next_connection() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry_path = temp_dir.path().join("registry.json");
+        let mut manager = DeviceManager::new(&registry_path).unwrap();
+
+        let info = DeviceInfo::with_id("phone_id", "My Phone", DeviceType::Phone, 1716);
+        let addr = TransportAddress::Tcp("192.168.1.50:1716".parse().unwrap());
+        manager.update_from_discovery(info, addr);
+        assert_eq!(
+            manager.get_device("phone_id").unwrap().connect_addresses(),
+            vec!["192.168.1.50:1716".parse::<SocketAddr>().unwrap()]
+        );
+
+        // Incoming connection records the peer's ephemeral source port
+        manager
+            .mark_connected("phone_id", "192.168.1.50".to_string(), 52314)
+            .unwrap();
+        assert_eq!(
+            manager.get_device("phone_id").unwrap().connect_addresses(),
+            vec!["192.168.1.50:1716".parse::<SocketAddr>().unwrap()]
+        );
+
+        // Peer restarts and advertises a different port
+        let info = DeviceInfo::with_id("phone_id", "My Phone", DeviceType::Phone, 1814);
+        let addr = TransportAddress::Tcp("192.168.1.50:1814".parse().unwrap());
+        manager.update_from_discovery(info, addr);
+
+        let device = manager.get_device("phone_id").unwrap();
+        assert_eq!(device.info.tcp_port, 1814);
+        assert_eq!(
+            device.connect_addresses(),
+            vec!["192.168.1.50:1814".parse::<SocketAddr>().unwrap()]
+        );
+
+        // Post-TLS identity advertises yet another port
+        assert!(manager.update_tcp_port("phone_id", 1739).unwrap());
+        assert!(!manager.update_tcp_port("phone_id", 1739).unwrap());
+        assert_eq!(
+            manager.get_device("phone_id").unwrap().connect_addresses()[0].port(),
+            1739
+        );
+    }
+
+    #[test]
+    fn test_connect_addresses_without_advertised_port() {
+        let mut device = Device::from_discovery(DeviceInfo::with_id(
+            "phone_id",
+            "My Phone",
+            DeviceType::Phone,
+            0,
+        ));
+        assert!(device.connect_addresses().is_empty());
+
+        device.host = Some("192.168.1.50".to_string());
+        let ports: Vec<u16> = device
+            .connect_addresses()
+            .iter()
+            .map(|addr| addr.port())
+            .collect();
+        assert_eq!(ports, crate::DEFAULT_TCP_PORTS);
+    }
+
+    #[test]
+    fn test_transport_addresses_follow_discovery() {
+        let temp_dir = TempDir::new().unwrap();
+        let registry_path = temp_dir.path().join("registry.json");
+        let mut manager = DeviceManager::new(&registry_path).unwrap();
+        let info = DeviceInfo::with_id("phone_id", "My Phone", DeviceType::Phone, 1716);
+
+        manager.update_from_discovery(
+            info.clone(),
+            TransportAddress::Tcp("192.168.1.50:1716".parse().unwrap()),
+        );
+        assert_eq!(
+            manager
+                .get_device("phone_id")
+                .unwrap()
+                .transport_addresses(),
+            vec![TransportAddress::Tcp("192.168.1.50:1716".parse().unwrap())]
+        );
+
+        manager.update_from_discovery(
+            info,
+            TransportAddress::Bluetooth {
+                address: "00:11:22:33:44:55".to_string(),
+                service_uuid: None,
+            },
+        );
+        assert_eq!(
+            manager
+                .get_device("phone_id")
+                .unwrap()
+                .transport_addresses(),
+            vec![TransportAddress::Bluetooth {
+                address: "00:11:22:33:44:55".to_string(),
+                service_uuid: Some(crate::transport::CCONNECT_SERVICE_UUID),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_certificate_pinned_at_pairing() {
+        let paired_cert = CertificateInfo::generate("phone_id").unwrap().certificate;
+        let other_cert = CertificateInfo::generate("phone_id").unwrap().certificate;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = DeviceManager::new(temp_dir.path().join("registry.json")).unwrap();
+        manager.add_device(Device::from_discovery(DeviceInfo::with_id(
+            "phone_id",
+            "My Phone",
+            DeviceType::Phone,
+            1716,
+        )));
+
+        // Anything goes before pairing
+        assert!(manager
+            .verify_certificate("phone_id", Some(&other_cert))
+            .is_ok());
+        assert!(manager.verify_certificate("phone_id", None).is_ok());
+        assert!(manager
+            .verify_certificate("unknown", Some(&other_cert))
+            .is_ok());
+
+        // Pinned fingerprints match regardless of case and separators
+        let fingerprint = CertificateInfo::calculate_fingerprint(&paired_cert);
+        manager
+            .mark_paired("phone_id", fingerprint.to_lowercase().replace(':', ""))
+            .unwrap();
+
+        assert!(manager
+            .verify_certificate("phone_id", Some(&paired_cert))
+            .is_ok());
+        assert!(matches!(
+            manager.verify_certificate("phone_id", Some(&other_cert)),
+            Err(ProtocolError::Authentication(AuthError::CertificateMismatch(id))) if id == "phone_id"
+        ));
+        assert!(matches!(
+            manager.verify_certificate("phone_id", None),
+            Err(ProtocolError::Authentication(_))
+        ));
+    }
+
+    #[test]
+    fn test_unpair_revokes_trust() {
+        let cert = CertificateInfo::generate("phone_id").unwrap().certificate;
+        let other_cert = CertificateInfo::generate("phone_id").unwrap().certificate;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = DeviceManager::new(temp_dir.path().join("registry.json")).unwrap();
+        let mut device = Device::from_discovery(DeviceInfo::with_id(
+            "phone_id",
+            "My Phone",
+            DeviceType::Phone,
+            1716,
+        ));
+        device.certificate_data = Some(cert.clone());
+        manager.add_device(device);
+        manager
+            .mark_paired("phone_id", CertificateInfo::calculate_fingerprint(&cert))
+            .unwrap();
+        assert!(manager
+            .verify_certificate("phone_id", Some(&other_cert))
+            .is_err());
+
+        assert!(manager.unpair("phone_id").unwrap());
+
+        let device = manager.get_device("phone_id").unwrap();
+        assert_eq!(device.pairing_status, PairingStatus::Unpaired);
+        assert!(!device.is_trusted);
+        assert!(device.certificate_fingerprint.is_none());
+        assert!(device.certificate_data.is_none());
+        assert!(manager.paired_devices().next().is_none());
+
+        // Nothing pinned anymore: the next connection is treated as a new device
+        assert!(manager
+            .verify_certificate("phone_id", Some(&other_cert))
+            .is_ok());
+
+        // Unpairing again is a no-op
+        assert!(!manager.unpair("phone_id").unwrap());
+        assert!(manager.unpair("unknown").is_err());
+    }
+
+    #[test]
+    fn test_capability_negotiation_intersection() {
+        let caps = |list: &[&str]| list.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let our_incoming = caps(&[
+            "cconnect.battery",
+            "cconnect.ping",
+            "cconnect.share.request",
+        ]);
+        let our_outgoing = caps(&[
+            "cconnect.clipboard",
+            "cconnect.ping",
+            "cconnect.share.request",
+        ]);
+
+        let mut info = create_test_device_info();
+        info.incoming_capabilities = caps(&["kdeconnect.ping", "kdeconnect.share.request"]);
+        info.outgoing_capabilities = caps(&["kdeconnect.battery", "kdeconnect.ping"]);
+        let mut device = Device::from_discovery(info);
+
+        let diff = device
+            .negotiate_capabilities(&our_incoming, &our_outgoing)
+            .unwrap();
+        assert_eq!(diff.peer_consumes, caps(&["ping", "share.request"]));
+        assert_eq!(diff.we_consume, caps(&["battery", "ping"]));
+        assert_eq!(diff.peer_missing, caps(&["clipboard"]));
+        assert!(diff.we_missing.is_empty());
+
+        assert!(device.ignores_packet("cconnect.clipboard"));
+        assert!(!device.ignores_packet("cconnect.ping"));
+        assert!(!device.ignores_packet("cconnect.pair"));
+
+        // A device without advertised capabilities gets everything
+        let mut device = Device::from_discovery(create_test_device_info());
+        assert!(device
+            .negotiate_capabilities(&our_incoming, &our_outgoing)
+            .is_none());
+        assert!(!device.ignores_packet("cconnect.clipboard"));
+    }
+
+    #[test]
+    fn test_effective_protocol_version() {
+        for (advertised, effective) in [(6, 6), (7, 7), (8, 8), (9, 8)] {
+            let mut info = create_test_device_info();
+            info.protocol_version = advertised;
+            let device = Device::from_discovery(info);
+
+            assert_eq!(device.effective_protocol_version(), effective);
+            assert_eq!(device.version_compat().peer_version(), advertised);
+        }
+    }
 }
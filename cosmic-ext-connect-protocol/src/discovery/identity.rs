@@ -0,0 +1,175 @@
+//! Identity Packet Builder
+//!
+//! Builds our identity packet from the registered plugins and checks the
+//! protocol version of the identities peers send.
+//!
+//! [`IdentityPacket::build`] advertises the union of the capabilities of all
+//! registered plugin factories and always stamps [`PROTOCOL_VERSION`], so the
+//! identity can not drift from what the plugin manager actually handles.
+//! [`IdentityPacket::parse`] refuses peers older than
+//! [`MIN_PROTOCOL_VERSION`] with [`ProtocolError::ProtocolVersionMismatch`].
+
+use super::DeviceInfo;
+use crate::plugins::PluginManager;
+use crate::{Packet, ProtocolError, Result, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+
+/// Typed builder and parser for `cconnect.identity` packets
+pub struct IdentityPacket;
+
+impl IdentityPacket {
+    /// Build our identity packet
+    ///
+    /// Takes name, type, id and port from `device_info`; capabilities and the
+    /// protocol version are replaced as described in [`Self::local_info`].
+    pub fn build(device_info: &DeviceInfo, plugin_manager: &PluginManager) -> Packet {
+        Self::local_info(device_info, plugin_manager).to_identity_packet()
+    }
+
+    /// Our device info as advertised in the identity packet
+    ///
+    /// Incoming and outgoing capabilities are the sorted union of those of all
+    /// factories registered with `plugin_manager`, and the protocol version is
+    /// [`PROTOCOL_VERSION`].
+    pub fn local_info(device_info: &DeviceInfo, plugin_manager: &PluginManager) -> DeviceInfo {
+        let mut incoming = plugin_manager.get_all_incoming_capabilities();
+        incoming.sort();
+        incoming.dedup();
+
+        DeviceInfo {
+            protocol_version: PROTOCOL_VERSION,
+            incoming_capabilities: incoming,
+            outgoing_capabilities: plugin_manager.get_all_outgoing_capabilities(),
+            ..device_info.clone()
+        }
+    }
+
+    /// Parse a peer's identity packet
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProtocolError::ProtocolVersionMismatch`] if the peer is older
+    /// than [`MIN_PROTOCOL_VERSION`], and [`ProtocolError::InvalidPacket`] if
+    /// the packet is not a valid identity.
+    pub fn parse(packet: &Packet) -> Result<DeviceInfo> {
+        Self::check_version(packet)?;
+        DeviceInfo::from_identity_packet(packet)
+    }
+
+    /// Check the protocol version an identity packet advertises
+    ///
+    /// A packet without `protocolVersion` is accepted, like
+    /// [`DeviceInfo::from_identity_packet`] assumes our own version for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProtocolError::ProtocolVersionMismatch`] if the version is
+    /// older than [`MIN_PROTOCOL_VERSION`].
+    pub fn check_version(packet: &Packet) -> Result<()> {
+        let Some(version) = packet.get_body_field::<u32>("protocolVersion") else {
+            return Ok(());
+        };
+        if version < MIN_PROTOCOL_VERSION {
+            return Err(ProtocolError::ProtocolVersionMismatch(format!(
+                "peer speaks protocol version {}, at least {} is required",
+                version, MIN_PROTOCOL_VERSION
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::battery::BatteryPluginFactory;
+    use crate::plugins::ping::PingPluginFactory;
+    use crate::plugins::PluginFactory;
+    use crate::DeviceType;
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+
+    fn plugin_manager() -> PluginManager {
+        let mut manager = PluginManager::new();
+        manager
+            .register_factory(Arc::new(PingPluginFactory))
+            .unwrap();
+        manager
+            .register_factory(Arc::new(BatteryPluginFactory))
+            .unwrap();
+        manager
+    }
+
+    fn identity_with_version(version: u32) -> Packet {
+        let mut packet = DeviceInfo::new("Phone", DeviceType::Phone, 1716).to_identity_packet();
+        packet.body["protocolVersion"] = version.into();
+        packet
+    }
+
+    #[test]
+    fn test_build_advertises_union_of_registered_plugins() {
+        let manager = plugin_manager();
+        let mut info = DeviceInfo::new("Desktop", DeviceType::Desktop, 1716);
+        info.protocol_version = 1;
+        info.incoming_capabilities = vec!["cconnect.stale".to_string()];
+
+        let packet = IdentityPacket::build(&info, &manager);
+        let parsed = IdentityPacket::parse(&packet).unwrap();
+
+        let factories: [Arc<dyn PluginFactory>; 2] =
+            [Arc::new(PingPluginFactory), Arc::new(BatteryPluginFactory)];
+        let expected_incoming: BTreeSet<String> = factories
+            .iter()
+            .flat_map(|f| f.incoming_capabilities())
+            .collect();
+        let expected_outgoing: BTreeSet<String> = factories
+            .iter()
+            .flat_map(|f| f.outgoing_capabilities())
+            .collect();
+
+        assert_eq!(parsed.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(parsed.device_id, info.device_id);
+        assert_eq!(parsed.device_name, "Desktop");
+        assert_eq!(
+            parsed.incoming_capabilities,
+            expected_incoming.into_iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            parsed.outgoing_capabilities,
+            expected_outgoing.into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_parse_accepts_supported_versions() {
+        for version in [MIN_PROTOCOL_VERSION, PROTOCOL_VERSION, PROTOCOL_VERSION + 1] {
+            let info = IdentityPacket::parse(&identity_with_version(version)).unwrap();
+            assert_eq!(info.protocol_version, version);
+        }
+    }
+
+    #[test]
+    fn test_parse_refuses_too_old_version() {
+        let err =
+            IdentityPacket::parse(&identity_with_version(MIN_PROTOCOL_VERSION - 1)).unwrap_err();
+
+        assert!(matches!(err, ProtocolError::ProtocolVersionMismatch(_)));
+        assert!(err.to_string().contains(&format!(
+            "protocol version {}, at least {} is required",
+            MIN_PROTOCOL_VERSION - 1,
+            MIN_PROTOCOL_VERSION
+        )));
+    }
+
+    #[test]
+    fn test_missing_version_is_accepted() {
+        let mut packet = identity_with_version(PROTOCOL_VERSION);
+        packet
+            .body
+            .as_object_mut()
+            .unwrap()
+            .remove("protocolVersion");
+
+        let info = IdentityPacket::parse(&packet).unwrap();
+        assert_eq!(info.protocol_version, PROTOCOL_VERSION);
+    }
+}
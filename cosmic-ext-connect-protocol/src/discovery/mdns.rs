@@ -0,0 +1,333 @@
+//! mDNS / DNS-SD Discovery Module
+//!
+//! This module advertises this device as a `_cconnect._udp` DNS-SD service
+//! and browses for peers doing the same. It complements UDP broadcast
+//! discovery on networks that filter broadcast traffic or route multicast
+//! between subnets.
+//!
+//! ## TXT Records
+//!
+//! | Key        | Value                              |
+//! |------------|------------------------------------|
+//! | `id`       | Device ID                          |
+//! | `name`     | Human-readable device name         |
+//! | `type`     | Device type (`desktop`, `phone`..) |
+//! | `protocol` | Protocol version                   |
+//! | `port`     | TCP port for connections           |
+//!
+//! Capabilities are not advertised; they are exchanged in the identity
+//! packet once a connection is established.
+
+use super::events::DiscoveryEvent;
+use crate::{DeviceInfo, DeviceType, ProtocolError, Result, PROTOCOL_VERSION};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, info, warn};
+
+/// DNS-SD service type advertised and browsed by CConnect
+pub const MDNS_SERVICE_TYPE: &str = "_cconnect._udp.local.";
+
+/// TXT record key for the device ID
+pub const TXT_ID: &str = "id";
+/// TXT record key for the device name
+pub const TXT_NAME: &str = "name";
+/// TXT record key for the device type
+pub const TXT_TYPE: &str = "type";
+/// TXT record key for the protocol version
+pub const TXT_PROTOCOL: &str = "protocol";
+/// TXT record key for the TCP port
+pub const TXT_PORT: &str = "port";
+
+/// Build the TXT record entries advertised for a device
+pub fn txt_records(info: &DeviceInfo) -> Vec<(&'static str, String)> {
+    vec![
+        (TXT_ID, info.device_id.clone()),
+        (TXT_NAME, info.device_name.clone()),
+        (TXT_TYPE, info.device_type.as_str().to_string()),
+        (TXT_PROTOCOL, info.protocol_version.to_string()),
+        (TXT_PORT, info.tcp_port.to_string()),
+    ]
+}
+
+/// Parse TXT record entries back into a [`DeviceInfo`]
+///
+/// Keys are matched case-insensitively, as DNS-SD requires. `id`, `name`
+/// and `type` are mandatory. A missing `protocol` defaults to
+/// [`PROTOCOL_VERSION`]; a missing or invalid `port` falls back to the port
+/// from the SRV record.
+pub fn device_info_from_txt<'a, I>(records: I, srv_port: u16) -> Result<DeviceInfo>
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    let records: HashMap<String, &str> = records
+        .into_iter()
+        .map(|(key, value)| (key.to_ascii_lowercase(), value))
+        .collect();
+    let required = |key: &str| {
+        records
+            .get(key)
+            .filter(|value| !value.is_empty())
+            .copied()
+            .ok_or_else(|| ProtocolError::InvalidPacket(format!("Missing TXT record: {}", key)))
+    };
+
+    let device_id = required(TXT_ID)?;
+    let device_name = required(TXT_NAME)?;
+    let device_type = required(TXT_TYPE)?.parse::<DeviceType>()?;
+
+    let protocol_version = records
+        .get(TXT_PROTOCOL)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(PROTOCOL_VERSION);
+
+    let tcp_port = records
+        .get(TXT_PORT)
+        .and_then(|value| value.parse::<u16>().ok())
+        .filter(|&port| port != 0)
+        .unwrap_or(srv_port);
+
+    let mut info = DeviceInfo::with_id(device_id, device_name, device_type, tcp_port);
+    info.protocol_version = protocol_version;
+    Ok(info)
+}
+
+/// Pick the address to connect to, preferring IPv4
+fn preferred_address(addresses: impl IntoIterator<Item = IpAddr>) -> Option<IpAddr> {
+    let mut fallback = None;
+    for address in addresses {
+        if address.is_ipv4() {
+            return Some(address);
+        }
+        fallback.get_or_insert(address);
+    }
+    fallback
+}
+
+/// mDNS discovery service
+///
+/// Registers this device with the local mDNS responder and emits
+/// [`DiscoveryEvent`]s for resolved peers.
+pub struct MdnsDiscoveryService {
+    /// Information about this device
+    device_info: DeviceInfo,
+
+    /// mDNS daemon (created on start)
+    daemon: Option<ServiceDaemon>,
+
+    /// Full name of our registered service instance
+    fullname: Option<String>,
+
+    /// Event channel sender
+    event_tx: mpsc::UnboundedSender<DiscoveryEvent>,
+
+    /// Event channel receiver
+    event_rx: Arc<RwLock<mpsc::UnboundedReceiver<DiscoveryEvent>>>,
+}
+
+impl MdnsDiscoveryService {
+    /// Create a new mDNS discovery service
+    ///
+    /// # Arguments
+    ///
+    /// * `device_info` - Information about this device
+    pub fn new(device_info: DeviceInfo) -> Self {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        Self {
+            device_info,
+            daemon: None,
+            fullname: None,
+            event_tx,
+            event_rx: Arc::new(RwLock::new(event_rx)),
+        }
+    }
+
+    /// Get a receiver for discovery events
+    pub async fn subscribe(&self) -> mpsc::UnboundedReceiver<DiscoveryEvent> {
+        let mut rx = self.event_rx.write().await;
+        let (_tx, new_rx) = mpsc::unbounded_channel();
+        std::mem::replace(&mut *rx, new_rx)
+    }
+
+    /// Advertise this device and start browsing for peers
+    pub async fn start(&mut self) -> Result<()> {
+        let daemon =
+            ServiceDaemon::new().map_err(|e| ProtocolError::Io(std::io::Error::other(e)))?;
+
+        let records = txt_records(&self.device_info);
+        let properties: Vec<(&str, &str)> = records
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect();
+        let host_name = format!("{}.local.", self.device_info.device_id);
+        let service = ServiceInfo::new(
+            MDNS_SERVICE_TYPE,
+            &self.device_info.device_id,
+            &host_name,
+            "",
+            self.device_info.tcp_port,
+            &properties[..],
+        )
+        .map_err(|e| ProtocolError::Io(std::io::Error::other(e)))?
+        .enable_addr_auto();
+        let fullname = service.get_fullname().to_string();
+
+        daemon
+            .register(service)
+            .map_err(|e| ProtocolError::Io(std::io::Error::other(e)))?;
+        let receiver = daemon
+            .browse(MDNS_SERVICE_TYPE)
+            .map_err(|e| ProtocolError::Io(std::io::Error::other(e)))?;
+
+        info!("Advertising {} via mDNS", fullname);
+
+        let own_device_id = self.device_info.device_id.clone();
+        let event_tx = self.event_tx.clone();
+        tokio::spawn(async move {
+            // Service instance name -> device ID, for removals
+            let mut known: HashMap<String, String> = HashMap::new();
+
+            while let Ok(event) = receiver.recv_async().await {
+                match event {
+                    ServiceEvent::ServiceResolved(service) => {
+                        let records = service
+                            .txt_properties
+                            .iter()
+                            .map(|property| (property.key(), property.val_str()));
+                        let info = match device_info_from_txt(records, service.port) {
+                            Ok(info) => info,
+                            Err(e) => {
+                                debug!("Ignoring mDNS service {}: {}", service.fullname, e);
+                                continue;
+                            }
+                        };
+                        if info.device_id == own_device_id {
+                            continue;
+                        }
+
+                        let addresses = service.addresses.iter().map(|ip| ip.to_ip_addr());
+                        let Some(ip) = preferred_address(addresses) else {
+                            debug!("mDNS service {} has no address", service.fullname);
+                            continue;
+                        };
+                        let address = SocketAddr::new(ip, info.tcp_port_candidates()[0]);
+
+                        let is_new = known
+                            .insert(service.fullname.clone(), info.device_id.clone())
+                            .is_none();
+                        let event = if is_new {
+                            info!(
+                                "Discovered device via mDNS: {} ({}) at {}",
+                                info.device_name,
+                                info.device_type.as_str(),
+                                address
+                            );
+                            DiscoveryEvent::tcp_discovered(info, address)
+                        } else {
+                            DiscoveryEvent::tcp_updated(info, address)
+                        };
+                        if event_tx.send(event).is_err() {
+                            break;
+                        }
+                    }
+                    ServiceEvent::ServiceRemoved(_, fullname) => {
+                        if let Some(device_id) = known.remove(&fullname) {
+                            debug!("mDNS service {} removed", fullname);
+                            let _ = event_tx.send(DiscoveryEvent::DeviceTimeout { device_id });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            debug!("mDNS browser stopped");
+        });
+
+        self.daemon = Some(daemon);
+        self.fullname = Some(fullname);
+        Ok(())
+    }
+
+    /// Withdraw our advertisement and stop browsing
+    pub async fn stop(&mut self) {
+        let Some(daemon) = self.daemon.take() else {
+            return;
+        };
+        if let Some(fullname) = self.fullname.take() {
+            if let Err(e) = daemon.unregister(&fullname) {
+                warn!("Failed to unregister mDNS service: {}", e);
+            }
+        }
+        if let Err(e) = daemon.shutdown() {
+            warn!("Failed to shut down mDNS daemon: {}", e);
+        }
+        info!("mDNS discovery stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn as_pairs(records: &[(&'static str, String)]) -> Vec<(&'static str, &str)> {
+        records.iter().map(|(k, v)| (*k, v.as_str())).collect()
+    }
+
+    #[test]
+    fn test_txt_round_trip() {
+        let info = DeviceInfo::with_id("abc_123", "My Phone", DeviceType::Phone, 1739);
+        let records = txt_records(&info);
+
+        let parsed = device_info_from_txt(as_pairs(&records), 9999).unwrap();
+        assert_eq!(parsed.device_id, "abc_123");
+        assert_eq!(parsed.device_name, "My Phone");
+        assert_eq!(parsed.device_type, DeviceType::Phone);
+        assert_eq!(parsed.protocol_version, info.protocol_version);
+        assert_eq!(parsed.tcp_port, 1739);
+    }
+
+    #[test]
+    fn test_txt_keys_case_insensitive() {
+        let records = [("ID", "dev"), ("Name", "Laptop"), ("TYPE", "laptop")];
+        let parsed = device_info_from_txt(records, 1816).unwrap();
+        assert_eq!(parsed.device_id, "dev");
+        assert_eq!(parsed.device_type, DeviceType::Laptop);
+    }
+
+    #[test]
+    fn test_txt_defaults() {
+        let records = [
+            ("id", "dev"),
+            ("name", "Tablet"),
+            ("type", "tablet"),
+            ("port", "x"),
+        ];
+        let parsed = device_info_from_txt(records, 1816).unwrap();
+        assert_eq!(parsed.protocol_version, PROTOCOL_VERSION);
+        // An unparsable port falls back to the SRV port
+        assert_eq!(parsed.tcp_port, 1816);
+        assert!(parsed.incoming_capabilities.is_empty());
+    }
+
+    #[test]
+    fn test_txt_missing_required() {
+        assert!(device_info_from_txt([("name", "A"), ("type", "phone")], 1816).is_err());
+        assert!(
+            device_info_from_txt([("id", ""), ("name", "A"), ("type", "phone")], 1816).is_err()
+        );
+        assert!(device_info_from_txt([("id", "a"), ("type", "phone")], 1816).is_err());
+        assert!(
+            device_info_from_txt([("id", "a"), ("name", "A"), ("type", "fridge")], 1816).is_err()
+        );
+    }
+
+    #[test]
+    fn test_preferred_address() {
+        let v6: IpAddr = "fe80::1".parse().unwrap();
+        let v4: IpAddr = "192.168.1.10".parse().unwrap();
+        assert_eq!(preferred_address([v6, v4]), Some(v4));
+        assert_eq!(preferred_address([v6]), Some(v6));
+        assert_eq!(preferred_address([]), None);
+    }
+}
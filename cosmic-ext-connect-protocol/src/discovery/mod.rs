@@ -1,6 +1,9 @@
 //! CConnect Device Discovery
 //!
 //! This module implements UDP broadcast-based device discovery for CConnect.
+//! With the `mdns` feature, devices are also advertised and browsed as a
+//! `_cconnect._udp` DNS-SD service, which reaches networks where broadcast
+//! is filtered.
 //!
 //! ## Discovery Protocol
 //!
@@ -52,6 +55,9 @@
 
 pub mod bluetooth;
 pub mod events;
+pub mod identity;
+#[cfg(feature = "mdns")]
+pub mod mdns;
 pub mod service;
 pub mod unified;
 
@@ -59,6 +65,7 @@ use crate::{Packet, ProtocolError, Result, PROTOCOL_VERSION};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::str::FromStr;
 use std::time::Duration;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
@@ -66,12 +73,20 @@ use uuid::Uuid;
 /// Default timeout for discovery operations
 pub const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// TCP ports tried, in order, when a peer does not advertise a `tcpPort`
+///
+/// 1716 is the KDE Connect default; 1814 and 1816 are used by CConnect.
+pub const DEFAULT_TCP_PORTS: &[u16] = &[1716, 1814, 1816];
+
 // Re-export main types
 pub use bluetooth::{
     BluetoothDiscoveryConfig, BluetoothDiscoveryService, DEFAULT_BT_DEVICE_TIMEOUT,
     DEFAULT_BT_SCAN_INTERVAL,
 };
 pub use events::DiscoveryEvent;
+pub use identity::IdentityPacket;
+#[cfg(feature = "mdns")]
+pub use mdns::{MdnsDiscoveryService, MDNS_SERVICE_TYPE};
 pub use service::{
     default_additional_broadcast_addrs, DiscoveryConfig, DiscoveryService, BROADCAST_ADDR,
     DEFAULT_BROADCAST_INTERVAL, DEFAULT_DEVICE_TIMEOUT, DISCOVERY_PORT, PORT_RANGE_END,
@@ -103,6 +118,24 @@ impl DeviceType {
     }
 }
 
+impl FromStr for DeviceType {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "desktop" => Ok(DeviceType::Desktop),
+            "laptop" => Ok(DeviceType::Laptop),
+            "phone" => Ok(DeviceType::Phone),
+            "tablet" => Ok(DeviceType::Tablet),
+            "tv" => Ok(DeviceType::Tv),
+            _ => Err(ProtocolError::InvalidPacket(format!(
+                "Unknown device type: {}",
+                s
+            ))),
+        }
+    }
+}
+
 /// Device identity information
 ///
 /// Contains all information about a device needed for discovery and pairing.
@@ -118,7 +151,7 @@ pub struct DeviceInfo {
     /// Type of device
     pub device_type: DeviceType,
 
-    /// Protocol version (currently 7)
+    /// Protocol version (currently 8)
     pub protocol_version: u32,
 
     /// Packet types this device can receive
@@ -127,7 +160,7 @@ pub struct DeviceInfo {
     /// Packet types this device can send
     pub outgoing_capabilities: Vec<String>,
 
-    /// TCP port for connections
+    /// TCP port for connections (0 if the peer did not advertise one)
     pub tcp_port: u16,
 }
 
@@ -235,7 +268,22 @@ impl DeviceInfo {
         )
     }
 
+    /// TCP ports to try, in order, for an outbound connection
+    ///
+    /// Returns the advertised port if there is one, otherwise
+    /// [`DEFAULT_TCP_PORTS`].
+    pub fn tcp_port_candidates(&self) -> Vec<u16> {
+        if self.tcp_port != 0 {
+            vec![self.tcp_port]
+        } else {
+            DEFAULT_TCP_PORTS.to_vec()
+        }
+    }
+
     /// Parse DeviceInfo from an identity packet
+    ///
+    /// A missing or invalid `tcpPort` is stored as 0; see
+    /// [`tcp_port_candidates`](Self::tcp_port_candidates).
     pub fn from_identity_packet(packet: &Packet) -> Result<Self> {
         if !packet.is_type("cconnect.identity") {
             return Err(ProtocolError::InvalidPacket(
@@ -255,27 +303,16 @@ impl DeviceInfo {
             .get_body_field::<String>("deviceType")
             .ok_or_else(|| ProtocolError::InvalidPacket("Missing deviceType".to_string()))?;
 
-        let device_type = match device_type_str.as_str() {
-            "desktop" => DeviceType::Desktop,
-            "laptop" => DeviceType::Laptop,
-            "phone" => DeviceType::Phone,
-            "tablet" => DeviceType::Tablet,
-            "tv" => DeviceType::Tv,
-            _ => {
-                return Err(ProtocolError::InvalidPacket(format!(
-                    "Unknown device type: {}",
-                    device_type_str
-                )))
-            }
-        };
+        let device_type = device_type_str.parse::<DeviceType>()?;
 
         let protocol_version = packet
             .get_body_field::<u32>("protocolVersion")
             .unwrap_or(PROTOCOL_VERSION);
 
-        let tcp_port = packet
-            .get_body_field::<u16>("tcpPort")
-            .ok_or_else(|| ProtocolError::InvalidPacket("Missing tcpPort".to_string()))?;
+        let tcp_port = packet.get_body_field::<u16>("tcpPort").unwrap_or_else(|| {
+            debug!("Device {} did not advertise a tcpPort", device_id);
+            0
+        });
 
         let incoming_capabilities = parse_capabilities(&packet, "incomingCapabilities");
         let outgoing_capabilities = parse_capabilities(&packet, "outgoingCapabilities");
@@ -476,6 +513,23 @@ mod tests {
         assert_eq!(DeviceType::Tv.as_str(), "tv");
     }
 
+    #[test]
+    fn test_device_type_from_str() {
+        for device_type in [
+            DeviceType::Desktop,
+            DeviceType::Laptop,
+            DeviceType::Phone,
+            DeviceType::Tablet,
+            DeviceType::Tv,
+        ] {
+            assert_eq!(
+                device_type.as_str().parse::<DeviceType>().unwrap(),
+                device_type
+            );
+        }
+        assert!("toaster".parse::<DeviceType>().is_err());
+    }
+
     #[test]
     #[ignore]
     fn test_discovery_broadcast() {
@@ -546,4 +600,33 @@ mod tests {
         assert!(info.incoming_capabilities.is_empty());
         assert!(info.outgoing_capabilities.is_empty());
     }
+
+    #[test]
+    fn test_tcp_port_candidates() {
+        let packet = Packet::new(
+            "cconnect.identity",
+            serde_json::json!({
+                "deviceId": "test-port",
+                "deviceName": "Port",
+                "deviceType": "phone",
+                "tcpPort": 1739,
+            }),
+        );
+        let info = DeviceInfo::from_identity_packet(&packet).unwrap();
+        assert_eq!(info.tcp_port, 1739);
+        assert_eq!(info.tcp_port_candidates(), vec![1739]);
+
+        // No advertised port falls back to the known defaults
+        let packet = Packet::new(
+            "cconnect.identity",
+            serde_json::json!({
+                "deviceId": "test-no-port",
+                "deviceName": "No Port",
+                "deviceType": "phone",
+            }),
+        );
+        let info = DeviceInfo::from_identity_packet(&packet).unwrap();
+        assert_eq!(info.tcp_port, 0);
+        assert_eq!(info.tcp_port_candidates(), DEFAULT_TCP_PORTS);
+    }
 }
@@ -1,4 +1,5 @@
 use super::events::DiscoveryEvent;
+use super::IdentityPacket;
 use crate::{DeviceInfo, Packet, ProtocolError, Result};
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
@@ -246,7 +247,7 @@ impl DiscoveryService {
         if !packet.is_type("cconnect.identity") {
             return Ok(());
         }
-        let device_info = DeviceInfo::from_identity_packet(&packet)?;
+        let device_info = IdentityPacket::parse(&packet)?;
         if device_info.device_id == own_device_id {
             return Ok(());
         }
@@ -259,7 +260,7 @@ impl DiscoveryService {
         last_seen_map.insert(device_info.device_id.clone(), current_time);
         drop(last_seen_map);
         let mut tcp_addr = src_addr;
-        tcp_addr.set_port(device_info.tcp_port);
+        tcp_addr.set_port(device_info.tcp_port_candidates()[0]);
         let event = if is_new {
             info!(
                 "Discovered new device: {} ({}) at {}",
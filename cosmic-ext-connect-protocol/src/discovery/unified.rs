@@ -1,15 +1,22 @@
 //! Unified Discovery Service
 //!
-//! This module provides a unified discovery service that coordinates both
-//! UDP (TCP/IP) and Bluetooth discovery, emitting unified DiscoveryEvents.
+//! This module provides a unified discovery service that coordinates
+//! UDP (TCP/IP), mDNS and Bluetooth discovery, emitting unified DiscoveryEvents.
+//!
+//! A device found by more than one method is reported as discovered once;
+//! later sightings become `DeviceUpdated`, and `DeviceTimeout` is only emitted
+//! once every method has lost the device.
 
 use super::bluetooth::{BluetoothDiscoveryConfig, BluetoothDiscoveryService};
 use super::events::DiscoveryEvent;
+#[cfg(feature = "mdns")]
+use super::mdns::MdnsDiscoveryService;
 use super::service::{DiscoveryConfig, DiscoveryService};
 use crate::{DeviceInfo, Result};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 /// Configuration for unified discovery
 #[derive(Debug, Clone)]
@@ -20,6 +27,10 @@ pub struct UnifiedDiscoveryConfig {
     /// Enable Bluetooth discovery
     pub enable_bluetooth: bool,
 
+    /// Enable mDNS (DNS-SD) discovery
+    #[cfg(feature = "mdns")]
+    pub enable_mdns: bool,
+
     /// TCP discovery configuration
     pub tcp_config: DiscoveryConfig,
 
@@ -32,6 +43,8 @@ impl Default for UnifiedDiscoveryConfig {
         Self {
             enable_tcp: true,
             enable_bluetooth: false, // Opt-in
+            #[cfg(feature = "mdns")]
+            enable_mdns: true,
             tcp_config: DiscoveryConfig::default(),
             bluetooth_config: BluetoothDiscoveryConfig::default(),
         }
@@ -42,6 +55,7 @@ impl Default for UnifiedDiscoveryConfig {
 ///
 /// This service acts as a facade coordinating:
 /// - UDP broadcast discovery (TCP/IP)
+/// - mDNS service discovery (with the `mdns` feature)
 /// - Bluetooth Low Energy (BLE) discovery
 ///
 /// It emits unified DiscoveryEvents regardless of the discovery method used.
@@ -52,6 +66,13 @@ pub struct UnifiedDiscoveryService {
     /// Bluetooth discovery service (optional)
     bluetooth_service: Option<Arc<RwLock<BluetoothDiscoveryService>>>,
 
+    /// mDNS discovery service (optional)
+    #[cfg(feature = "mdns")]
+    mdns_service: Option<Arc<RwLock<MdnsDiscoveryService>>>,
+
+    /// Which discovery methods currently see each device
+    sources: Arc<RwLock<DeviceSources>>,
+
     /// Unified event channel sender
     event_tx: mpsc::UnboundedSender<DiscoveryEvent>,
 
@@ -79,6 +100,12 @@ impl UnifiedDiscoveryService {
         let tcp_service = DiscoveryService::new(device_info.clone(), config.tcp_config.clone())?;
         let tcp_service = Arc::new(RwLock::new(tcp_service));
 
+        // Create mDNS discovery service if enabled
+        #[cfg(feature = "mdns")]
+        let mdns_service = config
+            .enable_mdns
+            .then(|| Arc::new(RwLock::new(MdnsDiscoveryService::new(device_info.clone()))));
+
         // Create Bluetooth discovery service if enabled
         let bluetooth_service = if config.enable_bluetooth {
             match BluetoothDiscoveryService::new(config.bluetooth_config.clone()).await {
@@ -106,6 +133,9 @@ impl UnifiedDiscoveryService {
         Ok(Self {
             tcp_service,
             bluetooth_service,
+            #[cfg(feature = "mdns")]
+            mdns_service,
+            sources: Arc::new(RwLock::new(DeviceSources::default())),
             event_tx,
             event_rx: Arc::new(RwLock::new(event_rx)),
             config,
@@ -151,6 +181,21 @@ impl UnifiedDiscoveryService {
             self.spawn_tcp_event_forwarder();
         }
 
+        // Start mDNS discovery if enabled
+        #[cfg(feature = "mdns")]
+        if let Some(mdns_service) = &self.mdns_service {
+            let mut mdns = mdns_service.write().await;
+            if let Err(e) = mdns.start().await {
+                warn!("Failed to start mDNS discovery: {}", e);
+            } else {
+                let events = mdns.subscribe().await;
+                drop(mdns);
+
+                // Forward mDNS events
+                self.spawn_event_forwarder(DiscoverySource::Mdns, events);
+            }
+        }
+
         // Start Bluetooth discovery if available
         if let Some(bluetooth_service) = &self.bluetooth_service {
             let mut bt_service = bluetooth_service.write().await;
@@ -172,6 +217,7 @@ impl UnifiedDiscoveryService {
     fn spawn_tcp_event_forwarder(&self) {
         let tcp_service = self.tcp_service.clone();
         let event_tx = self.event_tx.clone();
+        let sources = self.sources.clone();
 
         tokio::spawn(async move {
             let tcp_srv = tcp_service.read().await;
@@ -179,7 +225,28 @@ impl UnifiedDiscoveryService {
             drop(tcp_srv);
 
             while let Some(event) = tcp_events.recv().await {
-                let _ = event_tx.send(event);
+                if let Some(event) = sources.write().await.merge(DiscoverySource::Udp, event) {
+                    let _ = event_tx.send(event);
+                }
+            }
+        });
+    }
+
+    /// Spawn task to forward events from an already subscribed receiver
+    #[cfg(feature = "mdns")]
+    fn spawn_event_forwarder(
+        &self,
+        source: DiscoverySource,
+        mut events: mpsc::UnboundedReceiver<DiscoveryEvent>,
+    ) {
+        let event_tx = self.event_tx.clone();
+        let sources = self.sources.clone();
+
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                if let Some(event) = sources.write().await.merge(source, event) {
+                    let _ = event_tx.send(event);
+                }
             }
         });
     }
@@ -189,6 +256,7 @@ impl UnifiedDiscoveryService {
         if let Some(bluetooth_service) = &self.bluetooth_service {
             let bt_service = bluetooth_service.clone();
             let event_tx = self.event_tx.clone();
+            let sources = self.sources.clone();
 
             tokio::spawn(async move {
                 let bt_srv = bt_service.read().await;
@@ -196,7 +264,13 @@ impl UnifiedDiscoveryService {
                 drop(bt_srv);
 
                 while let Some(event) = bt_events.recv().await {
-                    let _ = event_tx.send(event);
+                    let merged = sources
+                        .write()
+                        .await
+                        .merge(DiscoverySource::Bluetooth, event);
+                    if let Some(event) = merged {
+                        let _ = event_tx.send(event);
+                    }
                 }
             });
         }
@@ -212,6 +286,12 @@ impl UnifiedDiscoveryService {
             let _ = tcp_service.stop().await;
         }
 
+        // Stop mDNS service if enabled
+        #[cfg(feature = "mdns")]
+        if let Some(mdns_service) = &self.mdns_service {
+            mdns_service.write().await.stop().await;
+        }
+
         // Stop Bluetooth service if available
         if let Some(bluetooth_service) = &self.bluetooth_service {
             let mut bt_service = bluetooth_service.write().await;
@@ -231,6 +311,87 @@ impl UnifiedDiscoveryService {
     pub fn has_bluetooth(&self) -> bool {
         self.bluetooth_service.is_some()
     }
+
+    /// Check if mDNS discovery is enabled
+    #[cfg(feature = "mdns")]
+    pub fn has_mdns(&self) -> bool {
+        self.mdns_service.is_some()
+    }
+}
+
+/// Discovery method an event came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum DiscoverySource {
+    Udp,
+    #[cfg(feature = "mdns")]
+    Mdns,
+    Bluetooth,
+}
+
+/// Tracks which discovery methods currently see each device
+///
+/// Used to merge the per-method event streams so that each device is
+/// discovered and timed out exactly once.
+#[derive(Debug, Default)]
+struct DeviceSources {
+    devices: HashMap<String, HashSet<DiscoverySource>>,
+}
+
+impl DeviceSources {
+    /// Record an event from `source`, returning the event to forward (if any)
+    fn merge(&mut self, source: DiscoverySource, event: DiscoveryEvent) -> Option<DiscoveryEvent> {
+        match event {
+            #[allow(deprecated)]
+            DiscoveryEvent::DeviceDiscovered {
+                info,
+                address,
+                transport_address,
+                transport_type,
+            }
+            | DiscoveryEvent::DeviceUpdated {
+                info,
+                address,
+                transport_address,
+                transport_type,
+            } => {
+                let seen_by = self.devices.entry(info.device_id.clone()).or_default();
+                let is_new = seen_by.is_empty();
+                seen_by.insert(source);
+
+                #[allow(deprecated)]
+                let event = if is_new {
+                    DiscoveryEvent::DeviceDiscovered {
+                        info,
+                        address,
+                        transport_address,
+                        transport_type,
+                    }
+                } else {
+                    DiscoveryEvent::DeviceUpdated {
+                        info,
+                        address,
+                        transport_address,
+                        transport_type,
+                    }
+                };
+                Some(event)
+            }
+            DiscoveryEvent::DeviceTimeout { device_id } => {
+                let seen_by = self.devices.get_mut(&device_id)?;
+                seen_by.remove(&source);
+                if !seen_by.is_empty() {
+                    debug!(
+                        "Device {} lost via {:?}, still seen via {:?}",
+                        device_id, source, seen_by
+                    );
+                    return None;
+                }
+                self.devices.remove(&device_id);
+                Some(DiscoveryEvent::DeviceTimeout { device_id })
+            }
+            event => Some(event),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -272,4 +433,77 @@ mod tests {
             .unwrap();
         assert!(!service.has_bluetooth());
     }
+
+    fn discovered(device_id: &str) -> DiscoveryEvent {
+        let info = DeviceInfo::with_id(device_id, "Phone", DeviceType::Phone, 1816);
+        DiscoveryEvent::tcp_discovered(info, "192.168.1.20:1816".parse().unwrap())
+    }
+
+    fn timeout(device_id: &str) -> DiscoveryEvent {
+        DiscoveryEvent::DeviceTimeout {
+            device_id: device_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_device_seen_by_two_sources_is_discovered_once() {
+        let mut sources = DeviceSources::default();
+
+        let first = sources.merge(DiscoverySource::Udp, discovered("phone"));
+        assert!(first.unwrap().is_device_discovered());
+
+        let second = sources.merge(DiscoverySource::Bluetooth, discovered("phone"));
+        assert!(second.unwrap().is_device_updated());
+
+        // A different device is still reported as new
+        let other = sources.merge(DiscoverySource::Bluetooth, discovered("tablet"));
+        assert!(other.unwrap().is_device_discovered());
+    }
+
+    #[test]
+    fn test_timeout_waits_for_all_sources() {
+        let mut sources = DeviceSources::default();
+        sources.merge(DiscoverySource::Udp, discovered("phone"));
+        sources.merge(DiscoverySource::Bluetooth, discovered("phone"));
+
+        assert!(sources
+            .merge(DiscoverySource::Udp, timeout("phone"))
+            .is_none());
+        assert!(matches!(
+            sources.merge(DiscoverySource::Bluetooth, timeout("phone")),
+            Some(DiscoveryEvent::DeviceTimeout { .. })
+        ));
+
+        // Unknown devices do not time out again
+        assert!(sources
+            .merge(DiscoverySource::Udp, timeout("phone"))
+            .is_none());
+
+        // Seen again after timing out: discovered anew
+        let again = sources.merge(DiscoverySource::Udp, discovered("phone"));
+        assert!(again.unwrap().is_device_discovered());
+    }
+
+    #[cfg(feature = "mdns")]
+    #[test]
+    fn test_udp_and_mdns_sightings_merged() {
+        let mut sources = DeviceSources::default();
+
+        let first = sources.merge(DiscoverySource::Mdns, discovered("phone"));
+        assert!(first.unwrap().is_device_discovered());
+        let second = sources.merge(DiscoverySource::Udp, discovered("phone"));
+        assert!(second.unwrap().is_device_updated());
+
+        // Broadcasts filtered: mDNS still sees the device
+        assert!(sources
+            .merge(DiscoverySource::Udp, timeout("phone"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_other_events_pass_through() {
+        let mut sources = DeviceSources::default();
+        let event = sources.merge(DiscoverySource::Udp, DiscoveryEvent::ServiceStopped);
+        assert!(matches!(event, Some(DiscoveryEvent::ServiceStopped)));
+    }
 }
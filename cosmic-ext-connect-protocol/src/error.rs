@@ -132,6 +132,15 @@
 //! - `NotPaired`: Operation requires paired device
 //! - `InvalidPacket`: Malformed or invalid packet
 //! - `Plugin`: Plugin-specific errors
+//!
+//! ### Failure Classes
+//! Variants reconnection and retry logic can match on. Where there is an
+//! underlying error it is kept as the [`source`](std::error::Error::source):
+//! - `Transport`: The link failed or was lost; reconnecting may help
+//! - `Timeout`: An operation took too long; retrying may help
+//! - `Deserialization`: The peer sent data that does not parse; retrying won't help
+//! - `Authentication`: The peer failed authentication; needs re-pairing
+//! - `PayloadTooLarge`: A packet or payload exceeded a size limit
 
 use thiserror::Error;
 
@@ -269,9 +278,33 @@ pub enum ProtocolError {
 
     /// Transport layer error
     ///
-    /// This error occurs during transport operations (TCP, Bluetooth, etc.).
-    #[error("Transport error: {0}")]
-    Transport(String),
+    /// This error occurs when a link (TCP, Bluetooth, QUIC, etc.) fails or is
+    /// lost. `source` holds the underlying error, if any. Create it with
+    /// [`ProtocolError::transport`] or [`ProtocolError::transport_with_source`].
+    #[error("Transport error: {message}")]
+    Transport {
+        message: String,
+        #[source]
+        source: Option<Box<dyn std::error::Error + Send + Sync>>,
+    },
+
+    /// Deserialization error
+    ///
+    /// This error occurs when data received from a peer does not parse, e.g. a
+    /// packet that is not valid JSON.
+    #[error("Failed to deserialize {context}: {source}")]
+    Deserialization {
+        context: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// Authentication error
+    ///
+    /// This error occurs when a peer fails challenge-response authentication.
+    /// Automatically converted from [`AuthError`](crate::auth::AuthError).
+    #[error("Authentication failed: {0}")]
+    Authentication(#[from] crate::auth::AuthError),
 
     /// Certificate validation error
     ///
@@ -279,6 +312,34 @@ pub enum ProtocolError {
     #[error("Certificate validation error: {0}")]
     CertificateValidation(String),
 
+    /// Peer certificate not trusted
+    ///
+    /// This error occurs when a TLS handshake fails because one side does not
+    /// trust the other's certificate, e.g. after unpairing on only one device.
+    #[error("Certificate not trusted: {0}")]
+    CertUntrusted(String),
+
+    /// Peer certificate does not match the paired certificate
+    ///
+    /// This error occurs when a paired device presents a different certificate
+    /// than the one stored at pairing, e.g. after the app was reinstalled.
+    #[error("Certificate mismatch: {0}")]
+    CertMismatch(String),
+
+    /// No common TLS version
+    ///
+    /// This error occurs when the peer only offers TLS versions we do not
+    /// support (we require TLS 1.2 or newer).
+    #[error("Unsupported TLS version: {0}")]
+    TlsVersionUnsupported(String),
+
+    /// TLS handshake did not complete in time
+    ///
+    /// This error occurs when the TCP connection succeeded but the peer
+    /// stopped responding during the TLS handshake.
+    #[error("TLS handshake timeout: {0}")]
+    HandshakeTimeout(String),
+
     /// Device not found in registry
     ///
     /// This error occurs when attempting to access a device that doesn't
@@ -397,11 +458,26 @@ pub enum ProtocolError {
     #[error("Operation cancelled: {0}")]
     Cancelled(String),
 
-    /// Packet size exceeded
+    /// Payload too large
     ///
-    /// This error occurs when a packet exceeds maximum allowed size (DoS prevention).
-    #[error("Packet size exceeded: {0} bytes (max: {1})")]
-    PacketSizeExceeded(usize, usize),
+    /// This error occurs when a packet or payload exceeds the maximum allowed
+    /// size (DoS prevention).
+    #[error("Payload too large: {size} bytes (max: {max})")]
+    PayloadTooLarge { size: u64, max: u64 },
+
+    /// Malformed frame
+    ///
+    /// This error occurs when a frame read from a connection is truncated or does
+    /// not hold exactly one JSON packet.
+    #[error("Malformed frame: {0}")]
+    MalformedFrame(String),
+
+    /// Integrity check failed
+    ///
+    /// This error occurs when a received payload does not match the hash its
+    /// sender announced.
+    #[error("Integrity check failed: {0}")]
+    IntegrityMismatch(String),
 
     /// Invalid state
     ///
@@ -415,6 +491,13 @@ pub enum ProtocolError {
     #[error("Unsupported feature: {0}")]
     UnsupportedFeature(String),
 
+    /// Device busy
+    ///
+    /// This error occurs when the remote device or local backend rejects a request
+    /// because another operation is already in progress.
+    #[error("Device busy: {0}")]
+    DeviceBusy(String),
+
     /// Database error
     ///
     /// This error occurs during database operations (Contacts sync, etc.).
@@ -423,6 +506,62 @@ pub enum ProtocolError {
 }
 
 impl ProtocolError {
+    /// Create a transport error without an underlying cause
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::ProtocolError;
+    ///
+    /// let error = ProtocolError::transport("QUIC endpoint closed");
+    /// assert_eq!(error.to_string(), "Transport error: QUIC endpoint closed");
+    /// ```
+    pub fn transport(message: impl Into<String>) -> Self {
+        ProtocolError::Transport {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Create a transport error caused by `source`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::ProtocolError;
+    /// use std::error::Error;
+    /// use std::io::{Error as IoError, ErrorKind};
+    ///
+    /// let io_error = IoError::new(ErrorKind::ConnectionReset, "reset by peer");
+    /// let error = ProtocolError::transport_with_source("connection to phone lost", io_error);
+    /// assert!(error.source().is_some());
+    /// ```
+    pub fn transport_with_source(
+        message: impl Into<String>,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        ProtocolError::Transport {
+            message: message.into(),
+            source: Some(Box::new(source)),
+        }
+    }
+
+    /// Create a deserialization error for data described by `context`
+    pub fn deserialization(context: impl Into<String>, source: serde_json::Error) -> Self {
+        ProtocolError::Deserialization {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// Create a payload size error
+    pub fn payload_too_large(size: usize, max: usize) -> Self {
+        ProtocolError::PayloadTooLarge {
+            size: size as u64,
+            max: max as u64,
+        }
+    }
+
     /// Convert a generic I/O error into a more specific network error
     ///
     /// This method examines the error kind and returns a more specific
@@ -463,6 +602,102 @@ impl ProtocolError {
         }
     }
 
+    /// Convert a rustls error into an actionable handshake error
+    ///
+    /// Returns `None` for errors that do not fall into one of the handshake
+    /// failure categories. A certificate rejected by our pinned-certificate
+    /// verifier or issued for another name is reported as a mismatch; every
+    /// other certificate rejection, by either side, as untrusted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::ProtocolError;
+    ///
+    /// let tls_error = rustls::Error::AlertReceived(rustls::AlertDescription::ProtocolVersion);
+    /// let error = ProtocolError::from_rustls_error(&tls_error, "connecting to phone");
+    ///
+    /// assert!(matches!(error, Some(ProtocolError::TlsVersionUnsupported(_))));
+    /// ```
+    pub fn from_rustls_error(error: &rustls::Error, context: &str) -> Option<Self> {
+        use rustls::{AlertDescription, CertificateError, Error, PeerIncompatible};
+
+        let detail = format!("{}: {}", context, error);
+        match error {
+            Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure
+                | CertificateError::NotValidForName,
+            ) => Some(ProtocolError::CertMismatch(detail)),
+            Error::InvalidCertificate(_)
+            | Error::NoCertificatesPresented
+            | Error::AlertReceived(
+                AlertDescription::BadCertificate
+                | AlertDescription::UnsupportedCertificate
+                | AlertDescription::CertificateRevoked
+                | AlertDescription::CertificateExpired
+                | AlertDescription::CertificateUnknown
+                | AlertDescription::UnknownCA,
+            ) => Some(ProtocolError::CertUntrusted(detail)),
+            Error::AlertReceived(AlertDescription::ProtocolVersion)
+            | Error::PeerIncompatible(
+                PeerIncompatible::ServerDoesNotSupportTls12Or13
+                | PeerIncompatible::ServerTlsVersionIsDisabledByOurConfig
+                | PeerIncompatible::SupportedVersionsExtensionRequired
+                | PeerIncompatible::Tls12NotOffered
+                | PeerIncompatible::Tls12NotOfferedOrEnabled,
+            ) => Some(ProtocolError::TlsVersionUnsupported(detail)),
+            _ => None,
+        }
+    }
+
+    /// Classify an error returned by a failed TLS handshake
+    ///
+    /// Looks through the error's source chain for the underlying rustls error
+    /// (tokio-rustls wraps it in an `io::Error`) or a timed-out I/O operation and
+    /// maps it to [`CertUntrusted`](Self::CertUntrusted),
+    /// [`CertMismatch`](Self::CertMismatch),
+    /// [`TlsVersionUnsupported`](Self::TlsVersionUnsupported) or
+    /// [`HandshakeTimeout`](Self::HandshakeTimeout). Other errors are returned
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::ProtocolError;
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// let io_error = Error::new(ErrorKind::TimedOut, "handshake stalled");
+    /// let error = ProtocolError::Io(io_error).classify_tls_handshake("connecting to phone");
+    ///
+    /// assert!(matches!(error, ProtocolError::HandshakeTimeout(_)));
+    /// ```
+    pub fn classify_tls_handshake(self, context: &str) -> Self {
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&self);
+        while let Some(error) = source {
+            if let Some(tls_error) = error.downcast_ref::<rustls::Error>() {
+                if let Some(mapped) = Self::from_rustls_error(tls_error, context) {
+                    return mapped;
+                }
+            }
+            if let Some(io_error) = error.downcast_ref::<std::io::Error>() {
+                if io_error.kind() == std::io::ErrorKind::TimedOut {
+                    return ProtocolError::HandshakeTimeout(format!("{}: {}", context, io_error));
+                }
+                // io::Error::source() skips the wrapped error itself
+                let wrapped = io_error
+                    .get_ref()
+                    .and_then(|inner| inner.downcast_ref::<rustls::Error>());
+                if let Some(mapped) =
+                    wrapped.and_then(|tls_error| Self::from_rustls_error(tls_error, context))
+                {
+                    return mapped;
+                }
+            }
+            source = error.source();
+        }
+        self
+    }
+
     /// Check if this error is recoverable (transient error that can be retried)
     ///
     /// Returns `true` if the error might succeed on retry, `false` if it's permanent.
@@ -482,9 +717,12 @@ impl ProtocolError {
         matches!(
             self,
             ProtocolError::Timeout(_)
+                | ProtocolError::HandshakeTimeout(_)
                 | ProtocolError::NetworkError(_)
                 | ProtocolError::NetworkUnreachable(_)
                 | ProtocolError::ConnectionRefused(_)
+                | ProtocolError::DeviceBusy(_)
+                | ProtocolError::Transport { .. }
                 | ProtocolError::Io(_)
         )
     }
@@ -511,9 +749,13 @@ impl ProtocolError {
             ProtocolError::NotPaired
                 | ProtocolError::Certificate(_)
                 | ProtocolError::CertificateValidation(_)
+                | ProtocolError::CertUntrusted(_)
+                | ProtocolError::CertMismatch(_)
+                | ProtocolError::TlsVersionUnsupported(_)
                 | ProtocolError::PermissionDenied(_)
                 | ProtocolError::Configuration(_)
                 | ProtocolError::ProtocolVersionMismatch(_)
+                | ProtocolError::Authentication(_)
                 | ProtocolError::Database(_)
         )
     }
@@ -581,7 +823,26 @@ impl ProtocolError {
                     msg
                 )
             }
-            ProtocolError::PacketSizeExceeded(size, max) => {
+            ProtocolError::CertUntrusted(_) => {
+                "The device does not trust this computer's certificate. Re-pair this device."
+                    .to_string()
+            }
+            ProtocolError::CertMismatch(_) => {
+                "The device's certificate changed since pairing. If it was reset or reinstalled, \
+                 unpair and re-pair this device."
+                    .to_string()
+            }
+            ProtocolError::TlsVersionUnsupported(_) => {
+                "The device does not support a compatible TLS version. \
+                 Update CConnect on the device."
+                    .to_string()
+            }
+            ProtocolError::HandshakeTimeout(_) => {
+                "Secure connection setup timed out. Check that both devices are on the same \
+                 network and try again."
+                    .to_string()
+            }
+            ProtocolError::PayloadTooLarge { size, max } => {
                 format!(
                     "Packet too large ({} bytes, max {} bytes). Try sending smaller files.",
                     size, max
@@ -590,6 +851,14 @@ impl ProtocolError {
             ProtocolError::InvalidPacket(msg) => {
                 format!("Invalid data received: {}.", msg)
             }
+            ProtocolError::MalformedFrame(msg) => {
+                format!("Corrupted data received: {}. Reconnect the device.", msg)
+            }
+            ProtocolError::IntegrityMismatch(_) => {
+                "The received file was corrupted in transit and has been discarded. \
+                 Send it again."
+                    .to_string()
+            }
             ProtocolError::Plugin(msg) => {
                 format!("Plugin error: {}.", msg)
             }
@@ -611,18 +880,27 @@ impl ProtocolError {
             ProtocolError::CoreProtocol(e) => {
                 format!("Core protocol error: {}.", e)
             }
-            ProtocolError::Transport(msg) => {
+            ProtocolError::Transport { message, .. } => {
                 format!(
                     "Transport error: {}. Check network and Bluetooth connections.",
-                    msg
+                    message
                 )
             }
+            ProtocolError::Deserialization { context, .. } => {
+                format!("Invalid {} received from the device.", context)
+            }
+            ProtocolError::Authentication(e) => {
+                format!("Device authentication failed: {}. Re-pair this device.", e)
+            }
             ProtocolError::InvalidState(msg) => {
                 format!("Invalid state: {}.", msg)
             }
             ProtocolError::UnsupportedFeature(msg) => {
                 format!("Feature not available: {}.", msg)
             }
+            ProtocolError::DeviceBusy(msg) => {
+                format!("Device is busy: {}. Try again in a moment.", msg)
+            }
             ProtocolError::Database(msg) => {
                 format!(
                     "Database error: {}. Contact synchronization may be affected.",
@@ -663,6 +941,22 @@ impl ProtocolError {
     pub fn unsupported_feature(msg: impl Into<String>) -> Self {
         ProtocolError::UnsupportedFeature(msg.into())
     }
+
+    /// Create a device busy error
+    ///
+    /// This helper method creates a `DeviceBusy` error variant.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::ProtocolError;
+    ///
+    /// let error = ProtocolError::device_busy("another power operation is in progress");
+    /// assert!(matches!(error, ProtocolError::DeviceBusy(_)));
+    /// ```
+    pub fn device_busy(msg: impl Into<String>) -> Self {
+        ProtocolError::DeviceBusy(msg.into())
+    }
 }
 
 #[cfg(test)]
@@ -684,6 +978,92 @@ mod tests {
         assert_eq!(error.to_string(), "Plugin error: initialization failed");
     }
 
+    #[test]
+    fn test_rejection_reason_display() {
+        let error = ProtocolError::unsupported_feature("hibernate");
+        assert_eq!(error.to_string(), "Unsupported feature: hibernate");
+
+        let error = ProtocolError::PermissionDenied("suspend".to_string());
+        assert_eq!(error.to_string(), "Permission denied: suspend");
+
+        let error = ProtocolError::device_busy("shutdown in progress");
+        assert_eq!(error.to_string(), "Device busy: shutdown in progress");
+
+        let error = ProtocolError::Timeout("handshake with 10.0.0.2".to_string());
+        assert_eq!(
+            error.to_string(),
+            "Connection timeout: handshake with 10.0.0.2"
+        );
+    }
+
+    #[test]
+    fn test_rejection_reason_user_messages() {
+        let error = ProtocolError::device_busy("shutdown in progress");
+        assert_eq!(
+            error.user_message(),
+            "Device is busy: shutdown in progress. Try again in a moment."
+        );
+        assert!(error.is_recoverable());
+        assert!(!error.requires_user_action());
+
+        let error = ProtocolError::unsupported_feature("hibernate");
+        assert_eq!(error.user_message(), "Feature not available: hibernate.");
+        assert!(!error.is_recoverable());
+
+        let error = ProtocolError::NotPaired;
+        assert!(error.requires_user_action());
+    }
+
+    #[test]
+    fn test_handshake_error_display() {
+        let error = ProtocolError::CertMismatch("phone".to_string());
+        assert_eq!(error.to_string(), "Certificate mismatch: phone");
+
+        let error = ProtocolError::HandshakeTimeout("phone".to_string());
+        assert_eq!(error.to_string(), "TLS handshake timeout: phone");
+
+        // Wrapped in the core error chain the same way as by tokio-rustls
+        let io_error = std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            rustls::Error::AlertReceived(rustls::AlertDescription::CertificateUnknown),
+        );
+        let error = ProtocolError::Io(io_error).classify_tls_handshake("phone");
+        assert!(matches!(error, ProtocolError::CertUntrusted(_)));
+        assert!(error
+            .to_string()
+            .starts_with("Certificate not trusted: phone: "));
+    }
+
+    #[test]
+    fn test_failure_classes() {
+        use std::error::Error;
+
+        let io_error = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset");
+        let error = ProtocolError::transport_with_source("connection lost", io_error);
+        assert!(matches!(error, ProtocolError::Transport { .. }));
+        assert!(error.is_recoverable());
+        assert_eq!(error.source().unwrap().to_string(), "reset");
+
+        let json_error = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let error = ProtocolError::deserialization("packet", json_error);
+        assert!(error
+            .to_string()
+            .starts_with("Failed to deserialize packet: "));
+        assert!(error.source().is_some());
+        assert!(!error.is_recoverable());
+
+        let error: ProtocolError = crate::auth::AuthError::InvalidSignature.into();
+        assert!(matches!(error, ProtocolError::Authentication(_)));
+        assert!(error.requires_user_action());
+        assert!(!error.is_recoverable());
+
+        let error = ProtocolError::payload_too_large(2048, 1024);
+        assert_eq!(
+            error.to_string(),
+            "Payload too large: 2048 bytes (max: 1024)"
+        );
+    }
+
     #[test]
     fn test_io_error_conversion() {
         use std::io::{Error, ErrorKind};
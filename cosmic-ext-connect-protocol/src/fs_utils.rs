@@ -6,7 +6,7 @@
 use crate::{ProtocolError, Result};
 use std::path::{Path, PathBuf};
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tracing::{debug, info, warn};
 
 /// Check if sufficient disk space is available
@@ -97,6 +97,26 @@ pub async fn check_disk_space(path: impl AsRef<Path>, required_bytes: u64) -> Re
     Ok(())
 }
 
+/// Check whether an I/O error means the disk (or quota) is full
+///
+/// Detects `ENOSPC`/`EDQUOT` from the OS error code, falling back to the
+/// error message for platforms or wrappers that don't preserve the code.
+pub fn is_disk_full_error(error: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        use nix::errno::Errno;
+
+        if let Some(code) = error.raw_os_error() {
+            if code == Errno::ENOSPC as i32 || code == Errno::EDQUOT as i32 {
+                return true;
+            }
+        }
+    }
+
+    let error_msg = error.to_string().to_lowercase();
+    error_msg.contains("no space") || error_msg.contains("disk full")
+}
+
 /// Ensure parent directory exists, creating it if necessary
 ///
 /// Returns `Ok(())` if directory exists or was created successfully.
@@ -179,18 +199,10 @@ pub async fn create_file_safe(path: impl AsRef<Path>) -> Result<fs::File> {
             "Cannot create file {}: permission denied",
             path.display()
         )),
-        std::io::ErrorKind::Other => {
-            // Check if error message contains disk-related keywords
-            let error_msg = e.to_string().to_lowercase();
-            if error_msg.contains("no space") || error_msg.contains("disk full") {
-                ProtocolError::ResourceExhausted(format!(
-                    "Disk full: cannot create file {}",
-                    path.display()
-                ))
-            } else {
-                ProtocolError::from_io_error(e, &format!("creating file {}", path.display()))
-            }
-        }
+        _ if is_disk_full_error(&e) => ProtocolError::ResourceExhausted(format!(
+            "Disk full: cannot create file {}",
+            path.display()
+        )),
         _ => ProtocolError::from_io_error(e, &format!("creating file {}", path.display())),
     })?;
 
@@ -200,7 +212,8 @@ pub async fn create_file_safe(path: impl AsRef<Path>) -> Result<fs::File> {
 
 /// Safe file write with disk full detection
 ///
-/// Writes data to a file, converting disk full errors to `ResourceExhausted`.
+/// Writes data to a file (or any async sink), converting disk full errors
+/// to `ResourceExhausted`.
 ///
 /// # Arguments
 ///
@@ -211,17 +224,16 @@ pub async fn create_file_safe(path: impl AsRef<Path>) -> Result<fs::File> {
 ///
 /// Returns `ResourceExhausted` if disk is full during write.
 /// Returns `Io` for other errors.
-pub async fn write_file_safe(file: &mut fs::File, data: &[u8]) -> Result<()> {
-    file.write_all(data).await.map_err(|e| match e.kind() {
-        std::io::ErrorKind::Other => {
-            let error_msg = e.to_string().to_lowercase();
-            if error_msg.contains("no space") || error_msg.contains("disk full") {
-                ProtocolError::ResourceExhausted("Disk full during file write".to_string())
-            } else {
-                ProtocolError::Io(e)
-            }
+pub async fn write_file_safe<W>(file: &mut W, data: &[u8]) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    file.write_all(data).await.map_err(|e| {
+        if is_disk_full_error(&e) {
+            ProtocolError::ResourceExhausted("Disk full during file write".to_string())
+        } else {
+            ProtocolError::Io(e)
         }
-        _ => ProtocolError::Io(e),
     })
 }
 
@@ -385,6 +397,21 @@ mod tests {
         assert_eq!(path, temp.path().join("test (3).txt"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_is_disk_full_error() {
+        use nix::errno::Errno;
+
+        let enospc = std::io::Error::from_raw_os_error(Errno::ENOSPC as i32);
+        assert!(is_disk_full_error(&enospc));
+
+        let edquot = std::io::Error::from_raw_os_error(Errno::EDQUOT as i32);
+        assert!(is_disk_full_error(&edquot));
+
+        let other = std::io::Error::from_raw_os_error(Errno::EACCES as i32);
+        assert!(!is_disk_full_error(&other));
+    }
+
     #[tokio::test]
     async fn test_cleanup_partial_file() {
         let temp = TempDir::new().unwrap();
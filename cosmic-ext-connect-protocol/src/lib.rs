@@ -4,8 +4,12 @@
 //! enabling device synchronization and communication between computers and mobile devices.
 
 pub mod auth;
+pub mod bandwidth;
 pub mod bluetooth_connection_manager;
+pub mod capabilities;
+pub mod compression;
 pub mod connection;
+pub mod cpu_pool;
 pub mod device;
 pub mod discovery;
 pub mod fs_utils;
@@ -13,11 +17,13 @@ pub mod packet;
 pub mod pairing;
 pub mod payload;
 pub mod plugins;
+pub mod quic_connection_manager;
 pub mod recovery;
 pub mod recovery_coordinator;
 pub mod resource_manager;
 pub mod transport;
 pub mod transport_manager;
+pub mod version_compat;
 
 mod error;
 
@@ -30,37 +36,48 @@ pub use cosmic_ext_connect_core::{Packet as CorePacket, ProtocolError as CorePro
 
 // Re-export local types
 pub use bluetooth_connection_manager::BluetoothConnectionManager;
-pub use connection::{ConnectionConfig, ConnectionEvent, ConnectionManager};
+pub use capabilities::{CapabilityDiff, CapabilityOverrides, PluginNegotiation};
+pub use compression::{CompressionAlgorithm, CompressionConfig, PacketCompressor};
+pub use connection::{ConnectionConfig, ConnectionEvent, ConnectionManager, PacketConnection};
 pub use device::{ConnectionState, Device, DeviceManager};
 pub use discovery::{
     DeviceInfo, DeviceType, Discovery, DiscoveryConfig, DiscoveryEvent, DiscoveryService,
-    DISCOVERY_PORT,
+    IdentityPacket, DEFAULT_TCP_PORTS, DISCOVERY_PORT,
 };
 pub use error::{ProtocolError, Result};
-pub use packet::{current_timestamp, Packet};
+pub use packet::{current_timestamp, Packet, PayloadStream};
 pub use pairing::{
     PairingConfig, PairingEvent, PairingHandler, PairingPacket, PairingService, PairingStatus,
     PAIRING_TIMEOUT,
 };
 pub use payload::{
     FileTransferInfo, PayloadClient, PayloadServer, TlsPayloadClient, TlsPayloadServer,
+    TransferEvent,
 };
 pub use plugins::{Plugin, PluginManager};
+pub use quic_connection_manager::QuicConnectionManager;
 pub use recovery::{ReconnectionStrategy, RecoveryManager, TransferState};
 pub use recovery_coordinator::RecoveryCoordinator;
 pub use resource_manager::{MemoryStats, ResourceConfig, ResourceManager, TransferInfo};
 pub use transport::{
-    BluetoothConnection, BluetoothTransportFactory, LatencyCategory, TcpConnection,
-    TcpTransportFactory, Transport, TransportAddress, TransportCapabilities, TransportFactory,
-    TransportPreference, TransportType, CCONNECT_SERVICE_UUID, RFCOMM_READ_CHAR_UUID,
-    RFCOMM_WRITE_CHAR_UUID,
+    BluetoothConnection, BluetoothTransportFactory, HeartbeatIntervals, LatencyCategory,
+    QuicConnection, QuicListener, QuicTransportFactory, TcpConnection, TcpKeepaliveConfig,
+    TcpSocketOptions, TcpTransportFactory, Transport, TransportAddress, TransportCapabilities,
+    TransportFactory, TransportPreference, TransportSelector, TransportType, CCONNECT_SERVICE_UUID,
+    RFCOMM_READ_CHAR_UUID, RFCOMM_WRITE_CHAR_UUID,
 };
 pub use transport_manager::{TransportManager, TransportManagerConfig, TransportManagerEvent};
+pub use version_compat::VersionCompat;
 
 /// Protocol version we implement
 /// Updated to version 8 to match latest CConnect Android app
 pub const PROTOCOL_VERSION: u32 = 8;
 
+/// Oldest protocol version a peer may advertise
+///
+/// Identities from older peers are refused, see [`IdentityPacket::parse`].
+pub const MIN_PROTOCOL_VERSION: u32 = 6;
+
 #[cfg(test)]
 pub mod test_utils;
 
@@ -2,12 +2,86 @@
 //!
 //! This module implements the core packet structure for the CConnect protocol.
 //! Packets are JSON-formatted messages with a newline terminator.
+//!
+//! Large transfers are not carried in the JSON itself. The packet advertises
+//! `payloadSize` and `payloadTransferInfo` (usually a `port`) and the bytes
+//! follow on a separate TCP stream. Locally, a [`PayloadStream`] can be
+//! attached to a packet with [`Packet::with_payload`] so that plugins hand
+//! over a reader instead of buffering the whole payload.
 
 use crate::{ProtocolError, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Byte stream carried alongside a packet
+///
+/// Wraps any async reader together with the number of bytes it will yield.
+pub struct PayloadStream {
+    reader: Box<dyn AsyncRead + Send + Unpin>,
+    size: u64,
+}
+
+impl PayloadStream {
+    pub fn new(reader: impl AsyncRead + Send + Unpin + 'static, size: u64) -> Self {
+        Self {
+            reader: Box::new(reader),
+            size,
+        }
+    }
+
+    /// Number of bytes the stream will yield
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn into_reader(self) -> Box<dyn AsyncRead + Send + Unpin> {
+        self.reader
+    }
+}
+
+impl AsyncRead for PayloadStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.reader).poll_read(cx, buf)
+    }
+}
+
+impl fmt::Debug for PayloadStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PayloadStream")
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Holder for a packet's attached payload
+///
+/// A stream cannot be duplicated, so cloning a packet leaves the clone
+/// without a payload, and the payload is ignored when comparing packets.
+#[derive(Debug, Default)]
+struct PayloadSlot(Option<PayloadStream>);
+
+impl Clone for PayloadSlot {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+impl PartialEq for PayloadSlot {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -29,6 +103,9 @@ pub struct Packet {
         skip_serializing_if = "Option::is_none"
     )]
     pub payload_transfer_info: Option<HashMap<String, Value>>,
+
+    #[serde(skip)]
+    payload: PayloadSlot,
 }
 
 impl Packet {
@@ -49,6 +126,7 @@ impl Packet {
             body: packet.body,
             payload_size: packet.payload_size,
             payload_transfer_info: packet.payload_transfer_info,
+            payload: PayloadSlot::default(),
         }
     }
 
@@ -59,6 +137,7 @@ impl Packet {
             body,
             payload_size: None,
             payload_transfer_info: None,
+            payload: PayloadSlot::default(),
         }
     }
 
@@ -69,6 +148,7 @@ impl Packet {
             body,
             payload_size: None,
             payload_transfer_info: None,
+            payload: PayloadSlot::default(),
         }
     }
 
@@ -93,9 +173,7 @@ impl Packet {
             .cloned()
             .collect::<Vec<u8>>();
 
-        serde_json::from_slice(&trimmed).map_err(|e| {
-            ProtocolError::InvalidPacket(format!("Failed to deserialize packet: {}", e))
-        })
+        serde_json::from_slice(&trimmed).map_err(|e| ProtocolError::deserialization("packet", e))
     }
 
     pub fn with_payload_size(mut self, size: i64) -> Self {
@@ -108,6 +186,31 @@ impl Packet {
         self
     }
 
+    /// Attach a payload stream of `size` bytes
+    ///
+    /// Sets `payloadSize`; `payloadTransferInfo` is filled in by whoever
+    /// serves the stream.
+    pub fn with_payload(
+        mut self,
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        size: u64,
+    ) -> Self {
+        self.payload_size = Some(size as i64);
+        self.payload = PayloadSlot(Some(PayloadStream::new(reader, size)));
+        self
+    }
+
+    /// Detach the payload stream, if one is attached
+    ///
+    /// `payloadSize` and `payloadTransferInfo` are left in place.
+    pub fn take_payload(&mut self) -> Option<PayloadStream> {
+        self.payload.0.take()
+    }
+
+    pub fn has_payload(&self) -> bool {
+        self.payload.0.is_some()
+    }
+
     pub fn with_body_field(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
         if let Value::Object(ref mut map) = self.body {
             map.insert(key.into(), value.into());
@@ -173,3 +276,95 @@ where
 pub fn current_timestamp() -> i64 {
     Utc::now().timestamp_millis()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::io::AsyncReadExt;
+
+    fn share_packet() -> Packet {
+        let mut transfer_info = HashMap::new();
+        transfer_info.insert("port".to_string(), json!(1739));
+        Packet::with_id(
+            1_700_000_000_000,
+            "kdeconnect.share.request",
+            json!({ "filename": "photo.jpg" }),
+        )
+        .with_payload_size(2048)
+        .with_payload_transfer_info(transfer_info)
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_invalid_json() {
+        let err = Packet::from_bytes(b"{\"id\": 1, ").unwrap_err();
+        assert!(matches!(err, ProtocolError::Deserialization { .. }));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_payload_descriptor_json_format() {
+        let bytes = share_packet().to_bytes().unwrap();
+        let value: Value = serde_json::from_slice(&bytes).unwrap();
+
+        assert_eq!(
+            value,
+            json!({
+                "id": 1_700_000_000_000_i64,
+                "type": "kdeconnect.share.request",
+                "body": { "filename": "photo.jpg" },
+                "payloadSize": 2048,
+                "payloadTransferInfo": { "port": 1739 },
+            })
+        );
+    }
+
+    #[test]
+    fn test_payload_descriptor_round_trip() {
+        let packet = share_packet();
+        let parsed = Packet::from_bytes(&packet.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(parsed, packet);
+        assert_eq!(parsed.payload_size, Some(2048));
+        assert_eq!(
+            parsed.payload_transfer_info.unwrap()["port"].as_u64(),
+            Some(1739)
+        );
+        assert!(!parsed.has_payload());
+    }
+
+    #[test]
+    fn test_no_payload_keys_without_payload() {
+        let bytes = Packet::new("kdeconnect.ping", json!({}))
+            .to_bytes()
+            .unwrap();
+        let value: Value = serde_json::from_slice(&bytes).unwrap();
+
+        assert!(value.get("payloadSize").is_none());
+        assert!(value.get("payloadTransferInfo").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_with_payload_and_take_payload() {
+        let data = b"hello payload".to_vec();
+        let mut packet = Packet::new("kdeconnect.share.request", json!({}))
+            .with_payload(io::Cursor::new(data.clone()), data.len() as u64);
+
+        assert_eq!(packet.payload_size, Some(data.len() as i64));
+        assert!(packet.has_payload());
+
+        // The stream is not serialized and does not survive a clone
+        let value: Value = serde_json::from_slice(&packet.to_bytes().unwrap()).unwrap();
+        assert_eq!(value["payloadSize"], json!(data.len()));
+        assert!(!packet.clone().has_payload());
+
+        let mut stream = packet.take_payload().unwrap();
+        assert_eq!(stream.size(), data.len() as u64);
+        assert!(packet.take_payload().is_none());
+        assert_eq!(packet.payload_size, Some(data.len() as i64));
+
+        let mut received = Vec::new();
+        stream.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, data);
+    }
+}
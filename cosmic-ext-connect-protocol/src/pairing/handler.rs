@@ -35,6 +35,7 @@ use crate::{Packet, ProtocolError, Result};
 use cosmic_ext_connect_core::crypto::CertificateInfo;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -128,11 +129,11 @@ pub struct PairingHandler {
     /// This device's certificate
     certificate: CertificateInfo,
 
-    /// Pairing status
-    status: PairingStatus,
+    /// Requests in flight (device_id -> Requested or RequestedByPeer)
+    pending: HashMap<String, PairingStatus>,
 
     /// Paired device certificates (device_id -> certificate)
-    paired_devices: std::collections::HashMap<String, Vec<u8>>,
+    paired_devices: HashMap<String, Vec<u8>>,
 
     /// Certificate storage directory
     cert_dir: PathBuf,
@@ -168,8 +169,8 @@ impl PairingHandler {
 
         Ok(Self {
             certificate,
-            status: PairingStatus::Unpaired,
-            paired_devices: std::collections::HashMap::new(),
+            pending: HashMap::new(),
+            paired_devices: HashMap::new(),
             cert_dir,
         })
     }
@@ -184,15 +185,34 @@ impl PairingHandler {
         &self.certificate
     }
 
-    /// Get current pairing status
-    pub fn status(&self) -> PairingStatus {
-        self.status
+    /// Get the pairing status with a device
+    pub fn status(&self, device_id: &str) -> PairingStatus {
+        if let Some(&status) = self.pending.get(device_id) {
+            status
+        } else if self.paired_devices.contains_key(device_id) {
+            PairingStatus::Paired
+        } else {
+            PairingStatus::Unpaired
+        }
+    }
+
+    /// Record a device's status; paired and unpaired follow the stored
+    /// certificates, so only requests are kept
+    fn set_status(&mut self, device_id: &str, status: PairingStatus) {
+        match status {
+            PairingStatus::Requested | PairingStatus::RequestedByPeer => {
+                self.pending.insert(device_id.to_string(), status);
+            }
+            PairingStatus::Paired | PairingStatus::Unpaired => {
+                self.pending.remove(device_id);
+            }
+        }
     }
 
     /// Send pairing request
-    pub fn request_pairing(&mut self) -> Packet {
-        self.status = PairingStatus::Requested;
-        info!("Sending pairing request");
+    pub fn request_pairing(&mut self, device_id: &str) -> Packet {
+        self.set_status(device_id, PairingStatus::Requested);
+        info!("Sending pairing request to device {}", device_id);
         PairingPacket::request()
     }
 
@@ -219,10 +239,10 @@ impl PairingHandler {
 
         if pairing.pair {
             // Pairing request or accept
-            match self.status {
+            match self.status(device_id) {
                 PairingStatus::Unpaired => {
                     // Received pairing request
-                    self.status = PairingStatus::RequestedByPeer;
+                    self.set_status(device_id, PairingStatus::RequestedByPeer);
                     info!("Received pairing request from device {}", device_id);
                     // Don't auto-accept, wait for user confirmation
                     Ok((false, None))
@@ -230,7 +250,7 @@ impl PairingHandler {
                 PairingStatus::Requested => {
                     // Received pairing accept - send confirmation response
                     self.store_device_certificate(device_id, device_cert)?;
-                    self.status = PairingStatus::Paired;
+                    self.set_status(device_id, PairingStatus::Paired);
                     info!(
                         "Pairing accepted by device {} - sending confirmation",
                         device_id
@@ -253,50 +273,60 @@ impl PairingHandler {
             }
         } else {
             // Pairing rejection or unpair
-            if self.status == PairingStatus::Paired {
+            if self.status(device_id) == PairingStatus::Paired {
                 self.remove_device_certificate(device_id)?;
                 info!("Unpaired from device {}", device_id);
             } else {
                 info!("Pairing rejected by device {}", device_id);
             }
-            self.status = PairingStatus::Unpaired;
+            self.set_status(device_id, PairingStatus::Unpaired);
             Ok((false, None))
         }
     }
 
     /// Accept pairing request (user confirmed)
     pub fn accept_pairing(&mut self, device_id: &str, device_cert: &[u8]) -> Result<Packet> {
-        if self.status != PairingStatus::RequestedByPeer {
+        if self.status(device_id) != PairingStatus::RequestedByPeer {
             return Err(ProtocolError::InvalidPacket(
                 "No pairing request pending".to_string(),
             ));
         }
 
         self.store_device_certificate(device_id, device_cert)?;
-        self.status = PairingStatus::Paired;
+        self.set_status(device_id, PairingStatus::Paired);
         info!("Accepted pairing with device {}", device_id);
 
         Ok(PairingPacket::accept())
     }
 
     /// Reject pairing request (user declined)
-    pub fn reject_pairing(&mut self) -> Packet {
-        self.status = PairingStatus::Unpaired;
-        info!("Rejected pairing request");
+    pub fn reject_pairing(&mut self, device_id: &str) -> Packet {
+        self.pending.remove(device_id);
+        info!("Rejected pairing request from device {}", device_id);
         PairingPacket::reject()
     }
 
+    /// Drop an unanswered pairing request (e.g. after it timed out)
+    ///
+    /// Only an outstanding request is cancelled; a completed pairing stays,
+    /// as do requests with other devices.
+    pub fn cancel_request(&mut self, device_id: &str) {
+        if self.pending.remove(device_id).is_some() {
+            info!("Pairing request with device {} cancelled", device_id);
+        }
+    }
+
     /// Unpair from a device
     pub fn unpair(&mut self, device_id: &str) -> Result<Packet> {
         self.remove_device_certificate(device_id)?;
-        self.status = PairingStatus::Unpaired;
+        self.set_status(device_id, PairingStatus::Unpaired);
         info!("Unpairing from device {}", device_id);
         Ok(PairingPacket::unpair())
     }
 
     /// Check if a device is paired
     pub fn is_paired(&self, device_id: &str) -> bool {
-        self.paired_devices.contains_key(device_id) || self.status == PairingStatus::Paired
+        self.status(device_id) == PairingStatus::Paired
     }
 
     /// Store device certificate
@@ -449,7 +479,7 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         let handler = PairingHandler::new("test_device", temp_dir.path()).unwrap();
 
-        assert_eq!(handler.status(), PairingStatus::Unpaired);
+        assert_eq!(handler.status("peer"), PairingStatus::Unpaired);
         assert!(!handler.fingerprint().is_empty());
     }
 
@@ -459,11 +489,37 @@ mod tests {
         let mut handler = PairingHandler::new("test_device", temp_dir.path()).unwrap();
 
         // Send pairing request
-        let request = handler.request_pairing();
-        assert_eq!(handler.status(), PairingStatus::Requested);
+        let request = handler.request_pairing("peer");
+        assert_eq!(handler.status("peer"), PairingStatus::Requested);
         assert!(request.is_type("cconnect.pair"));
     }
 
+    #[test]
+    fn test_status_kept_per_device() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut handler = PairingHandler::new("test_device", temp_dir.path()).unwrap();
+        let cert = CertificateInfo::generate("phone").unwrap().certificate;
+
+        handler.request_pairing("laptop");
+        handler
+            .handle_pairing_packet(&PairingPacket::request(), "phone", &cert)
+            .unwrap();
+        assert_eq!(handler.status("phone"), PairingStatus::RequestedByPeer);
+
+        // Cancelling one request leaves the other pending
+        handler.cancel_request("laptop");
+        assert_eq!(handler.status("laptop"), PairingStatus::Unpaired);
+        assert_eq!(handler.status("phone"), PairingStatus::RequestedByPeer);
+
+        handler.accept_pairing("phone", &cert).unwrap();
+        assert!(handler.is_paired("phone"));
+        assert!(!handler.is_paired("laptop"));
+
+        // A completed pairing is not a request to cancel
+        handler.cancel_request("phone");
+        assert_eq!(handler.status("phone"), PairingStatus::Paired);
+    }
+
     #[test]
     fn test_certificate_fingerprint() {
         let cert1 = CertificateInfo::generate("device1").unwrap();
@@ -1,22 +1,32 @@
 //! Pairing Service
 //!
 //! Manages pairing for multiple devices simultaneously.
+//!
+//! ## Outcomes
+//!
+//! Every pairing request, sent or received, ends in exactly one event:
+//!
+//! - [`PairingEvent::PairingAccepted`] when either side accepts
+//! - [`PairingEvent::PairingRejected`] when the user declines or the device
+//!   answers with `{ "pair": false }`
+//! - [`PairingEvent::PairingTimeout`] when nobody answers within
+//!   [`PairingConfig::timeout`]
+//!
+//! Rejected and timed-out requests leave the device unpaired.
 
 use super::events::PairingEvent;
-use super::handler::{PairingHandler, PairingStatus};
+use super::handler::{PairingHandler, PairingStatus, PAIRING_TIMEOUT};
 use crate::{DeviceInfo, Packet, Result};
 use cosmic_ext_connect_core::crypto::CertificateInfo;
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
+use tokio::time::Instant;
 use tracing::{debug, error, info, warn};
 
-/// Pairing timeout duration (30 seconds)
-const PAIRING_TIMEOUT: Duration = Duration::from_secs(30);
-
 /// Pairing request state
 #[derive(Debug)]
 struct PairingRequest {
@@ -35,7 +45,7 @@ struct PairingRequest {
 pub struct PairingConfig {
     /// Certificate storage directory
     pub cert_dir: PathBuf,
-    /// Pairing timeout duration
+    /// How long a pairing request may stay unanswered (30 seconds by default)
     pub timeout: Duration,
 }
 
@@ -132,7 +142,8 @@ impl PairingService {
 
     /// Request pairing with a device
     ///
-    /// Sends pairing request packet and starts timeout tracking.
+    /// Sends pairing request packet and starts timeout tracking. Does nothing
+    /// if the device is already paired or a request is already pending.
     pub async fn request_pairing(
         &self,
         device_info: DeviceInfo,
@@ -153,9 +164,14 @@ impl PairingService {
         }
         drop(handler);
 
+        if self.has_pending_request(&device_id).await {
+            debug!("Pairing with device {} is already pending", device_id);
+            return Ok(());
+        }
+
         // Create pairing request packet
         let mut handler = self.handler.write().await;
-        let packet = handler.request_pairing();
+        let packet = handler.request_pairing(&device_id);
         drop(handler);
 
         // For Protocol v8 unpaired devices: Ensure we have an active connection
@@ -196,18 +212,9 @@ impl PairingService {
         // Send request over TLS connection (Protocol v8)
         match self.send_pairing_packet(&packet, &device_id).await {
             Ok(_) => {
-                // Track active request
-                let mut requests = self.active_requests.write().await;
-                requests.insert(
-                    device_id.clone(),
-                    PairingRequest {
-                        started_at: Instant::now(),
-                        device_info: device_info.clone(),
-                        remote_addr,
-                        device_cert: Vec::new(), // Will be received in response
-                    },
-                );
-                drop(requests);
+                // Track active request; the certificate arrives with the response
+                self.track_request(device_info, remote_addr, Vec::new())
+                    .await;
 
                 // Emit event
                 let _ = self.event_tx.send(PairingEvent::RequestSent {
@@ -215,9 +222,6 @@ impl PairingService {
                     our_fingerprint: self.fingerprint().to_string(),
                 });
 
-                // Start timeout checker
-                self.spawn_timeout_checker();
-
                 Ok(())
             }
             Err(e) => {
@@ -247,10 +251,11 @@ impl PairingService {
         );
 
         let mut handler = self.handler.write().await;
+        let was_paired = handler.is_paired(device_id);
         let (should_respond, response_packet) =
             handler.handle_pairing_packet(packet, device_id, device_cert)?;
 
-        let status = handler.status();
+        let status = handler.status(device_id);
         drop(handler);
 
         // Handle state changes
@@ -262,17 +267,8 @@ impl PairingService {
                 );
 
                 // Store the pairing request with certificate for later acceptance
-                let mut requests = self.active_requests.write().await;
-                requests.insert(
-                    device_id.clone(),
-                    PairingRequest {
-                        started_at: Instant::now(),
-                        device_info: device_info.clone(),
-                        remote_addr,
-                        device_cert: device_cert.to_vec(),
-                    },
-                );
-                drop(requests);
+                self.track_request(device_info.clone(), remote_addr, device_cert.to_vec())
+                    .await;
 
                 let fingerprint = CertificateInfo::calculate_fingerprint(device_cert);
 
@@ -281,9 +277,6 @@ impl PairingService {
                     device_name: device_info.device_name.clone(),
                     their_fingerprint: fingerprint,
                 });
-
-                // Start timeout checker
-                self.spawn_timeout_checker();
             }
             PairingStatus::Paired => {
                 info!("Successfully paired with device {}", device_id);
@@ -301,8 +294,18 @@ impl PairingService {
                     certificate_fingerprint: fingerprint,
                 });
             }
+            PairingStatus::Unpaired if was_paired => {
+                info!("Device {} unpaired from us", device_id);
+
+                self.active_requests.write().await.remove(device_id);
+                self.drop_connection(device_id).await;
+
+                let _ = self.event_tx.send(PairingEvent::DeviceUnpaired {
+                    device_id: device_id.clone(),
+                });
+            }
             PairingStatus::Unpaired => {
-                debug!("Pairing rejected or unpaired for device {}", device_id);
+                info!("Pairing rejected by device {}", device_id);
 
                 // Remove from active requests
                 let mut requests = self.active_requests.write().await;
@@ -311,7 +314,7 @@ impl PairingService {
 
                 let _ = self.event_tx.send(PairingEvent::PairingRejected {
                     device_id: device_id.clone(),
-                    reason: None,
+                    reason: Some("Rejected by device".to_string()),
                 });
             }
             _ => {}
@@ -326,9 +329,17 @@ impl PairingService {
     }
 
     /// Accept a pairing request (user confirmed)
+    ///
+    /// Accepting a device that is already paired does nothing, so a repeated
+    /// accept succeeds.
     pub async fn accept_pairing(&self, device_id: &str) -> Result<()> {
         info!("Accepting pairing with device {}", device_id);
 
+        if !self.has_pending_request(device_id).await && self.is_paired(device_id).await {
+            debug!("Device {} is already paired, nothing to accept", device_id);
+            return Ok(());
+        }
+
         // Get the stored pairing request with certificate and address
         debug!(
             "Step 1: Retrieving stored pairing request data for {}",
@@ -454,12 +465,23 @@ impl PairingService {
     }
 
     /// Reject a pairing request (user declined)
+    ///
+    /// Does nothing if no request from the device is pending, e.g. because it
+    /// was already answered or timed out. A paired device stays paired.
     pub async fn reject_pairing(&self, device_id: &str) -> Result<()> {
         info!("Rejecting pairing with device {}", device_id);
 
+        if !self.has_pending_request(device_id).await {
+            debug!(
+                "No pending pairing request from {}, nothing to reject",
+                device_id
+            );
+            return Ok(());
+        }
+
         let response = {
             let mut handler = self.handler.write().await;
-            handler.reject_pairing()
+            handler.reject_pairing(device_id)
         };
 
         // Remove from active requests
@@ -479,11 +501,17 @@ impl PairingService {
     }
 
     /// Unpair from a device
+    ///
+    /// Forgets the device's certificate, tells the device (if reachable) and
+    /// closes the connection to it, which stops its plugins. The device needs
+    /// a fresh pairing request to be trusted again. Unpairing a device that is
+    /// not paired is harmless and only repeats these steps.
     pub async fn unpair(&self, device_id: &str) -> Result<()> {
         info!("Unpairing from device {}", device_id);
 
         // Generate unpair packet and update pairing state
         let packet = self.handler.write().await.unpair(device_id)?;
+        self.active_requests.write().await.remove(device_id);
 
         // Send unpair packet to the device via TLS connection
         if let Err(e) = self.send_pairing_packet(&packet, device_id).await {
@@ -494,6 +522,10 @@ impl PairingService {
             debug!("Unpair packet sent successfully to {}", device_id);
         }
 
+        // The connection task writes out the unpair packet queued above before
+        // it handles the close, and the disconnect waits for it to finish
+        self.drop_connection(device_id).await;
+
         let _ = self.event_tx.send(PairingEvent::DeviceUnpaired {
             device_id: device_id.to_string(),
         });
@@ -501,6 +533,20 @@ impl PairingService {
         Ok(())
     }
 
+    /// Close the connection to a device that is no longer trusted
+    async fn drop_connection(&self, device_id: &str) {
+        if let Some(conn_mgr) = &self.connection_manager {
+            if let Err(e) = conn_mgr.read().await.disconnect(device_id).await {
+                warn!("Failed to disconnect unpaired device {}: {}", device_id, e);
+            }
+        }
+    }
+
+    /// Check if a pairing request to or from a device is pending
+    pub async fn has_pending_request(&self, device_id: &str) -> bool {
+        self.active_requests.read().await.contains_key(device_id)
+    }
+
     /// Check if a device is paired
     pub async fn is_paired(&self, device_id: &str) -> bool {
         let handler = self.handler.read().await;
@@ -527,43 +573,48 @@ impl PairingService {
         }
     }
 
-    /// Spawn timeout checker task
-    fn spawn_timeout_checker(&self) {
+    /// Track a pairing request and expire it after the configured timeout
+    async fn track_request(
+        &self,
+        device_info: DeviceInfo,
+        remote_addr: SocketAddr,
+        device_cert: Vec<u8>,
+    ) {
+        let device_id = device_info.device_id.clone();
+        let started_at = Instant::now();
+        self.active_requests.write().await.insert(
+            device_id.clone(),
+            PairingRequest {
+                started_at,
+                device_info,
+                remote_addr,
+                device_cert,
+            },
+        );
+
         let active_requests = self.active_requests.clone();
+        let handler = self.handler.clone();
         let event_tx = self.event_tx.clone();
         let timeout = self.config.timeout;
 
         tokio::spawn(async move {
-            tokio::time::sleep(Duration::from_secs(5)).await;
-
-            loop {
-                let mut requests = active_requests.write().await;
-                let now = Instant::now();
-                let mut timed_out = Vec::new();
-
-                for (device_id, request) in requests.iter() {
-                    if now.duration_since(request.started_at) > timeout {
-                        timed_out.push(device_id.clone());
-                    }
-                }
-
-                for device_id in timed_out {
-                    info!("Pairing request timed out for device {}", device_id);
-                    requests.remove(&device_id);
-
-                    let _ = event_tx.send(PairingEvent::PairingTimeout {
-                        device_id: device_id.clone(),
-                    });
-                }
-
-                drop(requests);
+            tokio::time::sleep_until(started_at + timeout).await;
+
+            // Only expire this request, not a newer one for the same device
+            let mut requests = active_requests.write().await;
+            if !requests
+                .get(&device_id)
+                .is_some_and(|request| request.started_at == started_at)
+            {
+                return;
+            }
+            requests.remove(&device_id);
+            drop(requests);
 
-                if active_requests.read().await.is_empty() {
-                    break;
-                }
+            info!("Pairing request timed out for device {}", device_id);
+            handler.write().await.cancel_request(&device_id);
 
-                tokio::time::sleep(Duration::from_secs(5)).await;
-            }
+            let _ = event_tx.send(PairingEvent::PairingTimeout { device_id });
         });
     }
 }
@@ -571,8 +622,182 @@ impl PairingService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pairing::PairingPacket;
+    use crate::DeviceType;
     use tempfile::TempDir;
 
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    fn service(temp_dir: &TempDir) -> PairingService {
+        let config = PairingConfig {
+            cert_dir: temp_dir.path().to_path_buf(),
+            timeout: TIMEOUT,
+        };
+        PairingService::new("test_device", config).unwrap()
+    }
+
+    fn peer() -> (DeviceInfo, Vec<u8>, SocketAddr) {
+        let info = DeviceInfo::new("Phone", DeviceType::Phone, 1716);
+        let cert = CertificateInfo::generate(&info.device_id)
+            .unwrap()
+            .certificate;
+        (info, cert, "192.168.1.50:1716".parse().unwrap())
+    }
+
+    /// Send a pairing request to `peer` without a connection
+    async fn request(service: &PairingService, peer: &DeviceInfo, addr: SocketAddr) {
+        service
+            .handler
+            .write()
+            .await
+            .request_pairing(&peer.device_id);
+        service.track_request(peer.clone(), addr, Vec::new()).await;
+    }
+
+    /// Whether another event arrives before the request would have expired
+    async fn quiet(events: &mut mpsc::UnboundedReceiver<PairingEvent>) -> bool {
+        tokio::time::timeout(TIMEOUT * 2, events.recv())
+            .await
+            .is_err()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_request_accepted() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = service(&temp_dir);
+        let mut events = service.subscribe().await;
+        let (peer, cert, addr) = peer();
+
+        request(&service, &peer, addr).await;
+        service
+            .handle_pairing_packet(&PairingPacket::accept(), &peer, &cert, addr)
+            .await
+            .unwrap();
+
+        match events.recv().await {
+            Some(PairingEvent::PairingAccepted {
+                device_id,
+                certificate_fingerprint,
+                ..
+            }) => {
+                assert_eq!(device_id, peer.device_id);
+                assert_eq!(
+                    certificate_fingerprint,
+                    CertificateInfo::calculate_fingerprint(&cert)
+                );
+            }
+            other => panic!("expected acceptance, got {:?}", other),
+        }
+        assert!(service.is_paired(&peer.device_id).await);
+
+        // An answered request never times out
+        assert!(quiet(&mut events).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_request_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = service(&temp_dir);
+        let mut events = service.subscribe().await;
+        let (peer, cert, addr) = peer();
+
+        request(&service, &peer, addr).await;
+        service
+            .handle_pairing_packet(&PairingPacket::reject(), &peer, &cert, addr)
+            .await
+            .unwrap();
+
+        match events.recv().await {
+            Some(PairingEvent::PairingRejected { device_id, reason }) => {
+                assert_eq!(device_id, peer.device_id);
+                assert!(reason.is_some());
+            }
+            other => panic!("expected rejection, got {:?}", other),
+        }
+        assert_eq!(
+            service.handler.read().await.status(&peer.device_id),
+            PairingStatus::Unpaired
+        );
+        assert!(quiet(&mut events).await);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unanswered_request_times_out() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = service(&temp_dir);
+        let mut events = service.subscribe().await;
+        let (peer, cert, addr) = peer();
+
+        let started = Instant::now();
+        service
+            .handle_pairing_packet(&PairingPacket::request(), &peer, &cert, addr)
+            .await
+            .unwrap();
+        assert!(events.recv().await.unwrap().is_request_received());
+
+        match events.recv().await {
+            Some(PairingEvent::PairingTimeout { device_id }) => {
+                assert_eq!(device_id, peer.device_id);
+            }
+            other => panic!("expected timeout, got {:?}", other),
+        }
+        assert!(started.elapsed() >= TIMEOUT);
+        assert_eq!(
+            service.handler.read().await.status(&peer.device_id),
+            PairingStatus::Unpaired
+        );
+
+        // The expired request can no longer be accepted
+        assert!(service.accept_pairing(&peer.device_id).await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_repeated_answers_are_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = service(&temp_dir);
+        let mut events = service.subscribe().await;
+        let (peer, cert, addr) = peer();
+
+        service
+            .handle_pairing_packet(&PairingPacket::request(), &peer, &cert, addr)
+            .await
+            .unwrap();
+        match events.recv().await {
+            Some(PairingEvent::RequestReceived {
+                their_fingerprint, ..
+            }) => {
+                assert_eq!(
+                    their_fingerprint,
+                    CertificateInfo::calculate_fingerprint(&cert)
+                );
+            }
+            other => panic!("expected request, got {:?}", other),
+        }
+
+        service.reject_pairing(&peer.device_id).await.unwrap();
+        assert!(matches!(
+            events.recv().await,
+            Some(PairingEvent::PairingRejected { .. })
+        ));
+        service.reject_pairing(&peer.device_id).await.unwrap();
+        assert!(quiet(&mut events).await);
+
+        // Once paired, accepting again succeeds and rejecting does not unpair
+        request(&service, &peer, addr).await;
+        service
+            .handle_pairing_packet(&PairingPacket::accept(), &peer, &cert, addr)
+            .await
+            .unwrap();
+        assert!(matches!(
+            events.recv().await,
+            Some(PairingEvent::PairingAccepted { .. })
+        ));
+        service.accept_pairing(&peer.device_id).await.unwrap();
+        service.reject_pairing(&peer.device_id).await.unwrap();
+        assert!(service.is_paired(&peer.device_id).await);
+        assert!(quiet(&mut events).await);
+    }
+
     #[tokio::test]
     async fn test_pairing_service_creation() {
         let temp_dir = TempDir::new().unwrap();
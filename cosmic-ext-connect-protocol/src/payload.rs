@@ -59,14 +59,26 @@
 //! let client = TlsPayloadClient::new(remote_addr, port, &tls_config).await?;
 //! client.receive_file("/path/to/save/file.pdf", size).await?;
 //! ```
-
-use crate::fs_utils::{cleanup_partial_file, create_file_safe, write_file_safe};
+//!
+//! ## Integrity Check
+//!
+//! TLS protects the bytes on the wire, but not every transport will, so a
+//! sender may put the BLAKE3 hash of the file in the packet body under
+//! [`PAYLOAD_HASH_FIELD`]. A client given that hash with `with_expected_hash`
+//! hashes the payload while writing it; on a mismatch the file is removed and
+//! the receive fails with [`ProtocolError::IntegrityMismatch`].
+
+use crate::bandwidth::BandwidthLimiter;
+use crate::fs_utils::{
+    cleanup_partial_file, create_file_safe, is_disk_full_error, write_file_safe,
+};
 use crate::{ProtocolError, Result, TlsConfig};
 use std::net::{SocketAddr, ToSocketAddrs};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio::fs::File;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
 use tokio::time::{timeout, Duration};
 use tokio_rustls::{TlsAcceptor, TlsConnector};
 use tracing::{debug, error, info, warn};
@@ -84,6 +96,48 @@ const BUFFER_SIZE: usize = 65536;
 const PORT_RANGE_START: u16 = 1739;
 const PORT_RANGE_END: u16 = 1764;
 
+/// Packet body field holding the BLAKE3 hash (hex) of a file payload
+pub const PAYLOAD_HASH_FIELD: &str = "payloadHash";
+
+/// Compute the BLAKE3 hash of a file as sent in [`PAYLOAD_HASH_FIELD`]
+pub fn payload_hash(path: impl AsRef<Path>) -> Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher
+        .update_reader(std::fs::File::open(path).map_err(ProtocolError::Io)?)
+        .map_err(ProtocolError::Io)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// Compute the BLAKE3 hash of the first `len` bytes of a file
+///
+/// Sent with a resume request so the sender can check that the receiver's
+/// partial file matches the start of the original before appending to it.
+pub fn prefix_hash(path: impl AsRef<Path>, len: u64) -> Result<String> {
+    Ok(prefix_hasher(path.as_ref(), len)?
+        .finalize()
+        .to_hex()
+        .to_string())
+}
+
+/// Hasher fed with the first `len` bytes of a file
+fn prefix_hasher(path: &Path, len: u64) -> Result<blake3::Hasher> {
+    use std::io::Read;
+
+    let file = std::fs::File::open(path).map_err(ProtocolError::Io)?;
+    let mut hasher = blake3::Hasher::new();
+    let read = hasher
+        .update_reader(file.take(len))
+        .map_err(ProtocolError::Io)?
+        .count();
+    if read < len {
+        return Err(ProtocolError::Io(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            format!("{} is shorter than {} bytes", path.display(), len),
+        )));
+    }
+    Ok(hasher)
+}
+
 /// Information about a file to be transferred
 ///
 /// Contains metadata extracted from the filesystem.
@@ -103,6 +157,9 @@ pub struct FileTransferInfo {
 
     /// Last modified time (UNIX milliseconds)
     pub last_modified: Option<i64>,
+
+    /// BLAKE3 hash of the content, if computed with [`Self::with_hash`]
+    pub hash: Option<String>,
 }
 
 impl FileTransferInfo {
@@ -146,8 +203,21 @@ impl FileTransferInfo {
             path: path.to_string_lossy().to_string(),
             creation_time,
             last_modified,
+            hash: None,
         })
     }
+
+    /// Hash the file so the receiver can verify the payload
+    ///
+    /// Reads the whole file on a blocking thread.
+    pub async fn with_hash(mut self) -> Result<Self> {
+        let path = self.path.clone();
+        let hash = tokio::task::spawn_blocking(move || payload_hash(path))
+            .await
+            .map_err(|e| ProtocolError::Plugin(format!("Hash task panicked: {}", e)))??;
+        self.hash = Some(hash);
+        Ok(self)
+    }
 }
 
 /// Converts FileTransferInfo to Share plugin's FileShareInfo
@@ -159,6 +229,7 @@ impl From<FileTransferInfo> for crate::plugins::share::FileShareInfo {
             creation_time: info.creation_time,
             last_modified: info.last_modified,
             open: false,
+            hash: info.hash,
         }
     }
 }
@@ -169,6 +240,292 @@ impl From<FileTransferInfo> for crate::plugins::share::FileShareInfo {
 /// Return `false` to cancel the transfer.
 pub type ProgressCallback = Box<dyn Fn(u64, u64) -> bool + Send + Sync>;
 
+/// Failure reason reported when the destination disk is full
+pub const TRANSFER_FAILED_DISK_FULL: &str = "disk_full";
+
+/// Failure reason reported when the transfer was cancelled
+pub const TRANSFER_FAILED_CANCELLED: &str = "cancelled";
+
+/// Failure reason reported when the remote stopped responding
+pub const TRANSFER_FAILED_TIMEOUT: &str = "timeout";
+
+/// Failure reason reported when the payload does not match its hash
+pub const TRANSFER_FAILED_INTEGRITY: &str = "integrity_mismatch";
+
+/// Failure reason reported for any other I/O or network error
+pub const TRANSFER_FAILED_IO: &str = "io_error";
+
+/// Outcome of a payload receive, reported to an optional event channel
+///
+/// Lets callers (share plugin, daemon) react to specific failures such as a
+/// full download directory without string-matching on error messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferEvent {
+    /// All expected bytes were written to `path`
+    Completed { path: PathBuf, bytes: u64 },
+    /// The transfer was aborted and the partial file at `path` removed
+    Failed {
+        path: PathBuf,
+        reason: &'static str,
+        bytes_written: u64,
+    },
+}
+
+impl TransferEvent {
+    /// Map a receive error to one of the `TRANSFER_FAILED_*` reasons
+    pub fn failure_reason(error: &ProtocolError) -> &'static str {
+        match error {
+            ProtocolError::ResourceExhausted(_) => TRANSFER_FAILED_DISK_FULL,
+            ProtocolError::Cancelled(_) => TRANSFER_FAILED_CANCELLED,
+            ProtocolError::Io(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+                TRANSFER_FAILED_CANCELLED
+            }
+            ProtocolError::Timeout(_) => TRANSFER_FAILED_TIMEOUT,
+            ProtocolError::IntegrityMismatch(_) => TRANSFER_FAILED_INTEGRITY,
+            _ => TRANSFER_FAILED_IO,
+        }
+    }
+
+    /// Whether a failure with `reason` leaves a partial file worth resuming
+    ///
+    /// Only dropped connections and timeouts qualify; a cancelled, corrupted
+    /// or disk-full transfer starts over.
+    pub fn is_resumable(reason: &str) -> bool {
+        reason == TRANSFER_FAILED_IO || reason == TRANSFER_FAILED_TIMEOUT
+    }
+}
+
+/// Receive settings shared by the plain and TLS payload clients
+#[derive(Default)]
+struct ReceiveOptions {
+    progress_callback: Option<ProgressCallback>,
+    event_sender: Option<mpsc::UnboundedSender<TransferEvent>>,
+    expected_hash: Option<String>,
+    resumable: bool,
+}
+
+impl ReceiveOptions {
+    fn send_event(&self, event: TransferEvent) {
+        if let Some(tx) = &self.event_sender {
+            let _ = tx.send(event);
+        }
+    }
+}
+
+/// Open `save_path` to receive a payload into, keeping the first `offset` bytes
+///
+/// With an offset of 0 the file is created (or truncated). Otherwise the
+/// existing file is cut back to `offset` bytes, dropping anything written
+/// after the last recorded position, and positioned at its end.
+async fn open_for_receive(save_path: &Path, offset: u64) -> Result<File> {
+    if offset == 0 {
+        return create_file_safe(save_path).await;
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(save_path)
+        .await
+        .map_err(|e| {
+            ProtocolError::from_io_error(e, &format!("opening {} to resume", save_path.display()))
+        })?;
+    let len = file.metadata().await.map_err(ProtocolError::Io)?.len();
+    if len < offset {
+        return Err(ProtocolError::InvalidState(format!(
+            "Cannot resume {} at {} bytes, only {} on disk",
+            save_path.display(),
+            offset,
+            len
+        )));
+    }
+    file.set_len(offset).await.map_err(ProtocolError::Io)?;
+    file.seek(std::io::SeekFrom::Start(offset))
+        .await
+        .map_err(ProtocolError::Io)?;
+    Ok(file)
+}
+
+/// Stream bytes `offset..expected_size` from `reader` into `sink`, which backs `save_path`
+///
+/// Shared by the plain and TLS payload clients. On any error the partial file
+/// is removed and a [`TransferEvent::Failed`] is emitted; a full disk aborts
+/// immediately with `ResourceExhausted` rather than surfacing a raw I/O error.
+/// A resumable receive keeps the partial file when the connection drops.
+///
+/// With an expected hash, the bytes are hashed as they are written (after the
+/// first `offset` bytes already in `save_path`) and checked once the transfer
+/// is complete.
+async fn receive_payload<R, W>(
+    reader: &mut R,
+    sink: &mut W,
+    save_path: &Path,
+    offset: u64,
+    expected_size: u64,
+    options: &ReceiveOptions,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut total_bytes = offset;
+
+    let result = async {
+        let mut hasher = match options.expected_hash {
+            Some(_) if offset > 0 => {
+                let path = save_path.to_path_buf();
+                Some(
+                    tokio::task::spawn_blocking(move || prefix_hasher(&path, offset))
+                        .await
+                        .map_err(|e| {
+                            ProtocolError::Plugin(format!("Hash task panicked: {}", e))
+                        })??,
+                )
+            }
+            Some(_) => Some(blake3::Hasher::new()),
+            None => None,
+        };
+
+        while total_bytes < expected_size {
+            let remaining = expected_size - total_bytes;
+            let to_read = std::cmp::min(remaining, BUFFER_SIZE as u64) as usize;
+
+            // Read from stream
+            let bytes_read = timeout(TRANSFER_TIMEOUT, reader.read(&mut buffer[..to_read]))
+                .await
+                .map_err(|_| {
+                    ProtocolError::Timeout("Stream read timeout during file transfer".to_string())
+                })?
+                .map_err(ProtocolError::Io)?;
+
+            if bytes_read == 0 {
+                return Err(ProtocolError::Io(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Connection closed prematurely: received {} bytes, expected {}",
+                        total_bytes, expected_size
+                    ),
+                )));
+            }
+
+            // Write to file with safe error handling (detects disk full)
+            write_file_safe(sink, &buffer[..bytes_read]).await?;
+            if let Some(hasher) = hasher.as_mut() {
+                hasher.update(&buffer[..bytes_read]);
+            }
+
+            total_bytes += bytes_read as u64;
+
+            debug!(
+                "Received {} bytes ({}/{} total)",
+                bytes_read, total_bytes, expected_size
+            );
+
+            // Call progress callback if set
+            if let Some(callback) = &options.progress_callback {
+                if !callback(total_bytes, expected_size) {
+                    info!("Transfer cancelled by progress callback");
+                    return Err(ProtocolError::Io(std::io::Error::new(
+                        std::io::ErrorKind::Interrupted,
+                        "Transfer cancelled",
+                    )));
+                }
+            }
+        }
+
+        // Flush file (buffered data may still hit a full disk here)
+        sink.flush().await.map_err(|e| {
+            if is_disk_full_error(&e) {
+                ProtocolError::ResourceExhausted("Disk full during file write".to_string())
+            } else {
+                ProtocolError::Io(e)
+            }
+        })?;
+
+        if let (Some(expected), Some(hasher)) = (options.expected_hash.as_deref(), hasher) {
+            let actual = hasher.finalize().to_hex();
+            if !actual.as_str().eq_ignore_ascii_case(expected) {
+                return Err(ProtocolError::IntegrityMismatch(format!(
+                    "{:?}: expected BLAKE3 {}, received {}",
+                    save_path, expected, actual
+                )));
+            }
+        }
+
+        Ok(())
+    }
+    .await;
+
+    let event = match &result {
+        Ok(()) => {
+            info!(
+                "File transfer complete: {} bytes received to {:?}",
+                total_bytes, save_path
+            );
+            TransferEvent::Completed {
+                path: save_path.to_path_buf(),
+                bytes: total_bytes,
+            }
+        }
+        Err(e) => {
+            let reason = TransferEvent::failure_reason(e);
+            if reason == TRANSFER_FAILED_DISK_FULL {
+                error!(
+                    "Disk full after {} bytes, aborting transfer to {:?}",
+                    total_bytes, save_path
+                );
+            }
+            if options.resumable && TransferEvent::is_resumable(reason) {
+                // Make sure what was received is on disk before it is resumed from
+                let _ = sink.flush().await;
+                warn!(
+                    "Transfer interrupted after {} bytes, keeping partial file {:?} to resume",
+                    total_bytes, save_path
+                );
+            } else {
+                // Clean up partial file on error
+                warn!("Transfer failed, cleaning up partial file: {:?}", save_path);
+                cleanup_partial_file(save_path).await;
+            }
+            TransferEvent::Failed {
+                path: save_path.to_path_buf(),
+                reason,
+                bytes_written: total_bytes,
+            }
+        }
+    };
+
+    options.send_event(event);
+    result
+}
+
+/// Open `save_path` at `offset` and receive the rest of the payload into it
+async fn receive_into<R>(
+    reader: &mut R,
+    save_path: &Path,
+    offset: u64,
+    expected_size: u64,
+    options: &ReceiveOptions,
+) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut file = match open_for_receive(save_path, offset).await {
+        Ok(f) => f,
+        Err(e) => {
+            warn!("Failed to open file {:?}: {}", save_path, e);
+            options.send_event(TransferEvent::Failed {
+                path: save_path.to_path_buf(),
+                reason: TransferEvent::failure_reason(&e),
+                bytes_written: 0,
+            });
+            return Err(e);
+        }
+    };
+
+    receive_payload(reader, &mut file, save_path, offset, expected_size, options).await
+}
+
 /// TCP server for sending file payloads
 ///
 /// Listens on an available port and accepts a single connection
@@ -177,6 +534,7 @@ pub struct PayloadServer {
     listener: TcpListener,
     port: u16,
     progress_callback: Option<ProgressCallback>,
+    bandwidth_limiter: BandwidthLimiter,
 }
 
 impl PayloadServer {
@@ -197,6 +555,7 @@ impl PayloadServer {
                     listener,
                     port,
                     progress_callback: None,
+                    bandwidth_limiter: BandwidthLimiter::unlimited(),
                 });
             }
         }
@@ -237,6 +596,7 @@ impl PayloadServer {
                     listener,
                     port,
                     progress_callback: None,
+                    bandwidth_limiter: BandwidthLimiter::unlimited(),
                 });
             }
         }
@@ -269,6 +629,14 @@ impl PayloadServer {
         self
     }
 
+    /// Limit the send rate with a shared bandwidth limiter
+    ///
+    /// Transfers using clones of the same limiter share its rate.
+    pub fn with_bandwidth_limit(mut self, limiter: BandwidthLimiter) -> Self {
+        self.bandwidth_limiter = limiter;
+        self
+    }
+
     /// Get the port this server is listening on
     pub fn port(&self) -> u16 {
         self.port
@@ -348,6 +716,8 @@ impl PayloadServer {
                 break; // EOF
             }
 
+            self.bandwidth_limiter.consume(bytes_read).await;
+
             // Write to stream
             timeout(TRANSFER_TIMEOUT, stream.write_all(&buffer[..bytes_read]))
                 .await
@@ -395,7 +765,7 @@ impl PayloadServer {
 /// Connects to a remote payload server and downloads file data.
 pub struct PayloadClient {
     stream: TcpStream,
-    progress_callback: Option<ProgressCallback>,
+    options: ReceiveOptions,
 }
 
 impl PayloadClient {
@@ -448,7 +818,7 @@ impl PayloadClient {
 
         Ok(Self {
             stream,
-            progress_callback: None,
+            options: ReceiveOptions::default(),
         })
     }
 
@@ -467,7 +837,34 @@ impl PayloadClient {
     /// }));
     /// ```
     pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
-        self.progress_callback = Some(callback);
+        self.options.progress_callback = Some(callback);
+        self
+    }
+
+    /// Report the transfer outcome as a [`TransferEvent`] on `sender`
+    ///
+    /// A [`TransferEvent::Failed`] with reason [`TRANSFER_FAILED_DISK_FULL`]
+    /// is sent when the download directory fills up mid-transfer.
+    pub fn with_events(mut self, sender: mpsc::UnboundedSender<TransferEvent>) -> Self {
+        self.options.event_sender = Some(sender);
+        self
+    }
+
+    /// Verify the received bytes against a BLAKE3 hash (hex)
+    ///
+    /// On a mismatch the file is removed and the receive fails with
+    /// [`ProtocolError::IntegrityMismatch`].
+    pub fn with_expected_hash(mut self, hash: impl Into<String>) -> Self {
+        self.options.expected_hash = Some(hash.into());
+        self
+    }
+
+    /// Keep the partial file if the connection drops or times out
+    ///
+    /// The received prefix can then be completed with [`Self::resume_file`]
+    /// on a new connection. Other failures still remove the file.
+    pub fn resumable(mut self) -> Self {
+        self.options.resumable = true;
         self
     }
 
@@ -487,6 +884,9 @@ impl PayloadClient {
     /// - Transfer fails or times out
     /// - Size mismatch (received != expected)
     /// - Transfer is cancelled via progress callback
+    /// - Disk fills up (`ResourceExhausted`; the partial file is removed)
+    /// - The payload does not match the expected hash (`IntegrityMismatch`;
+    ///   the file is removed)
     ///
     /// # Example
     ///
@@ -494,97 +894,40 @@ impl PayloadClient {
     /// let client = PayloadClient::new("192.168.1.100", 1739).await?;
     /// client.receive_file("/tmp/received_file.pdf", 1048576).await?;
     /// ```
-    pub async fn receive_file(
+    pub async fn receive_file(self, save_path: impl AsRef<Path>, expected_size: u64) -> Result<()> {
+        self.resume_file(save_path, 0, expected_size).await
+    }
+
+    /// Receive the rest of a file whose first `offset` bytes are already saved
+    ///
+    /// The server streams bytes `offset..expected_size`, which are appended
+    /// to `save_path` after cutting it back to `offset` bytes. Progress is
+    /// reported against the whole file, and an expected hash covers the whole
+    /// file too. An `offset` of 0 is the same as [`Self::receive_file`].
+    ///
+    /// # Errors
+    ///
+    /// As for [`Self::receive_file`], plus `InvalidState` if `save_path` holds
+    /// fewer than `offset` bytes.
+    pub async fn resume_file(
         mut self,
         save_path: impl AsRef<Path>,
+        offset: u64,
         expected_size: u64,
     ) -> Result<()> {
         let save_path = save_path.as_ref();
         info!(
-            "Receiving file to {:?} ({} bytes expected)",
-            save_path, expected_size
+            "Receiving file to {:?} ({} bytes expected, from {})",
+            save_path, expected_size, offset
         );
-
-        // Create file with safe error handling
-        let mut file = match create_file_safe(save_path).await {
-            Ok(f) => f,
-            Err(e) => {
-                warn!("Failed to create file {:?}: {}", save_path, e);
-                return Err(e);
-            }
-        };
-
-        // Read and write data
-        let mut buffer = vec![0u8; BUFFER_SIZE];
-        let mut total_bytes = 0u64;
-
-        let result = async {
-            while total_bytes < expected_size {
-                let remaining = expected_size - total_bytes;
-                let to_read = std::cmp::min(remaining, BUFFER_SIZE as u64) as usize;
-
-                // Read from stream
-                let bytes_read =
-                    timeout(TRANSFER_TIMEOUT, self.stream.read(&mut buffer[..to_read]))
-                        .await
-                        .map_err(|_| {
-                            ProtocolError::Timeout(
-                                "Stream read timeout during file transfer".to_string(),
-                            )
-                        })?
-                        .map_err(ProtocolError::Io)?;
-
-                if bytes_read == 0 {
-                    return Err(ProtocolError::Io(std::io::Error::new(
-                        std::io::ErrorKind::UnexpectedEof,
-                        format!(
-                            "Connection closed prematurely: received {} bytes, expected {}",
-                            total_bytes, expected_size
-                        ),
-                    )));
-                }
-
-                // Write to file with safe error handling
-                write_file_safe(&mut file, &buffer[..bytes_read]).await?;
-
-                total_bytes += bytes_read as u64;
-
-                debug!(
-                    "Received {} bytes ({}/{} total)",
-                    bytes_read, total_bytes, expected_size
-                );
-
-                // Call progress callback if set
-                if let Some(ref callback) = self.progress_callback {
-                    if !callback(total_bytes, expected_size) {
-                        info!("Transfer cancelled by progress callback");
-                        return Err(ProtocolError::Io(std::io::Error::new(
-                            std::io::ErrorKind::Interrupted,
-                            "Transfer cancelled",
-                        )));
-                    }
-                }
-            }
-
-            // Flush file
-            file.flush().await.map_err(ProtocolError::Io)?;
-
-            info!(
-                "File transfer complete: {} bytes received to {:?}",
-                total_bytes, save_path
-            );
-
-            Ok(())
-        }
-        .await;
-
-        // Clean up partial file on error
-        if result.is_err() {
-            warn!("Transfer failed, cleaning up partial file: {:?}", save_path);
-            cleanup_partial_file(save_path).await;
-        }
-
-        result
+        receive_into(
+            &mut self.stream,
+            save_path,
+            offset,
+            expected_size,
+            &self.options,
+        )
+        .await
     }
 }
 
@@ -613,7 +956,7 @@ impl PayloadClient {
 /// ```
 pub struct TlsPayloadClient {
     stream: tokio_rustls::server::TlsStream<TcpStream>,
-    progress_callback: Option<ProgressCallback>,
+    options: ReceiveOptions,
 }
 
 impl TlsPayloadClient {
@@ -699,7 +1042,7 @@ impl TlsPayloadClient {
 
         Ok(Self {
             stream: tls_stream,
-            progress_callback: None,
+            options: ReceiveOptions::default(),
         })
     }
 
@@ -708,7 +1051,34 @@ impl TlsPayloadClient {
     /// The callback receives (bytes_transferred, total_bytes) and returns
     /// `true` to continue or `false` to cancel the transfer.
     pub fn with_progress(mut self, callback: ProgressCallback) -> Self {
-        self.progress_callback = Some(callback);
+        self.options.progress_callback = Some(callback);
+        self
+    }
+
+    /// Report the transfer outcome as a [`TransferEvent`] on `sender`
+    ///
+    /// A [`TransferEvent::Failed`] with reason [`TRANSFER_FAILED_DISK_FULL`]
+    /// is sent when the download directory fills up mid-transfer.
+    pub fn with_events(mut self, sender: mpsc::UnboundedSender<TransferEvent>) -> Self {
+        self.options.event_sender = Some(sender);
+        self
+    }
+
+    /// Verify the received bytes against a BLAKE3 hash (hex)
+    ///
+    /// On a mismatch the file is removed and the receive fails with
+    /// [`ProtocolError::IntegrityMismatch`].
+    pub fn with_expected_hash(mut self, hash: impl Into<String>) -> Self {
+        self.options.expected_hash = Some(hash.into());
+        self
+    }
+
+    /// Keep the partial file if the connection drops or times out
+    ///
+    /// The received prefix can then be completed with [`Self::resume_file`]
+    /// on a new connection. Other failures still remove the file.
+    pub fn resumable(mut self) -> Self {
+        self.options.resumable = true;
         self
     }
 
@@ -728,100 +1098,43 @@ impl TlsPayloadClient {
     /// - Transfer fails or times out
     /// - Size mismatch (received != expected)
     /// - Transfer is cancelled via progress callback
-    pub async fn receive_file(
+    /// - Disk fills up (`ResourceExhausted`; the partial file is removed)
+    /// - The payload does not match the expected hash (`IntegrityMismatch`;
+    ///   the file is removed)
+    pub async fn receive_file(self, save_path: impl AsRef<Path>, expected_size: u64) -> Result<()> {
+        self.resume_file(save_path, 0, expected_size).await
+    }
+
+    /// Receive the rest of a file whose first `offset` bytes are already saved
+    ///
+    /// The server streams bytes `offset..expected_size`, which are appended
+    /// to `save_path` after cutting it back to `offset` bytes. Progress is
+    /// reported against the whole file, and an expected hash covers the whole
+    /// file too. An `offset` of 0 is the same as [`Self::receive_file`].
+    ///
+    /// # Errors
+    ///
+    /// As for [`Self::receive_file`], plus `InvalidState` if `save_path` holds
+    /// fewer than `offset` bytes.
+    pub async fn resume_file(
         mut self,
         save_path: impl AsRef<Path>,
+        offset: u64,
         expected_size: u64,
     ) -> Result<()> {
         let save_path = save_path.as_ref();
         info!(
-            "Receiving file to {:?} ({} bytes expected) over TLS",
-            save_path, expected_size
+            "Receiving file to {:?} ({} bytes expected, from {}) over TLS",
+            save_path, expected_size, offset
         );
-
-        // Create file with safe error handling
-        let mut file = match create_file_safe(save_path).await {
-            Ok(f) => f,
-            Err(e) => {
-                warn!("Failed to create file {:?}: {}", save_path, e);
-                return Err(e);
-            }
-        };
-
-        // Read and write data
-        let mut buffer = vec![0u8; BUFFER_SIZE];
-        let mut total_bytes = 0u64;
-
-        let result = async {
-            while total_bytes < expected_size {
-                let remaining = expected_size - total_bytes;
-                let to_read = std::cmp::min(remaining, BUFFER_SIZE as u64) as usize;
-
-                // Read from TLS stream
-                let bytes_read: usize =
-                    timeout(TRANSFER_TIMEOUT, self.stream.read(&mut buffer[..to_read]))
-                        .await
-                        .map_err(|_| {
-                            ProtocolError::Timeout(
-                                "TLS stream read timeout during file transfer".to_string(),
-                            )
-                        })?
-                        .map_err(ProtocolError::Io)?;
-
-                if bytes_read == 0 {
-                    return Err(ProtocolError::Io(std::io::Error::new(
-                        std::io::ErrorKind::UnexpectedEof,
-                        format!(
-                            "TLS connection closed prematurely: received {} bytes, expected {}",
-                            total_bytes, expected_size
-                        ),
-                    )));
-                }
-
-                // Write to file with safe error handling
-                write_file_safe(&mut file, &buffer[..bytes_read]).await?;
-
-                total_bytes += bytes_read as u64;
-
-                debug!(
-                    "Received {} bytes over TLS ({}/{} total)",
-                    bytes_read, total_bytes, expected_size
-                );
-
-                // Call progress callback if set
-                if let Some(ref callback) = self.progress_callback {
-                    if !callback(total_bytes, expected_size) {
-                        info!("Transfer cancelled by progress callback");
-                        return Err(ProtocolError::Io(std::io::Error::new(
-                            std::io::ErrorKind::Interrupted,
-                            "Transfer cancelled",
-                        )));
-                    }
-                }
-            }
-
-            // Flush file
-            file.flush().await.map_err(ProtocolError::Io)?;
-
-            info!(
-                "TLS file transfer complete: {} bytes received to {:?}",
-                total_bytes, save_path
-            );
-
-            Ok(())
-        }
-        .await;
-
-        // Clean up partial file on error
-        if result.is_err() {
-            warn!(
-                "TLS transfer failed, cleaning up partial file: {:?}",
-                save_path
-            );
-            cleanup_partial_file(save_path).await;
-        }
-
-        result
+        receive_into(
+            &mut self.stream,
+            save_path,
+            offset,
+            expected_size,
+            &self.options,
+        )
+        .await
     }
 }
 
@@ -927,6 +1240,24 @@ impl TlsPayloadServer {
         let file_path = file_path.as_ref();
         info!("Waiting for TLS connection to send file: {:?}", file_path);
 
+        let file = File::open(file_path).await.map_err(ProtocolError::Io)?;
+        let file_size = file.metadata().await.map_err(ProtocolError::Io)?.len();
+        self.send_stream(file, file_size).await
+    }
+
+    /// Accept connection and send `size` bytes from `reader` over TLS
+    ///
+    /// Used for payloads attached to a packet with
+    /// [`Packet::with_payload`](crate::Packet::with_payload), such as the
+    /// remainder of a resumed transfer.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::send_file`]; a reader that ends early fails the transfer.
+    pub async fn send_stream<R>(self, mut reader: R, size: u64) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+    {
         // Accept TCP connection
         let (tcp_stream, peer_addr) = timeout(CONNECTION_TIMEOUT, self.listener.accept())
             .await
@@ -980,16 +1311,14 @@ impl TlsPayloadServer {
             peer_addr
         );
 
-        // Open file and get size
-        let mut file = File::open(file_path).await.map_err(ProtocolError::Io)?;
-        let file_size = file.metadata().await.map_err(ProtocolError::Io)?.len();
-
-        // Stream file data over TLS
+        // Stream payload data over TLS
+        let file_size = size;
         let mut buffer = vec![0u8; BUFFER_SIZE];
         let mut total_bytes: u64 = 0;
 
-        loop {
-            let bytes_read = timeout(TRANSFER_TIMEOUT, file.read(&mut buffer))
+        while total_bytes < file_size {
+            let to_read = std::cmp::min(file_size - total_bytes, BUFFER_SIZE as u64) as usize;
+            let bytes_read = timeout(TRANSFER_TIMEOUT, reader.read(&mut buffer[..to_read]))
                 .await
                 .map_err(|_| {
                     ProtocolError::Io(std::io::Error::new(
@@ -1000,7 +1329,13 @@ impl TlsPayloadServer {
                 .map_err(ProtocolError::Io)?;
 
             if bytes_read == 0 {
-                break;
+                return Err(ProtocolError::Io(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Payload ended early: sent {} bytes, expected {}",
+                        total_bytes, file_size
+                    ),
+                )));
             }
 
             // Write to TLS stream
@@ -1124,6 +1459,7 @@ mod tests {
             path: "/tmp/test.txt".to_string(),
             creation_time: Some(1640000000000),
             last_modified: Some(1640000000000),
+            hash: Some("abc123".to_string()),
         };
 
         let share_info: crate::plugins::share::FileShareInfo = transfer_info.into();
@@ -1133,6 +1469,24 @@ mod tests {
         assert_eq!(share_info.creation_time, Some(1640000000000));
         assert_eq!(share_info.last_modified, Some(1640000000000));
         assert!(!share_info.open);
+        assert_eq!(share_info.hash.as_deref(), Some("abc123"));
+    }
+
+    #[tokio::test]
+    async fn test_file_transfer_info_with_hash() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"test content").unwrap();
+        temp_file.flush().unwrap();
+
+        let info = FileTransferInfo::from_path(temp_file.path())
+            .await
+            .unwrap()
+            .with_hash()
+            .await
+            .unwrap();
+
+        let expected = blake3::hash(b"test content").to_hex().to_string();
+        assert_eq!(info.hash, Some(expected));
     }
 
     #[tokio::test]
@@ -1146,6 +1500,319 @@ mod tests {
         assert!(result.is_err() || result.unwrap().is_err());
     }
 
+    /// Sink that accepts `remaining` bytes then fails with ENOSPC
+    #[cfg(unix)]
+    struct FullDiskSink {
+        inner: File,
+        remaining: usize,
+    }
+
+    #[cfg(unix)]
+    impl AsyncWrite for FullDiskSink {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            use std::task::Poll;
+
+            let this = self.get_mut();
+            if this.remaining == 0 {
+                return Poll::Ready(Err(std::io::Error::from_raw_os_error(
+                    nix::errno::Errno::ENOSPC as i32,
+                )));
+            }
+            let len = buf.len().min(this.remaining);
+            match std::pin::Pin::new(&mut this.inner).poll_write(cx, &buf[..len]) {
+                Poll::Ready(Ok(written)) => {
+                    this.remaining -= written;
+                    Poll::Ready(Ok(written))
+                }
+                other => other,
+            }
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::pin::Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::pin::Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_receive_disk_full_removes_partial_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let save_path = temp.path().join("large.bin");
+        let data = vec![0xAB_u8; BUFFER_SIZE + 10_000];
+
+        let mut sink = FullDiskSink {
+            inner: File::create(&save_path).await.unwrap(),
+            remaining: BUFFER_SIZE + 1_000,
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let options = ReceiveOptions {
+            event_sender: Some(tx),
+            ..Default::default()
+        };
+        let mut reader = &data[..];
+
+        let err = receive_payload(
+            &mut reader,
+            &mut sink,
+            &save_path,
+            0,
+            data.len() as u64,
+            &options,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, ProtocolError::ResourceExhausted(_)));
+        assert!(!save_path.exists(), "partial file should be removed");
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            TransferEvent::Failed {
+                path: save_path.clone(),
+                reason: TRANSFER_FAILED_DISK_FULL,
+                bytes_written: BUFFER_SIZE as u64,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_receive_payload_reports_completion() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let save_path = temp.path().join("small.txt");
+        let data = b"payload contents";
+
+        let mut file = File::create(&save_path).await.unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let options = ReceiveOptions {
+            event_sender: Some(tx),
+            ..Default::default()
+        };
+        let mut reader = &data[..];
+
+        receive_payload(
+            &mut reader,
+            &mut file,
+            &save_path,
+            0,
+            data.len() as u64,
+            &options,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tokio::fs::read(&save_path).await.unwrap(), data);
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            TransferEvent::Completed {
+                path: save_path,
+                bytes: data.len() as u64,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_receive_corrupted_payload_removes_file() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let save_path = temp.path().join("photo.jpg");
+        let expected = blake3::hash(b"original contents").to_hex().to_string();
+        let data = b"corrupted content";
+
+        let mut file = File::create(&save_path).await.unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let options = ReceiveOptions {
+            event_sender: Some(tx),
+            expected_hash: Some(expected),
+            ..Default::default()
+        };
+        let mut reader = &data[..];
+
+        let err = receive_payload(
+            &mut reader,
+            &mut file,
+            &save_path,
+            0,
+            data.len() as u64,
+            &options,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, ProtocolError::IntegrityMismatch(_)));
+        assert!(!save_path.exists(), "corrupted file should be removed");
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            TransferEvent::Failed {
+                path: save_path,
+                reason: TRANSFER_FAILED_INTEGRITY,
+                bytes_written: data.len() as u64,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_receive_payload_matching_hash() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let save_path = temp.path().join("photo.jpg");
+        let data = b"original contents";
+        let expected = blake3::hash(data).to_hex().to_uppercase();
+
+        let mut file = File::create(&save_path).await.unwrap();
+        let options = ReceiveOptions {
+            expected_hash: Some(expected),
+            ..Default::default()
+        };
+        let mut reader = &data[..];
+
+        receive_payload(
+            &mut reader,
+            &mut file,
+            &save_path,
+            0,
+            data.len() as u64,
+            &options,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tokio::fs::read(&save_path).await.unwrap(), data);
+    }
+
+    /// Serve `data` to the first client, then close the connection
+    async fn serve_bytes(data: Vec<u8>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            stream.write_all(&data).await.unwrap();
+            stream.shutdown().await.unwrap();
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_disconnect_at_half() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let source = temp.path().join("source.bin");
+        let save_path = temp.path().join("received.bin");
+        let data: Vec<u8> = (0..3 * BUFFER_SIZE as u32)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        std::fs::write(&source, &data).unwrap();
+        let total = data.len() as u64;
+        let half = total / 2;
+        let hash = payload_hash(&source).unwrap();
+
+        // The connection drops after half of the file
+        let port = serve_bytes(data[..half as usize].to_vec()).await;
+        let err = PayloadClient::new("127.0.0.1", port)
+            .await
+            .unwrap()
+            .with_expected_hash(&hash)
+            .resumable()
+            .receive_file(&save_path, total)
+            .await
+            .unwrap_err();
+        assert_eq!(TransferEvent::failure_reason(&err), TRANSFER_FAILED_IO);
+        assert_eq!(std::fs::metadata(&save_path).unwrap().len(), half);
+
+        // The sender checks the received prefix before sending the rest
+        assert_eq!(
+            prefix_hash(&save_path, half).unwrap(),
+            prefix_hash(&source, half).unwrap()
+        );
+
+        let port = serve_bytes(data[half as usize..].to_vec()).await;
+        PayloadClient::new("127.0.0.1", port)
+            .await
+            .unwrap()
+            .with_expected_hash(&hash)
+            .resume_file(&save_path, half, total)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&save_path).unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_transfer_removed_unless_resumable() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let save_path = temp.path().join("received.bin");
+
+        let port = serve_bytes(vec![1; 100]).await;
+        PayloadClient::new("127.0.0.1", port)
+            .await
+            .unwrap()
+            .receive_file(&save_path, 200)
+            .await
+            .unwrap_err();
+
+        assert!(!save_path.exists());
+    }
+
+    #[test]
+    fn test_prefix_hash() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"hello world").unwrap();
+        temp_file.flush().unwrap();
+
+        assert_eq!(
+            prefix_hash(temp_file.path(), 5).unwrap(),
+            blake3::hash(b"hello").to_hex().as_str()
+        );
+        assert!(prefix_hash(temp_file.path(), 20).is_err());
+    }
+
+    #[test]
+    fn test_transfer_failure_reason() {
+        let disk_full = ProtocolError::ResourceExhausted("Disk full".to_string());
+        assert_eq!(
+            TransferEvent::failure_reason(&disk_full),
+            TRANSFER_FAILED_DISK_FULL
+        );
+
+        let cancelled = ProtocolError::Io(std::io::Error::new(
+            std::io::ErrorKind::Interrupted,
+            "Transfer cancelled",
+        ));
+        assert_eq!(
+            TransferEvent::failure_reason(&cancelled),
+            TRANSFER_FAILED_CANCELLED
+        );
+
+        let timeout = ProtocolError::Timeout("read".to_string());
+        assert_eq!(
+            TransferEvent::failure_reason(&timeout),
+            TRANSFER_FAILED_TIMEOUT
+        );
+
+        let mismatch = ProtocolError::IntegrityMismatch("hash".to_string());
+        assert_eq!(
+            TransferEvent::failure_reason(&mismatch),
+            TRANSFER_FAILED_INTEGRITY
+        );
+
+        let eof = ProtocolError::Io(std::io::ErrorKind::UnexpectedEof.into());
+        assert_eq!(TransferEvent::failure_reason(&eof), TRANSFER_FAILED_IO);
+
+        assert!(TransferEvent::is_resumable(TRANSFER_FAILED_IO));
+        assert!(TransferEvent::is_resumable(TRANSFER_FAILED_TIMEOUT));
+        assert!(!TransferEvent::is_resumable(TRANSFER_FAILED_CANCELLED));
+        assert!(!TransferEvent::is_resumable(TRANSFER_FAILED_INTEGRITY));
+    }
+
     #[tokio::test]
     async fn test_invalid_file_path() {
         let server = PayloadServer::new().await.unwrap();
@@ -2,13 +2,61 @@
 //!
 //! Provides encoding and decoding for various audio codecs.
 
+#[cfg(feature = "aac")]
+use fdk_aac_sys as fdk;
 #[cfg(feature = "opus")]
 use opus::{Channels as OpusChannels, Decoder as OpusDecoder, Encoder as OpusEncoder};
+#[cfg(feature = "aac")]
+use std::os::raw::c_int;
 use tracing::debug;
 
 use crate::{ProtocolError, Result};
 
 use super::audio_backend::AudioSample;
+use super::AudioCodec;
+
+/// Common interface of the audio codecs
+///
+/// Streams hold a `Box<dyn Codec>`, so the codec can be chosen at runtime.
+pub trait Codec: Send + Sync {
+    /// Encode interleaved f32 samples into packets
+    ///
+    /// Framed codecs may return no packets, or several, and keep samples
+    /// that don't fill a frame for the next call.
+    fn encode(&mut self, samples: &[AudioSample]) -> Result<Vec<Vec<u8>>>;
+
+    /// Encode any samples still held back, padded to a full frame
+    fn flush(&mut self) -> Result<Vec<Vec<u8>>> {
+        Ok(Vec::new())
+    }
+
+    /// Decode one packet into interleaved f32 samples
+    fn decode(&mut self, packet: &[u8]) -> Result<Vec<AudioSample>>;
+
+    /// Samples per channel encoded into one packet
+    fn frame_size(&self) -> usize;
+
+    /// Audio to play in place of a lost or undecodable packet
+    ///
+    /// `None` if the codec cannot conceal losses.
+    fn conceal_loss(&mut self) -> Option<Vec<AudioSample>> {
+        None
+    }
+}
+
+/// Create the codec for `kind`
+pub fn create_codec(
+    kind: AudioCodec,
+    sample_rate: u32,
+    channels: u8,
+    bitrate: u32,
+) -> Result<Box<dyn Codec>> {
+    Ok(match kind {
+        AudioCodec::Opus => Box::new(OpusCodec::new(sample_rate, channels, bitrate)?),
+        AudioCodec::Pcm => Box::new(PcmCodec::new(sample_rate, channels)),
+        AudioCodec::Aac => Box::new(AacCodec::new(sample_rate, channels, bitrate)?),
+    })
+}
 
 /// Opus codec wrapper
 ///
@@ -23,6 +71,8 @@ pub struct OpusCodec {
     sample_rate: u32,
     channels: u8,
     frame_size: usize,
+    /// Samples of an incomplete frame, encoded once the frame fills up
+    pending: Vec<AudioSample>,
 }
 
 // SAFETY: OpusCodec is protected by RwLock in AudioStreamPlugin,
@@ -108,29 +158,69 @@ impl OpusCodec {
             sample_rate,
             channels,
             frame_size,
+            pending: Vec::new(),
         })
     }
 
     /// Encode audio samples to Opus
     ///
+    /// Samples are buffered until they fill a frame, so any length is
+    /// accepted. Samples left over after the last complete frame are kept and
+    /// prepended to the next call; [`flush`](Self::flush) encodes them.
+    ///
     /// # Arguments
     /// * `samples` - Interleaved f32 audio samples
     ///
     /// # Returns
-    /// Encoded opus packet as bytes
-    pub fn encode(&mut self, samples: &[AudioSample]) -> Result<Vec<u8>> {
-        // Check if we have enough samples for a frame
-        let expected_samples = self.frame_size * self.channels as usize;
-        if samples.len() < expected_samples {
-            return Err(ProtocolError::InvalidPacket(format!(
-                "Not enough samples for encoding: got {}, expected {}",
-                samples.len(),
-                expected_samples
-            )));
+    /// One encoded opus packet per complete frame
+    pub fn encode(&mut self, samples: &[AudioSample]) -> Result<Vec<Vec<u8>>> {
+        let frame_samples = self.frame_size * self.channels as usize;
+        self.pending.extend_from_slice(samples);
+        let complete = self.pending.len() - self.pending.len() % frame_samples;
+
+        let encoder = &mut self.encoder;
+        let packets = self.pending[..complete]
+            .chunks_exact(frame_samples)
+            .map(|frame| Self::encode_frame(encoder, frame))
+            .collect::<Result<Vec<_>>>();
+        // Drop the frames even if one failed, so a bad frame isn't retried
+        self.pending.drain(..complete);
+        let packets = packets?;
+
+        debug!(
+            "Encoded {} samples to {} packets, {} samples pending",
+            samples.len(),
+            packets.len(),
+            self.pending.len()
+        );
+
+        Ok(packets)
+    }
+
+    /// Encode the pending samples, padded with silence to a full frame
+    ///
+    /// # Returns
+    /// The final packet, or `None` if no samples were pending
+    pub fn flush(&mut self) -> Result<Option<Vec<u8>>> {
+        if self.pending.is_empty() {
+            return Ok(None);
         }
 
+        let mut frame = std::mem::take(&mut self.pending);
+        frame.resize(self.frame_size * self.channels as usize, 0.0);
+        Self::encode_frame(&mut self.encoder, &frame).map(Some)
+    }
+
+    /// Number of samples (all channels) waiting for a complete frame
+    #[allow(dead_code)]
+    pub fn pending_samples(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Encode exactly one frame of samples
+    fn encode_frame(encoder: &mut OpusEncoder, frame: &[AudioSample]) -> Result<Vec<u8>> {
         // Convert f32 samples to i16 for Opus
-        let pcm_samples: Vec<i16> = samples[..expected_samples]
+        let pcm_samples: Vec<i16> = frame
             .iter()
             .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
             .collect();
@@ -139,19 +229,11 @@ impl OpusCodec {
         let mut output = vec![0u8; 4000];
 
         // Encode
-        let encoded_size = self
-            .encoder
+        let encoded_size = encoder
             .encode(&pcm_samples, &mut output)
             .map_err(|e| ProtocolError::InvalidPacket(format!("Opus encoding failed: {:?}", e)))?;
 
         output.truncate(encoded_size);
-
-        debug!(
-            "Encoded {} samples to {} bytes",
-            samples.len(),
-            encoded_size
-        );
-
         Ok(output)
     }
 
@@ -245,12 +327,25 @@ impl OpusCodec {
     }
 
     /// Encode (stub)
-    pub fn encode(&mut self, _samples: &[AudioSample]) -> Result<Vec<u8>> {
+    pub fn encode(&mut self, _samples: &[AudioSample]) -> Result<Vec<Vec<u8>>> {
+        Err(ProtocolError::InvalidPacket(
+            "Opus codec not available".to_string(),
+        ))
+    }
+
+    /// Flush (stub)
+    pub fn flush(&mut self) -> Result<Option<Vec<u8>>> {
         Err(ProtocolError::InvalidPacket(
             "Opus codec not available".to_string(),
         ))
     }
 
+    /// Pending samples (stub)
+    #[allow(dead_code)]
+    pub fn pending_samples(&self) -> usize {
+        0
+    }
+
     /// Decode (stub)
     pub fn decode(&mut self, _packet: &[u8]) -> Result<Vec<AudioSample>> {
         Err(ProtocolError::InvalidPacket(
@@ -284,6 +379,28 @@ impl OpusCodec {
     }
 }
 
+impl Codec for OpusCodec {
+    fn encode(&mut self, samples: &[AudioSample]) -> Result<Vec<Vec<u8>>> {
+        OpusCodec::encode(self, samples)
+    }
+
+    fn flush(&mut self) -> Result<Vec<Vec<u8>>> {
+        Ok(OpusCodec::flush(self)?.into_iter().collect())
+    }
+
+    fn decode(&mut self, packet: &[u8]) -> Result<Vec<AudioSample>> {
+        OpusCodec::decode(self, packet)
+    }
+
+    fn frame_size(&self) -> usize {
+        OpusCodec::frame_size(self)
+    }
+
+    fn conceal_loss(&mut self) -> Option<Vec<AudioSample>> {
+        self.decode_plc().ok()
+    }
+}
+
 /// PCM codec (uncompressed)
 #[allow(dead_code)]
 pub struct PcmCodec {
@@ -348,15 +465,79 @@ impl PcmCodec {
     }
 }
 
-/// AAC codec wrapper
+impl Codec for PcmCodec {
+    fn encode(&mut self, samples: &[AudioSample]) -> Result<Vec<Vec<u8>>> {
+        if samples.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(vec![PcmCodec::encode(self, samples)?])
+    }
+
+    fn decode(&mut self, packet: &[u8]) -> Result<Vec<AudioSample>> {
+        PcmCodec::decode(self, packet)
+    }
+
+    /// PCM accepts any length; packets are 20ms like Opus
+    fn frame_size(&self) -> usize {
+        (self.sample_rate as usize * 20) / 1000
+    }
+}
+
+/// Owned fdk-aac encoder handle, closed on drop
+#[cfg(feature = "aac")]
+struct AacEncoderHandle(fdk::HANDLE_AACENCODER);
+
+#[cfg(feature = "aac")]
+impl Drop for AacEncoderHandle {
+    fn drop(&mut self) {
+        // SAFETY: the handle was opened by aacEncOpen and is closed only here
+        unsafe {
+            fdk::aacEncClose(&mut self.0);
+        }
+    }
+}
+
+/// Owned fdk-aac decoder handle, closed on drop
+#[cfg(feature = "aac")]
+struct AacDecoderHandle(fdk::HANDLE_AACDECODER);
+
+#[cfg(feature = "aac")]
+impl Drop for AacDecoderHandle {
+    fn drop(&mut self) {
+        // SAFETY: the handle was opened by aacDecoder_Open and is closed only here
+        unsafe {
+            fdk::aacDecoder_Close(self.0);
+        }
+    }
+}
+
+/// Largest number of samples (all channels) one AAC frame decodes to
+#[cfg(feature = "aac")]
+const AAC_MAX_DECODED_SAMPLES: usize = 2048 * 8;
+
+/// AAC-LC codec wrapper using fdk-aac, with ADTS framing
+///
+/// # Safety
+/// Like [`OpusCodec`], the fdk-aac handles are raw pointers that aren't `Send`
+/// by default. Access is protected by `RwLock` in the plugin.
 #[cfg(feature = "aac")]
 pub struct AacCodec {
+    encoder: AacEncoderHandle,
+    decoder: AacDecoderHandle,
     sample_rate: u32,
     channels: u8,
     bitrate: u32,
     frame_size: usize,
+    max_output_bytes: usize,
 }
 
+// SAFETY: AacCodec is protected by RwLock in AudioStreamPlugin, and the
+// fdk-aac handles are only used through &mut self methods.
+#[cfg(feature = "aac")]
+unsafe impl Send for AacCodec {}
+#[cfg(feature = "aac")]
+unsafe impl Sync for AacCodec {}
+
 /// Stub AAC codec when feature is disabled
 #[cfg(not(feature = "aac"))]
 #[allow(dead_code)]
@@ -384,22 +565,73 @@ impl AacCodec {
             )));
         }
 
-        // Validate channels
-        if channels < 1 || channels > 2 {
-            return Err(ProtocolError::InvalidPacket(format!(
-                "Unsupported channel count: {}. Must be 1 or 2",
-                channels
-            )));
+        let channel_mode = match channels {
+            1 => fdk::CHANNEL_MODE_MODE_1,
+            2 => fdk::CHANNEL_MODE_MODE_2,
+            _ => {
+                return Err(ProtocolError::InvalidPacket(format!(
+                    "Unsupported channel count: {}. Must be 1 or 2",
+                    channels
+                )))
+            }
+        };
+
+        let mut raw_encoder: fdk::HANDLE_AACENCODER = std::ptr::null_mut();
+        // SAFETY: aacEncOpen only writes the new handle through the pointer
+        Self::check_encoder("open", unsafe {
+            fdk::aacEncOpen(&mut raw_encoder, 0, channels as _)
+        })?;
+        let encoder = AacEncoderHandle(raw_encoder);
+
+        let params = [
+            (
+                fdk::AACENC_PARAM_AACENC_AOT,
+                fdk::AUDIO_OBJECT_TYPE_AOT_AAC_LC as u32,
+            ),
+            (fdk::AACENC_PARAM_AACENC_SAMPLERATE, sample_rate),
+            (fdk::AACENC_PARAM_AACENC_CHANNELMODE, channel_mode as u32),
+            (fdk::AACENC_PARAM_AACENC_CHANNELORDER, 1),
+            (fdk::AACENC_PARAM_AACENC_BITRATE, bitrate),
+            (
+                fdk::AACENC_PARAM_AACENC_TRANSMUX,
+                fdk::TRANSPORT_TYPE_TT_MP4_ADTS as u32,
+            ),
+            (fdk::AACENC_PARAM_AACENC_AFTERBURNER, 1),
+        ];
+        for (param, value) in params {
+            // SAFETY: the encoder handle is valid until dropped
+            Self::check_encoder("configure", unsafe {
+                fdk::aacEncoder_SetParam(encoder.0, param as _, value as _)
+            })?;
         }
 
-        // AAC typically uses 1024 samples per frame for most sample rates
-        let frame_size = match sample_rate {
-            8000 => 256,
-            16000 => 512,
-            24000 => 768,
-            48000 => 1024,
-            _ => 1024,
-        };
+        // Applies the parameters
+        // SAFETY: null buffers are the documented way to initialize the encoder
+        Self::check_encoder("initialize", unsafe {
+            fdk::aacEncEncode(
+                encoder.0,
+                std::ptr::null(),
+                std::ptr::null(),
+                std::ptr::null(),
+                std::ptr::null_mut(),
+            )
+        })?;
+
+        // SAFETY: AACENC_InfoStruct is plain data, filled in by aacEncInfo
+        let mut info: fdk::AACENC_InfoStruct = unsafe { std::mem::zeroed() };
+        Self::check_encoder("query", unsafe { fdk::aacEncInfo(encoder.0, &mut info) })?;
+
+        // SAFETY: aacDecoder_Open returns a new handle or null
+        let raw_decoder = unsafe { fdk::aacDecoder_Open(fdk::TRANSPORT_TYPE_TT_MP4_ADTS, 1) };
+        if raw_decoder.is_null() {
+            return Err(ProtocolError::InvalidPacket(
+                "Failed to create AAC decoder".to_string(),
+            ));
+        }
+        let decoder = AacDecoderHandle(raw_decoder);
+
+        // AAC-LC frames are 1024 samples per channel
+        let frame_size = info.frameLength as usize;
 
         debug!(
             "Created AAC codec: {}Hz, {} channels, {} bps, {} samples/frame",
@@ -407,99 +639,35 @@ impl AacCodec {
         );
 
         Ok(Self {
+            encoder,
+            decoder,
             sample_rate,
             channels,
             bitrate,
             frame_size,
+            max_output_bytes: info.maxOutBufBytes as usize,
         })
     }
 
+    fn check_encoder(operation: &str, result: fdk::AACENC_ERROR) -> Result<()> {
+        if result == fdk::AACENC_ERROR_AACENC_OK {
+            Ok(())
+        } else {
+            Err(ProtocolError::InvalidPacket(format!(
+                "Failed to {} AAC encoder: error {:#x}",
+                operation, result
+            )))
+        }
+    }
+
     /// Encode audio samples to AAC
     ///
     /// # Arguments
     /// * `samples` - Interleaved f32 audio samples
     ///
     /// # Returns
-    /// Encoded AAC packet as bytes
-    ///
-    /// # Implementation Notes
-    ///
-    /// This method is not yet fully implemented. AAC encoding requires integration
-    /// with an external AAC encoder library.
-    ///
-    /// ## Recommended Implementation Approaches
-    ///
-    /// ### Option 1: fdk-aac (Fraunhofer FDK AAC)
-    ///
-    /// The industry-standard AAC encoder/decoder library.
-    ///
-    /// **Rust Bindings:**
-    /// - `fdk-aac-sys`: Low-level FFI bindings to libfdk-aac
-    /// - `fdk-aac`: Higher-level Rust wrapper (may need custom wrapper)
-    ///
-    /// **System Dependencies:**
-    /// ```bash
-    /// # Debian/Ubuntu
-    /// sudo apt-get install libfdk-aac-dev
-    ///
-    /// # macOS
-    /// brew install fdk-aac
-    /// ```
-    ///
-    /// **Key Functions:**
-    /// - `aacEncOpen()`: Create encoder instance
-    /// - `aacEncoder_SetParam()`: Configure bitrate, sample rate, channels
-    /// - `aacEncEncode()`: Encode audio frames
-    /// - `aacEncClose()`: Clean up encoder
-    ///
-    /// ### Option 2: FFmpeg's libavcodec
-    ///
-    /// Using FFmpeg's AAC encoder through Rust bindings.
-    ///
-    /// **Rust Bindings:**
-    /// - `ffmpeg-next`: Safe Rust bindings to FFmpeg
-    /// - More features but heavier dependency
-    ///
-    /// ### Option 3: Native Rust Implementation
-    ///
-    /// Currently no production-ready pure Rust AAC encoder exists.
-    /// This would be a significant undertaking.
-    ///
-    /// ## Implementation Pattern (fdk-aac example)
-    ///
-    /// ```ignore
-    /// use fdk_aac_sys::*;
-    ///
-    /// // 1. Create encoder handle
-    /// let mut encoder: HANDLE_AACENCODER = std::ptr::null_mut();
-    /// aacEncOpen(&mut encoder, 0, self.channels as u32);
-    ///
-    /// // 2. Configure encoder
-    /// aacEncoder_SetParam(encoder, AACENC_AOT, AOT_AAC_LC as i32); // AAC-LC profile
-    /// aacEncoder_SetParam(encoder, AACENC_SAMPLERATE, self.sample_rate as i32);
-    /// aacEncoder_SetParam(encoder, AACENC_CHANNELMODE, MODE_2 as i32); // Stereo
-    /// aacEncoder_SetParam(encoder, AACENC_BITRATE, self.bitrate as i32);
-    /// aacEncEncode(encoder, std::ptr::null(), std::ptr::null_mut(), &mut out_args);
-    ///
-    /// // 3. Convert f32 samples to i16
-    /// let pcm_samples: Vec<i16> = samples.iter()
-    ///     .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
-    ///     .collect();
-    ///
-    /// // 4. Setup buffers and encode
-    /// // ... (buffer configuration omitted for brevity)
-    /// aacEncEncode(encoder, &mut in_args, &mut out_args);
-    ///
-    /// // 5. Collect output and cleanup
-    /// // ... (output handling and aacEncClose)
-    /// ```
-    ///
-    /// ## Testing Considerations
-    ///
-    /// - Verify output with standard AAC decoders (ffplay, VLC)
-    /// - Test various bitrates (64kbps - 320kbps)
-    /// - Validate frame alignment and sample rates
-    /// - Handle encoder initialization failures gracefully
+    /// Encoded ADTS frame as bytes. The encoder delays its output by a few
+    /// frames, so the first calls may return no bytes.
     pub fn encode(&mut self, samples: &[AudioSample]) -> Result<Vec<u8>> {
         // Check if we have enough samples for a frame
         let expected_samples = self.frame_size * self.channels as usize;
@@ -511,95 +679,156 @@ impl AacCodec {
             )));
         }
 
-        // AAC encoding requires fdk-aac library integration (see implementation notes above)
-        Err(ProtocolError::InvalidPacket(
-            "AAC encoding not yet implemented - requires fdk-aac library integration".to_string(),
-        ))
+        // Convert f32 samples to i16 for fdk-aac
+        let mut pcm_samples: Vec<i16> = samples[..expected_samples]
+            .iter()
+            .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
+            .collect();
+        let mut output = vec![0u8; self.max_output_bytes];
+
+        let mut in_ptr = pcm_samples.as_mut_ptr() as *mut std::ffi::c_void;
+        let mut in_identifier = fdk::AACENC_BufferIdentifier_IN_AUDIO_DATA as c_int;
+        let mut in_size = (pcm_samples.len() * std::mem::size_of::<i16>()) as c_int;
+        let mut in_element_size = std::mem::size_of::<i16>() as c_int;
+        let in_desc = fdk::AACENC_BufDesc {
+            numBufs: 1,
+            bufs: &mut in_ptr,
+            bufferIdentifiers: &mut in_identifier,
+            bufSizes: &mut in_size,
+            bufElSizes: &mut in_element_size,
+        };
+
+        let mut out_ptr = output.as_mut_ptr() as *mut std::ffi::c_void;
+        let mut out_identifier = fdk::AACENC_BufferIdentifier_OUT_BITSTREAM_DATA as c_int;
+        let mut out_size = output.len() as c_int;
+        let mut out_element_size: c_int = 1;
+        let out_desc = fdk::AACENC_BufDesc {
+            numBufs: 1,
+            bufs: &mut out_ptr,
+            bufferIdentifiers: &mut out_identifier,
+            bufSizes: &mut out_size,
+            bufElSizes: &mut out_element_size,
+        };
+
+        let in_args = fdk::AACENC_InArgs {
+            numInSamples: pcm_samples.len() as _,
+            numAncBytes: 0,
+        };
+        // SAFETY: AACENC_OutArgs is plain data, filled in by aacEncEncode
+        let mut out_args: fdk::AACENC_OutArgs = unsafe { std::mem::zeroed() };
+
+        // SAFETY: the descriptors point at live buffers of the stated sizes
+        let result = unsafe {
+            fdk::aacEncEncode(self.encoder.0, &in_desc, &out_desc, &in_args, &mut out_args)
+        };
+        if result != fdk::AACENC_ERROR_AACENC_OK {
+            return Err(ProtocolError::InvalidPacket(format!(
+                "AAC encoding failed: error {:#x}",
+                result
+            )));
+        }
+
+        let encoded_size = out_args.numOutBytes as usize;
+        output.truncate(encoded_size);
+
+        debug!(
+            "Encoded {} samples to {} bytes",
+            samples.len(),
+            encoded_size
+        );
+
+        Ok(output)
     }
 
     /// Decode AAC packet to audio samples
     ///
     /// # Arguments
-    /// * `packet` - Encoded AAC packet
+    /// * `packet` - Encoded ADTS data, one or more frames
     ///
     /// # Returns
     /// Decoded audio samples as interleaved f32
-    ///
-    /// # Implementation Notes
-    ///
-    /// This method is not yet fully implemented. AAC decoding requires integration
-    /// with an external AAC decoder library.
-    ///
-    /// ## Recommended Implementation Approaches
-    ///
-    /// ### Option 1: fdk-aac (Fraunhofer FDK AAC)
-    ///
-    /// Same library as encoding, provides both encoder and decoder.
-    ///
-    /// **Key Decoder Functions:**
-    /// - `aacDecoder_Open()`: Create decoder instance
-    /// - `aacDecoder_ConfigRaw()`: Configure decoder with audio info
-    /// - `aacDecoder_Fill()`: Feed encoded data to decoder
-    /// - `aacDecoder_DecodeFrame()`: Decode one frame
-    /// - `aacDecoder_Close()`: Clean up decoder
-    ///
-    /// ### Option 2: FFmpeg's libavcodec
-    ///
-    /// More robust error handling and format support.
-    ///
-    /// ### Option 3: mp4parse + External Decoder
-    ///
-    /// Parse AAC bitstream with pure Rust, decode with C library.
-    ///
-    /// ## Implementation Pattern (fdk-aac example)
-    ///
-    /// ```ignore
-    /// use fdk_aac_sys::*;
-    ///
-    /// // 1. Create decoder handle
-    /// let decoder = aacDecoder_Open(TT_MP4_ADTS, 1); // ADTS transport format
-    ///
-    /// // 2. Feed input data
-    /// let mut bytes_valid = packet.len();
-    /// let mut input_ptr = packet.as_ptr();
-    /// aacDecoder_Fill(decoder, &mut input_ptr, &packet.len(), &mut bytes_valid);
-    ///
-    /// // 3. Decode frame
-    /// let mut output_buffer = vec![0i16; self.frame_size * self.channels as usize];
-    /// aacDecoder_DecodeFrame(
-    ///     decoder,
-    ///     output_buffer.as_mut_ptr(),
-    ///     output_buffer.len() as i32,
-    ///     0
-    /// );
-    ///
-    /// // 4. Convert i16 to f32
-    /// let samples: Vec<f32> = output_buffer.iter()
-    ///     .map(|&s| s as f32 / 32767.0)
-    ///     .collect();
-    ///
-    /// // 5. Cleanup
-    /// aacDecoder_Close(decoder);
-    /// ```
-    ///
-    /// ## Error Handling
-    ///
-    /// - Validate packet size before decoding
-    /// - Handle decoder errors (corrupted packets, format mismatches)
-    /// - Implement packet loss concealment (silence or repeat last frame)
-    /// - Reset decoder on stream discontinuities
-    ///
-    /// ## Testing Considerations
-    ///
-    /// - Test with packets generated by the encoder
-    /// - Validate with standard AAC test vectors
-    /// - Test error recovery (missing packets, corrupted data)
-    /// - Verify sample rate and channel count match configuration
-    pub fn decode(&mut self, _packet: &[u8]) -> Result<Vec<AudioSample>> {
-        // AAC decoding requires fdk-aac library integration (see implementation notes above)
-        Err(ProtocolError::InvalidPacket(
-            "AAC decoding not yet implemented - requires fdk-aac library integration".to_string(),
-        ))
+    pub fn decode(&mut self, packet: &[u8]) -> Result<Vec<AudioSample>> {
+        let mut samples = Vec::new();
+        let mut remaining = packet;
+
+        while !remaining.is_empty() {
+            let mut buffer = remaining.as_ptr() as *mut u8;
+            let buffer_size = remaining.len() as u32;
+            let mut bytes_valid = buffer_size;
+            // SAFETY: fdk-aac only reads `buffer_size` bytes from the buffer
+            let result = unsafe {
+                fdk::aacDecoder_Fill(
+                    self.decoder.0,
+                    &mut buffer,
+                    &buffer_size as *const u32 as *const _,
+                    &mut bytes_valid as *mut u32 as *mut _,
+                )
+            };
+            if result != fdk::AAC_DECODER_ERROR_AAC_DEC_OK {
+                return Err(ProtocolError::InvalidPacket(format!(
+                    "AAC decoder fill failed: error {:#x}",
+                    result
+                )));
+            }
+            let consumed = (buffer_size - bytes_valid) as usize;
+            self.decode_buffered(&mut samples)?;
+
+            // A full input buffer that decodes to nothing holds no valid frames
+            if consumed == 0 {
+                return Err(ProtocolError::InvalidPacket(
+                    "AAC decoder input buffer full without a decodable frame".to_string(),
+                ));
+            }
+            remaining = &remaining[consumed..];
+        }
+
+        debug!(
+            "Decoded {} bytes to {} samples",
+            packet.len(),
+            samples.len()
+        );
+
+        Ok(samples)
+    }
+
+    /// Decode every complete frame in the decoder's input buffer
+    fn decode_buffered(&mut self, samples: &mut Vec<AudioSample>) -> Result<()> {
+        let mut pcm_output = vec![0i16; AAC_MAX_DECODED_SAMPLES];
+
+        loop {
+            // SAFETY: the output buffer holds AAC_MAX_DECODED_SAMPLES samples
+            let result = unsafe {
+                fdk::aacDecoder_DecodeFrame(
+                    self.decoder.0,
+                    pcm_output.as_mut_ptr(),
+                    pcm_output.len() as _,
+                    0,
+                )
+            };
+            if result == fdk::AAC_DECODER_ERROR_AAC_DEC_NOT_ENOUGH_BITS {
+                return Ok(());
+            }
+            if result != fdk::AAC_DECODER_ERROR_AAC_DEC_OK {
+                return Err(ProtocolError::InvalidPacket(format!(
+                    "AAC decoding failed: error {:#x}",
+                    result
+                )));
+            }
+
+            // SAFETY: the stream info is owned by the decoder and valid after a
+            // successfully decoded frame
+            let decoded = unsafe {
+                let info = &*fdk::aacDecoder_GetStreamInfo(self.decoder.0);
+                (info.frameSize * info.numChannels) as usize
+            };
+
+            // Convert i16 to f32
+            samples.extend(
+                pcm_output[..decoded.min(pcm_output.len())]
+                    .iter()
+                    .map(|&s| s as f32 / 32767.0),
+            );
+        }
     }
 
     /// Get frame size in samples per channel
@@ -676,6 +905,26 @@ impl AacCodec {
     }
 }
 
+impl Codec for AacCodec {
+    fn encode(&mut self, samples: &[AudioSample]) -> Result<Vec<Vec<u8>>> {
+        // Nothing is emitted while the encoder delay fills up
+        let packet = AacCodec::encode(self, samples)?;
+        Ok(if packet.is_empty() {
+            Vec::new()
+        } else {
+            vec![packet]
+        })
+    }
+
+    fn decode(&mut self, packet: &[u8]) -> Result<Vec<AudioSample>> {
+        AacCodec::decode(self, packet)
+    }
+
+    fn frame_size(&self) -> usize {
+        AacCodec::frame_size(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -705,7 +954,9 @@ mod tests {
         // Encode
         let encoded = codec.encode(&samples);
         assert!(encoded.is_ok());
-        let encoded = encoded.unwrap();
+        let mut packets = encoded.unwrap();
+        assert_eq!(packets.len(), 1);
+        let encoded = packets.remove(0);
         assert!(!encoded.is_empty());
 
         // Decode
@@ -715,6 +966,38 @@ mod tests {
         assert_eq!(decoded.len(), samples.len());
     }
 
+    #[test]
+    #[cfg(feature = "opus")]
+    fn test_opus_encode_partial_frames() {
+        let mut codec = OpusCodec::new(48000, 2, 128000).unwrap();
+        let frame_samples = codec.frame_size() * codec.channels() as usize;
+
+        // 2.5 frames
+        let samples: Vec<f32> = (0..frame_samples * 5 / 2)
+            .map(|i| ((i / 2) as f32 * 440.0 * 2.0 * std::f32::consts::PI / 48000.0).sin() * 0.5)
+            .collect();
+
+        let packets = codec.encode(&samples).unwrap();
+        assert_eq!(packets.len(), 2);
+        assert!(packets.iter().all(|packet| !packet.is_empty()));
+        assert_eq!(codec.pending_samples(), frame_samples / 2);
+
+        // The carried half frame completes with the next half
+        let packets = codec.encode(&samples[..frame_samples / 2]).unwrap();
+        assert_eq!(packets.len(), 1);
+        assert_eq!(codec.pending_samples(), 0);
+
+        assert!(codec.encode(&samples[..10]).unwrap().is_empty());
+        let last = codec.flush().unwrap().unwrap();
+        assert_eq!(
+            codec.decode(&last).unwrap().len(),
+            frame_samples,
+            "flushed frame is padded to full length"
+        );
+        assert_eq!(codec.pending_samples(), 0);
+        assert!(codec.flush().unwrap().is_none());
+    }
+
     #[test]
     #[cfg(feature = "opus")]
     fn test_opus_plc() {
@@ -749,6 +1032,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "aac"))]
     fn test_aac_codec_creation_without_feature() {
         // Without aac feature, codec creation should fail
         let codec = AacCodec::new(48000, 2, 128000);
@@ -774,4 +1058,100 @@ mod tests {
         let codec = AacCodec::new(44100, 2, 128000);
         assert!(codec.is_err());
     }
+
+    #[test]
+    #[cfg(feature = "aac")]
+    fn test_aac_encode_decode() {
+        let mut codec = AacCodec::new(48000, 2, 128000).unwrap();
+        let channels = codec.channels() as usize;
+        let frame_samples = codec.frame_size() * channels;
+
+        // 1kHz tone, 20 frames (~430ms)
+        let samples: Vec<f32> = (0..frame_samples * 20)
+            .map(|i| {
+                let t = (i / channels) as f32 / 48000.0;
+                (t * 1000.0 * 2.0 * std::f32::consts::PI).sin() * 0.5
+            })
+            .collect();
+
+        // Encode
+        let mut encoded = Vec::new();
+        for frame in samples.chunks_exact(frame_samples) {
+            encoded.extend(codec.encode(frame).unwrap());
+        }
+        assert!(!encoded.is_empty());
+
+        // Decode
+        let decoded = codec.decode(&encoded).unwrap();
+        assert!(decoded.len() >= frame_samples * 10);
+        assert_eq!(decoded.len() % frame_samples, 0);
+
+        // Skip the encoder delay and compare the steady state
+        let left: Vec<f32> = decoded[frame_samples * 4..]
+            .iter()
+            .step_by(channels)
+            .copied()
+            .collect();
+        let rms = (left.iter().map(|s| s * s).sum::<f32>() / left.len() as f32).sqrt();
+        let expected_rms = 0.5 / std::f32::consts::SQRT_2;
+        assert!(
+            (rms - expected_rms).abs() < expected_rms * 0.1,
+            "rms {} vs {}",
+            rms,
+            expected_rms
+        );
+
+        // Two zero crossings per cycle at 1kHz
+        let crossings = left
+            .windows(2)
+            .filter(|w| (w[0] < 0.0) != (w[1] < 0.0))
+            .count();
+        let expected_crossings = left.len() as f32 / 48000.0 * 2000.0;
+        assert!(
+            (crossings as f32 - expected_crossings).abs() < expected_crossings * 0.05,
+            "{} zero crossings, expected ~{}",
+            crossings,
+            expected_crossings
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "aac")]
+    fn test_aac_empty_and_short_input() {
+        let mut codec = AacCodec::new(48000, 2, 128000).unwrap();
+        assert!(codec.decode(&[]).unwrap().is_empty());
+
+        let mut codec = AacCodec::new(48000, 1, 64000).unwrap();
+        let frame = vec![0.0; codec.frame_size() - 1];
+        assert!(codec.encode(&frame).is_err());
+    }
+
+    #[test]
+    fn test_create_codec_pcm() {
+        let mut codec = create_codec(AudioCodec::Pcm, 48000, 2, 128000).unwrap();
+        assert_eq!(codec.frame_size(), 960);
+
+        let samples: Vec<f32> = (0..1920).map(|i| (i as f32 / 1920.0) - 0.5).collect();
+        let encoded = codec.encode(&samples).unwrap();
+        assert_eq!(encoded.len(), 1);
+        let decoded = codec.decode(&encoded[0]).unwrap();
+        assert_eq!(decoded.len(), samples.len());
+        assert!(codec.conceal_loss().is_none());
+        assert!(codec.encode(&[]).unwrap().is_empty());
+        assert!(codec.flush().unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(not(feature = "opus"))]
+    fn test_create_codec_opus_without_feature() {
+        assert!(create_codec(AudioCodec::Opus, 48000, 2, 128000).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "opus")]
+    fn test_create_codec_opus() {
+        let mut codec = create_codec(AudioCodec::Opus, 48000, 2, 128000).unwrap();
+        assert_eq!(codec.frame_size(), 960);
+        assert_eq!(codec.conceal_loss().unwrap().len(), 960 * 2);
+    }
 }
@@ -0,0 +1,250 @@
+//! Jitter buffer for incoming audio packets
+//!
+//! Packets are held until `depth` of them are buffered, then released in
+//! sequence order. A packet arriving out of order is put back in place as long
+//! as it arrives before its slot is played. A missing packet is given up on
+//! once `depth` later packets have arrived, and reported as
+//! [`Playout::Missing`] so the player can conceal the gap. Packets arriving
+//! after their slot was played are dropped.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Typical audio per data packet
+const PACKET_DURATION_MS: u32 = 20;
+
+/// Jitter buffer statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JitterStats {
+    /// Packets dropped because their slot was already played
+    pub late_packets: u64,
+
+    /// Packets never received in time, played as concealment
+    pub lost_packets: u64,
+
+    /// Packets that arrived after a packet with a higher sequence number
+    pub reordered: u64,
+}
+
+/// Next item to play
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Playout {
+    /// The packet for this slot
+    Packet {
+        /// Sender timestamp in milliseconds
+        timestamp: u64,
+        /// Encoded audio
+        data: Vec<u8>,
+    },
+    /// The packet for this slot was lost; play concealment or silence
+    Missing,
+}
+
+/// Reorders incoming audio packets by sequence number
+#[derive(Debug)]
+pub struct AudioJitterBuffer {
+    /// Packets buffered before playout starts, and the reorder window
+    depth: u64,
+    /// Buffered packets by sequence number, with their timestamps
+    packets: BTreeMap<u64, (u64, Vec<u8>)>,
+    /// Sequence number of the next slot to play, once playout has started
+    next_sequence: Option<u64>,
+    /// Highest sequence number received
+    highest_sequence: Option<u64>,
+    stats: JitterStats,
+}
+
+impl AudioJitterBuffer {
+    /// Create a buffer holding `buffer_ms` of audio in typical packets
+    pub fn with_duration(buffer_ms: u32) -> Self {
+        Self::new((buffer_ms / PACKET_DURATION_MS) as usize)
+    }
+
+    /// Create a buffer holding `depth` packets (at least 1)
+    pub fn new(depth: usize) -> Self {
+        Self {
+            depth: depth.max(1) as u64,
+            packets: BTreeMap::new(),
+            next_sequence: None,
+            highest_sequence: None,
+            stats: JitterStats::default(),
+        }
+    }
+
+    /// Add a received packet
+    pub fn push(&mut self, sequence: u64, timestamp: u64, data: Vec<u8>) {
+        if self.next_sequence.is_some_and(|next| sequence < next) {
+            self.stats.late_packets += 1;
+            return;
+        }
+        if self.packets.contains_key(&sequence) {
+            return;
+        }
+
+        match self.highest_sequence {
+            Some(highest) if sequence < highest => self.stats.reordered += 1,
+            _ => self.highest_sequence = Some(sequence),
+        }
+        self.packets.insert(sequence, (timestamp, data));
+    }
+
+    /// Add a packet from a sender that doesn't number its packets
+    ///
+    /// Such packets are assumed to arrive in order.
+    pub fn push_unsequenced(&mut self, timestamp: u64, data: Vec<u8>) {
+        let sequence = self.highest_sequence.map_or(0, |highest| highest + 1);
+        self.push(sequence, timestamp, data);
+    }
+
+    /// Take the next item to play, if it is due
+    ///
+    /// Returns `None` while the buffer is filling up, or while waiting for a
+    /// missing packet that may still arrive.
+    pub fn pop(&mut self) -> Option<Playout> {
+        let next = match self.next_sequence {
+            Some(next) => next,
+            None if self.packets.len() as u64 >= self.depth => *self.packets.keys().next()?,
+            None => return None,
+        };
+
+        if let Some((timestamp, data)) = self.packets.remove(&next) {
+            self.next_sequence = Some(next + 1);
+            return Some(Playout::Packet { timestamp, data });
+        }
+
+        // Give up on the missing packet once the window has moved past it
+        let highest = self.highest_sequence?;
+        if self.packets.is_empty() || highest - next < self.depth {
+            self.next_sequence = Some(next);
+            return None;
+        }
+
+        self.stats.lost_packets += 1;
+        self.next_sequence = Some(next + 1);
+        Some(Playout::Missing)
+    }
+
+    /// Statistics since the buffer was created
+    pub fn stats(&self) -> JitterStats {
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(sequence: u64) -> Playout {
+        Playout::Packet {
+            timestamp: sequence * 20,
+            data: vec![sequence as u8],
+        }
+    }
+
+    fn push(buffer: &mut AudioJitterBuffer, sequence: u64) {
+        buffer.push(sequence, sequence * 20, vec![sequence as u8]);
+    }
+
+    fn drain(buffer: &mut AudioJitterBuffer) -> Vec<Playout> {
+        std::iter::from_fn(|| buffer.pop()).collect()
+    }
+
+    #[test]
+    fn test_waits_for_depth() {
+        let mut buffer = AudioJitterBuffer::new(3);
+        push(&mut buffer, 0);
+        push(&mut buffer, 1);
+        assert_eq!(buffer.pop(), None);
+
+        push(&mut buffer, 2);
+        assert_eq!(drain(&mut buffer), vec![packet(0), packet(1), packet(2)]);
+        assert!(buffer.packets.is_empty());
+    }
+
+    #[test]
+    fn test_depth_from_duration() {
+        assert_eq!(AudioJitterBuffer::with_duration(100).depth, 5);
+        assert_eq!(AudioJitterBuffer::with_duration(10).depth, 1);
+    }
+
+    #[test]
+    fn test_reordered_packet_plays_in_order() {
+        let mut buffer = AudioJitterBuffer::new(3);
+        for sequence in [0, 1, 2] {
+            push(&mut buffer, sequence);
+        }
+        assert_eq!(drain(&mut buffer).len(), 3);
+
+        // 4 arrives before 3
+        push(&mut buffer, 4);
+        assert_eq!(buffer.pop(), None);
+        push(&mut buffer, 3);
+
+        assert_eq!(drain(&mut buffer), vec![packet(3), packet(4)]);
+        assert_eq!(
+            buffer.stats(),
+            JitterStats {
+                late_packets: 0,
+                lost_packets: 0,
+                reordered: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_lost_packet_concealed_once() {
+        let mut buffer = AudioJitterBuffer::new(3);
+        for sequence in [0, 1, 2] {
+            push(&mut buffer, sequence);
+        }
+        assert_eq!(drain(&mut buffer).len(), 3);
+
+        // 3 never arrives
+        for sequence in [4, 5] {
+            push(&mut buffer, sequence);
+            assert_eq!(buffer.pop(), None);
+        }
+        push(&mut buffer, 6);
+
+        assert_eq!(
+            drain(&mut buffer),
+            vec![Playout::Missing, packet(4), packet(5), packet(6)]
+        );
+        assert_eq!(buffer.stats().lost_packets, 1);
+    }
+
+    #[test]
+    fn test_late_packet_dropped() {
+        let mut buffer = AudioJitterBuffer::new(2);
+        for sequence in [0, 2, 3] {
+            push(&mut buffer, sequence);
+        }
+        assert_eq!(
+            drain(&mut buffer),
+            vec![packet(0), Playout::Missing, packet(2), packet(3)]
+        );
+
+        push(&mut buffer, 1);
+        assert!(buffer.packets.is_empty());
+        assert_eq!(buffer.stats().late_packets, 1);
+        assert_eq!(buffer.stats().reordered, 0);
+    }
+
+    #[test]
+    fn test_duplicates_ignored() {
+        let mut buffer = AudioJitterBuffer::new(1);
+        push(&mut buffer, 0);
+        push(&mut buffer, 0);
+        assert_eq!(buffer.packets.len(), 1);
+        assert_eq!(drain(&mut buffer), vec![packet(0)]);
+    }
+
+    #[test]
+    fn test_unsequenced_packets_in_arrival_order() {
+        let mut buffer = AudioJitterBuffer::new(2);
+        buffer.push_unsequenced(0, vec![0]);
+        buffer.push_unsequenced(20, vec![1]);
+
+        assert_eq!(drain(&mut buffer), vec![packet(0), packet(1)]);
+    }
+}
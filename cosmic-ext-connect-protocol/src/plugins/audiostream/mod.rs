@@ -10,7 +10,8 @@
 //! ### Packet Types
 //!
 //! - `cconnect.audiostream.start` - Start audio stream with configuration
-//! - `cconnect.audiostream.data` - Audio data packet (via payload)
+//! - `cconnect.audiostream.data` - Audio data packet (via payload), numbered by
+//!   `sequence` with a `timestamp` in milliseconds since the stream started
 //! - `cconnect.audiostream.stop` - Stop audio stream
 //! - `cconnect.audiostream.config` - Update stream configuration
 //! - `cconnect.audiostream.volume` - Request volume change on remote stream
@@ -31,7 +32,7 @@
 //!
 //! ## Features
 //!
-//! - **Multiple Codecs**: Opus (recommended), PCM, AAC
+//! - **Multiple Codecs**: Opus (recommended), PCM, AAC, negotiated per stream
 //! - **Quality Control**: Configurable bitrate and sample rate
 //! - **Low Latency Mode**: Minimize audio delay
 //! - **Multi-channel**: Stereo and mono support
@@ -68,11 +69,20 @@ mod audio_backend;
 #[cfg(feature = "audiostream")]
 mod codec;
 
+#[cfg(feature = "audiostream")]
+mod jitter_buffer;
+
 #[cfg(feature = "audiostream")]
 use audio_backend::{AudioBackend, AudioSample, BackendConfig};
 
 #[cfg(feature = "audiostream")]
-use codec::{AacCodec, OpusCodec, PcmCodec};
+use codec::{create_codec, Codec};
+
+#[cfg(feature = "audiostream")]
+use jitter_buffer::{AudioJitterBuffer, Playout};
+
+#[cfg(feature = "audiostream")]
+pub use jitter_buffer::JitterStats;
 
 const PLUGIN_NAME: &str = "audiostream";
 const INCOMING_CAPABILITY: &str = "cconnect.audiostream";
@@ -113,6 +123,27 @@ impl AudioCodec {
             Self::Aac => "aac",
         }
     }
+
+    /// Preference when negotiating, higher is better
+    fn preference(&self) -> u8 {
+        match self {
+            Self::Opus => 2,
+            Self::Aac => 1,
+            Self::Pcm => 0,
+        }
+    }
+
+    /// Best codec supported by both sides (Opus > AAC > PCM)
+    ///
+    /// Falls back to PCM, which every peer with audio streaming can decode.
+    pub fn negotiate(local: &[AudioCodec], remote: &[AudioCodec]) -> AudioCodec {
+        local
+            .iter()
+            .filter(|codec| remote.contains(codec))
+            .max_by_key(|codec| codec.preference())
+            .copied()
+            .unwrap_or(AudioCodec::Pcm)
+    }
 }
 
 /// Audio stream direction
@@ -125,6 +156,16 @@ pub enum StreamDirection {
     Input,
 }
 
+impl StreamDirection {
+    /// The same stream as seen from the other device
+    pub fn opposite(&self) -> Self {
+        match self {
+            Self::Output => Self::Input,
+            Self::Input => Self::Output,
+        }
+    }
+}
+
 /// Audio stream configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamConfig {
@@ -154,6 +195,13 @@ pub struct StreamConfig {
     /// Buffer size in milliseconds
     #[serde(default = "default_buffer_size")]
     pub buffer_size_ms: u32,
+
+    /// Codecs the requesting device supports
+    ///
+    /// When set on a start request, the receiver picks the best codec both
+    /// sides support instead of `codec`, and answers with the chosen config.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub supported_codecs: Vec<AudioCodec>,
 }
 
 fn default_sample_rate() -> u32 {
@@ -186,6 +234,7 @@ impl Default for StreamConfig {
             direction: StreamDirection::Output,
             low_latency: false,
             buffer_size_ms: default_buffer_size(),
+            supported_codecs: Vec::new(),
         }
     }
 }
@@ -249,23 +298,19 @@ struct AudioStream {
     /// Packets sent/received
     packet_count: u64,
 
-    /// Audio buffer (for playback)
-    buffer: std::collections::VecDeque<Vec<u8>>,
+    /// Sequence number of the next data packet sent
+    next_sequence: u64,
 
     /// Volume level (0.0 to 1.0)
     volume: f32,
 
     #[cfg(feature = "audiostream")]
-    /// Opus codec instance
-    opus_codec: Option<OpusCodec>,
-
-    #[cfg(feature = "audiostream")]
-    /// PCM codec instance
-    pcm_codec: Option<PcmCodec>,
+    /// Reorders received packets before decoding
+    jitter_buffer: AudioJitterBuffer,
 
     #[cfg(feature = "audiostream")]
-    /// AAC codec instance
-    aac_codec: Option<AacCodec>,
+    /// Codec instance for `config.codec`
+    codec: Option<Box<dyn Codec>>,
 
     #[cfg(feature = "audiostream")]
     /// Audio capture channel receiver
@@ -280,23 +325,40 @@ struct AudioStream {
 impl AudioStream {
     fn new(config: StreamConfig) -> Self {
         Self {
-            config,
             started_at: std::time::Instant::now(),
             bytes_streamed: 0,
             packet_count: 0,
-            buffer: std::collections::VecDeque::new(),
+            next_sequence: 0,
             volume: 1.0, // Default to full volume
             #[cfg(feature = "audiostream")]
-            opus_codec: None,
-            #[cfg(feature = "audiostream")]
-            pcm_codec: None,
+            jitter_buffer: AudioJitterBuffer::with_duration(config.buffer_size_ms),
             #[cfg(feature = "audiostream")]
-            aac_codec: None,
+            codec: None,
             #[cfg(feature = "audiostream")]
             capture_rx: None,
             #[cfg(feature = "audiostream")]
             playback_tx: None,
+            config,
+        }
+    }
+
+    /// Replace the configuration, switching codec if it changed
+    fn apply_config(&mut self, config: StreamConfig) -> Result<()> {
+        #[cfg(feature = "audiostream")]
+        {
+            if self.codec.is_some() && config.codec != self.config.codec {
+                self.codec = Some(create_codec(
+                    config.codec,
+                    config.sample_rate,
+                    config.channels,
+                    config.bitrate,
+                )?);
+                info!("Switched stream codec to {}", config.codec.as_str());
+            }
         }
+
+        self.config = config;
+        Ok(())
     }
 
     fn update_stats(&mut self, bytes: u64) {
@@ -304,6 +366,43 @@ impl AudioStream {
         self.packet_count += 1;
     }
 
+    #[cfg(feature = "audiostream")]
+    /// Number encoded audio and wrap it in data packets
+    fn data_packets(&mut self, encoded: Vec<Vec<u8>>) -> Vec<Packet> {
+        let timestamp = self.started_at.elapsed().as_millis() as u64;
+        encoded
+            .into_iter()
+            .map(|data| {
+                self.update_stats(data.len() as u64);
+                let sequence = self.next_sequence;
+                self.next_sequence += 1;
+
+                let mut body = serde_json::Map::new();
+                body.insert(
+                    "data".to_string(),
+                    serde_json::Value::String(BASE64.encode(&data)),
+                );
+                body.insert("sequence".to_string(), sequence.into());
+                body.insert("timestamp".to_string(), timestamp.into());
+                Packet::new("cconnect.audiostream.data", serde_json::Value::Object(body))
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "audiostream")]
+    /// Data packets for the samples the codec still holds back
+    fn flush_codec(&mut self) -> Vec<Packet> {
+        let encoded = match self.codec.as_mut().map(|codec| codec.flush()) {
+            Some(Ok(encoded)) => encoded,
+            Some(Err(e)) => {
+                warn!("{} flush failed: {}", self.config.codec.as_str(), e);
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+        self.data_packets(encoded)
+    }
+
     fn get_stats(&self) -> StreamStats {
         let duration = self.started_at.elapsed();
         let bitrate = if duration.as_secs() > 0 {
@@ -434,26 +533,12 @@ impl AudioStreamPlugin {
                     let mut stream = AudioStream::new(config.clone());
 
                     // Initialize codec
-                    match config.codec {
-                        AudioCodec::Opus => {
-                            stream.opus_codec = Some(OpusCodec::new(
-                                config.sample_rate,
-                                config.channels,
-                                config.bitrate,
-                            )?);
-                        }
-                        AudioCodec::Pcm => {
-                            stream.pcm_codec =
-                                Some(PcmCodec::new(config.sample_rate, config.channels));
-                        }
-                        AudioCodec::Aac => {
-                            stream.aac_codec = Some(AacCodec::new(
-                                config.sample_rate,
-                                config.channels,
-                                config.bitrate,
-                            )?);
-                        }
-                    }
+                    stream.codec = Some(create_codec(
+                        config.codec,
+                        config.sample_rate,
+                        config.channels,
+                        config.bitrate,
+                    )?);
 
                     // Start audio capture
                     if let Some(backend) = &mut self.audio_backend {
@@ -489,26 +574,12 @@ impl AudioStreamPlugin {
                     let mut stream = AudioStream::new(config.clone());
 
                     // Initialize codec
-                    match config.codec {
-                        AudioCodec::Opus => {
-                            stream.opus_codec = Some(OpusCodec::new(
-                                config.sample_rate,
-                                config.channels,
-                                config.bitrate,
-                            )?);
-                        }
-                        AudioCodec::Pcm => {
-                            stream.pcm_codec =
-                                Some(PcmCodec::new(config.sample_rate, config.channels));
-                        }
-                        AudioCodec::Aac => {
-                            stream.aac_codec = Some(AacCodec::new(
-                                config.sample_rate,
-                                config.channels,
-                                config.bitrate,
-                            )?);
-                        }
-                    }
+                    stream.codec = Some(create_codec(
+                        config.codec,
+                        config.sample_rate,
+                        config.channels,
+                        config.bitrate,
+                    )?);
 
                     // Start audio playback
                     if let Some(backend) = &mut self.audio_backend {
@@ -535,9 +606,23 @@ impl AudioStreamPlugin {
     }
 
     /// Stop outgoing audio stream
+    ///
+    /// Audio the codec still holds back is sent before the stream ends.
     pub async fn stop_outgoing_stream(&mut self) -> Result<()> {
         let mut stream_lock = self.outgoing_stream.write().await;
         if let Some(stream) = stream_lock.take() {
+            drop(stream_lock);
+
+            #[cfg(feature = "audiostream")]
+            let stream = {
+                let mut stream = stream;
+                let packets = stream.flush_codec();
+                if let (Some(sender), Some(device_id)) = (&self.packet_sender, &self.device_id) {
+                    Self::send_data_packets(sender, device_id, packets).await;
+                }
+                stream
+            };
+
             let stats = stream.get_stats();
             info!(
                 "Stopped outgoing stream: {} packets, {} bytes, {} seconds",
@@ -573,18 +658,16 @@ impl AudioStreamPlugin {
         match config.direction {
             StreamDirection::Output => {
                 if let Some(stream) = self.outgoing_stream.write().await.as_mut() {
-                    stream.config = config;
+                    stream.apply_config(config)?;
                     info!("Updated outgoing stream configuration");
-                    // Encoder reconfiguration requires stopping and restarting the stream
-                    // This is handled by the client calling stop then start with new config
+                    // Other encoder settings take effect when the stream is restarted
                 }
             }
             StreamDirection::Input => {
                 if let Some(stream) = self.incoming_stream.write().await.as_mut() {
-                    stream.config = config;
+                    stream.apply_config(config)?;
                     info!("Updated incoming stream configuration");
-                    // Decoder reconfiguration requires stopping and restarting the stream
-                    // This is handled by the client calling stop then start with new config
+                    // Other decoder settings take effect when the stream is restarted
                 }
             }
         }
@@ -614,6 +697,11 @@ impl AudioStreamPlugin {
                             }
                             Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => {
                                 debug!("Capture channel disconnected");
+                                let packets = stream.flush_codec();
+                                drop(stream_lock);
+                                if let (Some(sender), Some(dev_id)) = (&packet_sender, &device_id) {
+                                    Self::send_data_packets(sender, dev_id, packets).await;
+                                }
                                 break;
                             }
                         }
@@ -621,28 +709,12 @@ impl AudioStreamPlugin {
                         break;
                     };
 
-                    // Encode samples based on codec
-                    let encoded = if let Some(opus) = &mut stream.opus_codec {
-                        match opus.encode(&samples) {
-                            Ok(data) => data,
+                    // Encode samples with the stream's codec
+                    let packets = if let Some(codec) = &mut stream.codec {
+                        match codec.encode(&samples) {
+                            Ok(packets) => packets,
                             Err(e) => {
-                                error!("Opus encoding failed: {}", e);
-                                continue;
-                            }
-                        }
-                    } else if let Some(pcm) = &stream.pcm_codec {
-                        match pcm.encode(&samples) {
-                            Ok(data) => data,
-                            Err(e) => {
-                                error!("PCM encoding failed: {}", e);
-                                continue;
-                            }
-                        }
-                    } else if let Some(aac) = &mut stream.aac_codec {
-                        match aac.encode(&samples) {
-                            Ok(data) => data,
-                            Err(e) => {
-                                error!("AAC encoding failed: {}", e);
+                                error!("{} encoding failed: {}", stream.config.codec.as_str(), e);
                                 continue;
                             }
                         }
@@ -651,29 +723,20 @@ impl AudioStreamPlugin {
                         break;
                     };
 
-                    // Update stats
-                    stream.update_stats(encoded.len() as u64);
-
-                    drop(stream_lock);
+                    // Framed codecs emit nothing until a frame fills up
+                    if packets.is_empty() {
+                        continue;
+                    }
 
-                    // Send packet with audio data
-                    if let Some(sender) = &packet_sender {
-                        if let Some(dev_id) = &device_id {
-                            let mut body = serde_json::Map::new();
-                            body.insert(
-                                "data".to_string(),
-                                serde_json::Value::String(BASE64.encode(&encoded)),
-                            );
+                    // Update stats and number the packets
+                    let packets = stream.data_packets(packets);
 
-                            let packet = Packet::new(
-                                "cconnect.audiostream.data",
-                                serde_json::Value::Object(body),
-                            );
+                    drop(stream_lock);
 
-                            if let Err(e) = sender.send((dev_id.clone(), packet)).await {
-                                error!("Failed to send audio packet: {}", e);
-                                break;
-                            }
+                    // Send packets with audio data
+                    if let (Some(sender), Some(dev_id)) = (&packet_sender, &device_id) {
+                        if !Self::send_data_packets(sender, dev_id, packets).await {
+                            break;
                         }
                     }
                 } else {
@@ -686,6 +749,22 @@ impl AudioStreamPlugin {
         Ok(())
     }
 
+    #[cfg(feature = "audiostream")]
+    /// Send audio data packets, returns `false` once the channel is closed
+    async fn send_data_packets(
+        sender: &mpsc::Sender<(String, Packet)>,
+        device_id: &str,
+        packets: Vec<Packet>,
+    ) -> bool {
+        for packet in packets {
+            if let Err(e) = sender.send((device_id.to_string(), packet)).await {
+                error!("Failed to send audio packet: {}", e);
+                return false;
+            }
+        }
+        true
+    }
+
     #[cfg(feature = "audiostream")]
     /// Start incoming audio decoding and playback task
     async fn start_incoming_task(&mut self) -> Result<()> {
@@ -697,36 +776,33 @@ impl AudioStreamPlugin {
 
                 let mut stream_lock = incoming_stream.write().await;
                 if let Some(stream) = stream_lock.as_mut() {
-                    // Process buffered packets
-                    while let Some(encoded_data) = stream.buffer.pop_front() {
-                        // Decode based on codec
-                        let samples = if let Some(opus) = &mut stream.opus_codec {
-                            match opus.decode(&encoded_data) {
-                                Ok(data) => data,
-                                Err(e) => {
-                                    error!("Opus decoding failed: {}", e);
-                                    // Use packet loss concealment
-                                    match opus.decode_plc() {
-                                        Ok(plc) => plc,
-                                        Err(_) => continue,
+                    // Play packets as the jitter buffer releases them
+                    while let Some(playout) = stream.jitter_buffer.pop() {
+                        // Decode with the stream's codec
+                        let samples = if let Some(codec) = &mut stream.codec {
+                            match playout {
+                                Playout::Packet { timestamp, data } => match codec.decode(&data) {
+                                    Ok(data) => {
+                                        debug!("Playing audio packet sent at {}ms", timestamp);
+                                        data
                                     }
-                                }
-                            }
-                        } else if let Some(pcm) = &stream.pcm_codec {
-                            match pcm.decode(&encoded_data) {
-                                Ok(data) => data,
-                                Err(e) => {
-                                    error!("PCM decoding failed: {}", e);
-                                    continue;
-                                }
-                            }
-                        } else if let Some(aac) = &mut stream.aac_codec {
-                            match aac.decode(&encoded_data) {
-                                Ok(data) => data,
-                                Err(e) => {
-                                    error!("AAC decoding failed: {}", e);
-                                    continue;
-                                }
+                                    Err(e) => {
+                                        error!(
+                                            "{} decoding failed: {}",
+                                            stream.config.codec.as_str(),
+                                            e
+                                        );
+                                        // Use packet loss concealment where available
+                                        match codec.conceal_loss() {
+                                            Some(concealed) => concealed,
+                                            None => continue,
+                                        }
+                                    }
+                                },
+                                // Conceal the lost packet, or play a frame of silence
+                                Playout::Missing => codec.conceal_loss().unwrap_or_else(|| {
+                                    vec![0.0; codec.frame_size() * stream.config.channels as usize]
+                                }),
                             }
                         } else {
                             error!("No codec available for decoding");
@@ -752,16 +828,27 @@ impl AudioStreamPlugin {
     }
 
     /// Process audio data packet
-    async fn process_audio_data(&self, _data: &[u8]) -> Result<()> {
+    ///
+    /// Packets without a sequence number come from older peers and are
+    /// assumed to arrive in order.
+    async fn process_audio_data(
+        &self,
+        data: &[u8],
+        sequence: Option<u64>,
+        timestamp: u64,
+    ) -> Result<()> {
         #[cfg(feature = "audiostream")]
         {
-            let data = _data;
             let mut stream_lock = self.incoming_stream.write().await;
             if let Some(stream) = stream_lock.as_mut() {
                 stream.update_stats(data.len() as u64);
 
-                // Add to buffer for processing by incoming task
-                stream.buffer.push_back(data.to_vec());
+                // Add to jitter buffer for playout by incoming task
+                let jitter_buffer = &mut stream.jitter_buffer;
+                match sequence {
+                    Some(sequence) => jitter_buffer.push(sequence, timestamp, data.to_vec()),
+                    None => jitter_buffer.push_unsequenced(timestamp, data.to_vec()),
+                }
 
                 debug!("Buffered {} bytes of audio data", data.len());
             } else {
@@ -771,6 +858,7 @@ impl AudioStreamPlugin {
 
         #[cfg(not(feature = "audiostream"))]
         {
+            let _ = (data, sequence, timestamp);
             warn!("Cannot process audio data without 'audiostream' feature");
         }
 
@@ -795,6 +883,16 @@ impl AudioStreamPlugin {
         }
     }
 
+    /// Jitter buffer statistics of the incoming stream, if one is active
+    #[cfg(feature = "audiostream")]
+    pub async fn jitter_stats(&self) -> Option<JitterStats> {
+        self.incoming_stream
+            .read()
+            .await
+            .as_ref()
+            .map(|s| s.jitter_buffer.stats())
+    }
+
     /// Check if a codec is supported
     pub fn is_codec_supported(&self, codec: AudioCodec) -> bool {
         self.supported_codecs.contains(&codec)
@@ -805,6 +903,11 @@ impl AudioStreamPlugin {
         &self.supported_codecs
     }
 
+    /// Best codec supported by both this system and the remote device
+    pub fn negotiate_codec(&self, remote: &[AudioCodec]) -> AudioCodec {
+        AudioCodec::negotiate(&self.supported_codecs, remote)
+    }
+
     /// Set volume level for a stream
     ///
     /// # Arguments
@@ -986,10 +1089,33 @@ impl Plugin for AudioStreamPlugin {
 
         if packet.is_type("cconnect.audiostream.start") {
             // Start audio stream with configuration
-            let config: StreamConfig = serde_json::from_value(packet.body.clone())
+            let mut config: StreamConfig = serde_json::from_value(packet.body.clone())
                 .map_err(|e| ProtocolError::InvalidPacket(e.to_string()))?;
 
-            self.start_stream(config).await?;
+            let negotiated = !config.supported_codecs.is_empty();
+            if negotiated {
+                config.codec = self.negotiate_codec(&config.supported_codecs);
+                info!("Negotiated {} codec with remote", config.codec.as_str());
+            }
+
+            self.start_stream(config.clone()).await?;
+
+            // Tell the remote which codec was chosen for its side of the stream
+            if negotiated {
+                if let (Some(sender), Some(dev_id)) = (&self.packet_sender, &self.device_id) {
+                    let reply = StreamConfig {
+                        direction: config.direction.opposite(),
+                        supported_codecs: Vec::new(),
+                        ..config
+                    };
+                    let body = serde_json::to_value(&reply)
+                        .map_err(|e| ProtocolError::InvalidPacket(e.to_string()))?;
+                    let packet = Packet::new("cconnect.audiostream.config", body);
+                    if let Err(e) = sender.send((dev_id.clone(), packet)).await {
+                        error!("Failed to send negotiated stream config: {}", e);
+                    }
+                }
+            }
 
             info!("Audio stream started from remote request");
         } else if packet.is_type("cconnect.audiostream.stop") {
@@ -1021,7 +1147,14 @@ impl Plugin for AudioStreamPlugin {
                 match BASE64.decode(payload_b64) {
                     Ok(audio_data) => {
                         debug!("Received audio data packet: {} bytes", audio_data.len());
-                        self.process_audio_data(&audio_data).await?;
+                        let sequence = packet.body.get("sequence").and_then(|v| v.as_u64());
+                        let timestamp = packet
+                            .body
+                            .get("timestamp")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(0);
+                        self.process_audio_data(&audio_data, sequence, timestamp)
+                            .await?;
                     }
                     Err(e) => {
                         warn!("Failed to decode base64 audio data: {}", e);
@@ -1486,4 +1619,185 @@ mod tests {
         let volume = audio_plugin.get_volume(StreamDirection::Input).await;
         assert_eq!(volume, Some(0.3));
     }
+
+    #[test]
+    fn test_negotiate_prefers_opus() {
+        let all = [AudioCodec::Pcm, AudioCodec::Aac, AudioCodec::Opus];
+        assert_eq!(AudioCodec::negotiate(&all, &all), AudioCodec::Opus);
+    }
+
+    #[test]
+    fn test_negotiate_without_local_opus() {
+        let local = [AudioCodec::Pcm, AudioCodec::Aac];
+        let remote = [AudioCodec::Opus, AudioCodec::Pcm, AudioCodec::Aac];
+        assert_eq!(AudioCodec::negotiate(&local, &remote), AudioCodec::Aac);
+
+        let local = [AudioCodec::Pcm];
+        assert_eq!(AudioCodec::negotiate(&local, &remote), AudioCodec::Pcm);
+    }
+
+    #[test]
+    fn test_negotiate_without_remote_opus() {
+        let local = [AudioCodec::Pcm, AudioCodec::Opus];
+        let remote = [AudioCodec::Pcm];
+        assert_eq!(AudioCodec::negotiate(&local, &remote), AudioCodec::Pcm);
+        assert_eq!(AudioCodec::negotiate(&local, &[]), AudioCodec::Pcm);
+    }
+
+    #[test]
+    fn test_supported_codecs_round_trip() {
+        let config = StreamConfig {
+            supported_codecs: vec![AudioCodec::Opus, AudioCodec::Pcm],
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&config).unwrap();
+        assert_eq!(json["supported_codecs"], serde_json::json!(["opus", "pcm"]));
+
+        let json = serde_json::to_value(StreamConfig::default()).unwrap();
+        assert!(json.get("supported_codecs").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_start_packet_negotiates_codec() {
+        let mut device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        let mut plugin = AudioStreamPlugin::new();
+        plugin.init(&device, tx).await.unwrap();
+        plugin.start().await.unwrap();
+
+        let expected = plugin.negotiate_codec(&[AudioCodec::Opus, AudioCodec::Pcm]);
+        let packet = Packet::new(
+            "cconnect.audiostream.start",
+            serde_json::json!({
+                "codec": "aac",
+                "direction": "input",
+                "supported_codecs": ["opus", "pcm"],
+            }),
+        );
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let stream = plugin.incoming_stream.read().await;
+        assert_eq!(stream.as_ref().unwrap().config.codec, expected);
+        drop(stream);
+
+        let (_, reply) = rx.try_recv().unwrap();
+        assert_eq!(reply.packet_type, "cconnect.audiostream.config");
+        let reply: StreamConfig = serde_json::from_value(reply.body).unwrap();
+        assert_eq!(reply.codec, expected);
+        assert_eq!(reply.direction, StreamDirection::Output);
+    }
+
+    #[tokio::test]
+    async fn test_update_config_switches_codec() {
+        let mut plugin = AudioStreamPlugin::new();
+        plugin.enabled = true;
+
+        let config = StreamConfig {
+            direction: StreamDirection::Input,
+            codec: AudioCodec::Pcm,
+            ..Default::default()
+        };
+        plugin.start_stream(config.clone()).await.unwrap();
+
+        #[cfg(feature = "opus")]
+        {
+            let opus = StreamConfig {
+                codec: AudioCodec::Opus,
+                ..config
+            };
+            plugin.update_config(opus).await.unwrap();
+
+            let stream = plugin.incoming_stream.read().await;
+            let stream = stream.as_ref().unwrap();
+            assert_eq!(stream.config.codec, AudioCodec::Opus);
+            assert_eq!(stream.codec.as_ref().unwrap().frame_size(), 960);
+        }
+
+        #[cfg(not(feature = "opus"))]
+        {
+            let opus = StreamConfig {
+                codec: AudioCodec::Opus,
+                ..config
+            };
+            assert!(plugin.update_config(opus).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_data_reordered() {
+        let mut plugin = AudioStreamPlugin::new();
+        plugin.enabled = true;
+
+        let config = StreamConfig {
+            direction: StreamDirection::Input,
+            codec: AudioCodec::Pcm,
+            ..Default::default()
+        };
+        plugin.start_stream(config).await.unwrap();
+
+        plugin
+            .process_audio_data(&[0, 0, 0, 0], Some(1), 20)
+            .await
+            .unwrap();
+        plugin
+            .process_audio_data(&[0, 0, 0, 0], Some(0), 0)
+            .await
+            .unwrap();
+
+        let stats = plugin.jitter_stats().await.unwrap();
+        assert_eq!(stats.reordered, 1);
+        assert_eq!(stats.late_packets, 0);
+    }
+
+    /// Codec that holds every sample back until flushed
+    struct HoldingCodec {
+        held: Vec<AudioSample>,
+    }
+
+    impl Codec for HoldingCodec {
+        fn encode(&mut self, samples: &[AudioSample]) -> Result<Vec<Vec<u8>>> {
+            self.held.extend_from_slice(samples);
+            Ok(Vec::new())
+        }
+
+        fn flush(&mut self) -> Result<Vec<Vec<u8>>> {
+            let held = std::mem::take(&mut self.held);
+            Ok(vec![vec![0; held.len()]])
+        }
+
+        fn decode(&mut self, _packet: &[u8]) -> Result<Vec<AudioSample>> {
+            Ok(Vec::new())
+        }
+
+        fn frame_size(&self) -> usize {
+            960
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stop_sends_held_back_audio() {
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut plugin = AudioStreamPlugin::new();
+        plugin.device_id = Some("phone".to_string());
+        plugin.packet_sender = Some(tx);
+
+        let mut codec = HoldingCodec { held: Vec::new() };
+        assert!(codec.encode(&[0.5; 100]).unwrap().is_empty());
+        let mut stream = AudioStream::new(StreamConfig::default());
+        stream.codec = Some(Box::new(codec));
+        stream.next_sequence = 7;
+        *plugin.outgoing_stream.write().await = Some(stream);
+
+        plugin.stop_outgoing_stream().await.unwrap();
+
+        let (device_id, packet) = rx.try_recv().unwrap();
+        assert_eq!(device_id, "phone");
+        assert!(packet.is_type("cconnect.audiostream.data"));
+        assert_eq!(packet.body["sequence"], 7);
+        let data = BASE64
+            .decode(packet.body["data"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(data.len(), 100);
+        assert!(rx.try_recv().is_err());
+    }
 }
@@ -0,0 +1,452 @@
+//! Audit Log for Privileged Plugin Actions
+//!
+//! Every permission decision for a power, run-command or remote input request
+//! is recorded as an [`AuditEntry`]: when it happened, which device asked,
+//! what it asked for and whether it was allowed.
+//!
+//! ## Sinks
+//!
+//! Entries are always emitted as an info event with the `AUDIT` prefix, which
+//! ends up in the systemd journal when the daemon runs as a user service. With
+//! a file configured, they are also appended to it as JSON lines:
+//!
+//! ```json
+//! {"timestamp":1700000000000,"device_id":"phone","plugin":"power","action":"shutdown","parameters":{"action":"shutdown"},"outcome":"denied","reason":"device is not allowed to use power"}
+//! ```
+//!
+//! The file is opened in append mode with owner-only permissions and is never
+//! truncated or rewritten; there is deliberately no API to delete entries.
+//! Once it grows past [`MAX_FILE_SIZE`] it is renamed to `<file>.1`, replacing
+//! the previous rotation, so queries read at most two bounded files. If the
+//! file cannot be opened or a write fails, a warning is logged once and the
+//! log carries on with the journal only.
+//!
+//! ## Redaction
+//!
+//! Parameters are written as given. Callers redact secrets before recording,
+//! e.g. with [`redact_command`] for run-command commands flagged sensitive.
+//!
+//! ## Sharing the Log
+//!
+//! The daemon opens one log and shares it as `Arc<AuditLog>`: the
+//! [`AllowlistPolicy`](super::permission::AllowlistPolicy) records its
+//! decisions to it and the D-Bus interface reads it. Reads do blocking file
+//! IO, so async callers run them on the blocking thread pool.
+
+use super::permission::PrivilegedAction;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// Largest page a single query returns
+pub const MAX_QUERY_LIMIT: u32 = 500;
+
+/// Size after which the audit file is rotated (1 MiB)
+pub const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Placeholder replacing redacted values
+pub const REDACTED: &str = "[redacted]";
+
+/// Whether a privileged action was allowed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    /// The permission policy allowed the action
+    Allowed,
+    /// The permission policy denied the action
+    Denied,
+}
+
+/// A recorded permission decision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the decision was made (UNIX timestamp in milliseconds)
+    pub timestamp: i64,
+    /// Device that requested the action
+    pub device_id: String,
+    /// Plugin performing the action
+    pub plugin: String,
+    /// Action detail, e.g. the power action or command key
+    pub action: String,
+    /// Request parameters, already redacted
+    pub parameters: serde_json::Value,
+    /// Whether the action was allowed
+    pub outcome: AuditOutcome,
+    /// Why the action was denied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl AuditEntry {
+    /// Create an entry for a decision made now
+    pub fn new(
+        device_id: &str,
+        action: &PrivilegedAction,
+        parameters: serde_json::Value,
+        outcome: AuditOutcome,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            device_id: device_id.to_string(),
+            plugin: action.plugin().to_string(),
+            action: action.detail().to_string(),
+            parameters,
+            outcome,
+            reason,
+        }
+    }
+}
+
+/// Append-only audit log
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use cosmic_ext_connect_protocol::plugins::audit::AuditLog;
+///
+/// let log = AuditLog::open("/home/user/.local/share/cosmic-ext-connect/audit.log");
+/// let recent = log.query(Some("phone"), 0, 20).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct AuditLog {
+    /// Audit file, if one is configured
+    path: Option<PathBuf>,
+
+    /// Open handle; `None` once opening or writing failed
+    file: Mutex<Option<File>>,
+
+    /// Size after which the file is rotated
+    max_file_size: u64,
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self {
+            path: None,
+            file: Mutex::new(None),
+            max_file_size: MAX_FILE_SIZE,
+        }
+    }
+}
+
+/// Open an audit file for appending, creating it with mode 0600
+fn open_append(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(path)
+}
+
+impl AuditLog {
+    /// Create a log that only writes to the journal
+    pub fn journal_only() -> Self {
+        Self::default()
+    }
+
+    /// Create a log appending to `path`
+    ///
+    /// The file is created with mode 0600 if missing. If it cannot be
+    /// opened, the log falls back to the journal only.
+    pub fn open(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let file = match open_append(&path) {
+            Ok(file) => {
+                debug!("Audit log opened at {:?}", path);
+                Some(file)
+            }
+            Err(e) => {
+                warn!(
+                    "Audit log {:?} is not writable, using the journal only: {}",
+                    path, e
+                );
+                None
+            }
+        };
+
+        Self {
+            path: Some(path),
+            file: Mutex::new(file),
+            max_file_size: MAX_FILE_SIZE,
+        }
+    }
+
+    /// Rotate the file once it grows past `max_file_size` bytes
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Path of the audit file, if one is configured
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Path the audit file is rotated to
+    fn rotated_path(&self) -> Option<PathBuf> {
+        self.path.as_ref().map(|path| {
+            let mut rotated = path.clone().into_os_string();
+            rotated.push(".1");
+            PathBuf::from(rotated)
+        })
+    }
+
+    /// Whether entries are currently written to the audit file
+    pub fn is_file_writable(&self) -> bool {
+        self.file.lock().map(|file| file.is_some()).unwrap_or(false)
+    }
+
+    /// Record an entry
+    ///
+    /// Never fails; file errors are logged and disable the file sink.
+    pub fn record(&self, entry: &AuditEntry) {
+        let line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to serialize audit entry: {}", e);
+                return;
+            }
+        };
+
+        info!("AUDIT {}", line);
+
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if let Some(handle) = file.as_mut() {
+            // One write per line so concurrent writers never interleave
+            if let Err(e) = handle.write_all(format!("{}\n", line).as_bytes()) {
+                warn!(
+                    "Failed to write audit log {:?}, using the journal only: {}",
+                    self.path, e
+                );
+                *file = None;
+                return;
+            }
+
+            let size = handle.metadata().map(|m| m.len()).unwrap_or_default();
+            if size > self.max_file_size {
+                *file = self.rotate();
+            }
+        }
+    }
+
+    /// Move the full audit file aside and start a new one
+    ///
+    /// Returns the new handle, `None` if the file could not be reopened.
+    fn rotate(&self) -> Option<File> {
+        let (path, rotated) = (self.path.as_ref()?, self.rotated_path()?);
+        if let Err(e) = std::fs::rename(path, &rotated) {
+            warn!("Failed to rotate audit log {:?}: {}", path, e);
+        } else {
+            debug!("Audit log rotated to {:?}", rotated);
+        }
+
+        match open_append(path) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                warn!(
+                    "Audit log {:?} is not writable, using the journal only: {}",
+                    path, e
+                );
+                None
+            }
+        }
+    }
+
+    /// Read entries from the audit file, newest first
+    ///
+    /// # Arguments
+    /// * `device_id` - Only entries of this device (`None` for all devices)
+    /// * `offset` - Number of newer entries to skip
+    /// * `limit` - Maximum number of entries (capped at [`MAX_QUERY_LIMIT`])
+    ///
+    /// Lines that fail to parse are skipped. Without an audit file the result
+    /// is empty.
+    pub fn query(
+        &self,
+        device_id: Option<&str>,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<AuditEntry>> {
+        let mut entries = self.read_entries(device_id)?;
+        entries.reverse();
+        Ok(entries
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit.min(MAX_QUERY_LIMIT) as usize)
+            .collect())
+    }
+
+    /// Number of entries in the audit file
+    ///
+    /// # Arguments
+    /// * `device_id` - Only entries of this device (`None` for all devices)
+    pub fn count(&self, device_id: Option<&str>) -> Result<u64> {
+        Ok(self.read_entries(device_id)?.len() as u64)
+    }
+
+    /// Read all entries in file order, the rotated file first
+    fn read_entries(&self, device_id: Option<&str>) -> Result<Vec<AuditEntry>> {
+        let mut entries = Vec::new();
+        for path in self.rotated_path().iter().chain(&self.path) {
+            read_file_entries(path, device_id, &mut entries)?;
+        }
+        Ok(entries)
+    }
+}
+
+/// Append the entries of one audit file, a missing file has none
+fn read_file_entries(
+    path: &Path,
+    device_id: Option<&str>,
+    entries: &mut Vec<AuditEntry>,
+) -> Result<()> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = match serde_json::from_str::<AuditEntry>(&line) {
+            Ok(entry) => entry,
+            Err(e) => {
+                debug!("Skipping malformed audit line: {}", e);
+                continue;
+            }
+        };
+        if device_id.is_some_and(|id| entry.device_id != id) {
+            continue;
+        }
+        entries.push(entry);
+    }
+    Ok(())
+}
+
+/// Keep only the program of a shell command
+///
+/// `"mysql -u root -psecret"` becomes `"mysql [redacted]"`.
+pub fn redact_command(command: &str) -> String {
+    match command.split_whitespace().next() {
+        Some(program) => format!("{} {}", program, REDACTED),
+        None => REDACTED.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(device_id: &str, outcome: AuditOutcome) -> AuditEntry {
+        AuditEntry::new(
+            device_id,
+            &PrivilegedAction::Power("shutdown".to_string()),
+            json!({ "action": "shutdown" }),
+            outcome,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_record_appends_to_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+
+        AuditLog::open(&path).record(&entry("phone", AuditOutcome::Allowed));
+        let log = AuditLog::open(&path);
+        log.record(&entry("tablet", AuditOutcome::Denied));
+
+        assert_eq!(log.count(None).unwrap(), 2);
+        let entries = log.query(None, 0, 10).unwrap();
+        assert_eq!(entries[0].device_id, "tablet");
+        assert_eq!(entries[0].outcome, AuditOutcome::Denied);
+        assert_eq!(entries[1].device_id, "phone");
+    }
+
+    #[test]
+    fn test_query_filters_and_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::open(dir.path().join("audit.log"));
+        for _ in 0..3 {
+            log.record(&entry("phone", AuditOutcome::Allowed));
+            log.record(&entry("tablet", AuditOutcome::Denied));
+        }
+
+        assert_eq!(log.count(Some("phone")).unwrap(), 3);
+        let page = log.query(Some("tablet"), 1, 5).unwrap();
+        assert_eq!(page.len(), 2);
+        assert!(page.iter().all(|e| e.device_id == "tablet"));
+    }
+
+    #[test]
+    fn test_malformed_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        std::fs::write(&path, "not json\n").unwrap();
+
+        let log = AuditLog::open(&path);
+        log.record(&entry("phone", AuditOutcome::Allowed));
+        assert_eq!(log.count(None).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_unwritable_path_falls_back_to_journal() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::open(dir.path().join("missing").join("audit.log"));
+
+        assert!(!log.is_file_writable());
+        log.record(&entry("phone", AuditOutcome::Allowed));
+        assert!(log.query(None, 0, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_full_file_is_rotated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let log = AuditLog::open(&path).with_max_file_size(1);
+
+        log.record(&entry("phone", AuditOutcome::Allowed));
+        log.record(&entry("tablet", AuditOutcome::Denied));
+        assert!(dir.path().join("audit.log.1").exists());
+
+        // Only the last rotation is kept, and queries read it before the
+        // current file
+        log.record(&entry("laptop", AuditOutcome::Allowed));
+        let entries = log.query(None, 0, 10).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].device_id, "laptop");
+        assert!(log.is_file_writable());
+
+        let log = AuditLog::open(&path);
+        log.record(&entry("phone", AuditOutcome::Allowed));
+        let entries = log.query(None, 0, 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].device_id, "phone");
+        assert_eq!(entries[1].device_id, "laptop");
+    }
+
+    #[test]
+    fn test_journal_only_query_is_empty() {
+        let log = AuditLog::journal_only();
+        log.record(&entry("phone", AuditOutcome::Allowed));
+        assert_eq!(log.count(None).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_redact_command() {
+        assert_eq!(redact_command("mysql -u root -psecret"), "mysql [redacted]");
+        assert_eq!(redact_command("   "), REDACTED);
+    }
+}
@@ -34,6 +34,10 @@
 //! - `isCharging` (bool): Whether device is charging
 //! - `thresholdEvent` (i32): 0 = above threshold, 1 = below threshold
 //!
+//! When reporting the desktop's own battery, `thresholdEvent` is 1 while the
+//! charge is at or below [`LOW_BATTERY_THRESHOLD`] and the battery is not
+//! charging.
+//!
 //! ### Battery Request (`cconnect.battery.request`) - Deprecated
 //!
 //! ```json
@@ -48,8 +52,11 @@
 //!
 //! ## Behavior
 //!
-//! - **Proactive Updates**: Send battery status when it changes
-//! - **Polling (Deprecated)**: Respond to battery requests
+//! - **Proactive Updates**: The local battery is read through UPower every
+//!   [`BATTERY_POLL_INTERVAL`] and sent when it changes
+//! - **Debouncing**: Charge changes smaller than [`CHARGE_REPORT_DELTA`] points
+//!   are not reported unless the charging state or threshold event changes
+//! - **Polling (Deprecated)**: Respond to battery requests with the current status
 //! - **Idempotent**: Multiple status updates are safe
 //! - **No Battery**: Use -1 for currentCharge if device has no battery
 //!
@@ -96,11 +103,24 @@ use crate::{Device, Packet, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
+use super::upower_backend::{PowerStatus, UPowerBackend};
 use super::{Plugin, PluginFactory};
 
+/// Charge (percent) at or below which outgoing reports set `thresholdEvent`
+pub const LOW_BATTERY_THRESHOLD: i32 = 15;
+
+/// Minimum charge change (percentage points) before a new report is sent
+pub const CHARGE_REPORT_DELTA: i32 = 2;
+
+/// How often the local battery is polled through UPower
+pub const BATTERY_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Battery status information
 ///
 /// Represents the power state of a device.
@@ -207,6 +227,117 @@ impl BatteryStatus {
     pub fn is_low_battery(&self) -> bool {
         self.threshold_event == 1
     }
+
+    /// Build the local battery status from a UPower reading
+    ///
+    /// Systems without a battery (or without a known charge) report
+    /// [`BatteryStatus::no_battery`]. The threshold event is left at 0;
+    /// [`BatteryReporter`] fills it in.
+    pub fn from_power_status(power: &PowerStatus) -> Self {
+        match power.battery_percentage {
+            Some(percentage) if power.battery_present => Self::new(
+                percentage.round().clamp(0.0, 100.0) as i32,
+                power.battery_state.is_charging(),
+                0,
+            ),
+            _ => Self::no_battery(),
+        }
+    }
+}
+
+/// Debounces outgoing reports of the local battery
+///
+/// Tracks the last status sent to the remote device and decides whether a
+/// new reading is worth sending.
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmic_ext_connect_protocol::plugins::battery::{BatteryReporter, BatteryStatus};
+///
+/// let mut reporter = BatteryReporter::new(15, 2);
+/// assert!(reporter.update(BatteryStatus::new(50, false, 0)).is_some());
+///
+/// // One point is below the debounce delta
+/// assert!(reporter.update(BatteryStatus::new(49, false, 0)).is_none());
+///
+/// // Crossing the low threshold is always reported
+/// let status = reporter.update(BatteryStatus::new(15, false, 0)).unwrap();
+/// assert!(status.is_low_battery());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatteryReporter {
+    /// Charge at or below which `thresholdEvent` is set
+    low_threshold: i32,
+
+    /// Minimum charge change before a new report is sent
+    min_delta: i32,
+
+    /// Last status sent to the remote device
+    last_sent: Option<BatteryStatus>,
+}
+
+impl BatteryReporter {
+    /// Create a reporter with the given low threshold and debounce delta
+    pub fn new(low_threshold: i32, min_delta: i32) -> Self {
+        Self {
+            low_threshold,
+            min_delta,
+            last_sent: None,
+        }
+    }
+
+    /// Last status sent to the remote device
+    pub fn last_sent(&self) -> Option<&BatteryStatus> {
+        self.last_sent.as_ref()
+    }
+
+    /// Fill in the threshold event for a local reading
+    fn with_threshold(&self, mut status: BatteryStatus) -> BatteryStatus {
+        status.threshold_event = i32::from(
+            status.has_battery()
+                && !status.is_charging
+                && status.current_charge <= self.low_threshold,
+        );
+        status
+    }
+
+    /// Return the status to send for a new reading, or `None` if debounced
+    pub fn update(&mut self, status: BatteryStatus) -> Option<BatteryStatus> {
+        let status = self.with_threshold(status);
+
+        let changed = match &self.last_sent {
+            None => true,
+            Some(last) => {
+                last.is_charging != status.is_charging
+                    || last.threshold_event != status.threshold_event
+                    || last.has_battery() != status.has_battery()
+                    || (status.current_charge - last.current_charge).abs() >= self.min_delta
+            }
+        };
+
+        if !changed {
+            return None;
+        }
+
+        self.last_sent = Some(status.clone());
+        Some(status)
+    }
+
+    /// Return the status to send regardless of debouncing
+    ///
+    /// Used when the remote device explicitly requests our battery status.
+    pub fn force(&mut self, status: BatteryStatus) -> BatteryStatus {
+        let status = self.with_threshold(status);
+        self.last_sent = Some(status.clone());
+        status
+    }
+}
+
+impl Default for BatteryReporter {
+    fn default() -> Self {
+        Self::new(LOW_BATTERY_THRESHOLD, CHARGE_REPORT_DELTA)
+    }
 }
 
 /// Battery plugin for power status monitoring
@@ -217,6 +348,7 @@ impl BatteryStatus {
 ///
 /// - Receive battery status from remote devices
 /// - Store latest battery status
+/// - Report the local battery (read through UPower) on change
 /// - Respond to battery requests (deprecated protocol)
 /// - Create battery status packets
 ///
@@ -239,6 +371,18 @@ pub struct BatteryPlugin {
 
     /// Latest battery status from remote device
     battery_status: Arc<RwLock<Option<BatteryStatus>>>,
+
+    /// Packet sender for outgoing battery reports
+    packet_sender: Option<Sender<(String, Packet)>>,
+
+    /// Debounce state for outgoing reports, shared with the poll task
+    reporter: Arc<Mutex<BatteryReporter>>,
+
+    /// UPower backend for reading the local battery on request
+    upower: UPowerBackend,
+
+    /// Background task polling the local battery
+    poll_handle: Option<JoinHandle<()>>,
 }
 
 impl BatteryPlugin {
@@ -256,6 +400,10 @@ impl BatteryPlugin {
         Self {
             device_id: None,
             battery_status: Arc::new(RwLock::new(None)),
+            packet_sender: None,
+            reporter: Arc::new(Mutex::new(BatteryReporter::default())),
+            upower: UPowerBackend::new(),
+            poll_handle: None,
         }
     }
 
@@ -332,6 +480,98 @@ impl BatteryPlugin {
         Packet::new("cconnect.battery.request", body)
     }
 
+    /// Read the local battery through UPower
+    async fn read_local_battery(upower: &mut UPowerBackend) -> Option<BatteryStatus> {
+        match upower.get_power_status().await {
+            Ok(power) => Some(BatteryStatus::from_power_status(&power)),
+            Err(e) => {
+                debug!("Failed to read local battery: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Send the local battery status to the connected device
+    ///
+    /// With `force` unset the report is debounced against the last status
+    /// sent. Returns `true` if a packet was sent.
+    pub async fn send_local_battery(&mut self, force: bool) -> bool {
+        let (Some(device_id), Some(sender)) = (self.device_id.clone(), self.packet_sender.clone())
+        else {
+            warn!("Cannot send battery status - plugin not initialized");
+            return false;
+        };
+
+        let Some(reading) = Self::read_local_battery(&mut self.upower).await else {
+            return false;
+        };
+
+        let status = {
+            let Ok(mut reporter) = self.reporter.lock() else {
+                return false;
+            };
+            if force {
+                Some(reporter.force(reading))
+            } else {
+                reporter.update(reading)
+            }
+        };
+
+        let Some(status) = status else {
+            debug!("Local battery change below report delta, skipping send");
+            return false;
+        };
+
+        let packet = self.create_battery_packet(&status);
+        if let Err(e) = sender.send((device_id, packet)).await {
+            warn!("Failed to send battery status: {}", e);
+            return false;
+        }
+
+        debug!("Sent local battery status: {:?}", status);
+        true
+    }
+
+    /// Spawn the task that polls the local battery and reports changes
+    fn spawn_poll_task(&mut self) {
+        let (Some(device_id), Some(sender)) = (self.device_id.clone(), self.packet_sender.clone())
+        else {
+            return;
+        };
+        let reporter = Arc::clone(&self.reporter);
+
+        let handle = tokio::spawn(async move {
+            let mut upower = UPowerBackend::new();
+            let mut interval = tokio::time::interval(BATTERY_POLL_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                let Some(reading) = Self::read_local_battery(&mut upower).await else {
+                    continue;
+                };
+                let status = match reporter.lock() {
+                    Ok(mut reporter) => reporter.update(reading),
+                    Err(_) => break,
+                };
+                let Some(status) = status else {
+                    continue;
+                };
+
+                let packet = Packet::new(
+                    "cconnect.battery",
+                    serde_json::to_value(&status).unwrap_or_default(),
+                );
+                if sender.send((device_id.clone(), packet)).await.is_err() {
+                    debug!("Packet channel closed, stopping battery poll task");
+                    break;
+                }
+            }
+        });
+
+        self.poll_handle = Some(handle);
+    }
+
     /// Handle incoming battery status packet
     fn handle_battery_status(&self, packet: &Packet, device: &Device) {
         match serde_json::from_value::<BatteryStatus>(packet.body.clone()) {
@@ -379,15 +619,15 @@ impl BatteryPlugin {
     }
 
     /// Handle incoming battery request packet
-    fn handle_battery_request(&self, _packet: &Packet, device: &Device) {
+    ///
+    /// Replies with the current local battery status, bypassing debouncing.
+    async fn handle_battery_request(&mut self, _packet: &Packet, device: &Device) {
         info!(
             "Received battery request from {} ({})",
             device.name(),
             device.id()
         );
-        // Note: In a full implementation, this would trigger sending our battery status
-        // For now, just log the request
-        debug!("Battery request handling (deprecated protocol feature)");
+        self.send_local_battery(true).await;
     }
 }
 
@@ -430,19 +670,27 @@ impl Plugin for BatteryPlugin {
     async fn init(
         &mut self,
         device: &Device,
-        _packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
+        packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
     ) -> Result<()> {
         self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
         info!("Battery plugin initialized for device {}", device.name());
         Ok(())
     }
 
     async fn start(&mut self) -> Result<()> {
+        if self.poll_handle.is_none() {
+            self.spawn_poll_task();
+        }
         info!("Battery plugin started");
         Ok(())
     }
 
     async fn stop(&mut self) -> Result<()> {
+        if let Some(handle) = self.poll_handle.take() {
+            handle.abort();
+            debug!("Aborted battery poll task");
+        }
         info!("Battery plugin stopped");
         Ok(())
     }
@@ -453,7 +701,7 @@ impl Plugin for BatteryPlugin {
         } else if packet.is_type("cconnect.battery.request")
             || packet.is_type("kdeconnect.battery.request")
         {
-            self.handle_battery_request(packet, device);
+            self.handle_battery_request(packet, device).await;
         }
         Ok(())
     }
@@ -712,4 +960,88 @@ mod tests {
         assert!(!status.is_charging);
         assert!(status.is_low_battery());
     }
+
+    #[test]
+    fn test_reporter_first_reading_emits() {
+        let mut reporter = BatteryReporter::default();
+        let status = reporter.update(BatteryStatus::new(80, false, 0)).unwrap();
+        assert_eq!(status.current_charge, 80);
+        assert_eq!(status.threshold_event, 0);
+        assert_eq!(reporter.last_sent(), Some(&status));
+    }
+
+    #[test]
+    fn test_reporter_debounces_small_changes() {
+        let mut reporter = BatteryReporter::new(15, 2);
+        assert!(reporter.update(BatteryStatus::new(60, false, 0)).is_some());
+
+        // Below the debounce delta
+        assert!(reporter.update(BatteryStatus::new(59, false, 0)).is_none());
+        assert!(reporter.update(BatteryStatus::new(61, false, 0)).is_none());
+        assert_eq!(reporter.last_sent().unwrap().current_charge, 60);
+
+        // Drift accumulates against the last sent value
+        let status = reporter.update(BatteryStatus::new(58, false, 0)).unwrap();
+        assert_eq!(status.current_charge, 58);
+    }
+
+    #[test]
+    fn test_reporter_charging_change_emits() {
+        let mut reporter = BatteryReporter::new(15, 2);
+        assert!(reporter.update(BatteryStatus::new(60, false, 0)).is_some());
+
+        let status = reporter.update(BatteryStatus::new(60, true, 0)).unwrap();
+        assert!(status.is_charging);
+    }
+
+    #[test]
+    fn test_reporter_low_threshold_sets_threshold_event() {
+        let mut reporter = BatteryReporter::new(15, 5);
+        assert!(reporter.update(BatteryStatus::new(17, false, 0)).is_some());
+
+        // Crossing the threshold emits even though the delta is small
+        let status = reporter.update(BatteryStatus::new(15, false, 0)).unwrap();
+        assert_eq!(status.threshold_event, 1);
+        assert!(status.is_low_battery());
+
+        // Plugging in clears the threshold event
+        let status = reporter.update(BatteryStatus::new(15, true, 0)).unwrap();
+        assert_eq!(status.threshold_event, 0);
+    }
+
+    #[test]
+    fn test_reporter_no_battery_has_no_threshold_event() {
+        let mut reporter = BatteryReporter::default();
+        let status = reporter.update(BatteryStatus::no_battery()).unwrap();
+        assert!(!status.has_battery());
+        assert_eq!(status.threshold_event, 0);
+        assert!(reporter.update(BatteryStatus::no_battery()).is_none());
+    }
+
+    #[test]
+    fn test_reporter_force_bypasses_debounce() {
+        let mut reporter = BatteryReporter::new(15, 2);
+        assert!(reporter.update(BatteryStatus::new(60, false, 0)).is_some());
+
+        let status = reporter.force(BatteryStatus::new(60, false, 0));
+        assert_eq!(status.current_charge, 60);
+    }
+
+    #[test]
+    fn test_status_from_power_status() {
+        use super::super::upower_backend::BatteryState;
+
+        let power = PowerStatus {
+            battery_present: true,
+            battery_percentage: Some(42.6),
+            battery_state: BatteryState::Charging,
+            ..Default::default()
+        };
+        let status = BatteryStatus::from_power_status(&power);
+        assert_eq!(status.current_charge, 43);
+        assert!(status.is_charging);
+
+        let status = BatteryStatus::from_power_status(&PowerStatus::default());
+        assert!(!status.has_battery());
+    }
 }
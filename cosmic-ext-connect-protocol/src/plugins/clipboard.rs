@@ -21,11 +21,16 @@
 //!     "id": 1234567890,
 //!     "type": "cconnect.clipboard",
 //!     "body": {
-//!         "content": "some text"
+//!         "content": "some text",
+//!         "timestamp": 1640000000000,
+//!         "hash": "b94d27b9934d3e08..."
 //!     }
 //! }
 //! ```
 //!
+//! `timestamp` and `hash` (SHA-256 of the content, hex) are optional; updates
+//! from peers that omit them are applied unless they repeat the local content.
+//!
 //! ## Connection Sync
 //!
 //! Initial clipboard sync sent when devices connect, includes timestamp:
@@ -50,6 +55,33 @@
 //! 3. Incoming updates with timestamp ≤ local timestamp are **ignored**
 //! 4. Incoming updates with timestamp > local timestamp are **accepted**
 //! 5. Connect packets with timestamp `0` are ignored (no content)
+//! 6. Incoming updates whose content hash matches the local content are
+//!    echoes and are **ignored**
+//!
+//! On the sending side, [`LocalClipboardTracker`] only reports a selection
+//! when it changes. Each device's plugin tracks the content last synced with
+//! that device, and content it already holds (because it was just applied
+//! from the device, or already sent to it) is not sent to it again. Other
+//! devices still receive it.
+//!
+//! Local timestamps act as a logical clock: a new local copy is always
+//! stamped later than the current content, even if the wall clock went
+//! backwards or the current content came from a peer with a faster clock.
+//! Simultaneous copies on several devices therefore resolve to the last
+//! writer everywhere instead of overwriting each other back and forth.
+//!
+//! ## Size Limit
+//!
+//! Content larger than [`MAX_CLIPBOARD_SIZE`] bytes is neither sent nor
+//! applied. A connect packet for oversized content is sent with timestamp
+//! `0`, which peers ignore.
+//!
+//! ## PRIMARY Selection
+//!
+//! The PRIMARY selection (select/middle-click) changes whenever text is
+//! selected, so it is excluded from sync unless enabled with
+//! [`LocalClipboardTracker::new`]. Incoming content is always applied to the
+//! regular clipboard.
 //!
 //! ## System Clipboard Access
 //!
@@ -103,6 +135,8 @@ use crate::{Device, Packet, Result};
 use async_trait::async_trait;
 use chrono::Utc;
 use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::RwLock;
@@ -111,6 +145,119 @@ use tracing::{debug, info, warn};
 use super::clipboard_backend::ClipboardBackend;
 use super::{Plugin, PluginFactory};
 
+/// Maximum clipboard content size in bytes that is synced (1 MiB)
+pub const MAX_CLIPBOARD_SIZE: usize = 1024 * 1024;
+
+/// Check if clipboard content is too large to sync
+pub fn exceeds_size_limit(content: &str) -> bool {
+    content.len() > MAX_CLIPBOARD_SIZE
+}
+
+/// Hash of clipboard content used for echo detection
+///
+/// Returns the hex-encoded SHA-256 digest of the UTF-8 content.
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmic_ext_connect_protocol::plugins::clipboard::content_hash;
+///
+/// assert_eq!(content_hash("a"), content_hash("a"));
+/// assert_ne!(content_hash("a"), content_hash("b"));
+/// ```
+pub fn content_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Local selection watched for changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClipboardSelection {
+    /// The regular clipboard (copy/paste)
+    Clipboard,
+    /// The PRIMARY selection (select/middle-click)
+    Primary,
+}
+
+/// Tracks local selections to decide which changes to sync
+///
+/// Remembers the last content seen in each selection so a change is only
+/// reported once, however often the selection is polled. The PRIMARY
+/// selection is ignored unless enabled.
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmic_ext_connect_protocol::plugins::clipboard::{
+///     ClipboardSelection, LocalClipboardTracker,
+/// };
+///
+/// let mut tracker = LocalClipboardTracker::new(false);
+/// let clipboard = ClipboardSelection::Clipboard;
+///
+/// assert_eq!(tracker.observe(clipboard, "copied"), Some("copied".to_string()));
+/// assert_eq!(tracker.observe(clipboard, "copied"), None);
+/// assert_eq!(tracker.observe(ClipboardSelection::Primary, "selected"), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LocalClipboardTracker {
+    /// Whether the PRIMARY selection is synced
+    sync_primary: bool,
+
+    /// Last content seen in each selection
+    last_seen: HashMap<ClipboardSelection, String>,
+}
+
+impl LocalClipboardTracker {
+    /// Create a tracker, syncing the PRIMARY selection if `sync_primary`
+    pub fn new(sync_primary: bool) -> Self {
+        Self {
+            sync_primary,
+            last_seen: HashMap::new(),
+        }
+    }
+
+    /// Selections to watch
+    pub fn selections(&self) -> Vec<ClipboardSelection> {
+        if self.sync_primary {
+            vec![ClipboardSelection::Clipboard, ClipboardSelection::Primary]
+        } else {
+            vec![ClipboardSelection::Clipboard]
+        }
+    }
+
+    /// Record the current content of a selection
+    ///
+    /// Returns the content to sync if the selection changed since it was last
+    /// observed, is synced, and is neither empty nor larger than
+    /// [`MAX_CLIPBOARD_SIZE`].
+    pub fn observe(&mut self, selection: ClipboardSelection, content: &str) -> Option<String> {
+        if !self.selections().contains(&selection) {
+            return None;
+        }
+
+        if self.last_seen.get(&selection).map(String::as_str) == Some(content) {
+            return None;
+        }
+        self.last_seen.insert(selection, content.to_string());
+
+        if content.is_empty() {
+            return None;
+        }
+
+        if exceeds_size_limit(content) {
+            debug!(
+                "Not syncing {:?} content of {} bytes (limit {})",
+                selection,
+                content.len(),
+                MAX_CLIPBOARD_SIZE
+            );
+            return None;
+        }
+
+        Some(content.to_string())
+    }
+}
+
 /// Clipboard state with content and timestamp
 ///
 /// Tracks the current clipboard content and when it was last modified.
@@ -222,6 +369,35 @@ impl ClipboardState {
     pub fn is_newer_than(&self, other: &ClipboardState) -> bool {
         self.timestamp > other.timestamp
     }
+
+    /// Create the state for new local content replacing this one
+    ///
+    /// The timestamp is the current time, or one past this state's
+    /// timestamp if that is not later, so local updates always win over the
+    /// content they replace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::plugins::clipboard::ClipboardState;
+    ///
+    /// let future = ClipboardState::with_timestamp("remote".to_string(), i64::MAX - 1);
+    /// let next = future.successor("local".to_string());
+    /// assert!(next.is_newer_than(&future));
+    /// ```
+    pub fn successor(&self, content: String) -> Self {
+        Self {
+            content,
+            timestamp: Utc::now()
+                .timestamp_millis()
+                .max(self.timestamp.saturating_add(1)),
+        }
+    }
+
+    /// Hash of the content, see [`content_hash`]
+    pub fn hash(&self) -> String {
+        content_hash(&self.content)
+    }
 }
 
 impl Default for ClipboardState {
@@ -295,7 +471,8 @@ impl ClipboardPlugin {
     /// Create a standard clipboard update packet
     ///
     /// Creates `cconnect.clipboard` packet for syncing clipboard changes.
-    /// Does not include timestamp (standard update).
+    /// The content is stamped later than the current local content and
+    /// carries its hash so peers can ignore echoes.
     ///
     /// # Parameters
     ///
@@ -318,10 +495,17 @@ impl ClipboardPlugin {
     /// ```
     pub async fn create_clipboard_packet(&self, content: String) -> Packet {
         // Update internal state
-        let new_state = ClipboardState::new(content.clone());
-        *self.state.write().await = new_state;
+        let mut state = self.state.write().await;
+        *state = state.successor(content);
 
-        Packet::new("cconnect.clipboard", json!({ "content": content }))
+        Packet::new(
+            "cconnect.clipboard",
+            json!({
+                "content": state.content,
+                "timestamp": state.timestamp,
+                "hash": state.hash(),
+            }),
+        )
     }
 
     /// Create a clipboard connect packet
@@ -346,6 +530,15 @@ impl ClipboardPlugin {
     /// ```
     pub async fn create_connect_packet(&self) -> Packet {
         let state = self.state.read().await;
+        if exceeds_size_limit(&state.content) {
+            return Packet::new(
+                "cconnect.clipboard.connect",
+                json!({
+                    "content": "",
+                    "timestamp": 0
+                }),
+            );
+        }
         Packet::new(
             "cconnect.clipboard.connect",
             json!({
@@ -372,6 +565,14 @@ impl ClipboardPlugin {
         self.state.read().await.content.clone()
     }
 
+    /// Check if the clipboard state holds `content`
+    ///
+    /// True for content just applied from the device or already sent to it,
+    /// which must not be sent (back) again.
+    pub async fn holds(&self, content: &str) -> bool {
+        self.state.read().await.content == content
+    }
+
     /// Get current clipboard timestamp
     ///
     /// # Example
@@ -408,7 +609,7 @@ impl ClipboardPlugin {
 
     /// Update clipboard content
     ///
-    /// Sets new clipboard content with current timestamp.
+    /// Sets new clipboard content, stamped later than the current content.
     ///
     /// # Example
     ///
@@ -421,7 +622,8 @@ impl ClipboardPlugin {
     /// # }
     /// ```
     pub async fn set_content(&self, content: String) {
-        *self.state.write().await = ClipboardState::new(content);
+        let mut state = self.state.write().await;
+        *state = state.successor(content);
     }
 
     /// Update clipboard with specific timestamp
@@ -444,8 +646,9 @@ impl ClipboardPlugin {
 
     /// Handle incoming clipboard update packet
     ///
-    /// Processes standard clipboard updates (without timestamp).
-    /// Always applies the update since standard packets don't include timestamp.
+    /// Processes standard clipboard updates. Echoes of the local content are
+    /// ignored, as are updates with a timestamp not newer than the local
+    /// content. Updates without a timestamp are applied.
     /// Writes the content to the system clipboard.
     async fn handle_clipboard_update(&mut self, packet: &Packet, device: &Device) {
         let content = packet
@@ -463,6 +666,47 @@ impl ClipboardPlugin {
             return;
         }
 
+        if exceeds_size_limit(content) {
+            warn!(
+                "Ignoring clipboard update from {} ({}) - {} bytes exceeds limit of {}",
+                device.name(),
+                device.id(),
+                content.len(),
+                MAX_CLIPBOARD_SIZE
+            );
+            return;
+        }
+
+        let timestamp = packet.body.get("timestamp").and_then(|v| v.as_i64());
+        let hash = match packet.body.get("hash").and_then(|v| v.as_str()) {
+            Some(hash) => hash.to_string(),
+            None => content_hash(content),
+        };
+
+        let current_state = self.state.read().await.clone();
+
+        if !current_state.is_empty() && hash == current_state.hash() {
+            debug!(
+                "Ignoring clipboard echo from {} ({})",
+                device.name(),
+                device.id()
+            );
+            return;
+        }
+
+        if let Some(timestamp) = timestamp {
+            if timestamp <= current_state.timestamp {
+                debug!(
+                    "Ignoring clipboard update from {} ({}) - timestamp {} <= local {}",
+                    device.name(),
+                    device.id(),
+                    timestamp,
+                    current_state.timestamp
+                );
+                return;
+            }
+        }
+
         info!(
             "Received clipboard update from {} ({}): {} chars",
             device.name(),
@@ -471,7 +715,13 @@ impl ClipboardPlugin {
         );
 
         // Update internal state
-        self.set_content(content.to_string()).await;
+        match timestamp {
+            Some(timestamp) => {
+                self.set_content_with_timestamp(content.to_string(), timestamp)
+                    .await
+            }
+            None => self.set_content(content.to_string()).await,
+        }
 
         // Write to system clipboard
         if !self.backend.write(content).await {
@@ -516,6 +766,17 @@ impl ClipboardPlugin {
             return;
         }
 
+        if exceeds_size_limit(content) {
+            warn!(
+                "Ignoring connect packet from {} ({}) - {} bytes exceeds limit of {}",
+                device.name(),
+                device.id(),
+                content.len(),
+                MAX_CLIPBOARD_SIZE
+            );
+            return;
+        }
+
         let current_state = self.state.read().await.clone();
 
         // Only apply if incoming timestamp is newer
@@ -593,6 +854,14 @@ impl ClipboardPlugin {
             return false;
         }
 
+        if exceeds_size_limit(&content) {
+            debug!(
+                "Clipboard content of {} bytes too large to send",
+                content.len()
+            );
+            return false;
+        }
+
         // Create and send packet
         let packet = self.create_clipboard_packet(content).await;
         if let Err(e) = packet_sender.send((device_id, packet)).await {
@@ -828,10 +1097,37 @@ mod tests {
             packet.body.get("content").and_then(|v| v.as_str()),
             Some("Test content")
         );
+        assert_eq!(
+            packet.body.get("hash").and_then(|v| v.as_str()),
+            Some(content_hash("Test content").as_str())
+        );
 
         // Check internal state updated
-        let content = plugin.get_content().await;
-        assert_eq!(content, "Test content");
+        let state = plugin.get_state().await;
+        assert_eq!(state.content, "Test content");
+        assert_eq!(
+            packet.body.get("timestamp").and_then(|v| v.as_i64()),
+            Some(state.timestamp)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_update_stamped_after_current() {
+        let plugin = ClipboardPlugin::new();
+
+        // Content from a peer whose clock is ahead of ours
+        let ahead = Utc::now().timestamp_millis() + 60_000;
+        plugin
+            .set_content_with_timestamp("Remote".to_string(), ahead)
+            .await;
+
+        let packet = plugin.create_clipboard_packet("Local".to_string()).await;
+        let timestamp = packet.body.get("timestamp").and_then(|v| v.as_i64());
+        assert_eq!(timestamp, Some(ahead + 1));
+
+        let packet = plugin.create_clipboard_packet("Local 2".to_string()).await;
+        let next = packet.body.get("timestamp").and_then(|v| v.as_i64());
+        assert!(next > timestamp);
     }
 
     #[tokio::test]
@@ -1060,6 +1356,74 @@ mod tests {
         assert_eq!(content, "Second update");
     }
 
+    #[tokio::test]
+    async fn test_handle_clipboard_update_older_ignored() {
+        let mut plugin = ClipboardPlugin::new();
+        let device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+        plugin.start().await.unwrap();
+
+        plugin
+            .set_content_with_timestamp("Local content".to_string(), 2000)
+            .await;
+
+        let mut device = create_test_device();
+
+        // Older concurrent copy from the peer loses
+        let packet = Packet::new(
+            "cconnect.clipboard",
+            json!({ "content": "Older content", "timestamp": 1000i64 }),
+        );
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let state = plugin.get_state().await;
+        assert_eq!(state.content, "Local content");
+        assert_eq!(state.timestamp, 2000);
+
+        // Newer copy wins and keeps the peer's timestamp
+        let packet = Packet::new(
+            "cconnect.clipboard",
+            json!({ "content": "Newer content", "timestamp": 3000i64 }),
+        );
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let state = plugin.get_state().await;
+        assert_eq!(state.content, "Newer content");
+        assert_eq!(state.timestamp, 3000);
+    }
+
+    #[tokio::test]
+    async fn test_handle_clipboard_echo_ignored() {
+        let mut plugin = ClipboardPlugin::new();
+        let device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+        plugin.start().await.unwrap();
+
+        plugin
+            .set_content_with_timestamp("Shared".to_string(), 2000)
+            .await;
+
+        // The peer sends our own content back with a later timestamp
+        let mut device = create_test_device();
+        let packet = Packet::new(
+            "cconnect.clipboard",
+            json!({
+                "content": "Shared",
+                "timestamp": 5000i64,
+                "hash": content_hash("Shared"),
+            }),
+        );
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        assert_eq!(plugin.get_timestamp().await, 2000);
+    }
+
     #[tokio::test]
     async fn test_sync_loop_prevention() {
         let mut plugin = ClipboardPlugin::new();
@@ -1093,4 +1457,146 @@ mod tests {
         assert_eq!(state.content, "Current");
         assert_eq!(state.timestamp, 2000);
     }
+
+    #[test]
+    fn test_tracker_reports_changes_once() {
+        let mut tracker = LocalClipboardTracker::new(false);
+        let clipboard = ClipboardSelection::Clipboard;
+
+        assert_eq!(tracker.observe(clipboard, "one"), Some("one".to_string()));
+        assert_eq!(tracker.observe(clipboard, "one"), None);
+        assert_eq!(tracker.observe(clipboard, "two"), Some("two".to_string()));
+
+        // Copying the earlier text again is a change
+        assert_eq!(tracker.observe(clipboard, "one"), Some("one".to_string()));
+
+        // Clearing the clipboard isn't synced, but the next copy is
+        assert_eq!(tracker.observe(clipboard, ""), None);
+        assert_eq!(tracker.observe(clipboard, "one"), Some("one".to_string()));
+    }
+
+    #[test]
+    fn test_tracker_primary_selection() {
+        let mut excluded = LocalClipboardTracker::new(false);
+        assert_eq!(excluded.selections(), vec![ClipboardSelection::Clipboard]);
+        assert_eq!(
+            excluded.observe(ClipboardSelection::Primary, "selected"),
+            None
+        );
+
+        let mut included = LocalClipboardTracker::new(true);
+        assert!(included.selections().contains(&ClipboardSelection::Primary));
+        assert_eq!(
+            included.observe(ClipboardSelection::Primary, "selected"),
+            Some("selected".to_string())
+        );
+
+        // Selections are tracked separately
+        assert_eq!(
+            included.observe(ClipboardSelection::Clipboard, "selected"),
+            Some("selected".to_string())
+        );
+        assert_eq!(
+            included.observe(ClipboardSelection::Primary, "selected"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tracker_size_limit() {
+        let mut tracker = LocalClipboardTracker::new(false);
+        let large = "x".repeat(MAX_CLIPBOARD_SIZE + 1);
+
+        assert_eq!(tracker.observe(ClipboardSelection::Clipboard, &large), None);
+        assert_eq!(tracker.observe(ClipboardSelection::Clipboard, &large), None);
+
+        let limit = "x".repeat(MAX_CLIPBOARD_SIZE);
+        assert!(tracker
+            .observe(ClipboardSelection::Clipboard, &limit)
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_applied_update_not_resent() {
+        let mut plugin = ClipboardPlugin::new();
+        let device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+        plugin.start().await.unwrap();
+
+        let mut tracker = LocalClipboardTracker::new(false);
+        let local = tracker.observe(ClipboardSelection::Clipboard, "Local");
+        assert!(!plugin.holds(local.as_deref().unwrap()).await);
+        plugin.create_clipboard_packet(local.unwrap()).await;
+
+        let mut device = create_test_device();
+        let packet = Packet::new("cconnect.clipboard", json!({ "content": "From phone" }));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        // The monitor then sees the applied content as a clipboard change,
+        // but the device already holds it so it isn't sent back
+        let changed = tracker.observe(ClipboardSelection::Clipboard, "From phone");
+        assert!(plugin.holds(changed.as_deref().unwrap()).await);
+
+        // Copying the previous content again is sent
+        let changed = tracker.observe(ClipboardSelection::Clipboard, "Local");
+        assert!(!plugin.holds(changed.as_deref().unwrap()).await);
+    }
+
+    #[tokio::test]
+    async fn test_applied_update_still_sent_to_other_devices() {
+        let mut phone = ClipboardPlugin::new();
+        let device = create_test_device();
+        phone
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+        phone.start().await.unwrap();
+        let laptop = ClipboardPlugin::new();
+
+        let mut device = create_test_device();
+        let packet = Packet::new("cconnect.clipboard", json!({ "content": "From phone" }));
+        phone.handle_packet(&packet, &mut device).await.unwrap();
+
+        // Only the device the content came from already has it
+        assert!(phone.holds("From phone").await);
+        assert!(!laptop.holds("From phone").await);
+
+        laptop
+            .create_clipboard_packet("From phone".to_string())
+            .await;
+        assert!(laptop.holds("From phone").await);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_content_not_synced() {
+        let mut plugin = ClipboardPlugin::new();
+        let device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+        plugin.start().await.unwrap();
+        plugin
+            .set_content_with_timestamp("Small".to_string(), 1000)
+            .await;
+
+        let large = "x".repeat(MAX_CLIPBOARD_SIZE + 1);
+        let mut device = create_test_device();
+        for packet_type in ["cconnect.clipboard", "cconnect.clipboard.connect"] {
+            let packet = Packet::new(
+                packet_type,
+                json!({ "content": large, "timestamp": 2000i64 }),
+            );
+            plugin.handle_packet(&packet, &mut device).await.unwrap();
+            assert_eq!(plugin.get_content().await, "Small");
+        }
+
+        plugin.set_content(large).await;
+        let packet = plugin.create_connect_packet().await;
+        assert_eq!(packet.body["content"], "");
+        assert_eq!(packet.body["timestamp"], 0);
+    }
 }
@@ -48,11 +48,13 @@
 //!     timestamp: 1234567890000,
 //!     phone_numbers: vec!["+1234567890".to_string()],
 //!     emails: vec!["john@example.com".to_string()],
+//!     photo: None,
 //! };
 //!
 //! db.upsert_contact(contact).await?;
 //! ```
 
+use super::vcard::VCard;
 use crate::{ProtocolError, Result};
 use rusqlite::{params, Connection, OptionalExtension};
 use std::path::Path;
@@ -76,6 +78,8 @@ pub struct Contact {
     pub phone_numbers: Vec<PhoneNumber>,
     /// Email addresses
     pub emails: Vec<Email>,
+    /// Photo from the vCard (not stored separately, parsed from `vcard_data`)
+    pub photo: Option<Vec<u8>>,
 }
 
 /// Phone number with optional type
@@ -337,6 +341,7 @@ impl ContactsDatabase {
                 uid: uid.to_string(),
                 device_id,
                 name,
+                photo: VCard::parse(&vcard_data).photo,
                 vcard_data,
                 timestamp,
                 phone_numbers,
@@ -520,6 +525,7 @@ mod tests {
                 address: "test@example.com".to_string(),
                 email_type: Some("HOME".to_string()),
             }],
+            photo: None,
         };
 
         assert_eq!(contact.uid, "test-123");
@@ -11,28 +11,63 @@
 //! - `cconnect.contacts.response_uids_timestamps` - Response with UID/timestamp pairs
 //! - `cconnect.contacts.response_vcards` - Response with vCard data
 //!
+//! ### Body Format
+//! KDE Connect puts the contacts at the top level of the body, next to a
+//! `uids` list: `{"uids": ["1", "2"], "1": 1700000000000, "2": ...}` for
+//! timestamps and `{"uids": ["1"], "1": "BEGIN:VCARD..."}` for vCards. The
+//! older `{"uids": {"1": ...}}` and `{"vcards": {"1": ...}}` bodies are
+//! accepted as well.
+//!
 //! ### vCard Format
 //! - Standard: vCard 2.1
 //! - Extensions:
 //!   - `X-KDECONNECT-ID-DEV-[device-id]` - Device-specific contact ID
 //!   - `X-KDECONNECT-TIMESTAMP` - Last modification time (milliseconds)
 //!
+//! See [`vcard`] for the parsing rules.
+//!
+//! ## Sync
+//!
+//! On start the plugin requests all UIDs with timestamps, then requests the
+//! vCards of the contacts that are new or whose timestamp is newer than the
+//! cached one. Contacts missing from the UID list were deleted on the phone
+//! and are dropped. With a database ([`ContactsPlugin::init_database`]) the
+//! cache survives restarts, so a reconnect only fetches what changed.
+//!
+//! ## Looking Up Numbers
+//!
+//! [`ContactsPlugin::store`] returns a [`ContactStore`] handle the SMS and
+//! telephony UIs can keep to resolve numbers to contacts:
+//!
+//! ```rust,ignore
+//! let store = contacts_plugin.store();
+//! if let Some(name) = store.name_for_number("0170 1234567").await {
+//!     println!("Call from {}", name);
+//! }
+//! ```
+//!
+//! Numbers are compared by their digits, see [`phone_numbers_match`].
+//!
 //! ## References
 //! - [Valent Protocol](https://valent.andyholmes.ca/documentation/protocol.html)
 
 pub mod database;
 pub mod signals;
+pub mod vcard;
 
 use crate::plugins::{Plugin, PluginFactory};
 use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
-use database::{Contact, ContactsDatabase, Email, PhoneNumber};
+use database::{Contact, ContactsDatabase};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use signals::{ContactEvent, ContactsSignals};
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
+use vcard::VCard;
 
 // Re-export for external use
 pub use database::{Contact as ContactData, Email as EmailAddress, PhoneNumber as PhoneInfo};
@@ -94,6 +129,172 @@ pub struct VCardRequest {
     pub uids: Vec<String>,
 }
 
+/// Fewest digits two numbers must have to match on their trailing digits
+const MIN_MATCH_DIGITS: usize = 7;
+
+/// Trailing digits compared when the numbers differ in prefixes
+///
+/// Long enough to tell subscribers apart, short enough to skip country and
+/// trunk prefixes (`+44 7700 900123` and `07700 900123`).
+const SUFFIX_MATCH_DIGITS: usize = 9;
+
+/// Reduce a phone number to its digits and a leading `+`
+///
+/// Formatting is dropped, a `00` international prefix becomes `+` and
+/// anything after a pause or extension marker (`,`, `;`, `p`, `w`, `x`) is
+/// cut off.
+pub fn normalize_phone_number(number: &str) -> String {
+    let number = number.trim();
+    let number = number.strip_prefix("tel:").unwrap_or(number);
+
+    let mut normalized = String::with_capacity(number.len());
+    for c in number.chars() {
+        match c {
+            '0'..='9' => normalized.push(c),
+            '+' if normalized.is_empty() => normalized.push(c),
+            ',' | ';' | 'p' | 'P' | 'w' | 'W' | 'x' | 'X' => break,
+            _ => {}
+        }
+    }
+
+    match normalized.strip_prefix("00") {
+        Some(rest) => format!("+{}", rest),
+        None => normalized,
+    }
+}
+
+/// Whether two phone numbers belong to the same line
+///
+/// Numbers match if their digits are equal, or if they share the last
+/// [`SUFFIX_MATCH_DIGITS`] digits (at least seven) and not both carry a
+/// country code, so the same number written with and without country or
+/// trunk prefix matches.
+pub fn phone_numbers_match(a: &str, b: &str) -> bool {
+    let (a, b) = (normalize_phone_number(a), normalize_phone_number(b));
+    let (digits_a, digits_b) = (a.trim_start_matches('+'), b.trim_start_matches('+'));
+    if digits_a.is_empty() || digits_b.is_empty() {
+        return false;
+    }
+    if digits_a == digits_b {
+        return true;
+    }
+    if a.starts_with('+') && b.starts_with('+') {
+        return false;
+    }
+
+    let len = digits_a.len().min(digits_b.len());
+    if len < MIN_MATCH_DIGITS {
+        return false;
+    }
+    let len = len.min(SUFFIX_MATCH_DIGITS);
+    digits_a[digits_a.len() - len..] == digits_b[digits_b.len() - len..]
+}
+
+/// Shared view of a device's synced contacts, keyed by UID
+///
+/// Cheap to clone; all clones see the plugin's updates.
+#[derive(Debug, Clone, Default)]
+pub struct ContactStore {
+    contacts: Arc<RwLock<HashMap<String, Contact>>>,
+}
+
+impl ContactStore {
+    /// All contacts, sorted by name
+    pub async fn all(&self) -> Vec<Contact> {
+        let mut contacts: Vec<Contact> = self.contacts.read().await.values().cloned().collect();
+        contacts.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.uid.cmp(&b.uid)));
+        contacts
+    }
+
+    /// Contact with the given UID
+    pub async fn get(&self, uid: &str) -> Option<Contact> {
+        self.contacts.read().await.get(uid).cloned()
+    }
+
+    /// Contact owning a phone number, preferring an exact match
+    pub async fn find_by_number(&self, number: &str) -> Option<Contact> {
+        let normalized = normalize_phone_number(number);
+        let contacts = self.contacts.read().await;
+
+        let has_number = |contact: &&Contact, exact: bool| {
+            contact.phone_numbers.iter().any(|phone| {
+                if exact {
+                    normalize_phone_number(&phone.number) == normalized
+                } else {
+                    phone_numbers_match(&phone.number, number)
+                }
+            })
+        };
+        contacts
+            .values()
+            .find(|contact| has_number(contact, true))
+            .or_else(|| contacts.values().find(|contact| has_number(contact, false)))
+            .cloned()
+    }
+
+    /// Name of the contact owning a phone number
+    pub async fn name_for_number(&self, number: &str) -> Option<String> {
+        self.find_by_number(number)
+            .await
+            .and_then(|contact| contact.name)
+    }
+
+    /// Number of contacts
+    pub async fn len(&self) -> usize {
+        self.contacts.read().await.len()
+    }
+
+    /// Whether no contacts are synced
+    pub async fn is_empty(&self) -> bool {
+        self.contacts.read().await.is_empty()
+    }
+
+    async fn insert(&self, contact: Contact) -> Option<Contact> {
+        self.contacts
+            .write()
+            .await
+            .insert(contact.uid.clone(), contact)
+    }
+
+    async fn remove(&self, uid: &str) -> Option<Contact> {
+        self.contacts.write().await.remove(uid)
+    }
+
+    async fn clear(&self) {
+        self.contacts.write().await.clear();
+    }
+
+    async fn timestamps(&self) -> HashMap<String, i64> {
+        self.contacts
+            .read()
+            .await
+            .iter()
+            .map(|(uid, contact)| (uid.clone(), contact.timestamp))
+            .collect()
+    }
+}
+
+/// Read `uid -> value` pairs from a response body
+///
+/// Supports the KDE Connect layout (a `uids` list plus one top-level key per
+/// UID) and the older layout with everything nested under `nested_key`.
+fn response_entries<'a>(
+    body: &'a serde_json::Value,
+    nested_key: &str,
+) -> Option<Vec<(String, &'a serde_json::Value)>> {
+    if let Some(nested) = body.get(nested_key).and_then(|v| v.as_object()) {
+        return Some(nested.iter().map(|(uid, v)| (uid.clone(), v)).collect());
+    }
+
+    let uids = body.get("uids")?.as_array()?;
+    Some(
+        uids.iter()
+            .filter_map(|uid| uid.as_str())
+            .filter_map(|uid| body.get(uid).map(|v| (uid.to_string(), v)))
+            .collect(),
+    )
+}
+
 /// Contacts plugin for synchronizing contacts from mobile device
 pub struct ContactsPlugin {
     /// Device ID this plugin is associated with
@@ -105,6 +306,9 @@ pub struct ContactsPlugin {
     /// Cache of vCard data
     vcards_cache: HashMap<String, String>,
 
+    /// Parsed contacts, shared with lookups from the UIs
+    store: ContactStore,
+
     /// Database for persistent storage (optional - requires rusqlite)
     /// For now, this is None (stub implementation)
     database: Option<ContactsDatabase>,
@@ -123,47 +327,38 @@ impl ContactsPlugin {
             device_id: None,
             contacts_cache: HashMap::new(),
             vcards_cache: HashMap::new(),
+            store: ContactStore::default(),
             database: None,
             signals: None,
             packet_sender: None,
         }
     }
 
-    /// Parse TYPE parameter from vCard property line
-    ///
-    /// Handles formats like:
-    /// - `TEL;TYPE=CELL:+1234567890` -> Some("CELL")
-    /// - `TEL;TYPE=HOME,VOICE:+1234567890` -> Some("HOME")
-    /// - `EMAIL;TYPE=WORK:test@example.com` -> Some("WORK")
-    /// - `TEL:+1234567890` -> None
-    fn parse_vcard_type_param(line: &str) -> Option<String> {
-        // Split line into property name/params and value
-        let property_part = line.split(':').next()?;
-
-        // Look for TYPE= in the parameters
-        for param in property_part.split(';').skip(1) {
-            let param_upper = param.to_uppercase();
-            if param_upper.starts_with("TYPE=") {
-                // Extract the type value, handling comma-separated values
-                let type_value = &param[5..]; // Skip "TYPE="
-                                              // Take first value if comma-separated (e.g., "HOME,VOICE" -> "HOME")
-                let first_type = type_value.split(',').next()?;
-                return Some(first_type.to_uppercase());
-            }
-        }
-
-        None
-    }
-
     /// Initialize database storage
     ///
-    /// Call this to enable persistent storage for contacts.
-    /// Requires rusqlite dependency to be added.
+    /// Call this to enable persistent storage for contacts. Contacts stored
+    /// by an earlier session are loaded, so the next sync only fetches the
+    /// ones that changed since.
     pub async fn init_database(&mut self, db_path: &str) -> Result<()> {
         info!("Initializing contacts database at: {}", db_path);
 
         match ContactsDatabase::new(db_path).await {
             Ok(db) => {
+                if let Some(device_id) = &self.device_id {
+                    match db.get_contacts_by_device(device_id).await {
+                        Ok(contacts) => {
+                            debug!("Loaded {} stored contacts", contacts.len());
+                            for contact in contacts {
+                                self.contacts_cache
+                                    .insert(contact.uid.clone(), contact.timestamp);
+                                self.vcards_cache
+                                    .insert(contact.uid.clone(), contact.vcard_data.clone());
+                                self.store.insert(contact).await;
+                            }
+                        }
+                        Err(e) => warn!("Failed to load stored contacts: {}", e),
+                    }
+                }
                 self.database = Some(db);
                 info!("Contacts database initialized successfully");
                 Ok(())
@@ -218,120 +413,128 @@ impl ContactsPlugin {
     }
 
     /// Handle response with contact UIDs and timestamps
+    ///
+    /// Requests the vCards of new and changed contacts and drops the ones
+    /// the device no longer reports.
     async fn handle_uids_timestamps_response(&mut self, packet: &Packet) -> Result<()> {
         debug!("Processing UIDs/timestamps response");
 
-        // The body contains UID -> timestamp mappings
-        if let Some(uids_obj) = packet.body.get("uids").and_then(|v| v.as_object()) {
-            let mut updated_count = 0;
-            let mut new_count = 0;
-
-            for (uid, timestamp_value) in uids_obj {
-                if let Some(timestamp) = timestamp_value.as_i64() {
-                    // Check if this is a new contact or an update
-                    match self.contacts_cache.get(uid) {
-                        Some(&cached_timestamp) if cached_timestamp < timestamp => {
-                            debug!(
-                                "Contact {} updated: {} -> {}",
-                                uid, cached_timestamp, timestamp
-                            );
-                            self.contacts_cache.insert(uid.clone(), timestamp);
-                            updated_count += 1;
-                        }
-                        None => {
-                            debug!("New contact: {}", uid);
-                            self.contacts_cache.insert(uid.clone(), timestamp);
-                            new_count += 1;
-                        }
-                        _ => {
-                            // Contact unchanged
-                        }
-                    }
+        let Some(entries) = response_entries(&packet.body, "uids") else {
+            warn!("Invalid UIDs/timestamps response format");
+            return Err(ProtocolError::Plugin(
+                "Invalid response format for UIDs/timestamps".to_string(),
+            ));
+        };
+        let reported: HashMap<String, i64> = entries
+            .into_iter()
+            .filter_map(|(uid, timestamp)| Some((uid, timestamp.as_i64()?)))
+            .collect();
+
+        let fetched = self.store.timestamps().await;
+        let mut changed = Vec::new();
+        let mut new_count = 0;
+        for (uid, &timestamp) in &reported {
+            match fetched.get(uid) {
+                None => {
+                    debug!("New contact: {}", uid);
+                    changed.push(uid.clone());
+                    new_count += 1;
+                }
+                Some(&cached_timestamp) if cached_timestamp < timestamp => {
+                    debug!(
+                        "Contact {} updated: {} -> {}",
+                        uid, cached_timestamp, timestamp
+                    );
+                    changed.push(uid.clone());
+                }
+                Some(_) => {
+                    // Contact unchanged
                 }
             }
+        }
 
-            info!(
-                "Contacts sync: {} new, {} updated, {} total",
-                new_count,
-                updated_count,
-                self.contacts_cache.len()
-            );
+        let deleted: BTreeSet<String> = fetched
+            .keys()
+            .chain(self.contacts_cache.keys())
+            .filter(|uid| !reported.contains_key(*uid))
+            .cloned()
+            .collect();
+        for uid in &deleted {
+            self.remove_contact(uid).await;
+        }
 
-            Ok(())
-        } else {
-            warn!("Invalid UIDs/timestamps response format");
-            Err(ProtocolError::Plugin(
-                "Invalid response format for UIDs/timestamps".to_string(),
-            ))
+        info!(
+            "Contacts sync: {} new, {} updated, {} deleted, {} total",
+            new_count,
+            changed.len() - new_count,
+            deleted.len(),
+            reported.len()
+        );
+        self.contacts_cache = reported;
+
+        if !changed.is_empty() {
+            changed.sort();
+            let request = self.create_request_vcards_by_uid(changed);
+            self.send_packet(request, "vCards request").await;
         }
+
+        Ok(())
     }
 
     /// Handle response with vCard data
     async fn handle_vcards_response(&mut self, packet: &Packet) -> Result<()> {
         debug!("Processing vCards response");
 
-        if let Some(vcards_obj) = packet.body.get("vcards").and_then(|v| v.as_object()) {
-            let mut processed_count = 0;
-
-            for (uid, vcard_value) in vcards_obj {
-                if let Some(vcard_str) = vcard_value.as_str() {
-                    debug!("Received vCard for contact: {}", uid);
-                    self.vcards_cache.insert(uid.clone(), vcard_str.to_string());
-                    processed_count += 1;
-
-                    // Parse vCard to extract contact information and store/emit
-                    self.parse_and_store_vcard(uid, vcard_str).await;
+        let Some(entries) = response_entries(&packet.body, "vcards") else {
+            warn!("Invalid vCards response format");
+            return Err(ProtocolError::Plugin(
+                "Invalid response format for vCards".to_string(),
+            ));
+        };
+
+        let mut added = 0;
+        let mut updated = 0;
+        for (uid, vcard_value) in entries {
+            if let Some(vcard_str) = vcard_value.as_str() {
+                debug!("Received vCard for contact: {}", uid);
+                if self.parse_and_store_vcard(&uid, vcard_str).await {
+                    added += 1;
+                } else {
+                    updated += 1;
                 }
             }
+        }
 
-            info!("Processed {} vCards", processed_count);
-            Ok(())
-        } else {
-            warn!("Invalid vCards response format");
-            Err(ProtocolError::Plugin(
-                "Invalid response format for vCards".to_string(),
-            ))
+        info!("Processed {} vCards", added + updated);
+
+        if let (Some(signals), Some(device_id)) = (&self.signals, &self.device_id) {
+            let event = ContactEvent::SyncCompleted {
+                device_id: device_id.clone(),
+                total: self.store.len().await as u32,
+                added,
+                updated,
+            };
+            if let Err(e) = event.emit(signals).await {
+                warn!("Failed to emit contact event: {}", e);
+            }
         }
+
+        Ok(())
     }
 
     /// Parse vCard data, store to database, and emit DBus signals
-    async fn parse_and_store_vcard(&mut self, uid: &str, vcard_data: &str) {
-        // Parse basic vCard fields
-        let mut name = None;
-        let mut phone_numbers = Vec::new();
-        let mut emails = Vec::new();
-
-        for line in vcard_data.lines() {
-            let line = line.trim();
-            if let Some(fn_name) = line.strip_prefix("FN:") {
-                name = Some(fn_name.to_string());
-            } else if line.starts_with("TEL") {
-                // Parse TEL lines - format: TEL;TYPE=CELL:+1234567890 or TEL:+1234567890
-                let phone_type = Self::parse_vcard_type_param(line);
-                if let Some(number) = line.split(':').nth(1) {
-                    phone_numbers.push(PhoneNumber {
-                        number: number.to_string(),
-                        phone_type,
-                    });
-                }
-            } else if line.starts_with("EMAIL") {
-                // Parse EMAIL lines - format: EMAIL;TYPE=WORK:test@example.com or EMAIL:test@example.com
-                let email_type = Self::parse_vcard_type_param(line);
-                if let Some(email) = line.split(':').nth(1) {
-                    emails.push(Email {
-                        address: email.to_string(),
-                        email_type,
-                    });
-                }
-            }
-        }
+    ///
+    /// Returns whether the contact is new.
+    async fn parse_and_store_vcard(&mut self, uid: &str, vcard_data: &str) -> bool {
+        let card = VCard::parse(vcard_data);
 
         debug!(
-            "Parsed contact {}: name={:?}, {} phones, {} emails",
+            "Parsed contact {}: name={:?}, {} phones, {} emails, photo={}",
             uid,
-            name,
-            phone_numbers.len(),
-            emails.len()
+            card.name,
+            card.phone_numbers.len(),
+            card.emails.len(),
+            card.photo.is_some()
         );
 
         // Get device ID and timestamp
@@ -339,23 +542,31 @@ impl ContactsPlugin {
             .device_id
             .clone()
             .unwrap_or_else(|| "unknown".to_string());
-        let timestamp = self.contacts_cache.get(uid).copied().unwrap_or(0);
-
-        // Check if this is a new contact or update
-        let is_new = !self.vcards_cache.contains_key(uid);
+        let timestamp = self
+            .contacts_cache
+            .get(uid)
+            .copied()
+            .or(card.timestamp)
+            .unwrap_or(0);
+
+        let contact = Contact {
+            uid: uid.to_string(),
+            device_id: device_id.clone(),
+            name: card.name,
+            vcard_data: vcard_data.to_string(),
+            timestamp,
+            phone_numbers: card.phone_numbers,
+            emails: card.emails,
+            photo: card.photo,
+        };
+        let name = contact.name.clone();
+
+        self.vcards_cache
+            .insert(uid.to_string(), vcard_data.to_string());
+        let is_new = self.store.insert(contact.clone()).await.is_none();
 
         // Store to database if available
         if let Some(ref mut db) = self.database {
-            let contact = Contact {
-                uid: uid.to_string(),
-                device_id: device_id.clone(),
-                name: name.clone(),
-                vcard_data: vcard_data.to_string(),
-                timestamp,
-                phone_numbers,
-                emails,
-            };
-
             match db.upsert_contact(contact).await {
                 Ok(_) => {
                     debug!("Contact {} stored to database", uid);
@@ -386,6 +597,48 @@ impl ContactsPlugin {
                 warn!("Failed to emit contact event: {}", e);
             }
         }
+
+        is_new
+    }
+
+    /// Drop a contact deleted on the device
+    async fn remove_contact(&mut self, uid: &str) {
+        debug!("Contact {} deleted", uid);
+        self.contacts_cache.remove(uid);
+        self.vcards_cache.remove(uid);
+        self.store.remove(uid).await;
+
+        if let Some(ref mut db) = self.database {
+            if let Err(e) = db.delete_contact(uid).await {
+                warn!("Failed to delete contact {} from database: {}", uid, e);
+            }
+        }
+
+        if let (Some(signals), Some(device_id)) = (&self.signals, &self.device_id) {
+            let event = ContactEvent::Deleted {
+                device_id: device_id.clone(),
+                uid: uid.to_string(),
+            };
+            if let Err(e) = event.emit(signals).await {
+                warn!("Failed to emit contact event: {}", e);
+            }
+        }
+    }
+
+    /// Send a packet to the device
+    async fn send_packet(&self, packet: Packet, what: &str) {
+        if let (Some(sender), Some(device_id)) = (&self.packet_sender, &self.device_id) {
+            if let Err(e) = sender.send((device_id.clone(), packet)).await {
+                warn!("Failed to send {}: {}", what, e);
+            } else {
+                debug!("Sent {}", what);
+            }
+        }
+    }
+
+    /// Handle for looking up the synced contacts
+    pub fn store(&self) -> ContactStore {
+        self.store.clone()
     }
 
     /// Get all cached contact UIDs
@@ -404,9 +657,10 @@ impl ContactsPlugin {
     }
 
     /// Clear all cached data
-    pub fn clear_cache(&mut self) {
+    pub async fn clear_cache(&mut self) {
         self.contacts_cache.clear();
         self.vcards_cache.clear();
+        self.store.clear().await;
         info!("Cleared contacts cache");
     }
 
@@ -691,56 +945,159 @@ mod tests {
         assert_eq!(plugin.get_vcard("contact1").unwrap(), vcard_data);
     }
 
-    #[test]
-    fn test_clear_cache() {
+    #[tokio::test]
+    async fn test_clear_cache() {
         let mut plugin = create_test_plugin();
         plugin.contacts_cache.insert("test".to_string(), 123);
         plugin
             .vcards_cache
             .insert("test".to_string(), "data".to_string());
 
-        plugin.clear_cache();
+        plugin.clear_cache().await;
 
         assert_eq!(plugin.get_contact_count(), 0);
         assert!(plugin.get_vcard("test").is_none());
     }
 
     #[test]
-    fn test_parse_vcard_type_param() {
-        // Test basic TYPE= parsing
+    fn test_normalize_phone_number() {
+        assert_eq!(normalize_phone_number("+1 (555) 123-4567"), "+15551234567");
+        assert_eq!(normalize_phone_number("0044 7700 900123"), "+447700900123");
         assert_eq!(
-            ContactsPlugin::parse_vcard_type_param("TEL;TYPE=CELL:+1234567890"),
-            Some("CELL".to_string())
+            normalize_phone_number("tel:+33-1-23-45-67-89"),
+            "+33123456789"
         );
+        assert_eq!(normalize_phone_number("555-1234 ext. 12"), "5551234");
+        assert_eq!(normalize_phone_number("555 1234,,99"), "5551234");
+        assert_eq!(normalize_phone_number("Unknown"), "");
+    }
+
+    #[test]
+    fn test_phone_numbers_match() {
+        // Formatting only
+        assert!(phone_numbers_match("+1 555-123-4567", "+15551234567"));
+        // With and without country or trunk prefix
+        assert!(phone_numbers_match("+44 7700 900123", "07700 900123"));
+        assert!(phone_numbers_match("+1 555 123 4567", "555-123-4567"));
+        assert!(phone_numbers_match("0049 170 1234567", "+49 170 1234567"));
+        // Different country codes never match
+        assert!(!phone_numbers_match("+44 7700 900123", "+33 7700 900123"));
+        // Short codes must match exactly
+        assert!(phone_numbers_match("112", "112"));
+        assert!(!phone_numbers_match("22112", "112"));
+        assert!(!phone_numbers_match("", ""));
+        assert!(!phone_numbers_match("+1 555 123 4567", "+1 555 123 4568"));
+    }
 
-        // Test lowercase type
+    #[tokio::test]
+    async fn test_store_find_by_number() {
+        let mut plugin = create_test_plugin();
+        plugin
+            .parse_and_store_vcard(
+                "1",
+                "BEGIN:VCARD\nVERSION:2.1\nFN:John Doe\nTEL;CELL:+44 7700 900123\nTEL;WORK:020 7946 0000\nEND:VCARD",
+            )
+            .await;
+        plugin
+            .parse_and_store_vcard(
+                "2",
+                "BEGIN:VCARD\nVERSION:2.1\nFN:Jane Roe\nTEL;CELL:07700900123\nEND:VCARD",
+            )
+            .await;
+
+        let store = plugin.store();
+        assert_eq!(store.len().await, 2);
+
+        // Exact matches win over suffix matches
         assert_eq!(
-            ContactsPlugin::parse_vcard_type_param("TEL;TYPE=home:+1234567890"),
-            Some("HOME".to_string())
+            store.name_for_number("00447700900123").await.as_deref(),
+            Some("John Doe")
         );
-
-        // Test comma-separated types (takes first)
         assert_eq!(
-            ContactsPlugin::parse_vcard_type_param("TEL;TYPE=HOME,VOICE:+1234567890"),
-            Some("HOME".to_string())
+            store.name_for_number("07700 900123").await.as_deref(),
+            Some("Jane Roe")
         );
-
-        // Test email type
+        // Any of a contact's numbers resolves to it
         assert_eq!(
-            ContactsPlugin::parse_vcard_type_param("EMAIL;TYPE=WORK:test@example.com"),
-            Some("WORK".to_string())
+            store.name_for_number("+44 20 7946 0000").await.as_deref(),
+            Some("John Doe")
         );
+        assert!(store.find_by_number("+1 555 000 0000").await.is_none());
 
-        // Test no type parameter
+        let names: Vec<_> = store.all().await.into_iter().map(|c| c.name).collect();
         assert_eq!(
-            ContactsPlugin::parse_vcard_type_param("TEL:+1234567890"),
-            None
+            names,
+            vec![Some("Jane Roe".to_string()), Some("John Doe".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_fetches_only_changed_contacts() {
+        let mut plugin = create_test_plugin();
+        let mut device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, tx).await.unwrap();
+
+        // KDE Connect body layout
+        let uids = Packet::new(
+            "kdeconnect.contacts.response_uids_timestamps",
+            json!({ "uids": ["1", "2", "3"], "1": 100, "2": 100, "3": 100 }),
+        );
+        plugin.handle_packet(&uids, &mut device).await.unwrap();
+
+        let (_, request) = rx.try_recv().unwrap();
+        assert!(request.is_type(PACKET_TYPE_REQUEST_VCARDS_BY_UID));
+        assert_eq!(request.body["uids"], json!(["1", "2", "3"]));
+
+        let vcards = Packet::new(
+            "kdeconnect.contacts.response_vcards",
+            json!({
+                "uids": ["1", "2", "3"],
+                "1": "BEGIN:VCARD\nFN:One\nEND:VCARD",
+                "2": "BEGIN:VCARD\nFN:Two\nEND:VCARD",
+                "3": "BEGIN:VCARD\nFN:Three\nEND:VCARD",
+            }),
+        );
+        plugin.handle_packet(&vcards, &mut device).await.unwrap();
+        let store = plugin.store();
+        assert_eq!(store.len().await, 3);
+        assert_eq!(store.get("2").await.unwrap().timestamp, 100);
+
+        // 1 unchanged, 2 edited, 3 deleted, 4 added
+        let uids = Packet::new(
+            PACKET_TYPE_RESPONSE_UIDS_TIMESTAMPS,
+            json!({ "uids": ["1", "2", "4"], "1": 100, "2": 200, "4": 50 }),
+        );
+        plugin.handle_packet(&uids, &mut device).await.unwrap();
+
+        let (_, request) = rx.try_recv().unwrap();
+        assert_eq!(request.body["uids"], json!(["2", "4"]));
+        assert!(store.get("3").await.is_none());
+        assert!(plugin.get_vcard("3").is_none());
+        assert_eq!(plugin.get_contact_count(), 3);
+
+        // Contacts still waiting for their vCard are requested again
+        plugin.handle_packet(&uids, &mut device).await.unwrap();
+        let (_, request) = rx.try_recv().unwrap();
+        assert_eq!(request.body["uids"], json!(["2", "4"]));
+        let vcards = Packet::new(
+            PACKET_TYPE_RESPONSE_VCARDS,
+            json!({
+                "vcards": {
+                    "2": "BEGIN:VCARD\nFN:Two Edited\nEND:VCARD",
+                    "4": "BEGIN:VCARD\nFN:Four\nEND:VCARD",
+                }
+            }),
         );
+        plugin.handle_packet(&vcards, &mut device).await.unwrap();
+        assert_eq!(store.get("2").await.unwrap().timestamp, 200);
 
-        // Test multiple parameters with type
+        // Nothing changed, nothing requested
+        plugin.handle_packet(&uids, &mut device).await.unwrap();
+        assert!(rx.try_recv().is_err());
         assert_eq!(
-            ContactsPlugin::parse_vcard_type_param("TEL;PREF;TYPE=CELL:+1234567890"),
-            Some("CELL".to_string())
+            store.get("2").await.unwrap().name.as_deref(),
+            Some("Two Edited")
         );
     }
 }
@@ -0,0 +1,444 @@
+//! vCard Parsing
+//!
+//! Parses the vCards sent by the phone into the fields the desktop uses:
+//! name, phone numbers, email addresses, photo and the KDE Connect
+//! modification timestamp.
+//!
+//! Android sends vCard 2.1, so besides the folding and escaping of vCard 3.0
+//! and 4.0 the parser handles the 2.1 specifics:
+//! - Bare parameters as types (`TEL;CELL;PREF:...`)
+//! - `ENCODING=QUOTED-PRINTABLE` values with soft line breaks
+//! - `ENCODING=BASE64` photos spread over indented lines
+//!
+//! Property groups (`item1.TEL`) are ignored and unknown properties skipped.
+
+use super::database::{Email, PhoneNumber};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use tracing::debug;
+
+/// vCard property carrying the KDE Connect modification timestamp
+pub const TIMESTAMP_PROPERTY: &str = "X-KDECONNECT-TIMESTAMP";
+
+/// Parameters that are not the kind of a number or address
+const IGNORED_TYPES: &[&str] = &["PREF", "VOICE", "INTERNET"];
+
+/// Fields of a parsed vCard
+#[derive(Debug, Clone, Default)]
+pub struct VCard {
+    /// Formatted name (`FN`), or the structured name (`N`) if missing
+    pub name: Option<String>,
+    /// Phone numbers in the order they appear
+    pub phone_numbers: Vec<PhoneNumber>,
+    /// Email addresses in the order they appear
+    pub emails: Vec<Email>,
+    /// Decoded photo (usually JPEG)
+    pub photo: Option<Vec<u8>>,
+    /// Last modification time from `X-KDECONNECT-TIMESTAMP` (milliseconds)
+    pub timestamp: Option<i64>,
+}
+
+impl VCard {
+    /// Parse a vCard, skipping lines that can't be understood
+    pub fn parse(data: &str) -> Self {
+        let mut card = Self::default();
+        let mut structured_name = None;
+
+        for line in unfold(data) {
+            let Some(property) = Property::parse(&line) else {
+                continue;
+            };
+
+            match property.name.as_str() {
+                "FN" => {
+                    let name = unescape(&property.text());
+                    if !name.trim().is_empty() {
+                        card.name = Some(name.trim().to_string());
+                    }
+                }
+                "N" => structured_name = format_structured_name(&property.text()),
+                "TEL" => {
+                    let text = property.text();
+                    let number = text.trim().trim_start_matches("tel:");
+                    if !number.is_empty() {
+                        card.phone_numbers.push(PhoneNumber {
+                            number: number.to_string(),
+                            phone_type: property.kind(),
+                        });
+                    }
+                }
+                "EMAIL" => {
+                    let address = unescape(property.text().trim());
+                    if !address.is_empty() {
+                        card.emails.push(Email {
+                            address,
+                            email_type: property.kind(),
+                        });
+                    }
+                }
+                "PHOTO" => match property.binary() {
+                    Some(photo) => card.photo = Some(photo),
+                    None => debug!("Skipping vCard photo that is not inline base64"),
+                },
+                TIMESTAMP_PROPERTY => card.timestamp = property.text().trim().parse().ok(),
+                _ => {}
+            }
+        }
+
+        if card.name.is_none() {
+            card.name = structured_name;
+        }
+        card
+    }
+}
+
+/// One `NAME;PARAMS:VALUE` line
+#[derive(Debug)]
+struct Property {
+    /// Upper-case property name without group
+    name: String,
+    /// Parameters as upper-case `(key, value)`, bare parameters have no key
+    params: Vec<(Option<String>, String)>,
+    /// Raw value, still encoded and escaped
+    value: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> Option<Self> {
+        let colon = find_unquoted(line, ':')?;
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+        let mut parts = head.split(';');
+        let name = parts.next()?.trim();
+        let name = name.rsplit('.').next().unwrap_or(name).to_uppercase();
+        if name.is_empty() {
+            return None;
+        }
+
+        let params = parts
+            .filter(|param| !param.is_empty())
+            .map(|param| match param.split_once('=') {
+                Some((key, value)) => (
+                    Some(key.trim().to_uppercase()),
+                    value.trim().trim_matches('"').to_uppercase(),
+                ),
+                None => (None, param.trim().to_uppercase()),
+            })
+            .collect();
+
+        Some(Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k.as_deref() == Some(key))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn encoding(&self) -> Option<&str> {
+        self.param("ENCODING").or_else(|| {
+            self.params
+                .iter()
+                .find(|(key, value)| key.is_none() && is_encoding(value))
+                .map(|(_, value)| value.as_str())
+        })
+    }
+
+    /// First type (`TYPE=HOME,VOICE` or a bare `CELL`), ignoring preference markers
+    fn kind(&self) -> Option<String> {
+        self.params
+            .iter()
+            .filter(|(key, value)| match key.as_deref() {
+                Some("TYPE") => true,
+                None => !is_encoding(value),
+                _ => false,
+            })
+            .flat_map(|(_, value)| value.split(','))
+            .map(str::trim)
+            .find(|kind| !kind.is_empty() && !IGNORED_TYPES.contains(kind))
+            .map(str::to_string)
+    }
+
+    /// Value as text, decoding quoted-printable
+    fn text(&self) -> String {
+        match self.encoding() {
+            Some("QUOTED-PRINTABLE") => {
+                String::from_utf8_lossy(&decode_quoted_printable(&self.value)).into_owned()
+            }
+            _ => self.value.clone(),
+        }
+    }
+
+    /// Inline binary value (`ENCODING=BASE64`, `ENCODING=b` or a data URI)
+    fn binary(&self) -> Option<Vec<u8>> {
+        let encoded = match self.encoding() {
+            Some("BASE64" | "B") => self.value.as_str(),
+            _ => self.value.split_once(";base64,")?.1,
+        };
+        let encoded: String = encoded.split_whitespace().collect();
+        BASE64.decode(encoded).ok()
+    }
+}
+
+fn is_encoding(value: &str) -> bool {
+    matches!(value, "QUOTED-PRINTABLE" | "BASE64" | "B" | "8BIT")
+}
+
+/// Position of the first `needle` outside double quotes
+fn find_unquoted(line: &str, needle: char) -> Option<usize> {
+    let mut quoted = false;
+    line.char_indices().find_map(|(i, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        (c == needle && !quoted).then_some(i)
+    })
+}
+
+/// Join folded lines into logical lines
+///
+/// Lines starting with a space or tab continue the previous one, and so does
+/// the line after a quoted-printable value ending in a soft break (`=`).
+fn unfold(data: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut quoted_printable = false;
+    let mut soft_break = false;
+
+    for raw in data.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+
+        match lines.last_mut() {
+            Some(last) if soft_break => last.push_str(raw),
+            Some(last) if raw.starts_with([' ', '\t']) => last.push_str(&raw[1..]),
+            _ if raw.trim().is_empty() => {}
+            _ => {
+                let head = raw.split(':').next().unwrap_or_default();
+                quoted_printable = head.to_uppercase().contains("QUOTED-PRINTABLE");
+                lines.push(raw.to_string());
+            }
+        }
+
+        soft_break = false;
+        if let Some(last) = lines.last_mut().filter(|_| quoted_printable) {
+            if last.ends_with('=') {
+                last.pop();
+                soft_break = true;
+            }
+        }
+    }
+
+    lines
+}
+
+/// Decode `=XX` escapes, leaving malformed ones as they are
+fn decode_quoted_printable(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| bytes[i] == b'=' && hex.iter().all(u8::is_ascii_hexdigit));
+        if let Some(hex) = hex {
+            let hex = std::str::from_utf8(hex).unwrap_or_default();
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    decoded
+}
+
+/// Undo vCard text escaping (`\,`, `\;`, `\n` and `\\`)
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(next) => out.push(next),
+            None => out.push('\\'),
+        }
+    }
+
+    out
+}
+
+/// Display name from `N` (`Family;Given;Additional;Prefix;Suffix`)
+fn format_structured_name(value: &str) -> Option<String> {
+    let parts: Vec<String> = split_unescaped(value, ';')
+        .iter()
+        .map(|part| unescape(part).trim().to_string())
+        .collect();
+    let part = |i: usize| parts.get(i).map(String::as_str).unwrap_or("");
+
+    let name = [part(3), part(1), part(2), part(0), part(4)]
+        .into_iter()
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!name.is_empty()).then_some(name)
+}
+
+/// Split on `separator` unless it is escaped with a backslash
+fn split_unescaped(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (i, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == separator {
+            parts.push(&value[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(line: &str) -> Option<String> {
+        Property::parse(line).and_then(|p| p.kind())
+    }
+
+    #[test]
+    fn test_property_kind() {
+        assert_eq!(kind("TEL;TYPE=CELL:+1234567890"), Some("CELL".to_string()));
+        assert_eq!(kind("TEL;TYPE=home:+1234567890"), Some("HOME".to_string()));
+        assert_eq!(
+            kind("TEL;TYPE=HOME,VOICE:+1234567890"),
+            Some("HOME".to_string())
+        );
+        assert_eq!(
+            kind("EMAIL;TYPE=WORK:test@example.com"),
+            Some("WORK".to_string())
+        );
+        assert_eq!(kind("TEL:+1234567890"), None);
+        assert_eq!(
+            kind("TEL;PREF;TYPE=CELL:+1234567890"),
+            Some("CELL".to_string())
+        );
+        // vCard 2.1 bare types
+        assert_eq!(kind("TEL;CELL;PREF:+1234567890"), Some("CELL".to_string()));
+        assert_eq!(
+            kind("EMAIL;INTERNET;HOME:a@example.com"),
+            Some("HOME".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_android_vcard() {
+        let data = "BEGIN:VCARD\r\n\
+                    VERSION:2.1\r\n\
+                    N:Doe;John;;;\r\n\
+                    FN:John Doe\r\n\
+                    TEL;CELL:+1 555-123-4567\r\n\
+                    TEL;HOME:555 987 6543\r\n\
+                    item1.TEL;WORK;PREF:(555) 000-1111\r\n\
+                    EMAIL;HOME:john@example.com\r\n\
+                    X-KDECONNECT-ID-DEV-abc:42\r\n\
+                    X-KDECONNECT-TIMESTAMP:1700000000000\r\n\
+                    END:VCARD\r\n";
+
+        let card = VCard::parse(data);
+        assert_eq!(card.name.as_deref(), Some("John Doe"));
+        assert_eq!(card.timestamp, Some(1_700_000_000_000));
+
+        let numbers: Vec<_> = card
+            .phone_numbers
+            .iter()
+            .map(|p| (p.number.as_str(), p.phone_type.as_deref()))
+            .collect();
+        assert_eq!(
+            numbers,
+            vec![
+                ("+1 555-123-4567", Some("CELL")),
+                ("555 987 6543", Some("HOME")),
+                ("(555) 000-1111", Some("WORK")),
+            ]
+        );
+        assert_eq!(card.emails.len(), 1);
+        assert_eq!(card.emails[0].address, "john@example.com");
+        assert_eq!(card.emails[0].email_type.as_deref(), Some("HOME"));
+    }
+
+    #[test]
+    fn test_parse_photo() {
+        let photo = b"\xff\xd8\xff\xe0 not really a jpeg, but long enough to fold";
+        let encoded = BASE64.encode(photo);
+        let (first, rest) = encoded.split_at(20);
+        let data = format!(
+            "BEGIN:VCARD\nVERSION:2.1\nFN:Jane\nPHOTO;ENCODING=BASE64;JPEG:{}\n  {}\n\nEND:VCARD\n",
+            first, rest
+        );
+
+        let card = VCard::parse(&data);
+        assert_eq!(card.photo.as_deref(), Some(&photo[..]));
+        assert_eq!(card.name.as_deref(), Some("Jane"));
+
+        // vCard 4.0 data URI
+        let data = format!("FN:Jane\nPHOTO:data:image/jpeg;base64,{}\n", encoded);
+        assert_eq!(VCard::parse(&data).photo.as_deref(), Some(&photo[..]));
+
+        // Linked photos are skipped
+        let card = VCard::parse("PHOTO;VALUE=uri:https://example.com/jane.jpg\n");
+        assert!(card.photo.is_none());
+    }
+
+    #[test]
+    fn test_parse_quoted_printable_and_folding() {
+        let data = "BEGIN:VCARD\n\
+                    VERSION:2.1\n\
+                    FN;CHARSET=UTF-8;ENCODING=QUOTED-PRINTABLE:J=C3=BCrgen M=C3=BC=\n\
+                    ller\n\
+                    EMAIL;TYPE=WORK:juergen.mueller@exam\n ple.com\n\
+                    END:VCARD";
+
+        let card = VCard::parse(data);
+        assert_eq!(card.name.as_deref(), Some("Jürgen Müller"));
+        assert_eq!(card.emails[0].address, "juergen.mueller@example.com");
+    }
+
+    #[test]
+    fn test_name_falls_back_to_structured_name() {
+        let card = VCard::parse("BEGIN:VCARD\nN:Smith;Anna;Maria;Dr.;\nTEL:123\nEND:VCARD");
+        assert_eq!(card.name.as_deref(), Some("Dr. Anna Maria Smith"));
+
+        let card = VCard::parse("FN:Smith\\, Anna\n");
+        assert_eq!(card.name.as_deref(), Some("Smith, Anna"));
+
+        assert!(VCard::parse("BEGIN:VCARD\nTEL:123\nEND:VCARD")
+            .name
+            .is_none());
+    }
+
+    #[test]
+    fn test_tel_uri() {
+        let card = VCard::parse("TEL;VALUE=uri;TYPE=cell:tel:+33-1-23-45-67-89\n");
+        assert_eq!(card.phone_numbers[0].number, "+33-1-23-45-67-89");
+        assert_eq!(card.phone_numbers[0].phone_type.as_deref(), Some("CELL"));
+    }
+}
@@ -13,6 +13,7 @@
 //! - `cconnect.filesync.index` - File list with hashes and metadata
 //! - `cconnect.filesync.transfer` - File data transfer (via payload)
 //! - `cconnect.filesync.request` - Request file transfer
+//! - `cconnect.filesync.signature` - Request a delta against block checksums
 //! - `cconnect.filesync.conflict` - Conflict notification
 //! - `cconnect.filesync.delete` - File deletion synchronization
 //!
@@ -51,17 +52,24 @@
 //! - [x] File system monitoring (notify integration)
 //! - [x] BLAKE3 hashing for content comparison
 //! - [x] Sync logic and plan generation
-//! - [ ] File transfer implementation (upload/download)
-//! - [ ] SQLite database for sync state (history)
-//! - [ ] Delta sync algorithm (rsync-like)
-//! - [ ] File versioning system
-//! - [ ] Bandwidth limiting implementation
-
+//! - [x] File transfer implementation (upload/download)
+//! - [x] SQLite database for sync state (hash cache)
+//! - [x] Delta sync algorithm (rsync-like)
+//! - [x] File versioning system
+//! - [x] Bandwidth limiting implementation
+
+use crate::bandwidth::BandwidthLimiter;
+use crate::cpu_pool;
 use crate::payload::{PayloadClient, PayloadServer};
+use crate::plugins::filesync_delta::{
+    apply_delta, block_size_for, compute_block_signatures, compute_delta, Delta, FileSignature,
+};
+use crate::plugins::filesync_state::FileStateDb;
+use crate::plugins::filesync_versions::{FileVersion, VersionStore, VERSIONS_DIR};
 use crate::plugins::{Plugin, PluginFactory};
 use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
-use globset::{Glob, GlobSetBuilder};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
@@ -69,7 +77,7 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fs;
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc::Sender;
@@ -82,11 +90,20 @@ const INCOMING_CAPABILITY: &str = "cconnect.filesync";
 const OUTGOING_CAPABILITY: &str = "cconnect.filesync";
 
 // File sync configuration constants
-#[allow(dead_code)]
 const MAX_FILE_SIZE_MB: u64 = 1024; // 1GB max file size
+const MAX_FILE_SIZE_BYTES: u64 = MAX_FILE_SIZE_MB * 1024 * 1024;
 const DEFAULT_SCAN_INTERVAL_SECS: u64 = 60; // Scan every minute
 const DEFAULT_VERSION_KEEP: usize = 5; // Keep 5 previous versions
 
+/// Default maximum number of pending conflicts kept per device
+pub const DEFAULT_MAX_PENDING_CONFLICTS: usize = 100;
+
+/// Suffix of files still being received, kept next to their final path
+const PARTIAL_SUFFIX: &str = ".cconnect-part";
+
+/// Smallest local copy worth requesting a delta against instead of the file
+const DELTA_MIN_FILE_SIZE: u64 = 64 * 1024;
+
 /// Conflict resolution strategy
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -146,7 +163,7 @@ pub struct SyncFolder {
     #[serde(rename = "conflictStrategy", default)]
     pub conflict_strategy: ConflictStrategy,
 
-    /// Enable file versioning
+    /// Keep old versions of overwritten and deleted files in `.stversions`
     #[serde(default = "default_true")]
     pub versioning: bool,
 
@@ -175,6 +192,156 @@ fn default_scan_interval() -> u64 {
     DEFAULT_SCAN_INTERVAL_SECS
 }
 
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+/// Last modified time in milliseconds since epoch
+fn modified_millis(metadata: &fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .unwrap_or(SystemTime::now())
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+/// Unix permission bits (if applicable)
+#[cfg(unix)]
+fn unix_mode(metadata: &fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.mode() & 0o777)
+}
+
+#[cfg(not(unix))]
+fn unix_mode(_metadata: &fs::Metadata) -> Option<u32> {
+    None
+}
+
+/// Relative path within a sync folder, as sent by the peer
+///
+/// Rejects absolute paths and `..` components so the result can be joined
+/// onto the folder without escaping it.
+fn sync_relative_path(path: impl Into<PathBuf>) -> Result<PathBuf> {
+    let path = path.into();
+    let contained = !path.as_os_str().is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if contained {
+        Ok(path)
+    } else {
+        Err(ProtocolError::InvalidPacket(format!(
+            "Path {} is outside the sync folder",
+            path.display()
+        )))
+    }
+}
+
+/// Existing file at `relative` within the sync folder `root`
+///
+/// Both paths are canonicalized before the containment check, so neither
+/// `..` components nor symlinks can lead outside the folder. `None` if the
+/// file doesn't exist or lies outside.
+async fn existing_path_in_folder(root: &Path, relative: &Path) -> Option<PathBuf> {
+    let root = tokio::fs::canonicalize(root).await.ok()?;
+    let path = tokio::fs::canonicalize(root.join(relative)).await.ok()?;
+    path.starts_with(&root).then_some(path)
+}
+
+/// Temporary file an encoded delta is received into before it is applied
+fn delta_temp_path() -> PathBuf {
+    std::env::temp_dir().join(format!("cconnect-delta-{}.bin", uuid::Uuid::new_v4()))
+}
+
+/// Hidden temporary path a file is received into before it is renamed
+fn partial_path(target: &Path) -> PathBuf {
+    let mut name = std::ffi::OsString::from(".");
+    name.push(target.file_name().unwrap_or_default());
+    name.push(PARTIAL_SUFFIX);
+    target.with_file_name(name)
+}
+
+/// Flags of a compiled ignore pattern
+#[derive(Debug, Clone, Copy)]
+struct IgnoreRule {
+    negated: bool,
+    dir_only: bool,
+}
+
+/// Gitignore-style matcher for a sync folder's ignore patterns
+///
+/// Patterns are matched against the path relative to the sync root, and the
+/// last matching pattern decides:
+///
+/// - A pattern without a `/` matches at any depth; one with a `/` is anchored
+///   to the root
+/// - `*` does not cross directories, `**` does
+/// - A trailing `/` matches directories only
+/// - A leading `!` re-includes what an earlier pattern excluded
+///
+/// As in git, nothing below an ignored directory can be re-included, since
+/// the directory is not descended into.
+struct IgnoreMatcher {
+    globs: GlobSet,
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreMatcher {
+    fn new<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Result<Self> {
+        let mut builder = GlobSetBuilder::new();
+        let mut rules = Vec::new();
+
+        for raw in patterns {
+            if raw.is_empty() || raw.starts_with('#') {
+                continue;
+            }
+
+            let (negated, pattern) = match raw.strip_prefix('!') {
+                Some(pattern) => (true, pattern),
+                None => (false, raw),
+            };
+            let (dir_only, pattern) = match pattern.strip_suffix('/') {
+                Some(pattern) => (true, pattern),
+                None => (false, pattern),
+            };
+
+            let glob = match pattern.strip_prefix('/') {
+                Some(anchored) => anchored.to_string(),
+                None if pattern.contains('/') => pattern.to_string(),
+                None => format!("**/{}", pattern),
+            };
+
+            match GlobBuilder::new(&glob).literal_separator(true).build() {
+                Ok(glob) => {
+                    builder.add(glob);
+                    rules.push(IgnoreRule { negated, dir_only });
+                }
+                Err(e) => warn!("Invalid glob pattern ignored: {}: {}", raw, e),
+            }
+        }
+
+        let globs = builder
+            .build()
+            .map_err(|e| ProtocolError::Plugin(format!("Failed to build globset: {}", e)))?;
+
+        Ok(Self { globs, rules })
+    }
+
+    /// Whether a path relative to the sync root is excluded
+    fn is_ignored(&self, relative_path: &Path, is_dir: bool) -> bool {
+        self.globs
+            .matches(relative_path)
+            .into_iter()
+            .filter(|&i| is_dir || !self.rules[i].dir_only)
+            .max()
+            .is_some_and(|i| !self.rules[i].negated)
+    }
+}
+
 impl SyncFolder {
     pub fn validate(&self) -> Result<()> {
         if !self.local_path.exists() {
@@ -199,6 +366,12 @@ impl SyncFolder {
 
         Ok(())
     }
+
+    /// Where old versions of this folder's files are kept, if versioning is on
+    pub fn version_store(&self) -> Option<VersionStore> {
+        self.versioning
+            .then(|| VersionStore::new(&self.local_path, self.version_keep))
+    }
 }
 
 /// File metadata for sync index
@@ -247,6 +420,24 @@ pub struct SyncIndex {
     pub file_count: usize,
 }
 
+impl SyncIndex {
+    /// Insert or replace the entry for a file and refresh the totals
+    fn upsert_file(&mut self, file: FileMetadata) {
+        match self.files.iter_mut().find(|f| f.path == file.path) {
+            Some(existing) => *existing = file,
+            None => self.files.push(file),
+        }
+        self.file_count = self.files.len();
+        self.total_size = self
+            .files
+            .iter()
+            .filter(|f| !f.is_dir)
+            .map(|f| f.size)
+            .sum();
+        self.timestamp = now_millis();
+    }
+}
+
 /// File conflict information
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FileConflict {
@@ -316,11 +507,14 @@ pub struct FileSyncPlugin {
     sync_folders: Arc<RwLock<HashMap<String, SyncFolder>>>,
 
     /// Current sync index by folder ID
-    sync_indexes: HashMap<String, SyncIndex>,
+    sync_indexes: Arc<RwLock<HashMap<String, SyncIndex>>>,
 
-    /// Pending conflicts
+    /// Pending conflicts, oldest first, at most one per (folder, path)
     pending_conflicts: Vec<FileConflict>,
 
+    /// Maximum number of pending conflicts before the oldest are evicted
+    max_pending_conflicts: usize,
+
     /// Active transfers (folder_id -> file_path)
     active_transfers: HashMap<String, Vec<PathBuf>>,
 
@@ -335,6 +529,12 @@ pub struct FileSyncPlugin {
 
     /// Path to configuration file
     config_path: Option<PathBuf>,
+
+    /// Cached file hashes, so unchanged files are not rehashed
+    state_db: Option<FileStateDb>,
+
+    /// Upload rate limiters by folder ID, shared by the folder's transfers
+    bandwidth_limiters: Arc<RwLock<HashMap<String, BandwidthLimiter>>>,
 }
 
 impl FileSyncPlugin {
@@ -344,13 +544,16 @@ impl FileSyncPlugin {
             device_id: None,
             enabled: false,
             sync_folders: Arc::new(RwLock::new(HashMap::new())),
-            sync_indexes: HashMap::new(),
+            sync_indexes: Arc::new(RwLock::new(HashMap::new())),
             pending_conflicts: Vec::new(),
+            max_pending_conflicts: DEFAULT_MAX_PENDING_CONFLICTS,
             active_transfers: HashMap::new(),
             watcher: None,
             watcher_handle: None,
             packet_sender: None,
             config_path: None,
+            state_db: None,
+            bandwidth_limiters: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -501,7 +704,10 @@ impl FileSyncPlugin {
                         folder_id, index.file_count
                     );
 
-                    self.sync_indexes.insert(folder_id.clone(), index.clone());
+                    self.sync_indexes
+                        .write()
+                        .await
+                        .insert(folder_id.clone(), index.clone());
 
                     if let Some(sender) = &self.packet_sender {
                         if let Some(device_id) = &self.device_id {
@@ -538,7 +744,13 @@ impl FileSyncPlugin {
 
         if let Some(config) = config {
             // Clean up related data
-            self.sync_indexes.remove(folder_id);
+            self.sync_indexes.write().await.remove(folder_id);
+            self.bandwidth_limiters.write().await.remove(folder_id);
+            if let Some(db) = &self.state_db {
+                if let Err(e) = db.remove_folder(folder_id) {
+                    warn!("Failed to clear cached hashes for '{}': {}", folder_id, e);
+                }
+            }
             self.active_transfers.remove(folder_id);
             self.pending_conflicts.retain(|c| c.folder_id != folder_id);
 
@@ -603,10 +815,41 @@ impl FileSyncPlugin {
             }
         };
 
-        Self::generate_index_internal(folder_id, &config).await
+        Self::generate_index_internal(folder_id, &config, self.state_db.clone()).await
     }
 
-    async fn generate_index_internal(folder_id: &str, config: &SyncFolder) -> Result<SyncIndex> {
+    /// Open the database caching file hashes between scans
+    ///
+    /// Once open, scans only hash files whose size or modification time
+    /// changed since the previous scan.
+    pub fn open_state_db(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.state_db = Some(FileStateDb::open(path)?);
+        Ok(())
+    }
+
+    /// Scan and hash a folder on the CPU pool, off the async runtime
+    async fn generate_index_internal(
+        folder_id: &str,
+        config: &SyncFolder,
+        state_db: Option<FileStateDb>,
+    ) -> Result<SyncIndex> {
+        let folder_id = folder_id.to_string();
+        let config = config.clone();
+        cpu_pool::global()
+            .run(move || Self::scan_folder(&folder_id, &config, state_db.as_ref()))
+            .await?
+    }
+
+    /// Walk a sync folder and hash its files
+    ///
+    /// Files whose size and modification time match `state_db` reuse the
+    /// cached hash. Blocking; called through
+    /// [`generate_index_internal`](Self::generate_index_internal).
+    fn scan_folder(
+        folder_id: &str,
+        config: &SyncFolder,
+        state_db: Option<&FileStateDb>,
+    ) -> Result<SyncIndex> {
         info!(
             "Generating sync index for folder '{}' at {}",
             folder_id,
@@ -615,30 +858,38 @@ impl FileSyncPlugin {
 
         let mut files = Vec::new();
         let mut total_size = 0;
+        let mut hashed = 0;
 
-        let mut globset_builder = GlobSetBuilder::new();
-
-        for pattern in &config.ignore_patterns {
-            if let Ok(glob) = Glob::new(pattern) {
-                globset_builder.add(glob);
-            } else {
-                warn!("Invalid glob pattern ignored: {}", pattern);
+        let cached = match state_db.map(|db| db.folder_hashes(folder_id)) {
+            Some(Ok(cached)) => cached,
+            Some(Err(e)) => {
+                warn!("Failed to load cached hashes for '{}': {}", folder_id, e);
+                HashMap::new()
             }
-        }
+            None => HashMap::new(),
+        };
 
-        if let Ok(glob) = Glob::new("**/.git/**") {
-            globset_builder.add(glob);
-        }
-        if let Ok(glob) = Glob::new("**/.DS_Store") {
-            globset_builder.add(glob);
-        }
-
-        let globset = globset_builder
-            .build()
-            .map_err(|e| ProtocolError::Plugin(format!("Failed to build globset: {}", e)))?;
+        // Built-in patterns come last so they cannot be negated
+        let partial_pattern = format!("*{}", PARTIAL_SUFFIX);
+        let versions_pattern = format!("/{}/", VERSIONS_DIR);
+        let builtin = [
+            ".git/",
+            ".DS_Store",
+            partial_pattern.as_str(),
+            versions_pattern.as_str(),
+        ];
+        let patterns = config.ignore_patterns.iter().map(String::as_str);
+        let ignore = IgnoreMatcher::new(patterns.chain(builtin))?;
+        let root = &config.local_path;
 
         for entry in WalkDir::new(&config.local_path)
             .into_iter()
+            .filter_entry(|entry| match entry.path().strip_prefix(root) {
+                Ok(relative) if entry.depth() > 0 => {
+                    !ignore.is_ignored(relative, entry.file_type().is_dir())
+                }
+                _ => true,
+            })
             .filter_map(|e| e.ok())
         {
             let path = entry.path();
@@ -652,32 +903,21 @@ impl FileSyncPlugin {
                 Err(_) => continue,
             };
 
-            if globset.is_match(&relative_path) {
-                continue;
-            }
-
             let metadata = entry.metadata().map_err(|e| ProtocolError::Io(e.into()))?;
             let is_dir = metadata.is_dir();
             let size = metadata.len();
-            let modified = metadata
-                .modified()
-                .unwrap_or(SystemTime::now())
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis() as i64;
-
-            // Unix permissions (if applicable)
-            #[cfg(unix)]
-            let permissions = {
-                use std::os::unix::fs::MetadataExt;
-                Some(metadata.mode())
-            };
-            #[cfg(not(unix))]
-            let permissions = None;
+            let modified = modified_millis(&metadata);
+            let permissions = unix_mode(&metadata);
 
+            let cached_hash = cached
+                .get(&relative_path)
+                .and_then(|c| c.hash_if_unchanged(size, modified));
             let hash = if is_dir {
                 String::new()
+            } else if let Some(hash) = cached_hash {
+                hash.to_string()
             } else {
+                hashed += 1;
                 Self::compute_file_hash(path)?
             };
 
@@ -701,12 +941,19 @@ impl FileSyncPlugin {
             .as_millis() as i64;
 
         debug!(
-            "Generated index for {}: {} files, {} bytes",
+            "Generated index for {}: {} files, {} bytes, {} hashed",
             folder_id,
             files.len(),
-            total_size
+            total_size,
+            hashed
         );
 
+        if let Some(db) = state_db {
+            if let Err(e) = db.store_folder(folder_id, &files) {
+                warn!("Failed to cache hashes for '{}': {}", folder_id, e);
+            }
+        }
+
         let index = SyncIndex {
             folder_id: folder_id.to_string(),
             file_count: files.len(),
@@ -827,6 +1074,9 @@ impl FileSyncPlugin {
             strategy
         );
 
+        // Conflicts restored from the database may predate path validation
+        sync_relative_path(&conflict.path)?;
+
         let device_id = self.device_id.clone().ok_or_else(|| {
             ProtocolError::Plugin("Plugin not initialized (missing device_id)".to_string())
         })?;
@@ -840,6 +1090,7 @@ impl FileSyncPlugin {
                         device_id,
                         conflict.folder_id.clone(),
                         conflict.path.clone(),
+                        None,
                     )
                     .await?;
                 } else {
@@ -918,6 +1169,7 @@ impl FileSyncPlugin {
                         device_id,
                         conflict.folder_id.clone(),
                         conflict.path.clone(),
+                        None,
                     )
                     .await?;
                 } else {
@@ -939,19 +1191,115 @@ impl FileSyncPlugin {
         Ok(())
     }
 
-    /// Get list of pending conflicts
+    /// Archived versions of a file in a sync folder, newest first
+    pub async fn list_versions(&self, folder_id: &str, path: &Path) -> Result<Vec<FileVersion>> {
+        let config = self.get_folder_config(folder_id).await.ok_or_else(|| {
+            ProtocolError::Plugin(format!("Sync folder not found: {}", folder_id))
+        })?;
+        let versions = VersionStore::new(&config.local_path, config.version_keep);
+        let path = path.to_path_buf();
+
+        tokio::task::spawn_blocking(move || versions.list(&path))
+            .await
+            .map_err(|e| ProtocolError::Plugin(format!("Version task panicked: {}", e)))?
+    }
+
+    /// Restore the version of a file archived at `timestamp`
+    ///
+    /// The version is copied back, so it stays available. If the folder keeps
+    /// versions, the current content is archived before it is replaced.
+    pub async fn restore_version(
+        &self,
+        folder_id: &str,
+        path: &Path,
+        timestamp: i64,
+    ) -> Result<()> {
+        let config = self.get_folder_config(folder_id).await.ok_or_else(|| {
+            ProtocolError::Plugin(format!("Sync folder not found: {}", folder_id))
+        })?;
+        let versions = VersionStore::new(&config.local_path, config.version_keep);
+        let path = path.to_path_buf();
+
+        tokio::task::spawn_blocking(move || {
+            let version = versions.find(&path, timestamp)?;
+            let target = config.local_path.join(&path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(ProtocolError::Io)?;
+            }
+
+            // Copy before archiving, which may prune the version being restored
+            let partial = partial_path(&target);
+            let restored = fs::copy(&version.stored_path, &partial)
+                .map_err(ProtocolError::Io)
+                .and_then(|_| match config.version_store() {
+                    Some(store) => store.archive(&path).map(|_| ()),
+                    None => Ok(()),
+                })
+                .and_then(|_| fs::rename(&partial, &target).map_err(ProtocolError::Io));
+            if restored.is_err() {
+                let _ = fs::remove_file(&partial);
+            }
+            restored?;
+
+            info!("Restored {} to version {}", target.display(), timestamp);
+            Ok(())
+        })
+        .await
+        .map_err(|e| ProtocolError::Plugin(format!("Version task panicked: {}", e)))?
+    }
+
+    /// Get list of pending conflicts, oldest first
     pub fn get_pending_conflicts(&self) -> &[FileConflict] {
         &self.pending_conflicts
     }
 
+    /// Number of pending conflicts
+    pub fn pending_conflict_count(&self) -> usize {
+        self.pending_conflicts.len()
+    }
+
+    /// Set the maximum number of pending conflicts
+    ///
+    /// The oldest conflicts are evicted if the current count exceeds the new
+    /// limit.
+    pub fn set_max_pending_conflicts(&mut self, max: usize) {
+        self.max_pending_conflicts = max;
+        self.evict_pending_conflicts();
+    }
+
+    /// Record a conflict awaiting manual resolution
+    ///
+    /// Replaces any pending conflict for the same folder and path, so the
+    /// latest one is kept, then evicts the oldest conflicts over the limit.
+    fn add_pending_conflict(&mut self, conflict: FileConflict) {
+        self.pending_conflicts
+            .retain(|c| c.folder_id != conflict.folder_id || c.path != conflict.path);
+        self.pending_conflicts.push(conflict);
+        self.evict_pending_conflicts();
+    }
+
+    fn evict_pending_conflicts(&mut self) {
+        let excess = self
+            .pending_conflicts
+            .len()
+            .saturating_sub(self.max_pending_conflicts);
+        if excess > 0 {
+            warn!(
+                "Evicting {} oldest pending conflict(s), limit is {}",
+                excess, self.max_pending_conflicts
+            );
+            self.pending_conflicts.drain(..excess);
+        }
+    }
+
     /// Get sync folder configuration
     pub async fn get_folder_config(&self, folder_id: &str) -> Option<SyncFolder> {
         self.sync_folders.read().await.get(folder_id).cloned()
     }
 
     /// Get current sync index for a folder
-    pub fn get_sync_index(&self, folder_id: &str) -> Option<&SyncIndex> {
-        self.sync_indexes.get(folder_id)
+    pub async fn get_sync_index(&self, folder_id: &str) -> Option<SyncIndex> {
+        self.sync_indexes.read().await.get(folder_id).cloned()
     }
 }
 
@@ -993,7 +1341,7 @@ impl FileSyncPlugin {
             }
 
             let db_path_clone = db_path.clone();
-            let sync_indexes = self.sync_indexes.clone();
+            let sync_indexes = self.sync_indexes.read().await.clone();
             let pending_conflicts = self.pending_conflicts.clone();
 
             tokio::task::spawn_blocking(move || {
@@ -1088,11 +1436,16 @@ impl FileSyncPlugin {
         Ok(())
     }
 
+    /// Offer a local file to the peer
+    ///
+    /// With the peer's `signature` of its copy, only a delta against that
+    /// copy is sent, unless the delta would not be smaller than the file.
     async fn initiate_upload(
         &self,
         device_id: String,
         folder_id: String,
         relative_path: PathBuf,
+        signature: Option<FileSignature>,
     ) -> Result<()> {
         let path_str = relative_path.to_string_lossy().to_string();
 
@@ -1102,27 +1455,58 @@ impl FileSyncPlugin {
         };
 
         if let Some(config) = config {
-            let local_path = config.local_path.join(&relative_path);
+            let local_path = existing_path_in_folder(&config.local_path, &relative_path).await;
+
+            if let Some(local_path) = local_path {
+                let metadata = tokio::fs::metadata(&local_path)
+                    .await
+                    .map_err(ProtocolError::Io)?;
+                let size = metadata.len();
+                if size > MAX_FILE_SIZE_BYTES {
+                    return Err(ProtocolError::InvalidPacket(format!(
+                        "File {} is {} bytes, over the {} MB sync limit",
+                        path_str, size, MAX_FILE_SIZE_MB
+                    )));
+                }
+
+                let hash_path = local_path.clone();
+                let hash = cpu_pool::global()
+                    .run(move || Self::compute_file_hash(hash_path))
+                    .await??;
+
+                let delta_file = match signature {
+                    Some(signature) => {
+                        Self::write_delta(local_path.clone(), signature, size).await?
+                    }
+                    None => None,
+                };
+                let is_delta = delta_file.is_some();
+                let (payload_path, payload_size) =
+                    delta_file.unwrap_or_else(|| (local_path.clone(), size));
 
-            if local_path.exists() && local_path.starts_with(&config.local_path) {
                 // Start PayloadServer
+                let mut sending = false;
+                let limiter = self
+                    .bandwidth_limiter(&folder_id, config.bandwidth_limit_kbps)
+                    .await;
                 match PayloadServer::new().await {
                     Ok(server) => {
+                        let server = server.with_bandwidth_limit(limiter);
                         let port = server.port();
-                        let size = tokio::fs::metadata(&local_path)
-                            .await
-                            .map_err(ProtocolError::Io)?
-                            .len();
 
                         // Create transfer packet
                         let mut transfer_packet = Packet::new(
                             "cconnect.filesync.transfer",
                             serde_json::json!({
                                 "folderId": folder_id,
-                                "path": path_str
+                                "path": path_str,
+                                "hash": hash,
+                                "modified": modified_millis(&metadata),
+                                "permissions": unix_mode(&metadata),
+                                "delta": is_delta,
                             }),
                         )
-                        .with_payload_size(size as i64);
+                        .with_payload_size(payload_size as i64);
 
                         let mut transfer_info = HashMap::new();
                         transfer_info.insert("port".to_string(), serde_json::json!(port));
@@ -1130,20 +1514,27 @@ impl FileSyncPlugin {
 
                         // Send packet
                         if let Some(sender) = &self.packet_sender {
-                            sender
-                                .send((device_id, transfer_packet))
-                                .await
-                                .map_err(|_| {
-                                    ProtocolError::Plugin("Failed to send packet".to_string())
-                                })?;
+                            if sender.send((device_id, transfer_packet)).await.is_err() {
+                                if is_delta {
+                                    let _ = tokio::fs::remove_file(&payload_path).await;
+                                }
+                                return Err(ProtocolError::Plugin(
+                                    "Failed to send packet".to_string(),
+                                ));
+                            }
 
                             // Spawn task to send file
+                            sending = true;
+                            let payload_path = payload_path.clone();
                             tokio::spawn(async move {
-                                if let Err(e) = server.send_file(&local_path).await {
+                                if let Err(e) = server.send_file(&payload_path).await {
                                     warn!("Failed to send file {}: {}", local_path.display(), e);
                                 } else {
                                     info!("Successfully sent file {}", local_path.display());
                                 }
+                                if is_delta {
+                                    let _ = tokio::fs::remove_file(&payload_path).await;
+                                }
                             });
                         } else {
                             warn!("No packet sender available");
@@ -1151,16 +1542,65 @@ impl FileSyncPlugin {
                     }
                     Err(e) => warn!("Failed to start payload server: {}", e),
                 }
+
+                if is_delta && !sending {
+                    let _ = tokio::fs::remove_file(&payload_path).await;
+                }
             } else {
-                warn!(
-                    "Requested file not found or invalid path: {}",
-                    local_path.display()
-                );
+                warn!("Requested file not found or invalid path: {}", path_str);
             }
         }
         Ok(())
     }
 
+    /// Upload rate limiter of a folder
+    ///
+    /// The limiter is shared by all uploads of the folder and replaced when
+    /// the folder's limit changes.
+    async fn bandwidth_limiter(&self, folder_id: &str, limit_kbps: u32) -> BandwidthLimiter {
+        let mut limiters = self.bandwidth_limiters.write().await;
+        let limiter = limiters
+            .entry(folder_id.to_string())
+            .or_insert_with(|| BandwidthLimiter::new(limit_kbps));
+        if limiter.limit_kbps() != limit_kbps {
+            *limiter = BandwidthLimiter::new(limit_kbps);
+        }
+        limiter.clone()
+    }
+
+    /// Write the delta of a local file against the peer's signature to a temporary file
+    ///
+    /// Returns the temporary file and its size, or `None` if the delta would
+    /// not be smaller than the file itself.
+    async fn write_delta(
+        path: PathBuf,
+        signature: FileSignature,
+        size: u64,
+    ) -> Result<Option<(PathBuf, u64)>> {
+        cpu_pool::global()
+            .run(move || {
+                let encoded = compute_delta(&path, &signature)?.encode();
+                if encoded.len() as u64 >= size {
+                    return Ok(None);
+                }
+
+                let delta_path = delta_temp_path();
+                fs::write(&delta_path, &encoded).map_err(ProtocolError::Io)?;
+                debug!(
+                    "Delta for {} is {} of {} bytes",
+                    path.display(),
+                    encoded.len(),
+                    size
+                );
+                Ok(Some((delta_path, encoded.len() as u64)))
+            })
+            .await?
+    }
+
+    /// Ask the peer for a file
+    ///
+    /// If a large enough local copy exists, its block signatures are sent so
+    /// the peer can answer with a delta instead of the whole file.
     async fn request_download(
         &self,
         device_id: String,
@@ -1168,13 +1608,48 @@ impl FileSyncPlugin {
         relative_path: PathBuf,
     ) -> Result<()> {
         let path_str = relative_path.to_string_lossy().to_string();
-        let packet = Packet::new(
-            "cconnect.filesync.request",
-            serde_json::json!({
-                "folderId": folder_id,
-                "path": path_str
-            }),
-        );
+
+        let folder_path = {
+            let folders = self.sync_folders.read().await;
+            folders
+                .get(&folder_id)
+                .map(|config| config.local_path.clone())
+        };
+        let base_path = match folder_path {
+            Some(root) => existing_path_in_folder(&root, &relative_path).await,
+            None => None,
+        };
+        let base_size = match &base_path {
+            Some(path) => tokio::fs::metadata(path)
+                .await
+                .ok()
+                .filter(|metadata| metadata.is_file())
+                .map(|metadata| metadata.len()),
+            None => None,
+        };
+
+        let packet = match (base_path, base_size) {
+            (Some(base_path), Some(base_size)) if base_size >= DELTA_MIN_FILE_SIZE => {
+                let signature = cpu_pool::global()
+                    .run(move || compute_block_signatures(&base_path, block_size_for(base_size)))
+                    .await??;
+                Packet::new(
+                    "cconnect.filesync.signature",
+                    serde_json::json!({
+                        "folderId": folder_id,
+                        "path": path_str,
+                        "signature": signature,
+                    }),
+                )
+            }
+            _ => Packet::new(
+                "cconnect.filesync.request",
+                serde_json::json!({
+                    "folderId": folder_id,
+                    "path": path_str
+                }),
+            ),
+        };
 
         if let Some(sender) = &self.packet_sender {
             sender
@@ -1184,6 +1659,261 @@ impl FileSyncPlugin {
         }
         Ok(())
     }
+
+    /// Carry out the transfers and local deletions of a sync plan
+    ///
+    /// Uploads are offered to the peer as transfer packets with an attached
+    /// payload. Downloads are requested from the peer, which answers with a
+    /// transfer packet of its own. Conflicts are resolved separately and
+    /// skipped here. Failed actions are logged and do not stop the rest.
+    pub async fn execute_sync_plan(&self, device_id: &str, folder_id: &str, plan: &SyncPlan) {
+        for action in &plan.actions {
+            match action {
+                SyncAction::Upload(path) => {
+                    if let Err(e) = self
+                        .initiate_upload(
+                            device_id.to_string(),
+                            folder_id.to_string(),
+                            path.clone(),
+                            None,
+                        )
+                        .await
+                    {
+                        warn!("Failed to initiate upload of {}: {}", path.display(), e);
+                    }
+                }
+                SyncAction::Download(path) => {
+                    if let Err(e) = self
+                        .request_download(
+                            device_id.to_string(),
+                            folder_id.to_string(),
+                            path.clone(),
+                        )
+                        .await
+                    {
+                        warn!("Failed to request download of {}: {}", path.display(), e);
+                    }
+                }
+                SyncAction::DeleteLocal(path) => {
+                    if let Some(config) = self.sync_folders.read().await.get(folder_id) {
+                        let local_path = config.local_path.join(path);
+                        if local_path.exists() {
+                            if let Err(e) = Self::delete_local_file(config, path).await {
+                                warn!(
+                                    "Failed to delete local file {}: {}",
+                                    local_path.display(),
+                                    e
+                                );
+                            } else {
+                                info!("Deleted local file per sync plan: {}", local_path.display());
+                            }
+                        }
+                    }
+                }
+                _ => {} // Conflicts and Remote Deletes handled differently or already processed
+            }
+        }
+    }
+
+    /// Delete a synced file, archiving it first if the folder keeps versions
+    async fn delete_local_file(config: &SyncFolder, relative_path: &Path) -> Result<()> {
+        let versions = config.version_store();
+        let relative_path = relative_path.to_path_buf();
+        let local_path = config.local_path.join(&relative_path);
+
+        tokio::task::spawn_blocking(move || {
+            if let Some(versions) = versions {
+                versions.archive(&relative_path)?;
+            }
+            fs::remove_file(&local_path).map_err(ProtocolError::Io)
+        })
+        .await
+        .map_err(|e| ProtocolError::Plugin(format!("Delete task panicked: {}", e)))?
+    }
+
+    /// Download a transfer's payload and move it into place
+    ///
+    /// The payload is written to a hidden partial file next to the target, so
+    /// an interrupted transfer never touches the existing file.
+    async fn download_file(
+        host: &str,
+        port: u16,
+        size: u64,
+        folder: SyncFolder,
+        relative_path: PathBuf,
+        expected_hash: Option<String>,
+        permissions: Option<u32>,
+    ) -> Result<FileMetadata> {
+        let target_path = folder.local_path.join(&relative_path);
+        let partial = partial_path(&target_path);
+        Self::receive_payload(host, port, size, expected_hash.as_deref(), &partial).await?;
+
+        cpu_pool::global()
+            .run(move || {
+                Self::commit_received_file(
+                    &partial,
+                    &target_path,
+                    relative_path,
+                    expected_hash.as_deref(),
+                    permissions,
+                    folder.version_store().as_ref(),
+                )
+            })
+            .await?
+    }
+
+    /// Download a delta against the existing target and rebuild the file from it
+    ///
+    /// The rebuilt file goes through the same partial file and hash check as
+    /// a full download.
+    async fn download_delta(
+        host: &str,
+        port: u16,
+        size: u64,
+        folder: SyncFolder,
+        relative_path: PathBuf,
+        expected_hash: Option<String>,
+        permissions: Option<u32>,
+    ) -> Result<FileMetadata> {
+        let target_path = folder.local_path.join(&relative_path);
+        let delta_path = delta_temp_path();
+        Self::receive_payload(host, port, size, None, &delta_path).await?;
+
+        cpu_pool::global()
+            .run(move || {
+                let partial = partial_path(&target_path);
+                let applied = fs::read(&delta_path)
+                    .map_err(ProtocolError::Io)
+                    .and_then(|bytes| Delta::decode(&bytes))
+                    .and_then(|delta| apply_delta(&target_path, &delta, &partial));
+                let _ = fs::remove_file(&delta_path);
+                if let Err(e) = applied {
+                    let _ = fs::remove_file(&partial);
+                    return Err(e);
+                }
+
+                Self::commit_received_file(
+                    &partial,
+                    &target_path,
+                    relative_path,
+                    expected_hash.as_deref(),
+                    permissions,
+                    folder.version_store().as_ref(),
+                )
+            })
+            .await?
+    }
+
+    /// Receive a payload into `dest`, removing it again if the transfer fails
+    ///
+    /// With `expected_hash` (the [`FileMetadata::hash`] of the file), a
+    /// corrupted payload is rejected before it is committed.
+    async fn receive_payload(
+        host: &str,
+        port: u16,
+        size: u64,
+        expected_hash: Option<&str>,
+        dest: &Path,
+    ) -> Result<()> {
+        let received = match PayloadClient::new(host, port).await {
+            Ok(client) => {
+                let client = match expected_hash {
+                    Some(hash) => client.with_expected_hash(hash),
+                    None => client,
+                };
+                client.receive_file(dest, size).await
+            }
+            Err(e) => Err(e),
+        };
+        if received.is_err() {
+            let _ = tokio::fs::remove_file(dest).await;
+        }
+        received
+    }
+
+    /// Verify a fully received partial file and rename it over the target
+    ///
+    /// The partial file is synced to disk before the rename, and the rename
+    /// itself is persisted by syncing the directory. With `versions`, the
+    /// target's old content is archived first. On error the partial file is
+    /// removed and the target is left as it was.
+    fn commit_received_file(
+        partial: &Path,
+        target: &Path,
+        relative_path: PathBuf,
+        expected_hash: Option<&str>,
+        permissions: Option<u32>,
+        versions: Option<&VersionStore>,
+    ) -> Result<FileMetadata> {
+        let result = Self::replace_with_partial(
+            partial,
+            target,
+            relative_path,
+            expected_hash,
+            permissions,
+            versions,
+        );
+        if result.is_err() {
+            let _ = fs::remove_file(partial);
+        }
+        result
+    }
+
+    fn replace_with_partial(
+        partial: &Path,
+        target: &Path,
+        relative_path: PathBuf,
+        expected_hash: Option<&str>,
+        permissions: Option<u32>,
+        versions: Option<&VersionStore>,
+    ) -> Result<FileMetadata> {
+        fs::File::open(partial)
+            .and_then(|f| f.sync_all())
+            .map_err(ProtocolError::Io)?;
+
+        let hash = Self::compute_file_hash(partial)?;
+        if let Some(expected) = expected_hash {
+            if expected != hash {
+                return Err(ProtocolError::InvalidPacket(format!(
+                    "Hash mismatch for {}: expected {}, received {}",
+                    relative_path.display(),
+                    expected,
+                    hash
+                )));
+            }
+        }
+
+        #[cfg(unix)]
+        if let Some(mode) = permissions {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(partial, fs::Permissions::from_mode(mode & 0o777))
+                .map_err(ProtocolError::Io)?;
+        }
+        #[cfg(not(unix))]
+        let _ = permissions;
+
+        if let Some(versions) = versions {
+            versions.archive(&relative_path)?;
+        }
+        fs::rename(partial, target).map_err(ProtocolError::Io)?;
+
+        #[cfg(unix)]
+        if let Some(parent) = target.parent() {
+            fs::File::open(parent)
+                .and_then(|dir| dir.sync_all())
+                .map_err(ProtocolError::Io)?;
+        }
+
+        let metadata = fs::metadata(target).map_err(ProtocolError::Io)?;
+        Ok(FileMetadata {
+            path: relative_path,
+            size: metadata.len(),
+            modified: modified_millis(&metadata),
+            hash,
+            is_dir: false,
+            permissions: unix_mode(&metadata),
+        })
+    }
 }
 
 #[async_trait]
@@ -1227,6 +1957,11 @@ impl Plugin for FileSyncPlugin {
             warn!("Failed to load config: {}", e);
         }
 
+        // Load cached file hashes
+        if let Err(e) = Self::get_db_path(device.id()).and_then(|path| self.open_state_db(path)) {
+            warn!("Failed to open sync state database: {}", e);
+        }
+
         Ok(())
     }
 
@@ -1271,6 +2006,7 @@ impl Plugin for FileSyncPlugin {
         let sync_folders = self.sync_folders.clone();
         let packet_sender = self.packet_sender.clone();
         let device_id = self.device_id.clone();
+        let state_db = self.state_db.clone();
 
         let handle = tokio::spawn(async move {
             info!("FileSync watcher task started");
@@ -1292,7 +2028,9 @@ impl Plugin for FileSyncPlugin {
                             // Regenerate index for this folder
                             info!("Changes detected in {}, generating index...", fid);
 
-                            if let Ok(index) = Self::generate_index_internal(fid, config).await {
+                            if let Ok(index) =
+                                Self::generate_index_internal(fid, config, state_db.clone()).await
+                            {
                                 // Send index packet
                                 if let Some(sender) = &packet_sender {
                                     if let Some(did) = &device_id {
@@ -1371,6 +2109,9 @@ impl Plugin for FileSyncPlugin {
             // Receive remote sync index
             let index: SyncIndex = serde_json::from_value(packet.body.clone())
                 .map_err(|e| ProtocolError::InvalidPacket(e.to_string()))?;
+            for file in &index.files {
+                sync_relative_path(&file.path)?;
+            }
 
             let folder_id = index.folder_id.clone();
 
@@ -1397,64 +2138,26 @@ impl Plugin for FileSyncPlugin {
                     if let SyncAction::Conflict(conflict) = action {
                         let strategy = conflict.suggested_strategy;
                         if strategy == ConflictStrategy::Manual {
-                            self.pending_conflicts.push(conflict.clone());
+                            self.add_pending_conflict(conflict.clone());
                         } else if let Err(e) = self.resolve_conflict(conflict, strategy).await {
                             warn!(
                                 "Failed to auto-resolve conflict for {}: {}",
                                 conflict.path.display(),
                                 e
                             );
-                            self.pending_conflicts.push(conflict.clone());
+                            self.add_pending_conflict(conflict.clone());
                         }
                     }
                 }
 
                 // Store remote index
-                self.sync_indexes.insert(folder_id.clone(), index);
+                self.sync_indexes
+                    .write()
+                    .await
+                    .insert(folder_id.clone(), index);
 
                 // Execute transfers (Uploads / Downloads)
-                let device_id = device.id().to_string();
-
-                for action in plan.actions {
-                    match action {
-                        SyncAction::Upload(path) => {
-                            if let Err(e) = self
-                                .initiate_upload(device_id.clone(), folder_id.clone(), path)
-                                .await
-                            {
-                                warn!("Failed to initiate upload: {}", e);
-                            }
-                        }
-                        SyncAction::Download(path) => {
-                            if let Err(e) = self
-                                .request_download(device_id.clone(), folder_id.clone(), path)
-                                .await
-                            {
-                                warn!("Failed to request download: {}", e);
-                            }
-                        }
-                        SyncAction::DeleteLocal(path) => {
-                            if let Some(config) = self.sync_folders.read().await.get(&folder_id) {
-                                let local_path = config.local_path.join(&path);
-                                if local_path.exists() {
-                                    if let Err(e) = tokio::fs::remove_file(&local_path).await {
-                                        warn!(
-                                            "Failed to delete local file {}: {}",
-                                            local_path.display(),
-                                            e
-                                        );
-                                    } else {
-                                        info!(
-                                            "Deleted local file per sync plan: {}",
-                                            local_path.display()
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                        _ => {} // Conflicts and Remote Deletes handled differently or already processed
-                    }
-                }
+                self.execute_sync_plan(device.id(), &folder_id, &plan).await;
             }
 
             info!("Processed sync index");
@@ -1479,12 +2182,45 @@ impl Plugin for FileSyncPlugin {
                 path_str, folder_id
             );
 
+            let path = sync_relative_path(path_str)?;
+            let device_id = device.id().to_string();
+            if let Err(e) = self.initiate_upload(device_id, folder_id, path, None).await {
+                warn!("Failed to process file request: {}", e);
+            }
+        } else if packet.is_type("cconnect.filesync.signature") {
+            // Remote wants a file and has an older copy to apply a delta to
+            let folder_id: String = packet
+                .body
+                .get("folderId")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| ProtocolError::InvalidPacket("Missing folderId".to_string()))?
+                .to_string();
+
+            let path_str: String = packet
+                .body
+                .get("path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| ProtocolError::InvalidPacket("Missing path".to_string()))?
+                .to_string();
+
+            let signature: FileSignature =
+                serde_json::from_value(packet.body.get("signature").cloned().ok_or_else(|| {
+                    ProtocolError::InvalidPacket("Missing signature".to_string())
+                })?)
+                .map_err(|e| ProtocolError::InvalidPacket(e.to_string()))?;
+
+            info!(
+                "Received delta request for file {} in folder {}",
+                path_str, folder_id
+            );
+
+            let path = sync_relative_path(path_str)?;
             let device_id = device.id().to_string();
             if let Err(e) = self
-                .initiate_upload(device_id, folder_id, PathBuf::from(path_str))
+                .initiate_upload(device_id, folder_id, path, Some(signature))
                 .await
             {
-                warn!("Failed to process file request: {}", e);
+                warn!("Failed to process delta request: {}", e);
             }
         } else if packet.is_type("cconnect.filesync.transfer") {
             // Receive file data transfer (Remote is sending to us)
@@ -1502,7 +2238,34 @@ impl Plugin for FileSyncPlugin {
                 .ok_or_else(|| ProtocolError::InvalidPacket("Missing path".to_string()))?
                 .to_string();
 
-            let path = PathBuf::from(&path_str);
+            let path = sync_relative_path(&path_str)?;
+
+            let size = packet.payload_size.unwrap_or(0);
+            if size < 0 || size as u64 > MAX_FILE_SIZE_BYTES {
+                return Err(ProtocolError::InvalidPacket(format!(
+                    "File transfer for {} of {} bytes exceeds the {} MB sync limit",
+                    path.display(),
+                    size,
+                    MAX_FILE_SIZE_MB
+                )));
+            }
+            let size = size as u64;
+
+            let expected_hash = packet
+                .body
+                .get("hash")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            let permissions = packet
+                .body
+                .get("permissions")
+                .and_then(|v| v.as_u64())
+                .map(|mode| mode as u32);
+            let delta = packet
+                .body
+                .get("delta")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
 
             debug!(
                 "Received file transfer offer for {} in {}",
@@ -1532,7 +2295,6 @@ impl Plugin for FileSyncPlugin {
                         let port = port as u16;
                         if let Some(host) = &device.host {
                             let host = host.clone();
-                            let size = packet.payload_size.unwrap_or(0);
 
                             // Ensure parent directory exists
                             if let Some(parent) = target_path.parent() {
@@ -1546,44 +2308,51 @@ impl Plugin for FileSyncPlugin {
                                 target_path.display()
                             );
 
-                            let sync_folders = self.sync_folders.clone();
-                            let folder_id_clone = folder_id.clone();
+                            let sync_indexes = self.sync_indexes.clone();
 
                             tokio::spawn(async move {
-                                match PayloadClient::new(&host, port).await {
-                                    Ok(client) => {
-                                        if let Err(e) =
-                                            client.receive_file(&target_path, size as u64).await
-                                        {
-                                            warn!(
-                                                "Failed to receive file {}: {}",
-                                                target_path.display(),
-                                                e
-                                            );
-                                        } else {
-                                            info!(
-                                                "Successfully received file {}",
-                                                target_path.display()
-                                            );
-
-                                            let folders = sync_folders.read().await;
-                                            if let Some(config) = folders.get(&folder_id_clone) {
-                                                if let Ok(index) = Self::generate_index_internal(
-                                                    &folder_id_clone,
-                                                    config,
-                                                )
-                                                .await
-                                                {
-                                                    debug!(
-                                                        "Updated local sync index after receiving file: {} files",
-                                                        index.file_count
-                                                    );
-                                                }
-                                            }
-                                        }
+                                let display_path = target_path.display().to_string();
+                                let received = if delta {
+                                    Self::download_delta(
+                                        &host,
+                                        port,
+                                        size,
+                                        config,
+                                        path,
+                                        expected_hash,
+                                        permissions,
+                                    )
+                                    .await
+                                } else {
+                                    Self::download_file(
+                                        &host,
+                                        port,
+                                        size,
+                                        config,
+                                        path,
+                                        expected_hash,
+                                        permissions,
+                                    )
+                                    .await
+                                };
+                                match received {
+                                    Ok(file) => {
+                                        info!("Successfully received file {}", display_path);
+                                        sync_indexes
+                                            .write()
+                                            .await
+                                            .entry(folder_id.clone())
+                                            .or_insert_with(|| SyncIndex {
+                                                folder_id,
+                                                files: Vec::new(),
+                                                timestamp: 0,
+                                                total_size: 0,
+                                                file_count: 0,
+                                            })
+                                            .upsert_file(file);
                                     }
                                     Err(e) => {
-                                        warn!("Failed to connect to payload server: {}", e)
+                                        warn!("Failed to receive file {}: {}", display_path, e)
                                     }
                                 }
                             });
@@ -1611,12 +2380,12 @@ impl Plugin for FileSyncPlugin {
                 .ok_or_else(|| ProtocolError::InvalidPacket("Missing path".to_string()))?
                 .to_string();
 
-            let file_path = PathBuf::from(&path_str);
+            let file_path = sync_relative_path(&path_str)?;
 
             if let Some(config) = self.sync_folders.read().await.get(&folder_id) {
                 let local_path = config.local_path.join(&file_path);
                 if local_path.exists() && local_path.starts_with(&config.local_path) {
-                    if let Err(e) = tokio::fs::remove_file(&local_path).await {
+                    if let Err(e) = Self::delete_local_file(config, &file_path).await {
                         warn!("Failed to delete file {}: {}", local_path.display(), e);
                     } else {
                         info!("Deleted file handled: {}", local_path.display());
@@ -1625,16 +2394,17 @@ impl Plugin for FileSyncPlugin {
             }
         } else if packet.is_type("cconnect.filesync.conflict") {
             // Receive conflict notification
-            let conflict: FileConflict = serde_json::from_value(packet.body.clone())
+            let mut conflict: FileConflict = serde_json::from_value(packet.body.clone())
                 .map_err(|e| ProtocolError::InvalidPacket(e.to_string()))?;
-
-            self.pending_conflicts.push(conflict.clone());
+            conflict.path = sync_relative_path(conflict.path)?;
 
             warn!(
                 "Conflict detected for {} in folder '{}'",
                 conflict.path.display(),
                 conflict.folder_id
             );
+
+            self.add_pending_conflict(conflict);
         }
 
         Ok(())
@@ -1642,11 +2412,38 @@ impl Plugin for FileSyncPlugin {
 }
 
 /// File Sync plugin factory
-pub struct FileSyncPluginFactory;
+#[derive(Debug, Clone, Copy)]
+pub struct FileSyncPluginFactory {
+    /// Pending conflict limit of every created plugin
+    max_pending_conflicts: usize,
+}
+
+impl FileSyncPluginFactory {
+    /// Create factory with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most `max` pending conflicts in every created plugin
+    pub fn with_max_pending_conflicts(mut self, max: usize) -> Self {
+        self.max_pending_conflicts = max;
+        self
+    }
+}
+
+impl Default for FileSyncPluginFactory {
+    fn default() -> Self {
+        Self {
+            max_pending_conflicts: DEFAULT_MAX_PENDING_CONFLICTS,
+        }
+    }
+}
 
 impl PluginFactory for FileSyncPluginFactory {
     fn create(&self) -> Box<dyn Plugin> {
-        Box::new(FileSyncPlugin::new())
+        let mut plugin = FileSyncPlugin::new();
+        plugin.set_max_pending_conflicts(self.max_pending_conflicts);
+        Box::new(plugin)
     }
 
     fn name(&self) -> &str {
@@ -1707,6 +2504,33 @@ mod tests {
         assert!(plugin.get_folder_config("test_folder").await.is_some());
     }
 
+    #[tokio::test]
+    async fn test_index_hashing_runs_on_cpu_pool() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), b"hello").unwrap();
+
+        let mut plugin = FileSyncPlugin::new();
+        plugin.enabled = true;
+        plugin
+            .configure_folder(
+                "pool".to_string(),
+                dir.path().to_path_buf(),
+                ConflictStrategy::LastModifiedWins,
+            )
+            .await
+            .unwrap();
+
+        let dispatched = cpu_pool::global().stats().dispatched;
+        let index = plugin.generate_index("pool").await.unwrap();
+
+        assert_eq!(index.file_count, 1);
+        assert_eq!(
+            index.files[0].hash,
+            blake3::hash(b"hello").to_hex().to_string()
+        );
+        assert!(cpu_pool::global().stats().dispatched > dispatched);
+    }
+
     #[tokio::test]
     async fn test_remove_folder() {
         let mut plugin = FileSyncPlugin::new();
@@ -1837,4 +2661,572 @@ mod tests {
         let plugin = FileSyncPlugin::new();
         assert_eq!(plugin.get_pending_conflicts().len(), 0);
     }
+
+    fn conflict_packet(path: &str, timestamp: i64) -> Packet {
+        let metadata = FileMetadata {
+            path: PathBuf::from(path),
+            size: 10,
+            modified: timestamp,
+            hash: "abc".to_string(),
+            is_dir: false,
+            permissions: None,
+        };
+        let conflict = FileConflict {
+            folder_id: "docs".to_string(),
+            path: PathBuf::from(path),
+            local_metadata: metadata.clone(),
+            remote_metadata: metadata,
+            suggested_strategy: ConflictStrategy::Manual,
+            timestamp,
+        };
+        Packet::new(
+            "cconnect.filesync.conflict",
+            serde_json::to_value(conflict).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_repeated_conflict_not_duplicated() {
+        let mut plugin = FileSyncPlugin::new();
+        plugin.enabled = true;
+        let mut device = create_test_device();
+
+        for timestamp in [1, 2, 3] {
+            plugin
+                .handle_packet(&conflict_packet("a.txt", timestamp), &mut device)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(plugin.pending_conflict_count(), 1);
+        assert_eq!(plugin.get_pending_conflicts()[0].timestamp, 3);
+    }
+
+    #[tokio::test]
+    async fn test_pending_conflicts_evict_oldest() {
+        let mut plugin = FileSyncPlugin::new();
+        plugin.enabled = true;
+        let mut device = create_test_device();
+        plugin.set_max_pending_conflicts(2);
+
+        for (i, path) in ["a.txt", "b.txt", "c.txt"].iter().enumerate() {
+            plugin
+                .handle_packet(&conflict_packet(path, i as i64), &mut device)
+                .await
+                .unwrap();
+        }
+
+        let paths: Vec<_> = plugin
+            .get_pending_conflicts()
+            .iter()
+            .map(|c| c.path.clone())
+            .collect();
+        assert_eq!(paths, vec![PathBuf::from("b.txt"), PathBuf::from("c.txt")]);
+
+        plugin.set_max_pending_conflicts(1);
+        assert_eq!(plugin.pending_conflict_count(), 1);
+        assert_eq!(plugin.get_pending_conflicts()[0].path, PathBuf::from("c.txt"));
+    }
+
+    #[test]
+    fn test_factory_sets_conflict_limit() {
+        let mut plugin = FileSyncPluginFactory::new()
+            .with_max_pending_conflicts(5)
+            .create();
+        let plugin = plugin
+            .as_any_mut()
+            .downcast_mut::<FileSyncPlugin>()
+            .unwrap();
+        assert_eq!(plugin.max_pending_conflicts, 5);
+
+        let mut plugin = FileSyncPluginFactory::new().create();
+        let plugin = plugin
+            .as_any_mut()
+            .downcast_mut::<FileSyncPlugin>()
+            .unwrap();
+        assert_eq!(plugin.max_pending_conflicts, DEFAULT_MAX_PENDING_CONFLICTS);
+    }
+
+    #[tokio::test]
+    async fn test_conflict_outside_folder_rejected() {
+        let mut plugin = FileSyncPlugin::new();
+        plugin.enabled = true;
+        let mut device = create_test_device();
+
+        for path in ["../../home/user/.ssh/id_rsa", "/etc/passwd", ""] {
+            let err = plugin
+                .handle_packet(&conflict_packet(path, 1), &mut device)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ProtocolError::InvalidPacket(_)), "{path}");
+        }
+        assert_eq!(plugin.pending_conflict_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_upload_outside_folder_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("sync");
+        fs::create_dir(&folder).unwrap();
+        fs::write(dir.path().join("secret.txt"), b"private").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path().join("secret.txt"), folder.join("link.txt")).unwrap();
+        let (plugin, mut rx) = plugin_with_folder(&folder).await;
+
+        let plan = SyncPlan {
+            actions: vec![
+                SyncAction::Upload(PathBuf::from("../secret.txt")),
+                SyncAction::Upload(PathBuf::from("link.txt")),
+            ],
+            stats: SyncStats::default(),
+        };
+        plugin.execute_sync_plan("peer", "docs", &plan).await;
+
+        assert!(rx.try_recv().is_err());
+    }
+
+    async fn plugin_with_folder(
+        dir: &Path,
+    ) -> (
+        FileSyncPlugin,
+        tokio::sync::mpsc::Receiver<(String, Packet)>,
+    ) {
+        let (tx, rx) = tokio::sync::mpsc::channel(100);
+        let mut plugin = FileSyncPlugin::new();
+        plugin.enabled = true;
+        plugin
+            .configure_folder(
+                "docs".to_string(),
+                dir.to_path_buf(),
+                ConflictStrategy::LastModifiedWins,
+            )
+            .await
+            .unwrap();
+        plugin.packet_sender = Some(tx);
+        (plugin, rx)
+    }
+
+    fn transfer_packet(path: &str, size: i64, hash: &str, port: u16) -> Packet {
+        let mut transfer_info = HashMap::new();
+        transfer_info.insert("port".to_string(), serde_json::json!(port));
+        Packet::new(
+            "cconnect.filesync.transfer",
+            serde_json::json!({
+                "folderId": "docs",
+                "path": path,
+                "hash": hash,
+                "permissions": 0o100640,
+            }),
+        )
+        .with_payload_size(size)
+        .with_payload_transfer_info(transfer_info)
+    }
+
+    #[test]
+    fn test_commit_received_file_replaces_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("notes.txt");
+        let partial = partial_path(&target);
+        fs::write(&target, b"old").unwrap();
+        fs::write(&partial, b"new contents").unwrap();
+
+        let hash = blake3::hash(b"new contents").to_hex().to_string();
+        let file = FileSyncPlugin::commit_received_file(
+            &partial,
+            &target,
+            PathBuf::from("notes.txt"),
+            Some(&hash),
+            Some(0o100600),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(fs::read(&target).unwrap(), b"new contents");
+        assert!(!partial.exists());
+        assert_eq!(file.path, PathBuf::from("notes.txt"));
+        assert_eq!(file.size, 12);
+        assert_eq!(file.hash, hash);
+
+        #[cfg(unix)]
+        assert_eq!(file.permissions, Some(0o600));
+    }
+
+    #[test]
+    fn test_corrupt_transfer_keeps_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("notes.txt");
+        let partial = partial_path(&target);
+        fs::write(&target, b"old").unwrap();
+        fs::write(&partial, b"truncat").unwrap();
+
+        let hash = blake3::hash(b"truncated payload").to_hex().to_string();
+        let err = FileSyncPlugin::commit_received_file(
+            &partial,
+            &target,
+            PathBuf::from("notes.txt"),
+            Some(&hash),
+            None,
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, ProtocolError::InvalidPacket(_)));
+        assert_eq!(fs::read(&target).unwrap(), b"old");
+        assert!(!partial.exists());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_transfer_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut plugin, _rx) = plugin_with_folder(dir.path()).await;
+        let mut device = create_test_device();
+
+        let packet = transfer_packet("big.iso", (MAX_FILE_SIZE_BYTES + 1) as i64, "", 1739);
+        let err = plugin
+            .handle_packet(&packet, &mut device)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, ProtocolError::InvalidPacket(_)));
+        assert!(!dir.path().join("big.iso").exists());
+    }
+
+    #[tokio::test]
+    async fn test_transfer_outside_folder_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("docs");
+        fs::create_dir(&folder).unwrap();
+        let (mut plugin, _rx) = plugin_with_folder(&folder).await;
+        let mut device = create_test_device();
+
+        for path in [
+            "../escape.txt",
+            "sub/../../escape.txt",
+            "/tmp/escape.txt",
+            "",
+        ] {
+            let packet = transfer_packet(path, 4, "", 1739);
+            let err = plugin
+                .handle_packet(&packet, &mut device)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ProtocolError::InvalidPacket(_)), "{path}");
+        }
+        assert!(!dir.path().join("escape.txt").exists());
+
+        assert_eq!(
+            sync_relative_path("sub/./notes.txt").unwrap(),
+            PathBuf::from("sub/./notes.txt")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transfer_written_and_indexed() {
+        let dir = tempfile::tempdir().unwrap();
+        let (mut plugin, _rx) = plugin_with_folder(dir.path()).await;
+        let mut device = create_test_device();
+        device.host = Some("127.0.0.1".to_string());
+
+        let source_dir = tempfile::tempdir().unwrap();
+        let source = source_dir.path().join("report.txt");
+        fs::write(&source, b"quarterly numbers").unwrap();
+        let hash = blake3::hash(b"quarterly numbers").to_hex().to_string();
+
+        let server = PayloadServer::new().await.unwrap();
+        let packet = transfer_packet("sub/report.txt", 17, &hash, server.port());
+        tokio::spawn(async move { server.send_file(&source).await });
+
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let path = PathBuf::from("sub/report.txt");
+        let mut indexed = None;
+        for _ in 0..100 {
+            indexed = plugin
+                .get_sync_index("docs")
+                .await
+                .and_then(|index| index.files.into_iter().find(|f| f.path == path));
+            if indexed.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        let file = indexed.expect("received file not indexed");
+        assert_eq!(file.hash, hash);
+        assert_eq!(
+            fs::read(dir.path().join("sub/report.txt")).unwrap(),
+            b"quarterly numbers"
+        );
+        assert!(!partial_path(&dir.path().join("sub/report.txt")).exists());
+    }
+
+    #[tokio::test]
+    async fn test_execute_sync_plan_sends_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("local.txt"), b"mine").unwrap();
+        let (plugin, mut rx) = plugin_with_folder(dir.path()).await;
+
+        let plan = SyncPlan {
+            actions: vec![
+                SyncAction::Upload(PathBuf::from("local.txt")),
+                SyncAction::Download(PathBuf::from("remote.txt")),
+            ],
+            stats: SyncStats::default(),
+        };
+        plugin.execute_sync_plan("peer", "docs", &plan).await;
+
+        let (device_id, upload) = rx.recv().await.unwrap();
+        assert_eq!(device_id, "peer");
+        assert!(upload.is_type("cconnect.filesync.transfer"));
+        assert_eq!(upload.payload_size, Some(4));
+        assert!(upload.payload_transfer_info.is_some());
+        assert_eq!(
+            upload.body["hash"],
+            blake3::hash(b"mine").to_hex().to_string()
+        );
+
+        let (_, download) = rx.recv().await.unwrap();
+        assert!(download.is_type("cconnect.filesync.request"));
+        assert_eq!(download.body["path"], "remote.txt");
+    }
+
+    #[tokio::test]
+    async fn test_download_uses_delta_against_local_copy() {
+        let old: Vec<u8> = (0..256 * 1024u32).map(|i| (i * 7 % 251) as u8).collect();
+        let mut new = old.clone();
+        new[100_000..101_024].fill(0xaa);
+
+        let local_dir = tempfile::tempdir().unwrap();
+        let remote_dir = tempfile::tempdir().unwrap();
+        fs::write(local_dir.path().join("data.bin"), &old).unwrap();
+        fs::write(remote_dir.path().join("data.bin"), &new).unwrap();
+        let (mut local, mut local_rx) = plugin_with_folder(local_dir.path()).await;
+        let (mut remote, mut remote_rx) = plugin_with_folder(remote_dir.path()).await;
+        let mut device = create_test_device();
+        device.host = Some("127.0.0.1".to_string());
+
+        let plan = SyncPlan {
+            actions: vec![SyncAction::Download(PathBuf::from("data.bin"))],
+            stats: SyncStats::default(),
+        };
+        local.execute_sync_plan("peer", "docs", &plan).await;
+        let (_, request) = local_rx.recv().await.unwrap();
+        assert!(request.is_type("cconnect.filesync.signature"));
+
+        remote.handle_packet(&request, &mut device).await.unwrap();
+        let (_, transfer) = remote_rx.recv().await.unwrap();
+        assert_eq!(transfer.body["delta"], true);
+        assert!(transfer.payload_size.unwrap() < 8 * 1024);
+
+        local.handle_packet(&transfer, &mut device).await.unwrap();
+        let target = local_dir.path().join("data.bin");
+        for _ in 0..100 {
+            if fs::read(&target).unwrap() == new {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(fs::read(&target).unwrap(), new);
+        assert!(!partial_path(&target).exists());
+    }
+
+    #[test]
+    fn test_commit_archives_previous_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("notes.txt");
+        let partial = partial_path(&target);
+        fs::write(&target, b"old").unwrap();
+        fs::write(&partial, b"new").unwrap();
+
+        let versions = VersionStore::new(dir.path(), 5);
+        FileSyncPlugin::commit_received_file(
+            &partial,
+            &target,
+            PathBuf::from("notes.txt"),
+            None,
+            None,
+            Some(&versions),
+        )
+        .unwrap();
+
+        assert_eq!(fs::read(&target).unwrap(), b"new");
+        let archived = versions.list(Path::new("notes.txt")).unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(fs::read(&archived[0].stored_path).unwrap(), b"old");
+    }
+
+    #[tokio::test]
+    async fn test_deleted_file_versioned_and_restored() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("sub")).unwrap();
+        fs::write(dir.path().join("sub/a.txt"), b"keep me").unwrap();
+        let (mut plugin, _rx) = plugin_with_folder(dir.path()).await;
+        plugin
+            .sync_folders
+            .write()
+            .await
+            .get_mut("docs")
+            .unwrap()
+            .versioning = true;
+        let mut device = create_test_device();
+
+        let packet = Packet::new(
+            "cconnect.filesync.delete",
+            serde_json::json!({ "folderId": "docs", "path": "sub/a.txt" }),
+        );
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        assert!(!dir.path().join("sub/a.txt").exists());
+
+        // The archived version is not part of the index
+        let index = plugin.generate_index("docs").await.unwrap();
+        assert!(index
+            .files
+            .iter()
+            .all(|f| !f.path.starts_with(VERSIONS_DIR)));
+
+        let path = Path::new("sub/a.txt");
+        let versions = plugin.list_versions("docs", path).await.unwrap();
+        assert_eq!(versions.len(), 1);
+
+        plugin
+            .restore_version("docs", path, versions[0].timestamp)
+            .await
+            .unwrap();
+        assert_eq!(fs::read(dir.path().join(path)).unwrap(), b"keep me");
+        assert_eq!(plugin.list_versions("docs", path).await.unwrap().len(), 1);
+        assert!(plugin.restore_version("docs", path, 1).await.is_err());
+    }
+
+    #[test]
+    fn test_sync_index_upsert() {
+        let mut index = SyncIndex {
+            folder_id: "docs".to_string(),
+            files: Vec::new(),
+            timestamp: 0,
+            total_size: 0,
+            file_count: 0,
+        };
+        let file = |size| FileMetadata {
+            path: PathBuf::from("a.txt"),
+            size,
+            modified: 0,
+            hash: String::new(),
+            is_dir: false,
+            permissions: None,
+        };
+
+        index.upsert_file(file(10));
+        index.upsert_file(file(25));
+
+        assert_eq!(index.file_count, 1);
+        assert_eq!(index.total_size, 25);
+        assert!(index.timestamp > 0);
+    }
+
+    #[test]
+    fn test_ignore_patterns_gitignore_semantics() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::create_dir_all(root.join("src/build")).unwrap();
+        fs::write(root.join("foo.log"), b"x").unwrap();
+        fs::write(root.join("src/debug.log"), b"x").unwrap();
+        fs::write(root.join("important.log"), b"x").unwrap();
+        fs::write(root.join("node_modules/pkg/index.js"), b"x").unwrap();
+        fs::write(root.join("node_modules/important.log"), b"x").unwrap();
+        fs::write(root.join("main.rs"), b"x").unwrap();
+        fs::write(root.join("build"), b"x").unwrap();
+        fs::write(root.join("src/build/out.o"), b"x").unwrap();
+
+        let config = SyncFolder {
+            folder_id: "docs".to_string(),
+            local_path: root.to_path_buf(),
+            remote_path: PathBuf::from("/remote"),
+            enabled: true,
+            bidirectional: true,
+            ignore_patterns: vec![
+                "*.log".to_string(),
+                "node_modules/".to_string(),
+                "!important.log".to_string(),
+                "build/".to_string(),
+            ],
+            conflict_strategy: ConflictStrategy::default(),
+            versioning: false,
+            version_keep: 5,
+            scan_interval_secs: 60,
+            bandwidth_limit_kbps: 0,
+        };
+
+        let index = FileSyncPlugin::scan_folder("docs", &config, None).unwrap();
+        let mut paths: Vec<String> = index
+            .files
+            .iter()
+            .map(|f| f.path.to_string_lossy().to_string())
+            .collect();
+        paths.sort();
+
+        // `build/` only matches directories, so the file named `build` stays
+        assert_eq!(paths, vec!["build", "important.log", "main.rs", "src"]);
+    }
+
+    #[test]
+    fn test_ignore_matcher_anchoring() {
+        let matcher = IgnoreMatcher::new(["/target", "docs/*.md", "**/cache/**"]).unwrap();
+
+        assert!(matcher.is_ignored(Path::new("target"), true));
+        assert!(!matcher.is_ignored(Path::new("crates/target"), true));
+        assert!(matcher.is_ignored(Path::new("docs/a.md"), false));
+        assert!(!matcher.is_ignored(Path::new("docs/sub/a.md"), false));
+        assert!(matcher.is_ignored(Path::new("a/b/cache/c/d.bin"), false));
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_files_not_rehashed() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_dir = tempfile::tempdir().unwrap();
+        let db_path = db_dir.path().join("sync_state.db");
+        fs::write(dir.path().join("a.txt"), b"hello").unwrap();
+        fs::write(dir.path().join("b.txt"), b"world").unwrap();
+
+        let (mut plugin, _rx) = plugin_with_folder(dir.path()).await;
+        plugin.open_state_db(&db_path).unwrap();
+        let index = plugin.generate_index("docs").await.unwrap();
+
+        // Plant a stale hash for a.txt while keeping its size and mtime
+        let db = FileStateDb::open(&db_path).unwrap();
+        let mut files = index.files.clone();
+        for file in &mut files {
+            if file.path == Path::new("a.txt") {
+                file.hash = "cached".to_string();
+            }
+        }
+        db.store_folder("docs", &files).unwrap();
+
+        // A restarted plugin reuses the cached hash of the unchanged file
+        let (mut plugin, _rx) = plugin_with_folder(dir.path()).await;
+        plugin.open_state_db(&db_path).unwrap();
+        let hash_of = |index: &SyncIndex, path: &str| {
+            index
+                .files
+                .iter()
+                .find(|f| f.path == Path::new(path))
+                .unwrap()
+                .hash
+                .clone()
+        };
+        let index = plugin.generate_index("docs").await.unwrap();
+        assert_eq!(hash_of(&index, "a.txt"), "cached");
+        assert_eq!(
+            hash_of(&index, "b.txt"),
+            blake3::hash(b"world").to_hex().to_string()
+        );
+
+        // A size change invalidates the cached hash
+        fs::write(dir.path().join("a.txt"), b"hello again").unwrap();
+        let index = plugin.generate_index("docs").await.unwrap();
+        assert_eq!(
+            hash_of(&index, "a.txt"),
+            blake3::hash(b"hello again").to_hex().to_string()
+        );
+    }
 }
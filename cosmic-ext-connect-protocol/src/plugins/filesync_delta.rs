@@ -0,0 +1,595 @@
+//! Delta Transfers for File Sync
+//!
+//! rsync-style delta encoding, so a changed file is sent as the parts that
+//! differ instead of in full.
+//!
+//! 1. The receiver splits its current copy into fixed-size blocks and sends a
+//!    weak rolling checksum and a strong BLAKE3 checksum for each block
+//!    ([`compute_block_signatures`]).
+//! 2. The sender slides a window over its version of the file. Wherever the
+//!    window matches a block of the receiver it emits a copy instruction,
+//!    and literal bytes everywhere else ([`compute_delta`]).
+//! 3. The receiver rebuilds the file from its copy and the delta
+//!    ([`apply_delta`]).
+//!
+//! The weak checksum can be rolled forward one byte in constant time, so
+//! blocks are found at any offset, including after inserted or removed bytes.
+
+use crate::{ProtocolError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Smallest block size used for signatures
+pub const MIN_BLOCK_SIZE: usize = 2048;
+
+/// Most blocks in one signature, keeping it well within one packet
+pub const MAX_SIGNATURE_BLOCKS: u64 = 16384;
+
+/// Length of the truncated BLAKE3 checksum of a block
+const STRONG_LEN: usize = 16;
+
+/// Encoded size of a block signature: weak checksum and strong checksum
+const BLOCK_SIGNATURE_LEN: usize = 4 + STRONG_LEN;
+
+const OP_COPY: u8 = 0;
+const OP_LITERAL: u8 = 1;
+
+/// Block size for a file of `file_size` bytes
+///
+/// A power of two of at least [`MIN_BLOCK_SIZE`], large enough that the file
+/// has at most [`MAX_SIGNATURE_BLOCKS`] blocks.
+pub fn block_size_for(file_size: u64) -> usize {
+    let needed =
+        ((file_size + MAX_SIGNATURE_BLOCKS - 1) / MAX_SIGNATURE_BLOCKS).next_power_of_two();
+    (needed as usize).max(MIN_BLOCK_SIZE)
+}
+
+/// Adler-style checksum that can be rolled forward one byte at a time
+#[derive(Debug, Clone, Copy)]
+struct RollingChecksum {
+    a: u32,
+    b: u32,
+    len: u32,
+}
+
+impl RollingChecksum {
+    fn new(block: &[u8]) -> Self {
+        let len = block.len() as u32;
+        let mut a = 0u32;
+        let mut b = 0u32;
+        for (i, &byte) in block.iter().enumerate() {
+            a = a.wrapping_add(byte as u32);
+            b = b.wrapping_add((len - i as u32).wrapping_mul(byte as u32));
+        }
+        Self { a, b, len }
+    }
+
+    /// Slide the window one byte: drop `out` from the front, append `new`
+    fn roll(&mut self, out: u8, new: u8) {
+        self.a = self.a.wrapping_sub(out as u32).wrapping_add(new as u32);
+        self.b = self
+            .b
+            .wrapping_sub(self.len.wrapping_mul(out as u32))
+            .wrapping_add(self.a);
+    }
+
+    fn digest(&self) -> u32 {
+        (self.a & 0xffff) | (self.b << 16)
+    }
+}
+
+fn strong_checksum(block: &[u8]) -> [u8; STRONG_LEN] {
+    let mut strong = [0u8; STRONG_LEN];
+    strong.copy_from_slice(&blake3::hash(block).as_bytes()[..STRONG_LEN]);
+    strong
+}
+
+/// Checksums of one block of the receiver's file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockSignature {
+    /// Rolling checksum
+    pub weak: u32,
+
+    /// Truncated BLAKE3 checksum
+    pub strong: [u8; STRONG_LEN],
+}
+
+/// Block checksums of a file, sent in `cconnect.filesync.signature`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileSignature {
+    /// Size of each block; the last block may be shorter
+    #[serde(rename = "blockSize")]
+    pub block_size: usize,
+
+    /// Size of the file the signature was computed from
+    #[serde(rename = "fileSize")]
+    pub file_size: u64,
+
+    /// Block checksums in file order, base64 encoded on the wire
+    #[serde(with = "blocks_base64")]
+    pub blocks: Vec<BlockSignature>,
+}
+
+mod blocks_base64 {
+    use super::{BlockSignature, BLOCK_SIGNATURE_LEN, STRONG_LEN};
+    use base64::Engine;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(blocks: &[BlockSignature], s: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::with_capacity(blocks.len() * BLOCK_SIGNATURE_LEN);
+        for block in blocks {
+            bytes.extend_from_slice(&block.weak.to_le_bytes());
+            bytes.extend_from_slice(&block.strong);
+        }
+        s.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<BlockSignature>, D::Error> {
+        let encoded = String::deserialize(d)?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(de::Error::custom)?;
+        if bytes.len() % BLOCK_SIGNATURE_LEN != 0 {
+            return Err(de::Error::custom("truncated block signature"));
+        }
+
+        Ok(bytes
+            .chunks_exact(BLOCK_SIGNATURE_LEN)
+            .map(|chunk| {
+                let mut weak = [0u8; 4];
+                weak.copy_from_slice(&chunk[..4]);
+                let mut strong = [0u8; STRONG_LEN];
+                strong.copy_from_slice(&chunk[4..]);
+                BlockSignature {
+                    weak: u32::from_le_bytes(weak),
+                    strong,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Compute the block checksums of a file
+pub fn compute_block_signatures(
+    path: impl AsRef<Path>,
+    block_size: usize,
+) -> Result<FileSignature> {
+    if block_size == 0 {
+        return Err(ProtocolError::InvalidPacket(
+            "Block size must be greater than 0".to_string(),
+        ));
+    }
+
+    let mut file = fs::File::open(path).map_err(ProtocolError::Io)?;
+    let mut buffer = vec![0u8; block_size];
+    let mut blocks = Vec::new();
+    let mut file_size = 0u64;
+
+    loop {
+        let mut filled = 0;
+        while filled < block_size {
+            let count = file
+                .read(&mut buffer[filled..])
+                .map_err(ProtocolError::Io)?;
+            if count == 0 {
+                break;
+            }
+            filled += count;
+        }
+        if filled == 0 {
+            break;
+        }
+
+        let block = &buffer[..filled];
+        blocks.push(BlockSignature {
+            weak: RollingChecksum::new(block).digest(),
+            strong: strong_checksum(block),
+        });
+        file_size += filled as u64;
+
+        if filled < block_size {
+            break;
+        }
+    }
+
+    Ok(FileSignature {
+        block_size,
+        file_size,
+        blocks,
+    })
+}
+
+/// One instruction for rebuilding a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeltaOp {
+    /// Copy `count` consecutive blocks of the base file, starting at `block`
+    Copy { block: u64, count: u64 },
+    /// Insert these bytes
+    Literal(Vec<u8>),
+}
+
+/// Instructions rebuilding the sender's file from the receiver's copy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delta {
+    /// Block size of the signature the delta was computed against
+    pub block_size: usize,
+
+    /// Instructions in file order
+    pub ops: Vec<DeltaOp>,
+}
+
+impl Delta {
+    fn push_copy(&mut self, index: u64) {
+        if let Some(DeltaOp::Copy { block, count }) = self.ops.last_mut() {
+            if *block + *count == index {
+                *count += 1;
+                return;
+            }
+        }
+        self.ops.push(DeltaOp::Copy {
+            block: index,
+            count: 1,
+        });
+    }
+
+    fn push_literal(&mut self, data: &[u8]) {
+        if !data.is_empty() {
+            self.ops.push(DeltaOp::Literal(data.to_vec()));
+        }
+    }
+
+    /// Number of literal bytes, which is what the delta saves over a full copy
+    pub fn literal_bytes(&self) -> u64 {
+        self.ops
+            .iter()
+            .map(|op| match op {
+                DeltaOp::Literal(data) => data.len() as u64,
+                DeltaOp::Copy { .. } => 0,
+            })
+            .sum()
+    }
+
+    /// Serialize for sending as a payload
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.literal_bytes() as usize);
+        bytes.extend_from_slice(&(self.block_size as u64).to_le_bytes());
+        for op in &self.ops {
+            match op {
+                DeltaOp::Copy { block, count } => {
+                    bytes.push(OP_COPY);
+                    bytes.extend_from_slice(&block.to_le_bytes());
+                    bytes.extend_from_slice(&count.to_le_bytes());
+                }
+                DeltaOp::Literal(data) => {
+                    bytes.push(OP_LITERAL);
+                    bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+                    bytes.extend_from_slice(data);
+                }
+            }
+        }
+        bytes
+    }
+
+    /// Parse a delta produced by [`encode`](Self::encode)
+    ///
+    /// # Errors
+    ///
+    /// `InvalidPacket` if the data is truncated or malformed.
+    pub fn decode(mut bytes: &[u8]) -> Result<Self> {
+        fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+            if bytes.len() < len {
+                return Err(ProtocolError::InvalidPacket("Truncated delta".to_string()));
+            }
+            let (head, rest) = bytes.split_at(len);
+            *bytes = rest;
+            Ok(head)
+        }
+        fn take_u64(bytes: &mut &[u8]) -> Result<u64> {
+            let mut value = [0u8; 8];
+            value.copy_from_slice(take(bytes, 8)?);
+            Ok(u64::from_le_bytes(value))
+        }
+
+        let block_size = take_u64(&mut bytes)? as usize;
+        let mut ops = Vec::new();
+        while !bytes.is_empty() {
+            match take(&mut bytes, 1)?[0] {
+                OP_COPY => {
+                    let block = take_u64(&mut bytes)?;
+                    let count = take_u64(&mut bytes)?;
+                    ops.push(DeltaOp::Copy { block, count });
+                }
+                OP_LITERAL => {
+                    let len = take_u64(&mut bytes)? as usize;
+                    ops.push(DeltaOp::Literal(take(&mut bytes, len)?.to_vec()));
+                }
+                op => {
+                    return Err(ProtocolError::InvalidPacket(format!(
+                        "Unknown delta instruction {}",
+                        op
+                    )))
+                }
+            }
+        }
+
+        Ok(Self { block_size, ops })
+    }
+}
+
+/// Compute the delta turning the receiver's file into the file at `path`
+///
+/// `signature` is the receiver's signature of its copy.
+pub fn compute_delta(path: impl AsRef<Path>, signature: &FileSignature) -> Result<Delta> {
+    let data = fs::read(path).map_err(ProtocolError::Io)?;
+    let block_size = signature.block_size;
+    let mut delta = Delta {
+        block_size,
+        ops: Vec::new(),
+    };
+
+    if block_size == 0 {
+        delta.push_literal(&data);
+        return Ok(delta);
+    }
+
+    // Full blocks are found by rolling; a short last block only at the end
+    let tail_len = (signature.file_size % block_size as u64) as usize;
+    let full_blocks = signature
+        .blocks
+        .len()
+        .saturating_sub(usize::from(tail_len > 0));
+    let mut by_weak: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (index, block) in signature.blocks[..full_blocks].iter().enumerate() {
+        by_weak.entry(block.weak).or_default().push(index);
+    }
+
+    let find_block = |window: &[u8], weak: u32| -> Option<usize> {
+        let candidates = by_weak.get(&weak)?;
+        let strong = strong_checksum(window);
+        candidates
+            .iter()
+            .copied()
+            .find(|&index| signature.blocks[index].strong == strong)
+    };
+
+    let mut literal_start = 0;
+    let mut offset = 0;
+    let mut rolling: Option<RollingChecksum> = None;
+
+    while data.len() - offset >= block_size {
+        let window = &data[offset..offset + block_size];
+        let checksum = *rolling.get_or_insert_with(|| RollingChecksum::new(window));
+
+        if let Some(index) = find_block(window, checksum.digest()) {
+            delta.push_literal(&data[literal_start..offset]);
+            delta.push_copy(index as u64);
+            offset += block_size;
+            literal_start = offset;
+            rolling = None;
+        } else {
+            if let (Some(rolling), Some(&next)) = (rolling.as_mut(), data.get(offset + block_size))
+            {
+                rolling.roll(data[offset], next);
+            }
+            offset += 1;
+        }
+    }
+
+    let tail_start = data.len().saturating_sub(tail_len);
+    let tail_matches = tail_len > 0
+        && data.len() >= tail_len
+        && tail_start >= literal_start
+        && signature
+            .blocks
+            .last()
+            .is_some_and(|last| last.strong == strong_checksum(&data[tail_start..]));
+
+    if tail_matches {
+        delta.push_literal(&data[literal_start..tail_start]);
+        delta.push_copy(full_blocks as u64);
+    } else {
+        delta.push_literal(&data[literal_start..]);
+    }
+
+    Ok(delta)
+}
+
+/// Rebuild a file from the receiver's copy at `base_path` and a delta
+///
+/// Writes the result to `out_path`, synced to disk, and returns its size.
+///
+/// # Errors
+///
+/// `InvalidPacket` if the delta refers to blocks the base file does not have.
+pub fn apply_delta(
+    base_path: impl AsRef<Path>,
+    delta: &Delta,
+    out_path: impl AsRef<Path>,
+) -> Result<u64> {
+    let mut base = fs::File::open(base_path).map_err(ProtocolError::Io)?;
+    let base_size = base.metadata().map_err(ProtocolError::Io)?.len();
+    let out = fs::File::create(out_path).map_err(ProtocolError::Io)?;
+    let mut writer = BufWriter::new(out);
+    let mut written = 0u64;
+
+    for op in &delta.ops {
+        match op {
+            DeltaOp::Copy { block, count } => {
+                let start = block.saturating_mul(delta.block_size as u64);
+                let end = (block.saturating_add(*count))
+                    .saturating_mul(delta.block_size as u64)
+                    .min(base_size);
+                if *count == 0 || start >= end {
+                    return Err(ProtocolError::InvalidPacket(format!(
+                        "Delta copies blocks {}..{} beyond the base file",
+                        block,
+                        block.saturating_add(*count)
+                    )));
+                }
+
+                base.seek(SeekFrom::Start(start))
+                    .map_err(ProtocolError::Io)?;
+                let copied = std::io::copy(&mut (&mut base).take(end - start), &mut writer)
+                    .map_err(ProtocolError::Io)?;
+                written += copied;
+            }
+            DeltaOp::Literal(data) => {
+                writer.write_all(data).map_err(ProtocolError::Io)?;
+                written += data.len() as u64;
+            }
+        }
+    }
+
+    let out = writer
+        .into_inner()
+        .map_err(|e| ProtocolError::Io(e.into_error()))?;
+    out.sync_all().map_err(ProtocolError::Io)?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random bytes
+    fn test_data(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                (state >> 33) as u8
+            })
+            .collect()
+    }
+
+    fn roundtrip(base: &[u8], new: &[u8], block_size: usize) -> (Vec<u8>, Delta) {
+        let dir = tempfile::tempdir().unwrap();
+        let base_path = dir.path().join("base");
+        let new_path = dir.path().join("new");
+        let out_path = dir.path().join("out");
+        fs::write(&base_path, base).unwrap();
+        fs::write(&new_path, new).unwrap();
+
+        let signature = compute_block_signatures(&base_path, block_size).unwrap();
+        let delta = compute_delta(&new_path, &signature).unwrap();
+        let decoded = Delta::decode(&delta.encode()).unwrap();
+        assert_eq!(decoded, delta);
+
+        let written = apply_delta(&base_path, &decoded, &out_path).unwrap();
+        assert_eq!(written, new.len() as u64);
+        (fs::read(&out_path).unwrap(), delta)
+    }
+
+    #[test]
+    fn test_rolling_checksum_matches_fresh() {
+        let data = test_data(64, 1);
+        let mut rolling = RollingChecksum::new(&data[..16]);
+        for offset in 1..=48 {
+            rolling.roll(data[offset - 1], data[offset + 15]);
+            assert_eq!(
+                rolling.digest(),
+                RollingChecksum::new(&data[offset..offset + 16]).digest()
+            );
+        }
+    }
+
+    #[test]
+    fn test_small_change_in_large_file() {
+        let base = test_data(8 * 1024 * 1024, 7);
+        let mut new = base.clone();
+        let changed = test_data(1024, 99);
+        new[3_000_000..3_001_024].copy_from_slice(&changed);
+
+        let block_size = block_size_for(base.len() as u64);
+        let (rebuilt, delta) = roundtrip(&base, &new, block_size);
+
+        assert_eq!(rebuilt, new);
+        assert!(
+            delta.encode().len() <= 2 * block_size + 64,
+            "delta of {} bytes",
+            delta.encode().len()
+        );
+    }
+
+    #[test]
+    fn test_insertion_realigns() {
+        let base = test_data(64 * 1024, 3);
+        let mut new = base.clone();
+        new.splice(10_000..10_000, b"inserted bytes".iter().copied());
+        new.truncate(new.len() - 100);
+
+        let (rebuilt, delta) = roundtrip(&base, &new, 2048);
+        assert_eq!(rebuilt, new);
+        assert!(delta.literal_bytes() < 3 * 2048);
+    }
+
+    #[test]
+    fn test_unrelated_and_empty_files() {
+        let base = test_data(10_000, 1);
+        let new = test_data(7_777, 2);
+        let (rebuilt, delta) = roundtrip(&base, &new, 2048);
+        assert_eq!(rebuilt, new);
+        assert_eq!(delta.literal_bytes(), new.len() as u64);
+
+        let (rebuilt, _) = roundtrip(&[], &new, 2048);
+        assert_eq!(rebuilt, new);
+        let (rebuilt, _) = roundtrip(&base, &[], 2048);
+        assert!(rebuilt.is_empty());
+    }
+
+    #[test]
+    fn test_short_last_block_copied() {
+        let base = test_data(5000, 4);
+        let mut new = base.clone();
+        new[100] ^= 0xff;
+
+        let (rebuilt, delta) = roundtrip(&base, &new, 2048);
+        assert_eq!(rebuilt, new);
+        assert_eq!(delta.literal_bytes(), 2048);
+    }
+
+    #[test]
+    fn test_signature_serde_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        fs::write(&path, test_data(5000, 5)).unwrap();
+
+        let signature = compute_block_signatures(&path, 2048).unwrap();
+        assert_eq!(signature.blocks.len(), 3);
+        assert_eq!(signature.file_size, 5000);
+
+        let json = serde_json::to_value(&signature).unwrap();
+        assert!(json["blocks"].is_string());
+        let parsed: FileSignature = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, signature);
+    }
+
+    #[test]
+    fn test_invalid_delta_rejected() {
+        assert!(Delta::decode(&[1, 2, 3]).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let base_path = dir.path().join("base");
+        fs::write(&base_path, b"short").unwrap();
+        let delta = Delta {
+            block_size: 2048,
+            ops: vec![DeltaOp::Copy { block: 4, count: 1 }],
+        };
+        let err = apply_delta(&base_path, &delta, dir.path().join("out")).unwrap_err();
+        assert!(matches!(err, ProtocolError::InvalidPacket(_)));
+    }
+
+    #[test]
+    fn test_block_size_bounds_signature() {
+        assert_eq!(block_size_for(0), MIN_BLOCK_SIZE);
+        assert_eq!(block_size_for(1024 * 1024), MIN_BLOCK_SIZE);
+        let block_size = block_size_for(100 * 1024 * 1024);
+        assert_eq!(block_size, 8192);
+        assert!(100 * 1024 * 1024 / block_size as u64 <= MAX_SIGNATURE_BLOCKS);
+    }
+}
@@ -0,0 +1,233 @@
+//! SQLite Hash Cache for File Sync
+//!
+//! Hashing every file of a large sync folder on each scan is slow. The
+//! BLAKE3 hash of each file is stored together with the size and modification
+//! time it was computed for, so a rescan only hashes files whose size or
+//! modification time changed.
+//!
+//! ## Database Schema
+//!
+//! ```sql
+//! CREATE TABLE file_state (
+//!     folder_id TEXT NOT NULL,
+//!     path TEXT NOT NULL,
+//!     size INTEGER NOT NULL,
+//!     modified INTEGER NOT NULL,
+//!     hash TEXT NOT NULL,
+//!     PRIMARY KEY (folder_id, path)
+//! );
+//! ```
+//!
+//! ## Storage Location
+//!
+//! Kept in the plugin's `sync_state.db` next to the saved sync indexes:
+//! `~/.config/cconnect/<device_id>/filesync/sync_state.db`
+
+use super::filesync::FileMetadata;
+use crate::{ProtocolError, Result};
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+/// Hash of a file as of its last scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedHash {
+    /// File size in bytes when hashed
+    pub size: u64,
+
+    /// Last modified timestamp (milliseconds since epoch) when hashed
+    pub modified: i64,
+
+    /// BLAKE3 hash of file content
+    pub hash: String,
+}
+
+impl CachedHash {
+    /// The cached hash if the file is unchanged since it was hashed
+    pub fn hash_if_unchanged(&self, size: u64, modified: i64) -> Option<&str> {
+        (self.size == size && self.modified == modified).then_some(self.hash.as_str())
+    }
+}
+
+/// SQLite-backed cache of file hashes, keyed by folder and relative path
+#[derive(Clone)]
+pub struct FileStateDb {
+    conn: Arc<Mutex<Connection>>,
+}
+
+fn db_error(context: &str, e: rusqlite::Error) -> ProtocolError {
+    ProtocolError::Plugin(format!("{}: {}", context, e))
+}
+
+impl FileStateDb {
+    /// Open or create the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                ProtocolError::Plugin(format!("Failed to create database directory: {}", e))
+            })?;
+        }
+
+        let conn = Connection::open(path).map_err(|e| db_error("Failed to open database", e))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS file_state (
+                folder_id TEXT NOT NULL,
+                path TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                modified INTEGER NOT NULL,
+                hash TEXT NOT NULL,
+                PRIMARY KEY (folder_id, path)
+            );",
+        )
+        .map_err(|e| db_error("Failed to create file_state table", e))?;
+
+        debug!("Opened file sync state database at {}", path.display());
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| ProtocolError::invalid_state("File sync state database lock poisoned"))
+    }
+
+    /// Cached hashes of a folder's files, keyed by relative path
+    pub fn folder_hashes(&self, folder_id: &str) -> Result<HashMap<PathBuf, CachedHash>> {
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare("SELECT path, size, modified, hash FROM file_state WHERE folder_id = ?1")
+            .map_err(|e| db_error("Failed to query file state", e))?;
+
+        let rows = stmt
+            .query_map(params![folder_id], |row| {
+                Ok((
+                    PathBuf::from(row.get::<_, String>(0)?),
+                    CachedHash {
+                        size: row.get::<_, i64>(1)? as u64,
+                        modified: row.get(2)?,
+                        hash: row.get(3)?,
+                    },
+                ))
+            })
+            .map_err(|e| db_error("Failed to query file state", e))?;
+
+        rows.collect::<rusqlite::Result<_>>()
+            .map_err(|e| db_error("Failed to read file state", e))
+    }
+
+    /// Replace a folder's cached hashes with those of a fresh scan
+    ///
+    /// Directories are skipped, and files no longer in the scan are dropped.
+    pub fn store_folder(&self, folder_id: &str, files: &[FileMetadata]) -> Result<()> {
+        let mut conn = self.lock()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| db_error("Failed to start transaction", e))?;
+
+        tx.execute(
+            "DELETE FROM file_state WHERE folder_id = ?1",
+            params![folder_id],
+        )
+        .map_err(|e| db_error("Failed to clear file state", e))?;
+        for file in files.iter().filter(|f| !f.is_dir) {
+            tx.execute(
+                "INSERT INTO file_state (folder_id, path, size, modified, hash)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    folder_id,
+                    file.path.to_string_lossy().to_string(),
+                    file.size as i64,
+                    file.modified,
+                    file.hash
+                ],
+            )
+            .map_err(|e| db_error("Failed to store file state", e))?;
+        }
+
+        tx.commit()
+            .map_err(|e| db_error("Failed to commit file state", e))
+    }
+
+    /// Drop all cached hashes of a folder
+    pub fn remove_folder(&self, folder_id: &str) -> Result<()> {
+        self.lock()?
+            .execute(
+                "DELETE FROM file_state WHERE folder_id = ?1",
+                params![folder_id],
+            )
+            .map_err(|e| db_error("Failed to clear file state", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, size: u64, modified: i64, hash: &str) -> FileMetadata {
+        FileMetadata {
+            path: PathBuf::from(path),
+            size,
+            modified,
+            hash: hash.to_string(),
+            is_dir: false,
+            permissions: None,
+        }
+    }
+
+    #[test]
+    fn test_state_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("state").join("sync_state.db");
+
+        let db = FileStateDb::open(&db_path).unwrap();
+        db.store_folder("docs", &[file("a.txt", 5, 1000, "aaa")])
+            .unwrap();
+        drop(db);
+
+        let db = FileStateDb::open(&db_path).unwrap();
+        let hashes = db.folder_hashes("docs").unwrap();
+        let cached = &hashes[&PathBuf::from("a.txt")];
+        assert_eq!(cached.hash_if_unchanged(5, 1000), Some("aaa"));
+        assert!(db.folder_hashes("other").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_changed_size_or_mtime_invalidates() {
+        let cached = CachedHash {
+            size: 5,
+            modified: 1000,
+            hash: "aaa".to_string(),
+        };
+
+        assert_eq!(cached.hash_if_unchanged(5, 1000), Some("aaa"));
+        assert_eq!(cached.hash_if_unchanged(6, 1000), None);
+        assert_eq!(cached.hash_if_unchanged(5, 1001), None);
+    }
+
+    #[test]
+    fn test_store_replaces_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = FileStateDb::open(dir.path().join("sync_state.db")).unwrap();
+
+        db.store_folder(
+            "docs",
+            &[file("a.txt", 1, 1, "a"), file("b.txt", 1, 1, "b")],
+        )
+        .unwrap();
+        db.store_folder("docs", &[file("b.txt", 2, 2, "b2")])
+            .unwrap();
+
+        let hashes = db.folder_hashes("docs").unwrap();
+        assert_eq!(hashes.len(), 1);
+        assert_eq!(hashes[&PathBuf::from("b.txt")].hash, "b2");
+
+        db.remove_folder("docs").unwrap();
+        assert!(db.folder_hashes("docs").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,286 @@
+//! File Versioning for File Sync
+//!
+//! Before a synced file is overwritten or deleted, its old content is kept in
+//! the folder's `.stversions` directory, at the file's relative path with the
+//! archive time appended:
+//!
+//! ```text
+//! <folder>/.stversions/<relative_path>.<timestamp>
+//! ```
+//!
+//! The timestamp is in milliseconds since the epoch. Only the `keep` most
+//! recent versions of each file are kept; older ones are pruned when a new
+//! version is archived. The `.stversions` directory is never indexed or
+//! synced.
+
+use crate::{ProtocolError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// Name of the versions directory inside a sync folder
+pub const VERSIONS_DIR: &str = ".stversions";
+
+/// Serializes archiving and pruning, so concurrent transfers into the same
+/// folder cannot pick the same version name or prune each other's versions
+static VERSIONS_LOCK: Mutex<()> = Mutex::new(());
+
+/// An archived version of a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileVersion {
+    /// Archive time (milliseconds since epoch)
+    pub timestamp: i64,
+
+    /// Size of the archived content in bytes
+    pub size: u64,
+
+    /// Location of the archived content
+    #[serde(rename = "storedPath")]
+    pub stored_path: PathBuf,
+}
+
+/// Old versions of the files of one sync folder
+#[derive(Debug, Clone)]
+pub struct VersionStore {
+    root: PathBuf,
+    keep: usize,
+}
+
+impl VersionStore {
+    /// Versions of the folder at `root`, keeping `keep` per file
+    pub fn new(root: impl Into<PathBuf>, keep: usize) -> Self {
+        Self {
+            root: root.into(),
+            keep,
+        }
+    }
+
+    fn lock() -> Result<std::sync::MutexGuard<'static, ()>> {
+        VERSIONS_LOCK
+            .lock()
+            .map_err(|_| ProtocolError::invalid_state("File versions lock poisoned"))
+    }
+
+    /// Directory holding the versions of `relative_path`, and their name prefix
+    fn location(&self, relative_path: &Path) -> Result<(PathBuf, String)> {
+        let name = relative_path
+            .file_name()
+            .ok_or_else(|| {
+                ProtocolError::InvalidPacket(format!(
+                    "Invalid path for versioning: {}",
+                    relative_path.display()
+                ))
+            })?
+            .to_string_lossy();
+        let dir = self
+            .root
+            .join(VERSIONS_DIR)
+            .join(relative_path.parent().unwrap_or(Path::new("")));
+        if !dir.starts_with(self.root.join(VERSIONS_DIR)) {
+            return Err(ProtocolError::InvalidPacket(format!(
+                "Invalid path for versioning: {}",
+                relative_path.display()
+            )));
+        }
+        Ok((dir, format!("{}.", name)))
+    }
+
+    /// Keep the current content of `relative_path` as a new version
+    ///
+    /// The file itself is left in place; the caller replaces or deletes it
+    /// afterwards. Returns `None` if there is no such file. Versions beyond
+    /// the retention limit are pruned.
+    pub fn archive(&self, relative_path: &Path) -> Result<Option<FileVersion>> {
+        let source = self.root.join(relative_path);
+        match fs::metadata(&source) {
+            Ok(metadata) if metadata.is_file() => {}
+            _ => return Ok(None),
+        }
+
+        let (dir, prefix) = self.location(relative_path)?;
+        let _guard = Self::lock()?;
+        fs::create_dir_all(&dir).map_err(ProtocolError::Io)?;
+
+        // Never reuse a name, even for two versions within one millisecond
+        let mut timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        let latest = self
+            .list_unlocked(&dir, &prefix)?
+            .first()
+            .map(|v| v.timestamp);
+        if let Some(latest) = latest {
+            timestamp = timestamp.max(latest + 1);
+        }
+        let stored_path = dir.join(format!("{}{}", prefix, timestamp));
+
+        // A hard link keeps the content without copying; fall back to a copy
+        // on filesystems without links
+        if fs::hard_link(&source, &stored_path).is_err() {
+            fs::copy(&source, &stored_path).map_err(ProtocolError::Io)?;
+        }
+        let size = fs::metadata(&stored_path).map_err(ProtocolError::Io)?.len();
+        debug!(
+            "Archived {} as version {}",
+            relative_path.display(),
+            timestamp
+        );
+
+        for old in self.list_unlocked(&dir, &prefix)?.iter().skip(self.keep) {
+            if let Err(e) = fs::remove_file(&old.stored_path) {
+                warn!(
+                    "Failed to prune version {}: {}",
+                    old.stored_path.display(),
+                    e
+                );
+            }
+        }
+
+        Ok(Some(FileVersion {
+            timestamp,
+            size,
+            stored_path,
+        }))
+    }
+
+    /// Versions of `relative_path`, newest first
+    pub fn list(&self, relative_path: &Path) -> Result<Vec<FileVersion>> {
+        let (dir, prefix) = self.location(relative_path)?;
+        let _guard = Self::lock()?;
+        self.list_unlocked(&dir, &prefix)
+    }
+
+    fn list_unlocked(&self, dir: &Path, prefix: &str) -> Result<Vec<FileVersion>> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(ProtocolError::Io(e)),
+        };
+
+        let mut versions = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(ProtocolError::Io)?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(timestamp) = name
+                .strip_prefix(prefix)
+                .and_then(|suffix| suffix.parse::<i64>().ok())
+            else {
+                continue;
+            };
+            let metadata = entry.metadata().map_err(ProtocolError::Io)?;
+            if metadata.is_file() {
+                versions.push(FileVersion {
+                    timestamp,
+                    size: metadata.len(),
+                    stored_path: entry.path(),
+                });
+            }
+        }
+
+        versions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(versions)
+    }
+
+    /// Find the version of `relative_path` archived at `timestamp`
+    pub fn find(&self, relative_path: &Path, timestamp: i64) -> Result<FileVersion> {
+        self.list(relative_path)?
+            .into_iter()
+            .find(|version| version.timestamp == timestamp)
+            .ok_or_else(|| {
+                ProtocolError::Plugin(format!(
+                    "No version {} of {}",
+                    timestamp,
+                    relative_path.display()
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_and_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = VersionStore::new(dir.path(), 5);
+        let path = Path::new("sub/notes.txt");
+        fs::create_dir_all(dir.path().join("sub")).unwrap();
+
+        assert!(store.archive(path).unwrap().is_none());
+
+        fs::write(dir.path().join(path), b"one").unwrap();
+        let first = store.archive(path).unwrap().unwrap();
+        fs::write(dir.path().join(path), b"two!").unwrap();
+        let second = store.archive(path).unwrap().unwrap();
+
+        assert!(second.timestamp > first.timestamp);
+        assert!(first
+            .stored_path
+            .starts_with(dir.path().join(VERSIONS_DIR).join("sub")));
+
+        let versions = store.list(path).unwrap();
+        assert_eq!(versions, vec![second.clone(), first.clone()]);
+        assert_eq!(fs::read(&first.stored_path).unwrap(), b"one");
+        assert_eq!(fs::read(&second.stored_path).unwrap(), b"two!");
+        assert_eq!(store.find(path, first.timestamp).unwrap(), first);
+        assert!(store.find(path, 1).is_err());
+    }
+
+    #[test]
+    fn test_prune_keeps_most_recent() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = VersionStore::new(dir.path(), 2);
+        let path = Path::new("a.txt");
+
+        for content in ["1", "2", "3", "4"] {
+            fs::write(dir.path().join(path), content).unwrap();
+            store.archive(path).unwrap();
+        }
+
+        let versions = store.list(path).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(fs::read(&versions[0].stored_path).unwrap(), b"4");
+        assert_eq!(fs::read(&versions[1].stored_path).unwrap(), b"3");
+    }
+
+    #[test]
+    fn test_similar_names_kept_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = VersionStore::new(dir.path(), 5);
+        fs::write(dir.path().join("a.txt"), b"a").unwrap();
+        fs::write(dir.path().join("a.txt.bak"), b"bak").unwrap();
+        store.archive(Path::new("a.txt")).unwrap();
+        store.archive(Path::new("a.txt.bak")).unwrap();
+
+        assert_eq!(store.list(Path::new("a.txt")).unwrap().len(), 1);
+        assert_eq!(store.list(Path::new("a.txt.bak")).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_archives_respect_retention() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = VersionStore::new(dir.path(), 3);
+        fs::write(dir.path().join("a.txt"), b"a").unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let store = store.clone();
+                std::thread::spawn(move || store.archive(Path::new("a.txt")).unwrap())
+            })
+            .collect();
+        let mut timestamps: Vec<i64> = handles
+            .into_iter()
+            .map(|h| h.join().unwrap().unwrap().timestamp)
+            .collect();
+        timestamps.sort();
+        timestamps.dedup();
+
+        assert_eq!(timestamps.len(), 8);
+        assert_eq!(store.list(Path::new("a.txt")).unwrap().len(), 3);
+    }
+}
@@ -8,17 +8,47 @@
 //!
 //! **Packet Types**:
 //! - `cconnect.findmyphone.request` - Ring request (bidirectional)
+//! - `cconnect.findmyphone.ack` - Ringing stopped (bidirectional)
 //!
 //! **Capabilities**:
-//! - Incoming: `cconnect.findmyphone.request` - Receive ring requests
-//! - Outgoing: `cconnect.findmyphone.request` - Send ring requests
+//! - Incoming: `cconnect.findmyphone.request`, `cconnect.findmyphone.ack`
+//! - Outgoing: `cconnect.findmyphone.request`, `cconnect.findmyphone.ack`
+//!
+//! ## Packet Format
+//!
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.findmyphone.request",
+//!     "body": {
+//!         "stop": true
+//!     }
+//! }
+//! ```
+//!
+//! An empty body starts ringing. Repeating it while ringing keeps the one
+//! ring going instead of starting another sound and overlay. `"stop": true`
+//! only ever stops it, so a stop request cannot start a ring that already
+//! ended.
 //!
 //! ## Behavior
 //!
-//! - Receiving a packet starts/stops ringing (toggle)
-//! - Sending a packet makes the remote device ring
+//! - Receiving a request makes the desktop ring; the applet shows a
+//!   full-screen overlay for as long as it rings
+//! - Ringing stops when the overlay is dismissed or the device cancels
+//! - Sending a request makes the remote device ring
+//! - Whenever ringing stops (local dismissal or remote stop) an ack is sent
+//!   back so the requesting side knows the device was found
+//! - Requests are answered with an ack instead of ringing while muted by policy
 //! - Sound plays using system audio (PulseAudio/PipeWire)
 //!
+//! ## Ring Policy
+//!
+//! The sound follows the system settings (see [`RingMode::from_system`]):
+//! it plays at the current output volume, which is never raised, and is
+//! skipped while COSMIC's do-not-disturb is on or the output is muted. The
+//! overlay is shown either way.
+//!
 //! ## Sound Playback
 //!
 //! Tries multiple methods in order:
@@ -32,7 +62,7 @@
 //! - [KDE Connect FindMyPhone](https://github.com/KDE/kdeconnect-android)
 //! - [Valent Protocol](https://valent.andyholmes.ca/documentation/protocol.html)
 
-use crate::{Device, Packet, Result};
+use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use serde_json::json;
 use std::any::Any;
@@ -49,6 +79,9 @@ pub const PACKET_TYPE_FINDMYPHONE_REQUEST: &str = "cconnect.findmyphone.request"
 /// KDE Connect compatible packet type
 const PACKET_TYPE_KDECONNECT_FINDMYPHONE: &str = "kdeconnect.findmyphone.request";
 
+/// Packet type acknowledging that ringing has stopped
+pub const PACKET_TYPE_FINDMYPHONE_ACK: &str = "cconnect.findmyphone.ack";
+
 /// System sound files to try (in order of preference)
 const SYSTEM_SOUNDS: &[&str] = &[
     "/usr/share/sounds/freedesktop/stereo/phone-incoming-call.oga",
@@ -58,6 +91,64 @@ const SYSTEM_SOUNDS: &[&str] = &[
     "/usr/share/sounds/Yaru/stereo/phone-incoming-call.oga",
 ];
 
+/// cosmic-config entry of the COSMIC notifications do-not-disturb switch
+const COSMIC_DND_CONFIG: &str = "cosmic/com.system76.CosmicNotifications/v1/do_not_disturb";
+
+/// How a ring on this desktop is presented
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RingMode {
+    /// Play the ring sound at the current output volume
+    Audible,
+    /// Only show the overlay
+    Silent,
+}
+
+impl RingMode {
+    /// Ring mode allowed by the system policy
+    ///
+    /// Silent while do-not-disturb is on or the default output is muted or
+    /// at zero volume.
+    pub fn from_system() -> Self {
+        if do_not_disturb() {
+            debug!("Do-not-disturb is on, ringing silently");
+            Self::Silent
+        } else if output_muted() {
+            debug!("Audio output is muted, ringing silently");
+            Self::Silent
+        } else {
+            Self::Audible
+        }
+    }
+}
+
+/// Whether COSMIC's do-not-disturb is on
+fn do_not_disturb() -> bool {
+    dirs::config_dir()
+        .and_then(|dir| std::fs::read_to_string(dir.join(COSMIC_DND_CONFIG)).ok())
+        .is_some_and(|value| value.trim() == "true")
+}
+
+/// Whether the default audio output is muted or at zero volume
+fn output_muted() -> bool {
+    Command::new("wpctl")
+        .args(["get-volume", "@DEFAULT_AUDIO_SINK@"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| wpctl_output_muted(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse `wpctl get-volume` output (`Volume: 0.40` or `Volume: 0.40 [MUTED]`)
+fn wpctl_output_muted(output: &str) -> bool {
+    output.contains("[MUTED]")
+        || output
+            .trim()
+            .strip_prefix("Volume:")
+            .and_then(|s| s.split_whitespace().next())
+            .and_then(|s| s.parse::<f32>().ok())
+            .is_some_and(|volume| volume <= 0.0)
+}
+
 /// Find My Phone plugin for locating devices
 pub struct FindMyPhonePlugin {
     /// Device ID this plugin is attached to
@@ -71,6 +162,18 @@ pub struct FindMyPhonePlugin {
 
     /// Current sound process (if playing)
     sound_process: Option<Child>,
+
+    /// Packet sender for acks and ring requests
+    packet_sender: Option<tokio::sync::mpsc::Sender<(String, Packet)>>,
+
+    /// Whether we asked the remote device to ring and it has not acked yet
+    remote_ringing: bool,
+
+    /// Whether ring requests from this device are muted by policy
+    muted: bool,
+
+    /// Decides whether a ring plays sound
+    ring_mode: fn() -> RingMode,
 }
 
 impl FindMyPhonePlugin {
@@ -81,6 +184,10 @@ impl FindMyPhonePlugin {
             enabled: false,
             is_ringing: Arc::new(AtomicBool::new(false)),
             sound_process: None,
+            packet_sender: None,
+            remote_ringing: false,
+            muted: false,
+            ring_mode: RingMode::from_system,
         }
     }
 
@@ -122,6 +229,35 @@ impl FindMyPhonePlugin {
         Arc::clone(&self.is_ringing)
     }
 
+    /// Check if the remote device is ringing at our request
+    pub fn is_remote_ringing(&self) -> bool {
+        self.remote_ringing
+    }
+
+    /// Check if ring requests from this device are muted
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    /// Mute or unmute ring requests from this device
+    ///
+    /// Muting also silences (and acks) a ring that is already playing.
+    pub async fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        if muted && self.is_ringing() {
+            self.stop_ringing();
+            self.send_ack().await;
+        }
+    }
+
+    /// Apply the mute policy for the next ring request
+    ///
+    /// Unlike [`set_muted`](Self::set_muted) a playing ring is left alone;
+    /// the request itself silences and acks it once.
+    pub fn set_mute_policy(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
     /// Create a ring request packet
     ///
     /// This packet makes the remote device ring. Sending it again cancels the ring.
@@ -140,13 +276,70 @@ impl FindMyPhonePlugin {
         Packet::new(PACKET_TYPE_FINDMYPHONE_REQUEST, json!({}))
     }
 
-    /// Handle incoming ring request
-    async fn handle_ring_request(&mut self, device: &Device) -> Result<()> {
-        let currently_ringing = self.is_ringing.load(Ordering::SeqCst);
+    /// Create a stop request packet
+    ///
+    /// Unlike a plain ring request this never starts ringing on the remote.
+    pub fn create_stop_request(&self) -> Packet {
+        Packet::new(PACKET_TYPE_FINDMYPHONE_REQUEST, json!({ "stop": true }))
+    }
+
+    /// Create an ack packet telling the remote device ringing has stopped
+    pub fn create_ack(&self) -> Packet {
+        Packet::new(PACKET_TYPE_FINDMYPHONE_ACK, json!({}))
+    }
+
+    /// Make the remote device ring
+    pub async fn ring(&mut self) -> Result<()> {
+        let packet = self.create_ring_request();
+        self.send_packet(packet).await?;
+        self.remote_ringing = true;
+        Ok(())
+    }
 
-        if currently_ringing {
-            info!("Stopping ring (requested by {})", device.name());
+    /// Stop ringing on both ends
+    ///
+    /// Silences a local ring requested by this device (acking it) and cancels
+    /// a ring we requested on the remote device.
+    pub async fn stop_ring(&mut self) -> Result<()> {
+        if self.is_ringing() {
             self.stop_ringing();
+            self.send_ack().await;
+        }
+
+        if self.remote_ringing {
+            let packet = self.create_stop_request();
+            self.send_packet(packet).await?;
+            self.remote_ringing = false;
+        }
+
+        Ok(())
+    }
+
+    /// Handle incoming ring request
+    async fn handle_ring_request(&mut self, packet: &Packet, device: &Device) -> Result<()> {
+        let currently_ringing = self.is_ringing.load(Ordering::SeqCst);
+        let stop = packet
+            .body
+            .get("stop")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if stop {
+            if currently_ringing {
+                info!("Stopping ring (requested by {})", device.name());
+                self.stop_ringing();
+                self.send_ack().await;
+            } else {
+                debug!("Ignoring stop request from {}, not ringing", device.name());
+            }
+        } else if self.muted {
+            info!("Not ringing for {}: muted by policy", device.name());
+            if currently_ringing {
+                self.stop_ringing();
+            }
+            self.send_ack().await;
+        } else if currently_ringing {
+            debug!("Already ringing for {}", device.name());
         } else {
             info!("Starting ring (requested by {})", device.name());
             self.start_ringing();
@@ -155,25 +348,57 @@ impl FindMyPhonePlugin {
         Ok(())
     }
 
-    /// Start playing the ring sound
+    /// Tell the remote device ringing has stopped
+    async fn send_ack(&self) {
+        if let Err(e) = self.send_packet(self.create_ack()).await {
+            warn!("Failed to send find my phone ack: {}", e);
+        }
+    }
+
+    /// Send a packet to the connected device
+    async fn send_packet(&self, packet: Packet) -> Result<()> {
+        let sender = self
+            .packet_sender
+            .as_ref()
+            .ok_or_else(|| ProtocolError::Plugin("Packet sender not initialized".to_string()))?;
+
+        let device_id = self
+            .device_id
+            .as_ref()
+            .ok_or_else(|| ProtocolError::Plugin("Device ID not set".to_string()))?;
+
+        sender
+            .send((device_id.clone(), packet))
+            .await
+            .map_err(|e| ProtocolError::Plugin(format!("Failed to send packet: {}", e)))
+    }
+
+    /// Start ringing, playing the ring sound unless the policy silences it
     fn start_ringing(&mut self) {
-        // Try sound players in order of preference
-        let player_attempts: Vec<(&str, Option<Child>)> =
-            if let Some(sound_path) = Self::find_sound_file() {
-                vec![
-                    ("paplay", Self::play_with_paplay(sound_path)),
-                    ("canberra-gtk-play", Self::play_with_canberra(sound_path)),
-                    ("pw-play", Self::play_with_pwplay(sound_path)),
-                    ("sound event", Self::play_sound_event()),
-                ]
-            } else {
-                vec![("sound event", Self::play_sound_event())]
-            };
+        if self.is_ringing.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        if (self.ring_mode)() == RingMode::Silent {
+            info!("Ring started silently");
+            return;
+        }
 
-        for (player_name, child_option) in player_attempts {
-            if let Some(child) = child_option {
+        // Try sound players in order of preference, stopping at the first
+        // one that starts
+        let sound_path = Self::find_sound_file();
+        let players: [(&str, &dyn Fn() -> Option<Child>); 4] = [
+            ("paplay", &|| sound_path.and_then(Self::play_with_paplay)),
+            ("canberra-gtk-play", &|| {
+                sound_path.and_then(Self::play_with_canberra)
+            }),
+            ("pw-play", &|| sound_path.and_then(Self::play_with_pwplay)),
+            ("sound event", &Self::play_sound_event),
+        ];
+
+        for (player_name, play) in players {
+            if let Some(child) = play() {
                 self.sound_process = Some(child);
-                self.is_ringing.store(true, Ordering::SeqCst);
                 info!("Ring started using {}", player_name);
                 return;
             }
@@ -181,7 +406,6 @@ impl FindMyPhonePlugin {
 
         // Last resort: send notification
         Self::send_notification();
-        self.is_ringing.store(true, Ordering::SeqCst);
         warn!("No sound player available, using notification fallback");
     }
 
@@ -266,7 +490,7 @@ impl FindMyPhonePlugin {
     }
 
     /// Check if a ring request packet
-    fn is_ring_request(packet: &Packet) -> bool {
+    pub fn is_ring_request(packet: &Packet) -> bool {
         packet.is_type(PACKET_TYPE_FINDMYPHONE_REQUEST)
             || packet.is_type(PACKET_TYPE_KDECONNECT_FINDMYPHONE)
     }
@@ -303,19 +527,24 @@ impl Plugin for FindMyPhonePlugin {
         vec![
             PACKET_TYPE_FINDMYPHONE_REQUEST.to_string(),
             PACKET_TYPE_KDECONNECT_FINDMYPHONE.to_string(),
+            PACKET_TYPE_FINDMYPHONE_ACK.to_string(),
         ]
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec![PACKET_TYPE_FINDMYPHONE_REQUEST.to_string()]
+        vec![
+            PACKET_TYPE_FINDMYPHONE_REQUEST.to_string(),
+            PACKET_TYPE_FINDMYPHONE_ACK.to_string(),
+        ]
     }
 
     async fn init(
         &mut self,
         device: &Device,
-        _packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
+        packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
     ) -> Result<()> {
         self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
         info!(
             "Find My Phone plugin initialized for device {}",
             device.name()
@@ -333,6 +562,7 @@ impl Plugin for FindMyPhonePlugin {
         info!("Find My Phone plugin stopped");
         self.enabled = false;
         self.stop_ringing();
+        self.remote_ringing = false;
         Ok(())
     }
 
@@ -343,7 +573,10 @@ impl Plugin for FindMyPhonePlugin {
         }
 
         if Self::is_ring_request(packet) {
-            self.handle_ring_request(device).await?;
+            self.handle_ring_request(packet, device).await?;
+        } else if packet.is_type(PACKET_TYPE_FINDMYPHONE_ACK) {
+            info!("{} stopped ringing", device.name());
+            self.remote_ringing = false;
         }
 
         Ok(())
@@ -363,11 +596,15 @@ impl PluginFactory for FindMyPhonePluginFactory {
         vec![
             PACKET_TYPE_FINDMYPHONE_REQUEST.to_string(),
             PACKET_TYPE_KDECONNECT_FINDMYPHONE.to_string(),
+            PACKET_TYPE_FINDMYPHONE_ACK.to_string(),
         ]
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec![PACKET_TYPE_FINDMYPHONE_REQUEST.to_string()]
+        vec![
+            PACKET_TYPE_FINDMYPHONE_REQUEST.to_string(),
+            PACKET_TYPE_FINDMYPHONE_ACK.to_string(),
+        ]
     }
 
     fn create(&self) -> Box<dyn Plugin> {
@@ -420,9 +657,10 @@ mod tests {
         let plugin = FindMyPhonePlugin::new();
         let incoming = plugin.incoming_capabilities();
 
-        assert_eq!(incoming.len(), 2);
+        assert_eq!(incoming.len(), 3);
         assert!(incoming.contains(&PACKET_TYPE_FINDMYPHONE_REQUEST.to_string()));
         assert!(incoming.contains(&PACKET_TYPE_KDECONNECT_FINDMYPHONE.to_string()));
+        assert!(incoming.contains(&PACKET_TYPE_FINDMYPHONE_ACK.to_string()));
     }
 
     #[test]
@@ -430,8 +668,9 @@ mod tests {
         let plugin = FindMyPhonePlugin::new();
         let outgoing = plugin.outgoing_capabilities();
 
-        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing.len(), 2);
         assert!(outgoing.contains(&PACKET_TYPE_FINDMYPHONE_REQUEST.to_string()));
+        assert!(outgoing.contains(&PACKET_TYPE_FINDMYPHONE_ACK.to_string()));
     }
 
     #[test]
@@ -443,7 +682,7 @@ mod tests {
         assert!(outgoing.contains(&PACKET_TYPE_FINDMYPHONE_REQUEST.to_string()));
 
         let incoming = factory.incoming_capabilities();
-        assert_eq!(incoming.len(), 2);
+        assert_eq!(incoming.len(), 3);
 
         let plugin = factory.create();
         assert_eq!(plugin.name(), "findmyphone");
@@ -540,4 +779,178 @@ mod tests {
         assert!(state1.load(Ordering::SeqCst));
         assert!(state2.load(Ordering::SeqCst));
     }
+
+    async fn started_plugin() -> (
+        FindMyPhonePlugin,
+        tokio::sync::mpsc::Receiver<(String, Packet)>,
+    ) {
+        let mut plugin = FindMyPhonePlugin::new();
+        let (tx, rx) = tokio::sync::mpsc::channel(10);
+        plugin.init(&create_test_device(), tx).await.unwrap();
+        plugin.start().await.unwrap();
+        (plugin, rx)
+    }
+
+    #[tokio::test]
+    async fn test_remote_stop_silences_and_acks() {
+        let (mut plugin, mut rx) = started_plugin().await;
+        plugin.is_ringing.store(true, Ordering::SeqCst);
+
+        let mut device = create_test_device();
+        let packet = plugin.create_stop_request();
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        assert!(!plugin.is_ringing());
+        let (_, ack) = rx.try_recv().unwrap();
+        assert_eq!(ack.packet_type, PACKET_TYPE_FINDMYPHONE_ACK);
+    }
+
+    #[tokio::test]
+    async fn test_stop_request_never_starts_ringing() {
+        let (mut plugin, mut rx) = started_plugin().await;
+
+        let mut device = create_test_device();
+        let packet = plugin.create_stop_request();
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        assert!(!plugin.is_ringing());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_local_dismissal_acks() {
+        let (mut plugin, mut rx) = started_plugin().await;
+        plugin.is_ringing.store(true, Ordering::SeqCst);
+
+        plugin.stop_ring().await.unwrap();
+
+        assert!(!plugin.is_ringing());
+        let (_, ack) = rx.try_recv().unwrap();
+        assert_eq!(ack.packet_type, PACKET_TYPE_FINDMYPHONE_ACK);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_muted_does_not_ring() {
+        let (mut plugin, mut rx) = started_plugin().await;
+        plugin.set_muted(true).await;
+
+        let mut device = create_test_device();
+        let packet = plugin.create_ring_request();
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        assert!(!plugin.is_ringing());
+        let (_, ack) = rx.try_recv().unwrap();
+        assert_eq!(ack.packet_type, PACKET_TYPE_FINDMYPHONE_ACK);
+    }
+
+    #[tokio::test]
+    async fn test_mute_policy_acks_playing_ring_once() {
+        let (mut plugin, mut rx) = started_plugin().await;
+        plugin.is_ringing.store(true, Ordering::SeqCst);
+        plugin.set_mute_policy(true);
+
+        let mut device = create_test_device();
+        let packet = plugin.create_ring_request();
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        assert!(!plugin.is_ringing());
+        let (_, ack) = rx.try_recv().unwrap();
+        assert_eq!(ack.packet_type, PACKET_TYPE_FINDMYPHONE_ACK);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ring_and_stop_remote() {
+        let (mut plugin, mut rx) = started_plugin().await;
+
+        plugin.ring().await.unwrap();
+        assert!(plugin.is_remote_ringing());
+        let (_, request) = rx.try_recv().unwrap();
+        assert!(request.body.get("stop").is_none());
+
+        plugin.stop_ring().await.unwrap();
+        assert!(!plugin.is_remote_ringing());
+        let (_, stop) = rx.try_recv().unwrap();
+        assert_eq!(stop.body.get("stop"), Some(&json!(true)));
+
+        // Nothing left to cancel
+        plugin.stop_ring().await.unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+
+    async fn silent_plugin() -> (
+        FindMyPhonePlugin,
+        tokio::sync::mpsc::Receiver<(String, Packet)>,
+    ) {
+        let (mut plugin, rx) = started_plugin().await;
+        plugin.ring_mode = || RingMode::Silent;
+        (plugin, rx)
+    }
+
+    #[tokio::test]
+    async fn test_ring_start_and_stop() {
+        let (mut plugin, mut rx) = silent_plugin().await;
+        let mut device = create_test_device();
+
+        let packet = plugin.create_ring_request();
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        assert!(plugin.is_ringing());
+        assert!(plugin.sound_process.is_none());
+        assert!(rx.try_recv().is_err());
+
+        let packet = plugin.create_stop_request();
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        assert!(!plugin.is_ringing());
+        let (_, ack) = rx.try_recv().unwrap();
+        assert_eq!(ack.packet_type, PACKET_TYPE_FINDMYPHONE_ACK);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_request_keeps_single_ring() {
+        let (mut plugin, mut rx) = silent_plugin().await;
+        let state = plugin.get_ringing_state();
+        let mut device = create_test_device();
+
+        let packet = plugin.create_ring_request();
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        assert!(state.load(Ordering::SeqCst));
+
+        // The overlay follows the ringing state, which must not flip or restart
+        let kde_packet = Packet::new(PACKET_TYPE_KDECONNECT_FINDMYPHONE, json!({}));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        plugin
+            .handle_packet(&kde_packet, &mut device)
+            .await
+            .unwrap();
+        assert!(state.load(Ordering::SeqCst));
+        assert!(plugin.sound_process.is_none());
+        assert!(rx.try_recv().is_err());
+
+        // One dismissal ends it
+        plugin.stop_ring().await.unwrap();
+        assert!(!state.load(Ordering::SeqCst));
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_wpctl_output_muted() {
+        assert!(!wpctl_output_muted("Volume: 0.40\n"));
+        assert!(wpctl_output_muted("Volume: 0.40 [MUTED]\n"));
+        assert!(wpctl_output_muted("Volume: 0.00\n"));
+        assert!(!wpctl_output_muted(""));
+    }
+
+    #[tokio::test]
+    async fn test_ack_clears_remote_ringing() {
+        let (mut plugin, _rx) = started_plugin().await;
+        plugin.ring().await.unwrap();
+
+        let mut device = create_test_device();
+        let ack = plugin.create_ack();
+        plugin.handle_packet(&ack, &mut device).await.unwrap();
+
+        assert!(!plugin.is_remote_ringing());
+    }
 }
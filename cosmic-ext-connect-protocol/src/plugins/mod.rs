@@ -101,6 +101,7 @@
 
 pub mod audio_backend;
 pub mod audiostream;
+pub mod audit;
 pub mod battery;
 pub mod camera;
 pub mod chat;
@@ -112,6 +113,9 @@ pub mod clipboardhistory;
 pub mod connectivity_report;
 pub mod contacts;
 pub mod filesync;
+pub mod filesync_delta;
+pub mod filesync_state;
+pub mod filesync_versions;
 pub mod findmyphone;
 pub mod lock;
 pub mod logind_backend;
@@ -122,6 +126,7 @@ pub mod mpris;
 pub mod mpris_backend;
 pub mod networkshare;
 pub mod notification;
+pub mod permission;
 pub mod phoneauth;
 pub mod ping;
 pub mod power;
@@ -142,14 +147,25 @@ pub mod wol;
 #[cfg(feature = "extendeddisplay")]
 pub mod extendeddisplay;
 
+#[cfg(feature = "native-sftp")]
+pub mod sftp_client;
+
+use crate::capabilities::{CapabilityOverrides, PluginNegotiation};
 use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::mpsc::Sender;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError, Sender};
+use tokio::sync::{oneshot, Mutex, OwnedMappedMutexGuard, OwnedMutexGuard};
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
+/// Packets a plugin's queue holds before further packets are dropped
+pub const PLUGIN_QUEUE_CAPACITY: usize = 64;
+
 /// Factory trait for creating plugin instances
 ///
 /// Plugins must implement this trait to support per-device instances.
@@ -285,7 +301,8 @@ pub trait Plugin: Send + Sync + Any {
     /// # Parameters
     ///
     /// - `packet`: The received packet
-    /// - `device`: Mutable reference to the device for state updates
+    /// - `device`: The device the packet came from. [`PluginManager`] passes a
+    ///   snapshot taken when the packet was queued; changes are not written back.
     ///
     /// # Errors
     ///
@@ -310,6 +327,153 @@ pub trait Plugin: Send + Sync + Any {
     }
 }
 
+/// Exclusive access to a running plugin instance
+///
+/// Returned by [`PluginManager::get_device_plugin`]. While it is held the
+/// plugin's queue is paused, so keep it short and never hold it while waiting
+/// on a [`PacketReceipt`] for the same plugin.
+pub type PluginGuard = OwnedMutexGuard<Box<dyn Plugin>>;
+
+/// [`PluginGuard`] narrowed to the plugin's concrete type
+///
+/// Returned by [`PluginManager::get_device_plugin_as`].
+pub type TypedPluginGuard<T> = OwnedMappedMutexGuard<Box<dyn Plugin>, T>;
+
+/// Completion of a packet handed to a plugin's queue
+#[derive(Debug)]
+pub struct PacketReceipt {
+    done: Option<oneshot::Receiver<Result<()>>>,
+}
+
+impl PacketReceipt {
+    /// Receipt for a packet that was dropped on purpose
+    fn dropped() -> Self {
+        Self { done: None }
+    }
+
+    /// Wait until the plugin has handled the packet
+    ///
+    /// Returns the plugin's result. A packet that was dropped because the
+    /// plugin is disabled resolves to `Ok(())` right away.
+    pub async fn wait(self) -> Result<()> {
+        match self.done {
+            Some(done) => done.await.unwrap_or_else(|_| {
+                Err(ProtocolError::Plugin(
+                    "Plugin stopped before handling the packet".to_string(),
+                ))
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Packet waiting in a plugin's queue
+struct QueuedPacket {
+    packet: Packet,
+    device: Device,
+    done: oneshot::Sender<Result<()>>,
+}
+
+/// Running plugin instance with its packet queue and worker task
+struct PluginSlot {
+    plugin: Arc<Mutex<Box<dyn Plugin>>>,
+    queue: mpsc::Sender<QueuedPacket>,
+    worker: JoinHandle<()>,
+    /// Packets dropped since the queue last had room
+    dropped: AtomicU64,
+}
+
+impl PluginSlot {
+    /// Take ownership of a started plugin and spawn its worker
+    ///
+    /// The worker handles queued packets one at a time, in arrival order.
+    fn spawn(device_id: &str, plugin_name: &str, plugin: Box<dyn Plugin>) -> Self {
+        let plugin = Arc::new(Mutex::new(plugin));
+        let (queue, mut packets) = mpsc::channel::<QueuedPacket>(PLUGIN_QUEUE_CAPACITY);
+
+        let worker = tokio::spawn({
+            let plugin = plugin.clone();
+            let device_id = device_id.to_string();
+            let plugin_name = plugin_name.to_string();
+            async move {
+                while let Some(QueuedPacket {
+                    packet,
+                    mut device,
+                    done,
+                }) = packets.recv().await
+                {
+                    let result = plugin
+                        .lock()
+                        .await
+                        .handle_packet(&packet, &mut device)
+                        .await;
+                    let result = isolate_plugin_error(&plugin_name, &device_id, &packet, result);
+                    let _ = done.send(result);
+                }
+                debug!(
+                    "Packet queue of plugin {} for device {} drained",
+                    plugin_name, device_id
+                );
+            }
+        });
+
+        Self {
+            plugin,
+            queue,
+            worker,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Close the queue, handle what is still queued, then stop the plugin
+    async fn shutdown(self) -> Result<()> {
+        drop(self.queue);
+        if let Err(e) = self.worker.await {
+            warn!("Plugin worker ended abnormally: {}", e);
+        }
+        self.plugin.lock().await.stop().await
+    }
+}
+
+/// Apply the plugin error policy to a packet handling result
+///
+/// Recoverable errors are logged and swallowed so the plugin keeps running;
+/// errors needing user action and critical errors are passed on.
+fn isolate_plugin_error(
+    plugin_name: &str,
+    device_id: &str,
+    packet: &Packet,
+    result: Result<()>,
+) -> Result<()> {
+    let Err(e) = result else {
+        return Ok(());
+    };
+    let packet_type = &packet.packet_type;
+
+    if e.is_recoverable() {
+        warn!(
+            "Plugin {} encountered recoverable error handling packet {} for device {}: {}",
+            plugin_name, packet_type, device_id, e
+        );
+        Ok(())
+    } else if e.requires_user_action() {
+        warn!(
+            "Plugin {} requires user action for packet {} on device {}: {}",
+            plugin_name,
+            packet_type,
+            device_id,
+            e.user_message()
+        );
+        Err(e)
+    } else {
+        error!(
+            "Plugin {} critically failed handling packet {} for device {}: {}",
+            plugin_name, packet_type, device_id, e
+        );
+        Err(e)
+    }
+}
+
 /// Plugin registry and packet router
 ///
 /// Manages plugin factories and per-device plugin instances. Routes incoming packets
@@ -321,6 +485,16 @@ pub trait Plugin: Send + Sync + Any {
 /// independent state per device. Plugin factories are registered once, and instances
 /// are created on-demand when devices connect.
 ///
+/// ## Packet Dispatch
+///
+/// Every plugin instance has its own bounded packet queue (see
+/// [`PLUGIN_QUEUE_CAPACITY`]) and worker task. [`dispatch_packet`](Self::dispatch_packet)
+/// only enqueues, so a plugin that is slow to handle a packet delays its own
+/// packets but not other plugins or the connection reading them. Packets reach
+/// a plugin in the order they were dispatched. When a plugin's queue is full
+/// further packets for it are dropped with a warning. Stopping a plugin closes
+/// its queue and handles the packets still in it before `stop` is called.
+///
 /// ## Example
 ///
 /// ```rust,ignore
@@ -338,7 +512,7 @@ pub trait Plugin: Send + Sync + Any {
 ///
 /// // Route packet to appropriate plugin for this device
 /// if let Some(packet) = receive_packet().await {
-///     manager.handle_packet(&device_id, &packet, &mut device).await?;
+///     manager.handle_packet(&device_id, &packet, &device).await?;
 /// }
 ///
 /// // Cleanup when device disconnects
@@ -350,12 +524,18 @@ pub struct PluginManager {
     /// Registered plugin factories by name
     factories: HashMap<String, Arc<dyn PluginFactory>>,
 
-    /// Per-device plugin instances
+    /// Per-device plugin instances with their packet queues
     /// Outer key: device_id, Inner key: plugin_name
-    device_plugins: HashMap<String, HashMap<String, Box<dyn Plugin>>>,
+    device_plugins: HashMap<String, HashMap<String, PluginSlot>>,
 
     /// Mapping from incoming capability to plugin name
     capability_map: HashMap<String, String>,
+
+    /// User capability overrides by device ID
+    capability_overrides: HashMap<String, CapabilityOverrides>,
+
+    /// Plugins the user has switched off, by device ID
+    disabled_plugins: HashMap<String, HashSet<String>>,
 }
 
 impl PluginManager {
@@ -365,9 +545,44 @@ impl PluginManager {
             factories: HashMap::new(),
             device_plugins: HashMap::new(),
             capability_map: HashMap::new(),
+            capability_overrides: HashMap::new(),
+            disabled_plugins: HashMap::new(),
+        }
+    }
+
+    /// Set the capability overrides for a device
+    ///
+    /// Takes effect the next time the device's plugins are initialized. Empty
+    /// overrides are removed.
+    pub fn set_capability_overrides(&mut self, device_id: &str, overrides: CapabilityOverrides) {
+        if overrides.is_empty() {
+            self.capability_overrides.remove(device_id);
+        } else {
+            self.capability_overrides
+                .insert(device_id.to_string(), overrides);
         }
     }
 
+    /// Set the plugins the user has disabled for a device
+    ///
+    /// Disabled plugins are skipped when the device's plugins are initialized.
+    /// Use `stop_device_plugin` and `start_device_plugin` to apply a change to
+    /// a connected device. Empty sets are removed.
+    pub fn set_disabled_plugins(&mut self, device_id: &str, plugins: HashSet<String>) {
+        if plugins.is_empty() {
+            self.disabled_plugins.remove(device_id);
+        } else {
+            self.disabled_plugins.insert(device_id.to_string(), plugins);
+        }
+    }
+
+    /// Check whether the user has disabled a plugin for a device
+    pub fn is_plugin_disabled(&self, device_id: &str, plugin_name: &str) -> bool {
+        self.disabled_plugins
+            .get(device_id)
+            .is_some_and(|plugins| plugins.contains(plugin_name))
+    }
+
     /// Register a plugin factory
     ///
     /// Adds the plugin factory to the registry and builds capability mappings.
@@ -424,6 +639,8 @@ impl PluginManager {
     ///
     /// Creates plugin instances from registered factories and initializes them
     /// for the given device. Each device gets its own set of plugin instances.
+    /// Only plugins negotiated with the device's advertised capabilities are
+    /// created, after applying its capability overrides.
     ///
     /// # Errors
     ///
@@ -441,8 +658,55 @@ impl PluginManager {
         );
 
         let mut device_plugins = HashMap::new();
+        let overrides = self
+            .capability_overrides
+            .get(device_id)
+            .cloned()
+            .unwrap_or_default();
 
         for (name, factory) in &self.factories {
+            let negotiation = PluginNegotiation::negotiate(
+                &factory.incoming_capabilities(),
+                &factory.outgoing_capabilities(),
+                &device.info.incoming_capabilities,
+                &device.info.outgoing_capabilities,
+                &overrides,
+            );
+            match &negotiation {
+                PluginNegotiation::Negotiated => {}
+                PluginNegotiation::NotAdvertised => {
+                    debug!(
+                        "Skipping plugin {} for device {}: not advertised by peer",
+                        name, device_id
+                    );
+                }
+                PluginNegotiation::ForceEnabled(capability) => {
+                    info!(
+                        "Capability override: loading plugin {} for device {} although \
+                         the peer does not advertise it (force_enable {})",
+                        name, device_id, capability
+                    );
+                }
+                PluginNegotiation::ForceDisabled(capability) => {
+                    info!(
+                        "Capability override: not loading plugin {} for device {} although \
+                         the peer advertises it (force_disable {})",
+                        name, device_id, capability
+                    );
+                }
+            }
+            if !negotiation.is_active() {
+                continue;
+            }
+
+            if self.is_plugin_disabled(device_id, name) {
+                info!(
+                    "Skipping plugin {} for device {}: disabled by user",
+                    name, device_id
+                );
+                continue;
+            }
+
             debug!("Creating plugin {} for device {}", name, device_id);
 
             // Create plugin instance
@@ -468,7 +732,7 @@ impl PluginManager {
                 continue;
             }
 
-            device_plugins.insert(name.clone(), plugin);
+            device_plugins.insert(name.clone(), PluginSlot::spawn(device_id, name, plugin));
         }
 
         info!(
@@ -485,8 +749,9 @@ impl PluginManager {
 
     /// Cleanup plugins for a specific device
     ///
-    /// Stops and removes all plugin instances for the given device.
-    /// Called when a device disconnects.
+    /// Stops and removes all plugin instances for the given device, after
+    /// each has handled the packets still in its queue. Called when a device
+    /// disconnects.
     ///
     /// # Errors
     ///
@@ -501,9 +766,9 @@ impl PluginManager {
 
             let mut errors = Vec::new();
 
-            for (name, mut plugin) in plugins.drain() {
+            for (name, slot) in plugins.drain() {
                 debug!("Stopping plugin {} for device {}", name, device_id);
-                if let Err(e) = plugin.stop().await {
+                if let Err(e) = slot.shutdown().await {
                     warn!(
                         "Failed to stop plugin {} for device {}: {}",
                         name, device_id, e
@@ -525,24 +790,168 @@ impl PluginManager {
         Ok(())
     }
 
-    /// Get reference to a plugin for a specific device
-    pub fn get_device_plugin(&self, device_id: &str, plugin_name: &str) -> Option<&dyn Plugin> {
-        self.device_plugins
+    /// Start a single plugin for a connected device
+    ///
+    /// Creates, initializes and starts the plugin if it is registered,
+    /// negotiated with the device and not already running. Devices whose
+    /// plugins have not been initialized (not connected) are left alone.
+    ///
+    /// Returns `true` if the plugin was started.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if plugin initialization or start fails
+    pub async fn start_device_plugin(
+        &mut self,
+        device_id: &str,
+        plugin_name: &str,
+        device: &Device,
+        packet_sender: Sender<(String, Packet)>,
+    ) -> Result<bool> {
+        let Some(plugins) = self.device_plugins.get(device_id) else {
+            return Ok(false);
+        };
+        if plugins.contains_key(plugin_name) {
+            return Ok(false);
+        }
+        let Some(factory) = self.factories.get(plugin_name) else {
+            debug!(
+                "Not starting plugin {} for device {}: no factory registered",
+                plugin_name, device_id
+            );
+            return Ok(false);
+        };
+
+        let overrides = self
+            .capability_overrides
             .get(device_id)
-            .and_then(|plugins| plugins.get(plugin_name))
-            .map(|p| p.as_ref())
+            .cloned()
+            .unwrap_or_default();
+        let negotiation = PluginNegotiation::negotiate(
+            &factory.incoming_capabilities(),
+            &factory.outgoing_capabilities(),
+            &device.info.incoming_capabilities,
+            &device.info.outgoing_capabilities,
+            &overrides,
+        );
+        if !negotiation.is_active() {
+            debug!(
+                "Not starting plugin {} for device {}: not negotiated",
+                plugin_name, device_id
+            );
+            return Ok(false);
+        }
+
+        let mut plugin = factory.create();
+        plugin.init(device, packet_sender).await?;
+        plugin.start().await?;
+
+        info!("Started plugin {} for device {}", plugin_name, device_id);
+        self.device_plugins
+            .entry(device_id.to_string())
+            .or_default()
+            .insert(
+                plugin_name.to_string(),
+                PluginSlot::spawn(device_id, plugin_name, plugin),
+            );
+
+        Ok(true)
+    }
+
+    /// Stop and remove a single plugin for a device
+    ///
+    /// Packets already queued for the plugin are handled first. Returns `true`
+    /// if the plugin was running.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the plugin fails to stop; it is removed regardless
+    pub async fn stop_device_plugin(&mut self, device_id: &str, plugin_name: &str) -> Result<bool> {
+        let Some(slot) = self
+            .device_plugins
+            .get_mut(device_id)
+            .and_then(|plugins| plugins.remove(plugin_name))
+        else {
+            return Ok(false);
+        };
+
+        info!("Stopping plugin {} for device {}", plugin_name, device_id);
+        slot.shutdown().await?;
+        Ok(true)
+    }
+
+    /// Disable a plugin for a device at runtime
+    ///
+    /// Adds the plugin to the device's disabled set and stops its running
+    /// instance, so no further packets are dispatched to it. Other devices keep
+    /// their own instance. Returns `true` if a running instance was stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the plugin fails to stop; it stays disabled regardless
+    pub async fn disable_plugin(&mut self, device_id: &str, plugin_name: &str) -> Result<bool> {
+        self.disabled_plugins
+            .entry(device_id.to_string())
+            .or_default()
+            .insert(plugin_name.to_string());
+        self.stop_device_plugin(device_id, plugin_name).await
     }
 
-    /// Get mutable reference to a plugin for a specific device
-    pub fn get_device_plugin_mut(
+    /// Enable a plugin for a device at runtime
+    ///
+    /// Removes the plugin from the device's disabled set and, if the device is
+    /// connected, starts it (see `start_device_plugin`). Returns `true` if the
+    /// plugin was started.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if plugin initialization or start fails
+    pub async fn enable_plugin(
         &mut self,
         device_id: &str,
         plugin_name: &str,
-    ) -> Option<&mut dyn Plugin> {
-        self.device_plugins
-            .get_mut(device_id)
-            .and_then(|plugins| plugins.get_mut(plugin_name))
-            .map(|p| p.as_mut())
+        device: &Device,
+        packet_sender: Sender<(String, Packet)>,
+    ) -> Result<bool> {
+        if let Some(plugins) = self.disabled_plugins.get_mut(device_id) {
+            plugins.remove(plugin_name);
+            if plugins.is_empty() {
+                self.disabled_plugins.remove(device_id);
+            }
+        }
+        self.start_device_plugin(device_id, plugin_name, device, packet_sender)
+            .await
+    }
+
+    /// Get exclusive access to a plugin for a specific device
+    ///
+    /// Waits until the plugin has finished the packet it is handling. See
+    /// [`PluginGuard`] for how long to hold on to it.
+    pub async fn get_device_plugin(
+        &self,
+        device_id: &str,
+        plugin_name: &str,
+    ) -> Option<PluginGuard> {
+        let plugin = self
+            .device_plugins
+            .get(device_id)
+            .and_then(|plugins| plugins.get(plugin_name))?
+            .plugin
+            .clone();
+        Some(plugin.lock_owned().await)
+    }
+
+    /// Get exclusive access to a plugin as its concrete type
+    ///
+    /// Like [`get_device_plugin`](Self::get_device_plugin), but returns `None`
+    /// if the plugin is not a `T`.
+    pub async fn get_device_plugin_as<T: Plugin>(
+        &self,
+        device_id: &str,
+        plugin_name: &str,
+    ) -> Option<TypedPluginGuard<T>> {
+        let plugin = self.get_device_plugin(device_id, plugin_name).await?;
+        OwnedMutexGuard::try_map(plugin, |plugin| plugin.as_any_mut().downcast_mut::<T>()).ok()
     }
 
     /// Unregister a plugin factory by name
@@ -647,23 +1056,26 @@ impl PluginManager {
         Ok(())
     }
 
-    /// Handle an incoming packet by routing to appropriate device-specific plugin
+    /// Queue an incoming packet for the device-specific plugin handling it
     ///
     /// Looks up the plugin that handles the packet's type for the given device
-    /// and delegates packet processing to that plugin instance.
+    /// and puts the packet on that plugin's queue without waiting for it to be
+    /// handled. The plugin sees `device` as it is now. Packets for a plugin the
+    /// user disabled for the device are dropped. Use the returned receipt to
+    /// wait for the plugin's result.
     ///
     /// # Errors
     ///
     /// Returns error if:
     /// - No plugin handles the packet type
     /// - Device has no initialized plugins
-    /// - Plugin packet handling fails critically
-    pub async fn handle_packet(
-        &mut self,
+    /// - The plugin's queue is full (the packet is dropped)
+    pub fn dispatch_packet(
+        &self,
         device_id: &str,
         packet: &Packet,
-        device: &mut Device,
-    ) -> Result<()> {
+        device: &Device,
+    ) -> Result<PacketReceipt> {
         let mut packet_type = packet.packet_type.clone();
 
         // Find plugin name for this packet type
@@ -692,13 +1104,21 @@ impl PluginManager {
             )));
         };
 
+        if self.is_plugin_disabled(device_id, &plugin_name) {
+            debug!(
+                "Dropping packet {} for device {}: plugin {} is disabled",
+                packet.packet_type, device_id, plugin_name
+            );
+            return Ok(PacketReceipt::dropped());
+        }
+
         // Get device plugins
-        let device_plugins = self.device_plugins.get_mut(device_id).ok_or_else(|| {
+        let device_plugins = self.device_plugins.get(device_id).ok_or_else(|| {
             ProtocolError::Plugin(format!("No plugins initialized for device {}", device_id))
         })?;
 
         // Get plugin instance for this device
-        let plugin = device_plugins.get_mut(&plugin_name).ok_or_else(|| {
+        let slot = device_plugins.get(&plugin_name).ok_or_else(|| {
             ProtocolError::Plugin(format!(
                 "Plugin '{}' not found for device {}",
                 plugin_name, device_id
@@ -710,48 +1130,84 @@ impl PluginManager {
             packet.packet_type, packet_type, plugin_name, device_id
         );
 
-        // Handle packet with error isolation
-        match plugin.handle_packet(packet, device).await {
-            Ok(()) => Ok(()),
-            Err(e) => {
-                // Check if error is recoverable
-                if e.is_recoverable() {
-                    // Log recoverable errors but don't propagate
-                    warn!(
-                        "Plugin {} encountered recoverable error handling packet {} for device {}: {}",
-                        plugin_name, packet_type, device_id, e
+        let (done, receipt) = oneshot::channel();
+        let queued = QueuedPacket {
+            packet: packet.clone(),
+            device: device.clone(),
+            done,
+        };
+        match slot.queue.try_send(queued) {
+            Ok(()) => {
+                let dropped = slot.dropped.swap(0, Ordering::Relaxed);
+                if dropped > 0 {
+                    info!(
+                        "Queue of plugin {} for device {} has room again, {} packets were dropped",
+                        plugin_name, device_id, dropped
                     );
-                    // Plugin continues to function
-                    Ok(())
-                } else if e.requires_user_action() {
-                    // User action required - log with appropriate level
+                }
+                Ok(PacketReceipt {
+                    done: Some(receipt),
+                })
+            }
+            Err(TrySendError::Full(_)) => {
+                // Logged once per overflow rather than for every dropped packet
+                if slot.dropped.fetch_add(1, Ordering::Relaxed) == 0 {
                     warn!(
-                        "Plugin {} requires user action for packet {} on device {}: {}",
-                        plugin_name,
-                        packet_type,
-                        device_id,
-                        e.user_message()
+                        "Queue of plugin {} for device {} is full, dropping packets",
+                        plugin_name, device_id
                     );
-                    // Don't crash the plugin, but return error for UI notification
-                    Err(e)
-                } else {
-                    // Critical error - plugin may be in bad state
-                    error!(
-                        "Plugin {} critically failed handling packet {} for device {}: {}",
-                        plugin_name, packet_type, device_id, e
-                    );
-                    // Propagate critical errors
-                    Err(e)
                 }
+                Err(ProtocolError::ResourceExhausted(format!(
+                    "Packet queue of plugin '{}' for device {} is full",
+                    plugin_name, device_id
+                )))
             }
+            Err(TrySendError::Closed(_)) => Err(ProtocolError::Plugin(format!(
+                "Plugin '{}' for device {} is stopping",
+                plugin_name, device_id
+            ))),
         }
     }
 
+    /// Handle an incoming packet and wait for the plugin's result
+    ///
+    /// Same as [`dispatch_packet`](Self::dispatch_packet) followed by waiting on
+    /// the receipt. Recoverable plugin errors are logged and not returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the packet could not be queued or the plugin failed in
+    /// a way that needs user action or is critical
+    pub async fn handle_packet(
+        &self,
+        device_id: &str,
+        packet: &Packet,
+        device: &Device,
+    ) -> Result<()> {
+        self.dispatch_packet(device_id, packet, device)?
+            .wait()
+            .await
+    }
+
     /// Check if a packet type is supported
     pub fn supports_packet_type(&self, packet_type: &str) -> bool {
         self.capability_map.contains_key(packet_type)
     }
 
+    /// Check if any plugin would receive a packet type in [`Self::dispatch_packet`]
+    ///
+    /// Unlike [`Self::supports_packet_type`], `kdeconnect.*` types also match
+    /// the `cconnect.*` capability they are routed to. Peers on a newer
+    /// protocol version may send types no plugin knows; check this first to
+    /// drop those without an error.
+    pub fn handles_packet_type(&self, packet_type: &str) -> bool {
+        self.supports_packet_type(packet_type)
+            || packet_type.strip_prefix("kdeconnect.").is_some_and(|rest| {
+                self.capability_map
+                    .contains_key(&format!("cconnect.{}", rest))
+            })
+    }
+
     /// Get the plugin name that handles a packet type
     pub fn get_plugin_for_packet(&self, packet_type: &str) -> Option<&str> {
         self.capability_map.get(packet_type).map(|s| s.as_str())
@@ -787,42 +1243,47 @@ impl PluginManager {
     ///
     /// `Some(BatteryStatus)` if the device has a battery plugin with status data,
     /// `None` if the device is not found, has no battery plugin, or no status has been received.
-    pub fn get_device_battery_status(&self, device_id: &str) -> Option<battery::BatteryStatus> {
-        // Get the device's plugins
-        let device_plugins = self.device_plugins.get(device_id)?;
-
-        // Get the battery plugin
-        let battery_plugin = device_plugins.get("battery")?;
-
-        // Downcast to BatteryPlugin
-        let battery_plugin = battery_plugin
-            .as_any()
-            .downcast_ref::<battery::BatteryPlugin>()?;
-
-        // Get the battery status
-        battery_plugin.get_battery_status()
+    pub async fn get_device_battery_status(
+        &self,
+        device_id: &str,
+    ) -> Option<battery::BatteryStatus> {
+        self.get_device_plugin_as::<battery::BatteryPlugin>(device_id, "battery")
+            .await?
+            .get_battery_status()
     }
 
     /// Get screen share statistics for a device
     ///
     /// Returns viewer count and other sharing metrics when the device is sharing its screen
-    pub fn get_device_screen_share_stats(
+    pub async fn get_device_screen_share_stats(
         &self,
         device_id: &str,
     ) -> Option<screenshare::ShareStats> {
-        // Get the device's plugins
-        let device_plugins = self.device_plugins.get(device_id)?;
-
-        // Get the screenshare plugin
-        let screenshare_plugin = device_plugins.get("screenshare")?;
+        self.get_device_plugin_as::<screenshare::ScreenSharePlugin>(device_id, "screenshare")
+            .await?
+            .get_stats()
+    }
 
-        // Downcast to ScreenSharePlugin
-        let screenshare_plugin = screenshare_plugin
-            .as_any()
-            .downcast_ref::<screenshare::ScreenSharePlugin>()?;
+    /// Measure the round-trip time to a device
+    ///
+    /// Sends a timing ping through the device's ping plugin and waits up to
+    /// [`ping::PING_TIMEOUT`] for the echo. The plugin is not held while
+    /// waiting, so its queue keeps draining and the echo can be handled.
+    pub async fn ping_with_timing(&self, device_id: &str) -> Result<Duration> {
+        let timer = self.get_device_ping_timer(device_id).await.ok_or_else(|| {
+            ProtocolError::Plugin(format!("Ping plugin not active for device {}", device_id))
+        })?;
+        timer.ping_with_timing(ping::PING_TIMEOUT).await
+    }
 
-        // Get the share stats
-        screenshare_plugin.get_stats()
+    /// Get a handle for measuring the round-trip time to a device
+    ///
+    /// Unlike [`Self::ping_with_timing`], waiting on the returned timer does
+    /// not require keeping the manager borrowed.
+    pub async fn get_device_ping_timer(&self, device_id: &str) -> Option<ping::PingTimer> {
+        self.get_device_plugin_as::<ping::PingPlugin>(device_id, "ping")
+            .await?
+            .timer()
     }
 
     /// Get number of registered plugins (deprecated)
@@ -1079,7 +1540,7 @@ mod tests {
 
         manager.register_factory(factory).unwrap();
 
-        let mut device = create_test_device();
+        let device = create_test_device();
         let device_id = device.id().to_string();
 
         let (tx, _rx) = tokio::sync::mpsc::channel(100);
@@ -1090,7 +1551,7 @@ mod tests {
 
         let packet = Packet::new("cconnect.test", serde_json::json!({}));
         assert!(manager
-            .handle_packet(&device_id, &packet, &mut device)
+            .handle_packet(&device_id, &packet, &device)
             .await
             .is_ok());
     }
@@ -1130,6 +1591,459 @@ mod tests {
         assert_eq!(manager.device_plugin_count(device2_id), 1);
     }
 
+    fn manager_with_peer_plugins() -> PluginManager {
+        let mut manager = PluginManager::new();
+        manager
+            .register_factory(Arc::new(MockPluginFactory::new(
+                "clipboard",
+                vec!["cconnect.clipboard"],
+                vec!["cconnect.clipboard"],
+            )))
+            .unwrap();
+        manager
+            .register_factory(Arc::new(MockPluginFactory::new(
+                "mpris",
+                vec!["cconnect.mpris.request"],
+                vec!["cconnect.mpris"],
+            )))
+            .unwrap();
+        manager
+    }
+
+    fn device_advertising(capabilities: &[&str]) -> Device {
+        let mut device = create_test_device();
+        let capabilities: Vec<String> = capabilities.iter().map(|c| c.to_string()).collect();
+        device.info.incoming_capabilities = capabilities.clone();
+        device.info.outgoing_capabilities = capabilities;
+        device
+    }
+
+    #[tokio::test]
+    async fn test_plugins_negotiated_from_peer_capabilities() {
+        let mut manager = manager_with_peer_plugins();
+        let device = device_advertising(&["kdeconnect.clipboard"]);
+        let device_id = device.id().to_string();
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(&device_id, &device, tx)
+            .await
+            .unwrap();
+
+        assert!(manager
+            .get_device_plugin(&device_id, "clipboard")
+            .await
+            .is_some());
+        assert!(manager
+            .get_device_plugin(&device_id, "mpris")
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_force_disable_suppresses_advertised_plugin() {
+        let mut manager = manager_with_peer_plugins();
+        let device = device_advertising(&["kdeconnect.clipboard"]);
+        let device_id = device.id().to_string();
+
+        manager.set_capability_overrides(
+            &device_id,
+            CapabilityOverrides {
+                force_enable: Vec::new(),
+                force_disable: vec!["clipboard".to_string()],
+            },
+        );
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(&device_id, &device, tx)
+            .await
+            .unwrap();
+
+        assert!(manager
+            .get_device_plugin(&device_id, "clipboard")
+            .await
+            .is_none());
+        assert_eq!(manager.device_plugin_count(&device_id), 0);
+    }
+
+    #[tokio::test]
+    async fn test_force_enable_activates_missing_plugin() {
+        let mut manager = manager_with_peer_plugins();
+        let device = device_advertising(&["kdeconnect.clipboard"]);
+        let device_id = device.id().to_string();
+
+        manager.set_capability_overrides(
+            &device_id,
+            CapabilityOverrides {
+                force_enable: vec!["cconnect.mpris".to_string()],
+                force_disable: Vec::new(),
+            },
+        );
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(&device_id, &device, tx)
+            .await
+            .unwrap();
+
+        assert!(manager
+            .get_device_plugin(&device_id, "clipboard")
+            .await
+            .is_some());
+        assert!(manager
+            .get_device_plugin(&device_id, "mpris")
+            .await
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_plugin_skipped_at_init() {
+        let mut manager = manager_with_peer_plugins();
+        let device = device_advertising(&["kdeconnect.clipboard", "kdeconnect.mpris"]);
+        let device_id = device.id().to_string();
+
+        manager.set_disabled_plugins(&device_id, HashSet::from(["mpris".to_string()]));
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(&device_id, &device, tx)
+            .await
+            .unwrap();
+
+        assert!(manager
+            .get_device_plugin(&device_id, "clipboard")
+            .await
+            .is_some());
+        assert!(manager
+            .get_device_plugin(&device_id, "mpris")
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stop_and_start_single_plugin() {
+        let mut manager = manager_with_peer_plugins();
+        let device = device_advertising(&["kdeconnect.clipboard", "kdeconnect.mpris"]);
+        let device_id = device.id().to_string();
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(&device_id, &device, tx.clone())
+            .await
+            .unwrap();
+        assert_eq!(manager.device_plugin_count(&device_id), 2);
+
+        assert!(manager
+            .stop_device_plugin(&device_id, "mpris")
+            .await
+            .unwrap());
+        assert!(manager
+            .get_device_plugin(&device_id, "mpris")
+            .await
+            .is_none());
+        assert!(!manager
+            .stop_device_plugin(&device_id, "mpris")
+            .await
+            .unwrap());
+
+        assert!(manager
+            .start_device_plugin(&device_id, "mpris", &device, tx.clone())
+            .await
+            .unwrap());
+        assert!(manager
+            .get_device_plugin(&device_id, "mpris")
+            .await
+            .is_some());
+        assert!(!manager
+            .start_device_plugin(&device_id, "mpris", &device, tx)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_start_plugin_ignores_disconnected_device() {
+        let mut manager = manager_with_peer_plugins();
+        let device = device_advertising(&["kdeconnect.clipboard"]);
+        let device_id = device.id().to_string();
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        assert!(!manager
+            .start_device_plugin(&device_id, "clipboard", &device, tx)
+            .await
+            .unwrap());
+        assert_eq!(manager.device_plugin_count(&device_id), 0);
+    }
+
+    async fn packets_handled(manager: &PluginManager, device_id: &str, plugin_name: &str) -> usize {
+        manager
+            .get_device_plugin_as::<MockPlugin>(device_id, plugin_name)
+            .await
+            .map_or(0, |plugin| plugin.packets_handled)
+    }
+
+    #[tokio::test]
+    async fn test_packet_routed_to_matching_plugin() {
+        let mut manager = manager_with_peer_plugins();
+        let device = device_advertising(&["kdeconnect.clipboard", "kdeconnect.mpris"]);
+        let device_id = device.id().to_string();
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(&device_id, &device, tx)
+            .await
+            .unwrap();
+
+        let packet = Packet::new("kdeconnect.mpris.request", serde_json::json!({}));
+        manager
+            .handle_packet(&device_id, &packet, &device)
+            .await
+            .unwrap();
+
+        assert_eq!(packets_handled(&manager, &device_id, "mpris").await, 1);
+        assert_eq!(packets_handled(&manager, &device_id, "clipboard").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_disable_plugin_prevents_dispatch() {
+        let mut manager = manager_with_peer_plugins();
+        let device = device_advertising(&["kdeconnect.clipboard"]);
+        let device_id = device.id().to_string();
+        let other = device_advertising(&["kdeconnect.clipboard"]);
+        let other_id = other.id().to_string();
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(&device_id, &device, tx.clone())
+            .await
+            .unwrap();
+        manager
+            .init_device_plugins(&other_id, &other, tx.clone())
+            .await
+            .unwrap();
+
+        assert!(manager
+            .disable_plugin(&device_id, "clipboard")
+            .await
+            .unwrap());
+        assert!(manager.is_plugin_disabled(&device_id, "clipboard"));
+
+        let packet = Packet::new("cconnect.clipboard", serde_json::json!({}));
+        manager
+            .handle_packet(&device_id, &packet, &device)
+            .await
+            .unwrap();
+        assert!(manager
+            .get_device_plugin(&device_id, "clipboard")
+            .await
+            .is_none());
+
+        // The other device's instance is unaffected
+        manager
+            .handle_packet(&other_id, &packet, &other)
+            .await
+            .unwrap();
+        assert_eq!(packets_handled(&manager, &other_id, "clipboard").await, 1);
+
+        assert!(manager
+            .enable_plugin(&device_id, "clipboard", &device, tx)
+            .await
+            .unwrap());
+        assert!(!manager.is_plugin_disabled(&device_id, "clipboard"));
+        manager
+            .handle_packet(&device_id, &packet, &device)
+            .await
+            .unwrap();
+        assert_eq!(packets_handled(&manager, &device_id, "clipboard").await, 1);
+    }
+
+    /// Plugin that needs a permit from its gate for every packet it handles
+    struct GatedPlugin {
+        gate: Arc<tokio::sync::Semaphore>,
+        handled: Arc<std::sync::Mutex<Vec<u64>>>,
+    }
+
+    #[async_trait]
+    impl Plugin for GatedPlugin {
+        fn name(&self) -> &str {
+            "gated"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn incoming_capabilities(&self) -> Vec<String> {
+            vec!["cconnect.gated".to_string()]
+        }
+
+        fn outgoing_capabilities(&self) -> Vec<String> {
+            vec![]
+        }
+
+        async fn init(
+            &mut self,
+            _device: &Device,
+            _packet_sender: Sender<(String, Packet)>,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn start(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn handle_packet(&mut self, packet: &Packet, _device: &mut Device) -> Result<()> {
+            self.gate.acquire().await.unwrap().forget();
+            let seq = packet.body["seq"].as_u64().unwrap_or_default();
+            self.handled.lock().unwrap().push(seq);
+            Ok(())
+        }
+    }
+
+    struct GatedPluginFactory {
+        gate: Arc<tokio::sync::Semaphore>,
+        handled: Arc<std::sync::Mutex<Vec<u64>>>,
+    }
+
+    impl PluginFactory for GatedPluginFactory {
+        fn name(&self) -> &str {
+            "gated"
+        }
+
+        fn incoming_capabilities(&self) -> Vec<String> {
+            vec!["cconnect.gated".to_string()]
+        }
+
+        fn outgoing_capabilities(&self) -> Vec<String> {
+            vec![]
+        }
+
+        fn create(&self) -> Box<dyn Plugin> {
+            Box::new(GatedPlugin {
+                gate: self.gate.clone(),
+                handled: self.handled.clone(),
+            })
+        }
+    }
+
+    async fn manager_with_gated_plugin() -> (
+        PluginManager,
+        Device,
+        Arc<tokio::sync::Semaphore>,
+        Arc<std::sync::Mutex<Vec<u64>>>,
+    ) {
+        let gate = Arc::new(tokio::sync::Semaphore::new(0));
+        let handled = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let mut manager = PluginManager::new();
+        manager
+            .register_factory(Arc::new(GatedPluginFactory {
+                gate: gate.clone(),
+                handled: handled.clone(),
+            }))
+            .unwrap();
+        manager
+            .register_factory(Arc::new(MockPluginFactory::new(
+                "fast",
+                vec!["cconnect.fast"],
+                vec![],
+            )))
+            .unwrap();
+
+        let device = create_test_device();
+        let (tx, _rx) = tokio::sync::mpsc::channel(100);
+        manager
+            .init_device_plugins(device.id(), &device, tx)
+            .await
+            .unwrap();
+
+        (manager, device, gate, handled)
+    }
+
+    fn gated_packet(seq: u64) -> Packet {
+        Packet::new("cconnect.gated", serde_json::json!({ "seq": seq }))
+    }
+
+    #[tokio::test]
+    async fn test_slow_plugin_does_not_delay_others() {
+        let (manager, device, gate, handled) = manager_with_gated_plugin().await;
+        let device_id = device.id();
+
+        let slow = manager
+            .dispatch_packet(device_id, &gated_packet(0), &device)
+            .unwrap();
+        let fast = manager
+            .dispatch_packet(
+                device_id,
+                &Packet::new("cconnect.fast", serde_json::json!({})),
+                &device,
+            )
+            .unwrap();
+
+        // The fast plugin finishes while the slow one is still blocked
+        tokio::time::timeout(Duration::from_secs(5), fast.wait())
+            .await
+            .expect("fast plugin was delayed by the slow one")
+            .unwrap();
+        assert_eq!(packets_handled(&manager, device_id, "fast").await, 1);
+        assert!(handled.lock().unwrap().is_empty());
+
+        gate.add_permits(1);
+        slow.wait().await.unwrap();
+        assert_eq!(*handled.lock().unwrap(), vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_queued_packets_drained_in_order_on_stop() {
+        let (mut manager, device, gate, handled) = manager_with_gated_plugin().await;
+        let device_id = device.id().to_string();
+
+        let receipts: Vec<PacketReceipt> = (0..3)
+            .map(|seq| {
+                manager
+                    .dispatch_packet(&device_id, &gated_packet(seq), &device)
+                    .unwrap()
+            })
+            .collect();
+
+        gate.add_permits(3);
+        manager.cleanup_device_plugins(&device_id).await.unwrap();
+
+        assert_eq!(*handled.lock().unwrap(), vec![0, 1, 2]);
+        for receipt in receipts {
+            receipt.wait().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_packet() {
+        let (manager, device, _gate, _handled) = manager_with_gated_plugin().await;
+        let device_id = device.id();
+
+        // The worker does not run before this task yields, so the queue fills up
+        for seq in 0..PLUGIN_QUEUE_CAPACITY as u64 {
+            manager
+                .dispatch_packet(device_id, &gated_packet(seq), &device)
+                .unwrap();
+        }
+
+        for seq in 99..102 {
+            let result = manager.dispatch_packet(device_id, &gated_packet(seq), &device);
+            assert!(matches!(result, Err(ProtocolError::ResourceExhausted(_))));
+        }
+        let slot = &manager.device_plugins[device_id]["gated"];
+        assert_eq!(slot.dropped.load(Ordering::Relaxed), 3);
+    }
+
     #[test]
     fn test_capability_lookup() {
         let mut manager = PluginManager::new();
@@ -1146,6 +2060,22 @@ mod tests {
         assert!(capabilities.contains(&"cconnect.test2".to_string()));
     }
 
+    #[test]
+    fn test_handles_packet_type_with_alias() {
+        let mut manager = PluginManager::new();
+        let factory = Arc::new(MockPluginFactory::new(
+            "test_plugin",
+            vec!["cconnect.test"],
+            vec![],
+        ));
+        manager.register_factory(factory).unwrap();
+
+        assert!(manager.handles_packet_type("cconnect.test"));
+        assert!(manager.handles_packet_type("kdeconnect.test"));
+        assert!(!manager.supports_packet_type("kdeconnect.test"));
+        assert!(!manager.handles_packet_type("kdeconnect.newfeature"));
+    }
+
     #[tokio::test]
     async fn test_unsupported_packet_type() {
         let mut manager = PluginManager::new();
@@ -1157,7 +2087,7 @@ mod tests {
 
         manager.register_factory(factory).unwrap();
 
-        let mut device = create_test_device();
+        let device = create_test_device();
         let device_id = device.id().to_string();
 
         let (tx, _rx) = tokio::sync::mpsc::channel(100);
@@ -1167,13 +2097,12 @@ mod tests {
             .unwrap();
 
         let packet = Packet::new("cconnect.unsupported", serde_json::json!({}));
-        let result = manager
-            .handle_packet(&device_id, &packet, &mut device)
-            .await;
+        let result = manager.handle_packet(&device_id, &packet, &device).await;
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
             .to_string()
             .contains("No plugin handles"));
+        assert!(!manager.handles_packet_type("cconnect.unsupported"));
     }
 }
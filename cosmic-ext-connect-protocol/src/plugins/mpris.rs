@@ -159,7 +159,21 @@
 //!
 //! ## Album Art Transfer
 //!
-//! Album art is transferred via TCP payload:
+//! When a peer advertises `supportAlbumArtPayload`, art for a track is
+//! requested by its URL:
+//!
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.mpris.request",
+//!     "body": {
+//!         "player": "spotify",
+//!         "albumArtUrl": "file:///path/to/art.jpg"
+//!     }
+//! }
+//! ```
+//!
+//! The art is then transferred via TCP payload and cached locally:
 //!
 //! ```json
 //! {
@@ -167,7 +181,8 @@
 //!     "type": "cconnect.mpris",
 //!     "body": {
 //!         "transferringAlbumArt": true,
-//!         "player": "spotify"
+//!         "player": "spotify",
+//!         "albumArtUrl": "file:///path/to/art.jpg"
 //!     },
 //!     "payloadSize": 204800,
 //!     "payloadTransferInfo": {
@@ -233,6 +248,13 @@
 //! }
 //! ```
 //!
+//! ## Remote Players
+//!
+//! Players reported by the peer are tracked per device. A new `playerList`
+//! drops players that are no longer listed and requests the state of new
+//! ones. A channel passed to [`MprisPlugin::with_events`] receives an
+//! [`MprisEvent`] whenever a remote player changes or disappears.
+//!
 //! ## Example
 //!
 //! ```rust,ignore
@@ -273,15 +295,24 @@ use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, Sender};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use super::mpris_backend::MprisBackend;
 use super::{Plugin, PluginFactory};
 
+/// Largest album art payload accepted (5 MiB)
+const MAX_ALBUM_ART_SIZE: i64 = 5 * 1024 * 1024;
+
+/// Minimum time between two album art downloads from the remote device
+const ALBUM_ART_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Loop status for media playback
 ///
 /// Indicates the repeat/loop mode of the player.
@@ -487,6 +518,54 @@ pub struct PlayerState {
     pub status: PlayerStatus,
     /// Track metadata
     pub metadata: PlayerMetadata,
+    /// When this state was received (UNIX epoch milliseconds)
+    ///
+    /// `status.position` was current at this time; while playing, the
+    /// position moves on from here.
+    pub updated_at: i64,
+}
+
+impl PlayerState {
+    /// Estimated playback position in milliseconds at time `now`
+    pub fn position_at(&self, now: i64) -> i64 {
+        let mut position = self.status.position;
+        if self.status.is_playing {
+            position += (now - self.updated_at).max(0);
+        }
+        if self.status.length > 0 {
+            position = position.min(self.status.length);
+        }
+        position
+    }
+}
+
+/// Event reported by the MPRIS plugin on its event channel
+#[derive(Debug, Clone, PartialEq)]
+pub enum MprisEvent {
+    /// A remote player was reported or its state changed
+    PlayerUpdated {
+        device_id: String,
+        state: PlayerState,
+        /// Cached album art for the current track, once downloaded
+        album_art: Option<PathBuf>,
+    },
+
+    /// A remote player went away
+    PlayerRemoved { device_id: String, player: String },
+}
+
+/// Default directory for cached album art
+pub fn default_album_art_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cosmic-ext-connect")
+        .join("album-art")
+}
+
+/// Cache file for the album art at `url`
+fn album_art_cache_path(dir: &Path, url: &str) -> PathBuf {
+    let digest = hex::encode(Sha256::digest(url.as_bytes()));
+    dir.join(&digest[..32])
 }
 
 /// MPRIS plugin for media player control
@@ -535,6 +614,27 @@ pub struct MprisPlugin {
 
     /// Packet sender for sending responses
     packet_sender: Option<Sender<(String, Packet)>>,
+
+    /// Whether the remote device sends album art payloads
+    remote_album_art: bool,
+
+    /// Album art URL -> cached file
+    album_art: Arc<RwLock<HashMap<String, PathBuf>>>,
+
+    /// Album art URLs already requested from the remote device
+    requested_album_art: HashSet<String>,
+
+    /// When the last album art download was started
+    last_album_art: Option<Instant>,
+
+    /// Directory for cached album art
+    album_art_dir: PathBuf,
+
+    /// TLS configuration for album art payloads
+    tls_config: Option<Arc<crate::TlsConfig>>,
+
+    /// Channel for [`MprisEvent`]s
+    event_sender: Option<mpsc::UnboundedSender<MprisEvent>>,
 }
 
 impl MprisPlugin {
@@ -555,6 +655,36 @@ impl MprisPlugin {
             support_album_art: true,
             backend: MprisBackend::new(),
             packet_sender: None,
+            remote_album_art: false,
+            album_art: Arc::new(RwLock::new(HashMap::new())),
+            requested_album_art: HashSet::new(),
+            last_album_art: None,
+            album_art_dir: default_album_art_dir(),
+            tls_config: None,
+            event_sender: None,
+        }
+    }
+
+    /// Report remote player changes on `sender`
+    pub fn with_events(mut self, sender: mpsc::UnboundedSender<MprisEvent>) -> Self {
+        self.event_sender = Some(sender);
+        self
+    }
+
+    /// Set the TLS configuration used to download album art
+    pub fn set_tls_config(&mut self, config: Arc<crate::TlsConfig>) {
+        self.tls_config = Some(config);
+    }
+
+    /// Cached album art for `url`, if it has been downloaded
+    pub async fn album_art_path(&self, url: &str) -> Option<PathBuf> {
+        self.album_art.read().await.get(url).cloned()
+    }
+
+    /// Send an event to the event channel, if any
+    fn emit(&self, event: MprisEvent) {
+        if let Some(sender) = &self.event_sender {
+            let _ = sender.send(event);
         }
     }
 
@@ -722,6 +852,32 @@ impl MprisPlugin {
         )
     }
 
+    /// Create an album art request packet
+    ///
+    /// Asks the remote device to send the art at `url` as a payload.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::plugins::mpris::MprisPlugin;
+    ///
+    /// let plugin = MprisPlugin::new();
+    /// let packet = plugin.create_request_album_art_packet(
+    ///     "spotify".to_string(),
+    ///     "file:///art.jpg".to_string(),
+    /// );
+    /// assert_eq!(packet.body["albumArtUrl"], "file:///art.jpg");
+    /// ```
+    pub fn create_request_album_art_packet(&self, player: String, url: String) -> Packet {
+        Packet::new(
+            "cconnect.mpris.request",
+            json!({
+                "player": player,
+                "albumArtUrl": url
+            }),
+        )
+    }
+
     /// Create a playback control packet
     ///
     /// Sends playback command to a player.
@@ -1087,8 +1243,163 @@ impl MprisPlugin {
         self.players.write().await.remove(player);
     }
 
+    /// Drop players the remote device no longer lists and ask about new ones
+    async fn sync_player_list(&self, players: &[String], device_id: &str) {
+        let (removed, added) = {
+            let mut known = self.players.write().await;
+            let removed: Vec<String> = known
+                .keys()
+                .filter(|name| !players.contains(name))
+                .cloned()
+                .collect();
+            for name in &removed {
+                known.remove(name);
+            }
+            let added: Vec<String> = players
+                .iter()
+                .filter(|name| !known.contains_key(*name))
+                .cloned()
+                .collect();
+            (removed, added)
+        };
+
+        for player in removed {
+            info!("Remote player {} on {} went away", player, device_id);
+            self.emit(MprisEvent::PlayerRemoved {
+                device_id: device_id.to_string(),
+                player,
+            });
+        }
+
+        for player in added {
+            let packet = self.create_request_now_playing_packet(player);
+            if let Err(e) = self.send_packet(packet).await {
+                debug!("Failed to request now playing: {}", e);
+            }
+        }
+    }
+
+    /// Cached art for a player's current track, requesting it if needed
+    async fn album_art_for(&mut self, player: &str, url: Option<&str>) -> Option<PathBuf> {
+        let url = url?;
+        if let Some(path) = self.album_art_path(url).await {
+            return Some(path);
+        }
+
+        if self.remote_album_art && self.requested_album_art.insert(url.to_string()) {
+            let packet = self.create_request_album_art_packet(player.to_string(), url.to_string());
+            if let Err(e) = self.send_packet(packet).await {
+                debug!("Failed to request album art: {}", e);
+            }
+        }
+        None
+    }
+
+    /// Whether an album art payload should be downloaded
+    ///
+    /// Only art we requested and have not cached yet is accepted, up to
+    /// [`MAX_ALBUM_ART_SIZE`] and at most one download per
+    /// [`ALBUM_ART_INTERVAL`].
+    async fn accept_album_art(&mut self, url: &str, size: i64) -> bool {
+        if !self.requested_album_art.contains(url) {
+            debug!("Ignoring album art that was not requested: {}", url);
+            return false;
+        }
+        if self.album_art_path(url).await.is_some() {
+            debug!("Album art already cached: {}", url);
+            return false;
+        }
+        if size <= 0 || size > MAX_ALBUM_ART_SIZE {
+            warn!("Ignoring album art payload of {} bytes for {}", size, url);
+            return false;
+        }
+        if self
+            .last_album_art
+            .is_some_and(|last| last.elapsed() < ALBUM_ART_INTERVAL)
+        {
+            debug!("Album art arriving too fast, dropping {}", url);
+            return false;
+        }
+        self.last_album_art = Some(Instant::now());
+        true
+    }
+
+    /// Download an album art payload into the cache
+    ///
+    /// Once saved, the player is reported again with its art if it is still
+    /// on the same track.
+    async fn receive_album_art(&mut self, packet: &Packet, device: &Device) {
+        let body = &packet.body;
+        let (Some(player), Some(url)) = (
+            body.get("player").and_then(|v| v.as_str()),
+            body.get("albumArtUrl").and_then(|v| v.as_str()),
+        ) else {
+            debug!("Album art packet without player or URL");
+            return;
+        };
+        let size = packet.payload_size.unwrap_or(0);
+        if !self.accept_album_art(url, size).await {
+            return;
+        }
+        let port = packet
+            .payload_transfer_info
+            .as_ref()
+            .and_then(|info| info.get("port"))
+            .and_then(|v| v.as_u64())
+            .and_then(|port| u16::try_from(port).ok());
+        let (Some(port), Some(host)) = (port, device.host.clone()) else {
+            warn!("Cannot download album art: payload port or device host missing");
+            return;
+        };
+        let Some(tls_config) = self.tls_config.clone() else {
+            warn!("Cannot download album art: TLS config not set");
+            return;
+        };
+
+        let dir = self.album_art_dir.clone();
+        let path = album_art_cache_path(&dir, url);
+        let album_art = self.album_art.clone();
+        let players = self.players.clone();
+        let event_sender = self.event_sender.clone();
+        let device_id = device.id().to_string();
+        let player = player.to_string();
+        let url = url.to_string();
+
+        tokio::spawn(async move {
+            if let Err(e) = tokio::fs::create_dir_all(&dir).await {
+                warn!("Failed to create album art cache {:?}: {}", dir, e);
+                return;
+            }
+            let result = match crate::TlsPayloadClient::new(&host, port, &tls_config).await {
+                Ok(client) => client.receive_file(&path, size as u64).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                warn!("Failed to download album art for {}: {}", player, e);
+                return;
+            }
+
+            debug!("Cached album art for {} at {:?}", player, path);
+            album_art.write().await.insert(url.clone(), path.clone());
+
+            let state = players.read().await.get(&player).cloned();
+            let Some(state) =
+                state.filter(|state| state.metadata.album_art_url.as_deref() == Some(url.as_str()))
+            else {
+                return;
+            };
+            if let Some(sender) = event_sender {
+                let _ = sender.send(MprisEvent::PlayerUpdated {
+                    device_id,
+                    state,
+                    album_art: Some(path),
+                });
+            }
+        });
+    }
+
     /// Handle incoming MPRIS status packet
-    async fn handle_mpris_status(&self, packet: &Packet, device: &Device) {
+    async fn handle_mpris_status(&mut self, packet: &Packet, device: &Device) {
         // Check if this is a player list
         if let Some(player_list) = packet.body.get("playerList") {
             if let Some(players) = player_list.as_array() {
@@ -1103,10 +1414,27 @@ impl MprisPlugin {
                     device.id(),
                     player_names
                 );
+                self.remote_album_art = packet
+                    .body
+                    .get("supportAlbumArtPayload")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                self.sync_player_list(&player_names, device.id()).await;
                 return;
             }
         }
 
+        // Album art we asked for; carries no player status
+        if packet
+            .body
+            .get("transferringAlbumArt")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+        {
+            self.receive_album_art(packet, device).await;
+            return;
+        }
+
         // Otherwise, parse player status
         let player_name = packet
             .body
@@ -1217,9 +1545,18 @@ impl MprisPlugin {
             name: player_name,
             status,
             metadata,
+            updated_at: crate::current_timestamp(),
         };
 
-        self.update_player_state(state).await;
+        let album_art = self
+            .album_art_for(&state.name, state.metadata.album_art_url.as_deref())
+            .await;
+        self.update_player_state(state.clone()).await;
+        self.emit(MprisEvent::PlayerUpdated {
+            device_id: device.id().to_string(),
+            state,
+            album_art,
+        });
     }
 
     /// Handle incoming MPRIS request packet
@@ -1480,14 +1817,29 @@ impl Plugin for MprisPlugin {
             }
         }
 
+        // Ask the remote device which players it has
+        let packet = self.create_request_player_list_packet();
+        if let Err(e) = self.send_packet(packet).await {
+            debug!("Failed to request remote player list: {}", e);
+        }
+
         info!("MPRIS plugin started");
         Ok(())
     }
 
     async fn stop(&mut self) -> Result<()> {
         self.enabled = false;
-        let player_count = self.players.read().await.len();
-        info!("MPRIS plugin stopped - {} players tracked", player_count);
+        let players: Vec<String> = self.players.write().await.drain().map(|(k, _)| k).collect();
+        info!("MPRIS plugin stopped - {} players tracked", players.len());
+
+        if let Some(device_id) = &self.device_id {
+            for player in players {
+                self.emit(MprisEvent::PlayerRemoved {
+                    device_id: device_id.clone(),
+                    player,
+                });
+            }
+        }
         Ok(())
     }
 
@@ -1509,8 +1861,24 @@ impl Plugin for MprisPlugin {
 }
 
 /// Factory for creating MprisPlugin instances
-#[derive(Debug, Clone, Copy)]
-pub struct MprisPluginFactory;
+#[derive(Debug, Clone, Default)]
+pub struct MprisPluginFactory {
+    /// Event channel handed to every created plugin
+    event_sender: Option<mpsc::UnboundedSender<MprisEvent>>,
+}
+
+impl MprisPluginFactory {
+    /// Create factory with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report remote player changes from every created plugin on `sender`
+    pub fn with_events(mut self, sender: mpsc::UnboundedSender<MprisEvent>) -> Self {
+        self.event_sender = Some(sender);
+        self
+    }
+}
 
 impl PluginFactory for MprisPluginFactory {
     fn name(&self) -> &str {
@@ -1534,7 +1902,11 @@ impl PluginFactory for MprisPluginFactory {
     }
 
     fn create(&self) -> Box<dyn Plugin> {
-        Box::new(MprisPlugin::new())
+        let plugin = MprisPlugin::new();
+        match &self.event_sender {
+            Some(sender) => Box::new(plugin.with_events(sender.clone())),
+            None => Box::new(plugin),
+        }
     }
 }
 
@@ -1548,6 +1920,22 @@ mod tests {
         Device::from_discovery(info)
     }
 
+    #[tokio::test]
+    async fn test_album_art_only_accepted_when_requested() {
+        let mut plugin = MprisPlugin::new();
+        let url = "file:///art.jpg";
+
+        assert!(!plugin.accept_album_art(url, 1024).await);
+
+        plugin.requested_album_art.insert(url.to_string());
+        assert!(!plugin.accept_album_art(url, MAX_ALBUM_ART_SIZE + 1).await);
+        assert!(!plugin.accept_album_art(url, 0).await);
+        assert!(plugin.accept_album_art(url, 1024).await);
+
+        // A second push right away is dropped
+        assert!(!plugin.accept_album_art(url, 1024).await);
+    }
+
     #[test]
     fn test_loop_status() {
         assert_eq!(LoopStatus::None.as_str(), "None");
@@ -1895,4 +2283,141 @@ mod tests {
         plugin.handle_packet(&packet, &mut device).await.unwrap();
         // Request logged - actual player control requires DBus which may not be available
     }
+
+    #[test]
+    fn test_position_at() {
+        let mut state = PlayerState {
+            name: "vlc".to_string(),
+            updated_at: 1_000,
+            ..Default::default()
+        };
+        state.status.position = 5_000;
+        state.status.length = 8_000;
+
+        // Paused players stay put
+        assert_eq!(state.position_at(3_000), 5_000);
+
+        state.status.is_playing = true;
+        assert_eq!(state.position_at(3_000), 7_000);
+        // Clamped to the track length
+        assert_eq!(state.position_at(10_000), 8_000);
+        // Clock skew never moves the position backwards
+        assert_eq!(state.position_at(0), 5_000);
+    }
+
+    #[test]
+    fn test_album_art_cache_path() {
+        let dir = Path::new("/cache");
+        let a = album_art_cache_path(dir, "file:///a.jpg");
+        assert_eq!(a, album_art_cache_path(dir, "file:///a.jpg"));
+        assert_ne!(a, album_art_cache_path(dir, "file:///b.jpg"));
+        assert_eq!(a.parent(), Some(dir));
+    }
+
+    #[tokio::test]
+    async fn test_player_list_syncs_players() {
+        let (event_tx, mut events) = mpsc::unbounded_channel();
+        let (packet_tx, mut packets) = mpsc::channel(100);
+        let mut plugin = MprisPlugin::new().with_events(event_tx);
+        let mut device = create_test_device();
+        plugin.init(&device, packet_tx).await.unwrap();
+        plugin.enabled = true;
+
+        for name in ["vlc", "spotify"] {
+            plugin
+                .update_player_state(PlayerState {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .await;
+        }
+
+        let packet = Packet::new("cconnect.mpris", json!({ "playerList": ["vlc", "mpv"] }));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let mut players = plugin.get_player_list().await;
+        players.sort();
+        assert_eq!(players, vec!["vlc".to_string()]);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            MprisEvent::PlayerRemoved {
+                device_id: device.id().to_string(),
+                player: "spotify".to_string(),
+            }
+        );
+
+        // The new player's state is requested
+        let (_, request) = packets.try_recv().unwrap();
+        assert_eq!(request.body["player"], "mpv");
+        assert_eq!(request.body["requestNowPlaying"], true);
+        assert!(packets.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_status_requests_album_art_once() {
+        let (event_tx, mut events) = mpsc::unbounded_channel();
+        let (packet_tx, mut packets) = mpsc::channel(100);
+        let mut plugin = MprisPlugin::new().with_events(event_tx);
+        let mut device = create_test_device();
+        plugin.init(&device, packet_tx).await.unwrap();
+        plugin.enabled = true;
+
+        let list = Packet::new(
+            "cconnect.mpris",
+            json!({ "playerList": [], "supportAlbumArtPayload": true }),
+        );
+        plugin.handle_packet(&list, &mut device).await.unwrap();
+
+        let status = Packet::new(
+            "cconnect.mpris",
+            json!({
+                "player": "spotify",
+                "isPlaying": true,
+                "title": "Track",
+                "albumArtUrl": "file:///art.jpg"
+            }),
+        );
+        plugin.handle_packet(&status, &mut device).await.unwrap();
+        plugin.handle_packet(&status, &mut device).await.unwrap();
+
+        match events.try_recv().unwrap() {
+            MprisEvent::PlayerUpdated {
+                state, album_art, ..
+            } => {
+                assert_eq!(state.name, "spotify");
+                assert!(state.updated_at > 0);
+                assert_eq!(album_art, None);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let (_, request) = packets.try_recv().unwrap();
+        assert_eq!(request.body["albumArtUrl"], "file:///art.jpg");
+        assert!(packets.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stop_removes_players() {
+        let (event_tx, mut events) = mpsc::unbounded_channel();
+        let mut plugin = MprisPlugin::new().with_events(event_tx);
+        let device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+        plugin
+            .update_player_state(PlayerState {
+                name: "vlc".to_string(),
+                ..Default::default()
+            })
+            .await;
+
+        plugin.stop().await.unwrap();
+
+        assert!(plugin.get_player_list().await.is_empty());
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            MprisEvent::PlayerRemoved { player, .. } if player == "vlc"
+        ));
+    }
 }
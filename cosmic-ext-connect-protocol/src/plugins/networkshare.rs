@@ -7,9 +7,11 @@
 //! **Packet Types**:
 //! - `kdeconnect.sftp` - SFTP connection details (incoming)
 //! - `cconnect.sftp` - COSMIC Connect SFTP details (incoming)
+//! - `kdeconnect.sftp.request` - Ask the device to start its SFTP server (outgoing)
 //!
 //! **Capabilities**:
 //! - Incoming: `kdeconnect.sftp`, `cconnect.sftp` - Receive SFTP connection info
+//! - Outgoing: `kdeconnect.sftp.request` - Request fresh credentials
 //!
 //! ## Packet Format
 //!
@@ -34,11 +36,46 @@
 //!
 //! `sshfs -p <port> <user>@<ip>:/ <mountpoint> -o password_stdin`
 //!
+//! ## Mounting
+//!
+//! [`NetworkSharePlugin::mount`] runs sshfs with the password fed through
+//! stdin and tracks the mount; [`NetworkSharePlugin::unmount`] releases it
+//! with `fusermount3 -u` (falling back to `fusermount -u`). Mounts are
+//! released when the plugin stops, which happens when the device disconnects.
+//!
+//! The device's SFTP server presents the key pair of its pairing
+//! certificate as host key. sshfs only accepts that key: it is written to a
+//! per-device known_hosts file (see [`ssh_host_key`]) and checked strictly,
+//! so a mount can't be diverted to another server on the network. Devices
+//! without a stored certificate can't be mounted.
+//!
+//! Commands go through a [`CommandRunner`], so tests can swap in a fake one
+//! with [`NetworkSharePlugin::with_runner`]. A missing sshfs is reported as
+//! [`ProtocolError::UnsupportedFeature`] and a mountpoint that is already
+//! mounted or not empty as [`ProtocolError::DeviceBusy`].
+//!
+//! ## Native SFTP
+//!
+//! With the `native-sftp` feature, [`NetworkSharePlugin::list_dir`],
+//! [`read_file`](NetworkSharePlugin::read_file) and
+//! [`write_file`](NetworkSharePlugin::write_file) reach the share in-process
+//! through a [`NativeSftpClient`](super::sftp_client::NativeSftpClient),
+//! without sshfs or FUSE. One connection per device is opened on first use
+//! and reused until the device sends new credentials or the plugin stops.
+//! The daemon exposes them over D-Bus as `SftpListDir`, `SftpReadFile` and
+//! `SftpWriteFile`, working on a [`detached`](NetworkSharePlugin::detached)
+//! copy so the plugin lock is not held while connecting.
+//! sshfs mounting keeps working alongside it as the fallback.
+//!
 //! ## Public API
 //!
 //! ```rust,ignore
 //! use cosmic_ext_connect_core::plugins::networkshare::NetworkSharePlugin;
 //!
+//! // Mount a device's share, then release it
+//! plugin.mount("device-id", "/run/user/1000/phone").await?;
+//! plugin.unmount("device-id").await?;
+//!
 //! // Get all active shares
 //! let shares = plugin.get_shares().await;
 //!
@@ -53,25 +90,116 @@
 //! }
 //! ```
 //!
+//! ## Freshness
+//!
+//! SFTP credentials are only considered fresh for a limited window after they
+//! were received (5 minutes by default). The window is configurable through
+//! [`NetworkShareConfig`], globally and per device:
+//!
+//! ```rust,ignore
+//! let mut config = NetworkShareConfig::default();
+//! config.freshness_window_secs = 600;
+//! config.device_freshness_overrides.insert("phone-id".to_string(), 60);
+//!
+//! let plugin = NetworkSharePlugin::with_config(config);
+//! ```
+//!
+//! Freshness only gates new mounts. A share that is already mounted is kept
+//! as long as the mount is healthy, even after its credentials go stale.
+//!
+//! Stale shares that are not mounted are dropped, together with any native
+//! SFTP connection to them: lazily by the share accessors, and every
+//! [`STALE_SHARE_REAP_INTERVAL`] by a background task while the plugin runs.
+//! Mounting or connecting with stale credentials sends a
+//! `kdeconnect.sftp.request` and waits up to [`CREDENTIALS_REQUEST_TIMEOUT`]
+//! for the device to answer with new ones.
+//!
 //! ## References
 //!
 //! - [KDE Connect SFTP Plugin](https://invent.kde.org/network/kdeconnect-kde/-/tree/master/plugins/sftp)
 
-use crate::{Device, Packet, Result};
+use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use openssl::bn::BigNumContext;
+use openssl::ec::PointConversionForm;
+use openssl::nid::Nid;
+use openssl::pkey::Id;
+use openssl::x509::X509;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::collections::HashMap;
+use std::fmt;
+use std::io::ErrorKind;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::{Notify, RwLock};
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
+#[cfg(feature = "native-sftp")]
+use super::sftp_client::{NativeSftpClient, SftpEntry};
 use super::{Plugin, PluginFactory};
 
 /// Packet type for SFTP connection info
 pub const PACKET_TYPE_SFTP: &str = "kdeconnect.sftp";
 pub const PACKET_TYPE_CCONNECT_SFTP: &str = "cconnect.sftp";
 
+/// Packet type asking the device to start its SFTP server and send credentials
+pub const PACKET_TYPE_SFTP_REQUEST: &str = "kdeconnect.sftp.request";
+
+/// How often stale shares are dropped while the plugin runs
+pub const STALE_SHARE_REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long a mount waits for the device to send fresh credentials
+pub const CREDENTIALS_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default window during which received SFTP credentials are considered fresh
+pub const DEFAULT_FRESHNESS_WINDOW_SECS: u64 = 300;
+
+/// How long sshfs or fusermount may take before the attempt is abandoned
+pub const MOUNT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Network Share plugin configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkShareConfig {
+    /// How long received SFTP credentials stay fresh, in seconds
+    pub freshness_window_secs: u64,
+
+    /// Per-device freshness windows in seconds, keyed by device ID
+    #[serde(default)]
+    pub device_freshness_overrides: HashMap<String, u64>,
+}
+
+impl NetworkShareConfig {
+    /// Get the freshness window that applies to a device
+    ///
+    /// Returns the device-specific override if set, otherwise the plugin-wide window.
+    pub fn freshness_window_for(&self, device_id: &str) -> Duration {
+        let secs = self
+            .device_freshness_overrides
+            .get(device_id)
+            .copied()
+            .unwrap_or(self.freshness_window_secs);
+        Duration::from_secs(secs)
+    }
+}
+
+impl Default for NetworkShareConfig {
+    fn default() -> Self {
+        Self {
+            freshness_window_secs: DEFAULT_FRESHNESS_WINDOW_SECS,
+            device_freshness_overrides: HashMap::new(),
+        }
+    }
+}
+
 /// SFTP connection details
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SftpInfo {
@@ -88,6 +216,9 @@ pub struct SftpInfo {
     /// Timestamp when this info was received
     #[serde(skip)]
     pub received_at: Option<std::time::Instant>,
+    /// Expected host key in OpenSSH format, from the device's certificate
+    #[serde(skip)]
+    pub host_key: Option<String>,
 }
 
 impl SftpInfo {
@@ -124,31 +255,402 @@ impl SftpInfo {
         )
     }
 
-    /// Check if this connection info is still fresh (within 5 minutes)
+    /// Host pattern of the server in a known_hosts file
+    pub fn known_hosts_host(&self) -> String {
+        match self.effective_port() {
+            22 => self.ip.clone(),
+            port => format!("[{}]:{}", self.ip, port),
+        }
+    }
+
+    /// Check if this connection info is still fresh (within the default window)
     pub fn is_fresh(&self) -> bool {
+        self.is_fresh_within(Duration::from_secs(DEFAULT_FRESHNESS_WINDOW_SECS))
+    }
+
+    /// Check if this connection info was received within the given window
+    pub fn is_fresh_within(&self, window: Duration) -> bool {
         self.received_at
-            .map(|t| t.elapsed().as_secs() < 300)
+            .map(|t| t.elapsed() < window)
             .unwrap_or(false)
     }
 }
 
+/// Append an SSH wire format string
+fn put_ssh_string(blob: &mut Vec<u8>, data: &[u8]) {
+    blob.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    blob.extend_from_slice(data);
+}
+
+/// Append an SSH wire format mpint from big-endian magnitude bytes
+fn put_ssh_mpint(blob: &mut Vec<u8>, magnitude: &[u8]) {
+    if magnitude.first().is_some_and(|byte| byte & 0x80 != 0) {
+        let mut padded = vec![0];
+        padded.extend_from_slice(magnitude);
+        put_ssh_string(blob, &padded);
+    } else {
+        put_ssh_string(blob, magnitude);
+    }
+}
+
+/// OpenSSH public key (`<type> <base64>`) of a DER-encoded certificate
+///
+/// Supports RSA, ECDSA (P-256, P-384, P-521) and Ed25519 keys.
+pub fn ssh_host_key(certificate_der: &[u8]) -> Result<String> {
+    let key = X509::from_der(certificate_der)?.public_key()?;
+    let mut blob = Vec::new();
+
+    let algorithm = match key.id() {
+        Id::RSA => {
+            let rsa = key.rsa()?;
+            put_ssh_string(&mut blob, b"ssh-rsa");
+            put_ssh_mpint(&mut blob, &rsa.e().to_vec());
+            put_ssh_mpint(&mut blob, &rsa.n().to_vec());
+            "ssh-rsa"
+        }
+        Id::EC => {
+            let ec = key.ec_key()?;
+            let (algorithm, curve) = match ec.group().curve_name() {
+                Some(Nid::X9_62_PRIME256V1) => ("ecdsa-sha2-nistp256", "nistp256"),
+                Some(Nid::SECP384R1) => ("ecdsa-sha2-nistp384", "nistp384"),
+                Some(Nid::SECP521R1) => ("ecdsa-sha2-nistp521", "nistp521"),
+                other => {
+                    return Err(ProtocolError::unsupported_feature(format!(
+                        "No SSH host key type for curve {:?}",
+                        other
+                    )))
+                }
+            };
+            let mut ctx = BigNumContext::new()?;
+            let point = ec.public_key().to_bytes(
+                ec.group(),
+                PointConversionForm::UNCOMPRESSED,
+                &mut ctx,
+            )?;
+            put_ssh_string(&mut blob, algorithm.as_bytes());
+            put_ssh_string(&mut blob, curve.as_bytes());
+            put_ssh_string(&mut blob, &point);
+            algorithm
+        }
+        Id::ED25519 => {
+            put_ssh_string(&mut blob, b"ssh-ed25519");
+            put_ssh_string(&mut blob, &key.raw_public_key()?);
+            "ssh-ed25519"
+        }
+        other => {
+            return Err(ProtocolError::unsupported_feature(format!(
+                "No SSH host key type for {:?} certificates",
+                other
+            )))
+        }
+    };
+
+    Ok(format!("{} {}", algorithm, BASE64.encode(blob)))
+}
+
+/// Write a known_hosts file trusting only the share's host key
+///
+/// The file is private to the user and replaced on every mount.
+fn write_known_hosts(path: &Path, info: &SftpInfo, host_key: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    std::io::Write::write_all(
+        &mut file,
+        format!("{} {}\n", info.known_hosts_host(), host_key).as_bytes(),
+    )?;
+    Ok(())
+}
+
+/// Result of a command run by a [`CommandRunner`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunnerOutput {
+    /// Whether the command exited successfully
+    pub success: bool,
+
+    /// Standard error, used in error messages
+    pub stderr: String,
+}
+
+/// Runs the external mount and unmount commands
+#[async_trait]
+pub trait CommandRunner: Send + Sync + fmt::Debug {
+    /// Run `program` with `args`, writing `stdin` to its standard input
+    ///
+    /// Returns an [`ErrorKind::NotFound`] error if the program is not installed.
+    async fn run(
+        &self,
+        program: &str,
+        args: &[String],
+        stdin: Option<&str>,
+    ) -> std::io::Result<RunnerOutput>;
+}
+
+/// Command runner shared between plugin instances
+pub type SharedCommandRunner = Arc<dyn CommandRunner>;
+
+/// Runs commands as child processes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemCommandRunner;
+
+#[async_trait]
+impl CommandRunner for SystemCommandRunner {
+    async fn run(
+        &self,
+        program: &str,
+        args: &[String],
+        stdin: Option<&str>,
+    ) -> std::io::Result<RunnerOutput> {
+        let mut child = tokio::process::Command::new(program)
+            .args(args)
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            pipe.write_all(input.as_bytes()).await?;
+            pipe.write_all(b"\n").await?;
+            // Dropping the pipe closes it so the program stops reading
+        }
+
+        let output = child.wait_with_output().await?;
+        Ok(RunnerOutput {
+            success: output.status.success(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
+    }
+}
+
+/// Whether `path` is the root of a mounted filesystem
+fn is_mountpoint(path: &Path) -> bool {
+    let Some(parent) = path.parent() else {
+        return true;
+    };
+    match (std::fs::metadata(path), std::fs::metadata(parent)) {
+        (Ok(meta), Ok(parent_meta)) => meta.dev() != parent_meta.dev(),
+        _ => false,
+    }
+}
+
+/// Make sure `mountpoint` is an empty directory nothing is mounted on
+///
+/// Creates the directory if it does not exist.
+fn prepare_mountpoint(mountpoint: &Path) -> Result<()> {
+    if !mountpoint.exists() {
+        std::fs::create_dir_all(mountpoint)?;
+        return Ok(());
+    }
+    if !mountpoint.is_dir() {
+        return Err(ProtocolError::Configuration(format!(
+            "Mountpoint {} is not a directory",
+            mountpoint.display()
+        )));
+    }
+    if is_mountpoint(mountpoint) {
+        return Err(ProtocolError::device_busy(format!(
+            "{} is already a mountpoint",
+            mountpoint.display()
+        )));
+    }
+    if std::fs::read_dir(mountpoint)?.next().is_some() {
+        return Err(ProtocolError::device_busy(format!(
+            "Mountpoint {} is not empty",
+            mountpoint.display()
+        )));
+    }
+    Ok(())
+}
+
 /// Network Share plugin for SFTP mounting
 ///
-/// Stores SFTP connection details received from connected devices
-/// and provides an API for accessing them.
+/// Stores SFTP connection details received from connected devices,
+/// provides an API for accessing them and mounts them with sshfs.
 pub struct NetworkSharePlugin {
     /// SFTP connection info keyed by device ID
     shares: Arc<RwLock<HashMap<String, SftpInfo>>>,
+
+    /// Mountpoints of the currently mounted shares, keyed by device ID
+    mounted: Arc<RwLock<HashMap<String, PathBuf>>>,
+
+    /// Plugin configuration
+    config: NetworkShareConfig,
+
+    /// Runs sshfs and fusermount
+    runner: SharedCommandRunner,
+
+    /// Directory for the per-device known_hosts files
+    known_hosts_dir: PathBuf,
+
+    /// Open native SFTP connections, keyed by device ID
+    #[cfg(feature = "native-sftp")]
+    sftp_clients: Arc<RwLock<HashMap<String, Arc<NativeSftpClient>>>>,
+
+    /// Woken whenever a device sends SFTP connection info
+    share_updated: Arc<Notify>,
+
+    /// Sender for credential requests
+    packet_sender: Option<Sender<(String, Packet)>>,
+
+    /// Background task dropping stale shares
+    reaper_handle: Option<JoinHandle<()>>,
+}
+
+/// Remove shares whose credentials are stale and which are not mounted
+///
+/// Returns the device IDs whose shares were removed.
+async fn prune_stale(
+    shares: &RwLock<HashMap<String, SftpInfo>>,
+    mounted: &RwLock<HashMap<String, PathBuf>>,
+    config: &NetworkShareConfig,
+) -> Vec<String> {
+    let mounted = mounted.read().await;
+    let mut shares = shares.write().await;
+
+    let stale: Vec<String> = shares
+        .iter()
+        .filter(|(id, info)| {
+            !mounted.contains_key(*id) && !info.is_fresh_within(config.freshness_window_for(id))
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    for id in &stale {
+        shares.remove(id);
+        debug!("Pruned stale SFTP share for device {}", id);
+    }
+
+    stale
+}
+
+/// Close the native SFTP connections of the given devices
+#[cfg(feature = "native-sftp")]
+async fn close_clients(
+    clients: &RwLock<HashMap<String, Arc<NativeSftpClient>>>,
+    device_ids: &[String],
+) {
+    let closed: Vec<Arc<NativeSftpClient>> = {
+        let mut clients = clients.write().await;
+        device_ids
+            .iter()
+            .filter_map(|id| clients.remove(id))
+            .collect()
+    };
+    for client in closed {
+        client.close().await;
+    }
 }
 
 impl NetworkSharePlugin {
     /// Create a new Network Share plugin
     pub fn new() -> Self {
+        Self::with_config(NetworkShareConfig::default())
+    }
+
+    /// Create with custom configuration
+    pub fn with_config(config: NetworkShareConfig) -> Self {
         Self {
             shares: Arc::new(RwLock::new(HashMap::new())),
+            mounted: Arc::new(RwLock::new(HashMap::new())),
+            config,
+            runner: Arc::new(SystemCommandRunner),
+            known_hosts_dir: dirs::runtime_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("cosmic-ext-connect")
+                .join("sftp"),
+            #[cfg(feature = "native-sftp")]
+            sftp_clients: Arc::new(RwLock::new(HashMap::new())),
+            share_updated: Arc::new(Notify::new()),
+            packet_sender: None,
+            reaper_handle: None,
+        }
+    }
+
+    /// A copy sharing this plugin's shares, mounts and connections
+    ///
+    /// Lets callers work with a share after releasing the plugin lock, so
+    /// slow SFTP operations don't hold up the device's packets (including
+    /// the credentials they may be waiting for). The copy runs no reaper.
+    pub fn detached(&self) -> Self {
+        Self {
+            shares: self.shares.clone(),
+            mounted: self.mounted.clone(),
+            config: self.config.clone(),
+            runner: self.runner.clone(),
+            known_hosts_dir: self.known_hosts_dir.clone(),
+            #[cfg(feature = "native-sftp")]
+            sftp_clients: self.sftp_clients.clone(),
+            share_updated: self.share_updated.clone(),
+            packet_sender: self.packet_sender.clone(),
+            reaper_handle: None,
         }
     }
 
+    /// Use a different command runner for sshfs and fusermount
+    pub fn with_runner(mut self, runner: SharedCommandRunner) -> Self {
+        self.runner = runner;
+        self
+    }
+
+    /// Keep the per-device known_hosts files in a different directory
+    pub fn with_known_hosts_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.known_hosts_dir = dir.into();
+        self
+    }
+
+    /// known_hosts file pinning a device's host key
+    fn known_hosts_path(&self, device_id: &str) -> PathBuf {
+        let name: String = device_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.known_hosts_dir.join(format!("{}.known_hosts", name))
+    }
+
+    /// Get the plugin configuration
+    pub fn config(&self) -> &NetworkShareConfig {
+        &self.config
+    }
+
+    /// Set or clear the freshness window override for a device
+    pub fn set_device_freshness_window(&mut self, device_id: &str, window: Option<Duration>) {
+        match window {
+            Some(window) => {
+                self.config
+                    .device_freshness_overrides
+                    .insert(device_id.to_string(), window.as_secs());
+            }
+            None => {
+                self.config.device_freshness_overrides.remove(device_id);
+            }
+        }
+    }
+
+    /// Get the freshness window that applies to a device
+    pub fn freshness_window(&self, device_id: &str) -> Duration {
+        self.config.freshness_window_for(device_id)
+    }
+
     /// Handle SFTP packet from a device
     async fn handle_sftp_packet(&self, device: &Device, packet: &Packet) -> Result<()> {
         let mut info: SftpInfo = serde_json::from_value(packet.body.clone()).map_err(|e| {
@@ -156,6 +658,14 @@ impl NetworkSharePlugin {
         })?;
 
         info.received_at = Some(std::time::Instant::now());
+        info.host_key = match device.certificate_data.as_deref().map(ssh_host_key) {
+            Some(Ok(host_key)) => Some(host_key),
+            Some(Err(e)) => {
+                warn!("No SFTP host key for {}: {}", device.name(), e);
+                None
+            }
+            None => None,
+        };
 
         info!(
             "Received SFTP connection info from {}: {}",
@@ -168,6 +678,12 @@ impl NetworkSharePlugin {
             .await
             .insert(device.id().to_string(), info);
 
+        // The old connection was authenticated with the previous password
+        #[cfg(feature = "native-sftp")]
+        self.close_sftp_client(device.id()).await;
+
+        self.share_updated.notify_waiters();
+
         debug!("SFTP share stored and ready for mounting");
 
         Ok(())
@@ -177,48 +693,400 @@ impl NetworkSharePlugin {
 
     /// Get all active SFTP shares
     ///
-    /// Returns a map of device ID to SFTP connection info.
+    /// Returns a map of device ID to SFTP connection info. Stale shares are
+    /// pruned first, as in all share accessors.
     pub async fn get_shares(&self) -> HashMap<String, SftpInfo> {
+        self.prune_stale_shares().await;
         self.shares.read().await.clone()
     }
 
     /// Get SFTP share info for a specific device
     pub async fn get_share(&self, device_id: &str) -> Option<SftpInfo> {
+        self.prune_stale_shares().await;
         self.shares.read().await.get(device_id).cloned()
     }
 
     /// Check if any SFTP shares are available
     pub async fn has_shares(&self) -> bool {
+        self.prune_stale_shares().await;
         !self.shares.read().await.is_empty()
     }
 
     /// Get the number of active shares
     pub async fn share_count(&self) -> usize {
+        self.prune_stale_shares().await;
         self.shares.read().await.len()
     }
 
-    /// Get all fresh shares (received within last 5 minutes)
+    /// Get all fresh shares (received within each device's freshness window)
     pub async fn get_fresh_shares(&self) -> HashMap<String, SftpInfo> {
         self.shares
             .read()
             .await
             .iter()
-            .filter(|(_, info)| info.is_fresh())
+            .filter(|(id, info)| info.is_fresh_within(self.freshness_window(id)))
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect()
     }
 
+    /// Get share info for a device only if it can be used for a new mount
+    ///
+    /// New mounts (and remounts after a failure) require fresh credentials,
+    /// since the remote SFTP server may have rotated its password.
+    pub async fn get_mountable_share(&self, device_id: &str) -> Option<SftpInfo> {
+        let window = self.freshness_window(device_id);
+        self.shares
+            .read()
+            .await
+            .get(device_id)
+            .filter(|info| info.is_fresh_within(window))
+            .cloned()
+    }
+
+    /// Get fresh credentials for a device, asking the device if needed
+    ///
+    /// Sends a [`PACKET_TYPE_SFTP_REQUEST`] when the stored credentials are
+    /// stale or missing, then waits up to [`CREDENTIALS_REQUEST_TIMEOUT`] for
+    /// new ones.
+    pub async fn fresh_share_or_request(&self, device_id: &str) -> Result<SftpInfo> {
+        if let Some(info) = self.get_mountable_share(device_id).await {
+            return Ok(info);
+        }
+
+        let no_share = || {
+            ProtocolError::invalid_state(format!("No fresh SFTP share for device {}", device_id))
+        };
+        let Some(sender) = &self.packet_sender else {
+            return Err(no_share());
+        };
+
+        // Listen before asking so a quick reply is not missed
+        let deadline = tokio::time::Instant::now() + CREDENTIALS_REQUEST_TIMEOUT;
+        let updated = self.share_updated.notified();
+        tokio::pin!(updated);
+        updated.as_mut().enable();
+
+        info!("Requesting fresh SFTP credentials from {}", device_id);
+        let packet = Packet::new(
+            PACKET_TYPE_SFTP_REQUEST,
+            serde_json::json!({ "startBrowsing": true }),
+        );
+        sender
+            .send((device_id.to_string(), packet))
+            .await
+            .map_err(|e| ProtocolError::Plugin(format!("Failed to request SFTP share: {}", e)))?;
+
+        loop {
+            if tokio::time::timeout_at(deadline, updated.as_mut())
+                .await
+                .is_err()
+            {
+                return Err(no_share());
+            }
+            if let Some(info) = self.get_mountable_share(device_id).await {
+                return Ok(info);
+            }
+            updated.set(self.share_updated.notified());
+            updated.as_mut().enable();
+        }
+    }
+
+    /// Record that the share for a device has been mounted at `mountpoint`
+    pub async fn mark_mounted(&self, device_id: &str, mountpoint: impl Into<PathBuf>) {
+        self.mounted
+            .write()
+            .await
+            .insert(device_id.to_string(), mountpoint.into());
+    }
+
+    /// Record that the share for a device has been unmounted
+    pub async fn mark_unmounted(&self, device_id: &str) {
+        self.mounted.write().await.remove(device_id);
+    }
+
+    /// Check if the share for a device is currently mounted
+    pub async fn is_mounted(&self, device_id: &str) -> bool {
+        self.mounted.read().await.contains_key(device_id)
+    }
+
+    /// Get the mountpoint of a device's mounted share
+    pub async fn mountpoint(&self, device_id: &str) -> Option<PathBuf> {
+        self.mounted.read().await.get(device_id).cloned()
+    }
+
+    /// Mount a device's share at `mountpoint` with sshfs
+    ///
+    /// Requires fresh credentials (see [`get_mountable_share`](Self::get_mountable_share)).
+    /// The mountpoint is created if missing and must otherwise be an empty,
+    /// unmounted directory. Mounting a share again at the same mountpoint is
+    /// a no-op.
+    pub async fn mount(&self, device_id: &str, mountpoint: impl AsRef<Path>) -> Result<()> {
+        let mountpoint = mountpoint.as_ref();
+
+        if let Some(current) = self.mountpoint(device_id).await {
+            if current == mountpoint {
+                debug!("SFTP share of {} already mounted", device_id);
+                return Ok(());
+            }
+            return Err(ProtocolError::invalid_state(format!(
+                "SFTP share of {} is already mounted at {}",
+                device_id,
+                current.display()
+            )));
+        }
+
+        let info = self.fresh_share_or_request(device_id).await?;
+        let host_key = info.host_key.as_deref().ok_or_else(|| {
+            ProtocolError::invalid_state(format!(
+                "No certificate known for {} to verify its SFTP server",
+                device_id
+            ))
+        })?;
+
+        prepare_mountpoint(mountpoint)?;
+
+        // Only the key of the paired device's certificate is trusted
+        let known_hosts = self.known_hosts_path(device_id);
+        write_known_hosts(&known_hosts, &info, host_key)?;
+        let args = vec![
+            "-p".to_string(),
+            info.effective_port().to_string(),
+            format!("{}@{}:{}", info.user, info.ip, info.effective_path()),
+            mountpoint.display().to_string(),
+            "-o".to_string(),
+            "password_stdin".to_string(),
+            "-o".to_string(),
+            "StrictHostKeyChecking=yes".to_string(),
+            "-o".to_string(),
+            format!("UserKnownHostsFile={}", known_hosts.display()),
+        ];
+
+        info!(
+            "Mounting SFTP share {} at {}",
+            info.connection_string(),
+            mountpoint.display()
+        );
+        let output = self
+            .run_with_timeout("sshfs", &args, Some(&info.password))
+            .await
+            .map_err(|e| {
+                if matches!(&e, ProtocolError::Io(io) if io.kind() == ErrorKind::NotFound) {
+                    ProtocolError::unsupported_feature(
+                        "sshfs is not installed; install it to mount device storage",
+                    )
+                } else {
+                    e
+                }
+            })?;
+
+        if !output.success {
+            return Err(mount_command_error("sshfs", mountpoint, &output.stderr));
+        }
+
+        self.mark_mounted(device_id, mountpoint).await;
+        info!("SFTP share of {} mounted", device_id);
+        Ok(())
+    }
+
+    /// Unmount a device's share
+    ///
+    /// Does nothing if the share is not mounted. The mount stays tracked if
+    /// unmounting fails, e.g. because files on it are still open.
+    pub async fn unmount(&self, device_id: &str) -> Result<()> {
+        let Some(mountpoint) = self.mountpoint(device_id).await else {
+            return Ok(());
+        };
+
+        let args = vec!["-u".to_string(), mountpoint.display().to_string()];
+        let output = match self.run_with_timeout("fusermount3", &args, None).await {
+            Err(ProtocolError::Io(e)) if e.kind() == ErrorKind::NotFound => {
+                self.run_with_timeout("fusermount", &args, None).await?
+            }
+            result => result?,
+        };
+
+        if !output.success {
+            return Err(mount_command_error(
+                "fusermount",
+                &mountpoint,
+                &output.stderr,
+            ));
+        }
+
+        self.mark_unmounted(device_id).await;
+        let _ = std::fs::remove_file(self.known_hosts_path(device_id));
+        info!(
+            "SFTP share of {} unmounted from {}",
+            device_id,
+            mountpoint.display()
+        );
+        Ok(())
+    }
+
+    /// Unmount every mounted share, logging failures
+    pub async fn unmount_all(&self) {
+        let device_ids: Vec<String> = self.mounted.read().await.keys().cloned().collect();
+        for device_id in device_ids {
+            if let Err(e) = self.unmount(&device_id).await {
+                warn!("Failed to unmount SFTP share of {}: {}", device_id, e);
+            }
+        }
+    }
+
+    /// Get the native SFTP connection to a device's share
+    ///
+    /// Reuses the open connection; a new one requires fresh credentials.
+    #[cfg(feature = "native-sftp")]
+    pub async fn sftp_client(&self, device_id: &str) -> Result<Arc<NativeSftpClient>> {
+        if let Some(client) = self.sftp_clients.read().await.get(device_id) {
+            if client.is_open() {
+                return Ok(client.clone());
+            }
+        }
+
+        let info = self.fresh_share_or_request(device_id).await?;
+
+        // Connecting takes up to CONNECT_TIMEOUT, so no lock is held meanwhile
+        let client = Arc::new(NativeSftpClient::connect(&info).await?);
+
+        let mut clients = self.sftp_clients.write().await;
+        // Another task may have connected at the same time; keep its client
+        let existing = clients
+            .get(device_id)
+            .filter(|existing| existing.is_open())
+            .cloned();
+        if let Some(existing) = existing {
+            drop(clients);
+            client.close().await;
+            return Ok(existing);
+        }
+        clients.insert(device_id.to_string(), client.clone());
+        Ok(client)
+    }
+
+    /// List a directory of a device's share, relative to the share root
+    #[cfg(feature = "native-sftp")]
+    pub async fn list_dir(&self, device_id: &str, path: &str) -> Result<Vec<SftpEntry>> {
+        self.sftp_client(device_id).await?.list_dir(path).await
+    }
+
+    /// Read a file from a device's share, relative to the share root
+    #[cfg(feature = "native-sftp")]
+    pub async fn read_file(&self, device_id: &str, path: &str) -> Result<Vec<u8>> {
+        self.sftp_client(device_id).await?.read_file(path).await
+    }
+
+    /// Write a file to a device's share, relative to the share root
+    #[cfg(feature = "native-sftp")]
+    pub async fn write_file(&self, device_id: &str, path: &str, data: &[u8]) -> Result<()> {
+        self.sftp_client(device_id)
+            .await?
+            .write_file(path, data)
+            .await
+    }
+
+    /// Close the native SFTP connection to a device, if open
+    #[cfg(feature = "native-sftp")]
+    pub async fn close_sftp_client(&self, device_id: &str) {
+        let client = self.sftp_clients.write().await.remove(device_id);
+        if let Some(client) = client {
+            client.close().await;
+        }
+    }
+
+    /// Close every native SFTP connection
+    #[cfg(feature = "native-sftp")]
+    pub async fn close_sftp_clients(&self) {
+        let clients: Vec<_> = self.sftp_clients.write().await.drain().collect();
+        for (_, client) in clients {
+            client.close().await;
+        }
+    }
+
+    /// Run a mount command, giving up after [`MOUNT_COMMAND_TIMEOUT`]
+    async fn run_with_timeout(
+        &self,
+        program: &str,
+        args: &[String],
+        stdin: Option<&str>,
+    ) -> Result<RunnerOutput> {
+        tokio::time::timeout(MOUNT_COMMAND_TIMEOUT, self.runner.run(program, args, stdin))
+            .await
+            .map_err(|_| ProtocolError::Timeout(format!("{} did not finish", program)))?
+            .map_err(ProtocolError::from)
+    }
+
+    /// Remove shares whose credentials are stale and which are not mounted
+    ///
+    /// Mounted shares are kept regardless of freshness: a healthy mount should
+    /// not be torn down just because its credentials aged out.
+    ///
+    /// Returns the device IDs whose shares were removed.
+    pub async fn prune_stale_shares(&self) -> Vec<String> {
+        let stale = prune_stale(&self.shares, &self.mounted, &self.config).await;
+        #[cfg(feature = "native-sftp")]
+        close_clients(&self.sftp_clients, &stale).await;
+        stale
+    }
+
+    /// Start dropping stale shares every [`STALE_SHARE_REAP_INTERVAL`]
+    ///
+    /// Replaces the reaper of an earlier start, so restarting the plugin
+    /// never leaves a second one running.
+    fn spawn_reaper(&mut self) {
+        if let Some(handle) = self.reaper_handle.take() {
+            handle.abort();
+        }
+
+        let shares = self.shares.clone();
+        let mounted = self.mounted.clone();
+        let config = self.config.clone();
+        #[cfg(feature = "native-sftp")]
+        let sftp_clients = self.sftp_clients.clone();
+
+        self.reaper_handle = Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(STALE_SHARE_REAP_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                let stale = prune_stale(&shares, &mounted, &config).await;
+                #[cfg(feature = "native-sftp")]
+                close_clients(&sftp_clients, &stale).await;
+                if !stale.is_empty() {
+                    debug!("Reaped {} stale SFTP shares", stale.len());
+                }
+            }
+        }));
+    }
+
     /// Remove a share for a specific device
+    ///
+    /// Only forgets the mount state; use [`unmount`](Self::unmount) first to
+    /// release a mount.
     pub async fn remove_share(&self, device_id: &str) -> Option<SftpInfo> {
+        self.mounted.write().await.remove(device_id);
         self.shares.write().await.remove(device_id)
     }
 
     /// Clear all stored shares
     pub async fn clear_shares(&self) {
+        self.mounted.write().await.clear();
         self.shares.write().await.clear();
     }
 }
 
+/// Turn a failed sshfs or fusermount run into an error
+///
+/// Busy mountpoints are reported as [`ProtocolError::DeviceBusy`].
+fn mount_command_error(program: &str, mountpoint: &Path, stderr: &str) -> ProtocolError {
+    let lower = stderr.to_lowercase();
+    if lower.contains("busy") || lower.contains("not empty") {
+        ProtocolError::device_busy(format!("{} is busy: {}", mountpoint.display(), stderr))
+    } else {
+        ProtocolError::Plugin(format!("{} failed: {}", program, stderr))
+    }
+}
+
 impl Default for NetworkSharePlugin {
     fn default() -> Self {
         Self::new()
@@ -247,14 +1115,15 @@ impl Plugin for NetworkSharePlugin {
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec![]
+        vec![PACKET_TYPE_SFTP_REQUEST.to_string()]
     }
 
     async fn init(
         &mut self,
         device: &Device,
-        _packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
+        packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
     ) -> Result<()> {
+        self.packet_sender = Some(packet_sender);
         info!(
             "NetworkShare plugin initialized for device {}",
             device.name()
@@ -263,11 +1132,18 @@ impl Plugin for NetworkSharePlugin {
     }
 
     async fn start(&mut self) -> Result<()> {
+        self.spawn_reaper();
         info!("NetworkShare plugin started");
         Ok(())
     }
 
     async fn stop(&mut self) -> Result<()> {
+        if let Some(handle) = self.reaper_handle.take() {
+            handle.abort();
+        }
+        self.unmount_all().await;
+        #[cfg(feature = "native-sftp")]
+        self.close_sftp_clients().await;
         self.clear_shares().await;
         info!("NetworkShare plugin stopped");
         Ok(())
@@ -287,8 +1163,23 @@ impl Plugin for NetworkSharePlugin {
 }
 
 /// Factory for creating NetworkSharePlugin instances
-#[derive(Debug, Clone, Copy)]
-pub struct NetworkSharePluginFactory;
+#[derive(Debug, Clone, Default)]
+pub struct NetworkSharePluginFactory {
+    /// Configuration applied to every created plugin
+    config: NetworkShareConfig,
+}
+
+impl NetworkSharePluginFactory {
+    /// Create factory with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create factory with explicit configuration
+    pub fn with_config(config: NetworkShareConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl PluginFactory for NetworkSharePluginFactory {
     fn name(&self) -> &str {
@@ -303,11 +1194,11 @@ impl PluginFactory for NetworkSharePluginFactory {
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec![]
+        vec![PACKET_TYPE_SFTP_REQUEST.to_string()]
     }
 
     fn create(&self) -> Box<dyn Plugin> {
-        Box::new(NetworkSharePlugin::new())
+        Box::new(NetworkSharePlugin::with_config(self.config.clone()))
     }
 }
 
@@ -344,7 +1235,7 @@ mod tests {
 
     #[test]
     fn test_factory() {
-        let factory = NetworkSharePluginFactory;
+        let factory = NetworkSharePluginFactory::new();
         assert_eq!(factory.name(), "networkshare");
         assert!(factory
             .incoming_capabilities()
@@ -352,12 +1243,15 @@ mod tests {
         assert!(factory
             .incoming_capabilities()
             .contains(&PACKET_TYPE_CCONNECT_SFTP.to_string()));
-        assert!(factory.outgoing_capabilities().is_empty());
+        assert_eq!(
+            factory.outgoing_capabilities(),
+            vec![PACKET_TYPE_SFTP_REQUEST.to_string()]
+        );
     }
 
     #[test]
     fn test_factory_create() {
-        let factory = NetworkSharePluginFactory;
+        let factory = NetworkSharePluginFactory::new();
         let plugin = factory.create();
         assert_eq!(plugin.name(), "networkshare");
     }
@@ -373,6 +1267,7 @@ mod tests {
             password: "pass".to_string(),
             path: None,
             received_at: None,
+            host_key: None,
         };
         assert_eq!(info.effective_port(), 22);
     }
@@ -386,6 +1281,7 @@ mod tests {
             password: "pass".to_string(),
             path: None,
             received_at: None,
+            host_key: None,
         };
         assert_eq!(info.effective_port(), 1739);
     }
@@ -399,6 +1295,7 @@ mod tests {
             password: "pass".to_string(),
             path: None,
             received_at: None,
+            host_key: None,
         };
         assert_eq!(info.effective_path(), "/");
     }
@@ -412,6 +1309,7 @@ mod tests {
             password: "pass".to_string(),
             path: Some("/storage/emulated/0".to_string()),
             received_at: None,
+            host_key: None,
         };
         assert_eq!(info.effective_path(), "/storage/emulated/0");
     }
@@ -425,6 +1323,7 @@ mod tests {
             password: "secret".to_string(),
             path: Some("/sdcard".to_string()),
             received_at: None,
+            host_key: None,
         };
         assert_eq!(
             info.connection_string(),
@@ -441,6 +1340,7 @@ mod tests {
             password: "secret".to_string(),
             path: Some("/sdcard".to_string()),
             received_at: None,
+            host_key: None,
         };
         let cmd = info.sshfs_command("/mnt/phone");
         assert!(cmd.contains("sshfs -p 1739"));
@@ -458,6 +1358,7 @@ mod tests {
             password: "pass".to_string(),
             path: None,
             received_at: None,
+            host_key: None,
         };
         assert!(!info.is_fresh());
     }
@@ -471,10 +1372,110 @@ mod tests {
             password: "pass".to_string(),
             path: None,
             received_at: Some(std::time::Instant::now()),
+            host_key: None,
         };
         assert!(info.is_fresh());
     }
 
+    fn sftp_info_received_ago(age: Duration) -> SftpInfo {
+        SftpInfo {
+            ip: "192.168.1.10".to_string(),
+            port: None,
+            user: "test".to_string(),
+            password: "pass".to_string(),
+            path: None,
+            received_at: std::time::Instant::now().checked_sub(age),
+            host_key: None,
+        }
+    }
+
+    #[test]
+    fn test_sftp_info_is_fresh_within_window() {
+        let cases = [
+            (Duration::from_secs(30), Duration::from_secs(60), true),
+            (Duration::from_secs(90), Duration::from_secs(60), false),
+            (Duration::from_secs(400), Duration::from_secs(3600), true),
+            (Duration::from_secs(400), Duration::from_secs(300), false),
+        ];
+
+        for (age, window, expected) in cases {
+            let info = sftp_info_received_ago(age);
+            assert_eq!(
+                info.is_fresh_within(window),
+                expected,
+                "age {:?} with window {:?}",
+                age,
+                window
+            );
+        }
+    }
+
+    #[test]
+    fn test_sftp_info_is_fresh_uses_default_window() {
+        assert!(sftp_info_received_ago(Duration::from_secs(299)).is_fresh());
+        assert!(!sftp_info_received_ago(Duration::from_secs(301)).is_fresh());
+    }
+
+    // ========== Configuration Tests ==========
+
+    #[test]
+    fn test_config_default_window() {
+        let config = NetworkShareConfig::default();
+        assert_eq!(config.freshness_window_secs, DEFAULT_FRESHNESS_WINDOW_SECS);
+        assert_eq!(
+            config.freshness_window_for("any-device"),
+            Duration::from_secs(DEFAULT_FRESHNESS_WINDOW_SECS)
+        );
+    }
+
+    #[test]
+    fn test_config_device_override() {
+        let mut config = NetworkShareConfig {
+            freshness_window_secs: 600,
+            ..Default::default()
+        };
+        config
+            .device_freshness_overrides
+            .insert("short".to_string(), 30);
+
+        assert_eq!(
+            config.freshness_window_for("short"),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            config.freshness_window_for("other"),
+            Duration::from_secs(600)
+        );
+    }
+
+    #[test]
+    fn test_set_device_freshness_window() {
+        let mut plugin = NetworkSharePlugin::new();
+        plugin.set_device_freshness_window("phone", Some(Duration::from_secs(42)));
+        assert_eq!(plugin.freshness_window("phone"), Duration::from_secs(42));
+
+        plugin.set_device_freshness_window("phone", None);
+        assert_eq!(
+            plugin.freshness_window("phone"),
+            Duration::from_secs(DEFAULT_FRESHNESS_WINDOW_SECS)
+        );
+    }
+
+    #[test]
+    fn test_factory_with_config() {
+        let config = NetworkShareConfig {
+            freshness_window_secs: 1200,
+            ..Default::default()
+        };
+        let factory = NetworkSharePluginFactory::with_config(config.clone());
+        let plugin = factory.create();
+        let plugin = plugin
+            .as_any()
+            .downcast_ref::<NetworkSharePlugin>()
+            .unwrap();
+        assert_eq!(plugin.config(), &config);
+    }
+
     // ========== Plugin Lifecycle Tests ==========
 
     #[tokio::test]
@@ -666,6 +1667,20 @@ mod tests {
         assert_eq!(plugin.share_count().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_restart_replaces_reaper() {
+        let mut plugin = NetworkSharePlugin::new();
+
+        plugin.start().await.unwrap();
+        let first = plugin.reaper_handle.as_ref().unwrap().abort_handle();
+        plugin.start().await.unwrap();
+        tokio::task::yield_now().await;
+
+        assert!(first.is_finished());
+        assert!(!plugin.reaper_handle.as_ref().unwrap().is_finished());
+        plugin.stop().await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_stop_clears_shares() {
         let mut plugin = NetworkSharePlugin::new();
@@ -707,6 +1722,148 @@ mod tests {
         assert_eq!(fresh.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_get_fresh_shares_honors_windows() {
+        let mut config = NetworkShareConfig {
+            freshness_window_secs: 60,
+            ..Default::default()
+        };
+        config
+            .device_freshness_overrides
+            .insert("long-window".to_string(), 3600);
+        let plugin = NetworkSharePlugin::with_config(config);
+
+        {
+            let mut shares = plugin.shares.write().await;
+            shares.insert(
+                "long-window".to_string(),
+                sftp_info_received_ago(Duration::from_secs(120)),
+            );
+            shares.insert(
+                "default-window".to_string(),
+                sftp_info_received_ago(Duration::from_secs(120)),
+            );
+        }
+
+        let fresh = plugin.get_fresh_shares().await;
+        assert_eq!(fresh.len(), 1);
+        assert!(fresh.contains_key("long-window"));
+
+        assert!(plugin.get_mountable_share("long-window").await.is_some());
+        assert!(plugin.get_mountable_share("default-window").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_mounted_stale_shares() {
+        let plugin = NetworkSharePlugin::with_config(NetworkShareConfig {
+            freshness_window_secs: 60,
+            ..Default::default()
+        });
+
+        {
+            let mut shares = plugin.shares.write().await;
+            shares.insert(
+                "mounted".to_string(),
+                sftp_info_received_ago(Duration::from_secs(120)),
+            );
+            shares.insert(
+                "unmounted".to_string(),
+                sftp_info_received_ago(Duration::from_secs(120)),
+            );
+            shares.insert(
+                "fresh".to_string(),
+                sftp_info_received_ago(Duration::from_secs(10)),
+            );
+        }
+        plugin.mark_mounted("mounted", "/mnt/mounted").await;
+
+        let pruned = plugin.prune_stale_shares().await;
+        assert_eq!(pruned, vec!["unmounted".to_string()]);
+        assert!(plugin.get_share("mounted").await.is_some());
+        assert!(plugin.get_share("fresh").await.is_some());
+        assert!(plugin.is_mounted("mounted").await);
+
+        // Once unmounted, the stale share can no longer be remounted and is pruned
+        plugin.mark_unmounted("mounted").await;
+        assert!(plugin.get_mountable_share("mounted").await.is_none());
+        assert_eq!(
+            plugin.prune_stale_shares().await,
+            vec!["mounted".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stale_share_not_returned() {
+        let plugin = NetworkSharePlugin::new();
+        {
+            let mut shares = plugin.shares.write().await;
+            shares.insert(
+                "old".to_string(),
+                sftp_info_received_ago(Duration::from_secs(DEFAULT_FRESHNESS_WINDOW_SECS + 1)),
+            );
+            shares.insert(
+                "new".to_string(),
+                sftp_info_received_ago(Duration::from_secs(10)),
+            );
+        }
+
+        let shares = plugin.get_shares().await;
+        assert_eq!(shares.len(), 1);
+        assert!(shares.contains_key("new"));
+        assert!(plugin.get_share("old").await.is_none());
+        assert_eq!(plugin.share_count().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_mount_requests_fresh_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut device = create_test_device_with_id("phone", "Phone");
+        device.certificate_data = Some(
+            crate::CertificateInfo::generate("phone")
+                .unwrap()
+                .certificate,
+        );
+        let runner = Arc::new(FakeRunner::default());
+        let mut plugin = NetworkSharePlugin::new()
+            .with_runner(runner.clone())
+            .with_known_hosts_dir(dir.path().join("known_hosts"));
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        plugin.init(&device, tx).await.unwrap();
+        plugin.shares.write().await.insert(
+            "phone".to_string(),
+            sftp_info_received_ago(Duration::from_secs(DEFAULT_FRESHNESS_WINDOW_SECS + 1)),
+        );
+
+        let plugin = Arc::new(plugin);
+        let responder = {
+            let plugin = plugin.clone();
+            tokio::spawn(async move {
+                let (device_id, packet) = rx.recv().await.unwrap();
+                assert_eq!(device_id, "phone");
+                assert!(packet.is_type(PACKET_TYPE_SFTP_REQUEST));
+
+                let reply = Packet::new(
+                    PACKET_TYPE_SFTP,
+                    json!({
+                        "ip": "192.168.1.10",
+                        "user": "kdeconnect",
+                        "password": "rotated"
+                    }),
+                );
+                plugin.handle_sftp_packet(&device, &reply).await.unwrap();
+            })
+        };
+
+        plugin
+            .mount("phone", dir.path().join("phone"))
+            .await
+            .unwrap();
+        responder.await.unwrap();
+
+        assert!(plugin.is_mounted("phone").await);
+        assert_eq!(runner.calls()[0].2.as_deref(), Some("rotated"));
+    }
+
     #[tokio::test]
     async fn test_share_update_replaces_old() {
         let mut plugin = NetworkSharePlugin::new();
@@ -740,6 +1897,269 @@ mod tests {
         assert_eq!(share.user, "user2");
     }
 
+    // ========== Mount Tests ==========
+
+    /// A recorded runner call: (program, args, stdin)
+    type RunnerCall = (String, Vec<String>, Option<String>);
+
+    /// Runner that records calls instead of running anything
+    #[derive(Debug, Default)]
+    struct FakeRunner {
+        calls: std::sync::Mutex<Vec<RunnerCall>>,
+        missing: Vec<&'static str>,
+        stderr: Option<&'static str>,
+    }
+
+    impl FakeRunner {
+        fn missing(programs: &[&'static str]) -> Self {
+            Self {
+                missing: programs.to_vec(),
+                ..Default::default()
+            }
+        }
+
+        fn failing(stderr: &'static str) -> Self {
+            Self {
+                stderr: Some(stderr),
+                ..Default::default()
+            }
+        }
+
+        fn calls(&self) -> Vec<RunnerCall> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl CommandRunner for FakeRunner {
+        async fn run(
+            &self,
+            program: &str,
+            args: &[String],
+            stdin: Option<&str>,
+        ) -> std::io::Result<RunnerOutput> {
+            self.calls.lock().unwrap().push((
+                program.to_string(),
+                args.to_vec(),
+                stdin.map(str::to_string),
+            ));
+            if self.missing.contains(&program) {
+                return Err(std::io::Error::from(ErrorKind::NotFound));
+            }
+            Ok(RunnerOutput {
+                success: self.stderr.is_none(),
+                stderr: self.stderr.unwrap_or_default().to_string(),
+            })
+        }
+    }
+
+    /// Host key the test shares expect
+    const HOST_KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIHGK3dwPXGyrYaTz4Hbz0XSbJPDqTVd0qpc0Q1qD0Ynq";
+
+    async fn plugin_with_share(
+        runner: Arc<FakeRunner>,
+        known_hosts_dir: &Path,
+    ) -> NetworkSharePlugin {
+        let plugin = NetworkSharePlugin::new()
+            .with_runner(runner)
+            .with_known_hosts_dir(known_hosts_dir);
+        plugin.shares.write().await.insert(
+            "phone".to_string(),
+            SftpInfo {
+                ip: "192.168.1.10".to_string(),
+                port: Some(1739),
+                user: "kdeconnect".to_string(),
+                password: "secret".to_string(),
+                path: Some("/sdcard".to_string()),
+                received_at: Some(std::time::Instant::now()),
+                host_key: Some(HOST_KEY.to_string()),
+            },
+        );
+        plugin
+    }
+
+    #[tokio::test]
+    async fn test_mount_then_unmount_clears_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let mountpoint = dir.path().join("phone");
+        let runner = Arc::new(FakeRunner::default());
+        let plugin = plugin_with_share(runner.clone(), &dir.path().join("known_hosts")).await;
+
+        plugin.mount("phone", &mountpoint).await.unwrap();
+        assert!(plugin.is_mounted("phone").await);
+        assert_eq!(plugin.mountpoint("phone").await, Some(mountpoint.clone()));
+        assert!(mountpoint.is_dir());
+
+        // Only the device's own host key is trusted
+        let known_hosts = dir.path().join("known_hosts").join("phone.known_hosts");
+        assert_eq!(
+            std::fs::read_to_string(&known_hosts).unwrap(),
+            format!("[192.168.1.10]:1739 {}\n", HOST_KEY)
+        );
+
+        // Mounting again at the same place is a no-op
+        plugin.mount("phone", &mountpoint).await.unwrap();
+
+        plugin.unmount("phone").await.unwrap();
+        assert!(!plugin.is_mounted("phone").await);
+        assert!(plugin.mountpoint("phone").await.is_none());
+
+        let calls = runner.calls();
+        assert_eq!(calls.len(), 2);
+        let (program, args, stdin) = &calls[0];
+        assert_eq!(program, "sshfs");
+        assert!(args.contains(&"kdeconnect@192.168.1.10:/sdcard".to_string()));
+        assert!(args.contains(&"password_stdin".to_string()));
+        assert!(args.contains(&"StrictHostKeyChecking=yes".to_string()));
+        assert!(args.contains(&format!("UserKnownHostsFile={}", known_hosts.display())));
+        assert!(!args.iter().any(|arg| arg.contains("secret")));
+        assert_eq!(stdin.as_deref(), Some("secret"));
+        assert_eq!(calls[1].0, "fusermount3");
+        assert_eq!(calls[1].1[0], "-u");
+        assert!(!known_hosts.exists());
+    }
+
+    #[tokio::test]
+    async fn test_detached_copy_shares_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = Arc::new(FakeRunner::default());
+        let plugin = plugin_with_share(runner, &dir.path().join("known_hosts")).await;
+
+        let detached = plugin.detached();
+        detached
+            .mount("phone", dir.path().join("phone"))
+            .await
+            .unwrap();
+        assert!(plugin.is_mounted("phone").await);
+        assert!(detached.reaper_handle.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mount_without_host_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = Arc::new(FakeRunner::default());
+        let plugin = plugin_with_share(runner.clone(), &dir.path().join("known_hosts")).await;
+        if let Some(info) = plugin.shares.write().await.get_mut("phone") {
+            info.host_key = None;
+        }
+
+        let err = plugin
+            .mount("phone", dir.path().join("phone"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProtocolError::InvalidState(_)));
+        assert!(runner.calls().is_empty());
+    }
+
+    #[test]
+    fn test_ssh_host_key_from_certificate() {
+        let certificate = crate::CertificateInfo::generate("phone").unwrap();
+        let host_key = ssh_host_key(&certificate.certificate).unwrap();
+
+        let (algorithm, encoded) = host_key.split_once(' ').unwrap();
+        let blob = BASE64.decode(encoded).unwrap();
+        let len = u32::from_be_bytes(blob[..4].try_into().unwrap()) as usize;
+        assert_eq!(&blob[4..4 + len], algorithm.as_bytes());
+
+        assert!(ssh_host_key(b"not a certificate").is_err());
+    }
+
+    #[test]
+    fn test_known_hosts_host() {
+        let mut info = sftp_info_received_ago(Duration::ZERO);
+        assert_eq!(info.known_hosts_host(), "192.168.1.10");
+        info.port = Some(1739);
+        assert_eq!(info.known_hosts_host(), "[192.168.1.10]:1739");
+    }
+
+    #[tokio::test]
+    async fn test_unmount_falls_back_to_fusermount() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = Arc::new(FakeRunner::missing(&["fusermount3"]));
+        let plugin = plugin_with_share(runner.clone(), &dir.path().join("known_hosts")).await;
+
+        plugin
+            .mount("phone", dir.path().join("phone"))
+            .await
+            .unwrap();
+        plugin.unmount("phone").await.unwrap();
+
+        assert!(!plugin.is_mounted("phone").await);
+        assert_eq!(runner.calls().last().unwrap().0, "fusermount");
+    }
+
+    #[tokio::test]
+    async fn test_mount_without_sshfs() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = Arc::new(FakeRunner::missing(&["sshfs"]));
+        let plugin = plugin_with_share(runner, &dir.path().join("known_hosts")).await;
+
+        let err = plugin
+            .mount("phone", dir.path().join("phone"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProtocolError::UnsupportedFeature(_)));
+        assert!(!plugin.is_mounted("phone").await);
+    }
+
+    #[tokio::test]
+    async fn test_mount_busy_mountpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("file"), "data").unwrap();
+        let runner = Arc::new(FakeRunner::default());
+        let plugin = plugin_with_share(runner.clone(), &dir.path().join("known_hosts")).await;
+
+        let err = plugin.mount("phone", dir.path()).await.unwrap_err();
+        assert!(matches!(err, ProtocolError::DeviceBusy(_)));
+        assert!(runner.calls().is_empty());
+        assert!(!plugin.is_mounted("phone").await);
+    }
+
+    #[tokio::test]
+    async fn test_unmount_busy_keeps_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = Arc::new(FakeRunner::failing(
+            "fusermount3: failed to unmount: Device or resource busy",
+        ));
+        let plugin = plugin_with_share(runner, &dir.path().join("known_hosts")).await;
+        plugin.mark_mounted("phone", dir.path()).await;
+
+        let err = plugin.unmount("phone").await.unwrap_err();
+        assert!(matches!(err, ProtocolError::DeviceBusy(_)));
+        assert!(plugin.is_mounted("phone").await);
+    }
+
+    #[tokio::test]
+    async fn test_mount_requires_fresh_share() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = Arc::new(FakeRunner::default());
+        let plugin = NetworkSharePlugin::new().with_runner(runner.clone());
+
+        let err = plugin
+            .mount("phone", dir.path().join("phone"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProtocolError::InvalidState(_)));
+        assert!(runner.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stop_unmounts_shares() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = Arc::new(FakeRunner::default());
+        let mut plugin = plugin_with_share(runner.clone(), &dir.path().join("known_hosts")).await;
+        plugin
+            .mount("phone", dir.path().join("phone"))
+            .await
+            .unwrap();
+
+        plugin.stop().await.unwrap();
+
+        assert!(!plugin.is_mounted("phone").await);
+        assert_eq!(runner.calls().last().unwrap().0, "fusermount3");
+    }
+
     // ========== Capability Tests ==========
 
     #[test]
@@ -751,9 +2171,9 @@ mod tests {
     }
 
     #[test]
-    fn test_outgoing_capabilities_empty() {
+    fn test_outgoing_capabilities() {
         let plugin = NetworkSharePlugin::new();
         let caps = plugin.outgoing_capabilities();
-        assert!(caps.is_empty());
+        assert_eq!(caps, vec![PACKET_TYPE_SFTP_REQUEST.to_string()]);
     }
 }
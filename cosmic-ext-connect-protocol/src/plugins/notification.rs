@@ -10,10 +10,11 @@
 //! - `cconnect.notification.request` - Request all notifications or dismiss one
 //! - `cconnect.notification.action` - Trigger notification action button
 //! - `cconnect.notification.reply` - Reply to notification (chat apps)
+//! - `cconnect.notification.dismissed` - Desktop notification was closed
 //!
 //! **Capabilities**:
-//! - Incoming: All four packet types
-//! - Outgoing: All four packet types
+//! - Incoming: The first four packet types
+//! - Outgoing: All five packet types
 //!
 //! ## Packet Formats
 //!
@@ -132,6 +133,45 @@
 //! - `key` (string): The notification ID that contains the action
 //! - `action` (string): The action ID (from `actionButtons[].id`)
 //!
+//! ### Inline Reply (Android → Desktop)
+//!
+//! Forwarded desktop notifications whose app supports inline replies carry a
+//! `requestReplyId` (and optionally a `replyPlaceholder`). Text typed on the
+//! remote device is sent back with the same ID:
+//!
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.notification.reply",
+//!     "body": {
+//!         "requestReplyId": "desktop-Thunderbird-1704067200000",
+//!         "message": "Sounds good, see you then"
+//!     }
+//! }
+//! ```
+//!
+//! ### Notification Icons (Desktop → Android)
+//!
+//! A forwarded notification's icon is sent as a PNG payload, with its SHA-256
+//! in `payloadHash`. Each icon is attached only the first time it is sent to
+//! a device during a connection; later notifications carry just the hash:
+//!
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.notification",
+//!     "body": {
+//!         "id": "desktop-Thunderbird-1704067200000",
+//!         "appName": "Thunderbird",
+//!         "title": "New message",
+//!         "text": "Lunch tomorrow?",
+//!         "payloadHash": "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+//!     },
+//!     "payloadSize": 4096,
+//!     "payloadTransferInfo": { "port": 1739 }
+//! }
+//! ```
+//!
 //! ### Notification Dismissal (Android → Desktop)
 //!
 //! Sent when notification is dismissed on Android:
@@ -147,12 +187,27 @@
 //! }
 //! ```
 //!
+//! ### Desktop Notification Closed (Desktop → Android)
+//!
+//! Sent when a forwarded desktop notification is closed, so the device
+//! removes its copy:
+//!
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.notification.dismissed",
+//!     "body": {
+//!         "id": "desktop-Thunderbird-1704067200000"
+//!     }
+//! }
+//! ```
+//!
 //! ## Features
 //!
 //! - **Notification Mirroring**: Display remote notifications locally
 //! - **Dismissal Sync**: Dismiss notification on one device, gone on all
 //! - **Action Buttons**: Trigger notification actions (future)
-//! - **Inline Replies**: Reply to messages directly (future)
+//! - **Inline Replies**: Reply to desktop notifications from the remote device
 //! - **Icon Transfer**: Download notification icons (future)
 //!
 //! ## Use Cases
@@ -190,12 +245,16 @@ use crate::{Device, Packet, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, RwLock};
 use tracing::{debug, info, warn};
 
 use super::{Plugin, PluginFactory};
 
+/// Maximum number of sent icon hashes remembered per connection
+pub const MAX_SENT_ICONS: usize = 128;
+
 /// Notification urgency level
 ///
 /// Follows the freedesktop.org notification spec urgency levels.
@@ -804,6 +863,10 @@ pub struct NotificationPlugin {
 
     /// Active notifications by ID
     notifications: Arc<RwLock<HashMap<String, Notification>>>,
+
+    /// Hashes of icons already sent to the device during this connection,
+    /// least recently used first
+    sent_icons: VecDeque<String>,
 }
 
 impl NotificationPlugin {
@@ -821,6 +884,7 @@ impl NotificationPlugin {
         Self {
             device_id: None,
             notifications: Arc::new(RwLock::new(HashMap::new())),
+            sent_icons: VecDeque::new(),
         }
     }
 
@@ -978,6 +1042,113 @@ impl NotificationPlugin {
         Packet::new("cconnect.notification.action", body)
     }
 
+    /// Create an inline reply packet (Android → Desktop)
+    ///
+    /// # Arguments
+    ///
+    /// * `request_reply_id` - The `requestReplyId` of the notification being answered
+    /// * `message` - The reply text
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::plugins::notification::NotificationPlugin;
+    ///
+    /// let plugin = NotificationPlugin::new();
+    /// let packet = plugin.create_reply_packet("desktop-App-123", "On my way");
+    ///
+    /// assert_eq!(packet.packet_type, "cconnect.notification.reply");
+    /// assert_eq!(packet.body["requestReplyId"], "desktop-App-123");
+    /// assert_eq!(packet.body["message"], "On my way");
+    /// ```
+    pub fn create_reply_packet(&self, request_reply_id: &str, message: &str) -> Packet {
+        let body = json!({
+            "requestReplyId": request_reply_id,
+            "message": message
+        });
+        Packet::new("cconnect.notification.reply", body)
+    }
+
+    /// Mark a forwarded notification packet as supporting inline replies
+    ///
+    /// The remote device offers a reply field and answers with a
+    /// `cconnect.notification.reply` packet carrying `request_reply_id`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::plugins::notification::NotificationPlugin;
+    ///
+    /// let mut packet = NotificationPlugin::create_desktop_notification_packet(
+    ///     "Chat", "Alice", "Lunch?", 1704067200000, None, &[], None, None, None,
+    /// );
+    /// NotificationPlugin::set_request_reply_id(&mut packet, "desktop-Chat-1704067200000", None);
+    ///
+    /// assert_eq!(packet.body["requestReplyId"], "desktop-Chat-1704067200000");
+    /// ```
+    pub fn set_request_reply_id(
+        packet: &mut Packet,
+        request_reply_id: &str,
+        placeholder: Option<&str>,
+    ) {
+        packet.body["requestReplyId"] = json!(request_reply_id);
+        if let Some(placeholder) = placeholder {
+            packet.body["replyPlaceholder"] = json!(placeholder);
+        }
+    }
+
+    /// Hash identifying a notification icon in `payloadHash`
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::plugins::notification::NotificationPlugin;
+    ///
+    /// let hash = NotificationPlugin::icon_hash(b"png bytes");
+    /// assert_eq!(hash, NotificationPlugin::icon_hash(b"png bytes"));
+    /// assert_eq!(hash.len(), 64);
+    /// ```
+    pub fn icon_hash(icon: &[u8]) -> String {
+        hex::encode(Sha256::digest(icon))
+    }
+
+    /// Tag a forwarded notification packet with its icon's hash
+    ///
+    /// The icon itself is attached as the packet's payload by whoever sends it,
+    /// unless the device already received it (see [`Self::needs_icon`]).
+    pub fn set_icon_hash(packet: &mut Packet, hash: &str) {
+        packet.body["payloadHash"] = json!(hash);
+    }
+
+    /// Check whether an icon has to be attached as a payload
+    ///
+    /// Returns `false` if the device already received the icon during this
+    /// connection, which also marks it as recently used.
+    pub fn needs_icon(&mut self, hash: &str) -> bool {
+        match self.sent_icons.iter().position(|sent| sent == hash) {
+            Some(index) => {
+                if let Some(sent) = self.sent_icons.remove(index) {
+                    self.sent_icons.push_back(sent);
+                }
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Record that the device received an icon
+    ///
+    /// Call once the payload transfer succeeded. Only the
+    /// [`MAX_SENT_ICONS`] most recently used hashes are remembered.
+    pub fn mark_icon_sent(&mut self, hash: &str) {
+        if self.needs_icon(hash) {
+            self.sent_icons.push_back(hash.to_string());
+            while self.sent_icons.len() > MAX_SENT_ICONS {
+                self.sent_icons.pop_front();
+            }
+        }
+    }
+
     /// Create a notification dismissal packet (Android → Desktop)
     ///
     /// This packet is sent when a notification is dismissed on the remote device
@@ -1008,6 +1179,29 @@ impl NotificationPlugin {
         Packet::new("cconnect.notification", body)
     }
 
+    /// Create a desktop notification closed packet (Desktop → Android)
+    ///
+    /// Sent when a forwarded desktop notification is closed (expired,
+    /// dismissed, or closed by its app), so the device removes its copy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::plugins::notification::NotificationPlugin;
+    ///
+    /// let plugin = NotificationPlugin::new();
+    /// let packet = plugin.create_desktop_dismissed_packet("desktop-App-123");
+    ///
+    /// assert_eq!(packet.packet_type, "cconnect.notification.dismissed");
+    /// assert_eq!(packet.body["id"], "desktop-App-123");
+    /// ```
+    pub fn create_desktop_dismissed_packet(&self, notification_id: &str) -> Packet {
+        let body = json!({
+            "id": notification_id
+        });
+        Packet::new("cconnect.notification.dismissed", body)
+    }
+
     /// Create a notification packet from a captured desktop notification
     ///
     /// Creates a notification packet suitable for sending desktop notifications to
@@ -1315,6 +1509,7 @@ impl Plugin for NotificationPlugin {
             "cconnect.notification.request".to_string(),
             "cconnect.notification.action".to_string(),
             "cconnect.notification.reply".to_string(),
+            "cconnect.notification.dismissed".to_string(),
         ]
     }
 
@@ -1393,6 +1588,7 @@ impl PluginFactory for NotificationPluginFactory {
             "cconnect.notification.request".to_string(),
             "cconnect.notification.action".to_string(),
             "cconnect.notification.reply".to_string(),
+            "cconnect.notification.dismissed".to_string(),
         ]
     }
 
@@ -1477,7 +1673,8 @@ mod tests {
         assert!(incoming.contains(&"kdeconnect.notification.reply".to_string()));
 
         let outgoing = plugin.outgoing_capabilities();
-        assert_eq!(outgoing.len(), 4);
+        assert_eq!(outgoing.len(), 5);
+        assert!(outgoing.contains(&"cconnect.notification.dismissed".to_string()));
     }
 
     #[tokio::test]
@@ -2172,6 +2369,16 @@ mod tests {
         assert_eq!(packet.body["isCancel"], true);
     }
 
+    #[test]
+    fn test_create_desktop_dismissed_packet() {
+        let plugin = NotificationPlugin::new();
+        let packet = plugin.create_desktop_dismissed_packet("desktop-App-123");
+
+        assert_eq!(packet.packet_type, "cconnect.notification.dismissed");
+        assert_eq!(packet.body["id"], "desktop-App-123");
+        assert!(packet.body.get("isCancel").is_none());
+    }
+
     #[test]
     fn test_desktop_notification_with_urgency_levels() {
         // Test low urgency
@@ -2261,4 +2468,71 @@ mod tests {
         assert!(packet.body["appIcon"].is_null());
         assert!(packet.body["actionButtons"].is_null());
     }
+
+    #[test]
+    fn test_icon_hash_identifies_content() {
+        let hash = NotificationPlugin::icon_hash(b"icon one");
+
+        assert_eq!(hash, NotificationPlugin::icon_hash(b"icon one"));
+        assert_ne!(hash, NotificationPlugin::icon_hash(b"icon two"));
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_set_icon_hash() {
+        let mut packet = NotificationPlugin::create_desktop_notification_packet(
+            "Chat",
+            "Alice",
+            "Lunch?",
+            1704067200000,
+            None,
+            &[],
+            None,
+            None,
+            None,
+        );
+        let hash = NotificationPlugin::icon_hash(b"icon");
+        NotificationPlugin::set_icon_hash(&mut packet, &hash);
+
+        assert_eq!(packet.body["payloadHash"], hash.as_str());
+        assert!(packet.body.get("imageData").is_none());
+    }
+
+    #[test]
+    fn test_mark_icon_sent_once_per_connection() {
+        let mut plugin = NotificationPlugin::new();
+        let hash = NotificationPlugin::icon_hash(b"icon");
+
+        // Not remembered until the transfer succeeded
+        assert!(plugin.needs_icon(&hash));
+        assert!(plugin.needs_icon(&hash));
+
+        plugin.mark_icon_sent(&hash);
+        assert!(!plugin.needs_icon(&hash));
+        assert!(plugin.needs_icon(&NotificationPlugin::icon_hash(b"other")));
+
+        // A new connection gets a fresh plugin instance
+        let mut reconnected = NotificationPlugin::new();
+        assert!(reconnected.needs_icon(&hash));
+    }
+
+    #[test]
+    fn test_sent_icons_evict_least_recently_used() {
+        let mut plugin = NotificationPlugin::new();
+        let hashes: Vec<String> = (0..=MAX_SENT_ICONS)
+            .map(|i| NotificationPlugin::icon_hash(&i.to_le_bytes()))
+            .collect();
+
+        for hash in &hashes[..MAX_SENT_ICONS] {
+            plugin.mark_icon_sent(hash);
+        }
+
+        // Using the oldest icon again keeps it over the second oldest
+        assert!(!plugin.needs_icon(&hashes[0]));
+        plugin.mark_icon_sent(&hashes[MAX_SENT_ICONS]);
+
+        assert!(!plugin.needs_icon(&hashes[0]));
+        assert!(plugin.needs_icon(&hashes[1]));
+        assert!(!plugin.needs_icon(&hashes[MAX_SENT_ICONS]));
+    }
 }
@@ -0,0 +1,578 @@
+//! Permission Policy for Privileged Plugin Actions
+//!
+//! Power actions, run-command execution and remote input give a paired device
+//! control over the desktop. Plugins that perform them consult a
+//! [`PermissionPolicy`] before acting.
+//!
+//! ## Policies
+//!
+//! - [`DenyAllPolicy`]: Used when no policy is configured; denies everything
+//! - [`AllowlistPolicy`]: Allows the plugins listed for each device and can
+//!   additionally require PolicyKit authorization
+//!
+//! Every decision is logged, allowed ones at info and denied ones at warn
+//! level, and recorded in the policy's [audit log](super::audit). Actions
+//! that still need the user's confirmation are recorded once the user
+//! answers, see [`authorize_pending`] and [`record_answer`].
+//!
+//! ## Denial Notification
+//!
+//! Plugins tell the peer about a denied request with a
+//! `cconnect.permission.denied` packet:
+//!
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.permission.denied",
+//!     "body": {
+//!         "plugin": "power",
+//!         "action": "shutdown",
+//!         "reason": "device is not allowed to use power"
+//!     }
+//! }
+//! ```
+//!
+//! ## PolicyKit
+//!
+//! With PolicyKit enabled, [`AllowlistPolicy`] checks the action IDs listed
+//! in `dbus/polkit/io.github.olafkfreund.CosmicExtConnect.policy` for the
+//! desktop session the daemon runs in, so `allow_active` and
+//! `allow_inactive` decide whether a device may act on a locked or
+//! switched-away session. Changes to the allowlist made over D-Bus are
+//! checked against the credentials of the caller with [`polkit_check`].
+//! Errors talking to PolicyKit deny the action.
+
+use super::audit::{AuditEntry, AuditLog, AuditOutcome};
+use crate::{Packet, ProtocolError, Result};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use tracing::{debug, info, warn};
+use zbus::names::{BusName, UniqueName};
+use zbus::zvariant::Value;
+use zbus::Connection;
+
+/// Packet type telling the peer a privileged request was denied
+pub const PACKET_TYPE_PERMISSION_DENIED: &str = "cconnect.permission.denied";
+
+/// A privileged action requested by a remote device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivilegedAction {
+    /// Power action ("shutdown", "reboot", "suspend", "hibernate")
+    Power(String),
+    /// Run the configured command with this key
+    RunCommand(String),
+    /// Inject pointer or keyboard input
+    RemoteInput,
+}
+
+impl PrivilegedAction {
+    /// Name of the plugin performing the action
+    pub fn plugin(&self) -> &'static str {
+        match self {
+            Self::Power(_) => "power",
+            Self::RunCommand(_) => "runcommand",
+            Self::RemoteInput => "remoteinput",
+        }
+    }
+
+    /// Action detail sent to the peer and logged
+    pub fn detail(&self) -> &str {
+        match self {
+            Self::Power(action) => action,
+            Self::RunCommand(key) => key,
+            Self::RemoteInput => "input",
+        }
+    }
+
+    /// PolicyKit action ID guarding the action
+    pub fn polkit_action_id(&self) -> &'static str {
+        plugin_polkit_action_id(self.plugin()).unwrap_or_default()
+    }
+}
+
+/// PolicyKit action ID guarding a privileged plugin, `None` for other plugins
+pub fn plugin_polkit_action_id(plugin: &str) -> Option<&'static str> {
+    match plugin {
+        "power" => Some("io.github.olafkfreund.CosmicExtConnect.power"),
+        "runcommand" => Some("io.github.olafkfreund.CosmicExtConnect.runcommand"),
+        "remoteinput" => Some("io.github.olafkfreund.CosmicExtConnect.remoteinput"),
+        _ => None,
+    }
+}
+
+impl fmt::Display for PrivilegedAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.plugin(), self.detail())
+    }
+}
+
+/// Outcome of a permission check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PermissionDecision {
+    /// The action may run
+    Allow,
+    /// The action is denied, with the reason
+    Deny(String),
+}
+
+impl PermissionDecision {
+    /// Whether the action may run
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allow)
+    }
+}
+
+/// Decides whether a device may perform a privileged action
+#[async_trait]
+pub trait PermissionPolicy: Send + Sync + fmt::Debug {
+    /// Check whether `device_id` may perform `action`
+    async fn check(&self, device_id: &str, action: &PrivilegedAction) -> PermissionDecision;
+
+    /// Audit log decisions are recorded to, `None` for the journal only
+    fn audit_log(&self) -> Option<&AuditLog> {
+        None
+    }
+}
+
+/// Permission policy shared between plugin instances
+pub type SharedPermissionPolicy = Arc<dyn PermissionPolicy>;
+
+/// Policy that denies every privileged action
+///
+/// Plugins use it until a policy is configured.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DenyAllPolicy;
+
+#[async_trait]
+impl PermissionPolicy for DenyAllPolicy {
+    async fn check(&self, _device_id: &str, _action: &PrivilegedAction) -> PermissionDecision {
+        PermissionDecision::Deny("no permission policy configured".to_string())
+    }
+}
+
+/// Policy allowing privileged plugins per device
+///
+/// Devices may only use the plugins on their allowlist. With PolicyKit
+/// enabled, allowlisted actions must also be authorized by PolicyKit.
+/// Clones share the allowlist, so updates apply to plugins already running.
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmic_ext_connect_protocol::plugins::permission::AllowlistPolicy;
+///
+/// let policy = AllowlistPolicy::new();
+/// policy.allow("phone", "remoteinput");
+/// assert!(policy.is_allowlisted("phone", "remoteinput"));
+/// assert!(!policy.is_allowlisted("phone", "power"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AllowlistPolicy {
+    /// Allowed plugin names by device ID
+    allowlist: Arc<RwLock<HashMap<String, HashSet<String>>>>,
+
+    /// Also require PolicyKit authorization
+    use_polkit: bool,
+
+    /// Where decisions are recorded
+    audit_log: Option<Arc<AuditLog>>,
+}
+
+impl AllowlistPolicy {
+    /// Create a policy with an empty allowlist
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require PolicyKit authorization for allowlisted actions
+    pub fn with_polkit(mut self, use_polkit: bool) -> Self {
+        self.use_polkit = use_polkit;
+        self
+    }
+
+    /// Whether allowlisted actions also need PolicyKit authorization
+    pub fn uses_polkit(&self) -> bool {
+        self.use_polkit
+    }
+
+    /// Record decisions to an audit log
+    pub fn with_audit_log(mut self, audit_log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Replace the plugins a device may use
+    ///
+    /// Empty sets are removed.
+    pub fn set_allowed(&self, device_id: &str, plugins: HashSet<String>) {
+        if let Ok(mut allowlist) = self.allowlist.write() {
+            if plugins.is_empty() {
+                allowlist.remove(device_id);
+            } else {
+                allowlist.insert(device_id.to_string(), plugins);
+            }
+        }
+    }
+
+    /// Allow a device to use a plugin
+    pub fn allow(&self, device_id: &str, plugin: &str) {
+        if let Ok(mut allowlist) = self.allowlist.write() {
+            allowlist
+                .entry(device_id.to_string())
+                .or_default()
+                .insert(plugin.to_string());
+        }
+    }
+
+    /// Revoke a device's use of a plugin
+    pub fn revoke(&self, device_id: &str, plugin: &str) {
+        if let Ok(mut allowlist) = self.allowlist.write() {
+            if let Some(plugins) = allowlist.get_mut(device_id) {
+                plugins.remove(plugin);
+                if plugins.is_empty() {
+                    allowlist.remove(device_id);
+                }
+            }
+        }
+    }
+
+    /// Whether a device's allowlist includes a plugin
+    pub fn is_allowlisted(&self, device_id: &str, plugin: &str) -> bool {
+        self.allowlist
+            .read()
+            .map(|allowlist| {
+                allowlist
+                    .get(device_id)
+                    .is_some_and(|plugins| plugins.contains(plugin))
+            })
+            .unwrap_or(false)
+    }
+}
+
+#[async_trait]
+impl PermissionPolicy for AllowlistPolicy {
+    async fn check(&self, device_id: &str, action: &PrivilegedAction) -> PermissionDecision {
+        if !self.is_allowlisted(device_id, action.plugin()) {
+            return PermissionDecision::Deny(format!(
+                "device is not allowed to use {}",
+                action.plugin()
+            ));
+        }
+
+        if !self.use_polkit {
+            return PermissionDecision::Allow;
+        }
+
+        let Some(session) = PolkitSubject::current_session() else {
+            return PermissionDecision::Deny(
+                "no desktop session to authorize with PolicyKit".to_string(),
+            );
+        };
+
+        match polkit_check(&session, action.polkit_action_id()).await {
+            Ok(true) => PermissionDecision::Allow,
+            Ok(false) => PermissionDecision::Deny(format!(
+                "not authorized by PolicyKit ({})",
+                action.polkit_action_id()
+            )),
+            Err(e) => {
+                warn!("PolicyKit check failed: {}", e);
+                PermissionDecision::Deny(format!("PolicyKit unavailable: {}", e))
+            }
+        }
+    }
+
+    fn audit_log(&self) -> Option<&AuditLog> {
+        self.audit_log.as_deref()
+    }
+}
+
+/// Whom PolicyKit is asked about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolkitSubject {
+    /// A login session, by logind session ID
+    Session(String),
+    /// A process, by PID and user ID
+    Process {
+        /// Process ID
+        pid: u32,
+        /// User ID the process runs as
+        uid: u32,
+    },
+}
+
+impl PolkitSubject {
+    /// The desktop session the daemon runs in, from `XDG_SESSION_ID`
+    pub fn current_session() -> Option<Self> {
+        std::env::var("XDG_SESSION_ID")
+            .ok()
+            .filter(|id| !id.is_empty())
+            .map(Self::Session)
+    }
+
+    /// The process behind a D-Bus caller, as reported by the bus
+    pub async fn dbus_caller(
+        connection: &Connection,
+        sender: &UniqueName<'_>,
+    ) -> std::result::Result<Self, String> {
+        let credentials = zbus::fdo::DBusProxy::new(connection)
+            .await
+            .map_err(|e| format!("Failed to create D-Bus proxy: {}", e))?
+            .get_connection_credentials(BusName::from(sender.clone()))
+            .await
+            .map_err(|e| format!("Failed to get credentials of {}: {}", sender, e))?;
+
+        match (credentials.process_id(), credentials.unix_user_id()) {
+            (Some(pid), Some(uid)) => Ok(Self::Process { pid, uid }),
+            _ => Err(format!("Bus reported no process for {}", sender)),
+        }
+    }
+
+    /// Subject as PolicyKit's `(sa{sv})` struct
+    fn to_polkit(&self) -> (&'static str, HashMap<&'static str, Value<'_>>) {
+        match self {
+            Self::Session(id) => (
+                "unix-session",
+                HashMap::from([("session-id", Value::from(id.as_str()))]),
+            ),
+            // start-time 0 lets PolicyKit look up the process start time itself
+            Self::Process { pid, uid } => (
+                "unix-process",
+                HashMap::from([
+                    ("pid", Value::from(*pid)),
+                    ("start-time", Value::from(0u64)),
+                    ("uid", Value::from(*uid as i32)),
+                ]),
+            ),
+        }
+    }
+}
+
+/// Ask PolicyKit whether a subject may perform an action
+///
+/// The check never prompts, so actions that would need authentication are
+/// reported as not authorized.
+pub async fn polkit_check(
+    subject: &PolkitSubject,
+    action_id: &str,
+) -> std::result::Result<bool, String> {
+    let conn = Connection::system()
+        .await
+        .map_err(|e| format!("Failed to connect to system bus: {}", e))?;
+
+    let subject = subject.to_polkit();
+    let details: HashMap<&str, &str> = HashMap::new();
+
+    let reply = conn
+        .call_method(
+            Some("org.freedesktop.PolicyKit1"),
+            "/org/freedesktop/PolicyKit1/Authority",
+            Some("org.freedesktop.PolicyKit1.Authority"),
+            "CheckAuthorization",
+            &(subject, action_id, details, 0u32, ""),
+        )
+        .await
+        .map_err(|e| format!("CheckAuthorization failed: {}", e))?;
+
+    let (authorized, _challenge, _details): (bool, bool, HashMap<String, String>) = reply
+        .body()
+        .deserialize()
+        .map_err(|e| format!("Failed to parse CheckAuthorization reply: {}", e))?;
+
+    debug!("PolicyKit {} authorized: {}", action_id, authorized);
+    Ok(authorized)
+}
+
+/// Record an entry to the policy's audit log, or the journal without one
+fn record(policy: &dyn PermissionPolicy, entry: &AuditEntry) {
+    match policy.audit_log() {
+        Some(audit_log) => audit_log.record(entry),
+        None => AuditLog::journal_only().record(entry),
+    }
+}
+
+/// Check an action against a policy, log the decision and audit it
+///
+/// `parameters` are written to the policy's audit log as given, so secrets
+/// must already be redacted. Returns [`ProtocolError::PermissionDenied`] if
+/// the action is denied.
+pub async fn authorize(
+    policy: &dyn PermissionPolicy,
+    device_id: &str,
+    action: &PrivilegedAction,
+    parameters: serde_json::Value,
+) -> Result<()> {
+    decide(policy, device_id, action, parameters, true).await
+}
+
+/// Check an action that still waits for the user's confirmation
+///
+/// Like [`authorize`], but an allowed action is not audited yet: the caller
+/// records the user's answer with [`record_answer`].
+pub async fn authorize_pending(
+    policy: &dyn PermissionPolicy,
+    device_id: &str,
+    action: &PrivilegedAction,
+    parameters: serde_json::Value,
+) -> Result<()> {
+    decide(policy, device_id, action, parameters, false).await
+}
+
+/// Audit the user's answer to an action passed by [`authorize_pending`]
+pub fn record_answer(
+    policy: &dyn PermissionPolicy,
+    device_id: &str,
+    action: &PrivilegedAction,
+    parameters: serde_json::Value,
+    accepted: bool,
+) {
+    let entry = if accepted {
+        info!("Confirmed by user: {} for device {}", action, device_id);
+        AuditEntry::new(device_id, action, parameters, AuditOutcome::Allowed, None)
+    } else {
+        info!("Declined by user: {} for device {}", action, device_id);
+        AuditEntry::new(
+            device_id,
+            action,
+            parameters,
+            AuditOutcome::Denied,
+            Some("declined by the user".to_string()),
+        )
+    };
+    record(policy, &entry);
+}
+
+/// Check an action, auditing denials and, with `record_allowed`, grants
+async fn decide(
+    policy: &dyn PermissionPolicy,
+    device_id: &str,
+    action: &PrivilegedAction,
+    parameters: serde_json::Value,
+    record_allowed: bool,
+) -> Result<()> {
+    match policy.check(device_id, action).await {
+        PermissionDecision::Allow => {
+            info!("Permission granted: {} for device {}", action, device_id);
+            if record_allowed {
+                record(
+                    policy,
+                    &AuditEntry::new(device_id, action, parameters, AuditOutcome::Allowed, None),
+                );
+            }
+            Ok(())
+        }
+        PermissionDecision::Deny(reason) => {
+            warn!(
+                "Permission denied: {} for device {}: {}",
+                action, device_id, reason
+            );
+            record(
+                policy,
+                &AuditEntry::new(
+                    device_id,
+                    action,
+                    parameters,
+                    AuditOutcome::Denied,
+                    Some(reason.clone()),
+                ),
+            );
+            Err(ProtocolError::PermissionDenied(format!(
+                "{} for {}: {}",
+                action, device_id, reason
+            )))
+        }
+    }
+}
+
+/// Create a packet telling the peer a privileged request was denied
+pub fn create_denied_packet(action: &PrivilegedAction, error: &ProtocolError) -> Packet {
+    let reason = match error {
+        ProtocolError::PermissionDenied(reason) => reason.clone(),
+        other => other.to_string(),
+    };
+    Packet::new(
+        PACKET_TYPE_PERMISSION_DENIED,
+        json!({
+            "plugin": action.plugin(),
+            "action": action.detail(),
+            "reason": reason,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_deny_all_policy() {
+        let decision = DenyAllPolicy
+            .check("phone", &PrivilegedAction::RemoteInput)
+            .await;
+        assert!(!decision.is_allowed());
+    }
+
+    #[tokio::test]
+    async fn test_allowlist_policy_per_device() {
+        let policy = AllowlistPolicy::new();
+        policy.allow("phone", "power");
+
+        let shutdown = PrivilegedAction::Power("shutdown".to_string());
+        assert!(policy.check("phone", &shutdown).await.is_allowed());
+        assert!(!policy.check("tablet", &shutdown).await.is_allowed());
+        assert!(!policy
+            .check("phone", &PrivilegedAction::RemoteInput)
+            .await
+            .is_allowed());
+    }
+
+    #[tokio::test]
+    async fn test_allowlist_updates_shared_between_clones() {
+        let policy = AllowlistPolicy::new();
+        let shared = policy.clone();
+
+        policy.set_allowed("phone", HashSet::from(["remoteinput".to_string()]));
+        assert!(shared.is_allowlisted("phone", "remoteinput"));
+
+        policy.revoke("phone", "remoteinput");
+        assert!(!shared
+            .check("phone", &PrivilegedAction::RemoteInput)
+            .await
+            .is_allowed());
+    }
+
+    #[test]
+    fn test_polkit_subjects() {
+        let (kind, details) = PolkitSubject::Session("c2".to_string()).to_polkit();
+        assert_eq!(kind, "unix-session");
+        assert_eq!(details["session-id"], Value::from("c2"));
+
+        let (kind, details) = PolkitSubject::Process { pid: 42, uid: 1000 }.to_polkit();
+        assert_eq!(kind, "unix-process");
+        assert_eq!(details["pid"], Value::from(42u32));
+        assert_eq!(details["uid"], Value::from(1000i32));
+
+        assert_eq!(
+            plugin_polkit_action_id("power"),
+            Some(PrivilegedAction::Power("reboot".to_string()).polkit_action_id())
+        );
+        assert_eq!(plugin_polkit_action_id("ping"), None);
+    }
+
+    #[tokio::test]
+    async fn test_authorize_returns_permission_denied() {
+        let action = PrivilegedAction::RunCommand("backup".to_string());
+        let err = authorize(&DenyAllPolicy, "phone", &action, json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProtocolError::PermissionDenied(_)));
+
+        let packet = create_denied_packet(&action, &err);
+        assert_eq!(packet.packet_type, PACKET_TYPE_PERMISSION_DENIED);
+        assert_eq!(packet.body["plugin"], "runcommand");
+        assert_eq!(packet.body["action"], "backup");
+    }
+}
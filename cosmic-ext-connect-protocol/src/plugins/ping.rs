@@ -25,11 +25,27 @@
 //!
 //! The `message` field is optional. If omitted, the packet body is empty.
 //!
+//! ### Timing Pings
+//!
+//! To measure the round-trip time, a ping carries a `nonce` and the receiver
+//! echoes it back with `reply` set:
+//!
+//! ```json
+//! { "type": "cconnect.ping", "body": { "keepalive": true, "nonce": 42 } }
+//! { "type": "cconnect.ping", "body": { "keepalive": true, "nonce": 42, "reply": true } }
+//! ```
+//!
+//! Both packets are marked `keepalive` so neither side shows a notification.
+//! The connection manager's keepalives carry a nonce as well, so echoing them
+//! keeps an idle connection's heartbeat alive; their echoes are ignored here.
+//! Peers that don't echo timing pings (e.g. KDE Connect) make
+//! [`PingTimer::ping_with_timing`] time out.
+//!
 //! ## Behavior
 //!
 //! - **Receiving**: When a ping is received, it's logged and can trigger notifications
 //! - **Bidirectional**: Both devices can send and receive pings
-//! - **Simple**: No response required, fire-and-forget
+//! - **Simple**: Plain pings are fire-and-forget; only timing pings are answered
 //! - **Notifications**: Pings are typically displayed as notifications
 //!
 //! ## Use Cases
@@ -63,15 +79,127 @@
 //!
 //! - [Valent Protocol - Ping](https://valent.andyholmes.ca/documentation/protocol.html)
 
-use crate::{Device, Packet, Result};
+use crate::{Device, LatencyCategory, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use tracing::{debug, info};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info, warn};
 
 use super::{Plugin, PluginFactory};
 
+/// How long a timing ping waits for its echo by default
+pub const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Timing ping waiting for its echo
+#[derive(Debug)]
+struct PendingPing {
+    /// When the ping was sent
+    sent_at: Instant,
+
+    /// Receives the measured round-trip time
+    done: oneshot::Sender<Duration>,
+}
+
+/// Timing state shared between the plugin and its [`PingTimer`]s
+#[derive(Debug, Default)]
+struct PingTiming {
+    /// Timing pings awaiting their echo, by nonce
+    pending: Mutex<HashMap<u64, PendingPing>>,
+
+    /// Nonce of the next timing ping
+    next_nonce: AtomicU64,
+
+    /// Most recently measured round-trip time
+    last_rtt: Mutex<Option<Duration>>,
+}
+
+impl PingTiming {
+    /// Resolve the timing ping an echo answers
+    fn complete(&self, nonce: u64) -> Option<Duration> {
+        let pending = self.pending.lock().unwrap().remove(&nonce)?;
+        let rtt = pending.sent_at.elapsed();
+        *self.last_rtt.lock().unwrap() = Some(rtt);
+        let _ = pending.done.send(rtt);
+        Some(rtt)
+    }
+}
+
+/// Handle for measuring the round-trip time to a device
+///
+/// Obtained from [`PingPlugin::timer`]. The timer shares its state with the
+/// plugin but not the plugin itself, so waiting for an echo never blocks the
+/// plugin from handling it.
+#[derive(Debug, Clone)]
+pub struct PingTimer {
+    /// Device the timing pings are sent to
+    device_id: String,
+
+    /// Channel for sending packets to the device
+    packet_sender: mpsc::Sender<(String, Packet)>,
+
+    /// State shared with the plugin
+    timing: Arc<PingTiming>,
+
+    /// Count of pings sent, shared with the plugin
+    pings_sent: Arc<AtomicU64>,
+}
+
+impl PingTimer {
+    /// Send a timing ping and wait for its echo
+    ///
+    /// Returns the measured round-trip time, or
+    /// [`ProtocolError::Timeout`] if no echo arrives within `timeout`.
+    pub async fn ping_with_timing(&self, timeout: Duration) -> Result<Duration> {
+        let nonce = self.timing.next_nonce.fetch_add(1, Ordering::Relaxed);
+        let (done, rtt) = oneshot::channel();
+        self.timing.pending.lock().unwrap().insert(
+            nonce,
+            PendingPing {
+                sent_at: Instant::now(),
+                done,
+            },
+        );
+
+        let packet = Packet::new(
+            "cconnect.ping",
+            json!({ "keepalive": true, "nonce": nonce }),
+        );
+        if self
+            .packet_sender
+            .send((self.device_id.clone(), packet))
+            .await
+            .is_err()
+        {
+            self.timing.pending.lock().unwrap().remove(&nonce);
+            return Err(ProtocolError::Plugin(
+                "Packet channel closed, can not send ping".to_string(),
+            ));
+        }
+        self.pings_sent.fetch_add(1, Ordering::Relaxed);
+
+        match tokio::time::timeout(timeout, rtt).await {
+            Ok(Ok(rtt)) => {
+                debug!("Ping round trip to {}: {:?}", self.device_id, rtt);
+                Ok(rtt)
+            }
+            Ok(Err(_)) => Err(ProtocolError::Plugin(
+                "Ping plugin stopped before the echo arrived".to_string(),
+            )),
+            Err(_) => {
+                self.timing.pending.lock().unwrap().remove(&nonce);
+                Err(ProtocolError::Timeout(format!(
+                    "No ping echo from {} within {:?}",
+                    self.device_id, timeout
+                )))
+            }
+        }
+    }
+}
+
 /// Ping plugin for connectivity testing
 ///
 /// Handles `cconnect.ping` packets for simple device-to-device communication
@@ -83,6 +211,7 @@ use super::{Plugin, PluginFactory};
 /// - Optional message in ping
 /// - Ping statistics (count)
 /// - Thread-safe statistics tracking
+/// - Round-trip time measurement with timing pings
 ///
 /// ## Example
 ///
@@ -104,6 +233,12 @@ pub struct PingPlugin {
 
     /// Count of pings sent
     pings_sent: Arc<AtomicU64>,
+
+    /// Channel for sending packets, set on init
+    packet_sender: Option<mpsc::Sender<(String, Packet)>>,
+
+    /// Timing ping state
+    timing: Arc<PingTiming>,
 }
 
 impl PingPlugin {
@@ -123,6 +258,8 @@ impl PingPlugin {
             device_id: None,
             pings_received: Arc::new(AtomicU64::new(0)),
             pings_sent: Arc::new(AtomicU64::new(0)),
+            packet_sender: None,
+            timing: Arc::new(PingTiming::default()),
         }
     }
 
@@ -154,6 +291,28 @@ impl PingPlugin {
         self.pings_sent.load(Ordering::Relaxed)
     }
 
+    /// Handle for measuring the round-trip time
+    ///
+    /// Returns `None` until the plugin is initialized.
+    pub fn timer(&self) -> Option<PingTimer> {
+        Some(PingTimer {
+            device_id: self.device_id.clone()?,
+            packet_sender: self.packet_sender.clone()?,
+            timing: self.timing.clone(),
+            pings_sent: self.pings_sent.clone(),
+        })
+    }
+
+    /// Most recently measured round-trip time
+    pub fn last_rtt(&self) -> Option<Duration> {
+        *self.timing.last_rtt.lock().unwrap()
+    }
+
+    /// Latency category of the most recently measured round-trip time
+    pub fn latency(&self) -> Option<LatencyCategory> {
+        self.last_rtt().map(LatencyCategory::from_rtt)
+    }
+
     /// Create a ping packet
     ///
     /// Creates a `cconnect.ping` packet with an optional message.
@@ -191,6 +350,41 @@ impl PingPlugin {
         Packet::new("cconnect.ping", body)
     }
 
+    /// Handle an incoming timing ping or echo
+    async fn handle_timing_ping(&self, nonce: u64, packet: &Packet, device: &Device) {
+        let is_reply = packet
+            .body
+            .get("reply")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if is_reply {
+            match self.timing.complete(nonce) {
+                Some(rtt) => debug!("Ping echo from {}: {:?}", device.id(), rtt),
+                None => debug!(
+                    "Ignoring ping echo {} from {} (timed out or unknown)",
+                    nonce,
+                    device.id()
+                ),
+            }
+            return;
+        }
+
+        self.pings_received.fetch_add(1, Ordering::Relaxed);
+        debug!("Echoing timing ping {} from {}", nonce, device.id());
+
+        let Some(sender) = &self.packet_sender else {
+            return;
+        };
+        let echo = Packet::new(
+            "cconnect.ping",
+            json!({ "keepalive": true, "nonce": nonce, "reply": true }),
+        );
+        if let Err(e) = sender.send((device.id().to_string(), echo)).await {
+            warn!("Failed to echo ping to {}: {}", device.id(), e);
+        }
+    }
+
     /// Handle an incoming ping packet
     ///
     /// Processes a received ping, extracts any message, and updates statistics.
@@ -255,9 +449,10 @@ impl Plugin for PingPlugin {
     async fn init(
         &mut self,
         device: &Device,
-        _packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
+        packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
     ) -> Result<()> {
         self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
         info!("Ping plugin initialized for device {}", device.name());
         Ok(())
     }
@@ -268,6 +463,8 @@ impl Plugin for PingPlugin {
     }
 
     async fn stop(&mut self) -> Result<()> {
+        // Fails any timing pings still waiting for an echo
+        self.timing.pending.lock().unwrap().clear();
         info!(
             "Ping plugin stopped - received: {}, sent: {}",
             self.pings_received(),
@@ -278,7 +475,10 @@ impl Plugin for PingPlugin {
 
     async fn handle_packet(&mut self, packet: &Packet, device: &mut Device) -> Result<()> {
         if packet.is_type("cconnect.ping") || packet.is_type("kdeconnect.ping") {
-            self.handle_ping(packet, device);
+            match packet.body.get("nonce").and_then(|v| v.as_u64()) {
+                Some(nonce) => self.handle_timing_ping(nonce, packet, device).await,
+                None => self.handle_ping(packet, device),
+            }
         }
         Ok(())
     }
@@ -465,6 +665,91 @@ mod tests {
         assert_eq!(plugin.pings_received(), 0);
     }
 
+    #[tokio::test]
+    async fn test_timing_ping_echo_correlated() {
+        let mut plugin = PingPlugin::new();
+        let device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        plugin.init(&device, tx).await.unwrap();
+
+        let timer = plugin.timer().unwrap();
+        let measurement =
+            tokio::spawn(async move { timer.ping_with_timing(Duration::from_secs(5)).await });
+
+        let (device_id, sent) = rx.recv().await.unwrap();
+        assert_eq!(device_id, device.id());
+        assert_eq!(sent.body["keepalive"], json!(true));
+        let nonce = sent.body["nonce"].as_u64().unwrap();
+
+        // An echo for another nonce doesn't complete the measurement
+        let mut device = create_test_device();
+        let stray = Packet::new(
+            "cconnect.ping",
+            json!({ "keepalive": true, "nonce": nonce + 1, "reply": true }),
+        );
+        plugin.handle_packet(&stray, &mut device).await.unwrap();
+        assert!(plugin.last_rtt().is_none());
+
+        let echo = Packet::new(
+            "cconnect.ping",
+            json!({ "keepalive": true, "nonce": nonce, "reply": true }),
+        );
+        plugin.handle_packet(&echo, &mut device).await.unwrap();
+
+        let rtt = measurement.await.unwrap().unwrap();
+        assert_eq!(plugin.last_rtt(), Some(rtt));
+        assert_eq!(plugin.latency(), Some(LatencyCategory::from_rtt(rtt)));
+        assert_eq!(plugin.pings_sent(), 1);
+        // Echoes are not counted as received pings
+        assert_eq!(plugin.pings_received(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_timing_ping_is_echoed() {
+        let mut plugin = PingPlugin::new();
+        let device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        plugin.init(&device, tx).await.unwrap();
+
+        let mut device = create_test_device();
+        let packet = Packet::new("cconnect.ping", json!({ "keepalive": true, "nonce": 7 }));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let (device_id, echo) = rx.recv().await.unwrap();
+        assert_eq!(device_id, device.id());
+        assert_eq!(echo.body["nonce"], json!(7));
+        assert_eq!(echo.body["reply"], json!(true));
+        assert_eq!(plugin.pings_received(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timing_ping_timeout() {
+        let mut plugin = PingPlugin::new();
+        let device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        plugin.init(&device, tx).await.unwrap();
+
+        let timer = plugin.timer().unwrap();
+        let result = timer.ping_with_timing(Duration::from_millis(20)).await;
+        assert!(matches!(result, Err(ProtocolError::Timeout(_))));
+
+        // A late echo is ignored
+        let (_, sent) = rx.recv().await.unwrap();
+        let mut device = create_test_device();
+        let echo = Packet::new(
+            "cconnect.ping",
+            json!({ "keepalive": true, "nonce": sent.body["nonce"].clone(), "reply": true }),
+        );
+        plugin.handle_packet(&echo, &mut device).await.unwrap();
+        assert!(plugin.last_rtt().is_none());
+        assert!(plugin.latency().is_none());
+    }
+
+    #[test]
+    fn test_timer_requires_init() {
+        assert!(PingPlugin::new().timer().is_none());
+    }
+
     #[test]
     fn test_statistics() {
         let plugin = PingPlugin::new();
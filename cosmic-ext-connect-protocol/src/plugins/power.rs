@@ -7,7 +7,7 @@
 //!
 //! **Packet Types**:
 //! - Incoming: `cconnect.power.request`, `cconnect.power.inhibit`, `cconnect.power.query`
-//! - Outgoing: `cconnect.power.status`
+//! - Outgoing: `cconnect.power.status`, `cconnect.permission.denied`
 //!
 //! **Capabilities**: `cconnect.power`
 //!
@@ -71,12 +71,16 @@
 //!
 //! ## Security Considerations
 //!
-//! - Power actions are disabled by default (config: enable_power = false)
-//! - Requires explicit opt-in per device
-//! - Uses PolicyKit for permission checking
-//! - Audit logging for all power actions
+//! - Every power action is checked against a [`PermissionPolicy`]; without a
+//!   configured policy all actions are denied
+//! - The daemon's policy requires explicit opt-in per device and can also
+//!   require PolicyKit authorization
+//! - Denied requests return `ProtocolError::PermissionDenied` and send a
+//!   `cconnect.permission.denied` packet to the device
 //! - Only paired devices can trigger power actions
 //!
+//! [`PermissionPolicy`]: super::permission::PermissionPolicy
+//!
 //! ## System Integration
 //!
 //! Uses systemd-logind DBus interface for power management:
@@ -110,10 +114,16 @@ use std::sync::{Arc, RwLock};
 use tracing::{debug, info, warn};
 
 use super::logind_backend::LogindBackend;
+use super::permission::{
+    self, DenyAllPolicy, PrivilegedAction, SharedPermissionPolicy, PACKET_TYPE_PERMISSION_DENIED,
+};
 use super::systemd_inhibitor::{InhibitMode, InhibitType, InhibitorLock, SystemdInhibitor};
 use super::upower_backend::UPowerBackend;
 use super::{Plugin, PluginFactory};
 
+/// Power actions a device can request
+const POWER_ACTIONS: &[&str] = &["shutdown", "reboot", "suspend", "hibernate"];
+
 /// Inhibition state for thread-safe access
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct InhibitionState {
@@ -148,10 +158,16 @@ pub struct PowerPlugin {
 
     /// Packet sender for response packets
     packet_sender: Option<tokio::sync::mpsc::Sender<(String, Packet)>>,
+
+    /// Policy consulted before executing power actions
+    policy: SharedPermissionPolicy,
 }
 
 impl PowerPlugin {
     /// Create a new Power plugin
+    ///
+    /// Power actions are denied until a policy is set with
+    /// [`with_policy`](Self::with_policy).
     pub fn new() -> Self {
         Self {
             device_id: None,
@@ -162,9 +178,16 @@ impl PowerPlugin {
             upower: UPowerBackend::new(),
             packet_sender: None,
             logind: LogindBackend::new(),
+            policy: Arc::new(DenyAllPolicy),
         }
     }
 
+    /// Set the policy consulted before executing power actions
+    pub fn with_policy(mut self, policy: SharedPermissionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     // ========== Public API for UI Integration ==========
 
     /// Check if sleep is currently inhibited
@@ -321,6 +344,15 @@ impl PowerPlugin {
 
     /// Handle power action request
     async fn handle_power_request(&mut self, packet: &Packet, device: &Device) -> Result<()> {
+        if !device.is_paired() {
+            warn!(
+                "Rejecting power request from unpaired device {} ({})",
+                device.name(),
+                device.id()
+            );
+            return Err(crate::ProtocolError::NotPaired);
+        }
+
         if let Some(action) = packet.body.get("action").and_then(|v| v.as_str()) {
             info!(
                 "Received power request from {} ({}): {}",
@@ -329,21 +361,50 @@ impl PowerPlugin {
                 action
             );
 
+            if !POWER_ACTIONS.contains(&action) {
+                warn!("Unknown power action: {}", action);
+                return Err(crate::ProtocolError::unsupported_feature(format!(
+                    "power action '{}'",
+                    action
+                )));
+            }
+
+            self.authorize_power_action(action, device).await?;
+
             // Execute power action
             match action {
                 "shutdown" => self.shutdown().await?,
                 "reboot" => self.reboot().await?,
                 "suspend" => self.suspend().await?,
                 "hibernate" => self.hibernate().await?,
-                _ => {
-                    warn!("Unknown power action: {}", action);
-                }
+                _ => unreachable!("power action validated above"),
             }
         }
 
         Ok(())
     }
 
+    /// Check a power action against the permission policy
+    ///
+    /// Tells the device when the action is denied.
+    async fn authorize_power_action(&self, action: &str, device: &Device) -> Result<()> {
+        let action = PrivilegedAction::Power(action.to_string());
+        let parameters = json!({ "action": action.detail() });
+        let Err(e) =
+            permission::authorize(self.policy.as_ref(), device.id(), &action, parameters).await
+        else {
+            return Ok(());
+        };
+
+        if let (Some(device_id), Some(sender)) = (&self.device_id, &self.packet_sender) {
+            let packet = permission::create_denied_packet(&action, &e);
+            if let Err(send_err) = sender.send((device_id.clone(), packet)).await {
+                warn!("Failed to send permission denied packet: {}", send_err);
+            }
+        }
+        Err(e)
+    }
+
     /// Handle sleep inhibit request
     async fn handle_inhibit_request(&mut self, packet: &Packet, device: &Device) -> Result<()> {
         if let Some(inhibit) = packet.body.get("inhibit").and_then(|v| v.as_bool()) {
@@ -469,8 +530,30 @@ impl PowerPlugin {
     }
 
     /// Convert logind error to protocol error
+    ///
+    /// Maps well-known logind/D-Bus error names onto specific variants so
+    /// callers can tell the user *why* the action was rejected.
     fn logind_error(action: &str, e: String) -> crate::ProtocolError {
-        crate::ProtocolError::invalid_state(format!("Failed to {}: {}", action, e))
+        use crate::ProtocolError;
+
+        let detail = format!("{}: {}", action, e);
+        if e.contains("AccessDenied")
+            || e.contains("InteractiveAuthorizationRequired")
+            || e.contains("not authorized")
+        {
+            ProtocolError::PermissionDenied(detail)
+        } else if e.contains("OperationInProgress") || e.contains("BlockedByInhibitorLock") {
+            ProtocolError::device_busy(detail)
+        } else if e.contains("SleepVerbNotSupported")
+            || e.contains("NotSupported")
+            || e.contains("UnknownMethod")
+        {
+            ProtocolError::unsupported_feature(detail)
+        } else if e.contains("NoReply") || e.contains("Timeout") || e.contains("timed out") {
+            ProtocolError::Timeout(detail)
+        } else {
+            ProtocolError::invalid_state(format!("Failed to {}", detail))
+        }
     }
 
     /// Shutdown the system via logind DBus
@@ -538,7 +621,10 @@ impl Plugin for PowerPlugin {
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec!["cconnect.power.status".to_string()]
+        vec![
+            "cconnect.power.status".to_string(),
+            PACKET_TYPE_PERMISSION_DENIED.to_string(),
+        ]
     }
 
     async fn init(
@@ -595,11 +681,36 @@ impl Plugin for PowerPlugin {
 }
 
 /// Factory for creating Power plugin instances
-pub struct PowerPluginFactory;
+#[derive(Debug, Clone)]
+pub struct PowerPluginFactory {
+    /// Policy shared by every created plugin
+    policy: SharedPermissionPolicy,
+}
+
+impl PowerPluginFactory {
+    /// Create factory whose plugins deny all power actions
+    pub fn new() -> Self {
+        Self {
+            policy: Arc::new(DenyAllPolicy),
+        }
+    }
+
+    /// Set the policy consulted before executing power actions
+    pub fn with_policy(mut self, policy: SharedPermissionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl Default for PowerPluginFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl PluginFactory for PowerPluginFactory {
     fn create(&self) -> Box<dyn Plugin> {
-        Box::new(PowerPlugin::new())
+        Box::new(PowerPlugin::new().with_policy(Arc::clone(&self.policy)))
     }
 
     fn name(&self) -> &str {
@@ -618,13 +729,17 @@ impl PluginFactory for PowerPluginFactory {
     }
 
     fn outgoing_capabilities(&self) -> Vec<String> {
-        vec!["cconnect.power.status".to_string()]
+        vec![
+            "cconnect.power.status".to_string(),
+            PACKET_TYPE_PERMISSION_DENIED.to_string(),
+        ]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::MockPermissionPolicy;
     use crate::{DeviceInfo, DeviceType};
 
     fn create_test_device() -> Device {
@@ -718,8 +833,9 @@ mod tests {
         assert!(incoming.contains(&"kdeconnect.power.query".to_string()));
 
         let outgoing = plugin.outgoing_capabilities();
-        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing.len(), 2);
         assert!(outgoing.contains(&"cconnect.power.status".to_string()));
+        assert!(outgoing.contains(&"cconnect.permission.denied".to_string()));
     }
 
     #[tokio::test]
@@ -740,6 +856,139 @@ mod tests {
         assert!(!plugin.enabled);
     }
 
+    #[tokio::test]
+    async fn test_power_request_from_unpaired_device_rejected() {
+        let mut plugin = PowerPlugin::new();
+        let mut device = create_test_device();
+        device.pairing_status = crate::PairingStatus::Unpaired;
+
+        let packet = plugin.create_power_request("shutdown");
+        let err = plugin
+            .handle_power_request(&packet, &device)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::ProtocolError::NotPaired));
+    }
+
+    #[tokio::test]
+    async fn test_power_request_denied_by_policy() {
+        let policy = Arc::new(MockPermissionPolicy::denying());
+        let mut plugin = PowerPlugin::new().with_policy(policy.clone());
+        let device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, tx).await.unwrap();
+
+        let packet = plugin.create_power_request("shutdown");
+        let err = plugin
+            .handle_power_request(&packet, &device)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::ProtocolError::PermissionDenied(_)));
+        assert_eq!(
+            policy.checks(),
+            vec![(
+                "test_device".to_string(),
+                PrivilegedAction::Power("shutdown".to_string())
+            )]
+        );
+
+        // The device is told why nothing happened
+        let (device_id, denied) = rx.try_recv().unwrap();
+        assert_eq!(device_id, "test_device");
+        assert_eq!(denied.packet_type, PACKET_TYPE_PERMISSION_DENIED);
+        assert_eq!(denied.body["plugin"], "power");
+        assert_eq!(denied.body["action"], "shutdown");
+    }
+
+    #[tokio::test]
+    async fn test_power_action_allowed_by_policy() {
+        let policy = Arc::new(MockPermissionPolicy::allowing());
+        let mut plugin = PowerPlugin::new().with_policy(policy.clone());
+        let device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        plugin.init(&device, tx).await.unwrap();
+
+        // Only the policy check; executing would really shut down
+        plugin
+            .authorize_power_action("reboot", &device)
+            .await
+            .unwrap();
+        assert_eq!(policy.checks().len(), 1);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_power_denied_without_policy() {
+        let plugin = PowerPlugin::new();
+        let device = create_test_device();
+
+        let err = plugin
+            .authorize_power_action("suspend", &device)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::ProtocolError::PermissionDenied(_)));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_power_action_unsupported() {
+        let mut plugin = PowerPlugin::new();
+        let device = create_test_device();
+
+        let packet = plugin.create_power_request("self-destruct");
+        let err = plugin
+            .handle_power_request(&packet, &device)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::ProtocolError::UnsupportedFeature(_)));
+        assert!(err.to_string().contains("self-destruct"));
+    }
+
+    #[test]
+    fn test_logind_error_classification() {
+        use crate::ProtocolError;
+
+        let cases = [
+            (
+                "org.freedesktop.DBus.Error.AccessDenied: Permission denied",
+                "PermissionDenied",
+            ),
+            (
+                "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired: auth needed",
+                "PermissionDenied",
+            ),
+            (
+                "org.freedesktop.login1.OperationInProgress: There's already a shutdown or sleep operation in progress",
+                "DeviceBusy",
+            ),
+            (
+                "org.freedesktop.login1.BlockedByInhibitorLock: Operation inhibited",
+                "DeviceBusy",
+            ),
+            (
+                "org.freedesktop.login1.SleepVerbNotSupported: Sleep verb not supported",
+                "UnsupportedFeature",
+            ),
+            (
+                "org.freedesktop.DBus.Error.NoReply: Did not receive a reply",
+                "Timeout",
+            ),
+            ("something unexpected", "InvalidState"),
+        ];
+
+        for (message, expected) in cases {
+            let err = PowerPlugin::logind_error("suspend", message.to_string());
+            let actual = match err {
+                ProtocolError::PermissionDenied(_) => "PermissionDenied",
+                ProtocolError::DeviceBusy(_) => "DeviceBusy",
+                ProtocolError::UnsupportedFeature(_) => "UnsupportedFeature",
+                ProtocolError::Timeout(_) => "Timeout",
+                ProtocolError::InvalidState(_) => "InvalidState",
+                _ => "other",
+            };
+            assert_eq!(actual, expected, "classifying {:?}", message);
+        }
+    }
+
     #[test]
     fn test_inhibition_state_initial() {
         let plugin = PowerPlugin::new();
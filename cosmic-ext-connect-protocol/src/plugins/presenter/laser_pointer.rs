@@ -10,17 +10,41 @@
 //! - `tiny-skia` for rendering the colored dot
 //! - Separate thread for Wayland event loop to avoid blocking
 //!
+//! The event loop sleeps until a Wayland event arrives or the pointer is
+//! moved. It only redraws every [`FRAME_INTERVAL`] while the pointer fades
+//! out, and is otherwise idle between strokes.
+//!
 //! ## Features
 //!
 //! - Configurable pointer color and size
 //! - Smooth position updates
+//! - Fades out when the pointer stops moving
 //! - Clean lifecycle management
 //! - Thread-safe operation
+//!
+//! ## Positioning
+//!
+//! The pointer position is normalized: `(0.0, 0.0)` is the top-left and
+//! `(1.0, 1.0)` the bottom-right corner. It maps onto the output named in
+//! [`LaserPointerConfig::output`], or else onto the bounding box of all
+//! outputs in the compositor's logical coordinates, so with several monitors
+//! the pointer crosses from one to the next. Points in a gap between
+//! monitors snap to the nearest one. See [`map_to_output`].
+//!
+//! The overlay never takes input: it has no keyboard interactivity and an
+//! empty input region, so clicks and focus stay with the presentation.
 
 use smithay_client_toolkit::{
-    compositor::{CompositorHandler, CompositorState},
+    compositor::{CompositorHandler, CompositorState, Region},
     delegate_compositor, delegate_layer, delegate_output, delegate_registry, delegate_shm,
     output::{OutputHandler, OutputState},
+    reexports::{
+        calloop::{
+            ping::{make_ping, Ping},
+            EventLoop,
+        },
+        calloop_wayland_source::WaylandSource,
+    },
     registry::{ProvidesRegistryState, RegistryState},
     registry_handlers,
     shell::{
@@ -37,6 +61,7 @@ use std::sync::{
     Arc, Mutex,
 };
 use std::thread;
+use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 use wayland_client::{
     globals::registry_queue_init,
@@ -44,6 +69,12 @@ use wayland_client::{
     Connection, QueueHandle,
 };
 
+/// How long the pointer takes to fade out once the fade timeout passed
+const FADE_DURATION: Duration = Duration::from_millis(500);
+
+/// Interval between overlay updates while the pointer fades out
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
 /// Laser pointer color (RGBA)
 #[derive(Debug, Clone, Copy)]
 pub struct LaserPointerColor {
@@ -74,6 +105,10 @@ pub struct LaserPointerConfig {
     pub color: LaserPointerColor,
     /// Fade out after inactivity (milliseconds)
     pub fade_timeout_ms: u64,
+    /// Name of the output to show the pointer on (e.g. "HDMI-A-1")
+    ///
+    /// `None` spans the pointer across all outputs.
+    pub output: Option<String>,
 }
 
 impl Default for LaserPointerConfig {
@@ -82,34 +117,174 @@ impl Default for LaserPointerConfig {
             radius: 20.0,
             color: LaserPointerColor::default(),
             fade_timeout_ms: 2000,
+            output: None,
         }
     }
 }
 
+/// An output's area in the compositor's logical coordinate space
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputRect {
+    /// Output name, such as "eDP-1"
+    pub name: Option<String>,
+    /// Left edge
+    pub x: i32,
+    /// Top edge
+    pub y: i32,
+    /// Logical width
+    pub width: i32,
+    /// Logical height
+    pub height: i32,
+}
+
+impl OutputRect {
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64
+            && x <= (self.x + self.width) as f64
+            && y >= self.y as f64
+            && y <= (self.y + self.height) as f64
+    }
+
+    fn distance_to(&self, x: f64, y: f64) -> f64 {
+        let cx = x.clamp(self.x as f64, (self.x + self.width) as f64);
+        let cy = y.clamp(self.y as f64, (self.y + self.height) as f64);
+        (x - cx).hypot(y - cy)
+    }
+}
+
+/// Map a normalized pointer position onto an output
+///
+/// With a `preferred` output that exists, `(nx, ny)` spans just that output.
+/// Otherwise it spans the bounding box of all outputs and the output under
+/// the point is picked, or the nearest one if the point is in a gap.
+///
+/// Returns the index of the output and the position relative to its top-left
+/// corner in logical pixels, or `None` if there are no outputs.
+pub fn map_to_output(
+    outputs: &[OutputRect],
+    preferred: Option<&str>,
+    nx: f64,
+    ny: f64,
+) -> Option<(usize, f64, f64)> {
+    let nx = nx.clamp(0.0, 1.0);
+    let ny = ny.clamp(0.0, 1.0);
+
+    if let Some(index) =
+        preferred.and_then(|name| outputs.iter().position(|o| o.name.as_deref() == Some(name)))
+    {
+        let output = &outputs[index];
+        return Some((index, nx * output.width as f64, ny * output.height as f64));
+    }
+
+    let left = outputs.iter().map(|o| o.x).min()?;
+    let top = outputs.iter().map(|o| o.y).min()?;
+    let right = outputs.iter().map(|o| o.x + o.width).max()?;
+    let bottom = outputs.iter().map(|o| o.y + o.height).max()?;
+
+    let x = left as f64 + nx * (right - left) as f64;
+    let y = top as f64 + ny * (bottom - top) as f64;
+
+    let index = outputs.iter().position(|o| o.contains(x, y)).or_else(|| {
+        (0..outputs.len()).min_by(|&a, &b| {
+            outputs[a]
+                .distance_to(x, y)
+                .total_cmp(&outputs[b].distance_to(x, y))
+        })
+    })?;
+
+    let output = &outputs[index];
+    Some((
+        index,
+        (x - output.x as f64).clamp(0.0, output.width as f64),
+        (y - output.y as f64).clamp(0.0, output.height as f64),
+    ))
+}
+
+/// Opacity of the pointer `idle` after it last moved
+///
+/// Fully visible until `timeout`, then fades out over [`FADE_DURATION`].
+fn fade_alpha(idle: Duration, timeout: Duration) -> f32 {
+    let Some(fading) = idle.checked_sub(timeout) else {
+        return 1.0;
+    };
+    1.0 - (fading.as_secs_f32() / FADE_DURATION.as_secs_f32()).min(1.0)
+}
+
+/// How long the overlay can sleep before its next redraw
+///
+/// `None` once the pointer has faded out completely: nothing changes until
+/// it is moved again, which wakes the event loop.
+fn next_tick(idle: Duration, timeout: Duration, drawn_alpha: f32) -> Option<Duration> {
+    match timeout.checked_sub(idle) {
+        Some(until_fade) if !until_fade.is_zero() => Some(until_fade),
+        _ if drawn_alpha > 0.0 => Some(FRAME_INTERVAL),
+        _ => None,
+    }
+}
+
 /// Shared state between main thread and Wayland thread
 #[derive(Clone)]
 struct SharedState {
     position: Arc<Mutex<(f64, f64)>>,
+    last_moved: Arc<Mutex<Instant>>,
     config: Arc<Mutex<LaserPointerConfig>>,
     needs_redraw: Arc<AtomicBool>,
     active: Arc<AtomicBool>,
+    /// Wakes the Wayland thread's event loop while it runs
+    wakeup: Arc<Mutex<Option<Ping>>>,
 }
 
 impl SharedState {
     fn new(config: LaserPointerConfig) -> Self {
         Self {
-            position: Arc::new(Mutex::new((0.0, 0.0))),
+            position: Arc::new(Mutex::new((0.5, 0.5))),
+            last_moved: Arc::new(Mutex::new(Instant::now())),
             config: Arc::new(Mutex::new(config)),
             needs_redraw: Arc::new(AtomicBool::new(false)),
             active: Arc::new(AtomicBool::new(false)),
+            wakeup: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Wake the event loop to pick up a change
+    fn wake(&self) {
+        if let Ok(wakeup) = self.wakeup.lock() {
+            if let Some(ping) = wakeup.as_ref() {
+                ping.ping();
+            }
         }
     }
 
     fn set_position(&self, x: f64, y: f64) {
         if let Ok(mut pos) = self.position.lock() {
-            *pos = (x, y);
-            self.needs_redraw.store(true, Ordering::Relaxed);
+            *pos = (x.clamp(0.0, 1.0), y.clamp(0.0, 1.0));
+            self.touch();
+        }
+    }
+
+    /// Mark the pointer as just moved, making it fully visible again
+    fn touch(&self) {
+        if let Ok(mut last_moved) = self.last_moved.lock() {
+            *last_moved = Instant::now();
         }
+        self.needs_redraw.store(true, Ordering::Relaxed);
+        self.wake();
+    }
+
+    /// Time since the pointer last moved
+    fn idle(&self) -> Duration {
+        self.last_moved
+            .lock()
+            .map(|last_moved| last_moved.elapsed())
+            .unwrap_or_default()
+    }
+
+    fn fade_timeout(&self) -> Duration {
+        Duration::from_millis(self.get_config().fade_timeout_ms)
+    }
+
+    fn alpha(&self) -> f32 {
+        fade_alpha(self.idle(), self.fade_timeout())
     }
 
     fn get_position(&self) -> (f64, f64) {
@@ -123,6 +298,7 @@ impl SharedState {
             *cfg = config;
             self.needs_redraw.store(true, Ordering::Relaxed);
         }
+        self.wake();
     }
 
     fn get_config(&self) -> LaserPointerConfig {
@@ -141,6 +317,12 @@ struct LaserPointerApp {
     shm_state: Shm,
     layer_shell: LayerShell,
     layer_surface: Option<LayerSurface>,
+    /// Output the layer surface was created on
+    surface_output: Option<wl_output::WlOutput>,
+    /// Whether the layer surface received its first configure
+    configured: bool,
+    /// Opacity of the last drawn frame
+    drawn_alpha: f32,
     shared_state: SharedState,
     pool: Option<SlotPool>,
 }
@@ -161,24 +343,55 @@ impl LaserPointerApp {
             shm_state,
             layer_shell,
             layer_surface: None,
+            surface_output: None,
+            configured: false,
+            drawn_alpha: 0.0,
             shared_state,
             pool: None,
         }
     }
 
-    fn create_layer_surface(&mut self, qh: &QueueHandle<Self>) {
+    /// Outputs with known logical geometry
+    fn outputs(&self) -> Vec<(wl_output::WlOutput, OutputRect)> {
+        self.output_state
+            .outputs()
+            .filter_map(|output| {
+                let info = self.output_state.info(&output)?;
+                let (x, y) = info.logical_position?;
+                let (width, height) = info.logical_size?;
+                Some((
+                    output,
+                    OutputRect {
+                        name: info.name.clone(),
+                        x,
+                        y,
+                        width,
+                        height,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    fn create_layer_surface(&mut self, qh: &QueueHandle<Self>, output: &wl_output::WlOutput) {
         let surface = self.compositor_state.create_surface(qh);
 
+        // Let all input through to the windows below
+        match Region::new(&self.compositor_state) {
+            Ok(region) => surface.set_input_region(Some(region.wl_region())),
+            Err(e) => warn!("Failed to create empty input region: {}", e),
+        }
+
         let layer_surface = self.layer_shell.create_layer_surface(
             qh,
             surface,
             Layer::Overlay,
             Some("cosmic-ext-connect-laser-pointer"),
-            None,
+            Some(output),
         );
 
-        // Configure the layer surface
-        layer_surface.set_anchor(Anchor::empty());
+        // Anchor to the top-left corner so the margins position the dot
+        layer_surface.set_anchor(Anchor::TOP | Anchor::LEFT);
         layer_surface.set_keyboard_interactivity(KeyboardInteractivity::None);
         layer_surface.set_exclusive_zone(-1);
 
@@ -188,14 +401,20 @@ impl LaserPointerApp {
 
         layer_surface.commit();
         self.layer_surface = Some(layer_surface);
+        self.surface_output = Some(output.clone());
+        self.configured = false;
     }
 
     fn draw(&mut self, _qh: &QueueHandle<Self>) {
         let Some(layer_surface) = &self.layer_surface else {
             return;
         };
+        if !self.configured {
+            return;
+        }
 
         let config = self.shared_state.get_config();
+        let alpha = self.shared_state.alpha();
         let size = (config.radius * 2.0).ceil() as u32;
         let stride = size * 4;
         let buffer_size = (stride * size) as usize;
@@ -225,7 +444,7 @@ impl LaserPointerApp {
             .expect("Failed to create buffer");
 
         // Render to canvas
-        Self::render_pointer(canvas, size, &config);
+        Self::render_pointer(canvas, size, &config, alpha);
 
         // Attach and commit
         let wl_buffer = buffer.wl_buffer();
@@ -235,12 +454,13 @@ impl LaserPointerApp {
             .damage_buffer(0, 0, size as i32, size as i32);
         layer_surface.wl_surface().commit();
 
+        self.drawn_alpha = alpha;
         self.shared_state
             .needs_redraw
             .store(false, Ordering::Relaxed);
     }
 
-    fn render_pointer(canvas: &mut [u8], size: u32, config: &LaserPointerConfig) {
+    fn render_pointer(canvas: &mut [u8], size: u32, config: &LaserPointerConfig, alpha: f32) {
         let width = size;
         let height = size;
         let mut pixmap = tiny_skia::PixmapMut::from_bytes(canvas, width, height)
@@ -258,7 +478,7 @@ impl LaserPointerApp {
             (config.color.r * 255.0) as u8,
             (config.color.g * 255.0) as u8,
             (config.color.b * 255.0) as u8,
-            (config.color.a * 255.0) as u8,
+            (config.color.a * alpha * 255.0) as u8,
         );
         paint.anti_alias = true;
 
@@ -277,20 +497,55 @@ impl LaserPointerApp {
         );
     }
 
-    fn update_position(&mut self) {
-        let Some(layer_surface) = &self.layer_surface else {
+    /// Move the layer surface to the pointer, switching outputs if needed
+    fn update_position(&mut self, qh: &QueueHandle<Self>) {
+        let (nx, ny) = self.shared_state.get_position();
+        let config = self.shared_state.get_config();
+        let outputs = self.outputs();
+        let rects: Vec<OutputRect> = outputs.iter().map(|(_, rect)| rect.clone()).collect();
+
+        let Some((index, x, y)) = map_to_output(&rects, config.output.as_deref(), nx, ny) else {
             return;
         };
+        let output = &outputs[index].0;
 
-        let (x, y) = self.shared_state.get_position();
-        let config = self.shared_state.get_config();
-        let offset = config.radius as i32;
+        if self.surface_output.as_ref() != Some(output) {
+            debug!("Moving laser pointer to output {:?}", rects[index].name);
+            self.layer_surface = None;
+            self.create_layer_surface(qh, output);
+        }
 
-        // Set position relative to pointer coordinates
-        layer_surface.set_anchor(Anchor::empty());
+        let Some(layer_surface) = &self.layer_surface else {
+            return;
+        };
+        let offset = config.radius as i32;
         layer_surface.set_margin(y as i32 - offset, 0, 0, x as i32 - offset);
         layer_surface.commit();
     }
+
+    /// Redraw after moves and while fading
+    fn tick(&mut self, qh: &QueueHandle<Self>) {
+        let moved = self.shared_state.needs_redraw.load(Ordering::Relaxed);
+        let alpha = self.shared_state.alpha();
+        if moved {
+            self.update_position(qh);
+        }
+        if moved || alpha != self.drawn_alpha {
+            self.draw(qh);
+        }
+    }
+
+    /// How long to sleep before the next tick, `None` until woken
+    fn next_tick(&self) -> Option<Duration> {
+        if self.shared_state.needs_redraw.load(Ordering::Relaxed) {
+            return Some(Duration::ZERO);
+        }
+        next_tick(
+            self.shared_state.idle(),
+            self.shared_state.fade_timeout(),
+            self.drawn_alpha,
+        )
+    }
 }
 
 impl CompositorHandler for LaserPointerApp {
@@ -311,10 +566,7 @@ impl CompositorHandler for LaserPointerApp {
         _surface: &wl_surface::WlSurface,
         _time: u32,
     ) {
-        if self.shared_state.needs_redraw.load(Ordering::Relaxed) {
-            self.update_position();
-            self.draw(qh);
-        }
+        self.tick(qh);
     }
 
     fn transform_changed(
@@ -381,6 +633,7 @@ impl OutputHandler for LaserPointerApp {
 impl LayerShellHandler for LaserPointerApp {
     fn closed(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _layer: &LayerSurface) {
         self.layer_surface = None;
+        self.surface_output = None;
     }
 
     fn configure(
@@ -392,6 +645,7 @@ impl LayerShellHandler for LaserPointerApp {
         _serial: u32,
     ) {
         if self.layer_surface.is_some() {
+            self.configured = true;
             self.draw(qh);
         }
     }
@@ -453,6 +707,7 @@ impl LaserPointer {
 
         info!("Showing laser pointer overlay");
         self.shared_state.active.store(true, Ordering::Relaxed);
+        self.shared_state.touch();
 
         // Start Wayland thread
         let shared_state = self.shared_state.clone();
@@ -471,6 +726,7 @@ impl LaserPointer {
 
         info!("Hiding laser pointer overlay");
         self.shared_state.active.store(false, Ordering::Relaxed);
+        self.shared_state.wake();
 
         // Wait for thread to finish
         if let Some(handle) = self.wayland_thread.take() {
@@ -478,27 +734,27 @@ impl LaserPointer {
         }
     }
 
-    /// Update laser pointer position with delta movement
+    /// Move the laser pointer by a normalized delta
+    ///
+    /// The position stays within `0.0..=1.0` on both axes.
     pub fn move_by(&mut self, dx: f64, dy: f64) {
         let (x, y) = self.shared_state.get_position();
-        let new_x = x + dx;
-        let new_y = y + dy;
-
-        self.shared_state.set_position(new_x, new_y);
+        self.shared_state.set_position(x + dx, y + dy);
 
+        let (new_x, new_y) = self.shared_state.get_position();
         debug!(
             "Laser pointer moved by ({}, {}) to ({}, {})",
             dx, dy, new_x, new_y
         );
     }
 
-    /// Set absolute position
+    /// Set the normalized position
     pub fn set_position(&mut self, x: f64, y: f64) {
         self.shared_state.set_position(x, y);
         debug!("Laser pointer position set to ({}, {})", x, y);
     }
 
-    /// Get current position
+    /// Get the normalized position
     pub fn position(&self) -> (f64, f64) {
         self.shared_state.get_position()
     }
@@ -541,19 +797,39 @@ impl LaserPointer {
             shared_state.clone(),
         );
 
-        app.create_layer_surface(&qh);
-
-        // Event loop
-        while shared_state.active.load(Ordering::Relaxed) {
-            event_queue.blocking_dispatch(&mut app)?;
+        // Learn the outputs before placing the surface
+        event_queue.roundtrip(&mut app)?;
+        app.update_position(&qh);
+
+        // Sleep until a compositor event, a wakeup from the controlling
+        // thread, or the next fade frame
+        let mut event_loop: EventLoop<LaserPointerApp> = EventLoop::try_new()?;
+        WaylandSource::new(conn, event_queue)
+            .insert(event_loop.handle())
+            .map_err(|e| e.error)?;
+        let (ping, ping_source) = make_ping()?;
+        event_loop
+            .handle()
+            .insert_source(ping_source, |_, _, _| {})
+            .map_err(|e| e.error)?;
+        if let Ok(mut wakeup) = shared_state.wakeup.lock() {
+            *wakeup = Some(ping);
+        }
 
-            if shared_state.needs_redraw.load(Ordering::Relaxed) {
-                app.update_position();
-                app.draw(&qh);
+        let result = loop {
+            if !shared_state.active.load(Ordering::Relaxed) {
+                break Ok(());
             }
-        }
+            if let Err(e) = event_loop.dispatch(app.next_tick(), &mut app) {
+                break Err(e.into());
+            }
+            app.tick(&qh);
+        };
 
-        Ok(())
+        if let Ok(mut wakeup) = shared_state.wakeup.lock() {
+            *wakeup = None;
+        }
+        result
     }
 }
 
@@ -580,7 +856,7 @@ mod tests {
     fn test_laser_pointer_creation() {
         let pointer = LaserPointer::new();
         assert!(!pointer.is_active());
-        assert_eq!(pointer.position(), (0.0, 0.0));
+        assert_eq!(pointer.position(), (0.5, 0.5));
     }
 
     #[test]
@@ -600,19 +876,146 @@ mod tests {
     fn test_movement() {
         let mut pointer = LaserPointer::new();
 
-        pointer.move_by(10.0, 20.0);
-        assert_eq!(pointer.position(), (10.0, 20.0));
+        pointer.move_by(0.25, -0.25);
+        assert_eq!(pointer.position(), (0.75, 0.25));
+
+        pointer.move_by(-0.5, 0.5);
+        assert_eq!(pointer.position(), (0.25, 0.75));
 
-        pointer.move_by(-5.0, 15.0);
-        assert_eq!(pointer.position(), (5.0, 35.0));
+        // Clamped to the screen
+        pointer.move_by(-1.0, 1.0);
+        assert_eq!(pointer.position(), (0.0, 1.0));
     }
 
     #[test]
     fn test_set_position() {
         let mut pointer = LaserPointer::new();
 
-        pointer.set_position(100.0, 200.0);
-        assert_eq!(pointer.position(), (100.0, 200.0));
+        pointer.set_position(0.1, 0.9);
+        assert_eq!(pointer.position(), (0.1, 0.9));
+
+        pointer.set_position(100.0, -200.0);
+        assert_eq!(pointer.position(), (1.0, 0.0));
+    }
+
+    fn output(name: &str, x: i32, y: i32, width: i32, height: i32) -> OutputRect {
+        OutputRect {
+            name: Some(name.to_string()),
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_map_single_output() {
+        let outputs = [output("eDP-1", 0, 0, 1920, 1080)];
+        assert_eq!(
+            map_to_output(&outputs, None, 0.5, 0.5),
+            Some((0, 960.0, 540.0))
+        );
+        assert_eq!(
+            map_to_output(&outputs, None, 1.0, 1.0),
+            Some((0, 1920.0, 1080.0))
+        );
+        assert_eq!(map_to_output(&[], None, 0.5, 0.5), None);
+    }
+
+    #[test]
+    fn test_map_side_by_side_outputs() {
+        // Laptop panel left of a larger external monitor
+        let outputs = [
+            output("eDP-1", 0, 0, 1280, 800),
+            output("HDMI-A-1", 1280, 0, 2560, 1440),
+        ];
+
+        // The desktop is 3840 wide, a third of the way is the laptop's edge
+        let (index, x, y) = map_to_output(&outputs, None, 0.25, 0.25).unwrap();
+        assert_eq!(index, 0);
+        assert_eq!((x, y), (960.0, 360.0));
+
+        let (index, x, y) = map_to_output(&outputs, None, 0.75, 0.5).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!((x, y), (1600.0, 720.0));
+    }
+
+    #[test]
+    fn test_map_gap_snaps_to_nearest_output() {
+        let outputs = [
+            output("eDP-1", 0, 0, 1280, 800),
+            output("HDMI-A-1", 1280, 0, 2560, 1440),
+        ];
+
+        // Below the shorter laptop panel
+        let (index, x, y) = map_to_output(&outputs, None, 0.1, 0.9).unwrap();
+        assert_eq!(index, 0);
+        assert_eq!((x, y), (384.0, 800.0));
+    }
+
+    #[test]
+    fn test_map_negative_offsets() {
+        // External monitor left of the primary one
+        let outputs = [
+            output("DP-1", -1920, 0, 1920, 1080),
+            output("eDP-1", 0, 0, 1920, 1080),
+        ];
+
+        let (index, x, _) = map_to_output(&outputs, None, 0.25, 0.5).unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(x, 960.0);
+
+        let (index, x, _) = map_to_output(&outputs, None, 0.75, 0.5).unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(x, 960.0);
+    }
+
+    #[test]
+    fn test_map_preferred_output() {
+        let outputs = [
+            output("eDP-1", 0, 0, 1280, 800),
+            output("HDMI-A-1", 1280, 0, 2560, 1440),
+        ];
+
+        assert_eq!(
+            map_to_output(&outputs, Some("HDMI-A-1"), 0.5, 0.5),
+            Some((1, 1280.0, 720.0))
+        );
+
+        // Unknown outputs fall back to the whole desktop
+        assert_eq!(
+            map_to_output(&outputs, Some("DP-9"), 0.0, 0.0),
+            Some((0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn test_fade_alpha() {
+        let timeout = Duration::from_millis(2000);
+        assert_eq!(fade_alpha(Duration::ZERO, timeout), 1.0);
+        assert_eq!(fade_alpha(Duration::from_millis(2000), timeout), 1.0);
+        assert!((fade_alpha(Duration::from_millis(2250), timeout) - 0.5).abs() < 1e-6);
+        assert_eq!(fade_alpha(Duration::from_secs(10), timeout), 0.0);
+    }
+
+    #[test]
+    fn test_overlay_idle_between_strokes() {
+        let timeout = Duration::from_millis(2000);
+
+        // Fully visible: sleep until the fade starts
+        assert_eq!(
+            next_tick(Duration::from_millis(500), timeout, 1.0),
+            Some(Duration::from_millis(1500))
+        );
+
+        // Fading: redraw every frame
+        assert_eq!(
+            next_tick(Duration::from_millis(2250), timeout, 0.5),
+            Some(FRAME_INTERVAL)
+        );
+
+        // Faded out: sleep until the pointer moves again
+        assert_eq!(next_tick(Duration::from_secs(10), timeout, 0.0), None);
     }
 
     #[test]
@@ -626,6 +1029,7 @@ mod tests {
                 a: 1.0,
             },
             fade_timeout_ms: 3000,
+            output: Some("HDMI-A-1".to_string()),
         };
 
         let pointer = LaserPointer::with_config(config.clone());
@@ -24,8 +24,18 @@
 //!
 //! Presenter packets contain one of:
 //! - `dx`, `dy`: Pointer movement delta (for laser pointer)
+//! - `next`: Boolean, true to go to the next slide
+//! - `previous`: Boolean, true to go to the previous slide
 //! - `stop`: Boolean, true to stop presentation mode
 //!
+//! `dx` and `dy` are fractions of the screen size. The pointer starts in the
+//! middle of the screen when it appears and stays within its edges; see
+//! [`laser_pointer`] for how the screen is chosen with several monitors.
+//!
+//! Slide changes are injected as key presses through uinput, PageDown and
+//! PageUp by default, which every common presentation program understands.
+//! They can be changed with [`PresenterConfig`].
+//!
 //! ## References
 //!
 //! - [CConnect Presenter Plugin](https://github.com/KDE/cconnect-kde/tree/master/plugins/presenter)
@@ -36,18 +46,75 @@ pub mod laser_pointer;
 use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use laser_pointer::LaserPointer;
+use mouse_keyboard_input::VirtualDevice;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
-use tracing::{debug, info};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, error, info};
 
 use super::{Plugin, PluginFactory};
 
 // Re-export for external use
-pub use laser_pointer::{LaserPointerColor, LaserPointerConfig};
+pub use laser_pointer::{LaserPointerColor, LaserPointerConfig, OutputRect};
 
 /// Packet type for presenter events
 pub const PACKET_TYPE_PRESENTER: &str = "cconnect.presenter";
 
+/// Key pressed to change slides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlideKey {
+    PageDown,
+    PageUp,
+    Right,
+    Left,
+    Down,
+    Up,
+    Space,
+    Backspace,
+    Enter,
+}
+
+impl SlideKey {
+    /// Linux input key code
+    pub fn keycode(self) -> u16 {
+        use mouse_keyboard_input::*;
+
+        match self {
+            Self::PageDown => KEY_PAGEDOWN,
+            Self::PageUp => KEY_PAGEUP,
+            Self::Right => KEY_RIGHT,
+            Self::Left => KEY_LEFT,
+            Self::Down => KEY_DOWN,
+            Self::Up => KEY_UP,
+            Self::Space => KEY_SPACE,
+            Self::Backspace => KEY_BACKSPACE,
+            Self::Enter => KEY_ENTER,
+        }
+    }
+}
+
+/// Presenter plugin configuration
+#[derive(Debug, Clone)]
+pub struct PresenterConfig {
+    /// Key pressed for the next slide
+    pub next_key: SlideKey,
+    /// Key pressed for the previous slide
+    pub previous_key: SlideKey,
+    /// Laser pointer appearance and output
+    pub pointer: LaserPointerConfig,
+}
+
+impl Default for PresenterConfig {
+    fn default() -> Self {
+        Self {
+            next_key: SlideKey::PageDown,
+            previous_key: SlideKey::PageUp,
+            pointer: LaserPointerConfig::default(),
+        }
+    }
+}
+
 /// Presenter event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PresenterEvent {
@@ -59,6 +126,14 @@ pub struct PresenterEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dy: Option<f64>,
 
+    /// Go to the next slide
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<bool>,
+
+    /// Go to the previous slide
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous: Option<bool>,
+
     /// Stop presentation mode
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop: Option<bool>,
@@ -69,15 +144,26 @@ pub struct PresenterPlugin {
     device_id: Option<String>,
     presentation_active: bool,
     laser_pointer: LaserPointer,
+    next_key: SlideKey,
+    previous_key: SlideKey,
+    virtual_device: Arc<Mutex<Option<VirtualDevice>>>,
 }
 
 impl PresenterPlugin {
     /// Create a new Presenter plugin
     pub fn new() -> Self {
+        Self::with_config(PresenterConfig::default())
+    }
+
+    /// Create a plugin with explicit configuration
+    pub fn with_config(config: PresenterConfig) -> Self {
         Self {
             device_id: None,
             presentation_active: false,
-            laser_pointer: LaserPointer::new(),
+            laser_pointer: LaserPointer::with_config(config.pointer),
+            next_key: config.next_key,
+            previous_key: config.previous_key,
+            virtual_device: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -91,11 +177,55 @@ impl PresenterPlugin {
         &mut self.laser_pointer
     }
 
+    /// Key to press for an event, if it changes slides
+    fn slide_key(&self, event: &PresenterEvent) -> Option<SlideKey> {
+        if event.next.unwrap_or(false) {
+            Some(self.next_key)
+        } else if event.previous.unwrap_or(false) {
+            Some(self.previous_key)
+        } else {
+            None
+        }
+    }
+
+    /// Press and release a key on the virtual keyboard
+    fn press_key(&self, key: SlideKey) -> Result<()> {
+        let mut device_guard = self.virtual_device.lock().unwrap();
+        if device_guard.is_none() {
+            match VirtualDevice::default() {
+                Ok(dev) => {
+                    info!("Created virtual input device for presenter");
+                    *device_guard = Some(dev);
+                }
+                Err(e) => {
+                    error!("Failed to create virtual input device: {}", e);
+                    return Err(ProtocolError::Plugin(format!(
+                        "Failed to create virtual input device: {}",
+                        e
+                    )));
+                }
+            }
+        }
+
+        if let Some(dev) = device_guard.as_mut() {
+            let code = key.keycode();
+            dev.press(code)
+                .and_then(|_| dev.release(code))
+                .map_err(|e| ProtocolError::Plugin(format!("Failed to press {:?}: {}", key, e)))?;
+        }
+        Ok(())
+    }
+
     /// Handle a presenter event packet
     async fn handle_presenter_event(&mut self, packet: &Packet) -> Result<()> {
         let event: PresenterEvent = serde_json::from_value(packet.body.clone())
             .map_err(|e| ProtocolError::InvalidPacket(format!("Failed to parse event: {}", e)))?;
 
+        if let Some(key) = self.slide_key(&event) {
+            debug!("Presenter slide change: {:?}", key);
+            return self.press_key(key);
+        }
+
         // Handle stop event
         if event.stop.unwrap_or(false) {
             info!("Presentation mode stopped");
@@ -188,8 +318,22 @@ impl Plugin for PresenterPlugin {
 }
 
 /// Factory for creating Presenter plugin instances
-#[derive(Debug, Clone, Copy)]
-pub struct PresenterPluginFactory;
+#[derive(Debug, Clone, Default)]
+pub struct PresenterPluginFactory {
+    config: PresenterConfig,
+}
+
+impl PresenterPluginFactory {
+    /// Create a factory with the default configuration
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a factory whose plugins use the given configuration
+    pub fn with_config(config: PresenterConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl PluginFactory for PresenterPluginFactory {
     fn name(&self) -> &str {
@@ -208,7 +352,7 @@ impl PluginFactory for PresenterPluginFactory {
     }
 
     fn create(&self) -> Box<dyn Plugin> {
-        Box::new(PresenterPlugin::new())
+        Box::new(PresenterPlugin::with_config(self.config.clone()))
     }
 }
 
@@ -256,8 +400,8 @@ mod tests {
         let packet = Packet::new(
             "cconnect.presenter",
             json!({
-                "dx": 10.5,
-                "dy": -5.2
+                "dx": 0.25,
+                "dy": -0.125
             }),
         );
 
@@ -266,7 +410,8 @@ mod tests {
         assert!(result.is_ok());
         assert!(plugin.presentation_active);
         assert!(plugin.laser_pointer().is_active());
-        assert_eq!(plugin.laser_pointer().position(), (10.5, -5.2));
+        // Starts in the middle of the screen
+        assert_eq!(plugin.laser_pointer().position(), (0.75, 0.375));
     }
 
     #[tokio::test]
@@ -298,7 +443,7 @@ mod tests {
 
     #[test]
     fn test_factory() {
-        let factory = PresenterPluginFactory;
+        let factory = PresenterPluginFactory::new();
         assert_eq!(factory.name(), "presenter");
 
         let incoming = factory.incoming_capabilities();
@@ -344,8 +489,8 @@ mod tests {
         let packet1 = Packet::new(
             "cconnect.presenter",
             json!({
-                "dx": 5.0,
-                "dy": 10.0
+                "dx": 0.125,
+                "dy": 0.25
             }),
         );
 
@@ -356,14 +501,14 @@ mod tests {
             .unwrap();
         assert!(plugin.presentation_active);
         assert!(plugin.laser_pointer().is_active());
-        assert_eq!(plugin.laser_pointer().position(), (5.0, 10.0));
+        assert_eq!(plugin.laser_pointer().position(), (0.625, 0.75));
 
         // Second movement - should accumulate position
         let packet2 = Packet::new(
             "cconnect.presenter",
             json!({
-                "dx": -2.0,
-                "dy": 3.0
+                "dx": -0.5,
+                "dy": 0.5
             }),
         );
 
@@ -372,6 +517,65 @@ mod tests {
             .handle_packet(&packet2, &mut device_mut2)
             .await
             .unwrap();
-        assert_eq!(plugin.laser_pointer().position(), (3.0, 13.0));
+        // Accumulates and stays on screen
+        assert_eq!(plugin.laser_pointer().position(), (0.125, 1.0));
+    }
+
+    fn event(body: serde_json::Value) -> PresenterEvent {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_default_slide_keys() {
+        use mouse_keyboard_input::{KEY_PAGEDOWN, KEY_PAGEUP};
+
+        let plugin = PresenterPlugin::new();
+        let next = plugin.slide_key(&event(json!({ "next": true })));
+        let previous = plugin.slide_key(&event(json!({ "previous": true })));
+
+        assert_eq!(next.map(SlideKey::keycode), Some(KEY_PAGEDOWN));
+        assert_eq!(previous.map(SlideKey::keycode), Some(KEY_PAGEUP));
+        assert_eq!(plugin.slide_key(&event(json!({ "dx": 0.1 }))), None);
+        assert_eq!(plugin.slide_key(&event(json!({ "next": false }))), None);
+    }
+
+    #[test]
+    fn test_configured_slide_keys() {
+        let plugin = PresenterPlugin::with_config(PresenterConfig {
+            next_key: SlideKey::Right,
+            previous_key: SlideKey::Left,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            plugin.slide_key(&event(json!({ "next": true }))),
+            Some(SlideKey::Right)
+        );
+        assert_eq!(
+            plugin.slide_key(&event(json!({ "previous": true }))),
+            Some(SlideKey::Left)
+        );
+    }
+
+    #[test]
+    fn test_slide_key_serde() {
+        let key: SlideKey = serde_json::from_str("\"page_down\"").unwrap();
+        assert_eq!(key, SlideKey::PageDown);
+        assert_eq!(
+            serde_json::to_string(&SlideKey::Space).unwrap(),
+            "\"space\""
+        );
+    }
+
+    #[test]
+    fn test_factory_config() {
+        let factory = PresenterPluginFactory::with_config(PresenterConfig {
+            next_key: SlideKey::Space,
+            ..Default::default()
+        });
+        let plugin = factory.create();
+        let presenter = plugin.as_any().downcast_ref::<PresenterPlugin>().unwrap();
+        assert_eq!(presenter.next_key, SlideKey::Space);
+        assert_eq!(presenter.previous_key, SlideKey::PageUp);
     }
 }
@@ -21,14 +21,24 @@
 #[cfg(feature = "remotedesktop")]
 use super::{
     capture::WaylandCapture,
-    vnc::{generate_password, VncServer},
+    vnc::{generate_password, ClipboardHandler, VncServer},
 };
+#[cfg(feature = "remotedesktop")]
+use crate::plugins::clipboard_backend::ClipboardBackend;
 use crate::Result;
 use std::sync::Arc;
+#[cfg(feature = "remotedesktop")]
+use std::time::Duration;
+#[cfg(feature = "remotedesktop")]
+use tokio::sync::watch;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
+/// How often the host clipboard is checked for text to push to the VNC client
+#[cfg(feature = "remotedesktop")]
+const CLIPBOARD_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Session state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SessionState {
@@ -76,6 +86,15 @@ pub struct SessionManager {
 
     /// VNC server task handle
     server_handle: Arc<RwLock<Option<JoinHandle<()>>>>,
+
+    /// Receives text copied on the VNC client
+    clipboard_handler: Option<ClipboardHandler>,
+
+    /// Host clipboard text pushed to the VNC client
+    host_clipboard: Arc<watch::Sender<String>>,
+
+    /// Polls the host clipboard while a session runs
+    clipboard_task: Option<JoinHandle<()>>,
 }
 
 #[cfg(feature = "remotedesktop")]
@@ -86,7 +105,73 @@ impl SessionManager {
             state: Arc::new(RwLock::new(SessionState::Idle)),
             info: Arc::new(RwLock::new(None)),
             server_handle: Arc::new(RwLock::new(None)),
+            clipboard_handler: None,
+            host_clipboard: Arc::new(watch::channel(String::new()).0),
+            clipboard_task: None,
+        }
+    }
+
+    /// Forward text copied on the VNC client to `handler`
+    ///
+    /// Takes effect for sessions started afterwards.
+    pub fn set_clipboard_handler(&mut self, handler: ClipboardHandler) {
+        self.clipboard_handler = Some(handler);
+    }
+
+    /// Push host clipboard text to the VNC client
+    pub fn set_host_clipboard(&self, text: String) {
+        self.host_clipboard.send_replace(text);
+    }
+
+    /// Connect sessions to the system clipboard
+    ///
+    /// Text copied on the VNC client is written to the host clipboard, and the
+    /// host clipboard is polled for text to push to the client until the
+    /// session ends.
+    async fn connect_system_clipboard(&mut self) {
+        let backend = Arc::new(ClipboardBackend::new());
+
+        let writer = backend.clone();
+        self.set_clipboard_handler(Arc::new(move |text: String| {
+            let writer = writer.clone();
+            tokio::spawn(async move {
+                if !writer.write(&text).await {
+                    warn!("Failed to copy VNC client clipboard to the host");
+                }
+            });
+        }));
+
+        if let Some(text) = backend.read().await {
+            self.set_host_clipboard(text);
         }
+
+        if let Some(task) = self.clipboard_task.take() {
+            task.abort();
+        }
+        let host_clipboard = self.host_clipboard.clone();
+        let state = self.state.clone();
+        self.clipboard_task = Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CLIPBOARD_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                if matches!(
+                    *state.read().await,
+                    SessionState::Stopped | SessionState::Error
+                ) {
+                    break;
+                }
+                let Some(text) = backend.read().await else {
+                    continue;
+                };
+                host_clipboard.send_if_modified(|current| {
+                    let changed = *current != text;
+                    if changed {
+                        *current = text;
+                    }
+                    changed
+                });
+            }
+        }));
     }
 
     /// Get current session state
@@ -156,6 +241,9 @@ impl SessionManager {
         capture.start_capture().await?;
         info!("Screen capture session started");
 
+        // Share the clipboard in both directions
+        self.connect_system_clipboard().await;
+
         // Generate VNC password
         let password = generate_password();
         debug!("Generated VNC password: {}", password);
@@ -175,12 +263,17 @@ impl SessionManager {
         // Create and start VNC server in background task
         let state_clone = self.state.clone();
         let info_clone = self.info.clone();
+        let clipboard_handler = self.clipboard_handler.clone();
+        let host_clipboard = self.host_clipboard.subscribe();
 
         let server_handle = tokio::spawn(async move {
             info!("VNC server task starting...");
 
             // Create VNC server
-            let mut server = VncServer::new(port, password);
+            let mut server = VncServer::new(port, password).with_host_clipboard(host_clipboard);
+            if let Some(handler) = clipboard_handler {
+                server = server.with_clipboard_handler(handler);
+            }
 
             // Update state to active
             *state_clone.write().await = SessionState::Active;
@@ -223,6 +316,9 @@ impl SessionManager {
             handle.abort();
             debug!("VNC server task aborted");
         }
+        if let Some(task) = self.clipboard_task.take() {
+            task.abort();
+        }
 
         // Clear session info
         *self.info.write().await = None;
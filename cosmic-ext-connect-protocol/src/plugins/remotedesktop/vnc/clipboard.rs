@@ -0,0 +1,294 @@
+//! Clipboard Sync for VNC Sessions
+//!
+//! Text copied on the VNC client is passed to a [`ClipboardHandler`], and host
+//! clipboard changes are pushed to the client.
+//!
+//! Clients that advertise the ExtendedClipboard pseudo-encoding get UTF-8 text
+//! through its caps/notify/request/provide exchange:
+//!
+//! ```text
+//! Server                    Client
+//!   |  Caps                   |
+//!   |------------------------>|
+//!   |  Notify (text)          |
+//!   |------------------------>|
+//!   |  Request (text)         |
+//!   |<------------------------|
+//!   |  Provide (text)         |
+//!   |------------------------>|
+//! ```
+//!
+//! Other clients get plain ISO 8859-1 cut text.
+
+use super::protocol::{
+    CutText, ExtendedClipboard, RfbEncoding, CLIPBOARD_ACTION_NOTIFY, CLIPBOARD_ACTION_PEEK,
+    CLIPBOARD_ACTION_PROVIDE, CLIPBOARD_ACTION_REQUEST, CLIPBOARD_FORMAT_TEXT, MAX_CUT_TEXT_LEN,
+};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::debug;
+
+/// Callback receiving text copied on the VNC client
+pub type ClipboardHandler = Arc<dyn Fn(String) + Send + Sync>;
+
+/// Capabilities assumed for an extended clipboard client that hasn't sent
+/// its own
+const DEFAULT_CLIENT_CAPS: u32 = CLIPBOARD_FORMAT_TEXT
+    | CLIPBOARD_ACTION_REQUEST
+    | CLIPBOARD_ACTION_NOTIFY
+    | CLIPBOARD_ACTION_PROVIDE;
+
+/// Per-connection clipboard state
+pub(crate) struct ClipboardSync {
+    /// Receives text copied on the client
+    handler: Option<ClipboardHandler>,
+
+    /// Host clipboard text
+    host: Option<watch::Receiver<String>>,
+
+    /// Client advertised the ExtendedClipboard pseudo-encoding
+    extended: bool,
+
+    /// Client's extended clipboard capabilities
+    client_caps: u32,
+
+    /// Last text exchanged, so a change isn't echoed back to its source
+    last_text: Option<String>,
+}
+
+impl ClipboardSync {
+    /// Create clipboard state for a new connection
+    pub(crate) fn new(
+        handler: Option<ClipboardHandler>,
+        host: Option<watch::Receiver<String>>,
+    ) -> Self {
+        Self {
+            handler,
+            host,
+            extended: false,
+            client_caps: DEFAULT_CLIENT_CAPS,
+            last_text: None,
+        }
+    }
+
+    /// Update for the encodings in a SetEncodings message
+    ///
+    /// Returns the messages to send to the client.
+    pub(crate) fn set_client_encodings(&mut self, encodings: &[RfbEncoding]) -> Vec<CutText> {
+        self.extended = encodings.contains(&RfbEncoding::ExtendedClipboard);
+        if !self.extended {
+            return Vec::new();
+        }
+
+        debug!("Client supports extended clipboard");
+        vec![CutText::Extended(ExtendedClipboard::Caps {
+            flags: CLIPBOARD_FORMAT_TEXT
+                | CLIPBOARD_ACTION_REQUEST
+                | CLIPBOARD_ACTION_PEEK
+                | CLIPBOARD_ACTION_NOTIFY
+                | CLIPBOARD_ACTION_PROVIDE,
+            max_sizes: vec![MAX_CUT_TEXT_LEN as u32],
+        })]
+    }
+
+    /// Handle a ClientCutText message
+    ///
+    /// Returns the replies to send to the client.
+    pub(crate) fn handle_client_message(&mut self, message: CutText) -> Vec<CutText> {
+        match message {
+            CutText::Text(text) => {
+                self.client_text(text);
+                Vec::new()
+            }
+            CutText::Extended(ExtendedClipboard::Caps { flags, .. }) => {
+                self.client_caps = flags;
+                Vec::new()
+            }
+            CutText::Extended(ExtendedClipboard::Notify { formats })
+                if formats & CLIPBOARD_FORMAT_TEXT != 0 =>
+            {
+                vec![CutText::Extended(ExtendedClipboard::Request {
+                    formats: CLIPBOARD_FORMAT_TEXT,
+                })]
+            }
+            CutText::Extended(ExtendedClipboard::Provide { text: Some(text) }) => {
+                self.client_text(text);
+                Vec::new()
+            }
+            CutText::Extended(ExtendedClipboard::Request { formats })
+                if formats & CLIPBOARD_FORMAT_TEXT != 0 =>
+            {
+                vec![CutText::Extended(ExtendedClipboard::Provide {
+                    text: Some(self.host_text()),
+                })]
+            }
+            CutText::Extended(ExtendedClipboard::Peek) => {
+                let formats = if self.host_text().is_empty() {
+                    0
+                } else {
+                    CLIPBOARD_FORMAT_TEXT
+                };
+                vec![CutText::Extended(ExtendedClipboard::Notify { formats })]
+            }
+            CutText::Extended(_) => Vec::new(),
+        }
+    }
+
+    /// Check for a host clipboard change
+    ///
+    /// Returns the messages announcing it to the client, if it changed.
+    pub(crate) fn poll_host(&mut self) -> Vec<CutText> {
+        let Some(host) = self.host.as_mut() else {
+            return Vec::new();
+        };
+        if !host.has_changed().unwrap_or(false) {
+            return Vec::new();
+        }
+
+        let text = host.borrow_and_update().clone();
+        if self.last_text.as_ref() == Some(&text) {
+            return Vec::new();
+        }
+        self.last_text = Some(text.clone());
+
+        if !self.extended {
+            vec![CutText::Text(text)]
+        } else if self.client_caps & CLIPBOARD_ACTION_NOTIFY != 0 {
+            vec![CutText::Extended(ExtendedClipboard::Notify {
+                formats: CLIPBOARD_FORMAT_TEXT,
+            })]
+        } else if self.client_caps & CLIPBOARD_ACTION_PROVIDE != 0 {
+            vec![CutText::Extended(ExtendedClipboard::Provide {
+                text: Some(text),
+            })]
+        } else {
+            vec![CutText::Text(text)]
+        }
+    }
+
+    fn client_text(&mut self, text: String) {
+        debug!("Client clipboard changed ({} bytes)", text.len());
+        self.last_text = Some(text.clone());
+        if let Some(handler) = &self.handler {
+            handler(text);
+        }
+    }
+
+    fn host_text(&self) -> String {
+        self.host
+            .as_ref()
+            .map(|host| host.borrow().clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn recording_handler() -> (ClipboardHandler, Arc<Mutex<Vec<String>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let handler: ClipboardHandler =
+            Arc::new(move |text: String| sink.lock().unwrap().push(text));
+        (handler, received)
+    }
+
+    #[test]
+    fn test_plain_client_text_forwarded() {
+        let (handler, received) = recording_handler();
+        let mut sync = ClipboardSync::new(Some(handler), None);
+
+        let replies = sync.handle_client_message(CutText::Text("hello".to_string()));
+        assert!(replies.is_empty());
+        assert_eq!(*received.lock().unwrap(), vec!["hello".to_string()]);
+    }
+
+    #[test]
+    fn test_host_change_sent_as_plain_text() {
+        let (host_tx, host_rx) = watch::channel(String::new());
+        let mut sync = ClipboardSync::new(None, Some(host_rx));
+        sync.set_client_encodings(&[RfbEncoding::Raw]);
+
+        assert!(sync.poll_host().is_empty());
+        host_tx.send_replace("copied".to_string());
+        assert_eq!(sync.poll_host(), vec![CutText::Text("copied".to_string())]);
+        assert!(sync.poll_host().is_empty());
+    }
+
+    #[test]
+    fn test_client_text_not_echoed_back() {
+        let (host_tx, host_rx) = watch::channel(String::new());
+        let mut sync = ClipboardSync::new(None, Some(host_rx));
+
+        sync.handle_client_message(CutText::Text("from client".to_string()));
+        host_tx.send_replace("from client".to_string());
+        assert!(sync.poll_host().is_empty());
+    }
+
+    #[test]
+    fn test_extended_exchange() {
+        let (handler, received) = recording_handler();
+        let (host_tx, host_rx) = watch::channel(String::new());
+        let mut sync = ClipboardSync::new(Some(handler), Some(host_rx));
+
+        let caps = sync.set_client_encodings(&[RfbEncoding::Raw, RfbEncoding::ExtendedClipboard]);
+        assert!(matches!(
+            caps.as_slice(),
+            [CutText::Extended(ExtendedClipboard::Caps { .. })]
+        ));
+
+        // Host change is announced, then provided on request
+        host_tx.send_replace("héllo 🌍".to_string());
+        assert_eq!(
+            sync.poll_host(),
+            vec![CutText::Extended(ExtendedClipboard::Notify {
+                formats: CLIPBOARD_FORMAT_TEXT
+            })]
+        );
+        let reply = sync.handle_client_message(CutText::Extended(ExtendedClipboard::Request {
+            formats: CLIPBOARD_FORMAT_TEXT,
+        }));
+        assert_eq!(
+            reply,
+            vec![CutText::Extended(ExtendedClipboard::Provide {
+                text: Some("héllo 🌍".to_string())
+            })]
+        );
+
+        // Client change is requested, then forwarded when provided
+        let reply = sync.handle_client_message(CutText::Extended(ExtendedClipboard::Notify {
+            formats: CLIPBOARD_FORMAT_TEXT,
+        }));
+        assert_eq!(
+            reply,
+            vec![CutText::Extended(ExtendedClipboard::Request {
+                formats: CLIPBOARD_FORMAT_TEXT
+            })]
+        );
+        sync.handle_client_message(CutText::Extended(ExtendedClipboard::Provide {
+            text: Some("từ khách".to_string()),
+        }));
+        assert_eq!(*received.lock().unwrap(), vec!["từ khách".to_string()]);
+    }
+
+    #[test]
+    fn test_extended_client_without_notify_gets_provide() {
+        let (host_tx, host_rx) = watch::channel(String::new());
+        let mut sync = ClipboardSync::new(None, Some(host_rx));
+        sync.set_client_encodings(&[RfbEncoding::ExtendedClipboard]);
+        sync.handle_client_message(CutText::Extended(ExtendedClipboard::Caps {
+            flags: CLIPBOARD_FORMAT_TEXT | CLIPBOARD_ACTION_PROVIDE,
+            max_sizes: vec![4096],
+        }));
+
+        host_tx.send_replace("text".to_string());
+        assert_eq!(
+            sync.poll_host(),
+            vec![CutText::Extended(ExtendedClipboard::Provide {
+                text: Some("text".to_string())
+            })]
+        );
+    }
+}
@@ -28,25 +28,14 @@
 //!
 //! - **Hextile**: VNC standard tile-based encoding (RFC 6143 Section 7.7.4)
 //!   - Divides framebuffer into 16x16 tiles
-//!   - Solid color optimization for uniform tiles
-//!   - Falls back to raw encoding for complex tiles
+//!   - Background color plus subrectangles, with a single foreground color
+//!     for two-color tiles and per-subrectangle colors otherwise
+//!   - Background/foreground carried over between tiles when unchanged
+//!   - Falls back to raw encoding when subrectangles would be larger
 //!   - Good for static content with limited color palette
 //!
 //! ## Future Enhancement Opportunities
 //!
-//! ### Advanced Hextile Implementation
-//!
-//! The current Hextile implementation uses a simplified approach (solid color
-//! detection + raw fallback). A complete RFC 6143 implementation could add:
-//!
-//! - Subrectangle encoding for partial tile updates
-//! - Foreground/background color coding
-//! - Run-length encoding for repeated colors
-//! - Colored subrectangle support
-//!
-//! These optimizations can significantly improve compression for UI content
-//! with limited color palettes (terminals, text editors, etc.).
-//!
 //! ### Additional Encoding Types (Future Phases)
 //!
 //! - **ZRLE** (Zlib Run-Length Encoding): Better than Hextile for complex screens
@@ -65,6 +54,7 @@
 
 use crate::plugins::remotedesktop::capture::{EncodedFrame, EncodingType, QualityPreset, RawFrame};
 use crate::Result;
+use std::collections::HashMap;
 use std::time::Instant;
 use tracing::{debug, info};
 
@@ -115,6 +105,9 @@ pub struct FrameEncoder {
     /// Preferred encoding type
     preferred_encoding: EncodingType,
 
+    /// Encoding was chosen with `set_encoding` rather than by quality
+    encoding_fixed: bool,
+
     /// Statistics
     stats: EncoderStats,
 }
@@ -123,17 +116,14 @@ pub struct FrameEncoder {
 impl FrameEncoder {
     /// Create a new frame encoder
     pub fn new(quality: QualityPreset) -> Self {
-        let preferred_encoding = match quality {
-            QualityPreset::Low => EncodingType::H264,
-            QualityPreset::Medium => EncodingType::LZ4,
-            QualityPreset::High => EncodingType::Raw,
-        };
+        let preferred_encoding = Self::encoding_for_quality(quality);
 
         info!("Creating frame encoder with {:?} quality", quality);
 
         Self {
             quality,
             preferred_encoding,
+            encoding_fixed: false,
             stats: EncoderStats::default(),
         }
     }
@@ -325,6 +315,7 @@ impl FrameEncoder {
         const TILE_SIZE: u32 = 16;
 
         let mut encoded_data = Vec::new();
+        let mut tile_encoder = HextileTileEncoder::new(frame.format.bytes_per_pixel() as usize);
         let mut tiles_skipped = 0u32;
         let mut tiles_encoded = 0u32;
 
@@ -359,14 +350,13 @@ impl FrameEncoder {
                 }
 
                 tiles_encoded += 1;
-                self.encode_hextile_tile(
-                    frame,
-                    tile_x,
-                    tile_y,
-                    tile_width,
-                    tile_height,
+                let tile = Self::extract_tile(frame, tile_x, tile_y, tile_width, tile_height);
+                tile_encoder.encode_tile(
+                    &tile,
+                    tile_width as usize,
+                    tile_height as usize,
                     &mut encoded_data,
-                )?;
+                );
             }
         }
 
@@ -389,29 +379,17 @@ impl FrameEncoder {
         Ok(encoded)
     }
 
-    /// Encode a single Hextile tile
-    fn encode_hextile_tile(
-        &self,
+    /// Copy the pixels of one tile out of the frame, row by row
+    fn extract_tile(
         frame: &RawFrame,
         tile_x: u32,
         tile_y: u32,
         tile_width: u32,
         tile_height: u32,
-        output: &mut Vec<u8>,
-    ) -> Result<()> {
-        // Hextile encoding modes (RFC 6143):
-        // Bit 0: Raw - tile data is raw pixels
-        // Bit 1: BackgroundSpecified - background color follows subencoding byte
-        // Bit 2: ForegroundSpecified - foreground color follows background
-        // Bit 3: AnySubrects - subrectangles follow
-        // Bit 4: SubrectsColoured - each subrect has its own color
-
-        const RAW: u8 = 1 << 0;
-        const BACKGROUND_SPECIFIED: u8 = 1 << 1;
-
-        // Extract tile pixels
-        let mut tile_pixels = Vec::new();
+    ) -> Vec<u8> {
         let bytes_per_pixel = frame.format.bytes_per_pixel() as usize;
+        let mut tile_pixels =
+            Vec::with_capacity((tile_width * tile_height) as usize * bytes_per_pixel);
 
         for y in 0..tile_height {
             let row_start = ((tile_y + y) * frame.width + tile_x) as usize * bytes_per_pixel;
@@ -422,37 +400,7 @@ impl FrameEncoder {
             }
         }
 
-        // Analyze tile for compression opportunities
-        let (is_uniform, background_color) = self.analyze_tile(&tile_pixels, bytes_per_pixel);
-
-        if is_uniform {
-            // Tile is solid color - use background only
-            output.push(BACKGROUND_SPECIFIED);
-            output.extend_from_slice(&background_color);
-        } else {
-            // Tile has variations - use raw encoding for simplicity
-            // Advanced implementation could detect subrectangles
-            output.push(RAW);
-            output.extend_from_slice(&tile_pixels);
-        }
-
-        Ok(())
-    }
-
-    /// Analyze tile to determine if it's uniform color
-    fn analyze_tile(&self, pixels: &[u8], bytes_per_pixel: usize) -> (bool, Vec<u8>) {
-        if pixels.len() < bytes_per_pixel {
-            return (false, Vec::new());
-        }
-
-        let first_pixel = &pixels[0..bytes_per_pixel];
-
-        // Check if all pixels match the first pixel
-        let is_uniform = pixels
-            .chunks_exact(bytes_per_pixel)
-            .all(|pixel| pixel == first_pixel);
-
-        (is_uniform, first_pixel.to_vec())
+        tile_pixels
     }
 
     /// Get encoder statistics
@@ -472,19 +420,248 @@ impl FrameEncoder {
             self.quality, quality
         );
         self.quality = quality;
+        self.encoding_fixed = false;
 
         // Update preferred encoding
-        self.preferred_encoding = match quality {
-            QualityPreset::Low => EncodingType::H264,
-            QualityPreset::Medium => EncodingType::LZ4,
-            QualityPreset::High => EncodingType::Raw,
-        };
+        self.preferred_encoding = Self::encoding_for_quality(quality);
+    }
+
+    /// Change quality preset in response to network conditions
+    ///
+    /// Unlike [`Self::set_quality`], keeps an encoding chosen with
+    /// [`Self::set_encoding`], since that is what the client accepts.
+    pub fn adapt_quality(&mut self, quality: QualityPreset) {
+        debug!("Adapting encoder quality to {:?}", quality);
+        self.quality = quality;
+
+        if !self.encoding_fixed {
+            self.preferred_encoding = Self::encoding_for_quality(quality);
+        }
     }
 
     /// Change encoding type
     pub fn set_encoding(&mut self, encoding: EncodingType) {
         info!("Changing encoder type to {:?}", encoding);
         self.preferred_encoding = encoding;
+        self.encoding_fixed = true;
+    }
+
+    /// Encoding used for a quality preset
+    fn encoding_for_quality(quality: QualityPreset) -> EncodingType {
+        match quality {
+            QualityPreset::Low => EncodingType::H264,
+            QualityPreset::Medium => EncodingType::LZ4,
+            QualityPreset::High => EncodingType::Raw,
+        }
+    }
+}
+
+/// A run of same-colored pixels within a Hextile tile
+#[cfg(feature = "remotedesktop")]
+#[derive(Debug, Clone, Copy)]
+struct HextileSubrect {
+    color: u32,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+/// Encodes the tiles of one Hextile rectangle (RFC 6143 Section 7.7.4)
+///
+/// Each tile is sent as a background color plus subrectangles of other
+/// colors, or as raw pixels when that would be smaller. The background and
+/// foreground colors carry over from one tile to the next, so they are only
+/// sent when they change.
+#[cfg(feature = "remotedesktop")]
+struct HextileTileEncoder {
+    bytes_per_pixel: usize,
+
+    /// Background color the client holds from the previous tile
+    background: Option<u32>,
+
+    /// Foreground color the client holds from the previous tile
+    foreground: Option<u32>,
+}
+
+#[cfg(feature = "remotedesktop")]
+impl HextileTileEncoder {
+    // Subencoding mask bits
+    const RAW: u8 = 1 << 0;
+    const BACKGROUND_SPECIFIED: u8 = 1 << 1;
+    const FOREGROUND_SPECIFIED: u8 = 1 << 2;
+    const ANY_SUBRECTS: u8 = 1 << 3;
+    const SUBRECTS_COLOURED: u8 = 1 << 4;
+
+    /// Subrectangle count is sent as a single byte
+    const MAX_SUBRECTS: usize = u8::MAX as usize;
+
+    fn new(bytes_per_pixel: usize) -> Self {
+        Self {
+            bytes_per_pixel,
+            background: None,
+            foreground: None,
+        }
+    }
+
+    /// Append one `width` x `height` tile to `output`
+    fn encode_tile(&mut self, tile: &[u8], width: usize, height: usize, output: &mut Vec<u8>) {
+        let pixels: Vec<u32> = tile
+            .chunks_exact(self.bytes_per_pixel)
+            .map(|pixel| self.read_pixel(pixel))
+            .collect();
+
+        match self.encode_subrects(&pixels, width, height) {
+            Some(encoded) if encoded.len() <= 1 + tile.len() => output.extend_from_slice(&encoded),
+            _ => {
+                output.push(Self::RAW);
+                output.extend_from_slice(tile);
+
+                // Colors are not carried over from a raw tile
+                self.background = None;
+                self.foreground = None;
+            }
+        }
+    }
+
+    /// Encode a tile as background plus subrectangles, updating the carried
+    /// colors. Returns `None` if the tile can't be encoded this way.
+    fn encode_subrects(&mut self, pixels: &[u32], width: usize, height: usize) -> Option<Vec<u8>> {
+        if pixels.is_empty() || pixels.len() != width * height {
+            return None;
+        }
+
+        let mut counts: HashMap<u32, usize> = HashMap::new();
+        for &pixel in pixels {
+            *counts.entry(pixel).or_insert(0) += 1;
+        }
+
+        // The most common color is the background; on a tie keep the one the
+        // client already has
+        let background = counts
+            .iter()
+            .max_by_key(|&(&color, &count)| (count, Some(color) == self.background, color))
+            .map(|(&color, _)| color)?;
+
+        let subrects = Self::find_subrects(pixels, width, height, background);
+        if subrects.len() > Self::MAX_SUBRECTS {
+            return None;
+        }
+
+        // With two colors every subrect shares the foreground color
+        let foreground = match counts.len() {
+            2 => counts.keys().copied().find(|&color| color != background),
+            _ => None,
+        };
+        let colored = !subrects.is_empty() && foreground.is_none();
+
+        let mut subencoding = 0u8;
+        if self.background != Some(background) {
+            subencoding |= Self::BACKGROUND_SPECIFIED;
+        }
+        if !subrects.is_empty() {
+            subencoding |= Self::ANY_SUBRECTS;
+            if colored {
+                subencoding |= Self::SUBRECTS_COLOURED;
+            } else if self.foreground != foreground {
+                subencoding |= Self::FOREGROUND_SPECIFIED;
+            }
+        }
+
+        let mut encoded = vec![subencoding];
+        if subencoding & Self::BACKGROUND_SPECIFIED != 0 {
+            self.write_pixel(background, &mut encoded);
+        }
+        if subencoding & Self::FOREGROUND_SPECIFIED != 0 {
+            self.write_pixel(foreground?, &mut encoded);
+        }
+        if subencoding & Self::ANY_SUBRECTS != 0 {
+            encoded.push(subrects.len() as u8);
+            for subrect in &subrects {
+                if colored {
+                    self.write_pixel(subrect.color, &mut encoded);
+                }
+                encoded.push(((subrect.x << 4) | subrect.y) as u8);
+                encoded.push((((subrect.width - 1) << 4) | (subrect.height - 1)) as u8);
+            }
+        }
+
+        self.background = Some(background);
+        if colored {
+            self.foreground = None;
+        } else if foreground.is_some() {
+            self.foreground = foreground;
+        }
+
+        Some(encoded)
+    }
+
+    /// Cover every non-background pixel with same-colored rectangles
+    ///
+    /// Scans in row order; from each uncovered pixel takes the largest
+    /// rectangle of its color extending right and down.
+    fn find_subrects(
+        pixels: &[u32],
+        width: usize,
+        height: usize,
+        background: u32,
+    ) -> Vec<HextileSubrect> {
+        let mut covered = vec![false; pixels.len()];
+        let mut subrects = Vec::new();
+
+        for y in 0..height {
+            for x in 0..width {
+                let color = pixels[y * width + x];
+                if color == background || covered[y * width + x] {
+                    continue;
+                }
+
+                let mut max_width = width - x;
+                let (mut best_width, mut best_height) = (0, 0);
+                for row in y..height {
+                    let start = row * width + x;
+                    let run = pixels[start..start + max_width]
+                        .iter()
+                        .take_while(|&&pixel| pixel == color)
+                        .count();
+                    if run == 0 {
+                        break;
+                    }
+
+                    max_width = run;
+                    let rows = row - y + 1;
+                    if run * rows > best_width * best_height {
+                        best_width = run;
+                        best_height = rows;
+                    }
+                }
+
+                for row in y..y + best_height {
+                    let start = row * width + x;
+                    covered[start..start + best_width].fill(true);
+                }
+
+                subrects.push(HextileSubrect {
+                    color,
+                    x,
+                    y,
+                    width: best_width,
+                    height: best_height,
+                });
+            }
+        }
+
+        subrects
+    }
+
+    fn read_pixel(&self, bytes: &[u8]) -> u32 {
+        let mut pixel = [0u8; 4];
+        pixel[..self.bytes_per_pixel].copy_from_slice(bytes);
+        u32::from_le_bytes(pixel)
+    }
+
+    fn write_pixel(&self, pixel: u32, output: &mut Vec<u8>) {
+        output.extend_from_slice(&pixel.to_le_bytes()[..self.bytes_per_pixel]);
     }
 }
 
@@ -635,6 +812,19 @@ mod tests {
         assert_eq!(encoder.preferred_encoding, EncodingType::Raw);
     }
 
+    #[test]
+    #[cfg(feature = "remotedesktop")]
+    fn test_adapt_quality_keeps_chosen_encoding() {
+        let mut encoder = FrameEncoder::new(QualityPreset::High);
+        encoder.adapt_quality(QualityPreset::Medium);
+        assert_eq!(encoder.preferred_encoding, EncodingType::LZ4);
+
+        encoder.set_encoding(EncodingType::Hextile);
+        encoder.adapt_quality(QualityPreset::Low);
+        assert_eq!(encoder.quality, QualityPreset::Low);
+        assert_eq!(encoder.preferred_encoding, EncodingType::Hextile);
+    }
+
     #[test]
     #[cfg(feature = "remotedesktop")]
     fn test_compression_ratio() {
@@ -850,6 +1040,169 @@ mod tests {
         );
     }
 
+    /// Fill `(x, y, width, height)` of a decoded framebuffer with `color`
+    fn fill_rect(
+        frame: &mut [u8],
+        stride: usize,
+        bytes_per_pixel: usize,
+        rect: (usize, usize, usize, usize),
+        color: &[u8],
+    ) {
+        let (x, y, width, height) = rect;
+        for row in y..y + height {
+            for col in x..x + width {
+                let idx = (row * stride + col) * bytes_per_pixel;
+                frame[idx..idx + bytes_per_pixel].copy_from_slice(color);
+            }
+        }
+    }
+
+    /// Reconstruct a framebuffer from a Hextile rectangle, as a client would
+    fn decode_hextile(data: &[u8], width: usize, height: usize, bytes_per_pixel: usize) -> Vec<u8> {
+        let mut frame = vec![0u8; width * height * bytes_per_pixel];
+        let mut background = vec![0u8; bytes_per_pixel];
+        let mut foreground = vec![0u8; bytes_per_pixel];
+        let mut pos = 0;
+
+        for tile_y in (0..height).step_by(16) {
+            for tile_x in (0..width).step_by(16) {
+                let tile_width = 16.min(width - tile_x);
+                let tile_height = 16.min(height - tile_y);
+
+                let subencoding = data[pos];
+                pos += 1;
+
+                if subencoding & 1 != 0 {
+                    for row in tile_y..tile_y + tile_height {
+                        let start = (row * width + tile_x) * bytes_per_pixel;
+                        let len = tile_width * bytes_per_pixel;
+                        frame[start..start + len].copy_from_slice(&data[pos..pos + len]);
+                        pos += len;
+                    }
+                    continue;
+                }
+
+                if subencoding & 2 != 0 {
+                    background = data[pos..pos + bytes_per_pixel].to_vec();
+                    pos += bytes_per_pixel;
+                }
+                let tile = (tile_x, tile_y, tile_width, tile_height);
+                fill_rect(&mut frame, width, bytes_per_pixel, tile, &background);
+
+                if subencoding & 4 != 0 {
+                    foreground = data[pos..pos + bytes_per_pixel].to_vec();
+                    pos += bytes_per_pixel;
+                }
+
+                if subencoding & 8 != 0 {
+                    let count = data[pos];
+                    pos += 1;
+
+                    for _ in 0..count {
+                        let color = if subencoding & 16 != 0 {
+                            pos += bytes_per_pixel;
+                            data[pos - bytes_per_pixel..pos].to_vec()
+                        } else {
+                            foreground.clone()
+                        };
+                        let (xy, wh) = (data[pos] as usize, data[pos + 1] as usize);
+                        pos += 2;
+
+                        let rect = (
+                            tile_x + (xy >> 4),
+                            tile_y + (xy & 0xf),
+                            (wh >> 4) + 1,
+                            (wh & 0xf) + 1,
+                        );
+                        fill_rect(&mut frame, width, bytes_per_pixel, rect, &color);
+                    }
+                }
+            }
+        }
+
+        assert_eq!(pos, data.len(), "trailing Hextile data");
+        frame
+    }
+
+    #[test]
+    #[cfg(feature = "remotedesktop")]
+    fn test_hextile_subrect_bytes() {
+        // Two identical 16x16 tiles: black with a 2x3 white block at (4, 5)
+        let (width, height) = (32, 16);
+        let mut data = [0u8, 0, 0, 255].repeat(width * height);
+        for y in 5..8 {
+            for x in [4, 5, 20, 21] {
+                let idx = (y * width + x) * 4;
+                data[idx..idx + 4].copy_from_slice(&[255, 255, 255, 255]);
+            }
+        }
+        let frame = RawFrame::new(width as u32, height as u32, PixelFormat::RGBA, data);
+
+        let mut encoder = FrameEncoder::new(QualityPreset::Medium);
+        encoder.set_encoding(EncodingType::Hextile);
+        let encoded = encoder.encode(&frame).unwrap();
+
+        #[rustfmt::skip]
+        let expected = vec![
+            // BackgroundSpecified | ForegroundSpecified | AnySubrects
+            0x0e,
+            0, 0, 0, 255,
+            255, 255, 255, 255,
+            1, 0x45, 0x12,
+            // Second tile reuses both colors
+            0x08,
+            1, 0x45, 0x12,
+        ];
+        assert_eq!(encoded.data, expected);
+    }
+
+    #[test]
+    #[cfg(feature = "remotedesktop")]
+    fn test_hextile_decode_reconstructs_frame() {
+        // Partial tiles on the right and bottom edges
+        let (width, height) = (50, 37);
+        let mut data = Vec::with_capacity(width * height * 4);
+        let mut noise = 0x1234_5678u32;
+
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = if y < 16 && x < 16 {
+                    // Two-color "text": dark glyph strokes on white
+                    if x % 5 == 1 || (y % 7 == 3 && x % 5 < 4) {
+                        [20, 20, 20, 255]
+                    } else {
+                        [255, 255, 255, 255]
+                    }
+                } else if y < 16 {
+                    // Colored bands and a box on a gray background
+                    match (x / 4 % 4, y) {
+                        (_, 2..=4) => [200, (x * 5) as u8, 0, 255],
+                        (1, 8..=12) => [0, 0, 255, 255],
+                        (3, 6..=13) => [0, 255, 0, 255],
+                        _ => [128, 128, 128, 255],
+                    }
+                } else if y < 32 && x < 32 {
+                    // Noise, which only raw encoding can hold
+                    noise = noise.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                    let [r, g, b, _] = noise.to_le_bytes();
+                    [r, g, b, 255]
+                } else {
+                    [10, 20, 30, 255]
+                };
+                data.extend_from_slice(&pixel);
+            }
+        }
+        let frame = RawFrame::new(width as u32, height as u32, PixelFormat::RGBA, data);
+
+        let mut encoder = FrameEncoder::new(QualityPreset::Medium);
+        encoder.set_encoding(EncodingType::Hextile);
+        let encoded = encoder.encode(&frame).unwrap();
+
+        assert!(encoded.data.len() < frame.size());
+        let decoded = decode_hextile(&encoded.data, width, height, 4);
+        assert!(decoded == frame.data, "decoded framebuffer differs");
+    }
+
     #[test]
     #[cfg(feature = "remotedesktop")]
     fn test_frame_damage_rect_intersects_tile() {
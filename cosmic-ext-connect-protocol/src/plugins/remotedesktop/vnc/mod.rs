@@ -6,6 +6,7 @@
 //!
 //! - `protocol`: RFB protocol constants and message types
 //! - `auth`: VNC authentication (security type 2)
+//! - `clipboard`: Clipboard sync between the VNC client and the host
 //! - `encoding`: Frame encoding with multiple compression types (Raw, LZ4, H.264, Hextile)
 //! - `streaming`: Async streaming pipeline from screen capture to encoded frames
 //! - `server`: VNC server with TCP listener and protocol implementation
@@ -31,16 +32,19 @@
 //! ```
 
 pub mod auth;
+pub mod clipboard;
 pub mod encoding;
 pub mod protocol;
 pub mod server;
 pub mod streaming;
 
 pub use auth::{generate_password, VncAuth};
+pub use clipboard::ClipboardHandler;
 pub use encoding::{EncoderStats, FrameEncoder};
 pub use protocol::{
-    ClientMessage, FramebufferUpdate, FramebufferUpdateRequest, KeyEvent, PixelFormat,
-    PointerEvent, Rectangle, RfbEncoding, ServerInit, ServerMessage,
+    ClientMessage, CutText, ExtendedClipboard, FramebufferUpdate, FramebufferUpdateRequest,
+    KeyEvent, PixelFormat, PointerEvent, Rectangle, RfbEncoding, Screen, ServerInit, ServerMessage,
+    SetDesktopSize,
 };
 pub use server::{ServerState, VncServer};
 pub use streaming::{StreamConfig, StreamState, StreamStats, StreamingSession};
@@ -38,7 +38,8 @@
 //!
 //! - [RFB Protocol Specification](https://github.com/rfbproto/rfbproto/blob/master/rfbproto.rst)
 
-use crate::Result;
+use crate::{ProtocolError, Result};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
 use std::io::{Read, Write};
 
 /// RFB Protocol version 3.8
@@ -62,6 +63,42 @@ pub const SECURITY_RESULT_OK: u32 = 0;
 /// Security result: Failed
 pub const SECURITY_RESULT_FAILED: u32 = 1;
 
+/// Largest cut text accepted from a client (10 MiB)
+pub const MAX_CUT_TEXT_LEN: usize = 10 * 1024 * 1024;
+
+/// Extended clipboard format: UTF-8 text
+pub const CLIPBOARD_FORMAT_TEXT: u32 = 1 << 0;
+
+/// Extended clipboard action: capabilities
+pub const CLIPBOARD_ACTION_CAPS: u32 = 1 << 24;
+
+/// Extended clipboard action: request clipboard data
+pub const CLIPBOARD_ACTION_REQUEST: u32 = 1 << 25;
+
+/// Extended clipboard action: ask which formats are available
+pub const CLIPBOARD_ACTION_PEEK: u32 = 1 << 26;
+
+/// Extended clipboard action: announce available formats
+pub const CLIPBOARD_ACTION_NOTIFY: u32 = 1 << 27;
+
+/// Extended clipboard action: clipboard data
+pub const CLIPBOARD_ACTION_PROVIDE: u32 = 1 << 28;
+
+/// Extended clipboard format bits (the low 16 bits of the flags)
+const CLIPBOARD_FORMAT_MASK: u32 = 0xffff;
+
+/// ExtendedDesktopSize reason: the server changed the size
+pub const DESKTOP_SIZE_REASON_SERVER: u16 = 0;
+
+/// ExtendedDesktopSize reason: reply to this client's SetDesktopSize
+pub const DESKTOP_SIZE_REASON_CLIENT: u16 = 1;
+
+/// ExtendedDesktopSize status: no error
+pub const DESKTOP_SIZE_STATUS_OK: u16 = 0;
+
+/// ExtendedDesktopSize status: resize is administratively prohibited
+pub const DESKTOP_SIZE_STATUS_PROHIBITED: u16 = 1;
+
 /// Client to server message types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
@@ -83,6 +120,9 @@ pub enum ClientMessage {
 
     /// Client cut text
     ClientCutText = 6,
+
+    /// Set desktop size (ExtendedDesktopSize)
+    SetDesktopSize = 251,
 }
 
 impl ClientMessage {
@@ -95,6 +135,7 @@ impl ClientMessage {
             4 => Some(Self::KeyEvent),
             5 => Some(Self::PointerEvent),
             6 => Some(Self::ClientCutText),
+            251 => Some(Self::SetDesktopSize),
             _ => None,
         }
     }
@@ -141,6 +182,12 @@ pub enum RfbEncoding {
 
     /// DesktopSize pseudo-encoding
     DesktopSize = -223,
+
+    /// ExtendedDesktopSize pseudo-encoding
+    ExtendedDesktopSize = -308,
+
+    /// ExtendedClipboard pseudo-encoding (0xC0A1E5CE)
+    ExtendedClipboard = -1063131698,
 }
 
 impl RfbEncoding {
@@ -154,6 +201,8 @@ impl RfbEncoding {
             16 => Some(Self::ZRLE),
             -239 => Some(Self::Cursor),
             -223 => Some(Self::DesktopSize),
+            -308 => Some(Self::ExtendedDesktopSize),
+            -1063131698 => Some(Self::ExtendedClipboard),
             _ => None,
         }
     }
@@ -377,6 +426,89 @@ impl PointerEvent {
     }
 }
 
+/// Screen (monitor) within the framebuffer, as used by ExtendedDesktopSize
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Screen {
+    /// Screen identifier, stable across layout changes
+    pub id: u32,
+
+    /// X position
+    pub x: u16,
+
+    /// Y position
+    pub y: u16,
+
+    /// Width
+    pub width: u16,
+
+    /// Height
+    pub height: u16,
+
+    /// Flags (unused)
+    pub flags: u32,
+}
+
+impl Screen {
+    /// Serialize to bytes
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        bytes[0..4].copy_from_slice(&self.id.to_be_bytes());
+        bytes[4..6].copy_from_slice(&self.x.to_be_bytes());
+        bytes[6..8].copy_from_slice(&self.y.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.width.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.height.to_be_bytes());
+        bytes[12..16].copy_from_slice(&self.flags.to_be_bytes());
+        bytes
+    }
+
+    /// Parse from bytes
+    pub fn from_bytes(bytes: &[u8; 16]) -> Self {
+        Self {
+            id: u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            x: u16::from_be_bytes([bytes[4], bytes[5]]),
+            y: u16::from_be_bytes([bytes[6], bytes[7]]),
+            width: u16::from_be_bytes([bytes[8], bytes[9]]),
+            height: u16::from_be_bytes([bytes[10], bytes[11]]),
+            flags: u32::from_be_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]),
+        }
+    }
+}
+
+/// Set desktop size message (client asks the server to resize)
+#[derive(Debug, Clone)]
+pub struct SetDesktopSize {
+    /// Requested framebuffer width
+    pub width: u16,
+
+    /// Requested framebuffer height
+    pub height: u16,
+
+    /// Requested screen layout
+    pub screens: Vec<Screen>,
+}
+
+impl SetDesktopSize {
+    /// Parse from reader
+    pub fn from_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        // Padding (1 byte) + width, height (2 bytes each) + screen count (1 byte) + padding
+        let mut buf = [0u8; 7];
+        reader.read_exact(&mut buf)?;
+
+        let mut screens = Vec::with_capacity(buf[5] as usize);
+        for _ in 0..buf[5] {
+            let mut screen = [0u8; 16];
+            reader.read_exact(&mut screen)?;
+            screens.push(Screen::from_bytes(&screen));
+        }
+
+        Ok(Self {
+            width: u16::from_be_bytes([buf[1], buf[2]]),
+            height: u16::from_be_bytes([buf[3], buf[4]]),
+            screens,
+        })
+    }
+}
+
 /// Framebuffer update header
 #[derive(Debug, Clone)]
 pub struct FramebufferUpdate {
@@ -447,6 +579,44 @@ impl Rectangle {
         }
     }
 
+    /// Create a DesktopSize pseudo-rectangle announcing a new framebuffer size
+    pub fn desktop_size(width: u16, height: u16) -> Self {
+        Self::new(
+            0,
+            0,
+            width,
+            height,
+            RfbEncoding::DesktopSize as i32,
+            Vec::new(),
+        )
+    }
+
+    /// Create an ExtendedDesktopSize pseudo-rectangle with the screen layout
+    ///
+    /// The reason and status are carried in the x and y positions.
+    pub fn extended_desktop_size(
+        reason: u16,
+        status: u16,
+        width: u16,
+        height: u16,
+        screens: &[Screen],
+    ) -> Self {
+        // Screen count (1 byte) + padding (3 bytes) + screens
+        let mut data = vec![screens.len() as u8, 0, 0, 0];
+        for screen in screens {
+            data.extend_from_slice(&screen.to_bytes());
+        }
+
+        Self::new(
+            reason,
+            status,
+            width,
+            height,
+            RfbEncoding::ExtendedDesktopSize as i32,
+            data,
+        )
+    }
+
     /// Serialize to bytes
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::new();
@@ -467,6 +637,231 @@ impl Rectangle {
     }
 }
 
+/// Clipboard contents of a ClientCutText or ServerCutText message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CutText {
+    /// Plain cut text (ISO 8859-1 on the wire)
+    Text(String),
+
+    /// Extended clipboard message (negative length on the wire)
+    Extended(ExtendedClipboard),
+}
+
+impl CutText {
+    /// Parse the message body following the message type byte
+    pub fn from_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        // Padding (3 bytes) + length (4 bytes)
+        let mut header = [0u8; 7];
+        reader.read_exact(&mut header)?;
+
+        let length = i32::from_be_bytes([header[3], header[4], header[5], header[6]]);
+        let len = length.unsigned_abs() as usize;
+        if len > MAX_CUT_TEXT_LEN {
+            return Err(ProtocolError::InvalidPacket(format!(
+                "Cut text too long: {} bytes",
+                len
+            )));
+        }
+
+        let mut data = vec![0u8; len];
+        reader.read_exact(&mut data)?;
+
+        if length < 0 {
+            ExtendedClipboard::from_bytes(&data).map(Self::Extended)
+        } else {
+            Ok(Self::Text(data.iter().map(|&b| b as char).collect()))
+        }
+    }
+
+    /// Serialize as a ServerCutText message
+    pub fn to_server_message(&self) -> Vec<u8> {
+        self.to_bytes(ServerMessage::ServerCutText as u8)
+    }
+
+    /// Serialize as a ClientCutText message
+    pub fn to_client_message(&self) -> Vec<u8> {
+        self.to_bytes(ClientMessage::ClientCutText as u8)
+    }
+
+    fn to_bytes(&self, message_type: u8) -> Vec<u8> {
+        let (length, data) = match self {
+            Self::Text(text) => {
+                // Characters outside ISO 8859-1 can't be sent as plain cut text
+                let data: Vec<u8> = text
+                    .chars()
+                    .map(|c| u8::try_from(c).unwrap_or(b'?'))
+                    .collect();
+                (data.len() as i32, data)
+            }
+            Self::Extended(message) => {
+                let data = message.to_bytes();
+                (-(data.len() as i32), data)
+            }
+        };
+
+        let mut bytes = vec![message_type, 0, 0, 0];
+        bytes.extend_from_slice(&length.to_be_bytes());
+        bytes.extend_from_slice(&data);
+        bytes
+    }
+}
+
+/// Extended clipboard message
+///
+/// Only the text format is carried; other formats are skipped when parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtendedClipboard {
+    /// Supported formats and actions, with the largest size accepted for
+    /// each format in bit order
+    Caps { flags: u32, max_sizes: Vec<u32> },
+
+    /// Ask the peer to provide its clipboard in `formats`
+    Request { formats: u32 },
+
+    /// Ask the peer which formats its clipboard holds
+    Peek,
+
+    /// The clipboard now holds `formats`
+    Notify { formats: u32 },
+
+    /// Clipboard contents
+    Provide { text: Option<String> },
+}
+
+impl ExtendedClipboard {
+    /// Parse an extended clipboard payload
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        if data.len() < 4 {
+            return Err(ProtocolError::InvalidPacket(
+                "Extended clipboard message too short".to_string(),
+            ));
+        }
+
+        let flags = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+        let formats = flags & CLIPBOARD_FORMAT_MASK;
+        let payload = &data[4..];
+
+        if flags & CLIPBOARD_ACTION_CAPS != 0 {
+            let count = formats.count_ones() as usize;
+            if payload.len() < count * 4 {
+                return Err(ProtocolError::InvalidPacket(
+                    "Extended clipboard caps truncated".to_string(),
+                ));
+            }
+            let max_sizes = payload
+                .chunks_exact(4)
+                .take(count)
+                .map(|size| u32::from_be_bytes([size[0], size[1], size[2], size[3]]))
+                .collect();
+            Ok(Self::Caps { flags, max_sizes })
+        } else if flags & CLIPBOARD_ACTION_REQUEST != 0 {
+            Ok(Self::Request { formats })
+        } else if flags & CLIPBOARD_ACTION_PEEK != 0 {
+            Ok(Self::Peek)
+        } else if flags & CLIPBOARD_ACTION_NOTIFY != 0 {
+            Ok(Self::Notify { formats })
+        } else if flags & CLIPBOARD_ACTION_PROVIDE != 0 {
+            Ok(Self::Provide {
+                text: Self::parse_provide(formats, payload)?,
+            })
+        } else {
+            Err(ProtocolError::InvalidPacket(format!(
+                "Unknown extended clipboard action: {:#010x}",
+                flags
+            )))
+        }
+    }
+
+    /// Serialize to an extended clipboard payload
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (flags, payload) = match self {
+            Self::Caps { flags, max_sizes } => {
+                let payload = max_sizes
+                    .iter()
+                    .flat_map(|size| size.to_be_bytes())
+                    .collect::<Vec<u8>>();
+                (*flags | CLIPBOARD_ACTION_CAPS, payload)
+            }
+            Self::Request { formats } => (formats | CLIPBOARD_ACTION_REQUEST, Vec::new()),
+            Self::Peek => (CLIPBOARD_ACTION_PEEK, Vec::new()),
+            Self::Notify { formats } => (formats | CLIPBOARD_ACTION_NOTIFY, Vec::new()),
+            Self::Provide { text } => {
+                let formats = if text.is_some() {
+                    CLIPBOARD_FORMAT_TEXT
+                } else {
+                    0
+                };
+                let payload = Self::compress_provide(text.as_deref());
+                (formats | CLIPBOARD_ACTION_PROVIDE, payload)
+            }
+        };
+
+        let mut bytes = flags.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&payload);
+        bytes
+    }
+
+    /// Read the text out of a provide payload: a zlib stream holding a
+    /// length-prefixed entry per format
+    fn parse_provide(formats: u32, payload: &[u8]) -> Result<Option<String>> {
+        let mut decompressed = Vec::new();
+        ZlibDecoder::new(payload)
+            .take(MAX_CUT_TEXT_LEN as u64 + 1)
+            .read_to_end(&mut decompressed)
+            .map_err(|e| ProtocolError::InvalidPacket(format!("Invalid clipboard data: {}", e)))?;
+        if decompressed.len() > MAX_CUT_TEXT_LEN {
+            return Err(ProtocolError::InvalidPacket(
+                "Clipboard data too long".to_string(),
+            ));
+        }
+
+        let mut entries = decompressed.as_slice();
+        let mut text = None;
+        for bit in 0..16 {
+            if formats & (1 << bit) == 0 {
+                continue;
+            }
+
+            let truncated = || ProtocolError::InvalidPacket("Clipboard data truncated".to_string());
+            let size = entries.get(..4).ok_or_else(truncated)?;
+            let size = u32::from_be_bytes([size[0], size[1], size[2], size[3]]) as usize;
+            let entry = entries.get(4..4 + size).ok_or_else(truncated)?;
+            entries = &entries[4 + size..];
+
+            if 1 << bit == CLIPBOARD_FORMAT_TEXT {
+                // UTF-8 with CRLF line endings and a terminating NUL
+                let end = entry.iter().position(|&b| b == 0).unwrap_or(entry.len());
+                let decoded = std::str::from_utf8(&entry[..end]).map_err(|e| {
+                    ProtocolError::InvalidPacket(format!("Invalid clipboard text: {}", e))
+                })?;
+                text = Some(decoded.replace("\r\n", "\n"));
+            }
+        }
+
+        Ok(text)
+    }
+
+    /// Build a provide payload holding `text`
+    fn compress_provide(text: Option<&str>) -> Vec<u8> {
+        let mut entries = Vec::new();
+        if let Some(text) = text {
+            let mut entry = text
+                .replace("\r\n", "\n")
+                .replace('\n', "\r\n")
+                .into_bytes();
+            entry.push(0);
+            entries.extend_from_slice(&(entry.len() as u32).to_be_bytes());
+            entries.extend_from_slice(&entry);
+        }
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&entries)
+            .expect("writing to a Vec cannot fail");
+        encoder.finish().expect("writing to a Vec cannot fail")
+    }
+}
+
 /// Write helper for protocol messages
 pub trait ProtocolWrite {
     /// Write u8
@@ -570,5 +965,170 @@ mod tests {
         assert_eq!(RfbEncoding::from_i32(5), Some(RfbEncoding::Hextile));
         assert_eq!(RfbEncoding::from_i32(-223), Some(RfbEncoding::DesktopSize));
         assert_eq!(RfbEncoding::from_i32(999), None);
+        assert_eq!(
+            RfbEncoding::from_i32(0xC0A1_E5CE_u32 as i32),
+            Some(RfbEncoding::ExtendedClipboard)
+        );
+    }
+
+    #[test]
+    fn test_desktop_size_rectangle() {
+        let bytes = Rectangle::desktop_size(2560, 1440).to_bytes();
+
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(u16::from_be_bytes([bytes[4], bytes[5]]), 2560);
+        assert_eq!(u16::from_be_bytes([bytes[6], bytes[7]]), 1440);
+        assert_eq!(
+            i32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+            RfbEncoding::DesktopSize as i32
+        );
+    }
+
+    #[test]
+    fn test_extended_desktop_size_rectangle() {
+        let screens = [
+            Screen {
+                id: 0,
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+                flags: 0,
+            },
+            Screen {
+                id: 1,
+                x: 1920,
+                y: 0,
+                width: 1280,
+                height: 1024,
+                flags: 0,
+            },
+        ];
+        let rect = Rectangle::extended_desktop_size(
+            DESKTOP_SIZE_REASON_SERVER,
+            DESKTOP_SIZE_STATUS_OK,
+            3200,
+            1080,
+            &screens,
+        );
+
+        assert_eq!(rect.encoding, RfbEncoding::ExtendedDesktopSize as i32);
+        assert_eq!((rect.width, rect.height), (3200, 1080));
+        assert_eq!(rect.data.len(), 4 + 2 * 16);
+        assert_eq!(rect.data[0], 2);
+
+        let mut second = [0u8; 16];
+        second.copy_from_slice(&rect.data[20..36]);
+        assert_eq!(Screen::from_bytes(&second), screens[1]);
+    }
+
+    #[test]
+    fn test_set_desktop_size_parsing() {
+        let screen = Screen {
+            id: 7,
+            x: 0,
+            y: 0,
+            width: 1024,
+            height: 768,
+            flags: 0,
+        };
+        let mut bytes = vec![0u8];
+        bytes.extend_from_slice(&1024u16.to_be_bytes());
+        bytes.extend_from_slice(&768u16.to_be_bytes());
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&screen.to_bytes());
+
+        let request = SetDesktopSize::from_reader(&mut bytes.as_slice()).unwrap();
+        assert_eq!((request.width, request.height), (1024, 768));
+        assert_eq!(request.screens, vec![screen]);
+        assert_eq!(
+            ClientMessage::from_u8(251),
+            Some(ClientMessage::SetDesktopSize)
+        );
+    }
+
+    #[test]
+    fn test_cut_text_latin1_round_trip() {
+        let message = CutText::Text("café\nnaïve".to_string());
+        let bytes = message.to_server_message();
+
+        assert_eq!(bytes[0], ServerMessage::ServerCutText as u8);
+        assert_eq!(
+            i32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            10
+        );
+        assert_eq!(CutText::from_reader(&mut &bytes[1..]).unwrap(), message);
+    }
+
+    #[test]
+    fn test_cut_text_replaces_non_latin1() {
+        let bytes = CutText::Text("a→b".to_string()).to_client_message();
+        assert_eq!(&bytes[8..], b"a?b");
+    }
+
+    #[test]
+    fn test_extended_clipboard_unicode_round_trip() {
+        let text = "Grüße, 世界! 🦀\nsecond line\n";
+        let message = CutText::Extended(ExtendedClipboard::Provide {
+            text: Some(text.to_string()),
+        });
+        let bytes = message.to_client_message();
+
+        // Negative length marks the extended format
+        let length = i32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        assert_eq!(-length as usize, bytes.len() - 8);
+        let flags = u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+        assert_eq!(flags, CLIPBOARD_ACTION_PROVIDE | CLIPBOARD_FORMAT_TEXT);
+
+        // Text is sent with CRLF line endings and a terminating NUL
+        let mut entries = Vec::new();
+        ZlibDecoder::new(&bytes[12..])
+            .read_to_end(&mut entries)
+            .unwrap();
+        let expected = "Grüße, 世界! 🦀\r\nsecond line\r\n\0";
+        assert_eq!(&entries[4..], expected.as_bytes());
+        assert_eq!(
+            u32::from_be_bytes([entries[0], entries[1], entries[2], entries[3]]) as usize,
+            expected.len()
+        );
+
+        assert_eq!(CutText::from_reader(&mut &bytes[1..]).unwrap(), message);
+    }
+
+    #[test]
+    fn test_extended_clipboard_actions_round_trip() {
+        let messages = [
+            ExtendedClipboard::Caps {
+                flags: CLIPBOARD_FORMAT_TEXT | CLIPBOARD_ACTION_NOTIFY,
+                max_sizes: vec![1024],
+            },
+            ExtendedClipboard::Request {
+                formats: CLIPBOARD_FORMAT_TEXT,
+            },
+            ExtendedClipboard::Peek,
+            ExtendedClipboard::Notify {
+                formats: CLIPBOARD_FORMAT_TEXT,
+            },
+            ExtendedClipboard::Provide { text: None },
+        ];
+
+        for message in messages {
+            let parsed = ExtendedClipboard::from_bytes(&message.to_bytes()).unwrap();
+            let expected = match message {
+                ExtendedClipboard::Caps { flags, max_sizes } => ExtendedClipboard::Caps {
+                    flags: flags | CLIPBOARD_ACTION_CAPS,
+                    max_sizes,
+                },
+                other => other,
+            };
+            assert_eq!(parsed, expected);
+        }
+    }
+
+    #[test]
+    fn test_cut_text_too_long() {
+        let mut bytes = vec![0u8, 0, 0];
+        bytes.extend_from_slice(&(MAX_CUT_TEXT_LEN as i32 + 1).to_be_bytes());
+        assert!(CutText::from_reader(&mut bytes.as_slice()).is_err());
     }
 }
@@ -30,6 +30,7 @@
 
 use super::{
     auth::{generate_password, VncAuth},
+    clipboard::{ClipboardHandler, ClipboardSync},
     protocol::*,
     StreamConfig, StreamingSession,
 };
@@ -43,7 +44,7 @@ use crate::{
 use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 use tracing::{debug, error, info, warn};
 
 /// VNC server state
@@ -74,6 +75,12 @@ pub struct VncServer {
     /// Framebuffer dimensions
     width: u16,
     height: u16,
+
+    /// Receives text copied on the client
+    clipboard_handler: Option<ClipboardHandler>,
+
+    /// Host clipboard text to push to the client
+    host_clipboard: Option<watch::Receiver<String>>,
 }
 
 #[cfg(feature = "remotedesktop")]
@@ -93,9 +100,23 @@ impl VncServer {
             state: Arc::new(RwLock::new(ServerState::Idle)),
             width: 1920,
             height: 1080,
+            clipboard_handler: None,
+            host_clipboard: None,
         }
     }
 
+    /// Forward text copied on the client to `handler`
+    pub fn with_clipboard_handler(mut self, handler: ClipboardHandler) -> Self {
+        self.clipboard_handler = Some(handler);
+        self
+    }
+
+    /// Push host clipboard changes to the client
+    pub fn with_host_clipboard(mut self, host_clipboard: watch::Receiver<String>) -> Self {
+        self.host_clipboard = Some(host_clipboard);
+        self
+    }
+
     /// Create VNC server with auto-generated password
     pub fn with_generated_password(port: u16) -> (Self, String) {
         let password = generate_password();
@@ -121,14 +142,15 @@ impl VncServer {
             quality: QualityPreset::Medium,
             buffer_size: 3,
             allow_frame_skip: true,
+            ..StreamConfig::default()
         };
 
         let mut session = StreamingSession::new(config);
 
-        // Get framebuffer dimensions from capture
-        // For now, use defaults; in production, get from capture monitors
-        self.width = 1920;
-        self.height = 1080;
+        // Initial framebuffer dimensions; clients supporting DesktopSize are
+        // told when the captured size differs
+        self.width = session.config().width as u16;
+        self.height = session.config().height as u16;
 
         // Start streaming session
         session.start(capture).await?;
@@ -288,8 +310,12 @@ impl VncServer {
     ) -> Result<()> {
         info!("Entering protocol loop");
 
-        // Client encodings (supported by client)
-        let mut _client_encodings: Vec<RfbEncoding> = Vec::new();
+        let mut clipboard =
+            ClipboardSync::new(self.clipboard_handler.clone(), self.host_clipboard.clone());
+
+        // Framebuffer size the client knows, and how to tell it of changes
+        let mut framebuffer = (self.width, self.height);
+        let mut resize_encoding = None;
 
         // Set stream to non-blocking for frame updates
         stream.set_nonblocking(true).ok();
@@ -307,12 +333,22 @@ impl VncServer {
                                 self.handle_set_pixel_format(stream)?;
                             }
                             ClientMessage::SetEncodings => {
-                                _client_encodings = self.handle_set_encodings(stream)?;
+                                let client_encodings = self.handle_set_encodings(stream)?;
+                                session.set_encoding(Self::choose_encoding(&client_encodings));
+                                resize_encoding = Self::choose_resize_encoding(&client_encodings);
+                                let caps = clipboard.set_client_encodings(&client_encodings);
+                                self.send_cut_text(stream, &caps)?;
                             }
                             ClientMessage::FramebufferUpdateRequest => {
                                 let req = FramebufferUpdateRequest::from_reader(stream)?;
-                                self.handle_framebuffer_update_request(stream, session, req)
-                                    .await?;
+                                self.handle_framebuffer_update_request(
+                                    stream,
+                                    session,
+                                    req,
+                                    resize_encoding,
+                                    &mut framebuffer,
+                                )
+                                .await?;
                             }
                             ClientMessage::KeyEvent => {
                                 let event = KeyEvent::from_reader(stream)?;
@@ -323,7 +359,14 @@ impl VncServer {
                                 self.handle_pointer_event(event, input_handler).await?;
                             }
                             ClientMessage::ClientCutText => {
-                                self.handle_client_cut_text(stream)?;
+                                let message = CutText::from_reader(stream)?;
+                                debug!("Client cut text: {:?}", message);
+                                let replies = clipboard.handle_client_message(message);
+                                self.send_cut_text(stream, &replies)?;
+                            }
+                            ClientMessage::SetDesktopSize => {
+                                let request = SetDesktopSize::from_reader(stream)?;
+                                self.handle_set_desktop_size(stream, session, request)?;
                             }
                         }
                     } else {
@@ -331,7 +374,8 @@ impl VncServer {
                     }
                 }
                 Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                    // No message available, continue
+                    // No message available; push any host clipboard change
+                    self.send_cut_text(stream, &clipboard.poll_host())?;
                     tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
                 }
                 Err(e) => {
@@ -390,25 +434,104 @@ impl VncServer {
         Ok(encodings)
     }
 
+    /// Pick the frame encoding for the encodings a client supports
+    ///
+    /// Hextile when the client accepts it, otherwise Raw, which every client
+    /// must support.
+    fn choose_encoding(client_encodings: &[RfbEncoding]) -> EncodingType {
+        if client_encodings.contains(&RfbEncoding::Hextile) {
+            EncodingType::Hextile
+        } else {
+            EncodingType::Raw
+        }
+    }
+
+    /// Pick the pseudo-encoding for telling a client the framebuffer resized
+    fn choose_resize_encoding(client_encodings: &[RfbEncoding]) -> Option<RfbEncoding> {
+        [RfbEncoding::ExtendedDesktopSize, RfbEncoding::DesktopSize]
+            .into_iter()
+            .find(|encoding| client_encodings.contains(encoding))
+    }
+
     /// Handle FramebufferUpdateRequest message
+    ///
+    /// `framebuffer` is the size the client knows, updated when a resize is
+    /// sent along with the frame.
     async fn handle_framebuffer_update_request(
         &self,
         stream: &mut TcpStream,
         session: &mut StreamingSession,
         req: FramebufferUpdateRequest,
+        resize_encoding: Option<RfbEncoding>,
+        framebuffer: &mut (u16, u16),
     ) -> Result<()> {
         debug!("Handling FramebufferUpdateRequest: {:?}", req);
 
         // Get next frame from streaming session
-        if let Some(encoded_frame) = session.next_frame().await {
-            self.send_framebuffer_update(stream, &encoded_frame)?;
+        let Some(encoded_frame) = session.next_frame().await else {
+            return Ok(());
+        };
+
+        let resize = resize_encoding.and_then(|encoding| session.take_resize(encoding));
+        if let Some(rect) = &resize {
+            info!(
+                "Resizing client framebuffer to {}x{}",
+                rect.width, rect.height
+            );
+            *framebuffer = (rect.width, rect.height);
+        }
+
+        // A frame that doesn't fit the client's framebuffer would desync it
+        let frame_size = (encoded_frame.width as u16, encoded_frame.height as u16);
+        if frame_size != *framebuffer {
+            warn!(
+                "Dropping {}x{} frame that doesn't match the {}x{} client framebuffer",
+                frame_size.0, frame_size.1, framebuffer.0, framebuffer.1
+            );
+            if let Some(rect) = resize {
+                stream.write_all(&FramebufferUpdate::new(vec![rect]).to_bytes())?;
+            }
+            return Ok(());
+        }
+
+        self.send_framebuffer_update(stream, resize, &encoded_frame)
+    }
+
+    /// Handle SetDesktopSize message
+    ///
+    /// The framebuffer follows the captured monitors, so client resize
+    /// requests are refused with the current layout.
+    fn handle_set_desktop_size(
+        &self,
+        stream: &mut TcpStream,
+        session: &StreamingSession,
+        request: SetDesktopSize,
+    ) -> Result<()> {
+        debug!(
+            "Client requested desktop size {}x{} with {} screen(s)",
+            request.width,
+            request.height,
+            request.screens.len()
+        );
+
+        if let Some(rect) = session.layout_rectangle(
+            RfbEncoding::ExtendedDesktopSize,
+            DESKTOP_SIZE_REASON_CLIENT,
+            DESKTOP_SIZE_STATUS_PROHIBITED,
+        ) {
+            stream.write_all(&FramebufferUpdate::new(vec![rect]).to_bytes())?;
         }
 
         Ok(())
     }
 
-    /// Send framebuffer update to client
-    fn send_framebuffer_update(&self, stream: &mut TcpStream, frame: &EncodedFrame) -> Result<()> {
+    /// Send framebuffer update to client, preceded by a resize if given
+    fn send_framebuffer_update(
+        &self,
+        stream: &mut TcpStream,
+        resize: Option<Rectangle>,
+        frame: &EncodedFrame,
+    ) -> Result<()> {
         // Map our encoding type to RFB encoding
         let rfb_encoding = match frame.encoding {
             EncodingType::Raw => RfbEncoding::Raw as i32,
@@ -428,7 +551,7 @@ impl VncServer {
         );
 
         // Create framebuffer update
-        let update = FramebufferUpdate::new(vec![rect]);
+        let update = FramebufferUpdate::new(resize.into_iter().chain([rect]).collect());
 
         // Send update
         let bytes = update.to_bytes();
@@ -479,21 +602,11 @@ impl VncServer {
         Ok(())
     }
 
-    /// Handle ClientCutText message
-    fn handle_client_cut_text(&self, stream: &mut TcpStream) -> Result<()> {
-        debug!("Handling ClientCutText");
-
-        // Read padding (3 bytes) + length (4 bytes)
-        let mut header = [0u8; 7];
-        stream.read_exact(&mut header)?;
-
-        let text_len = u32::from_be_bytes([header[3], header[4], header[5], header[6]]);
-
-        // Read text
-        let mut text_buf = vec![0u8; text_len as usize];
-        stream.read_exact(&mut text_buf)?;
-
-        debug!("Client cut text: {:?}", String::from_utf8_lossy(&text_buf));
+    /// Send ServerCutText messages to the client
+    fn send_cut_text(&self, stream: &mut TcpStream, messages: &[CutText]) -> Result<()> {
+        for message in messages {
+            stream.write_all(&message.to_server_message())?;
+        }
         Ok(())
     }
 }
@@ -522,4 +635,36 @@ mod tests {
         let server = VncServer::new(5900, String::new());
         assert_eq!(server.state().await, ServerState::Idle);
     }
+
+    #[test]
+    fn test_choose_encoding() {
+        assert_eq!(
+            VncServer::choose_encoding(&[RfbEncoding::Raw, RfbEncoding::Hextile]),
+            EncodingType::Hextile
+        );
+        assert_eq!(
+            VncServer::choose_encoding(&[RfbEncoding::Raw]),
+            EncodingType::Raw
+        );
+        assert_eq!(VncServer::choose_encoding(&[]), EncodingType::Raw);
+    }
+
+    #[test]
+    fn test_choose_resize_encoding() {
+        assert_eq!(
+            VncServer::choose_resize_encoding(&[
+                RfbEncoding::DesktopSize,
+                RfbEncoding::ExtendedDesktopSize
+            ]),
+            Some(RfbEncoding::ExtendedDesktopSize)
+        );
+        assert_eq!(
+            VncServer::choose_resize_encoding(&[RfbEncoding::Raw, RfbEncoding::DesktopSize]),
+            Some(RfbEncoding::DesktopSize)
+        );
+        assert_eq!(
+            VncServer::choose_resize_encoding(&[RfbEncoding::Hextile]),
+            None
+        );
+    }
 }
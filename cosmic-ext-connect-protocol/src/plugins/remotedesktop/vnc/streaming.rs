@@ -14,14 +14,18 @@
 //! ```
 
 use crate::plugins::remotedesktop::capture::{
-    EncodedFrame, QualityPreset, RawFrame, WaylandCapture,
+    EncodedFrame, EncodingType, QualityPreset, RawFrame, WaylandCapture,
 };
 use crate::plugins::remotedesktop::vnc::encoding::FrameEncoder;
+use crate::plugins::remotedesktop::vnc::protocol::{
+    Rectangle, RfbEncoding, Screen, DESKTOP_SIZE_REASON_SERVER, DESKTOP_SIZE_STATUS_OK,
+};
 use crate::Result;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, RwLock};
-use tokio::time::interval;
+use tokio::time::interval_at;
 use tracing::{debug, error, info, warn};
 
 /// Streaming session configuration
@@ -38,6 +42,15 @@ pub struct StreamConfig {
 
     /// Enable frame skipping if encoder can't keep up
     pub allow_frame_skip: bool,
+
+    /// Framebuffer width
+    pub width: u32,
+
+    /// Framebuffer height
+    pub height: u32,
+
+    /// Monitors within the framebuffer (empty for one screen covering it)
+    pub screens: Vec<Screen>,
 }
 
 impl Default for StreamConfig {
@@ -47,6 +60,9 @@ impl Default for StreamConfig {
             quality: QualityPreset::Medium,
             buffer_size: 3, // Small buffer to reduce latency
             allow_frame_skip: true,
+            width: 1920,
+            height: 1080,
+            screens: Vec::new(),
         }
     }
 }
@@ -81,6 +97,111 @@ pub struct StreamStats {
 
     /// Average frame time
     pub avg_frame_time: Duration,
+
+    /// Capture rate set by congestion control
+    pub target_fps: u32,
+
+    /// Quality set by congestion control
+    pub quality: QualityPreset,
+}
+
+/// Lowest capture rate congestion control goes down to
+const MIN_FPS: u32 = 1;
+
+/// Uncongested samples needed before raising the capture rate by one FPS
+const RECOVERY_SAMPLES: u32 = 5;
+
+/// Adapts capture rate and quality to how fast the client takes frames
+///
+/// Fed one sample per frame the client takes: the depth of the encoded frame
+/// queue, and the time since the previous frame was taken (the update request
+/// round trip). A backed up queue, or a round trip longer than the frame
+/// interval, cuts the rate by a quarter; otherwise the rate creeps back up.
+/// Quality drops a step while the rate is at most half the configured one,
+/// and another at a quarter.
+#[derive(Debug, Clone)]
+pub struct CongestionController {
+    /// Configured capture rate
+    max_fps: u32,
+
+    /// Configured quality
+    base_quality: QualityPreset,
+
+    /// Current capture rate
+    target_fps: u32,
+
+    /// Smoothed update request round trip
+    rtt: Option<Duration>,
+
+    /// Uncongested samples since the rate last changed
+    healthy_samples: u32,
+}
+
+impl CongestionController {
+    /// Create a controller starting at the configured rate and quality
+    pub fn new(max_fps: u32, quality: QualityPreset) -> Self {
+        let max_fps = max_fps.max(MIN_FPS);
+        Self {
+            max_fps,
+            base_quality: quality,
+            target_fps: max_fps,
+            rtt: None,
+            healthy_samples: 0,
+        }
+    }
+
+    /// Record a sample taken as the client takes a frame
+    pub fn update(&mut self, queue_depth: usize, queue_capacity: usize, rtt: Option<Duration>) {
+        if let Some(sample) = rtt {
+            self.rtt = Some(match self.rtt {
+                Some(average) => (average * 7 + sample) / 8,
+                None => sample,
+            });
+        }
+
+        let frame_interval = Duration::from_secs(1) / self.target_fps;
+        let queue_backed_up = queue_depth * 2 > queue_capacity;
+        let client_slower = self.rtt.is_some_and(|rtt| rtt > frame_interval);
+
+        if queue_backed_up || client_slower {
+            self.healthy_samples = 0;
+            let reduced = (self.target_fps * 3 / 4).max(MIN_FPS);
+            if reduced != self.target_fps {
+                debug!(
+                    "Congestion (queue {}/{}, rtt {:?}): capture rate {} -> {} FPS",
+                    queue_depth, queue_capacity, self.rtt, self.target_fps, reduced
+                );
+                self.target_fps = reduced;
+            }
+        } else if self.target_fps < self.max_fps {
+            self.healthy_samples += 1;
+            if self.healthy_samples >= RECOVERY_SAMPLES {
+                self.healthy_samples = 0;
+                self.target_fps += 1;
+            }
+        }
+    }
+
+    /// Capture rate to use
+    pub fn target_fps(&self) -> u32 {
+        self.target_fps
+    }
+
+    /// Quality to encode with
+    pub fn quality(&self) -> QualityPreset {
+        let steps = if self.target_fps * 4 <= self.max_fps {
+            2
+        } else if self.target_fps * 2 <= self.max_fps {
+            1
+        } else {
+            0
+        };
+
+        (0..steps).fold(self.base_quality, |quality, _| match quality {
+            QualityPreset::High => QualityPreset::Medium,
+            _ => QualityPreset::Low,
+        })
+    }
 }
 
 /// Streaming session for async frame pipeline
@@ -95,6 +216,9 @@ pub struct StreamingSession {
     /// Statistics
     stats: Arc<RwLock<StreamStats>>,
 
+    /// Frame encoder, shared with the encoding task
+    encoder: Arc<std::sync::Mutex<FrameEncoder>>,
+
     /// Encoded frame output channel (receiver side)
     output_rx: Option<mpsc::Receiver<EncodedFrame>>,
 
@@ -103,6 +227,18 @@ pub struct StreamingSession {
 
     /// Capture handle
     capture_handle: Option<tokio::task::JoinHandle<()>>,
+
+    /// Framebuffer layout changed since the client was last told
+    resize_pending: bool,
+
+    /// Adapts capture rate and quality to the client
+    congestion: CongestionController,
+
+    /// Capture rate, shared with the capture task
+    target_fps: Arc<AtomicU32>,
+
+    /// When the client last took a frame
+    last_frame_taken: Option<Instant>,
 }
 
 #[cfg(feature = "remotedesktop")]
@@ -111,13 +247,25 @@ impl StreamingSession {
     pub fn new(config: StreamConfig) -> Self {
         info!("Creating streaming session with {:?}", config);
 
+        let encoder = FrameEncoder::new(config.quality);
+        let stats = StreamStats {
+            target_fps: config.target_fps,
+            quality: config.quality,
+            ..StreamStats::default()
+        };
+
         Self {
+            congestion: CongestionController::new(config.target_fps, config.quality),
+            target_fps: Arc::new(AtomicU32::new(config.target_fps)),
+            last_frame_taken: None,
             config,
             state: Arc::new(RwLock::new(StreamState::Idle)),
-            stats: Arc::new(RwLock::new(StreamStats::default())),
+            stats: Arc::new(RwLock::new(stats)),
+            encoder: Arc::new(std::sync::Mutex::new(encoder)),
             output_rx: None,
             encoder_handle: None,
             capture_handle: None,
+            resize_pending: false,
         }
     }
 
@@ -135,6 +283,10 @@ impl StreamingSession {
             self.config.target_fps
         );
 
+        self.congestion = CongestionController::new(self.config.target_fps, self.config.quality);
+        self.target_fps
+            .store(self.congestion.target_fps(), Ordering::Relaxed);
+
         // Create channels
         let (raw_tx, raw_rx) = mpsc::channel::<RawFrame>(self.config.buffer_size);
         let (encoded_tx, encoded_rx) = mpsc::channel::<EncodedFrame>(self.config.buffer_size);
@@ -145,7 +297,7 @@ impl StreamingSession {
         // Spawn capture task
         let capture_state = self.state.clone();
         let capture_stats = self.stats.clone();
-        let target_fps = self.config.target_fps;
+        let target_fps = self.target_fps.clone();
         let allow_skip = self.config.allow_frame_skip;
 
         self.capture_handle = Some(tokio::spawn(async move {
@@ -163,10 +315,10 @@ impl StreamingSession {
         // Spawn encoding task
         let encoder_state = self.state.clone();
         let encoder_stats = self.stats.clone();
-        let quality = self.config.quality;
+        let encoder = self.encoder.clone();
 
         self.encoder_handle = Some(tokio::spawn(async move {
-            Self::encoding_loop(raw_rx, encoded_tx, encoder_state, encoder_stats, quality).await;
+            Self::encoding_loop(raw_rx, encoded_tx, encoder_state, encoder_stats, encoder).await;
         }));
 
         *state = StreamState::Streaming;
@@ -181,17 +333,26 @@ impl StreamingSession {
         tx: mpsc::Sender<RawFrame>,
         state: Arc<RwLock<StreamState>>,
         stats: Arc<RwLock<StreamStats>>,
-        target_fps: u32,
+        target_fps: Arc<AtomicU32>,
         allow_skip: bool,
     ) {
-        let frame_duration = Duration::from_millis(1000 / target_fps as u64);
-        let mut ticker = interval(frame_duration);
+        let frame_interval = |fps: u32| Duration::from_secs(1) / fps.max(MIN_FPS);
+        let mut fps = target_fps.load(Ordering::Relaxed);
+        let mut ticker = interval_at(tokio::time::Instant::now(), frame_interval(fps));
         let mut last_fps_check = Instant::now();
         let mut frames_since_check = 0u64;
 
         loop {
             ticker.tick().await;
 
+            // Follow congestion control
+            let new_fps = target_fps.load(Ordering::Relaxed);
+            if new_fps != fps {
+                fps = new_fps;
+                let period = frame_interval(fps);
+                ticker = interval_at(tokio::time::Instant::now() + period, period);
+            }
+
             // Check state
             let current_state = *state.read().await;
             if current_state == StreamState::Stopped {
@@ -261,9 +422,8 @@ impl StreamingSession {
         tx: mpsc::Sender<EncodedFrame>,
         state: Arc<RwLock<StreamState>>,
         stats: Arc<RwLock<StreamStats>>,
-        quality: QualityPreset,
+        encoder: Arc<std::sync::Mutex<FrameEncoder>>,
     ) {
-        let encoder = Arc::new(std::sync::Mutex::new(FrameEncoder::new(quality)));
         let mut frame_times = Vec::with_capacity(30);
 
         while let Some(raw_frame) = rx.recv().await {
@@ -317,19 +477,147 @@ impl StreamingSession {
     }
 
     /// Get next encoded frame (non-blocking)
+    ///
+    /// A frame of a different size than the framebuffer (e.g. after a
+    /// monitor was hotplugged) resizes the framebuffer to match.
     pub async fn next_frame(&mut self) -> Option<EncodedFrame> {
-        if let Some(rx) = &mut self.output_rx {
-            rx.recv().await
-        } else {
-            None
+        let queue_depth = self.output_rx.as_ref()?.len();
+        let rtt = self.last_frame_taken.map(|taken| taken.elapsed());
+        self.congestion
+            .update(queue_depth, self.config.buffer_size, rtt);
+        self.apply_congestion().await;
+
+        let frame = self.output_rx.as_mut()?.recv().await?;
+        self.last_frame_taken = Some(Instant::now());
+
+        if (frame.width, frame.height) != (self.config.width, self.config.height) {
+            info!(
+                "Captured frame size changed from {}x{} to {}x{}",
+                self.config.width, self.config.height, frame.width, frame.height
+            );
+            self.config.width = frame.width;
+            self.config.height = frame.height;
+            self.config.screens.clear();
+            self.resize_pending = true;
+        }
+
+        Some(frame)
+    }
+
+    /// Pass congestion control decisions on to capture and encoding
+    async fn apply_congestion(&mut self) {
+        let target_fps = self.congestion.target_fps();
+        let quality = self.congestion.quality();
+        self.target_fps.store(target_fps, Ordering::Relaxed);
+
+        let mut stats = self.stats.write().await;
+        if stats.quality != quality {
+            info!(
+                "Congestion control: quality {:?} -> {:?}",
+                stats.quality, quality
+            );
+            self.encoder.lock().unwrap().adapt_quality(quality);
+        }
+        stats.target_fps = target_fps;
+        stats.quality = quality;
+    }
+
+    /// Get current configuration
+    pub fn config(&self) -> &StreamConfig {
+        &self.config
+    }
+
+    /// Update the configuration
+    ///
+    /// A new framebuffer size or screen layout takes effect immediately:
+    /// frames already encoded at the old size are dropped and the resize is
+    /// reported by [`Self::take_resize`]. Other settings apply from the next
+    /// [`Self::start`].
+    pub fn update_config(&mut self, config: StreamConfig) {
+        let resized = config.width != self.config.width
+            || config.height != self.config.height
+            || config.screens != self.config.screens;
+
+        if resized {
+            info!(
+                "Framebuffer layout changed to {}x{} with {} screen(s)",
+                config.width,
+                config.height,
+                config.screens.len().max(1)
+            );
+
+            if let Some(rx) = &mut self.output_rx {
+                while rx.try_recv().is_ok() {}
+            }
+            self.resize_pending = true;
+        }
+
+        self.config = config;
+    }
+
+    /// Take a pending framebuffer resize as a pseudo-rectangle for
+    /// `encoding` (DesktopSize or ExtendedDesktopSize)
+    pub fn take_resize(&mut self, encoding: RfbEncoding) -> Option<Rectangle> {
+        if !self.resize_pending {
+            return None;
+        }
+
+        let rect =
+            self.layout_rectangle(encoding, DESKTOP_SIZE_REASON_SERVER, DESKTOP_SIZE_STATUS_OK)?;
+        self.resize_pending = false;
+        Some(rect)
+    }
+
+    /// Describe the current framebuffer layout as a pseudo-rectangle for
+    /// `encoding`, with the ExtendedDesktopSize `reason` and `status`
+    pub fn layout_rectangle(
+        &self,
+        encoding: RfbEncoding,
+        reason: u16,
+        status: u16,
+    ) -> Option<Rectangle> {
+        let width = self.config.width as u16;
+        let height = self.config.height as u16;
+
+        match encoding {
+            RfbEncoding::DesktopSize => Some(Rectangle::desktop_size(width, height)),
+            RfbEncoding::ExtendedDesktopSize => Some(Rectangle::extended_desktop_size(
+                reason,
+                status,
+                width,
+                height,
+                &self.screens(),
+            )),
+            _ => None,
         }
     }
 
+    /// Screens within the framebuffer
+    fn screens(&self) -> Vec<Screen> {
+        if !self.config.screens.is_empty() {
+            return self.config.screens.clone();
+        }
+
+        vec![Screen {
+            id: 0,
+            x: 0,
+            y: 0,
+            width: self.config.width as u16,
+            height: self.config.height as u16,
+            flags: 0,
+        }]
+    }
+
     /// Get current statistics
     pub async fn stats(&self) -> StreamStats {
         self.stats.read().await.clone()
     }
 
+    /// Switch the encoding used for subsequent frames
+    pub fn set_encoding(&self, encoding: EncodingType) {
+        self.encoder.lock().unwrap().set_encoding(encoding);
+    }
+
     /// Get current state
     pub async fn state(&self) -> StreamState {
         *self.state.read().await
@@ -357,6 +645,8 @@ impl StreamingSession {
             ));
         }
         *state = StreamState::Streaming;
+        // The pause isn't a slow round trip
+        self.last_frame_taken = None;
         info!("Streaming session resumed");
         Ok(())
     }
@@ -423,4 +713,177 @@ mod tests {
 
         assert_eq!(session.state().await, StreamState::Idle);
     }
+
+    /// Run a capture → queue → client pipeline for `duration`, with the client
+    /// taking a frame every `client_interval`
+    ///
+    /// The queue is unbounded, so only congestion control can keep it short.
+    /// Returns the number of frames captured in the final 10 seconds and the
+    /// largest queue seen in them.
+    fn simulate_client(
+        controller: &mut CongestionController,
+        client_interval: Duration,
+        duration: Duration,
+    ) -> (u32, usize) {
+        let step = Duration::from_millis(1);
+        let measure_from = duration - Duration::from_secs(10);
+        let mut now = Duration::ZERO;
+        let mut next_capture = Duration::ZERO;
+        let mut next_take = Duration::ZERO;
+        let mut last_take = None;
+        let mut queue = 0usize;
+        let (mut captured, mut max_queue) = (0, 0);
+
+        while now < duration {
+            if now >= next_capture {
+                queue += 1;
+                next_capture = now + Duration::from_secs(1) / controller.target_fps();
+                if now >= measure_from {
+                    captured += 1;
+                }
+            }
+
+            if now >= next_take {
+                let rtt = last_take.map(|taken| now - taken);
+                controller.update(queue, 3, rtt);
+                queue = queue.saturating_sub(1);
+                last_take = Some(now);
+                next_take = now + client_interval;
+            }
+
+            if now >= measure_from {
+                max_queue = max_queue.max(queue);
+            }
+            now += step;
+        }
+
+        (captured, max_queue)
+    }
+
+    #[test]
+    fn test_slow_client_lowers_capture_rate() {
+        let mut controller = CongestionController::new(30, QualityPreset::High);
+
+        // Client only manages 5 FPS
+        let (captured, max_queue) = simulate_client(
+            &mut controller,
+            Duration::from_millis(200),
+            Duration::from_secs(60),
+        );
+
+        assert!(
+            captured <= 70,
+            "captured {} frames in 10s for a 5 FPS client",
+            captured
+        );
+        assert!(max_queue <= 3, "queue grew to {}", max_queue);
+        assert!(controller.target_fps() < 10);
+        assert_eq!(controller.quality(), QualityPreset::Low);
+    }
+
+    #[test]
+    fn test_fast_client_keeps_full_rate() {
+        let mut controller = CongestionController::new(30, QualityPreset::High);
+
+        let (captured, max_queue) = simulate_client(
+            &mut controller,
+            Duration::from_millis(10),
+            Duration::from_secs(20),
+        );
+
+        assert!(captured >= 280, "captured {} frames in 10s", captured);
+        assert!(max_queue <= 1);
+        assert_eq!(controller.target_fps(), 30);
+        assert_eq!(controller.quality(), QualityPreset::High);
+    }
+
+    #[test]
+    fn test_rate_recovers_after_congestion() {
+        let mut controller = CongestionController::new(30, QualityPreset::Medium);
+        for _ in 0..4 {
+            controller.update(3, 3, None);
+        }
+        assert_eq!(controller.target_fps(), 9);
+        assert_eq!(controller.quality(), QualityPreset::Low);
+
+        for _ in 0..(25 * RECOVERY_SAMPLES) {
+            controller.update(0, 3, Some(Duration::from_millis(5)));
+        }
+        assert_eq!(controller.target_fps(), 30);
+        assert_eq!(controller.quality(), QualityPreset::Medium);
+    }
+
+    #[test]
+    #[cfg(feature = "remotedesktop")]
+    fn test_config_resize_emits_rectangle() {
+        let mut session = StreamingSession::new(StreamConfig::default());
+        assert!(session.take_resize(RfbEncoding::DesktopSize).is_none());
+
+        session.update_config(StreamConfig {
+            width: 2560,
+            height: 1440,
+            ..StreamConfig::default()
+        });
+
+        let rect = session.take_resize(RfbEncoding::DesktopSize).unwrap();
+        assert_eq!(rect.encoding, RfbEncoding::DesktopSize as i32);
+        assert_eq!((rect.width, rect.height), (2560, 1440));
+
+        // Reported once
+        assert!(session.take_resize(RfbEncoding::DesktopSize).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "remotedesktop")]
+    fn test_monitor_hotplug_emits_screen_layout() {
+        let mut session = StreamingSession::new(StreamConfig::default());
+        let laptop = Screen {
+            id: 0,
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            flags: 0,
+        };
+        let external = Screen {
+            id: 1,
+            x: 1920,
+            y: 0,
+            width: 2560,
+            height: 1440,
+            flags: 0,
+        };
+
+        session.update_config(StreamConfig {
+            width: 4480,
+            height: 1440,
+            screens: vec![laptop, external],
+            ..StreamConfig::default()
+        });
+
+        let rect = session
+            .take_resize(RfbEncoding::ExtendedDesktopSize)
+            .unwrap();
+        assert_eq!(rect.encoding, RfbEncoding::ExtendedDesktopSize as i32);
+        assert_eq!(
+            (rect.x, rect.y),
+            (DESKTOP_SIZE_REASON_SERVER, DESKTOP_SIZE_STATUS_OK)
+        );
+        assert_eq!((rect.width, rect.height), (4480, 1440));
+        assert_eq!(rect.data[0], 2);
+    }
+
+    #[test]
+    #[cfg(feature = "remotedesktop")]
+    fn test_unchanged_config_no_resize() {
+        let mut session = StreamingSession::new(StreamConfig::default());
+        session.update_config(StreamConfig {
+            target_fps: 60,
+            ..StreamConfig::default()
+        });
+
+        assert!(session
+            .take_resize(RfbEncoding::ExtendedDesktopSize)
+            .is_none());
+    }
 }
@@ -14,6 +14,28 @@
 //! - Incoming: `cconnect.mousepad.request` - Receives pointer and keyboard events
 //! - Outgoing: `cconnect.mousepad.keyboardstate` - Sends keyboard support status
 //!
+//! ## Input Injection
+//!
+//! Events are injected through a `uinput` virtual device, so they work in any
+//! Wayland or X11 session with access to `/dev/uinput`. Key events use Linux
+//! key codes for a US layout: `key` characters that need Shift on that layout
+//! are typed with Shift held, and characters without a key are skipped.
+//! The `alt`, `ctrl`, `shift` and `super` fields are held down around the
+//! `key` or `specialKey` they arrive with, so `ctrl` + `key: "c"` copies.
+//!
+//! Pointer deltas are applied as sent, keeping fractional movement between
+//! packets. Pointer acceleration on top of the phone's own is optional, see
+//! [`RemoteInputConfig`].
+//!
+//! ## Permissions
+//!
+//! Input is only injected if the [`PermissionPolicy`] allows remote input for
+//! the device; without a configured policy all input is denied. A decision is
+//! reused for a few seconds so the policy is not consulted for every pointer
+//! packet. Denied input is dropped without telling the device.
+//!
+//! [`PermissionPolicy`]: super::permission::PermissionPolicy
+//!
 //! ## References
 //!
 //! - [CConnect MousePad Plugin](https://github.com/KDE/cconnect-kde/tree/master/plugins/mousepad)
@@ -25,8 +47,10 @@ use mouse_keyboard_input::VirtualDevice;
 use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 
+use super::permission::{self, DenyAllPolicy, PrivilegedAction, SharedPermissionPolicy};
 use super::{Plugin, PluginFactory};
 
 /// Packet type for remote input requests
@@ -39,34 +63,173 @@ pub const PACKET_TYPE_MOUSEPAD_ECHO: &str = "cconnect.mousepad.echo";
 pub const PACKET_TYPE_MOUSEPAD_KEYBOARDSTATE: &str = "cconnect.mousepad.keyboardstate";
 
 /// Special key codes for non-printable characters
+///
+/// Values are the `specialKey` codes sent by KDE Connect clients; 17-20 are
+/// unused.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[allow(dead_code)]
 pub enum SpecialKey {
     Backspace = 1,
     Tab = 2,
+    Linefeed = 3,
+    Left = 4,
+    Up = 5,
+    Right = 6,
+    Down = 7,
+    PageUp = 8,
+    PageDown = 9,
+    Home = 10,
+    End = 11,
     Enter = 12,
-    Escape = 27,
-    Left = 21,
-    Up = 22,
-    Right = 23,
-    Down = 24,
-    PageUp = 25,
-    PageDown = 26,
-    Home = 28,
-    End = 29,
-    Delete = 30,
-    F1 = 31,
-    F2 = 32,
-    F3 = 33,
-    F4 = 34,
-    F5 = 35,
-    F6 = 36,
-    F7 = 37,
-    F8 = 38,
-    F9 = 39,
-    F10 = 40,
-    F11 = 41,
-    F12 = 42,
+    Delete = 13,
+    Escape = 14,
+    SysRq = 15,
+    ScrollLock = 16,
+    F1 = 21,
+    F2 = 22,
+    F3 = 23,
+    F4 = 24,
+    F5 = 25,
+    F6 = 26,
+    F7 = 27,
+    F8 = 28,
+    F9 = 29,
+    F10 = 30,
+    F11 = 31,
+    F12 = 32,
+}
+
+impl SpecialKey {
+    /// Parse a `specialKey` code
+    pub fn from_code(code: i32) -> Option<Self> {
+        Some(match code {
+            1 => Self::Backspace,
+            2 => Self::Tab,
+            3 => Self::Linefeed,
+            4 => Self::Left,
+            5 => Self::Up,
+            6 => Self::Right,
+            7 => Self::Down,
+            8 => Self::PageUp,
+            9 => Self::PageDown,
+            10 => Self::Home,
+            11 => Self::End,
+            12 => Self::Enter,
+            13 => Self::Delete,
+            14 => Self::Escape,
+            15 => Self::SysRq,
+            16 => Self::ScrollLock,
+            21 => Self::F1,
+            22 => Self::F2,
+            23 => Self::F3,
+            24 => Self::F4,
+            25 => Self::F5,
+            26 => Self::F6,
+            27 => Self::F7,
+            28 => Self::F8,
+            29 => Self::F9,
+            30 => Self::F10,
+            31 => Self::F11,
+            32 => Self::F12,
+            _ => return None,
+        })
+    }
+
+    /// Linux key code for this key
+    pub fn keycode(self) -> u16 {
+        use mouse_keyboard_input::*;
+        match self {
+            Self::Backspace => KEY_BACKSPACE,
+            Self::Tab => KEY_TAB,
+            Self::Linefeed => 101, // KEY_LINEFEED
+            Self::Left => KEY_LEFT,
+            Self::Up => KEY_UP,
+            Self::Right => KEY_RIGHT,
+            Self::Down => KEY_DOWN,
+            Self::PageUp => KEY_PAGEUP,
+            Self::PageDown => KEY_PAGEDOWN,
+            Self::Home => KEY_HOME,
+            Self::End => KEY_END,
+            Self::Enter => KEY_ENTER,
+            Self::Delete => KEY_DELETE,
+            Self::Escape => KEY_ESC,
+            Self::SysRq => 99,      // KEY_SYSRQ
+            Self::ScrollLock => 70, // KEY_SCROLLLOCK
+            Self::F1 => KEY_F1,
+            Self::F2 => KEY_F2,
+            Self::F3 => KEY_F3,
+            Self::F4 => KEY_F4,
+            Self::F5 => KEY_F5,
+            Self::F6 => KEY_F6,
+            Self::F7 => KEY_F7,
+            Self::F8 => KEY_F8,
+            Self::F9 => KEY_F9,
+            Self::F10 => KEY_F10,
+            Self::F11 => KEY_F11,
+            Self::F12 => KEY_F12,
+        }
+    }
+}
+
+/// Remote Input plugin configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RemoteInputConfig {
+    /// Speed up fast pointer movements
+    ///
+    /// Off by default since most clients already accelerate the deltas they
+    /// send.
+    pub pointer_acceleration: bool,
+}
+
+/// Pointer speed (pixels per packet) above which acceleration kicks in
+const ACCELERATION_THRESHOLD: f64 = 4.0;
+
+/// Largest pointer speed multiplier applied by acceleration
+const ACCELERATION_MAX_GAIN: f64 = 3.0;
+
+/// How long a permission decision is reused for further input packets
+const PERMISSION_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Converts pointer deltas from packets into whole-pixel moves
+///
+/// Keeps the fractional remainder so slow finger movements, which arrive as
+/// many sub-pixel deltas, still move the pointer.
+#[derive(Debug, Default)]
+struct PointerMotion {
+    acceleration: bool,
+    remainder_x: f64,
+    remainder_y: f64,
+}
+
+impl PointerMotion {
+    fn new(acceleration: bool) -> Self {
+        Self {
+            acceleration,
+            ..Default::default()
+        }
+    }
+
+    /// Turn a packet delta into the pixels to move
+    fn apply(&mut self, dx: f64, dy: f64) -> (i32, i32) {
+        let gain = if self.acceleration {
+            (dx.hypot(dy) / ACCELERATION_THRESHOLD).clamp(1.0, ACCELERATION_MAX_GAIN)
+        } else {
+            1.0
+        };
+
+        let x = self.remainder_x + dx * gain;
+        let y = self.remainder_y + dy * gain;
+        let (move_x, move_y) = (x.trunc(), y.trunc());
+        self.remainder_x = x - move_x;
+        self.remainder_y = y - move_y;
+        (move_x as i32, move_y as i32)
+    }
+}
+
+/// A key press or release
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyAction {
+    Press(u16),
+    Release(u16),
 }
 
 /// Remote input request
@@ -141,15 +304,61 @@ pub struct RemoteInputRequest {
 pub struct RemoteInputPlugin {
     device_id: Option<String>,
     virtual_device: Arc<Mutex<Option<VirtualDevice>>>,
+    motion: Mutex<PointerMotion>,
+    policy: SharedPermissionPolicy,
+    /// Last permission decision and when it was made
+    permission_cache: Mutex<Option<(Instant, bool)>>,
 }
 
 impl RemoteInputPlugin {
     /// Create a new Remote Input plugin
     pub fn new() -> Self {
+        Self::with_config(RemoteInputConfig::default())
+    }
+
+    /// Create a plugin with explicit configuration
+    pub fn with_config(config: RemoteInputConfig) -> Self {
         Self {
             device_id: None,
             virtual_device: Arc::new(Mutex::new(None)),
+            motion: Mutex::new(PointerMotion::new(config.pointer_acceleration)),
+            policy: Arc::new(DenyAllPolicy),
+            permission_cache: Mutex::new(None),
+        }
+    }
+
+    /// Set the policy consulted before injecting input
+    pub fn with_policy(mut self, policy: SharedPermissionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Check that the device may inject input
+    ///
+    /// Reuses the last decision for [`PERMISSION_CACHE_TTL`].
+    async fn authorize_input(&self, device_id: &str) -> Result<()> {
+        if let Some((checked_at, allowed)) = *self.permission_cache.lock().unwrap() {
+            if checked_at.elapsed() < PERMISSION_CACHE_TTL {
+                return if allowed {
+                    Ok(())
+                } else {
+                    Err(ProtocolError::PermissionDenied(format!(
+                        "remote input for {}",
+                        device_id
+                    )))
+                };
+            }
         }
+
+        let result = permission::authorize(
+            self.policy.as_ref(),
+            device_id,
+            &PrivilegedAction::RemoteInput,
+            serde_json::json!({}),
+        )
+        .await;
+        *self.permission_cache.lock().unwrap() = Some((Instant::now(), result.is_ok()));
+        result
     }
 
     /// Handle a remote input request packet
@@ -180,21 +389,25 @@ impl RemoteInputPlugin {
 
         // Handle mouse movement and scrolling
         if request.dx.is_some() || request.dy.is_some() {
-            let dx = request.dx.unwrap_or(0.0) as i32;
-            let dy = request.dy.unwrap_or(0.0) as i32;
+            let raw_dx = request.dx.unwrap_or(0.0);
+            let raw_dy = request.dy.unwrap_or(0.0);
             let is_scroll = request.scroll.unwrap_or(false);
 
             let mut device_guard = device.lock().unwrap();
             if let Some(dev) = device_guard.as_mut() {
                 if is_scroll {
+                    let (dx, dy) = (raw_dx as i32, raw_dy as i32);
                     debug!("Remote input: Scroll dx={}, dy={}", dx, dy);
                     if let Err(e) = dev.smooth_scroll(dx, dy) {
                         warn!("Failed to scroll: {}", e);
                     }
                 } else {
+                    let (dx, dy) = self.motion.lock().unwrap().apply(raw_dx, raw_dy);
                     debug!("Remote input: Move pointer dx={}, dy={}", dx, dy);
-                    if let Err(e) = dev.smooth_move_mouse(dx, dy) {
-                        warn!("Failed to move mouse: {}", e);
+                    if dx != 0 || dy != 0 {
+                        if let Err(e) = dev.smooth_move_mouse(dx, dy) {
+                            warn!("Failed to move mouse: {}", e);
+                        }
                     }
                 }
             }
@@ -244,122 +457,172 @@ impl RemoteInputPlugin {
         }
 
         // Handle keyboard input
-        if let Some(key) = &request.key {
-            debug!("Remote input: Key '{}'", key);
+        let actions = Self::key_actions(&request);
+        if !actions.is_empty() {
+            debug!(
+                "Remote input: Key {:?} special {:?}",
+                request.key, request.special_key
+            );
             let mut device_guard = device.lock().unwrap();
             if let Some(dev) = device_guard.as_mut() {
-                // Convert string to key codes and send
-                for ch in key.chars() {
-                    if let Some(key_code) = Self::char_to_keycode(ch) {
-                        if let Err(e) = dev.click(key_code) {
-                            warn!("Failed to send key '{}': {}", ch, e);
-                        }
+                for action in actions {
+                    let result = match action {
+                        KeyAction::Press(code) => dev.press(code),
+                        KeyAction::Release(code) => dev.release(code),
+                    };
+                    if let Err(e) = result {
+                        warn!("Failed to send key event {:?}: {}", action, e);
                     }
                 }
             }
         }
-        if let Some(special_key) = request.special_key {
-            debug!("Remote input: Special key {}", special_key);
-            let mut device_guard = device.lock().unwrap();
-            if let Some(dev) = device_guard.as_mut() {
-                if let Some(key_code) = Self::special_key_to_keycode(special_key) {
-                    if let Err(e) = dev.click(key_code) {
-                        warn!("Failed to send special key {}: {}", special_key, e);
-                    }
+
+        Ok(())
+    }
+
+    /// Modifier key codes held for a request
+    fn modifier_keycodes(request: &RemoteInputRequest) -> Vec<u16> {
+        use mouse_keyboard_input::{KEY_LEFTALT, KEY_LEFTCTRL, KEY_LEFTMETA, KEY_LEFTSHIFT};
+        [
+            (request.ctrl, KEY_LEFTCTRL),
+            (request.alt, KEY_LEFTALT),
+            (request.shift, KEY_LEFTSHIFT),
+            (request.super_key, KEY_LEFTMETA),
+        ]
+        .into_iter()
+        .filter(|(held, _)| held.unwrap_or(false))
+        .map(|(_, code)| code)
+        .collect()
+    }
+
+    /// Key presses and releases for the `key` and `specialKey` of a request
+    ///
+    /// Each key is pressed with the request's modifiers held, plus Shift
+    /// for characters that need it.
+    fn key_actions(request: &RemoteInputRequest) -> Vec<KeyAction> {
+        use mouse_keyboard_input::KEY_LEFTSHIFT;
+
+        let modifiers = Self::modifier_keycodes(request);
+        let mut keys: Vec<(u16, bool)> = Vec::new();
+
+        if let Some(key) = &request.key {
+            for ch in key.chars() {
+                match Self::char_to_keycode(ch) {
+                    Some(key) => keys.push(key),
+                    None => warn!("No key mapping for character {:?}", ch),
                 }
             }
         }
+        if let Some(code) = request.special_key {
+            match SpecialKey::from_code(code) {
+                Some(special) => keys.push((special.keycode(), false)),
+                None => warn!("Unknown special key code {}", code),
+            }
+        }
 
-        Ok(())
+        let mut actions = Vec::new();
+        for (code, needs_shift) in keys {
+            let mut held = modifiers.clone();
+            if needs_shift && !held.contains(&KEY_LEFTSHIFT) {
+                held.push(KEY_LEFTSHIFT);
+            }
+            actions.extend(held.iter().map(|&m| KeyAction::Press(m)));
+            actions.push(KeyAction::Press(code));
+            actions.push(KeyAction::Release(code));
+            actions.extend(held.iter().rev().map(|&m| KeyAction::Release(m)));
+        }
+        actions
     }
 
-    /// Convert character to Linux key code
-    fn char_to_keycode(ch: char) -> Option<u16> {
+    /// Convert character to a Linux key code on a US layout
+    ///
+    /// Returns the key code and whether Shift must be held to type it.
+    fn char_to_keycode(ch: char) -> Option<(u16, bool)> {
         use mouse_keyboard_input::*;
-        match ch {
-            'a' | 'A' => Some(KEY_A),
-            'b' | 'B' => Some(KEY_B),
-            'c' | 'C' => Some(KEY_C),
-            'd' | 'D' => Some(KEY_D),
-            'e' | 'E' => Some(KEY_E),
-            'f' | 'F' => Some(KEY_F),
-            'g' | 'G' => Some(KEY_G),
-            'h' | 'H' => Some(KEY_H),
-            'i' | 'I' => Some(KEY_I),
-            'j' | 'J' => Some(KEY_J),
-            'k' | 'K' => Some(KEY_K),
-            'l' | 'L' => Some(KEY_L),
-            'm' | 'M' => Some(KEY_M),
-            'n' | 'N' => Some(KEY_N),
-            'o' | 'O' => Some(KEY_O),
-            'p' | 'P' => Some(KEY_P),
-            'q' | 'Q' => Some(KEY_Q),
-            'r' | 'R' => Some(KEY_R),
-            's' | 'S' => Some(KEY_S),
-            't' | 'T' => Some(KEY_T),
-            'u' | 'U' => Some(KEY_U),
-            'v' | 'V' => Some(KEY_V),
-            'w' | 'W' => Some(KEY_W),
-            'x' | 'X' => Some(KEY_X),
-            'y' | 'Y' => Some(KEY_Y),
-            'z' | 'Z' => Some(KEY_Z),
-            '0' => Some(11), // KEY_0 (between KEY_9=10 and KEY_MINUS=12)
-            '1' => Some(KEY_1),
-            '2' => Some(KEY_2),
-            '3' => Some(KEY_3),
-            '4' => Some(KEY_4),
-            '5' => Some(KEY_5),
-            '6' => Some(KEY_6),
-            '7' => Some(KEY_7),
-            '8' => Some(KEY_8),
-            '9' => Some(KEY_9),
-            ' ' => Some(KEY_SPACE),
-            '\n' => Some(KEY_ENTER),
-            '\t' => Some(KEY_TAB),
-            '.' => Some(KEY_DOT),
-            ',' => Some(KEY_COMMA),
-            '/' => Some(KEY_SLASH),
-            '-' => Some(KEY_MINUS),
-            '=' => Some(KEY_EQUAL),
-            '[' => Some(KEY_LEFTBRACE),
-            ']' => Some(KEY_RIGHTBRACE),
-            ';' => Some(KEY_SEMICOLON),
-            '\'' => Some(KEY_APOSTROPHE),
-            '`' => Some(KEY_GRAVE),
-            '\\' => Some(KEY_BACKSLASH),
-            _ => None,
-        }
+        let shifted = ch.is_ascii_uppercase();
+        let code = match ch.to_ascii_lowercase() {
+            'a' => KEY_A,
+            'b' => KEY_B,
+            'c' => KEY_C,
+            'd' => KEY_D,
+            'e' => KEY_E,
+            'f' => KEY_F,
+            'g' => KEY_G,
+            'h' => KEY_H,
+            'i' => KEY_I,
+            'j' => KEY_J,
+            'k' => KEY_K,
+            'l' => KEY_L,
+            'm' => KEY_M,
+            'n' => KEY_N,
+            'o' => KEY_O,
+            'p' => KEY_P,
+            'q' => KEY_Q,
+            'r' => KEY_R,
+            's' => KEY_S,
+            't' => KEY_T,
+            'u' => KEY_U,
+            'v' => KEY_V,
+            'w' => KEY_W,
+            'x' => KEY_X,
+            'y' => KEY_Y,
+            'z' => KEY_Z,
+            '0' => 11, // KEY_0 (between KEY_9=10 and KEY_MINUS=12)
+            '1' => KEY_1,
+            '2' => KEY_2,
+            '3' => KEY_3,
+            '4' => KEY_4,
+            '5' => KEY_5,
+            '6' => KEY_6,
+            '7' => KEY_7,
+            '8' => KEY_8,
+            '9' => KEY_9,
+            ' ' => KEY_SPACE,
+            '\n' => KEY_ENTER,
+            '\t' => KEY_TAB,
+            '.' => KEY_DOT,
+            ',' => KEY_COMMA,
+            '/' => KEY_SLASH,
+            '-' => KEY_MINUS,
+            '=' => KEY_EQUAL,
+            '[' => KEY_LEFTBRACE,
+            ']' => KEY_RIGHTBRACE,
+            ';' => KEY_SEMICOLON,
+            '\'' => KEY_APOSTROPHE,
+            '`' => KEY_GRAVE,
+            '\\' => KEY_BACKSLASH,
+            _ => {
+                return Self::shifted_char_to_keycode(ch).map(|code| (code, true));
+            }
+        };
+        Some((code, shifted))
     }
 
-    /// Convert special key code to Linux key code
-    fn special_key_to_keycode(special: i32) -> Option<u16> {
+    /// Convert a character typed with Shift on a US layout to its key code
+    fn shifted_char_to_keycode(ch: char) -> Option<u16> {
         use mouse_keyboard_input::*;
-        match special {
-            1 => Some(KEY_BACKSPACE), // Backspace
-            2 => Some(KEY_TAB),       // Tab
-            12 => Some(KEY_ENTER),    // Enter
-            27 => Some(KEY_ESC),      // Escape
-            21 => Some(KEY_LEFT),     // Left
-            22 => Some(KEY_UP),       // Up
-            23 => Some(KEY_RIGHT),    // Right
-            24 => Some(KEY_DOWN),     // Down
-            25 => Some(KEY_PAGEUP),   // PageUp
-            26 => Some(KEY_PAGEDOWN), // PageDown
-            28 => Some(KEY_HOME),     // Home
-            29 => Some(KEY_END),      // End
-            30 => Some(KEY_DELETE),   // Delete
-            31 => Some(KEY_F1),       // F1
-            32 => Some(KEY_F2),       // F2
-            33 => Some(KEY_F3),       // F3
-            34 => Some(KEY_F4),       // F4
-            35 => Some(KEY_F5),       // F5
-            36 => Some(KEY_F6),       // F6
-            37 => Some(KEY_F7),       // F7
-            38 => Some(KEY_F8),       // F8
-            39 => Some(KEY_F9),       // F9
-            40 => Some(KEY_F10),      // F10
-            41 => Some(KEY_F11),      // F11
-            42 => Some(KEY_F12),      // F12
+        match ch {
+            '!' => Some(KEY_1),
+            '@' => Some(KEY_2),
+            '#' => Some(KEY_3),
+            '$' => Some(KEY_4),
+            '%' => Some(KEY_5),
+            '^' => Some(KEY_6),
+            '&' => Some(KEY_7),
+            '*' => Some(KEY_8),
+            '(' => Some(KEY_9),
+            ')' => Some(11), // KEY_0
+            '_' => Some(KEY_MINUS),
+            '+' => Some(KEY_EQUAL),
+            '{' => Some(KEY_LEFTBRACE),
+            '}' => Some(KEY_RIGHTBRACE),
+            ':' => Some(KEY_SEMICOLON),
+            '"' => Some(KEY_APOSTROPHE),
+            '~' => Some(KEY_GRAVE),
+            '|' => Some(KEY_BACKSLASH),
+            '<' => Some(KEY_COMMA),
+            '>' => Some(KEY_DOT),
+            '?' => Some(KEY_SLASH),
             _ => None,
         }
     }
@@ -419,11 +682,12 @@ impl Plugin for RemoteInputPlugin {
         Ok(())
     }
 
-    async fn handle_packet(&mut self, packet: &Packet, _device: &mut Device) -> Result<()> {
+    async fn handle_packet(&mut self, packet: &Packet, device: &mut Device) -> Result<()> {
         if packet.is_type(PACKET_TYPE_MOUSEPAD_REQUEST)
             || packet.is_type("kdeconnect.mousepad.request")
         {
             debug!("Received remote input request");
+            self.authorize_input(device.id()).await?;
             self.handle_request(packet).await
         } else {
             Ok(())
@@ -432,8 +696,35 @@ impl Plugin for RemoteInputPlugin {
 }
 
 /// Factory for creating Remote Input plugin instances
-#[derive(Debug, Clone, Copy)]
-pub struct RemoteInputPluginFactory;
+#[derive(Debug, Clone, Default)]
+pub struct RemoteInputPluginFactory {
+    /// Configuration applied to every created plugin
+    config: RemoteInputConfig,
+
+    /// Policy shared by every created plugin (None denies all input)
+    policy: Option<SharedPermissionPolicy>,
+}
+
+impl RemoteInputPluginFactory {
+    /// Create factory with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create factory with explicit configuration
+    pub fn with_config(config: RemoteInputConfig) -> Self {
+        Self {
+            config,
+            policy: None,
+        }
+    }
+
+    /// Set the policy consulted before injecting input
+    pub fn with_policy(mut self, policy: SharedPermissionPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+}
 
 impl PluginFactory for RemoteInputPluginFactory {
     fn name(&self) -> &str {
@@ -452,13 +743,18 @@ impl PluginFactory for RemoteInputPluginFactory {
     }
 
     fn create(&self) -> Box<dyn Plugin> {
-        Box::new(RemoteInputPlugin::new())
+        let plugin = RemoteInputPlugin::with_config(self.config.clone());
+        match &self.policy {
+            Some(policy) => Box::new(plugin.with_policy(Arc::clone(policy))),
+            None => Box::new(plugin),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::MockPermissionPolicy;
     use crate::{DeviceInfo, DeviceType};
 
     fn create_test_device() -> Device {
@@ -615,9 +911,190 @@ mod tests {
         let _ = plugin.handle_packet(&packet, &mut device_mut).await;
     }
 
+    fn request(body: serde_json::Value) -> RemoteInputRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_special_key_codes() {
+        use mouse_keyboard_input::*;
+
+        let expected = [
+            (1, KEY_BACKSPACE),
+            (2, KEY_TAB),
+            (4, KEY_LEFT),
+            (5, KEY_UP),
+            (6, KEY_RIGHT),
+            (7, KEY_DOWN),
+            (8, KEY_PAGEUP),
+            (9, KEY_PAGEDOWN),
+            (10, KEY_HOME),
+            (11, KEY_END),
+            (12, KEY_ENTER),
+            (13, KEY_DELETE),
+            (14, KEY_ESC),
+            (21, KEY_F1),
+            (32, KEY_F12),
+        ];
+        for (code, keycode) in expected {
+            assert_eq!(
+                SpecialKey::from_code(code).map(SpecialKey::keycode),
+                Some(keycode),
+                "specialKey {}",
+                code
+            );
+        }
+
+        assert_eq!(SpecialKey::from_code(0), None);
+        assert_eq!(SpecialKey::from_code(17), None);
+        assert_eq!(SpecialKey::from_code(33), None);
+    }
+
+    #[test]
+    fn test_special_key_round_trip() {
+        for code in (1..=16).chain(21..=32) {
+            let key = SpecialKey::from_code(code).unwrap();
+            assert_eq!(key as i32, code);
+        }
+    }
+
+    #[test]
+    fn test_key_actions_with_modifiers() {
+        use mouse_keyboard_input::{KEY_C, KEY_LEFT, KEY_LEFTCTRL, KEY_LEFTSHIFT};
+
+        let actions = RemoteInputPlugin::key_actions(&request(serde_json::json!({
+            "key": "c",
+            "ctrl": true
+        })));
+        assert_eq!(
+            actions,
+            vec![
+                KeyAction::Press(KEY_LEFTCTRL),
+                KeyAction::Press(KEY_C),
+                KeyAction::Release(KEY_C),
+                KeyAction::Release(KEY_LEFTCTRL),
+            ]
+        );
+
+        let actions = RemoteInputPlugin::key_actions(&request(serde_json::json!({
+            "specialKey": 4,
+            "ctrl": true,
+            "shift": true
+        })));
+        assert_eq!(
+            actions,
+            vec![
+                KeyAction::Press(KEY_LEFTCTRL),
+                KeyAction::Press(KEY_LEFTSHIFT),
+                KeyAction::Press(KEY_LEFT),
+                KeyAction::Release(KEY_LEFT),
+                KeyAction::Release(KEY_LEFTSHIFT),
+                KeyAction::Release(KEY_LEFTCTRL),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_actions_shifted_characters() {
+        use mouse_keyboard_input::{KEY_1, KEY_A, KEY_LEFTSHIFT};
+
+        let actions = RemoteInputPlugin::key_actions(&request(serde_json::json!({
+            "key": "A!"
+        })));
+        assert_eq!(
+            actions,
+            vec![
+                KeyAction::Press(KEY_LEFTSHIFT),
+                KeyAction::Press(KEY_A),
+                KeyAction::Release(KEY_A),
+                KeyAction::Release(KEY_LEFTSHIFT),
+                KeyAction::Press(KEY_LEFTSHIFT),
+                KeyAction::Press(KEY_1),
+                KeyAction::Release(KEY_1),
+                KeyAction::Release(KEY_LEFTSHIFT),
+            ]
+        );
+
+        // Shift is not pressed twice when the request already holds it
+        let actions = RemoteInputPlugin::key_actions(&request(serde_json::json!({
+            "key": "A",
+            "shift": true
+        })));
+        assert_eq!(actions.len(), 4);
+
+        // Characters without a key are skipped
+        assert!(RemoteInputPlugin::key_actions(&request(serde_json::json!({
+            "key": "é"
+        })))
+        .is_empty());
+    }
+
+    #[test]
+    fn test_pointer_motion_keeps_fractions() {
+        let mut motion = PointerMotion::new(false);
+        assert_eq!(motion.apply(0.4, -0.4), (0, 0));
+        assert_eq!(motion.apply(0.4, -0.4), (0, 0));
+        assert_eq!(motion.apply(0.4, -0.4), (1, -1));
+        assert_eq!(motion.apply(20.0, 0.0), (20, 0));
+    }
+
+    #[test]
+    fn test_pointer_acceleration_optional() {
+        let mut plain = PointerMotion::new(false);
+        let mut accelerated = PointerMotion::new(true);
+
+        // Slow movements are never accelerated
+        assert_eq!(accelerated.apply(2.0, 0.0), (2, 0));
+
+        assert_eq!(plain.apply(8.0, 0.0), (8, 0));
+        assert_eq!(accelerated.apply(8.0, 0.0), (16, 0));
+
+        // Gain is capped
+        assert_eq!(accelerated.apply(0.0, 100.0), (0, 300));
+    }
+
+    #[tokio::test]
+    async fn test_input_denied_by_policy() {
+        let policy = Arc::new(MockPermissionPolicy::denying());
+        let mut plugin = RemoteInputPlugin::new().with_policy(policy.clone());
+        let mut device = create_test_device();
+
+        let packet = Packet::new(
+            "cconnect.mousepad.request",
+            serde_json::json!({ "dx": 10.0, "dy": 20.0 }),
+        );
+        let err = plugin
+            .handle_packet(&packet, &mut device)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProtocolError::PermissionDenied(_)));
+        assert!(plugin.virtual_device.lock().unwrap().is_none());
+
+        // The denial is reused for the next packet
+        let err = plugin
+            .handle_packet(&packet, &mut device)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProtocolError::PermissionDenied(_)));
+        assert_eq!(
+            policy.checks(),
+            vec![(device.id().to_string(), PrivilegedAction::RemoteInput)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_input_allowed_by_policy() {
+        let policy = Arc::new(MockPermissionPolicy::allowing());
+        let plugin = RemoteInputPlugin::new().with_policy(policy.clone());
+
+        plugin.authorize_input("phone").await.unwrap();
+        plugin.authorize_input("phone").await.unwrap();
+        assert_eq!(policy.checks().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_factory() {
-        let factory = RemoteInputPluginFactory;
+        let factory = RemoteInputPluginFactory::new();
         assert_eq!(factory.name(), "remoteinput");
 
         let incoming = factory.incoming_capabilities();
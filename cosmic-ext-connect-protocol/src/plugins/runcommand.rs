@@ -8,10 +8,13 @@
 //! **Packet Types**:
 //! - `cconnect.runcommand` - Command list response (outgoing)
 //! - `cconnect.runcommand.request` - Command execution request (incoming)
+//! - `cconnect.runcommand.result` - Output of an executed command (outgoing)
+//! - `cconnect.permission.denied` - Execution request denied by policy (outgoing)
 //!
 //! **Capabilities**:
 //! - Incoming: `cconnect.runcommand.request` - Receives command execution requests
 //! - Outgoing: `cconnect.runcommand` - Sends command list to devices
+//! - Outgoing: `cconnect.runcommand.result` - Sends command output to devices
 //!
 //! ## Packet Formats
 //!
@@ -52,6 +55,31 @@
 //! }
 //! ```
 //!
+//! `requestAll: true` is accepted as an alias. A `requestUuids` list of keys
+//! asks for just those commands; the reply is a command list holding the
+//! known keys only.
+//!
+//! ### Command Result (`cconnect.runcommand.result`)
+//!
+//! Sent to the requesting device once an executed command exits:
+//! ```json
+//! {
+//!     "id": 1234567890,
+//!     "type": "cconnect.runcommand.result",
+//!     "body": {
+//!         "key": "cmd1",
+//!         "exitCode": 0,
+//!         "stdout": "total 42\n...",
+//!         "stderr": ""
+//!     }
+//! }
+//! ```
+//!
+//! Output is not redacted, but each stream is capped at half of
+//! [`MAX_RESULT_OUTPUT_BYTES`]; longer output is cut and ends with
+//! [`OUTPUT_TRUNCATED_MARKER`]. Commands killed by a signal report an exit
+//! code of -1.
+//!
 //! ## Configuration
 //!
 //! Commands are stored in a JSON configuration file per device:
@@ -72,12 +100,25 @@
 //! }
 //! ```
 //!
+//! A command with `"requireConfirmation": true` is not run straight away:
+//! the plugin emits `cconnect.internal.runcommand.confirm` and the daemon
+//! asks the user, then calls [`RunCommandPlugin::confirm_command`] or
+//! [`RunCommandPlugin::reject_command`]. The flag is not sent to devices.
+//!
+//! Commands with `"sensitive": true` only have their program name recorded in
+//! the [audit log](super::audit); the arguments are redacted.
+//!
 //! ## Security
 //!
 //! - Commands are pre-configured by the user on the desktop
 //! - Only paired devices can trigger commands
 //! - Commands execute with the user's permissions
-//! - No arbitrary command execution from mobile devices
+//! - Execution requests are checked against a [`PermissionPolicy`] before
+//!   confirmation or execution; without a configured policy they are denied
+//! - No arbitrary command execution from mobile devices: requests name a
+//!   command key, and keys not in the configured list are rejected
+//!
+//! [`PermissionPolicy`]: super::permission::PermissionPolicy
 //!
 //! ## Example
 //!
@@ -107,15 +148,33 @@ use crate::{Device, Packet, ProtocolError, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::process::Stdio;
 use std::sync::Arc;
 use tokio::fs;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+use super::audit;
+use super::permission::{
+    self, DenyAllPolicy, PrivilegedAction, SharedPermissionPolicy, PACKET_TYPE_PERMISSION_DENIED,
+};
 use super::{Plugin, PluginFactory};
 
+/// Packet type for command results
+pub const PACKET_TYPE_RUNCOMMAND_RESULT: &str = "cconnect.runcommand.result";
+
+/// Maximum bytes of command output reported, across stdout and stderr
+pub const MAX_RESULT_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Appended to a stream whose output was cut at the size limit
+pub const OUTPUT_TRUNCATED_MARKER: &str = "\n[output truncated]";
+
+/// Internal packet asking the daemon to confirm a command before it runs
+pub const PACKET_TYPE_RUNCOMMAND_CONFIRM: &str = "cconnect.internal.runcommand.confirm";
+
 /// A runnable command definition
 ///
 /// Represents a pre-configured shell command that can be executed
@@ -127,6 +186,18 @@ pub struct Command {
 
     /// Shell command to execute
     pub command: String,
+
+    /// Ask the desktop user before running the command
+    #[serde(
+        default,
+        skip_serializing_if = "std::ops::Not::not",
+        rename = "requireConfirmation"
+    )]
+    pub require_confirmation: bool,
+
+    /// Keep the command line out of the audit log
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sensitive: bool,
 }
 
 impl Command {
@@ -135,8 +206,139 @@ impl Command {
         Self {
             name: name.into(),
             command: command.into(),
+            require_confirmation: false,
+            sensitive: false,
         }
     }
+
+    /// Require confirmation on the desktop before running
+    pub fn with_confirmation(mut self, require_confirmation: bool) -> Self {
+        self.require_confirmation = require_confirmation;
+        self
+    }
+
+    /// Redact the command line in the audit log
+    pub fn with_sensitive(mut self, sensitive: bool) -> Self {
+        self.sensitive = sensitive;
+        self
+    }
+
+    /// Command parameters for the audit log
+    ///
+    /// Sensitive commands only keep their program name.
+    pub fn audit_parameters(&self, key: &str) -> serde_json::Value {
+        let command = if self.sensitive {
+            audit::redact_command(&self.command)
+        } else {
+            self.command.clone()
+        };
+        json!({ "key": key, "name": self.name, "command": command })
+    }
+}
+
+/// Captured result of an executed command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    /// Exit code, or -1 if the command was terminated by a signal
+    pub exit_code: i32,
+
+    /// Standard output, possibly truncated
+    pub stdout: String,
+
+    /// Standard error, possibly truncated
+    pub stderr: String,
+}
+
+/// Read a stream to the end, keeping at most `limit` bytes
+///
+/// The rest is drained so the child never blocks on a full pipe. Returns the
+/// kept bytes and whether anything was dropped.
+async fn read_bounded<R: AsyncRead + Unpin>(
+    mut reader: R,
+    limit: usize,
+) -> std::io::Result<(Vec<u8>, bool)> {
+    let mut kept = Vec::new();
+    let mut truncated = false;
+    let mut buf = [0u8; 8192];
+
+    loop {
+        let n = reader.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        let room = limit - kept.len();
+        if n > room {
+            truncated = true;
+        }
+        kept.extend_from_slice(&buf[..n.min(room)]);
+    }
+
+    Ok((kept, truncated))
+}
+
+/// Convert captured bytes to text, marking truncated output
+fn output_to_string(mut bytes: Vec<u8>, truncated: bool) -> String {
+    if truncated {
+        // Drop a multi-byte character cut in half by the limit
+        if let Err(e) = std::str::from_utf8(&bytes) {
+            if e.error_len().is_none() {
+                bytes.truncate(e.valid_up_to());
+            }
+        }
+    }
+
+    let mut text = String::from_utf8_lossy(&bytes).into_owned();
+    if truncated {
+        text.push_str(OUTPUT_TRUNCATED_MARKER);
+    }
+    text
+}
+
+/// Spawn a command line in the system shell with its output piped
+fn spawn_shell(command_line: &str) -> std::io::Result<tokio::process::Child> {
+    // Execute command using sh -c (Linux/Unix) or cmd /C (Windows)
+    #[cfg(target_os = "windows")]
+    let (shell, flag) = ("cmd", "/C");
+
+    #[cfg(not(target_os = "windows"))]
+    let (shell, flag) = ("/bin/sh", "-c");
+
+    tokio::process::Command::new(shell)
+        .arg(flag)
+        .arg(command_line)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+}
+
+/// Wait for a spawned command and collect its bounded output
+async fn capture_output(mut child: tokio::process::Child) -> std::io::Result<CommandOutput> {
+    let stream_limit = MAX_RESULT_OUTPUT_BYTES / 2;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let (stdout, stderr) = tokio::try_join!(
+        async {
+            match stdout {
+                Some(stdout) => read_bounded(stdout, stream_limit).await,
+                None => Ok((Vec::new(), false)),
+            }
+        },
+        async {
+            match stderr {
+                Some(stderr) => read_bounded(stderr, stream_limit).await,
+                None => Ok((Vec::new(), false)),
+            }
+        },
+    )?;
+    let status = child.wait().await?;
+
+    Ok(CommandOutput {
+        exit_code: status.code().unwrap_or(-1),
+        stdout: output_to_string(stdout.0, stdout.1),
+        stderr: output_to_string(stderr.0, stderr.1),
+    })
 }
 
 /// Configuration storage for runcommand plugin
@@ -188,6 +390,12 @@ pub struct RunCommandPlugin {
 
     /// Channel to send packets
     packet_sender: Option<tokio::sync::mpsc::Sender<(String, Packet)>>,
+
+    /// Commands requested by the device that wait for user confirmation
+    pending_confirmations: Arc<RwLock<HashSet<String>>>,
+
+    /// Policy consulted before running a requested command
+    policy: SharedPermissionPolicy,
 }
 
 impl RunCommandPlugin {
@@ -210,9 +418,19 @@ impl RunCommandPlugin {
             config_path: None,
             commands_executed: Arc::new(RwLock::new(0)),
             packet_sender: None,
+            pending_confirmations: Arc::new(RwLock::new(HashSet::new())),
+            policy: Arc::new(DenyAllPolicy),
         }
     }
 
+    /// Set the policy consulted before running a requested command
+    ///
+    /// Without a policy every execution request is denied.
+    pub fn with_policy(mut self, policy: SharedPermissionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     /// Get the configuration file path for a device
     fn get_config_path(device_id: &str) -> Result<PathBuf> {
         // Use $HOME/.config/kdeconnect/<device_id>/kdeconnect_runcommand/commands.json
@@ -332,6 +550,19 @@ impl RunCommandPlugin {
         }
     }
 
+    /// Set whether a command needs confirmation before it runs
+    pub async fn set_require_confirmation(&self, id: &str, require: bool) -> Result<()> {
+        let mut config = self.config.write().await;
+        let command = config
+            .commands
+            .get_mut(id)
+            .ok_or_else(|| ProtocolError::Plugin(format!("Command '{}' not found", id)))?;
+        command.require_confirmation = require;
+        drop(config);
+
+        self.save_config().await
+    }
+
     /// Get all commands
     ///
     /// Returns a clone of the current command map
@@ -366,10 +597,26 @@ impl RunCommandPlugin {
     /// A `Packet` ready to be sent to the device
     pub async fn create_command_list_packet(&self) -> Packet {
         let config = self.config.read().await;
+        Self::command_list_packet(config.commands.iter())
+    }
+
+    /// Build a command list packet from `(key, command)` pairs
+    ///
+    /// Entries only carry `name` and `command`, as KDE Connect expects.
+    fn command_list_packet<'a>(
+        commands: impl Iterator<Item = (&'a String, &'a Command)>,
+    ) -> Packet {
+        let list: serde_json::Map<String, serde_json::Value> = commands
+            .map(|(key, cmd)| {
+                (
+                    key.clone(),
+                    json!({ "name": cmd.name, "command": cmd.command }),
+                )
+            })
+            .collect();
 
         // Serialize command list as JSON string (as per protocol spec)
-        let command_list_json =
-            serde_json::to_string(&config.commands).unwrap_or_else(|_| "{}".to_string());
+        let command_list_json = serde_json::to_string(&list).unwrap_or_else(|_| "{}".to_string());
 
         Packet::new(
             "cconnect.runcommand",
@@ -380,6 +627,114 @@ impl RunCommandPlugin {
         )
     }
 
+    /// Create a command list packet holding only the given keys
+    ///
+    /// Unknown keys are left out.
+    pub async fn create_partial_command_list_packet(&self, keys: &[String]) -> Packet {
+        let config = self.config.read().await;
+        Self::command_list_packet(
+            keys.iter()
+                .filter_map(|key| config.commands.get_key_value(key)),
+        )
+    }
+
+    /// Handle a device's request to run a command
+    ///
+    /// Only keys of configured commands are accepted, and only if the
+    /// permission policy allows it. Commands that require confirmation are
+    /// held until [`confirm_command`](Self::confirm_command).
+    async fn request_command(&self, id: &str) -> Result<()> {
+        let command = self
+            .get_command(id)
+            .await
+            .ok_or_else(|| ProtocolError::Plugin(format!("Command '{}' not found", id)))?;
+
+        // Commands needing confirmation are audited once the user answers
+        let action = PrivilegedAction::RunCommand(id.to_string());
+        let device_id = self.device_id.as_deref().unwrap_or_default();
+        let parameters = command.audit_parameters(id);
+        let authorized = if command.require_confirmation {
+            permission::authorize_pending(self.policy.as_ref(), device_id, &action, parameters)
+                .await
+        } else {
+            permission::authorize(self.policy.as_ref(), device_id, &action, parameters).await
+        };
+        if let Err(e) = authorized {
+            if let (Some(sender), Some(device_id)) = (&self.packet_sender, &self.device_id) {
+                let packet = permission::create_denied_packet(&action, &e);
+                let _ = sender.send((device_id.clone(), packet)).await;
+            }
+            return Err(e);
+        }
+
+        if !command.require_confirmation {
+            return self.execute_command(id).await;
+        }
+
+        info!("Command '{}' needs confirmation before running", id);
+        self.pending_confirmations
+            .write()
+            .await
+            .insert(id.to_string());
+
+        if let (Some(sender), Some(device_id)) = (&self.packet_sender, &self.device_id) {
+            let packet = Packet::new(
+                PACKET_TYPE_RUNCOMMAND_CONFIRM,
+                json!({
+                    "key": id,
+                    "name": command.name,
+                    "command": command.command,
+                }),
+            );
+            let _ = sender.send((device_id.clone(), packet)).await;
+        }
+        Ok(())
+    }
+
+    /// Run a command the user confirmed
+    ///
+    /// Fails unless the device requested the command and it is still
+    /// waiting for confirmation.
+    pub async fn confirm_command(&self, id: &str) -> Result<()> {
+        if !self.pending_confirmations.write().await.remove(id) {
+            return Err(ProtocolError::InvalidState(format!(
+                "Command '{}' is not awaiting confirmation",
+                id
+            )));
+        }
+        info!("Command '{}' confirmed", id);
+        self.record_answer(id, true).await;
+        self.execute_command(id).await
+    }
+
+    /// Drop a command the user declined
+    pub async fn reject_command(&self, id: &str) {
+        if self.pending_confirmations.write().await.remove(id) {
+            info!("Command '{}' declined", id);
+            self.record_answer(id, false).await;
+        }
+    }
+
+    /// Audit the user's answer to a command awaiting confirmation
+    async fn record_answer(&self, id: &str, accepted: bool) {
+        let parameters = match self.get_command(id).await {
+            Some(command) => command.audit_parameters(id),
+            None => json!({ "key": id }),
+        };
+        permission::record_answer(
+            self.policy.as_ref(),
+            self.device_id.as_deref().unwrap_or_default(),
+            &PrivilegedAction::RunCommand(id.to_string()),
+            parameters,
+            accepted,
+        );
+    }
+
+    /// Whether a requested command is waiting for confirmation
+    pub async fn is_awaiting_confirmation(&self, id: &str) -> bool {
+        self.pending_confirmations.read().await.contains(id)
+    }
+
     /// Execute a command by ID
     ///
     /// Looks up the command and executes it using the system shell.
@@ -400,20 +755,9 @@ impl RunCommandPlugin {
         info!("Executing command '{}': {}", id, command.name);
         debug!("Command: {}", command.command);
 
-        // Execute command using sh -c (Linux/Unix) or cmd /C (Windows)
-        #[cfg(target_os = "windows")]
-        let (shell, flag) = ("cmd", "/C");
-
-        #[cfg(not(target_os = "windows"))]
-        let (shell, flag) = ("/bin/sh", "-c");
-
         // Spawn command detached (non-blocking)
-        match tokio::process::Command::new(shell)
-            .arg(flag)
-            .arg(&command.command)
-            .spawn()
-        {
-            Ok(mut child) => {
+        match spawn_shell(&command.command) {
+            Ok(child) => {
                 // Increment execution counter
                 let mut count = self.commands_executed.write().await;
                 *count += 1;
@@ -425,15 +769,27 @@ impl RunCommandPlugin {
                     child.id()
                 );
 
-                // Wait for completion in background
+                // Wait for completion in background and report the result
                 let id_clone = id.to_string();
+                let packet_sender = self.packet_sender.clone();
+                let device_id = self.device_id.clone();
                 tokio::spawn(async move {
-                    match child.wait().await {
-                        Ok(status) => {
-                            if status.success() {
+                    match capture_output(child).await {
+                        Ok(output) => {
+                            if output.exit_code == 0 {
                                 debug!("Command '{}' completed successfully", id_clone);
                             } else {
-                                warn!("Command '{}' exited with status: {}", id_clone, status);
+                                warn!(
+                                    "Command '{}' exited with status: {}",
+                                    id_clone, output.exit_code
+                                );
+                            }
+
+                            if let (Some(sender), Some(device_id)) = (packet_sender, device_id) {
+                                let packet = Self::create_result_packet(&id_clone, &output);
+                                if let Err(e) = sender.send((device_id, packet)).await {
+                                    error!("Failed to send runcommand result: {}", e);
+                                }
                             }
                         }
                         Err(e) => {
@@ -454,22 +810,50 @@ impl RunCommandPlugin {
         }
     }
 
+    /// Create a command result packet
+    ///
+    /// Creates a `cconnect.runcommand.result` packet reporting the exit code
+    /// and captured output of the command `key`.
+    pub fn create_result_packet(key: &str, output: &CommandOutput) -> Packet {
+        Packet::new(
+            PACKET_TYPE_RUNCOMMAND_RESULT,
+            json!({
+                "key": key,
+                "exitCode": output.exit_code,
+                "stdout": output.stdout,
+                "stderr": output.stderr,
+            }),
+        )
+    }
+
     /// Handle a command request packet
     async fn handle_request(&mut self, packet: &Packet) -> Result<Option<Packet>> {
         // Check if it's a command list request
-        if let Some(request_list) = packet.body.get("requestCommandList") {
-            if request_list.as_bool().unwrap_or(false) {
-                info!("Received command list request");
-                let response = self.create_command_list_packet().await;
-                return Ok(Some(response));
-            }
+        let wants_list = ["requestCommandList", "requestAll"]
+            .iter()
+            .any(|field| packet.body.get(*field).and_then(|v| v.as_bool()) == Some(true));
+        if wants_list {
+            info!("Received command list request");
+            let response = self.create_command_list_packet().await;
+            return Ok(Some(response));
+        }
+
+        if let Some(uuids) = packet.body.get("requestUuids").and_then(|v| v.as_array()) {
+            let keys: Vec<String> = uuids
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect();
+            info!("Received command list request for {} commands", keys.len());
+            let response = self.create_partial_command_list_packet(&keys).await;
+            return Ok(Some(response));
         }
 
         // Check if it's a command execution request
         if let Some(key) = packet.body.get("key").and_then(|v| v.as_str()) {
             info!("Received command execution request for '{}'", key);
 
-            if let Err(e) = self.execute_command(key).await {
+            if let Err(e) = self.request_command(key).await {
                 warn!("Failed to execute command '{}': {}", key, e);
                 // Don't return error - just log it
             }
@@ -551,6 +935,8 @@ impl Plugin for RunCommandPlugin {
         vec![
             "cconnect.runcommand".to_string(),
             "cconnect.runcommand.request".to_string(),
+            PACKET_TYPE_RUNCOMMAND_RESULT.to_string(),
+            PACKET_TYPE_PERMISSION_DENIED.to_string(),
         ]
     }
 
@@ -621,12 +1007,36 @@ impl Plugin for RunCommandPlugin {
 /// use cosmic_ext_connect_protocol::plugins::PluginFactory;
 /// use std::sync::Arc;
 ///
-/// let factory: Arc<dyn PluginFactory> = Arc::new(RunCommandPluginFactory);
+/// let factory: Arc<dyn PluginFactory> = Arc::new(RunCommandPluginFactory::new());
 /// let plugin = factory.create();
 /// assert_eq!(plugin.name(), "runcommand");
 /// ```
-#[derive(Debug, Clone, Copy)]
-pub struct RunCommandPluginFactory;
+#[derive(Debug, Clone)]
+pub struct RunCommandPluginFactory {
+    /// Policy shared by every created plugin
+    policy: SharedPermissionPolicy,
+}
+
+impl RunCommandPluginFactory {
+    /// Create factory whose plugins deny all execution requests
+    pub fn new() -> Self {
+        Self {
+            policy: Arc::new(DenyAllPolicy),
+        }
+    }
+
+    /// Set the policy consulted before running a requested command
+    pub fn with_policy(mut self, policy: SharedPermissionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl Default for RunCommandPluginFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl PluginFactory for RunCommandPluginFactory {
     fn name(&self) -> &str {
@@ -648,17 +1058,21 @@ impl PluginFactory for RunCommandPluginFactory {
         vec![
             "cconnect.runcommand".to_string(),
             "cconnect.runcommand.request".to_string(),
+            PACKET_TYPE_RUNCOMMAND_RESULT.to_string(),
+            PACKET_TYPE_PERMISSION_DENIED.to_string(),
         ]
     }
 
     fn create(&self) -> Box<dyn Plugin> {
-        Box::new(RunCommandPlugin::new())
+        Box::new(RunCommandPlugin::new().with_policy(Arc::clone(&self.policy)))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plugins::audit::{AuditLog, AuditOutcome};
+    use crate::test_utils::MockPermissionPolicy;
     use crate::{DeviceInfo, DeviceType};
 
     fn create_test_device() -> Device {
@@ -684,9 +1098,10 @@ mod tests {
         assert!(incoming.contains(&"kdeconnect.runcommand".to_string()));
 
         let outgoing = plugin.outgoing_capabilities();
-        assert_eq!(outgoing.len(), 2);
+        assert_eq!(outgoing.len(), 4);
         assert!(outgoing.contains(&"cconnect.runcommand".to_string()));
         assert!(outgoing.contains(&"cconnect.runcommand.request".to_string()));
+        assert!(outgoing.contains(&"cconnect.runcommand.result".to_string()));
     }
 
     #[test]
@@ -696,6 +1111,20 @@ mod tests {
         assert_eq!(cmd.command, "ls -la");
     }
 
+    #[test]
+    fn test_sensitive_command_redacted_in_audit() {
+        let cmd = Command::new("DB Dump", "mysqldump -psecret db");
+        assert_eq!(
+            cmd.audit_parameters("dump")["command"],
+            "mysqldump -psecret db"
+        );
+
+        let cmd = cmd.with_sensitive(true);
+        let parameters = cmd.audit_parameters("dump");
+        assert_eq!(parameters["key"], "dump");
+        assert_eq!(parameters["command"], "mysqldump [redacted]");
+    }
+
     #[tokio::test]
     async fn test_add_and_get_command() {
         let plugin = RunCommandPlugin::new();
@@ -827,6 +1256,161 @@ mod tests {
         assert_eq!(response.packet_type, "cconnect.runcommand");
     }
 
+    #[tokio::test]
+    async fn test_command_list_kde_format() {
+        let plugin = RunCommandPlugin::new();
+        plugin
+            .add_command("cmd1", "List Files", "ls -la")
+            .await
+            .unwrap();
+        plugin.set_require_confirmation("cmd1", true).await.unwrap();
+
+        let packet = plugin.create_command_list_packet().await;
+        let list: serde_json::Value =
+            serde_json::from_str(packet.body["commandList"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            list,
+            json!({ "cmd1": { "name": "List Files", "command": "ls -la" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_all_and_uuids() {
+        let mut plugin = RunCommandPlugin::new();
+        plugin.add_command("a", "A", "true").await.unwrap();
+        plugin.add_command("b", "B", "true").await.unwrap();
+
+        let packet = Packet::new(
+            "kdeconnect.runcommand.request",
+            json!({ "requestAll": true }),
+        );
+        let response = plugin.handle_request(&packet).await.unwrap().unwrap();
+        let list: HashMap<String, Command> =
+            serde_json::from_str(response.body["commandList"].as_str().unwrap()).unwrap();
+        assert_eq!(list.len(), 2);
+
+        let packet = Packet::new(
+            "kdeconnect.runcommand.request",
+            json!({ "requestUuids": ["b", "missing"] }),
+        );
+        let response = plugin.handle_request(&packet).await.unwrap().unwrap();
+        let list: HashMap<String, Command> =
+            serde_json::from_str(response.body["commandList"].as_str().unwrap()).unwrap();
+        assert_eq!(list.keys().collect::<Vec<_>>(), vec!["b"]);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_key_rejected() {
+        let mut plugin = RunCommandPlugin::new();
+        plugin.add_command("known", "Known", "true").await.unwrap();
+
+        assert!(plugin.request_command("unknown").await.is_err());
+
+        // Command strings in the request are never run
+        let packet = Packet::new(
+            "kdeconnect.runcommand.request",
+            json!({ "key": "unknown", "command": "touch /tmp/should-not-exist" }),
+        );
+        assert!(plugin.handle_request(&packet).await.unwrap().is_none());
+        assert_eq!(plugin.commands_executed().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_command_requires_confirmation() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit_path = dir.path().join("audit.log");
+        let policy = MockPermissionPolicy::allowing().with_audit_log(AuditLog::open(&audit_path));
+        let mut plugin = RunCommandPlugin::new().with_policy(Arc::new(policy));
+        let audit_log = AuditLog::open(&audit_path);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        plugin.device_id = Some("phone".to_string());
+        plugin.packet_sender = Some(tx);
+        plugin
+            .add_command("guarded", "Guarded", "true")
+            .await
+            .unwrap();
+        plugin
+            .set_require_confirmation("guarded", true)
+            .await
+            .unwrap();
+
+        let packet = Packet::new("kdeconnect.runcommand.request", json!({ "key": "guarded" }));
+        plugin.handle_request(&packet).await.unwrap();
+
+        // Held until confirmed, and not audited before the user answers
+        assert_eq!(plugin.commands_executed().await, 0);
+        assert!(plugin.is_awaiting_confirmation("guarded").await);
+        assert_eq!(audit_log.count(None).unwrap(), 0);
+        let (_, confirm) = rx.try_recv().unwrap();
+        assert_eq!(confirm.packet_type, PACKET_TYPE_RUNCOMMAND_CONFIRM);
+        assert_eq!(confirm.body["key"], "guarded");
+
+        plugin.confirm_command("guarded").await.unwrap();
+        assert_eq!(plugin.commands_executed().await, 1);
+        let entries = audit_log.query(None, 0, 10).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].outcome, AuditOutcome::Allowed);
+
+        // A confirmation can't be replayed
+        assert!(plugin.confirm_command("guarded").await.is_err());
+
+        // Declined commands don't run
+        plugin.handle_request(&packet).await.unwrap();
+        plugin.reject_command("guarded").await;
+        assert!(!plugin.is_awaiting_confirmation("guarded").await);
+        assert!(plugin.confirm_command("guarded").await.is_err());
+        assert_eq!(plugin.commands_executed().await, 1);
+        let entries = audit_log.query(None, 0, 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].outcome, AuditOutcome::Denied);
+    }
+
+    #[tokio::test]
+    async fn test_command_denied_by_policy() {
+        let policy = Arc::new(MockPermissionPolicy::denying());
+        let mut plugin = RunCommandPlugin::new().with_policy(policy.clone());
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        plugin.device_id = Some("phone".to_string());
+        plugin.packet_sender = Some(tx);
+        plugin
+            .add_command("guarded", "Guarded", "true")
+            .await
+            .unwrap();
+        plugin
+            .set_require_confirmation("guarded", true)
+            .await
+            .unwrap();
+
+        let err = plugin.request_command("guarded").await.unwrap_err();
+        assert!(matches!(err, ProtocolError::PermissionDenied(_)));
+        assert_eq!(
+            policy.checks(),
+            vec![(
+                "phone".to_string(),
+                PrivilegedAction::RunCommand("guarded".to_string())
+            )]
+        );
+
+        // Denied before the user is asked to confirm
+        assert!(!plugin.is_awaiting_confirmation("guarded").await);
+        assert_eq!(plugin.commands_executed().await, 0);
+        let (_, denied) = rx.try_recv().unwrap();
+        assert_eq!(denied.packet_type, PACKET_TYPE_PERMISSION_DENIED);
+        assert_eq!(denied.body["action"], "guarded");
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_command_allowed_by_policy() {
+        let policy = Arc::new(MockPermissionPolicy::allowing());
+        let plugin = RunCommandPlugin::new().with_policy(policy.clone());
+        plugin.add_command("ok", "Ok", "true").await.unwrap();
+
+        plugin.request_command("ok").await.unwrap();
+        assert_eq!(policy.checks().len(), 1);
+        assert_eq!(plugin.commands_executed().await, 1);
+    }
+
     #[tokio::test]
     async fn test_plugin_lifecycle() {
         let mut plugin = RunCommandPlugin::new();
@@ -847,9 +1431,59 @@ mod tests {
         plugin.stop().await.unwrap();
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_result_packet_for_failing_command() {
+        let child = spawn_shell("echo out; echo err >&2; exit 3").unwrap();
+        let output = capture_output(child).await.unwrap();
+
+        let packet = RunCommandPlugin::create_result_packet("cmd1", &output);
+        assert_eq!(packet.packet_type, "cconnect.runcommand.result");
+        assert_eq!(packet.body["key"], "cmd1");
+        assert_eq!(packet.body["exitCode"], 3);
+        assert_eq!(packet.body["stdout"], "out\n");
+        assert_eq!(packet.body["stderr"], "err\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_result_output_truncated() {
+        // Write twice the total limit to stdout and a little to stderr
+        let command = format!(
+            "head -c {} /dev/zero | tr '\\0' 'x'; echo err >&2",
+            MAX_RESULT_OUTPUT_BYTES * 2
+        );
+        let child = spawn_shell(&command).unwrap();
+        let output = capture_output(child).await.unwrap();
+
+        assert_eq!(output.exit_code, 0);
+        assert!(output.stdout.ends_with(OUTPUT_TRUNCATED_MARKER));
+        assert_eq!(
+            output.stdout.len(),
+            MAX_RESULT_OUTPUT_BYTES / 2 + OUTPUT_TRUNCATED_MARKER.len()
+        );
+        assert_eq!(output.stderr, "err\n");
+
+        let packet = RunCommandPlugin::create_result_packet("big", &output);
+        let stdout = packet.body["stdout"].as_str().unwrap();
+        let stderr = packet.body["stderr"].as_str().unwrap();
+        assert!(
+            stdout.len() + stderr.len() <= MAX_RESULT_OUTPUT_BYTES + OUTPUT_TRUNCATED_MARKER.len()
+        );
+    }
+
+    #[test]
+    fn test_output_truncation_keeps_valid_utf8() {
+        // "é" is two bytes; cut after the first one
+        let text = output_to_string(vec![b'a', 0xC3], true);
+        assert_eq!(text, format!("a{}", OUTPUT_TRUNCATED_MARKER));
+
+        assert_eq!(output_to_string(b"done".to_vec(), false), "done");
+    }
+
     #[test]
     fn test_factory() {
-        let factory = RunCommandPluginFactory;
+        let factory = RunCommandPluginFactory::new();
         assert_eq!(factory.name(), "runcommand");
 
         let plugin = factory.create();
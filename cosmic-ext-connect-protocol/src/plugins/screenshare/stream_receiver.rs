@@ -111,7 +111,7 @@ impl StreamReceiver {
 
             // Sanity check size (e.g. max 10MB frame)
             if payload_size > 10 * 1024 * 1024 {
-                return Err(crate::ProtocolError::PacketSizeExceeded(
+                return Err(crate::ProtocolError::payload_too_large(
                     payload_size,
                     10 * 1024 * 1024,
                 ));
@@ -0,0 +1,348 @@
+//! Native SFTP Client
+//!
+//! In-process SFTP access to a device's shared storage, so it can be browsed
+//! without sshfs or FUSE. Enabled with the `native-sftp` feature; without it,
+//! [`NetworkSharePlugin::mount`](super::networkshare::NetworkSharePlugin::mount)
+//! remains the only way to reach the files.
+//!
+//! ## Paths
+//!
+//! Paths passed to [`NativeSftpClient`] are relative to the share root, the
+//! `path` of the [`SftpInfo`] (or `/` if the device did not send one). Leading
+//! slashes are ignored and `..` can not climb above the root, so
+//! `"/DCIM/../Music"` on a share rooted at `/storage/emulated/0` resolves to
+//! `/storage/emulated/0/Music`.
+//!
+//! ## Authentication
+//!
+//! The client logs in with the user and password from the [`SftpInfo`], and
+//! only after the server proved it holds the key of the paired device's
+//! certificate ([`SftpInfo::host_key`]). Shares without a known host key are
+//! refused.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use cosmic_ext_connect_protocol::plugins::sftp_client::NativeSftpClient;
+//!
+//! let client = NativeSftpClient::connect(&info).await?;
+//! for entry in client.list_dir("DCIM").await? {
+//!     println!("{} ({} bytes)", entry.path, entry.size);
+//! }
+//! let photo = client.read_file("DCIM/Camera/IMG_0001.jpg").await?;
+//! ```
+
+use super::networkshare::SftpInfo;
+use crate::{ProtocolError, Result};
+use async_trait::async_trait;
+use russh::client::{self, Handle};
+use russh_sftp::client::SftpSession;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// How long connecting and logging in may take
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Idle time after which the SSH connection is closed by the client
+const INACTIVITY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A file or directory on the share
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SftpEntry {
+    /// File name
+    pub name: String,
+    /// Path relative to the share root, without a leading slash
+    pub path: String,
+    /// Whether the entry is a directory
+    pub is_dir: bool,
+    /// Size in bytes (0 if unknown)
+    pub size: u64,
+    /// Modification time (UNIX timestamp in seconds), if known
+    pub modified: Option<u64>,
+}
+
+/// SSH event handler accepting only the device's host key
+struct ClientHandler {
+    /// Base64 part of the expected OpenSSH host key
+    host_key: String,
+}
+
+#[async_trait]
+impl client::Handler for ClientHandler {
+    type Error = russh::Error;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &russh_keys::key::PublicKey,
+    ) -> std::result::Result<bool, Self::Error> {
+        let trusted = russh_keys::parse_public_key_base64(&self.host_key)
+            .is_ok_and(|expected| expected.fingerprint() == server_public_key.fingerprint());
+        if !trusted {
+            warn!(
+                "SFTP server presented host key {}, not the paired device's",
+                server_public_key.fingerprint()
+            );
+        }
+        Ok(trusted)
+    }
+}
+
+/// Resolve a path relative to the share root
+///
+/// Empty and `.` components are dropped and `..` never leaves the root.
+pub fn resolve_path(root: &str, relative: &str) -> String {
+    let mut components: Vec<&str> = Vec::new();
+    for component in relative.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            name => components.push(name),
+        }
+    }
+
+    let root = root.trim_end_matches('/');
+    if components.is_empty() {
+        if root.is_empty() {
+            "/".to_string()
+        } else {
+            root.to_string()
+        }
+    } else {
+        format!("{}/{}", root, components.join("/"))
+    }
+}
+
+/// Path of `name` inside the relative directory `dir`
+fn child_path(dir: &str, name: &str) -> String {
+    let dir = resolve_path("", dir);
+    let dir = dir.trim_start_matches('/');
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+/// Connected SFTP session to a device's share
+///
+/// One SSH connection carries all operations, so a client should be kept and
+/// reused; [`NetworkSharePlugin`](super::networkshare::NetworkSharePlugin)
+/// caches one per device.
+pub struct NativeSftpClient {
+    /// SSH connection, kept to close it
+    session: Handle<ClientHandler>,
+
+    /// SFTP subsystem on the connection
+    sftp: SftpSession,
+
+    /// Absolute path of the share root on the device
+    root: String,
+
+    /// Set once the client was closed or an operation hit a dead connection
+    closed: AtomicBool,
+}
+
+impl std::fmt::Debug for NativeSftpClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NativeSftpClient")
+            .field("root", &self.root)
+            .field("closed", &self.closed.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl NativeSftpClient {
+    /// Connect and log in with the share's credentials
+    pub async fn connect(info: &SftpInfo) -> Result<Self> {
+        tokio::time::timeout(CONNECT_TIMEOUT, Self::connect_inner(info))
+            .await
+            .map_err(|_| {
+                ProtocolError::Timeout(format!("SFTP connection to {}", info.connection_string()))
+            })?
+    }
+
+    async fn connect_inner(info: &SftpInfo) -> Result<Self> {
+        let host_key = info
+            .host_key
+            .as_deref()
+            .and_then(|key| key.split_once(' '))
+            .map(|(_, encoded)| encoded.to_string())
+            .ok_or_else(|| {
+                ProtocolError::invalid_state(format!(
+                    "No host key known for {}",
+                    info.connection_string()
+                ))
+            })?;
+
+        let config = Arc::new(client::Config {
+            inactivity_timeout: Some(INACTIVITY_TIMEOUT),
+            ..Default::default()
+        });
+
+        let mut session = client::connect(
+            config,
+            (info.ip.as_str(), info.effective_port()),
+            ClientHandler { host_key },
+        )
+        .await
+        .map_err(|e| ProtocolError::NetworkError(format!("SSH connection failed: {}", e)))?;
+
+        let authenticated = session
+            .authenticate_password(&info.user, &info.password)
+            .await
+            .map_err(|e| ProtocolError::NetworkError(format!("SSH login failed: {}", e)))?;
+        if !authenticated {
+            return Err(ProtocolError::PermissionDenied(format!(
+                "SFTP login rejected for {}",
+                info.connection_string()
+            )));
+        }
+
+        let channel = session
+            .channel_open_session()
+            .await
+            .map_err(|e| ProtocolError::NetworkError(format!("SSH channel failed: {}", e)))?;
+        channel
+            .request_subsystem(true, "sftp")
+            .await
+            .map_err(|e| ProtocolError::NetworkError(format!("SFTP subsystem failed: {}", e)))?;
+        let sftp = SftpSession::new(channel.into_stream())
+            .await
+            .map_err(|e| ProtocolError::Plugin(format!("SFTP session failed: {}", e)))?;
+
+        info!("Connected to SFTP share {}", info.connection_string());
+        Ok(Self {
+            session,
+            sftp,
+            root: resolve_path(info.effective_path(), ""),
+            closed: AtomicBool::new(false),
+        })
+    }
+
+    /// Absolute path of the share root on the device
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Whether the client can still be used
+    pub fn is_open(&self) -> bool {
+        !self.closed.load(Ordering::Relaxed) && !self.session.is_closed()
+    }
+
+    /// List a directory, directories first, then by name
+    pub async fn list_dir(&self, path: &str) -> Result<Vec<SftpEntry>> {
+        let remote = resolve_path(&self.root, path);
+        debug!("SFTP list {}", remote);
+
+        let dir = self
+            .sftp
+            .read_dir(remote.as_str())
+            .await
+            .map_err(|e| self.sftp_error("list", &remote, e))?;
+
+        let mut entries: Vec<SftpEntry> = dir
+            .map(|entry| {
+                let metadata = entry.metadata();
+                let name = entry.file_name();
+                SftpEntry {
+                    path: child_path(path, &name),
+                    name,
+                    is_dir: metadata.is_dir(),
+                    size: metadata.size.unwrap_or(0),
+                    modified: metadata.mtime.map(u64::from),
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+        Ok(entries)
+    }
+
+    /// Read a whole file
+    pub async fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        let remote = resolve_path(&self.root, path);
+        debug!("SFTP read {}", remote);
+        self.sftp
+            .read(remote.as_str())
+            .await
+            .map_err(|e| self.sftp_error("read", &remote, e))
+    }
+
+    /// Create or replace a file
+    pub async fn write_file(&self, path: &str, data: &[u8]) -> Result<()> {
+        let remote = resolve_path(&self.root, path);
+        debug!("SFTP write {} ({} bytes)", remote, data.len());
+        self.sftp
+            .write(remote.as_str(), data)
+            .await
+            .map_err(|e| self.sftp_error("write", &remote, e))
+    }
+
+    /// Close the SFTP session and the SSH connection
+    pub async fn close(&self) {
+        if self.closed.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let _ = self.sftp.close().await;
+        let _ = self
+            .session
+            .disconnect(russh::Disconnect::ByApplication, "", "en")
+            .await;
+        debug!("SFTP connection to {} closed", self.root);
+    }
+
+    /// Map an SFTP error, marking the client closed if the connection died
+    fn sftp_error(
+        &self,
+        operation: &str,
+        path: &str,
+        error: russh_sftp::client::error::Error,
+    ) -> ProtocolError {
+        if self.session.is_closed() {
+            self.closed.store(true, Ordering::Relaxed);
+        }
+        ProtocolError::Plugin(format!("SFTP {} of {} failed: {}", operation, path, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_path_under_root() {
+        let root = "/storage/emulated/0";
+        assert_eq!(resolve_path(root, ""), "/storage/emulated/0");
+        assert_eq!(resolve_path(root, "DCIM"), "/storage/emulated/0/DCIM");
+        assert_eq!(
+            resolve_path(root, "/DCIM/./Camera/"),
+            "/storage/emulated/0/DCIM/Camera"
+        );
+        assert_eq!(resolve_path("/sdcard/", "Music"), "/sdcard/Music");
+    }
+
+    #[test]
+    fn test_resolve_path_stays_inside_root() {
+        let root = "/storage/emulated/0";
+        assert_eq!(
+            resolve_path(root, "DCIM/../Music"),
+            "/storage/emulated/0/Music"
+        );
+        assert_eq!(resolve_path(root, "../../etc"), "/storage/emulated/0/etc");
+    }
+
+    #[test]
+    fn test_resolve_path_default_root() {
+        assert_eq!(resolve_path("/", ""), "/");
+        assert_eq!(resolve_path("/", "sdcard"), "/sdcard");
+    }
+
+    #[test]
+    fn test_child_path() {
+        assert_eq!(child_path("", "DCIM"), "DCIM");
+        assert_eq!(child_path("/DCIM/", "Camera"), "DCIM/Camera");
+    }
+}
@@ -26,7 +26,8 @@
 //!         "filename": "image.png",
 //!         "creationTime": 1640000000000,
 //!         "lastModified": 1640000000000,
-//!         "open": false
+//!         "open": false,
+//!         "payloadHash": "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
 //!     },
 //!     "payloadSize": 1048576,
 //!     "payloadTransferInfo": {
@@ -106,9 +107,57 @@
 //! 3. Raw file bytes are transferred
 //! 4. Connection closes when `payloadSize` bytes received
 //!
+//! If the request carries a `payloadHash` (BLAKE3, hex), the received file is
+//! checked against it and discarded on a mismatch.
+//!
 //! The plugin handles packet creation and metadata. Actual payload transfer
 //! is handled by the transport layer.
 //!
+//! Received files are saved to the download directory. A name that is
+//! already taken gets a ` (1)`, ` (2)`, ... suffix before its extension.
+//!
+//! ### Resuming Transfers
+//!
+//! With a [`RecoveryManager`] (see [`SharePlugin::with_recovery`]), incoming
+//! transfers are recorded, and a download whose connection drops keeps its
+//! partial file. When the device connects again, the receiver asks for the
+//! rest:
+//!
+//! ```json
+//! {
+//!     "id": 1234567892,
+//!     "type": "cconnect.share.resume",
+//!     "body": {
+//!         "transferId": "1234567890",
+//!         "offset": 524288,
+//!         "prefixHash": "<BLAKE3 of the first 524288 bytes received>",
+//!         "segment": 1
+//!     }
+//! }
+//! ```
+//!
+//! The sender compares `prefixHash` with the start of its file and answers
+//! with a new `cconnect.share.request` carrying the original `transferId`,
+//! a `resumeOffset` and the remaining bytes as payload. The offset is 0 if
+//! the prefix did not match, in which case the whole file is sent again.
+//!
+//! Each payload of a transfer is a numbered segment: the first download is
+//! segment 0 and every resume request asks for the next one, which the
+//! sender echoes back as `resumeSegment`. The receiver only writes a segment
+//! that comes next in order, continues from the bytes already on disk, and
+//! does not overlap a segment still being written, so a duplicate or stale
+//! answer (for example after the connection flaps) cannot corrupt the
+//! partial file. Refused segments are counted in the transfer's
+//! [`TransferState`].
+//!
+//! ## Events
+//!
+//! A channel passed to [`SharePlugin::with_events`] receives a [`ShareEvent`]
+//! for every incoming text or URL, and progress and outcome updates for
+//! incoming files keyed by transfer ID (the ID of the request packet).
+//! [`ShareEvent::Started`] carries a [`CancelHandle`] that aborts the
+//! download.
+//!
 //! ## Example
 //!
 //! ```rust,ignore
@@ -139,6 +188,7 @@
 //!     creation_time: Some(1640000000000),
 //!     last_modified: Some(1640000000000),
 //!     open: false,
+//!     hash: None,
 //! };
 //! let packet = plugin.create_file_packet(file_info, 1739);
 //! // Send packet and handle payload transfer...
@@ -148,17 +198,281 @@
 //!
 //! - [Valent Protocol Documentation](https://valent.andyholmes.ca/documentation/protocol.html)
 
+use crate::recovery::{RecoveryManager, TransferState};
 use crate::{Device, Packet, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, info, warn};
 
 use super::{Plugin, PluginFactory};
 
+/// How long a failed or resumed outgoing file can still be resumed
+const OUTGOING_RESUME_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// Packet asking the sender to continue an interrupted file transfer
+pub const SHARE_RESUME_PACKET: &str = "cconnect.share.resume";
+
+/// Internal packet sent to the daemon when an incoming file download fails
+pub const INTERNAL_TRANSFER_FAILED: &str = "cconnect.internal.share.transfer_failed";
+
+/// Build the internal packet reporting a failed download
+///
+/// The `reason` field uses the short codes from [`crate::TransferEvent`], e.g.
+/// `"disk_full"`, so the daemon can pick a matching user notification.
+fn create_transfer_failed_packet(
+    filename: &str,
+    path: &std::path::Path,
+    error: &crate::ProtocolError,
+) -> Packet {
+    Packet::new(
+        INTERNAL_TRANSFER_FAILED,
+        json!({
+            "filename": filename,
+            "path": path.to_string_lossy(),
+            "reason": crate::TransferEvent::failure_reason(error),
+            "error": error.to_string(),
+        }),
+    )
+}
+
+/// Body of a `cconnect.share.request` packet for a file
+fn file_share_body(file_info: &FileShareInfo) -> serde_json::Value {
+    let mut body = json!({
+        "filename": file_info.filename,
+    });
+
+    // Add optional fields
+    if let Some(creation_time) = file_info.creation_time {
+        body["creationTime"] = json!(creation_time);
+    }
+    if let Some(last_modified) = file_info.last_modified {
+        body["lastModified"] = json!(last_modified);
+    }
+    if file_info.open {
+        body["open"] = json!(true);
+    }
+    if let Some(hash) = &file_info.hash {
+        body[crate::payload::PAYLOAD_HASH_FIELD] = json!(hash);
+    }
+
+    body
+}
+
+/// Build a request to continue `transfer_id` after the first `offset` bytes
+///
+/// `prefix_hash` is the BLAKE3 hash of those bytes as received, see
+/// [`crate::payload::prefix_hash`]. `segment` numbers the payload asked for,
+/// counting the first download as segment 0.
+pub fn create_resume_packet(
+    transfer_id: &str,
+    offset: u64,
+    prefix_hash: &str,
+    segment: u64,
+) -> Packet {
+    Packet::new(
+        SHARE_RESUME_PACKET,
+        json!({
+            "transferId": transfer_id,
+            "offset": offset,
+            "prefixHash": prefix_hash,
+            "segment": segment,
+        }),
+    )
+}
+
+/// Offset to resume sending `path` from
+///
+/// Returns `offset` if the receiver's `prefix_hash` matches the first
+/// `offset` bytes of the file, and 0 (start over) otherwise.
+fn resume_start(path: &Path, offset: u64, prefix_hash: &str) -> u64 {
+    if offset == 0 {
+        return 0;
+    }
+    match crate::payload::prefix_hash(path, offset) {
+        Ok(hash) if hash.eq_ignore_ascii_case(prefix_hash) => offset,
+        Ok(_) => {
+            warn!(
+                "Received prefix of {:?} does not match, sending it again",
+                path
+            );
+            0
+        }
+        Err(e) => {
+            warn!("Cannot resume {:?} at {} bytes: {}", path, offset, e);
+            0
+        }
+    }
+}
+
+/// Folder name used for devices whose name is empty after sanitizing
+const UNKNOWN_DEVICE_FOLDER: &str = "Unknown device";
+
+/// Maximum length in characters of a per-device folder name
+const MAX_DEVICE_FOLDER_LEN: usize = 64;
+
+/// Default directory for received files (`~/Downloads`)
+pub fn default_download_dir() -> PathBuf {
+    PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string())).join("Downloads")
+}
+
+/// Name used for received files whose name is empty after sanitizing
+const UNNAMED_FILE: &str = "received_file";
+
+/// Reduce a received filename to a safe single path component
+///
+/// Any directory part is dropped, so `../../.bashrc` becomes `.bashrc`.
+fn sanitize_file_name(filename: &str) -> String {
+    let base = filename
+        .rsplit(|c| c == '/' || c == '\\')
+        .next()
+        .unwrap_or_default();
+    let replaced: String = base
+        .chars()
+        .map(|c| if c.is_control() { '_' } else { c })
+        .collect();
+
+    let trimmed = replaced.trim();
+    if trimmed.is_empty() || trimmed == "." || trimmed == ".." {
+        UNNAMED_FILE.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Create an empty file in `dir` to save a received file to
+///
+/// Uses the sanitized `filename` if it is free, otherwise the first free
+/// `name (n).ext`. Each name is claimed with `create_new`, so two transfers
+/// of the same name can never end up writing to one file.
+///
+/// # Errors
+///
+/// Returns the first error other than the name already existing.
+pub async fn create_download_file(dir: &Path, filename: &str) -> std::io::Result<PathBuf> {
+    let name = sanitize_file_name(filename);
+    let name_path = Path::new(&name);
+    let stem = name_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| name.clone());
+    let extension = name_path
+        .extension()
+        .map(|e| e.to_string_lossy().into_owned());
+
+    let mut n = 0u32;
+    loop {
+        let candidate = match (n, &extension) {
+            (0, _) => dir.join(&name),
+            (_, Some(ext)) => dir.join(format!("{} ({}).{}", stem, n, ext)),
+            (_, None) => dir.join(format!("{} ({})", stem, n)),
+        };
+        match tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&candidate)
+            .await
+        {
+            Ok(_) => return Ok(candidate),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Turn a device name into a safe single path component
+///
+/// Path separators, control characters and characters reserved on common
+/// filesystems are replaced with `_`. Leading and trailing whitespace and
+/// dots are removed so the result can never be `.`, `..` or hidden.
+///
+/// ## Example
+///
+/// ```rust
+/// use cosmic_ext_connect_protocol::plugins::share::sanitize_device_name;
+///
+/// assert_eq!(sanitize_device_name("Pixel 8"), "Pixel 8");
+/// assert_eq!(sanitize_device_name("../etc/passwd"), "_etc_passwd");
+/// ```
+pub fn sanitize_device_name(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .take(MAX_DEVICE_FOLDER_LEN)
+        .collect();
+
+    let trimmed = replaced.trim_matches(|c: char| c.is_whitespace() || c == '.');
+    if trimmed.is_empty() {
+        UNKNOWN_DEVICE_FOLDER.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Share plugin configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShareConfig {
+    /// Directory received files are saved to
+    pub download_dir: PathBuf,
+
+    /// Save received files into `download_dir/<device name>/`
+    pub device_subfolders: bool,
+
+    /// Per-device subfolder setting, keyed by device ID
+    #[serde(default)]
+    pub device_subfolder_overrides: HashMap<String, bool>,
+}
+
+impl ShareConfig {
+    /// Whether files from a device go into a per-device subfolder
+    pub fn uses_subfolder(&self, device_id: &str) -> bool {
+        self.device_subfolder_overrides
+            .get(device_id)
+            .copied()
+            .unwrap_or(self.device_subfolders)
+    }
+
+    /// Directory files received from a device are saved to
+    pub fn receive_dir(&self, device_id: &str, device_name: &str) -> PathBuf {
+        if self.uses_subfolder(device_id) {
+            self.download_dir.join(sanitize_device_name(device_name))
+        } else {
+            self.download_dir.clone()
+        }
+    }
+
+    /// Create the receive directory for a device if needed and return it
+    pub async fn create_receive_dir(
+        &self,
+        device_id: &str,
+        device_name: &str,
+    ) -> std::io::Result<PathBuf> {
+        let dir = self.receive_dir(device_id, device_name);
+        tokio::fs::create_dir_all(&dir).await?;
+        Ok(dir)
+    }
+}
+
+impl Default for ShareConfig {
+    fn default() -> Self {
+        Self {
+            download_dir: default_download_dir(),
+            device_subfolders: false,
+            device_subfolder_overrides: HashMap::new(),
+        }
+    }
+}
+
 /// Information about a file being shared
 ///
 /// Contains metadata for file transfers including timestamps and display preferences.
@@ -182,6 +496,7 @@ use super::{Plugin, PluginFactory};
 ///     creation_time: Some(1640000000000),
 ///     last_modified: Some(1640000000000),
 ///     open: false,
+///     hash: None,
 /// };
 /// ```
 #[derive(Debug, Clone, PartialEq)]
@@ -200,6 +515,9 @@ pub struct FileShareInfo {
 
     /// Auto-open file after transfer
     pub open: bool,
+
+    /// BLAKE3 hash (hex) of the content, verified by the receiver if present
+    pub hash: Option<String>,
 }
 
 /// Information about a multi-file transfer
@@ -274,6 +592,82 @@ pub enum ShareContent {
     Url(String),
 }
 
+/// Cancellation flag shared between a running download and its observers
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+    /// Create a handle that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation; the transfer stops at its next progress update
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// The underlying flag, for sharing with other cancellation registries
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.0.clone()
+    }
+}
+
+impl PartialEq for CancelHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Event reported by the share plugin on its event channel
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShareEvent {
+    /// A device shared text with us
+    TextReceived { device_id: String, text: String },
+
+    /// A device shared a URL with us
+    UrlReceived { device_id: String, url: String },
+
+    /// An incoming file transfer is about to start
+    Started {
+        transfer_id: String,
+        device_id: String,
+        filename: String,
+        total_bytes: u64,
+        /// Cancels the download; it then ends with [`ShareEvent::Failed`]
+        cancel: CancelHandle,
+    },
+
+    /// An incoming file transfer made progress
+    Progress {
+        device_id: String,
+        progress: TransferProgress,
+    },
+
+    /// An incoming file was saved to `path`
+    Completed {
+        transfer_id: String,
+        device_id: String,
+        filename: String,
+        path: PathBuf,
+    },
+
+    /// An incoming file transfer failed
+    Failed {
+        transfer_id: String,
+        device_id: String,
+        filename: String,
+        /// One of the `TRANSFER_FAILED_*` codes from [`crate::payload`]
+        reason: &'static str,
+        error: String,
+    },
+}
+
 /// Record of an incoming or outgoing share
 ///
 /// Tracks share operations for history and progress monitoring.
@@ -295,6 +689,280 @@ pub struct ShareRecord {
     pub incoming: bool,
 }
 
+/// Incoming file download run in a background task
+struct Download {
+    /// Transfer ID (the request packet ID)
+    transfer_id: String,
+    device_id: String,
+    device_name: String,
+    filename: String,
+    host: String,
+    port: u16,
+    /// Size of the whole file in bytes
+    size: u64,
+    /// Expected BLAKE3 hash of the payload, if the sender provided one
+    hash: Option<String>,
+    /// Partial file and the number of its bytes to keep, when resuming
+    resume: Option<(PathBuf, u64)>,
+}
+
+impl Download {
+    /// Download the payload into the receive directory, reporting progress
+    ///
+    /// With `recovery`, the transfer is tracked there and a dropped connection
+    /// keeps the partial file so it can be resumed later.
+    async fn run(
+        self,
+        config: ShareConfig,
+        tls_config: Option<Arc<crate::TlsConfig>>,
+        packet_sender: Option<mpsc::Sender<(String, Packet)>>,
+        event_sender: Option<mpsc::UnboundedSender<ShareEvent>>,
+        recovery: Option<Arc<RecoveryManager>>,
+    ) {
+        use crate::TlsPayloadClient;
+
+        // Use TLS for payload transfer (required for Android compatibility)
+        let Some(tls_config) = tls_config else {
+            warn!(
+                "Cannot download file '{}' from {}: TLS config not set. \
+                 Call set_tls_config() on SharePlugin before receiving files.",
+                self.filename, self.device_name
+            );
+            if let (Some(recovery), Some(_)) = (&recovery, &self.resume) {
+                recovery.end_segment(&self.transfer_id).await;
+            }
+            return;
+        };
+
+        let (file_path, offset) = match &self.resume {
+            Some((path, offset)) => (path.clone(), *offset),
+            None => {
+                // Create downloads directory
+                let downloads_dir = match config
+                    .create_receive_dir(&self.device_id, &self.device_name)
+                    .await
+                {
+                    Ok(dir) => dir,
+                    Err(e) => {
+                        warn!("Failed to create downloads directory: {}", e);
+                        return;
+                    }
+                };
+                match create_download_file(&downloads_dir, &self.filename).await {
+                    Ok(path) => (path, 0),
+                    Err(e) => {
+                        warn!("Failed to create file for '{}': {}", self.filename, e);
+                        return;
+                    }
+                }
+            }
+        };
+
+        info!(
+            "Downloading file '{}' from {} ({}:{}) to {:?}",
+            self.filename, self.device_name, self.host, self.port, file_path
+        );
+
+        let cancel = CancelHandle::new();
+        if let Some(events) = &event_sender {
+            let _ = events.send(ShareEvent::Started {
+                transfer_id: self.transfer_id.clone(),
+                device_id: self.device_id.clone(),
+                filename: self.filename.clone(),
+                total_bytes: self.size,
+                cancel: cancel.clone(),
+            });
+        }
+
+        if let (Some(recovery), None) = (&recovery, &self.resume) {
+            let mut state = TransferState::new(
+                self.transfer_id.clone(),
+                self.device_id.clone(),
+                self.filename.clone(),
+                file_path.clone(),
+                self.size,
+            );
+            state.hash = self.hash.clone();
+            if let Err(e) = recovery.register_transfer(state).await {
+                warn!("Failed to record transfer {}: {}", self.transfer_id, e);
+            } else if let Err(e) = recovery.begin_segment(&self.transfer_id, 0, 0).await {
+                warn!("Failed to start transfer {}: {}", self.transfer_id, e);
+            }
+        }
+
+        let result = match TlsPayloadClient::new(&self.host, self.port, &tls_config).await {
+            Ok(client) => {
+                let mut client =
+                    client.with_progress(self.progress_callback(cancel, event_sender.clone()));
+                if let Some(hash) = &self.hash {
+                    client = client.with_expected_hash(hash.as_str());
+                }
+                if recovery.is_some() {
+                    client = client.resumable();
+                }
+                client.resume_file(&file_path, offset, self.size).await
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to connect to TLS payload server {}:{}: {}",
+                    self.host, self.port, e
+                );
+                // Nothing was received into the file created for this transfer
+                if self.resume.is_none() {
+                    let _ = tokio::fs::remove_file(&file_path).await;
+                }
+                Err(e)
+            }
+        };
+
+        if let Some(recovery) = &recovery {
+            let reason = result
+                .as_ref()
+                .err()
+                .map(crate::TransferEvent::failure_reason);
+            let kept = match reason {
+                Some(reason) if crate::TransferEvent::is_resumable(reason) => {
+                    tokio::fs::metadata(&file_path).await.ok().map(|m| m.len())
+                }
+                _ => None,
+            };
+            let recorded = match kept {
+                Some(bytes) => {
+                    info!(
+                        "Keeping {} of {} bytes of '{}' to resume later",
+                        bytes, self.size, self.filename
+                    );
+                    recovery
+                        .update_transfer_progress(&self.transfer_id, bytes)
+                        .await
+                }
+                None => recovery.complete_transfer(&self.transfer_id).await,
+            };
+            if let Err(e) = recorded {
+                warn!("Failed to update transfer {}: {}", self.transfer_id, e);
+            }
+            recovery.end_segment(&self.transfer_id).await;
+        }
+
+        match result {
+            Ok(()) => {
+                info!(
+                    "Successfully downloaded file '{}' from {} via TLS",
+                    self.filename, self.device_name
+                );
+                if let Some(events) = &event_sender {
+                    let _ = events.send(ShareEvent::Completed {
+                        transfer_id: self.transfer_id,
+                        device_id: self.device_id,
+                        filename: self.filename,
+                        path: file_path,
+                    });
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to download file '{}' from {} via TLS: {}",
+                    self.filename, self.device_name, e
+                );
+                // Partial file has already been removed by the client, unless
+                // it was kept to resume
+                if let Some(sender) = &packet_sender {
+                    let failed = create_transfer_failed_packet(&self.filename, &file_path, &e);
+                    if let Err(send_err) = sender.send((self.device_id.clone(), failed)).await {
+                        warn!("Failed to report download failure: {}", send_err);
+                    }
+                }
+                if let Some(events) = &event_sender {
+                    let _ = events.send(ShareEvent::Failed {
+                        transfer_id: self.transfer_id,
+                        device_id: self.device_id,
+                        filename: self.filename,
+                        reason: crate::TransferEvent::failure_reason(&e),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Progress callback that logs and emits [`ShareEvent::Progress`]
+    ///
+    /// Updates are rate limited to one every 500ms, plus the final one. The
+    /// transfer stops as soon as `cancel` is triggered.
+    fn progress_callback(
+        &self,
+        cancel: CancelHandle,
+        event_sender: Option<mpsc::UnboundedSender<ShareEvent>>,
+    ) -> crate::payload::ProgressCallback {
+        use std::sync::atomic::AtomicU64;
+        use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+        let transfer_start = Instant::now();
+        let last_update = AtomicU64::new(0);
+        let transfer_id = self.transfer_id.clone();
+        let device_id = self.device_id.clone();
+        let device_name = self.device_name.clone();
+        let filename = self.filename.clone();
+
+        Box::new(move |transferred, total| {
+            if cancel.is_cancelled() {
+                info!("Download of '{}' from {} cancelled", filename, device_name);
+                return false;
+            }
+
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64;
+            let last = last_update.load(Ordering::Relaxed);
+            if now - last < 500 && transferred < total {
+                return true;
+            }
+            last_update.store(now, Ordering::Relaxed);
+
+            let percent = (transferred as f64 / total as f64 * 100.0) as u8;
+            let elapsed = transfer_start.elapsed().as_secs_f64();
+            let speed = if elapsed > 0.0 {
+                transferred as f64 / elapsed
+            } else {
+                0.0
+            };
+            let eta = if speed > 0.0 {
+                (total.saturating_sub(transferred) as f64 / speed) as u64
+            } else {
+                0
+            };
+
+            info!(
+                "Download progress '{}' from {}: {} / {} bytes ({}%, {:.2} KB/s)",
+                filename,
+                device_name,
+                transferred,
+                total,
+                percent,
+                speed / 1024.0
+            );
+
+            if let Some(events) = &event_sender {
+                let _ = events.send(ShareEvent::Progress {
+                    device_id: device_id.clone(),
+                    progress: TransferProgress {
+                        transfer_id: transfer_id.clone(),
+                        filename: filename.clone(),
+                        bytes_transferred: transferred,
+                        total_bytes: total,
+                        percent_complete: percent,
+                        speed_bytes_per_second: speed as u64,
+                        eta,
+                    },
+                });
+            }
+
+            true // Continue transfer
+        })
+    }
+}
+
 /// Share plugin for file, text, and URL sharing
 ///
 /// Handles `cconnect.share.request` packets for transferring content between devices.
@@ -330,6 +998,36 @@ pub struct SharePlugin {
     /// TLS configuration for secure payload transfers
     /// Required for receiving files from Android (uses TLS for payload transfers)
     tls_config: Option<Arc<crate::TlsConfig>>,
+
+    /// Packet sender for reporting failed downloads to the daemon
+    packet_sender: Option<tokio::sync::mpsc::Sender<(String, Packet)>>,
+
+    /// Where received files are saved
+    config: ShareConfig,
+
+    /// Channel for incoming shares and transfer progress
+    event_sender: Option<mpsc::UnboundedSender<ShareEvent>>,
+
+    /// Persisted state of incoming transfers, for resuming them
+    recovery: Option<Arc<RecoveryManager>>,
+
+    /// Files sent by this plugin, by transfer ID, so they can be resumed
+    outgoing_files: Arc<RwLock<HashMap<String, OutgoingFile>>>,
+}
+
+/// A file sent to the device, kept to serve resume requests
+#[derive(Debug, Clone)]
+struct OutgoingFile {
+    path: PathBuf,
+    info: FileShareInfo,
+    /// When the entry is dropped, set once the transfer failed or was resumed
+    expires: Option<Instant>,
+}
+
+impl OutgoingFile {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
 }
 
 // Manual Debug impl to skip tls_config (TlsConfig doesn't implement Debug)
@@ -342,6 +1040,7 @@ impl std::fmt::Debug for SharePlugin {
                 "tls_config",
                 &self.tls_config.as_ref().map(|_| "<TlsConfig>"),
             )
+            .field("config", &self.config)
             .finish()
     }
 }
@@ -358,13 +1057,88 @@ impl SharePlugin {
     /// assert_eq!(plugin.share_count(), 0);
     /// ```
     pub fn new() -> Self {
+        Self::with_config(ShareConfig::default())
+    }
+
+    /// Create a share plugin with explicit configuration
+    pub fn with_config(config: ShareConfig) -> Self {
         Self {
             device_id: None,
             shares: Arc::new(RwLock::new(Vec::new())),
             tls_config: None,
+            packet_sender: None,
+            config,
+            event_sender: None,
+            recovery: None,
+            outgoing_files: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Report incoming shares and transfer progress on `sender`
+    pub fn with_events(mut self, sender: mpsc::UnboundedSender<ShareEvent>) -> Self {
+        self.event_sender = Some(sender);
+        self
+    }
+
+    /// Track incoming transfers in `recovery` so interrupted ones can resume
+    ///
+    /// Partial files are kept when a connection drops, and resume requests
+    /// for them are sent when the device connects again.
+    pub fn with_recovery(mut self, recovery: Arc<RecoveryManager>) -> Self {
+        self.recovery = Some(recovery);
+        self
+    }
+
+    /// Remember a file sent as `transfer_id` so the device can resume it
+    ///
+    /// [`Self::send_file`] does this itself; callers serving the payload on
+    /// their own use this after sending the packet from
+    /// [`Self::create_file_packet`].
+    pub async fn register_outgoing_file(
+        &self,
+        transfer_id: impl Into<String>,
+        path: impl Into<PathBuf>,
+        info: FileShareInfo,
+    ) {
+        let mut outgoing = self.outgoing_files.write().await;
+        let now = Instant::now();
+        outgoing.retain(|_, file| !file.is_expired(now));
+        outgoing.insert(
+            transfer_id.into(),
+            OutgoingFile {
+                path: path.into(),
+                info,
+                expires: None,
+            },
+        );
+    }
+
+    /// Forget an outgoing file once its transfer completed or was cancelled
+    pub async fn finish_outgoing_file(&self, transfer_id: &str) {
+        self.outgoing_files.write().await.remove(transfer_id);
+    }
+
+    /// Keep a failed outgoing file only for the resume window
+    ///
+    /// The device can still resume the transfer after reconnecting; the
+    /// entry is dropped once the window has passed.
+    pub async fn fail_outgoing_file(&self, transfer_id: &str) {
+        if let Some(file) = self.outgoing_files.write().await.get_mut(transfer_id) {
+            file.expires = Some(Instant::now() + OUTGOING_RESUME_WINDOW);
+        }
+    }
+
+    fn emit(&self, event: ShareEvent) {
+        if let Some(sender) = &self.event_sender {
+            let _ = sender.send(event);
         }
     }
 
+    /// Get the plugin configuration
+    pub fn config(&self) -> &ShareConfig {
+        &self.config
+    }
+
     /// Set TLS configuration for secure payload transfers
     ///
     /// Must be called before receiving files from Android devices, as they
@@ -412,6 +1186,7 @@ impl SharePlugin {
     ///     creation_time: Some(1640000000000),
     ///     last_modified: Some(1640000000000),
     ///     open: false,
+    ///     hash: None,
     /// };
     ///
     /// let packet = plugin.create_file_packet(file_info, 1739);
@@ -419,30 +1194,59 @@ impl SharePlugin {
     /// assert_eq!(packet.payload_size, Some(1024));
     /// ```
     pub fn create_file_packet(&self, file_info: FileShareInfo, port: u16) -> Packet {
-        let mut body = json!({
-            "filename": file_info.filename,
-        });
-
-        // Add optional fields
-        if let Some(creation_time) = file_info.creation_time {
-            body["creationTime"] = json!(creation_time);
-        }
-        if let Some(last_modified) = file_info.last_modified {
-            body["lastModified"] = json!(last_modified);
-        }
-        if file_info.open {
-            body["open"] = json!(true);
-        }
-
         // Create payload transfer info
         let mut transfer_info = HashMap::new();
         transfer_info.insert("port".to_string(), json!(port));
 
-        Packet::new("cconnect.share.request", body)
+        Packet::new("cconnect.share.request", file_share_body(&file_info))
             .with_payload_size(file_info.size)
             .with_payload_transfer_info(transfer_info)
     }
 
+    /// Create a file share packet with the file attached as its payload
+    ///
+    /// The file is streamed, not read into memory. Whoever sends the packet
+    /// serves the payload (see [`Packet::take_payload`]) and sets
+    /// `payloadTransferInfo` to the port it listens on.
+    ///
+    /// The share is recorded as outgoing.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the file cannot be opened or its metadata read.
+    pub async fn send_file(&self, path: impl AsRef<Path>) -> Result<Packet> {
+        let path = path.as_ref();
+        let file_info: FileShareInfo = crate::FileTransferInfo::from_path(path)
+            .await?
+            .with_hash()
+            .await?
+            .into();
+        let file = tokio::fs::File::open(path).await?;
+
+        let packet = Packet::new("cconnect.share.request", file_share_body(&file_info))
+            .with_payload(file, file_info.size as u64);
+
+        info!(
+            "Sharing file '{}' ({} bytes)",
+            file_info.filename, file_info.size
+        );
+
+        self.register_outgoing_file(packet.id.to_string(), path, file_info.clone())
+            .await;
+
+        if let Some(device_id) = &self.device_id {
+            self.shares.write().await.push(ShareRecord {
+                id: packet.id.to_string(),
+                device_id: device_id.clone(),
+                content: ShareContent::File(file_info),
+                timestamp: packet.id,
+                incoming: false,
+            });
+        }
+
+        Ok(packet)
+    }
+
     /// Create a text share packet
     ///
     /// Creates a `cconnect.share.request` packet for text sharing.
@@ -570,6 +1374,29 @@ impl SharePlugin {
         Packet::new("cconnect.share.request.progress", json!(progress))
     }
 
+    /// Check that a device can receive shares from us
+    ///
+    /// Returns [`ProtocolError::NotPaired`](crate::ProtocolError::NotPaired) if the
+    /// device is not paired, or
+    /// [`ProtocolError::UnsupportedFeature`](crate::ProtocolError::UnsupportedFeature)
+    /// if it did not advertise the share capability during identity exchange.
+    pub fn validate_share_target(device: &Device) -> Result<()> {
+        if !device.is_paired() {
+            return Err(crate::ProtocolError::NotPaired);
+        }
+
+        if !device.has_incoming_capability("cconnect.share.request")
+            && !device.has_incoming_capability("kdeconnect.share.request")
+        {
+            return Err(crate::ProtocolError::unsupported_feature(format!(
+                "{} does not accept shared files, text or URLs",
+                device.name()
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Get the number of recorded shares
     ///
     /// # Example
@@ -659,6 +1486,11 @@ impl SharePlugin {
                     .get("open")
                     .and_then(|v| v.as_bool())
                     .unwrap_or(false),
+                hash: packet
+                    .body
+                    .get(crate::payload::PAYLOAD_HASH_FIELD)
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
             };
 
             info!(
@@ -673,144 +1505,38 @@ impl SharePlugin {
             if let Some(transfer_info) = &packet.payload_transfer_info {
                 // Extract port from payloadTransferInfo
                 if let Some(port_value) = transfer_info.get("port") {
-                    let port = port_value.as_i64().unwrap_or(0) as u16;
-
-                    // Get remote host from device
                     if let Some(host) = &device.host {
-                        let host_clone = host.clone();
-                        let filename_clone = filename.to_string();
-                        let size = file_info.size;
-                        let device_name = device.name().to_string();
-
-                        // Get TLS config for secure payload transfer
-                        let tls_config = self.get_tls_config();
-
-                        // Spawn background task to download file
-                        tokio::spawn(async move {
-                            // Create downloads directory
-                            let downloads_dir = std::path::PathBuf::from(
-                                std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string()),
-                            )
-                            .join("Downloads");
-
-                            if let Err(e) = tokio::fs::create_dir_all(&downloads_dir).await {
-                                warn!("Failed to create downloads directory: {}", e);
-                                return;
+                        let mut download = Download {
+                            transfer_id: packet.id.to_string(),
+                            device_id: device_id.clone(),
+                            device_name: device.name().to_string(),
+                            filename: filename.to_string(),
+                            host: host.clone(),
+                            port: port_value.as_i64().unwrap_or(0) as u16,
+                            size: file_info.size as u64,
+                            hash: file_info.hash.clone(),
+                            resume: None,
+                        };
+
+                        let resumed_id = packet.body.get("transferId").and_then(|v| v.as_str());
+                        let resumed = match resumed_id {
+                            Some(transfer_id) => {
+                                self.resume_download(&mut download, transfer_id, packet)
+                                    .await
                             }
+                            None => true,
+                        };
 
-                            let file_path = downloads_dir.join(&filename_clone);
-
-                            info!(
-                                "Downloading file '{}' from {} ({}:{}) to {:?}",
-                                filename_clone, device_name, host_clone, port, file_path
-                            );
-
-                            // Connect to payload server and download file with progress tracking
-                            use crate::TlsPayloadClient;
-                            use std::sync::atomic::{AtomicU64, Ordering};
-                            use std::sync::Arc;
-                            use std::time::{Instant, SystemTime, UNIX_EPOCH};
-
-                            // Use TLS for payload transfer (required for Android compatibility)
-                            if let Some(config) = tls_config {
-                                match TlsPayloadClient::new(&host_clone, port, &config).await {
-                                    Ok(client) => {
-                                        let transfer_start = Instant::now();
-                                        let last_update = Arc::new(AtomicU64::new(0));
-                                        let filename_for_callback = filename_clone.clone();
-                                        let device_name_for_callback = device_name.clone();
-
-                                        // Add progress callback with rate limiting (update every 500ms)
-                                        let client_with_progress = client.with_progress(Box::new(move |transferred, total| {
-                                            let now = SystemTime::now()
-                                                .duration_since(UNIX_EPOCH)
-                                                .unwrap()
-                                                .as_millis() as u64;
-                                            let last = last_update.load(Ordering::Relaxed);
-
-                                            // Only log progress every 500ms to avoid spam
-                                            if now - last >= 500 {
-                                                last_update.store(now, Ordering::Relaxed);
-                                                let percent = (transferred as f64 / total as f64 * 100.0) as u8;
-                                                let elapsed = transfer_start.elapsed().as_secs_f64();
-                                                let speed = if elapsed > 0.0 {
-                                                    transferred as f64 / elapsed
-                                                } else {
-                                                    0.0
-                                                };
-
-                                                info!(
-                                                    "Download progress '{}' from {}: {} / {} bytes ({}%, {:.2} KB/s)",
-                                                    filename_for_callback,
-                                                    device_name_for_callback,
-                                                    transferred,
-                                                    total,
-                                                    percent,
-                                                    speed / 1024.0
-                                                );
-
-                                                // DESIGN LIMITATION: Progress packets not sent to sender device
-                                                //
-                                                // The current architecture spawns a detached async task for file downloads,
-                                                // which doesn't have access to the device's packet sender channel. This is
-                                                // intentional to avoid blocking packet processing.
-                                                //
-                                                // To enable progress packet sending, we would need to:
-                                                // 1. Pass packet_sender channel into this spawned task
-                                                // 2. Send cconnect.share.request.progress packets periodically
-                                                //
-                                                // Progress is currently logged locally (see lines 742-750) and could be
-                                                // exposed via a callback mechanism if needed by the UI layer.
-                                                //
-                                                // Example implementation:
-                                                //   let progress_packet = Packet::new("cconnect.share.request.progress", json!({
-                                                //       "transferId": transfer_id,
-                                                //       "filename": filename,
-                                                //       "bytesTransferred": transferred,
-                                                //       "totalBytes": total,
-                                                //       "percentComplete": percent,
-                                                //       "speedBytesPerSecond": speed as u64,
-                                                //       "eta": eta
-                                                //   }));
-                                                //   packet_sender.send((device_id, progress_packet)).await;
-                                            }
-
-                                            true // Continue transfer
-                                        }));
-
-                                        match client_with_progress
-                                            .receive_file(&file_path, size as u64)
-                                            .await
-                                        {
-                                            Ok(()) => {
-                                                info!(
-                                                    "Successfully downloaded file '{}' from {} via TLS",
-                                                    filename_clone, device_name
-                                                );
-                                            }
-                                            Err(e) => {
-                                                warn!(
-                                                    "Failed to download file '{}' from {} via TLS: {}",
-                                                    filename_clone, device_name, e
-                                                );
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        warn!(
-                                            "Failed to connect to TLS payload server {}:{}: {}",
-                                            host_clone, port, e
-                                        );
-                                    }
-                                }
-                            } else {
-                                warn!(
-                                    "Cannot download file '{}' from {}: TLS config not set. \
-                                     Call set_tls_config() on SharePlugin before receiving files.",
-                                    filename_clone, device_name
-                                );
-                            }
-                        });
+                        // Spawn background task to download file
+                        if resumed {
+                            tokio::spawn(download.run(
+                                self.config.clone(),
+                                self.get_tls_config(),
+                                self.packet_sender.clone(),
+                                self.event_sender.clone(),
+                                self.recovery.clone(),
+                            ));
+                        }
                     } else {
                         warn!("Cannot download file: device host not available");
                     }
@@ -829,6 +1555,11 @@ impl SharePlugin {
                 text.len()
             );
 
+            self.emit(ShareEvent::TextReceived {
+                device_id: device_id.clone(),
+                text: text.to_string(),
+            });
+
             ShareContent::Text(text.to_string())
         } else if let Some(url) = packet.body.get("url").and_then(|v| v.as_str()) {
             // URL share
@@ -839,6 +1570,11 @@ impl SharePlugin {
                 url
             );
 
+            self.emit(ShareEvent::UrlReceived {
+                device_id: device_id.clone(),
+                url: url.to_string(),
+            });
+
             ShareContent::Url(url.to_string())
         } else {
             warn!(
@@ -863,6 +1599,178 @@ impl SharePlugin {
         debug!("Share history size: {}", self.shares.read().await.len());
     }
 
+    /// Turn `download` into the continuation of the recorded `transfer_id`
+    ///
+    /// The sender answers a resume request with a share request carrying the
+    /// original `transferId`, the `resumeOffset` it streams from and the
+    /// `resumeSegment` asked for. Returns `false` if the transfer is unknown,
+    /// the offset does not fit the partial file, or the segment is out of
+    /// sequence, in which case the payload is not fetched.
+    async fn resume_download(
+        &self,
+        download: &mut Download,
+        transfer_id: &str,
+        packet: &Packet,
+    ) -> bool {
+        let state = match &self.recovery {
+            Some(recovery) => recovery.get_transfer_state(transfer_id).await,
+            None => None,
+        };
+        let Some(state) = state.filter(|s| s.device_id == download.device_id) else {
+            warn!("Ignoring resumed share of unknown transfer {}", transfer_id);
+            return false;
+        };
+
+        let offset = packet
+            .body
+            .get("resumeOffset")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        let Some(segment) = packet.body.get("resumeSegment").and_then(|v| v.as_u64()) else {
+            warn!(
+                "Ignoring resumed share of {} without a segment",
+                transfer_id
+            );
+            return false;
+        };
+        if offset + download.size != state.total_size {
+            warn!(
+                "Cannot resume transfer {} at {} bytes with {} remaining ({} expected in total)",
+                transfer_id, offset, download.size, state.total_size
+            );
+            return false;
+        }
+        if let Some(recovery) = &self.recovery {
+            if let Err(e) = recovery.begin_segment(transfer_id, segment, offset).await {
+                warn!("Refusing resumed share: {}", e);
+                return false;
+            }
+        }
+
+        info!(
+            "Resuming '{}' at {} of {} bytes",
+            state.filename, offset, state.total_size
+        );
+        download.transfer_id = state.transfer_id;
+        download.filename = state.filename;
+        download.size = state.total_size;
+        download.hash = state.hash.or(download.hash.take());
+        download.resume = Some((state.file_path, offset));
+        true
+    }
+
+    /// Ask the device to continue every interrupted download from it
+    ///
+    /// Each request carries the size of the partial file and a hash of its
+    /// content, so the sender only appends if the prefix matches its file.
+    async fn request_resumes(&self) {
+        let (Some(recovery), Some(device_id), Some(sender)) =
+            (&self.recovery, &self.device_id, &self.packet_sender)
+        else {
+            return;
+        };
+
+        for state in recovery.get_device_transfers(device_id).await {
+            let path = state.file_path.clone();
+            let offset = state.resume_offset();
+            // If nothing usable was kept, the empty hash asks for the whole file again
+            let prefix =
+                tokio::task::spawn_blocking(move || crate::payload::prefix_hash(&path, offset))
+                    .await
+                    .ok()
+                    .and_then(|hash| hash.ok())
+                    .unwrap_or_default();
+
+            info!(
+                "Requesting resume of '{}' from {} at {} bytes",
+                state.filename, device_id, offset
+            );
+            let packet = create_resume_packet(&state.transfer_id, offset, &prefix, state.segments);
+            if let Err(e) = sender.send((device_id.clone(), packet)).await {
+                warn!("Failed to request resume of {}: {}", state.transfer_id, e);
+            }
+        }
+    }
+
+    /// Answer a resume request by sending the rest of the file
+    ///
+    /// The remainder is attached as the packet payload. If the receiver's
+    /// prefix does not match the file (or it asks for more than the file
+    /// holds), the whole file is sent again from offset 0.
+    async fn handle_resume_request(&self, packet: &Packet, device: &Device) -> Result<()> {
+        use tokio::io::AsyncSeekExt;
+
+        let transfer_id = packet
+            .body
+            .get("transferId")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                crate::ProtocolError::InvalidPacket("Resume request without transferId".into())
+            })?;
+        let offset = packet
+            .body
+            .get("offset")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        let prefix = packet
+            .body
+            .get("prefixHash")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let segment = packet.body.get("segment").and_then(|v| v.as_u64());
+
+        let outgoing = {
+            let mut outgoing_files = self.outgoing_files.write().await;
+            let now = Instant::now();
+            outgoing_files.retain(|_, file| !file.is_expired(now));
+            // We can't see when the resumed payload finishes, so it expires too
+            outgoing_files.get_mut(transfer_id).map(|file| {
+                file.expires = Some(now + OUTGOING_RESUME_WINDOW);
+                file.clone()
+            })
+        };
+        let Some(outgoing) = outgoing else {
+            warn!(
+                "{} asked to resume unknown transfer {}",
+                device.name(),
+                transfer_id
+            );
+            return Ok(());
+        };
+        let Some(sender) = &self.packet_sender else {
+            return Ok(());
+        };
+
+        let path = outgoing.path.clone();
+        let start = tokio::task::spawn_blocking(move || resume_start(&path, offset, &prefix))
+            .await
+            .map_err(|e| crate::ProtocolError::Plugin(format!("Hash task panicked: {}", e)))?;
+
+        let mut file = tokio::fs::File::open(&outgoing.path).await?;
+        let size = file.metadata().await?.len();
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+
+        info!(
+            "Resuming '{}' for {} at {} of {} bytes",
+            outgoing.info.filename,
+            device.name(),
+            start,
+            size
+        );
+        let mut packet = Packet::new("cconnect.share.request", file_share_body(&outgoing.info))
+            .with_body_field("transferId", transfer_id)
+            .with_body_field("resumeOffset", start);
+        if let Some(segment) = segment {
+            packet = packet.with_body_field("resumeSegment", segment);
+        }
+        let packet = packet.with_payload(file, size - start);
+        sender
+            .send((device.id().to_string(), packet))
+            .await
+            .map_err(|e| crate::ProtocolError::Plugin(format!("Failed to send packet: {}", e)))
+    }
+
     /// Handle a multi-file update packet
     ///
     /// Logs multi-file transfer announcement.
@@ -914,6 +1822,7 @@ impl Plugin for SharePlugin {
             "cconnect.share.request.update".to_string(),
             "kdeconnect.share.request".to_string(),
             "kdeconnect.share.request.update".to_string(),
+            SHARE_RESUME_PACKET.to_string(),
         ]
     }
 
@@ -921,21 +1830,24 @@ impl Plugin for SharePlugin {
         vec![
             "cconnect.share.request".to_string(),
             "cconnect.share.request.update".to_string(),
+            SHARE_RESUME_PACKET.to_string(),
         ]
     }
 
     async fn init(
         &mut self,
         device: &Device,
-        _packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
+        packet_sender: tokio::sync::mpsc::Sender<(String, Packet)>,
     ) -> Result<()> {
         self.device_id = Some(device.id().to_string());
+        self.packet_sender = Some(packet_sender);
         info!("Share plugin initialized for device {}", device.name());
         Ok(())
     }
 
     async fn start(&mut self) -> Result<()> {
         info!("Share plugin started");
+        self.request_resumes().await;
         Ok(())
     }
 
@@ -952,14 +1864,53 @@ impl Plugin for SharePlugin {
             || packet.is_type("kdeconnect.share.request.update")
         {
             self.handle_multifile_update(packet, device);
+        } else if packet.is_type(SHARE_RESUME_PACKET) {
+            self.handle_resume_request(packet, device).await?;
         }
         Ok(())
     }
 }
 
 /// Factory for creating SharePlugin instances
-#[derive(Debug, Clone, Copy)]
-pub struct SharePluginFactory;
+#[derive(Debug, Clone, Default)]
+pub struct SharePluginFactory {
+    /// Configuration applied to every created plugin
+    config: ShareConfig,
+
+    /// Event channel handed to every created plugin
+    event_sender: Option<mpsc::UnboundedSender<ShareEvent>>,
+
+    /// Transfer tracking shared by every created plugin
+    recovery: Option<Arc<RecoveryManager>>,
+}
+
+impl SharePluginFactory {
+    /// Create factory with default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create factory with explicit configuration
+    pub fn with_config(config: ShareConfig) -> Self {
+        Self {
+            config,
+            event_sender: None,
+            recovery: None,
+        }
+    }
+
+    /// Report events from every created plugin on `sender`
+    pub fn with_events(mut self, sender: mpsc::UnboundedSender<ShareEvent>) -> Self {
+        self.event_sender = Some(sender);
+        self
+    }
+
+    /// Track incoming transfers of every created plugin in `recovery`
+    pub fn with_recovery(mut self, recovery: Arc<RecoveryManager>) -> Self {
+        self.recovery = Some(recovery);
+        self
+    }
+}
 
 impl PluginFactory for SharePluginFactory {
     fn name(&self) -> &str {
@@ -972,6 +1923,7 @@ impl PluginFactory for SharePluginFactory {
             "cconnect.share.request.update".to_string(),
             "kdeconnect.share.request".to_string(),
             "kdeconnect.share.request.update".to_string(),
+            SHARE_RESUME_PACKET.to_string(),
         ]
     }
 
@@ -979,11 +1931,19 @@ impl PluginFactory for SharePluginFactory {
         vec![
             "cconnect.share.request".to_string(),
             "cconnect.share.request.update".to_string(),
+            SHARE_RESUME_PACKET.to_string(),
         ]
     }
 
     fn create(&self) -> Box<dyn Plugin> {
-        Box::new(SharePlugin::new())
+        let mut plugin = SharePlugin::with_config(self.config.clone());
+        if let Some(sender) = &self.event_sender {
+            plugin = plugin.with_events(sender.clone());
+        }
+        if let Some(recovery) = &self.recovery {
+            plugin = plugin.with_recovery(recovery.clone());
+        }
+        Box::new(plugin)
     }
 }
 
@@ -1010,16 +1970,126 @@ mod tests {
         let plugin = SharePlugin::new();
 
         let incoming = plugin.incoming_capabilities();
-        assert_eq!(incoming.len(), 4);
+        assert_eq!(incoming.len(), 5);
         assert!(incoming.contains(&"cconnect.share.request".to_string()));
         assert!(incoming.contains(&"cconnect.share.request.update".to_string()));
         assert!(incoming.contains(&"kdeconnect.share.request".to_string()));
         assert!(incoming.contains(&"kdeconnect.share.request.update".to_string()));
+        assert!(incoming.contains(&SHARE_RESUME_PACKET.to_string()));
 
         let outgoing = plugin.outgoing_capabilities();
-        assert_eq!(outgoing.len(), 2);
+        assert_eq!(outgoing.len(), 3);
         assert!(outgoing.contains(&"cconnect.share.request".to_string()));
         assert!(outgoing.contains(&"cconnect.share.request.update".to_string()));
+        assert!(outgoing.contains(&SHARE_RESUME_PACKET.to_string()));
+    }
+
+    #[test]
+    fn test_transfer_failed_packet_reports_disk_full() {
+        let error = crate::ProtocolError::ResourceExhausted("Disk full".to_string());
+        let packet = create_transfer_failed_packet(
+            "video.mp4",
+            std::path::Path::new("/tmp/Downloads/video.mp4"),
+            &error,
+        );
+
+        assert_eq!(packet.packet_type, INTERNAL_TRANSFER_FAILED);
+        assert_eq!(packet.body["filename"], "video.mp4");
+        assert_eq!(packet.body["path"], "/tmp/Downloads/video.mp4");
+        assert_eq!(packet.body["reason"], "disk_full");
+    }
+
+    #[test]
+    fn test_sanitize_device_name() {
+        assert_eq!(sanitize_device_name("Pixel 8"), "Pixel 8");
+        assert_eq!(sanitize_device_name("Work/Phone"), "Work_Phone");
+        assert_eq!(sanitize_device_name("a\\b"), "a_b");
+        assert_eq!(sanitize_device_name("tab\tnew\nline\0"), "tab_new_line_");
+        assert_eq!(sanitize_device_name(".."), UNKNOWN_DEVICE_FOLDER);
+        assert_eq!(sanitize_device_name("  .hidden "), "hidden");
+        assert_eq!(sanitize_device_name(""), UNKNOWN_DEVICE_FOLDER);
+        assert_eq!(
+            sanitize_device_name(&"x".repeat(200)).len(),
+            MAX_DEVICE_FOLDER_LEN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_receive_dir_per_device_subfolder() {
+        let temp_dir = std::env::temp_dir().join("cconnect-share-subfolder-test");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+
+        let config = ShareConfig {
+            download_dir: temp_dir.clone(),
+            device_subfolders: true,
+            device_subfolder_overrides: HashMap::new(),
+        };
+
+        let dir = config
+            .create_receive_dir("phone-id", "My/Phone")
+            .await
+            .unwrap();
+        assert_eq!(dir, temp_dir.join("My_Phone"));
+        assert!(dir.is_dir());
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_receive_dir_flat_when_disabled() {
+        let mut config = ShareConfig {
+            download_dir: PathBuf::from("/tmp/Downloads"),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.receive_dir("phone-id", "Phone"),
+            PathBuf::from("/tmp/Downloads")
+        );
+
+        // Per-device override wins over the global toggle
+        config
+            .device_subfolder_overrides
+            .insert("phone-id".to_string(), true);
+        assert_eq!(
+            config.receive_dir("phone-id", "Phone"),
+            PathBuf::from("/tmp/Downloads/Phone")
+        );
+        assert_eq!(
+            config.receive_dir("tablet-id", "Tablet"),
+            PathBuf::from("/tmp/Downloads")
+        );
+    }
+
+    #[test]
+    fn test_factory_applies_config() {
+        let config = ShareConfig {
+            device_subfolders: true,
+            ..Default::default()
+        };
+        let plugin = SharePluginFactory::with_config(config.clone()).create();
+        let plugin = plugin.as_any().downcast_ref::<SharePlugin>().unwrap();
+        assert_eq!(plugin.config(), &config);
+    }
+
+    #[test]
+    fn test_validate_share_target() {
+        let mut device = create_test_device();
+        assert!(matches!(
+            SharePlugin::validate_share_target(&device),
+            Err(crate::ProtocolError::NotPaired)
+        ));
+
+        device.pairing_status = crate::PairingStatus::Paired;
+        assert!(matches!(
+            SharePlugin::validate_share_target(&device),
+            Err(crate::ProtocolError::UnsupportedFeature(_))
+        ));
+
+        device
+            .info
+            .incoming_capabilities
+            .push("kdeconnect.share.request".to_string());
+        assert!(SharePlugin::validate_share_target(&device).is_ok());
     }
 
     #[tokio::test]
@@ -1050,6 +2120,7 @@ mod tests {
             creation_time: Some(1640000000000),
             last_modified: Some(1640000000000),
             open: false,
+            hash: Some("abc123".to_string()),
         };
 
         let packet = plugin.create_file_packet(file_info, 1739);
@@ -1059,6 +2130,7 @@ mod tests {
             packet.body.get("filename").and_then(|v| v.as_str()),
             Some("test.txt")
         );
+        assert_eq!(packet.body["payloadHash"], "abc123");
         assert_eq!(packet.payload_size, Some(1024));
 
         let transfer_info = packet.payload_transfer_info.as_ref().unwrap();
@@ -1337,4 +2409,502 @@ mod tests {
         // Should not create a share record
         assert_eq!(plugin.share_count(), 0);
     }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("photo.jpg"), "photo.jpg");
+        assert_eq!(sanitize_file_name("../../.bashrc"), ".bashrc");
+        assert_eq!(sanitize_file_name("C:\\Users\\me\\notes.txt"), "notes.txt");
+        assert_eq!(sanitize_file_name("bad\nname.txt"), "bad_name.txt");
+        assert_eq!(sanitize_file_name(".."), UNNAMED_FILE);
+        assert_eq!(sanitize_file_name("dir/"), UNNAMED_FILE);
+    }
+
+    #[tokio::test]
+    async fn test_create_download_file_collisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+
+        let first = create_download_file(dir, "report.pdf").await.unwrap();
+        assert_eq!(first, dir.join("report.pdf"));
+        assert!(first.exists());
+
+        // The created file claims the name, even though it is still empty
+        let second = create_download_file(dir, "report.pdf").await.unwrap();
+        assert_eq!(second, dir.join("report (1).pdf"));
+
+        assert_eq!(
+            create_download_file(dir, "report.pdf").await.unwrap(),
+            dir.join("report (2).pdf")
+        );
+
+        // Names without an extension get the suffix at the end
+        std::fs::write(dir.join("README"), b"").unwrap();
+        assert_eq!(
+            create_download_file(dir, "README").await.unwrap(),
+            dir.join("README (1)")
+        );
+
+        // Traversal attempts stay inside the directory
+        assert_eq!(
+            create_download_file(dir, "../report.pdf").await.unwrap(),
+            dir.join("report (3).pdf")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_text_and_file_dispatch_events() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut plugin = SharePlugin::new().with_events(tx);
+        let mut device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+
+        let text = Packet::new("cconnect.share.request", json!({ "text": "hello" }));
+        plugin.handle_packet(&text, &mut device).await.unwrap();
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            ShareEvent::TextReceived {
+                device_id: device.id().to_string(),
+                text: "hello".to_string(),
+            }
+        );
+
+        let url = Packet::new(
+            "kdeconnect.share.request",
+            json!({ "url": "https://example.com" }),
+        );
+        plugin.handle_packet(&url, &mut device).await.unwrap();
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            ShareEvent::UrlReceived { url, .. } if url == "https://example.com"
+        ));
+
+        // A file share is recorded as a file, never reported as text
+        let file = Packet::new(
+            "cconnect.share.request",
+            json!({ "filename": "notes.txt", "text": "ignored" }),
+        )
+        .with_payload_size(5);
+        plugin.handle_packet(&file, &mut device).await.unwrap();
+        assert!(rx.try_recv().is_err());
+
+        let shares = plugin.get_all_shares().await;
+        assert!(matches!(shares[2].content, ShareContent::File(_)));
+    }
+
+    #[tokio::test]
+    async fn test_send_file_attaches_payload() {
+        use tokio::io::AsyncReadExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hello.txt");
+        std::fs::write(&path, b"hello world").unwrap();
+
+        let mut plugin = SharePlugin::new();
+        let device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+
+        let mut packet = plugin.send_file(&path).await.unwrap();
+        assert_eq!(packet.packet_type, "cconnect.share.request");
+        assert_eq!(packet.body["filename"], "hello.txt");
+        assert_eq!(
+            packet.body["payloadHash"],
+            blake3::hash(b"hello world").to_hex().as_str()
+        );
+        assert_eq!(packet.payload_size, Some(11));
+
+        let mut payload = packet.take_payload().unwrap();
+        assert_eq!(payload.size(), 11);
+        let mut data = Vec::new();
+        payload.read_to_end(&mut data).await.unwrap();
+        assert_eq!(data, b"hello world");
+
+        let outgoing = plugin.get_outgoing_shares().await;
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing[0].device_id, device.id());
+    }
+
+    #[test]
+    fn test_resume_start_checks_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("video.mp4");
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        let prefix = blake3::hash(b"01234").to_hex().to_string();
+        assert_eq!(resume_start(&path, 5, &prefix), 5);
+        assert_eq!(resume_start(&path, 5, "not the prefix"), 0);
+        assert_eq!(resume_start(&path, 20, &prefix), 0);
+        assert_eq!(resume_start(&path, 0, ""), 0);
+    }
+
+    #[tokio::test]
+    async fn test_resume_request_sends_remainder() {
+        use tokio::io::AsyncReadExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("video.mp4");
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        let mut plugin = SharePlugin::new();
+        let mut device = create_test_device();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        plugin.init(&device, tx).await.unwrap();
+
+        let sent = plugin.send_file(&path).await.unwrap();
+        let transfer_id = sent.id.to_string();
+
+        let prefix = blake3::hash(b"01234").to_hex().to_string();
+        let request = create_resume_packet(&transfer_id, 5, &prefix, 1);
+        plugin.handle_packet(&request, &mut device).await.unwrap();
+
+        let (_, mut packet) = rx.recv().await.unwrap();
+        assert_eq!(packet.packet_type, "cconnect.share.request");
+        assert_eq!(packet.body["transferId"], transfer_id);
+        assert_eq!(packet.body["resumeOffset"], 5);
+        assert_eq!(packet.body["resumeSegment"], 1);
+        assert_eq!(packet.payload_size, Some(5));
+
+        let mut data = Vec::new();
+        let mut payload = packet.take_payload().unwrap();
+        payload.read_to_end(&mut data).await.unwrap();
+        assert_eq!(data, b"56789");
+
+        // A prefix that does not match gets the whole file again
+        let request = create_resume_packet(&transfer_id, 5, "mismatch", 2);
+        plugin.handle_packet(&request, &mut device).await.unwrap();
+        let (_, packet) = rx.recv().await.unwrap();
+        assert_eq!(packet.body["resumeOffset"], 0);
+        assert_eq!(packet.body["resumeSegment"], 2);
+        assert_eq!(packet.payload_size, Some(10));
+    }
+
+    #[tokio::test]
+    async fn test_outgoing_files_pruned() {
+        let plugin = SharePlugin::new();
+        let info = FileShareInfo {
+            filename: "video.mp4".to_string(),
+            size: 10,
+            creation_time: None,
+            last_modified: None,
+            open: false,
+            hash: None,
+        };
+        for id in ["done", "failed"] {
+            plugin
+                .register_outgoing_file(id, "/tmp/video.mp4", info.clone())
+                .await;
+        }
+
+        // Completed or cancelled transfers are dropped right away
+        plugin.finish_outgoing_file("done").await;
+        assert!(!plugin.outgoing_files.read().await.contains_key("done"));
+
+        // Failed ones stay resumable until the window has passed
+        plugin.fail_outgoing_file("failed").await;
+        assert!(plugin.outgoing_files.read().await.contains_key("failed"));
+
+        plugin
+            .outgoing_files
+            .write()
+            .await
+            .get_mut("failed")
+            .unwrap()
+            .expires = Some(Instant::now());
+        plugin
+            .register_outgoing_file("next", "/tmp/video.mp4", info)
+            .await;
+        let outgoing = plugin.outgoing_files.read().await;
+        assert!(!outgoing.contains_key("failed"));
+        assert!(outgoing.contains_key("next"));
+    }
+
+    #[tokio::test]
+    async fn test_start_requests_resume_of_kept_transfer() {
+        let dir = tempfile::tempdir().unwrap();
+        let partial = dir.path().join("video.mp4");
+        std::fs::write(&partial, b"01234").unwrap();
+
+        let device = create_test_device();
+        let recovery = Arc::new(RecoveryManager::new(dir.path()));
+        let mut state = TransferState::new(
+            "1700000000000".to_string(),
+            device.id().to_string(),
+            "video.mp4".to_string(),
+            partial.clone(),
+            10,
+        );
+        state.update_progress(5);
+        // The first download was segment 0
+        state.segments = 1;
+        recovery.register_transfer(state).await.unwrap();
+
+        let mut plugin = SharePlugin::new().with_recovery(recovery);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(100);
+        plugin.init(&device, tx).await.unwrap();
+        plugin.start().await.unwrap();
+
+        let (device_id, packet) = rx.recv().await.unwrap();
+        assert_eq!(device_id, device.id());
+        assert_eq!(packet.packet_type, SHARE_RESUME_PACKET);
+        assert_eq!(packet.body["transferId"], "1700000000000");
+        assert_eq!(packet.body["offset"], 5);
+        assert_eq!(
+            packet.body["prefixHash"],
+            blake3::hash(b"01234").to_hex().as_str()
+        );
+        assert_eq!(packet.body["segment"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_resumed_share_continues_recorded_transfer() {
+        let dir = tempfile::tempdir().unwrap();
+        let partial = dir.path().join("video.mp4");
+        std::fs::write(&partial, b"01234").unwrap();
+
+        let device = create_test_device();
+        let recovery = Arc::new(RecoveryManager::new(dir.path()));
+        let mut state = TransferState::new(
+            "1700000000000".to_string(),
+            device.id().to_string(),
+            "video.mp4".to_string(),
+            partial.clone(),
+            10,
+        );
+        state.hash = Some("abc123".to_string());
+        state.segments = 1;
+        recovery.register_transfer(state).await.unwrap();
+        let plugin = SharePlugin::new().with_recovery(recovery.clone());
+
+        let packet = Packet::new(
+            "cconnect.share.request",
+            json!({
+                "filename": "video.mp4",
+                "transferId": "1700000000000",
+                "resumeOffset": 5,
+                "resumeSegment": 1,
+            }),
+        )
+        .with_payload_size(5);
+        let mut download = Download {
+            transfer_id: packet.id.to_string(),
+            device_id: device.id().to_string(),
+            device_name: device.name().to_string(),
+            filename: "video.mp4".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 1739,
+            size: 5,
+            hash: None,
+            resume: None,
+        };
+
+        assert!(
+            plugin
+                .resume_download(&mut download, "1700000000000", &packet)
+                .await
+        );
+        assert_eq!(download.transfer_id, "1700000000000");
+        assert_eq!(download.size, 10);
+        assert_eq!(download.hash.as_deref(), Some("abc123"));
+        assert_eq!(download.resume, Some((partial, 5)));
+
+        // A duplicate answer can not write while the segment is downloading
+        download.size = 5;
+        assert!(
+            !plugin
+                .resume_download(&mut download, "1700000000000", &packet)
+                .await
+        );
+        recovery.end_segment("1700000000000").await;
+
+        // More than was kept can not be resumed
+        download.size = 2;
+        let packet = packet
+            .with_body_field("resumeOffset", 8)
+            .with_body_field("resumeSegment", 2);
+        assert!(
+            !plugin
+                .resume_download(&mut download, "1700000000000", &packet)
+                .await
+        );
+
+        // Nor can an answer without a segment number
+        let mut packet = packet.with_body_field("resumeOffset", 5);
+        packet.body.as_object_mut().unwrap().remove("resumeSegment");
+        download.size = 5;
+        assert!(
+            !plugin
+                .resume_download(&mut download, "1700000000000", &packet)
+                .await
+        );
+
+        let state = recovery.get_transfer_state("1700000000000").await.unwrap();
+        assert_eq!(state.segments, 2);
+        assert_eq!(state.rejected_segments, 2);
+    }
+
+    #[tokio::test]
+    async fn test_sequenced_segments_reassemble_intact() {
+        use crate::payload::{PayloadClient, PayloadServer};
+
+        let dir = tempfile::tempdir().unwrap();
+        let data = b"0123456789";
+        let first = dir.path().join("first.bin");
+        let rest = dir.path().join("rest.bin");
+        std::fs::write(&first, &data[..5]).unwrap();
+        std::fs::write(&rest, &data[5..]).unwrap();
+        let save_path = dir.path().join("video.mp4");
+        let hash = blake3::hash(data).to_hex().to_string();
+
+        let device = create_test_device();
+        let recovery = Arc::new(RecoveryManager::new(dir.path()));
+        let state = TransferState::new(
+            "1700000000000".to_string(),
+            device.id().to_string(),
+            "video.mp4".to_string(),
+            save_path.clone(),
+            10,
+        );
+        recovery.register_transfer(state).await.unwrap();
+        let plugin = SharePlugin::new().with_recovery(recovery.clone());
+
+        // Segment 0 drops after the first half
+        recovery.begin_segment("1700000000000", 0, 0).await.unwrap();
+        let server = PayloadServer::new().await.unwrap();
+        let port = server.port();
+        tokio::spawn(server.send_file(first));
+        assert!(PayloadClient::new("127.0.0.1", port)
+            .await
+            .unwrap()
+            .resumable()
+            .receive_file(&save_path, 10)
+            .await
+            .is_err());
+        recovery.end_segment("1700000000000").await;
+
+        let packet = Packet::new(
+            "cconnect.share.request",
+            json!({
+                "filename": "video.mp4",
+                "transferId": "1700000000000",
+                "resumeOffset": 5,
+                "resumeSegment": 1,
+            }),
+        )
+        .with_payload_size(5);
+        let mut download = Download {
+            transfer_id: packet.id.to_string(),
+            device_id: device.id().to_string(),
+            device_name: device.name().to_string(),
+            filename: "video.mp4".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 1739,
+            size: 5,
+            hash: Some(hash.clone()),
+            resume: None,
+        };
+        assert!(
+            plugin
+                .resume_download(&mut download, "1700000000000", &packet)
+                .await
+        );
+
+        // A stale copy of the same answer is refused while segment 1 is written
+        let mut stale = Download {
+            transfer_id: packet.id.to_string(),
+            device_id: device.id().to_string(),
+            device_name: device.name().to_string(),
+            filename: "video.mp4".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 1739,
+            size: 5,
+            hash: None,
+            resume: None,
+        };
+        assert!(
+            !plugin
+                .resume_download(&mut stale, "1700000000000", &packet)
+                .await
+        );
+
+        let server = PayloadServer::new().await.unwrap();
+        let port = server.port();
+        tokio::spawn(server.send_file(rest));
+        PayloadClient::new("127.0.0.1", port)
+            .await
+            .unwrap()
+            .with_expected_hash(&hash)
+            .resumable()
+            .resume_file(&save_path, 5, download.size)
+            .await
+            .unwrap();
+        recovery.end_segment("1700000000000").await;
+
+        assert_eq!(std::fs::read(&save_path).unwrap(), data);
+        let state = recovery.get_transfer_state("1700000000000").await.unwrap();
+        assert_eq!(state.segments, 2);
+        assert_eq!(state.rejected_segments, 1);
+    }
+
+    #[tokio::test]
+    async fn test_factory_passes_event_channel() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let factory = SharePluginFactory::new().with_events(tx);
+        let mut plugin = factory.create();
+        let mut device = create_test_device();
+        plugin
+            .init(&device, tokio::sync::mpsc::channel(100).0)
+            .await
+            .unwrap();
+
+        let packet = Packet::new("cconnect.share.request", json!({ "text": "hi" }));
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            ShareEvent::TextReceived { .. }
+        ));
+    }
+
+    #[test]
+    fn test_download_progress_stops_when_cancelled() {
+        let download = Download {
+            transfer_id: "t1".to_string(),
+            device_id: "dev".to_string(),
+            device_name: "Phone".to_string(),
+            filename: "a.bin".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 1739,
+            size: 100,
+            hash: None,
+            resume: None,
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let cancel = CancelHandle::new();
+        let callback = download.progress_callback(cancel.clone(), Some(tx));
+
+        assert!(callback(10, 100));
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            ShareEvent::Progress { progress, .. } if progress.transfer_id == "t1"
+        ));
+
+        cancel.cancel();
+        assert!(cancel.is_cancelled());
+        assert!(!callback(100, 100));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_cancel_handle_identity() {
+        let handle = CancelHandle::new();
+        assert_eq!(handle, handle.clone());
+        assert_ne!(handle, CancelHandle::new());
+        handle.flag().store(true, Ordering::SeqCst);
+        assert!(handle.is_cancelled());
+    }
 }
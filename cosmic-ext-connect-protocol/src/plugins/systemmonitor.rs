@@ -1,7 +1,8 @@
 //! System Monitor Plugin
 //!
 //! Provides real-time system monitoring capabilities for remote desktop machines.
-//! Allows viewing CPU, memory, disk, network statistics, and process lists.
+//! Allows viewing CPU, memory, disk, network, GPU and temperature statistics,
+//! and process lists.
 //!
 //! ## Protocol
 //!
@@ -9,10 +10,12 @@
 //! - `cconnect.systemmonitor.request` - Request system statistics
 //! - `cconnect.systemmonitor.stats` - System statistics response
 //! - `cconnect.systemmonitor.processes` - Process list response
+//! - `cconnect.systemmonitor.gpu` - GPU and temperature response
 //!
 //! **Capabilities**:
 //! - Incoming: `cconnect.systemmonitor.request`
-//! - Outgoing: `cconnect.systemmonitor.stats`, `cconnect.systemmonitor.processes`
+//! - Outgoing: `cconnect.systemmonitor.stats`, `cconnect.systemmonitor.processes`,
+//!   `cconnect.systemmonitor.gpu`
 //!
 //! ## Packet Formats
 //!
@@ -58,11 +61,33 @@
 //!             "bytesReceived": 1234567890,
 //!             "bytesSent": 987654321
 //!         },
+//!         "gpu": [
+//!             {
+//!                 "vendor": "AMD",
+//!                 "name": "card0",
+//!                 "usagePercent": 23.0,
+//!                 "vramTotal": 8573157376,
+//!                 "vramUsed": 1073741824,
+//!                 "temperatureC": 52.0
+//!             }
+//!         ],
+//!         "thermals": [
+//!             {
+//!                 "chip": "k10temp",
+//!                 "label": "Tctl",
+//!                 "temperatureC": 61.5,
+//!                 "criticalC": null
+//!             }
+//!         ],
 //!         "uptime": 86400
 //!     }
 //! }
 //! ```
 //!
+//! `gpu` and `thermals` are empty when no data is available. A request with
+//! `"requestType": "gpu"` is answered with a `cconnect.systemmonitor.gpu`
+//! packet carrying only these two fields.
+//!
 //! ### Request Process List
 //!
 //! ```json
@@ -71,11 +96,15 @@
 //!     "type": "cconnect.systemmonitor.request",
 //!     "body": {
 //!         "requestType": "processes",
-//!         "limit": 10
+//!         "limit": 10,
+//!         "sortBy": "cpu"
 //!     }
 //! }
 //! ```
 //!
+//! `sortBy` is one of `cpu` (default), `memory` or `io` (bytes read plus
+//! bytes written).
+//!
 //! ### Process List Response
 //!
 //! ```json
@@ -88,7 +117,10 @@
 //!                 "pid": 1234,
 //!                 "name": "firefox",
 //!                 "cpu": 12.5,
-//!                 "memory": 1073741824
+//!                 "memory": 1073741824,
+//!                 "readBytes": 52428800,
+//!                 "writeBytes": 1048576,
+//!                 "openFds": 142
 //!             }
 //!         ]
 //!     }
@@ -106,19 +138,44 @@
 //! ## Platform Support
 //!
 //! - **Linux**: Full support via /proc filesystem
-//! - **macOS**: Limited support (minimal stats)
+//! - **macOS**: System statistics via Mach host statistics and sysctl; no process list
 //! - **Windows**: Limited support (minimal stats)
 
+use crate::cpu_pool;
 use crate::{Device, Packet, Result};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use super::{Plugin, PluginFactory};
 
+/// Default time between the two CPU counter reads of a measurement
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Oldest previous CPU reading still used as the start of a measurement
+const MAX_SAMPLE_AGE: Duration = Duration::from_secs(5);
+
+/// Longest wait for `nvidia-smi` before reporting no NVIDIA GPUs
+#[cfg(target_os = "linux")]
+const NVIDIA_SMI_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Hardware monitoring sensors exported by the kernel
+#[cfg(target_os = "linux")]
+const HWMON_ROOT: &str = "/sys/class/hwmon";
+
+/// DRM devices, one `cardN` directory per GPU
+#[cfg(target_os = "linux")]
+const DRM_ROOT: &str = "/sys/class/drm";
+
+/// Process information, one `<pid>` directory per process
+#[cfg(target_os = "linux")]
+const PROC_ROOT: &str = "/proc";
+
 /// CPU statistics
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CpuStats {
@@ -170,6 +227,43 @@ pub struct NetworkStats {
     pub bytes_sent: u64,
 }
 
+/// Statistics of one GPU
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GpuStats {
+    /// Vendor name (`AMD`, `Intel`, `NVIDIA`, or the PCI vendor ID)
+    pub vendor: String,
+    /// Model name, or the DRM card name if unknown
+    #[serde(default)]
+    pub name: String,
+    /// GPU load percentage, if the driver reports it
+    #[serde(rename = "usagePercent", default)]
+    pub usage_percent: Option<f64>,
+    /// Total video memory in bytes (0 if unknown)
+    #[serde(rename = "vramTotal", default)]
+    pub vram_total: u64,
+    /// Used video memory in bytes (0 if unknown)
+    #[serde(rename = "vramUsed", default)]
+    pub vram_used: u64,
+    /// GPU temperature in degrees Celsius, if available
+    #[serde(rename = "temperatureC", default)]
+    pub temperature_c: Option<f64>,
+}
+
+/// Temperature of one hardware sensor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThermalZone {
+    /// Sensor chip name, e.g. `coretemp` or `k10temp`
+    pub chip: String,
+    /// Sensor label, e.g. `Package id 0`, or `tempN` if unlabelled
+    pub label: String,
+    /// Current temperature in degrees Celsius
+    #[serde(rename = "temperatureC")]
+    pub temperature_c: f64,
+    /// Critical temperature in degrees Celsius, if reported
+    #[serde(rename = "criticalC", default)]
+    pub critical_c: Option<f64>,
+}
+
 /// Process information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
@@ -181,6 +275,35 @@ pub struct ProcessInfo {
     pub cpu: f64,
     /// Memory usage in bytes
     pub memory: u64,
+    /// Bytes read from storage
+    #[serde(rename = "readBytes", default)]
+    pub read_bytes: u64,
+    /// Bytes written to storage
+    #[serde(rename = "writeBytes", default)]
+    pub write_bytes: u64,
+    /// Number of open file descriptors
+    #[serde(rename = "openFds", default)]
+    pub open_fds: u64,
+}
+
+impl ProcessInfo {
+    /// Total bytes read and written
+    pub fn io_bytes(&self) -> u64 {
+        self.read_bytes.saturating_add(self.write_bytes)
+    }
+}
+
+/// Order of a process list, highest usage first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessSort {
+    /// CPU usage
+    #[default]
+    Cpu,
+    /// Resident memory
+    Memory,
+    /// Bytes read plus bytes written
+    Io,
 }
 
 /// Complete system statistics
@@ -194,6 +317,12 @@ pub struct SystemStats {
     pub disk: Vec<DiskStats>,
     /// Network statistics
     pub network: NetworkStats,
+    /// GPU statistics, empty if unavailable
+    #[serde(default)]
+    pub gpu: Vec<GpuStats>,
+    /// Temperature sensors, empty if unavailable
+    #[serde(default)]
+    pub thermals: Vec<ThermalZone>,
     /// System uptime in seconds
     pub uptime: u64,
 }
@@ -224,6 +353,26 @@ pub struct SystemMonitorPlugin {
 
     /// Packet sender for response packets
     packet_sender: Option<tokio::sync::mpsc::Sender<(String, Packet)>>,
+
+    /// Time between the two CPU counter reads of a measurement
+    sample_interval: Duration,
+
+    /// Previous per-core CPU counters, the start of the next measurement
+    last_cpu_sample: Arc<Mutex<Option<CpuSample>>>,
+}
+
+/// Cumulative CPU time of one core, in jiffies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct CpuTimes {
+    active: u64,
+    idle: u64,
+}
+
+/// Per-core CPU counters read at one point in time
+#[derive(Debug, Clone)]
+struct CpuSample {
+    taken: Instant,
+    cores: Vec<CpuTimes>,
 }
 
 impl SystemMonitorPlugin {
@@ -235,9 +384,25 @@ impl SystemMonitorPlugin {
             stats: Arc::new(RwLock::new(SystemStats::default())),
             processes: Arc::new(RwLock::new(Vec::new())),
             packet_sender: None,
+            sample_interval: DEFAULT_SAMPLE_INTERVAL,
+            last_cpu_sample: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Time between the two CPU counter reads of a measurement
+    pub fn sample_interval(&self) -> Duration {
+        self.sample_interval
+    }
+
+    /// Set the time between the two CPU counter reads of a measurement
+    ///
+    /// CPU usage is the share of busy time between two reads of the kernel's
+    /// counters. A request waits this long unless a recent enough reading
+    /// from an earlier request can serve as the first read.
+    pub fn set_sample_interval(&mut self, interval: Duration) {
+        self.sample_interval = interval;
+    }
+
     /// Get cached system statistics
     ///
     /// Returns the most recently collected system stats.
@@ -258,6 +423,16 @@ impl SystemMonitorPlugin {
         self.get_stats().memory
     }
 
+    /// Get GPU statistics
+    pub fn get_gpu_stats(&self) -> Vec<GpuStats> {
+        self.get_stats().gpu
+    }
+
+    /// Get temperature sensor readings
+    pub fn get_thermals(&self) -> Vec<ThermalZone> {
+        self.get_stats().thermals
+    }
+
     /// Get disk statistics for all mount points
     pub fn get_disk_stats(&self) -> Vec<DiskStats> {
         self.get_stats().disk
@@ -308,6 +483,26 @@ impl SystemMonitorPlugin {
         )
     }
 
+    /// Create a process list request packet sorted by `sort_by`
+    pub fn create_sorted_processes_request(&self, limit: usize, sort_by: ProcessSort) -> Packet {
+        Packet::new(
+            "cconnect.systemmonitor.request",
+            json!({
+                "requestType": "processes",
+                "limit": limit,
+                "sortBy": sort_by
+            }),
+        )
+    }
+
+    /// Create a GPU and temperature request packet
+    pub fn create_gpu_request(&self) -> Packet {
+        Packet::new(
+            "cconnect.systemmonitor.request",
+            json!({ "requestType": "gpu" }),
+        )
+    }
+
     /// Update cached stats
     fn update_stats(&self, stats: SystemStats) {
         if let Ok(mut guard) = self.stats.try_write() {
@@ -315,6 +510,14 @@ impl SystemMonitorPlugin {
         }
     }
 
+    /// Update cached GPU and temperature statistics
+    fn update_gpu_info(&self, gpu: Vec<GpuStats>, thermals: Vec<ThermalZone>) {
+        if let Ok(mut guard) = self.stats.try_write() {
+            guard.gpu = gpu;
+            guard.thermals = thermals;
+        }
+    }
+
     /// Update cached processes
     fn update_processes(&self, processes: Vec<ProcessInfo>) {
         if let Ok(mut guard) = self.processes.try_write() {
@@ -323,82 +526,176 @@ impl SystemMonitorPlugin {
     }
 
     /// Collect current system statistics
-    fn collect_system_stats(&self) -> serde_json::Value {
+    async fn collect_system_stats(&self) -> serde_json::Value {
         #[cfg(target_os = "linux")]
         {
             json!({
-                "cpu": self.get_cpu_usage(),
+                "cpu": self.get_cpu_usage().await,
                 "memory": self.get_memory_info(),
                 "disk": self.get_disk_info(),
                 "network": self.get_network_info(),
+                "gpu": Self::collect_gpu_stats().await,
+                "thermals": Self::read_hwmon_thermals(Path::new(HWMON_ROOT)),
                 "uptime": self.get_uptime(),
             })
         }
 
-        #[cfg(not(target_os = "linux"))]
+        #[cfg(target_os = "macos")]
+        {
+            json!({
+                "cpu": self.get_cpu_usage().await,
+                "memory": macos::memory_info(),
+                "disk": macos::disk_info(),
+                "network": macos::network_info(),
+                "gpu": [],
+                "thermals": [],
+                "uptime": macos::uptime(),
+            })
+        }
+
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
         {
             json!({
                 "cpu": { "usage": 0.0, "cores": [] },
                 "memory": { "total": 0, "used": 0, "available": 0, "usagePercent": 0.0 },
                 "disk": [],
                 "network": { "bytesReceived": 0, "bytesSent": 0 },
+                "gpu": [],
+                "thermals": [],
                 "uptime": 0,
             })
         }
     }
 
-    #[cfg(target_os = "linux")]
-    fn get_cpu_usage(&self) -> serde_json::Value {
-        use std::fs;
+    /// Collect GPU and temperature statistics
+    async fn collect_gpu_info() -> serde_json::Value {
+        #[cfg(target_os = "linux")]
+        {
+            json!({
+                "gpu": Self::collect_gpu_stats().await,
+                "thermals": Self::read_hwmon_thermals(Path::new(HWMON_ROOT)),
+            })
+        }
 
-        let Ok(stat_content) = fs::read_to_string("/proc/stat") else {
-            return json!({ "usage": 0.0, "cores": [] });
-        };
+        #[cfg(not(target_os = "linux"))]
+        {
+            json!({ "gpu": [], "thermals": [] })
+        }
+    }
 
-        let mut cores = Vec::new();
-        let mut total_usage = 0.0;
+    /// CPU usage between two reads of the kernel's per-core counters
+    ///
+    /// The previous request's reading is used as the first read if it is at
+    /// least one sample interval and at most [`MAX_SAMPLE_AGE`] old;
+    /// otherwise the counters are read twice, one sample interval apart.
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    async fn get_cpu_usage(&self) -> serde_json::Value {
+        #[cfg(target_os = "linux")]
+        fn read_cpu_times() -> Option<Vec<CpuTimes>> {
+            let content = std::fs::read_to_string("/proc/stat").ok()?;
+            Some(SystemMonitorPlugin::parse_proc_stat(&content))
+        }
+        #[cfg(target_os = "macos")]
+        use macos::cpu_times as read_cpu_times;
 
-        for line in stat_content.lines() {
-            if line.starts_with("cpu") && !line.starts_with("cpu ") {
-                if let Some(usage) = self.parse_cpu_line(line) {
-                    cores.push(usage);
-                    total_usage += usage;
-                }
+        let previous = self
+            .last_cpu_sample
+            .lock()
+            .ok()
+            .and_then(|mut last| last.take())
+            .filter(|sample| {
+                let age = sample.taken.elapsed();
+                age >= self.sample_interval && age <= MAX_SAMPLE_AGE
+            });
+
+        let before = match previous {
+            Some(sample) => sample.cores,
+            None => {
+                let Some(cores) = read_cpu_times() else {
+                    return json!({ "usage": 0.0, "cores": [] });
+                };
+                tokio::time::sleep(self.sample_interval).await;
+                cores
             }
-        }
+        };
 
-        let avg_usage = if cores.is_empty() {
-            0.0
-        } else {
-            total_usage / cores.len() as f64
+        let Some(after) = read_cpu_times() else {
+            return json!({ "usage": 0.0, "cores": [] });
         };
+        let usage = Self::cpu_usage_between(&before, &after);
 
-        json!({
-            "usage": (avg_usage * 100.0).round() / 100.0,
-            "cores": cores.iter().map(|u| (u * 100.0).round() / 100.0).collect::<Vec<f64>>(),
-        })
+        if let Ok(mut last) = self.last_cpu_sample.lock() {
+            *last = Some(CpuSample {
+                taken: Instant::now(),
+                cores: after,
+            });
+        }
+
+        usage
     }
 
-    #[cfg(target_os = "linux")]
-    fn parse_cpu_line(&self, line: &str) -> Option<f64> {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 5 {
+    /// Per-core counters from the `cpuN` lines of `/proc/stat`
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn parse_proc_stat(content: &str) -> Vec<CpuTimes> {
+        content
+            .lines()
+            .filter(|line| line.starts_with("cpu") && !line.starts_with("cpu "))
+            .filter_map(Self::parse_cpu_line)
+            .collect()
+    }
+
+    /// Busy and idle jiffies of one `cpuN` line
+    ///
+    /// Idle time includes iowait; busy time is user, nice, system, irq,
+    /// softirq and steal.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn parse_cpu_line(line: &str) -> Option<CpuTimes> {
+        let values: Vec<u64> = line
+            .split_whitespace()
+            .skip(1)
+            .take(8)
+            .map(|v| v.parse().ok())
+            .collect::<Option<_>>()?;
+        if values.len() < 4 {
             return None;
         }
 
-        let user: u64 = parts.get(1)?.parse().ok()?;
-        let nice: u64 = parts.get(2)?.parse().ok()?;
-        let system: u64 = parts.get(3)?.parse().ok()?;
-        let idle: u64 = parts.get(4)?.parse().ok()?;
+        let field = |i: usize| values.get(i).copied().unwrap_or(0);
+        Some(CpuTimes {
+            active: field(0) + field(1) + field(2) + field(5) + field(6) + field(7),
+            idle: field(3) + field(4),
+        })
+    }
 
-        let total = user + nice + system + idle;
-        let active = user + nice + system;
+    /// Overall and per-core usage percentages between two readings
+    fn cpu_usage_between(before: &[CpuTimes], after: &[CpuTimes]) -> serde_json::Value {
+        let round = |value: f64| (value * 100.0).round() / 100.0;
+        let percent = |active: u64, total: u64| {
+            if total == 0 {
+                0.0
+            } else {
+                active as f64 / total as f64 * 100.0
+            }
+        };
 
-        if total == 0 {
-            return Some(0.0);
-        }
+        let mut active_total = 0;
+        let mut ticks_total = 0;
+        let cores: Vec<f64> = before
+            .iter()
+            .zip(after)
+            .map(|(before, after)| {
+                let active = after.active.saturating_sub(before.active);
+                let ticks = active + after.idle.saturating_sub(before.idle);
+                active_total += active;
+                ticks_total += ticks;
+                round(percent(active, ticks))
+            })
+            .collect();
 
-        Some(active as f64 / total as f64)
+        json!({
+            "usage": round(percent(active_total, ticks_total)),
+            "cores": cores,
+        })
     }
 
     #[cfg(target_os = "linux")]
@@ -549,34 +846,194 @@ impl SystemMonitorPlugin {
         0
     }
 
-    /// Collect top processes by resource usage
-    fn collect_process_list(&self, limit: usize) -> serde_json::Value {
-        #[cfg(target_os = "linux")]
-        {
-            use std::fs;
+    /// Entries of a sysfs directory, sorted by path
+    fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        entries.sort();
+        entries
+    }
 
-            let Ok(entries) = fs::read_dir("/proc") else {
-                return json!({ "processes": [] });
-            };
+    fn read_sysfs_string(path: &Path) -> Option<String> {
+        let value = std::fs::read_to_string(path).ok()?;
+        let value = value.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    }
 
-            let mut processes: Vec<_> = entries
-                .flatten()
-                .filter_map(|entry| {
-                    let file_name = entry.file_name().into_string().ok()?;
-                    let pid = file_name.parse::<u32>().ok()?;
-                    self.get_process_info(pid)
+    fn read_sysfs_number(path: &Path) -> Option<f64> {
+        Self::read_sysfs_string(path)?.parse().ok()
+    }
+
+    /// Temperature sensors of every hwmon chip below `root`
+    ///
+    /// Reads each `tempN_input` in millidegrees Celsius, with its optional
+    /// `tempN_label` and `tempN_crit`.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn read_hwmon_thermals(root: &Path) -> Vec<ThermalZone> {
+        let mut thermals = Vec::new();
+
+        for chip_dir in Self::sorted_entries(root) {
+            let chip = Self::read_sysfs_string(&chip_dir.join("name")).unwrap_or_else(|| {
+                chip_dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+
+            let mut sensors: Vec<(u32, PathBuf)> = Self::sorted_entries(&chip_dir)
+                .into_iter()
+                .filter_map(|path| {
+                    let name = path.file_name()?.to_str()?;
+                    let index = name.strip_prefix("temp")?.strip_suffix("_input")?;
+                    Some((index.parse().ok()?, path))
                 })
                 .collect();
+            sensors.sort_by_key(|(index, _)| *index);
+
+            for (index, input) in sensors {
+                let Some(millidegrees) = Self::read_sysfs_number(&input) else {
+                    continue;
+                };
+                let label = Self::read_sysfs_string(&chip_dir.join(format!("temp{}_label", index)))
+                    .unwrap_or_else(|| format!("temp{}", index));
+                let critical_c =
+                    Self::read_sysfs_number(&chip_dir.join(format!("temp{}_crit", index)))
+                        .map(|crit| crit / 1000.0);
+
+                thermals.push(ThermalZone {
+                    chip: chip.clone(),
+                    label,
+                    temperature_c: millidegrees / 1000.0,
+                    critical_c,
+                });
+            }
+        }
 
-            // Sort by CPU usage (descending)
-            processes.sort_by(|a, b| {
-                let cpu_a = a["cpu"].as_f64().unwrap_or(0.0);
-                let cpu_b = b["cpu"].as_f64().unwrap_or(0.0);
-                cpu_b
-                    .partial_cmp(&cpu_a)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
+        thermals
+    }
+
+    /// GPUs below a DRM class directory such as `/sys/class/drm`
+    ///
+    /// Load and VRAM are reported by the amdgpu driver; other drivers only
+    /// provide the vendor and, where exposed through hwmon, the temperature.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn read_drm_gpus(root: &Path) -> Vec<GpuStats> {
+        Self::sorted_entries(root)
+            .into_iter()
+            .filter_map(|card| {
+                let name = card.file_name()?.to_str()?.to_string();
+                let index = name.strip_prefix("card")?;
+                if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                }
+
+                let device = card.join("device");
+                let vendor_id = Self::read_sysfs_string(&device.join("vendor"))?;
+                let vendor = match vendor_id.as_str() {
+                    "0x1002" => "AMD".to_string(),
+                    "0x8086" => "Intel".to_string(),
+                    "0x10de" => "NVIDIA".to_string(),
+                    _ => vendor_id,
+                };
+                let temperature_c = Self::read_hwmon_thermals(&device.join("hwmon"))
+                    .first()
+                    .map(|thermal| thermal.temperature_c);
+
+                Some(GpuStats {
+                    vendor,
+                    name,
+                    usage_percent: Self::read_sysfs_number(&device.join("gpu_busy_percent")),
+                    vram_total: Self::read_sysfs_number(&device.join("mem_info_vram_total"))
+                        .unwrap_or(0.0) as u64,
+                    vram_used: Self::read_sysfs_number(&device.join("mem_info_vram_used"))
+                        .unwrap_or(0.0) as u64,
+                    temperature_c,
+                })
+            })
+            .collect()
+    }
+
+    /// Parse `nvidia-smi --query-gpu=name,utilization.gpu,memory.total,memory.used,
+    /// temperature.gpu --format=csv,noheader,nounits` output
+    ///
+    /// Memory is reported in MiB; fields the driver cannot read are `[N/A]`.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn parse_nvidia_smi(output: &str) -> Vec<GpuStats> {
+        const MIB: f64 = 1024.0 * 1024.0;
+
+        output
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+                if fields.len() < 5 || fields[0].is_empty() {
+                    return None;
+                }
+                let number = |i: usize| fields[i].parse::<f64>().ok();
+
+                Some(GpuStats {
+                    vendor: "NVIDIA".to_string(),
+                    name: fields[0].to_string(),
+                    usage_percent: number(1),
+                    vram_total: number(2).map_or(0, |mib| (mib * MIB) as u64),
+                    vram_used: number(3).map_or(0, |mib| (mib * MIB) as u64),
+                    temperature_c: number(4),
+                })
+            })
+            .collect()
+    }
 
+    /// NVIDIA GPUs as reported by `nvidia-smi`, empty if it is unavailable
+    #[cfg(target_os = "linux")]
+    async fn query_nvidia_smi() -> Vec<GpuStats> {
+        let query = tokio::process::Command::new("nvidia-smi")
+            .args([
+                "--query-gpu=name,utilization.gpu,memory.total,memory.used,temperature.gpu",
+                "--format=csv,noheader,nounits",
+            ])
+            .kill_on_drop(true)
+            .output();
+
+        match tokio::time::timeout(NVIDIA_SMI_TIMEOUT, query).await {
+            Ok(Ok(output)) if output.status.success() => {
+                Self::parse_nvidia_smi(&String::from_utf8_lossy(&output.stdout))
+            }
+            Ok(Ok(output)) => {
+                debug!("nvidia-smi exited with {}", output.status);
+                Vec::new()
+            }
+            Ok(Err(_)) => Vec::new(),
+            Err(_) => {
+                warn!("nvidia-smi timed out");
+                Vec::new()
+            }
+        }
+    }
+
+    /// GPUs from `nvidia-smi` and the DRM devices
+    ///
+    /// NVIDIA cards found by `nvidia-smi` are not listed again from DRM,
+    /// which knows nothing beyond their vendor.
+    #[cfg(target_os = "linux")]
+    async fn collect_gpu_stats() -> Vec<GpuStats> {
+        let mut gpus = Self::query_nvidia_smi().await;
+        let have_nvidia = !gpus.is_empty();
+        gpus.extend(
+            Self::read_drm_gpus(Path::new(DRM_ROOT))
+                .into_iter()
+                .filter(|gpu| !(have_nvidia && gpu.vendor == "NVIDIA")),
+        );
+        gpus
+    }
+
+    /// Collect top processes by resource usage
+    ///
+    /// Blocking; reads every `/proc/<pid>` entry.
+    fn collect_process_list(limit: usize, sort_by: ProcessSort) -> serde_json::Value {
+        #[cfg(target_os = "linux")]
+        {
+            let mut processes = Self::read_processes(Path::new(PROC_ROOT));
+            Self::sort_processes(&mut processes, sort_by);
             processes.truncate(limit);
 
             json!({ "processes": processes })
@@ -584,25 +1041,60 @@ impl SystemMonitorPlugin {
 
         #[cfg(not(target_os = "linux"))]
         {
-            let _ = limit;
+            let _ = (limit, sort_by);
             json!({ "processes": [] })
         }
     }
 
-    #[cfg(target_os = "linux")]
-    fn get_process_info(&self, pid: u32) -> Option<serde_json::Value> {
+    /// Order `processes` by `sort_by`, highest usage first
+    fn sort_processes(processes: &mut [ProcessInfo], sort_by: ProcessSort) {
+        match sort_by {
+            ProcessSort::Cpu => processes.sort_by(|a, b| {
+                b.cpu
+                    .partial_cmp(&a.cpu)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+            ProcessSort::Memory => processes.sort_by(|a, b| b.memory.cmp(&a.memory)),
+            ProcessSort::Io => processes.sort_by(|a, b| b.io_bytes().cmp(&a.io_bytes())),
+        }
+    }
+
+    /// Every process below a procfs root such as `/proc`
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn read_processes(proc_root: &Path) -> Vec<ProcessInfo> {
+        let Ok(entries) = std::fs::read_dir(proc_root) else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let file_name = entry.file_name().into_string().ok()?;
+                let pid = file_name.parse::<u32>().ok()?;
+                Self::read_process_info(proc_root, pid)
+            })
+            .collect()
+    }
+
+    /// Read one process from `<proc_root>/<pid>`
+    ///
+    /// I/O counters and open file descriptors are only readable for our own
+    /// processes unless running as root; they are 0 where unreadable.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn read_process_info(proc_root: &Path, pid: u32) -> Option<ProcessInfo> {
         use std::fs;
 
-        let stat_content = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+        let dir = proc_root.join(pid.to_string());
+        let stat_content = fs::read_to_string(dir.join("stat")).ok()?;
 
         let start = stat_content.find('(')?;
         let end = stat_content.rfind(')')?;
         let name = &stat_content[start + 1..end];
 
-        let stats_part = &stat_content[end + 2..];
+        let stats_part = stat_content.get(end + 2..)?;
         let parts: Vec<&str> = stats_part.split_whitespace().collect();
 
-        let memory = fs::read_to_string(format!("/proc/{pid}/statm"))
+        let memory = fs::read_to_string(dir.join("statm"))
             .ok()
             .and_then(|content| {
                 content
@@ -618,12 +1110,48 @@ impl SystemMonitorPlugin {
         let stime: u64 = parts.get(12)?.parse().ok()?;
         let cpu_percent = ((utime + stime) as f64 / 1000.0).min(100.0);
 
-        Some(json!({
-            "pid": pid,
-            "name": name,
-            "cpu": (cpu_percent * 100.0).round() / 100.0,
-            "memory": memory,
-        }))
+        let (read_bytes, write_bytes) = fs::read_to_string(dir.join("io"))
+            .map(|content| Self::parse_proc_io(&content))
+            .unwrap_or((0, 0));
+        let open_fds = fs::read_dir(dir.join("fd"))
+            .map(|entries| entries.count() as u64)
+            .unwrap_or(0);
+
+        Some(ProcessInfo {
+            pid,
+            name: name.to_string(),
+            cpu: (cpu_percent * 100.0).round() / 100.0,
+            memory,
+            read_bytes,
+            write_bytes,
+            open_fds,
+        })
+    }
+
+    /// Bytes read and written from `/proc/<pid>/io` content
+    ///
+    /// Uses `read_bytes`/`write_bytes`, which count actual storage I/O, rather
+    /// than `rchar`/`wchar`, which include reads served from the page cache.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn parse_proc_io(content: &str) -> (u64, u64) {
+        let mut read_bytes = 0;
+        let mut write_bytes = 0;
+
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<u64>() else {
+                continue;
+            };
+            match key.trim() {
+                "read_bytes" => read_bytes = value,
+                "write_bytes" => write_bytes = value,
+                _ => {}
+            }
+        }
+
+        (read_bytes, write_bytes)
     }
 
     /// Handle system monitor request
@@ -639,7 +1167,7 @@ impl SystemMonitorPlugin {
         match request_type {
             "stats" => {
                 info!("Collecting system statistics for {}", device.name());
-                let stats_json = self.collect_system_stats();
+                let stats_json = self.collect_system_stats().await;
 
                 if let Ok(stats) = serde_json::from_value::<SystemStats>(stats_json.clone()) {
                     self.update_stats(stats);
@@ -667,9 +1195,21 @@ impl SystemMonitorPlugin {
                     .get("limit")
                     .and_then(|v| v.as_u64())
                     .unwrap_or(10) as usize;
-
-                info!("Collecting top {} processes for {}", limit, device.name());
-                let process_list = self.collect_process_list(limit);
+                let sort_by = packet
+                    .body
+                    .get("sortBy")
+                    .and_then(|v| serde_json::from_value::<ProcessSort>(v.clone()).ok())
+                    .unwrap_or_default();
+
+                info!(
+                    "Collecting top {} processes by {:?} for {}",
+                    limit,
+                    sort_by,
+                    device.name()
+                );
+                let process_list = cpu_pool::global()
+                    .run(move || Self::collect_process_list(limit, sort_by))
+                    .await?;
 
                 if let Some(processes_array) = process_list.get("processes") {
                     if let Ok(processes) =
@@ -695,6 +1235,35 @@ impl SystemMonitorPlugin {
                     warn!("Cannot send process list - plugin not properly initialized");
                 }
             }
+            "gpu" => {
+                info!(
+                    "Collecting GPU and temperature statistics for {}",
+                    device.name()
+                );
+                let gpu_info = Self::collect_gpu_info().await;
+
+                let gpu = serde_json::from_value::<Vec<GpuStats>>(gpu_info["gpu"].clone());
+                let thermals =
+                    serde_json::from_value::<Vec<ThermalZone>>(gpu_info["thermals"].clone());
+                if let (Ok(gpu), Ok(thermals)) = (gpu, thermals) {
+                    self.update_gpu_info(gpu, thermals);
+                }
+
+                let response = Packet::new("cconnect.systemmonitor.gpu", gpu_info);
+                debug!(
+                    "GPU statistics collected for {}: {:?}",
+                    device.name(),
+                    response.body
+                );
+
+                if let (Some(device_id), Some(sender)) = (&self.device_id, &self.packet_sender) {
+                    if let Err(e) = sender.send((device_id.clone(), response)).await {
+                        warn!("Failed to send GPU stats packet: {}", e);
+                    }
+                } else {
+                    warn!("Cannot send GPU stats - plugin not properly initialized");
+                }
+            }
             _ => {
                 warn!("Unknown system monitor request type: {}", request_type);
             }
@@ -735,6 +1304,7 @@ impl Plugin for SystemMonitorPlugin {
         vec![
             "cconnect.systemmonitor.stats".to_string(),
             "cconnect.systemmonitor.processes".to_string(),
+            "cconnect.systemmonitor.gpu".to_string(),
         ]
     }
 
@@ -798,6 +1368,7 @@ impl PluginFactory for SystemMonitorPluginFactory {
         vec![
             "cconnect.systemmonitor.stats".to_string(),
             "cconnect.systemmonitor.processes".to_string(),
+            "cconnect.systemmonitor.gpu".to_string(),
         ]
     }
 
@@ -806,6 +1377,329 @@ impl PluginFactory for SystemMonitorPluginFactory {
     }
 }
 
+/// macOS statistics backend
+///
+/// Reads the same figures Activity Monitor shows: per-core CPU ticks from the
+/// Mach host, memory from `vm_statistics64`, mounted volumes from
+/// `getfsstat`, and interface counters from the routing sysctl.
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::CpuTimes;
+    use serde_json::json;
+    use std::ffi::{c_void, CStr};
+    use std::mem::{size_of, MaybeUninit};
+    use std::ptr;
+    use std::sync::OnceLock;
+
+    const KERN_SUCCESS: i32 = 0;
+    const PROCESSOR_CPU_LOAD_INFO: i32 = 2;
+    const CPU_STATE_MAX: usize = 4;
+    const CPU_STATE_USER: usize = 0;
+    const CPU_STATE_SYSTEM: usize = 1;
+    const CPU_STATE_IDLE: usize = 2;
+    const CPU_STATE_NICE: usize = 3;
+    const HOST_VM_INFO64: i32 = 4;
+    const MNT_DONTBROWSE: u32 = 0x0010_0000;
+
+    /// `vm_statistics64` from `<mach/vm_statistics.h>`
+    ///
+    /// All fields are declared so the layout matches, though few are read.
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(dead_code)]
+    struct VmStatistics64 {
+        free_count: u32,
+        active_count: u32,
+        inactive_count: u32,
+        wire_count: u32,
+        zero_fill_count: u64,
+        reactivations: u64,
+        pageins: u64,
+        pageouts: u64,
+        faults: u64,
+        cow_faults: u64,
+        lookups: u64,
+        hits: u64,
+        purges: u64,
+        purgeable_count: u32,
+        speculative_count: u32,
+        decompressions: u64,
+        compressions: u64,
+        swapins: u64,
+        swapouts: u64,
+        compressor_page_count: u32,
+        throttled_count: u32,
+        external_page_count: u32,
+        internal_page_count: u32,
+        total_uncompressed_pages_in_compressor: u64,
+    }
+
+    extern "C" {
+        static mach_task_self_: u32;
+        fn mach_host_self() -> u32;
+        fn host_processor_info(
+            host: u32,
+            flavor: i32,
+            out_processor_count: *mut u32,
+            out_processor_info: *mut *mut i32,
+            out_processor_info_count: *mut u32,
+        ) -> i32;
+        fn host_statistics64(host: u32, flavor: i32, info: *mut i32, count: *mut u32) -> i32;
+        fn vm_deallocate(task: u32, address: usize, size: usize) -> i32;
+    }
+
+    fn host_port() -> u32 {
+        static HOST: OnceLock<u32> = OnceLock::new();
+        // SAFETY: returns a send right to the host port; cached so it is
+        // requested only once
+        *HOST.get_or_init(|| unsafe { mach_host_self() })
+    }
+
+    /// Read a fixed-size sysctl value by name
+    fn sysctl_value<T: Copy>(name: &CStr) -> Option<T> {
+        let mut value = MaybeUninit::<T>::zeroed();
+        let mut size = size_of::<T>();
+        // SAFETY: the buffer is exactly `size` bytes and the size is checked
+        // before the value is used
+        let ret = unsafe {
+            libc::sysctlbyname(
+                name.as_ptr(),
+                value.as_mut_ptr() as *mut c_void,
+                &mut size,
+                ptr::null_mut(),
+                0,
+            )
+        };
+        (ret == 0 && size == size_of::<T>()).then(|| unsafe { value.assume_init() })
+    }
+
+    fn cstr(bytes: &'static [u8]) -> &'static CStr {
+        CStr::from_bytes_with_nul(bytes).expect("sysctl name must be nul-terminated")
+    }
+
+    fn round2(value: f64) -> f64 {
+        (value * 100.0).round() / 100.0
+    }
+
+    fn percent(part: u64, total: u64) -> f64 {
+        if total == 0 {
+            0.0
+        } else {
+            round2(part as f64 / total as f64 * 100.0)
+        }
+    }
+
+    /// Cumulative busy and idle ticks of each core
+    pub(super) fn cpu_times() -> Option<Vec<CpuTimes>> {
+        let mut cpu_count = 0u32;
+        let mut info: *mut i32 = ptr::null_mut();
+        let mut info_count = 0u32;
+
+        // SAFETY: on success the kernel allocates `info_count` integers in
+        // our address space, which are copied and then deallocated
+        unsafe {
+            if host_processor_info(
+                host_port(),
+                PROCESSOR_CPU_LOAD_INFO,
+                &mut cpu_count,
+                &mut info,
+                &mut info_count,
+            ) != KERN_SUCCESS
+            {
+                return None;
+            }
+
+            let values = std::slice::from_raw_parts(info, info_count as usize);
+            let times = values
+                .chunks_exact(CPU_STATE_MAX)
+                .take(cpu_count as usize)
+                .map(|core| {
+                    let ticks = |state: usize| core[state] as u32 as u64;
+                    CpuTimes {
+                        active: ticks(CPU_STATE_USER)
+                            + ticks(CPU_STATE_SYSTEM)
+                            + ticks(CPU_STATE_NICE),
+                        idle: ticks(CPU_STATE_IDLE),
+                    }
+                })
+                .collect();
+            vm_deallocate(
+                mach_task_self_,
+                info as usize,
+                info_count as usize * size_of::<i32>(),
+            );
+            Some(times)
+        }
+    }
+
+    /// Memory use as Activity Monitor counts it: app, wired and compressed
+    pub(super) fn memory_info() -> serde_json::Value {
+        let total: u64 = sysctl_value(cstr(b"hw.memsize\0")).unwrap_or(0);
+        // SAFETY: sysconf has no preconditions
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(0) as u64;
+
+        let mut stats = VmStatistics64::default();
+        let mut count = (size_of::<VmStatistics64>() / size_of::<i32>()) as u32;
+        // SAFETY: `count` is the size of `stats` in integers
+        let ret = unsafe {
+            host_statistics64(
+                host_port(),
+                HOST_VM_INFO64,
+                &mut stats as *mut VmStatistics64 as *mut i32,
+                &mut count,
+            )
+        };
+        if ret != KERN_SUCCESS || total == 0 {
+            return json!({ "total": total, "used": 0, "available": total, "usagePercent": 0.0 });
+        }
+
+        let app_pages = stats
+            .internal_page_count
+            .saturating_sub(stats.purgeable_count) as u64;
+        let used_pages = app_pages + stats.wire_count as u64 + stats.compressor_page_count as u64;
+        let used = (used_pages * page_size).min(total);
+
+        json!({
+            "total": total,
+            "used": used,
+            "available": total - used,
+            "usagePercent": percent(used, total),
+        })
+    }
+
+    /// Local, browsable volumes, one entry per device
+    pub(super) fn disk_info() -> serde_json::Value {
+        // SAFETY: a null buffer only asks for the number of mounts
+        let count = unsafe { libc::getfsstat(ptr::null_mut(), 0, libc::MNT_NOWAIT) };
+        if count <= 0 {
+            return json!([]);
+        }
+
+        // SAFETY: statfs is plain data, and the buffer size is passed along
+        let mut mounts: Vec<libc::statfs> = vec![unsafe { std::mem::zeroed() }; count as usize];
+        let count = unsafe {
+            libc::getfsstat(
+                mounts.as_mut_ptr(),
+                (mounts.len() * size_of::<libc::statfs>()) as i32,
+                libc::MNT_NOWAIT,
+            )
+        };
+        mounts.truncate(count.max(0) as usize);
+
+        let mut seen_devices = std::collections::HashSet::new();
+        let disks: Vec<serde_json::Value> = mounts
+            .iter()
+            .filter(|m| m.f_flags & libc::MNT_LOCAL as u32 != 0)
+            .filter(|m| m.f_flags & MNT_DONTBROWSE == 0)
+            .filter_map(|m| {
+                // SAFETY: the kernel nul-terminates both names
+                let device = unsafe { CStr::from_ptr(m.f_mntfromname.as_ptr()) };
+                let mount_point = unsafe { CStr::from_ptr(m.f_mntonname.as_ptr()) };
+                if !seen_devices.insert(device.to_owned()) {
+                    return None;
+                }
+
+                let block_size = m.f_bsize as u64;
+                let total = m.f_blocks * block_size;
+                let available = m.f_bavail * block_size;
+                let used = total.saturating_sub(available);
+                Some(json!({
+                    "mountPoint": mount_point.to_string_lossy(),
+                    "total": total,
+                    "used": used,
+                    "available": available,
+                    "usagePercent": percent(used, total),
+                }))
+            })
+            .collect();
+
+        json!(disks)
+    }
+
+    /// Byte counters of all interfaces except loopback
+    ///
+    /// Uses the 64-bit counters of `NET_RT_IFLIST2`, as the 32-bit ones from
+    /// `getifaddrs` wrap after 4 GiB.
+    pub(super) fn network_info() -> serde_json::Value {
+        let mut mib = [libc::CTL_NET, libc::PF_ROUTE, 0, 0, libc::NET_RT_IFLIST2, 0];
+        let mut len = 0usize;
+        // SAFETY: a null buffer only asks for the required length
+        let ret = unsafe {
+            libc::sysctl(
+                mib.as_mut_ptr(),
+                mib.len() as u32,
+                ptr::null_mut(),
+                &mut len,
+                ptr::null_mut(),
+                0,
+            )
+        };
+        if ret != 0 || len == 0 {
+            return json!({ "bytesReceived": 0, "bytesSent": 0 });
+        }
+
+        let mut buffer = vec![0u8; len];
+        // SAFETY: `len` is the size of the buffer
+        let ret = unsafe {
+            libc::sysctl(
+                mib.as_mut_ptr(),
+                mib.len() as u32,
+                buffer.as_mut_ptr() as *mut c_void,
+                &mut len,
+                ptr::null_mut(),
+                0,
+            )
+        };
+        if ret != 0 {
+            return json!({ "bytesReceived": 0, "bytesSent": 0 });
+        }
+        buffer.truncate(len);
+
+        let mut received = 0u64;
+        let mut sent = 0u64;
+        let mut offset = 0;
+        while offset + size_of::<libc::if_msghdr>() <= buffer.len() {
+            // SAFETY: at least a message header remains; read unaligned as
+            // messages are packed back to back
+            let header: libc::if_msghdr =
+                unsafe { ptr::read_unaligned(buffer[offset..].as_ptr() as *const _) };
+            let msg_len = header.ifm_msglen as usize;
+            if msg_len == 0 || offset + msg_len > buffer.len() {
+                break;
+            }
+
+            if header.ifm_type as i32 == libc::RTM_IFINFO2
+                && msg_len >= size_of::<libc::if_msghdr2>()
+            {
+                // SAFETY: the message is long enough for an if_msghdr2
+                let info: libc::if_msghdr2 =
+                    unsafe { ptr::read_unaligned(buffer[offset..].as_ptr() as *const _) };
+                if info.ifm_flags & libc::IFF_LOOPBACK == 0 {
+                    received += info.ifm_data.ifi_ibytes;
+                    sent += info.ifm_data.ifi_obytes;
+                }
+            }
+            offset += msg_len;
+        }
+
+        json!({
+            "bytesReceived": received,
+            "bytesSent": sent,
+        })
+    }
+
+    /// Seconds since boot
+    pub(super) fn uptime() -> u64 {
+        let Some(boot) = sysctl_value::<libc::timeval>(cstr(b"kern.boottime\0")) else {
+            return 0;
+        };
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|now| now.as_secs().saturating_sub(boot.tv_sec as u64))
+            .unwrap_or(0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -833,9 +1727,10 @@ mod tests {
         assert!(incoming.contains(&"kdeconnect.systemmonitor.request".to_string()));
 
         let outgoing = plugin.outgoing_capabilities();
-        assert_eq!(outgoing.len(), 2);
+        assert_eq!(outgoing.len(), 3);
         assert!(outgoing.contains(&"cconnect.systemmonitor.stats".to_string()));
         assert!(outgoing.contains(&"cconnect.systemmonitor.processes".to_string()));
+        assert!(outgoing.contains(&"cconnect.systemmonitor.gpu".to_string()));
     }
 
     #[tokio::test]
@@ -901,11 +1796,11 @@ mod tests {
         assert!(result.is_ok());
     }
 
-    #[cfg(target_os = "linux")]
-    #[test]
-    fn test_collect_system_stats() {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    #[tokio::test]
+    async fn test_collect_system_stats() {
         let plugin = SystemMonitorPlugin::new();
-        let stats = plugin.collect_system_stats();
+        let stats = plugin.collect_system_stats().await;
 
         assert!(stats.get("cpu").is_some());
         assert!(stats.get("memory").is_some());
@@ -914,11 +1809,70 @@ mod tests {
         assert!(stats.get("uptime").is_some());
     }
 
+    #[cfg(target_os = "macos")]
+    #[tokio::test]
+    async fn test_macos_stats_plausible() {
+        let plugin = SystemMonitorPlugin::new();
+        let stats: SystemStats =
+            serde_json::from_value(plugin.collect_system_stats().await).unwrap();
+
+        assert!(!stats.cpu.cores.is_empty());
+        assert!(stats.cpu.cores.iter().all(|c| (0.0..=100.0).contains(c)));
+        assert!(stats.memory.total > 0);
+        assert!(stats.memory.used > 0 && stats.memory.used <= stats.memory.total);
+        assert_eq!(
+            stats.memory.used + stats.memory.available,
+            stats.memory.total
+        );
+        assert!(stats.disk.iter().any(|d| d.mount_point == "/"));
+        assert!(stats.uptime > 0);
+    }
+
+    #[test]
+    fn test_cpu_usage_from_deltas() {
+        let before = "cpu  300 0 100 600 0 0 0 0 0 0\n\
+                      cpu0 100 0 50 350 0 0 0 0 0 0\n\
+                      cpu1 200 0 50 250 0 0 0 0 0 0\n\
+                      intr 12345\n";
+        // cpu0 busy 50 of 100 jiffies, cpu1 fully busy for 100 jiffies
+        let after = "cpu  450 0 150 650 0 0 0 0 0 0\n\
+                     cpu0 125 0 75 400 0 0 0 0 0 0\n\
+                     cpu1 300 0 50 250 0 0 0 0 0 0\n";
+
+        let usage = SystemMonitorPlugin::cpu_usage_between(
+            &SystemMonitorPlugin::parse_proc_stat(before),
+            &SystemMonitorPlugin::parse_proc_stat(after),
+        );
+        let cpu: CpuStats = serde_json::from_value(usage).unwrap();
+
+        assert_eq!(cpu.cores, vec![50.0, 100.0]);
+        assert_eq!(cpu.usage, 75.0);
+    }
+
+    #[test]
+    fn test_cpu_usage_counts_iowait_idle() {
+        let before = SystemMonitorPlugin::parse_proc_stat("cpu0 0 0 0 0 0 0 0 0\n");
+        let after = SystemMonitorPlugin::parse_proc_stat("cpu0 10 0 0 40 50 0 0 0\n");
+        let cpu: CpuStats =
+            serde_json::from_value(SystemMonitorPlugin::cpu_usage_between(&before, &after))
+                .unwrap();
+
+        assert_eq!(cpu.cores, vec![10.0]);
+    }
+
+    #[tokio::test]
+    async fn test_sample_interval_configurable() {
+        let mut plugin = SystemMonitorPlugin::new();
+        assert_eq!(plugin.sample_interval(), DEFAULT_SAMPLE_INTERVAL);
+
+        plugin.set_sample_interval(Duration::from_millis(20));
+        assert_eq!(plugin.sample_interval(), Duration::from_millis(20));
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn test_collect_process_list() {
-        let plugin = SystemMonitorPlugin::new();
-        let processes = plugin.collect_process_list(5);
+        let processes = SystemMonitorPlugin::collect_process_list(5, ProcessSort::Memory);
 
         assert!(processes.get("processes").is_some());
         assert!(processes["processes"].is_array());
@@ -1019,6 +1973,8 @@ mod tests {
                 bytes_received: 1_000_000,
                 bytes_sent: 500_000,
             },
+            gpu: Vec::new(),
+            thermals: Vec::new(),
             uptime: 86400,
         };
 
@@ -1044,12 +2000,18 @@ mod tests {
                 name: "firefox".to_string(),
                 cpu: 12.5,
                 memory: 1_000_000_000,
+                read_bytes: 0,
+                write_bytes: 0,
+                open_fds: 0,
             },
             ProcessInfo {
                 pid: 5678,
                 name: "code".to_string(),
                 cpu: 8.3,
                 memory: 500_000_000,
+                read_bytes: 0,
+                write_bytes: 0,
+                open_fds: 0,
             },
         ];
 
@@ -1075,6 +2037,144 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_create_gpu_request() {
+        let plugin = SystemMonitorPlugin::new();
+        let packet = plugin.create_gpu_request();
+
+        assert_eq!(packet.packet_type, "cconnect.systemmonitor.request");
+        assert_eq!(packet.body["requestType"], "gpu");
+    }
+
+    #[tokio::test]
+    async fn test_handle_gpu_request() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let mut plugin = SystemMonitorPlugin::new();
+        let mut device = create_test_device();
+        plugin.init(&device, tx).await.unwrap();
+
+        let packet = plugin.create_gpu_request();
+        plugin.handle_packet(&packet, &mut device).await.unwrap();
+
+        let (_, response) = rx.recv().await.unwrap();
+        assert!(response.is_type("cconnect.systemmonitor.gpu"));
+        assert!(response.body["gpu"].is_array());
+        assert!(response.body["thermals"].is_array());
+    }
+
+    fn write_file(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_read_hwmon_thermals() {
+        let root = tempfile::tempdir().unwrap();
+        let cpu = root.path().join("hwmon0");
+        write_file(&cpu.join("name"), "coretemp\n");
+        write_file(&cpu.join("temp1_input"), "54000\n");
+        write_file(&cpu.join("temp1_label"), "Package id 0\n");
+        write_file(&cpu.join("temp1_crit"), "100000\n");
+        write_file(&cpu.join("temp10_input"), "48500\n");
+        write_file(&cpu.join("temp2_input"), "garbage\n");
+        let nvme = root.path().join("hwmon1");
+        write_file(&nvme.join("name"), "nvme\n");
+        write_file(&nvme.join("temp1_input"), "-1500\n");
+
+        let thermals = SystemMonitorPlugin::read_hwmon_thermals(root.path());
+
+        assert_eq!(
+            thermals,
+            vec![
+                ThermalZone {
+                    chip: "coretemp".to_string(),
+                    label: "Package id 0".to_string(),
+                    temperature_c: 54.0,
+                    critical_c: Some(100.0),
+                },
+                ThermalZone {
+                    chip: "coretemp".to_string(),
+                    label: "temp10".to_string(),
+                    temperature_c: 48.5,
+                    critical_c: None,
+                },
+                ThermalZone {
+                    chip: "nvme".to_string(),
+                    label: "temp1".to_string(),
+                    temperature_c: -1.5,
+                    critical_c: None,
+                },
+            ]
+        );
+        assert!(SystemMonitorPlugin::read_hwmon_thermals(&root.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_read_drm_gpus() {
+        let root = tempfile::tempdir().unwrap();
+        let amd = root.path().join("card0/device");
+        write_file(&amd.join("vendor"), "0x1002\n");
+        write_file(&amd.join("gpu_busy_percent"), "23\n");
+        write_file(&amd.join("mem_info_vram_total"), "8573157376\n");
+        write_file(&amd.join("mem_info_vram_used"), "1073741824\n");
+        write_file(&amd.join("hwmon/hwmon3/temp1_input"), "52000\n");
+        let intel = root.path().join("card1/device");
+        write_file(&intel.join("vendor"), "0x8086\n");
+        // Connector directories are not GPUs
+        write_file(&root.path().join("card0-DP-1/status"), "connected\n");
+
+        let gpus = SystemMonitorPlugin::read_drm_gpus(root.path());
+
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(
+            gpus[0],
+            GpuStats {
+                vendor: "AMD".to_string(),
+                name: "card0".to_string(),
+                usage_percent: Some(23.0),
+                vram_total: 8_573_157_376,
+                vram_used: 1_073_741_824,
+                temperature_c: Some(52.0),
+            }
+        );
+        assert_eq!(gpus[1].vendor, "Intel");
+        assert_eq!(gpus[1].usage_percent, None);
+        assert_eq!(gpus[1].vram_total, 0);
+    }
+
+    #[test]
+    fn test_parse_nvidia_smi() {
+        let output = "NVIDIA GeForce RTX 3080, 35, 10240, 2048, 61\n\
+                      Tesla T4, [N/A], 15360, 0, [N/A]\n";
+
+        let gpus = SystemMonitorPlugin::parse_nvidia_smi(output);
+
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 3080");
+        assert_eq!(gpus[0].usage_percent, Some(35.0));
+        assert_eq!(gpus[0].vram_total, 10240 * 1024 * 1024);
+        assert_eq!(gpus[0].vram_used, 2048 * 1024 * 1024);
+        assert_eq!(gpus[0].temperature_c, Some(61.0));
+        assert_eq!(gpus[1].usage_percent, None);
+        assert_eq!(gpus[1].temperature_c, None);
+        assert!(SystemMonitorPlugin::parse_nvidia_smi("").is_empty());
+    }
+
+    #[test]
+    fn test_stats_without_gpu_fields_deserialize() {
+        let stats: SystemStats = serde_json::from_value(json!({
+            "cpu": { "usage": 0.0, "cores": [] },
+            "memory": { "total": 0, "used": 0, "available": 0, "usagePercent": 0.0 },
+            "disk": [],
+            "network": { "bytesReceived": 0, "bytesSent": 0 },
+            "uptime": 0,
+        }))
+        .unwrap();
+
+        assert!(stats.gpu.is_empty());
+        assert!(stats.thermals.is_empty());
+    }
+
     #[test]
     fn test_create_processes_request() {
         let plugin = SystemMonitorPlugin::new();
@@ -1121,6 +2221,9 @@ mod tests {
             name: "test".to_string(),
             cpu: 10.5,
             memory: 1_000_000,
+            read_bytes: 4096,
+            write_bytes: 8192,
+            open_fds: 12,
         };
 
         let json = serde_json::to_value(&process).unwrap();
@@ -1128,5 +2231,132 @@ mod tests {
         assert_eq!(json["name"], "test");
         assert_eq!(json["cpu"], 10.5);
         assert_eq!(json["memory"], 1_000_000);
+        assert_eq!(json["readBytes"], 4096);
+        assert_eq!(json["writeBytes"], 8192);
+        assert_eq!(json["openFds"], 12);
+    }
+
+    #[test]
+    fn test_process_info_without_io_fields_deserializes() {
+        let process: ProcessInfo = serde_json::from_value(json!({
+            "pid": 1234,
+            "name": "test",
+            "cpu": 10.5,
+            "memory": 1_000_000,
+        }))
+        .unwrap();
+
+        assert_eq!(process.read_bytes, 0);
+        assert_eq!(process.write_bytes, 0);
+        assert_eq!(process.open_fds, 0);
+    }
+
+    fn write_proc_fixture(root: &Path, pid: u32, name: &str, rss_pages: u64, io: &str) {
+        let dir = root.join(pid.to_string());
+        write_file(
+            &dir.join("stat"),
+            &format!(
+                "{} ({}) S 1 {} {} 0 -1 4194304 100 0 0 0 250 50 0 0 20 0 1 0 100 0 0\n",
+                pid, name, pid, pid
+            ),
+        );
+        write_file(
+            &dir.join("statm"),
+            &format!("1000 {} 100 10 0 200 0\n", rss_pages),
+        );
+        if !io.is_empty() {
+            write_file(&dir.join("io"), io);
+        }
+        std::fs::create_dir_all(dir.join("fd")).unwrap();
+    }
+
+    #[test]
+    fn test_read_process_info_io() {
+        let root = tempfile::tempdir().unwrap();
+        write_proc_fixture(
+            root.path(),
+            4321,
+            "rsync",
+            256,
+            "rchar: 900000000\n\
+             wchar: 800000000\n\
+             syscr: 1200\n\
+             syscw: 900\n\
+             read_bytes: 52428800\n\
+             write_bytes: 1048576\n\
+             cancelled_write_bytes: 4096\n",
+        );
+        let fd_dir = root.path().join("4321/fd");
+        for fd in ["0", "1", "2"] {
+            std::fs::write(fd_dir.join(fd), "").unwrap();
+        }
+
+        let process = SystemMonitorPlugin::read_process_info(root.path(), 4321).unwrap();
+
+        assert_eq!(process.pid, 4321);
+        assert_eq!(process.name, "rsync");
+        assert_eq!(process.memory, 256 * 4096);
+        assert_eq!(process.read_bytes, 52_428_800);
+        assert_eq!(process.write_bytes, 1_048_576);
+        assert_eq!(process.io_bytes(), 53_477_376);
+        assert_eq!(process.open_fds, 3);
+    }
+
+    #[test]
+    fn test_read_process_info_without_io_access() {
+        let root = tempfile::tempdir().unwrap();
+        write_proc_fixture(root.path(), 1, "init", 10, "");
+
+        let process = SystemMonitorPlugin::read_process_info(root.path(), 1).unwrap();
+
+        assert_eq!(process.read_bytes, 0);
+        assert_eq!(process.write_bytes, 0);
+        assert!(SystemMonitorPlugin::read_process_info(root.path(), 2).is_none());
+    }
+
+    #[test]
+    fn test_sort_processes() {
+        let root = tempfile::tempdir().unwrap();
+        write_proc_fixture(
+            root.path(),
+            10,
+            "big",
+            1000,
+            "read_bytes: 1\nwrite_bytes: 1\n",
+        );
+        write_proc_fixture(
+            root.path(),
+            20,
+            "busy",
+            10,
+            "read_bytes: 5000\nwrite_bytes: 5000\n",
+        );
+        write_proc_fixture(
+            root.path(),
+            30,
+            "idle",
+            100,
+            "read_bytes: 0\nwrite_bytes: 0\n",
+        );
+        write_file(&root.path().join("self/stat"), "not a pid");
+
+        let mut processes = SystemMonitorPlugin::read_processes(root.path());
+        assert_eq!(processes.len(), 3);
+
+        let pids = |processes: &[ProcessInfo]| processes.iter().map(|p| p.pid).collect::<Vec<_>>();
+        SystemMonitorPlugin::sort_processes(&mut processes, ProcessSort::Memory);
+        assert_eq!(pids(&processes), vec![10, 30, 20]);
+        SystemMonitorPlugin::sort_processes(&mut processes, ProcessSort::Io);
+        assert_eq!(pids(&processes), vec![20, 10, 30]);
+    }
+
+    #[test]
+    fn test_create_sorted_processes_request() {
+        let plugin = SystemMonitorPlugin::new();
+        let packet = plugin.create_sorted_processes_request(5, ProcessSort::Io);
+
+        assert_eq!(packet.body["requestType"], "processes");
+        assert_eq!(packet.body["limit"], 5);
+        assert_eq!(packet.body["sortBy"], "io");
     }
 }
@@ -347,7 +347,7 @@ impl SystemVolumePlugin {
                 .send((device_id.clone(), packet))
                 .await
                 .map_err(|e| {
-                    crate::ProtocolError::Transport(format!("Failed to send packet: {}", e))
+                    crate::ProtocolError::transport(format!("Failed to send packet: {}", e))
                 })?;
         }
 
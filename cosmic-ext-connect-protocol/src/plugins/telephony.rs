@@ -26,6 +26,12 @@
 //! - `phoneNumber`: Caller's phone number
 //! - `contactName`: Contact name from phone's address book (optional)
 //! - `messageBody`: SMS body (deprecated, use SMS plugin instead)
+//! - `isCancel`: Set when the ringing or talking call has ended
+//!
+//! A call starts with `ringing` (or `talking` for outgoing calls) and ends
+//! with a cancelled event or `missedCall`. The end of a call is signaled
+//! once, however many cancel events the phone sends, so the daemon can undo
+//! its on-ring action (resume media, unmute audio) exactly once.
 //!
 //! ## SMS Messages
 //!
@@ -36,6 +42,33 @@
 //! - Read/unread status
 //! - Sender information
 //!
+//! `cconnect.sms.messages` carries either a `conversations` list of threads or,
+//! as KDE Connect sends it, a flat `messages` list; messages are grouped into
+//! threads by `thread_id` either way. MMS group messages list every
+//! participant in `addresses`:
+//!
+//! ```json
+//! {
+//!     "messages": [{
+//!         "_id": 42,
+//!         "thread_id": 7,
+//!         "addresses": [{ "address": "+1555000111" }, { "address": "+1555000222" }],
+//!         "body": "See you all at 8",
+//!         "date": 1700000000000,
+//!         "type": 1,
+//!         "read": 0
+//!     }]
+//! }
+//! ```
+//!
+//! Incoming messages are merged into the cached threads by message ID, so a
+//! packet with one new message (as sent when it arrives in an open thread)
+//! appends it instead of replacing the thread. Only messages not seen before
+//! raise an SMS received signal.
+//!
+//! Replies to a thread go to all of its participants, so answering a group
+//! thread stays in the group.
+//!
 //! ## References
 //!
 //! - [CConnect Telephony Plugin](https://github.com/KDE/cconnect-kde/tree/master/plugins/telephony)
@@ -128,6 +161,21 @@ pub struct TelephonyEvent {
     /// SMS message body (deprecated)
     #[serde(skip_serializing_if = "Option::is_none", rename = "messageBody")]
     pub message_body: Option<String>,
+
+    /// Whether this event cancels the ringing or talking state
+    #[serde(
+        default,
+        skip_serializing_if = "std::ops::Not::not",
+        rename = "isCancel"
+    )]
+    pub is_cancel: bool,
+}
+
+/// Address of an SMS/MMS participant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmsAddress {
+    /// Phone number/address
+    pub address: String,
 }
 
 /// SMS message in a conversation
@@ -138,13 +186,19 @@ pub struct SmsMessage {
     pub id: i64,
 
     /// Thread ID
-    #[serde(rename = "threadId")]
+    #[serde(rename = "threadId", alias = "thread_id")]
     pub thread_id: i64,
 
-    /// Phone number/address
+    /// Phone number/address (single-recipient format)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub address: String,
 
-    /// Message body
+    /// All participant addresses (several for MMS group messages)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<SmsAddress>,
+
+    /// Message body (empty for MMS without text)
+    #[serde(default)]
     pub body: String,
 
     /// Timestamp (milliseconds since epoch)
@@ -158,22 +212,121 @@ pub struct SmsMessage {
     pub read: i32,
 }
 
+impl SmsMessage {
+    /// Participant addresses of this message
+    ///
+    /// `addresses` if present, otherwise the single `address`.
+    pub fn participants(&self) -> Vec<&str> {
+        if !self.addresses.is_empty() {
+            self.addresses.iter().map(|a| a.address.as_str()).collect()
+        } else if !self.address.is_empty() {
+            vec![self.address.as_str()]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Check if this is an unread received message
+    pub fn is_unread(&self) -> bool {
+        self.read == 0 && self.message_type == 1
+    }
+}
+
 /// SMS conversation thread
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SmsConversation {
     /// Thread ID
-    #[serde(rename = "threadId")]
+    #[serde(rename = "threadId", alias = "thread_id")]
     pub thread_id: i64,
 
-    /// Messages in this conversation
+    /// Messages in this conversation, oldest first
     pub messages: Vec<SmsMessage>,
 }
 
+impl SmsConversation {
+    /// Create an empty thread
+    pub fn new(thread_id: i64) -> Self {
+        Self {
+            thread_id,
+            messages: Vec::new(),
+        }
+    }
+
+    /// Addresses of everyone in the thread, in order of first appearance
+    pub fn participants(&self) -> Vec<String> {
+        let mut participants: Vec<String> = Vec::new();
+        for address in self.messages.iter().flat_map(|m| m.participants()) {
+            if !participants.iter().any(|p| p == address) {
+                participants.push(address.to_string());
+            }
+        }
+        participants
+    }
+
+    /// Check if this is a group (MMS) thread with several participants
+    pub fn is_group(&self) -> bool {
+        self.participants().len() > 1
+    }
+
+    /// Most recent message in the thread
+    pub fn latest_message(&self) -> Option<&SmsMessage> {
+        self.messages.iter().max_by_key(|m| m.date)
+    }
+
+    /// Merge messages into the thread
+    ///
+    /// Messages already in the thread (by ID) are updated in place, e.g. when
+    /// they have been read. Returns the messages that were new.
+    pub fn merge(&mut self, messages: Vec<SmsMessage>) -> Vec<SmsMessage> {
+        let mut added = Vec::new();
+
+        for message in messages {
+            match self.messages.iter_mut().find(|m| m.id == message.id) {
+                Some(existing) => *existing = message,
+                None => {
+                    added.push(message.clone());
+                    self.messages.push(message);
+                }
+            }
+        }
+
+        self.messages.sort_by_key(|m| m.date);
+        added
+    }
+}
+
 /// SMS messages packet body
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SmsMessages {
     /// List of conversations
+    #[serde(default)]
     pub conversations: Vec<SmsConversation>,
+
+    /// Flat list of messages from any thread (KDE Connect format)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub messages: Vec<SmsMessage>,
+}
+
+impl SmsMessages {
+    /// All messages grouped into threads by thread ID
+    pub fn into_threads(self) -> Vec<SmsConversation> {
+        let mut threads = self.conversations;
+
+        for message in self.messages {
+            match threads
+                .iter_mut()
+                .find(|t| t.thread_id == message.thread_id)
+            {
+                Some(thread) => thread.messages.push(message),
+                None => threads.push(SmsConversation {
+                    thread_id: message.thread_id,
+                    messages: vec![message],
+                }),
+            }
+        }
+
+        threads
+    }
 }
 
 /// Request for conversation messages
@@ -357,12 +510,62 @@ impl TelephonyPlugin {
                 guard
                     .values()
                     .flat_map(|c| &c.messages)
-                    .filter(|m| m.read == 0 && m.message_type == 1)
+                    .filter(|m| m.is_unread())
                     .count()
             })
             .unwrap_or(0)
     }
 
+    /// Ask the device for the latest message in each thread
+    ///
+    /// The reply updates [`Self::get_conversations`].
+    pub async fn request_conversations(&self) -> Result<()> {
+        self.send_packet(self.create_conversations_request()).await
+    }
+
+    /// Ask the device for the messages of a thread
+    ///
+    /// The reply updates [`Self::get_conversation`].
+    pub async fn request_conversation(&self, thread_id: i64) -> Result<()> {
+        self.send_packet(self.create_conversation_request(thread_id, None, None))
+            .await
+    }
+
+    /// Send a message to one or more recipients
+    ///
+    /// Several recipients are sent a group (MMS) message.
+    pub async fn send_message(&self, addresses: &[String], message: String) -> Result<()> {
+        if addresses.is_empty() {
+            return Err(ProtocolError::InvalidPacket(
+                "Message has no recipients".to_string(),
+            ));
+        }
+
+        self.send_packet(self.create_send_message_request(addresses, message))
+            .await
+    }
+
+    /// Reply to a thread, addressing all of its participants
+    pub async fn reply(&self, thread_id: i64, message: String) -> Result<()> {
+        let participants = self
+            .get_conversation(thread_id)
+            .map(|c| c.participants())
+            .unwrap_or_default();
+        if participants.is_empty() {
+            return Err(ProtocolError::InvalidState(format!(
+                "Unknown SMS thread {}",
+                thread_id
+            )));
+        }
+
+        self.send_message(&participants, message).await
+    }
+
+    /// Ask the phone to mute its ringer
+    pub async fn mute_ringing(&self) -> Result<()> {
+        self.send_packet(self.create_mute_request()).await
+    }
+
     /// Clear current call state
     pub fn clear_current_call(&self) {
         if let Ok(mut guard) = self.current_call.write() {
@@ -371,10 +574,11 @@ impl TelephonyPlugin {
     }
 
     /// Update current call state (internal)
-    fn update_current_call(&self, event: Option<TelephonyEvent>) {
-        if let Ok(mut guard) = self.current_call.write() {
-            *guard = event;
-        }
+    ///
+    /// Returns the previous call state.
+    fn update_current_call(&self, event: Option<TelephonyEvent>) -> Option<TelephonyEvent> {
+        let mut guard = self.current_call.write().ok()?;
+        std::mem::replace(&mut *guard, event)
     }
 
     /// Add to call history (internal)
@@ -387,13 +591,23 @@ impl TelephonyPlugin {
         }
     }
 
-    /// Update conversations (internal)
-    fn update_conversations(&self, conversations: Vec<SmsConversation>) {
-        if let Ok(mut guard) = self.conversations.write() {
-            for conv in conversations {
-                guard.insert(conv.thread_id, conv);
-            }
-        }
+    /// Merge conversations into the cache (internal)
+    ///
+    /// Returns the messages not seen before.
+    fn update_conversations(&self, conversations: Vec<SmsConversation>) -> Vec<SmsMessage> {
+        let Ok(mut guard) = self.conversations.write() else {
+            return Vec::new();
+        };
+
+        conversations
+            .into_iter()
+            .flat_map(|conv| {
+                guard
+                    .entry(conv.thread_id)
+                    .or_insert_with(|| SmsConversation::new(conv.thread_id))
+                    .merge(conv.messages)
+            })
+            .collect()
     }
 
     /// Create a mute ringer request packet
@@ -486,6 +700,61 @@ impl TelephonyPlugin {
         )
     }
 
+    /// Create a request to send a message to one or more recipients
+    ///
+    /// Uses the `addresses` list understood by current peers, plus
+    /// `phoneNumber` for single recipients so older peers can send it too.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use cosmic_ext_connect_protocol::plugins::telephony::TelephonyPlugin;
+    ///
+    /// let plugin = TelephonyPlugin::new();
+    /// let group = vec!["+1555000111".to_string(), "+1555000222".to_string()];
+    /// let packet = plugin.create_send_message_request(&group, "Hi all".to_string());
+    /// assert_eq!(packet.body["addresses"][1]["address"], "+1555000222");
+    /// ```
+    pub fn create_send_message_request(&self, addresses: &[String], message: String) -> Packet {
+        debug!(
+            "Creating send message request to {} recipients",
+            addresses.len()
+        );
+
+        let mut body = json!({
+            "addresses": addresses
+                .iter()
+                .map(|address| SmsAddress { address: address.clone() })
+                .collect::<Vec<_>>(),
+            "messageBody": message,
+            "version": 2,
+        });
+
+        if let [address] = addresses {
+            body["phoneNumber"] = json!(address);
+        }
+
+        Packet::new(PACKET_TYPE_SMS_REQUEST, body)
+    }
+
+    /// Send a packet to the connected device
+    async fn send_packet(&self, packet: Packet) -> Result<()> {
+        let sender = self
+            .packet_sender
+            .as_ref()
+            .ok_or_else(|| ProtocolError::Plugin("Packet sender not initialized".to_string()))?;
+
+        let device_id = self
+            .device_id
+            .as_ref()
+            .ok_or_else(|| ProtocolError::Plugin("Device ID not set".to_string()))?;
+
+        sender
+            .send((device_id.clone(), packet))
+            .await
+            .map_err(|e| ProtocolError::Plugin(format!("Failed to send packet: {}", e)))
+    }
+
     /// Emit an internal packet for D-Bus signaling
     ///
     /// Internal packets are intercepted by the daemon and converted to D-Bus signals.
@@ -497,6 +766,17 @@ impl TelephonyPlugin {
         }
     }
 
+    /// Clear the current call and signal its end
+    ///
+    /// Only signals if a call was in progress, so repeated cancel events
+    /// end the call once.
+    async fn end_call(&self, device_id: &str, signal_body: serde_json::Value) {
+        if self.update_current_call(None).is_some() {
+            self.emit_internal_packet(device_id, "cconnect.internal.telephony.ended", signal_body)
+                .await;
+        }
+    }
+
     /// Handle a telephony event packet
     async fn handle_telephony_event(&self, packet: &Packet) -> Result<()> {
         let event: TelephonyEvent = serde_json::from_value(packet.body.clone())
@@ -517,6 +797,12 @@ impl TelephonyPlugin {
             "contactName": event.contact_name,
         });
 
+        if event.is_cancel && event_type != CallEvent::Sms {
+            info!("Call with {} ({}) ended", phone, contact);
+            self.end_call(device_id, signal_body).await;
+            return Ok(());
+        }
+
         match event_type {
             CallEvent::Ringing => {
                 info!("Incoming call from {} ({})", phone, contact);
@@ -541,8 +827,8 @@ impl TelephonyPlugin {
             }
             CallEvent::MissedCall => {
                 info!("Missed call from {} ({})", phone, contact);
-                self.update_current_call(None);
                 self.add_to_history(event.clone());
+                self.end_call(device_id, signal_body.clone()).await;
                 self.emit_internal_packet(
                     device_id,
                     "cconnect.internal.telephony.missed_call",
@@ -563,15 +849,13 @@ impl TelephonyPlugin {
         let messages: SmsMessages = serde_json::from_value(packet.body.clone())
             .map_err(|e| ProtocolError::InvalidPacket(format!("Failed to parse SMS: {}", e)))?;
 
-        info!(
-            "Received {} SMS conversations",
-            messages.conversations.len()
-        );
+        let conversations = messages.into_threads();
+        info!("Received {} SMS conversations", conversations.len());
 
         let device_id = self.device_id.as_deref().unwrap_or("unknown");
 
         // Log conversation details before caching
-        for conversation in &messages.conversations {
+        for conversation in &conversations {
             debug!(
                 "Thread {}: {} messages",
                 conversation.thread_id,
@@ -582,30 +866,36 @@ impl TelephonyPlugin {
                 let preview: String = message.body.chars().take(50).collect();
                 debug!(
                     "  Message {}: {} from {} at {}",
-                    message.id, preview, message.address, message.date
+                    message.id,
+                    preview,
+                    message.participants().join(", "),
+                    message.date
                 );
-
-                // Emit signal for unread received messages
-                if message.read == 0 && message.message_type == 1 {
-                    self.emit_internal_packet(
-                        device_id,
-                        "cconnect.internal.sms.received",
-                        json!({
-                            "threadId": message.thread_id,
-                            "address": message.address,
-                            "body": message.body,
-                            "date": message.date,
-                        }),
-                    )
-                    .await;
-                }
             }
         }
 
-        let conv_count = messages.conversations.len() as u32;
+        let conv_count = conversations.len() as u32;
 
         // Cache conversations (consumes the data)
-        self.update_conversations(messages.conversations);
+        let new_messages = self.update_conversations(conversations);
+
+        // Emit signal for new unread received messages; messages already
+        // cached were signaled when they first arrived
+        for message in new_messages.iter().filter(|m| m.is_unread()) {
+            let participants = message.participants();
+            self.emit_internal_packet(
+                device_id,
+                "cconnect.internal.sms.received",
+                json!({
+                    "threadId": message.thread_id,
+                    "address": participants.first().copied().unwrap_or_default(),
+                    "addresses": participants,
+                    "body": message.body,
+                    "date": message.date,
+                }),
+            )
+            .await;
+        }
 
         // Emit conversations updated signal
         self.emit_internal_packet(
@@ -675,6 +965,11 @@ impl Plugin for TelephonyPlugin {
     }
 
     async fn stop(&mut self) -> Result<()> {
+        // The end of a call in progress won't be reported once the device is gone
+        if let Some(device_id) = self.device_id.clone() {
+            self.end_call(&device_id, json!({})).await;
+        }
+
         info!("Telephony plugin stopped");
         Ok(())
     }
@@ -914,6 +1209,133 @@ mod tests {
         assert_eq!(plugin.missed_call_count(), 1);
     }
 
+    fn call_event(event: &str, is_cancel: bool) -> Packet {
+        Packet::new(
+            "cconnect.telephony",
+            json!({
+                "event": event,
+                "phoneNumber": "+1234567890",
+                "contactName": "John Doe",
+                "isCancel": is_cancel
+            }),
+        )
+    }
+
+    fn internal_packets(rx: &mut mpsc::Receiver<(String, Packet)>) -> Vec<String> {
+        let mut types = Vec::new();
+        while let Ok((_, packet)) = rx.try_recv() {
+            types.push(packet.packet_type);
+        }
+        types
+    }
+
+    #[tokio::test]
+    async fn test_ring_talk_end_state_machine() {
+        let mut plugin = TelephonyPlugin::new();
+        let (tx, mut rx) = mpsc::channel(100);
+        plugin.init(&create_test_device(), tx).await.unwrap();
+
+        plugin
+            .handle_telephony_event(&call_event("ringing", false))
+            .await
+            .unwrap();
+        assert!(plugin.is_ringing());
+
+        plugin
+            .handle_telephony_event(&call_event("talking", false))
+            .await
+            .unwrap();
+        assert!(plugin.has_active_call());
+
+        plugin
+            .handle_telephony_event(&call_event("talking", true))
+            .await
+            .unwrap();
+        assert!(plugin.get_current_call().is_none());
+
+        assert_eq!(
+            internal_packets(&mut rx),
+            vec![
+                "cconnect.internal.telephony.ringing",
+                "cconnect.internal.telephony.talking",
+                "cconnect.internal.telephony.ended",
+            ]
+        );
+        assert_eq!(plugin.call_history_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_call_ended_signaled_once() {
+        let mut plugin = TelephonyPlugin::new();
+        let (tx, mut rx) = mpsc::channel(100);
+        plugin.init(&create_test_device(), tx).await.unwrap();
+
+        plugin
+            .handle_telephony_event(&call_event("ringing", false))
+            .await
+            .unwrap();
+
+        // Phones send a cancelled ringing event and then a missed call
+        plugin
+            .handle_telephony_event(&call_event("ringing", true))
+            .await
+            .unwrap();
+        plugin
+            .handle_telephony_event(&call_event("ringing", true))
+            .await
+            .unwrap();
+        plugin
+            .handle_telephony_event(&call_event("missedCall", false))
+            .await
+            .unwrap();
+
+        let ended = internal_packets(&mut rx)
+            .into_iter()
+            .filter(|t| t == "cconnect.internal.telephony.ended")
+            .count();
+        assert_eq!(ended, 1);
+        assert_eq!(plugin.missed_call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stop_ends_call_in_progress() {
+        let mut plugin = TelephonyPlugin::new();
+        let (tx, mut rx) = mpsc::channel(100);
+        plugin.init(&create_test_device(), tx).await.unwrap();
+
+        plugin
+            .handle_telephony_event(&call_event("ringing", false))
+            .await
+            .unwrap();
+        plugin.stop().await.unwrap();
+
+        assert!(plugin.get_current_call().is_none());
+        assert_eq!(
+            internal_packets(&mut rx),
+            vec![
+                "cconnect.internal.telephony.ringing",
+                "cconnect.internal.telephony.ended",
+            ]
+        );
+
+        // Nothing to end without a call
+        plugin.stop().await.unwrap();
+        assert!(internal_packets(&mut rx).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mute_ringing() {
+        let mut plugin = TelephonyPlugin::new();
+        assert!(plugin.mute_ringing().await.is_err());
+
+        let (tx, mut rx) = mpsc::channel(100);
+        plugin.init(&create_test_device(), tx).await.unwrap();
+        plugin.mute_ringing().await.unwrap();
+
+        let (_, packet) = rx.try_recv().unwrap();
+        assert_eq!(packet.packet_type, PACKET_TYPE_TELEPHONY_MUTE);
+    }
+
     #[tokio::test]
     async fn test_sms_conversations() {
         let plugin = TelephonyPlugin::new();
@@ -997,4 +1419,133 @@ mod tests {
         let history = plugin.get_call_history();
         assert_eq!(history[0].phone_number.as_deref(), Some("+1234567894"));
     }
+
+    fn sms_message(id: i64, thread_id: i64, addresses: &[&str], date: i64) -> serde_json::Value {
+        json!({
+            "_id": id,
+            "thread_id": thread_id,
+            "addresses": addresses
+                .iter()
+                .map(|address| json!({ "address": address }))
+                .collect::<Vec<_>>(),
+            "body": format!("Message {}", id),
+            "date": date,
+            "type": 1,
+            "read": 0
+        })
+    }
+
+    #[tokio::test]
+    async fn test_sms_group_thread() {
+        let plugin = TelephonyPlugin::new();
+
+        let packet = Packet::new(
+            "kdeconnect.sms.messages",
+            json!({
+                "messages": [
+                    sms_message(1, 7, &["+1555000111", "+1555000222"], 1000),
+                    sms_message(2, 7, &["+1555000222", "+1555000333"], 2000),
+                    sms_message(3, 8, &["+1555000444"], 1500),
+                ]
+            }),
+        );
+        plugin.handle_sms_messages(&packet).await.unwrap();
+
+        assert_eq!(plugin.conversation_count(), 2);
+
+        let group = plugin.get_conversation(7).unwrap();
+        assert!(group.is_group());
+        assert_eq!(
+            group.participants(),
+            vec!["+1555000111", "+1555000222", "+1555000333"]
+        );
+        assert_eq!(group.latest_message().unwrap().id, 2);
+
+        let single = plugin.get_conversation(8).unwrap();
+        assert!(!single.is_group());
+        assert_eq!(single.participants(), vec!["+1555000444"]);
+    }
+
+    #[tokio::test]
+    async fn test_sms_incremental_update() {
+        let mut plugin = TelephonyPlugin::new();
+        let (tx, mut rx) = mpsc::channel(100);
+        plugin.init(&create_test_device(), tx).await.unwrap();
+
+        let packet = Packet::new(
+            "cconnect.sms.messages",
+            json!({
+                "messages": [
+                    sms_message(1, 7, &["+1555000111"], 1000),
+                    sms_message(2, 7, &["+1555000111"], 2000),
+                ]
+            }),
+        );
+        plugin.handle_sms_messages(&packet).await.unwrap();
+        while rx.try_recv().is_ok() {}
+
+        // A new message arrives in the open thread, and an earlier one is read
+        let mut read = sms_message(1, 7, &["+1555000111"], 1000);
+        read["read"] = json!(1);
+        let packet = Packet::new(
+            "cconnect.sms.messages",
+            json!({
+                "messages": [sms_message(3, 7, &["+1555000111"], 3000), read]
+            }),
+        );
+        plugin.handle_sms_messages(&packet).await.unwrap();
+
+        let thread = plugin.get_conversation(7).unwrap();
+        let ids: Vec<i64> = thread.messages.iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(plugin.unread_sms_count(), 2);
+
+        // Only the new message is signaled
+        let mut received = Vec::new();
+        while let Ok((_, packet)) = rx.try_recv() {
+            if packet.is_type("cconnect.internal.sms.received") {
+                received.push(packet.body["body"].clone());
+            }
+        }
+        assert_eq!(received, vec![json!("Message 3")]);
+    }
+
+    #[tokio::test]
+    async fn test_reply_to_group_thread() {
+        let mut plugin = TelephonyPlugin::new();
+        let (tx, mut rx) = mpsc::channel(100);
+        plugin.init(&create_test_device(), tx).await.unwrap();
+
+        let packet = Packet::new(
+            "cconnect.sms.messages",
+            json!({
+                "messages": [sms_message(1, 7, &["+1555000111", "+1555000222"], 1000)]
+            }),
+        );
+        plugin.handle_sms_messages(&packet).await.unwrap();
+        while rx.try_recv().is_ok() {}
+
+        plugin.reply(7, "On my way".to_string()).await.unwrap();
+        let (_, packet) = rx.try_recv().unwrap();
+        assert_eq!(packet.packet_type, PACKET_TYPE_SMS_REQUEST);
+        assert_eq!(packet.body["messageBody"], "On my way");
+        assert_eq!(
+            packet.body["addresses"],
+            json!([{ "address": "+1555000111" }, { "address": "+1555000222" }])
+        );
+        assert!(packet.body.get("phoneNumber").is_none());
+
+        plugin
+            .send_message(&["+1555000111".to_string()], "Hi".to_string())
+            .await
+            .unwrap();
+        let (_, packet) = rx.try_recv().unwrap();
+        assert_eq!(packet.body["phoneNumber"], "+1555000111");
+
+        assert!(plugin.reply(99, "Hello?".to_string()).await.is_err());
+        assert!(plugin
+            .send_message(&[], "Hello?".to_string())
+            .await
+            .is_err());
+    }
 }
@@ -110,6 +110,7 @@ impl Default for PowerStatus {
 }
 
 /// UPower DBus backend for power state detection
+#[derive(Debug)]
 pub struct UPowerBackend {
     /// DBus connection
     connection: Option<Connection>,
@@ -0,0 +1,554 @@
+//! QUIC Connection Manager
+//!
+//! Manages QUIC connections to multiple devices using the QuicConnection
+//! transport. This is analogous to BluetoothConnectionManager but for QUIC
+//! over UDP.
+//!
+//! QUIC is only used between devices that are already paired over TCP.
+//! Peers are identified by the certificate they present in the handshake,
+//! which must be the one pinned when pairing; any other peer is refused.
+
+use crate::{
+    transport::{quic, QuicConnection, QuicListener, Transport},
+    transport_manager::TransportManagerEvent,
+    CertificateInfo, DeviceManager, Packet, ProtocolError, Result, TransportType,
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+/// Commands that can be sent to a QUIC connection task
+enum QuicConnectionCommand {
+    /// Send a packet
+    SendPacket(Packet),
+    /// Close the connection
+    Close,
+}
+
+/// Active QUIC connection to a device
+struct ActiveQuicConnection {
+    /// Channel to send commands to the connection task
+    command_tx: mpsc::UnboundedSender<QuicConnectionCommand>,
+    /// Task handling this connection
+    task: Option<JoinHandle<()>>,
+}
+
+/// QUIC connection manager for handling multiple QUIC connections
+pub struct QuicConnectionManager {
+    /// Active connections (device_id -> connection)
+    connections: Arc<RwLock<HashMap<String, ActiveQuicConnection>>>,
+
+    /// Event channel sender
+    event_tx: mpsc::UnboundedSender<TransportManagerEvent>,
+
+    /// Event channel receiver
+    event_rx: Arc<RwLock<mpsc::UnboundedReceiver<TransportManagerEvent>>>,
+
+    /// Local device certificate used for both directions of the handshake
+    certificate: Arc<CertificateInfo>,
+
+    /// Device manager, for the certificates of paired devices
+    device_manager: Arc<RwLock<DeviceManager>>,
+
+    /// Client configuration for outgoing connections
+    client_config: quinn::ClientConfig,
+
+    /// Local address to accept incoming connections on
+    listen_addr: SocketAddr,
+
+    /// Listening endpoint, closed on stop
+    listener: Arc<RwLock<Option<Arc<QuicListener>>>>,
+
+    /// Listener task handle (for accepting incoming connections)
+    listener_task: Arc<RwLock<Option<JoinHandle<()>>>>,
+}
+
+impl QuicConnectionManager {
+    /// Create a new QUIC connection manager
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate` - Local device certificate used for the handshake
+    /// * `listen_addr` - Local UDP address to accept connections on
+    /// * `device_manager` - Device manager holding the paired devices
+    pub fn new(
+        certificate: &CertificateInfo,
+        listen_addr: SocketAddr,
+        device_manager: Arc<RwLock<DeviceManager>>,
+    ) -> Result<Self> {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+
+        Ok(Self {
+            connections: Arc::new(RwLock::new(HashMap::new())),
+            event_tx,
+            event_rx: Arc::new(RwLock::new(event_rx)),
+            certificate: Arc::new(certificate.clone()),
+            device_manager,
+            client_config: quic::client_config(certificate)?,
+            listen_addr,
+            listener: Arc::new(RwLock::new(None)),
+            listener_task: Arc::new(RwLock::new(None)),
+        })
+    }
+
+    /// Start the QUIC connection manager
+    ///
+    /// Binds the listening endpoint and accepts incoming connections.
+    /// Returns the local address the endpoint is bound to.
+    pub async fn start(&self) -> Result<SocketAddr> {
+        info!("Starting QUIC connection manager...");
+
+        let listener = Arc::new(QuicListener::bind(self.listen_addr, &self.certificate)?);
+        let local_addr = listener.local_addr()?;
+        *self.listener.write().await = Some(listener.clone());
+
+        let connections = self.connections.clone();
+        let event_tx = self.event_tx.clone();
+        let device_manager = self.device_manager.clone();
+
+        let task = tokio::spawn(async move {
+            info!("QUIC listener task started");
+
+            loop {
+                match listener.accept().await {
+                    Ok(connection) => {
+                        let remote_addr = connection.remote_addr();
+                        let device_id =
+                            match Self::authenticate(&device_manager, &connection, None).await {
+                                Ok(device_id) => device_id,
+                                Err(e) => {
+                                    warn!("Refusing QUIC connection from {}: {}", remote_addr, e);
+                                    let _ = Box::new(connection).close().await;
+                                    continue;
+                                }
+                            };
+                        info!(
+                            "Accepted QUIC connection from device {} at {}",
+                            device_id, remote_addr
+                        );
+
+                        Self::spawn_connection_handler(
+                            connection,
+                            device_id,
+                            event_tx.clone(),
+                            connections.clone(),
+                        )
+                        .await;
+                    }
+                    Err(ProtocolError::Timeout(e)) => {
+                        debug!("Incoming QUIC connection opened no stream: {}", e);
+                    }
+                    Err(e) => {
+                        error!("Error accepting QUIC connection: {}", e);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                }
+            }
+        });
+
+        *self.listener_task.write().await = Some(task);
+
+        info!("QUIC connection manager listening on {}", local_addr);
+        Ok(local_addr)
+    }
+
+    /// Connect to a remote device via QUIC
+    ///
+    /// The device must be paired and present its pinned certificate.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - Device ID for identification
+    /// * `addr` - Remote UDP socket address
+    pub async fn connect(&self, device_id: &str, addr: SocketAddr) -> Result<()> {
+        info!("Connecting to device {} at {} over QUIC", device_id, addr);
+
+        if self.has_connection(device_id).await {
+            info!("Already connected to device {}", device_id);
+            return Ok(());
+        }
+
+        let connection = QuicConnection::connect_with_config(addr, self.client_config.clone())
+            .await
+            .map_err(|e| {
+                ProtocolError::transport(format!("Failed to connect to {} over QUIC: {}", addr, e))
+            })?;
+        if let Err(e) = Self::authenticate(&self.device_manager, &connection, Some(device_id)).await
+        {
+            let _ = Box::new(connection).close().await;
+            return Err(e);
+        }
+
+        Self::spawn_connection_handler(
+            connection,
+            device_id.to_string(),
+            self.event_tx.clone(),
+            self.connections.clone(),
+        )
+        .await;
+
+        info!("Connected to device {} at {} over QUIC", device_id, addr);
+        Ok(())
+    }
+
+    /// Paired device a QUIC peer authenticated as
+    ///
+    /// The peer is identified by the certificate it presented. With
+    /// `expected` set, as for outgoing connections, it must be that device.
+    /// Peers without a certificate, or whose certificate isn't pinned to a
+    /// paired device, are refused.
+    async fn authenticate(
+        device_manager: &RwLock<DeviceManager>,
+        connection: &QuicConnection,
+        expected: Option<&str>,
+    ) -> Result<String> {
+        let certificate = connection.peer_certificate().ok_or_else(|| {
+            ProtocolError::CertUntrusted("QUIC peer presented no certificate".to_string())
+        })?;
+
+        let dm = device_manager.read().await;
+        match expected {
+            Some(device_id) => {
+                if !dm.get_device(device_id).is_some_and(|d| d.is_paired()) {
+                    return Err(ProtocolError::NotPaired);
+                }
+                dm.verify_certificate(device_id, Some(&certificate))?;
+                Ok(device_id.to_string())
+            }
+            None => dm
+                .paired_device_for_certificate(&certificate)
+                .map(|device| device.id().to_string())
+                .ok_or(ProtocolError::NotPaired),
+        }
+    }
+
+    /// Spawn a task to handle a QUIC connection (send/receive)
+    ///
+    /// The connection is registered before the task starts, so packets can be
+    /// queued as soon as this returns.
+    async fn spawn_connection_handler(
+        mut connection: QuicConnection,
+        device_id: String,
+        event_tx: mpsc::UnboundedSender<TransportManagerEvent>,
+        connections: Arc<RwLock<HashMap<String, ActiveQuicConnection>>>,
+    ) {
+        let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+
+        let mut conns = connections.write().await;
+        conns.insert(
+            device_id.clone(),
+            ActiveQuicConnection {
+                command_tx,
+                task: None,
+            },
+        );
+
+        let connections_for_task = connections.clone();
+        let device_id_for_task = device_id.clone();
+        let task = tokio::spawn(async move {
+            let device_id = device_id_for_task;
+            info!("QUIC connection handler started for {}", device_id);
+
+            let _ = event_tx.send(TransportManagerEvent::Connected {
+                device_id: device_id.clone(),
+                transport_type: TransportType::Quic,
+            });
+
+            loop {
+                tokio::select! {
+                    cmd = command_rx.recv() => {
+                        match cmd {
+                            Some(QuicConnectionCommand::SendPacket(packet)) => {
+                                debug!("Sending packet '{}' to {} via QUIC", packet.packet_type, device_id);
+                                if let Err(e) = connection.send_packet(&packet).await {
+                                    error!("Failed to send packet to {} via QUIC: {}", device_id, e);
+                                    break;
+                                }
+                            }
+                            Some(QuicConnectionCommand::Close) | None => {
+                                info!("Closing QUIC connection to {}", device_id);
+                                break;
+                            }
+                        }
+                    }
+
+                    result = connection.receive_packet() => {
+                        match result {
+                            Ok(packet) => {
+                                debug!("Received packet '{}' from {} via QUIC", packet.packet_type, device_id);
+                                let _ = event_tx.send(TransportManagerEvent::PacketReceived {
+                                    device_id: device_id.clone(),
+                                    packet,
+                                    transport_type: TransportType::Quic,
+                                });
+                            }
+                            Err(e) => {
+                                warn!("Error receiving packet from {} via QUIC: {}", device_id, e);
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+
+            connections_for_task.write().await.remove(&device_id);
+
+            let _ = event_tx.send(TransportManagerEvent::Disconnected {
+                device_id: device_id.clone(),
+                transport_type: TransportType::Quic,
+                reason: Some("Connection closed".to_string()),
+            });
+
+            if let Err(e) = Box::new(connection).close().await {
+                warn!("Error closing QUIC connection to {}: {}", device_id, e);
+            }
+
+            info!("QUIC connection handler for {} stopped", device_id);
+        });
+
+        if let Some(conn) = conns.get_mut(&device_id) {
+            conn.task = Some(task);
+        }
+    }
+
+    /// Send a packet to a device
+    pub async fn send_packet(&self, device_id: &str, packet: &Packet) -> Result<()> {
+        let connections = self.connections.read().await;
+        let connection = connections.get(device_id).ok_or_else(|| {
+            ProtocolError::DeviceNotFound(format!("Not connected to device {} via QUIC", device_id))
+        })?;
+
+        connection
+            .command_tx
+            .send(QuicConnectionCommand::SendPacket(packet.clone()))
+            .map_err(|_| {
+                ProtocolError::Io(std::io::Error::new(
+                    std::io::ErrorKind::BrokenPipe,
+                    "QUIC connection closed",
+                ))
+            })
+    }
+
+    /// Disconnect from a device
+    ///
+    /// The connection task closes the connection itself once it sees the
+    /// close command.
+    pub async fn disconnect(&self, device_id: &str) -> Result<()> {
+        info!("Disconnecting from device {} (QUIC)", device_id);
+
+        let active_conn = self.connections.write().await.remove(device_id);
+        if let Some(active_conn) = active_conn {
+            let _ = active_conn.command_tx.send(QuicConnectionCommand::Close);
+        }
+
+        Ok(())
+    }
+
+    /// Check if there's an active connection to a device
+    pub async fn has_connection(&self, device_id: &str) -> bool {
+        self.connections.read().await.contains_key(device_id)
+    }
+
+    /// Get a receiver for connection events
+    pub async fn subscribe(&self) -> mpsc::UnboundedReceiver<TransportManagerEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        // Forward events
+        let event_rx = self.event_rx.clone();
+        tokio::spawn(async move {
+            let mut rx_lock = event_rx.write().await;
+            while let Some(event) = rx_lock.recv().await {
+                if tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        rx
+    }
+
+    /// Stop the QUIC connection manager
+    pub async fn stop(&self) {
+        info!("Stopping QUIC connection manager");
+
+        if let Some(task) = self.listener_task.write().await.take() {
+            task.abort();
+        }
+
+        let active: Vec<ActiveQuicConnection> = self
+            .connections
+            .write()
+            .await
+            .drain()
+            .map(|(_, conn)| conn)
+            .collect();
+        for conn in active {
+            let _ = conn.command_tx.send(QuicConnectionCommand::Close);
+            if let Some(task) = conn.task {
+                let _ = task.await;
+            }
+        }
+
+        if let Some(listener) = self.listener.write().await.take() {
+            listener.close();
+        }
+
+        info!("QUIC connection manager stopped");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{Device, DeviceInfo, DeviceType};
+
+    /// A device's QUIC manager, with its own certificate and device registry
+    struct Side {
+        info: DeviceInfo,
+        device_id: String,
+        certificate: CertificateInfo,
+        device_manager: Arc<RwLock<DeviceManager>>,
+        manager: QuicConnectionManager,
+        _dir: tempfile::TempDir,
+    }
+
+    impl Side {
+        fn new(name: &str) -> Self {
+            let info = DeviceInfo::new(name, DeviceType::Desktop, 1716);
+            let certificate = CertificateInfo::generate(&info.device_id).unwrap();
+            let dir = tempfile::TempDir::new().unwrap();
+            let device_manager = Arc::new(RwLock::new(
+                DeviceManager::new(dir.path().join("registry.json")).unwrap(),
+            ));
+            let manager = QuicConnectionManager::new(
+                &certificate,
+                "127.0.0.1:0".parse().unwrap(),
+                device_manager.clone(),
+            )
+            .unwrap();
+
+            Self {
+                device_id: info.device_id.clone(),
+                info,
+                certificate,
+                device_manager,
+                manager,
+                _dir: dir,
+            }
+        }
+
+        /// Record `other` as paired, pinned to its certificate
+        async fn pair_with(&self, other: &Side) {
+            let mut dm = self.device_manager.write().await;
+            dm.add_device(Device::from_discovery(other.info.clone()));
+            dm.mark_paired(
+                &other.device_id,
+                CertificateInfo::calculate_fingerprint(&other.certificate.certificate),
+            )
+            .unwrap();
+        }
+    }
+
+    /// Wait for the first packet the manager reports, if any within a second
+    async fn next_packet(
+        events: &mut mpsc::UnboundedReceiver<TransportManagerEvent>,
+    ) -> Option<(String, Packet)> {
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while let Some(event) = events.recv().await {
+                if let TransportManagerEvent::PacketReceived {
+                    device_id,
+                    packet,
+                    transport_type,
+                } = event
+                {
+                    assert_eq!(transport_type, TransportType::Quic);
+                    return Some((device_id, packet));
+                }
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    #[tokio::test]
+    async fn test_packets_flow_between_managers() {
+        let server = Side::new("Server");
+        let client = Side::new("Client");
+        server.pair_with(&client).await;
+        client.pair_with(&server).await;
+
+        let addr = server.manager.start().await.unwrap();
+        let mut server_events = server.manager.subscribe().await;
+
+        client
+            .manager
+            .connect(&server.device_id, addr)
+            .await
+            .unwrap();
+        assert!(client.manager.has_connection(&server.device_id).await);
+
+        // The accepting side only sees the connection once the first packet arrives
+        client
+            .manager
+            .send_packet(
+                &server.device_id,
+                &Packet::new("cconnect.ping", serde_json::json!({})),
+            )
+            .await
+            .unwrap();
+
+        // Identified by its certificate, not its address
+        let (device_id, packet) = next_packet(&mut server_events).await.unwrap();
+        assert_eq!(device_id, client.device_id);
+        assert_eq!(packet.packet_type, "cconnect.ping");
+
+        client.manager.disconnect(&server.device_id).await.unwrap();
+        assert!(!client.manager.has_connection(&server.device_id).await);
+
+        client.manager.stop().await;
+        server.manager.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_unpaired_peers_refused() {
+        let server = Side::new("Server");
+        let client = Side::new("Client");
+        client.pair_with(&server).await;
+
+        let addr = server.manager.start().await.unwrap();
+        let mut server_events = server.manager.subscribe().await;
+
+        // The server doesn't know the client, so nothing it sends gets through
+        client
+            .manager
+            .connect(&server.device_id, addr)
+            .await
+            .unwrap();
+        let _ = client
+            .manager
+            .send_packet(
+                &server.device_id,
+                &Packet::new("cconnect.ping", serde_json::json!({})),
+            )
+            .await;
+        assert!(next_packet(&mut server_events).await.is_none());
+
+        // Nor does a client connect to a server it isn't paired with
+        let stranger = Side::new("Stranger");
+        assert!(matches!(
+            stranger.manager.connect(&server.device_id, addr).await,
+            Err(ProtocolError::NotPaired)
+        ));
+        assert!(!stranger.manager.has_connection(&server.device_id).await);
+
+        client.manager.stop().await;
+        server.manager.stop().await;
+    }
+}
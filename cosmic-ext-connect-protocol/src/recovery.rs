@@ -8,7 +8,7 @@
 
 use crate::{Packet, ProtocolError, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -116,6 +116,15 @@ pub struct TransferState {
     pub started_at: u64,
     /// Last update timestamp
     pub last_updated: u64,
+    /// BLAKE3 hash (hex) of the whole file, if the sender provided one
+    #[serde(default)]
+    pub hash: Option<String>,
+    /// Number of payload segments started (the first download and each resume)
+    #[serde(default)]
+    pub segments: u64,
+    /// Segments refused as overlapping, out of order, or leaving a gap
+    #[serde(default)]
+    pub rejected_segments: u64,
 }
 
 impl TransferState {
@@ -141,6 +150,9 @@ impl TransferState {
             bytes_received: 0,
             started_at: now,
             last_updated: now,
+            hash: None,
+            segments: 0,
+            rejected_segments: 0,
         }
     }
 
@@ -158,6 +170,17 @@ impl TransferState {
         self.bytes_received >= self.total_size
     }
 
+    /// Number of received bytes a resumed transfer can keep
+    ///
+    /// This is the length of the partial file, capped at the expected size,
+    /// since more may have been written than the last recorded progress.
+    /// Returns 0 if the file is gone.
+    pub fn resume_offset(&self) -> u64 {
+        std::fs::metadata(&self.file_path)
+            .map(|m| m.len().min(self.total_size))
+            .unwrap_or(0)
+    }
+
     /// Get transfer progress percentage
     pub fn progress_percentage(&self) -> f64 {
         if self.total_size == 0 {
@@ -181,6 +204,7 @@ struct PacketRetryEntry {
 }
 
 /// Recovery manager for handling connection and transfer recovery
+#[derive(Debug)]
 pub struct RecoveryManager {
     /// Reconnection strategies per device
     reconnection_strategies: Arc<RwLock<HashMap<String, ReconnectionStrategy>>>,
@@ -188,6 +212,8 @@ pub struct RecoveryManager {
     transfer_states: Arc<RwLock<HashMap<String, TransferState>>>,
     /// Packet retry queue
     retry_queue: Arc<RwLock<Vec<PacketRetryEntry>>>,
+    /// Transfers with a segment currently being written
+    writing_segments: Arc<RwLock<HashSet<String>>>,
     /// Path to state persistence file
     state_file_path: PathBuf,
 }
@@ -201,6 +227,7 @@ impl RecoveryManager {
             reconnection_strategies: Arc::new(RwLock::new(HashMap::new())),
             transfer_states: Arc::new(RwLock::new(HashMap::new())),
             retry_queue: Arc::new(RwLock::new(Vec::new())),
+            writing_segments: Arc::new(RwLock::new(HashSet::new())),
             state_file_path,
         }
     }
@@ -315,6 +342,69 @@ impl RecoveryManager {
         Ok(())
     }
 
+    /// Start writing payload segment `segment` of a transfer at `offset`
+    ///
+    /// Segments must arrive in order, one at a time, and continue from what
+    /// is already on disk. A segment that overlaps one still being written,
+    /// repeats or skips a segment number, or would leave a gap in the file is
+    /// refused and counted in the transfer's `rejected_segments`.
+    pub async fn begin_segment(&self, transfer_id: &str, segment: u64, offset: u64) -> Result<()> {
+        let mut writing = self.writing_segments.write().await;
+        let mut states = self.transfer_states.write().await;
+        let state = states.get_mut(transfer_id).ok_or_else(|| {
+            ProtocolError::InvalidState(format!("Unknown transfer {}", transfer_id))
+        })?;
+
+        let refusal = if writing.contains(transfer_id) {
+            Some(format!(
+                "a segment of transfer {} is still being written",
+                transfer_id
+            ))
+        } else if segment != state.segments {
+            Some(format!(
+                "segment {} of transfer {} is out of order, expected {}",
+                segment, transfer_id, state.segments
+            ))
+        } else if offset > state.resume_offset() {
+            Some(format!(
+                "segment {} of transfer {} starts at {} past the {} bytes received",
+                segment,
+                transfer_id,
+                offset,
+                state.resume_offset()
+            ))
+        } else {
+            None
+        };
+
+        match refusal {
+            Some(reason) => {
+                state.rejected_segments += 1;
+                drop(states);
+                drop(writing);
+                self.persist_transfer_states().await?;
+                Err(ProtocolError::InvalidState(reason))
+            }
+            None => {
+                state.segments = segment + 1;
+                writing.insert(transfer_id.to_string());
+                drop(states);
+                drop(writing);
+                self.persist_transfer_states().await?;
+                debug!(
+                    "Started segment {} of transfer {} at offset {}",
+                    segment, transfer_id, offset
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Finish writing the current payload segment of a transfer
+    pub async fn end_segment(&self, transfer_id: &str) {
+        self.writing_segments.write().await.remove(transfer_id);
+    }
+
     /// Get transfer state by ID
     pub async fn get_transfer_state(&self, transfer_id: &str) -> Option<TransferState> {
         let states = self.transfer_states.read().await;
@@ -548,6 +638,26 @@ mod tests {
         assert_eq!(state.progress_percentage(), 100.0);
     }
 
+    #[test]
+    fn test_transfer_state_resume_offset() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("video.mp4");
+        let state = TransferState::new(
+            "transfer-1".to_string(),
+            "device-1".to_string(),
+            "video.mp4".to_string(),
+            path.clone(),
+            1000,
+        );
+        assert_eq!(state.resume_offset(), 0);
+
+        std::fs::write(&path, vec![0u8; 600]).unwrap();
+        assert_eq!(state.resume_offset(), 600);
+
+        std::fs::write(&path, vec![0u8; 1200]).unwrap();
+        assert_eq!(state.resume_offset(), 1000);
+    }
+
     #[tokio::test]
     async fn test_recovery_manager_transfer_tracking() {
         let temp_dir = TempDir::new().unwrap();
@@ -583,6 +693,50 @@ mod tests {
         assert!(manager.get_transfer_state("transfer-1").await.is_none());
     }
 
+    #[tokio::test]
+    async fn test_recovery_manager_segment_sequence() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("video.mp4");
+        let manager = RecoveryManager::new(temp_dir.path());
+        manager
+            .register_transfer(TransferState::new(
+                "transfer-1".to_string(),
+                "device-1".to_string(),
+                "video.mp4".to_string(),
+                path.clone(),
+                1000,
+            ))
+            .await
+            .unwrap();
+
+        manager.begin_segment("transfer-1", 0, 0).await.unwrap();
+        // A duplicate resume while the first segment is still written
+        assert!(manager.begin_segment("transfer-1", 1, 0).await.is_err());
+        std::fs::write(&path, vec![0u8; 400]).unwrap();
+        manager.end_segment("transfer-1").await;
+
+        // Repeated and skipped segment numbers
+        assert!(manager.begin_segment("transfer-1", 0, 400).await.is_err());
+        assert!(manager.begin_segment("transfer-1", 2, 400).await.is_err());
+        // Starting past the received bytes would leave a gap
+        assert!(manager.begin_segment("transfer-1", 1, 600).await.is_err());
+
+        manager.begin_segment("transfer-1", 1, 400).await.unwrap();
+        manager.end_segment("transfer-1").await;
+
+        let state = manager.get_transfer_state("transfer-1").await.unwrap();
+        assert_eq!(state.segments, 2);
+        assert_eq!(state.rejected_segments, 4);
+
+        // The counters survive a daemon restart
+        let restored = RecoveryManager::new(temp_dir.path());
+        restored.init().await.unwrap();
+        let state = restored.get_transfer_state("transfer-1").await.unwrap();
+        assert_eq!(state.segments, 2);
+        assert_eq!(state.rejected_segments, 4);
+        assert!(restored.begin_segment("unknown", 0, 0).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_recovery_manager_packet_retry() {
         let temp_dir = TempDir::new().unwrap();
@@ -5,7 +5,6 @@
 //! listening for connection failures and triggering appropriate recovery actions.
 
 use crate::{ConnectionEvent, ConnectionManager, DeviceManager, RecoveryManager, Result};
-use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio::time::sleep;
@@ -49,7 +48,7 @@ impl RecoveryCoordinator {
 
         tokio::spawn(async move {
             while let Some(event) = event_rx.recv().await {
-                match event {
+                let device_id = match event {
                     ConnectionEvent::Connected { device_id, .. } => {
                         // Reset reconnection strategy on successful connection
                         recovery_manager
@@ -59,6 +58,7 @@ impl RecoveryCoordinator {
 
                         // Clear retry queue for this device
                         recovery_manager.clear_device_retry_queue(&device_id).await;
+                        continue;
                     }
 
                     ConnectionEvent::Disconnected { device_id, reason, reconnect } => {
@@ -68,97 +68,16 @@ impl RecoveryCoordinator {
                             reason.as_deref().unwrap_or("unknown reason"),
                             reconnect
                         );
+                        device_id
+                    }
 
-                        // Check if device is paired (only auto-reconnect to paired devices)
-                        let dm = device_manager.read().await;
-                        let should_reconnect = if let Some(device) = dm.get_device(&device_id) {
-                            device.is_paired() && device.is_trusted
-                        } else {
-                            false
-                        };
-                        drop(dm);
-
-                        if !should_reconnect {
-                            debug!(
-                                "Skipping auto-reconnect for device {} (not paired or trusted)",
-                                device_id
-                            );
-                            continue;
-                        }
-
-                        // Get reconnection delay with exponential backoff
-                        if let Some(delay) = recovery_manager.should_reconnect(&device_id).await {
-                            info!(
-                                "Scheduling reconnection for device {} after {:?}",
-                                device_id, delay
-                            );
-
-                            // Spawn reconnection task with delay
-                            let device_id_clone = device_id.clone();
-                            let device_manager_clone = device_manager.clone();
-                            let connection_manager_clone = connection_manager.clone();
-
-                            tokio::spawn(async move {
-                                // Wait for backoff delay
-                                sleep(delay).await;
-
-                                // Get device info for connection
-                                let (host_opt, port_opt) = {
-                                    let dm = device_manager_clone.read().await;
-                                    if let Some(device) = dm.get_device(&device_id_clone) {
-                                        (device.host.clone(), device.port)
-                                    } else {
-                                        (None, None)
-                                    }
-                                };
-
-                                if let (Some(host), Some(port)) = (host_opt, port_opt) {
-                                    info!(
-                                        "Attempting reconnection to device {} at {}:{}",
-                                        device_id_clone, host, port
-                                    );
-
-                                    // Parse socket address
-                                    let addr_str = format!("{}:{}", host, port);
-                                    if let Ok(addr) = addr_str.parse::<SocketAddr>() {
-                                        // Attempt reconnection
-                                        match connection_manager_clone
-                                            .connect(&device_id_clone, addr)
-                                            .await
-                                        {
-                                            Ok(_) => {
-                                                info!(
-                                                    "Successfully reconnected to device {}",
-                                                    device_id_clone
-                                                );
-                                            }
-                                            Err(e) => {
-                                                warn!(
-                                                    "Failed to reconnect to device {}: {}",
-                                                    device_id_clone, e
-                                                );
-                                                // The next disconnection event will trigger another attempt
-                                            }
-                                        }
-                                    } else {
-                                        warn!(
-                                            "Invalid address {}:{} for device {}",
-                                            host, port, device_id_clone
-                                        );
-                                    }
-                                } else {
-                                    debug!(
-                                        "Device {} has no host/port info, cannot reconnect",
-                                        device_id_clone
-                                    );
-                                }
-                            });
-                        } else {
-                            warn!(
-                                "Max reconnection attempts reached for device {}, giving up",
-                                device_id
-                            );
-                        }
+                    ConnectionEvent::Timeout { device_id, idle } => {
+                        info!(
+                            "Device {} timed out after {}s without traffic",
+                            device_id,
+                            idle.as_secs()
+                        );
+                        device_id
                     }
 
                     ConnectionEvent::ConnectionError { device_id, message } => {
@@ -169,11 +88,93 @@ impl RecoveryCoordinator {
                         } else {
                             warn!("Connection error: {}", message);
                         }
+                        continue;
                     }
 
                     _ => {
                         // Ignore other events
+                        continue;
                     }
+                };
+
+                // Check if device is paired (only auto-reconnect to paired devices)
+                let dm = device_manager.read().await;
+                let should_reconnect = if let Some(device) = dm.get_device(&device_id) {
+                    device.is_paired() && device.is_trusted
+                } else {
+                    false
+                };
+                drop(dm);
+
+                if !should_reconnect {
+                    debug!(
+                        "Skipping auto-reconnect for device {} (not paired or trusted)",
+                        device_id
+                    );
+                    continue;
+                }
+
+                // Get reconnection delay with exponential backoff
+                if let Some(delay) = recovery_manager.should_reconnect(&device_id).await {
+                    info!(
+                        "Scheduling reconnection for device {} after {:?}",
+                        device_id, delay
+                    );
+
+                    // Spawn reconnection task with delay
+                    let device_id_clone = device_id.clone();
+                    let device_manager_clone = device_manager.clone();
+                    let connection_manager_clone = connection_manager.clone();
+
+                    tokio::spawn(async move {
+                        // Wait for backoff delay
+                        sleep(delay).await;
+
+                        // Addresses from the device's latest identity packet
+                        let addresses = {
+                            let dm = device_manager_clone.read().await;
+                            dm.get_device(&device_id_clone)
+                                .map(|device| device.connect_addresses())
+                                .unwrap_or_default()
+                        };
+
+                        if addresses.is_empty() {
+                            debug!(
+                                "Device {} has no host/port info, cannot reconnect",
+                                device_id_clone
+                            );
+                            return;
+                        }
+
+                        for addr in addresses {
+                            info!(
+                                "Attempting reconnection to device {} at {}",
+                                device_id_clone, addr
+                            );
+
+                            match connection_manager_clone
+                                .connect(&device_id_clone, addr)
+                                .await
+                            {
+                                Ok(_) => {
+                                    info!("Successfully reconnected to device {}", device_id_clone);
+                                    return;
+                                }
+                                Err(e) => {
+                                    warn!(
+                                        "Failed to reconnect to device {} at {}: {}",
+                                        device_id_clone, addr, e
+                                    );
+                                }
+                            }
+                        }
+                        // The next disconnection event will trigger another attempt
+                    });
+                } else {
+                    warn!(
+                        "Max reconnection attempts reached for device {}, giving up",
+                        device_id
+                    );
                 }
             }
 
@@ -1,5 +1,60 @@
+use crate::plugins::audit::AuditLog;
+use crate::plugins::permission::{PermissionDecision, PermissionPolicy, PrivilegedAction};
 use crate::{Device, DeviceInfo, DeviceType};
+use async_trait::async_trait;
+use std::sync::Mutex;
 
 pub fn create_test_device() -> Device {
     Device::from_discovery(DeviceInfo::new("Test Device", DeviceType::Desktop, 1814))
 }
+
+/// Permission policy with a fixed answer that records every check
+#[derive(Debug, Default)]
+pub struct MockPermissionPolicy {
+    allow: bool,
+    checks: Mutex<Vec<(String, PrivilegedAction)>>,
+    audit_log: Option<AuditLog>,
+}
+
+impl MockPermissionPolicy {
+    pub fn allowing() -> Self {
+        Self {
+            allow: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn denying() -> Self {
+        Self::default()
+    }
+
+    /// Record decisions to an audit log
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Checks made so far, as (device ID, action)
+    pub fn checks(&self) -> Vec<(String, PrivilegedAction)> {
+        self.checks.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl PermissionPolicy for MockPermissionPolicy {
+    async fn check(&self, device_id: &str, action: &PrivilegedAction) -> PermissionDecision {
+        self.checks
+            .lock()
+            .unwrap()
+            .push((device_id.to_string(), action.clone()));
+        if self.allow {
+            PermissionDecision::Allow
+        } else {
+            PermissionDecision::Deny("denied by mock policy".to_string())
+        }
+    }
+
+    fn audit_log(&self) -> Option<&AuditLog> {
+        self.audit_log.as_ref()
+    }
+}
@@ -154,6 +154,14 @@ impl BluetoothConnection {
 
         Ok(())
     }
+
+    /// Wrap an I/O error on the RFCOMM stream as a transport failure
+    fn link_error(&self, error: std::io::Error) -> ProtocolError {
+        ProtocolError::transport_with_source(
+            format!("Bluetooth link to {} failed", self.remote_address_str),
+            error,
+        )
+    }
 }
 
 impl std::fmt::Debug for BluetoothConnection {
@@ -192,11 +200,10 @@ impl Transport for BluetoothConnection {
         let bytes = packet.to_bytes()?;
 
         if bytes.len() > MAX_BT_PACKET_SIZE {
-            return Err(ProtocolError::InvalidPacket(format!(
-                "Packet too large for Bluetooth: {} bytes (max {})",
+            return Err(ProtocolError::payload_too_large(
                 bytes.len(),
-                MAX_BT_PACKET_SIZE
-            )));
+                MAX_BT_PACKET_SIZE,
+            ));
         }
 
         debug!(
@@ -213,13 +220,13 @@ impl Transport for BluetoothConnection {
         self.stream
             .write_all(&len_bytes)
             .await
-            .map_err(ProtocolError::Io)?;
+            .map_err(|e| self.link_error(e))?;
 
         // Write packet data
         self.stream
             .write_all(&bytes)
             .await
-            .map_err(ProtocolError::Io)?;
+            .map_err(|e| self.link_error(e))?;
 
         debug!("Packet sent successfully to {}", self.remote_address_str);
         Ok(())
@@ -236,21 +243,15 @@ impl Transport for BluetoothConnection {
         timeout(BT_TIMEOUT, self.stream.read_exact(&mut len_buf))
             .await
             .map_err(|_| {
-                ProtocolError::Io(std::io::Error::new(
-                    std::io::ErrorKind::TimedOut,
-                    "Read timeout waiting for packet length",
-                ))
+                ProtocolError::Timeout("Read timeout waiting for packet length".to_string())
             })?
-            .map_err(ProtocolError::Io)?;
+            .map_err(|e| self.link_error(e))?;
 
         let len = u32::from_be_bytes(len_buf) as usize;
 
         if len > MAX_BT_PACKET_SIZE {
             error!("Packet too large: {} bytes", len);
-            return Err(ProtocolError::InvalidPacket(format!(
-                "Packet too large: {} bytes (max {})",
-                len, MAX_BT_PACKET_SIZE
-            )));
+            return Err(ProtocolError::payload_too_large(len, MAX_BT_PACKET_SIZE));
         }
 
         // Read packet data
@@ -258,12 +259,9 @@ impl Transport for BluetoothConnection {
         timeout(BT_TIMEOUT, self.stream.read_exact(&mut packet_data))
             .await
             .map_err(|_| {
-                ProtocolError::Io(std::io::Error::new(
-                    std::io::ErrorKind::TimedOut,
-                    "Read timeout waiting for packet data",
-                ))
+                ProtocolError::Timeout("Read timeout waiting for packet data".to_string())
             })?
-            .map_err(ProtocolError::Io)?;
+            .map_err(|e| self.link_error(e))?;
 
         let packet = Packet::from_bytes(&packet_data)?;
         debug!(
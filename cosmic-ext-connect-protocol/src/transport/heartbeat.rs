@@ -0,0 +1,218 @@
+//! Per-Device Heartbeat Intervals
+//!
+//! Chooses how often keepalive pings are sent to each connected device.
+//! The default interval follows the [`LatencyCategory`] of the transport the
+//! device is currently connected over: low-latency links are pinged often so
+//! dead connections are noticed quickly, while slower links such as Bluetooth
+//! are pinged less often to save power. A per-device override always wins.
+//!
+//! The interval is recomputed whenever a device switches transport, and
+//! when the round-trip time measured from echoed keepalives moves a TCP
+//! connection into another latency category.
+
+use super::{LatencyCategory, TransportType};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::debug;
+
+/// Heartbeat interval for low-latency transports (TCP/QUIC on a LAN)
+pub const LOW_LATENCY_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Heartbeat interval for medium-latency transports (Bluetooth RFCOMM)
+pub const MEDIUM_LATENCY_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Heartbeat interval for high-latency transports
+pub const HIGH_LATENCY_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+impl LatencyCategory {
+    /// Default heartbeat interval for a transport in this latency category
+    pub fn heartbeat_interval(self) -> Duration {
+        match self {
+            LatencyCategory::Low => LOW_LATENCY_HEARTBEAT_INTERVAL,
+            LatencyCategory::Medium => MEDIUM_LATENCY_HEARTBEAT_INTERVAL,
+            LatencyCategory::High => HIGH_LATENCY_HEARTBEAT_INTERVAL,
+        }
+    }
+}
+
+impl TransportType {
+    /// Typical latency category of this transport type
+    ///
+    /// Matches the `latency` reported by the transport's
+    /// [`TransportCapabilities`](super::TransportCapabilities).
+    pub fn latency(self) -> LatencyCategory {
+        match self {
+            TransportType::Tcp => LatencyCategory::Low,
+            TransportType::Bluetooth => LatencyCategory::Medium,
+            TransportType::Quic => LatencyCategory::Low,
+        }
+    }
+}
+
+/// Heartbeat interval registry for connected devices
+///
+/// Tracks the latency category of each device's active transport together
+/// with optional per-device overrides.
+#[derive(Debug, Clone, Default)]
+pub struct HeartbeatIntervals {
+    /// Latency category of each device's active transport
+    active: HashMap<String, LatencyCategory>,
+
+    /// User-configured interval per device
+    overrides: HashMap<String, Duration>,
+}
+
+impl HeartbeatIntervals {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Heartbeat interval for a device
+    ///
+    /// Returns the device override if set, otherwise the default for the
+    /// device's active transport. Returns `None` if the device has no active
+    /// transport and no override.
+    pub fn interval_for(&self, device_id: &str) -> Option<Duration> {
+        self.overrides.get(device_id).copied().or_else(|| {
+            self.active
+                .get(device_id)
+                .map(|latency| latency.heartbeat_interval())
+        })
+    }
+
+    /// Heartbeat interval for a device on a transport with `latency`
+    ///
+    /// Like [`interval_for`](Self::interval_for), but falls back to `latency`
+    /// when the device has no active transport recorded yet.
+    pub fn interval_for_latency(&self, device_id: &str, latency: LatencyCategory) -> Duration {
+        self.overrides
+            .get(device_id)
+            .copied()
+            .unwrap_or_else(|| latency.heartbeat_interval())
+    }
+
+    /// Record that a device is now connected over a transport with `latency`
+    ///
+    /// Returns the recomputed heartbeat interval for the device.
+    pub fn transport_changed(&mut self, device_id: &str, latency: LatencyCategory) -> Duration {
+        let previous = self.active.insert(device_id.to_string(), latency);
+        if previous != Some(latency) {
+            debug!(
+                "Device {} transport latency changed {:?} -> {:?}",
+                device_id, previous, latency
+            );
+        }
+        self.interval_for_latency(device_id, latency)
+    }
+
+    /// Forget the active transport of a disconnected device
+    ///
+    /// Only clears the entry if it still refers to a transport with `latency`,
+    /// so a late disconnect of the old transport after a switch is ignored.
+    /// Overrides are kept so they apply again on the next connection.
+    pub fn transport_closed(&mut self, device_id: &str, latency: LatencyCategory) {
+        if self.active.get(device_id) == Some(&latency) {
+            self.active.remove(device_id);
+        }
+    }
+
+    /// Set or clear the heartbeat override for a device
+    pub fn set_override(&mut self, device_id: &str, interval: Option<Duration>) {
+        match interval {
+            Some(interval) => {
+                self.overrides.insert(device_id.to_string(), interval);
+            }
+            None => {
+                self.overrides.remove(device_id);
+            }
+        }
+    }
+
+    /// Latency category of a device's active transport
+    pub fn active_latency(&self, device_id: &str) -> Option<LatencyCategory> {
+        self.active.get(device_id).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_matches_latency_category() {
+        assert!(
+            LatencyCategory::Low.heartbeat_interval()
+                < LatencyCategory::Medium.heartbeat_interval()
+        );
+        assert!(
+            LatencyCategory::Medium.heartbeat_interval()
+                < LatencyCategory::High.heartbeat_interval()
+        );
+
+        let mut intervals = HeartbeatIntervals::new();
+        assert_eq!(intervals.interval_for("phone"), None);
+
+        let interval = intervals.transport_changed("phone", TransportType::Tcp.latency());
+        assert_eq!(interval, LOW_LATENCY_HEARTBEAT_INTERVAL);
+        assert_eq!(
+            intervals.interval_for("phone"),
+            Some(LOW_LATENCY_HEARTBEAT_INTERVAL)
+        );
+
+        let interval = intervals.transport_changed("tablet", TransportType::Bluetooth.latency());
+        assert_eq!(interval, MEDIUM_LATENCY_HEARTBEAT_INTERVAL);
+    }
+
+    #[test]
+    fn test_interval_updates_on_transport_switch() {
+        let mut intervals = HeartbeatIntervals::new();
+
+        intervals.transport_changed("phone", LatencyCategory::Low);
+        assert_eq!(
+            intervals.interval_for("phone"),
+            Some(LOW_LATENCY_HEARTBEAT_INTERVAL)
+        );
+
+        // Wi-Fi drops, device falls back to Bluetooth
+        let interval = intervals.transport_changed("phone", LatencyCategory::Medium);
+        assert_eq!(interval, MEDIUM_LATENCY_HEARTBEAT_INTERVAL);
+        assert_eq!(
+            intervals.active_latency("phone"),
+            Some(LatencyCategory::Medium)
+        );
+
+        // Late disconnect of the old TCP link does not reset the new transport
+        intervals.transport_closed("phone", LatencyCategory::Low);
+        assert_eq!(
+            intervals.interval_for("phone"),
+            Some(MEDIUM_LATENCY_HEARTBEAT_INTERVAL)
+        );
+
+        intervals.transport_closed("phone", LatencyCategory::Medium);
+        assert_eq!(intervals.interval_for("phone"), None);
+    }
+
+    #[test]
+    fn test_override_takes_precedence() {
+        let mut intervals = HeartbeatIntervals::new();
+        let custom = Duration::from_secs(5);
+
+        intervals.set_override("phone", Some(custom));
+        assert_eq!(intervals.interval_for("phone"), Some(custom));
+        assert_eq!(
+            intervals.transport_changed("phone", LatencyCategory::Medium),
+            custom
+        );
+
+        // Override survives disconnects
+        intervals.transport_closed("phone", LatencyCategory::Medium);
+        assert_eq!(intervals.interval_for("phone"), Some(custom));
+
+        intervals.set_override("phone", None);
+        assert_eq!(
+            intervals.interval_for_latency("phone", LatencyCategory::High),
+            HIGH_LATENCY_HEARTBEAT_INTERVAL
+        );
+    }
+}
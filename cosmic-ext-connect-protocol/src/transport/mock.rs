@@ -0,0 +1,283 @@
+//! In-Memory Mock Transport
+//!
+//! Connects two protocol stacks inside one process without sockets, so
+//! end-to-end flows (identity exchange, pairing, plugin packets) can be tested.
+//!
+//! A [`MockTransport`] is one end of a link made of two in-memory channels.
+//! Packets are serialized on send and parsed on receive, so they go through
+//! the same encoding as on a real link. Once either end is closed or dropped,
+//! the other end reports [`ProtocolError::Transport`].
+//!
+//! [`MockTransportFactory`] plays the part of a listener: every
+//! [`connect`](TransportFactory::connect) to its address returns one end of a
+//! new link and queues the other end for [`MockTransportFactory::accept`].
+//!
+//! A boxed end can be handed to
+//! [`ConnectionManager::attach_connection`](crate::ConnectionManager::attach_connection),
+//! which then runs its identity exchange and packet flow over the link.
+
+use crate::transport::{
+    LatencyCategory, Transport, TransportAddress, TransportCapabilities, TransportFactory,
+    TransportType, DEFAULT_MAX_PACKET_SIZE,
+};
+use crate::{Packet, ProtocolError, Result};
+use async_trait::async_trait;
+use tokio::sync::{mpsc, Mutex};
+use tracing::debug;
+
+/// Number of packets a link buffers in each direction
+const LINK_BUFFER: usize = 64;
+
+/// One end of an in-memory link
+#[derive(Debug)]
+pub struct MockTransport {
+    /// Address reported as the remote end
+    remote_addr: TransportAddress,
+
+    /// Serialized packets to the other end
+    outgoing: mpsc::Sender<Vec<u8>>,
+
+    /// Serialized packets from the other end
+    incoming: mpsc::Receiver<Vec<u8>>,
+
+    /// Largest packet accepted in either direction
+    max_packet_size: usize,
+}
+
+impl MockTransport {
+    /// Create a connected pair of transports
+    ///
+    /// `a` reports `b_addr` as its remote address and `b` reports `a_addr`.
+    pub fn pair(a_addr: TransportAddress, b_addr: TransportAddress) -> (Self, Self) {
+        let (a_tx, b_rx) = mpsc::channel(LINK_BUFFER);
+        let (b_tx, a_rx) = mpsc::channel(LINK_BUFFER);
+
+        let a = Self {
+            remote_addr: b_addr,
+            outgoing: a_tx,
+            incoming: a_rx,
+            max_packet_size: DEFAULT_MAX_PACKET_SIZE,
+        };
+        let b = Self {
+            remote_addr: a_addr,
+            outgoing: b_tx,
+            incoming: b_rx,
+            max_packet_size: DEFAULT_MAX_PACKET_SIZE,
+        };
+        (a, b)
+    }
+
+    /// Set the largest packet this end sends or accepts
+    pub fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = max_packet_size;
+        self
+    }
+
+    fn link_closed(&self) -> ProtocolError {
+        ProtocolError::transport(format!("mock link to {} closed", self.remote_addr))
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    fn capabilities(&self) -> TransportCapabilities {
+        TransportCapabilities {
+            max_packet_size: self.max_packet_size,
+            reliable: true,
+            connection_oriented: true,
+            latency: LatencyCategory::Low,
+        }
+    }
+
+    fn remote_address(&self) -> TransportAddress {
+        self.remote_addr.clone()
+    }
+
+    async fn send_packet(&mut self, packet: &Packet) -> Result<()> {
+        let bytes = packet.to_bytes()?;
+        if bytes.len() > self.max_packet_size {
+            return Err(ProtocolError::payload_too_large(
+                bytes.len(),
+                self.max_packet_size,
+            ));
+        }
+
+        debug!(
+            "Sending packet ({} bytes) to {}",
+            bytes.len(),
+            self.remote_addr
+        );
+        self.outgoing
+            .send(bytes)
+            .await
+            .map_err(|_| self.link_closed())
+    }
+
+    async fn receive_packet(&mut self) -> Result<Packet> {
+        let bytes = self
+            .incoming
+            .recv()
+            .await
+            .ok_or_else(|| self.link_closed())?;
+        if bytes.len() > self.max_packet_size {
+            return Err(ProtocolError::payload_too_large(
+                bytes.len(),
+                self.max_packet_size,
+            ));
+        }
+        Packet::from_bytes(&bytes)
+    }
+
+    async fn close(self: Box<Self>) -> Result<()> {
+        debug!("Closing mock link to {}", self.remote_addr);
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        !self.outgoing.is_closed()
+    }
+}
+
+/// Factory handing out in-memory links to a single listening address
+#[derive(Debug)]
+pub struct MockTransportFactory {
+    /// Address connections are accepted on
+    address: TransportAddress,
+
+    /// Queue of accepted ends
+    accepted_tx: mpsc::UnboundedSender<MockTransport>,
+
+    /// Accepted ends waiting for [`Self::accept`]
+    accepted_rx: Mutex<mpsc::UnboundedReceiver<MockTransport>>,
+}
+
+impl MockTransportFactory {
+    /// Create a factory listening on `address`
+    pub fn new(address: TransportAddress) -> Self {
+        let (accepted_tx, accepted_rx) = mpsc::unbounded_channel();
+        Self {
+            address,
+            accepted_tx,
+            accepted_rx: Mutex::new(accepted_rx),
+        }
+    }
+
+    /// Address connections are accepted on
+    pub fn address(&self) -> &TransportAddress {
+        &self.address
+    }
+
+    /// Wait for the next incoming connection
+    ///
+    /// The accepted end reports the listening address as its remote address.
+    pub async fn accept(&self) -> Option<MockTransport> {
+        self.accepted_rx.lock().await.recv().await
+    }
+}
+
+#[async_trait]
+impl TransportFactory for MockTransportFactory {
+    async fn connect(&self, address: TransportAddress) -> Result<Box<dyn Transport>> {
+        if address != self.address {
+            return Err(ProtocolError::ConnectionRefused(address.to_string()));
+        }
+
+        let (local, remote) = MockTransport::pair(address.clone(), address);
+        self.accepted_tx
+            .send(remote)
+            .map_err(|_| ProtocolError::ConnectionRefused(self.address.to_string()))?;
+        Ok(Box::new(local))
+    }
+
+    fn transport_type(&self) -> TransportType {
+        self.address.transport_type()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tcp_address() -> TransportAddress {
+        TransportAddress::Tcp("127.0.0.1:1716".parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_pair_round_trip() {
+        let (mut a, mut b) = MockTransport::pair(tcp_address(), tcp_address());
+
+        a.send_packet(&Packet::new("cconnect.ping", json!({ "message": "hi" })))
+            .await
+            .unwrap();
+        let packet = b.receive_packet().await.unwrap();
+        assert_eq!(packet.packet_type, "cconnect.ping");
+        assert_eq!(packet.body["message"], "hi");
+
+        b.send_packet(&Packet::new("cconnect.ping", json!({})))
+            .await
+            .unwrap();
+        assert!(a.receive_packet().await.unwrap().is_type("cconnect.ping"));
+    }
+
+    #[tokio::test]
+    async fn test_closed_link() {
+        let (mut a, b) = MockTransport::pair(tcp_address(), tcp_address());
+        assert!(a.is_connected());
+
+        Box::new(b).close().await.unwrap();
+        assert!(!a.is_connected());
+        assert!(matches!(
+            a.send_packet(&Packet::new("cconnect.ping", json!({})))
+                .await,
+            Err(ProtocolError::Transport { .. })
+        ));
+        assert!(matches!(
+            a.receive_packet().await,
+            Err(ProtocolError::Transport { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_packet_too_large() {
+        let (a, _b) = MockTransport::pair(tcp_address(), tcp_address());
+        let mut a = a.with_max_packet_size(16);
+
+        let packet = Packet::new("cconnect.ping", json!({ "message": "too long" }));
+        assert!(matches!(
+            a.send_packet(&packet).await,
+            Err(ProtocolError::PayloadTooLarge { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_factory_connect_and_accept() {
+        let factory = MockTransportFactory::new(tcp_address());
+        assert_eq!(factory.transport_type(), TransportType::Tcp);
+
+        let mut client = factory.connect(tcp_address()).await.unwrap();
+        let mut server = factory.accept().await.unwrap();
+        assert_eq!(client.remote_address(), tcp_address());
+
+        client
+            .send_packet(&Packet::new("cconnect.ping", json!({})))
+            .await
+            .unwrap();
+        assert!(server
+            .receive_packet()
+            .await
+            .unwrap()
+            .is_type("cconnect.ping"));
+    }
+
+    #[tokio::test]
+    async fn test_factory_refuses_other_address() {
+        let factory = MockTransportFactory::new(tcp_address());
+        let other = TransportAddress::Tcp("127.0.0.1:1717".parse().unwrap());
+
+        assert!(matches!(
+            factory.connect(other).await,
+            Err(ProtocolError::ConnectionRefused(_))
+        ));
+    }
+}
@@ -3,10 +3,15 @@
 //! This module provides network transport for CConnect protocol.
 //! TLS implementation moved to cosmic-ext-connect-core (rustls-based).
 //!
-//! The transport layer supports multiple transport types (TCP, Bluetooth)
-//! through a common trait interface.
+//! The transport layer supports multiple transport types (TCP, Bluetooth, QUIC)
+//! through a common trait interface. An in-memory [`MockTransport`] connects
+//! two stacks in one process for testing.
 
 pub mod bluetooth;
+pub mod heartbeat;
+pub mod mock;
+pub mod quic;
+pub mod selector;
 pub mod tcp;
 mod r#trait;
 
@@ -19,11 +24,19 @@ pub use bluetooth::{
     BluetoothConnection, BluetoothListener, BluetoothProfileService, BluetoothTransportFactory,
     CCONNECT_SERVICE_UUID, RFCOMM_READ_CHAR_UUID, RFCOMM_WRITE_CHAR_UUID,
 };
+pub use heartbeat::HeartbeatIntervals;
+pub use mock::{MockTransport, MockTransportFactory};
+pub use quic::{QuicConnection, QuicListener, QuicTransportFactory, CCONNECT_ALPN};
 pub use r#trait::{
     LatencyCategory, Transport, TransportAddress, TransportCapabilities, TransportFactory,
     TransportPreference, TransportType,
 };
-pub use tcp::{TcpConnection, TcpTransportFactory};
+pub use selector::{FailoverTransport, TransportSelector};
+pub use tcp::{
+    TcpConnection, TcpKeepaliveConfig, TcpSocketOptions, TcpTransportFactory,
+    DEFAULT_KEEPALIVE_IDLE, DEFAULT_KEEPALIVE_INTERVAL, DEFAULT_KEEPALIVE_PROBES,
+    DEFAULT_MAX_PACKET_SIZE,
+};
 
 // TLS types now re-exported from cosmic-ext-connect-core in lib.rs
 // pub use cosmic_ext_connect_core::crypto::{TlsConnection, TlsServer, TlsConfig};
@@ -0,0 +1,604 @@
+//! Automatic Transport Selection
+//!
+//! Picks a transport for a device from the addresses it advertises. Candidates
+//! are tried in priority order: the transport named by the
+//! [`TransportPreference`] first, then the rest by
+//! [`LatencyCategory`](crate::transport::LatencyCategory), lowest first. If a
+//! connection attempt fails the next candidate is tried.
+//!
+//! Connections made by [`TransportSelector::connect_best`] are wrapped in a
+//! [`FailoverTransport`]. When the active link fails (for example TCP when
+//! WiFi drops) the wrapper brings up the next transport, such as Bluetooth,
+//! and retries the operation, so plugins using the [`Transport`] keep working
+//! without noticing the switch.
+
+use crate::transport::{
+    Transport, TransportAddress, TransportCapabilities, TransportFactory, TransportPreference,
+    TransportType,
+};
+use crate::{Packet, ProtocolError, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Chooses and connects transports for a device
+#[derive(Debug, Clone, Default)]
+pub struct TransportSelector {
+    factories: Vec<Arc<dyn TransportFactory>>,
+}
+
+impl TransportSelector {
+    /// Create a selector with no transports registered
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a transport factory
+    ///
+    /// Replaces any factory already registered for the same transport type.
+    pub fn register(&mut self, factory: Arc<dyn TransportFactory>) {
+        let transport_type = factory.transport_type();
+        self.factories
+            .retain(|existing| existing.transport_type() != transport_type);
+        self.factories.push(factory);
+    }
+
+    /// Register a transport factory (builder style)
+    pub fn with_factory(mut self, factory: Arc<dyn TransportFactory>) -> Self {
+        self.register(factory);
+        self
+    }
+
+    /// Get the factory for a transport type
+    fn factory(&self, transport_type: TransportType) -> Option<&Arc<dyn TransportFactory>> {
+        self.factories
+            .iter()
+            .find(|factory| factory.transport_type() == transport_type)
+    }
+
+    /// Addresses to try, in priority order
+    ///
+    /// Addresses without a registered factory, or excluded by
+    /// [`TransportPreference::Only`], are dropped.
+    pub fn candidates(
+        &self,
+        addresses: &[TransportAddress],
+        preference: TransportPreference,
+    ) -> Vec<TransportAddress> {
+        Self::rank(addresses, preference, |transport_type| {
+            self.factory(transport_type).is_some()
+        })
+    }
+
+    /// Order addresses by priority, keeping those whose transport is usable
+    ///
+    /// Shared by [`candidates`](Self::candidates) and the
+    /// [`TransportManager`](crate::TransportManager), which connects through
+    /// its per-transport connection managers instead of factories.
+    pub fn rank(
+        addresses: &[TransportAddress],
+        preference: TransportPreference,
+        usable: impl Fn(TransportType) -> bool,
+    ) -> Vec<TransportAddress> {
+        let preferred = match preference {
+            TransportPreference::PreferTcp | TransportPreference::TcpFirst => TransportType::Tcp,
+            TransportPreference::PreferBluetooth | TransportPreference::BluetoothFirst => {
+                TransportType::Bluetooth
+            }
+            TransportPreference::Only(transport_type) => transport_type,
+        };
+
+        let mut candidates: Vec<TransportAddress> = addresses
+            .iter()
+            .filter(|address| {
+                let transport_type = address.transport_type();
+                let allowed = match preference {
+                    TransportPreference::Only(only) => transport_type == only,
+                    _ => true,
+                };
+                allowed && usable(transport_type)
+            })
+            .cloned()
+            .collect();
+
+        // Stable sort keeps the advertised order between equal candidates
+        candidates.sort_by_key(|address| {
+            let transport_type = address.transport_type();
+            (transport_type != preferred, transport_type.latency())
+        });
+        candidates
+    }
+
+    /// Connect to the first candidate that accepts the connection
+    async fn connect_first(&self, candidates: &[TransportAddress]) -> Result<Box<dyn Transport>> {
+        let mut last_error = None;
+
+        for address in candidates {
+            let Some(factory) = self.factory(address.transport_type()) else {
+                continue;
+            };
+
+            debug!(
+                "Trying {} transport at {}",
+                address.transport_type(),
+                address
+            );
+            match factory.connect(address.clone()).await {
+                Ok(transport) => {
+                    info!("Connected via {}", address);
+                    return Ok(transport);
+                }
+                Err(e) => {
+                    warn!("Failed to connect via {}: {}", address, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| ProtocolError::transport("No usable transport address")))
+    }
+
+    /// Connect using the best available transport
+    ///
+    /// Tries each candidate from [`candidates`](Self::candidates) in turn.
+    /// The returned transport fails over to the remaining candidates if its
+    /// link goes down.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last connection error if every candidate failed, or a
+    /// transport error if no candidate was usable.
+    pub async fn connect_best(
+        &self,
+        addresses: &[TransportAddress],
+        preference: TransportPreference,
+    ) -> Result<Box<dyn Transport>> {
+        let transport = self.connect_failover(addresses, preference).await?;
+        Ok(Box::new(transport))
+    }
+
+    /// Connect using the best available transport, keeping the concrete
+    /// [`FailoverTransport`] type
+    pub async fn connect_failover(
+        &self,
+        addresses: &[TransportAddress],
+        preference: TransportPreference,
+    ) -> Result<FailoverTransport> {
+        let candidates = self.candidates(addresses, preference);
+        let active = self.connect_first(&candidates).await?;
+
+        Ok(FailoverTransport {
+            selector: self.clone(),
+            addresses: addresses.to_vec(),
+            preference,
+            active,
+        })
+    }
+}
+
+/// Whether an error means the link itself has failed
+fn is_link_failure(error: &ProtocolError) -> bool {
+    match error {
+        // Idle links time out on receive without having failed
+        ProtocolError::Io(e) => e.kind() != std::io::ErrorKind::TimedOut,
+        ProtocolError::Transport { .. }
+        | ProtocolError::NetworkError(_)
+        | ProtocolError::NetworkUnreachable(_)
+        | ProtocolError::ConnectionRefused(_) => true,
+        _ => false,
+    }
+}
+
+/// Transport that moves to another link when the active one fails
+#[derive(Debug)]
+pub struct FailoverTransport {
+    selector: TransportSelector,
+    addresses: Vec<TransportAddress>,
+    preference: TransportPreference,
+    active: Box<dyn Transport>,
+}
+
+impl FailoverTransport {
+    /// Type of the transport currently in use
+    pub fn active_type(&self) -> TransportType {
+        self.active.remote_address().transport_type()
+    }
+
+    /// Move the session to a transport of the given type
+    ///
+    /// Used when the current link is known to be going away, e.g. moving to
+    /// Bluetooth when WiFi drops. The current transport is kept if no
+    /// connection of the requested type can be made.
+    pub async fn migrate_to(&mut self, transport_type: TransportType) -> Result<()> {
+        let candidates: Vec<TransportAddress> = self
+            .selector
+            .candidates(&self.addresses, self.preference)
+            .into_iter()
+            .filter(|address| address.transport_type() == transport_type)
+            .collect();
+
+        let transport = self.selector.connect_first(&candidates).await?;
+        self.switch(transport).await;
+        Ok(())
+    }
+
+    /// Replace a failed link with the next working candidate
+    async fn failover(&mut self) -> Result<()> {
+        let failed = self.active.remote_address();
+        let mut candidates = self.selector.candidates(&self.addresses, self.preference);
+
+        // The failed link is only retried once everything else has been tried
+        if let Some(index) = candidates.iter().position(|address| *address == failed) {
+            let address = candidates.remove(index);
+            candidates.push(address);
+        }
+
+        let transport = self.selector.connect_first(&candidates).await?;
+        self.switch(transport).await;
+        Ok(())
+    }
+
+    async fn switch(&mut self, transport: Box<dyn Transport>) {
+        info!(
+            "Switching transport from {} to {}",
+            self.active.remote_address(),
+            transport.remote_address()
+        );
+
+        let previous = std::mem::replace(&mut self.active, transport);
+        if let Err(e) = previous.close().await {
+            debug!("Error closing previous transport: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for FailoverTransport {
+    fn capabilities(&self) -> TransportCapabilities {
+        self.active.capabilities()
+    }
+
+    fn remote_address(&self) -> TransportAddress {
+        self.active.remote_address()
+    }
+
+    async fn send_packet(&mut self, packet: &Packet) -> Result<()> {
+        match self.active.send_packet(packet).await {
+            Err(e) if is_link_failure(&e) => {
+                warn!(
+                    "Send over {} failed: {}, failing over",
+                    self.active.remote_address(),
+                    e
+                );
+                self.failover().await?;
+                self.active.send_packet(packet).await
+            }
+            result => result,
+        }
+    }
+
+    async fn receive_packet(&mut self) -> Result<Packet> {
+        match self.active.receive_packet().await {
+            Err(e) if is_link_failure(&e) => {
+                warn!(
+                    "Receive over {} failed: {}, failing over",
+                    self.active.remote_address(),
+                    e
+                );
+                self.failover().await?;
+                self.active.receive_packet().await
+            }
+            result => result,
+        }
+    }
+
+    async fn close(self: Box<Self>) -> Result<()> {
+        self.active.close().await
+    }
+
+    fn is_connected(&self) -> bool {
+        self.active.is_connected()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::LatencyCategory;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Transport whose link can be cut from the test
+    #[derive(Debug)]
+    struct MockTransport {
+        address: TransportAddress,
+        link_up: Arc<AtomicBool>,
+    }
+
+    impl MockTransport {
+        fn check_link(&self) -> Result<()> {
+            if self.link_up.load(Ordering::SeqCst) {
+                Ok(())
+            } else {
+                Err(ProtocolError::Io(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionReset,
+                    "link down",
+                )))
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Transport for MockTransport {
+        fn capabilities(&self) -> TransportCapabilities {
+            TransportCapabilities {
+                max_packet_size: 1024,
+                reliable: true,
+                connection_oriented: true,
+                latency: self.address.transport_type().latency(),
+            }
+        }
+
+        fn remote_address(&self) -> TransportAddress {
+            self.address.clone()
+        }
+
+        async fn send_packet(&mut self, _packet: &Packet) -> Result<()> {
+            self.check_link()
+        }
+
+        async fn receive_packet(&mut self) -> Result<Packet> {
+            self.check_link()?;
+            Ok(Packet::new("cconnect.test", json!({})))
+        }
+
+        async fn close(self: Box<Self>) -> Result<()> {
+            Ok(())
+        }
+
+        fn is_connected(&self) -> bool {
+            self.link_up.load(Ordering::SeqCst)
+        }
+    }
+
+    #[derive(Debug)]
+    struct MockFactory {
+        transport_type: TransportType,
+        reachable: Arc<AtomicBool>,
+        link_up: Arc<AtomicBool>,
+        attempts: AtomicUsize,
+    }
+
+    impl MockFactory {
+        fn new(transport_type: TransportType) -> Arc<Self> {
+            Arc::new(Self {
+                transport_type,
+                reachable: Arc::new(AtomicBool::new(true)),
+                link_up: Arc::new(AtomicBool::new(true)),
+                attempts: AtomicUsize::new(0),
+            })
+        }
+
+        fn unreachable(transport_type: TransportType) -> Arc<Self> {
+            let factory = Self::new(transport_type);
+            factory.reachable.store(false, Ordering::SeqCst);
+            factory
+        }
+
+        /// Simulate the network behind this transport going away
+        fn drop_link(&self) {
+            self.link_up.store(false, Ordering::SeqCst);
+            self.reachable.store(false, Ordering::SeqCst);
+        }
+    }
+
+    #[async_trait]
+    impl TransportFactory for MockFactory {
+        async fn connect(&self, address: TransportAddress) -> Result<Box<dyn Transport>> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            if !self.reachable.load(Ordering::SeqCst) {
+                return Err(ProtocolError::ConnectionRefused(address.to_string()));
+            }
+            Ok(Box::new(MockTransport {
+                address,
+                link_up: self.link_up.clone(),
+            }))
+        }
+
+        fn transport_type(&self) -> TransportType {
+            self.transport_type
+        }
+    }
+
+    fn tcp_address() -> TransportAddress {
+        TransportAddress::Tcp("192.168.1.100:1716".parse().unwrap())
+    }
+
+    fn quic_address() -> TransportAddress {
+        TransportAddress::Quic("192.168.1.100:1716".parse().unwrap())
+    }
+
+    fn bluetooth_address() -> TransportAddress {
+        TransportAddress::Bluetooth {
+            address: "00:11:22:33:44:55".to_string(),
+            service_uuid: None,
+        }
+    }
+
+    fn all_addresses() -> Vec<TransportAddress> {
+        vec![bluetooth_address(), quic_address(), tcp_address()]
+    }
+
+    fn selector_with(factories: &[&Arc<MockFactory>]) -> TransportSelector {
+        factories
+            .iter()
+            .fold(TransportSelector::new(), |selector, &factory| {
+                selector.with_factory(factory.clone())
+            })
+    }
+
+    #[test]
+    fn test_candidates_ordered_by_preference_then_latency() {
+        let tcp = MockFactory::new(TransportType::Tcp);
+        let bluetooth = MockFactory::new(TransportType::Bluetooth);
+        let quic = MockFactory::new(TransportType::Quic);
+        let selector = selector_with(&[&tcp, &bluetooth, &quic]);
+
+        assert_eq!(
+            selector.candidates(&all_addresses(), TransportPreference::PreferTcp),
+            vec![tcp_address(), quic_address(), bluetooth_address()]
+        );
+        assert_eq!(
+            selector.candidates(&all_addresses(), TransportPreference::BluetoothFirst),
+            vec![bluetooth_address(), quic_address(), tcp_address()]
+        );
+        assert_eq!(
+            selector.candidates(
+                &all_addresses(),
+                TransportPreference::Only(TransportType::Quic)
+            ),
+            vec![quic_address()]
+        );
+    }
+
+    #[test]
+    fn test_candidates_skip_unregistered_transports() {
+        let tcp = MockFactory::new(TransportType::Tcp);
+        let selector = selector_with(&[&tcp]);
+
+        assert_eq!(
+            selector.candidates(&all_addresses(), TransportPreference::PreferBluetooth),
+            vec![tcp_address()]
+        );
+    }
+
+    #[test]
+    fn test_rank_skips_unusable_transports() {
+        assert_eq!(
+            TransportSelector::rank(
+                &all_addresses(),
+                TransportPreference::PreferBluetooth,
+                |transport_type| transport_type != TransportType::Bluetooth,
+            ),
+            vec![quic_address(), tcp_address()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_best_falls_back_on_failure() {
+        let tcp = MockFactory::unreachable(TransportType::Tcp);
+        let bluetooth = MockFactory::new(TransportType::Bluetooth);
+        let selector = selector_with(&[&tcp, &bluetooth]);
+
+        let transport = selector
+            .connect_best(&all_addresses(), TransportPreference::PreferTcp)
+            .await
+            .unwrap();
+
+        assert_eq!(transport.remote_address(), bluetooth_address());
+        assert_eq!(transport.capabilities().latency, LatencyCategory::Medium);
+        assert_eq!(tcp.attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_connect_best_without_candidates() {
+        let selector = TransportSelector::new();
+        let result = selector
+            .connect_best(&all_addresses(), TransportPreference::PreferTcp)
+            .await;
+        assert!(matches!(result, Err(ProtocolError::Transport { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_bluetooth_when_tcp_dies() {
+        let tcp = MockFactory::new(TransportType::Tcp);
+        let bluetooth = MockFactory::new(TransportType::Bluetooth);
+        let selector = selector_with(&[&tcp, &bluetooth]);
+
+        let mut transport = selector
+            .connect_best(
+                &[tcp_address(), bluetooth_address()],
+                TransportPreference::PreferTcp,
+            )
+            .await
+            .unwrap();
+        assert_eq!(transport.remote_address(), tcp_address());
+
+        // WiFi drops: the send is transparently retried over Bluetooth
+        tcp.drop_link();
+        let packet = Packet::new("cconnect.ping", json!({}));
+        transport.send_packet(&packet).await.unwrap();
+        assert_eq!(transport.remote_address(), bluetooth_address());
+        assert!(transport.is_connected());
+
+        transport.receive_packet().await.unwrap();
+        assert_eq!(bluetooth.attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failover_on_receive() {
+        let tcp = MockFactory::new(TransportType::Tcp);
+        let bluetooth = MockFactory::new(TransportType::Bluetooth);
+        let selector = selector_with(&[&tcp, &bluetooth]);
+
+        let mut transport = selector
+            .connect_failover(&all_addresses(), TransportPreference::PreferTcp)
+            .await
+            .unwrap();
+
+        tcp.drop_link();
+        transport.receive_packet().await.unwrap();
+        assert_eq!(transport.active_type(), TransportType::Bluetooth);
+    }
+
+    #[tokio::test]
+    async fn test_failover_error_when_no_link_left() {
+        let tcp = MockFactory::new(TransportType::Tcp);
+        let selector = selector_with(&[&tcp]);
+
+        let mut transport = selector
+            .connect_best(&[tcp_address()], TransportPreference::PreferTcp)
+            .await
+            .unwrap();
+
+        tcp.drop_link();
+        let packet = Packet::new("cconnect.ping", json!({}));
+        assert!(transport.send_packet(&packet).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_to_bluetooth() {
+        let tcp = MockFactory::new(TransportType::Tcp);
+        let bluetooth = MockFactory::new(TransportType::Bluetooth);
+        let selector = selector_with(&[&tcp, &bluetooth]);
+
+        let mut transport = selector
+            .connect_failover(&all_addresses(), TransportPreference::PreferTcp)
+            .await
+            .unwrap();
+        assert_eq!(transport.active_type(), TransportType::Tcp);
+
+        transport
+            .migrate_to(TransportType::Bluetooth)
+            .await
+            .unwrap();
+        assert_eq!(transport.active_type(), TransportType::Bluetooth);
+
+        // Migration fails without a reachable address, keeping the link
+        assert!(transport.migrate_to(TransportType::Quic).await.is_err());
+        assert_eq!(transport.active_type(), TransportType::Bluetooth);
+    }
+
+    #[test]
+    fn test_timeouts_are_not_link_failures() {
+        let timeout = ProtocolError::Io(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            "Read timeout",
+        ));
+        assert!(!is_link_failure(&timeout));
+        assert!(!is_link_failure(&ProtocolError::InvalidPacket(
+            "too large".to_string()
+        )));
+        assert!(is_link_failure(&ProtocolError::transport("closed")));
+    }
+}
@@ -1,6 +1,20 @@
 //! Basic TCP Transport for Pairing
 //!
 //! Simple TCP connection for exchanging pairing packets before TLS is established.
+//!
+//! ## Framing
+//!
+//! Each packet is sent as a 4-byte big-endian length followed by that many
+//! bytes of newline-terminated JSON. On receive, a length above the
+//! connection's maximum packet size is rejected with
+//! [`ProtocolError::PayloadTooLarge`] before anything is allocated, and the
+//! connection is shut down since the rest of the stream can not be trusted.
+//! A frame cut short by the peer, or one that does not hold exactly one JSON
+//! packet, is rejected with [`ProtocolError::MalformedFrame`]; one that is not
+//! valid JSON with [`ProtocolError::Deserialization`].
+//!
+//! Read timeouts are reported as [`ProtocolError::Timeout`] and a failed or
+//! closed connection as [`ProtocolError::Transport`].
 
 use crate::transport::{
     LatencyCategory, Transport, TransportAddress, TransportCapabilities, TransportFactory,
@@ -8,23 +22,161 @@ use crate::transport::{
 };
 use crate::{Packet, ProtocolError, Result};
 use async_trait::async_trait;
-use std::net::SocketAddr;
+use socket2::{SockRef, Socket, TcpKeepalive};
+use std::net::{IpAddr, SocketAddr};
+use std::os::fd::{BorrowedFd, RawFd};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 use tokio::time::{timeout, Duration};
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 /// Default timeout for TCP operations
 const TCP_TIMEOUT: Duration = Duration::from_secs(10);
 
-/// Maximum packet size (1MB)
-const MAX_PACKET_SIZE: usize = 1024 * 1024;
+/// Default maximum packet size (1MB)
+pub const DEFAULT_MAX_PACKET_SIZE: usize = 1024 * 1024;
+
+/// Default idle time before the first keepalive probe
+pub const DEFAULT_KEEPALIVE_IDLE: Duration = Duration::from_secs(60);
+
+/// Default time between keepalive probes
+pub const DEFAULT_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Default number of unanswered probes before the connection is dropped
+pub const DEFAULT_KEEPALIVE_PROBES: u32 = 3;
+
+/// OS-level TCP keepalive parameters
+///
+/// Lets the kernel detect dead peers independently of the application
+/// heartbeat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TcpKeepaliveConfig {
+    /// Idle time before the first probe is sent
+    pub idle: Duration,
+    /// Time between probes
+    pub interval: Duration,
+    /// Unanswered probes before the connection is considered dead
+    pub probes: u32,
+}
+
+impl Default for TcpKeepaliveConfig {
+    fn default() -> Self {
+        Self {
+            idle: DEFAULT_KEEPALIVE_IDLE,
+            interval: DEFAULT_KEEPALIVE_INTERVAL,
+            probes: DEFAULT_KEEPALIVE_PROBES,
+        }
+    }
+}
+
+/// Socket options applied to TCP transport connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TcpSocketOptions {
+    /// Set `TCP_NODELAY` so small control packets are sent immediately
+    pub nodelay: bool,
+    /// OS keepalive parameters, `None` to disable keepalive
+    pub keepalive: Option<TcpKeepaliveConfig>,
+}
+
+impl Default for TcpSocketOptions {
+    fn default() -> Self {
+        Self {
+            nodelay: true,
+            keepalive: Some(TcpKeepaliveConfig::default()),
+        }
+    }
+}
+
+impl TcpSocketOptions {
+    /// Apply the options to a connected stream
+    pub fn apply(&self, stream: &TcpStream) -> Result<()> {
+        self.apply_to(&SockRef::from(stream))
+    }
+
+    /// Apply the options to this process's TCP socket connected to `peer`
+    ///
+    /// For streams owned by another crate, such as the ones inside the
+    /// connection manager's [`TlsConnection`](crate::TlsConnection)s. The
+    /// socket is looked up among the open file descriptors in
+    /// `/proc/self/fd`. Returns whether it was found.
+    pub fn apply_to_peer(&self, peer: SocketAddr) -> Result<bool> {
+        for entry in std::fs::read_dir("/proc/self/fd")? {
+            let Some(fd) = entry?
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<RawFd>().ok())
+            else {
+                continue;
+            };
+            // SAFETY: `fd` was just listed as open and is only borrowed to be
+            // duplicated; if it was closed in between, duplicating it fails
+            let Ok(fd) = unsafe { BorrowedFd::borrow_raw(fd) }.try_clone_to_owned() else {
+                continue;
+            };
+
+            let socket = Socket::from(fd);
+            let is_peer = socket
+                .peer_addr()
+                .ok()
+                .and_then(|addr| addr.as_socket())
+                .is_some_and(|addr| same_address(addr, peer));
+            if is_peer && socket.r#type().ok() == Some(socket2::Type::STREAM) {
+                self.apply_to(&socket)?;
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn apply_to(&self, socket: &Socket) -> Result<()> {
+        socket.set_nodelay(self.nodelay)?;
+
+        match self.keepalive {
+            Some(config) => {
+                let keepalive = TcpKeepalive::new()
+                    .with_time(config.idle)
+                    .with_interval(config.interval)
+                    .with_retries(config.probes);
+                socket.set_tcp_keepalive(&keepalive)?;
+            }
+            None => socket.set_keepalive(false)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether two socket addresses are the same, comparing IPv4-mapped IPv6
+/// addresses (as seen by a dual-stack listener) by their IPv4 address
+fn same_address(a: SocketAddr, b: SocketAddr) -> bool {
+    let ip = |addr: SocketAddr| match addr.ip() {
+        IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or(IpAddr::V6(ip), IpAddr::V4),
+        ip => ip,
+    };
+    a.port() == b.port() && ip(a) == ip(b)
+}
+
+/// Decode the body of a received frame into a packet
+///
+/// The body must be a single line of JSON, optionally newline-terminated.
+fn decode_frame(data: &[u8]) -> Result<Packet> {
+    let line = data.strip_suffix(b"\n").unwrap_or(data);
+    if line.contains(&b'\n') {
+        return Err(ProtocolError::MalformedFrame(
+            "frame holds more than one line".to_string(),
+        ));
+    }
+
+    serde_json::from_slice(line).map_err(|e| ProtocolError::deserialization("packet", e))
+}
 
 /// Simple TCP connection for pairing
 #[derive(Debug)]
 pub struct TcpConnection {
     stream: TcpStream,
     remote_addr: SocketAddr,
+    max_packet_size: usize,
 }
 
 impl TcpConnection {
@@ -34,37 +186,70 @@ impl TcpConnection {
     ///
     /// * `addr` - Remote socket address (IP:port)
     pub async fn connect(addr: SocketAddr) -> Result<Self> {
+        Self::connect_with_options(addr, TcpSocketOptions::default()).await
+    }
+
+    /// Connect to a remote device with custom socket options
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Remote socket address (IP:port)
+    /// * `options` - Socket options to apply once connected
+    pub async fn connect_with_options(addr: SocketAddr, options: TcpSocketOptions) -> Result<Self> {
         debug!("Connecting to {}", addr);
 
         let stream = timeout(TCP_TIMEOUT, TcpStream::connect(addr))
             .await
-            .map_err(|_| {
-                ProtocolError::Io(std::io::Error::new(
-                    std::io::ErrorKind::TimedOut,
-                    "Connection timeout",
-                ))
-            })??;
+            .map_err(|_| ProtocolError::Timeout(format!("connecting to {}", addr)))??;
 
         debug!("Connected to {}", addr);
+        options.apply(&stream)?;
 
         Ok(Self {
             stream,
             remote_addr: addr,
+            max_packet_size: DEFAULT_MAX_PACKET_SIZE,
         })
     }
 
     /// Create from an existing TcpStream
+    ///
+    /// Applies the default [`TcpSocketOptions`]; failures are logged, not
+    /// returned.
     pub fn from_stream(stream: TcpStream, remote_addr: SocketAddr) -> Self {
+        if let Err(e) = TcpSocketOptions::default().apply(&stream) {
+            warn!("Failed to set socket options for {}: {}", remote_addr, e);
+        }
+
         Self {
             stream,
             remote_addr,
+            max_packet_size: DEFAULT_MAX_PACKET_SIZE,
         }
     }
 
+    /// Set the largest packet that may be sent or received
+    pub fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = max_packet_size;
+        self
+    }
+
+    /// Largest packet that may be sent or received
+    pub fn max_packet_size(&self) -> usize {
+        self.max_packet_size
+    }
+
     /// Send a packet
     pub async fn send_packet(&mut self, packet: &Packet) -> Result<()> {
         let bytes = packet.to_bytes()?;
 
+        if bytes.len() > self.max_packet_size {
+            return Err(ProtocolError::payload_too_large(
+                bytes.len(),
+                self.max_packet_size,
+            ));
+        }
+
         debug!(
             "Sending packet ({} bytes) to {}",
             bytes.len(),
@@ -73,11 +258,17 @@ impl TcpConnection {
 
         // Send packet length as 4-byte big-endian
         let len = bytes.len() as u32;
-        self.stream.write_all(&len.to_be_bytes()).await?;
+        self.stream
+            .write_all(&len.to_be_bytes())
+            .await
+            .map_err(|e| self.link_error(e))?;
 
         // Send packet data
-        self.stream.write_all(&bytes).await?;
-        self.stream.flush().await?;
+        self.stream
+            .write_all(&bytes)
+            .await
+            .map_err(|e| self.link_error(e))?;
+        self.stream.flush().await.map_err(|e| self.link_error(e))?;
 
         debug!("Packet sent successfully to {}", self.remote_addr);
         Ok(())
@@ -91,37 +282,45 @@ impl TcpConnection {
         let mut len_bytes = [0u8; 4];
         timeout(TCP_TIMEOUT, self.stream.read_exact(&mut len_bytes))
             .await
-            .map_err(|_| {
-                ProtocolError::Io(std::io::Error::new(
-                    std::io::ErrorKind::TimedOut,
-                    "Read timeout",
-                ))
-            })??;
+            .map_err(|_| ProtocolError::Timeout(format!("reading from {}", self.remote_addr)))?
+            .map_err(|e| self.link_error(e))?;
 
         let len = u32::from_be_bytes(len_bytes) as usize;
 
-        if len > MAX_PACKET_SIZE {
-            error!("Packet too large: {} bytes", len);
-            return Err(ProtocolError::InvalidPacket(format!(
-                "Packet too large: {} bytes (max {})",
-                len, MAX_PACKET_SIZE
-            )));
+        if len > self.max_packet_size {
+            error!(
+                "Packet too large: {} bytes from {}, disconnecting",
+                len, self.remote_addr
+            );
+            let _ = self.stream.shutdown().await;
+            return Err(ProtocolError::payload_too_large(len, self.max_packet_size));
+        }
+        if len == 0 {
+            return Err(ProtocolError::MalformedFrame("empty frame".to_string()));
         }
 
         debug!("Receiving packet ({} bytes) from {}", len, self.remote_addr);
 
         // Read packet data
         let mut data = vec![0u8; len];
-        timeout(TCP_TIMEOUT, self.stream.read_exact(&mut data))
-            .await
-            .map_err(|_| {
-                ProtocolError::Io(std::io::Error::new(
-                    std::io::ErrorKind::TimedOut,
-                    "Read timeout",
-                ))
-            })??;
-
-        let packet = Packet::from_bytes(&data)?;
+        match timeout(TCP_TIMEOUT, self.stream.read_exact(&mut data)).await {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return Err(ProtocolError::MalformedFrame(format!(
+                    "truncated frame, expected {} bytes",
+                    len
+                )));
+            }
+            Ok(Err(e)) => return Err(self.link_error(e)),
+            Err(_) => {
+                return Err(ProtocolError::Timeout(format!(
+                    "reading from {}",
+                    self.remote_addr
+                )));
+            }
+        }
+
+        let packet = decode_frame(&data)?;
         debug!(
             "Received packet type '{}' from {}",
             packet.packet_type, self.remote_addr
@@ -135,6 +334,14 @@ impl TcpConnection {
         self.remote_addr
     }
 
+    /// Wrap an I/O error on the stream as a transport failure
+    fn link_error(&self, error: std::io::Error) -> ProtocolError {
+        ProtocolError::transport_with_source(
+            format!("connection to {} failed", self.remote_addr),
+            error,
+        )
+    }
+
     /// Close the connection
     pub async fn close_conn(mut self) -> Result<()> {
         debug!("Closing connection to {}", self.remote_addr);
@@ -149,7 +356,7 @@ impl Transport for TcpConnection {
     fn capabilities(&self) -> TransportCapabilities {
         TransportCapabilities {
             // TCP can handle large packets
-            max_packet_size: MAX_PACKET_SIZE,
+            max_packet_size: self.max_packet_size,
             // TCP is reliable
             reliable: true,
             // TCP is connection-oriented
@@ -164,78 +371,11 @@ impl Transport for TcpConnection {
     }
 
     async fn send_packet(&mut self, packet: &Packet) -> Result<()> {
-        let bytes = packet.to_bytes()?;
-
-        if bytes.len() > MAX_PACKET_SIZE {
-            return Err(ProtocolError::InvalidPacket(format!(
-                "Packet too large: {} bytes (max {})",
-                bytes.len(),
-                MAX_PACKET_SIZE
-            )));
-        }
-
-        debug!(
-            "Sending packet ({} bytes) to {}",
-            bytes.len(),
-            self.remote_addr
-        );
-
-        // Send packet length as 4-byte big-endian
-        let len = bytes.len() as u32;
-        self.stream.write_all(&len.to_be_bytes()).await?;
-
-        // Send packet data
-        self.stream.write_all(&bytes).await?;
-        self.stream.flush().await?;
-
-        debug!("Packet sent successfully to {}", self.remote_addr);
-        Ok(())
+        TcpConnection::send_packet(self, packet).await
     }
 
     async fn receive_packet(&mut self) -> Result<Packet> {
-        debug!("Waiting for packet from {}", self.remote_addr);
-
-        // Read packet length (4 bytes, big-endian)
-        let mut len_bytes = [0u8; 4];
-        timeout(TCP_TIMEOUT, self.stream.read_exact(&mut len_bytes))
-            .await
-            .map_err(|_| {
-                ProtocolError::Io(std::io::Error::new(
-                    std::io::ErrorKind::TimedOut,
-                    "Read timeout",
-                ))
-            })??;
-
-        let len = u32::from_be_bytes(len_bytes) as usize;
-
-        if len > MAX_PACKET_SIZE {
-            error!("Packet too large: {} bytes", len);
-            return Err(ProtocolError::InvalidPacket(format!(
-                "Packet too large: {} bytes (max {})",
-                len, MAX_PACKET_SIZE
-            )));
-        }
-
-        debug!("Receiving packet ({} bytes) from {}", len, self.remote_addr);
-
-        // Read packet data
-        let mut data = vec![0u8; len];
-        timeout(TCP_TIMEOUT, self.stream.read_exact(&mut data))
-            .await
-            .map_err(|_| {
-                ProtocolError::Io(std::io::Error::new(
-                    std::io::ErrorKind::TimedOut,
-                    "Read timeout",
-                ))
-            })??;
-
-        let packet = Packet::from_bytes(&data)?;
-        debug!(
-            "Received packet type '{}' from {}",
-            packet.packet_type, self.remote_addr
-        );
-
-        Ok(packet)
+        TcpConnection::receive_packet(self).await
     }
 
     async fn close(mut self: Box<Self>) -> Result<()> {
@@ -253,12 +393,29 @@ impl Transport for TcpConnection {
 
 /// Factory for creating TCP connections
 #[derive(Debug, Clone)]
-pub struct TcpTransportFactory;
+pub struct TcpTransportFactory {
+    options: TcpSocketOptions,
+    max_packet_size: usize,
+}
 
 impl TcpTransportFactory {
     /// Create a new TCP transport factory
     pub fn new() -> Self {
-        Self
+        Self::with_options(TcpSocketOptions::default())
+    }
+
+    /// Create a TCP transport factory with custom socket options
+    pub fn with_options(options: TcpSocketOptions) -> Self {
+        Self {
+            options,
+            max_packet_size: DEFAULT_MAX_PACKET_SIZE,
+        }
+    }
+
+    /// Set the largest packet created connections may send or receive
+    pub fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = max_packet_size;
+        self
     }
 }
 
@@ -273,7 +430,9 @@ impl TransportFactory for TcpTransportFactory {
     async fn connect(&self, address: TransportAddress) -> Result<Box<dyn Transport>> {
         match address {
             TransportAddress::Tcp(addr) => {
-                let connection = TcpConnection::connect(addr).await?;
+                let connection = TcpConnection::connect_with_options(addr, self.options)
+                    .await?
+                    .with_max_packet_size(self.max_packet_size);
                 Ok(Box::new(connection))
             }
             _ => Err(ProtocolError::InvalidPacket(
@@ -326,6 +485,197 @@ mod tests {
         server_task.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_socket_options_applied() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let options = TcpSocketOptions {
+            nodelay: true,
+            keepalive: Some(TcpKeepaliveConfig {
+                idle: Duration::from_secs(30),
+                interval: Duration::from_secs(5),
+                probes: 4,
+            }),
+        };
+        let client = TcpConnection::connect_with_options(addr, options)
+            .await
+            .unwrap();
+        let (server_stream, _) = listener.accept().await.unwrap();
+
+        let socket = SockRef::from(&client.stream);
+        assert!(socket.nodelay().unwrap());
+        assert!(socket.keepalive().unwrap());
+        assert_eq!(socket.keepalive_time().unwrap(), Duration::from_secs(30));
+        assert_eq!(socket.keepalive_interval().unwrap(), Duration::from_secs(5));
+        assert_eq!(socket.keepalive_retries().unwrap(), 4);
+
+        // Accepted streams get the defaults
+        let server = TcpConnection::from_stream(server_stream, addr);
+        let socket = SockRef::from(&server.stream);
+        assert!(socket.nodelay().unwrap());
+        assert_eq!(socket.keepalive_time().unwrap(), DEFAULT_KEEPALIVE_IDLE);
+        assert_eq!(
+            socket.keepalive_interval().unwrap(),
+            DEFAULT_KEEPALIVE_INTERVAL
+        );
+        assert_eq!(
+            socket.keepalive_retries().unwrap(),
+            DEFAULT_KEEPALIVE_PROBES
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keepalive_disabled() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let options = TcpSocketOptions {
+            nodelay: false,
+            keepalive: None,
+        };
+        let client = TcpConnection::connect_with_options(addr, options)
+            .await
+            .unwrap();
+
+        let socket = SockRef::from(&client.stream);
+        assert!(!socket.nodelay().unwrap());
+        assert!(!socket.keepalive().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_socket_options_applied_by_peer() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = TcpStream::connect(addr).await.unwrap();
+        let (_server, _) = listener.accept().await.unwrap();
+
+        let options = TcpSocketOptions {
+            nodelay: true,
+            keepalive: Some(TcpKeepaliveConfig {
+                idle: Duration::from_secs(45),
+                interval: Duration::from_secs(7),
+                probes: 5,
+            }),
+        };
+        assert!(options.apply_to_peer(addr).unwrap());
+
+        let socket = SockRef::from(&client);
+        assert!(socket.nodelay().unwrap());
+        assert_eq!(socket.keepalive_time().unwrap(), Duration::from_secs(45));
+        assert_eq!(socket.keepalive_interval().unwrap(), Duration::from_secs(7));
+        assert_eq!(socket.keepalive_retries().unwrap(), 5);
+
+        // Nothing is connected to a port nobody listens on
+        let unused = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        assert!(!options.apply_to_peer(unused.local_addr().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_same_address_maps_ipv4() {
+        let v4: SocketAddr = "192.168.1.50:1716".parse().unwrap();
+        let mapped: SocketAddr = "[::ffff:192.168.1.50]:1716".parse().unwrap();
+        assert!(same_address(v4, mapped));
+        assert!(!same_address(v4, "192.168.1.50:1717".parse().unwrap()));
+    }
+
+    /// Accept one connection and return it with the raw client stream
+    async fn raw_pair(max_packet_size: usize) -> (TcpConnection, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = TcpStream::connect(addr).await.unwrap();
+        let (stream, remote_addr) = listener.accept().await.unwrap();
+        let server =
+            TcpConnection::from_stream(stream, remote_addr).with_max_packet_size(max_packet_size);
+        (server, client)
+    }
+
+    #[tokio::test]
+    async fn test_oversized_frame_rejected_and_disconnected() {
+        let (mut server, mut client) = raw_pair(DEFAULT_MAX_PACKET_SIZE).await;
+
+        // Announce a 4GB frame without sending it
+        client.write_all(&u32::MAX.to_be_bytes()).await.unwrap();
+
+        let err = server.receive_packet().await.unwrap_err();
+        assert!(matches!(
+            err,
+            ProtocolError::PayloadTooLarge { size, max }
+                if size == u32::MAX as u64 && max == DEFAULT_MAX_PACKET_SIZE as u64
+        ));
+
+        // The server hung up
+        let mut buf = [0u8; 1];
+        let read = timeout(Duration::from_secs(1), client.read(&mut buf))
+            .await
+            .unwrap();
+        assert!(matches!(read, Ok(0) | Err(_)));
+    }
+
+    #[tokio::test]
+    async fn test_truncated_frame_rejected() {
+        let (mut server, mut client) = raw_pair(1024).await;
+
+        client.write_all(&100u32.to_be_bytes()).await.unwrap();
+        client.write_all(b"{\"id\":1,").await.unwrap();
+        client.shutdown().await.unwrap();
+
+        let err = timeout(Duration::from_secs(1), server.receive_packet())
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(err, ProtocolError::MalformedFrame(_)));
+    }
+
+    #[tokio::test]
+    async fn test_configured_limit_applies_to_send_and_receive() {
+        let (mut server, mut client) = raw_pair(64).await;
+
+        client.write_all(&65u32.to_be_bytes()).await.unwrap();
+        let err = server.receive_packet().await.unwrap_err();
+        assert!(matches!(
+            err,
+            ProtocolError::PayloadTooLarge { size: 65, max: 64 }
+        ));
+
+        let packet = Packet::new("test.packet", json!({"data": "x".repeat(64)}));
+        let err = server.send_packet(&packet).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ProtocolError::PayloadTooLarge { max: 64, .. }
+        ));
+        assert_eq!(server.capabilities().max_packet_size, 64);
+    }
+
+    #[test]
+    fn test_decode_frame() {
+        let packet = Packet::new("test.packet", json!({"data": "hello"}));
+        let bytes = packet.to_bytes().unwrap();
+        assert_eq!(decode_frame(&bytes).unwrap().packet_type, "test.packet");
+
+        assert!(matches!(
+            decode_frame(b"not json\n"),
+            Err(ProtocolError::Deserialization { .. })
+        ));
+
+        let mut two = bytes.clone();
+        two.extend_from_slice(&bytes);
+        assert!(matches!(
+            decode_frame(&two),
+            Err(ProtocolError::MalformedFrame(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_closed_connection_is_transport_error() {
+        let (mut server, client) = raw_pair(1024).await;
+        drop(client);
+
+        let err = server.receive_packet().await.unwrap_err();
+        assert!(matches!(err, ProtocolError::Transport { .. }));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
     #[tokio::test]
     async fn test_connection_timeout() {
         // Try to connect to a non-existent server
@@ -1,11 +1,12 @@
 //! Transport Trait Abstraction
 //!
-//! Defines a common interface for different transport types (TCP, Bluetooth, etc.)
+//! Defines a common interface for different transport types (TCP, Bluetooth, QUIC)
 //! that can be used to send and receive CConnect packets.
 
 use crate::{Packet, Result};
 use async_trait::async_trait;
 use std::fmt::Debug;
+use std::time::Duration;
 
 /// Transport capabilities and characteristics
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,7 +25,9 @@ pub struct TransportCapabilities {
 }
 
 /// Latency categories for transports
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Ordered from lowest to highest latency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LatencyCategory {
     /// Low latency (< 10ms typical)
     Low,
@@ -36,6 +39,21 @@ pub enum LatencyCategory {
     High,
 }
 
+impl LatencyCategory {
+    /// Category of a measured round-trip time
+    ///
+    /// Uses the same boundaries as the typical values of the variants.
+    pub fn from_rtt(rtt: Duration) -> Self {
+        if rtt < Duration::from_millis(10) {
+            LatencyCategory::Low
+        } else if rtt <= Duration::from_millis(50) {
+            LatencyCategory::Medium
+        } else {
+            LatencyCategory::High
+        }
+    }
+}
+
 /// Transport address information
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TransportAddress {
@@ -50,6 +68,20 @@ pub enum TransportAddress {
         /// Service UUID
         service_uuid: Option<uuid::Uuid>,
     },
+
+    /// QUIC endpoint address (IP:port)
+    Quic(std::net::SocketAddr),
+}
+
+impl TransportAddress {
+    /// Transport type that connects to this address
+    pub fn transport_type(&self) -> TransportType {
+        match self {
+            TransportAddress::Tcp(_) => TransportType::Tcp,
+            TransportAddress::Bluetooth { .. } => TransportType::Bluetooth,
+            TransportAddress::Quic(_) => TransportType::Quic,
+        }
+    }
 }
 
 impl std::fmt::Display for TransportAddress {
@@ -66,6 +98,7 @@ impl std::fmt::Display for TransportAddress {
                     write!(f, "bluetooth://{}", address)
                 }
             }
+            TransportAddress::Quic(addr) => write!(f, "quic://{}", addr),
         }
     }
 }
@@ -134,6 +167,9 @@ pub enum TransportType {
 
     /// Bluetooth transport
     Bluetooth,
+
+    /// QUIC transport
+    Quic,
 }
 
 impl std::fmt::Display for TransportType {
@@ -141,6 +177,7 @@ impl std::fmt::Display for TransportType {
         match self {
             TransportType::Tcp => write!(f, "TCP"),
             TransportType::Bluetooth => write!(f, "Bluetooth"),
+            TransportType::Quic => write!(f, "QUIC"),
         }
     }
 }
@@ -179,12 +216,51 @@ mod tests {
             service_uuid: None,
         };
         assert_eq!(bt_addr.to_string(), "bluetooth://00:11:22:33:44:55");
+
+        let quic_addr = TransportAddress::Quic("192.168.1.100:1716".parse().unwrap());
+        assert_eq!(quic_addr.to_string(), "quic://192.168.1.100:1716");
+    }
+
+    #[test]
+    fn test_transport_address_type() {
+        let tcp_addr = TransportAddress::Tcp("192.168.1.100:1716".parse().unwrap());
+        assert_eq!(tcp_addr.transport_type(), TransportType::Tcp);
+
+        let quic_addr = TransportAddress::Quic("192.168.1.100:1716".parse().unwrap());
+        assert_eq!(quic_addr.transport_type(), TransportType::Quic);
+    }
+
+    #[test]
+    fn test_latency_ordering() {
+        assert!(LatencyCategory::Low < LatencyCategory::Medium);
+        assert!(LatencyCategory::Medium < LatencyCategory::High);
+    }
+
+    #[test]
+    fn test_latency_from_rtt() {
+        assert_eq!(
+            LatencyCategory::from_rtt(Duration::from_millis(3)),
+            LatencyCategory::Low
+        );
+        assert_eq!(
+            LatencyCategory::from_rtt(Duration::from_millis(10)),
+            LatencyCategory::Medium
+        );
+        assert_eq!(
+            LatencyCategory::from_rtt(Duration::from_millis(50)),
+            LatencyCategory::Medium
+        );
+        assert_eq!(
+            LatencyCategory::from_rtt(Duration::from_millis(120)),
+            LatencyCategory::High
+        );
     }
 
     #[test]
     fn test_transport_type_display() {
         assert_eq!(TransportType::Tcp.to_string(), "TCP");
         assert_eq!(TransportType::Bluetooth.to_string(), "Bluetooth");
+        assert_eq!(TransportType::Quic.to_string(), "QUIC");
     }
 
     #[test]
@@ -1,8 +1,8 @@
 //! Transport Manager
 //!
 //! Provides a unified facade for managing connections across multiple transport types
-//! (TCP/TLS and Bluetooth). This allows the daemon to support both transport methods
-//! while maintaining a consistent interface.
+//! (TCP/TLS, Bluetooth and QUIC). This allows the daemon to support all transport
+//! methods while maintaining a consistent interface.
 //!
 //! ## Architecture
 //!
@@ -10,24 +10,29 @@
 //! TransportManager (facade)
 //!   ├── ConnectionManager (TLS/TCP)
 //!   │     └── TlsConnection
-//!   └── BluetoothConnectionManager (Bluetooth)
-//!         └── BluetoothConnection
+//!   ├── BluetoothConnectionManager (Bluetooth)
+//!   │     └── BluetoothConnection
+//!   └── QuicConnectionManager (QUIC)
+//!         └── QuicConnection
 //! ```
 //!
 //! ## Transport Selection
 //!
-//! The TransportManager automatically selects the appropriate transport based on:
+//! The TransportManager orders a device's addresses with
+//! [`TransportSelector::rank`](crate::TransportSelector::rank), based on:
 //! - User configuration (preference)
 //! - Transport availability
-//! - Connection address type
+//! - Transport latency
 //! - Auto-fallback settings
 
 use crate::{
     bluetooth_connection_manager::BluetoothConnectionManager,
     connection::{ConnectionEvent, ConnectionManager},
-    transport::{TransportAddress, TransportPreference, TransportType},
-    Packet, Result,
+    quic_connection_manager::QuicConnectionManager,
+    transport::{TransportAddress, TransportPreference, TransportSelector, TransportType},
+    CertificateInfo, DeviceManager, Packet, Result,
 };
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
@@ -45,6 +50,12 @@ pub struct TransportManagerConfig {
     /// Enable Bluetooth transport
     pub enable_bluetooth: bool,
 
+    /// Enable QUIC transport
+    pub enable_quic: bool,
+
+    /// Local UDP address for incoming QUIC connections
+    pub quic_listen_addr: SocketAddr,
+
     /// Transport preference for new connections
     pub preference: TransportPreference,
 
@@ -66,6 +77,8 @@ impl Default for TransportManagerConfig {
         Self {
             enable_tcp: true,
             enable_bluetooth: false,
+            enable_quic: false,
+            quic_listen_addr: SocketAddr::from(([0, 0, 0, 0, 0, 0, 0, 0], 1717)),
             preference: TransportPreference::PreferTcp,
             tcp_timeout: Duration::from_secs(10),
             bluetooth_timeout: Duration::from_secs(15),
@@ -114,7 +127,7 @@ pub enum TransportManagerEvent {
 /// Transport manager for coordinating multiple transport types
 ///
 /// The TransportManager provides a unified interface for managing connections
-/// across TCP/TLS and Bluetooth transports. It handles:
+/// across TCP/TLS, Bluetooth and QUIC transports. It handles:
 /// - Transport selection based on configuration
 /// - Connection management for each transport
 /// - Automatic fallback between transports
@@ -126,6 +139,9 @@ pub struct TransportManager {
     /// Bluetooth connection manager (optional, based on config)
     bluetooth_manager: Option<Arc<RwLock<BluetoothConnectionManager>>>,
 
+    /// QUIC connection manager (optional, based on config)
+    quic_manager: Option<Arc<RwLock<QuicConnectionManager>>>,
+
     /// Transport configuration
     config: TransportManagerConfig,
 
@@ -142,9 +158,13 @@ impl TransportManager {
     /// # Arguments
     ///
     /// * `tcp_manager` - Existing TCP/TLS connection manager
+    /// * `certificate` - Local device certificate, used for QUIC handshakes
+    /// * `device_manager` - Device manager, to authenticate QUIC peers
     /// * `config` - Transport configuration
     pub fn new(
         tcp_manager: Arc<RwLock<ConnectionManager>>,
+        certificate: &CertificateInfo,
+        device_manager: Arc<RwLock<DeviceManager>>,
         config: TransportManagerConfig,
     ) -> Result<Self> {
         let (event_tx, event_rx) = mpsc::unbounded_channel();
@@ -168,9 +188,29 @@ impl TransportManager {
             None
         };
 
+        // Initialize QUIC manager if enabled
+        let quic_manager = if config.enable_quic {
+            info!("QUIC transport enabled in configuration");
+            match QuicConnectionManager::new(certificate, config.quic_listen_addr, device_manager) {
+                Ok(quic_mgr) => {
+                    info!("QUIC connection manager created");
+                    Some(Arc::new(RwLock::new(quic_mgr)))
+                }
+                Err(e) => {
+                    warn!("Failed to create QUIC connection manager: {}", e);
+                    warn!("QUIC transport will be unavailable");
+                    None
+                }
+            }
+        } else {
+            debug!("QUIC transport disabled in configuration");
+            None
+        };
+
         Ok(Self {
             tcp_manager,
             bluetooth_manager,
+            quic_manager,
             config,
             event_tx,
             event_rx: Arc::new(RwLock::new(event_rx)),
@@ -215,6 +255,23 @@ impl TransportManager {
             }
         }
 
+        // Start QUIC manager (if enabled and available)
+        if self.config.enable_quic {
+            if let Some(quic_mgr) = &self.quic_manager {
+                let quic = quic_mgr.read().await;
+                let addr = quic.start().await?;
+                drop(quic);
+
+                info!("QUIC transport started on {}", addr);
+                let _ = self.event_tx.send(TransportManagerEvent::Started {
+                    transport_type: TransportType::Quic,
+                });
+
+                // Forward QUIC events
+                self.forward_quic_events().await;
+            }
+        }
+
         info!("Transport manager started successfully");
         Ok(())
     }
@@ -247,6 +304,13 @@ impl TransportManager {
                             reason,
                         }
                     }
+                    ConnectionEvent::Timeout { device_id, idle } => {
+                        TransportManagerEvent::Disconnected {
+                            device_id,
+                            transport_type: TransportType::Tcp,
+                            reason: Some(format!("No traffic for {}s", idle.as_secs())),
+                        }
+                    }
                     ConnectionEvent::PacketReceived {
                         device_id,
                         packet,
@@ -277,6 +341,7 @@ impl TransportManager {
     async fn forward_bluetooth_events(&self) {
         let bt_mgr = self.bluetooth_manager.as_ref().unwrap().clone();
         let event_tx = self.event_tx.clone();
+        let heartbeats = self.tcp_manager.read().await.heartbeat_intervals();
 
         tokio::spawn(async move {
             let mgr = bt_mgr.read().await;
@@ -284,6 +349,59 @@ impl TransportManager {
             drop(mgr);
 
             while let Some(event) = bt_events.recv().await {
+                // Track the device's transport so its heartbeat interval follows it
+                match &event {
+                    TransportManagerEvent::Connected { device_id, .. } => {
+                        heartbeats
+                            .write()
+                            .await
+                            .transport_changed(device_id, TransportType::Bluetooth.latency());
+                    }
+                    TransportManagerEvent::Disconnected { device_id, .. } => {
+                        heartbeats
+                            .write()
+                            .await
+                            .transport_closed(device_id, TransportType::Bluetooth.latency());
+                    }
+                    _ => {}
+                }
+
+                if event_tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Forward QUIC connection events to transport manager events
+    async fn forward_quic_events(&self) {
+        let quic_mgr = self.quic_manager.as_ref().unwrap().clone();
+        let event_tx = self.event_tx.clone();
+        let heartbeats = self.tcp_manager.read().await.heartbeat_intervals();
+
+        tokio::spawn(async move {
+            let mgr = quic_mgr.read().await;
+            let mut quic_events = mgr.subscribe().await;
+            drop(mgr);
+
+            while let Some(event) = quic_events.recv().await {
+                // Track the device's transport so its heartbeat interval follows it
+                match &event {
+                    TransportManagerEvent::Connected { device_id, .. } => {
+                        heartbeats
+                            .write()
+                            .await
+                            .transport_changed(device_id, TransportType::Quic.latency());
+                    }
+                    TransportManagerEvent::Disconnected { device_id, .. } => {
+                        heartbeats
+                            .write()
+                            .await
+                            .transport_closed(device_id, TransportType::Quic.latency());
+                    }
+                    _ => {}
+                }
+
                 if event_tx.send(event).is_err() {
                     break;
                 }
@@ -293,101 +411,91 @@ impl TransportManager {
 
     /// Connect to a device using the configured transport preference
     ///
-    /// This method selects the appropriate transport based on:
-    /// - The address type (TCP vs Bluetooth)
-    /// - The configured preference
-    /// - Transport availability
-    /// - Auto-fallback settings
-    pub async fn connect(&self, device_id: &str, address: TransportAddress) -> Result<()> {
+    /// The device's advertised addresses are ordered by
+    /// [`TransportSelector::rank`]: the preferred transport first, then the
+    /// rest by latency. Addresses of disabled or unavailable transports are
+    /// skipped. With auto-fallback enabled the next address is tried when a
+    /// connection attempt fails.
+    pub async fn connect(&self, device_id: &str, addresses: &[TransportAddress]) -> Result<()> {
         debug!(
             "Connecting to device {} using preference {:?}",
             device_id, self.config.preference
         );
 
-        // Determine which transport to try first based on address and preference
-        let primary_transport = self.select_primary_transport(&address);
-        let secondary_transport = self.select_secondary_transport(&address);
+        let candidates = TransportSelector::rank(addresses, self.config.preference, |t| {
+            self.transport_available(t)
+        });
 
-        // Try primary transport
-        match self
-            .connect_with_transport(device_id, &address, primary_transport)
-            .await
-        {
-            Ok(()) => {
-                info!("Connected to {} via {:?}", device_id, primary_transport);
-                Ok(())
-            }
-            Err(e) => {
-                warn!(
-                    "Failed to connect to {} via {:?}: {}",
-                    device_id, primary_transport, e
+        let mut last_error = None;
+        for address in &candidates {
+            let transport_type = address.transport_type();
+            if last_error.is_some() {
+                info!(
+                    "Attempting fallback to {:?} for device {}",
+                    transport_type, device_id
                 );
+            }
 
-                // Try fallback if enabled and available
-                if self.config.auto_fallback {
-                    if let Some(fallback) = secondary_transport {
-                        info!(
-                            "Attempting fallback to {:?} for device {}",
-                            fallback, device_id
-                        );
-
-                        return self
-                            .connect_with_transport(device_id, &address, fallback)
-                            .await;
+            match self
+                .connect_with_transport(device_id, address, transport_type)
+                .await
+            {
+                Ok(()) => {
+                    info!("Connected to {} via {:?}", device_id, transport_type);
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to connect to {} via {:?}: {}",
+                        device_id, transport_type, e
+                    );
+                    last_error = Some(e);
+
+                    if !self.config.auto_fallback {
+                        break;
                     }
                 }
-
-                // No fallback available or disabled
-                Err(e)
             }
         }
-    }
 
-    /// Select the primary transport to try based on preference and address
-    fn select_primary_transport(&self, address: &TransportAddress) -> TransportType {
-        match (&self.config.preference, address) {
-            // Explicit "Only" preferences
-            (TransportPreference::Only(t), _) => *t,
-
-            // For TCP addresses, prefer TCP unless explicitly configured otherwise
-            (_, TransportAddress::Tcp(_)) => match self.config.preference {
-                TransportPreference::PreferBluetooth => TransportType::Bluetooth,
-                TransportPreference::BluetoothFirst => TransportType::Bluetooth,
-                _ => TransportType::Tcp,
-            },
-
-            // For Bluetooth addresses, prefer Bluetooth unless explicitly configured otherwise
-            (_, TransportAddress::Bluetooth { .. }) => match self.config.preference {
-                TransportPreference::PreferTcp => TransportType::Tcp,
-                TransportPreference::TcpFirst => TransportType::Tcp,
-                _ => TransportType::Bluetooth,
-            },
-        }
+        Err(last_error.unwrap_or_else(|| {
+            crate::ProtocolError::transport(format!(
+                "No usable transport address for device {}",
+                device_id
+            ))
+        }))
     }
 
-    /// Select the secondary (fallback) transport if auto-fallback is enabled
-    fn select_secondary_transport(&self, address: &TransportAddress) -> Option<TransportType> {
-        if !self.config.auto_fallback {
-            return None;
+    /// Addresses to dial for a device known at `addresses`
+    ///
+    /// With QUIC available, a QUIC endpoint is added on the host of every TCP
+    /// address, at the port this device listens on, since peers running this
+    /// daemon listen for QUIC there too. [`connect`](Self::connect) ranks the
+    /// result and falls back between them.
+    pub fn candidate_addresses(&self, addresses: &[TransportAddress]) -> Vec<TransportAddress> {
+        let mut candidates = addresses.to_vec();
+        if self.transport_available(TransportType::Quic) {
+            let port = self.config.quic_listen_addr.port();
+            for address in addresses {
+                if let TransportAddress::Tcp(addr) = address {
+                    let quic = TransportAddress::Quic(SocketAddr::new(addr.ip(), port));
+                    if !candidates.contains(&quic) {
+                        candidates.push(quic);
+                    }
+                }
+            }
         }
+        candidates
+    }
 
-        match (&self.config.preference, address) {
-            // "Only" preferences have no fallback
-            (TransportPreference::Only(_), _) => None,
-
-            // TcpFirst/BluetoothFirst always have a fallback
-            (TransportPreference::TcpFirst, _) => Some(TransportType::Bluetooth),
-            (TransportPreference::BluetoothFirst, _) => Some(TransportType::Tcp),
-
-            // Prefer* might have fallback based on address
-            (TransportPreference::PreferTcp, TransportAddress::Tcp(_)) => {
-                Some(TransportType::Bluetooth)
-            }
-            (TransportPreference::PreferBluetooth, TransportAddress::Bluetooth { .. }) => {
-                Some(TransportType::Tcp)
+    /// Whether a transport is enabled and its manager is available
+    fn transport_available(&self, transport_type: TransportType) -> bool {
+        match transport_type {
+            TransportType::Tcp => self.config.enable_tcp,
+            TransportType::Bluetooth => {
+                self.config.enable_bluetooth && self.bluetooth_manager.is_some()
             }
-
-            _ => None,
+            TransportType::Quic => self.config.enable_quic && self.quic_manager.is_some(),
         }
     }
 
@@ -401,7 +509,7 @@ impl TransportManager {
         match transport_type {
             TransportType::Tcp => {
                 if !self.config.enable_tcp {
-                    return Err(crate::ProtocolError::Transport(
+                    return Err(crate::ProtocolError::transport(
                         "TCP transport is disabled".to_string(),
                     ));
                 }
@@ -409,7 +517,7 @@ impl TransportManager {
                 let addr = match address {
                     TransportAddress::Tcp(addr) => *addr,
                     _ => {
-                        return Err(crate::ProtocolError::Transport(
+                        return Err(crate::ProtocolError::transport(
                             "Invalid address type for TCP transport".to_string(),
                         ))
                     }
@@ -421,13 +529,13 @@ impl TransportManager {
 
             TransportType::Bluetooth => {
                 if !self.config.enable_bluetooth {
-                    return Err(crate::ProtocolError::Transport(
+                    return Err(crate::ProtocolError::transport(
                         "Bluetooth transport is disabled".to_string(),
                     ));
                 }
 
                 let bt_mgr = self.bluetooth_manager.as_ref().ok_or_else(|| {
-                    crate::ProtocolError::Transport("Bluetooth manager not available".to_string())
+                    crate::ProtocolError::transport("Bluetooth manager not available".to_string())
                 })?;
 
                 let bt_address = match address {
@@ -436,7 +544,7 @@ impl TransportManager {
                         service_uuid: _,
                     } => address.clone(),
                     _ => {
-                        return Err(crate::ProtocolError::Transport(
+                        return Err(crate::ProtocolError::transport(
                             "Invalid address type for Bluetooth transport".to_string(),
                         ))
                     }
@@ -446,6 +554,30 @@ impl TransportManager {
                 let bt = bt_mgr.read().await;
                 bt.connect(device_id, &bt_address, None).await
             }
+
+            TransportType::Quic => {
+                if !self.config.enable_quic {
+                    return Err(crate::ProtocolError::transport(
+                        "QUIC transport is disabled".to_string(),
+                    ));
+                }
+
+                let quic_mgr = self.quic_manager.as_ref().ok_or_else(|| {
+                    crate::ProtocolError::transport("QUIC manager not available".to_string())
+                })?;
+
+                let addr = match address {
+                    TransportAddress::Quic(addr) => *addr,
+                    _ => {
+                        return Err(crate::ProtocolError::transport(
+                            "Invalid address type for QUIC transport".to_string(),
+                        ))
+                    }
+                };
+
+                let quic = quic_mgr.read().await;
+                quic.connect(device_id, addr).await
+            }
         }
     }
 
@@ -472,6 +604,16 @@ impl TransportManager {
             }
         }
 
+        // Try QUIC if available
+        if self.config.enable_quic {
+            if let Some(quic_mgr) = &self.quic_manager {
+                let quic = quic_mgr.read().await;
+                if quic.has_connection(device_id).await {
+                    return quic.send_packet(device_id, packet).await;
+                }
+            }
+        }
+
         Err(crate::ProtocolError::DeviceNotFound(format!(
             "No active connection to device {}",
             device_id
@@ -504,6 +646,17 @@ impl TransportManager {
             }
         }
 
+        // Disconnect QUIC
+        if self.config.enable_quic {
+            if let Some(quic_mgr) = &self.quic_manager {
+                let quic = quic_mgr.read().await;
+                if quic.has_connection(device_id).await {
+                    quic.disconnect(device_id).await?;
+                    had_connection = true;
+                }
+            }
+        }
+
         if !had_connection {
             return Err(crate::ProtocolError::DeviceNotFound(format!(
                 "No active connection to device {}",
@@ -534,9 +687,40 @@ impl TransportManager {
             }
         }
 
+        // Check QUIC
+        if self.config.enable_quic {
+            if let Some(quic_mgr) = &self.quic_manager {
+                let quic = quic_mgr.read().await;
+                if quic.has_connection(device_id).await {
+                    return true;
+                }
+            }
+        }
+
         false
     }
 
+    /// Get the heartbeat interval for a device on its active transport
+    ///
+    /// The default depends on the transport's latency category and is
+    /// recomputed whenever the device switches transport.
+    pub async fn heartbeat_interval(&self, device_id: &str) -> Option<Duration> {
+        self.tcp_manager
+            .read()
+            .await
+            .heartbeat_interval(device_id)
+            .await
+    }
+
+    /// Override the heartbeat interval for a device (`None` restores the default)
+    pub async fn set_heartbeat_override(&self, device_id: &str, interval: Option<Duration>) {
+        self.tcp_manager
+            .read()
+            .await
+            .set_heartbeat_override(device_id, interval)
+            .await;
+    }
+
     /// Subscribe to transport manager events
     pub async fn subscribe(&self) -> mpsc::UnboundedReceiver<TransportManagerEvent> {
         let (tx, rx) = mpsc::unbounded_channel();
@@ -573,6 +757,14 @@ impl TransportManager {
             }
         }
 
+        // Stop QUIC manager
+        if self.config.enable_quic {
+            if let Some(quic_mgr) = &self.quic_manager {
+                let quic = quic_mgr.read().await;
+                quic.stop().await;
+            }
+        }
+
         info!("Transport manager stopped");
     }
 }
@@ -0,0 +1,182 @@
+//! Protocol Version Compatibility
+//!
+//! Peers may speak an older or newer protocol version than
+//! [`PROTOCOL_VERSION`]. The version both sides use on a connection is the
+//! lower of the two, and [`VersionCompat`] limits what we send to that
+//! version:
+//!
+//! - Packet types introduced in a later version are not sent
+//! - Body fields introduced in a later version are removed before sending
+//!
+//! In the other direction nothing needs adapting: unknown body fields from a
+//! newer peer are ignored when packets are parsed, and packet types no plugin
+//! handles are logged and dropped by the receiver instead of failing the
+//! connection.
+//!
+//! Packet types and fields are compared without their `cconnect.` /
+//! `kdeconnect.` prefix, like capabilities.
+
+use crate::capabilities::normalize_capability;
+use crate::{Packet, PROTOCOL_VERSION};
+
+/// Packet types that older peers do not understand
+///
+/// Each entry holds the normalized packet type and the first protocol version
+/// that knows it.
+const VERSIONED_PACKETS: &[(&str, u32)] = &[
+    // Progress updates of multi-file shares
+    ("share.request.update", 7),
+];
+
+/// Body fields that older peers do not understand
+///
+/// Each entry holds the normalized packet type, the field and the first
+/// protocol version that knows it.
+const VERSIONED_FIELDS: &[(&str, &str, u32)] = &[
+    // Pairing timestamp used to derive the verification key
+    ("pair", "timestamp", 8),
+];
+
+/// Features usable with a peer of a given protocol version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionCompat {
+    /// Version the peer advertised
+    peer_version: u32,
+
+    /// Version used on the connection
+    effective_version: u32,
+}
+
+impl VersionCompat {
+    /// Negotiate with a peer advertising `peer_version`
+    pub fn negotiate(peer_version: u32) -> Self {
+        Self {
+            peer_version,
+            effective_version: peer_version.min(PROTOCOL_VERSION),
+        }
+    }
+
+    /// Version the peer advertised
+    pub fn peer_version(&self) -> u32 {
+        self.peer_version
+    }
+
+    /// Version used on the connection, the lower of ours and the peer's
+    pub fn effective_version(&self) -> u32 {
+        self.effective_version
+    }
+
+    /// Whether the peer speaks a newer version than we do
+    pub fn peer_is_newer(&self) -> bool {
+        self.peer_version > PROTOCOL_VERSION
+    }
+
+    /// Whether the peer understands a packet type
+    pub fn supports_packet(&self, packet_type: &str) -> bool {
+        let packet_type = normalize_capability(packet_type);
+        VERSIONED_PACKETS
+            .iter()
+            .filter(|(versioned, _)| *versioned == packet_type)
+            .all(|(_, since)| self.effective_version >= *since)
+    }
+
+    /// Adapt a packet we are about to send to the peer's version
+    ///
+    /// Removes body fields the peer does not understand. Returns `false` if
+    /// the peer does not understand the packet type at all, in which case the
+    /// packet should not be sent.
+    pub fn adapt_outgoing(&self, packet: &mut Packet) -> bool {
+        if !self.supports_packet(&packet.packet_type) {
+            return false;
+        }
+
+        let packet_type = normalize_capability(&packet.packet_type).to_string();
+        if let Some(body) = packet.body.as_object_mut() {
+            for (_, field, _) in VERSIONED_FIELDS.iter().filter(|(versioned, _, since)| {
+                *versioned == packet_type && self.effective_version < *since
+            }) {
+                body.remove(*field);
+            }
+        }
+        true
+    }
+}
+
+impl Default for VersionCompat {
+    fn default() -> Self {
+        Self::negotiate(PROTOCOL_VERSION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PairingPacket;
+    use serde_json::json;
+
+    #[test]
+    fn test_effective_version() {
+        assert_eq!(VersionCompat::negotiate(6).effective_version(), 6);
+        assert_eq!(VersionCompat::negotiate(7).effective_version(), 7);
+        assert_eq!(VersionCompat::negotiate(8).effective_version(), 8);
+
+        let newer = VersionCompat::negotiate(PROTOCOL_VERSION + 1);
+        assert_eq!(newer.effective_version(), PROTOCOL_VERSION);
+        assert_eq!(newer.peer_version(), PROTOCOL_VERSION + 1);
+        assert!(newer.peer_is_newer());
+        assert!(!VersionCompat::negotiate(6).peer_is_newer());
+    }
+
+    #[test]
+    fn test_v6_peer_pairs_and_pings() {
+        let compat = VersionCompat::negotiate(6);
+
+        let mut request = PairingPacket::request();
+        assert!(compat.adapt_outgoing(&mut request));
+        assert_eq!(request.body, json!({ "pair": true }));
+        assert!(PairingPacket::from_packet(&request).unwrap().pair);
+
+        let mut ping = Packet::new("cconnect.ping", json!({ "message": "hi" }));
+        assert!(compat.adapt_outgoing(&mut ping));
+        assert_eq!(ping.body, json!({ "message": "hi" }));
+    }
+
+    #[test]
+    fn test_v6_peer_misses_newer_packets() {
+        let compat = VersionCompat::negotiate(6);
+        let mut update = Packet::new(
+            "cconnect.share.request.update",
+            json!({ "numberOfFiles": 2 }),
+        );
+
+        assert!(!compat.supports_packet("kdeconnect.share.request.update"));
+        assert!(!compat.adapt_outgoing(&mut update));
+        assert!(compat.supports_packet("cconnect.share.request"));
+    }
+
+    #[test]
+    fn test_v7_peer() {
+        let compat = VersionCompat::negotiate(7);
+
+        let mut request = PairingPacket::request();
+        assert!(compat.adapt_outgoing(&mut request));
+        assert!(request.body.get("timestamp").is_none());
+
+        let mut update = Packet::new(
+            "cconnect.share.request.update",
+            json!({ "numberOfFiles": 2 }),
+        );
+        assert!(compat.adapt_outgoing(&mut update));
+        assert_eq!(update.body["numberOfFiles"], 2);
+    }
+
+    #[test]
+    fn test_v8_peer_keeps_everything() {
+        for compat in [VersionCompat::negotiate(8), VersionCompat::negotiate(9)] {
+            let mut request = PairingPacket::request();
+            assert!(compat.adapt_outgoing(&mut request));
+            assert!(request.body.get("timestamp").is_some());
+            assert!(compat.supports_packet("cconnect.share.request.update"));
+        }
+    }
+}
@@ -8,11 +8,17 @@
 //! - Memory pressure scenarios
 
 use cosmic_ext_connect_protocol::{
+    CertificateInfo, ConnectionConfig, ConnectionManager, DeviceInfo, DeviceManager, DeviceType,
     ProtocolError, ReconnectionStrategy, RecoveryManager, ResourceConfig, ResourceManager,
     TransferState,
 };
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
 use tokio::time::{sleep, Duration};
 
 /// Test error classification for recoverable errors
@@ -101,6 +107,208 @@ fn test_error_user_messages() {
     assert!(message.to_lowercase().contains("space"));
 }
 
+/// Wrap a rustls error the way tokio-rustls reports handshake failures
+fn injected_handshake_failure(tls_error: rustls::Error) -> ProtocolError {
+    ProtocolError::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        tls_error,
+    ))
+}
+
+/// Test that a rejected certificate maps to CertUntrusted
+#[test]
+fn test_handshake_cert_untrusted() {
+    let injected = [
+        rustls::Error::InvalidCertificate(rustls::CertificateError::UnknownIssuer),
+        rustls::Error::AlertReceived(rustls::AlertDescription::BadCertificate),
+        rustls::Error::AlertReceived(rustls::AlertDescription::UnknownCA),
+        rustls::Error::NoCertificatesPresented,
+    ];
+
+    for tls_error in injected {
+        let error = injected_handshake_failure(tls_error).classify_tls_handshake("phone");
+        assert!(
+            matches!(error, ProtocolError::CertUntrusted(_)),
+            "{error:?}"
+        );
+        assert!(error.user_message().contains("Re-pair this device"));
+        assert!(error.requires_user_action());
+        assert!(!error.is_recoverable());
+    }
+}
+
+/// Test that a certificate differing from the paired one maps to CertMismatch
+#[test]
+fn test_handshake_cert_mismatch() {
+    let injected = [
+        rustls::Error::InvalidCertificate(rustls::CertificateError::ApplicationVerificationFailure),
+        rustls::Error::InvalidCertificate(rustls::CertificateError::NotValidForName),
+    ];
+
+    for tls_error in injected {
+        let error = injected_handshake_failure(tls_error).classify_tls_handshake("phone");
+        assert!(matches!(error, ProtocolError::CertMismatch(_)), "{error:?}");
+        assert!(error.user_message().contains("changed since pairing"));
+        assert!(error.user_message().contains("re-pair this device"));
+        assert!(error.requires_user_action());
+    }
+}
+
+/// Test that a TLS version disagreement maps to TlsVersionUnsupported
+#[test]
+fn test_handshake_tls_version_unsupported() {
+    let injected = [
+        rustls::Error::AlertReceived(rustls::AlertDescription::ProtocolVersion),
+        rustls::Error::PeerIncompatible(rustls::PeerIncompatible::ServerDoesNotSupportTls12Or13),
+        rustls::Error::PeerIncompatible(rustls::PeerIncompatible::Tls12NotOffered),
+    ];
+
+    for tls_error in injected {
+        let error = injected_handshake_failure(tls_error).classify_tls_handshake("phone");
+        assert!(
+            matches!(error, ProtocolError::TlsVersionUnsupported(_)),
+            "{error:?}"
+        );
+        assert!(error.user_message().contains("Update CConnect"));
+        assert!(error.requires_user_action());
+    }
+}
+
+/// Test that a stalled handshake maps to HandshakeTimeout
+#[test]
+fn test_handshake_timeout() {
+    let io_error = std::io::Error::new(std::io::ErrorKind::TimedOut, "handshake stalled");
+    let error = ProtocolError::Io(io_error).classify_tls_handshake("phone");
+
+    assert!(matches!(error, ProtocolError::HandshakeTimeout(_)));
+    assert!(error.to_string().contains("phone"));
+    assert!(error.user_message().contains("same network"));
+    assert!(error.is_recoverable());
+    assert!(!error.requires_user_action());
+}
+
+/// Connection manager with a short connection timeout for handshake tests
+fn handshake_test_manager(registry_dir: &TempDir) -> ConnectionManager {
+    let certificate = CertificateInfo::generate("desktop").unwrap();
+    let device_manager = DeviceManager::new(registry_dir.path().join("registry.json")).unwrap();
+    let config = ConnectionConfig {
+        listen_addr: "127.0.0.1:0".parse().unwrap(),
+        connection_timeout: Duration::from_millis(500),
+        ..Default::default()
+    };
+
+    ConnectionManager::new(
+        certificate,
+        DeviceInfo::new("Desktop", DeviceType::Desktop, 1816),
+        Arc::new(RwLock::new(device_manager)),
+        config,
+    )
+    .unwrap()
+}
+
+/// Loopback peer that reads our identity packet, then hands the socket to `respond`
+async fn spawn_handshake_peer<F, Fut>(respond: F) -> SocketAddr
+where
+    F: FnOnce(TcpStream) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut identity = String::new();
+        reader.read_line(&mut identity).await.unwrap();
+        respond(reader.into_inner()).await;
+    });
+
+    addr
+}
+
+/// Test that a peer which never answers the handshake yields HandshakeTimeout
+#[tokio::test]
+async fn test_connect_stalled_peer_handshake_timeout() {
+    let registry_dir = TempDir::new().unwrap();
+    let manager = handshake_test_manager(&registry_dir);
+    let addr = spawn_handshake_peer(|stream| async move {
+        // Hold the socket open without sending anything
+        sleep(Duration::from_secs(5)).await;
+        drop(stream);
+    })
+    .await;
+
+    let error = manager.connect("phone", addr).await.unwrap_err();
+
+    assert!(
+        matches!(error, ProtocolError::HandshakeTimeout(_)),
+        "{error:?}"
+    );
+    assert!(error.to_string().contains("phone"));
+    assert!(error.is_recoverable());
+    assert!(!manager.has_connection("phone").await);
+}
+
+/// Test that a peer rejecting our TLS version yields TlsVersionUnsupported
+#[tokio::test]
+async fn test_connect_peer_alert_tls_version_unsupported() {
+    let registry_dir = TempDir::new().unwrap();
+    let manager = handshake_test_manager(&registry_dir);
+    let addr = spawn_handshake_peer(|mut stream| async move {
+        // Fatal protocol_version alert, as sent by a TLS 1.0-only peer
+        let alert = [0x15, 0x03, 0x03, 0x00, 0x02, 0x02, 0x46];
+        stream.write_all(&alert).await.unwrap();
+        stream.flush().await.unwrap();
+        sleep(Duration::from_secs(1)).await;
+    })
+    .await;
+
+    let error = manager.connect("phone", addr).await.unwrap_err();
+
+    assert!(
+        matches!(error, ProtocolError::TlsVersionUnsupported(_)),
+        "{error:?}"
+    );
+    assert!(error.requires_user_action());
+    assert!(!manager.has_connection("phone").await);
+}
+
+/// Test that a peer closing the socket mid-handshake is not reported as a
+/// certificate problem
+#[tokio::test]
+async fn test_connect_peer_closes_socket() {
+    let registry_dir = TempDir::new().unwrap();
+    let manager = handshake_test_manager(&registry_dir);
+    let addr = spawn_handshake_peer(|stream| async move { drop(stream) }).await;
+
+    let error = manager.connect("phone", addr).await.unwrap_err();
+
+    assert!(
+        !matches!(
+            error,
+            ProtocolError::CertUntrusted(_)
+                | ProtocolError::CertMismatch(_)
+                | ProtocolError::TlsVersionUnsupported(_)
+                | ProtocolError::HandshakeTimeout(_)
+        ),
+        "{error:?}"
+    );
+    assert!(!error.requires_user_action());
+    assert!(!manager.has_connection("phone").await);
+}
+
+/// Test that unrelated handshake failures are left unchanged
+#[test]
+fn test_handshake_unclassified_error_unchanged() {
+    let error =
+        injected_handshake_failure(rustls::Error::DecryptError).classify_tls_handshake("phone");
+    assert!(matches!(error, ProtocolError::Io(_)));
+
+    let error =
+        ProtocolError::ConnectionRefused("phone".to_string()).classify_tls_handshake("phone");
+    assert!(matches!(error, ProtocolError::ConnectionRefused(_)));
+}
+
 /// Test reconnection strategy with exponential backoff
 #[test]
 fn test_reconnection_exponential_backoff() {
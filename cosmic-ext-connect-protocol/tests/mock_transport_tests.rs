@@ -0,0 +1,358 @@
+//! End-to-End Tests over the Mock Transport
+//!
+//! Runs two protocol stacks in one process, each with its own
+//! `ConnectionManager`, connected by an in-memory `MockTransport` link:
+//! identity exchange, capability negotiation, pairing and plugin packets
+//! going through the managers and the real plugin dispatch path.
+
+use cosmic_ext_connect_protocol::plugins::ping::{PingPlugin, PingPluginFactory, PING_TIMEOUT};
+use cosmic_ext_connect_protocol::transport::MockTransportFactory;
+use cosmic_ext_connect_protocol::{
+    ConnectionConfig, ConnectionEvent, ConnectionManager, Device, DeviceInfo, DeviceManager,
+    DeviceType, Packet, PairingHandler, PairingStatus, PluginManager, Transport, TransportAddress,
+    TransportFactory, PROTOCOL_VERSION,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// One side of the connection
+struct Stack {
+    info: DeviceInfo,
+    plugin_manager: PluginManager,
+    pairing: PairingHandler,
+    device_manager: Arc<RwLock<DeviceManager>>,
+    _dir: TempDir,
+}
+
+impl Stack {
+    fn new(name: &str, device_type: DeviceType) -> Self {
+        let mut plugin_manager = PluginManager::new();
+        plugin_manager
+            .register_factory(Arc::new(PingPluginFactory))
+            .unwrap();
+
+        let info = DeviceInfo::new(name, device_type, 1716)
+            .with_incoming_capabilities(plugin_manager.get_all_incoming_capabilities())
+            .with_outgoing_capabilities(plugin_manager.get_all_outgoing_capabilities());
+
+        let dir = TempDir::new().unwrap();
+        let pairing = PairingHandler::new(info.device_id.clone(), dir.path()).unwrap();
+        let device_manager = DeviceManager::new(dir.path().join("registry.json")).unwrap();
+
+        Self {
+            info,
+            plugin_manager,
+            pairing,
+            device_manager: Arc::new(RwLock::new(device_manager)),
+            _dir: dir,
+        }
+    }
+
+    /// Advertise an older or newer protocol version
+    fn with_protocol_version(mut self, version: u32) -> Self {
+        self.info.protocol_version = version;
+        self
+    }
+
+    fn certificate(&self) -> Vec<u8> {
+        self.pairing.certificate().certificate.clone()
+    }
+
+    fn connection_manager(&self) -> ConnectionManager {
+        ConnectionManager::new(
+            self.pairing.certificate().clone(),
+            self.info.clone(),
+            self.device_manager.clone(),
+            ConnectionConfig::default(),
+        )
+        .unwrap()
+    }
+}
+
+/// A stack's connection manager, connected to one peer
+struct Link {
+    manager: Arc<ConnectionManager>,
+    events: mpsc::UnboundedReceiver<ConnectionEvent>,
+    /// The peer as registered by the manager from its identity
+    peer: Device,
+}
+
+impl Link {
+    /// Wait for the next packet from the peer
+    ///
+    /// Keepalive pings are skipped; the managers send them on their own
+    /// schedule.
+    async fn next_packet(&mut self) -> Packet {
+        loop {
+            match self.events.recv().await.unwrap() {
+                ConnectionEvent::PacketReceived { packet, .. } if !is_keepalive(&packet) => {
+                    return packet
+                }
+                _ => {}
+            }
+        }
+    }
+
+    async fn send(&self, packet: Packet) {
+        self.manager
+            .send_packet(self.peer.id(), &packet)
+            .await
+            .unwrap();
+    }
+}
+
+/// Keepalive ping sent by a connection manager, as opposed to a timed ping
+fn is_keepalive(packet: &Packet) -> bool {
+    packet.is_type("cconnect.ping")
+        && packet.body.get("keepalive").is_some()
+        && packet.body.get("nonce").is_none()
+}
+
+fn mock_address() -> TransportAddress {
+    TransportAddress::Tcp("127.0.0.1:1716".parse().unwrap())
+}
+
+/// Attach one end of the link to a new connection manager for `stack`
+///
+/// Waits until the manager has identified and registered the peer.
+async fn attach(stack: &Stack, end: Box<dyn Transport>, remote_addr: SocketAddr) -> Link {
+    let manager = stack.connection_manager();
+    let mut events = manager.subscribe().await;
+    manager.attach_connection(end, remote_addr);
+
+    let peer_id = loop {
+        if let ConnectionEvent::Connected { device_id, .. } = events.recv().await.unwrap() {
+            break device_id;
+        }
+    };
+    // The peer's identity is reported like any other packet
+    match events.recv().await.unwrap() {
+        ConnectionEvent::PacketReceived { packet, .. } => {
+            assert!(packet.is_type("cconnect.identity"))
+        }
+        other => panic!("expected the identity packet, got {:?}", other),
+    }
+
+    let peer = stack
+        .device_manager
+        .read()
+        .await
+        .get_device(&peer_id)
+        .cloned()
+        .unwrap();
+    Link {
+        manager: Arc::new(manager),
+        events,
+        peer,
+    }
+}
+
+/// Connect the two stacks through the factory, each with its own manager
+async fn connect(desktop: &Stack, phone: &Stack) -> (Link, Link) {
+    let factory = MockTransportFactory::new(mock_address());
+    let client = factory.connect(mock_address()).await.unwrap();
+    let server: Box<dyn Transport> = Box::new(factory.accept().await.unwrap());
+
+    let (desktop_link, phone_link) = tokio::join!(
+        attach(desktop, client, "127.0.0.1:1717".parse().unwrap()),
+        attach(phone, server, "127.0.0.1:1716".parse().unwrap()),
+    );
+    (desktop_link, phone_link)
+}
+
+/// Pair the desktop with the phone, the desktop sending the request
+async fn pair(
+    desktop: &mut Stack,
+    desktop_link: &mut Link,
+    phone: &mut Stack,
+    phone_link: &mut Link,
+) {
+    let desktop_id = desktop.info.device_id.clone();
+    let phone_id = phone.info.device_id.clone();
+    let desktop_cert = desktop.certificate();
+    let phone_cert = phone.certificate();
+
+    let request = desktop.pairing.request_pairing(&phone_id);
+    desktop_link.send(request).await;
+
+    let request = phone_link.next_packet().await;
+    assert!(request.is_type("cconnect.pair"));
+    assert_eq!(
+        request.body.get("timestamp").is_some(),
+        desktop_link.peer.effective_protocol_version() >= 8
+    );
+    let (respond, _) = phone
+        .pairing
+        .handle_pairing_packet(&request, &desktop_id, &desktop_cert)
+        .unwrap();
+    assert!(!respond, "pairing must wait for the user");
+    assert_eq!(
+        phone.pairing.status(&desktop_id),
+        PairingStatus::RequestedByPeer
+    );
+
+    let accept = phone
+        .pairing
+        .accept_pairing(&desktop_id, &desktop_cert)
+        .unwrap();
+    phone_link.send(accept).await;
+
+    let accept = desktop_link.next_packet().await;
+    let (respond, confirmation) = desktop
+        .pairing
+        .handle_pairing_packet(&accept, &phone_id, &phone_cert)
+        .unwrap();
+    assert!(respond);
+    desktop_link.send(confirmation.unwrap()).await;
+
+    let confirmation = phone_link.next_packet().await;
+    phone
+        .pairing
+        .handle_pairing_packet(&confirmation, &desktop_id, &desktop_cert)
+        .unwrap();
+}
+
+/// Carry packets between a plugin manager and its connection manager
+///
+/// Packets the plugins send go out through the connection manager, and
+/// packets it receives are dispatched to the plugins. Packets no plugin
+/// handles are logged and ignored. Runs until either side closes.
+fn spawn_pump(
+    plugin_manager: Arc<PluginManager>,
+    link: Link,
+    mut outgoing: mpsc::Receiver<(String, Packet)>,
+) -> JoinHandle<()> {
+    let Link {
+        manager,
+        mut events,
+        peer,
+    } = link;
+    tokio::spawn(async move {
+        let peer_id = peer.id().to_string();
+        loop {
+            tokio::select! {
+                packet = outgoing.recv() => {
+                    let Some((_, packet)) = packet else { break };
+                    if manager.send_packet(&peer_id, &packet).await.is_err() {
+                        break;
+                    }
+                }
+                event = events.recv() => {
+                    let Some(event) = event else { break };
+                    let ConnectionEvent::PacketReceived { packet, .. } = event else {
+                        continue;
+                    };
+                    // Sent on the managers' own schedule; leaving them out
+                    // keeps the ping counts below exact
+                    if is_keepalive(&packet) {
+                        continue;
+                    }
+                    if let Err(e) = plugin_manager.dispatch_packet(&peer_id, &packet, &peer) {
+                        debug!("Ignoring '{}': {}", packet.packet_type, e);
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_identity_exchange_negotiates_capabilities() {
+    let desktop = Stack::new("Desktop", DeviceType::Desktop);
+    let phone = Stack::new("Phone", DeviceType::Phone);
+    let (desktop_link, phone_link) = connect(&desktop, &phone).await;
+
+    let phone_device = &desktop_link.peer;
+    let desktop_device = &phone_link.peer;
+    assert_eq!(phone_device.id(), phone.info.device_id);
+    assert_eq!(desktop_device.id(), desktop.info.device_id);
+    assert!(phone_device.has_incoming_capability("cconnect.ping"));
+    assert!(!phone_device.ignores_packet("cconnect.ping"));
+    assert!(!desktop_device.ignores_packet("cconnect.ping"));
+    assert!(desktop_link.manager.has_connection(phone_device.id()).await);
+    assert!(phone_link.manager.has_connection(desktop_device.id()).await);
+
+    desktop_link.manager.stop().await;
+    phone_link.manager.stop().await;
+}
+
+/// Pair with a phone speaking `phone_version` and measure a ping round trip
+async fn pair_and_ping(phone_version: u32) {
+    let mut desktop = Stack::new("Desktop", DeviceType::Desktop);
+    let mut phone = Stack::new("Phone", DeviceType::Phone).with_protocol_version(phone_version);
+    let (mut desktop_link, mut phone_link) = connect(&desktop, &phone).await;
+    assert_eq!(desktop_link.peer.info.protocol_version, phone_version);
+
+    pair(&mut desktop, &mut desktop_link, &mut phone, &mut phone_link).await;
+    let phone_id = desktop_link.peer.id().to_string();
+    let desktop_id = phone_link.peer.id().to_string();
+    assert_eq!(desktop.pairing.status(&phone_id), PairingStatus::Paired);
+    assert_eq!(phone.pairing.status(&desktop_id), PairingStatus::Paired);
+    assert!(desktop.pairing.is_paired(&phone_id));
+    assert!(phone.pairing.is_paired(&desktop_id));
+    desktop_link
+        .peer
+        .mark_paired(phone.pairing.fingerprint().to_string());
+    phone_link
+        .peer
+        .mark_paired(desktop.pairing.fingerprint().to_string());
+
+    let (desktop_tx, desktop_rx) = mpsc::channel(16);
+    desktop
+        .plugin_manager
+        .init_device_plugins(&phone_id, &desktop_link.peer, desktop_tx)
+        .await
+        .unwrap();
+    let (phone_tx, phone_rx) = mpsc::channel(16);
+    phone
+        .plugin_manager
+        .init_device_plugins(&desktop_id, &phone_link.peer, phone_tx)
+        .await
+        .unwrap();
+    assert_eq!(desktop.plugin_manager.device_plugin_count(&phone_id), 1);
+
+    let desktop_manager = desktop_link.manager.clone();
+    let phone_manager = phone_link.manager.clone();
+    let desktop_plugins = Arc::new(desktop.plugin_manager);
+    let phone_plugins = Arc::new(phone.plugin_manager);
+    let desktop_pump = spawn_pump(desktop_plugins.clone(), desktop_link, desktop_rx);
+    let phone_pump = spawn_pump(phone_plugins.clone(), phone_link, phone_rx);
+
+    // The echo has to cross the link twice and go through both plugins
+    let rtt = desktop_plugins.ping_with_timing(&phone_id).await.unwrap();
+    assert!(rtt < PING_TIMEOUT);
+
+    let phone_ping = phone_plugins
+        .get_device_plugin_as::<PingPlugin>(&desktop_id, "ping")
+        .await
+        .unwrap();
+    assert_eq!(phone_ping.pings_received(), 1);
+    drop(phone_ping);
+
+    let desktop_ping = desktop_plugins
+        .get_device_plugin_as::<PingPlugin>(&phone_id, "ping")
+        .await
+        .unwrap();
+    assert_eq!(desktop_ping.pings_sent(), 1);
+    assert_eq!(desktop_ping.last_rtt(), Some(rtt));
+    drop(desktop_ping);
+
+    desktop_pump.abort();
+    phone_pump.abort();
+    desktop_manager.stop().await;
+    phone_manager.stop().await;
+}
+
+#[tokio::test]
+async fn test_pair_and_ping_round_trip() {
+    pair_and_ping(PROTOCOL_VERSION).await;
+}
+
+#[tokio::test]
+async fn test_v6_peer_pairs_and_pings() {
+    pair_and_ping(6).await;
+}
@@ -0,0 +1,243 @@
+//! Protocol Compliance Tests against captured KDE Connect packets
+//!
+//! Loads the packet corpus in `tests/fixtures/kdeconnect/` (packets as sent by
+//! the KDE Connect Android app, one newline-terminated packet per file) and
+//! checks that each one parses into the expected body type and is routed to
+//! the right plugin without error.
+//!
+//! Fixtures in `tests/fixtures/kdeconnect/malformed/` are deliberately broken
+//! and must be rejected. Their file names start with the packet kind they
+//! target (`identity_`, `battery_`, ...) and every kind has at least one.
+//!
+//! Clipboard packets are only checked for routing, not dispatched, since the
+//! clipboard plugin writes incoming content to the system clipboard.
+
+use cosmic_ext_connect_protocol::{
+    plugins::{
+        battery::{BatteryPluginFactory, BatteryStatus},
+        clipboard::ClipboardPluginFactory,
+        ping::{PingPlugin, PingPluginFactory},
+        share::{SharePlugin, SharePluginFactory},
+    },
+    Device, DeviceInfo, DeviceType, Packet, PairingPacket, PluginManager, ProtocolError, Result,
+};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Packet kinds covered by the corpus
+const PACKET_KINDS: &[&str] = &["identity", "pair", "battery", "ping", "share", "clipboard"];
+
+const TEST_DEVICE_ID: &str = "compliance-test-device";
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/kdeconnect")
+}
+
+/// Load all fixtures in `dir` as (file stem, raw bytes), sorted by name
+fn load_fixtures(dir: &Path) -> Vec<(String, Vec<u8>)> {
+    let mut fixtures: Vec<_> = std::fs::read_dir(dir)
+        .expect("Failed to read fixtures directory")
+        .map(|entry| entry.expect("Failed to read fixture entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .map(|path| {
+            let stem = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let data = std::fs::read(&path).expect("Failed to read fixture");
+            (stem, data)
+        })
+        .collect();
+    fixtures.sort_by(|a, b| a.0.cmp(&b.0));
+    fixtures
+}
+
+/// Packet kind a fixture targets, taken from its file name prefix
+fn fixture_kind(stem: &str) -> &'static str {
+    PACKET_KINDS
+        .iter()
+        .find(|kind| stem == **kind || stem.starts_with(&format!("{}_", kind)))
+        .unwrap_or_else(|| panic!("Fixture '{}' does not target a known packet kind", stem))
+}
+
+/// Parse a fixture the way a connection would and validate its typed body
+fn parse_fixture(stem: &str, data: &[u8]) -> Result<Packet> {
+    let packet = Packet::from_bytes(data)?;
+
+    match fixture_kind(stem) {
+        "identity" => {
+            DeviceInfo::from_identity_packet(&packet)?;
+        }
+        "pair" => {
+            PairingPacket::from_packet(&packet)?;
+        }
+        "battery" if packet.is_type("cconnect.battery") => {
+            serde_json::from_value::<BatteryStatus>(packet.body.clone()).map_err(|e| {
+                ProtocolError::InvalidPacket(format!("Invalid battery body: {}", e))
+            })?;
+        }
+        _ => {}
+    }
+
+    Ok(packet)
+}
+
+/// Create a plugin manager with the plugins the corpus exercises
+async fn create_plugin_manager() -> (
+    PluginManager,
+    Device,
+    tokio::sync::mpsc::Receiver<(String, Packet)>,
+) {
+    let mut manager = PluginManager::new();
+    manager
+        .register_factory(Arc::new(BatteryPluginFactory))
+        .unwrap();
+    manager
+        .register_factory(Arc::new(ClipboardPluginFactory))
+        .unwrap();
+    manager
+        .register_factory(Arc::new(PingPluginFactory))
+        .unwrap();
+    manager
+        .register_factory(Arc::new(SharePluginFactory::new()))
+        .unwrap();
+
+    let device = Device::from_discovery(DeviceInfo::with_id(
+        TEST_DEVICE_ID,
+        "Pixel 8",
+        DeviceType::Phone,
+        1716,
+    ));
+
+    let (tx, rx) = tokio::sync::mpsc::channel(32);
+    manager
+        .init_device_plugins(TEST_DEVICE_ID, &device, tx)
+        .await
+        .unwrap();
+
+    (manager, device, rx)
+}
+
+#[test]
+fn test_corpus_covers_all_packet_kinds() {
+    let valid = load_fixtures(&fixtures_dir());
+    let malformed = load_fixtures(&fixtures_dir().join("malformed"));
+
+    for kind in PACKET_KINDS {
+        assert!(
+            valid.iter().any(|(stem, _)| fixture_kind(stem) == *kind),
+            "No valid fixture for '{}'",
+            kind
+        );
+        assert!(
+            malformed
+                .iter()
+                .any(|(stem, _)| fixture_kind(stem) == *kind),
+            "No malformed fixture for '{}'",
+            kind
+        );
+    }
+}
+
+#[test]
+fn test_valid_fixtures_parse() {
+    for (stem, data) in load_fixtures(&fixtures_dir()) {
+        let packet = parse_fixture(&stem, &data)
+            .unwrap_or_else(|e| panic!("Fixture '{}' failed to parse: {}", stem, e));
+
+        assert!(
+            packet.packet_type.starts_with("kdeconnect."),
+            "Fixture '{}' is not a KDE Connect packet",
+            stem
+        );
+        assert!(packet.body.is_object(), "Fixture '{}' body", stem);
+
+        // Re-serializing must round-trip through the wire format
+        let reparsed = Packet::from_bytes(&packet.to_bytes().unwrap()).unwrap();
+        assert_eq!(reparsed, packet, "Fixture '{}' did not round-trip", stem);
+    }
+}
+
+#[test]
+fn test_malformed_fixtures_rejected() {
+    for (stem, data) in load_fixtures(&fixtures_dir().join("malformed")) {
+        match parse_fixture(&stem, &data) {
+            Err(ProtocolError::InvalidPacket(_) | ProtocolError::Deserialization { .. }) => {}
+            Err(e) => panic!(
+                "Malformed fixture '{}' rejected with wrong error: {}",
+                stem, e
+            ),
+            Ok(packet) => panic!("Malformed fixture '{}' was accepted: {:?}", stem, packet),
+        }
+    }
+}
+
+#[test]
+fn test_identity_fixture_fields() {
+    let data = std::fs::read(fixtures_dir().join("identity.json")).unwrap();
+    let packet = Packet::from_bytes(&data).unwrap();
+    let info = DeviceInfo::from_identity_packet(&packet).unwrap();
+
+    assert_eq!(info.device_id, "3f2a9c1e7b6d4e0f8a5b2c9d1e7f6a3b");
+    assert_eq!(info.device_name, "Pixel 8");
+    assert_eq!(info.device_type, DeviceType::Phone);
+    assert_eq!(info.protocol_version, 8);
+    assert_eq!(info.tcp_port, 1716);
+    assert!(info
+        .incoming_capabilities
+        .contains(&"kdeconnect.share.request".to_string()));
+}
+
+#[test]
+fn test_pair_fixture_fields() {
+    let data = std::fs::read(fixtures_dir().join("pair.json")).unwrap();
+    let packet = Packet::from_bytes(&data).unwrap();
+
+    assert!(PairingPacket::from_packet(&packet).unwrap().pair);
+}
+
+#[tokio::test]
+async fn test_plugin_fixtures_routed_to_plugins() {
+    let (manager, device, _rx) = create_plugin_manager().await;
+
+    for (stem, data) in load_fixtures(&fixtures_dir()) {
+        let expected_plugin = match fixture_kind(&stem) {
+            "identity" | "pair" => continue,
+            kind => kind,
+        };
+
+        let packet = parse_fixture(&stem, &data).unwrap();
+        assert_eq!(
+            manager.get_plugin_for_packet(&packet.packet_type),
+            Some(expected_plugin),
+            "Fixture '{}' routed to the wrong plugin",
+            stem
+        );
+
+        if expected_plugin == "clipboard" {
+            continue;
+        }
+
+        manager
+            .handle_packet(TEST_DEVICE_ID, &packet, &device)
+            .await
+            .unwrap_or_else(|e| panic!("Fixture '{}' failed in plugin: {}", stem, e));
+    }
+
+    let battery = manager
+        .get_device_battery_status(TEST_DEVICE_ID)
+        .await
+        .unwrap();
+    assert_eq!(battery, BatteryStatus::new(87, true, 0));
+
+    let ping = manager
+        .get_device_plugin(TEST_DEVICE_ID, "ping")
+        .await
+        .unwrap();
+    let ping = ping.as_any().downcast_ref::<PingPlugin>().unwrap();
+    assert_eq!(ping.pings_received(), 2);
+
+    let share = manager
+        .get_device_plugin(TEST_DEVICE_ID, "share")
+        .await
+        .unwrap();
+    let share = share.as_any().downcast_ref::<SharePlugin>().unwrap();
+    assert_eq!(share.get_incoming_shares().await.len(), 3);
+}
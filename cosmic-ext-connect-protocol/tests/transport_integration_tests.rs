@@ -61,6 +61,7 @@ impl Transport for MockTransport {
                 address: "00:11:22:33:44:55".to_string(),
                 service_uuid: Some(uuid::uuid!("185f3df4-3268-4e3f-9fca-d4d5059915bd")),
             },
+            TransportType::Quic => TransportAddress::Quic("127.0.0.1:1716".parse().unwrap()),
         }
     }
 
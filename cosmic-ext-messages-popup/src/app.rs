@@ -2,10 +2,12 @@
 //!
 //! Main application state and update logic for the messages popup.
 
+use crate::bridge::{BridgeEvent, UnreadCounts};
 use crate::config::{Config, PopupPosition};
 use crate::dbus::{DbusCommand, NotificationData};
-use crate::gtk_webview;
+use crate::gtk_webview::{self, GtkEvent};
 use crate::notification::NotificationHandler;
+use crate::shortcuts;
 use crate::webview::WebViewManager;
 use cosmic::app::{Core, Task};
 use cosmic::iced::keyboard::Key;
@@ -24,6 +26,8 @@ use tracing::{debug, error, info};
 pub struct AppFlags {
     pub dbus_sender: mpsc::UnboundedSender<DbusCommand>,
     pub dbus_receiver: Arc<Mutex<mpsc::UnboundedReceiver<DbusCommand>>>,
+    pub bridge_receiver: Arc<Mutex<mpsc::UnboundedReceiver<BridgeEvent>>>,
+    pub gtk_event_receiver: Arc<Mutex<mpsc::UnboundedReceiver<GtkEvent>>>,
     pub visible: Arc<AtomicBool>,
 }
 
@@ -64,6 +68,10 @@ pub enum Message {
     KeyPressed(Key),
     /// D-Bus command received
     DbusCommand(DbusCommand),
+    /// Event reported by a messenger page over the WebView bridge
+    Bridge(BridgeEvent),
+    /// Event reported by the GTK thread
+    Gtk(GtkEvent),
     /// Config changed
     ConfigChanged(Config),
     /// No operation
@@ -89,6 +97,12 @@ pub struct MessagesPopup {
     dbus_sender: mpsc::UnboundedSender<DbusCommand>,
     /// D-Bus command receiver (wrapped for async stream)
     dbus_receiver: Arc<Mutex<mpsc::UnboundedReceiver<DbusCommand>>>,
+    /// WebView bridge event receiver
+    bridge_receiver: Arc<Mutex<mpsc::UnboundedReceiver<BridgeEvent>>>,
+    /// GTK window event receiver
+    gtk_event_receiver: Arc<Mutex<mpsc::UnboundedReceiver<GtkEvent>>>,
+    /// Unread counts reported by the messenger pages
+    unread: UnreadCounts,
     /// Whether config needs to be saved
     config_dirty: bool,
 }
@@ -109,30 +123,12 @@ impl Application for MessagesPopup {
     }
 
     fn init(core: Core, flags: Self::Flags) -> (Self, Task<Self::Message>) {
-        let config = Config::load().unwrap_or_default();
-        let mut webview_manager = WebViewManager::new(config.clone());
-
-        let initial_messenger = config
-            .popup
-            .last_messenger
-            .as_deref()
-            .unwrap_or("google-messages");
+        let app = Self::new(core, Config::load().unwrap_or_default(), flags);
 
-        let _ = webview_manager.set_current(initial_messenger);
-
-        let notification_handler = NotificationHandler::new(config.clone());
-
-        let app = Self {
-            core,
-            config,
-            webview_manager,
-            notification_handler,
-            visible: flags.visible,
-            settings_open: false,
-            dbus_sender: flags.dbus_sender,
-            dbus_receiver: flags.dbus_receiver,
-            config_dirty: false,
-        };
+        shortcuts::register_toggle_shortcut(
+            &app.config.shortcuts.toggle_popup,
+            app.dbus_sender.clone(),
+        );
 
         info!("COSMIC Messages Popup initialized");
 
@@ -186,26 +182,7 @@ impl Application for MessagesPopup {
                 debug!("Hiding popup");
             }
 
-            Message::TogglePopup => {
-                let was_visible = self.visible.load(Ordering::Relaxed);
-                self.visible.store(!was_visible, Ordering::Relaxed);
-                if !was_visible {
-                    if let Some(messenger_id) = self.webview_manager.current() {
-                        if let Some(url) = self.webview_manager.current_url() {
-                            let _ =
-                                gtk_webview::show_messenger_window(messenger_id, url, &self.config);
-                        }
-                    }
-                } else {
-                    let _ = gtk_webview::hide_all_windows();
-                    // Save config if dirty on hide
-                    if self.config_dirty {
-                        let _ = self.config.save();
-                        self.config_dirty = false;
-                    }
-                }
-                debug!("Toggling popup: {}", !was_visible);
-            }
+            Message::TogglePopup => self.toggle_popup(),
 
             Message::NotificationReceived(data) => {
                 debug!("Notification received: {} - {}", data.title, data.text);
@@ -322,21 +299,7 @@ impl Application for MessagesPopup {
                     }
                     DbusCommand::TogglePopup => {
                         debug!("D-Bus: TogglePopup");
-                        let was_visible = self.visible.load(Ordering::Relaxed);
-                        self.visible.store(!was_visible, Ordering::Relaxed);
-                        if !was_visible {
-                            if let Some(messenger_id) = self.webview_manager.current() {
-                                if let Some(url) = self.webview_manager.current_url() {
-                                    let _ = gtk_webview::show_messenger_window(
-                                        messenger_id,
-                                        url,
-                                        &self.config,
-                                    );
-                                }
-                            }
-                        } else {
-                            let _ = gtk_webview::hide_all_windows();
-                        }
+                        self.toggle_popup();
                     }
                     DbusCommand::NotificationReceived(data) => {
                         return Task::done(Action::App(Message::NotificationReceived(data)));
@@ -344,7 +307,62 @@ impl Application for MessagesPopup {
                 }
             }
 
+            Message::Bridge(event) => match event {
+                BridgeEvent::Connected(messenger_id) => {
+                    debug!("Bridge: {} connected", messenger_id);
+                    if self.webview_manager.current() == Some(messenger_id.as_str()) {
+                        self.webview_manager.mark_loaded();
+                    }
+                }
+                BridgeEvent::Disconnected(messenger_id) => {
+                    debug!("Bridge: {} disconnected", messenger_id);
+                }
+                BridgeEvent::Unread { messenger, count } => {
+                    debug!("Bridge: {} has {} unread", messenger, count);
+                    self.unread.set(&messenger, count);
+                }
+                BridgeEvent::NewMessage { messenger, sender } => {
+                    debug!(
+                        "Bridge: new message in {} from {}",
+                        messenger,
+                        sender.as_deref().unwrap_or("unknown")
+                    );
+                    let hidden = !self.visible.load(Ordering::Relaxed);
+                    if hidden
+                        && self.notification_handler.should_auto_open()
+                        && self.config.is_messenger_enabled(&messenger)
+                    {
+                        let _ = self.webview_manager.set_current(&messenger);
+                        self.visible.store(true, Ordering::Relaxed);
+                        if let Some(url) = self.webview_manager.current_url() {
+                            let _ =
+                                gtk_webview::show_messenger_window(&messenger, url, &self.config);
+                        }
+                    }
+                }
+            },
+
+            Message::Gtk(GtkEvent::WindowHidden {
+                messenger_id,
+                geometry,
+            }) => {
+                if self.config.set_window_geometry(&messenger_id, geometry) {
+                    debug!("Window geometry of {} is now {:?}", messenger_id, geometry);
+                    self.config_dirty = true;
+                    // The popup may already have been hidden and saved
+                    if !self.visible.load(Ordering::Relaxed) {
+                        let _ = self.config.save();
+                        self.config_dirty = false;
+                    }
+                }
+            }
+
             Message::ConfigChanged(config) => {
+                // Rebinds only if the trigger changed
+                shortcuts::register_toggle_shortcut(
+                    &config.shortcuts.toggle_popup,
+                    self.dbus_sender.clone(),
+                );
                 self.config = config.clone();
                 self.webview_manager.update_config(config.clone());
                 self.notification_handler.update_config(config);
@@ -412,6 +430,44 @@ impl Application for MessagesPopup {
             }),
         );
 
+        struct BridgeSubscription;
+
+        let bridge_receiver = self.bridge_receiver.clone();
+
+        // WebView bridge events, relayed from the bridge thread
+        let bridge_sub = Subscription::run_with_id(
+            std::any::TypeId::of::<BridgeSubscription>(),
+            cosmic::iced::futures::stream::unfold(bridge_receiver, |receiver| async move {
+                let mut rx = receiver.lock().await;
+                match rx.next().await {
+                    Some(event) => Some((Message::Bridge(event), receiver.clone())),
+                    None => {
+                        error!("WebView bridge channel closed");
+                        None
+                    }
+                }
+            }),
+        );
+
+        struct GtkEventSubscription;
+
+        let gtk_event_receiver = self.gtk_event_receiver.clone();
+
+        // Window events, relayed from the GTK thread
+        let gtk_sub = Subscription::run_with_id(
+            std::any::TypeId::of::<GtkEventSubscription>(),
+            cosmic::iced::futures::stream::unfold(gtk_event_receiver, |receiver| async move {
+                let mut rx = receiver.lock().await;
+                match rx.next().await {
+                    Some(event) => Some((Message::Gtk(event), receiver.clone())),
+                    None => {
+                        error!("GTK event channel closed");
+                        None
+                    }
+                }
+            }),
+        );
+
         // Combine with keyboard events
         Subscription::batch([
             keyboard::on_key_press(|key, modifiers| {
@@ -422,11 +478,65 @@ impl Application for MessagesPopup {
                 }
             }),
             dbus_sub,
+            bridge_sub,
+            gtk_sub,
         ])
     }
 }
 
 impl MessagesPopup {
+    /// Create the application state from a loaded config
+    fn new(core: Core, config: Config, flags: AppFlags) -> Self {
+        let mut webview_manager = WebViewManager::new(config.clone());
+
+        let initial_messenger = config
+            .popup
+            .last_messenger
+            .as_deref()
+            .unwrap_or("google-messages");
+
+        let _ = webview_manager.set_current(initial_messenger);
+
+        let notification_handler = NotificationHandler::new(config.clone());
+
+        Self {
+            core,
+            config,
+            webview_manager,
+            notification_handler,
+            visible: flags.visible,
+            settings_open: false,
+            dbus_sender: flags.dbus_sender,
+            dbus_receiver: flags.dbus_receiver,
+            bridge_receiver: flags.bridge_receiver,
+            gtk_event_receiver: flags.gtk_event_receiver,
+            unread: UnreadCounts::default(),
+            config_dirty: false,
+        }
+    }
+
+    /// Show the popup if hidden, hide it otherwise
+    ///
+    /// Shared by the popup's own toggle and the D-Bus/global shortcut one.
+    fn toggle_popup(&mut self) {
+        let visible = toggle_visibility(&self.visible);
+        if visible {
+            if let Some(messenger_id) = self.webview_manager.current() {
+                if let Some(url) = self.webview_manager.current_url() {
+                    let _ = gtk_webview::show_messenger_window(messenger_id, url, &self.config);
+                }
+            }
+        } else {
+            let _ = gtk_webview::hide_all_windows();
+            // Save config if dirty on hide
+            if self.config_dirty {
+                let _ = self.config.save();
+                self.config_dirty = false;
+            }
+        }
+        debug!("Toggling popup: {}", visible);
+    }
+
     /// Build the header bar
     fn build_header(&self) -> Element<'_, Message> {
         let display_name = self
@@ -460,10 +570,14 @@ impl MessagesPopup {
         let tabs = self.webview_manager.get_all_info().into_iter().fold(
             row::with_capacity(6).spacing(4).padding(8),
             |tabs, info| {
+                let label = match self.unread.get(&info.messenger_id) {
+                    0 => info.display_name.clone(),
+                    count => format!("{} ({})", info.display_name, count),
+                };
                 let btn = if info.is_current {
-                    button::suggested(info.display_name.clone())
+                    button::suggested(label)
                 } else {
-                    button::text(info.display_name.clone())
+                    button::text(label)
                 };
                 tabs.push(btn.on_press(Message::SwitchMessenger(info.messenger_id)))
             },
@@ -618,10 +732,45 @@ impl MessagesPopup {
     }
 }
 
+/// Flip the popup visibility, returning the new state
+fn toggle_visibility(visible: &AtomicBool) -> bool {
+    !visible.fetch_xor(true, Ordering::Relaxed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn test_app() -> MessagesPopup {
+        let (dbus_sender, dbus_receiver) = mpsc::unbounded();
+        let (_bridge_sender, bridge_receiver) = mpsc::unbounded();
+        let (_gtk_sender, gtk_event_receiver) = mpsc::unbounded();
+        let flags = AppFlags {
+            dbus_sender,
+            dbus_receiver: Arc::new(Mutex::new(dbus_receiver)),
+            bridge_receiver: Arc::new(Mutex::new(bridge_receiver)),
+            gtk_event_receiver: Arc::new(Mutex::new(gtk_event_receiver)),
+            visible: Arc::new(AtomicBool::new(false)),
+        };
+        MessagesPopup::new(Core::default(), Config::default(), flags)
+    }
+
+    #[test]
+    fn test_toggle_popup_flips_visibility() {
+        let mut app = test_app();
+        let visible = app.visible.clone();
+
+        let _ = app.update(Message::TogglePopup);
+        assert!(visible.load(Ordering::Relaxed));
+
+        // The global shortcut arrives as a D-Bus command and toggles the same state
+        let _ = app.update(Message::DbusCommand(DbusCommand::TogglePopup));
+        assert!(!visible.load(Ordering::Relaxed));
+
+        let _ = app.update(Message::DbusCommand(DbusCommand::TogglePopup));
+        assert!(visible.load(Ordering::Relaxed));
+    }
+
     #[test]
     fn test_message_variants() {
         // Ensure all message variants are clonable
@@ -0,0 +1,442 @@
+//! WebView Bridge Module
+//!
+//! WebSocket server that the messenger web pages report their state to.
+//! A userscript injected into every WebView window connects to it and relays
+//! unread counts and new-message events, so tab badges and auto-open work off
+//! what the page actually shows.
+//!
+//! The server binds to `127.0.0.1` on a random port and only accepts clients
+//! that present the per-run token baked into the userscript. It runs on its
+//! own thread with its own tokio runtime and hands events to the app through
+//! an unbounded channel, so the iced update loop never waits on it.
+//!
+//! ## Message Schema
+//!
+//! Every message is a JSON object with a `type` field:
+//!
+//! ```json
+//! {"type": "hello", "messenger": "whatsapp", "token": "3f2a..."}
+//! {"type": "unread", "messenger": "whatsapp", "count": 3}
+//! {"type": "message", "messenger": "whatsapp", "sender": "Alice", "preview": "Hi"}
+//! ```
+//!
+//! `hello` must be the first message on a connection. The userscript
+//! reconnects with backoff whenever the connection drops and re-sends its
+//! current unread count.
+
+use anyhow::{Context, Result};
+use futures::channel::mpsc;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::OnceLock;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tracing::{debug, error, info, warn};
+
+/// Longest accepted WebSocket message in bytes
+const MAX_MESSAGE_SIZE: usize = 16 * 1024;
+
+/// Address and token of the running bridge, used when injecting the userscript
+static BRIDGE_ENDPOINT: OnceLock<BridgeEndpoint> = OnceLock::new();
+
+/// Where the userscript connects and how it authenticates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeEndpoint {
+    /// Local port the server listens on
+    pub port: u16,
+    /// Token clients must send in their `hello`
+    pub token: String,
+}
+
+/// Message sent by the userscript
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BridgeMessage {
+    /// First message on a connection
+    Hello { messenger: String, token: String },
+    /// Current number of unread messages
+    Unread { messenger: String, count: u32 },
+    /// A new message arrived
+    Message {
+        messenger: String,
+        #[serde(default)]
+        sender: Option<String>,
+        #[serde(default)]
+        preview: Option<String>,
+    },
+}
+
+/// Event forwarded to the app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BridgeEvent {
+    /// A messenger page connected
+    Connected(String),
+    /// A messenger page disconnected
+    Disconnected(String),
+    /// Unread count of a messenger changed
+    Unread { messenger: String, count: u32 },
+    /// A messenger received a new message
+    NewMessage {
+        messenger: String,
+        sender: Option<String>,
+    },
+}
+
+/// Unread counts per messenger as reported by the pages
+#[derive(Debug, Clone, Default)]
+pub struct UnreadCounts {
+    counts: HashMap<String, u32>,
+}
+
+impl UnreadCounts {
+    /// Unread count of a messenger (0 if unknown)
+    pub fn get(&self, messenger: &str) -> u32 {
+        self.counts.get(messenger).copied().unwrap_or(0)
+    }
+
+    /// Record a count, returning whether it went up
+    pub fn set(&mut self, messenger: &str, count: u32) -> bool {
+        let previous = self
+            .counts
+            .insert(messenger.to_string(), count)
+            .unwrap_or(0);
+        count > previous
+    }
+}
+
+/// The running bridge's endpoint, if it started
+pub fn endpoint() -> Option<&'static BridgeEndpoint> {
+    BRIDGE_ENDPOINT.get()
+}
+
+/// Generate a random hex token
+fn generate_token() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut bytes))
+        .context("Failed to read random token")?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Start the bridge server on a background thread
+///
+/// Blocks until the listener is bound so the endpoint is known before any
+/// WebView window is created.
+pub fn start_bridge(sender: mpsc::UnboundedSender<BridgeEvent>) -> Result<BridgeEndpoint> {
+    let token = generate_token()?;
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+    let server_token = token.clone();
+    std::thread::spawn(move || {
+        let rt = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                let _ = ready_tx.send(Err(anyhow::anyhow!("Failed to create runtime: {}", e)));
+                return;
+            }
+        };
+        rt.block_on(async move {
+            let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+            let listener = match TcpListener::bind(addr).await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    let _ = ready_tx.send(Err(anyhow::anyhow!("Failed to bind bridge: {}", e)));
+                    return;
+                }
+            };
+            let port = listener.local_addr().map(|a| a.port()).unwrap_or_default();
+            let _ = ready_tx.send(Ok(port));
+
+            serve(listener, server_token, sender).await;
+        });
+    });
+
+    let port = ready_rx
+        .recv()
+        .context("Bridge thread exited before binding")??;
+    let endpoint = BridgeEndpoint { port, token };
+    let _ = BRIDGE_ENDPOINT.set(endpoint.clone());
+    info!("WebView bridge listening on 127.0.0.1:{}", port);
+    Ok(endpoint)
+}
+
+/// Accept connections until the app exits
+async fn serve(listener: TcpListener, token: String, sender: mpsc::UnboundedSender<BridgeEvent>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                debug!("Bridge connection from {}", peer);
+                tokio::spawn(handle_connection(stream, token.clone(), sender.clone()));
+            }
+            Err(e) => {
+                warn!("Bridge accept failed: {}", e);
+            }
+        }
+    }
+}
+
+/// Relay one userscript connection's messages to the app
+async fn handle_connection(
+    stream: TcpStream,
+    token: String,
+    sender: mpsc::UnboundedSender<BridgeEvent>,
+) {
+    let config = WebSocketConfig::default()
+        .max_message_size(Some(MAX_MESSAGE_SIZE))
+        .max_frame_size(Some(MAX_MESSAGE_SIZE));
+    let mut ws = match tokio_tungstenite::accept_async_with_config(stream, Some(config)).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            debug!("Bridge handshake failed: {}", e);
+            return;
+        }
+    };
+
+    let mut messenger: Option<String> = None;
+    while let Some(frame) = ws.next().await {
+        let text = match frame {
+            Ok(WsMessage::Text(text)) => text,
+            Ok(WsMessage::Close(_)) | Err(_) => break,
+            Ok(_) => continue,
+        };
+        let message: BridgeMessage = match serde_json::from_str(&text) {
+            Ok(message) => message,
+            Err(e) => {
+                debug!("Ignoring invalid bridge message: {}", e);
+                continue;
+            }
+        };
+
+        match handle_message(message, messenger.as_deref(), &token) {
+            Ok(Some(event)) => {
+                if let BridgeEvent::Connected(id) = &event {
+                    messenger = Some(id.clone());
+                }
+                if sender.unbounded_send(event).is_err() {
+                    break;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!("Closing bridge connection: {}", e);
+                let _ = ws.send(WsMessage::Close(None)).await;
+                break;
+            }
+        }
+    }
+
+    if let Some(id) = messenger {
+        debug!("Bridge connection for {} closed", id);
+        let _ = sender.unbounded_send(BridgeEvent::Disconnected(id));
+    }
+}
+
+/// Turn a message into an event for the app
+///
+/// `authenticated` is the messenger of the connection once its `hello` was
+/// accepted. Errors end the connection.
+fn handle_message(
+    message: BridgeMessage,
+    authenticated: Option<&str>,
+    token: &str,
+) -> Result<Option<BridgeEvent>> {
+    match (message, authenticated) {
+        (
+            BridgeMessage::Hello {
+                messenger,
+                token: given,
+            },
+            None,
+        ) => {
+            if given != token {
+                anyhow::bail!("invalid token");
+            }
+            Ok(Some(BridgeEvent::Connected(messenger)))
+        }
+        (BridgeMessage::Hello { .. }, Some(_)) => Ok(None),
+        (_, None) => anyhow::bail!("message before hello"),
+        (message, Some(connected)) => {
+            let (messenger, event) = match message {
+                BridgeMessage::Unread { messenger, count } => {
+                    (messenger.clone(), BridgeEvent::Unread { messenger, count })
+                }
+                BridgeMessage::Message {
+                    messenger, sender, ..
+                } => (
+                    messenger.clone(),
+                    BridgeEvent::NewMessage { messenger, sender },
+                ),
+                BridgeMessage::Hello { .. } => unreachable!("handled above"),
+            };
+            // A page may only report for the messenger it said hello as
+            if messenger != connected {
+                warn!(
+                    "Bridge client for {} reported for {}, ignoring",
+                    connected, messenger
+                );
+                return Ok(None);
+            }
+            Ok(Some(event))
+        }
+    }
+}
+
+/// Userscript injected into a messenger's WebView
+///
+/// Watches the page title for an unread count like `(3) WhatsApp`, reports
+/// changes and reconnects with backoff when the connection drops.
+pub fn userscript(endpoint: &BridgeEndpoint, messenger_id: &str) -> String {
+    let config = serde_json::json!({
+        "url": format!("ws://127.0.0.1:{}/", endpoint.port),
+        "token": endpoint.token,
+        "messenger": messenger_id,
+    });
+    format!(
+        "(function(config) {{\n{}\n}})({});",
+        USERSCRIPT_BODY, config
+    )
+}
+
+/// Body of the userscript; `config` holds url, token and messenger
+const USERSCRIPT_BODY: &str = r#"
+  if (window.top !== window || window.__cosmicBridge) return;
+  window.__cosmicBridge = true;
+  var socket = null, delay = 1000, unread = 0;
+
+  function send(message) {
+    message.messenger = config.messenger;
+    if (socket && socket.readyState === WebSocket.OPEN) socket.send(JSON.stringify(message));
+  }
+
+  function readUnread() {
+    var match = /^\((\d+)\)/.exec(document.title || "");
+    return match ? parseInt(match[1], 10) : 0;
+  }
+
+  function update() {
+    var count = readUnread();
+    if (count === unread) return;
+    if (count > unread) send({ type: "message", sender: null, preview: null });
+    unread = count;
+    send({ type: "unread", count: count });
+  }
+
+  function connect() {
+    socket = new WebSocket(config.url);
+    socket.onopen = function () {
+      delay = 1000;
+      send({ type: "hello", token: config.token });
+      unread = readUnread();
+      send({ type: "unread", count: unread });
+    };
+    socket.onclose = function () {
+      socket = null;
+      setTimeout(connect, delay);
+      delay = Math.min(delay * 2, 30000);
+    };
+  }
+
+  function watchTitle() {
+    var title = document.querySelector("title");
+    if (!title) return setTimeout(watchTitle, 1000);
+    new MutationObserver(update).observe(title, { childList: true, characterData: true, subtree: true });
+  }
+
+  connect();
+  if (document.readyState === "loading") document.addEventListener("DOMContentLoaded", watchTitle);
+  else watchTitle();
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_schema() {
+        let message: BridgeMessage =
+            serde_json::from_str(r#"{"type":"unread","messenger":"whatsapp","count":3}"#).unwrap();
+        assert_eq!(
+            message,
+            BridgeMessage::Unread {
+                messenger: "whatsapp".to_string(),
+                count: 3
+            }
+        );
+
+        let message: BridgeMessage =
+            serde_json::from_str(r#"{"type":"message","messenger":"telegram"}"#).unwrap();
+        assert!(matches!(
+            message,
+            BridgeMessage::Message { sender: None, .. }
+        ));
+    }
+
+    #[test]
+    fn test_hello_requires_token() {
+        let hello = |token: &str| BridgeMessage::Hello {
+            messenger: "whatsapp".to_string(),
+            token: token.to_string(),
+        };
+
+        assert!(handle_message(hello("wrong"), None, "secret").is_err());
+        assert_eq!(
+            handle_message(hello("secret"), None, "secret").unwrap(),
+            Some(BridgeEvent::Connected("whatsapp".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_messages_need_hello_and_matching_messenger() {
+        let unread = |messenger: &str| BridgeMessage::Unread {
+            messenger: messenger.to_string(),
+            count: 2,
+        };
+
+        assert!(handle_message(unread("whatsapp"), None, "secret").is_err());
+        assert_eq!(
+            handle_message(unread("telegram"), Some("whatsapp"), "secret").unwrap(),
+            None
+        );
+        assert_eq!(
+            handle_message(unread("whatsapp"), Some("whatsapp"), "secret").unwrap(),
+            Some(BridgeEvent::Unread {
+                messenger: "whatsapp".to_string(),
+                count: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_unread_counts() {
+        let mut counts = UnreadCounts::default();
+        assert!(counts.set("whatsapp", 2));
+        assert!(!counts.set("whatsapp", 1));
+        assert!(counts.set("telegram", 4));
+        assert_eq!(counts.get("whatsapp"), 1);
+        assert_eq!(counts.get("signal"), 0);
+        assert_eq!(counts.get("telegram"), 4);
+    }
+
+    #[test]
+    fn test_userscript_embeds_endpoint() {
+        let endpoint = BridgeEndpoint {
+            port: 4242,
+            token: "abc123".to_string(),
+        };
+        let script = userscript(&endpoint, "whatsapp");
+        assert!(script.contains("ws://127.0.0.1:4242/"));
+        assert!(script.contains("\"token\":\"abc123\""));
+        assert!(script.contains("\"messenger\":\"whatsapp\""));
+    }
+
+    #[test]
+    fn test_generate_token() {
+        let token = generate_token().unwrap();
+        assert_eq!(token.len(), 32);
+        assert_ne!(token, generate_token().unwrap());
+    }
+}
@@ -4,6 +4,7 @@
 //! enabled messengers, popup settings, and notification preferences.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use tracing::{debug, error, info};
 
@@ -19,6 +20,10 @@ pub struct Config {
     /// Notification settings
     pub notifications: NotificationConfig,
 
+    /// Global keyboard shortcuts
+    #[serde(default)]
+    pub shortcuts: ShortcutConfig,
+
     /// Whether config needs to be saved
     #[serde(skip)]
     pub dirty: bool,
@@ -56,6 +61,37 @@ pub struct PopupConfig {
     pub remember_last: bool,
     /// Last used messenger id
     pub last_messenger: Option<String>,
+    /// Last window geometry per messenger id
+    #[serde(default)]
+    pub window_geometry: HashMap<String, WindowGeometry>,
+}
+
+/// Position and size of a messenger window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl WindowGeometry {
+    /// Fit the geometry inside `area`, e.g. a monitor's work area
+    ///
+    /// The window is shrunk if it is larger than the area, then moved just
+    /// enough to be fully visible.
+    pub fn clamp_to(&self, area: &WindowGeometry) -> WindowGeometry {
+        let width = self.width.min(area.width).max(1);
+        let height = self.height.min(area.height).max(1);
+        let x = self.x.min(area.x + area.width - width).max(area.x);
+        let y = self.y.min(area.y + area.height - height).max(area.y);
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
 }
 
 /// Popup positioning options
@@ -112,6 +148,22 @@ pub struct NotificationConfig {
     pub auto_open: bool,
 }
 
+/// Global keyboard shortcut configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShortcutConfig {
+    /// Preferred trigger for toggling the popup, e.g. "CTRL+ALT+M"
+    /// (empty to disable)
+    pub toggle_popup: String,
+}
+
+impl Default for ShortcutConfig {
+    fn default() -> Self {
+        Self {
+            toggle_popup: "CTRL+ALT+M".to_string(),
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -172,12 +224,14 @@ impl Default for Config {
                 persistent: false,
                 remember_last: true,
                 last_messenger: None,
+                window_geometry: HashMap::new(),
             },
             notifications: NotificationConfig {
                 show_notifications: true,
                 play_sound: true,
                 auto_open: false,
             },
+            shortcuts: ShortcutConfig::default(),
             dirty: false,
         }
     }
@@ -275,6 +329,18 @@ impl Config {
         });
     }
 
+    /// Saved window geometry of a messenger
+    pub fn window_geometry(&self, id: &str) -> Option<WindowGeometry> {
+        self.popup.window_geometry.get(id).copied()
+    }
+
+    /// Remember a messenger's window geometry
+    ///
+    /// Returns `true` if it differs from the saved one.
+    pub fn set_window_geometry(&mut self, id: &str, geometry: WindowGeometry) -> bool {
+        self.popup.window_geometry.insert(id.to_string(), geometry) != Some(geometry)
+    }
+
     /// Mark config as dirty (needs saving)
     pub fn mark_dirty(&mut self) {
         self.dirty = true;
@@ -341,6 +407,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_geometry_clamped_into_work_area() {
+        let work_area = WindowGeometry {
+            x: 0,
+            y: 32,
+            width: 1920,
+            height: 1048,
+        };
+
+        // Saved on a monitor that is no longer connected
+        let saved = WindowGeometry {
+            x: 2500,
+            y: -200,
+            width: 420,
+            height: 650,
+        };
+        assert_eq!(
+            saved.clamp_to(&work_area),
+            WindowGeometry {
+                x: 1500,
+                y: 32,
+                width: 420,
+                height: 650,
+            }
+        );
+
+        // Larger than the work area
+        let saved = WindowGeometry {
+            x: -50,
+            y: 900,
+            width: 2560,
+            height: 1440,
+        };
+        assert_eq!(saved.clamp_to(&work_area), work_area);
+
+        // Already visible
+        let saved = WindowGeometry {
+            x: 100,
+            y: 100,
+            width: 420,
+            height: 650,
+        };
+        assert_eq!(saved.clamp_to(&work_area), saved);
+    }
+
+    #[test]
+    fn test_window_geometry_per_messenger() {
+        let mut config = Config::default();
+        assert!(config.window_geometry("whatsapp").is_none());
+
+        let geometry = WindowGeometry {
+            x: 10,
+            y: 20,
+            width: 420,
+            height: 650,
+        };
+        assert!(config.set_window_geometry("whatsapp", geometry));
+        assert!(!config.set_window_geometry("whatsapp", geometry));
+        assert_eq!(config.window_geometry("whatsapp"), Some(geometry));
+        assert!(config.window_geometry("telegram").is_none());
+    }
+
     #[test]
     fn test_serialization() {
         let config = Config::default();
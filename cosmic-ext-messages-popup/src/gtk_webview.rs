@@ -5,8 +5,14 @@
 //!
 //! Note: GTK operations must happen on the main GTK thread. This module provides
 //! a channel-based API for cross-thread communication.
+//!
+//! Window geometry is reported back as a [`GtkEvent`] whenever a window is
+//! hidden, so the app can persist it and pass it along on the next show. A
+//! restored window is clamped to the work area of the nearest monitor. On
+//! Wayland the compositor decides window placement, so only the size sticks.
 
-use crate::config::Config;
+use crate::bridge;
+use crate::config::{Config, WindowGeometry};
 use crate::webview::user_agent_for_messenger;
 use anyhow::{Context, Result};
 use gtk::prelude::*;
@@ -34,6 +40,8 @@ pub enum GtkCommand {
         width: i32,
         height: i32,
         position: String,
+        /// Saved geometry, overriding size and position
+        geometry: Option<WindowGeometry>,
     },
     /// Hide a WebView window
     Hide { messenger_id: String },
@@ -49,9 +57,23 @@ pub enum GtkCommand {
     Shutdown,
 }
 
+/// Events reported by the GTK thread
+#[derive(Debug, Clone)]
+pub enum GtkEvent {
+    /// A window was hidden
+    WindowHidden {
+        messenger_id: String,
+        geometry: WindowGeometry,
+    },
+}
+
 /// Channel sender for GTK commands
 static GTK_SENDER: OnceLock<Sender<GtkCommand>> = OnceLock::new();
 
+/// Channel sender for events from the GTK thread
+static GTK_EVENT_SENDER: OnceLock<futures::channel::mpsc::UnboundedSender<GtkEvent>> =
+    OnceLock::new();
+
 /// Initialize GTK if not already initialized
 #[allow(dead_code)]
 pub fn ensure_gtk_init() -> Result<()> {
@@ -86,15 +108,27 @@ fn handle_gtk_command(
             width,
             height,
             position,
+            geometry,
         } => {
             if let Some((window, _, _)) = windows.get(&messenger_id) {
                 // Window exists, just show it
+                if let Some(geometry) = geometry.filter(|_| !window.is_visible()) {
+                    restore_geometry(window, geometry);
+                }
                 window.present();
                 window.grab_focus();
                 debug!("Presenting existing window for {}", messenger_id);
             } else {
                 // Create new window
-                match create_webview_window(&messenger_id, &url, &title, width, height, &position) {
+                match create_webview_window(
+                    &messenger_id,
+                    &url,
+                    &title,
+                    width,
+                    height,
+                    &position,
+                    geometry,
+                ) {
                     Ok((window, webview, context)) => {
                         windows.insert(messenger_id.clone(), (window, webview, context));
                         info!("Created WebView window for {}", messenger_id);
@@ -107,13 +141,13 @@ fn handle_gtk_command(
         }
         GtkCommand::Hide { messenger_id } => {
             if let Some((window, _, _)) = windows.get(&messenger_id) {
-                window.hide();
+                hide_window(&messenger_id, window);
                 debug!("Hidden window for {}", messenger_id);
             }
         }
         GtkCommand::HideAll => {
-            for (window, _, _) in windows.values() {
-                window.hide();
+            for (messenger_id, (window, _, _)) in windows.iter() {
+                hide_window(messenger_id, window);
             }
             debug!("Hidden all windows");
         }
@@ -149,17 +183,65 @@ fn handle_gtk_command(
     }
 }
 
+/// Hide a window, reporting its geometry first
+fn hide_window(messenger_id: &str, window: &gtk::Window) {
+    if window.is_visible() {
+        let (x, y) = window.position();
+        let (width, height) = window.size();
+        if let Some(sender) = GTK_EVENT_SENDER.get() {
+            let _ = sender.unbounded_send(GtkEvent::WindowHidden {
+                messenger_id: messenger_id.to_string(),
+                geometry: WindowGeometry {
+                    x,
+                    y,
+                    width,
+                    height,
+                },
+            });
+        }
+    }
+    window.hide();
+}
+
+/// Work area of the monitor at or nearest to a point
+fn work_area_near(x: i32, y: i32) -> Option<WindowGeometry> {
+    let display = gdk::Display::default()?;
+    let monitor = display.monitor_at_point(x, y)?;
+    let area = monitor.workarea();
+    Some(WindowGeometry {
+        x: area.x(),
+        y: area.y(),
+        width: area.width(),
+        height: area.height(),
+    })
+}
+
+/// Move and resize a window to a saved geometry, keeping it on screen
+fn restore_geometry(window: &gtk::Window, geometry: WindowGeometry) {
+    let geometry = match work_area_near(geometry.x, geometry.y) {
+        Some(area) => geometry.clamp_to(&area),
+        None => geometry,
+    };
+    debug!("Restoring window geometry {:?}", geometry);
+    window.set_position(gtk::WindowPosition::None);
+    window.move_(geometry.x, geometry.y);
+    window.resize(geometry.width, geometry.height);
+}
+
 /// Start the GTK event loop in a background thread
 ///
-/// Returns the thread handle
+/// Returns the thread handle. Window events are sent to `events`.
 ///
 /// NOTE: GTK must be initialized ON the thread where it will be used.
 /// This function initializes GTK inside the spawned thread.
-pub fn start_gtk_event_loop() -> JoinHandle<()> {
+pub fn start_gtk_event_loop(
+    events: futures::channel::mpsc::UnboundedSender<GtkEvent>,
+) -> JoinHandle<()> {
     let (tx, rx): (Sender<GtkCommand>, Receiver<GtkCommand>) = mpsc::channel();
 
-    // Store sender globally
+    // Store senders globally
     let _ = GTK_SENDER.set(tx);
+    let _ = GTK_EVENT_SENDER.set(events);
 
     thread::spawn(move || {
         // Initialize GTK on THIS thread (GTK requires all ops on same thread)
@@ -207,6 +289,7 @@ fn create_webview_window(
     width: i32,
     height: i32,
     position: &str,
+    geometry: Option<WindowGeometry>,
 ) -> Result<(gtk::Window, WebView, wry::WebContext)> {
     // Create persistent data directory for this messenger's sessions
     // This stores cookies, local storage, IndexedDB - users only login once!
@@ -241,6 +324,11 @@ fn create_webview_window(
         _ => window.set_position(gtk::WindowPosition::Center),
     }
 
+    // A saved geometry wins over the configured default
+    if let Some(geometry) = geometry {
+        restore_geometry(&window, geometry);
+    }
+
     // Set window hints for popup-like behavior
     window.set_type_hint(gdk::WindowTypeHint::Utility);
     window.set_decorated(true);
@@ -259,10 +347,16 @@ fn create_webview_window(
     // Get user agent for this messenger
     let user_agent = user_agent_for_messenger(messenger_id);
 
+    // Report unread counts back to the app if the bridge is running
+    let bridge_script = bridge::endpoint()
+        .map(|endpoint| bridge::userscript(endpoint, messenger_id))
+        .unwrap_or_default();
+
     // Build WebView using GTK extension for Wayland support
     // Note: Don't use with_bounds() - let GTK handle sizing through widget properties
     let webview = WebViewBuilder::with_web_context(&mut web_context)
         .with_url(url)
+        .with_initialization_script(&bridge_script)
         .with_user_agent(&user_agent)
         .with_devtools(cfg!(debug_assertions))
         .with_autoplay(true)
@@ -310,7 +404,7 @@ fn create_webview_window(
     let messenger_id_clone = messenger_id.to_string();
     window.connect_delete_event(move |win, _| {
         debug!("Window close requested for {}", messenger_id_clone);
-        win.hide();
+        hide_window(&messenger_id_clone, win);
         glib::Propagation::Stop
     });
 
@@ -351,6 +445,7 @@ pub fn show_messenger_window(messenger_id: &str, url: &str, config: &Config) ->
         width: config.popup.width as i32,
         height: config.popup.height as i32,
         position: config.popup.position.as_str().to_string(),
+        geometry: config.window_geometry(messenger_id),
     })
 }
 
@@ -412,6 +507,7 @@ mod tests {
             width: 400,
             height: 600,
             position: "center".to_string(),
+            geometry: None,
         };
 
         let _cmd = GtkCommand::Hide {
@@ -11,7 +11,8 @@
 //! - D-Bus interface for cosmic-connect notification integration
 //! - Session persistence for each messenger
 //! - Configurable popup settings
-//! - Keyboard shortcuts for quick access
+//! - Keyboard shortcuts for quick access and a global toggle shortcut
+//! - Unread counts reported by the messenger pages over a local WebSocket
 //!
 //! ## Architecture
 //!
@@ -39,10 +40,12 @@ use tracing::{error, info};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 mod app;
+mod bridge;
 mod config;
 mod dbus;
 mod gtk_webview;
 mod notification;
+mod shortcuts;
 mod webview;
 
 pub use app::{AppFlags, Message, MessagesPopup};
@@ -92,12 +95,19 @@ fn main() -> cosmic::iced::Result {
 
     // Start GTK event loop in background thread
     // Note: GTK is initialized INSIDE the thread (GTK requires all ops on same thread)
-    let _gtk_handle = gtk_webview::start_gtk_event_loop();
+    let (gtk_event_sender, gtk_event_receiver) = mpsc::unbounded::<gtk_webview::GtkEvent>();
+    let _gtk_handle = gtk_webview::start_gtk_event_loop(gtk_event_sender);
     info!("GTK event loop thread spawned");
 
     // Create D-Bus channel using futures (no tokio runtime needed)
     let (dbus_sender, dbus_receiver) = mpsc::unbounded::<DbusCommand>();
 
+    // Start the WebView bridge before any window injects its userscript
+    let (bridge_sender, bridge_receiver) = mpsc::unbounded::<bridge::BridgeEvent>();
+    if let Err(e) = bridge::start_bridge(bridge_sender) {
+        error!("Failed to start WebView bridge: {}", e);
+    }
+
     // Create shared visibility state
     let visible = Arc::new(AtomicBool::new(false));
 
@@ -147,6 +157,8 @@ fn main() -> cosmic::iced::Result {
     let flags = AppFlags {
         dbus_sender,
         dbus_receiver: Arc::new(Mutex::new(dbus_receiver)),
+        bridge_receiver: Arc::new(Mutex::new(bridge_receiver)),
+        gtk_event_receiver: Arc::new(Mutex::new(gtk_event_receiver)),
         visible,
     };
 
@@ -0,0 +1,131 @@
+//! Global Shortcut Module
+//!
+//! Registers a system-wide shortcut that toggles the popup through the XDG
+//! GlobalShortcuts portal. Activations are sent as [`DbusCommand::TogglePopup`]
+//! on the D-Bus command channel, so they go through the same subscription as
+//! the `Toggle` D-Bus method.
+//!
+//! The portal runs on its own thread with its own tokio runtime. Calling
+//! [`register_toggle_shortcut`] again (e.g. after a config reload) only
+//! rebinds when the trigger changed, so the shortcut is never registered
+//! twice. If the portal is not available the shortcut is skipped with a
+//! warning and the popup keeps working through D-Bus and the applet.
+
+use crate::dbus::DbusCommand;
+use ashpd::desktop::global_shortcuts::{GlobalShortcuts, NewShortcut};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use std::sync::OnceLock;
+use tokio::sync::watch;
+use tracing::{debug, error, info, warn};
+
+/// Portal id of the toggle shortcut
+const TOGGLE_SHORTCUT_ID: &str = "toggle-popup";
+
+/// Trigger of the running registration, if one was started
+static TOGGLE_TRIGGER: OnceLock<watch::Sender<String>> = OnceLock::new();
+
+/// Register (or rebind) the shortcut toggling the popup
+///
+/// `trigger` uses the portal's shortcut syntax, e.g. `CTRL+ALT+M`; an empty
+/// trigger removes the shortcut. The compositor may ask the user to confirm
+/// or pick a different trigger.
+pub fn register_toggle_shortcut(trigger: &str, sender: mpsc::UnboundedSender<DbusCommand>) {
+    if let Some(current) = TOGGLE_TRIGGER.get() {
+        let changed = current.send_if_modified(|current| {
+            if current == trigger {
+                false
+            } else {
+                *current = trigger.to_string();
+                true
+            }
+        });
+        if changed {
+            debug!("Rebinding popup toggle shortcut to {:?}", trigger);
+        }
+        return;
+    }
+
+    let (trigger_tx, trigger_rx) = watch::channel(trigger.to_string());
+    if TOGGLE_TRIGGER.set(trigger_tx).is_err() {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let rt = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                error!("Failed to create runtime for global shortcuts: {}", e);
+                return;
+            }
+        };
+        rt.block_on(run(trigger_rx, sender));
+    });
+}
+
+/// Keep the shortcut bound to the current trigger and forward activations
+async fn run(mut trigger: watch::Receiver<String>, sender: mpsc::UnboundedSender<DbusCommand>) {
+    loop {
+        let current = trigger.borrow_and_update().clone();
+
+        if current.is_empty() {
+            info!("Popup toggle shortcut disabled");
+        } else if let Err(e) = bind_and_forward(&current, &mut trigger, &sender).await {
+            warn!(
+                "Global shortcuts portal unavailable, popup toggle shortcut disabled: {}",
+                e
+            );
+        }
+
+        if sender.is_closed() {
+            return;
+        }
+        // Wait for a new trigger unless one already arrived
+        if *trigger.borrow() == current && trigger.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Bind the shortcut and forward activations until the trigger changes
+async fn bind_and_forward(
+    trigger: &str,
+    trigger_rx: &mut watch::Receiver<String>,
+    sender: &mpsc::UnboundedSender<DbusCommand>,
+) -> ashpd::Result<()> {
+    let portal = GlobalShortcuts::new().await?;
+    let session = portal.create_session().await?;
+
+    let shortcut = NewShortcut::new(TOGGLE_SHORTCUT_ID, "Toggle the messages popup")
+        .preferred_trigger(trigger);
+    let bound = portal
+        .bind_shortcuts(&session, &[shortcut], None)
+        .await?
+        .response()?;
+    for shortcut in bound.shortcuts() {
+        info!(
+            "Popup toggle shortcut bound to {}",
+            shortcut.trigger_description()
+        );
+    }
+
+    let mut activated = Box::pin(portal.receive_activated().await?);
+    loop {
+        tokio::select! {
+            event = activated.next() => {
+                let Some(event) = event else { break };
+                if event.shortcut_id() != TOGGLE_SHORTCUT_ID {
+                    continue;
+                }
+                debug!("Popup toggle shortcut activated");
+                if sender.unbounded_send(DbusCommand::TogglePopup).is_err() {
+                    break;
+                }
+            }
+            _ = trigger_rx.changed() => break,
+        }
+    }
+
+    let _ = session.close().await;
+    Ok(())
+}